@@ -1,7 +1,8 @@
 use std::sync::Arc;
 use crate::connection::{DbConnection, DbError, StreamingProgress};
 use crate::executor::{ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult, SqlStatementClassifier};
-use crate::{DatabasePlugin, SqlValue};
+use crate::proxy::{self, ProxyForward};
+use crate::{tls, DatabasePlugin, SqlValue};
 
 use clickhouse::{Client, Row};
 use serde::Deserialize;
@@ -13,6 +14,8 @@ use tokio::sync::mpsc;
 pub struct ClickHouseDbConnection {
     config: DbConnectionConfig,
     client: Option<Client>,
+    /// Keeps the local proxy port open while connected
+    proxy_forward: Option<ProxyForward>,
 }
 
 impl ClickHouseDbConnection {
@@ -20,6 +23,7 @@ impl ClickHouseDbConnection {
         Self {
             config,
             client: None,
+            proxy_forward: None,
         }
     }
 
@@ -50,6 +54,38 @@ impl ClickHouseDbConnection {
         })
     }
 
+    async fn execute_with_client(
+        client: &Client,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+    ) -> Result<Vec<SqlResult>, DbError> {
+        let statements = plugin.split_statements(script);
+        let mut results = Vec::new();
+
+        // ClickHouse doesn't have traditional transactions like MySQL
+        // We'll execute statements sequentially
+        for sql in statements {
+            let sql = sql.trim();
+            if sql.is_empty() {
+                continue;
+            }
+
+            let modified_sql = Self::apply_max_rows_limit(sql, options.max_rows);
+            let is_query = plugin.is_query_statement(&modified_sql);
+            let result = Self::execute_single(client, &modified_sql, is_query).await?;
+
+            let is_error = result.is_error();
+            results.push(result);
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
     async fn execute_single(
         client: &Client,
         sql: &str,
@@ -164,9 +200,26 @@ impl DbConnection for ClickHouseDbConnection {
     }
 
     async fn connect(&mut self) -> anyhow::Result<(), DbError> {
+        let proxy_forward = ProxyForward::start(&self.config).await?;
         let config = &self.config;
 
-        let url = format!("http://{}:{}", config.host, config.port);
+        // The HTTP client checks certificates against the system trust store only,
+        // so every SSL mode verifies the server and custom certificates cannot be used
+        let ssl = config.ssl();
+        if ssl.ca_cert.is_some() && ssl.is_enabled() {
+            return Err(tls::unsupported("ClickHouse", "custom CA certificates, install the CA in the system trust store"));
+        }
+        if ssl.is_enabled() && tls::client_identity(&ssl)?.is_some() {
+            return Err(tls::unsupported("ClickHouse", "client certificates"));
+        }
+        if proxy_forward.is_some() && ssl.is_enabled() {
+            return Err(proxy::hostname_check_unsupported());
+        }
+        let scheme = if ssl.is_enabled() { "https" } else { "http" };
+        let url = match &proxy_forward {
+            Some(forward) => format!("{}://127.0.0.1:{}", scheme, forward.local_port()),
+            None => format!("{}://{}:{}", scheme, config.host, config.port),
+        };
 
         let mut client = Client::default()
             .with_url(&url)
@@ -189,45 +242,56 @@ impl DbConnection for ClickHouseDbConnection {
             .query("SELECT 1")
             .fetch_all::<u8>()
             .await
-            .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))?;
+            .map_err(|e| ProxyForward::connect_error(proxy_forward.as_ref(), tls::connection_error(&ssl, e)))?;
 
         self.client = Some(client);
+        self.proxy_forward = proxy_forward;
 
         Ok(())
     }
 
     async fn disconnect(&mut self) -> Result<(), DbError> {
         self.client = None;
+        self.proxy_forward = None;
         Ok(())
     }
 
     async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
         let client = self.ensure_connected()?;
+        Self::execute_with_client(client, plugin, script, options).await
+    }
 
-        let statements = plugin.split_statements(script);
-        let mut results = Vec::new();
-
-        // ClickHouse doesn't have traditional transactions like MySQL
-        // We'll execute statements sequentially
-        for sql in statements {
-            let sql = sql.trim();
-            if sql.is_empty() {
-                continue;
-            }
-
-            let modified_sql = Self::apply_max_rows_limit(sql, options.max_rows);
-            let is_query = plugin.is_query_statement(&modified_sql);
-            let result = Self::execute_single(client, &modified_sql, is_query).await?;
-
-            let is_error = result.is_error();
-            results.push(result);
-
-            if is_error && options.stop_on_error {
-                break;
-            }
+    async fn execute_import(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        async_insert: bool,
+    ) -> Result<Vec<SqlResult>, DbError> {
+        let client = self.ensure_connected()?;
+        if !async_insert {
+            return Self::execute_with_client(client, plugin, script, options).await;
         }
 
-        Ok(results)
+        // Let the server batch small inserts, but wait for the flush so errors are reported
+        let client = client
+            .clone()
+            .with_option("async_insert", "1")
+            .with_option("wait_for_async_insert", "1");
+        Self::execute_with_client(&client, plugin, script, options).await
+    }
+
+    async fn export_formatted(&self, sql: &str, format: &str) -> Result<Option<Vec<u8>>, DbError> {
+        let client = self.ensure_connected()?;
+        let mut cursor = client
+            .query(sql)
+            .fetch_bytes(format)
+            .map_err(|e| DbError::QueryError(e.to_string()))?;
+        let data = cursor
+            .collect()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?;
+        Ok(Some(data.to_vec()))
     }
 
     async fn query(
@@ -5,7 +5,10 @@ use one_core::storage::{DatabaseType, DbConnectionConfig};
 use crate::connection::{DbConnection, DbError};
 use crate::executor::{ExecOptions, SqlResult};
 use crate::clickhouse::connection::ClickHouseDbConnection;
+use crate::import_export::NativeExportFormat;
 use crate::plugin::{DatabaseOperationRequest, DatabasePlugin, SqlCompletionInfo};
+use crate::server_status::{query_server_status, ServerStatus};
+use crate::session_variable::session_variable_literal;
 use crate::types::*;
 
 /// ClickHouse database plugin implementation (stateless)
@@ -31,6 +34,14 @@ impl DatabasePlugin for ClickHousePlugin {
         Box::new(sqlparser::dialect::ClickHouseDialect {})
     }
 
+    fn native_export_formats(&self) -> Vec<NativeExportFormat> {
+        NativeExportFormat::all()
+    }
+
+    fn supports_async_insert(&self) -> bool {
+        true
+    }
+
     fn get_completion_info(&self) -> SqlCompletionInfo {
         SqlCompletionInfo {
             keywords: vec![
@@ -54,6 +65,18 @@ impl DatabasePlugin for ClickHousePlugin {
                 ("now()", "Current timestamp"),
                 ("today()", "Current date"),
                 ("yesterday()", "Yesterday's date"),
+                ("addDays(date, n)", "Add days"),
+                ("addMonths(date, n)", "Add months"),
+                ("addHours(dt, n)", "Add hours"),
+                ("subtractDays(date, n)", "Subtract days"),
+                ("dateDiff('unit', start, end)", "Difference in specified unit"),
+                ("toStartOfDay(dt)", "Truncate to day"),
+                ("toStartOfHour(dt)", "Truncate to hour"),
+                ("toStartOfMonth(date)", "Truncate to month"),
+                ("toStartOfInterval(dt, INTERVAL n unit)", "Truncate to interval"),
+                ("toYYYYMM(date)", "Year and month as number"),
+                ("formatDateTime(dt, fmt)", "Format date/time"),
+                ("parseDateTimeBestEffort(str)", "Parse date/time from string"),
                 ("toDate(expr)", "Convert to Date"),
                 ("toDateTime(expr)", "Convert to DateTime"),
                 ("toString(expr)", "Convert to String"),
@@ -77,6 +100,21 @@ impl DatabasePlugin for ClickHousePlugin {
                 ("varPop(expr)", "Population variance"),
                 ("corr(x, y)", "Correlation"),
                 ("covarPop(x, y)", "Population covariance"),
+                ("any(expr)", "Any value from group"),
+                ("argMax(arg, val)", "Arg at maximum value"),
+                ("argMin(arg, val)", "Arg at minimum value"),
+                ("if(cond, then, else)", "Conditional expression"),
+                ("multiIf(cond1, then1, ..., else)", "Multi-branch conditional"),
+                ("lagInFrame(col, n)", "Previous row value in frame"),
+                ("leadInFrame(col, n)", "Next row value in frame"),
+                ("JSONExtractString(json, key)", "Extract JSON string"),
+                ("JSONExtractInt(json, key)", "Extract JSON integer"),
+                ("JSONExtractFloat(json, key)", "Extract JSON float"),
+                ("JSONExtractRaw(json, key)", "Extract raw JSON"),
+                ("JSONExtractKeys(json)", "Top-level object keys"),
+                ("JSONHas(json, key)", "Check JSON key exists"),
+                ("JSONLength(json)", "JSON array/object length"),
+                ("JSONType(json, key)", "JSON value type"),
             ],
             operators: vec![
                 ("GLOBAL IN", "Global IN operator"),
@@ -86,6 +124,8 @@ impl DatabasePlugin for ClickHousePlugin {
                 ("LIKE", "Pattern match"),
                 ("ILIKE", "Case-insensitive LIKE"),
                 ("NOT LIKE", "Negated LIKE"),
+                ("->", "Lambda expression"),
+                ("||", "String concatenation"),
             ],
             data_types: vec![
                 ("Int8", "8-bit signed integer"),
@@ -113,6 +153,10 @@ impl DatabasePlugin for ClickHousePlugin {
                 ("Tuple(T1, T2, ...)", "Tuple type"),
                 ("Nullable(T)", "Nullable type"),
                 ("LowCardinality(T)", "Low cardinality optimization"),
+                ("Map(K, V)", "Key-value map"),
+                ("Bool", "Boolean"),
+                ("Date32", "Extended-range date"),
+                ("Nested(...)", "Nested data structure"),
                 ("JSON", "JSON data type"),
             ],
             snippets: vec![
@@ -337,6 +381,7 @@ impl DatabasePlugin for ClickHousePlugin {
                         default_value,
                         is_primary_key,
                         comment,
+                        collation: None,
                     });
                 }
             }
@@ -621,6 +666,26 @@ impl DatabasePlugin for ClickHousePlugin {
         sql
     }
 
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        Some("SELECT value FROM system.events WHERE event = 'Query'")
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        query_server_status(
+            connection,
+            "SELECT concat('ClickHouse ', version()), uptime(), \
+             (SELECT sum(value) FROM system.metrics WHERE metric IN ('TCPConnection', 'HTTPConnection', 'MySQLConnection', 'PostgreSQLConnection'))",
+        ).await
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        Some("SELECT name, value, description, readonly = 0 FROM system.settings ORDER BY name".to_string())
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!("SET {} = {}", name, session_variable_literal(self, value)))
+    }
+
     fn build_limit_clause(&self) -> String {
         " LIMIT 1".to_string()
     }
@@ -722,6 +787,7 @@ impl DatabasePlugin for ClickHousePlugin {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::import_export::{DataExporter, ExportConfig};
     use crate::plugin::DatabasePlugin;
     use crate::types::{ColumnDefinition, IndexDefinition, TableDesign, TableOptions};
     use std::collections::HashMap;
@@ -746,6 +812,25 @@ mod tests {
         assert_eq!(plugin.quote_identifier("col`umn"), "`col``umn`");
     }
 
+    #[test]
+    fn test_native_export_select() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.native_export_formats(), NativeExportFormat::all());
+        assert!(plugin.supports_async_insert());
+
+        let config = ExportConfig {
+            database: "logs".to_string(),
+            tables: vec!["events".to_string()],
+            where_clause: Some("level = 'error'".to_string()),
+            limit: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(
+            DataExporter::build_native_select(&plugin, &config, "events"),
+            "SELECT * FROM `logs`.`events` WHERE level = 'error' LIMIT 100 OFFSET 0"
+        );
+    }
+
     // ==================== DDL SQL Generation Tests ====================
 
     #[test]
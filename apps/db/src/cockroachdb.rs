@@ -0,0 +1,3 @@
+pub mod plugin;
+
+pub use plugin::CockroachPlugin;
@@ -0,0 +1,795 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig};
+
+use crate::connection::{DbConnection, DbError};
+use crate::executor::{ExecOptions, SqlResult};
+use crate::plugin::{on_conflict_clause, DatabasePlugin, SqlCompletionInfo};
+use crate::import_export::{InsertConflictClause, SqlConflictMode};
+use crate::server_status::{query_server_status, ServerStatus};
+use crate::postgresql::{PostgresDbConnection, PostgresPlugin};
+use crate::types::*;
+
+/// Schemas CockroachDB exposes for introspection, hidden from the tree
+const SYSTEM_SCHEMAS: &[&str] = &["crdb_internal", "information_schema", "pg_catalog", "pg_extension"];
+
+/// CockroachDB plugin. CockroachDB speaks the PostgreSQL wire protocol and most of its SQL,
+/// so statement handling, DDL generation and routine browsing are delegated to
+/// [`PostgresPlugin`]; only the catalog queries and the table designer differ.
+pub struct CockroachPlugin {
+    postgres: PostgresPlugin,
+}
+
+impl CockroachPlugin {
+    pub fn new() -> Self {
+        Self {
+            postgres: PostgresPlugin::new(),
+        }
+    }
+
+    fn qualified_table(&self, schema: Option<&str>, table: &str) -> String {
+        self.postgres.format_table_reference("", schema, table)
+    }
+
+    /// Ranges of a table with their leaseholders and replica placement
+    pub fn build_show_ranges_sql(&self, schema: Option<&str>, table: &str) -> String {
+        format!("SHOW RANGES FROM TABLE {} WITH DETAILS", self.qualified_table(schema, table))
+    }
+
+    /// Ranges of every table in a database
+    pub fn build_show_database_ranges_sql(&self, database: &str) -> String {
+        format!("SHOW RANGES FROM DATABASE {} WITH TABLES", self.quote_identifier(database))
+    }
+
+    /// Zone configuration of a table, inherited from the database or the default zone when not set
+    pub fn build_show_zone_configuration_sql(&self, schema: Option<&str>, table: &str) -> String {
+        format!("SHOW ZONE CONFIGURATION FROM TABLE {}", self.qualified_table(schema, table))
+    }
+
+    pub fn build_show_database_zone_configuration_sql(&self, database: &str) -> String {
+        format!("SHOW ZONE CONFIGURATION FROM DATABASE {}", self.quote_identifier(database))
+    }
+
+    /// Only type changes that need no rewrite (same type, wider or unbounded length) run online;
+    /// anything else requires `enable_experimental_alter_column_type_general` and can't run in a transaction
+    fn is_online_type_change(original: &ColumnDefinition, new: &ColumnDefinition) -> bool {
+        if !original.data_type.eq_ignore_ascii_case(&new.data_type) || original.scale != new.scale {
+            return false;
+        }
+        match (original.length, new.length) {
+            (Some(old_length), Some(new_length)) => new_length >= old_length,
+            (_, None) => true,
+            (None, Some(_)) => false,
+        }
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for CockroachPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::CockroachDB
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        self.postgres.quote_identifier(identifier)
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        let mut info = self.postgres.get_completion_info();
+        info.keywords.extend([
+            ("AS OF SYSTEM TIME", "Historical read at a timestamp"),
+            ("SHOW RANGES", "Show range distribution"),
+            ("SHOW ZONE CONFIGURATION", "Show replication zone"),
+            ("CONFIGURE ZONE", "Change replication zone"),
+            ("ALTER PRIMARY KEY", "Change the primary key online"),
+            ("USING HASH", "Hash-sharded index"),
+            ("STORING", "Covering index columns"),
+        ]);
+        info
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = PostgresDbConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        self.postgres.sql_dialect()
+    }
+
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        self.postgres.read_only_transaction_sql()
+    }
+
+    fn supports_schema(&self) -> bool {
+        true
+    }
+
+    fn supports_sequences(&self) -> bool {
+        true
+    }
+
+    fn format_table_reference(&self, database: &str, schema: Option<&str>, table: &str) -> String {
+        self.postgres.format_table_reference(database, schema, table)
+    }
+
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        // Metrics are per node, so this counts statements routed through the node we are connected to
+        Some("SELECT value::INT8 FROM crdb_internal.node_metrics WHERE name = 'sql.query.count'")
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        query_server_status(
+            connection,
+            "SELECT version(), NULL, (SELECT count(*) FROM crdb_internal.cluster_sessions)",
+        ).await
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        // pg_settings in CockroachDB does not tell which settings can be changed
+        Some("SHOW ALL".to_string())
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        self.postgres.build_set_session_variable_sql(name, value)
+    }
+
+    fn insert_conflict_clause(&self, mode: SqlConflictMode, columns: &[String], key_columns: &[String]) -> Option<InsertConflictClause> {
+        on_conflict_clause(self, mode, columns, key_columns)
+    }
+
+    // === Database/Schema Level Operations ===
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        Ok(self.list_databases_detailed(connection).await?
+            .into_iter()
+            .map(|database| database.name)
+            .collect())
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let databases = self.list_databases_detailed(connection).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("region", "Primary Region").width(px(160.0)),
+            Column::new("comment", "Comment").width(px(300.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = databases.iter().map(|db| {
+            vec![
+                db.name.clone(),
+                db.collation.as_deref().unwrap_or("-").to_string(),
+                db.comment.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Database,
+            title: format!("{} database(s)", databases.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        // pg_database_size and friends are not implemented, SHOW DATABASES carries what is available
+        let result = connection.query(
+            "SELECT database_name, primary_region, comment FROM [SHOW DATABASES WITH COMMENT] \
+             WHERE database_name <> 'system' \
+             ORDER BY database_name",
+            None,
+            ExecOptions::default()
+        ).await.map_err(|e| anyhow::anyhow!("Failed to list databases: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter()
+                .filter_map(|row| {
+                    let name = row.first().and_then(|v| v.clone())?;
+                    Some(DatabaseInfo {
+                        name,
+                        charset: Some("UTF8".to_string()),
+                        // The region takes the collation slot, CockroachDB databases have no default collation
+                        collation: row.get(1).and_then(|v| v.clone()),
+                        size: None,
+                        table_count: None,
+                        comment: row.get(2).and_then(|v| v.clone()).filter(|s| !s.is_empty()),
+                    })
+                })
+                .collect())
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    async fn list_schemas(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<String>> {
+        let result = connection.query(
+            "SELECT schema_name FROM information_schema.schemata ORDER BY schema_name",
+            None,
+            ExecOptions::default()
+        ).await.map_err(|e| anyhow::anyhow!("Failed to list schemas: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter()
+                .filter_map(|row| row.first().and_then(|v| v.clone()))
+                .filter(|schema| !SYSTEM_SCHEMAS.contains(&schema.as_str()))
+                .collect())
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    async fn list_schemas_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let schemas = self.list_schemas(connection, database).await?;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Schema,
+            title: format!("{} schema(s)", schemas.len()),
+            columns: vec![Column::new("name", "Name").width(px(240.0))],
+            rows: schemas.into_iter().map(|schema| vec![schema]).collect(),
+        })
+    }
+
+    // === Table Operations ===
+
+    async fn list_tables(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<TableInfo>> {
+        // SHOW TABLES keeps an estimated row count from table statistics, reltuples is always zero
+        let sql = "SELECT table_name, schema_name, comment, estimated_row_count \
+                   FROM [SHOW TABLES WITH COMMENT] \
+                   WHERE type = 'table' \
+                   ORDER BY schema_name, table_name";
+
+        let result = connection.query(sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list tables: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                TableInfo {
+                    name: row.first().and_then(|v| v.clone()).unwrap_or_default(),
+                    schema: row.get(1).and_then(|v| v.clone()),
+                    comment: row.get(2).and_then(|v| v.clone()).filter(|s| !s.is_empty()),
+                    engine: None,
+                    row_count: row.get(3).and_then(|v| v.clone()).and_then(|s| s.parse::<i64>().ok()),
+                    create_time: None,
+                    charset: None,
+                    collation: None,
+                }
+            }).collect())
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("schema", "Schema").width(px(120.0)),
+            Column::new("rows", "Rows").width(px(100.0)).text_right(),
+            Column::new("comment", "Comment").width(px(400.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = tables.iter().map(|table| {
+            vec![
+                table.name.clone(),
+                table.schema.as_deref().unwrap_or("").to_string(),
+                table.row_count.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string()),
+                table.comment.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} table(s)", tables.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, _database: &str, schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let schema_val = schema.unwrap_or("public").replace("'", "''");
+        let table_val = table.replace("'", "''");
+        // Tables without a primary key get a hidden rowid column; it is not returned by SELECT *,
+        // so listing it would shift every column after it in the data grid
+        let sql = format!(
+            "SELECT c.column_name, c.data_type, c.is_nullable, c.column_default, \
+             EXISTS (SELECT 1 FROM information_schema.key_column_usage kcu \
+              JOIN information_schema.table_constraints tc \
+              ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema AND tc.table_name = kcu.table_name \
+              WHERE tc.constraint_type = 'PRIMARY KEY' AND kcu.table_schema = c.table_schema \
+              AND kcu.table_name = c.table_name AND kcu.column_name = c.column_name) AS is_primary, \
+             c.collation_name, col_description((quote_ident(c.table_schema) || '.' || quote_ident(c.table_name))::regclass,c.ordinal_position::INT) AS column_comment \
+             FROM information_schema.columns c \
+             WHERE c.table_schema = '{}' AND c.table_name = '{}' AND c.is_hidden = 'NO' \
+             ORDER BY c.ordinal_position",
+            schema_val, table_val
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list columns: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                ColumnInfo {
+                    name: row.first().and_then(|v| v.clone()).unwrap_or_default(),
+                    data_type: row.get(1).and_then(|v| v.clone()).unwrap_or_default(),
+                    is_nullable: row.get(2).and_then(|v| v.clone()).map(|v| v == "YES").unwrap_or(true),
+                    is_primary_key: row.get(4).and_then(|v| v.clone()).map(|v| v == "t" || v == "true" || v == "1").unwrap_or(false),
+                    default_value: row.get(3).and_then(|v| v.clone()),
+                    comment: row.get(6).and_then(|v| v.clone()).filter(|s| !s.is_empty()),
+                    collation: row.get(5).and_then(|v| v.clone()),
+                }
+            }).collect())
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns_data = self.list_columns(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("type", "Type").width(px(150.0)),
+            Column::new("nullable", "Nullable").width(px(80.0)),
+            Column::new("key", "Key").width(px(80.0)),
+            Column::new("default", "Default").width(px(200.0)),
+            Column::new("comment", "Comment").width(px(250.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = columns_data.iter().map(|col| {
+            vec![
+                col.name.clone(),
+                col.data_type.clone(),
+                if col.is_nullable { "YES" } else { "NO" }.to_string(),
+                if col.is_primary_key { "PRI" } else { "" }.to_string(),
+                col.default_value.as_deref().unwrap_or("").to_string(),
+                col.comment.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: format!("{} column(s)", columns_data.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_indexes(&self, connection: &dyn DbConnection, _database: &str, schema: Option<&str>, table: &str) -> Result<Vec<IndexInfo>> {
+        // STORING columns and the implicit primary key suffix of secondary indexes are not key columns
+        let sql = format!(
+            "SELECT index_name, column_name, non_unique \
+             FROM [SHOW INDEXES FROM {}] \
+             WHERE NOT storing AND NOT implicit \
+             ORDER BY index_name, seq_in_index",
+            self.qualified_table(schema, table)
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list indexes: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            let mut indexes: Vec<IndexInfo> = Vec::new();
+
+            for row in query_result.rows {
+                let index_name = row.first().and_then(|v| v.clone()).unwrap_or_default();
+                let column_name = row.get(1).and_then(|v| v.clone()).unwrap_or_default();
+                let non_unique = row.get(2).and_then(|v| v.clone()).map(|v| v == "t" || v == "true").unwrap_or(true);
+
+                match indexes.iter_mut().find(|index| index.name == index_name) {
+                    Some(index) => index.columns.push(column_name),
+                    None => indexes.push(IndexInfo {
+                        name: index_name,
+                        columns: vec![column_name],
+                        is_unique: !non_unique,
+                        index_type: None,
+                    }),
+                }
+            }
+
+            Ok(indexes)
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    async fn list_indexes_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let indexes = self.list_indexes(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("columns", "Columns").width(px(250.0)),
+            Column::new("unique", "Unique").width(px(80.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = indexes.iter().map(|idx| {
+            vec![
+                idx.name.clone(),
+                idx.columns.join(", "),
+                if idx.is_unique { "YES" } else { "NO" }.to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: format!("{} index(es)", indexes.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_table_checks(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<CheckInfo>> {
+        self.postgres.list_table_checks(connection, database, schema, table).await
+    }
+
+    // === View Operations ===
+
+    async fn list_views(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<ViewInfo>> {
+        self.postgres.list_views(connection, database).await
+    }
+
+    async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.postgres.list_views_view(connection, database).await
+    }
+
+    // === Function/Procedure Operations ===
+
+    async fn list_functions(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<FunctionInfo>> {
+        self.postgres.list_functions(connection, database).await
+    }
+
+    async fn list_functions_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.postgres.list_functions_view(connection, database).await
+    }
+
+    async fn list_procedures(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<FunctionInfo>> {
+        self.postgres.list_procedures(connection, database).await
+    }
+
+    async fn list_procedures_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.postgres.list_procedures_view(connection, database).await
+    }
+
+    async fn get_routine_source(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        self.postgres.get_routine_source(connection, database, schema, object_type, name).await
+    }
+
+    // === Trigger Operations ===
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        // pg_trigger is an empty compatibility table, triggers are not browsable
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns: vec![
+                Column::new("name", "Name").width(px(180.0)),
+                Column::new("table", "Table").width(px(150.0)),
+            ],
+            rows: Vec::new(),
+        })
+    }
+
+    // === Sequence Operations ===
+
+    async fn list_sequences(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<SequenceInfo>> {
+        self.postgres.list_sequences(connection, database).await
+    }
+
+    async fn list_sequences_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.postgres.list_sequences_view(connection, database).await
+    }
+
+    fn get_data_types(&self) -> Vec<DataTypeInfo> {
+        self.postgres.get_data_types()
+    }
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        self.postgres.build_column_definition(column, include_name)
+    }
+
+    fn build_create_database_sql(&self, request: &crate::plugin::DatabaseOperationRequest) -> String {
+        // UTF8 is the only encoding, ENCODING clauses naming anything else are rejected
+        format!("CREATE DATABASE {};", self.quote_identifier(&request.database_name))
+    }
+
+    fn build_modify_database_sql(&self, request: &crate::plugin::DatabaseOperationRequest) -> String {
+        self.postgres.build_modify_database_sql(request)
+    }
+
+    fn build_drop_database_sql(&self, database_name: &str) -> String {
+        format!("DROP DATABASE {} CASCADE;", self.quote_identifier(database_name))
+    }
+
+    fn build_create_schema_sql(&self, schema_name: &str) -> String {
+        self.postgres.build_create_schema_sql(schema_name)
+    }
+
+    fn build_drop_schema_sql(&self, schema_name: &str) -> String {
+        self.postgres.build_drop_schema_sql(schema_name)
+    }
+
+    fn build_comment_schema_sql(&self, schema_name: &str, comment: &str) -> Option<String> {
+        self.postgres.build_comment_schema_sql(schema_name, comment)
+    }
+
+    fn rename_table(&self, database: &str, old_name: &str, new_name: &str) -> String {
+        self.postgres.rename_table(database, old_name, new_name)
+    }
+
+    fn build_limit_clause(&self) -> String {
+        self.postgres.build_limit_clause()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        request: &crate::types::TableSaveRequest,
+        original_data: &[String],
+    ) -> (String, String) {
+        let where_clause = self.build_table_change_where_clause(request, original_data);
+        (where_clause, self.build_limit_clause())
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        self.postgres.build_column_def(col)
+    }
+
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        self.postgres.build_create_table_sql(design)
+    }
+
+    fn build_alter_table_sql(&self, original: &TableDesign, new: &TableDesign) -> String {
+        let mut statements: Vec<String> = Vec::new();
+        let table_name = self.quote_identifier(&new.table_name);
+
+        let original_cols: std::collections::HashMap<&str, &ColumnDefinition> = original.columns
+            .iter()
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+        let new_cols: std::collections::HashMap<&str, &ColumnDefinition> = new.columns
+            .iter()
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+
+        for column in &original.columns {
+            if !new_cols.contains_key(column.name.as_str()) {
+                statements.push(format!(
+                    "ALTER TABLE {} DROP COLUMN {};",
+                    table_name,
+                    self.quote_identifier(&column.name)
+                ));
+            }
+        }
+
+        for col in new.columns.iter() {
+            let Some(orig_col) = original_cols.get(col.name.as_str()) else {
+                statements.push(format!("ALTER TABLE {} ADD COLUMN {};", table_name, self.build_column_def(col)));
+                continue;
+            };
+            if !self.column_changed(orig_col, col) {
+                continue;
+            }
+            let col_name = self.quote_identifier(&col.name);
+
+            if orig_col.data_type != col.data_type || orig_col.length != col.length || orig_col.scale != col.scale {
+                if Self::is_online_type_change(orig_col, col) {
+                    statements.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
+                        table_name, col_name, self.build_type_string(col)
+                    ));
+                } else {
+                    statements.push(format!(
+                        "-- Skipped: changing {} from {} to {} rewrites the column, which CockroachDB does not support online",
+                        col_name,
+                        self.build_type_string(orig_col),
+                        self.build_type_string(col)
+                    ));
+                }
+            }
+
+            if orig_col.is_nullable != col.is_nullable {
+                let action = if col.is_nullable { "DROP NOT NULL" } else { "SET NOT NULL" };
+                statements.push(format!("ALTER TABLE {} ALTER COLUMN {} {};", table_name, col_name, action));
+            }
+
+            if orig_col.default_value != col.default_value {
+                match &col.default_value {
+                    Some(default) => statements.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                        table_name, col_name, default
+                    )),
+                    None => statements.push(format!(
+                        "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                        table_name, col_name
+                    )),
+                }
+            }
+        }
+
+        let original_primary = original.indexes.iter().find(|i| i.is_primary);
+        let new_primary = new.indexes.iter().find(|i| i.is_primary);
+
+        for idx in &original.indexes {
+            if idx.is_primary || new.indexes.iter().any(|i| i.name == idx.name) {
+                continue;
+            }
+            // Index names are scoped to their table, so DROP INDEX needs the table@index form
+            statements.push(format!("DROP INDEX {}@{};", table_name, self.quote_identifier(&idx.name)));
+        }
+
+        // Every table has a primary key; it is swapped in one step rather than dropped and re-added
+        match (original_primary, new_primary) {
+            (Some(_), None) => {
+                statements.push("-- Skipped: CockroachDB tables always have a primary key, choose new key columns instead of dropping it".to_string());
+            }
+            (original_primary, Some(primary)) if original_primary.map(|i| &i.columns) != Some(&primary.columns) => {
+                let idx_cols: Vec<String> = primary.columns.iter().map(|c| self.quote_identifier(c)).collect();
+                statements.push(format!(
+                    "ALTER TABLE {} ALTER PRIMARY KEY USING COLUMNS ({});",
+                    table_name,
+                    idx_cols.join(", ")
+                ));
+            }
+            _ => {}
+        }
+
+        for idx in &new.indexes {
+            if idx.is_primary || original.indexes.iter().any(|i| i.name == idx.name) {
+                continue;
+            }
+            let idx_cols: Vec<String> = idx.columns.iter().map(|c| self.quote_identifier(c)).collect();
+            let unique_str = if idx.is_unique { "UNIQUE " } else { "" };
+            statements.push(format!(
+                "CREATE {}INDEX {} ON {} ({});",
+                unique_str,
+                self.quote_identifier(&idx.name),
+                table_name,
+                idx_cols.join(", ")
+            ));
+        }
+
+        if statements.is_empty() {
+            "-- No changes detected".to_string()
+        } else {
+            statements.join("\n")
+        }
+    }
+}
+
+impl Default for CockroachPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ColumnDefinition, IndexDefinition, TableDesign, TableOptions};
+
+    fn create_plugin() -> CockroachPlugin {
+        CockroachPlugin::new()
+    }
+
+    fn design(columns: Vec<ColumnDefinition>, indexes: Vec<IndexDefinition>) -> TableDesign {
+        TableDesign {
+            database_name: "defaultdb".to_string(),
+            table_name: "users".to_string(),
+            columns,
+            indexes,
+            foreign_keys: vec![],
+            options: TableOptions::default(),
+        }
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        assert_eq!(create_plugin().name(), DatabaseType::CockroachDB);
+    }
+
+    #[test]
+    fn test_ranges_and_zone_sql() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.build_show_ranges_sql(Some("public"), "users"),
+            "SHOW RANGES FROM TABLE \"public\".\"users\" WITH DETAILS"
+        );
+        assert_eq!(
+            plugin.build_show_database_ranges_sql("defaultdb"),
+            "SHOW RANGES FROM DATABASE \"defaultdb\" WITH TABLES"
+        );
+        assert_eq!(
+            plugin.build_show_zone_configuration_sql(None, "users"),
+            "SHOW ZONE CONFIGURATION FROM TABLE \"public\".\"users\""
+        );
+        assert_eq!(
+            plugin.build_show_database_zone_configuration_sql("defaultdb"),
+            "SHOW ZONE CONFIGURATION FROM DATABASE \"defaultdb\""
+        );
+    }
+
+    #[test]
+    fn test_database_sql() {
+        let plugin = create_plugin();
+        let request = crate::plugin::DatabaseOperationRequest {
+            database_name: "shop".to_string(),
+            field_values: std::collections::HashMap::from([("encoding".to_string(), "LATIN1".to_string())]),
+        };
+        assert_eq!(plugin.build_create_database_sql(&request), "CREATE DATABASE \"shop\";");
+        assert_eq!(plugin.build_drop_database_sql("shop"), "DROP DATABASE \"shop\" CASCADE;");
+    }
+
+    #[test]
+    fn test_alter_table_skips_rewriting_type_change() {
+        let plugin = create_plugin();
+        let original = design(vec![ColumnDefinition::new("age").data_type("INT4")], vec![]);
+        let new = design(vec![ColumnDefinition::new("age").data_type("TEXT")], vec![]);
+
+        let sql = plugin.build_alter_table_sql(&original, &new);
+        assert!(sql.starts_with("-- Skipped"));
+        assert!(!sql.contains("ALTER COLUMN \"age\" TYPE"));
+    }
+
+    #[test]
+    fn test_alter_table_widens_varchar() {
+        let plugin = create_plugin();
+        let original = design(vec![ColumnDefinition::new("name").data_type("VARCHAR").length(50)], vec![]);
+        let new = design(vec![ColumnDefinition::new("name").data_type("VARCHAR").length(100)], vec![]);
+
+        let sql = plugin.build_alter_table_sql(&original, &new);
+        assert_eq!(sql, "ALTER TABLE \"users\" ALTER COLUMN \"name\" TYPE VARCHAR(100);");
+
+        let shrink = plugin.build_alter_table_sql(&new, &original);
+        assert!(shrink.starts_with("-- Skipped"));
+    }
+
+    #[test]
+    fn test_alter_table_primary_key_and_indexes() {
+        let plugin = create_plugin();
+        let columns = vec![
+            ColumnDefinition::new("id").data_type("INT8"),
+            ColumnDefinition::new("email").data_type("STRING"),
+        ];
+        let original = design(columns.clone(), vec![
+            IndexDefinition::new("users_pkey").columns(vec!["id".to_string()]).primary(true),
+            IndexDefinition::new("users_email_idx").columns(vec!["email".to_string()]),
+        ]);
+        let new = design(columns.clone(), vec![
+            IndexDefinition::new("users_pkey").columns(vec!["id".to_string(), "email".to_string()]).primary(true),
+            IndexDefinition::new("users_email_key").columns(vec!["email".to_string()]).unique(true),
+        ]);
+
+        let sql = plugin.build_alter_table_sql(&original, &new);
+        assert!(sql.contains("DROP INDEX \"users\"@\"users_email_idx\";"));
+        assert!(sql.contains("ALTER TABLE \"users\" ALTER PRIMARY KEY USING COLUMNS (\"id\", \"email\");"));
+        assert!(sql.contains("CREATE UNIQUE INDEX \"users_email_key\" ON \"users\" (\"email\");"));
+        assert!(!sql.contains("DROP CONSTRAINT"));
+
+        let without_primary = design(columns, vec![]);
+        let sql = plugin.build_alter_table_sql(&original, &without_primary);
+        assert!(sql.contains("-- Skipped: CockroachDB tables always have a primary key"));
+        assert!(!sql.contains("DROP CONSTRAINT"));
+    }
+}
@@ -51,13 +51,23 @@ pub trait DbConnection: Sync + Send {
         true
     }
 
+    /// Access to the concrete connection, for plugins that need driver-specific APIs
+    fn as_any(&self) -> Option<&dyn std::any::Any> {
+        None
+    }
+
     async fn connect(&mut self) -> Result<(), DbError>;
     async fn disconnect(&mut self) -> Result<(), DbError>;
     async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError>;
     async fn query(&self, query: &str, params: Option<Vec<SqlValue>>, options: ExecOptions) -> Result<SqlResult, DbError>;
 
+    /// Drivers report statement failures as `SqlResult::Error`, which has to count as a failed ping
+    /// or a dropped connection would look healthy
     async fn ping(&self) -> Result<(), DbError> {
-        self.query("SELECT 1", None, ExecOptions::default()).await.map(|_| ())
+        match self.query("SELECT 1", None, ExecOptions::default()).await? {
+            SqlResult::Error(error) => Err(DbError::ConnectionError(error.message)),
+            _ => Ok(()),
+        }
     }
 
     /// Get current database/schema name from the connection
@@ -73,4 +83,24 @@ pub trait DbConnection: Sync + Send {
         options: ExecOptions,
         sender: mpsc::Sender<StreamingProgress>,
     ) -> Result<(), DbError>;
+
+    /// Execute statements of a bulk import. Connections that can buffer inserts
+    /// on the server (ClickHouse `async_insert`) apply it when requested
+    async fn execute_import(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        async_insert: bool,
+    ) -> Result<Vec<SqlResult>, DbError> {
+        let _ = async_insert;
+        self.execute(plugin, script, options).await
+    }
+
+    /// Run a query and return the raw result rendered by the server in `format`,
+    /// or `None` when the database cannot format results itself
+    async fn export_formatted(&self, sql: &str, format: &str) -> Result<Option<Vec<u8>>, DbError> {
+        let _ = (sql, format);
+        Ok(None)
+    }
 }
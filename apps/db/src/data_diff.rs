@@ -0,0 +1,212 @@
+//! Row-level difference between two result sets of the same table.
+//!
+//! The difference is expressed as `TableRowChange`s against the target so the SQL patch is
+//! generated by `DatabasePlugin::generate_table_changes_sql`, the same path used when saving
+//! edits from the data grid.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::executor::QueryResult;
+use crate::types::{TableCellChange, TableRowChange, TableSaveRequest};
+
+/// Changes that reconcile the target rows with the source rows
+#[derive(Debug, Clone)]
+pub struct RowDiff {
+    /// Column layout of the target, which all changes refer to
+    pub columns: Vec<String>,
+    pub key_indices: Vec<usize>,
+    pub changes: Vec<TableRowChange>,
+}
+
+impl RowDiff {
+    pub fn insert_count(&self) -> usize {
+        self.changes.iter().filter(|c| matches!(c, TableRowChange::Added { .. })).count()
+    }
+
+    pub fn update_count(&self) -> usize {
+        self.changes.iter().filter(|c| matches!(c, TableRowChange::Updated { .. })).count()
+    }
+
+    pub fn delete_count(&self) -> usize {
+        self.changes.iter().filter(|c| matches!(c, TableRowChange::Deleted { .. })).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Save request whose generated SQL applies the difference to `table`
+    pub fn into_save_request(self, database: &str, schema: Option<&str>, table: &str) -> TableSaveRequest {
+        TableSaveRequest {
+            database: database.to_string(),
+            schema: schema.map(|s| s.to_string()),
+            table: table.to_string(),
+            column_names: self.columns,
+            primary_key_indices: self.key_indices,
+            unique_key_indices: Vec::new(),
+            changes: self.changes,
+        }
+    }
+}
+
+/// Table changes use the literal "NULL" for null cells
+fn cell_text(cell: &Option<String>) -> String {
+    cell.clone().unwrap_or_else(|| "NULL".to_string())
+}
+
+fn column_index(columns: &[String], name: &str) -> Option<usize> {
+    columns.iter().position(|c| c.eq_ignore_ascii_case(name))
+}
+
+/// Compare `source` with `target` and return the changes that make `target` equal to `source`.
+///
+/// Rows are matched by `key_columns`; without key columns every column is part of the key, so
+/// changed rows become a DELETE plus an INSERT. Every target column must exist in `source`.
+pub fn diff_result_rows(source: &QueryResult, target: &QueryResult, key_columns: &[String]) -> Result<RowDiff> {
+    let source_indices: Vec<usize> = target
+        .columns
+        .iter()
+        .map(|name| column_index(&source.columns, name).ok_or_else(|| anyhow!("Column {} is missing from the source", name)))
+        .collect::<Result<_>>()?;
+    let key_indices: Vec<usize> = key_columns
+        .iter()
+        .map(|name| column_index(&target.columns, name).ok_or_else(|| anyhow!("Key column {} is missing from the target", name)))
+        .collect::<Result<_>>()?;
+    let match_indices: Vec<usize> = if key_indices.is_empty() {
+        (0..target.columns.len()).collect()
+    } else {
+        key_indices.clone()
+    };
+    let key_of = |row: &[Option<String>]| -> Vec<Option<String>> {
+        match_indices.iter().map(|&i| row.get(i).cloned().flatten()).collect()
+    };
+
+    // A key may map to several rows (duplicates when matching whole rows); match them in order
+    let mut target_by_key: HashMap<Vec<Option<String>>, Vec<usize>> = HashMap::new();
+    for (index, row) in target.rows.iter().enumerate().rev() {
+        target_by_key.entry(key_of(row)).or_default().push(index);
+    }
+
+    let mut matched = vec![false; target.rows.len()];
+    let mut changes = Vec::new();
+    for source_row in &source.rows {
+        let row: Vec<Option<String>> = source_indices.iter().map(|&i| source_row.get(i).cloned().flatten()).collect();
+        let Some(target_index) = target_by_key.get_mut(&key_of(&row)).and_then(|indices| indices.pop()) else {
+            changes.push(TableRowChange::Added { data: row.iter().map(cell_text).collect() });
+            continue;
+        };
+        matched[target_index] = true;
+
+        let target_row = &target.rows[target_index];
+        let cell_changes: Vec<TableCellChange> = row
+            .iter()
+            .enumerate()
+            .filter(|(index, value)| target_row.get(*index).cloned().flatten() != **value)
+            .map(|(index, value)| TableCellChange {
+                column_index: index,
+                column_name: target.columns[index].clone(),
+                old_value: cell_text(&target_row.get(index).cloned().flatten()),
+                new_value: cell_text(value),
+            })
+            .collect();
+        if !cell_changes.is_empty() {
+            changes.push(TableRowChange::Updated {
+                original_data: target_row.iter().map(cell_text).collect(),
+                changes: cell_changes,
+            });
+        }
+    }
+
+    for (index, row) in target.rows.iter().enumerate() {
+        if !matched[index] {
+            changes.push(TableRowChange::Deleted { original_data: row.iter().map(cell_text).collect() });
+        }
+    }
+
+    Ok(RowDiff {
+        columns: target.columns.clone(),
+        key_indices,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mysql::MySqlPlugin;
+    use crate::plugin::DatabasePlugin;
+
+    fn result(columns: &[&str], rows: &[&[Option<&str>]]) -> QueryResult {
+        QueryResult {
+            sql: String::new(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|cell| cell.map(|v| v.to_string())).collect())
+                .collect(),
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        }
+    }
+
+    #[test]
+    fn test_diff_by_key_produces_insert_update_delete() {
+        let source = result(
+            &["name", "id"],
+            &[&[Some("alice"), Some("1")], &[Some("bobby"), Some("2")], &[Some("carol"), Some("4")]],
+        );
+        let target = result(
+            &["id", "name"],
+            &[&[Some("1"), Some("alice")], &[Some("2"), Some("bob")], &[Some("3"), None]],
+        );
+
+        let diff = diff_result_rows(&source, &target, &["id".to_string()]).unwrap();
+        assert_eq!((diff.insert_count(), diff.update_count(), diff.delete_count()), (1, 1, 1));
+
+        match &diff.changes[0] {
+            TableRowChange::Updated { original_data, changes } => {
+                assert_eq!(original_data, &vec!["2".to_string(), "bob".to_string()]);
+                assert_eq!(changes.len(), 1);
+                assert_eq!(changes[0].column_name, "name");
+                assert_eq!(changes[0].new_value, "bobby");
+            }
+            other => panic!("unexpected change {:?}", other),
+        }
+        match &diff.changes[2] {
+            TableRowChange::Deleted { original_data } => assert_eq!(original_data, &vec!["3".to_string(), "NULL".to_string()]),
+            other => panic!("unexpected change {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff_without_key_matches_whole_rows() {
+        let source = result(&["id", "name"], &[&[Some("1"), Some("a")], &[Some("1"), Some("a")]]);
+        let target = result(&["id", "name"], &[&[Some("1"), Some("a")], &[Some("2"), Some("b")]]);
+
+        let diff = diff_result_rows(&source, &target, &[]).unwrap();
+        assert_eq!((diff.insert_count(), diff.update_count(), diff.delete_count()), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_diff_rejects_missing_columns() {
+        let source = result(&["id"], &[]);
+        let target = result(&["id", "name"], &[]);
+        assert!(diff_result_rows(&source, &target, &["id".to_string()]).is_err());
+        assert!(diff_result_rows(&target, &target, &["missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_diff_generates_patch_sql() {
+        let source = result(&["id", "name"], &[&[Some("1"), Some("O'Brien")]]);
+        let target = result(&["id", "name"], &[&[Some("1"), Some("Obrien")]]);
+
+        let request = diff_result_rows(&source, &target, &["id".to_string()])
+            .unwrap()
+            .into_save_request("shop", None, "users");
+        let sql = MySqlPlugin::new().generate_table_changes_sql(&request);
+        assert!(sql.starts_with("UPDATE `shop`.`users` SET `name` = 'O''Brien' WHERE `id` = '1'"), "{}", sql);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod connection;
+pub mod plugin;
+
+pub use connection::DuckDbConnection;
+pub use plugin::DuckDbPlugin;
@@ -0,0 +1,404 @@
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use duckdb::types::{TimeUnit, Value};
+use duckdb::{AccessMode, Config, Connection};
+use one_core::storage::DbConnectionConfig;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+
+use crate::connection::{DbConnection, DbError, StreamingProgress};
+use crate::duckdb::plugin::is_duckdb_query;
+use crate::executor::{
+    ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult,
+    SqlStatementClassifier,
+};
+use crate::{DatabasePlugin, SqlValue};
+
+/// Path that opens a throwaway in-memory database
+pub const IN_MEMORY_PATH: &str = ":memory:";
+
+pub struct DuckDbConnection {
+    config: DbConnectionConfig,
+    conn: Arc<Mutex<Option<Connection>>>,
+}
+
+impl DuckDbConnection {
+    pub fn new(config: DbConnectionConfig) -> Self {
+        Self {
+            config,
+            conn: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// DuckDB uses the `host` field as the database file path, like SQLite; empty means in-memory
+    pub fn database_path(config: &DbConnectionConfig) -> String {
+        let path = config.host.trim();
+        if path.is_empty() {
+            IN_MEMORY_PATH.to_string()
+        } else {
+            path.to_string()
+        }
+    }
+
+    fn open(config: &DbConnectionConfig) -> Result<Connection, DbError> {
+        let path = Self::database_path(config);
+        if path == IN_MEMORY_PATH {
+            return Connection::open_in_memory()
+                .map_err(|e| DbError::ConnectionError(format!("Failed to open in-memory database: {}", e)));
+        }
+
+        // A file opened read-write is locked against every other process, read-only lets several tools share it
+        let access_mode = if config.get_param_bool("read_only") {
+            AccessMode::ReadOnly
+        } else {
+            AccessMode::ReadWrite
+        };
+        let duckdb_config = Config::default()
+            .access_mode(access_mode)
+            .map_err(|e| DbError::ConnectionError(format!("Invalid configuration: {}", e)))?;
+
+        Connection::open_with_flags(&path, duckdb_config)
+            .map_err(|e| DbError::ConnectionError(format!("Failed to open {}: {}", path, e)))
+    }
+
+    fn timestamp_micros(unit: TimeUnit, value: i64) -> i64 {
+        match unit {
+            TimeUnit::Second => value.saturating_mul(1_000_000),
+            TimeUnit::Millisecond => value.saturating_mul(1_000),
+            TimeUnit::Microsecond => value,
+            TimeUnit::Nanosecond => value / 1_000,
+        }
+    }
+
+    pub fn value_to_text(value: Value) -> Option<String> {
+        use chrono::{DateTime, NaiveDate, NaiveTime};
+
+        let text = match value {
+            Value::Null => return None,
+            Value::Boolean(v) => v.to_string(),
+            Value::TinyInt(v) => v.to_string(),
+            Value::SmallInt(v) => v.to_string(),
+            Value::Int(v) => v.to_string(),
+            Value::BigInt(v) => v.to_string(),
+            Value::HugeInt(v) => v.to_string(),
+            Value::UTinyInt(v) => v.to_string(),
+            Value::USmallInt(v) => v.to_string(),
+            Value::UInt(v) => v.to_string(),
+            Value::UBigInt(v) => v.to_string(),
+            Value::Float(v) => v.to_string(),
+            Value::Double(v) => v.to_string(),
+            Value::Decimal(v) => v.to_string(),
+            Value::Text(v) | Value::Enum(v) => v,
+            Value::Blob(v) => match String::from_utf8(v) {
+                Ok(s) => s,
+                Err(e) => format!("0x{}", hex::encode(e.as_bytes())),
+            },
+            Value::Timestamp(unit, v) => {
+                let micros = Self::timestamp_micros(unit, v);
+                match DateTime::from_timestamp_micros(micros) {
+                    Some(dt) => dt.naive_utc().format("%Y-%m-%d %H:%M:%S%.f").to_string(),
+                    None => micros.to_string(),
+                }
+            }
+            Value::Date32(days) => NaiveDate::from_ymd_opt(1970, 1, 1)
+                .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(days as i64)))
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| days.to_string()),
+            Value::Time64(unit, v) => {
+                let micros = Self::timestamp_micros(unit, v);
+                let seconds = (micros / 1_000_000) as u32;
+                let nanos = ((micros % 1_000_000) * 1_000) as u32;
+                NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos)
+                    .map(|time| time.format("%H:%M:%S%.f").to_string())
+                    .unwrap_or_else(|| micros.to_string())
+            }
+            Value::List(values) | Value::Array(values) => format!(
+                "[{}]",
+                values
+                    .into_iter()
+                    .map(|v| Self::value_to_text(v).unwrap_or_else(|| "NULL".to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            other => format!("{:?}", other),
+        };
+        Some(text)
+    }
+
+    fn fetch_rows(
+        conn: &Connection,
+        sql: &str,
+        max_rows: Option<usize>,
+    ) -> Result<(Vec<String>, Vec<Vec<Option<String>>>), duckdb::Error> {
+        let mut statement = conn.prepare(sql)?;
+        let mut rows = statement.query([])?;
+        // Column metadata is only known once the statement has run
+        let columns = rows
+            .as_ref()
+            .map(|statement| statement.column_names())
+            .unwrap_or_default();
+
+        let mut data_rows = Vec::new();
+        while let Some(row) = rows.next()? {
+            if max_rows.is_some_and(|max| data_rows.len() >= max) {
+                break;
+            }
+            let values = (0..columns.len())
+                .map(|index| row.get::<_, Value>(index).ok().and_then(Self::value_to_text))
+                .collect();
+            data_rows.push(values);
+        }
+
+        Ok((columns, data_rows))
+    }
+
+    /// `max_rows` is applied while fetching so FROM-first queries and PIVOT don't need a rewritten LIMIT
+    fn execute_sync(conn: &Connection, sql: &str, max_rows: Option<usize>) -> SqlResult {
+        let start = Instant::now();
+        let sql_string = sql.to_string();
+
+        let outcome = if is_duckdb_query(sql) {
+            Self::fetch_rows(conn, sql, max_rows).map(|(columns, rows)| {
+                let table_name = SqlStatementClassifier::analyze_select_editability(sql);
+                let editable = table_name.is_some();
+                SqlResult::Query(QueryResult {
+                    sql: sql_string.clone(),
+                    columns,
+                    rows,
+                    elapsed_ms: start.elapsed().as_millis(),
+                    table_name,
+                    editable,
+                })
+            })
+        } else {
+            conn.execute(sql, []).map(|rows_affected| {
+                let rows_affected = rows_affected as u64;
+                SqlResult::Exec(ExecResult {
+                    sql: sql_string.clone(),
+                    rows_affected,
+                    elapsed_ms: start.elapsed().as_millis(),
+                    message: Some(SqlStatementClassifier::format_message(sql, rows_affected)),
+                })
+            })
+        };
+
+        outcome.unwrap_or_else(|e| SqlResult::Error(SqlErrorInfo {
+            sql: sql_string,
+            message: e.to_string(),
+        }))
+    }
+
+    /// The DuckDB API is synchronous, so every call runs on the blocking pool
+    async fn with_connection<T, F>(&self, f: F) -> Result<T, DbError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, DbError> + Send + 'static,
+    {
+        let conn_arc = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let guard = conn_arc.blocking_lock();
+            let conn = guard.as_ref()
+                .ok_or_else(|| DbError::ConnectionError("Not connected to database".to_string()))?;
+            f(conn)
+        })
+        .await
+        .map_err(|e| DbError::QueryError(format!("Task error: {}", e)))?
+    }
+}
+
+#[async_trait]
+impl DbConnection for DuckDbConnection {
+    fn config(&self) -> &DbConnectionConfig {
+        &self.config
+    }
+
+    fn set_config_database(&mut self, database: Option<String>) {
+        self.config.database = database;
+    }
+
+    fn supports_database_switch(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    async fn connect(&mut self) -> Result<(), DbError> {
+        let config = self.config.clone();
+        let conn = tokio::task::spawn_blocking(move || Self::open(&config))
+            .await
+            .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))??;
+
+        {
+            let mut guard = self.conn.lock().await;
+            *guard = Some(conn);
+        }
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), DbError> {
+        let conn_opt = {
+            let mut guard = self.conn.lock().await;
+            guard.take()
+        };
+
+        if let Some(conn) = conn_opt {
+            // Closing checkpoints the WAL into the database file, which may take a while
+            tokio::task::spawn_blocking(move || conn.close().map_err(|(_, e)| e))
+                .await
+                .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))?
+                .map_err(|e| DbError::ConnectionError(format!("Failed to disconnect: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
+        let statements: Vec<String> = plugin.split_statements(script)
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut results = Vec::new();
+        let max_rows = options.max_rows;
+
+        for sql in statements {
+            let result = self.with_connection(move |conn| Ok(Self::execute_sync(conn, &sql, max_rows))).await?;
+
+            let is_error = result.is_error();
+            results.push(result);
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn query(
+        &self,
+        query: &str,
+        params: Option<Vec<SqlValue>>,
+        options: ExecOptions,
+    ) -> Result<SqlResult, DbError> {
+        if params.is_some() {
+            return Err(DbError::QueryError(
+                "Parameterized queries not yet supported for DuckDB".to_string(),
+            ));
+        }
+
+        let sql = query.to_string();
+        let max_rows = options.max_rows;
+        self.with_connection(move |conn| Ok(Self::execute_sync(conn, &sql, max_rows))).await
+    }
+
+    async fn current_database(&self) -> Result<Option<String>, DbError> {
+        self.with_connection(|conn| {
+            conn.query_row("SELECT current_database()", [], |row| row.get::<_, String>(0))
+                .map(Some)
+                .map_err(|e| DbError::QueryError(e.to_string()))
+        })
+        .await
+    }
+
+    async fn switch_database(&self, database: &str) -> Result<(), DbError> {
+        let sql = format!("USE \"{}\"", database.replace('"', "\"\""));
+        self.with_connection(move |conn| {
+            conn.execute_batch(&sql)
+                .map_err(|e| DbError::QueryError(format!("Failed to switch database: {}", e)))
+        })
+        .await
+    }
+
+    async fn execute_streaming(
+        &self, plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        sender: mpsc::Sender<StreamingProgress>,
+    ) -> Result<(), DbError> {
+        let statements: Vec<String> = plugin.split_statements(script)
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let total = statements.len();
+        let max_rows = options.max_rows;
+
+        if options.transactional {
+            self.with_connection(|conn| {
+                conn.execute_batch("BEGIN TRANSACTION")
+                    .map_err(|e| DbError::QueryError(format!("Failed to begin transaction: {}", e)))
+            })
+            .await?;
+        }
+
+        let mut has_error = false;
+        for (index, sql) in statements.into_iter().enumerate() {
+            let current = index + 1;
+            let sql_clone = sql.clone();
+
+            let result = match self.with_connection(move |conn| Ok(Self::execute_sync(conn, &sql_clone, max_rows))).await {
+                Ok(r) => r,
+                Err(e) => SqlResult::Error(SqlErrorInfo {
+                    sql: sql.clone(),
+                    message: e.to_string(),
+                }),
+            };
+
+            let is_error = result.is_error();
+            has_error |= is_error;
+            let progress = StreamingProgress {
+                current,
+                total,
+                result,
+            };
+
+            if sender.send(progress).await.is_err() {
+                break;
+            }
+
+            if is_error && (options.stop_on_error || options.transactional) {
+                break;
+            }
+        }
+
+        if options.transactional {
+            let finish = if has_error { "ROLLBACK" } else { "COMMIT" };
+            self.with_connection(move |conn| {
+                conn.execute_batch(finish)
+                    .map_err(|e| DbError::QueryError(format!("Failed to {}: {}", finish.to_lowercase(), e)))
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_to_text() {
+        assert_eq!(DuckDbConnection::value_to_text(Value::Null), None);
+        assert_eq!(DuckDbConnection::value_to_text(Value::BigInt(42)), Some("42".to_string()));
+        assert_eq!(DuckDbConnection::value_to_text(Value::Date32(19723)), Some("2024-01-01".to_string()));
+        assert_eq!(
+            DuckDbConnection::value_to_text(Value::Timestamp(TimeUnit::Second, 1_704_067_200)),
+            Some("2024-01-01 00:00:00".to_string())
+        );
+        assert_eq!(
+            DuckDbConnection::value_to_text(Value::List(vec![Value::Int(1), Value::Null])),
+            Some("[1, NULL]".to_string())
+        );
+    }
+}
@@ -0,0 +1,951 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig};
+use sqlparser::parser::Parser;
+
+use crate::connection::{DbConnection, DbError};
+use crate::duckdb::DuckDbConnection;
+use crate::executor::{ExecOptions, SqlResult, StatementType};
+use crate::plugin::{classify_fallback, classify_stmt, fallback_split_with_db_type, on_conflict_clause, string_literal, DatabasePlugin, SqlCompletionInfo};
+use crate::import_export::{InsertConflictClause, SqlConflictMode};
+use crate::server_status::{query_server_status, ServerStatus};
+use crate::session_variable::session_variable_literal;
+use crate::types::*;
+
+/// Leading keywords of statements that return rows. Besides the usual SELECT family DuckDB
+/// allows FROM-first queries (`FROM 'data.parquet'`), SUMMARIZE and PIVOT.
+const QUERY_KEYWORDS: &[&str] = &[
+    "SELECT", "WITH", "FROM", "VALUES", "TABLE", "SHOW", "DESCRIBE", "DESC", "SUMMARIZE",
+    "EXPLAIN", "PIVOT", "UNPIVOT", "PRAGMA", "CALL",
+];
+
+/// Schemas DuckDB creates for catalog compatibility, hidden from the tree
+const SYSTEM_SCHEMAS: &[&str] = &["information_schema", "pg_catalog"];
+
+fn skip_leading_comments(sql: &str) -> &str {
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map(|(_, tail)| tail).unwrap_or("").trim_start();
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map(|(_, tail)| tail).unwrap_or("").trim_start();
+        } else {
+            return rest;
+        }
+    }
+}
+
+/// Check whether a DuckDB statement returns rows, judged by its leading keyword
+pub fn is_duckdb_query(sql: &str) -> bool {
+    let sql = skip_leading_comments(sql);
+    if sql.starts_with('(') {
+        return true;
+    }
+    let first_word = sql
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ';')
+        .next()
+        .unwrap_or("")
+        .to_uppercase();
+    QUERY_KEYWORDS.contains(&first_word.as_str())
+}
+
+fn cell(row: &[Option<String>], index: usize) -> Option<String> {
+    row.get(index).cloned().flatten()
+}
+
+/// DuckDB database plugin: a file-based analytics engine, opened like SQLite
+pub struct DuckDbPlugin;
+
+impl DuckDbPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn query_rows(&self, connection: &dyn DbConnection, sql: &str, what: &str) -> Result<Vec<Vec<Option<String>>>> {
+        let result = connection.query(sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list {}: {}", what, e))?;
+
+        match result {
+            SqlResult::Query(query_result) => Ok(query_result.rows),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("Failed to list {}: {}", what, err.message)),
+            SqlResult::Exec(_) => Err(anyhow::anyhow!("Unexpected result type")),
+        }
+    }
+
+    /// Columns named in `CREATE INDEX name ON table (a, b)`
+    fn index_columns(sql: &str) -> Vec<String> {
+        let (Some(start), Some(end)) = (sql.find('('), sql.rfind(')')) else {
+            return Vec::new();
+        };
+        if end <= start {
+            return Vec::new();
+        }
+        sql[start + 1..end]
+            .split(',')
+            .map(|column| column.trim().trim_matches('"').to_string())
+            .filter(|column| !column.is_empty())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for DuckDbPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::DuckDB
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace("\"", "\"\""))
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        Box::new(sqlparser::dialect::DuckDbDialect {})
+    }
+
+    fn supports_schema(&self) -> bool {
+        true
+    }
+
+    fn supports_sequences(&self) -> bool {
+        true
+    }
+
+    /// Friendly-SQL extensions (FROM-first, PIVOT, named function arguments) are not fully
+    /// covered by sqlparser, so statements are never round-tripped through it and keep the
+    /// user's original text. `$$` strings in macro bodies are honoured by the fallback splitter.
+    fn split_statements(&self, script: &str) -> Vec<String> {
+        fallback_split_with_db_type(script, DatabaseType::DuckDB)
+    }
+
+    fn is_query_statement(&self, sql: &str) -> bool {
+        is_duckdb_query(sql)
+    }
+
+    fn classify_statement(&self, sql: &str) -> StatementType {
+        if is_duckdb_query(sql) {
+            return StatementType::Query;
+        }
+        match Parser::parse_sql(self.sql_dialect().as_ref(), sql) {
+            Ok(statements) => statements.first().map(classify_stmt).unwrap_or_else(|| classify_fallback(sql)),
+            Err(_) => classify_fallback(sql),
+        }
+    }
+
+    fn format_table_reference(&self, database: &str, schema: Option<&str>, table: &str) -> String {
+        format!(
+            "{}.{}.{}",
+            self.quote_identifier(database),
+            self.quote_identifier(schema.unwrap_or("main")),
+            self.quote_identifier(table)
+        )
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        // An embedded database has no server process or sessions of its own
+        query_server_status(connection, "SELECT 'DuckDB ' || version()").await
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        Some("SELECT name, value, description FROM duckdb_settings() ORDER BY name".to_string())
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!("SET SESSION {} = {}", name, session_variable_literal(self, value)))
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        SqlCompletionInfo {
+            keywords: vec![
+                ("ATTACH", "Attach another database file"),
+                ("DETACH", "Detach attached database"),
+                ("USE", "Switch default database/schema"),
+                ("INSTALL", "Install an extension"),
+                ("LOAD", "Load an installed extension"),
+                ("COPY", "Copy table/query to or from a file"),
+                ("EXPORT DATABASE", "Export all tables to a directory"),
+                ("IMPORT DATABASE", "Import an exported directory"),
+                ("SUMMARIZE", "Column statistics of a table or query"),
+                ("DESCRIBE", "Describe table or query columns"),
+                ("PIVOT", "Turn rows into columns"),
+                ("UNPIVOT", "Turn columns into rows"),
+                ("QUALIFY", "Filter on window function results"),
+                ("EXCLUDE", "Drop columns from SELECT *"),
+                ("REPLACE", "Replace columns in SELECT *"),
+                ("GROUP BY ALL", "Group by all non-aggregated columns"),
+                ("ORDER BY ALL", "Order by all columns"),
+                ("ASOF JOIN", "Join on the nearest preceding match"),
+                ("POSITIONAL JOIN", "Join rows by position"),
+                ("USING SAMPLE", "Sample rows of a table"),
+                ("CREATE MACRO", "Create scalar or table macro"),
+                ("CREATE SEQUENCE", "Create a sequence"),
+                ("CHECKPOINT", "Write WAL into the database file"),
+                ("RETURNING", "Return inserted/updated rows"),
+                ("ON CONFLICT", "Upsert clause"),
+            ],
+            functions: vec![
+                ("read_csv(path)", "Read CSV file(s), types auto-detected"),
+                ("read_parquet(path)", "Read Parquet file(s)"),
+                ("read_json(path)", "Read JSON / NDJSON file(s)"),
+                ("parquet_schema(path)", "Schema of a Parquet file"),
+                ("glob(pattern)", "List files matching a pattern"),
+                ("COLUMNS(regex)", "Select columns by pattern"),
+                ("unnest(list)", "Expand list to rows"),
+                ("list_value(...)", "Create a list"),
+                ("list_aggregate(list, name)", "Aggregate list elements"),
+                ("list_filter(list, lambda)", "Filter list with lambda"),
+                ("list_transform(list, lambda)", "Map list with lambda"),
+                ("struct_pack(k := v, ...)", "Create a struct"),
+                ("string_split(str, sep)", "Split string to list"),
+                ("string_agg(x, sep)", "Concatenate group values"),
+                ("regexp_matches(str, pattern)", "Regex match test"),
+                ("regexp_extract(str, pattern, group)", "Extract regex group"),
+                ("strftime(ts, fmt)", "Format timestamp"),
+                ("strptime(str, fmt)", "Parse timestamp"),
+                ("date_trunc(part, ts)", "Truncate timestamp"),
+                ("date_diff(part, start, end)", "Difference between timestamps"),
+                ("epoch_ms(ms)", "Timestamp from epoch milliseconds"),
+                ("arg_max(arg, val)", "Arg of maximum value"),
+                ("arg_min(arg, val)", "Arg of minimum value"),
+                ("approx_count_distinct(x)", "HyperLogLog distinct count"),
+                ("quantile_cont(x, q)", "Interpolated quantile"),
+                ("median(x)", "Median value"),
+                ("mode(x)", "Most frequent value"),
+                ("histogram(x)", "Value counts as map"),
+                ("md5(str)", "MD5 hex digest"),
+                ("current_database()", "Current database name"),
+            ],
+            operators: vec![
+                ("||", "String/list concatenation"),
+                ("->", "JSON extract (value) / lambda"),
+                ("->>", "JSON extract (text)"),
+                ("::", "Type cast"),
+                ("ILIKE", "Case-insensitive LIKE"),
+                ("GLOB", "Unix pattern match"),
+                ("SIMILAR TO", "Regular expression match"),
+                ("IS DISTINCT FROM", "NULL-safe inequality"),
+            ],
+            data_types: vec![
+                ("INTEGER", "4-byte signed integer"),
+                ("BIGINT", "8-byte signed integer"),
+                ("HUGEINT", "16-byte signed integer"),
+                ("DOUBLE", "8-byte floating point"),
+                ("DECIMAL(p,s)", "Fixed-point decimal"),
+                ("VARCHAR", "Variable-length string"),
+                ("BOOLEAN", "True/false"),
+                ("DATE", "Calendar date"),
+                ("TIMESTAMP", "Date and time"),
+                ("TIMESTAMPTZ", "Timestamp with time zone"),
+                ("INTERVAL", "Time interval"),
+                ("BLOB", "Binary data"),
+                ("UUID", "UUID"),
+                ("JSON", "JSON document"),
+                ("LIST", "Variable-length list, e.g. INTEGER[]"),
+                ("STRUCT", "Named fields"),
+                ("MAP", "Key/value map"),
+            ],
+            snippets: vec![
+                ("csv", "SELECT * FROM read_csv('$1')", "Query a CSV file"),
+                ("pq", "SELECT * FROM read_parquet('$1')", "Query a Parquet file"),
+                ("ctas", "CREATE TABLE $1 AS SELECT * FROM read_parquet('$2')", "Load a file into a table"),
+                ("copy", "COPY ($1) TO '$2' (FORMAT PARQUET)", "Export query to Parquet"),
+                ("att", "ATTACH '$1' AS $2", "Attach database file"),
+                ("sum", "SUMMARIZE $1", "Column statistics"),
+            ],
+        }.with_standard_sql()
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = DuckDbConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        let rows = self.query_rows(
+            connection,
+            "SELECT database_name FROM duckdb_databases() WHERE NOT internal ORDER BY database_name",
+            "databases",
+        ).await?;
+        Ok(rows.iter().filter_map(|row| cell(row, 0)).collect())
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let databases = self.list_databases_detailed(connection).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("path", "Path").width(px(360.0)),
+        ];
+
+        let rows = databases.iter().map(|database| {
+            vec![database.name.clone(), database.comment.clone().unwrap_or_default()]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Database,
+            title: format!("{} database(s)", databases.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        let rows = self.query_rows(
+            connection,
+            "SELECT database_name, path FROM duckdb_databases() WHERE NOT internal ORDER BY database_name",
+            "databases",
+        ).await?;
+
+        Ok(rows.iter().map(|row| DatabaseInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            charset: None,
+            collation: None,
+            size: None,
+            table_count: None,
+            comment: cell(row, 1),
+        }).collect())
+    }
+
+    async fn list_schemas(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<String>> {
+        let sql = format!(
+            "SELECT schema_name FROM duckdb_schemas() WHERE database_name = {} AND NOT internal ORDER BY schema_name",
+            string_literal(database)
+        );
+        let rows = self.query_rows(connection, &sql, "schemas").await?;
+        Ok(rows
+            .iter()
+            .filter_map(|row| cell(row, 0))
+            .filter(|schema| !SYSTEM_SCHEMAS.contains(&schema.as_str()))
+            .collect())
+    }
+
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        let sql = format!(
+            "SELECT table_name, schema_name, comment, estimated_size FROM duckdb_tables() \
+             WHERE database_name = {} AND NOT internal ORDER BY schema_name, table_name",
+            string_literal(database)
+        );
+        let rows = self.query_rows(connection, &sql, "tables").await?;
+
+        Ok(rows.iter().map(|row| TableInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            schema: cell(row, 1),
+            comment: cell(row, 2),
+            engine: None,
+            row_count: cell(row, 3).and_then(|count| count.parse().ok()),
+            create_time: None,
+            charset: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("schema", "Schema").width(px(120.0)),
+            Column::new("rows", "Rows").width(px(100.0)),
+            Column::new("comment", "Comment").width(px(250.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = tables.iter().map(|table| {
+            vec![
+                table.name.clone(),
+                table.schema.clone().unwrap_or_default(),
+                table.row_count.map(|count| count.to_string()).unwrap_or_default(),
+                table.comment.clone().unwrap_or_default(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} table(s)", tables.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let filter = format!(
+            "database_name = {} AND schema_name = {} AND table_name = {}",
+            string_literal(database),
+            string_literal(schema.unwrap_or("main")),
+            string_literal(table)
+        );
+
+        let primary_key_sql = format!(
+            "SELECT unnest(constraint_column_names) FROM duckdb_constraints() \
+             WHERE {} AND constraint_type = 'PRIMARY KEY'",
+            filter
+        );
+        let primary_keys: Vec<String> = self.query_rows(connection, &primary_key_sql, "primary keys")
+            .await?
+            .iter()
+            .filter_map(|row| cell(row, 0))
+            .collect();
+
+        let sql = format!(
+            "SELECT column_name, data_type, is_nullable, column_default, comment FROM duckdb_columns() \
+             WHERE {} ORDER BY column_index",
+            filter
+        );
+        let rows = self.query_rows(connection, &sql, "columns").await?;
+
+        Ok(rows.iter().map(|row| {
+            let name = cell(row, 0).unwrap_or_default();
+            ColumnInfo {
+                is_primary_key: primary_keys.contains(&name),
+                name,
+                data_type: cell(row, 1).unwrap_or_default(),
+                is_nullable: cell(row, 2).map(|v| v == "true").unwrap_or(true),
+                default_value: cell(row, 3),
+                comment: cell(row, 4),
+                collation: None,
+            }
+        }).collect())
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns_data = self.list_columns(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("type", "Type").width(px(150.0)),
+            Column::new("nullable", "Nullable").width(px(80.0)),
+            Column::new("key", "Key").width(px(80.0)),
+            Column::new("default", "Default").width(px(120.0)),
+            Column::new("comment", "Comment").width(px(200.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = columns_data.iter().map(|col| {
+            vec![
+                col.name.clone(),
+                col.data_type.clone(),
+                if col.is_nullable { "YES" } else { "NO" }.to_string(),
+                if col.is_primary_key { "PRI" } else { "" }.to_string(),
+                col.default_value.as_deref().unwrap_or("").to_string(),
+                col.comment.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: format!("{} column(s)", columns_data.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_indexes(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<IndexInfo>> {
+        // duckdb_indexes() has no column list, so the columns are read back from the CREATE INDEX text
+        let sql = format!(
+            "SELECT index_name, is_unique, sql FROM duckdb_indexes() \
+             WHERE database_name = {} AND schema_name = {} AND table_name = {} ORDER BY index_name",
+            string_literal(database),
+            string_literal(schema.unwrap_or("main")),
+            string_literal(table)
+        );
+        let rows = self.query_rows(connection, &sql, "indexes").await?;
+
+        Ok(rows.iter().map(|row| IndexInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            columns: cell(row, 2).map(|sql| Self::index_columns(&sql)).unwrap_or_default(),
+            is_unique: cell(row, 1).map(|v| v == "true").unwrap_or(false),
+            index_type: Some("ART".to_string()),
+        }).collect())
+    }
+
+    async fn list_indexes_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let indexes = self.list_indexes(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("columns", "Columns").width(px(250.0)),
+            Column::new("unique", "Unique").width(px(80.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = indexes.iter().map(|idx| {
+            vec![
+                idx.name.clone(),
+                idx.columns.join(", "),
+                if idx.is_unique { "YES" } else { "NO" }.to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: format!("{} index(es)", indexes.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_views(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<ViewInfo>> {
+        let sql = format!(
+            "SELECT view_name, schema_name, sql, comment FROM duckdb_views() \
+             WHERE database_name = {} AND NOT internal ORDER BY schema_name, view_name",
+            string_literal(database)
+        );
+        let rows = self.query_rows(connection, &sql, "views").await?;
+
+        Ok(rows.iter().map(|row| ViewInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            schema: cell(row, 1),
+            definition: cell(row, 2),
+            comment: cell(row, 3),
+        }).collect())
+    }
+
+    async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let views = self.list_views(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("schema", "Schema").width(px(120.0)),
+            Column::new("definition", "Definition").width(px(400.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = views.iter().map(|view| {
+            vec![
+                view.name.clone(),
+                view.schema.clone().unwrap_or_default(),
+                view.definition.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::View,
+            title: format!("{} view(s)", views.len()),
+            columns,
+            rows,
+        })
+    }
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Function,
+            title: "0 function(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Procedure,
+            title: "0 procedure(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        // DuckDB has no triggers
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("table", "Table").width(px(150.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    async fn list_sequences(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<SequenceInfo>> {
+        let sql = format!(
+            "SELECT sequence_name, start_value, increment_by, min_value, max_value FROM duckdb_sequences() \
+             WHERE database_name = {} AND NOT temporary ORDER BY sequence_name",
+            string_literal(database)
+        );
+        let rows = self.query_rows(connection, &sql, "sequences").await?;
+
+        Ok(rows.iter().map(|row| SequenceInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            start_value: cell(row, 1).and_then(|v| v.parse().ok()),
+            increment: cell(row, 2).and_then(|v| v.parse().ok()),
+            min_value: cell(row, 3).and_then(|v| v.parse().ok()),
+            max_value: cell(row, 4).and_then(|v| v.parse().ok()),
+        }).collect())
+    }
+
+    async fn list_sequences_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let sequences = self.list_sequences(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("start", "Start").width(px(100.0)),
+            Column::new("increment", "Increment").width(px(100.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = sequences.iter().map(|sequence| {
+            vec![
+                sequence.name.clone(),
+                sequence.start_value.map(|v| v.to_string()).unwrap_or_default(),
+                sequence.increment.map(|v| v.to_string()).unwrap_or_default(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Sequence,
+            title: format!("{} sequence(s)", sequences.len()),
+            columns,
+            rows,
+        })
+    }
+
+    fn get_data_types(&self) -> Vec<DataTypeInfo> {
+        vec![
+            DataTypeInfo::new("INTEGER", "4-byte signed integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("BIGINT", "8-byte signed integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("HUGEINT", "16-byte signed integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("DOUBLE", "8-byte floating point").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("DECIMAL", "Fixed-point decimal").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("VARCHAR", "Variable-length string").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("BLOB", "Binary data").with_category(DataTypeCategory::Binary),
+            DataTypeInfo::new("BOOLEAN", "True/false").with_category(DataTypeCategory::Boolean),
+            DataTypeInfo::new("DATE", "Calendar date").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("TIMESTAMP", "Date and time").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("TIMESTAMPTZ", "Timestamp with time zone").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("INTERVAL", "Time interval").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("UUID", "UUID").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("JSON", "JSON document").with_category(DataTypeCategory::String),
+        ]
+    }
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        let mut def = String::new();
+
+        if include_name {
+            def.push_str(&self.quote_identifier(&column.name));
+            def.push(' ');
+        }
+
+        def.push_str(&column.data_type);
+
+        if !column.is_nullable {
+            def.push_str(" NOT NULL");
+        }
+
+        if let Some(default) = &column.default_value {
+            def.push_str(&format!(" DEFAULT {}", default));
+        }
+
+        if column.is_primary_key {
+            def.push_str(" PRIMARY KEY");
+        }
+
+        def
+    }
+
+    fn build_create_database_sql(&self, request: &crate::plugin::DatabaseOperationRequest) -> String {
+        format!(
+            "ATTACH {} AS {}",
+            string_literal(&format!("{}.duckdb", request.database_name)),
+            self.quote_identifier(&request.database_name)
+        )
+    }
+
+    fn build_modify_database_sql(&self, _request: &crate::plugin::DatabaseOperationRequest) -> String {
+        "-- DuckDB: database modification not supported".to_string()
+    }
+
+    fn build_drop_database_sql(&self, database_name: &str) -> String {
+        format!("DETACH {}", self.quote_identifier(database_name))
+    }
+
+    fn drop_table(&self, _database: &str, table: &str) -> String {
+        format!("DROP TABLE IF EXISTS {}", self.quote_identifier(table))
+    }
+
+    fn truncate_table(&self, _database: &str, table: &str) -> String {
+        format!("TRUNCATE {}", self.quote_identifier(table))
+    }
+
+    fn rename_table(&self, _database: &str, old_name: &str, new_name: &str) -> String {
+        format!("ALTER TABLE {} RENAME TO {}", self.quote_identifier(old_name), self.quote_identifier(new_name))
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        let mut def = String::new();
+        def.push_str(&self.quote_identifier(&col.name));
+        def.push(' ');
+        def.push_str(&self.build_type_string(col));
+
+        if !col.is_nullable {
+            def.push_str(" NOT NULL");
+        }
+
+        if let Some(default) = &col.default_value {
+            if !default.is_empty() {
+                def.push_str(&format!(" DEFAULT {}", default));
+            }
+        }
+
+        def
+    }
+
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        let table_name = self.quote_identifier(&design.table_name);
+        let mut definitions: Vec<String> = design.columns
+            .iter()
+            .map(|col| format!("  {}", self.build_column_def(col)))
+            .collect();
+
+        let pk_columns: Vec<String> = design.columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| self.quote_identifier(&c.name))
+            .collect();
+        if !pk_columns.is_empty() {
+            definitions.push(format!("  PRIMARY KEY ({})", pk_columns.join(", ")));
+        }
+
+        let mut sql = format!("CREATE TABLE {} (\n{}\n);", table_name, definitions.join(",\n"));
+
+        for idx in design.indexes.iter().filter(|idx| !idx.is_primary) {
+            let idx_cols: Vec<String> = idx.columns.iter().map(|c| self.quote_identifier(c)).collect();
+            sql.push_str(&format!(
+                "\nCREATE {}INDEX {} ON {} ({});",
+                if idx.is_unique { "UNIQUE " } else { "" },
+                self.quote_identifier(&idx.name),
+                table_name,
+                idx_cols.join(", ")
+            ));
+        }
+
+        for col in design.columns.iter().filter(|c| !c.comment.is_empty()) {
+            sql.push_str(&format!(
+                "\nCOMMENT ON COLUMN {}.{} IS {};",
+                table_name,
+                self.quote_identifier(&col.name),
+                string_literal(&col.comment)
+            ));
+        }
+
+        sql
+    }
+
+    fn build_alter_table_sql(&self, original: &TableDesign, new: &TableDesign) -> String {
+        let table_name = self.quote_identifier(&new.table_name);
+        let mut statements: Vec<String> = Vec::new();
+
+        for col in &original.columns {
+            if !new.columns.iter().any(|c| c.name == col.name) {
+                statements.push(format!("ALTER TABLE {} DROP COLUMN {};", table_name, self.quote_identifier(&col.name)));
+            }
+        }
+
+        for col in &new.columns {
+            let column_name = self.quote_identifier(&col.name);
+            let Some(orig) = original.columns.iter().find(|c| c.name == col.name) else {
+                statements.push(format!("ALTER TABLE {} ADD COLUMN {};", table_name, self.build_column_def(col)));
+                continue;
+            };
+            if !self.column_changed(orig, col) {
+                continue;
+            }
+
+            let new_type = self.build_type_string(col);
+            if self.build_type_string(orig).to_uppercase() != new_type.to_uppercase() {
+                statements.push(format!("ALTER TABLE {} ALTER COLUMN {} TYPE {};", table_name, column_name, new_type));
+            }
+            if orig.is_nullable != col.is_nullable {
+                let action = if col.is_nullable { "DROP NOT NULL" } else { "SET NOT NULL" };
+                statements.push(format!("ALTER TABLE {} ALTER COLUMN {} {};", table_name, column_name, action));
+            }
+            if orig.default_value != col.default_value {
+                match col.default_value.as_deref().filter(|d| !d.is_empty()) {
+                    Some(default) => statements.push(format!("ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};", table_name, column_name, default)),
+                    None => statements.push(format!("ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;", table_name, column_name)),
+                }
+            }
+            if orig.comment != col.comment {
+                statements.push(format!("COMMENT ON COLUMN {}.{} IS {};", table_name, column_name, string_literal(&col.comment)));
+            }
+        }
+
+        for idx in &original.indexes {
+            if !idx.is_primary && !new.indexes.iter().any(|i| i.name == idx.name) {
+                statements.push(format!("DROP INDEX IF EXISTS {};", self.quote_identifier(&idx.name)));
+            }
+        }
+
+        for idx in &new.indexes {
+            if !idx.is_primary && !original.indexes.iter().any(|i| i.name == idx.name) {
+                let idx_cols: Vec<String> = idx.columns.iter().map(|c| self.quote_identifier(c)).collect();
+                statements.push(format!(
+                    "CREATE {}INDEX {} ON {} ({});",
+                    if idx.is_unique { "UNIQUE " } else { "" },
+                    self.quote_identifier(&idx.name),
+                    table_name,
+                    idx_cols.join(", ")
+                ));
+            }
+        }
+
+        if statements.is_empty() {
+            "-- No changes detected".to_string()
+        } else {
+            statements.join("\n")
+        }
+    }
+
+    fn build_limit_clause(&self) -> String {
+        String::new()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        request: &crate::types::TableSaveRequest,
+        original_data: &[String],
+    ) -> (String, String) {
+        let where_clause = self.build_table_change_where_clause(request, original_data);
+        (where_clause, self.build_limit_clause())
+    }
+
+    fn insert_conflict_clause(&self, mode: SqlConflictMode, columns: &[String], key_columns: &[String]) -> Option<InsertConflictClause> {
+        on_conflict_clause(self, mode, columns, key_columns)
+    }
+
+    async fn export_table_create_sql(
+        &self,
+        connection: &dyn DbConnection,
+        database: &str,
+        table: &str,
+    ) -> Result<String> {
+        let sql = format!(
+            "SELECT sql FROM duckdb_tables() WHERE database_name = {} AND table_name = {}",
+            string_literal(database),
+            string_literal(table)
+        );
+        let rows = self.query_rows(connection, &sql, "table definition").await?;
+        Ok(rows.first().and_then(|row| cell(row, 0)).unwrap_or_default())
+    }
+}
+
+impl Default for DuckDbPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::DatabasePlugin;
+
+    fn create_plugin() -> DuckDbPlugin {
+        DuckDbPlugin::new()
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        assert_eq!(create_plugin().name(), DatabaseType::DuckDB);
+    }
+
+    #[test]
+    fn test_is_query_statement() {
+        let plugin = create_plugin();
+        assert!(plugin.is_query_statement("SELECT 1"));
+        assert!(plugin.is_query_statement("FROM 'events.parquet' WHERE id > 1"));
+        assert!(plugin.is_query_statement("SUMMARIZE orders"));
+        assert!(plugin.is_query_statement("-- preview\nPIVOT sales ON year USING sum(amount)"));
+        assert!(plugin.is_query_statement("(SELECT 1) UNION ALL (SELECT 2)"));
+        assert!(!plugin.is_query_statement("COPY orders TO 'orders.parquet' (FORMAT PARQUET)"));
+        assert!(!plugin.is_query_statement("INSTALL httpfs"));
+        assert!(!plugin.is_query_statement("CREATE TABLE t AS FROM read_csv('a.csv')"));
+    }
+
+    #[test]
+    fn test_classify_statement() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.classify_statement("FROM t"), StatementType::Query);
+        assert_eq!(plugin.classify_statement("INSERT INTO t VALUES (1)"), StatementType::Dml);
+        assert_eq!(plugin.classify_statement("DROP TABLE t"), StatementType::Ddl);
+    }
+
+    #[test]
+    fn test_split_statements_keeps_original_text() {
+        let plugin = create_plugin();
+        let script = "FROM read_csv('a;b.csv', header = true);\n\
+                      CREATE MACRO twice(x) AS $$ x * 2; $$;\n\
+                      SELECT twice(2)";
+        let statements = plugin.split_statements(script);
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "FROM read_csv('a;b.csv', header = true)");
+        assert_eq!(statements[1], "CREATE MACRO twice(x) AS $$ x * 2; $$");
+    }
+
+    #[test]
+    fn test_format_table_reference() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.format_table_reference("lake", None, "events"), "\"lake\".\"main\".\"events\"");
+        assert_eq!(plugin.format_table_reference("lake", Some("raw"), "events"), "\"lake\".\"raw\".\"events\"");
+    }
+
+    #[test]
+    fn test_index_columns() {
+        assert_eq!(DuckDbPlugin::index_columns("CREATE INDEX idx ON t(\"a\", b);"), vec!["a", "b"]);
+        assert!(DuckDbPlugin::index_columns("CREATE INDEX idx").is_empty());
+    }
+
+    #[test]
+    fn test_get_completion_info() {
+        let info = create_plugin().get_completion_info();
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("read_parquet")));
+        assert!(info.keywords.iter().any(|(k, _)| *k == "SUMMARIZE"));
+        assert!(!info.snippets.is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+pub mod connection;
+pub mod plugin;
+pub mod protocol;
+
+pub use connection::ElasticsearchConnection;
+pub use plugin::ElasticsearchPlugin;
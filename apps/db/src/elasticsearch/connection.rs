@@ -0,0 +1,307 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use one_core::storage::DbConnectionConfig;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Method, RequestBuilder};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::connection::{DbConnection, DbError, StreamingProgress};
+use crate::elasticsearch::protocol::{
+    cell_text, cursor_body, error_message, mapping_columns, query_body, QueryPages, SqlColumn, SqlPage,
+};
+use crate::executor::{ExecOptions, SqlErrorInfo, SqlResult};
+use crate::{proxy, tls, ColumnInfo, DatabasePlugin, SqlValue};
+
+/// Rows per page when a statement has no row limit
+const DEFAULT_FETCH_SIZE: usize = 1000;
+
+/// Cursor positioned before `next_page` of a grid query, so paging forward does not
+/// re-read every earlier page
+struct GridCursor {
+    next_page: usize,
+    cursor: String,
+    /// Pages after the first come without columns
+    columns: Vec<SqlColumn>,
+}
+
+pub struct ElasticsearchConnection {
+    config: DbConnectionConfig,
+    client: Option<Client>,
+    base_url: String,
+    cluster_name: Option<String>,
+    /// Keyed by the grid query, one open cursor each
+    grid_cursors: Mutex<HashMap<String, GridCursor>>,
+}
+
+impl ElasticsearchConnection {
+    pub fn new(config: DbConnectionConfig) -> Self {
+        Self {
+            config,
+            client: None,
+            base_url: String::new(),
+            cluster_name: None,
+            grid_cursors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn ensure_connected(&self) -> Result<&Client, DbError> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionError("Not connected to Elasticsearch".to_string()))
+    }
+
+    /// An API key in the advanced tab wins over username and password
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, DbError> {
+        let client = self.ensure_connected()?;
+        let builder = client.request(method, format!("{}{}", self.base_url, path));
+        let api_key = self.config.get_param("api_key").filter(|key| !key.trim().is_empty());
+        Ok(match api_key {
+            Some(api_key) => builder.header("Authorization", format!("ApiKey {}", api_key.trim())),
+            None if !self.config.username.is_empty() => {
+                builder.basic_auth(&self.config.username, Some(&self.config.password))
+            }
+            None => builder,
+        })
+    }
+
+    async fn send(&self, method: Method, path: &str, body: Option<Value>) -> Result<Vec<u8>, DbError> {
+        let mut request = self.request(method, path)?;
+        if let Some(body) = body {
+            let bytes = serde_json::to_vec(&body).map_err(|e| DbError::QueryError(e.to_string()))?;
+            request = request.header(CONTENT_TYPE, "application/json").body(bytes);
+        }
+        let response = request.send().await.map_err(|e| DbError::QueryError(e.to_string()))?;
+        let status = response.status();
+        let bytes = response.bytes().await.map_err(|e| DbError::QueryError(e.to_string()))?;
+        if !status.is_success() {
+            let message = error_message(&bytes)
+                .unwrap_or_else(|| format!("HTTP {}: {}", status, String::from_utf8_lossy(&bytes).trim()));
+            return Err(DbError::QueryError(message));
+        }
+        Ok(bytes.to_vec())
+    }
+
+    async fn sql(&self, body: Value) -> Result<SqlPage, DbError> {
+        let bytes = self.send(Method::POST, "/_sql?format=json", Some(body)).await?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| DbError::QueryError(format!("Invalid response from Elasticsearch: {}", e)))
+    }
+
+    /// Cursors hold a search context on the cluster until read to the end or closed
+    async fn close_cursor(&self, cursor: &str) {
+        let _ = self.send(Method::POST, "/_sql/close", Some(cursor_body(cursor))).await;
+    }
+
+    pub fn cluster_name(&self) -> Option<&str> {
+        self.cluster_name.as_deref()
+    }
+
+    /// Field mapping of an index, alias or pattern
+    pub async fn field_mappings(&self, index: &str) -> Result<Vec<ColumnInfo>, DbError> {
+        let bytes = self.send(Method::GET, &format!("/{}/_mapping", index), None).await?;
+        let mapping: Value = serde_json::from_slice(&bytes)
+            .map_err(|e| DbError::QueryError(format!("Invalid mapping response: {}", e)))?;
+        Ok(mapping_columns(&mapping))
+    }
+
+    async fn run_query(&self, sql: &str, params: Option<&[SqlValue]>, max_rows: Option<usize>) -> SqlResult {
+        let start = Instant::now();
+        let fetch_size = max_rows.unwrap_or(DEFAULT_FETCH_SIZE).min(DEFAULT_FETCH_SIZE);
+        let mut pages = QueryPages::default();
+        let mut page = match self.sql(query_body(sql, fetch_size, params)).await {
+            Ok(page) => page,
+            Err(e) => return SqlResult::Error(SqlErrorInfo { sql: sql.to_string(), message: e.to_string() }),
+        };
+
+        loop {
+            let keep_reading = pages.absorb(&page, max_rows);
+            let Some(cursor) = page.cursor.take() else {
+                break;
+            };
+            if !keep_reading {
+                self.close_cursor(&cursor).await;
+                break;
+            }
+            page = match self.sql(cursor_body(&cursor)).await {
+                Ok(page) => page,
+                Err(e) => return SqlResult::Error(SqlErrorInfo { sql: sql.to_string(), message: e.to_string() }),
+            };
+        }
+
+        pages.into_result(sql.to_string(), start.elapsed().as_millis())
+    }
+
+    /// One page of a grid query. SQL has no OFFSET, so the page is reached through the cursor:
+    /// the cursor left after the previous page is reused, otherwise earlier pages are skipped.
+    pub async fn query_page(&self, sql: &str, page: usize, page_size: usize) -> Result<(Vec<SqlColumn>, Vec<Vec<Option<String>>>), DbError> {
+        let page = page.max(1);
+        let cached = self.grid_cursors.lock().ok().and_then(|mut cursors| cursors.remove(sql));
+
+        let (mut current, mut columns) = (1, None);
+        let mut response = match cached {
+            Some(cached) if cached.next_page == page => {
+                current = page;
+                columns = Some(cached.columns);
+                self.sql(cursor_body(&cached.cursor)).await?
+            }
+            stale => {
+                if let Some(stale) = stale {
+                    self.close_cursor(&stale.cursor).await;
+                }
+                self.sql(query_body(sql, page_size, None)).await?
+            }
+        };
+
+        loop {
+            if columns.is_none() {
+                columns = response.columns.take();
+            }
+            if current == page {
+                break;
+            }
+            let Some(cursor) = response.cursor.take() else {
+                // Fewer pages than requested
+                return Ok((columns.unwrap_or_default(), Vec::new()));
+            };
+            response = self.sql(cursor_body(&cursor)).await?;
+            current += 1;
+        }
+
+        let columns = columns.unwrap_or_default();
+        if let Some(cursor) = response.cursor.take() {
+            if let Ok(mut cursors) = self.grid_cursors.lock() {
+                cursors.insert(sql.to_string(), GridCursor { next_page: page + 1, cursor, columns: columns.clone() });
+            }
+        }
+        let rows = response.rows.iter().map(|row| row.iter().map(cell_text).collect()).collect();
+        Ok((columns, rows))
+    }
+
+    fn statements(plugin: &Arc<dyn DatabasePlugin>, script: &str) -> Vec<String> {
+        plugin.split_statements(script)
+            .into_iter()
+            .map(|statement| statement.trim().trim_end_matches(';').trim().to_string())
+            .filter(|statement| !statement.is_empty())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbConnection for ElasticsearchConnection {
+    fn config(&self) -> &DbConnectionConfig {
+        &self.config
+    }
+
+    fn set_config_database(&mut self, database: Option<String>) {
+        self.config.database = database;
+    }
+
+    /// The cluster is the only database
+    fn supports_database_switch(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    async fn connect(&mut self) -> Result<(), DbError> {
+        let config = &self.config;
+        let ssl = config.ssl();
+        let scheme = if ssl.is_enabled() || config.get_param_bool("https") { "https" } else { "http" };
+        let connect_timeout = config.get_param_as::<u64>("connect_timeout").unwrap_or(30);
+        let builder = Client::builder().connect_timeout(Duration::from_secs(connect_timeout));
+        let builder = proxy::http_client_builder(config, builder)?;
+        let client = tls::http_client_builder(&ssl, builder)?
+            .build()
+            .map_err(|e| DbError::ConnectionError(e.to_string()))?;
+
+        self.base_url = format!("{}://{}:{}", scheme, config.host, config.port);
+        self.client = Some(client);
+
+        // `GET /` answers with the cluster name, which is shown as the database
+        let info = match self.send(Method::GET, "/", None).await {
+            Ok(bytes) => serde_json::from_slice::<Value>(&bytes).unwrap_or_default(),
+            Err(e) => {
+                self.client = None;
+                return Err(tls::connection_error(&ssl, e));
+            }
+        };
+        self.cluster_name = info
+            .get("cluster_name")
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+            .or_else(|| Some("elasticsearch".to_string()));
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), DbError> {
+        let cursors: Vec<String> = self.grid_cursors
+            .get_mut()
+            .map(|cursors| cursors.drain().map(|(_, cached)| cached.cursor).collect())
+            .unwrap_or_default();
+        for cursor in cursors {
+            self.close_cursor(&cursor).await;
+        }
+        self.client = None;
+        Ok(())
+    }
+
+    /// The SQL API is read-only; every statement is a query
+    async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
+        self.ensure_connected()?;
+        let mut results = Vec::new();
+        for sql in Self::statements(&plugin, script) {
+            let result = self.run_query(&sql, None, options.max_rows).await;
+            let is_error = result.is_error();
+            results.push(result);
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn query(&self, query: &str, params: Option<Vec<SqlValue>>, options: ExecOptions) -> Result<SqlResult, DbError> {
+        self.ensure_connected()?;
+        Ok(self.run_query(query.trim().trim_end_matches(';'), params.as_deref(), options.max_rows).await)
+    }
+
+    async fn current_database(&self) -> Result<Option<String>, DbError> {
+        Ok(self.cluster_name.clone())
+    }
+
+    async fn switch_database(&self, _database: &str) -> Result<(), DbError> {
+        Ok(())
+    }
+
+    async fn execute_streaming(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        sender: mpsc::Sender<StreamingProgress>,
+    ) -> Result<(), DbError> {
+        self.ensure_connected()?;
+        let statements = Self::statements(&plugin, script);
+        let total = statements.len();
+
+        for (index, sql) in statements.into_iter().enumerate() {
+            let result = self.run_query(&sql, None, options.max_rows).await;
+            let is_error = result.is_error();
+            if sender.send(StreamingProgress { current: index + 1, total, result }).await.is_err() {
+                break;
+            }
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,596 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig};
+
+use crate::connection::{DbConnection, DbError};
+use crate::elasticsearch::ElasticsearchConnection;
+use crate::executor::{ExecOptions, SqlResult, StatementType};
+use crate::plugin::{fallback_split_with_db_type, DatabaseOperationRequest, DatabasePlugin, SqlCompletionInfo};
+use crate::types::*;
+
+/// Field types of the mapping as grid field types
+fn field_type(es_type: &str) -> FieldType {
+    match es_type.to_lowercase().as_str() {
+        "byte" | "short" | "integer" | "long" | "unsigned_long" => FieldType::Integer,
+        "float" | "half_float" | "double" | "scaled_float" => FieldType::Decimal,
+        "boolean" => FieldType::Boolean,
+        "date" | "date_nanos" | "datetime" => FieldType::DateTime,
+        "keyword" | "constant_keyword" | "wildcard" | "ip" | "version" => FieldType::Text,
+        "text" | "match_only_text" => FieldType::LongText,
+        "binary" => FieldType::Binary,
+        "object" | "nested" | "flattened" | "geo_point" | "geo_shape" | "shape" => FieldType::Json,
+        _ => FieldType::Unknown,
+    }
+}
+
+/// Elasticsearch plugin on the SQL API. The cluster is shown as the only database, indices as
+/// tables and aliases as views; columns come from the field mapping. The SQL API only reads,
+/// so DDL methods return comments.
+pub struct ElasticsearchPlugin;
+
+impl ElasticsearchPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn es_connection<'a>(&self, connection: &'a dyn DbConnection) -> Result<&'a ElasticsearchConnection> {
+        connection
+            .as_any()
+            .and_then(|any| any.downcast_ref::<ElasticsearchConnection>())
+            .ok_or_else(|| anyhow::anyhow!("Not an Elasticsearch connection"))
+    }
+
+    fn unsupported(&self, operation: &str) -> String {
+        format!("-- Elasticsearch: {} is not supported by the SQL API", operation)
+    }
+
+    /// `SHOW TABLES` rows are `catalog, name, type, kind`; hidden indices are skipped
+    async fn show_tables(&self, connection: &dyn DbConnection, table_type: &str) -> Result<Vec<String>> {
+        let result = connection.query("SHOW TABLES", None, ExecOptions { max_rows: None, ..ExecOptions::default() })
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list indices: {}", e))?;
+
+        match result {
+            SqlResult::Query(query_result) => Ok(query_result.rows
+                .into_iter()
+                .filter(|row| row.get(2).cloned().flatten().as_deref() == Some(table_type))
+                .filter_map(|row| row.get(1).cloned().flatten())
+                .filter(|name| !name.starts_with('.'))
+                .collect()),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("Failed to list indices: {}", err.message)),
+            SqlResult::Exec(_) => Err(anyhow::anyhow!("Unexpected result type")),
+        }
+    }
+
+    fn build_where_clause(&self, request: &TableDataRequest) -> String {
+        if let Some(raw_where) = &request.where_clause {
+            return if raw_where.trim().is_empty() { String::new() } else { format!(" WHERE {}", raw_where) };
+        }
+        if request.filters.is_empty() {
+            return String::new();
+        }
+        let conditions: Vec<String> = request.filters.iter().map(|filter| {
+            let column = self.quote_identifier(&filter.column);
+            match filter.operator {
+                FilterOperator::IsNull => format!("{} IS NULL", column),
+                FilterOperator::IsNotNull => format!("{} IS NOT NULL", column),
+                FilterOperator::In | FilterOperator::NotIn => {
+                    format!("{} {} ({})", column, filter.operator.to_sql(), filter.value)
+                }
+                _ => format!("{} {} '{}'", column, filter.operator.to_sql(), filter.value.replace('\'', "''")),
+            }
+        }).collect();
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+
+    fn build_order_clause(&self, request: &TableDataRequest) -> String {
+        if let Some(raw_order) = &request.order_by_clause {
+            return if raw_order.trim().is_empty() { String::new() } else { format!(" ORDER BY {}", raw_order) };
+        }
+        if request.sorts.is_empty() {
+            return String::new();
+        }
+        let sorts: Vec<String> = request.sorts.iter().map(|sort| {
+            let direction = match sort.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            format!("{} {}", self.quote_identifier(&sort.column), direction)
+        }).collect();
+        format!(" ORDER BY {}", sorts.join(", "))
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for ElasticsearchPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::Elasticsearch
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace("\"", "\"\""))
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        Box::new(sqlparser::dialect::GenericDialect {})
+    }
+
+    fn split_statements(&self, script: &str) -> Vec<String> {
+        fallback_split_with_db_type(script, DatabaseType::Elasticsearch)
+    }
+
+    /// SELECT, SHOW and DESCRIBE are all the SQL API runs, and each returns rows
+    fn is_query_statement(&self, _sql: &str) -> bool {
+        true
+    }
+
+    fn classify_statement(&self, _sql: &str) -> StatementType {
+        StatementType::Query
+    }
+
+    fn is_read_only_query(&self, _sql: &str) -> bool {
+        true
+    }
+
+    /// The executable plan shows the query DSL the statement is translated to
+    fn build_explain_sql(&self, sql: &str) -> Option<String> {
+        Some(format!("EXPLAIN (PLAN EXECUTABLE) {}", sql.trim().trim_end_matches(';')))
+    }
+
+    /// There is no OFFSET; `query_table_data` pages through a cursor instead
+    fn format_pagination(&self, limit: usize, _offset: usize, _order_clause: &str) -> String {
+        format!(" LIMIT {}", limit)
+    }
+
+    /// The cluster is not part of the name; index patterns such as `logs-*` need the quotes
+    fn format_table_reference(&self, _database: &str, _schema: Option<&str>, table: &str) -> String {
+        self.quote_identifier(table)
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        SqlCompletionInfo {
+            keywords: vec![
+                ("SHOW TABLES", "List indices and aliases"),
+                ("SHOW TABLES LIKE", "List indices matching a pattern"),
+                ("SHOW COLUMNS FROM", "List fields of an index"),
+                ("SHOW FUNCTIONS", "List SQL functions"),
+                ("DESCRIBE", "Describe index fields"),
+                ("EXPLAIN", "Show the query plan"),
+                ("FROZEN", "Include frozen indices"),
+                ("PIVOT", "Pivot rows into columns"),
+                ("HAVING", "Filter groups"),
+            ],
+            functions: vec![
+                ("MATCH(field, query)", "Full-text match on a field"),
+                ("QUERY(query_string)", "Query string search"),
+                ("SCORE()", "Relevance score of the document"),
+                ("HISTOGRAM(field, interval)", "Bucket numbers or dates"),
+                ("DATE_TRUNC(unit, date)", "Truncate a date"),
+                ("DATE_ADD(unit, n, date)", "Add to a date"),
+                ("DATE_DIFF(unit, start, end)", "Difference between dates"),
+                ("DATE_FORMAT(date, format)", "Format a date"),
+                ("NOW()", "Current date and time"),
+                ("TODAY()", "Current date"),
+                ("FIRST(field, sort)", "First value of a group"),
+                ("LAST(field, sort)", "Last value of a group"),
+                ("PERCENTILE(field, p)", "Percentile of a group"),
+                ("PERCENTILE_RANK(field, value)", "Percentile rank of a value"),
+                ("MAD(field)", "Median absolute deviation"),
+                ("STDDEV_POP(field)", "Population standard deviation"),
+                ("KURTOSIS(field)", "Kurtosis of a group"),
+                ("CONVERT(value, type)", "Convert a value"),
+                ("IIF(condition, a, b)", "Inline if"),
+                ("ST_DISTANCE(geo1, geo2)", "Distance between points"),
+            ],
+            operators: vec![
+                ("RLIKE", "Regular expression match"),
+                ("LIKE", "Wildcard match"),
+                ("::", "Cast"),
+            ],
+            data_types: vec![
+                ("KEYWORD", "Exact string"),
+                ("TEXT", "Analyzed string"),
+                ("LONG", "64-bit integer"),
+                ("INTEGER", "32-bit integer"),
+                ("DOUBLE", "Double precision float"),
+                ("BOOLEAN", "True/false"),
+                ("DATETIME", "Date and time"),
+                ("IP", "IP address"),
+                ("GEO_POINT", "Geographic point"),
+            ],
+            snippets: vec![
+                ("tables", "SHOW TABLES", "List indices"),
+                ("cols", "SHOW COLUMNS FROM \"$1\"", "List fields of an index"),
+                ("match", "SELECT * FROM \"$1\" WHERE MATCH($2, '$3') ORDER BY SCORE() DESC", "Full-text search"),
+                ("hist", "SELECT HISTOGRAM(\"@timestamp\", INTERVAL 1 HOUR) AS h, COUNT(*) FROM \"$1\" GROUP BY h", "Hourly histogram"),
+            ],
+        }.with_standard_sql()
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = ElasticsearchConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        let conn = self.es_connection(connection)?;
+        Ok(conn.cluster_name().map(|name| vec![name.to_string()]).unwrap_or_default())
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let databases = self.list_databases(connection).await?;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Database,
+            title: format!("{} cluster(s)", databases.len()),
+            columns: vec![Column::new("name", "Name").width(px(240.0))],
+            rows: databases.into_iter().map(|name| vec![name]).collect(),
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        let databases = self.list_databases(connection).await?;
+        Ok(databases.into_iter().map(|name| DatabaseInfo {
+            name,
+            charset: None,
+            collation: None,
+            size: None,
+            table_count: None,
+            comment: None,
+        }).collect())
+    }
+
+    async fn list_tables(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<TableInfo>> {
+        let indices = self.show_tables(connection, "TABLE").await?;
+
+        Ok(indices.into_iter().map(|name| TableInfo {
+            name,
+            schema: None,
+            comment: None,
+            engine: None,
+            row_count: None,
+            create_time: None,
+            charset: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} index(es)", tables.len()),
+            columns: vec![Column::new("name", "Name").width(px(260.0))],
+            rows: tables.into_iter().map(|table| vec![table.name]).collect(),
+        })
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let conn = self.es_connection(connection)?;
+        conn.field_mappings(table)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read mapping: {}", e))
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let fields = self.list_columns(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(240.0)),
+            Column::new("type", "Mapping Type").width(px(160.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = fields.iter().map(|field| {
+            vec![field.name.clone(), field.data_type.clone()]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: format!("{} field(s)", fields.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_indexes(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<Vec<IndexInfo>> {
+        // Every field is indexed according to its mapping; there are no secondary indexes
+        Ok(Vec::new())
+    }
+
+    async fn list_indexes_view(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: "0 index(es)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(180.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_views(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<ViewInfo>> {
+        let aliases = self.show_tables(connection, "VIEW").await?;
+
+        Ok(aliases.into_iter().map(|name| ViewInfo {
+            name,
+            schema: None,
+            definition: None,
+            comment: Some("alias".to_string()),
+        }).collect())
+    }
+
+    async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let views = self.list_views(connection, database).await?;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::View,
+            title: format!("{} alias(es)", views.len()),
+            columns: vec![Column::new("name", "Name").width(px(260.0))],
+            rows: views.into_iter().map(|view| vec![view.name]).collect(),
+        })
+    }
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Function,
+            title: "0 function(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Procedure,
+            title: "0 procedure(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns: vec![
+                Column::new("name", "Name").width(px(180.0)),
+                Column::new("table", "Table").width(px(150.0)),
+            ],
+            rows: vec![],
+        })
+    }
+
+    async fn list_sequences(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SequenceInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sequences_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Sequence,
+            title: "0 sequence(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    /// Pages are read through a cursor kept on the connection, so moving to the next page
+    /// costs one request instead of re-reading every page before it
+    async fn query_table_data(&self, connection: &dyn DbConnection, request: &TableDataRequest) -> Result<TableDataResponse> {
+        let start_time = std::time::Instant::now();
+        let conn = self.es_connection(connection)?;
+
+        let table_ref = self.format_table_reference(&request.database, None, &request.table);
+        let where_clause = self.build_where_clause(request);
+        let data_sql = format!("SELECT * FROM {}{}{}", table_ref, where_clause, self.build_order_clause(request));
+
+        let count_sql = format!("SELECT COUNT(*) FROM {}{}", table_ref, where_clause);
+        let total_count = match connection.query(&count_sql, None, ExecOptions::default()).await? {
+            SqlResult::Query(result) => result.rows.first()
+                .and_then(|row| row.first())
+                .and_then(|value| value.as_ref())
+                .and_then(|value| value.parse::<usize>().ok())
+                .unwrap_or(0),
+            SqlResult::Error(err) => return Err(anyhow::anyhow!("Failed to count documents: {}", err.message)),
+            SqlResult::Exec(_) => 0,
+        };
+
+        let (columns, rows) = if request.page_size == 0 {
+            match connection.query(&data_sql, None, ExecOptions { max_rows: None, ..ExecOptions::default() }).await? {
+                SqlResult::Query(result) => {
+                    let columns = result.columns.into_iter().map(|name| (name, String::new())).collect::<Vec<_>>();
+                    (columns, result.rows)
+                }
+                SqlResult::Error(err) => return Err(anyhow::anyhow!("Failed to query index: {}", err.message)),
+                SqlResult::Exec(_) => (Vec::new(), Vec::new()),
+            }
+        } else {
+            let (columns, rows) = conn.query_page(&data_sql, request.page, request.page_size)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to query index: {}", e))?;
+            (columns.into_iter().map(|column| (column.name, column.type_name)).collect(), rows)
+        };
+
+        // Columns follow the SELECT * order; an unpaged read has no types, the mapping fills them in
+        let mapping = if columns.iter().any(|(_, type_name)| type_name.is_empty()) {
+            self.list_columns(connection, &request.database, None, &request.table).await.unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let columns: Vec<TableColumnMeta> = columns
+            .into_iter()
+            .enumerate()
+            .map(|(index, (name, type_name))| {
+                let db_type = if type_name.is_empty() {
+                    mapping.iter().find(|field| field.name == name).map(|field| field.data_type.clone()).unwrap_or_default()
+                } else {
+                    type_name
+                };
+                TableColumnMeta {
+                    name,
+                    field_type: field_type(&db_type),
+                    db_type,
+                    nullable: true,
+                    is_primary_key: false,
+                    collation: None,
+                    index,
+                }
+            })
+            .collect();
+
+        Ok(TableDataResponse {
+            columns,
+            rows,
+            total_count,
+            page: request.page,
+            page_size: request.page_size,
+            primary_key_indices: Vec::new(),
+            unique_key_indices: Vec::new(),
+            executed_sql: data_sql,
+            duration: start_time.elapsed().as_millis(),
+        })
+    }
+
+    fn get_data_types(&self) -> Vec<DataTypeInfo> {
+        vec![
+            DataTypeInfo::new("keyword", "Exact-value string").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("text", "Analyzed full-text string").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("long", "64-bit integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("integer", "32-bit integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("short", "16-bit integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("double", "Double precision float").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("float", "Single precision float").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("scaled_float", "Float stored as scaled long").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("boolean", "True/false").with_category(DataTypeCategory::Boolean),
+            DataTypeInfo::new("date", "Date and time").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("ip", "IPv4/IPv6 address").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("binary", "Base64 binary").with_category(DataTypeCategory::Binary),
+            DataTypeInfo::new("object", "JSON object").with_category(DataTypeCategory::Structured),
+            DataTypeInfo::new("nested", "Array of independent objects").with_category(DataTypeCategory::Structured),
+            DataTypeInfo::new("geo_point", "Latitude/longitude").with_category(DataTypeCategory::Other),
+        ]
+    }
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        if include_name {
+            format!("{} {}", self.quote_identifier(&column.name), column.data_type)
+        } else {
+            column.data_type.clone()
+        }
+    }
+
+    fn build_create_database_sql(&self, _request: &DatabaseOperationRequest) -> String {
+        self.unsupported("creating a database")
+    }
+
+    fn build_modify_database_sql(&self, _request: &DatabaseOperationRequest) -> String {
+        self.unsupported("modifying a database")
+    }
+
+    fn build_drop_database_sql(&self, _database_name: &str) -> String {
+        self.unsupported("dropping a database")
+    }
+
+    fn drop_table(&self, _database: &str, table: &str) -> String {
+        self.unsupported(&format!("dropping index {}", table))
+    }
+
+    fn truncate_table(&self, _database: &str, table: &str) -> String {
+        self.unsupported(&format!("truncating index {}", table))
+    }
+
+    fn rename_table(&self, _database: &str, old_name: &str, _new_name: &str) -> String {
+        self.unsupported(&format!("renaming index {}", old_name))
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        format!("{} {}", self.quote_identifier(&col.name), self.build_type_string(col))
+    }
+
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        self.unsupported(&format!("creating index {}", design.table_name))
+    }
+
+    fn build_alter_table_sql(&self, _original: &TableDesign, new: &TableDesign) -> String {
+        self.unsupported(&format!("changing the mapping of {}", new.table_name))
+    }
+
+    fn build_limit_clause(&self) -> String {
+        String::new()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        request: &crate::types::TableSaveRequest,
+        original_data: &[String],
+    ) -> (String, String) {
+        let where_clause = self.build_table_change_where_clause(request, original_data);
+        (where_clause, self.build_limit_clause())
+    }
+}
+
+impl Default for ElasticsearchPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_reference() {
+        let plugin = ElasticsearchPlugin::new();
+        assert_eq!(plugin.name(), DatabaseType::Elasticsearch);
+        assert_eq!(plugin.format_table_reference("docker-cluster", None, "logs-*"), "\"logs-*\"");
+        assert!(plugin.is_query_statement("SHOW TABLES"));
+    }
+
+    #[test]
+    fn test_mapping_field_types() {
+        assert_eq!(field_type("long"), FieldType::Integer);
+        assert_eq!(field_type("keyword"), FieldType::Text);
+        assert_eq!(field_type("text"), FieldType::LongText);
+        assert_eq!(field_type("datetime"), FieldType::DateTime);
+        assert_eq!(field_type("nested"), FieldType::Json);
+    }
+}
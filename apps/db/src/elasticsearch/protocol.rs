@@ -0,0 +1,234 @@
+//! Elasticsearch SQL REST API.
+//!
+//! `POST /_sql?format=json` runs a query and answers with the column list, the first
+//! `fetch_size` rows and a `cursor` when more rows remain. Posting `{"cursor": ...}` returns
+//! the next page (without columns) and `POST /_sql/close` releases a cursor that is not read
+//! to the end.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::executor::{QueryResult, SqlErrorInfo, SqlResult};
+use crate::types::{ColumnInfo, SqlValue};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SqlColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+/// One page of a SQL response; only the first page carries the columns
+#[derive(Debug, Clone, Deserialize)]
+pub struct SqlPage {
+    #[serde(default)]
+    pub columns: Option<Vec<SqlColumn>>,
+    #[serde(default)]
+    pub rows: Vec<Vec<Value>>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// Body of a SQL request; rows are returned in pages of `fetch_size`
+pub fn query_body(sql: &str, fetch_size: usize, params: Option<&[SqlValue]>) -> Value {
+    let mut body = json!({
+        "query": sql,
+        "fetch_size": fetch_size.max(1),
+    });
+    if let Some(params) = params {
+        body["params"] = Value::Array(params.iter().map(param_value).collect());
+    }
+    body
+}
+
+pub fn cursor_body(cursor: &str) -> Value {
+    json!({ "cursor": cursor })
+}
+
+/// `?` placeholders take typed parameters
+fn param_value(value: &SqlValue) -> Value {
+    let (type_name, value) = match value {
+        SqlValue::Null => ("null", Value::Null),
+        SqlValue::Bool(value) => ("boolean", Value::Bool(*value)),
+        SqlValue::Int(value) => ("long", json!(value)),
+        SqlValue::Float(value) => ("double", json!(value)),
+        SqlValue::String(value) => ("keyword", Value::String(value.clone())),
+        SqlValue::Bytes(value) => ("keyword", Value::String(String::from_utf8_lossy(value).into_owned())),
+        SqlValue::Json(value) => ("keyword", Value::String(value.to_string())),
+    };
+    json!({ "type": type_name, "value": value })
+}
+
+/// Error body: `{"error": {"root_cause": [{"type": ..., "reason": ...}], ...}, "status": 400}`
+pub fn error_message(body: &[u8]) -> Option<String> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    let error = value.get("error")?;
+    let cause = error
+        .get("root_cause")
+        .and_then(|causes| causes.as_array())
+        .and_then(|causes| causes.first())
+        .unwrap_or(error);
+    let reason = cause.get("reason").and_then(|reason| reason.as_str())?;
+    Some(match cause.get("type").and_then(|kind| kind.as_str()) {
+        Some(kind) => format!("{}: {}", kind, reason),
+        None => reason.to_string(),
+    })
+}
+
+/// Render a cell; arrays and objects of the source document are shown as JSON
+pub fn cell_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Collects the pages of a query into one result, stopping at `max_rows`
+#[derive(Debug, Default)]
+pub struct QueryPages {
+    pub columns: Vec<SqlColumn>,
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+impl QueryPages {
+    /// Add a page; returns false once `max_rows` is reached and the cursor should be closed
+    pub fn absorb(&mut self, page: &SqlPage, max_rows: Option<usize>) -> bool {
+        if let Some(columns) = &page.columns {
+            if self.columns.is_empty() {
+                self.columns = columns.clone();
+            }
+        }
+        for row in &page.rows {
+            if max_rows.is_some_and(|max| self.rows.len() >= max) {
+                return false;
+            }
+            self.rows.push(row.iter().map(cell_text).collect());
+        }
+        !max_rows.is_some_and(|max| self.rows.len() >= max)
+    }
+
+    pub fn into_result(self, sql: String, elapsed_ms: u128) -> SqlResult {
+        if self.columns.is_empty() {
+            return SqlResult::Error(SqlErrorInfo {
+                sql,
+                message: "Elasticsearch returned no columns".to_string(),
+            });
+        }
+        SqlResult::Query(QueryResult {
+            sql,
+            columns: self.columns.into_iter().map(|column| column.name).collect(),
+            rows: self.rows,
+            elapsed_ms,
+            table_name: None,
+            editable: false,
+        })
+    }
+}
+
+/// Fields of a `GET /<index>/_mapping` response as columns. Object and nested fields are
+/// flattened to dotted paths the way SQL addresses them, multi-fields such as `name.keyword`
+/// follow their parent. Patterns and aliases answer with several indices; a field is listed once.
+pub fn mapping_columns(mapping: &Value) -> Vec<ColumnInfo> {
+    let mut columns: Vec<ColumnInfo> = Vec::new();
+    let Some(indices) = mapping.as_object() else {
+        return columns;
+    };
+    for index in indices.values() {
+        if let Some(properties) = index.pointer("/mappings/properties").and_then(|p| p.as_object()) {
+            collect_fields(properties, "", &mut columns);
+        }
+    }
+    columns
+}
+
+fn collect_fields(properties: &Map<String, Value>, prefix: &str, columns: &mut Vec<ColumnInfo>) {
+    for (name, field) in properties {
+        let path = format!("{}{}", prefix, name);
+        if let Some(children) = field.get("properties").and_then(|p| p.as_object()) {
+            collect_fields(children, &format!("{}.", path), columns);
+            continue;
+        }
+        push_field(columns, &path, field);
+        if let Some(multi_fields) = field.get("fields").and_then(|f| f.as_object()) {
+            for (sub_name, sub_field) in multi_fields {
+                push_field(columns, &format!("{}.{}", path, sub_name), sub_field);
+            }
+        }
+    }
+}
+
+fn push_field(columns: &mut Vec<ColumnInfo>, path: &str, field: &Value) {
+    if columns.iter().any(|column| column.name == path) {
+        return;
+    }
+    let data_type = field.get("type").and_then(|t| t.as_str()).unwrap_or("object");
+    columns.push(ColumnInfo {
+        name: path.to_string(),
+        data_type: data_type.to_string(),
+        is_nullable: true,
+        is_primary_key: false,
+        default_value: None,
+        comment: None,
+        collation: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(json: &str) -> SqlPage {
+        serde_json::from_str(json).expect("valid page")
+    }
+
+    #[test]
+    fn test_collect_cursor_pages() {
+        let mut pages = QueryPages::default();
+        assert!(pages.absorb(&page(r#"{"columns":[{"name":"host","type":"keyword"},{"name":"tags","type":"keyword"}],
+            "rows":[["a",["x","y"]],["b",null]],"cursor":"c1"}"#), Some(3)));
+        assert!(!pages.absorb(&page(r#"{"rows":[["c",null],["d",null]],"cursor":"c2"}"#), Some(3)));
+
+        match pages.into_result("SELECT host, tags FROM logs".to_string(), 0) {
+            SqlResult::Query(result) => {
+                assert_eq!(result.columns, vec!["host", "tags"]);
+                assert_eq!(result.rows.len(), 3);
+                assert_eq!(result.rows[0][1].as_deref(), Some("[\"x\",\"y\"]"));
+            }
+            other => panic!("expected query result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_error_message() {
+        let body = br#"{"error":{"root_cause":[{"type":"verification_exception","reason":"Found 1 problem\nline 1:8: Unknown column [x]"}],
+            "type":"verification_exception","reason":"Found 1 problem"},"status":400}"#;
+        assert_eq!(
+            error_message(body).as_deref(),
+            Some("verification_exception: Found 1 problem\nline 1:8: Unknown column [x]")
+        );
+        assert_eq!(error_message(b"not json"), None);
+    }
+
+    #[test]
+    fn test_mapping_columns() {
+        let mapping: Value = serde_json::from_str(r#"{
+            "logs-1": {"mappings": {"properties": {
+                "message": {"type": "text", "fields": {"keyword": {"type": "keyword", "ignore_above": 256}}},
+                "host": {"properties": {"name": {"type": "keyword"}, "ip": {"type": "ip"}}},
+                "@timestamp": {"type": "date"}
+            }}},
+            "logs-2": {"mappings": {"properties": {"@timestamp": {"type": "date"}, "status": {"type": "short"}}}}
+        }"#).expect("valid mapping");
+
+        let columns: Vec<(String, String)> = mapping_columns(&mapping)
+            .into_iter()
+            .map(|column| (column.name, column.data_type))
+            .collect();
+        assert!(columns.contains(&("message.keyword".to_string(), "keyword".to_string())));
+        assert!(columns.contains(&("host.ip".to_string(), "ip".to_string())));
+        assert!(columns.contains(&("status".to_string(), "short".to_string())));
+        assert!(!columns.iter().any(|(name, _)| name == "host"));
+        assert_eq!(columns.iter().filter(|(name, _)| name == "@timestamp").count(), 1);
+    }
+}
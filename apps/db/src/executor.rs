@@ -1,8 +1,11 @@
+use std::sync::Arc;
+use std::time::Duration;
+use one_core::storage::DbConnectionConfig;
 use serde::{Deserialize, Serialize};
-use sqlparser::ast::Statement;
+use sqlparser::ast::{SetExpr, Statement};
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
-use crate::{analyze_query_editability, analyze_select_editability_fallback, classify_fallback, classify_stmt, is_query_statement_fallback, is_query_stmt};
+use crate::{analyze_query_editability, analyze_select_editability_fallback, classify_fallback, classify_stmt, is_query_statement_fallback, is_query_stmt, DatabasePlugin};
 
 /// Execution options for SQL script
 #[derive(Debug, Clone)]
@@ -13,6 +16,12 @@ pub struct ExecOptions {
     pub transactional: bool,
     /// Maximum number of rows to return for query results
     pub max_rows: Option<usize>,
+    /// Where the script was issued from (e.g. the editor tab title), passed to statement hooks
+    pub source: Option<String>,
+    /// How long a single statement may run before it is abandoned
+    pub timeout: StatementTimeout,
+    /// Named session to run on instead of a pooled one, see `open_named_session`; it stays open after the run
+    pub session_id: Option<String>,
 }
 
 impl Default for ExecOptions {
@@ -21,6 +30,36 @@ impl Default for ExecOptions {
             stop_on_error: true,
             transactional: false,
             max_rows: Some(1000),
+            source: None,
+            timeout: StatementTimeout::ConnectionDefault,
+            session_id: None,
+        }
+    }
+}
+
+/// Connection parameter (extra_params) holding the default statement timeout in seconds
+pub const STATEMENT_TIMEOUT_PARAM: &str = "statement_timeout";
+
+/// Statement timeout of a run, either the connection's default or overridden for the run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatementTimeout {
+    /// The connection's `statement_timeout` parameter, no timeout when it is not set
+    #[default]
+    ConnectionDefault,
+    /// No timeout, even when the connection has one
+    Disabled,
+    After(Duration),
+}
+
+impl StatementTimeout {
+    pub fn resolve(self, config: &DbConnectionConfig) -> Option<Duration> {
+        match self {
+            StatementTimeout::ConnectionDefault => config
+                .get_param_as::<u64>(STATEMENT_TIMEOUT_PARAM)
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            StatementTimeout::Disabled => None,
+            StatementTimeout::After(timeout) => Some(timeout),
         }
     }
 }
@@ -128,7 +167,7 @@ impl SqlStatementClassifier {
 
     /// Format execution message based on query type
     pub fn format_message(sql: &str, rows_affected: u64) -> String {
-        let trimmed = sql.trim().to_uppercase();
+        let trimmed = strip_leading_comments(sql).to_uppercase();
 
         if trimmed.starts_with("INSERT") {
             format!("Inserted {} row(s)", rows_affected)
@@ -164,6 +203,18 @@ impl SqlStatementClassifier {
     }
 }
 
+/// Skip block comments before the first keyword, such as the attribution comment added by [`AttributionCommentHook`]
+pub fn strip_leading_comments(sql: &str) -> &str {
+    let mut rest = sql.trim_start();
+    while let Some(comment) = rest.strip_prefix("/*") {
+        match comment.find("*/") {
+            Some(end) => rest = comment[end + 2..].trim_start(),
+            None => break,
+        }
+    }
+    rest.trim_end()
+}
+
 /// Statement type classification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatementType {
@@ -181,9 +232,294 @@ pub enum StatementType {
     Exec,
 }
 
+/// Connection parameter (extra_params) enabling [`AutoLimitHook`], value is the row limit
+pub const AUTO_LIMIT_PARAM: &str = "auto_limit";
+/// Connection parameter (extra_params) enabling [`BlockDropHook`]
+pub const BLOCK_DROP_PARAM: &str = "block_drop";
+/// Connection parameter (extra_params) enabling [`QueryTagHook`], value is the tag text
+pub const QUERY_TAG_PARAM: &str = "query_tag";
+/// Connection parameter (extra_params) enabling [`AttributionCommentHook`]
+pub const ATTRIBUTION_COMMENT_PARAM: &str = "attribution_comment";
+
+/// What a statement hook decided to do with a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookDecision {
+    /// Execute the statement unchanged
+    Continue,
+    /// Execute this SQL instead of the statement
+    Rewrite(String),
+    /// Refuse to execute the script, with the reason shown to the user
+    Veto(String),
+}
+
+/// Connection the hooked statements are executed on
+pub struct StatementHookContext<'a> {
+    pub config: &'a DbConnectionConfig,
+    pub plugin: &'a dyn DatabasePlugin,
+    /// See [`ExecOptions::source`]
+    pub source: Option<&'a str>,
+}
+
+/// Extension point of the statement execution pipeline.
+/// `before_execute` runs for every statement of a script before anything is sent to the database,
+/// `after_execute` runs for every result once the statement has been executed
+pub trait StatementHook: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn before_execute(&self, context: &StatementHookContext, sql: &str) -> HookDecision {
+        let _ = (context, sql);
+        HookDecision::Continue
+    }
+
+    fn after_execute(&self, context: &StatementHookContext, result: &SqlResult) {
+        let _ = (context, result);
+    }
+}
+
+/// Appends a row limit to SELECT statements that have none
+pub struct AutoLimitHook {
+    pub max_rows: usize,
+}
+
+impl AutoLimitHook {
+    fn needs_limit(statement: &Statement) -> bool {
+        let Statement::Query(query) = statement else {
+            return false;
+        };
+        if query.limit_clause.is_some() || query.fetch.is_some() {
+            return false;
+        }
+        match query.body.as_ref() {
+            SetExpr::Select(select) => select.top.is_none() && select.into.is_none(),
+            SetExpr::SetOperation { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+impl StatementHook for AutoLimitHook {
+    fn name(&self) -> &str {
+        "auto_limit"
+    }
+
+    fn before_execute(&self, context: &StatementHookContext, sql: &str) -> HookDecision {
+        let Ok(statements) = Parser::parse_sql(context.plugin.sql_dialect().as_ref(), sql) else {
+            return HookDecision::Continue;
+        };
+        match statements.as_slice() {
+            [statement] if Self::needs_limit(statement) => {
+                // MSSQL pagination needs an ORDER BY, only its emptiness matters to format_pagination
+                let order_clause = match statement {
+                    Statement::Query(query) if query.order_by.is_some() => "ORDER BY",
+                    _ => "",
+                };
+                let pagination = context.plugin.format_pagination(self.max_rows, 0, order_clause);
+                HookDecision::Rewrite(format!("{}{}", sql.trim().trim_end_matches(';'), pagination))
+            }
+            _ => HookDecision::Continue,
+        }
+    }
+}
+
+/// Refuses scripts containing DROP statements
+pub struct BlockDropHook;
+
+impl StatementHook for BlockDropHook {
+    fn name(&self) -> &str {
+        "block_drop"
+    }
+
+    fn before_execute(&self, context: &StatementHookContext, sql: &str) -> HookDecision {
+        let is_drop = match Parser::parse_sql(context.plugin.sql_dialect().as_ref(), sql) {
+            Ok(statements) => statements
+                .iter()
+                .any(|statement| statement.to_string().to_uppercase().starts_with("DROP ")),
+            Err(_) => sql.trim_start().to_uppercase().starts_with("DROP"),
+        };
+        if is_drop {
+            HookDecision::Veto(format!("DROP statements are blocked on connection '{}'", context.config.name))
+        } else {
+            HookDecision::Continue
+        }
+    }
+}
+
+/// Appends a `/* tag */` comment to every statement so APM and slow query logs can attribute it
+pub struct QueryTagHook {
+    pub tag: String,
+}
+
+impl StatementHook for QueryTagHook {
+    fn name(&self) -> &str {
+        "query_tag"
+    }
+
+    fn before_execute(&self, _context: &StatementHookContext, sql: &str) -> HookDecision {
+        // A `*/` inside the tag would end the comment early and leak the rest into the statement
+        let tag = self.tag.replace("*/", "");
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return HookDecision::Continue;
+        }
+        HookDecision::Rewrite(format!("{} /* {} */", sql.trim().trim_end_matches(';'), tag))
+    }
+}
+
+/// Prepends `/* onehub user:<os user> tab:<source> */` to every statement so DBAs can attribute
+/// load seen in server logs to the app user and editor tab that issued it
+pub struct AttributionCommentHook {
+    pub user: String,
+}
+
+impl AttributionCommentHook {
+    /// Attribute statements to the operating system user running the app
+    pub fn for_current_user() -> Self {
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+        Self { user }
+    }
+
+    /// Keep each value a single token that cannot close the comment
+    fn sanitize(value: &str) -> String {
+        value
+            .replace("*/", "")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("_")
+    }
+
+    pub fn comment(&self, source: Option<&str>) -> String {
+        let mut comment = String::from("/* onehub");
+        let user = Self::sanitize(&self.user);
+        if !user.is_empty() {
+            comment.push_str(&format!(" user:{}", user));
+        }
+        if let Some(source) = source.map(Self::sanitize).filter(|source| !source.is_empty()) {
+            comment.push_str(&format!(" tab:{}", source));
+        }
+        comment.push_str(" */");
+        comment
+    }
+}
+
+impl StatementHook for AttributionCommentHook {
+    fn name(&self) -> &str {
+        "attribution_comment"
+    }
+
+    fn before_execute(&self, context: &StatementHookContext, sql: &str) -> HookDecision {
+        HookDecision::Rewrite(format!("{} {}", self.comment(context.source), sql.trim()))
+    }
+}
+
+/// Ordered set of statement hooks applied to scripts of one connection
+#[derive(Clone, Default)]
+pub struct StatementPipeline {
+    hooks: Vec<Arc<dyn StatementHook>>,
+}
+
+impl StatementPipeline {
+    /// Built-in hooks enabled by the connection's parameters
+    pub fn from_config(config: &DbConnectionConfig) -> Self {
+        let mut pipeline = Self::default();
+        if config.get_param_bool(BLOCK_DROP_PARAM) {
+            pipeline = pipeline.with_hook(Arc::new(BlockDropHook));
+        }
+        if let Some(max_rows) = config.get_param_as::<usize>(AUTO_LIMIT_PARAM).filter(|rows| *rows > 0) {
+            pipeline = pipeline.with_hook(Arc::new(AutoLimitHook { max_rows }));
+        }
+        if let Some(tag) = config.get_param(QUERY_TAG_PARAM).filter(|tag| !tag.trim().is_empty()) {
+            pipeline = pipeline.with_hook(Arc::new(QueryTagHook { tag: tag.clone() }));
+        }
+        // Runs last so the comment stays in front of everything other hooks produced
+        if config.get_param_bool(ATTRIBUTION_COMMENT_PARAM) {
+            pipeline = pipeline.with_hook(Arc::new(AttributionCommentHook::for_current_user()));
+        }
+        pipeline
+    }
+
+    pub fn with_hook(mut self, hook: Arc<dyn StatementHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run the `before_execute` hooks over every statement of `script`.
+    /// Returns the script to execute, or the error of the first vetoed statement
+    pub fn prepare(&self, context: &StatementHookContext, script: &str) -> Result<String, SqlErrorInfo> {
+        if self.hooks.is_empty() {
+            return Ok(script.to_string());
+        }
+
+        let mut rewritten = false;
+        let mut statements = Vec::new();
+        for statement in context.plugin.split_statements(script) {
+            let mut sql = statement.trim().to_string();
+            if sql.is_empty() {
+                continue;
+            }
+            for hook in &self.hooks {
+                match hook.before_execute(context, &sql) {
+                    HookDecision::Continue => {}
+                    HookDecision::Rewrite(new_sql) => {
+                        rewritten = true;
+                        sql = new_sql;
+                    }
+                    HookDecision::Veto(reason) => {
+                        return Err(SqlErrorInfo {
+                            sql,
+                            message: format!("[{}] {}", hook.name(), reason),
+                        });
+                    }
+                }
+            }
+            statements.push(sql);
+        }
+
+        // Re-joining would drop dialect-specific separators such as DELIMITER, so keep untouched scripts as-is
+        if rewritten {
+            Ok(statements.join(";\n"))
+        } else {
+            Ok(script.to_string())
+        }
+    }
+
+    /// Run the `after_execute` hooks for one executed statement
+    pub fn finish(&self, context: &StatementHookContext, result: &SqlResult) {
+        for hook in &self.hooks {
+            hook.after_execute(context, result);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mysql::MySqlPlugin;
+    use one_core::storage::DatabaseType;
+    use std::collections::HashMap;
+
+    fn hooked_config(params: &[(&str, &str)]) -> DbConnectionConfig {
+        DbConnectionConfig {
+            id: "conn".to_string(),
+            database_type: DatabaseType::MySQL,
+            name: "prod".to_string(),
+            host: "localhost".to_string(),
+            port: 3306,
+            username: "root".to_string(),
+            password: String::new(),
+            database: None,
+            workspace_id: None,
+            extra_params: params
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
 
     #[test]
     fn test_classify_query_statements() {
@@ -234,4 +570,71 @@ mod tests {
         // DISTINCT should NOT be editable
         assert!(SqlStatementClassifier::analyze_select_editability("SELECT DISTINCT city FROM users").is_none());
     }
+
+    #[test]
+    fn test_statement_pipeline_rewrites_and_vetoes() {
+        let plugin = MySqlPlugin::new();
+        let config = hooked_config(&[(AUTO_LIMIT_PARAM, "100"), (QUERY_TAG_PARAM, "app=onehub"), (BLOCK_DROP_PARAM, "true")]);
+        let context = StatementHookContext { config: &config, plugin: &plugin, source: None };
+        let pipeline = StatementPipeline::from_config(&config);
+
+        let script = pipeline.prepare(&context, "SELECT * FROM users; SELECT * FROM orders LIMIT 5").unwrap();
+        assert_eq!(
+            script,
+            "SELECT * FROM users LIMIT 100 OFFSET 0 /* app=onehub */;\nSELECT * FROM orders LIMIT 5 /* app=onehub */"
+        );
+
+        let vetoed = pipeline.prepare(&context, "DELETE FROM logs; DROP TABLE users").unwrap_err();
+        assert_eq!(vetoed.sql, "DROP TABLE users");
+        assert!(vetoed.message.starts_with("[block_drop]"));
+    }
+
+    #[test]
+    fn test_statement_pipeline_keeps_untouched_script() {
+        let plugin = MySqlPlugin::new();
+        let config = hooked_config(&[]);
+        let context = StatementHookContext { config: &config, plugin: &plugin, source: None };
+        let script = "DROP TABLE users;\nSELECT 1";
+
+        assert!(StatementPipeline::from_config(&config).is_empty());
+        assert_eq!(StatementPipeline::from_config(&config).prepare(&context, script).unwrap(), script);
+
+        let pipeline = StatementPipeline::default().with_hook(Arc::new(AutoLimitHook { max_rows: 10 }));
+        assert_eq!(pipeline.prepare(&context, "INSERT INTO users VALUES (1)").unwrap(), "INSERT INTO users VALUES (1)");
+    }
+
+    #[test]
+    fn test_attribution_comment() {
+        let plugin = MySqlPlugin::new();
+        let config = hooked_config(&[(ATTRIBUTION_COMMENT_PARAM, "true")]);
+        let context = StatementHookContext { config: &config, plugin: &plugin, source: Some("Query 3") };
+        let hook = AttributionCommentHook { user: "alice".to_string() };
+
+        assert_eq!(
+            hook.before_execute(&context, "SELECT 1"),
+            HookDecision::Rewrite("/* onehub user:alice tab:Query_3 */ SELECT 1".to_string())
+        );
+        assert_eq!(AttributionCommentHook { user: "*/ bob".to_string() }.comment(None), "/* onehub user:bob */");
+        assert!(!StatementPipeline::from_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_format_message_skips_leading_comments() {
+        assert_eq!(
+            SqlStatementClassifier::format_message("/* onehub user:alice */ INSERT INTO users VALUES (1)", 1),
+            "Inserted 1 row(s)"
+        );
+        assert_eq!(strip_leading_comments("/* a */ /* b */ DROP TABLE t"), "DROP TABLE t");
+        assert_eq!(strip_leading_comments("/* unterminated DROP"), "/* unterminated DROP");
+    }
+
+    #[test]
+    fn test_statement_timeout_resolve() {
+        let config = hooked_config(&[(STATEMENT_TIMEOUT_PARAM, "30")]);
+        assert_eq!(StatementTimeout::ConnectionDefault.resolve(&config), Some(Duration::from_secs(30)));
+        assert_eq!(StatementTimeout::Disabled.resolve(&config), None);
+        assert_eq!(StatementTimeout::After(Duration::from_secs(5)).resolve(&config), Some(Duration::from_secs(5)));
+        assert_eq!(StatementTimeout::ConnectionDefault.resolve(&hooked_config(&[(STATEMENT_TIMEOUT_PARAM, "0")])), None);
+        assert_eq!(StatementTimeout::ConnectionDefault.resolve(&hooked_config(&[])), None);
+    }
 }
@@ -0,0 +1,105 @@
+use crate::executor::QueryResult;
+use crate::types::{DataTypeCategory, DataTypeInfo};
+
+/// 数据库扩展（目前仅 PostgreSQL）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    pub name: String,
+    /// 可安装的默认版本
+    pub default_version: Option<String>,
+    /// 已安装的版本，未安装时为 None
+    pub installed_version: Option<String>,
+    /// 扩展对象所在的 schema
+    pub schema: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl ExtensionInfo {
+    pub fn is_installed(&self) -> bool {
+        self.installed_version.is_some()
+    }
+
+    /// 已安装且有更新的默认版本
+    pub fn has_update(&self) -> bool {
+        matches!(
+            (&self.installed_version, &self.default_version),
+            (Some(installed), Some(default)) if installed != default
+        )
+    }
+
+    /// 从 `build_list_extensions_sql` 的查询结果解析扩展列表
+    pub fn from_query_result(result: &QueryResult) -> Vec<ExtensionInfo> {
+        result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let value = |i: usize| row.get(i).cloned().flatten();
+                Some(ExtensionInfo {
+                    name: value(0)?,
+                    default_version: value(1),
+                    installed_version: value(2),
+                    schema: value(3),
+                    comment: value(4),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 从 `build_list_extension_types_sql` 的查询结果（类型名、所属扩展）解析扩展提供的数据类型
+pub fn extension_types_from_query_result(result: &QueryResult) -> Vec<DataTypeInfo> {
+    result
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let name = row.first().cloned().flatten()?;
+            let extension = row.get(1).cloned().flatten().unwrap_or_default();
+            Some(DataTypeInfo::new(name, format!("扩展 {} 提供的类型", extension)).with_category(DataTypeCategory::Other))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_result(rows: Vec<Vec<Option<&str>>>) -> QueryResult {
+        QueryResult {
+            sql: String::new(),
+            columns: vec![],
+            rows: rows
+                .into_iter()
+                .map(|row| row.into_iter().map(|cell| cell.map(str::to_string)).collect())
+                .collect(),
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        }
+    }
+
+    #[test]
+    fn test_extensions_from_query_result() {
+        let result = query_result(vec![
+            vec![Some("postgis"), Some("3.4.2"), Some("3.4.0"), Some("public"), Some("PostGIS geometry")],
+            vec![Some("hstore"), Some("1.8"), None, None, Some("key/value pairs")],
+            vec![None, None, None, None, None],
+        ]);
+
+        let extensions = ExtensionInfo::from_query_result(&result);
+        assert_eq!(extensions.len(), 2);
+        assert!(extensions[0].is_installed());
+        assert!(extensions[0].has_update());
+        assert!(!extensions[1].is_installed());
+        assert!(!extensions[1].has_update());
+    }
+
+    #[test]
+    fn test_extension_types_from_query_result() {
+        let result = query_result(vec![vec![Some("geometry"), Some("postgis")], vec![None, Some("hstore")]]);
+
+        let types = extension_types_from_query_result(&result);
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0].name, "geometry");
+        assert_eq!(types[0].category, DataTypeCategory::Other);
+    }
+}
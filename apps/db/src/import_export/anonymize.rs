@@ -0,0 +1,338 @@
+use one_core::storage::DatabaseType;
+
+use crate::import_export::GridExportData;
+use crate::plugin::DatabasePlugin;
+
+/// 列的脱敏规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskingRule {
+    /// 保留原值
+    Keep,
+    /// 替换为稳定的哈希值，相同的原值得到相同的结果，邮箱保留邮箱格式
+    Hash,
+    /// 在样本行之间打乱这一列的值
+    Shuffle,
+    /// 数值置零
+    Zero,
+    /// 置为 NULL
+    Null,
+}
+
+impl MaskingRule {
+    pub fn all() -> Vec<MaskingRule> {
+        vec![Self::Keep, Self::Hash, Self::Shuffle, Self::Zero, Self::Null]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Keep => "保留",
+            Self::Hash => "哈希",
+            Self::Shuffle => "打乱",
+            Self::Zero => "置零",
+            Self::Null => "置空",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Keep => "keep",
+            Self::Hash => "hash",
+            Self::Shuffle => "shuffle",
+            Self::Zero => "zero",
+            Self::Null => "null",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        Self::all().into_iter().find(|rule| rule.as_str().eq_ignore_ascii_case(s.trim()))
+    }
+
+    /// 根据列名推测默认规则
+    pub fn suggest(column: &str) -> Self {
+        let name = column.to_lowercase();
+        let matches = |keywords: &[&str]| keywords.iter().any(|k| name.contains(k));
+
+        if matches(&["email", "mail", "phone", "mobile", "tel", "id_card", "idcard", "ssn", "passport", "ip_addr"]) {
+            Self::Hash
+        } else if matches(&["password", "passwd", "secret", "token", "salt"]) {
+            Self::Null
+        } else if matches(&["name", "address", "addr", "city", "nickname"]) {
+            Self::Shuffle
+        } else if matches(&["amount", "price", "salary", "balance", "cost", "fee", "total", "income"]) {
+            Self::Zero
+        } else {
+            Self::Keep
+        }
+    }
+}
+
+/// 脱敏抽样配置
+#[derive(Debug, Clone)]
+pub struct AnonymizeConfig {
+    /// 抽样行数，数据不足时保留全部行
+    pub sample_size: usize,
+    /// 与列一一对应的规则，缺省的列保留原值
+    pub rules: Vec<MaskingRule>,
+    /// 抽样和打乱使用的随机种子，相同的种子得到相同的结果
+    pub seed: u64,
+}
+
+/// splitmix64，足够用于抽样和打乱，且不需要引入随机数依赖
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// FNV-1a，结果不随进程或版本变化，同一份数据多次导出时哈希值一致
+fn stable_hash(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn hash_value(value: &str) -> String {
+    let digest = format!("{:016x}", stable_hash(value));
+    if value.contains('@') {
+        format!("user_{}@example.com", &digest[..10])
+    } else {
+        digest
+    }
+}
+
+fn zero_value(value: &str) -> String {
+    match value.split_once('.') {
+        Some((_, fraction)) if value.parse::<f64>().is_ok() => format!("0.{}", "0".repeat(fraction.len())),
+        _ => "0".to_string(),
+    }
+}
+
+impl GridExportData {
+    /// 随机抽取 `sample_size` 行（保持原有顺序），再按列规则脱敏
+    pub fn anonymized_sample(&self, config: &AnonymizeConfig) -> GridExportData {
+        let mut rng = SplitMix64(config.seed);
+
+        let mut indices: Vec<usize> = (0..self.rows.len()).collect();
+        if indices.len() > config.sample_size {
+            rng.shuffle(&mut indices);
+            indices.truncate(config.sample_size);
+            indices.sort_unstable();
+        }
+        let mut rows: Vec<Vec<Option<String>>> = indices.iter().map(|&i| self.rows[i].clone()).collect();
+
+        for (col, rule) in config.rules.iter().enumerate().take(self.columns.len()) {
+            match rule {
+                MaskingRule::Keep => {}
+                MaskingRule::Shuffle => {
+                    let mut values: Vec<Option<String>> = rows.iter_mut().map(|row| row.get_mut(col).and_then(Option::take)).collect();
+                    rng.shuffle(&mut values);
+                    for (row, value) in rows.iter_mut().zip(values) {
+                        if let Some(cell) = row.get_mut(col) {
+                            *cell = value;
+                        }
+                    }
+                }
+                MaskingRule::Hash | MaskingRule::Zero | MaskingRule::Null => {
+                    for cell in rows.iter_mut().filter_map(|row| row.get_mut(col)) {
+                        *cell = match (rule, cell.take()) {
+                            (MaskingRule::Hash, Some(value)) => Some(hash_value(&value)),
+                            (MaskingRule::Zero, Some(value)) => Some(zero_value(&value)),
+                            _ => None,
+                        };
+                    }
+                }
+            }
+        }
+
+        GridExportData::new(self.columns.clone(), rows)
+    }
+}
+
+/// 原地脱敏的一列
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMasking {
+    pub column: String,
+    pub rule: MaskingRule,
+    /// 哈希时保留邮箱格式
+    pub is_email: bool,
+}
+
+/// 把列值替换为 MD5 十六进制串的表达式，没有内置哈希函数的数据库返回 None
+fn hash_expression(database_type: DatabaseType, column: &str) -> Option<String> {
+    match database_type {
+        DatabaseType::MySQL | DatabaseType::TiDB => Some(format!("MD5({})", column)),
+        DatabaseType::PostgreSQL | DatabaseType::CockroachDB => Some(format!("md5({}::text)", column)),
+        DatabaseType::MSSQL => Some(format!(
+            "LOWER(CONVERT(VARCHAR(32), HASHBYTES('MD5', CAST({} AS NVARCHAR(MAX))), 2))",
+            column
+        )),
+        DatabaseType::Oracle => Some(format!("LOWER(RAWTOHEX(STANDARD_HASH({}, 'MD5')))", column)),
+        DatabaseType::ClickHouse => Some(format!("lower(hex(MD5({})))", column)),
+        DatabaseType::DuckDB => Some(format!("md5(CAST({} AS VARCHAR))", column)),
+        DatabaseType::Trino => Some(format!("lower(to_hex(md5(to_utf8(CAST({} AS VARCHAR)))))", column)),
+        DatabaseType::SQLite | DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::Elasticsearch | DatabaseType::ODBC => None,
+    }
+}
+
+fn email_expression(database_type: DatabaseType, digest: &str) -> String {
+    match database_type {
+        DatabaseType::MySQL | DatabaseType::TiDB | DatabaseType::ClickHouse => format!("CONCAT('user_', SUBSTRING({}, 1, 10), '@example.com')", digest),
+        DatabaseType::MSSQL => format!("'user_' + SUBSTRING({}, 1, 10) + '@example.com'", digest),
+        _ => format!("'user_' || SUBSTR({}, 1, 10) || '@example.com'", digest),
+    }
+}
+
+/// 按列规则生成原地脱敏的 UPDATE 语句（与导出样本的哈希结果不同，只保证相同原值得到相同结果）。
+/// 打乱无法用一条 UPDATE 表达，与数据库不支持的规则一起以注释列出
+pub fn build_masking_updates(
+    plugin: &dyn DatabasePlugin,
+    database: &str,
+    schema: Option<&str>,
+    table: &str,
+    columns: &[ColumnMasking],
+) -> String {
+    let database_type = plugin.name();
+    let mut assignments: Vec<String> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+
+    for masking in columns {
+        let column = plugin.quote_identifier(&masking.column);
+        let value = match masking.rule {
+            MaskingRule::Keep => continue,
+            MaskingRule::Null => Some("NULL".to_string()),
+            MaskingRule::Zero => Some("0".to_string()),
+            MaskingRule::Hash => hash_expression(database_type, &column).map(|digest| {
+                if masking.is_email {
+                    email_expression(database_type, &digest)
+                } else {
+                    digest
+                }
+            }),
+            MaskingRule::Shuffle => None,
+        };
+        match value {
+            Some(value) => assignments.push(format!("{} = {}", column, value)),
+            None => skipped.push(format!("{}（{}）", masking.column, masking.rule.label())),
+        }
+    }
+
+    let mut sql = String::new();
+    if !skipped.is_empty() {
+        sql.push_str(&format!("-- 以下列无法在数据库中直接脱敏，请导出时处理：{}\n", skipped.join("、")));
+    }
+    if !assignments.is_empty() {
+        let table_ref = plugin.format_table_reference(database, schema, table);
+        let statement = if database_type == DatabaseType::ClickHouse {
+            format!("ALTER TABLE {} UPDATE {} WHERE 1 = 1;", table_ref, assignments.join(", "))
+        } else {
+            format!("UPDATE {} SET {};", table_ref, assignments.join(",\n    "))
+        };
+        sql.push_str(&statement);
+    }
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> GridExportData {
+        GridExportData::new(
+            vec!["id".to_string(), "email".to_string(), "name".to_string(), "amount".to_string()],
+            (1..=10)
+                .map(|i| {
+                    vec![
+                        Some(i.to_string()),
+                        Some(format!("u{}@corp.com", i)),
+                        Some(format!("name{}", i)),
+                        Some(format!("{}.50", i * 10)),
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_suggest_rules() {
+        let rules: Vec<MaskingRule> = ["id", "user_email", "full_name", "order_amount", "password_hash"]
+            .iter()
+            .map(|c| MaskingRule::suggest(c))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![MaskingRule::Keep, MaskingRule::Hash, MaskingRule::Shuffle, MaskingRule::Zero, MaskingRule::Null]
+        );
+    }
+
+    #[test]
+    fn test_sample_keeps_order_and_is_reproducible() {
+        let config = AnonymizeConfig { sample_size: 4, rules: Vec::new(), seed: 7 };
+        let sample = data().anonymized_sample(&config);
+        assert_eq!(sample.rows.len(), 4);
+
+        let ids: Vec<usize> = sample.rows.iter().map(|r| r[0].as_ref().unwrap().parse().unwrap()).collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(sample.rows, data().anonymized_sample(&config).rows);
+
+        let all = data().anonymized_sample(&AnonymizeConfig { sample_size: 100, rules: Vec::new(), seed: 7 });
+        assert_eq!(all.rows, data().rows);
+    }
+
+    #[test]
+    fn test_masking_rules() {
+        let config = AnonymizeConfig {
+            sample_size: 10,
+            rules: vec![MaskingRule::Keep, MaskingRule::Hash, MaskingRule::Shuffle, MaskingRule::Zero],
+            seed: 1,
+        };
+        let sample = data().anonymized_sample(&config);
+
+        let first_email = sample.rows[0][1].clone().unwrap();
+        assert!(first_email.starts_with("user_") && first_email.ends_with("@example.com"));
+        assert_eq!(Some(first_email), data().anonymized_sample(&config).rows[0][1]);
+        assert!(sample.rows.iter().all(|r| r[3].as_deref() == Some("0.00")));
+
+        let mut names: Vec<String> = sample.rows.iter().map(|r| r[2].clone().unwrap()).collect();
+        names.sort();
+        let mut original: Vec<String> = data().rows.iter().map(|r| r[2].clone().unwrap()).collect();
+        original.sort();
+        assert_eq!(names, original);
+    }
+
+    #[test]
+    fn test_masking_updates() {
+        let columns = vec![
+            ColumnMasking { column: "id".to_string(), rule: MaskingRule::Keep, is_email: false },
+            ColumnMasking { column: "email".to_string(), rule: MaskingRule::Hash, is_email: true },
+            ColumnMasking { column: "phone".to_string(), rule: MaskingRule::Hash, is_email: false },
+            ColumnMasking { column: "name".to_string(), rule: MaskingRule::Shuffle, is_email: false },
+            ColumnMasking { column: "salary".to_string(), rule: MaskingRule::Zero, is_email: false },
+        ];
+        let sql = build_masking_updates(&crate::mysql::MySqlPlugin::new(), "shop", None, "users", &columns);
+        assert_eq!(
+            sql,
+            "-- 以下列无法在数据库中直接脱敏，请导出时处理：name（打乱）\n\
+             UPDATE `shop`.`users` SET `email` = CONCAT('user_', SUBSTRING(MD5(`email`), 1, 10), '@example.com'),\n    \
+             `phone` = MD5(`phone`),\n    `salary` = 0;"
+        );
+        assert_eq!(MaskingRule::from_str(" Hash "), Some(MaskingRule::Hash));
+        assert_eq!(MaskingRule::from_str("redact"), None);
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Serialize;
+
+use crate::import_export::{DataFormat, ExportConfig, TextEncoding};
+use crate::types::ColumnInfo;
+
+/// zip 包中描述各表文件的清单
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// 多表导出为 zip 包时附带的清单，导入方据此知道每个文件对应的表、列定义和行数
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveManifest {
+    pub format: String,
+    pub database: String,
+    pub encoding: String,
+    pub exported_at: String,
+    pub tables: Vec<ArchiveTable>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveTable {
+    pub name: String,
+    pub file: String,
+    pub rows: u64,
+    /// 拿不到列信息时为空
+    pub columns: Vec<ArchiveColumn>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveColumn {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+impl ArchiveManifest {
+    pub fn new(config: &ExportConfig) -> Self {
+        // Avro 中的字符串总是 UTF-8，不受导出编码影响
+        let encoding = match config.format {
+            DataFormat::Avro => TextEncoding::Utf8,
+            _ => config.encoding,
+        };
+        Self {
+            format: config.format.extension().to_string(),
+            database: config.database.clone(),
+            encoding: encoding.label().to_string(),
+            exported_at: chrono::Local::now().to_rfc3339(),
+            tables: Vec::new(),
+        }
+    }
+
+    pub fn add_table(&mut self, name: &str, file: String, rows: u64, columns: &[ColumnInfo]) {
+        self.tables.push(ArchiveTable {
+            name: name.to_string(),
+            file,
+            rows,
+            columns: columns
+                .iter()
+                .map(|column| ArchiveColumn {
+                    name: column.name.clone(),
+                    data_type: column.data_type.clone(),
+                    nullable: column.is_nullable,
+                    primary_key: column.is_primary_key,
+                })
+                .collect(),
+        });
+    }
+}
+
+/// 表名可能带有文件名中不允许的字符（例如 `schema/table`），替换为 `_`
+pub fn archive_file_name(table: &str, format: DataFormat) -> String {
+    let stem: String = table
+        .chars()
+        .map(|ch| if matches!(ch, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || ch.is_control() { '_' } else { ch })
+        .collect();
+    format!("{}.{}", stem, format.extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_lists_tables() {
+        let config = ExportConfig {
+            format: DataFormat::Csv,
+            database: "shop".to_string(),
+            encoding: TextEncoding::Gbk,
+            ..Default::default()
+        };
+        let mut manifest = ArchiveManifest::new(&config);
+        let columns = vec![ColumnInfo {
+            name: "id".to_string(),
+            data_type: "int".to_string(),
+            is_nullable: false,
+            is_primary_key: true,
+            default_value: None,
+            comment: None,
+            collation: None,
+        }];
+        manifest.add_table("a/b", archive_file_name("a/b", config.format), 3, &columns);
+
+        let value = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(value["format"], "csv");
+        assert_eq!(value["encoding"], "GBK");
+        assert_eq!(
+            value["tables"],
+            serde_json::json!([{
+                "name": "a/b",
+                "file": "a_b.csv",
+                "rows": 3,
+                "columns": [{ "name": "id", "data_type": "int", "nullable": false, "primary_key": true }]
+            }])
+        );
+    }
+}
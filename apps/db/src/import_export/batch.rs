@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use crate::connection::DbConnection;
+use crate::DatabasePlugin;
+use crate::executor::{ExecOptions, SqlResult, StatementTimeout};
+use crate::import_export::{ImportConfig, ImportProgressEvent, ImportProgressSender};
+
+/// 导入时每条多行 INSERT 包含的行数
+pub const DEFAULT_IMPORT_BATCH_ROWS: usize = 500;
+
+/// 使用事务导入时，每执行这么多条 INSERT 提交一次
+pub const DEFAULT_IMPORT_BATCHES_PER_COMMIT: usize = 20;
+
+/// 把逐行读到的数据拼成多行 INSERT，攒够一次提交的量后一起执行。
+/// 开启 `use_transaction` 时每次提交是一个事务，失败时只回滚这一组，之前提交的行保留
+pub struct BatchInserter<'a> {
+    plugin: Arc<dyn DatabasePlugin>,
+    connection: &'a dyn DbConnection,
+    config: &'a ImportConfig,
+    file_name: String,
+    progress_tx: Option<ImportProgressSender>,
+    /// `INSERT INTO t (a, b) VALUES `
+    insert_prefix: String,
+    rows_per_insert: usize,
+    pending_rows: Vec<String>,
+    pending_statements: Vec<String>,
+    /// 当前这组数据在源文件中的起止行号，用于错误信息
+    pending_lines: Option<(usize, usize)>,
+    rows_imported: u64,
+    errors: Vec<String>,
+    stopped: bool,
+}
+
+impl<'a> BatchInserter<'a> {
+    pub fn new(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &'a dyn DbConnection,
+        config: &'a ImportConfig,
+        table: &str,
+        columns: &[String],
+        file_name: &str,
+        progress_tx: Option<ImportProgressSender>,
+    ) -> Self {
+        let column_list = columns
+            .iter()
+            .map(|column| plugin.quote_identifier(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let insert_prefix = format!("INSERT INTO {} ({}) VALUES ", plugin.quote_identifier(table), column_list);
+        let rows_per_insert = plugin
+            .max_rows_per_insert()
+            .map_or(config.batch_size, |max_rows| config.batch_size.min(max_rows))
+            .max(1);
+
+        Self {
+            plugin,
+            connection,
+            config,
+            file_name: file_name.to_string(),
+            progress_tx,
+            insert_prefix,
+            rows_per_insert,
+            pending_rows: Vec::new(),
+            pending_statements: Vec::new(),
+            pending_lines: None,
+            rows_imported: 0,
+            errors: Vec::new(),
+            stopped: false,
+        }
+    }
+
+    /// 遇错停止时，出错后调用方应停止读取
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// `values` 是已经格式化好的 SQL 字面量，`line` 是这一行在源文件中的行号
+    pub async fn push(&mut self, line: usize, values: &[String]) {
+        if self.stopped {
+            return;
+        }
+        self.pending_rows.push(format!("({})", values.join(", ")));
+        self.pending_lines = Some(match self.pending_lines {
+            Some((first, _)) => (first, line),
+            None => (line, line),
+        });
+
+        if self.pending_rows.len() >= self.rows_per_insert {
+            self.close_statement();
+            if self.pending_statements.len() >= self.config.batches_per_commit.max(1) {
+                self.execute_pending().await;
+            }
+        }
+    }
+
+    /// 记录无法转换成 INSERT 的行
+    pub fn skip_row(&mut self, message: String) {
+        self.report_error(message);
+        if self.config.stop_on_error {
+            self.stopped = true;
+        }
+    }
+
+    /// 执行还没写入的行（包括遇错停止前读到的行），返回导入的行数和错误
+    pub async fn finish(mut self) -> (u64, Vec<String>) {
+        self.close_statement();
+        self.execute_pending().await;
+        (self.rows_imported, self.errors)
+    }
+
+    fn close_statement(&mut self) {
+        if self.pending_rows.is_empty() {
+            return;
+        }
+        let statement = format!("{}{}", self.insert_prefix, self.pending_rows.join(", "));
+        self.pending_rows.clear();
+        self.pending_statements.push(statement);
+    }
+
+    async fn execute_pending(&mut self) {
+        if self.pending_statements.is_empty() {
+            return;
+        }
+        let script = self.pending_statements.join(";\n");
+        self.pending_statements.clear();
+        let lines = match self.pending_lines.take() {
+            Some((first, last)) if first == last => format!("Line {}", first),
+            Some((first, last)) => format!("Lines {}-{}", first, last),
+            None => String::new(),
+        };
+
+        let exec_options = ExecOptions {
+            stop_on_error: self.config.stop_on_error,
+            transactional: self.config.use_transaction,
+            max_rows: None,
+            source: None,
+            // Bulk inserts of an import may legitimately run longer than interactive statements
+            timeout: StatementTimeout::Disabled,
+            session_id: None,
+        };
+
+        let mut rows_affected = 0u64;
+        let mut failed = false;
+        match self.connection.execute_import(self.plugin.clone(), &script, exec_options, self.config.async_insert).await {
+            Ok(results) => {
+                for result in results {
+                    match result {
+                        SqlResult::Exec(exec_result) => rows_affected += exec_result.rows_affected,
+                        SqlResult::Error(err) => {
+                            failed = true;
+                            self.report_error(format!("{}: {}", lines, err.message));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) => {
+                failed = true;
+                self.report_error(format!("{}: {}", lines, e));
+            }
+        }
+
+        // 事务中出错时整组已回滚，之前执行成功的 INSERT 也不算导入
+        if failed && self.config.use_transaction {
+            rows_affected = 0;
+        }
+        self.rows_imported += rows_affected;
+        if rows_affected > 0 {
+            if let Some(tx) = &self.progress_tx {
+                let _ = tx.send(ImportProgressEvent::StatementExecuted {
+                    file: self.file_name.clone(),
+                    rows_affected,
+                });
+            }
+        }
+        if failed && self.config.stop_on_error {
+            self.stopped = true;
+        }
+    }
+
+    fn report_error(&mut self, message: String) {
+        if let Some(tx) = &self.progress_tx {
+            let _ = tx.send(ImportProgressEvent::Error {
+                file: self.file_name.clone(),
+                message: message.clone(),
+            });
+        }
+        self.errors.push(message);
+    }
+}
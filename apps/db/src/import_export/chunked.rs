@@ -0,0 +1,219 @@
+use anyhow::{anyhow, Result};
+
+use crate::connection::DbConnection;
+use crate::DatabasePlugin;
+use crate::executor::{ExecOptions, SqlResult};
+use crate::import_export::{ExportConfig, ExportProgressEvent, ExportProgressSender};
+
+/// 导出时每次查询读取的行数
+pub const DEFAULT_EXPORT_CHUNK_ROWS: usize = 5000;
+
+/// 一次查询读到的一块数据
+#[derive(Debug, Clone)]
+pub struct TableChunk {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+/// keyset 分页使用的单列主键
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChunkKey {
+    column: String,
+    /// 整数主键的上一块最后一个值不加引号，避免按字符串比较
+    numeric: bool,
+}
+
+/// 按块读取一个表的数据，内存中只保留当前块。
+/// 表有单列主键时按主键做 keyset 分页（`WHERE key > 上一块最后的值 ORDER BY key`），
+/// 千万行的表翻到最后也不会越来越慢；没有主键时退回 LIMIT/OFFSET 分页
+pub struct TableChunkReader<'a> {
+    plugin: &'a dyn DatabasePlugin,
+    connection: &'a dyn DbConnection,
+    config: &'a ExportConfig,
+    table: &'a str,
+    /// 表的全部主键列，`key` 只在主键为单列时才有
+    key_columns: Vec<String>,
+    key: Option<ChunkKey>,
+    /// 为 false 时仍按主键排序，但用 OFFSET 翻页
+    keyset: bool,
+    last_key: Option<String>,
+    offset: usize,
+    /// 还能读取的行数，对应 `ExportConfig::limit`
+    remaining: Option<usize>,
+    chunks_read: usize,
+    finished: bool,
+}
+
+impl<'a> TableChunkReader<'a> {
+    pub async fn open(
+        plugin: &'a dyn DatabasePlugin,
+        connection: &'a dyn DbConnection,
+        config: &'a ExportConfig,
+        table: &'a str,
+    ) -> Self {
+        // 拿不到列信息时按没有主键处理
+        let columns = plugin
+            .list_columns(connection, &config.database, None, table)
+            .await
+            .unwrap_or_default();
+        let primary_keys: Vec<_> = columns.iter().filter(|column| column.is_primary_key).collect();
+        let key = match primary_keys.as_slice() {
+            [column] => Some(ChunkKey {
+                column: column.name.clone(),
+                numeric: is_integer_type(&column.data_type),
+            }),
+            _ => None,
+        };
+
+        Self {
+            plugin,
+            connection,
+            config,
+            table,
+            key_columns: primary_keys.iter().map(|column| column.name.clone()).collect(),
+            keyset: key.is_some(),
+            key,
+            last_key: None,
+            offset: 0,
+            remaining: config.limit,
+            chunks_read: 0,
+            finished: false,
+        }
+    }
+
+    /// 表的主键列，拿不到列信息或没有主键时为空
+    pub fn key_columns(&self) -> &[String] {
+        &self.key_columns
+    }
+
+    /// 读取下一块；第一块即使没有行也会返回，以便调用方拿到列名写表头
+    pub async fn next_chunk(&mut self) -> Result<Option<TableChunk>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let chunk_size = self.config.chunk_size.max(1);
+        let size = self.remaining.map_or(chunk_size, |remaining| remaining.min(chunk_size));
+        let select_sql = if self.keyset || self.supports_offset(size) {
+            self.build_select(Some(size))
+        } else {
+            // 数据库忽略 OFFSET 时分页会一直读到同一块，只能一次读完
+            self.finished = true;
+            self.build_select(self.remaining)
+        };
+
+        let result = self.connection.query(&select_sql, None, ExecOptions::default()).await
+            .map_err(|e| anyhow!("Query failed: {}", e))?;
+        let (columns, rows) = match result {
+            SqlResult::Query(query_result) => (query_result.columns, query_result.rows),
+            SqlResult::Error(err) => return Err(anyhow!("Query failed: {}", err.message)),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        if rows.len() < size || self.remaining == Some(rows.len()) {
+            self.finished = true;
+        }
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining = remaining.saturating_sub(rows.len());
+        }
+        self.offset += rows.len();
+        if let Some(key) = self.key.as_ref().filter(|_| self.keyset && !rows.is_empty()) {
+            let key_index = columns.iter().position(|column| column.eq_ignore_ascii_case(&key.column));
+            match key_index.and_then(|index| rows.last().and_then(|row| row.get(index).cloned().flatten())) {
+                Some(value) => self.last_key = Some(value),
+                // 结果里找不到主键列或主键为 NULL 时无法比较，按同样的排序改用 OFFSET 读剩下的行
+                None => self.keyset = false,
+            }
+        }
+
+        self.chunks_read += 1;
+        if rows.is_empty() && self.chunks_read > 1 {
+            return Ok(None);
+        }
+        Ok(Some(TableChunk { columns, rows }))
+    }
+
+    /// 部分数据库（如 Elasticsearch SQL）的分页语法不支持 OFFSET
+    fn supports_offset(&self, size: usize) -> bool {
+        self.plugin.format_pagination(size, 0, "") != self.plugin.format_pagination(size, size, "")
+    }
+
+    /// `size` 为 None 时不加分页
+    fn build_select(&self, size: Option<usize>) -> String {
+        let table_ref = self.plugin.format_table_reference(&self.config.database, None, self.table);
+        let mut conditions = Vec::new();
+        if let Some(where_clause) = &self.config.where_clause {
+            conditions.push(format!("({})", where_clause));
+        }
+        let mut order_clause = String::new();
+        if let Some(key) = &self.key {
+            let key_ident = self.plugin.quote_identifier(&key.column);
+            if let Some(last_key) = self.last_key.as_ref().filter(|_| self.keyset) {
+                conditions.push(format!("{} > {}", key_ident, key_literal(self.plugin, last_key, key.numeric)));
+            }
+            order_clause = format!(" ORDER BY {}", key_ident);
+        }
+
+        let mut sql = format!("SELECT * FROM {}", table_ref);
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(&order_clause);
+        if let Some(size) = size {
+            let offset = if self.keyset { 0 } else { self.offset };
+            sql.push_str(&self.plugin.format_pagination(size, offset, &order_clause));
+        }
+        sql
+    }
+}
+
+pub(crate) fn send_progress(progress_tx: &Option<ExportProgressSender>, event: ExportProgressEvent) {
+    if let Some(tx) = progress_tx {
+        let _ = tx.send(event);
+    }
+}
+
+fn is_integer_type(data_type: &str) -> bool {
+    let data_type = data_type.to_ascii_lowercase();
+    let base = data_type.split(['(', ' ']).next().unwrap_or_default();
+    matches!(
+        base,
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2" | "int4" | "int8"
+            | "serial" | "smallserial" | "bigserial" | "int16" | "int32" | "int64"
+            | "uint8" | "uint16" | "uint32" | "uint64"
+    )
+}
+
+fn key_literal(plugin: &dyn DatabasePlugin, value: &str, numeric: bool) -> String {
+    if numeric && value.parse::<i128>().is_ok() {
+        value.to_string()
+    } else {
+        plugin.string_literal(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_literal() {
+        let plugin = crate::postgresql::PostgresPlugin::new();
+        assert_eq!(key_literal(&plugin, "42", true), "42");
+        assert_eq!(key_literal(&plugin, "-7", true), "-7");
+        assert_eq!(key_literal(&plugin, "42", false), "'42'");
+        assert_eq!(key_literal(&plugin, "1 OR 1=1", true), "'1 OR 1=1'");
+        assert_eq!(key_literal(&plugin, "o'brien", false), "'o''brien'");
+        assert_eq!(key_literal(&crate::mysql::MySqlPlugin::new(), "a\\' OR 1=1 -- ", false), "'a\\\\'' OR 1=1 -- '");
+    }
+
+    #[test]
+    fn test_integer_types() {
+        assert!(is_integer_type("BIGINT(20) UNSIGNED"));
+        assert!(is_integer_type("int4"));
+        assert!(is_integer_type("UInt64"));
+        assert!(!is_integer_type("varchar(36)"));
+        assert!(!is_integer_type("decimal(10,0)"));
+    }
+}
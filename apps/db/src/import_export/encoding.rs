@@ -0,0 +1,251 @@
+use std::io::{self, BufRead, Read, Write};
+
+use encoding_rs::{Decoder, Encoder, EncoderResult, Encoding, GB18030, GBK, SHIFT_JIS, UTF_8, WINDOWS_1252};
+
+/// 导入导出文件的文本编码，数据库和界面内部始终是 UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    /// 简体中文 Windows 下 Excel 另存的 CSV 默认是 GBK
+    Gbk,
+    /// GBK 的超集，能表示所有 Unicode 字符
+    Gb18030,
+    /// 日文 Windows 下的默认编码
+    ShiftJis,
+    /// 按 Windows-1252 处理，它是 Latin-1 的超集
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Utf8, Self::Gbk, Self::Gb18030, Self::ShiftJis, Self::Latin1]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Gbk => "GBK",
+            Self::Gb18030 => "GB18030",
+            Self::ShiftJis => "Shift-JIS",
+            Self::Latin1 => "Latin-1",
+        }
+    }
+
+    fn encoding(&self) -> &'static Encoding {
+        match self {
+            Self::Utf8 => UTF_8,
+            Self::Gbk => GBK,
+            Self::Gb18030 => GB18030,
+            Self::ShiftJis => SHIFT_JIS,
+            Self::Latin1 => WINDOWS_1252,
+        }
+    }
+
+    /// 解码一段样本，去掉 UTF-8 BOM，非法字节替换为 U+FFFD
+    pub fn decode_lossy(&self, bytes: &[u8]) -> String {
+        self.encoding().decode_with_bom_removal(bytes).0.into_owned()
+    }
+}
+
+/// 把 GBK、Shift-JIS 等编码的输入边读边转成 UTF-8，非法字节替换为 U+FFFD。
+/// UTF-8 输入原样透传，非法字节仍由读取方报错
+pub struct TranscodingReader<'a> {
+    inner: &'a mut (dyn BufRead + Send),
+    decoder: Option<Decoder>,
+    buffer: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<'a> TranscodingReader<'a> {
+    pub fn new(inner: &'a mut (dyn BufRead + Send), encoding: TextEncoding) -> Self {
+        let decoder = match encoding {
+            TextEncoding::Utf8 => None,
+            encoding => Some(encoding.encoding().new_decoder_with_bom_removal()),
+        };
+        Self {
+            inner,
+            decoder,
+            buffer: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+}
+
+impl Read for TranscodingReader<'_> {
+    fn read(&mut self, output: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(output.len());
+        output[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl BufRead for TranscodingReader<'_> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let Some(decoder) = self.decoder.as_mut() else {
+            return self.inner.fill_buf();
+        };
+        while self.position >= self.buffer.len() && !self.finished {
+            let input = self.inner.fill_buf()?;
+            let last = input.is_empty();
+            let capacity = decoder
+                .max_utf8_buffer_length(input.len())
+                .ok_or_else(|| io::Error::other("Input chunk too large to transcode"))?;
+            self.buffer.clear();
+            self.buffer.resize(capacity, 0);
+            // 缓冲区按最大长度分配，一次就能解码完整个输入块；块末尾不完整的多字节字符由解码器留到下一块
+            let (_, read, written, _) = decoder.decode_to_utf8(input, &mut self.buffer, last);
+            self.buffer.truncate(written);
+            self.position = 0;
+            self.inner.consume(read);
+            self.finished = last;
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        if self.decoder.is_none() {
+            self.inner.consume(amount);
+            return;
+        }
+        self.position = (self.position + amount).min(self.buffer.len());
+    }
+}
+
+/// 把写入的 UTF-8 文本转成目标编码后写出，目标编码无法表示的字符写为 `?`。
+/// UTF-8 输出原样透传
+pub struct TranscodingWriter<'a> {
+    inner: &'a mut (dyn Write + Send),
+    encoder: Option<Encoder>,
+    /// 上次写入末尾不完整的 UTF-8 字符，等下次写入补齐
+    pending: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl<'a> TranscodingWriter<'a> {
+    pub fn new(inner: &'a mut (dyn Write + Send), encoding: TextEncoding) -> Self {
+        let encoder = match encoding {
+            TextEncoding::Utf8 => None,
+            encoding => Some(encoding.encoding().new_encoder()),
+        };
+        Self {
+            inner,
+            encoder,
+            pending: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// 只有输出 UTF-8 时才写 BOM，其他编码没有对应的字节
+    pub fn is_utf8(&self) -> bool {
+        self.encoder.is_none()
+    }
+}
+
+impl Write for TranscodingWriter<'_> {
+    fn write(&mut self, input: &[u8]) -> io::Result<usize> {
+        let Some(encoder) = self.encoder.as_mut() else {
+            return self.inner.write(input);
+        };
+        self.pending.extend_from_slice(input);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let mut text = std::str::from_utf8(&self.pending[..complete])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let capacity = encoder
+            .max_buffer_length_from_utf8_without_replacement(text.len())
+            .ok_or_else(|| io::Error::other("Output chunk too large to transcode"))?;
+        self.buffer.clear();
+        self.buffer.resize(capacity, 0);
+        let mut written = 0;
+        loop {
+            let (result, read, encoded) =
+                encoder.encode_from_utf8_without_replacement(text, &mut self.buffer[written..], false);
+            written += encoded;
+            text = &text[read..];
+            match result {
+                EncoderResult::InputEmpty => break,
+                EncoderResult::Unmappable(_) => {
+                    if written == self.buffer.len() {
+                        self.buffer.push(0);
+                    }
+                    self.buffer[written] = b'?';
+                    written += 1;
+                }
+                EncoderResult::OutputFull => {
+                    let length = self.buffer.len();
+                    self.buffer.resize(length * 2 + 16, 0);
+                }
+            }
+        }
+        self.inner.write_all(&self.buffer[..written])?;
+        self.pending.drain(..complete);
+        Ok(input.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import_export::LineReader;
+
+    #[test]
+    fn test_transcoded_lines() {
+        let mut data: &[u8] = b"\xd6\xd0,\xce\xc4\r\nok\n";
+        let mut decoded = TranscodingReader::new(&mut data, TextEncoding::Gbk);
+        let mut lines = LineReader::new(&mut decoded);
+        assert_eq!(lines.next_line().unwrap(), Some((1, "中,文".to_string())));
+        assert_eq!(lines.next_line().unwrap(), Some((2, "ok".to_string())));
+        assert_eq!(lines.next_line().unwrap(), None);
+
+        let mut data: &[u8] = b"caf\xe9";
+        let mut decoded = TranscodingReader::new(&mut data, TextEncoding::Latin1);
+        assert_eq!(LineReader::new(&mut decoded).next_line().unwrap(), Some((1, "café".to_string())));
+
+        // "テスト" in Shift-JIS
+        let mut data: &[u8] = b"\x83\x65\x83\x58\x83\x67";
+        let mut decoded = TranscodingReader::new(&mut data, TextEncoding::ShiftJis);
+        assert_eq!(LineReader::new(&mut decoded).next_line().unwrap(), Some((1, "テスト".to_string())));
+    }
+
+    #[test]
+    fn test_utf8_passes_through() {
+        let mut data: &[u8] = b"\xef\xbb\xbfa\n\xff\n";
+        let mut reader = TranscodingReader::new(&mut data, TextEncoding::Utf8);
+        let mut lines = LineReader::new(&mut reader);
+        // BOM 由各格式自己处理，非法字节照常报错
+        assert_eq!(lines.next_line().unwrap(), Some((1, "\u{FEFF}a".to_string())));
+        assert!(lines.next_line().is_err());
+    }
+
+    #[test]
+    fn test_transcoded_writes() {
+        let mut output = Vec::new();
+        let mut writer = TranscodingWriter::new(&mut output, TextEncoding::Gbk);
+        let bytes = "中,文€😀\n".as_bytes();
+        // 故意在多字节字符中间拆开写入
+        writer.write_all(&bytes[..2]).unwrap();
+        writer.write_all(&bytes[2..]).unwrap();
+        assert_eq!(output, b"\xd6\xd0,\xce\xc4\x80?\n");
+
+        let mut output = Vec::new();
+        TranscodingWriter::new(&mut output, TextEncoding::Gb18030).write_all("😀".as_bytes()).unwrap();
+        assert_eq!(output, b"\x95\x32\x82\x36");
+
+        let mut output = Vec::new();
+        TranscodingWriter::new(&mut output, TextEncoding::Latin1).write_all("café 中".as_bytes()).unwrap();
+        assert_eq!(output, b"caf\xe9 ?");
+    }
+}
@@ -1,9 +1,16 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use std::sync::Arc;
-use anyhow::Result;
+use std::time::Instant;
+use anyhow::{anyhow, Result};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
 use crate::connection::DbConnection;
-use crate::import_export::{DataFormat, ExportConfig, ExportResult, FormatHandler, ExportProgressSender};
-use crate::import_export::formats::{CsvFormatHandler, JsonFormatHandler, SqlFormatHandler};
+use crate::import_export::{archive_file_name, ArchiveManifest, DataFormat, ExportConfig, ExportProgressEvent, ExportResult, FormatHandler, ExportProgressSender, NativeExportFile, NativeExportFormat, MANIFEST_FILE_NAME};
+use crate::import_export::chunked::send_progress;
+use crate::import_export::formats::{AvroFormatHandler, CsvFormatHandler, JsonFormatHandler, SqlFormatHandler, YamlFormatHandler};
 use crate::DatabasePlugin;
 
 pub struct DataExporter;
@@ -33,6 +40,185 @@ impl DataExporter {
             DataFormat::Csv => {
                 CsvFormatHandler.export_with_progress(plugin, connection, &config, progress_tx).await
             }
+            DataFormat::Yaml => {
+                YamlFormatHandler.export_with_progress(plugin, connection, &config, progress_tx).await
+            }
+            DataFormat::Avro => {
+                AvroFormatHandler.export_with_progress(plugin, connection, &config, progress_tx).await
+            }
+        }
+    }
+
+    /// 边查询边写入文件，大表导出时内存占用只取决于 `ExportConfig::chunk_size`。
+    /// 导出失败时删除写了一半的文件
+    pub async fn export_to_file(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: ExportConfig,
+        path: &Path,
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult> {
+        let file = File::create(path)
+            .map_err(|e| anyhow!("Create {} failed: {}", path.display(), e))?;
+        let mut writer = BufWriter::new(file);
+        let result = Self::export_to_writer(plugin, connection, &config, &mut writer, progress_tx).await;
+        if result.is_err() {
+            drop(writer);
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+
+    /// 每个表导出为 zip 包中的一个文件，另附 `manifest.json` 记录各表的文件名、列定义和行数，
+    /// 多个表不必分多次导出。边查询边压缩写入，导出失败时删除写了一半的文件
+    pub async fn export_to_zip(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: ExportConfig,
+        path: &Path,
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult> {
+        let file = File::create(path)
+            .map_err(|e| anyhow!("Create {} failed: {}", path.display(), e))?;
+        let result = Self::write_zip(plugin, connection, &config, BufWriter::new(file), progress_tx).await;
+        if result.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        result
+    }
+
+    async fn write_zip(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+        output: BufWriter<File>,
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult> {
+        let start = Instant::now();
+        let mut archive = ZipWriter::new(output);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut manifest = ArchiveManifest::new(config);
+        let mut total_rows = 0u64;
+        let total_tables = config.tables.len();
+
+        for (table_index, table) in config.tables.iter().enumerate() {
+            send_progress(&progress_tx, ExportProgressEvent::TableStart {
+                table: table.clone(),
+                table_index,
+                total_tables,
+            });
+            let columns = plugin
+                .list_columns(connection, &config.database, None, table)
+                .await
+                .unwrap_or_default();
+            let file_name = archive_file_name(table, config.format);
+            archive.start_file(file_name.as_str(), options)?;
+
+            // 每个文件只含一个表，各格式按单表导出；逐表的进度由这里汇报
+            let table_config = ExportConfig {
+                tables: vec![table.clone()],
+                ..config.clone()
+            };
+            let result = Self::export_to_writer(plugin.clone(), connection, &table_config, &mut archive, None)
+                .await
+                .map_err(|e| anyhow!("Export {} failed: {}", table, e))?;
+            total_rows += result.rows_exported;
+            manifest.add_table(table, file_name, result.rows_exported, &columns);
+
+            send_progress(&progress_tx, ExportProgressEvent::DataExported {
+                table: table.clone(),
+                rows: result.rows_exported,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::TableFinished {
+                table: table.clone(),
+            });
+        }
+
+        archive.start_file(MANIFEST_FILE_NAME, options)?;
+        archive.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        archive.finish()?.flush()?;
+
+        let elapsed_ms = start.elapsed().as_millis();
+        send_progress(&progress_tx, ExportProgressEvent::Finished {
+            total_rows,
+            elapsed_ms,
+        });
+        Ok(ExportResult {
+            success: true,
+            output: String::new(),
+            rows_exported: total_rows,
+            elapsed_ms,
+        })
+    }
+
+    async fn export_to_writer(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult> {
+        match config.format {
+            DataFormat::Sql => {
+                SqlFormatHandler.export_to_writer(plugin, connection, config, writer, progress_tx).await
+            }
+            DataFormat::Json => {
+                JsonFormatHandler.export_to_writer(plugin, connection, config, writer, progress_tx).await
+            }
+            DataFormat::Csv => {
+                CsvFormatHandler.export_to_writer(plugin, connection, config, writer, progress_tx).await
+            }
+            DataFormat::Yaml => {
+                YamlFormatHandler.export_to_writer(plugin, connection, config, writer, progress_tx).await
+            }
+            DataFormat::Avro => {
+                AvroFormatHandler.export_to_writer(plugin, connection, config, writer, progress_tx).await
+            }
+        }
+    }
+
+    /// 导出二进制格式，每个表一个文件
+    pub async fn export_files(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+    ) -> Result<Vec<NativeExportFile>> {
+        match config.format {
+            DataFormat::Avro => AvroFormatHandler.export_files(plugin, connection, config).await,
+            format => Err(anyhow!("{} is a text format, export it with DataExporter::export", format.extension())),
+        }
+    }
+
+    /// 把查询下推到服务端按指定格式生成结果，每个表一个文件，不逐行转换
+    pub async fn export_native(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+        format: NativeExportFormat,
+    ) -> Result<Vec<NativeExportFile>> {
+        let mut files = Vec::with_capacity(config.tables.len());
+        for table in &config.tables {
+            let sql = Self::build_native_select(plugin.as_ref(), config, table);
+            let data = connection
+                .export_formatted(&sql, format.format_name())
+                .await
+                .map_err(|e| anyhow!("Export {} failed: {}", table, e))?
+                .ok_or_else(|| anyhow!("Server-side {} export is not supported", format.format_name()))?;
+            files.push(NativeExportFile { table: table.clone(), data });
+        }
+        Ok(files)
+    }
+
+    pub(crate) fn build_native_select(plugin: &dyn DatabasePlugin, config: &ExportConfig, table: &str) -> String {
+        let table_ref = plugin.format_table_reference(&config.database, None, table);
+        let mut sql = format!("SELECT * FROM {}", table_ref);
+        if let Some(where_clause) = &config.where_clause {
+            sql.push_str(" WHERE ");
+            sql.push_str(where_clause);
+        }
+        if let Some(limit) = config.limit {
+            sql.push_str(&plugin.format_pagination(limit, 0, ""));
         }
+        sql
     }
 }
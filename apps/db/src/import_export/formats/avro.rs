@@ -0,0 +1,734 @@
+use std::io::{BufRead, Read, Write};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use crate::connection::DbConnection;
+use crate::DatabasePlugin;
+use crate::executor::{ExecOptions, SqlResult};
+use crate::import_export::{BatchInserter, ColumnMapper, ExportConfig, ExportProgressEvent, ExportProgressSender, ExportResult, FormatHandler, ImportConfig, ImportProgressEvent, ImportProgressSender, ImportResult, NativeExportFile, SourceValue, TableChunkReader};
+use crate::import_export::chunked::send_progress;
+use crate::types::ColumnInfo;
+
+const MAGIC: &[u8; 4] = b"Obj\x01";
+/// 每个数据块写入的行数，读取方按块解码，块太大会占用较多内存
+const ROWS_PER_BLOCK: usize = 1000;
+
+/// Avro 对象容器文件的导出和导入。一个文件只有一个 schema，所以每个表导出为单独的文件；
+/// schema 由表的列信息生成，供 Kafka 等按 schema 消费数据的管道使用。
+/// 只写入和读取未压缩（null codec）的文件
+pub struct AvroFormatHandler;
+
+impl AvroFormatHandler {
+    /// 按表导出为 `.avro` 文件内容
+    pub async fn export_files(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+    ) -> Result<Vec<NativeExportFile>> {
+        let mut files = Vec::with_capacity(config.tables.len());
+        for table in &config.tables {
+            // 拿不到列信息时按查询结果的列名生成，所有字段都是可为空的字符串
+            let metadata = plugin
+                .list_columns(connection, &config.database, None, table)
+                .await
+                .unwrap_or_default();
+
+            // 分块读取并编码，内存中只保留当前块的行和编码后的文件内容
+            let mut chunks = TableChunkReader::open(plugin.as_ref(), connection, config, table).await;
+            let mut container: Option<(AvroSchema, Vec<u8>, [u8; 16])> = None;
+            while let Some(chunk) = chunks.next_chunk().await? {
+                let (schema, data, sync) = container.get_or_insert_with(|| {
+                    let schema = AvroSchema::from_columns(table, &chunk.columns, &metadata);
+                    let (data, sync) = write_header(&schema);
+                    (schema, data, sync)
+                });
+                write_blocks(data, schema, &chunk.rows, sync)
+                    .map_err(|e| anyhow!("Export {} failed: {}", table, e))?;
+            }
+            if let Some((_, data, _)) = container {
+                files.push(NativeExportFile { table: table.clone(), data });
+            }
+        }
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl FormatHandler for AvroFormatHandler {
+    /// 按数据块读取 Avro 文件并批量插入目标表，按字段的原列名对应表的列
+    async fn import_from_reader(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ImportConfig,
+        reader: &mut (dyn BufRead + Send),
+        file_name: &str,
+        progress_tx: Option<ImportProgressSender>,
+    ) -> Result<ImportResult> {
+        let start = Instant::now();
+        let mut errors = Vec::new();
+
+        let table = config.table.as_ref()
+            .ok_or_else(|| anyhow!("Table name required for Avro import"))?;
+        let mut container = ContainerReader::open(reader)?;
+        let columns: Vec<String> = container.schema.fields.iter().map(|field| field.column.clone()).collect();
+        let mapper = ColumnMapper::new(plugin.as_ref(), connection, config, table, &columns).await?;
+
+        if config.truncate_before_import {
+            let truncate_sql = format!("TRUNCATE TABLE {}", plugin.quote_identifier(table));
+            let results = connection.execute(plugin.clone(), &truncate_sql, ExecOptions::default()).await
+                .map_err(|e| anyhow!("Truncate failed: {}", e))?;
+
+            for result in results {
+                if let SqlResult::Error(err) = result {
+                    errors.push(format!("Truncate failed: {}", err.message));
+                    if config.stop_on_error {
+                        return Ok(ImportResult {
+                            success: false,
+                            rows_imported: 0,
+                            errors,
+                            elapsed_ms: start.elapsed().as_millis(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut inserter = BatchInserter::new(plugin.clone(), connection, config, table, mapper.target_columns(), file_name, progress_tx.clone());
+        let mut row_number = 0usize;
+        'blocks: loop {
+            let rows = match container.next_block() {
+                Ok(Some(rows)) => rows,
+                Ok(None) => break,
+                Err(e) => {
+                    inserter.skip_row(format!("Row {}: {}", row_number + 1, e));
+                    break;
+                }
+            };
+            for row in rows {
+                row_number += 1;
+                let values: Vec<SourceValue> = row.into_iter().map(AvroValue::into_source_value).collect();
+                match mapper.map_row(plugin.as_ref(), &values) {
+                    Ok(literals) => inserter.push(row_number, &literals).await,
+                    Err(e) => inserter.skip_row(format!("Row {}: {}", row_number, e)),
+                }
+                if inserter.stopped() {
+                    break 'blocks;
+                }
+            }
+        }
+
+        let (rows_imported, insert_errors) = inserter.finish().await;
+        errors.extend(insert_errors);
+        if let Some(tx) = &progress_tx {
+            let _ = tx.send(ImportProgressEvent::FileFinished {
+                file: file_name.to_string(),
+                rows_imported,
+            });
+        }
+
+        Ok(ImportResult {
+            success: errors.is_empty(),
+            rows_imported,
+            errors,
+            elapsed_ms: start.elapsed().as_millis(),
+        })
+    }
+
+    /// 一个文件只有一个 schema，只能写单个表；多个表用 `export_files` 或 zip 包导出
+    async fn export_to_writer(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult> {
+        let start = Instant::now();
+        let [table] = config.tables.as_slice() else {
+            return Err(anyhow!("Avro writes one table per file, export several tables with DataExporter::export_files"));
+        };
+        send_progress(&progress_tx, ExportProgressEvent::TableStart {
+            table: table.clone(),
+            table_index: 0,
+            total_tables: 1,
+        });
+        send_progress(&progress_tx, ExportProgressEvent::FetchingData {
+            table: table.clone(),
+        });
+
+        let metadata = plugin
+            .list_columns(connection, &config.database, None, table)
+            .await
+            .unwrap_or_default();
+        let mut chunks = TableChunkReader::open(plugin.as_ref(), connection, config, table).await;
+        let mut container: Option<(AvroSchema, [u8; 16])> = None;
+        let mut total_rows = 0u64;
+        while let Some(chunk) = chunks.next_chunk().await? {
+            if container.is_none() {
+                let schema = AvroSchema::from_columns(table, &chunk.columns, &metadata);
+                let (header, sync) = write_header(&schema);
+                writer.write_all(&header)?;
+                container = Some((schema, sync));
+            }
+            if let Some((schema, sync)) = &container {
+                // 每块单独编码后写出，内存中只保留当前块
+                let mut data = Vec::new();
+                write_blocks(&mut data, schema, &chunk.rows, sync)
+                    .map_err(|e| anyhow!("Export {} failed: {}", table, e))?;
+                writer.write_all(&data)?;
+            }
+            total_rows += chunk.rows.len() as u64;
+            send_progress(&progress_tx, ExportProgressEvent::DataProgress {
+                table: table.clone(),
+                rows: chunk.rows.len() as u64,
+            });
+        }
+        // 空表也写出只有 schema 的文件，读取方仍能拿到列定义
+        if container.is_none() {
+            let columns: Vec<String> = metadata.iter().map(|column| column.name.clone()).collect();
+            let (header, _) = write_header(&AvroSchema::from_columns(table, &columns, &metadata));
+            writer.write_all(&header)?;
+        }
+        writer.flush()?;
+
+        send_progress(&progress_tx, ExportProgressEvent::DataExported {
+            table: table.clone(),
+            rows: total_rows,
+        });
+        send_progress(&progress_tx, ExportProgressEvent::TableFinished {
+            table: table.clone(),
+        });
+        let elapsed_ms = start.elapsed().as_millis();
+        send_progress(&progress_tx, ExportProgressEvent::Finished {
+            total_rows,
+            elapsed_ms,
+        });
+
+        Ok(ExportResult {
+            success: true,
+            output: String::new(),
+            rows_exported: total_rows,
+            elapsed_ms,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AvroType {
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+}
+
+impl AvroType {
+    fn name(&self) -> &'static str {
+        match self {
+            AvroType::Boolean => "boolean",
+            AvroType::Int => "int",
+            AvroType::Long => "long",
+            AvroType::Float => "float",
+            AvroType::Double => "double",
+            AvroType::Bytes => "bytes",
+            AvroType::String => "string",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "boolean" => Some(AvroType::Boolean),
+            "int" => Some(AvroType::Int),
+            "long" => Some(AvroType::Long),
+            "float" => Some(AvroType::Float),
+            "double" => Some(AvroType::Double),
+            "bytes" => Some(AvroType::Bytes),
+            "string" => Some(AvroType::String),
+            _ => None,
+        }
+    }
+
+    /// 由列的数据库类型选择 Avro 类型；DECIMAL 等精确数值和日期时间写为字符串，避免丢失精度或时区
+    fn from_column_type(data_type: &str) -> Self {
+        let data_type = data_type.to_ascii_lowercase();
+        let base = data_type.split(['(', ' ']).next().unwrap_or_default();
+        match base {
+            "bool" | "boolean" => AvroType::Boolean,
+            // 无符号 BIGINT 可能超出 long 的范围
+            "bigint" | "int8" | "bigserial" if data_type.contains("unsigned") => AvroType::String,
+            "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2" | "int4" | "int8"
+            | "serial" | "smallserial" | "bigserial" | "int16" | "int32" | "int64" | "uint8" | "uint16" | "uint32" => AvroType::Long,
+            "float" | "double" | "real" | "float4" | "float8" | "float32" | "float64" => AvroType::Double,
+            _ => AvroType::String,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AvroField {
+    /// Avro 字段名，只含字母、数字和下划线
+    name: String,
+    /// 对应的数据库列名，写在 schema 的 `column` 属性中
+    column: String,
+    avro_type: AvroType,
+    nullable: bool,
+}
+
+/// 由表的列生成的 Avro record schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvroSchema {
+    name: String,
+    fields: Vec<AvroField>,
+}
+
+impl AvroSchema {
+    /// `columns` 为查询结果的列顺序，`metadata` 中找不到的列按可为空的字符串处理
+    pub fn from_columns(table: &str, columns: &[String], metadata: &[ColumnInfo]) -> Self {
+        let fields = columns
+            .iter()
+            .map(|column| {
+                let info = metadata.iter().find(|info| info.name.eq_ignore_ascii_case(column));
+                AvroField {
+                    name: avro_name(column),
+                    column: column.clone(),
+                    avro_type: info.map(|info| AvroType::from_column_type(&info.data_type)).unwrap_or(AvroType::String),
+                    nullable: info.is_none_or(|info| info.is_nullable),
+                }
+            })
+            .collect();
+        Self { name: avro_name(table), fields }
+    }
+
+    pub fn to_json(&self) -> String {
+        let fields: Vec<Value> = self.fields
+            .iter()
+            .map(|field| {
+                if field.nullable {
+                    json!({ "name": field.name, "type": ["null", field.avro_type.name()], "default": null, "column": field.column })
+                } else {
+                    json!({ "name": field.name, "type": field.avro_type.name(), "column": field.column })
+                }
+            })
+            .collect();
+        json!({ "type": "record", "name": self.name, "namespace": "onehub", "fields": fields }).to_string()
+    }
+
+    /// 只支持由基本类型（或 null 与一个基本类型的联合）组成的扁平 record
+    fn parse(schema: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(schema)?;
+        if value.get("type").and_then(Value::as_str) != Some("record") {
+            return Err(anyhow!("Only record schemas can be imported"));
+        }
+        let name = value.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+        let fields = value.get("fields")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Avro schema has no fields"))?
+            .iter()
+            .map(|field| {
+                let name = field.get("name").and_then(Value::as_str)
+                    .ok_or_else(|| anyhow!("Avro field without a name"))?;
+                let column = field.get("column").and_then(Value::as_str).unwrap_or(name);
+                let (avro_type, nullable) = parse_field_type(field.get("type").unwrap_or(&Value::Null))
+                    .ok_or_else(|| anyhow!("Unsupported type of Avro field {}", name))?;
+                Ok(AvroField { name: name.to_string(), column: column.to_string(), avro_type, nullable })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { name, fields })
+    }
+}
+
+/// 字段类型：基本类型名、`{"type": ...}` 对象（忽略 logicalType），或 null 与一个基本类型的联合
+fn parse_field_type(value: &Value) -> Option<(AvroType, bool)> {
+    match value {
+        Value::String(name) => AvroType::from_name(name).map(|avro_type| (avro_type, false)),
+        Value::Object(object) => object.get("type").and_then(parse_field_type),
+        Value::Array(branches) if branches.len() == 2 => {
+            let null_index = branches.iter().position(|branch| branch.as_str() == Some("null"))?;
+            let (avro_type, _) = parse_field_type(&branches[1 - null_index])?;
+            Some((avro_type, true))
+        }
+        _ => None,
+    }
+}
+
+/// Avro 名称只能由字母、数字和下划线组成且不能以数字开头
+fn avro_name(name: &str) -> String {
+    let mut result: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    result
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AvroValue {
+    Null,
+    Boolean(bool),
+    Long(i64),
+    Double(f64),
+    Text(String),
+}
+
+impl AvroValue {
+    fn into_source_value(self) -> SourceValue {
+        match self {
+            AvroValue::Null => SourceValue::Null,
+            AvroValue::Boolean(value) => SourceValue::Literal(if value { "1" } else { "0" }.to_string()),
+            AvroValue::Long(value) => SourceValue::Literal(value.to_string()),
+            AvroValue::Double(value) if value.is_finite() => SourceValue::Literal(value.to_string()),
+            // NaN 和无穷大没有 SQL 数字字面量，按文本交给数据库转换
+            AvroValue::Double(value) => SourceValue::Text(value.to_string()),
+            AvroValue::Text(value) => SourceValue::Text(value),
+        }
+    }
+}
+
+fn write_long(output: &mut Vec<u8>, value: i64) {
+    let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+    while zigzag >= 0x80 {
+        output.push((zigzag as u8 & 0x7F) | 0x80);
+        zigzag >>= 7;
+    }
+    output.push(zigzag as u8);
+}
+
+fn write_bytes(output: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(output, bytes.len() as i64);
+    output.extend_from_slice(bytes);
+}
+
+/// 查询结果中的值都是文本，按字段类型转换后编码
+fn write_value(output: &mut Vec<u8>, field: &AvroField, value: Option<&str>) -> Result<()> {
+    let Some(value) = value else {
+        if !field.nullable {
+            return Err(anyhow!("Column {} is NULL but not nullable", field.column));
+        }
+        write_long(output, 0);
+        return Ok(());
+    };
+    if field.nullable {
+        write_long(output, 1);
+    }
+    let invalid = || anyhow!("Column {}: '{}' is not a valid {}", field.column, value, field.avro_type.name());
+    match field.avro_type {
+        AvroType::Boolean => {
+            let flag = match value.trim().to_ascii_lowercase().as_str() {
+                "1" | "t" | "true" | "y" | "yes" | "on" => true,
+                "0" | "f" | "false" | "n" | "no" | "off" => false,
+                _ => return Err(invalid()),
+            };
+            output.push(flag as u8);
+        }
+        AvroType::Int | AvroType::Long => write_long(output, value.trim().parse().map_err(|_| invalid())?),
+        AvroType::Float => output.extend_from_slice(&value.trim().parse::<f32>().map_err(|_| invalid())?.to_le_bytes()),
+        AvroType::Double => output.extend_from_slice(&value.trim().parse::<f64>().map_err(|_| invalid())?.to_le_bytes()),
+        AvroType::Bytes | AvroType::String => write_bytes(output, value.as_bytes()),
+    }
+    Ok(())
+}
+
+/// 文件头和用来分隔数据块的 16 字节同步标记
+fn write_header(schema: &AvroSchema) -> (Vec<u8>, [u8; 16]) {
+    let sync = uuid::Uuid::new_v4().into_bytes();
+    let mut output = Vec::new();
+    output.extend_from_slice(MAGIC);
+    write_long(&mut output, 2);
+    write_bytes(&mut output, b"avro.schema");
+    write_bytes(&mut output, schema.to_json().as_bytes());
+    write_bytes(&mut output, b"avro.codec");
+    write_bytes(&mut output, b"null");
+    write_long(&mut output, 0);
+    output.extend_from_slice(&sync);
+    (output, sync)
+}
+
+fn write_blocks(output: &mut Vec<u8>, schema: &AvroSchema, rows: &[Vec<Option<String>>], sync: &[u8; 16]) -> Result<()> {
+    for chunk in rows.chunks(ROWS_PER_BLOCK) {
+        let mut block = Vec::new();
+        for row in chunk {
+            for (i, field) in schema.fields.iter().enumerate() {
+                write_value(&mut block, field, row.get(i).and_then(|value| value.as_deref()))?;
+            }
+        }
+        write_long(output, chunk.len() as i64);
+        write_bytes(output, &block);
+        output.extend_from_slice(sync);
+    }
+    Ok(())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!("Unexpected end of Avro data"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_long(&mut self) -> Result<i64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+            }
+        }
+        Err(anyhow!("Invalid variable-length integer in Avro data"))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8]> {
+        let length = usize::try_from(self.read_long()?).map_err(|_| anyhow!("Negative length in Avro data"))?;
+        self.take(length)
+    }
+
+    fn read_value(&mut self, field: &AvroField, null_first: bool) -> Result<AvroValue> {
+        if field.nullable {
+            let branch = self.read_long()?;
+            if (branch == 0) == null_first {
+                return Ok(AvroValue::Null);
+            }
+        }
+        Ok(match field.avro_type {
+            AvroType::Boolean => AvroValue::Boolean(self.take(1)?[0] != 0),
+            AvroType::Int | AvroType::Long => AvroValue::Long(self.read_long()?),
+            AvroType::Float => {
+                let bytes: [u8; 4] = self.take(4)?.try_into()?;
+                AvroValue::Double(f32::from_le_bytes(bytes) as f64)
+            }
+            AvroType::Double => {
+                let bytes: [u8; 8] = self.take(8)?.try_into()?;
+                AvroValue::Double(f64::from_le_bytes(bytes))
+            }
+            AvroType::Bytes | AvroType::String => AvroValue::Text(String::from_utf8_lossy(self.read_bytes()?).into_owned()),
+        })
+    }
+}
+
+/// 按数据块读取 Avro 容器文件，内存中只保留当前块
+struct ContainerReader<'a> {
+    input: &'a mut (dyn BufRead + Send),
+    schema: AvroSchema,
+    null_first: Vec<bool>,
+    sync: [u8; 16],
+}
+
+impl<'a> ContainerReader<'a> {
+    /// 读取文件头中的 schema 和同步标记
+    fn open(input: &'a mut (dyn BufRead + Send)) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        if input.read_exact(&mut magic).is_err() || &magic != MAGIC {
+            return Err(anyhow!("Not an Avro object container file"));
+        }
+
+        let mut schema_json = None;
+        let mut codec = "null".to_string();
+        loop {
+            let mut count = read_stream_long(input)?;
+            if count == 0 {
+                break;
+            }
+            // 负数表示块后面跟着字节长度
+            if count < 0 {
+                count = -count;
+                read_stream_long(input)?;
+            }
+            for _ in 0..count {
+                let key = String::from_utf8_lossy(&read_stream_bytes(input)?).into_owned();
+                let value = String::from_utf8_lossy(&read_stream_bytes(input)?).into_owned();
+                match key.as_str() {
+                    "avro.schema" => schema_json = Some(value),
+                    "avro.codec" => codec = value,
+                    _ => {}
+                }
+            }
+        }
+        if codec != "null" {
+            return Err(anyhow!("Avro codec '{}' is not supported, only uncompressed files can be imported", codec));
+        }
+        let schema_json = schema_json.ok_or_else(|| anyhow!("Avro file has no schema"))?;
+        let schema = AvroSchema::parse(&schema_json)?;
+        let null_first = null_branch_first(&schema_json, &schema);
+        let mut sync = [0u8; 16];
+        input.read_exact(&mut sync).map_err(|_| anyhow!("Unexpected end of Avro data"))?;
+
+        Ok(Self { input, schema, null_first, sync })
+    }
+
+    /// 读到文件末尾时返回 None
+    fn next_block(&mut self) -> Result<Option<Vec<Vec<AvroValue>>>> {
+        if self.input.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        let count = read_stream_long(self.input)?;
+        let block = read_stream_bytes(self.input)?;
+        let mut block_reader = Reader { data: &block, position: 0 };
+        let mut rows = Vec::new();
+        for _ in 0..count {
+            let row = self.schema.fields
+                .iter()
+                .zip(&self.null_first)
+                .map(|(field, null_first)| block_reader.read_value(field, *null_first))
+                .collect::<Result<Vec<_>>>()?;
+            rows.push(row);
+        }
+        let mut sync = [0u8; 16];
+        self.input.read_exact(&mut sync).map_err(|_| anyhow!("Unexpected end of Avro data"))?;
+        if sync != self.sync {
+            return Err(anyhow!("Avro block sync marker mismatch, the file is corrupted"));
+        }
+        Ok(Some(rows))
+    }
+}
+
+fn read_stream_long(input: &mut (dyn BufRead + Send)) -> Result<i64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8; 1];
+        input.read_exact(&mut byte).map_err(|_| anyhow!("Unexpected end of Avro data"))?;
+        value |= ((byte[0] & 0x7F) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+    Err(anyhow!("Invalid variable-length integer in Avro data"))
+}
+
+fn read_stream_bytes(input: &mut (dyn BufRead + Send)) -> Result<Vec<u8>> {
+    let length = u64::try_from(read_stream_long(input)?).map_err(|_| anyhow!("Negative length in Avro data"))?;
+    let mut bytes = Vec::new();
+    Read::take(&mut *input, length).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != length {
+        return Err(anyhow!("Unexpected end of Avro data"));
+    }
+    Ok(bytes)
+}
+
+/// 联合类型中 null 是否为第一个分支，决定编码下标 0 表示 null 还是值
+fn null_branch_first(schema_json: &str, schema: &AvroSchema) -> Vec<bool> {
+    let value: Value = serde_json::from_str(schema_json).unwrap_or(Value::Null);
+    let fields = value.get("fields").and_then(Value::as_array).cloned().unwrap_or_default();
+    schema.fields
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            fields.get(i)
+                .and_then(|field| field.get("type"))
+                .and_then(Value::as_array)
+                .and_then(|branches| branches.first())
+                .is_none_or(|first| first.as_str() == Some("null"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, is_nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable,
+            is_primary_key: false,
+            default_value: None,
+            comment: None,
+            collation: None,
+        }
+    }
+
+    #[test]
+    fn test_schema_from_columns() {
+        let columns = vec!["id".to_string(), "unit price".to_string(), "active".to_string(), "extra".to_string()];
+        let metadata = vec![
+            column("id", "bigint(20)", false),
+            column("unit price", "DECIMAL(10,2)", true),
+            column("active", "boolean", true),
+        ];
+        let schema = AvroSchema::from_columns("order-items", &columns, &metadata);
+        let value: Value = serde_json::from_str(&schema.to_json()).unwrap();
+        assert_eq!(value["name"], "order_items");
+        assert_eq!(value["fields"][0], json!({ "name": "id", "type": "long", "column": "id" }));
+        assert_eq!(value["fields"][1]["name"], "unit_price");
+        assert_eq!(value["fields"][1]["column"], "unit price");
+        assert_eq!(value["fields"][1]["type"], json!(["null", "string"]));
+        assert_eq!(value["fields"][2]["type"], json!(["null", "boolean"]));
+        assert_eq!(value["fields"][3]["type"], json!(["null", "string"]));
+        assert_eq!(AvroSchema::parse(&schema.to_json()).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_long_encoding() {
+        for (value, encoded) in [(0i64, vec![0u8]), (-1, vec![1]), (1, vec![2]), (-64, vec![127]), (64, vec![128, 1])] {
+            let mut output = Vec::new();
+            write_long(&mut output, value);
+            assert_eq!(output, encoded);
+            assert_eq!(Reader { data: &output, position: 0 }.read_long().unwrap(), value);
+        }
+        let mut output = Vec::new();
+        write_long(&mut output, i64::MIN);
+        assert_eq!(Reader { data: &output, position: 0 }.read_long().unwrap(), i64::MIN);
+    }
+
+    fn write_container(schema: &AvroSchema, rows: &[Vec<Option<String>>]) -> Result<Vec<u8>> {
+        let (mut output, sync) = write_header(schema);
+        write_blocks(&mut output, schema, rows, &sync)?;
+        Ok(output)
+    }
+
+    fn read_container(data: &[u8]) -> Result<(AvroSchema, Vec<Vec<AvroValue>>)> {
+        let mut input = data;
+        let mut container = ContainerReader::open(&mut input)?;
+        let mut rows = Vec::new();
+        while let Some(block) = container.next_block()? {
+            rows.extend(block);
+        }
+        Ok((container.schema, rows))
+    }
+
+    #[test]
+    fn test_container_round_trip() {
+        let columns = vec!["id".to_string(), "score".to_string(), "name".to_string()];
+        let metadata = vec![column("id", "int", false), column("score", "double", true), column("name", "varchar(20)", true)];
+        let schema = AvroSchema::from_columns("users", &columns, &metadata);
+        let rows: Vec<Vec<Option<String>>> = (0..ROWS_PER_BLOCK + 1)
+            .map(|i| vec![Some(i.to_string()), None, Some(format!("it's {}", i))])
+            .collect();
+
+        let data = write_container(&schema, &rows).unwrap();
+        let (parsed, values) = read_container(&data).unwrap();
+        assert_eq!(parsed, schema);
+        assert_eq!(values.len(), ROWS_PER_BLOCK + 1);
+        assert_eq!(values[1], vec![AvroValue::Long(1), AvroValue::Null, AvroValue::Text("it's 1".to_string())]);
+        assert_eq!(
+            values[1].iter().cloned().map(AvroValue::into_source_value).collect::<Vec<_>>(),
+            vec![SourceValue::Literal("1".to_string()), SourceValue::Null, SourceValue::Text("it's 1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        let schema = AvroSchema::from_columns("t", &["id".to_string()], &[column("id", "int", false)]);
+        assert!(write_container(&schema, &[vec![Some("abc".to_string())]]).is_err());
+        assert!(write_container(&schema, &[vec![None]]).is_err());
+        assert!(read_container(b"PAR1").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use std::io::{BufRead, Write};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -7,12 +8,13 @@ use async_trait::async_trait;
 use crate::connection::DbConnection;
 use crate::DatabasePlugin;
 use crate::executor::{ExecOptions, SqlResult};
-use crate::import_export::{ExportConfig, ExportResult, FormatHandler, ImportConfig, ImportResult};
+use crate::import_export::{BatchInserter, ColumnMapper, CsvExportConfig, CsvQuotePolicy, ExportConfig, ExportProgressEvent, ExportProgressSender, ExportResult, FormatHandler, ImportConfig, ImportProgressEvent, ImportProgressSender, ImportResult, LineReader, SourceValue, TableChunkReader, TranscodingReader, TranscodingWriter};
+use crate::import_export::chunked::send_progress;
 
 pub struct CsvFormatHandler;
 
 impl CsvFormatHandler {
-    fn parse_csv_line_with_config(line: &str, delimiter: char, qualifier: Option<char>) -> Vec<String> {
+    pub(crate) fn parse_csv_line_with_config(line: &str, delimiter: char, qualifier: Option<char>) -> Vec<String> {
         let mut fields = Vec::new();
         let mut current_field = String::new();
         let mut in_quotes = false;
@@ -46,27 +48,62 @@ impl CsvFormatHandler {
         fields
     }
 
-    fn escape_csv_field(field: &str) -> String {
-        if field.contains(',') || field.contains('"') || field.contains('\n') {
-            format!("\"{}\"", field.replace('"', "\"\""))
+    /// 按导出配置写出一个字段，识别符在字段内按 CSV 惯例重复一次转义
+    fn format_field(field: &str, config: &CsvExportConfig) -> String {
+        let qualifier = config.text_qualifier;
+        let quote = match config.quote_policy {
+            CsvQuotePolicy::Needed => {
+                field.contains(config.field_delimiter)
+                    || field.contains(qualifier)
+                    || field.contains('\n')
+                    || field.contains('\r')
+            }
+            CsvQuotePolicy::Always => true,
+            CsvQuotePolicy::NonNumeric => !field.parse::<f64>().is_ok_and(|number| number.is_finite()),
+            CsvQuotePolicy::Never => false,
+        };
+        if quote {
+            let escaped = field.replace(qualifier, &format!("{}{}", qualifier, qualifier));
+            format!("{}{}{}", qualifier, escaped, qualifier)
         } else {
             field.to_string()
         }
     }
+
+    /// 写出表头和数据行；NULL 写为空字段，不加引号
+    pub(crate) fn write_header(output: &mut String, columns: &[String], config: &CsvExportConfig) {
+        let delimiter = config.field_delimiter.to_string();
+        let header: Vec<String> = columns.iter().map(|col| Self::format_field(col, config)).collect();
+        output.push_str(&header.join(&delimiter));
+        output.push_str(&config.record_terminator);
+    }
+
+    pub(crate) fn write_rows(output: &mut String, rows: &[Vec<Option<String>>], config: &CsvExportConfig) {
+        let delimiter = config.field_delimiter.to_string();
+        for row in rows {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|val| val.as_deref().map(|v| Self::format_field(v, config)).unwrap_or_default())
+                .collect();
+            output.push_str(&fields.join(&delimiter));
+            output.push_str(&config.record_terminator);
+        }
+    }
 }
 
 #[async_trait]
 impl FormatHandler for CsvFormatHandler {
-    async fn import(
+    async fn import_from_reader(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ImportConfig,
-        data: &str,
+        reader: &mut (dyn BufRead + Send),
+        file_name: &str,
+        progress_tx: Option<ImportProgressSender>,
     ) -> Result<ImportResult> {
         let start = Instant::now();
         let mut errors = Vec::new();
-        let mut total_rows = 0u64;
 
         let table = config.table.as_ref()
             .ok_or_else(|| anyhow!("Table name required for CSV import"))?;
@@ -76,33 +113,38 @@ impl FormatHandler for CsvFormatHandler {
         let qualifier = csv_config.text_qualifier;
         let has_header = csv_config.has_header;
 
-        let lines: Vec<&str> = data.lines().collect();
-        if lines.is_empty() {
+        // 其他编码边读边转成 UTF-8
+        let mut reader = TranscodingReader::new(reader, config.encoding);
+        let mut lines = LineReader::new(&mut reader);
+        let Some((_, first_line)) = lines.next_line()? else {
             return Ok(ImportResult {
                 success: true,
                 rows_imported: 0,
                 errors,
                 elapsed_ms: start.elapsed().as_millis(),
             });
-        }
+        };
+        // 导出时可以选择写入 BOM，读回来时去掉，否则会成为第一个列名的一部分
+        let first_line = first_line.strip_prefix('\u{FEFF}').unwrap_or(&first_line).to_string();
 
         let columns: Vec<String>;
-        let data_start_line: usize;
+        // 没有表头时第一行也是数据
+        let mut first_data_line = None;
 
         if has_header {
-            columns = Self::parse_csv_line_with_config(lines[0], delimiter, qualifier);
-            data_start_line = 1;
+            columns = Self::parse_csv_line_with_config(&first_line, delimiter, qualifier);
         } else {
-            let first_row = Self::parse_csv_line_with_config(lines[0], delimiter, qualifier);
+            let first_row = Self::parse_csv_line_with_config(&first_line, delimiter, qualifier);
             columns = (0..first_row.len())
                 .map(|i| format!("col{}", i + 1))
                 .collect();
-            data_start_line = 0;
+            first_data_line = Some((1, first_line));
         }
 
         if columns.is_empty() {
             return Err(anyhow!("CSV header is empty"));
         }
+        let mapper = ColumnMapper::new(plugin.as_ref(), connection, config, table, &columns).await?;
 
         if config.truncate_before_import {
             let truncate_sql = format!("TRUNCATE TABLE {}", plugin.quote_identifier(table));
@@ -124,137 +166,181 @@ impl FormatHandler for CsvFormatHandler {
             }
         }
 
-        for (line_num, line) in lines.iter().skip(data_start_line).enumerate() {
+        let mut inserter = BatchInserter::new(plugin.clone(), connection, config, table, mapper.target_columns(), file_name, progress_tx.clone());
+        loop {
+            let (line_number, line) = match first_data_line.take() {
+                Some(first) => first,
+                None => match lines.next_line()? {
+                    Some(next) => next,
+                    None => break,
+                },
+            };
             if line.trim().is_empty() {
                 continue;
             }
 
-            let values = Self::parse_csv_line_with_config(line, delimiter, qualifier);
+            let values = Self::parse_csv_line_with_config(&line, delimiter, qualifier);
             if values.len() != columns.len() {
-                errors.push(format!("Line {}: column count mismatch", line_num + data_start_line + 1));
-                if config.stop_on_error {
-                    break;
+                inserter.skip_row(format!("Line {}: column count mismatch", line_number));
+            } else {
+                let values: Vec<SourceValue> = values
+                    .into_iter()
+                    .map(|val| {
+                        if val.is_empty() || val.eq_ignore_ascii_case("null") {
+                            SourceValue::Null
+                        } else {
+                            SourceValue::Text(val)
+                        }
+                    })
+                    .collect();
+                match mapper.map_row(plugin.as_ref(), &values) {
+                    Ok(literals) => inserter.push(line_number, &literals).await,
+                    Err(e) => inserter.skip_row(format!("Line {}: {}", line_number, e)),
                 }
-                continue;
             }
-
-            let mut insert_sql = format!("INSERT INTO {} (", plugin.quote_identifier(table));
-            for (i, col) in columns.iter().enumerate() {
-                if i > 0 {
-                    insert_sql.push_str(", ");
-                }
-                insert_sql.push_str(&plugin.quote_identifier(col));
+            if inserter.stopped() {
+                break;
             }
-            insert_sql.push_str(") VALUES (");
+        }
 
-            for (i, val) in values.iter().enumerate() {
-                if i > 0 {
-                    insert_sql.push_str(", ");
-                }
-                if val.is_empty() || val.eq_ignore_ascii_case("null") {
-                    insert_sql.push_str("NULL");
-                } else {
-                    insert_sql.push('\'');
-                    insert_sql.push_str(&val.replace('\'', "''"));
-                    insert_sql.push('\'');
-                }
-            }
-            insert_sql.push(')');
-
-            match connection.execute(plugin.clone(), &insert_sql, ExecOptions::default()).await {
-                Ok(results) => {
-                    for result in results {
-                        match result {
-                            SqlResult::Exec(exec_result) => {
-                                total_rows += exec_result.rows_affected;
-                            }
-                            SqlResult::Error(err) => {
-                                errors.push(format!("Line {}: {}", line_num + data_start_line + 1, err.message));
-                                if config.stop_on_error {
-                                    break;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-                Err(e) => {
-                    errors.push(format!("Line {}: {}", line_num + data_start_line + 1, e));
-                    if config.stop_on_error {
-                        break;
-                    }
-                }
-            }
+        let (rows_imported, insert_errors) = inserter.finish().await;
+        errors.extend(insert_errors);
+        if let Some(tx) = &progress_tx {
+            let _ = tx.send(ImportProgressEvent::FileFinished {
+                file: file_name.to_string(),
+                rows_imported,
+            });
         }
 
         Ok(ImportResult {
             success: errors.is_empty(),
-            rows_imported: total_rows,
+            rows_imported,
             errors,
             elapsed_ms: start.elapsed().as_millis(),
         })
     }
 
-    async fn export(
+    async fn export_to_writer(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
+        progress_tx: Option<ExportProgressSender>,
     ) -> Result<ExportResult> {
         let start = Instant::now();
-        let mut output = String::new();
+        // 数据库返回的文本是 UTF-8，写出时转成目标编码
+        let mut writer = TranscodingWriter::new(writer, config.encoding);
+        let csv_config = config.csv_config.clone().unwrap_or_default();
+        if csv_config.include_bom && writer.is_utf8() {
+            writer.write_all("\u{FEFF}".as_bytes())?;
+        }
         let mut total_rows = 0u64;
+        let total_tables = config.tables.len();
 
         for (table_idx, table) in config.tables.iter().enumerate() {
-            let table_ref = plugin.format_table_reference(&config.database, None, table);
-            let mut select_sql = format!("SELECT * FROM {}", table_ref);
-            if let Some(where_clause) = &config.where_clause {
-                select_sql.push_str(" WHERE ");
-                select_sql.push_str(where_clause);
-            }
-            if let Some(limit) = config.limit {
-                let pagination = plugin.format_pagination(limit, 0, "");
-                select_sql.push_str(&pagination);
-            }
-
-            let result = connection.query(&select_sql, None, ExecOptions::default()).await
-                .map_err(|e| anyhow!("Query failed: {}", e))?;
-
-            if let SqlResult::Query(query_result) = result {
-                if table_idx > 0 {
-                    output.push_str("\n\n");
-                }
-
-                // 写入表头
-                for (i, col) in query_result.columns.iter().enumerate() {
-                    if i > 0 {
-                        output.push(',');
-                    }
-                    output.push_str(&Self::escape_csv_field(col));
-                }
-                output.push('\n');
+            send_progress(&progress_tx, ExportProgressEvent::TableStart {
+                table: table.clone(),
+                table_index: table_idx,
+                total_tables,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::FetchingData {
+                table: table.clone(),
+            });
 
-                // 写入数据
-                for row in &query_result.rows {
-                    for (i, val) in row.iter().enumerate() {
-                        if i > 0 {
-                            output.push(',');
-                        }
-                        if let Some(v) = val {
-                            output.push_str(&Self::escape_csv_field(v));
-                        }
+            let mut table_rows = 0u64;
+            let mut chunks = TableChunkReader::open(plugin.as_ref(), connection, config, table).await;
+            let mut first_chunk = true;
+            while let Some(chunk) = chunks.next_chunk().await? {
+                let mut output = String::new();
+                if first_chunk {
+                    if table_idx > 0 {
+                        output.push_str(&csv_config.record_terminator);
                     }
-                    output.push('\n');
-                    total_rows += 1;
+                    Self::write_header(&mut output, &chunk.columns, &csv_config);
+                    first_chunk = false;
                 }
+                Self::write_rows(&mut output, &chunk.rows, &csv_config);
+                writer.write_all(output.as_bytes())?;
+                table_rows += chunk.rows.len() as u64;
+                send_progress(&progress_tx, ExportProgressEvent::DataProgress {
+                    table: table.clone(),
+                    rows: chunk.rows.len() as u64,
+                });
             }
+            total_rows += table_rows;
+
+            send_progress(&progress_tx, ExportProgressEvent::DataExported {
+                table: table.clone(),
+                rows: table_rows,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::TableFinished {
+                table: table.clone(),
+            });
         }
+        writer.flush()?;
+
+        let elapsed_ms = start.elapsed().as_millis();
+        send_progress(&progress_tx, ExportProgressEvent::Finished {
+            total_rows,
+            elapsed_ms,
+        });
 
         Ok(ExportResult {
             success: true,
-            output,
+            output: String::new(),
             rows_exported: total_rows,
-            elapsed_ms: start.elapsed().as_millis(),
+            elapsed_ms,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(config: &CsvExportConfig) -> String {
+        let columns = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            vec![Some("1".to_string()), Some("a;b".to_string())],
+            vec![Some("2".to_string()), None],
+        ];
+        let mut output = String::new();
+        CsvFormatHandler::write_header(&mut output, &columns, config);
+        CsvFormatHandler::write_rows(&mut output, &rows, config);
+        output
+    }
+
+    #[test]
+    fn test_default_export_quotes_only_when_needed() {
+        assert_eq!(render(&CsvExportConfig::default()), "id,note\n1,a;b\n2,\n");
+    }
+
+    #[test]
+    fn test_semicolon_delimiter_with_crlf() {
+        let config = CsvExportConfig {
+            field_delimiter: ';',
+            record_terminator: "\r\n".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(render(&config), "id;note\r\n1;\"a;b\"\r\n2;\r\n");
+    }
+
+    #[test]
+    fn test_quote_policies() {
+        let always = CsvExportConfig { field_delimiter: '\t', quote_policy: CsvQuotePolicy::Always, ..Default::default() };
+        assert_eq!(render(&always), "\"id\"\t\"note\"\n\"1\"\t\"a;b\"\n\"2\"\t\n");
+
+        let non_numeric = CsvExportConfig { quote_policy: CsvQuotePolicy::NonNumeric, text_qualifier: '\'', ..Default::default() };
+        assert_eq!(render(&non_numeric), "'id','note'\n1,'a;b'\n2,\n");
+
+        let never = CsvExportConfig { field_delimiter: ';', quote_policy: CsvQuotePolicy::Never, ..Default::default() };
+        assert_eq!(render(&never), "id;note\n1;a;b\n2;\n");
+    }
+
+    #[test]
+    fn test_qualifier_inside_field_is_doubled() {
+        let config = CsvExportConfig::default();
+        assert_eq!(CsvFormatHandler::format_field("say \"hi\"", &config), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -1,3 +1,4 @@
+use std::io::{BufRead, Write};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -8,42 +9,61 @@ use serde_json::Value;
 use crate::connection::DbConnection;
 use crate::DatabasePlugin;
 use crate::executor::{ExecOptions, SqlResult};
-use crate::import_export::{ExportConfig, ExportResult, FormatHandler, ImportConfig, ImportResult};
+use crate::import_export::{BatchInserter, ColumnMapper, ExportConfig, ExportProgressEvent, ExportProgressSender, ExportResult, FormatHandler, ImportConfig, ImportProgressEvent, ImportProgressSender, ImportResult, SourceValue, TableChunkReader, TranscodingReader, TranscodingWriter};
+use crate::import_export::chunked::send_progress;
 
 pub struct JsonFormatHandler;
 
+impl JsonFormatHandler {
+    /// 一行数据对应的 JSON 对象，NULL 写为 null，其它值都是字符串
+    pub(crate) fn row_to_json(columns: &[String], row: &[Option<String>]) -> Value {
+        let mut obj = serde_json::Map::new();
+        for (i, col_name) in columns.iter().enumerate() {
+            let value = match row.get(i) {
+                Some(Some(v)) => Value::String(v.clone()),
+                _ => Value::Null,
+            };
+            obj.insert(col_name.clone(), value);
+        }
+        Value::Object(obj)
+    }
+}
+
 #[async_trait]
 impl FormatHandler for JsonFormatHandler {
-    async fn import(
+    async fn import_from_reader(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ImportConfig,
-        data: &str,
+        reader: &mut (dyn BufRead + Send),
+        file_name: &str,
+        progress_tx: Option<ImportProgressSender>,
     ) -> Result<ImportResult> {
         let start = Instant::now();
         let mut errors = Vec::new();
-        let mut total_rows = 0u64;
 
         let table = config.table.as_ref()
             .ok_or_else(|| anyhow!("Table name required for JSON import"))?;
 
-        // 解析JSON
-        let json_value: Value = serde_json::from_str(data)?;
-        let rows = match json_value {
-            Value::Array(arr) => arr,
-            Value::Object(_) => vec![json_value],
-            _ => return Err(anyhow!("JSON must be array or object")),
-        };
-
-        if rows.is_empty() {
+        // 其他编码边读边转成 UTF-8
+        let mut reader = TranscodingReader::new(reader, config.encoding);
+        // 逐个读取数组元素（或 JSON Lines 的每一行），不把整个文件解析成一个 Value
+        let mut elements = JsonElementReader::new(&mut reader);
+        let Some(first_row) = elements.next_element()? else {
             return Ok(ImportResult {
                 success: true,
                 rows_imported: 0,
                 errors,
                 elapsed_ms: start.elapsed().as_millis(),
             });
-        }
+        };
+
+        // 获取第一行的字段
+        let first_obj = first_row.as_object()
+            .ok_or_else(|| anyhow!("JSON array must contain objects"))?;
+        let columns: Vec<String> = first_obj.keys().cloned().collect();
+        let mapper = ColumnMapper::new(plugin.as_ref(), connection, config, table, &columns).await?;
 
         // TRUNCATE表
         if config.truncate_before_import {
@@ -66,137 +86,306 @@ impl FormatHandler for JsonFormatHandler {
             }
         }
 
-        // 获取第一行的字段
-        let first_obj = rows[0].as_object()
-            .ok_or_else(|| anyhow!("JSON array must contain objects"))?;
-        let columns: Vec<String> = first_obj.keys().cloned().collect();
-
         // 批量插入
-        for row_obj in rows {
-            let obj = match row_obj.as_object() {
-                Some(o) => o,
-                None => {
-                    errors.push("Row is not an object".to_string());
-                    if config.stop_on_error {
-                        break;
+        let mut inserter = BatchInserter::new(plugin.clone(), connection, config, table, mapper.target_columns(), file_name, progress_tx.clone());
+        let mut next_row = Some(first_row);
+        let mut row_number = 0usize;
+        while let Some(row_obj) = next_row {
+            row_number += 1;
+            match row_obj.as_object() {
+                Some(obj) => {
+                    let values: Vec<SourceValue> = columns
+                        .iter()
+                        .map(|col| match obj.get(col) {
+                            Some(Value::Null) | None => SourceValue::Null,
+                            Some(Value::String(s)) => SourceValue::Text(s.clone()),
+                            Some(Value::Number(n)) => SourceValue::Literal(n.to_string()),
+                            Some(Value::Bool(b)) => SourceValue::Literal(if *b { "1" } else { "0" }.to_string()),
+                            Some(v) => SourceValue::Text(v.to_string()),
+                        })
+                        .collect();
+                    match mapper.map_row(plugin.as_ref(), &values) {
+                        Ok(literals) => inserter.push(row_number, &literals).await,
+                        Err(e) => inserter.skip_row(format!("Row {}: {}", row_number, e)),
                     }
-                    continue;
-                }
-            };
-
-            let mut insert_sql = format!("INSERT INTO {} (", plugin.quote_identifier(table));
-            for (i, col) in columns.iter().enumerate() {
-                if i > 0 {
-                    insert_sql.push_str(", ");
                 }
-                insert_sql.push_str(&plugin.quote_identifier(col));
+                None => inserter.skip_row(format!("Row {}: not an object", row_number)),
             }
-            insert_sql.push_str(") VALUES (");
-
-            for (i, col) in columns.iter().enumerate() {
-                if i > 0 {
-                    insert_sql.push_str(", ");
-                }
-                match obj.get(col) {
-                    Some(Value::Null) | None => insert_sql.push_str("NULL"),
-                    Some(Value::String(s)) => {
-                        insert_sql.push('\'');
-                        insert_sql.push_str(&s.replace('\'', "''"));
-                        insert_sql.push('\'');
-                    }
-                    Some(Value::Number(n)) => insert_sql.push_str(&n.to_string()),
-                    Some(Value::Bool(b)) => insert_sql.push_str(if *b { "1" } else { "0" }),
-                    Some(v) => {
-                        insert_sql.push('\'');
-                        insert_sql.push_str(&v.to_string().replace('\'', "''"));
-                        insert_sql.push('\'');
-                    }
-                }
+            if inserter.stopped() {
+                break;
             }
-            insert_sql.push(')');
-
-            match connection.execute(plugin.clone(), &insert_sql, ExecOptions::default()).await {
-                Ok(results) => {
-                    for result in results {
-                        match result {
-                            SqlResult::Exec(exec_result) => {
-                                total_rows += exec_result.rows_affected;
-                            }
-                            SqlResult::Error(err) => {
-                                errors.push(format!("Insert failed: {}", err.message));
-                                if config.stop_on_error {
-                                    break;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
+            next_row = match elements.next_element() {
+                Ok(row) => row,
                 Err(e) => {
-                    errors.push(format!("Insert failed: {}", e));
-                    if config.stop_on_error {
-                        break;
-                    }
+                    inserter.skip_row(format!("Row {}: {}", row_number + 1, e));
+                    None
                 }
-            }
+            };
+        }
+
+        let (rows_imported, insert_errors) = inserter.finish().await;
+        errors.extend(insert_errors);
+        if let Some(tx) = &progress_tx {
+            let _ = tx.send(ImportProgressEvent::FileFinished {
+                file: file_name.to_string(),
+                rows_imported,
+            });
         }
 
         Ok(ImportResult {
             success: errors.is_empty(),
-            rows_imported: total_rows,
+            rows_imported,
             errors,
             elapsed_ms: start.elapsed().as_millis(),
         })
     }
 
-    async fn export(
+    async fn export_to_writer(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
+        progress_tx: Option<ExportProgressSender>,
     ) -> Result<ExportResult> {
         let start = Instant::now();
-        let mut all_data = Vec::new();
+        // 数据库返回的文本是 UTF-8，写出时转成目标编码
+        let mut writer = TranscodingWriter::new(writer, config.encoding);
         let mut total_rows = 0u64;
+        let total_tables = config.tables.len();
 
-        for table in &config.tables {
-            let table_ref = plugin.format_table_reference(&config.database, None, table);
-            let mut select_sql = format!("SELECT * FROM {}", table_ref);
-            if let Some(where_clause) = &config.where_clause {
-                select_sql.push_str(" WHERE ");
-                select_sql.push_str(where_clause);
-            }
-            if let Some(limit) = config.limit {
-                let pagination = plugin.format_pagination(limit, 0, "");
-                select_sql.push_str(&pagination);
-            }
+        // 所有表的行写在同一个数组里，和一次性 to_string_pretty 的输出相同
+        writer.write_all(b"[")?;
+        for (index, table) in config.tables.iter().enumerate() {
+            send_progress(&progress_tx, ExportProgressEvent::TableStart {
+                table: table.clone(),
+                table_index: index,
+                total_tables,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::FetchingData {
+                table: table.clone(),
+            });
 
-            let result = connection.query(&select_sql, None, ExecOptions::default()).await
-                .map_err(|e| anyhow!("Query failed: {}", e))?;
-
-            if let SqlResult::Query(query_result) = result {
-                for row in &query_result.rows {
-                    let mut obj = serde_json::Map::new();
-                    for (i, col_name) in query_result.columns.iter().enumerate() {
-                        let value = match &row[i] {
-                            Some(v) => Value::String(v.clone()),
-                            None => Value::Null,
-                        };
-                        obj.insert(col_name.clone(), value);
+            let mut table_rows = 0u64;
+            let mut chunks = TableChunkReader::open(plugin.as_ref(), connection, config, table).await;
+            while let Some(chunk) = chunks.next_chunk().await? {
+                for row in &chunk.rows {
+                    writer.write_all(if total_rows == 0 { "\n" } else { ",\n" }.as_bytes())?;
+                    let pretty = serde_json::to_string_pretty(&Self::row_to_json(&chunk.columns, row))?;
+                    for (line_index, line) in pretty.lines().enumerate() {
+                        if line_index > 0 {
+                            writer.write_all(b"\n")?;
+                        }
+                        write!(writer, "  {}", line)?;
                     }
-                    all_data.push(Value::Object(obj));
                     total_rows += 1;
                 }
+                table_rows += chunk.rows.len() as u64;
+                send_progress(&progress_tx, ExportProgressEvent::DataProgress {
+                    table: table.clone(),
+                    rows: chunk.rows.len() as u64,
+                });
             }
+
+            send_progress(&progress_tx, ExportProgressEvent::DataExported {
+                table: table.clone(),
+                rows: table_rows,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::TableFinished {
+                table: table.clone(),
+            });
         }
+        writer.write_all(if total_rows == 0 { "]" } else { "\n]" }.as_bytes())?;
+        writer.flush()?;
 
-        let output = serde_json::to_string_pretty(&all_data)?;
+        let elapsed_ms = start.elapsed().as_millis();
+        send_progress(&progress_tx, ExportProgressEvent::Finished {
+            total_rows,
+            elapsed_ms,
+        });
 
         Ok(ExportResult {
             success: true,
-            output,
+            output: String::new(),
             rows_exported: total_rows,
-            elapsed_ms: start.elapsed().as_millis(),
+            elapsed_ms,
         })
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JsonReaderState {
+    Start,
+    /// 在顶层数组中，下一个字符应是 `,` 或 `]`
+    InArray,
+    /// 顶层是一个或多个连续的值（单个对象或 JSON Lines）
+    Sequence,
+    Done,
+}
+
+/// 从顶层数组或连续的 JSON 值中逐个读取元素
+struct JsonElementReader<'a> {
+    reader: &'a mut (dyn BufRead + Send),
+    state: JsonReaderState,
+}
+
+impl<'a> JsonElementReader<'a> {
+    fn new(reader: &'a mut (dyn BufRead + Send)) -> Self {
+        Self { reader, state: JsonReaderState::Start }
+    }
+
+    fn next_element(&mut self) -> Result<Option<Value>> {
+        match self.state {
+            JsonReaderState::Done => Ok(None),
+            JsonReaderState::Start => {
+                self.skip_bom()?;
+                match self.skip_whitespace()? {
+                    None => {
+                        self.state = JsonReaderState::Done;
+                        Ok(None)
+                    }
+                    Some(b'[') => {
+                        self.reader.consume(1);
+                        if self.skip_whitespace()? == Some(b']') {
+                            self.reader.consume(1);
+                            self.state = JsonReaderState::Done;
+                            return Ok(None);
+                        }
+                        self.state = JsonReaderState::InArray;
+                        self.read_value().map(Some)
+                    }
+                    Some(_) => {
+                        self.state = JsonReaderState::Sequence;
+                        self.read_value().map(Some)
+                    }
+                }
+            }
+            JsonReaderState::InArray => match self.skip_whitespace()? {
+                Some(b',') => {
+                    self.reader.consume(1);
+                    self.skip_whitespace()?;
+                    self.read_value().map(Some)
+                }
+                Some(b']') => {
+                    self.reader.consume(1);
+                    self.state = JsonReaderState::Done;
+                    Ok(None)
+                }
+                _ => Err(anyhow!("Expected ',' or ']' in JSON array")),
+            },
+            JsonReaderState::Sequence => match self.skip_whitespace()? {
+                None => {
+                    self.state = JsonReaderState::Done;
+                    Ok(None)
+                }
+                Some(_) => self.read_value().map(Some),
+            },
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<Option<u8>> {
+        while let Some(byte) = self.peek()? {
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+            self.reader.consume(1);
+        }
+        Ok(None)
+    }
+
+    fn skip_bom(&mut self) -> Result<()> {
+        if self.reader.fill_buf()?.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            self.reader.consume(3);
+        }
+        Ok(())
+    }
+
+    /// 读取一个完整的值：对象和数组读到括号配平，字符串读到结束引号，其余读到分隔符
+    fn read_value(&mut self) -> Result<Value> {
+        let mut raw = Vec::new();
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        while let Some(byte) = self.peek()? {
+            if in_string {
+                raw.push(byte);
+                self.reader.consume(1);
+                if escaped {
+                    escaped = false;
+                } else if byte == b'\\' {
+                    escaped = true;
+                } else if byte == b'"' {
+                    in_string = false;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                continue;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth == 0 => break,
+                b'}' | b']' => depth -= 1,
+                b',' if depth == 0 => break,
+                byte if depth == 0 && byte.is_ascii_whitespace() => break,
+                _ => {}
+            }
+            raw.push(byte);
+            self.reader.consume(1);
+            if depth == 0 && matches!(byte, b'}' | b']') {
+                break;
+            }
+        }
+        if in_string || depth > 0 {
+            return Err(anyhow!("Unexpected end of JSON data"));
+        }
+        Ok(serde_json::from_slice(&raw)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(data: &str) -> Result<Vec<Value>> {
+        let mut bytes = data.as_bytes();
+        let mut reader = JsonElementReader::new(&mut bytes);
+        let mut values = Vec::new();
+        while let Some(value) = reader.next_element()? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    #[test]
+    fn test_read_array_elements() {
+        let values = read_all("\u{FEFF}[\n  {\"a\": \"x, ]}\\\"\", \"b\": [1, {\"c\": null}]},\n  {\"a\": 2}\n]").unwrap();
+        assert_eq!(values, vec![
+            serde_json::json!({"a": "x, ]}\"", "b": [1, {"c": null}]}),
+            serde_json::json!({"a": 2}),
+        ]);
+        assert_eq!(read_all("[]").unwrap(), Vec::<Value>::new());
+        assert_eq!(read_all("[1, \"s\", true]").unwrap(), vec![serde_json::json!(1), serde_json::json!("s"), serde_json::json!(true)]);
+    }
+
+    #[test]
+    fn test_read_object_and_json_lines() {
+        assert_eq!(read_all("{\"a\": 1}").unwrap(), vec![serde_json::json!({"a": 1})]);
+        assert_eq!(
+            read_all("{\"a\": 1}\n{\"a\": 2}\n").unwrap(),
+            vec![serde_json::json!({"a": 1}), serde_json::json!({"a": 2})]
+        );
+        assert_eq!(read_all("  ").unwrap(), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn test_truncated_json_is_an_error() {
+        assert!(read_all("[{\"a\": 1}, {\"a\": ").is_err());
+        assert!(read_all("[{\"a\": 1} {\"a\": 2}]").is_err());
+    }
+}
@@ -1,7 +1,11 @@
 pub mod sql;
 pub mod json;
 pub mod csv;
+pub mod yaml;
+pub mod avro;
 
 pub use sql::SqlFormatHandler;
 pub use json::JsonFormatHandler;
 pub use csv::CsvFormatHandler;
+pub use yaml::YamlFormatHandler;
+pub use avro::AvroFormatHandler;
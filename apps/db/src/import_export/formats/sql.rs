@@ -1,3 +1,4 @@
+use std::io::{BufRead, Write};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -6,29 +7,23 @@ use async_trait::async_trait;
 
 use crate::connection::DbConnection;
 use crate::DatabasePlugin;
-use crate::executor::{ExecOptions, SqlResult};
-use crate::import_export::{ExportConfig, ExportResult, FormatHandler, ImportConfig, ImportResult, ExportProgressEvent, ExportProgressSender, ImportProgressEvent, ImportProgressSender};
+use crate::executor::{ExecOptions, SqlResult, StatementTimeout};
+use crate::import_export::{ExportConfig, ExportResult, FormatHandler, ImportConfig, ImportResult, ExportProgressEvent, ExportProgressSender, ImportProgressEvent, ImportProgressSender, InsertConflictClause, LineReader, SqlConflictMode, TableChunkReader, TranscodingReader, TranscodingWriter};
+use crate::import_export::chunked::send_progress;
+
+/// 读取 SQL 脚本时攒够这么多字节后，在下一个语句边界处切分执行
+const SCRIPT_CHUNK_BYTES: usize = 1024 * 1024;
 
 pub struct SqlFormatHandler;
 
 #[async_trait]
 impl FormatHandler for SqlFormatHandler {
-    async fn import(
-        &self,
-        plugin: Arc<dyn DatabasePlugin>,
-        connection: &dyn DbConnection,
-        config: &ImportConfig,
-        data: &str,
-    ) -> Result<ImportResult> {
-        self.import_with_progress(plugin, connection, config, data, "", None).await
-    }
-
-    async fn import_with_progress(
+    async fn import_from_reader(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ImportConfig,
-        data: &str,
+        reader: &mut (dyn BufRead + Send),
         file_name: &str,
         progress_tx: Option<ImportProgressSender>,
     ) -> Result<ImportResult> {
@@ -83,82 +78,106 @@ impl FormatHandler for SqlFormatHandler {
             }
         }
 
-        let statements: Vec<String> = plugin.split_statements(data);
-        let total_statements = statements.len();
+        // 其他编码边读边转成 UTF-8
+        let mut reader = TranscodingReader::new(reader, config.encoding);
+        // 按块读取脚本，每块结束在语句边界上时切分执行，内存中只保留当前块
+        let mut lines = LineReader::new(&mut reader);
+        let mut buffer = String::new();
+        let mut statements_read = 0usize;
+        loop {
+            let line = lines.next_line()?;
+            let statements = match &line {
+                Some((_, text)) => {
+                    buffer.push_str(text);
+                    buffer.push('\n');
+                    if buffer.len() < SCRIPT_CHUNK_BYTES || !text.trim_end().ends_with(';') {
+                        continue;
+                    }
+                    match complete_statements(&buffer, |script| plugin.split_statements(script)) {
+                        Some(statements) => statements,
+                        None => continue,
+                    }
+                }
+                None => plugin.split_statements(&buffer),
+            };
+            buffer.clear();
 
-        for (idx, stmt) in statements.iter().enumerate() {
-            let stmt = stmt.trim();
-            if stmt.is_empty() {
-                continue;
-            }
+            let first_index = statements_read;
+            statements_read += statements.len();
+            for (offset, stmt) in statements.iter().enumerate() {
+                let stmt = stmt.trim();
+                if stmt.is_empty() {
+                    continue;
+                }
 
-            send_progress(ImportProgressEvent::ExecutingStatement {
-                file: file_name.to_string(),
-                statement_index: idx,
-                total_statements,
-            });
+                send_progress(ImportProgressEvent::ExecutingStatement {
+                    file: file_name.to_string(),
+                    statement_index: first_index + offset,
+                    total_statements: statements_read,
+                });
 
-            let exec_options = ExecOptions {
-                stop_on_error: config.stop_on_error,
-                transactional: false,
-                max_rows: None,
-            };
+                let exec_options = ExecOptions {
+                    stop_on_error: config.stop_on_error,
+                    transactional: false,
+                    max_rows: None,
+                    source: None,
+                    // Bulk inserts of an import may legitimately run longer than interactive statements
+                    timeout: StatementTimeout::Disabled,
+                    session_id: None,
+                };
 
-            match connection.execute(plugin.clone(), stmt, exec_options).await {
-                Ok(results) => {
-                    for result in results {
-                        match result {
-                            SqlResult::Exec(exec_result) => {
-                                total_rows += exec_result.rows_affected;
-                                send_progress(ImportProgressEvent::StatementExecuted {
-                                    file: file_name.to_string(),
-                                    rows_affected: exec_result.rows_affected,
-                                });
-                            }
-                            SqlResult::Error(err) => {
-                                let error_msg = err.message.clone();
-                                errors.push(error_msg.clone());
-                                send_progress(ImportProgressEvent::Error {
-                                    file: file_name.to_string(),
-                                    message: error_msg,
-                                });
-                                if config.stop_on_error {
-                                    send_progress(ImportProgressEvent::Finished {
-                                        total_rows,
-                                        elapsed_ms: start.elapsed().as_millis(),
+                let mut failed = false;
+                match connection.execute_import(plugin.clone(), stmt, exec_options, config.async_insert).await {
+                    Ok(results) => {
+                        for result in results {
+                            match result {
+                                SqlResult::Exec(exec_result) => {
+                                    total_rows += exec_result.rows_affected;
+                                    send_progress(ImportProgressEvent::StatementExecuted {
+                                        file: file_name.to_string(),
+                                        rows_affected: exec_result.rows_affected,
                                     });
-                                    return Ok(ImportResult {
-                                        success: false,
-                                        rows_imported: total_rows,
-                                        errors,
-                                        elapsed_ms: start.elapsed().as_millis(),
+                                }
+                                SqlResult::Error(err) => {
+                                    failed = true;
+                                    let error_msg = err.message.clone();
+                                    errors.push(error_msg.clone());
+                                    send_progress(ImportProgressEvent::Error {
+                                        file: file_name.to_string(),
+                                        message: error_msg,
                                     });
                                 }
+                                _ => {}
                             }
-                            _ => {}
                         }
                     }
-                }
-                Err(e) => {
-                    let error_msg = e.to_string();
-                    errors.push(error_msg.clone());
-                    send_progress(ImportProgressEvent::Error {
-                        file: file_name.to_string(),
-                        message: error_msg,
-                    });
-                    if config.stop_on_error {
-                        send_progress(ImportProgressEvent::Finished {
-                            total_rows,
-                            elapsed_ms: start.elapsed().as_millis(),
-                        });
-                        return Ok(ImportResult {
-                            success: false,
-                            rows_imported: total_rows,
-                            errors,
-                            elapsed_ms: start.elapsed().as_millis(),
+                    Err(e) => {
+                        failed = true;
+                        let error_msg = e.to_string();
+                        errors.push(error_msg.clone());
+                        send_progress(ImportProgressEvent::Error {
+                            file: file_name.to_string(),
+                            message: error_msg,
                         });
                     }
                 }
+
+                if failed && config.stop_on_error {
+                    send_progress(ImportProgressEvent::Finished {
+                        total_rows,
+                        elapsed_ms: start.elapsed().as_millis(),
+                    });
+                    return Ok(ImportResult {
+                        success: false,
+                        rows_imported: total_rows,
+                        errors,
+                        elapsed_ms: start.elapsed().as_millis(),
+                    });
+                }
+            }
+
+            if line.is_none() {
+                break;
             }
         }
 
@@ -176,61 +195,57 @@ impl FormatHandler for SqlFormatHandler {
         })
     }
 
-    async fn export(
-        &self,
-        plugin: Arc<dyn DatabasePlugin>,
-        connection: &dyn DbConnection,
-        config: &ExportConfig,
-    ) -> Result<ExportResult> {
-        self.export_with_progress(plugin, connection, config, None).await
-    }
-
-    async fn export_with_progress(
+    async fn export_to_writer(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
         progress_tx: Option<ExportProgressSender>,
     ) -> Result<ExportResult> {
         let start = Instant::now();
-        let mut output = String::new();
+        // 数据库返回的文本是 UTF-8，写出时转成目标编码
+        let mut writer = TranscodingWriter::new(writer, config.encoding);
         let mut total_rows = 0u64;
         let total_tables = config.tables.len();
+        let sql_config = config.sql_config.clone().unwrap_or_default();
+        let rows_per_insert = plugin
+            .max_rows_per_insert()
+            .map_or(sql_config.rows_per_insert, |max_rows| sql_config.rows_per_insert.min(max_rows))
+            .max(1);
 
-        let send_progress = |event: ExportProgressEvent| {
-            if let Some(tx) = &progress_tx {
-                let _ = tx.send(event);
+        // 建表语句里的外键也会引用还没创建的表，所以整个脚本都在关闭检查的范围内
+        if sql_config.disable_foreign_keys {
+            match plugin.foreign_key_checks_sql(false) {
+                Some(sql) => write!(writer, "{};\n\n", sql)?,
+                None => writeln!(writer, "-- Foreign key checks can't be disabled for this database\n")?,
             }
-        };
+        }
 
         for (index, table) in config.tables.iter().enumerate() {
-            send_progress(ExportProgressEvent::TableStart {
+            send_progress(&progress_tx, ExportProgressEvent::TableStart {
                 table: table.clone(),
                 table_index: index,
                 total_tables,
             });
 
             if config.include_schema {
-                send_progress(ExportProgressEvent::GettingStructure {
+                send_progress(&progress_tx, ExportProgressEvent::GettingStructure {
                     table: table.clone(),
                 });
 
                 match plugin.export_table_create_sql(connection, &config.database, table).await {
                     Ok(schema_sql) => {
                         if !schema_sql.is_empty() {
-                            output.push_str("-- Table structure for ");
-                            output.push_str(table);
-                            output.push('\n');
-                            output.push_str(&schema_sql);
-                            output.push_str(";\n\n");
+                            write!(writer, "-- Table structure for {}\n{};\n\n", table, schema_sql)?;
                         }
-                        send_progress(ExportProgressEvent::StructureExported {
+                        send_progress(&progress_tx, ExportProgressEvent::StructureExported {
                             table: table.clone(),
                         });
                     }
                     Err(e) => {
-                        output.push_str(&format!("-- Failed to export structure for {}: {}\n\n", table, e));
-                        send_progress(ExportProgressEvent::Error {
+                        write!(writer, "-- Failed to export structure for {}: {}\n\n", table, e)?;
+                        send_progress(&progress_tx, ExportProgressEvent::Error {
                             table: table.clone(),
                             message: format!("Failed to export structure: {}", e),
                         });
@@ -239,60 +254,211 @@ impl FormatHandler for SqlFormatHandler {
             }
 
             if config.include_data {
-                send_progress(ExportProgressEvent::FetchingData {
+                send_progress(&progress_tx, ExportProgressEvent::FetchingData {
                     table: table.clone(),
                 });
 
-                match plugin.export_table_data_sql(
-                    connection,
-                    &config.database,
-                    table,
-                    config.where_clause.as_deref(),
-                    config.limit,
-                ).await {
-                    Ok(data_sql) => {
-                        let rows_count = if !data_sql.is_empty() {
-                            output.push_str("-- Data for table ");
-                            output.push_str(table);
-                            output.push('\n');
-                            output.push_str(&data_sql);
-                            output.push('\n');
-                            data_sql.lines().filter(|l| l.starts_with("INSERT")).count() as u64
-                        } else {
-                            0
-                        };
-                        total_rows += rows_count;
-                        send_progress(ExportProgressEvent::DataExported {
-                            table: table.clone(),
-                            rows: rows_count,
-                        });
-                    }
-                    Err(e) => {
-                        output.push_str(&format!("-- Failed to export data for {}: {}\n\n", table, e));
-                        send_progress(ExportProgressEvent::Error {
-                            table: table.clone(),
-                            message: format!("Failed to export data: {}", e),
-                        });
+                let mut rows_count = 0u64;
+                let mut conflict_clause = None;
+                let mut chunks = TableChunkReader::open(plugin.as_ref(), connection, config, table).await;
+                loop {
+                    match chunks.next_chunk().await {
+                        Ok(Some(chunk)) => {
+                            if chunk.rows.is_empty() {
+                                continue;
+                            }
+                            if rows_count == 0 {
+                                writeln!(writer, "-- Data for table {}", table)?;
+                                if sql_config.conflict_mode != SqlConflictMode::Error {
+                                    conflict_clause = plugin.insert_conflict_clause(sql_config.conflict_mode, &chunk.columns, chunks.key_columns());
+                                    if conflict_clause.is_none() {
+                                        writeln!(writer, "-- Conflict handling is not supported for {}, rows are inserted as-is", table)?;
+                                    }
+                                }
+                            }
+                            let statements = insert_statements(plugin.as_ref(), table, &chunk.columns, &chunk.rows, rows_per_insert, conflict_clause.as_ref());
+                            writer.write_all(statements.as_bytes())?;
+                            rows_count += chunk.rows.len() as u64;
+                            send_progress(&progress_tx, ExportProgressEvent::DataProgress {
+                                table: table.clone(),
+                                rows: chunk.rows.len() as u64,
+                            });
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            write!(writer, "-- Failed to export data for {}: {}\n\n", table, e)?;
+                            send_progress(&progress_tx, ExportProgressEvent::Error {
+                                table: table.clone(),
+                                message: format!("Failed to export data: {}", e),
+                            });
+                            break;
+                        }
                     }
                 }
+                if rows_count > 0 {
+                    writeln!(writer)?;
+                }
+                total_rows += rows_count;
+                send_progress(&progress_tx, ExportProgressEvent::DataExported {
+                    table: table.clone(),
+                    rows: rows_count,
+                });
             }
 
-            send_progress(ExportProgressEvent::TableFinished {
+            send_progress(&progress_tx, ExportProgressEvent::TableFinished {
                 table: table.clone(),
             });
         }
 
+        // Materialized views read from the tables above, so they come last and are filled
+        // from the exported data when created
+        if config.include_schema {
+            match plugin.export_materialized_views_sql(connection, &config.database).await {
+                Ok(views_sql) => writer.write_all(views_sql.as_bytes())?,
+                Err(e) => write!(writer, "-- Failed to export materialized views: {}\n\n", e)?,
+            }
+        }
+        if sql_config.disable_foreign_keys {
+            if let Some(sql) = plugin.foreign_key_checks_sql(true) {
+                writeln!(writer, "{};", sql)?;
+            }
+        }
+        writer.flush()?;
+
         let elapsed_ms = start.elapsed().as_millis();
-        send_progress(ExportProgressEvent::Finished {
+        send_progress(&progress_tx, ExportProgressEvent::Finished {
             total_rows,
             elapsed_ms,
         });
 
         Ok(ExportResult {
             success: true,
-            output,
+            output: String::new(),
             rows_exported: total_rows,
             elapsed_ms,
         })
     }
 }
+
+/// 把一块数据写成多行 INSERT，每条最多 `rows_per_insert` 行；值的写法与 `export_rows_sql` 相同
+fn insert_statements(
+    plugin: &dyn DatabasePlugin,
+    table: &str,
+    columns: &[String],
+    rows: &[Vec<Option<String>>],
+    rows_per_insert: usize,
+    conflict_clause: Option<&InsertConflictClause>,
+) -> String {
+    let (keyword, suffix) = conflict_clause.map_or(("INSERT", ""), |clause| (clause.keyword.as_str(), clause.suffix.as_str()));
+    let column_list = columns
+        .iter()
+        .map(|column| plugin.quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let prefix = format!("{} INTO {} ({}) VALUES ", keyword, plugin.quote_identifier(table), column_list);
+
+    let mut output = String::new();
+    for batch in rows.chunks(rows_per_insert.max(1)) {
+        output.push_str(&prefix);
+        for (row_index, row) in batch.iter().enumerate() {
+            if row_index > 0 {
+                output.push_str(",\n");
+            }
+            output.push('(');
+            for (value_index, value) in row.iter().enumerate() {
+                if value_index > 0 {
+                    output.push_str(", ");
+                }
+                match value {
+                    Some(v) => output.push_str(&plugin.string_literal(v)),
+                    None => output.push_str("NULL"),
+                }
+            }
+            output.push(')');
+        }
+        output.push_str(suffix);
+        output.push_str(";\n");
+    }
+    output
+}
+
+/// 缓冲区正好结束在语句边界上时返回切分出的语句；结尾还在字符串、注释、过程体
+/// 或自定义 DELIMITER 中时返回 None，需要继续读取。
+/// 在末尾追加一条探测语句，只有它被单独切出来，才说明前面的语句都已完整
+fn complete_statements(buffer: &str, split: impl Fn(&str) -> Vec<String>) -> Option<Vec<String>> {
+    let statements = split(buffer);
+    let probed = split(&format!("{}\nSELECT 1;\n", buffer));
+    (probed.len() == statements.len() + 1).then_some(statements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mysql::MySqlPlugin;
+    use crate::plugin::fallback_split;
+    use crate::postgresql::PostgresPlugin;
+
+    #[test]
+    fn test_complete_statements_at_boundary() {
+        let statements = complete_statements("INSERT INTO t VALUES (1);\nINSERT INTO t VALUES (2);\n", fallback_split).unwrap();
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn test_incomplete_statements_keep_reading() {
+        // 多行字符串中某一行恰好以分号结尾
+        assert!(complete_statements("INSERT INTO t VALUES ('a;\n", fallback_split).is_none());
+        assert!(complete_statements("/* x;\n", fallback_split).is_none());
+        assert!(complete_statements("DELIMITER $$\nCREATE PROCEDURE p() BEGIN SELECT 1;\n", fallback_split).is_none());
+    }
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_insert_statements_in_batches() {
+        let rows = vec![
+            vec![Some("1".to_string()), Some("O'Brien".to_string())],
+            vec![Some("2".to_string()), None],
+            vec![Some("3".to_string()), Some("c".to_string())],
+        ];
+        let sql = insert_statements(&MySqlPlugin::new(), "users", &columns(&["id", "name"]), &rows, 2, None);
+        assert_eq!(
+            sql,
+            "INSERT INTO `users` (`id`, `name`) VALUES ('1', 'O''Brien'),\n('2', NULL);\n\
+             INSERT INTO `users` (`id`, `name`) VALUES ('3', 'c');\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_statements_with_conflict_clause() {
+        let mysql = MySqlPlugin::new();
+        let names = columns(&["id", "name"]);
+        let rows = vec![vec![Some("1".to_string()), Some("a".to_string())]];
+
+        let clause = mysql.insert_conflict_clause(SqlConflictMode::Ignore, &names, &[]);
+        assert_eq!(
+            insert_statements(&mysql, "t", &names, &rows, 100, clause.as_ref()),
+            "INSERT IGNORE INTO `t` (`id`, `name`) VALUES ('1', 'a');\n"
+        );
+        let clause = mysql.insert_conflict_clause(SqlConflictMode::Update, &names, &[]);
+        assert_eq!(
+            insert_statements(&mysql, "t", &names, &rows, 100, clause.as_ref()),
+            "INSERT INTO `t` (`id`, `name`) VALUES ('1', 'a') ON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `name` = VALUES(`name`);\n"
+        );
+
+        let postgres = PostgresPlugin::new();
+        let clause = postgres.insert_conflict_clause(SqlConflictMode::Update, &names, &columns(&["id"]));
+        assert_eq!(
+            insert_statements(&postgres, "t", &names, &rows, 100, clause.as_ref()),
+            "INSERT INTO \"t\" (\"id\", \"name\") VALUES ('1', 'a') ON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\";\n"
+        );
+        // 没有主键时无法确定冲突目标，只能跳过
+        assert!(postgres.insert_conflict_clause(SqlConflictMode::Update, &names, &[]).is_none());
+        assert_eq!(
+            postgres.insert_conflict_clause(SqlConflictMode::Ignore, &names, &[]).map(|clause| clause.suffix),
+            Some(" ON CONFLICT DO NOTHING".to_string())
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::io::{BufRead, Write};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::connection::DbConnection;
+use crate::DatabasePlugin;
+use crate::import_export::{ExportConfig, ExportProgressEvent, ExportProgressSender, ExportResult, FormatHandler, ImportConfig, ImportProgressSender, ImportResult, TableChunkReader, TranscodingWriter};
+use crate::import_export::chunked::send_progress;
+
+/// 导出为 YAML，适合行数不多的配置类表；每个表一个 YAML 文档，多个表之间用 `---` 分隔
+pub struct YamlFormatHandler;
+
+#[async_trait]
+impl FormatHandler for YamlFormatHandler {
+    async fn import_from_reader(
+        &self,
+        _plugin: Arc<dyn DatabasePlugin>,
+        _connection: &dyn DbConnection,
+        _config: &ImportConfig,
+        _reader: &mut (dyn BufRead + Send),
+        _file_name: &str,
+        _progress_tx: Option<ImportProgressSender>,
+    ) -> Result<ImportResult> {
+        Err(anyhow!("YAML import is not supported"))
+    }
+
+    async fn export_to_writer(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult> {
+        let start = Instant::now();
+        // 数据库返回的文本是 UTF-8，写出时转成目标编码
+        let mut writer = TranscodingWriter::new(writer, config.encoding);
+        let mut total_rows = 0u64;
+        let total_tables = config.tables.len();
+
+        for (table_idx, table) in config.tables.iter().enumerate() {
+            send_progress(&progress_tx, ExportProgressEvent::TableStart {
+                table: table.clone(),
+                table_index: table_idx,
+                total_tables,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::FetchingData {
+                table: table.clone(),
+            });
+
+            let mut table_rows = 0u64;
+            let mut chunks = TableChunkReader::open(plugin.as_ref(), connection, config, table).await;
+            let mut first_chunk = true;
+            while let Some(chunk) = chunks.next_chunk().await? {
+                if first_chunk {
+                    if table_idx > 0 {
+                        writer.write_all(b"---\n")?;
+                    }
+                    writeln!(writer, "# {}", table.replace('\n', " "))?;
+                }
+                // 后面的块接着同一个列表写，只有整个表没有行时才写 `[]`
+                if first_chunk || !chunk.rows.is_empty() {
+                    writer.write_all(rows_to_yaml(&chunk.columns, &chunk.rows).as_bytes())?;
+                }
+                first_chunk = false;
+                table_rows += chunk.rows.len() as u64;
+                send_progress(&progress_tx, ExportProgressEvent::DataProgress {
+                    table: table.clone(),
+                    rows: chunk.rows.len() as u64,
+                });
+            }
+            total_rows += table_rows;
+
+            send_progress(&progress_tx, ExportProgressEvent::DataExported {
+                table: table.clone(),
+                rows: table_rows,
+            });
+            send_progress(&progress_tx, ExportProgressEvent::TableFinished {
+                table: table.clone(),
+            });
+        }
+        writer.flush()?;
+
+        let elapsed_ms = start.elapsed().as_millis();
+        send_progress(&progress_tx, ExportProgressEvent::Finished {
+            total_rows,
+            elapsed_ms,
+        });
+
+        Ok(ExportResult {
+            success: true,
+            output: String::new(),
+            rows_exported: total_rows,
+            elapsed_ms,
+        })
+    }
+}
+
+/// 把结果集写成 YAML 列表，每行一个以列名为键的映射；NULL 写为 `null`
+pub fn rows_to_yaml(columns: &[String], rows: &[Vec<Option<String>>]) -> String {
+    if rows.is_empty() {
+        return "[]\n".to_string();
+    }
+
+    let mut output = String::new();
+    for row in rows {
+        for (i, column) in columns.iter().enumerate() {
+            output.push_str(if i == 0 { "- " } else { "  " });
+            output.push_str(&yaml_scalar(column));
+            output.push_str(": ");
+            match row.get(i) {
+                Some(Some(value)) => output.push_str(&yaml_scalar(value)),
+                _ => output.push_str("null"),
+            }
+            output.push('\n');
+        }
+    }
+    output
+}
+
+/// 数值和普通文本不加引号；会被 YAML 解析成别的类型（布尔、null、日期、八进制等）
+/// 或含有特殊字符的值加引号，保证读回来仍是原来的字符串
+fn yaml_scalar(value: &str) -> String {
+    if value.chars().any(|c| c.is_control()) {
+        return double_quoted(value);
+    }
+    if needs_quotes(value) {
+        return format!("'{}'", value.replace('\'', "''"));
+    }
+    value.to_string()
+}
+
+fn needs_quotes(value: &str) -> bool {
+    let Some(first) = value.chars().next() else {
+        return true;
+    };
+    if value.trim() != value {
+        return true;
+    }
+    // YAML 1.1 会把这些词读成布尔值或 null
+    let lower = value.to_ascii_lowercase();
+    if matches!(lower.as_str(), "~" | "null" | "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n") {
+        return true;
+    }
+    // 以数字或符号开头的值只有规范的数字能不加引号，`007`、`0x1F`、`2024-01-01`、`12:30` 都会被改写
+    if first.is_ascii_digit() || matches!(first, '+' | '-' | '.') {
+        return !is_canonical_number(value);
+    }
+    if "?:,[]{}#&*!|>'\"%@`".contains(first) {
+        return true;
+    }
+    value.contains(": ") || value.contains(" #") || value.ends_with(':')
+}
+
+fn is_canonical_number(value: &str) -> bool {
+    if let Ok(integer) = value.parse::<i64>() {
+        return integer.to_string() == value;
+    }
+    value.contains('.')
+        && value.parse::<f64>().is_ok_and(|number| number.is_finite() && number.to_string() == value)
+}
+
+fn double_quoted(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_to_yaml() {
+        let columns = vec!["key".to_string(), "value".to_string()];
+        let rows = vec![
+            vec![Some("timeout".to_string()), Some("30".to_string())],
+            vec![Some("motd".to_string()), None],
+        ];
+        assert_eq!(
+            rows_to_yaml(&columns, &rows),
+            "- key: timeout\n  value: 30\n- key: motd\n  value: null\n"
+        );
+        assert_eq!(rows_to_yaml(&columns, &[]), "[]\n");
+    }
+
+    #[test]
+    fn test_scalars_that_would_change_type_are_quoted() {
+        assert_eq!(yaml_scalar("hello world"), "hello world");
+        assert_eq!(yaml_scalar("-12"), "-12");
+        assert_eq!(yaml_scalar("3.25"), "3.25");
+        assert_eq!(yaml_scalar(""), "''");
+        assert_eq!(yaml_scalar("yes"), "'yes'");
+        assert_eq!(yaml_scalar("NULL"), "'NULL'");
+        assert_eq!(yaml_scalar("007"), "'007'");
+        assert_eq!(yaml_scalar("1.50"), "'1.50'");
+        assert_eq!(yaml_scalar("2024-01-01"), "'2024-01-01'");
+        assert_eq!(yaml_scalar("- item"), "'- item'");
+        assert_eq!(yaml_scalar("a: b"), "'a: b'");
+        assert_eq!(yaml_scalar("it's #1"), "'it''s #1'");
+        assert_eq!(yaml_scalar(" padded"), "' padded'");
+        assert_eq!(yaml_scalar("line1\nline2\t\"x\""), "\"line1\\nline2\\t\\\"x\\\"\"");
+    }
+}
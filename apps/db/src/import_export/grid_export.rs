@@ -0,0 +1,218 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::import_export::{CsvExportConfig, DataFormat};
+use crate::import_export::formats::{CsvFormatHandler, JsonFormatHandler};
+use crate::import_export::formats::yaml::rows_to_yaml;
+use crate::DatabasePlugin;
+
+/// 表格导出范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridExportScope {
+    /// 导出表格中当前可见的数据（应用筛选、排序，使用显示格式）
+    Visible,
+    /// 重新执行底层查询并导出原始数据
+    RawQuery,
+}
+
+/// 复制到剪贴板时的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardFormat {
+    Csv,
+    Json,
+    /// 管道表格，可以直接贴进 issue、聊天和文档
+    Markdown,
+    /// 每行一条 INSERT，可以贴到另一个连接的编辑器里执行
+    Insert,
+}
+
+/// 表格导出目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridExportTarget {
+    /// 写入用户选择的文件
+    File(DataFormat),
+    /// 复制到系统剪贴板，不弹出保存对话框
+    Clipboard(ClipboardFormat),
+}
+
+/// 从数据表格中提取的待导出数据
+#[derive(Debug, Clone, Default)]
+pub struct GridExportData {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Option<String>>>,
+}
+
+impl GridExportData {
+    pub fn new(columns: Vec<String>, rows: Vec<Vec<Option<String>>>) -> Self {
+        Self { columns, rows }
+    }
+
+    /// 只保留指定的列并按给定顺序排列，用于去掉表格中隐藏的列
+    pub fn select_columns(self, indices: &[usize]) -> Self {
+        let indices: Vec<usize> = indices.iter().copied().filter(|&ix| ix < self.columns.len()).collect();
+        Self {
+            columns: indices.iter().map(|&ix| self.columns[ix].clone()).collect(),
+            rows: self
+                .rows
+                .iter()
+                .map(|row| indices.iter().map(|&ix| row.get(ix).cloned().flatten()).collect())
+                .collect(),
+        }
+    }
+
+    /// 按指定格式渲染导出内容
+    pub fn render(&self, format: DataFormat) -> Result<String> {
+        match format {
+            DataFormat::Csv => Ok(self.to_csv()),
+            DataFormat::Json => self.to_json(),
+            DataFormat::Yaml => Ok(rows_to_yaml(&self.columns, &self.rows)),
+            DataFormat::Sql => Err(anyhow!("SQL format is not supported for grid export")),
+            DataFormat::Avro => Err(anyhow!("Avro format is not supported for grid export")),
+        }
+    }
+
+    /// 按剪贴板格式渲染，`table` 只用于 INSERT 语句
+    pub fn render_clipboard(&self, format: ClipboardFormat, plugin: &dyn DatabasePlugin, table: &str) -> Result<String> {
+        match format {
+            ClipboardFormat::Csv => Ok(self.to_csv()),
+            ClipboardFormat::Json => self.to_json(),
+            ClipboardFormat::Markdown => Ok(self.to_markdown()),
+            ClipboardFormat::Insert => Ok(self.to_insert_sql(plugin, table)),
+        }
+    }
+
+    /// 与 CSV 格式导出的默认配置相同
+    pub fn to_csv(&self) -> String {
+        let config = CsvExportConfig::default();
+        let mut output = String::new();
+        CsvFormatHandler::write_header(&mut output, &self.columns, &config);
+        CsvFormatHandler::write_rows(&mut output, &self.rows, &config);
+        output
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let data: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| JsonFormatHandler::row_to_json(&self.columns, row))
+            .collect();
+        Ok(serde_json::to_string_pretty(&data)?)
+    }
+
+    /// NULL 写为空单元格；单元格里的 `|` 和换行会破坏表格结构，需要转义
+    pub fn to_markdown(&self) -> String {
+        let row_line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let mut output = row_line(self.columns.iter().map(|col| escape_markdown_cell(col)).collect());
+        output.push_str(&row_line(self.columns.iter().map(|_| "---".to_string()).collect()));
+        for row in &self.rows {
+            output.push_str(&row_line(
+                row.iter()
+                    .map(|val| val.as_deref().map(escape_markdown_cell).unwrap_or_default())
+                    .collect(),
+            ));
+        }
+        output
+    }
+
+    /// 带列名的 INSERT，结果集的列顺序不一定和表定义相同
+    pub fn to_insert_sql(&self, plugin: &dyn DatabasePlugin, table: &str) -> String {
+        let column_list = self
+            .columns
+            .iter()
+            .map(|col| plugin.quote_identifier(col))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let prefix = format!("INSERT INTO {} ({}) VALUES (", plugin.quote_identifier(table), column_list);
+
+        let mut output = String::new();
+        for row in &self.rows {
+            let values = row
+                .iter()
+                .map(|val| match val {
+                    Some(v) => plugin.string_literal(v),
+                    None => "NULL".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            output.push_str(&prefix);
+            output.push_str(&values);
+            output.push_str(");\n");
+        }
+        output
+    }
+}
+
+fn escape_markdown_cell(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_escapes_fields_and_nulls() {
+        let data = GridExportData::new(
+            vec!["id".to_string(), "name".to_string()],
+            vec![
+                vec![Some("1".to_string()), Some("a,b".to_string())],
+                vec![Some("2".to_string()), None],
+                vec![Some("3".to_string()), Some("x\ry".to_string())],
+            ],
+        );
+
+        assert_eq!(data.to_csv(), "id,name\n1,\"a,b\"\n2,\n3,\"x\ry\"\n");
+    }
+
+    #[test]
+    fn test_json_keeps_nulls() {
+        let data = GridExportData::new(
+            vec!["id".to_string()],
+            vec![vec![None]],
+        );
+
+        let value: Value = serde_json::from_str(&data.to_json().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!([{ "id": null }]));
+    }
+
+    #[test]
+    fn test_clipboard_markdown_and_insert() {
+        let data = GridExportData::new(
+            vec!["id".to_string(), "note".to_string()],
+            vec![
+                vec![Some("1".to_string()), Some("a|b\nc".to_string())],
+                vec![Some("2".to_string()), None],
+            ],
+        );
+
+        assert_eq!(data.to_markdown(), "| id | note |\n| --- | --- |\n| 1 | a\\|b<br>c |\n| 2 |  |\n");
+
+        let plugin = crate::mysql::MySqlPlugin::new();
+        assert_eq!(
+            data.render_clipboard(ClipboardFormat::Insert, &plugin, "notes").unwrap(),
+            "INSERT INTO `notes` (`id`, `note`) VALUES ('1', 'a|b\nc');\n\
+             INSERT INTO `notes` (`id`, `note`) VALUES ('2', NULL);\n"
+        );
+    }
+
+    #[test]
+    fn test_hidden_columns_are_omitted() {
+        let data = GridExportData::new(
+            vec!["id".to_string(), "secret".to_string(), "name".to_string()],
+            vec![vec![Some("1".to_string()), Some("x".to_string()), Some("a".to_string())]],
+        )
+        .select_columns(&[2, 0]);
+
+        assert_eq!(data.to_csv(), "name,id\na,1\n");
+    }
+
+    #[test]
+    fn test_sql_format_is_rejected() {
+        let data = GridExportData::default();
+        assert!(data.render(DataFormat::Sql).is_err());
+    }
+}
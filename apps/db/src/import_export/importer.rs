@@ -1,10 +1,13 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use std::sync::Arc;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use crate::connection::DbConnection;
 use crate::DatabasePlugin;
 use crate::import_export::{DataFormat, FormatHandler, ImportConfig, ImportResult, ImportProgressSender};
-use crate::import_export::formats::{CsvFormatHandler, JsonFormatHandler, SqlFormatHandler};
+use crate::import_export::formats::{AvroFormatHandler, CsvFormatHandler, JsonFormatHandler, SqlFormatHandler, YamlFormatHandler};
 
 pub struct DataImporter;
 
@@ -36,6 +39,46 @@ impl DataImporter {
             DataFormat::Csv => {
                 CsvFormatHandler.import_with_progress(plugin, connection, &config, &data, file_name, progress_tx).await
             }
+            DataFormat::Yaml => {
+                YamlFormatHandler.import_with_progress(plugin, connection, &config, &data, file_name, progress_tx).await
+            }
+            DataFormat::Avro => {
+                AvroFormatHandler.import_with_progress(plugin, connection, &config, &data, file_name, progress_tx).await
+            }
+        }
+    }
+
+    /// 边读文件边导入，大文件导入时内存占用只取决于 `ImportConfig::batch_size`
+    pub async fn import_file(
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: ImportConfig,
+        path: &Path,
+        progress_tx: Option<ImportProgressSender>,
+    ) -> Result<ImportResult> {
+        let file = File::open(path)
+            .map_err(|e| anyhow!("Open {} failed: {}", path.display(), e))?;
+        let mut reader = BufReader::new(file);
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match config.format {
+            DataFormat::Sql => {
+                SqlFormatHandler.import_from_reader(plugin, connection, &config, &mut reader, &file_name, progress_tx).await
+            }
+            DataFormat::Json => {
+                JsonFormatHandler.import_from_reader(plugin, connection, &config, &mut reader, &file_name, progress_tx).await
+            }
+            DataFormat::Csv => {
+                CsvFormatHandler.import_from_reader(plugin, connection, &config, &mut reader, &file_name, progress_tx).await
+            }
+            DataFormat::Yaml => {
+                YamlFormatHandler.import_from_reader(plugin, connection, &config, &mut reader, &file_name, progress_tx).await
+            }
+            DataFormat::Avro => {
+                AvroFormatHandler.import_from_reader(plugin, connection, &config, &mut reader, &file_name, progress_tx).await
+            }
         }
     }
 }
@@ -0,0 +1,367 @@
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::connection::DbConnection;
+use crate::DatabasePlugin;
+use crate::import_export::ImportConfig;
+
+/// 导入时源文件的列和目标表的列如何对应。
+/// 没有写映射的源列仍按同名写入目标表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnMapping {
+    /// 源列写入名字不同的目标列
+    Column { source: String, target: String },
+    /// 不导入这一源列
+    Skip { source: String },
+    /// 每行都写入同一个值
+    Constant { target: String, value: String },
+    /// 每行都写入一个 SQL 表达式，例如 `CURRENT_TIMESTAMP`
+    Expression { target: String, sql: String },
+}
+
+/// 从源文件读到的一个值
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceValue {
+    Null,
+    Text(String),
+    /// 数字、布尔等不加引号写进 SQL 的值
+    Literal(String),
+}
+
+/// 按目标列的类型转换文本值，只有设置了对应提示时才转换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coercion {
+    None,
+    Number,
+    Date,
+    DateTime,
+}
+
+#[derive(Debug, Clone)]
+enum Slot {
+    Source { index: usize, coercion: Coercion },
+    Fixed(String),
+}
+
+/// 把源文件的一行转换成目标列的 SQL 字面量
+#[derive(Debug, Clone)]
+pub struct ColumnMapper {
+    targets: Vec<String>,
+    slots: Vec<Slot>,
+    date_format: Option<String>,
+    decimal_separator: Option<char>,
+}
+
+impl ColumnMapper {
+    /// 没有映射和类型提示时原样按列名导入，不查询表结构
+    pub async fn new(
+        plugin: &dyn DatabasePlugin,
+        connection: &dyn DbConnection,
+        config: &ImportConfig,
+        table: &str,
+        source_columns: &[String],
+    ) -> Result<Self> {
+        let needs_types = config.date_format.is_some() || config.decimal_separator.is_some();
+        // 拿不到表结构时不做类型转换，由数据库自己转换
+        let target_types = if needs_types {
+            plugin
+                .list_columns(connection, &config.database, None, table)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|column| (column.name, column.data_type))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self::build(plugin, config, source_columns, &target_types)
+    }
+
+    fn build(
+        plugin: &dyn DatabasePlugin,
+        config: &ImportConfig,
+        source_columns: &[String],
+        target_types: &[(String, String)],
+    ) -> Result<Self> {
+        for mapping in &config.column_mappings {
+            if let ColumnMapping::Column { source, .. } | ColumnMapping::Skip { source } = mapping {
+                if !source_columns.contains(source) {
+                    return Err(anyhow!("Source column '{}' not found in file", source));
+                }
+            }
+        }
+
+        let coercion_for = |target: &str| {
+            let data_type = target_types
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(target))
+                .map(|(_, data_type)| data_type.as_str())
+                .unwrap_or_default();
+            coercion_for_type(data_type)
+        };
+
+        let mut mapper = Self {
+            targets: Vec::new(),
+            slots: Vec::new(),
+            date_format: config.date_format.clone(),
+            decimal_separator: config.decimal_separator,
+        };
+        for (index, source) in source_columns.iter().enumerate() {
+            let mut target = Some(source.clone());
+            for mapping in &config.column_mappings {
+                match mapping {
+                    ColumnMapping::Column { source: mapped, target: renamed } if mapped == source => {
+                        target = Some(renamed.clone());
+                    }
+                    ColumnMapping::Skip { source: mapped } if mapped == source => target = None,
+                    _ => {}
+                }
+            }
+            if let Some(target) = target {
+                mapper.slots.push(Slot::Source { index, coercion: coercion_for(&target) });
+                mapper.targets.push(target);
+            }
+        }
+        for mapping in &config.column_mappings {
+            match mapping {
+                ColumnMapping::Constant { target, value } => {
+                    let literal = mapper.literal(plugin, &SourceValue::Text(value.clone()), coercion_for(target))
+                        .map_err(|e| anyhow!("Column '{}': {}", target, e))?;
+                    mapper.slots.push(Slot::Fixed(literal));
+                    mapper.targets.push(target.clone());
+                }
+                ColumnMapping::Expression { target, sql } => {
+                    mapper.slots.push(Slot::Fixed(sql.clone()));
+                    mapper.targets.push(target.clone());
+                }
+                _ => {}
+            }
+        }
+
+        for (i, target) in mapper.targets.iter().enumerate() {
+            if mapper.targets[..i].iter().any(|other| other.eq_ignore_ascii_case(target)) {
+                return Err(anyhow!("Column '{}' is mapped more than once", target));
+            }
+        }
+        if mapper.targets.is_empty() {
+            return Err(anyhow!("No columns to import"));
+        }
+        Ok(mapper)
+    }
+
+    pub fn target_columns(&self) -> &[String] {
+        &self.targets
+    }
+
+    /// 返回目标列顺序的 SQL 字面量；值不符合日期格式时返回错误，由调用方跳过这一行。
+    /// 文本按 `plugin` 的方言转义，应传入创建时用的同一个插件
+    pub fn map_row(&self, plugin: &dyn DatabasePlugin, values: &[SourceValue]) -> Result<Vec<String>> {
+        self.slots
+            .iter()
+            .zip(&self.targets)
+            .map(|(slot, target)| match slot {
+                Slot::Source { index, coercion } => {
+                    let value = values.get(*index).unwrap_or(&SourceValue::Null);
+                    self.literal(plugin, value, *coercion).map_err(|e| anyhow!("Column '{}': {}", target, e))
+                }
+                Slot::Fixed(literal) => Ok(literal.clone()),
+            })
+            .collect()
+    }
+
+    fn literal(&self, plugin: &dyn DatabasePlugin, value: &SourceValue, coercion: Coercion) -> Result<String> {
+        let text = match value {
+            SourceValue::Null => return Ok("NULL".to_string()),
+            SourceValue::Literal(literal) => return Ok(literal.clone()),
+            SourceValue::Text(text) => text,
+        };
+        match (coercion, &self.date_format, self.decimal_separator) {
+            (Coercion::Number, _, Some(separator)) => {
+                let number = normalize_decimal(text, separator);
+                if number.parse::<f64>().is_ok() {
+                    return Ok(number);
+                }
+            }
+            (Coercion::Date | Coercion::DateTime, Some(format), _) => {
+                let parsed = parse_date_time(text.trim(), format)
+                    .ok_or_else(|| anyhow!("'{}' does not match date format '{}'", text, format))?;
+                let formatted = if coercion == Coercion::Date {
+                    parsed.format("%Y-%m-%d").to_string()
+                } else {
+                    parsed.format("%Y-%m-%d %H:%M:%S").to_string()
+                };
+                return Ok(format!("'{}'", formatted));
+            }
+            _ => {}
+        }
+        Ok(plugin.string_literal(text))
+    }
+}
+
+/// 解析导入界面里的映射文本，每行一条：
+/// `源列 -> 目标列`、`源列 ->`（跳过）、`目标列 = '常量'`、`目标列 = 表达式`
+pub fn parse_column_mappings(text: &str) -> Result<Vec<ColumnMapping>> {
+    let mut mappings = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || anyhow!("Line {}: expected 'source -> target' or 'target = value'", line_index + 1);
+        // 表达式里可能有 `->`（如 PostgreSQL 的 JSON 运算符），`=` 在前面时按赋值处理
+        let arrow = line.find("->").filter(|arrow| line.find('=').is_none_or(|equals| *arrow < equals));
+        if let Some((source, target)) = arrow.map(|arrow| (&line[..arrow], &line[arrow + 2..])) {
+            let (source, target) = (source.trim(), target.trim());
+            if source.is_empty() {
+                return Err(invalid());
+            }
+            mappings.push(if target.is_empty() {
+                ColumnMapping::Skip { source: source.to_string() }
+            } else {
+                ColumnMapping::Column { source: source.to_string(), target: target.to_string() }
+            });
+        } else if let Some((target, value)) = line.split_once('=') {
+            let (target, value) = (target.trim().to_string(), value.trim());
+            if target.is_empty() || value.is_empty() {
+                return Err(invalid());
+            }
+            // 单引号包起来的是常量，其余按 SQL 表达式原样写入
+            mappings.push(match value.strip_prefix('\'').and_then(|rest| rest.strip_suffix('\'')) {
+                Some(constant) => ColumnMapping::Constant { target, value: constant.replace("''", "'") },
+                None => ColumnMapping::Expression { target, sql: value.to_string() },
+            });
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(mappings)
+}
+
+fn coercion_for_type(data_type: &str) -> Coercion {
+    let data_type = data_type.to_ascii_lowercase();
+    let base = data_type.split(['(', ' ']).next().unwrap_or_default();
+    match base {
+        "date" => Coercion::Date,
+        "datetime" | "datetime2" | "smalldatetime" | "timestamp" | "timestamptz" | "datetime64" => Coercion::DateTime,
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2" | "int4" | "int8"
+        | "decimal" | "numeric" | "number" | "float" | "float4" | "float8" | "double" | "real" | "money"
+        | "float32" | "float64" => Coercion::Number,
+        _ => Coercion::None,
+    }
+}
+
+/// `separator` 是小数点，另一个符号和空格当作千位分隔符去掉
+fn normalize_decimal(text: &str, separator: char) -> String {
+    let grouping = if separator == ',' { '.' } else { ',' };
+    text.trim()
+        .chars()
+        .filter(|c| *c != grouping && !c.is_whitespace())
+        .map(|c| if c == separator { '.' } else { c })
+        .collect()
+}
+
+/// 格式里没有时间部分时按当天零点处理
+fn parse_date_time(text: &str, format: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(text, format)
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text, format).ok().and_then(|date| date.and_hms_opt(0, 0, 0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn text(value: &str) -> SourceValue {
+        SourceValue::Text(value.to_string())
+    }
+
+    fn plugin() -> crate::postgresql::PostgresPlugin {
+        crate::postgresql::PostgresPlugin::new()
+    }
+
+    #[test]
+    fn test_parse_column_mappings() {
+        let mappings = parse_column_mappings("Name -> name\n\n  Notes ->  \nregion = 'it''s EU'\ncreated_at = CURRENT_TIMESTAMP").unwrap();
+        assert_eq!(mappings, vec![
+            ColumnMapping::Column { source: "Name".to_string(), target: "name".to_string() },
+            ColumnMapping::Skip { source: "Notes".to_string() },
+            ColumnMapping::Constant { target: "region".to_string(), value: "it's EU".to_string() },
+            ColumnMapping::Expression { target: "created_at".to_string(), sql: "CURRENT_TIMESTAMP".to_string() },
+        ]);
+        assert_eq!(
+            parse_column_mappings("city = payload->>'city'").unwrap(),
+            vec![ColumnMapping::Expression { target: "city".to_string(), sql: "payload->>'city'".to_string() }]
+        );
+        assert!(parse_column_mappings("just a name").is_err());
+        assert!(parse_column_mappings("-> target").is_err());
+    }
+
+    #[test]
+    fn test_mapping_renames_skips_and_adds_columns() {
+        let config = ImportConfig {
+            column_mappings: parse_column_mappings("Full Name -> name\nNotes ->\nregion = 'EU'\ncreated_at = NOW()").unwrap(),
+            ..Default::default()
+        };
+        let mapper = ColumnMapper::build(&plugin(), &config, &columns(&["id", "Full Name", "Notes"]), &[]).unwrap();
+        assert_eq!(mapper.target_columns(), columns(&["id", "name", "region", "created_at"]).as_slice());
+        assert_eq!(
+            mapper.map_row(&plugin(), &[SourceValue::Literal("1".to_string()), text("O'Neil"), text("x")]).unwrap(),
+            vec!["1", "'O''Neil'", "'EU'", "NOW()"]
+        );
+        // 短行缺的列按 NULL 写入
+        assert_eq!(mapper.map_row(&plugin(), &[text("2")]).unwrap(), vec!["'2'", "NULL", "'EU'", "NOW()"]);
+
+        // MySQL 里反斜杠是转义符，文本要按目标库的方言转义
+        let mysql = crate::mysql::MySqlPlugin::new();
+        let mapper = ColumnMapper::build(&mysql, &config, &columns(&["id", "Full Name", "Notes"]), &[]).unwrap();
+        assert_eq!(mapper.map_row(&mysql, &[text("3"), text("a\\' OR 1=1 -- "), text("x")]).unwrap()[1], "'a\\\\'' OR 1=1 -- '");
+    }
+
+    #[test]
+    fn test_invalid_mappings_are_rejected() {
+        let missing = ImportConfig {
+            column_mappings: vec![ColumnMapping::Skip { source: "nope".to_string() }],
+            ..Default::default()
+        };
+        assert!(ColumnMapper::build(&plugin(), &missing, &columns(&["id"]), &[]).is_err());
+
+        let duplicate = ImportConfig {
+            column_mappings: vec![ColumnMapping::Column { source: "a".to_string(), target: "B".to_string() }],
+            ..Default::default()
+        };
+        assert!(ColumnMapper::build(&plugin(), &duplicate, &columns(&["a", "b"]), &[]).is_err());
+
+        let nothing = ImportConfig {
+            column_mappings: vec![ColumnMapping::Skip { source: "a".to_string() }],
+            ..Default::default()
+        };
+        assert!(ColumnMapper::build(&plugin(), &nothing, &columns(&["a"]), &[]).is_err());
+    }
+
+    #[test]
+    fn test_type_hints_coerce_values() {
+        let config = ImportConfig {
+            date_format: Some("%d/%m/%Y".to_string()),
+            decimal_separator: Some(','),
+            ..Default::default()
+        };
+        let types = vec![
+            ("price".to_string(), "DECIMAL(10,2)".to_string()),
+            ("born".to_string(), "date".to_string()),
+            ("seen".to_string(), "timestamp without time zone".to_string()),
+            ("label".to_string(), "varchar(20)".to_string()),
+        ];
+        let mapper = ColumnMapper::build(&plugin(), &config, &columns(&["price", "born", "seen", "label"]), &types).unwrap();
+        assert_eq!(
+            mapper.map_row(&plugin(), &[text("1.234,50"), text("31/12/2024"), text("01/02/2024"), text("3,5")]).unwrap(),
+            vec!["1234.50", "'2024-12-31'", "'2024-02-01 00:00:00'", "'3,5'"]
+        );
+        assert!(mapper.map_row(&plugin(), &[text("1"), text("2024-12-31"), SourceValue::Null, SourceValue::Null]).is_err());
+        // 不是数字的值原样交给数据库报错
+        assert_eq!(mapper.map_row(&plugin(), &[text("n/a"), SourceValue::Null, SourceValue::Null, SourceValue::Null]).unwrap()[0], "'n/a'");
+    }
+}
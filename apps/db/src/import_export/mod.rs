@@ -1,3 +1,4 @@
+use std::io::{BufRead, Write};
 use std::sync::Arc;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -9,10 +10,28 @@ use crate::connection::DbConnection;
 pub mod formats;
 pub mod importer;
 pub mod exporter;
+pub mod grid_export;
+pub mod anonymize;
+pub mod archive;
+pub mod batch;
+pub mod chunked;
+pub mod encoding;
+pub mod mapping;
+pub mod reader;
+pub mod sniff;
 
 // Re-exports
 pub use importer::DataImporter;
 pub use exporter::DataExporter;
+pub use grid_export::{ClipboardFormat, GridExportData, GridExportScope, GridExportTarget};
+pub use anonymize::{build_masking_updates, AnonymizeConfig, ColumnMasking, MaskingRule};
+pub use archive::{archive_file_name, ArchiveColumn, ArchiveManifest, ArchiveTable, MANIFEST_FILE_NAME};
+pub use batch::{BatchInserter, DEFAULT_IMPORT_BATCHES_PER_COMMIT, DEFAULT_IMPORT_BATCH_ROWS};
+pub use chunked::{TableChunk, TableChunkReader, DEFAULT_EXPORT_CHUNK_ROWS};
+pub use mapping::{parse_column_mappings, ColumnMapper, ColumnMapping, SourceValue};
+pub use encoding::{TextEncoding, TranscodingReader, TranscodingWriter};
+pub use reader::LineReader;
+pub use sniff::{preview_csv, read_csv_sample, sniff_csv, sniff_encoding, CSV_SAMPLE_BYTES};
 use crate::DatabasePlugin;
 
 /// 数据格式枚举
@@ -21,6 +40,10 @@ pub enum DataFormat {
     Sql,
     Json,
     Csv,
+    /// 只支持导出
+    Yaml,
+    /// 二进制格式，每个表一个文件，多个表通过 `DataExporter::export_files` 或 zip 包导出
+    Avro,
 }
 
 impl DataFormat {
@@ -29,6 +52,8 @@ impl DataFormat {
             "sql" => Some(Self::Sql),
             "json" => Some(Self::Json),
             "csv" => Some(Self::Csv),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "avro" => Some(Self::Avro),
             _ => None,
         }
     }
@@ -38,6 +63,39 @@ impl DataFormat {
             Self::Sql => "sql",
             Self::Json => "json",
             Self::Csv => "csv",
+            Self::Yaml => "yaml",
+            Self::Avro => "avro",
+        }
+    }
+}
+
+/// 由数据库服务端直接生成的导出格式，对应 ClickHouse 的 `FORMAT` 子句
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NativeExportFormat {
+    CsvWithNames,
+    JsonEachRow,
+    Parquet,
+}
+
+impl NativeExportFormat {
+    pub fn all() -> Vec<Self> {
+        vec![Self::CsvWithNames, Self::JsonEachRow, Self::Parquet]
+    }
+
+    /// `FORMAT` 子句中使用的格式名
+    pub fn format_name(&self) -> &'static str {
+        match self {
+            Self::CsvWithNames => "CSVWithNames",
+            Self::JsonEachRow => "JSONEachRow",
+            Self::Parquet => "Parquet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::CsvWithNames => "csv",
+            Self::JsonEachRow => "jsonl",
+            Self::Parquet => "parquet",
         }
     }
 }
@@ -62,6 +120,117 @@ impl Default for CsvImportConfig {
     }
 }
 
+/// CSV 导出时哪些字段加文本识别符
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvQuotePolicy {
+    /// 只有包含分隔符、识别符或换行的字段加引号
+    #[default]
+    Needed,
+    /// 所有字段和表头都加引号
+    Always,
+    /// 数字以外的字段都加引号，NULL 仍写为空
+    NonNumeric,
+    /// 从不加引号，字段中的分隔符和换行原样写出
+    Never,
+}
+
+impl CsvQuotePolicy {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Needed, Self::Always, Self::NonNumeric, Self::Never]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Needed => "必要时",
+            Self::Always => "全部",
+            Self::NonNumeric => "非数字",
+            Self::Never => "从不",
+        }
+    }
+}
+
+/// CSV导出配置，分隔符为制表符时即为 TSV
+#[derive(Debug, Clone)]
+pub struct CsvExportConfig {
+    pub field_delimiter: char,
+    pub text_qualifier: char,
+    pub quote_policy: CsvQuotePolicy,
+    pub record_terminator: String,
+    /// 在文件开头写入 UTF-8 BOM，Excel 靠它识别中文等非 ASCII 内容的编码；
+    /// 导出为其他编码时不写
+    pub include_bom: bool,
+}
+
+impl Default for CsvExportConfig {
+    fn default() -> Self {
+        Self {
+            field_delimiter: ',',
+            text_qualifier: '"',
+            quote_policy: CsvQuotePolicy::Needed,
+            record_terminator: "\n".to_string(),
+            include_bom: false,
+        }
+    }
+}
+
+/// SQL 导出时每条多行 INSERT 默认包含的行数
+pub const DEFAULT_SQL_EXPORT_ROWS_PER_INSERT: usize = 100;
+
+/// SQL 导出的 INSERT 在目标表已有相同主键或唯一键的行时怎么处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SqlConflictMode {
+    /// 普通 INSERT，冲突时由导入方报错
+    #[default]
+    Error,
+    /// 跳过已存在的行（`INSERT IGNORE`、`ON CONFLICT DO NOTHING`）
+    Ignore,
+    /// 用导出的值覆盖已存在的行（`ON DUPLICATE KEY UPDATE`、`ON CONFLICT DO UPDATE`）
+    Update,
+}
+
+impl SqlConflictMode {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Error, Self::Ignore, Self::Update]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Error => "报错",
+            Self::Ignore => "跳过",
+            Self::Update => "覆盖",
+        }
+    }
+}
+
+/// 数据库处理冲突行的 INSERT 写法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsertConflictClause {
+    /// 代替 `INSERT` 的关键字，例如 `INSERT IGNORE`
+    pub keyword: String,
+    /// 接在 VALUES 列表之后，为空或以空格开头，例如 ` ON CONFLICT DO NOTHING`
+    pub suffix: String,
+}
+
+/// SQL导出配置
+#[derive(Debug, Clone)]
+pub struct SqlExportConfig {
+    /// 每条 INSERT 包含的行数，不超过数据库允许的上限，为 1 时逐行写出
+    pub rows_per_insert: usize,
+    pub conflict_mode: SqlConflictMode,
+    /// 在脚本前后关闭、恢复外键检查，导入时不必按依赖顺序建表和写数据
+    pub disable_foreign_keys: bool,
+}
+
+impl Default for SqlExportConfig {
+    fn default() -> Self {
+        Self {
+            rows_per_insert: DEFAULT_SQL_EXPORT_ROWS_PER_INSERT,
+            conflict_mode: SqlConflictMode::Error,
+            disable_foreign_keys: false,
+        }
+    }
+}
+
 /// 导入配置
 #[derive(Debug, Clone)]
 pub struct ImportConfig {
@@ -72,6 +241,20 @@ pub struct ImportConfig {
     pub use_transaction: bool,
     pub truncate_before_import: bool,
     pub csv_config: Option<CsvImportConfig>,
+    /// 由服务端缓冲合并写入（ClickHouse async_insert），只对支持的数据库生效
+    pub async_insert: bool,
+    /// CSV、JSON、Avro 导入时每条多行 INSERT 包含的行数
+    pub batch_size: usize,
+    /// `use_transaction` 开启时每执行多少条 INSERT 提交一次事务
+    pub batches_per_commit: usize,
+    /// 表头和目标表列名对不上时的对应关系，为空时按同名导入
+    pub column_mappings: Vec<ColumnMapping>,
+    /// 源文件日期的 chrono 格式，例如 `%d/%m/%Y`，写入日期和时间戳列前转换
+    pub date_format: Option<String>,
+    /// 源文件数字的小数点，例如 `,`，写入数值列前转换
+    pub decimal_separator: Option<char>,
+    /// SQL、JSON、CSV 源文件的编码，Avro 中的字符串总是 UTF-8
+    pub encoding: TextEncoding,
 }
 
 impl Default for ImportConfig {
@@ -84,6 +267,13 @@ impl Default for ImportConfig {
             use_transaction: true,
             truncate_before_import: false,
             csv_config: None,
+            async_insert: false,
+            batch_size: DEFAULT_IMPORT_BATCH_ROWS,
+            batches_per_commit: DEFAULT_IMPORT_BATCHES_PER_COMMIT,
+            column_mappings: Vec::new(),
+            date_format: None,
+            decimal_separator: None,
+            encoding: TextEncoding::Utf8,
         }
     }
 }
@@ -98,6 +288,12 @@ pub struct ExportConfig {
     pub include_data: bool,
     pub where_clause: Option<String>,
     pub limit: Option<usize>,
+    pub csv_config: Option<CsvExportConfig>,
+    pub sql_config: Option<SqlExportConfig>,
+    /// 每次查询读取的行数，导出时内存中最多保留这么多行
+    pub chunk_size: usize,
+    /// 写入文件时使用的编码，Avro 中的字符串总是 UTF-8
+    pub encoding: TextEncoding,
 }
 
 impl Default for ExportConfig {
@@ -110,6 +306,10 @@ impl Default for ExportConfig {
             include_data: true,
             where_clause: None,
             limit: None,
+            csv_config: None,
+            sql_config: None,
+            chunk_size: DEFAULT_EXPORT_CHUNK_ROWS,
+            encoding: TextEncoding::Utf8,
         }
     }
 }
@@ -127,11 +327,19 @@ pub struct ImportResult {
 #[derive(Debug, Clone)]
 pub struct ExportResult {
     pub success: bool,
+    /// 导出到 writer 或文件时为空
     pub output: String,
     pub rows_exported: u64,
     pub elapsed_ms: u128,
 }
 
+/// 服务端格式导出的单个表文件
+#[derive(Debug, Clone)]
+pub struct NativeExportFile {
+    pub table: String,
+    pub data: Vec<u8>,
+}
+
 /// 导出进度事件
 #[derive(Debug, Clone)]
 pub enum ExportProgressEvent {
@@ -149,6 +357,11 @@ pub enum ExportProgressEvent {
     FetchingData {
         table: String,
     },
+    /// 写完一块数据，`rows` 为这一块的行数
+    DataProgress {
+        table: String,
+        rows: u64,
+    },
     DataExported {
         table: String,
         rows: u64,
@@ -186,6 +399,7 @@ pub enum ImportProgressEvent {
     ExecutingStatement {
         file: String,
         statement_index: usize,
+        /// 边读边执行，这里是目前已经读到的语句数
         total_statements: usize,
     },
     StatementExecuted {
@@ -219,7 +433,9 @@ pub trait FormatHandler: Send + Sync {
         connection: &dyn DbConnection,
         config: &ImportConfig,
         data: &str,
-    ) -> Result<ImportResult>;
+    ) -> Result<ImportResult> {
+        self.import_with_progress(plugin, connection, config, data, "", None).await
+    }
 
     /// 导入数据（带进度回调）
     async fn import_with_progress(
@@ -231,19 +447,32 @@ pub trait FormatHandler: Send + Sync {
         file_name: &str,
         progress_tx: Option<ImportProgressSender>,
     ) -> Result<ImportResult> {
-        let _ = (file_name, progress_tx);
-        self.import(plugin, connection, config, data).await
+        let mut reader = data.as_bytes();
+        self.import_from_reader(plugin, connection, config, &mut reader, file_name, progress_tx).await
     }
 
-    /// 导出数据
+    /// 从 `reader` 边读边导入，内存占用只取决于批大小，不随文件大小增长
+    async fn import_from_reader(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ImportConfig,
+        reader: &mut (dyn BufRead + Send),
+        file_name: &str,
+        progress_tx: Option<ImportProgressSender>,
+    ) -> Result<ImportResult>;
+
+    /// 导出数据，结果全部放在 `ExportResult::output` 中
     async fn export(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
         connection: &dyn DbConnection,
         config: &ExportConfig,
-    ) -> Result<ExportResult>;
+    ) -> Result<ExportResult> {
+        self.export_with_progress(plugin, connection, config, None).await
+    }
 
-    /// 导出数据（带进度回调）
+    /// 导出数据（带进度回调），`output` 是字符串，忽略 `ExportConfig::encoding`
     async fn export_with_progress(
         &self,
         plugin: Arc<dyn DatabasePlugin>,
@@ -251,7 +480,23 @@ pub trait FormatHandler: Send + Sync {
         config: &ExportConfig,
         progress_tx: Option<ExportProgressSender>,
     ) -> Result<ExportResult> {
-        let _ = progress_tx;
-        self.export(plugin, connection, config).await
+        let config = ExportConfig {
+            encoding: TextEncoding::Utf8,
+            ..config.clone()
+        };
+        let mut buffer = Vec::new();
+        let mut result = self.export_to_writer(plugin, connection, &config, &mut buffer, progress_tx).await?;
+        result.output = String::from_utf8(buffer)?;
+        Ok(result)
     }
+
+    /// 按 `ExportConfig::chunk_size` 分块查询并写入 `writer`，内存占用不随表的行数增长
+    async fn export_to_writer(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        connection: &dyn DbConnection,
+        config: &ExportConfig,
+        writer: &mut (dyn Write + Send),
+        progress_tx: Option<ExportProgressSender>,
+    ) -> Result<ExportResult>;
 }
@@ -0,0 +1,57 @@
+use std::io::BufRead;
+
+use anyhow::{anyhow, Result};
+
+/// 逐行读取导入文件，行尾的 `\n` 或 `\r\n` 不包含在返回的行中
+pub struct LineReader<'a> {
+    reader: &'a mut (dyn BufRead + Send),
+    line_number: usize,
+}
+
+impl<'a> LineReader<'a> {
+    pub fn new(reader: &'a mut (dyn BufRead + Send)) -> Self {
+        Self { reader, line_number: 0 }
+    }
+
+    /// 返回行号（从 1 开始）和行内容，读到文件末尾时返回 None
+    pub fn next_line(&mut self) -> Result<Option<(usize, String)>> {
+        let mut line = String::new();
+        let read = self.reader.read_line(&mut line)
+            .map_err(|e| anyhow!("Read line {} failed: {}", self.line_number + 1, e))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some((self.line_number, line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_without_terminators() {
+        let mut data: &[u8] = b"a,b\r\n1,2\n\nlast";
+        let mut lines = LineReader::new(&mut data);
+        assert_eq!(lines.next_line().unwrap(), Some((1, "a,b".to_string())));
+        assert_eq!(lines.next_line().unwrap(), Some((2, "1,2".to_string())));
+        assert_eq!(lines.next_line().unwrap(), Some((3, String::new())));
+        assert_eq!(lines.next_line().unwrap(), Some((4, "last".to_string())));
+        assert_eq!(lines.next_line().unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_an_error() {
+        let mut data: &[u8] = b"ok\n\xff\xfe\n";
+        let mut lines = LineReader::new(&mut data);
+        assert!(lines.next_line().unwrap().is_some());
+        assert!(lines.next_line().is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+use crate::import_export::formats::CsvFormatHandler;
+use crate::import_export::{CsvImportConfig, TextEncoding};
+
+/// 识别 CSV 格式时读取的文件开头字节数
+pub const CSV_SAMPLE_BYTES: usize = 64 * 1024;
+
+/// 参与判断分隔符和表头的最多行数
+const SAMPLE_LINES: usize = 50;
+
+const CANDIDATE_DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+/// 读取文件开头的一段用于识别格式和预览
+pub fn read_csv_sample(path: &Path) -> Result<Vec<u8>> {
+    let file = File::open(path).map_err(|e| anyhow!("Open {} failed: {}", path.display(), e))?;
+    let mut sample = Vec::with_capacity(CSV_SAMPLE_BYTES);
+    file.take(CSV_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+    Ok(sample)
+}
+
+/// 按 `encoding` 解码样本后猜出分隔符、文本识别符、换行符和是否有表头，作为导入界面的初始值
+pub fn sniff_csv(sample: &[u8], encoding: TextEncoding) -> CsvImportConfig {
+    let text = encoding.decode_lossy(sample);
+    let lines = sample_lines(&text, sample.len() >= CSV_SAMPLE_BYTES);
+
+    let text_qualifier = Some(detect_qualifier(&lines));
+    let field_delimiter = detect_delimiter(&lines, text_qualifier);
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|line| CsvFormatHandler::parse_csv_line_with_config(line, field_delimiter, text_qualifier))
+        .collect();
+
+    CsvImportConfig {
+        field_delimiter,
+        text_qualifier,
+        has_header: detect_header(&rows),
+        record_terminator: if text.contains("\r\n") { "\r\n" } else { "\n" }.to_string(),
+    }
+}
+
+/// 按当前配置解析样本的前几行，供界面预览
+pub fn preview_csv(sample: &[u8], encoding: TextEncoding, config: &CsvImportConfig, max_rows: usize) -> Vec<Vec<String>> {
+    let text = encoding.decode_lossy(sample);
+    sample_lines(&text, sample.len() >= CSV_SAMPLE_BYTES)
+        .into_iter()
+        .take(max_rows)
+        .map(|line| CsvFormatHandler::parse_csv_line_with_config(line, config.field_delimiter, config.text_qualifier))
+        .collect()
+}
+
+/// 样本被截断时最后一行可能不完整，不参与判断
+fn sample_lines(text: &str, truncated: bool) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+    if truncated && !text.ends_with('\n') {
+        lines.pop();
+    }
+    lines.truncate(SAMPLE_LINES);
+    lines
+}
+
+/// 猜测文件编码：合法的 UTF-8 优先，其次是 GBK，都不像时按 Latin-1。
+/// Shift-JIS 和 GBK 的字节分布太接近，需要用户手动选择
+pub fn sniff_encoding(sample: &[u8]) -> TextEncoding {
+    match std::str::from_utf8(sample) {
+        Ok(_) => return TextEncoding::Utf8,
+        // 只是样本末尾截断了一个多字节字符
+        Err(e) if e.error_len().is_none() => return TextEncoding::Utf8,
+        Err(_) => {}
+    }
+    if looks_like_gbk(sample) {
+        TextEncoding::Gbk
+    } else {
+        TextEncoding::Latin1
+    }
+}
+
+/// Latin-1 文本里的重音字母后面通常跟着 ASCII 字母，也常能凑成合法的 GBK 双字节，
+/// 所以只有高位字节大多成对出现（常用汉字两个字节都不小于 0xA1）时才认为是 GBK
+fn looks_like_gbk(sample: &[u8]) -> bool {
+    let mut paired = 0usize;
+    let mut single = 0usize;
+    let mut index = 0;
+    while index < sample.len() {
+        let byte = sample[index];
+        if byte < 0x80 {
+            index += 1;
+            continue;
+        }
+        match sample.get(index + 1) {
+            Some(next) if (0x81..=0xFE).contains(&byte) && *next >= 0xA1 && *next != 0xFF => paired += 1,
+            // 样本末尾截断的半个字符
+            None => {}
+            Some(_) => single += 1,
+        }
+        index += 2;
+    }
+    paired > 0 && single * 10 <= paired
+}
+
+/// 行首或分隔符后面紧跟的引号更多的那个就是识别符；都没有时用双引号
+fn detect_qualifier(lines: &[&str]) -> char {
+    let count = |quote: char| {
+        lines
+            .iter()
+            .map(|line| {
+                let mut previous = None;
+                line.chars()
+                    .filter(|current| {
+                        let at_field_start = previous.is_none_or(|before| CANDIDATE_DELIMITERS.contains(&before));
+                        previous = Some(*current);
+                        *current == quote && at_field_start
+                    })
+                    .count()
+            })
+            .sum::<usize>()
+    };
+    if count('\'') > count('"') { '\'' } else { '"' }
+}
+
+/// 选每行字段数最一致的分隔符，一致程度相同时选字段更多的
+fn detect_delimiter(lines: &[&str], qualifier: Option<char>) -> char {
+    let mut best = (',', 0usize, 0usize);
+    for delimiter in CANDIDATE_DELIMITERS {
+        let counts: Vec<usize> = lines
+            .iter()
+            .map(|line| CsvFormatHandler::parse_csv_line_with_config(line, delimiter, qualifier).len())
+            .collect();
+        let Some(mode) = most_common(&counts) else {
+            continue;
+        };
+        if mode < 2 {
+            continue;
+        }
+        let consistent = counts.iter().filter(|count| **count == mode).count();
+        if (consistent, mode) > (best.1, best.2) {
+            best = (delimiter, consistent, mode);
+        }
+    }
+    best.0
+}
+
+fn most_common(counts: &[usize]) -> Option<usize> {
+    let mut best: Option<(usize, usize)> = None;
+    for count in counts {
+        let frequency = counts.iter().filter(|other| *other == count).count();
+        if best.is_none_or(|(_, best_frequency)| frequency > best_frequency) {
+            best = Some((*count, frequency));
+        }
+    }
+    best.map(|(count, _)| count)
+}
+
+/// 每一列投票：数据行都是数字而第一行不是，或者数据行长度一致而第一行不同，
+/// 说明第一行是表头。只有一行时按有表头处理
+fn detect_header(rows: &[Vec<String>]) -> bool {
+    let Some((first, data)) = rows.split_first() else {
+        return true;
+    };
+    if data.is_empty() {
+        return true;
+    }
+
+    let is_number = |value: &str| value.trim().parse::<f64>().is_ok();
+    let mut votes = 0i32;
+    for (column, header) in first.iter().enumerate() {
+        let values: Vec<&str> = data
+            .iter()
+            .filter_map(|row| row.get(column).map(String::as_str))
+            .filter(|value| !value.is_empty())
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        if values.iter().all(|value| is_number(value)) {
+            votes += if is_number(header) { -1 } else { 1 };
+            continue;
+        }
+        let length = values[0].chars().count();
+        if values.iter().all(|value| value.chars().count() == length) {
+            votes += if header.chars().count() == length { -1 } else { 1 };
+        }
+    }
+    votes > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_semicolon_with_header() {
+        let config = sniff_csv("id;name;price\r\n1;\"Smith; John\";3,50\r\n2;Lee;12,00\r\n".as_bytes(), TextEncoding::Utf8);
+        assert_eq!(config.field_delimiter, ';');
+        assert_eq!(config.text_qualifier, Some('"'));
+        assert!(config.has_header);
+        assert_eq!(config.record_terminator, "\r\n");
+    }
+
+    #[test]
+    fn test_sniff_tab_without_header() {
+        let config = sniff_csv(b"1\t'a'\t2024\n2\t'b'\t2025\n3\t'c'\t2026\n", TextEncoding::Utf8);
+        assert_eq!(config.field_delimiter, '\t');
+        assert_eq!(config.text_qualifier, Some('\''));
+        assert!(!config.has_header);
+        assert_eq!(config.record_terminator, "\n");
+    }
+
+    #[test]
+    fn test_sniff_encoding() {
+        // "名称,数量" in GBK
+        assert_eq!(sniff_encoding(b"\xc3\xfb\xb3\xc6,\xca\xfd\xc1\xbf\n"), TextEncoding::Gbk);
+        assert_eq!(sniff_encoding(b"M\xfcller,Caf\xe9\n"), TextEncoding::Latin1);
+        assert_eq!(sniff_encoding("名称,数量\n".as_bytes()), TextEncoding::Utf8);
+        // 样本在多字节字符中间截断
+        assert_eq!(sniff_encoding(&"名称".as_bytes()[..4]), TextEncoding::Utf8);
+    }
+
+    #[test]
+    fn test_preview_ignores_truncated_last_line() {
+        let config = CsvImportConfig::default();
+        let mut sample = b"a,b\n1,2\n".to_vec();
+        sample.resize(CSV_SAMPLE_BYTES, b'x');
+        assert_eq!(
+            preview_csv(&sample, TextEncoding::Utf8, &config, 5),
+            vec![vec!["a".to_string(), "b".to_string()], vec!["1".to_string(), "2".to_string()]]
+        );
+    }
+}
@@ -5,6 +5,17 @@ pub mod connection;
 pub mod executor;
 pub mod import_export;
 pub mod sql_format;
+pub mod trash;
+pub mod extension;
+pub mod data_diff;
+pub mod tls;
+pub mod proxy;
+pub mod cloud_auth;
+pub mod connection_url;
+pub mod reconnect;
+pub mod server_status;
+pub mod session_variable;
+pub mod session_cleanup;
 
 // Database implementations
 pub mod mysql;
@@ -13,6 +24,14 @@ pub mod mssql;
 pub mod sqlite;
 pub mod oracle;
 pub mod clickhouse;
+pub mod odbc;
+pub mod mongodb;
+pub mod redis;
+pub mod duckdb;
+pub mod cockroachdb;
+pub mod tidb;
+pub mod trino;
+pub mod elasticsearch;
 pub mod sql_editor;
 
 // Re-exports
@@ -23,3 +42,10 @@ pub use connection::*;
 pub use executor::*;
 pub use import_export::*;
 pub use sql_format::*;
+pub use trash::*;
+pub use extension::*;
+pub use data_diff::*;
+pub use reconnect::*;
+pub use server_status::*;
+pub use session_variable::*;
+pub use session_cleanup::*;
@@ -6,19 +6,47 @@ use crate::sqlite::SqlitePlugin;
 use crate::clickhouse::ClickHousePlugin;
 use crate::mssql::MsSqlPlugin;
 use crate::oracle::OraclePlugin;
+use crate::odbc::OdbcPlugin;
+use crate::mongodb::MongoDbPlugin;
+use crate::redis::RedisPlugin;
+use crate::duckdb::DuckDbPlugin;
+use crate::cockroachdb::CockroachPlugin;
+use crate::tidb::TiDbPlugin;
+use crate::trino::TrinoPlugin;
+use crate::elasticsearch::ElasticsearchPlugin;
 use crate::import_export::{DataExporter, DataImporter, ExportConfig, ExportResult, ImportConfig, ImportResult, ExportProgressSender};
-use crate::{DbNode, DbNodeType, ExecOptions, SqlResult, TableSaveResponse};
+use crate::trash::{trash_table_name, TrashEntry};
+use crate::extension::{extension_types_from_query_result, ExtensionInfo};
+use crate::server_status::{ConnectionHealth, LastError, PoolUsage, ServerStatus};
+use crate::session_variable::SessionVariable;
+use crate::session_cleanup::{CleanupEvent, CleanupLog, CleanupReason, IdleCleanupPolicy};
+use crate::reconnect::{backoff_delay, ConnectionStatus, ConnectionStatuses, HEALTH_CHECK_IDLE, MAX_RECONNECT_ATTEMPTS, PING_TIMEOUT};
+use crate::{DataTypeInfo, DbNode, DbNodeType, ExecOptions, QueryResult, SqlErrorInfo, SqlResult, StatementHook, StatementHookContext, StatementPipeline, TableSaveResponse};
 use tokio::sync::mpsc;
 use one_core::gpui_tokio::Tokio;
 use one_core::storage::{DatabaseType, DbConnectionConfig, GlobalStorageState};
 use gpui::{AppContext, AsyncApp, Global};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use chrono::Local;
 
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
+/// Maximum number of values returned by `sample_column_values`
+pub const COLUMN_SAMPLE_LIMIT: usize = 20;
+
+/// Connection parameter (extra_params) with statements run on every new physical connection,
+/// e.g. `SET search_path`, `SET time_zone` or `SET ROLE`
+pub const INIT_SQL_PARAM: &str = "init_sql";
+
+/// How often the background task looks for sessions due for a keepalive ping
+const KEEPALIVE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// How often the background task closes expired sessions
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Macro to reduce boilerplate for plugin operations with session management
 macro_rules! with_plugin_session {
     ($self:expr, $cx:expr, $connection_id:expr, |$plugin:ident, $conn:ident| $body:expr) => {{
@@ -60,6 +88,14 @@ pub struct DbManager {
     clickhouse: Arc<dyn DatabasePlugin>,
     mssql: Arc<dyn DatabasePlugin>,
     oracle: Arc<dyn DatabasePlugin>,
+    odbc: Arc<dyn DatabasePlugin>,
+    mongodb: Arc<dyn DatabasePlugin>,
+    redis: Arc<dyn DatabasePlugin>,
+    duckdb: Arc<dyn DatabasePlugin>,
+    cockroachdb: Arc<dyn DatabasePlugin>,
+    tidb: Arc<dyn DatabasePlugin>,
+    trino: Arc<dyn DatabasePlugin>,
+    elasticsearch: Arc<dyn DatabasePlugin>,
 }
 
 impl DbManager {
@@ -71,6 +107,14 @@ impl DbManager {
             clickhouse: Arc::new(ClickHousePlugin::new()),
             mssql: Arc::new(MsSqlPlugin::new()),
             oracle: Arc::new(OraclePlugin::new()),
+            odbc: Arc::new(OdbcPlugin::new()),
+            mongodb: Arc::new(MongoDbPlugin::new()),
+            redis: Arc::new(RedisPlugin::new()),
+            duckdb: Arc::new(DuckDbPlugin::new()),
+            cockroachdb: Arc::new(CockroachPlugin::new()),
+            tidb: Arc::new(TiDbPlugin::new()),
+            trino: Arc::new(TrinoPlugin::new()),
+            elasticsearch: Arc::new(ElasticsearchPlugin::new()),
         }
     }
 
@@ -82,6 +126,14 @@ impl DbManager {
             DatabaseType::ClickHouse => Ok(Arc::clone(&self.clickhouse)),
             DatabaseType::MSSQL => Ok(Arc::clone(&self.mssql)),
             DatabaseType::Oracle => Ok(Arc::clone(&self.oracle)),
+            DatabaseType::ODBC => Ok(Arc::clone(&self.odbc)),
+            DatabaseType::MongoDB => Ok(Arc::clone(&self.mongodb)),
+            DatabaseType::Redis => Ok(Arc::clone(&self.redis)),
+            DatabaseType::DuckDB => Ok(Arc::clone(&self.duckdb)),
+            DatabaseType::CockroachDB => Ok(Arc::clone(&self.cockroachdb)),
+            DatabaseType::TiDB => Ok(Arc::clone(&self.tidb)),
+            DatabaseType::Trino => Ok(Arc::clone(&self.trino)),
+            DatabaseType::Elasticsearch => Ok(Arc::clone(&self.elasticsearch)),
         }
     }
 }
@@ -101,6 +153,14 @@ impl Clone for DbManager {
             clickhouse: Arc::clone(&self.clickhouse),
             mssql: Arc::clone(&self.mssql),
             oracle: Arc::clone(&self.oracle),
+            odbc: Arc::clone(&self.odbc),
+            mongodb: Arc::clone(&self.mongodb),
+            redis: Arc::clone(&self.redis),
+            duckdb: Arc::clone(&self.duckdb),
+            cockroachdb: Arc::clone(&self.cockroachdb),
+            tidb: Arc::clone(&self.tidb),
+            trino: Arc::clone(&self.trino),
+            elasticsearch: Arc::clone(&self.elasticsearch),
         }
     }
 }
@@ -109,9 +169,13 @@ impl Clone for DbManager {
 struct ConnectionSession {
     connection: Box<dyn DbConnection + Send + Sync>,
     last_active: Instant,
+    /// Kept apart from `last_active` so keepalive pings do not hold off the idle timeout
+    last_keepalive: Instant,
     created_at: Instant,
     session_id: String,
     in_use: bool,
+    /// Set for sessions the user opened by name, which are kept out of the pool
+    name: Option<String>,
 }
 
 impl ConnectionSession {
@@ -120,9 +184,11 @@ impl ConnectionSession {
         Self {
             connection,
             last_active: now,
+            last_keepalive: now,
             created_at: now,
             session_id,
             in_use: false,
+            name: None,
         }
     }
 
@@ -141,14 +207,27 @@ impl ConnectionSession {
     }
 
     fn is_expired(&self, timeout: Duration) -> bool {
-        if self.in_use {
+        // Named sessions hold transactions and temporary tables, only the user closes them
+        if self.in_use || self.name.is_some() {
             return false;
         }
         self.last_active.elapsed() > timeout
     }
 
     fn is_lifetime_expired(&self, max_lifetime: Duration) -> bool {
-        self.created_at.elapsed() > max_lifetime
+        self.name.is_none() && self.created_at.elapsed() > max_lifetime
+    }
+
+    /// Idle and without traffic for longer than the connection's `keepalive_interval` parameter
+    fn needs_keepalive(&self) -> bool {
+        let Some(interval) = self.connection.config()
+            .get_param_as::<u64>("keepalive_interval")
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+        else {
+            return false;
+        };
+        !self.in_use && self.last_active.max(self.last_keepalive).elapsed() >= interval
     }
 
     /// Check if current database matches config database
@@ -188,12 +267,16 @@ impl ConnectionSession {
 pub struct ConnectionManager {
     /// config_id -> list of sessions for that config
     sessions: Arc<RwLock<HashMap<String, Vec<ConnectionSession>>>>,
-    /// Connection idle timeout (default: 5 minutes)
+    /// Idle timeout for connections without their own `idle_timeout` parameter (default: 5 minutes)
     idle_timeout: Duration,
     /// Maximum connection lifetime (default: 30 minutes)
     max_lifetime: Duration,
     /// Session counter for generating unique IDs
     session_counter: Arc<tokio::sync::Mutex<u64>>,
+    /// config_id -> health of the connection, shown on the tree's connection nodes
+    statuses: ConnectionStatuses,
+    /// Sessions closed by the background task, shown in the settings
+    cleanup_log: CleanupLog,
 }
 
 impl ConnectionManager {
@@ -203,6 +286,8 @@ impl ConnectionManager {
             idle_timeout: Duration::from_secs(300),      // 5 minutes
             max_lifetime: Duration::from_secs(1800),     // 30 minutes
             session_counter: Arc::new(tokio::sync::Mutex::new(0)),
+            statuses: ConnectionStatuses::new(),
+            cleanup_log: CleanupLog::new(),
         }
     }
 
@@ -212,6 +297,8 @@ impl ConnectionManager {
             idle_timeout,
             max_lifetime,
             session_counter: Arc::new(tokio::sync::Mutex::new(0)),
+            statuses: ConnectionStatuses::new(),
+            cleanup_log: CleanupLog::new(),
         }
     }
 
@@ -231,18 +318,19 @@ impl ConnectionManager {
         let config_id = config.id.clone();
 
         // Try to acquire an existing session and switch database if needed
-        if let Some(session_id) = self.try_acquire_session(&config).await? {
-            return Ok(session_id);
+        let mut dropped = false;
+        if let Some((session_id, idle)) = self.try_acquire_session(&config).await? {
+            if idle < HEALTH_CHECK_IDLE || self.ping_session(&session_id).await {
+                return Ok(session_id);
+            }
+            warn!("Session {} lost its connection, reconnecting", session_id);
+            self.statuses.record_error(&config_id, "Connection lost while idle".to_string());
+            let _ = self.close_session(&session_id).await;
+            dropped = true;
         }
 
         let session_id = self.generate_session_id(&config_id).await;
-
-        // Create new connection
-        let plugin = db_manager.get_plugin(&config.database_type)?;
-        let mut connection = plugin.create_connection(config.clone()).await?;
-
-        // Connect to database
-        connection.connect().await?;
+        let connection = self.connect(&config, db_manager, dropped).await?;
         info!("Created new session: {} (database: {:?})", session_id, config.database);
 
         // Store session
@@ -257,6 +345,129 @@ impl ConnectionManager {
         Ok(session_id)
     }
 
+    /// Open a session reserved for the user under `name`. The pool never hands it out and it does
+    /// not expire, so its transaction state and temporary tables last until it is closed.
+    pub async fn create_named_session(
+        &self,
+        config: DbConnectionConfig,
+        db_manager: &DbManager,
+        name: String,
+    ) -> Result<String, DbError> {
+        let config_id = config.id.clone();
+        let session_id = self.generate_session_id(&config_id).await;
+        let connection = self.connect(&config, db_manager, false).await?;
+        info!("Created named session {}: {} (database: {:?})", name, session_id, config.database);
+
+        let mut session = ConnectionSession::new(connection, session_id.clone());
+        session.name = Some(name);
+
+        let mut sessions = self.sessions.write().await;
+        sessions.entry(config_id)
+            .or_insert_with(Vec::new)
+            .push(session);
+
+        Ok(session_id)
+    }
+
+    /// Open a new connection. A connection that was working before is retried with backoff,
+    /// since the server is most likely restarting or the network blipped; a first connection
+    /// fails right away because the error is usually in the configuration.
+    async fn connect(
+        &self,
+        config: &DbConnectionConfig,
+        db_manager: &DbManager,
+        dropped: bool,
+    ) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let plugin = db_manager.get_plugin(&config.database_type)?;
+        let was_connected = dropped || matches!(
+            self.statuses.get(&config.id),
+            Some(ConnectionStatus::Connected | ConnectionStatus::Reconnecting { .. })
+        );
+        let max_attempts = if was_connected { MAX_RECONNECT_ATTEMPTS } else { 1 };
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = async {
+                let mut connection = plugin.create_connection(config.clone()).await?;
+                connection.connect().await?;
+                Ok::<_, DbError>(connection)
+            }
+            .await;
+
+            match result {
+                Ok(mut connection) => {
+                    // Init SQL errors come from the configuration, retrying would not help
+                    if let Err(e) = run_init_sql(connection.as_ref(), plugin.clone()).await {
+                        let _ = connection.disconnect().await;
+                        self.statuses.set(&config.id, ConnectionStatus::Failed(e.to_string()));
+                        return Err(e);
+                    }
+                    self.statuses.set(&config.id, ConnectionStatus::Connected);
+                    return Ok(connection);
+                }
+                Err(e) if attempt < max_attempts => {
+                    let delay = backoff_delay(attempt);
+                    warn!("Reconnect attempt {} for config {} failed: {}, retrying in {:?}", attempt, config.id, e, delay);
+                    self.statuses.set(&config.id, ConnectionStatus::Reconnecting { attempt });
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    self.statuses.set(&config.id, ConnectionStatus::Failed(e.to_string()));
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// Whether a session's connection still answers
+    async fn ping_session(&self, session_id: &str) -> bool {
+        let Ok(mut guard) = self.get_session_connection(session_id).await else {
+            return false;
+        };
+        match guard.connection() {
+            // A dead TCP connection can hang until the OS gives up, while the sessions lock is held
+            Some(connection) => matches!(tokio::time::timeout(PING_TIMEOUT, connection.ping()).await, Ok(Ok(()))),
+            None => false,
+        }
+    }
+
+    /// Health of a connection, None until it has been used
+    pub fn connection_status(&self, config_id: &str) -> Option<ConnectionStatus> {
+        self.statuses.get(config_id)
+    }
+
+    /// Notified whenever a connection's status changes
+    pub fn subscribe_statuses(&self) -> tokio::sync::watch::Receiver<HashMap<String, ConnectionStatus>> {
+        self.statuses.subscribe()
+    }
+
+    /// Most recent connection error, kept after the connection recovers
+    pub fn last_error(&self, config_id: &str) -> Option<LastError> {
+        self.statuses.last_error(config_id)
+    }
+
+    /// Connections with open sessions or a failing status, with their session usage
+    pub async fn open_connections(&self) -> Vec<(String, PoolUsage)> {
+        let sessions = self.sessions.read().await;
+        let mut connections: HashMap<String, PoolUsage> = sessions
+            .iter()
+            .map(|(config_id, list)| {
+                let usage = PoolUsage { in_use: list.iter().filter(|s| s.in_use).count(), total: list.len() };
+                (config_id.clone(), usage)
+            })
+            .collect();
+        for (config_id, status) in self.statuses.all() {
+            if !matches!(status, ConnectionStatus::Connected) {
+                connections.entry(config_id).or_default();
+            }
+        }
+
+        let mut connections: Vec<_> = connections.into_iter().collect();
+        connections.sort_by(|a, b| a.0.cmp(&b.0));
+        connections
+    }
+
     /// Get mutable access to a session's connection
     /// Returns the connection wrapped in the write guard to maintain lock
     pub async fn get_session_connection(
@@ -280,25 +491,26 @@ impl ConnectionManager {
         })
     }
 
-    /// Try to acquire an existing idle session with matching database
+    /// Try to acquire an existing idle session with matching database; returns it with how long it was idle
     async fn try_acquire_session(
         &self,
         config: &DbConnectionConfig,
-    ) -> Result<Option<String>, DbError> {
+    ) -> Result<Option<(String, Duration)>, DbError> {
         let mut sessions = self.sessions.write().await;
 
         if let Some(session_list) = sessions.get_mut(&config.id) {
             // Find an idle session with matching database
             if let Some(session) = session_list.iter_mut().find(|s| {
-                !s.in_use && s.connection.config().database == config.database
+                !s.in_use && s.name.is_none() && s.connection.config().database == config.database
             }) {
+                let idle = session.last_active.elapsed();
                 session.mark_in_use();
 
                 info!(
                     "Reusing session: {} (database: {:?})",
                     session.session_id, config.database
                 );
-                return Ok(Some(session.session_id.clone()));
+                return Ok(Some((session.session_id.clone(), idle)));
             }
         }
 
@@ -306,6 +518,29 @@ impl ConnectionManager {
     }
 }
 
+/// Run the connection's init SQL on a freshly opened connection; a failing statement fails the connection
+async fn run_init_sql(
+    connection: &(dyn DbConnection + Send + Sync),
+    plugin: Arc<dyn DatabasePlugin>,
+) -> Result<(), DbError> {
+    let Some(init_sql) = connection.config()
+        .get_param(INIT_SQL_PARAM)
+        .filter(|sql| !sql.trim().is_empty())
+        .cloned()
+    else {
+        return Ok(());
+    };
+
+    let results = connection.execute(plugin, &init_sql, ExecOptions::default()).await?;
+    match results.into_iter().find(|result| result.is_error()) {
+        Some(SqlResult::Error(error)) => Err(DbError::ConnectionError(format!(
+            "Init SQL failed at `{}`: {}",
+            error.sql, error.message
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// Guard that holds the write lock and provides access to a session's connection
 pub struct SessionConnectionGuard<'a> {
     sessions: tokio::sync::RwLockWriteGuard<'a, HashMap<String, Vec<ConnectionSession>>>,
@@ -422,6 +657,7 @@ impl ConnectionManager {
 
     /// Remove all sessions for a connection config
     pub async fn remove_all_sessions(&self, config_id: &str) {
+        self.statuses.remove(config_id);
         let mut sessions = self.sessions.write().await;
 
         if let Some(mut session_list) = sessions.remove(config_id) {
@@ -433,7 +669,37 @@ impl ConnectionManager {
         }
     }
 
-    /// Clean up expired sessions
+    /// Ping idle sessions of connections with a keepalive interval, so firewalls and NAT devices
+    /// do not silently drop them. Sessions that no longer answer are closed and reconnected on next use.
+    async fn keep_alive_idle_sessions(&self) {
+        let mut sessions = self.sessions.write().await;
+
+        for (config_id, session_list) in sessions.iter_mut() {
+            let mut i = 0;
+            while i < session_list.len() {
+                if !session_list[i].needs_keepalive() {
+                    i += 1;
+                    continue;
+                }
+
+                let ping = tokio::time::timeout(PING_TIMEOUT, session_list[i].connection.ping()).await;
+                if matches!(ping, Ok(Ok(()))) {
+                    session_list[i].last_keepalive = Instant::now();
+                    i += 1;
+                } else {
+                    let mut session = session_list.remove(i);
+                    warn!("Keepalive ping failed for session {} of config {}, closing it", session.session_id, config_id);
+                    self.statuses.record_error(config_id, "Keepalive ping failed".to_string());
+                    self.record_cleanup(config_id, &session, CleanupReason::KeepaliveFailed);
+                    session.close().await;
+                }
+            }
+        }
+
+        sessions.retain(|_, list| !list.is_empty());
+    }
+
+    /// Clean up expired sessions, following each connection's idle cleanup policy
     async fn cleanup_expired_sessions(&self) {
         let mut sessions = self.sessions.write().await;
         let idle_timeout = self.idle_timeout;
@@ -442,11 +708,21 @@ impl ConnectionManager {
         for (config_id, session_list) in sessions.iter_mut() {
             let mut i = 0;
             while i < session_list.len() {
-                let should_remove = session_list[i].is_expired(idle_timeout)
-                    || session_list[i].is_lifetime_expired(max_lifetime);
+                let session = &session_list[i];
+                let reason = match IdleCleanupPolicy::from_config(session.connection.config(), idle_timeout) {
+                    IdleCleanupPolicy::Never => None,
+                    IdleCleanupPolicy::CloseAfter(timeout) if session.is_expired(timeout) => {
+                        Some(CleanupReason::IdleTimeout(session.last_active.elapsed()))
+                    }
+                    IdleCleanupPolicy::CloseAfter(_) if session.is_lifetime_expired(max_lifetime) => {
+                        Some(CleanupReason::LifetimeExceeded(session.created_at.elapsed()))
+                    }
+                    IdleCleanupPolicy::CloseAfter(_) => None,
+                };
 
-                if should_remove {
+                if let Some(reason) = reason {
                     let mut session = session_list.remove(i);
+                    self.record_cleanup(config_id, &session, reason);
                     warn!(
                         "Closing expired session {} for config {} (in_use: {}, idle: {}s, lifetime: {}s)",
                         session.session_id,
@@ -466,6 +742,25 @@ impl ConnectionManager {
         sessions.retain(|_, list| !list.is_empty());
     }
 
+    fn record_cleanup(&self, config_id: &str, session: &ConnectionSession, reason: CleanupReason) {
+        self.cleanup_log.record(CleanupEvent {
+            at: Local::now(),
+            connection_id: config_id.to_string(),
+            connection_name: session.connection.config().name.clone(),
+            session_id: session.session_id.clone(),
+            reason,
+        });
+    }
+
+    /// Sessions recently closed by the background task, newest first
+    pub fn cleanup_events(&self) -> Vec<CleanupEvent> {
+        self.cleanup_log.events()
+    }
+
+    pub fn clear_cleanup_events(&self) {
+        self.cleanup_log.clear();
+    }
+
     /// Get connection statistics
     pub async fn stats(&self) -> ConnectionStats {
         let sessions = self.sessions.read().await;
@@ -492,6 +787,7 @@ impl ConnectionManager {
             .map(|list| {
                 list.iter().map(|s| SessionInfo {
                     session_id: s.session_id.clone(),
+                    name: s.name.clone(),
                     database: s.connection.config().database.clone(),
                     in_use: s.in_use,
                     idle_time: s.last_active.elapsed(),
@@ -515,6 +811,8 @@ impl Clone for ConnectionManager {
             idle_timeout: self.idle_timeout,
             max_lifetime: self.max_lifetime,
             session_counter: Arc::clone(&self.session_counter),
+            statuses: self.statuses.clone(),
+            cleanup_log: self.cleanup_log.clone(),
         }
     }
 }
@@ -531,6 +829,8 @@ pub struct ConnectionStats {
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
     pub session_id: String,
+    /// None for pooled sessions
+    pub name: Option<String>,
     pub database: Option<String>,
     pub in_use: bool,
     pub idle_time: Duration,
@@ -552,6 +852,10 @@ impl ConnectionPool {
         let plugin = self.db_manager.get_plugin(&config.database_type)?;
         let mut connection = plugin.create_connection(config).await?;
         connection.connect().await?;
+        if let Err(e) = run_init_sql(connection.as_ref(), plugin).await {
+            let _ = connection.disconnect().await;
+            return Err(e.into());
+        }
         Ok(Arc::new(RwLock::new(connection)))
     }
 }
@@ -564,6 +868,8 @@ pub struct GlobalDbState {
     pub connection_pool: ConnectionPool,
     /// connection_id -> config mapping
     connections: Arc<RwLock<HashMap<String, DbConnectionConfig>>>,
+    /// connection_id -> hooks registered in addition to the ones enabled by the connection's parameters
+    statement_hooks: Arc<RwLock<HashMap<String, Vec<Arc<dyn StatementHook>>>>>,
 }
 
 impl GlobalDbState {
@@ -576,20 +882,26 @@ impl GlobalDbState {
             connection_manager: manager,
             connection_pool: ConnectionPool::new(db_manager),
             connections: Arc::new(RwLock::new(HashMap::new())),
+            statement_hooks: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    /// Start the cleanup task (should be called after Tokio runtime is available)
+    /// Start the cleanup and keepalive task (should be called after Tokio runtime is available)
     pub fn start_cleanup_task<C>(&self, cx: &mut C) 
     where 
         C: AppContext
     {
         let manager = Arc::new(self.connection_manager.clone());
         Tokio::spawn(cx, async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            let mut interval = tokio::time::interval(KEEPALIVE_CHECK_INTERVAL);
+            let mut last_cleanup = Instant::now();
             loop {
                 interval.tick().await;
-                manager.cleanup_expired_sessions().await;
+                manager.keep_alive_idle_sessions().await;
+                if last_cleanup.elapsed() >= CLEANUP_INTERVAL {
+                    manager.cleanup_expired_sessions().await;
+                    last_cleanup = Instant::now();
+                }
             }
         });
     }
@@ -611,11 +923,16 @@ impl GlobalDbState {
     }
 
 
+    /// Some dialects prefix the statement (e.g. Redis `SELECT <db>`), so an error anywhere wins over the first result
     fn wrapper_result(result: Vec<SqlResult>) -> anyhow::Result<SqlResult> {
-        match result.into_iter().next() {
-            Some(re) => Ok(re),
-            None => Err(anyhow::anyhow!("No result returned")),
+        let mut last = None;
+        for re in result {
+            if re.is_error() {
+                return Ok(re);
+            }
+            last = Some(re);
         }
+        last.ok_or_else(|| anyhow::anyhow!("No result returned"))
     }
     
     pub async fn drop_database(
@@ -712,136 +1029,591 @@ impl GlobalDbState {
         Self::wrapper_result(result)
     }
 
-    /// Register a connection configuration
-    pub async fn register_connection(
+    /// Refresh a materialized view, optionally without locking out readers
+    pub async fn refresh_materialized_view(
         &self,
-        config: DbConnectionConfig,
-    ) {
-        info!("register_connection: id={}, name={}, database_type={:?}",
-            config.id, config.name, config.database_type);
-        let mut connections = self.connections.write().await;
-        connections.insert(config.id.clone(), config);
+        cx: &mut AsyncApp,
+        config_id: String,
+        database: String,
+        schema: Option<String>,
+        view_name: String,
+        concurrently: bool,
+    ) -> anyhow::Result<SqlResult>
+    {
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = plugin.build_refresh_materialized_view_sql(schema.as_deref(), &view_name, concurrently)?;
+        config.database = Some(database);
+
+        let result = self.execute_with_session(cx, config, sql, None).await?;
+
+        Self::wrapper_result(result)
     }
 
-    /// Unregister a connection configuration
-    pub async fn unregister_connection(
+    /// Enable or disable a scheduled event
+    pub async fn set_event_enabled(
         &self,
         cx: &mut AsyncApp,
-        connection_id: String,
-    ) -> anyhow::Result<()>
+        config_id: String,
+        database: String,
+        event_name: String,
+        enabled: bool,
+    ) -> anyhow::Result<SqlResult>
     {
-        let clone_self = self.clone();
-        Tokio::spawn_result(cx, async move {
-            // Close all sessions for this connection
-            clone_self.connection_manager.remove_all_sessions(&connection_id).await;
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = plugin.build_alter_event_status_sql(&database, &event_name, enabled)?;
+        config.database = Some(database);
 
-            // Remove from registry
-            let mut connections = clone_self.connections.write().await;
-            connections.remove(&connection_id);
-            Ok(())
-        })?.await
-    }
+        let result = self.execute_with_session(cx, config, sql, None).await?;
 
+        Self::wrapper_result(result)
+    }
 
-    /// Get all registered connections
-    pub async fn list_connections(
+    /// Drop a scheduled event
+    pub async fn drop_event(
         &self,
         cx: &mut AsyncApp,
-    ) -> anyhow::Result<Vec<DbConnectionConfig>>
+        config_id: String,
+        database: String,
+        event_name: String,
+    ) -> anyhow::Result<SqlResult>
     {
-        let clone_self = self.clone();
-        Tokio::spawn_result(cx, async move {
-            let connections = clone_self.connections.read().await;
-            Ok(connections.values().cloned().collect())
-        })?.await
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = plugin.build_drop_event_sql(&database, &event_name)?;
+        config.database = Some(database);
+
+        let result = self.execute_with_session(cx, config, sql, None).await?;
+
+        Self::wrapper_result(result)
     }
 
-    /// Create a new session for executing queries
-    pub async fn create_session(
+    /// Move a table into the trash schema instead of dropping it
+    pub async fn move_table_to_trash(
         &self,
         cx: &mut AsyncApp,
-        connection_id: String,
-        database: Option<String>,
-    ) -> anyhow::Result<String>
+        config_id: String,
+        database: String,
+        schema: Option<String>,
+        table_name: String,
+    ) -> anyhow::Result<TrashEntry>
     {
-        let clone_self = self.clone();
-        Tokio::spawn_result(cx, async move {
-            let mut config = clone_self.get_config_async(&connection_id).await
-                .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        if !plugin.supports_trash() {
+            return Err(anyhow::anyhow!("{} does not support trash", config.database_type.as_str()));
+        }
 
-            // Override database if specified
-            if let Some(db) = database {
-                config.database = Some(db);
-            }
+        let dropped_at = one_core::storage::now();
+        let entry = TrashEntry {
+            trash_name: trash_table_name(&table_name, dropped_at),
+            original_schema: schema.unwrap_or_else(|| database.clone()),
+            original_name: table_name,
+            dropped_at,
+        };
+        let mut statements = plugin.build_create_trash_sql();
+        statements.extend(plugin.build_move_to_trash_sql(&entry));
 
-            clone_self.connection_manager.create_session(config, &clone_self.db_manager).await
-                .map_err(|e| anyhow::anyhow!("{}", e))
-        })?.await
+        config.database = Some(database);
+        let results = self.execute_with_session(cx, config, join_statements(statements), None).await?;
+        Self::check_results(results)?;
+        Ok(entry)
     }
 
-    /// Execute SQL  (simplified - creates session per execution)
-    pub async fn execute_single(
+    /// List tables in the trash, newest first
+    pub async fn list_trash(
         &self,
         cx: &mut AsyncApp,
-        connection_id: String,
-        script: String,
-        database: Option<String>,
-        opts: Option<ExecOptions>,
-    ) -> anyhow::Result<SqlResult>
+        config_id: String,
+        database: String,
+    ) -> anyhow::Result<Vec<TrashEntry>>
     {
-        let result=  self.execute_script(cx, connection_id, script, database, opts).await?;
-        Self::wrapper_result(result)
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        if !plugin.supports_trash() {
+            return Ok(Vec::new());
+        }
+        config.database = Some(database);
+
+        let exists = self.execute_with_session(cx, config.clone(), plugin.build_trash_exists_sql(), None).await?;
+        let exists = Self::check_results(exists)?
+            .iter()
+            .any(|result| matches!(result, SqlResult::Query(query) if !query.rows.is_empty()));
+        if !exists {
+            return Ok(Vec::new());
+        }
+
+        let opts = ExecOptions { max_rows: None, ..Default::default() };
+        let results = self.execute_with_session(cx, config, plugin.build_list_trash_sql(), Some(opts)).await?;
+        Ok(Self::check_results(results)?
+            .iter()
+            .find_map(|result| match result {
+                SqlResult::Query(query) => Some(TrashEntry::from_query_result(query)),
+                _ => None,
+            })
+            .unwrap_or_default())
     }
 
-    /// Execute SQL script (simplified - creates session per execution)
-    pub async fn execute_script(
+    /// Move a trashed table back to its original location
+    pub async fn restore_from_trash(
         &self,
         cx: &mut AsyncApp,
-        connection_id: String,
-        script: String,
-        database: Option<String>,
-        opts: Option<ExecOptions>,
-    ) -> anyhow::Result<Vec<SqlResult>>
+        config_id: String,
+        database: String,
+        entry: TrashEntry,
+    ) -> anyhow::Result<()>
     {
-        //  Get config
-        let mut config = self.get_config_async(&connection_id).await
-            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        config.database = Some(database);
 
-        if let Some(db) = database {
-            config.database = Some(db);
-        }
-        self.execute_with_session(cx, config, script, opts).await
+        let sql = join_statements(plugin.build_restore_from_trash_sql(&entry));
+        let results = self.execute_with_session(cx, config, sql, None).await?;
+        Self::check_results(results)?;
+        Ok(())
     }
 
-    /// Execute script with existing session (for transaction scenarios)
-    pub async fn execute_with_session(
+    /// Permanently drop a trashed table
+    pub async fn purge_trash(
         &self,
         cx: &mut AsyncApp,
-        config: DbConnectionConfig,
-        script: String,
-        opts: Option<ExecOptions>,
-    ) -> anyhow::Result<Vec<SqlResult>>
+        config_id: String,
+        database: String,
+        entry: TrashEntry,
+    ) -> anyhow::Result<()>
     {
-        let clone_self = self.clone();
-        Tokio::spawn_result(cx, async move {
-            // Create session
-            let session_id = clone_self.connection_manager
-                .create_session(config.clone(), &clone_self.db_manager)
-                .await?;
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        config.database = Some(database);
 
-            // Execute query on session
-            let opts = opts.unwrap_or_default();
-            let is_transactional = opts.transactional;
+        let sql = join_statements(plugin.build_purge_trash_sql(&entry));
+        let results = self.execute_with_session(cx, config, sql, None).await?;
+        Self::check_results(results)?;
+        Ok(())
+    }
 
-            let plugin= clone_self.get_plugin(&config.database_type)?;
+    /// Run a listing query against `database` and return the first result set
+    async fn query_in_database(
+        &self,
+        cx: &mut AsyncApp,
+        mut config: DbConnectionConfig,
+        database: String,
+        sql: String,
+    ) -> anyhow::Result<Option<QueryResult>>
+    {
+        config.database = Some(database);
+        let opts = ExecOptions { max_rows: None, ..Default::default() };
+        let results = self.execute_with_session(cx, config, sql, Some(opts)).await?;
+        Ok(Self::check_results(results)?
+            .into_iter()
+            .find_map(|result| match result {
+                SqlResult::Query(query) => Some(query),
+                _ => None,
+            }))
+    }
+
+    /// List available extensions with their installed versions
+    pub async fn list_extensions(
+        &self,
+        cx: &mut AsyncApp,
+        config_id: String,
+        database: String,
+    ) -> anyhow::Result<Vec<ExtensionInfo>>
+    {
+        let config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let Some(sql) = plugin.build_list_extensions_sql() else {
+            return Ok(Vec::new());
+        };
 
-            let result = {
-                let mut guard = clone_self.connection_manager.get_session_connection(&session_id).await?;
-                let conn = guard.connection()
-                    .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
-                conn.execute(plugin, &script, opts).await?
+        Ok(self.query_in_database(cx, config, database, sql).await?
+            .map(|query| ExtensionInfo::from_query_result(&query))
+            .unwrap_or_default())
+    }
+
+    /// Data types provided by extensions installed in `database`
+    pub async fn list_extension_types(
+        &self,
+        cx: &mut AsyncApp,
+        config_id: String,
+        database: String,
+    ) -> anyhow::Result<Vec<DataTypeInfo>>
+    {
+        let config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let Some(sql) = plugin.build_list_extension_types_sql() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(self.query_in_database(cx, config, database, sql).await?
+            .map(|query| extension_types_from_query_result(&query))
+            .unwrap_or_default())
+    }
+
+    /// Install an extension, optionally into `schema`
+    pub async fn create_extension(
+        &self,
+        cx: &mut AsyncApp,
+        config_id: String,
+        database: String,
+        name: String,
+        schema: Option<String>,
+    ) -> anyhow::Result<()>
+    {
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = plugin.build_create_extension_sql(&name, schema.as_deref())
+            .ok_or_else(|| anyhow::anyhow!("{} does not support extensions", config.database_type.as_str()))?;
+        config.database = Some(database);
+
+        let results = self.execute_with_session(cx, config, sql, None).await?;
+        Self::check_results(results)?;
+        Ok(())
+    }
+
+    /// Drop an extension, with CASCADE also dropping objects that depend on it
+    pub async fn drop_extension(
+        &self,
+        cx: &mut AsyncApp,
+        config_id: String,
+        database: String,
+        name: String,
+        cascade: bool,
+    ) -> anyhow::Result<()>
+    {
+        let mut config = self.get_config_async(&config_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", config_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = plugin.build_drop_extension_sql(&name, cascade)
+            .ok_or_else(|| anyhow::anyhow!("{} does not support extensions", config.database_type.as_str()))?;
+        config.database = Some(database);
+
+        let results = self.execute_with_session(cx, config, sql, None).await?;
+        Self::check_results(results)?;
+        Ok(())
+    }
+
+    /// Run a script on a session opened with `create_session`. The session stays open, so
+    /// settings changed with SET are kept for the next call on it.
+    async fn execute_on_session(
+        &self,
+        cx: &mut AsyncApp,
+        session_id: String,
+        sql: String,
+    ) -> anyhow::Result<Vec<SqlResult>>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let config = clone_self.connection_manager.get_session_config(&session_id).await
+                .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+            let plugin = clone_self.get_plugin(&config.database_type)?;
+            let mut guard = clone_self.connection_manager.get_session_connection(&session_id).await?;
+            let conn = guard.connection()
+                .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
+            let opts = ExecOptions { max_rows: None, ..Default::default() };
+            let results = conn.execute(plugin, &sql, opts).await?;
+            Self::check_results(results)
+        })?.await
+    }
+
+    /// Settings of a session opened with `create_session`, empty if the dialect has none
+    pub async fn list_session_variables(
+        &self,
+        cx: &mut AsyncApp,
+        session_id: String,
+    ) -> anyhow::Result<Vec<SessionVariable>>
+    {
+        let config = self.connection_manager.get_session_config(&session_id).await
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let Some(sql) = plugin.build_list_session_variables_sql() else {
+            return Ok(Vec::new());
+        };
+
+        Ok(self.execute_on_session(cx, session_id, sql).await?
+            .into_iter()
+            .find_map(|result| match result {
+                SqlResult::Query(query) => Some(SessionVariable::from_query_result(&query)),
+                _ => None,
+            })
+            .unwrap_or_default())
+    }
+
+    /// Change a setting of a session opened with `create_session`, returns the statement that was run
+    pub async fn set_session_variable(
+        &self,
+        cx: &mut AsyncApp,
+        session_id: String,
+        name: String,
+        value: String,
+    ) -> anyhow::Result<String>
+    {
+        let config = self.connection_manager.get_session_config(&session_id).await
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = plugin.build_set_session_variable_sql(&name, &value)
+            .ok_or_else(|| anyhow::anyhow!("{} does not support session settings", config.database_type.as_str()))?;
+
+        self.execute_on_session(cx, session_id, sql.clone()).await?;
+        Ok(sql)
+    }
+
+    /// Turn the first statement error into an `Err`
+    fn check_results(results: Vec<SqlResult>) -> anyhow::Result<Vec<SqlResult>> {
+        if let Some(SqlResult::Error(err)) = results.iter().find(|r| matches!(r, SqlResult::Error(_))) {
+            return Err(anyhow::anyhow!("{}", err.message));
+        }
+        Ok(results)
+    }
+
+    /// Register a connection configuration
+    pub async fn register_connection(
+        &self,
+        config: DbConnectionConfig,
+    ) {
+        info!("register_connection: id={}, name={}, database_type={:?}",
+            config.id, config.name, config.database_type);
+        let mut connections = self.connections.write().await;
+        connections.insert(config.id.clone(), config);
+    }
+
+    /// Unregister a connection configuration
+    pub async fn unregister_connection(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+    ) -> anyhow::Result<()>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            // Close all sessions for this connection
+            clone_self.connection_manager.remove_all_sessions(&connection_id).await;
+
+            // Remove from registry
+            let mut connections = clone_self.connections.write().await;
+            connections.remove(&connection_id);
+            Ok(())
+        })?.await
+    }
+
+
+    /// Register a statement hook that runs before and after every script executed on the connection
+    pub async fn register_statement_hook(
+        &self,
+        connection_id: String,
+        hook: Arc<dyn StatementHook>,
+    ) {
+        let mut hooks = self.statement_hooks.write().await;
+        hooks.entry(connection_id).or_default().push(hook);
+    }
+
+    /// Remove all statement hooks registered for the connection
+    pub async fn clear_statement_hooks(&self, connection_id: &str) {
+        let mut hooks = self.statement_hooks.write().await;
+        hooks.remove(connection_id);
+    }
+
+    async fn statement_pipeline(&self, config: &DbConnectionConfig) -> StatementPipeline {
+        let hooks = self.statement_hooks.read().await;
+        hooks
+            .get(&config.id)
+            .into_iter()
+            .flatten()
+            .fold(StatementPipeline::from_config(config), |pipeline, hook| pipeline.with_hook(hook.clone()))
+    }
+
+    /// Get all registered connections
+    pub async fn list_connections(
+        &self,
+        cx: &mut AsyncApp,
+    ) -> anyhow::Result<Vec<DbConnectionConfig>>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let connections = clone_self.connections.read().await;
+            Ok(connections.values().cloned().collect())
+        })?.await
+    }
+
+    /// Create a new session for executing queries
+    pub async fn create_session(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: Option<String>,
+    ) -> anyhow::Result<String>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let mut config = clone_self.get_config_async(&connection_id).await
+                .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+
+            // Override database if specified
+            if let Some(db) = database {
+                config.database = Some(db);
+            }
+
+            clone_self.connection_manager.create_session(config, &clone_self.db_manager).await
+                .map_err(|e| anyhow::anyhow!("{}", e))
+        })?.await
+    }
+
+    /// Open a named session that stays out of the pool until closed, for editors that pin one
+    pub async fn open_named_session(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        name: String,
+        database: Option<String>,
+    ) -> anyhow::Result<String>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let mut config = clone_self.get_config_async(&connection_id).await
+                .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+
+            if let Some(db) = database {
+                config.database = Some(db);
+            }
+
+            clone_self.connection_manager.create_named_session(config, &clone_self.db_manager, name).await
+                .map_err(|e| anyhow::anyhow!("{}", e))
+        })?.await
+    }
+
+    /// Execute SQL  (simplified - creates session per execution)
+    pub async fn execute_single(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        script: String,
+        database: Option<String>,
+        opts: Option<ExecOptions>,
+    ) -> anyhow::Result<SqlResult>
+    {
+        let result=  self.execute_script(cx, connection_id, script, database, opts).await?;
+        Self::wrapper_result(result)
+    }
+
+    /// Execute SQL script (simplified - creates session per execution)
+    pub async fn execute_script(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        script: String,
+        database: Option<String>,
+        opts: Option<ExecOptions>,
+    ) -> anyhow::Result<Vec<SqlResult>>
+    {
+        //  Get config
+        let mut config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+
+        if let Some(db) = database {
+            config.database = Some(db);
+        }
+        self.execute_with_session(cx, config, script, opts).await
+    }
+
+    /// Cancel the queries still running on a connection, for plugins that support it
+    pub async fn cancel_running_queries(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+    ) -> anyhow::Result<usize>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let config = clone_self.get_config_async(&connection_id).await
+                .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+            let plugin = clone_self.get_plugin(&config.database_type)?;
+            plugin.cancel_running_queries(&connection_id).await
+        })?.await
+    }
+
+    /// Execute script with existing session (for transaction scenarios)
+    pub async fn execute_with_session(
+        &self,
+        cx: &mut AsyncApp,
+        config: DbConnectionConfig,
+        script: String,
+        opts: Option<ExecOptions>,
+    ) -> anyhow::Result<Vec<SqlResult>>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let plugin= clone_self.get_plugin(&config.database_type)?;
+            let opts = opts.unwrap_or_default();
+            let source = opts.source.clone();
+
+            let pipeline = clone_self.statement_pipeline(&config).await;
+            let script = {
+                let context = StatementHookContext { config: &config, plugin: plugin.as_ref(), source: source.as_deref() };
+                match pipeline.prepare(&context, &script) {
+                    Ok(script) => script,
+                    Err(vetoed) => return Ok(vec![SqlResult::Error(vetoed)]),
+                }
+            };
+
+            // Run on the named session if one was picked, otherwise take one from the pool
+            let named_session = opts.session_id.clone();
+            let session_id = match &named_session {
+                Some(session_id) => session_id.clone(),
+                None => clone_self.connection_manager
+                    .create_session(config.clone(), &clone_self.db_manager)
+                    .await?,
             };
 
+            // Execute query on session
+            let is_transactional = opts.transactional;
+            let timeout = opts.timeout.resolve(&config);
+
+            let execution = async {
+                let mut guard = clone_self.connection_manager.get_session_connection(&session_id).await?;
+                let conn = guard.connection()
+                    .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
+                conn.execute(plugin.clone(), &script, opts).await.map_err(anyhow::Error::from)
+            };
+            let result = match timeout {
+                // The statements run as one call here, so the script gets the timeout of each of them
+                Some(timeout) => {
+                    let statement_count = plugin.split_statements(&script).len().max(1) as u32;
+                    // Bound separately so the abandoned execution releases its session guard before the close below
+                    let outcome = tokio::time::timeout(timeout * statement_count, execution).await;
+                    match outcome {
+                        Ok(result) => result?,
+                        Err(_) => {
+                            // The connection is left in the middle of a statement and cannot be reused
+                            let _ = clone_self.connection_manager.close_session(&session_id).await;
+                            return Err(anyhow::anyhow!("Statement timed out after {}s", timeout.as_secs()));
+                        }
+                    }
+                }
+                None => execution.await?,
+            };
+
+            if !pipeline.is_empty() {
+                let context = StatementHookContext { config: &config, plugin: plugin.as_ref(), source: source.as_deref() };
+                for sql_result in &result {
+                    pipeline.finish(&context, sql_result);
+                }
+            }
+
+            if named_session.is_some() {
+                return Ok(result);
+            }
+
             // Determine if session should stay open based on script content
             let upper_script = script.to_uppercase();
             let has_begin = upper_script.contains("BEGIN") || upper_script.contains("START TRANSACTION");
@@ -896,29 +1668,122 @@ impl GlobalDbState {
                 Ok(c) => c,
                 Err(_) => return,
             };
-            let session_result = clone_self.connection_manager
-                .create_session(config.clone(), &clone_self.db_manager)
-                .await;
+
+            let opts = opts.unwrap_or_default();
+            let source = opts.source.clone();
+
+            let pipeline = clone_self.statement_pipeline(&config).await;
+            let prepared = {
+                let context = StatementHookContext { config: &config, plugin: plugin.as_ref(), source: source.as_deref() };
+                pipeline.prepare(&context, &script)
+            };
+            let script = match prepared {
+                Ok(script) => script,
+                Err(vetoed) => {
+                    let progress = StreamingProgress { current: 1, total: 1, result: SqlResult::Error(vetoed) };
+                    if tx.send(progress).await.is_err() {
+                        warn!("Streaming receiver dropped before the vetoed result was delivered");
+                    }
+                    return;
+                }
+            };
+
+            // Run on the named session if one was picked, otherwise take one from the pool
+            let named_session = opts.session_id.clone();
+            let session_result = match &named_session {
+                Some(session_id) => match clone_self.connection_manager.get_session_config(session_id).await {
+                    Some(_) => Ok(session_id.clone()),
+                    None => Err(anyhow::anyhow!("Session {} has been closed", session_id)),
+                },
+                None => clone_self.connection_manager
+                    .create_session(config.clone(), &clone_self.db_manager)
+                    .await
+                    .map_err(anyhow::Error::from),
+            };
 
             let session_id = match session_result {
                 Ok(id) => id,
-                Err(_) => return,
+                Err(e) => {
+                    // Only a closed named session is worth reporting; pool errors show on the connection status
+                    if named_session.is_some() {
+                        let progress = StreamingProgress { current: 1, total: 1, result: SqlResult::Error(SqlErrorInfo { sql: script, message: e.to_string() }) };
+                        let _ = tx.send(progress).await;
+                    }
+                    return;
+                }
             };
 
-            let opts = opts.unwrap_or_default();
+            let timeout = opts.timeout.resolve(&config);
+            let statements = plugin.split_statements(&script);
+
+            // Results pass through the pipeline's after hooks on their way to the caller.
+            // Each result restarts the statement timeout, so it bounds every statement on its own
+            let (hooked_tx, mut hooked_rx) = mpsc::channel::<StreamingProgress>(100);
+            let forward = async {
+                let mut received = 0;
+                loop {
+                    let next = match timeout {
+                        Some(timeout) => match tokio::time::timeout(timeout, hooked_rx.recv()).await {
+                            Ok(next) => next,
+                            Err(_) => {
+                                let sql = statements.get(received).cloned().unwrap_or_default();
+                                let message = format!("Statement timed out after {}s", timeout.as_secs());
+                                let progress = StreamingProgress {
+                                    current: received + 1,
+                                    total: statements.len().max(received + 1),
+                                    result: SqlResult::Error(SqlErrorInfo { sql, message }),
+                                };
+                                let _ = tx.send(progress).await;
+                                return true;
+                            }
+                        },
+                        None => hooked_rx.recv().await,
+                    };
+                    let Some(progress) = next else {
+                        return false;
+                    };
+                    received += 1;
+                    let context = StatementHookContext { config: &config, plugin: plugin.as_ref(), source: source.as_deref() };
+                    pipeline.finish(&context, &progress.result);
+                    if tx.send(progress).await.is_err() {
+                        return false;
+                    }
+                }
+            };
 
-            let exec_result = async {
+            let exec = async {
                 let mut guard = clone_self.connection_manager.get_session_connection(&session_id).await?;
                 let conn = guard.connection()
                     .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
-                conn.execute_streaming(plugin, &script, opts, tx).await
+                conn.execute_streaming(plugin.clone(), &script, opts, hooked_tx).await
                     .map_err(|e| anyhow::anyhow!("{}", e))?;
                 Ok::<_, anyhow::Error>(())
-            }.await;
+            };
+
+            // A timed out statement is abandoned by dropping the execution, which releases the
+            // session guard at the end of this block before the session is closed
+            let (exec_result, timed_out) = {
+                tokio::pin!(exec);
+                tokio::pin!(forward);
+                let mut exec_result = None;
+                let timed_out = loop {
+                    tokio::select! {
+                        result = &mut exec, if exec_result.is_none() => exec_result = Some(result),
+                        timed_out = &mut forward => break timed_out,
+                    }
+                };
+                if !timed_out && exec_result.is_none() {
+                    exec_result = Some(exec.await);
+                }
+                (exec_result, timed_out)
+            };
 
-            let _ = clone_self.connection_manager.close_session(&session_id).await;
+            // Named sessions outlive the run unless a timeout left them mid-statement
+            if named_session.is_none() || timed_out {
+                let _ = clone_self.connection_manager.close_session(&session_id).await;
+            }
 
-            if let Err(e) = exec_result {
+            if let Some(Err(e)) = exec_result {
                 error!("Streaming execution error: {}", e);
             }
         })?.detach();
@@ -1038,14 +1903,14 @@ impl GlobalDbState {
         // This is especially important for PostgreSQL which doesn't support database switching
         let target_database = match node.node_type {
             DbNodeType::Connection => {
-                // For listing databases, use config database or fallback to "postgres" for PostgreSQL
-                if config.database.is_none() && config.database_type == DatabaseType::PostgreSQL {
-                    Some("postgres".to_string())
-                } else {
-                    None
+                // For listing databases, use config database or fallback to the built-in database
+                match (&config.database, config.database_type) {
+                    (None, DatabaseType::PostgreSQL) => Some("postgres".to_string()),
+                    (None, DatabaseType::CockroachDB) => Some("defaultdb".to_string()),
+                    _ => None,
                 }
             }
-            DbNodeType::Database => Some(node.name.clone()),
+            DbNodeType::Database | DbNodeType::Catalog => Some(node.name.clone()),
             DbNodeType::Schema => node.metadata.as_ref()
                 .and_then(|m| m.get("database"))
                 .cloned(),
@@ -1228,6 +2093,297 @@ impl GlobalDbState {
         })
     }
 
+    /// CREATE statement of a table, as used by structure export
+    pub async fn get_table_ddl(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        table: String,
+    ) -> anyhow::Result<String>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            plugin.export_table_create_sql(&*conn, &database, &table).await
+        })
+    }
+
+    /// Collect tables with their columns and foreign keys in a single session.
+    /// Tables outside `schema` are skipped when it is given; at most `max_tables` are loaded.
+    pub async fn collect_table_schemas(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        schema: Option<String>,
+        max_tables: usize,
+    ) -> anyhow::Result<Vec<crate::types::TableSchema>>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            async {
+                let mut tables = plugin.list_tables(&*conn, &database).await?;
+                if let Some(schema) = &schema {
+                    tables.retain(|t| t.schema.as_ref().map_or(true, |s| s == schema));
+                }
+                tables.truncate(max_tables);
+
+                let mut schemas = Vec::with_capacity(tables.len());
+                for table in tables {
+                    let table_schema = table.schema.clone().or_else(|| schema.clone());
+                    let columns = plugin
+                        .list_columns(&*conn, &database, table_schema.as_deref(), &table.name)
+                        .await?;
+                    // Not every plugin reports foreign keys; the table is still useful without them
+                    let foreign_keys = plugin
+                        .list_foreign_keys(&*conn, &database, table_schema.as_deref(), &table.name)
+                        .await
+                        .unwrap_or_default();
+                    schemas.push(crate::types::TableSchema { table, columns, foreign_keys, indexes: Vec::new() });
+                }
+                Ok::<_, anyhow::Error>(schemas)
+            }.await
+        })
+    }
+
+    /// Describe the given tables (matched case-insensitively) including their indexes
+    pub async fn describe_tables(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        schema: Option<String>,
+        table_names: Vec<String>,
+    ) -> anyhow::Result<Vec<crate::types::TableSchema>>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            async {
+                let mut tables = plugin.list_tables(&*conn, &database).await?;
+                tables.retain(|t| {
+                    schema.as_ref().map_or(true, |s| t.schema.as_ref().map_or(true, |ts| ts == s))
+                        && table_names.iter().any(|name| name.eq_ignore_ascii_case(&t.name))
+                });
+
+                let mut schemas = Vec::with_capacity(tables.len());
+                for table in tables {
+                    let table_schema = table.schema.clone().or_else(|| schema.clone());
+                    let columns = plugin
+                        .list_columns(&*conn, &database, table_schema.as_deref(), &table.name)
+                        .await?;
+                    let foreign_keys = plugin
+                        .list_foreign_keys(&*conn, &database, table_schema.as_deref(), &table.name)
+                        .await
+                        .unwrap_or_default();
+                    let indexes = plugin
+                        .list_indexes(&*conn, &database, table_schema.as_deref(), &table.name)
+                        .await?;
+                    schemas.push(crate::types::TableSchema { table, columns, foreign_keys, indexes });
+                }
+                Ok::<_, anyhow::Error>(schemas)
+            }.await
+        })
+    }
+
+    /// Up to `COLUMN_SAMPLE_LIMIT` distinct non-null values of a column, used as valid foreign key targets
+    pub async fn sample_column_values(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        schema: Option<String>,
+        table: String,
+        column: String,
+    ) -> anyhow::Result<Vec<String>>
+    {
+        let config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let column = plugin.quote_identifier(&column);
+        let order_clause = format!(" ORDER BY {}", column);
+        let sql = format!(
+            "SELECT DISTINCT {} FROM {} WHERE {} IS NOT NULL{}{}",
+            column,
+            plugin.format_table_reference(&database, schema.as_deref(), &table),
+            column,
+            order_clause,
+            plugin.format_pagination(COLUMN_SAMPLE_LIMIT, 0, &order_clause),
+        );
+
+        let results = self.execute_script(cx, connection_id, sql, Some(database), None).await?;
+        Ok(Self::check_results(results)?
+            .into_iter()
+            .find_map(|result| match result {
+                SqlResult::Query(query) => Some(query.rows.into_iter().filter_map(|row| row.into_iter().next().flatten()).collect()),
+                _ => None,
+            })
+            .unwrap_or_default())
+    }
+
+    /// Run the dialect's EXPLAIN for `sql` in `database` and return the plan result set
+    pub async fn explain_query(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: Option<String>,
+        sql: String,
+    ) -> anyhow::Result<crate::executor::QueryResult>
+    {
+        let config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let script = plugin.build_explain_sql(&sql)
+            .ok_or_else(|| anyhow::anyhow!("{} does not support EXPLAIN", config.database_type.as_str()))?;
+
+        let results = self.execute_script(cx, connection_id, script, database, None).await?;
+        let mut plan = None;
+        for result in results {
+            match result {
+                SqlResult::Query(query) => plan = Some(query),
+                SqlResult::Error(e) => return Err(anyhow::anyhow!("{}", e.message)),
+                SqlResult::Exec(_) => {}
+            }
+        }
+        plan.ok_or_else(|| anyhow::anyhow!("EXPLAIN returned no plan"))
+    }
+
+    /// Run `sql` only if the dialect parser confirms it is a single read-only query,
+    /// returning at most `max_rows` rows. Where the dialect allows, the query also runs in a
+    /// read-only transaction that is rolled back, since a SELECT may still call functions that write
+    pub async fn run_read_only_query(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: Option<String>,
+        sql: String,
+        max_rows: usize,
+    ) -> anyhow::Result<crate::executor::QueryResult>
+    {
+        let config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let sql = sql.trim().trim_end_matches(';').to_string();
+        if !plugin.is_read_only_query(&sql) {
+            anyhow::bail!("Only a single read-only SELECT statement is allowed");
+        }
+
+        let opts = ExecOptions { max_rows: Some(max_rows), ..Default::default() };
+        let Some((begin, end)) = plugin.read_only_transaction_sql() else {
+            return match self.execute_single(cx, connection_id, sql, database, Some(opts)).await? {
+                SqlResult::Query(query) => Ok(query),
+                SqlResult::Error(e) => Err(anyhow::anyhow!("{}", e.message)),
+                SqlResult::Exec(_) => Err(anyhow::anyhow!("Statement returned no result set")),
+            };
+        };
+
+        // Execution stops at the first error, so the query never runs outside the read-only
+        // transaction; a session left inside it is closed rather than returned to the pool
+        let script = format!("{};\n{};\n{};", begin, sql, end);
+        let results = self.execute_script(cx, connection_id, script, database, Some(opts)).await?;
+        if let Some(SqlResult::Error(e)) = results.iter().find(|result| matches!(result, SqlResult::Error(_))) {
+            return Err(anyhow::anyhow!("{}", e.message));
+        }
+        // Statements such as PRAGMA may also come back as (empty) result sets, so take the query's by position
+        match results.into_iter().nth(1) {
+            Some(SqlResult::Query(query)) => Ok(query),
+            _ => Err(anyhow::anyhow!("Statement returned no result set")),
+        }
+    }
+
+    /// Current value of the plugin's cumulative activity counter, None if the database has none
+    pub async fn activity_counter(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+    ) -> anyhow::Result<Option<u64>>
+    {
+        let config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+        let plugin = self.get_plugin(&config.database_type)?;
+        let Some(sql) = plugin.activity_counter_sql() else {
+            return Ok(None);
+        };
+
+        match self.execute_single(cx, connection_id, sql.to_string(), None, None).await? {
+            SqlResult::Query(query) => {
+                let value = query.rows.first()
+                    .and_then(|row| row.last().cloned().flatten())
+                    .ok_or_else(|| anyhow::anyhow!("Activity counter returned no value"))?;
+                // Aggregates such as sum() may come back as NUMERIC text
+                let value: f64 = value.trim().parse()?;
+                Ok(Some(value as u64))
+            }
+            SqlResult::Error(e) => Err(anyhow::anyhow!("{}", e.message)),
+            SqlResult::Exec(_) => Err(anyhow::anyhow!("Statement returned no result set")),
+        }
+    }
+
+    /// Health of every open connection: pool usage and errors from the connection manager,
+    /// ping latency and the plugin's `server_status` from a probe over one of its sessions
+    pub async fn connection_health(
+        &self,
+        cx: &mut AsyncApp,
+    ) -> anyhow::Result<Vec<ConnectionHealth>>
+    {
+        let clone_self = self.clone();
+        Tokio::spawn_result(cx, async move {
+            let mut report = Vec::new();
+            for (config_id, pool) in clone_self.connection_manager.open_connections().await {
+                let Some(config) = clone_self.get_config_async(&config_id).await else {
+                    continue;
+                };
+                let status = clone_self.connection_manager.connection_status(&config_id);
+                let mut health = ConnectionHealth {
+                    connection_id: config_id.clone(),
+                    name: config.name.clone(),
+                    database_type: config.database_type,
+                    status: status.clone(),
+                    pool,
+                    ping: None,
+                    server: ServerStatus::default(),
+                    probe_error: None,
+                    last_error: None,
+                };
+
+                // Probing a failing connection would only repeat its last error
+                if !matches!(status, Some(ConnectionStatus::Failed(_))) {
+                    match clone_self.probe_server(config).await {
+                        Ok((ping, server)) => {
+                            health.ping = Some(ping);
+                            health.server = server;
+                        }
+                        Err(e) => health.probe_error = Some(e.to_string()),
+                    }
+                }
+                health.last_error = clone_self.connection_manager.last_error(&config_id);
+                report.push(health);
+            }
+            Ok(report)
+        })?.await
+    }
+
+    async fn probe_server(&self, config: DbConnectionConfig) -> anyhow::Result<(Duration, ServerStatus)> {
+        let plugin = self.get_plugin(&config.database_type)?;
+        let session_id = self.connection_manager
+            .create_session(config, &self.db_manager)
+            .await?;
+
+        let result = async {
+            let mut guard = self.connection_manager.get_session_connection(&session_id).await?;
+            let conn = guard.connection()
+                .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
+
+            let started = Instant::now();
+            tokio::time::timeout(PING_TIMEOUT, conn.ping()).await
+                .map_err(|_| anyhow::anyhow!("Ping timed out after {}s", PING_TIMEOUT.as_secs()))??;
+            let ping = started.elapsed();
+
+            let server = plugin.server_status(&*conn).await?;
+            Ok::<_, anyhow::Error>((ping, server))
+        }.await;
+
+        self.connection_manager.release_session(&session_id).await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        result
+    }
+
     /// List columns view
     pub async fn list_columns_view(
         &self,
@@ -1297,6 +2453,65 @@ impl GlobalDbState {
         })
     }
 
+    /// Get the source of a package spec and body
+    pub async fn get_package_source(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        package: String,
+    ) -> anyhow::Result<crate::types::PackageSource>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            plugin.get_package_source(&*conn, &database, &package).await
+        })
+    }
+
+    /// Get the source of a function, procedure or trigger
+    pub async fn get_routine_source(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        schema: Option<String>,
+        object_type: DbNodeType,
+        name: String,
+    ) -> anyhow::Result<String>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            plugin.get_routine_source(&*conn, &database, schema.as_deref(), object_type, &name).await
+        })
+    }
+
+    /// Script that recreates an event from its current definition, for editing
+    pub async fn get_event_edit_script(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        event_name: String,
+    ) -> anyhow::Result<String>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            plugin.get_routine_source(&*conn, &database, None, DbNodeType::Event, &event_name).await
+                .and_then(|definition| plugin.build_event_edit_script(&database, &event_name, &definition))
+        })
+    }
+
+    /// Recompile a package and return its compilation errors
+    pub async fn compile_package(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+        package: String,
+    ) -> anyhow::Result<Vec<String>>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            plugin.compile_package(&*conn, &database, &package).await
+        })
+    }
+
     /// List triggers view
     pub async fn list_triggers_view(
         &self,
@@ -1310,6 +2525,19 @@ impl GlobalDbState {
         })
     }
 
+    /// List events view
+    pub async fn list_events_view(
+        &self,
+        cx: &mut AsyncApp,
+        connection_id: String,
+        database: String,
+    ) -> anyhow::Result<crate::types::ObjectView>
+    {
+        with_plugin_session!(self, cx, connection_id, |plugin, conn| {
+            plugin.list_events_view(&*conn, &database).await
+        })
+    }
+
     /// List sequences view
     pub async fn list_sequences_view(
         &self,
@@ -1416,6 +2644,38 @@ impl GlobalDbState {
         result
     }
 
+    /// Export data straight into a file, keeping memory bounded for large tables
+    /// (sync version for background tasks)
+    pub async fn export_data_to_file_with_progress_sync(
+        &self,
+        connection_id: String,
+        config: ExportConfig,
+        path: PathBuf,
+        progress_tx: Option<ExportProgressSender>,
+    ) -> anyhow::Result<ExportResult>
+    {
+        let db_config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+
+        let plugin = self.get_plugin(&db_config.database_type)?;
+        let session_id = self.connection_manager
+            .create_session(db_config.clone(), &self.db_manager)
+            .await?;
+
+        let result = {
+            let mut guard = self.connection_manager.get_session_connection(&session_id).await?;
+            let conn = guard.connection()
+                .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
+            DataExporter::export_to_file(plugin, conn, config, &path, progress_tx).await
+                .map_err(|e| anyhow::anyhow!("{}", e))
+        };
+
+        self.connection_manager.release_session(&session_id).await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        result
+    }
+
     /// Import data
     pub async fn import_data(
         &self,
@@ -1482,6 +2742,37 @@ impl GlobalDbState {
 
         result
     }
+
+    /// Import a file by streaming it in batches (sync version for background tasks)
+    pub async fn import_file_with_progress_sync(
+        &self,
+        connection_id: String,
+        config: ImportConfig,
+        path: PathBuf,
+        progress_tx: Option<crate::import_export::ImportProgressSender>,
+    ) -> anyhow::Result<ImportResult>
+    {
+        let db_config = self.get_config_async(&connection_id).await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {}", connection_id))?;
+
+        let plugin = self.get_plugin(&db_config.database_type)?;
+        let session_id = self.connection_manager
+            .create_session(db_config.clone(), &self.db_manager)
+            .await?;
+
+        let result = {
+            let mut guard = self.connection_manager.get_session_connection(&session_id).await?;
+            let conn = guard.connection()
+                .ok_or_else(|| anyhow::anyhow!("Session connection not found"))?;
+            DataImporter::import_file(plugin, conn, config, &path, progress_tx).await
+                .map_err(|e| anyhow::anyhow!("{}", e))
+        };
+
+        self.connection_manager.release_session(&session_id).await
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        result
+    }
 }
 
 impl Default for GlobalDbState {
@@ -1490,4 +2781,8 @@ impl Default for GlobalDbState {
     }
 }
 
-impl Global for GlobalDbState {}
\ No newline at end of file
+impl Global for GlobalDbState {}
+
+fn join_statements(statements: Vec<String>) -> String {
+    statements.join(";\n")
+}
@@ -0,0 +1,6 @@
+pub mod connection;
+pub mod plugin;
+pub mod query;
+
+pub use connection::MongoDbConnection;
+pub use plugin::MongoDbPlugin;
@@ -0,0 +1,655 @@
+use std::any::Any;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::options::{ClientOptions, Credential, ServerAddress, Tls, TlsOptions};
+use mongodb::{Client, Database, IndexModel};
+use one_core::storage::{DbConnectionConfig, SslConfig};
+use serde_json::Value;
+use tokio::sync::{mpsc, RwLock};
+
+use crate::connection::{DbConnection, DbError, StreamingProgress};
+use crate::executor::{ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult};
+use crate::mongodb::query::{parse_mongo_statement, FindCommand, MongoCommand};
+use crate::proxy::{self, ProxyForward};
+use crate::{tls, ColumnInfo, DatabasePlugin, IndexInfo, SqlValue};
+
+/// Documents read to infer the fields of a collection
+const FIELD_SAMPLE_SIZE: i64 = 100;
+
+pub struct MongoDbConnection {
+    config: DbConnectionConfig,
+    client: Option<Client>,
+    current_database: RwLock<Option<String>>,
+    /// Keeps the local proxy port open while connected
+    proxy_forward: Option<ProxyForward>,
+}
+
+impl MongoDbConnection {
+    pub fn new(config: DbConnectionConfig) -> Self {
+        let current_database = RwLock::new(config.database.clone());
+        Self {
+            config,
+            client: None,
+            current_database,
+            proxy_forward: None,
+        }
+    }
+
+    fn ensure_connected(&self) -> Result<&Client, DbError> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionError("Not connected to database".to_string()))
+    }
+
+    /// A `mongodb://` connection string in the advanced tab wins over the host and credential fields
+    async fn build_client_options(config: &DbConnectionConfig) -> Result<ClientOptions, DbError> {
+        let connect_timeout = config.get_param_as::<u64>("connect_timeout").map(Duration::from_secs);
+
+        let mut options = match config.get_param("connection_string").filter(|s| !s.trim().is_empty()) {
+            Some(connection_string) => ClientOptions::parse(connection_string.trim())
+                .await
+                .map_err(|e| DbError::ConnectionError(format!("Invalid connection string: {}", e)))?,
+            None => {
+                let mut options = ClientOptions::default();
+                options.hosts = vec![ServerAddress::Tcp {
+                    host: config.host.clone(),
+                    port: Some(config.port),
+                }];
+                if !config.username.is_empty() {
+                    let mut credential = Credential::default();
+                    credential.username = Some(config.username.clone());
+                    credential.password = Some(config.password.clone());
+                    credential.source = config.get_param("auth_source").filter(|s| !s.is_empty()).cloned();
+                    options.credential = Some(credential);
+                }
+                options.direct_connection = Some(config.get_param_bool("direct_connection"));
+                options
+            }
+        };
+
+        let ssl = config.ssl();
+        if ssl.is_enabled() {
+            options.tls = Some(Tls::Enabled(Self::tls_options(&ssl)?));
+        }
+
+        options.app_name = Some("OneHub".to_string());
+        if connect_timeout.is_some() {
+            options.connect_timeout = connect_timeout;
+            options.server_selection_timeout = connect_timeout;
+        }
+        Ok(options)
+    }
+
+    /// Without the openssl backend the driver cannot skip the host name check,
+    /// so verify-ca behaves like verify-full
+    fn tls_options(ssl: &SslConfig) -> Result<TlsOptions, DbError> {
+        let mut tls_options = TlsOptions::default();
+        tls_options.allow_invalid_certificates = Some(!ssl.mode.verifies_certificate());
+        tls_options.ca_file_path = ssl.ca_cert.as_ref().map(PathBuf::from);
+        if let Some((cert, key)) = tls::client_identity(ssl)? {
+            // The driver reads certificate and key from one PEM file
+            if cert != key {
+                return Err(tls::unsupported(
+                    "MongoDB",
+                    "separate client certificate and key files, combine them into one PEM file",
+                ));
+            }
+            tls_options.cert_key_file_path = Some(PathBuf::from(cert));
+        }
+        Ok(tls_options)
+    }
+
+    async fn database(&self, name: Option<&str>) -> Result<Database, DbError> {
+        let client = self.ensure_connected()?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => self
+                .current_database
+                .read()
+                .await
+                .clone()
+                .ok_or_else(|| DbError::QueryError("No database selected".to_string()))?,
+        };
+        Ok(client.database(&name))
+    }
+
+    pub async fn list_database_names(&self) -> Result<Vec<String>, DbError> {
+        let client = self.ensure_connected()?;
+        let mut names = client
+            .list_database_names()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?;
+        names.sort();
+        Ok(names)
+    }
+
+    pub async fn list_collection_names(&self, database: &str) -> Result<Vec<String>, DbError> {
+        let mut names = self
+            .database(Some(database))
+            .await?
+            .list_collection_names()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?;
+        names.sort();
+        Ok(names)
+    }
+
+    /// Collections have no schema, so fields and their types are inferred from the first documents
+    pub async fn sample_fields(&self, database: &str, collection: &str) -> Result<Vec<ColumnInfo>, DbError> {
+        let documents: Vec<Document> = self
+            .database(Some(database))
+            .await?
+            .collection::<Document>(collection)
+            .find(doc! {})
+            .limit(FIELD_SAMPLE_SIZE)
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?;
+
+        Ok(infer_fields(&documents))
+    }
+
+    pub async fn list_indexes(&self, database: &str, collection: &str) -> Result<Vec<IndexInfo>, DbError> {
+        let indexes: Vec<IndexModel> = self
+            .database(Some(database))
+            .await?
+            .collection::<Document>(collection)
+            .list_indexes()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?;
+
+        Ok(indexes
+            .into_iter()
+            .map(|index| {
+                let options = index.options.unwrap_or_default();
+                IndexInfo {
+                    name: options.name.unwrap_or_else(|| "-".to_string()),
+                    columns: index.keys.keys().cloned().collect(),
+                    is_unique: options.unique.unwrap_or(false),
+                    index_type: Some(Bson::Document(index.keys).into_relaxed_extjson().to_string()),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn count_documents(&self, database: &str, collection: &str, filter: Document) -> Result<u64, DbError> {
+        self.database(Some(database))
+            .await?
+            .collection::<Document>(collection)
+            .count_documents(filter)
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))
+    }
+
+    pub async fn find_documents(&self, database: Option<&str>, find: &FindCommand) -> Result<Vec<Document>, DbError> {
+        let collection = self.database(database).await?.collection::<Document>(&find.collection);
+
+        let mut action = collection.find(to_document(&find.filter)?);
+        if let Some(projection) = &find.projection {
+            action = action.projection(to_document(projection)?);
+        }
+        if let Some(sort) = &find.sort {
+            action = action.sort(to_document(sort)?);
+        }
+        if let Some(skip) = find.skip {
+            action = action.skip(skip);
+        }
+        if let Some(limit) = find.limit {
+            action = action.limit(limit);
+        }
+
+        action
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))
+    }
+
+    async fn run_command(
+        &self,
+        database: Option<&str>,
+        command: MongoCommand,
+        max_rows: Option<usize>,
+    ) -> Result<CommandOutput, DbError> {
+        let read_only = command.is_read_only();
+        let database = self.database(database).await?;
+        match command {
+            MongoCommand::Find(mut find) => {
+                if find.limit.is_none() {
+                    find.limit = max_rows.map(|max| max as i64);
+                }
+                self.find_documents(Some(database.name()), &find).await.map(CommandOutput::Documents)
+            }
+            MongoCommand::Aggregate { collection, pipeline } => {
+                let mut stages = pipeline.iter().map(to_document).collect::<Result<Vec<_>, _>>()?;
+                // `$out` and `$merge` must stay the last stage
+                if let (true, Some(max)) = (read_only, max_rows) {
+                    stages.push(doc! { "$limit": max as i64 });
+                }
+
+                let documents: Vec<Document> = database
+                    .collection::<Document>(&collection)
+                    .aggregate(stages)
+                    .await
+                    .map_err(|e| DbError::QueryError(e.to_string()))?
+                    .try_collect()
+                    .await
+                    .map_err(|e| DbError::QueryError(e.to_string()))?;
+                if read_only {
+                    Ok(CommandOutput::Documents(documents))
+                } else {
+                    Ok(CommandOutput::Affected {
+                        rows: 0,
+                        message: format!("Aggregation on {} written to the output collection", collection),
+                    })
+                }
+            }
+            MongoCommand::Drop { collection } => {
+                database
+                    .collection::<Document>(&collection)
+                    .drop()
+                    .await
+                    .map_err(|e| DbError::QueryError(e.to_string()))?;
+                Ok(CommandOutput::Affected {
+                    rows: 0,
+                    message: format!("Collection {}.{} dropped", database.name(), collection),
+                })
+            }
+            MongoCommand::DeleteMany { collection, filter } => {
+                let result = database
+                    .collection::<Document>(&collection)
+                    .delete_many(to_document(&filter)?)
+                    .await
+                    .map_err(|e| DbError::QueryError(e.to_string()))?;
+                Ok(CommandOutput::Affected {
+                    rows: result.deleted_count,
+                    message: format!("{} documents deleted from {}.{}", result.deleted_count, database.name(), collection),
+                })
+            }
+            MongoCommand::RenameCollection { collection, new_name } => {
+                // renameCollection is an admin command taking full namespaces
+                self.ensure_connected()?
+                    .database("admin")
+                    .run_command(doc! {
+                        "renameCollection": format!("{}.{}", database.name(), collection),
+                        "to": format!("{}.{}", database.name(), new_name),
+                    })
+                    .await
+                    .map_err(|e| DbError::QueryError(e.to_string()))?;
+                Ok(CommandOutput::Affected {
+                    rows: 0,
+                    message: format!("Collection {}.{} renamed to {}", database.name(), collection, new_name),
+                })
+            }
+            MongoCommand::DropDatabase => {
+                database.drop().await.map_err(|e| DbError::QueryError(e.to_string()))?;
+                Ok(CommandOutput::Affected {
+                    rows: 0,
+                    message: format!("Database {} dropped", database.name()),
+                })
+            }
+        }
+    }
+
+    async fn execute_single(&self, statement: &str, max_rows: Option<usize>) -> SqlResult {
+        let start = Instant::now();
+        let sql = statement.to_string();
+
+        let statement = match parse_mongo_statement(statement) {
+            Ok(statement) => statement,
+            Err(e) => return SqlResult::Error(SqlErrorInfo { sql, message: e.to_string() }),
+        };
+
+        match self.run_command(statement.database.as_deref(), statement.command, max_rows).await {
+            Ok(CommandOutput::Documents(documents)) => {
+                SqlResult::Query(documents_to_result(sql, &documents, start.elapsed().as_millis()))
+            }
+            Ok(CommandOutput::Affected { rows, message }) => SqlResult::Exec(ExecResult {
+                sql,
+                rows_affected: rows,
+                elapsed_ms: start.elapsed().as_millis(),
+                message: Some(message),
+            }),
+            Err(e) => SqlResult::Error(SqlErrorInfo { sql, message: e.to_string() }),
+        }
+    }
+
+    async fn execute_statements(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+    ) -> Result<Vec<SqlResult>, DbError> {
+        self.ensure_connected()?;
+        let mut results = Vec::new();
+
+        for statement in plugin.split_statements(script) {
+            let result = self.execute_single(&statement, options.max_rows).await;
+            let is_error = result.is_error();
+            results.push(result);
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// What a statement produced: documents for the grid, or a count and message for write commands
+enum CommandOutput {
+    Documents(Vec<Document>),
+    Affected { rows: u64, message: String },
+}
+
+pub(crate) fn to_document(value: &Value) -> Result<Document, DbError> {
+    match Bson::try_from(value.clone()) {
+        Ok(Bson::Document(document)) => Ok(document),
+        Ok(_) => Err(DbError::QueryError(format!("Expected a document: {}", value))),
+        Err(e) => Err(DbError::QueryError(format!("Invalid extended JSON {}: {}", value, e))),
+    }
+}
+
+pub fn bson_type_name(value: &Bson) -> &'static str {
+    match value {
+        Bson::Double(_) => "double",
+        Bson::String(_) => "string",
+        Bson::Array(_) => "array",
+        Bson::Document(_) => "object",
+        Bson::Boolean(_) => "bool",
+        Bson::Null => "null",
+        Bson::Int32(_) => "int",
+        Bson::Int64(_) => "long",
+        Bson::ObjectId(_) => "objectId",
+        Bson::DateTime(_) => "date",
+        Bson::Decimal128(_) => "decimal",
+        Bson::Binary(_) => "binData",
+        Bson::Timestamp(_) => "timestamp",
+        Bson::RegularExpression(_) => "regex",
+        _ => "other",
+    }
+}
+
+/// Grid text of a field: scalars as plain text, documents and arrays as relaxed extended JSON
+pub fn bson_to_text(value: &Bson) -> Option<String> {
+    match value {
+        Bson::Null | Bson::Undefined => None,
+        Bson::String(text) => Some(text.clone()),
+        Bson::ObjectId(id) => Some(id.to_hex()),
+        Bson::DateTime(date) => Some(date.try_to_rfc3339_string().unwrap_or_else(|_| date.to_string())),
+        Bson::Boolean(value) => Some(value.to_string()),
+        Bson::Int32(value) => Some(value.to_string()),
+        Bson::Int64(value) => Some(value.to_string()),
+        Bson::Double(value) => Some(value.to_string()),
+        Bson::Decimal128(value) => Some(value.to_string()),
+        other => Some(other.clone().into_relaxed_extjson().to_string()),
+    }
+}
+
+/// Top-level field names in order of first appearance, `_id` first
+pub fn document_fields(documents: &[Document]) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    for document in documents {
+        for key in document.keys() {
+            if !fields.contains(key) {
+                fields.push(key.clone());
+            }
+        }
+    }
+    if let Some(position) = fields.iter().position(|field| field == "_id") {
+        let id = fields.remove(position);
+        fields.insert(0, id);
+    }
+    fields
+}
+
+/// One column per top-level field; nested documents stay in a single cell as JSON
+pub fn documents_to_result(sql: String, documents: &[Document], elapsed_ms: u128) -> QueryResult {
+    let columns = document_fields(documents);
+    let rows = documents
+        .iter()
+        .map(|document| {
+            columns
+                .iter()
+                .map(|column| document.get(column).and_then(bson_to_text))
+                .collect()
+        })
+        .collect();
+
+    QueryResult {
+        sql,
+        columns,
+        rows,
+        elapsed_ms,
+        table_name: None,
+        editable: false,
+    }
+}
+
+/// A field is nullable when some sampled document lacks it or stores null
+pub fn infer_fields(documents: &[Document]) -> Vec<ColumnInfo> {
+    document_fields(documents)
+        .into_iter()
+        .map(|field| {
+            let mut types: Vec<&'static str> = Vec::new();
+            let mut is_nullable = false;
+            for document in documents {
+                match document.get(&field) {
+                    None | Some(Bson::Null) => is_nullable = true,
+                    Some(value) => {
+                        let type_name = bson_type_name(value);
+                        if !types.contains(&type_name) {
+                            types.push(type_name);
+                        }
+                    }
+                }
+            }
+
+            ColumnInfo {
+                is_primary_key: field == "_id",
+                name: field,
+                data_type: if types.is_empty() { "null".to_string() } else { types.join("|") },
+                is_nullable,
+                default_value: None,
+                comment: None,
+                collation: None,
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl DbConnection for MongoDbConnection {
+    fn config(&self) -> &DbConnectionConfig {
+        &self.config
+    }
+
+    fn set_config_database(&mut self, database: Option<String>) {
+        *self.current_database.get_mut() = database.clone();
+        self.config.database = database;
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    async fn connect(&mut self) -> Result<(), DbError> {
+        let mut options = Self::build_client_options(&self.config).await?;
+        let ssl = self.config.ssl();
+
+        let proxy_forward = ProxyForward::start(&self.config).await?;
+        if let Some(forward) = &proxy_forward {
+            // Replica set discovery would dial the members directly, bypassing the proxy
+            if self.config.get_param("connection_string").is_some_and(|s| !s.trim().is_empty()) {
+                return Err(DbError::ConnectionError(
+                    "A connection string cannot be used through a proxy, fill in the host instead".to_string(),
+                ));
+            }
+            if ssl.mode.verifies_certificate() {
+                return Err(proxy::hostname_check_unsupported());
+            }
+            options.hosts = vec![ServerAddress::Tcp {
+                host: "127.0.0.1".to_string(),
+                port: Some(forward.local_port()),
+            }];
+            options.direct_connection = Some(true);
+        }
+        let client = Client::with_options(options)
+            .map_err(|e| tls::connection_error(&ssl, e))?;
+
+        client
+            .database("admin")
+            .run_command(doc! { "ping": 1 })
+            .await
+            .map_err(|e| ProxyForward::connect_error(proxy_forward.as_ref(), tls::connection_error(&ssl, e)))?;
+
+        self.client = Some(client);
+        self.proxy_forward = proxy_forward;
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), DbError> {
+        if let Some(client) = self.client.take() {
+            client.shutdown().await;
+        }
+        self.proxy_forward = None;
+        Ok(())
+    }
+
+    async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
+        self.execute_statements(plugin, script, options).await
+    }
+
+    async fn query(&self, query: &str, params: Option<Vec<SqlValue>>, options: ExecOptions) -> Result<SqlResult, DbError> {
+        self.ensure_connected()?;
+        if params.is_some() {
+            return Err(DbError::QueryError(
+                "Parameterized queries are not supported for MongoDB".to_string(),
+            ));
+        }
+        Ok(self.execute_single(query, options.max_rows).await)
+    }
+
+    async fn ping(&self) -> Result<(), DbError> {
+        let client = self.ensure_connected()?;
+        client
+            .database("admin")
+            .run_command(doc! { "ping": 1 })
+            .await
+            .map(|_| ())
+            .map_err(|e| DbError::ConnectionError(e.to_string()))
+    }
+
+    async fn current_database(&self) -> Result<Option<String>, DbError> {
+        Ok(self.current_database.read().await.clone())
+    }
+
+    async fn switch_database(&self, database: &str) -> Result<(), DbError> {
+        *self.current_database.write().await = Some(database.to_string());
+        Ok(())
+    }
+
+    async fn execute_streaming(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        sender: mpsc::Sender<StreamingProgress>,
+    ) -> Result<(), DbError> {
+        self.ensure_connected()?;
+
+        let statements = plugin.split_statements(script);
+        let total = statements.len();
+
+        for (index, statement) in statements.into_iter().enumerate() {
+            let result = self.execute_single(&statement, options.max_rows).await;
+            let is_error = result.is_error();
+            let progress = StreamingProgress {
+                current: index + 1,
+                total,
+                result,
+            };
+
+            if sender.send(progress).await.is_err() {
+                break;
+            }
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::oid::ObjectId;
+
+    #[test]
+    fn test_documents_to_result() {
+        let id = ObjectId::parse_str("64b7f0c2a1b2c3d4e5f60718").unwrap();
+        let documents = vec![
+            doc! { "name": "alice", "_id": id, "tags": ["a", "b"] },
+            doc! { "_id": 2, "name": Bson::Null, "address": { "city": "Hangzhou" }, "age": 30_i64 },
+        ];
+
+        let result = documents_to_result("db.users.find()".to_string(), &documents, 0);
+        assert_eq!(result.columns, vec!["_id", "name", "tags", "address", "age"]);
+        assert_eq!(
+            result.rows[0],
+            vec![
+                Some("64b7f0c2a1b2c3d4e5f60718".to_string()),
+                Some("alice".to_string()),
+                Some("[\"a\",\"b\"]".to_string()),
+                None,
+                None,
+            ]
+        );
+        assert_eq!(result.rows[1][3], Some("{\"city\":\"Hangzhou\"}".to_string()));
+        assert_eq!(result.rows[1][4], Some("30".to_string()));
+    }
+
+    #[test]
+    fn test_infer_fields() {
+        let documents = vec![
+            doc! { "_id": 1, "score": 1.5 },
+            doc! { "_id": 2, "score": "n/a", "note": "x" },
+        ];
+
+        let fields = infer_fields(&documents);
+        let summary: Vec<(&str, &str, bool, bool)> = fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.data_type.as_str(), f.is_nullable, f.is_primary_key))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("_id", "int", false, true),
+                ("score", "double|string", false, false),
+                ("note", "string", true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_document_accepts_extended_json() {
+        let document = to_document(&serde_json::json!({ "_id": { "$oid": "64b7f0c2a1b2c3d4e5f60718" } })).unwrap();
+        assert!(matches!(document.get("_id"), Some(Bson::ObjectId(_))));
+        assert!(to_document(&serde_json::json!([1, 2])).is_err());
+    }
+}
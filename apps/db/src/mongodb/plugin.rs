@@ -0,0 +1,895 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, Document, Regex};
+use one_core::storage::{DatabaseType, DbConnectionConfig, GlobalStorageState};
+
+use crate::connection::{DbConnection, DbError};
+use crate::executor::StatementType;
+use crate::mongodb::connection::{documents_to_result, infer_fields, to_document};
+use crate::mongodb::query::{parse_mongo_command, parse_relaxed_json, split_mongo_statements, FindCommand};
+use crate::mongodb::MongoDbConnection;
+use crate::plugin::{DatabaseOperationRequest, DatabasePlugin, SqlCompletionInfo};
+use crate::types::*;
+
+/// Databases used by the server itself, hidden like the system databases of other plugins
+const SYSTEM_DATABASES: [&str; 3] = ["admin", "config", "local"];
+
+/// MongoDB plugin. Collections are shown as tables and the editor runs mongo shell
+/// `find`/`aggregate` commands instead of SQL; SQL generation methods return comments.
+pub struct MongoDbPlugin;
+
+impl MongoDbPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn mongo_connection<'a>(&self, connection: &'a dyn DbConnection) -> Result<&'a MongoDbConnection> {
+        connection
+            .as_any()
+            .and_then(|any| any.downcast_ref::<MongoDbConnection>())
+            .ok_or_else(|| anyhow::anyhow!("Not a MongoDB connection"))
+    }
+
+    fn unsupported(&self, operation: &str) -> String {
+        format!("// MongoDB: {} is not supported, use the mongo shell", operation)
+    }
+
+    /// Tree actions run on a fresh session, so the database is named explicitly instead of relying on `use`
+    fn collection_reference(&self, database: &str, collection: &str) -> String {
+        format!(
+            "db.getSiblingDB({}).getCollection({})",
+            self.quote_identifier(database),
+            self.quote_identifier(collection)
+        )
+    }
+}
+
+/// Grid filters are typed as text; numbers, booleans and object ids keep their BSON type
+fn filter_value(column: &str, text: &str) -> Bson {
+    let text = text.trim().trim_matches('\'');
+    if column == "_id" {
+        if let Ok(id) = ObjectId::parse_str(text) {
+            return Bson::ObjectId(id);
+        }
+    }
+    if let Ok(value) = text.parse::<i64>() {
+        return Bson::Int64(value);
+    }
+    if let Ok(value) = text.parse::<f64>() {
+        return Bson::Double(value);
+    }
+    match text {
+        "true" => Bson::Boolean(true),
+        "false" => Bson::Boolean(false),
+        _ => Bson::String(text.to_string()),
+    }
+}
+
+/// SQL LIKE pattern as an anchored regular expression
+fn like_to_regex(pattern: &str) -> Regex {
+    let mut regex = String::from("^");
+    for c in pattern.trim().trim_matches('\'').chars() {
+        match c {
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c if "\\^$.|?*+()[]{}".contains(c) => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex.push('$');
+    Regex {
+        pattern: regex,
+        options: "i".to_string(),
+    }
+}
+
+fn filter_condition(filter: &FilterCondition) -> Document {
+    let column = filter.column.as_str();
+    let value = || filter_value(column, &filter.value);
+    let list = || -> Vec<Bson> {
+        filter
+            .value
+            .split(',')
+            .map(|item| filter_value(column, item))
+            .collect()
+    };
+
+    let condition = match filter.operator {
+        FilterOperator::Equal => value(),
+        FilterOperator::NotEqual => Bson::Document(doc! { "$ne": value() }),
+        FilterOperator::GreaterThan => Bson::Document(doc! { "$gt": value() }),
+        FilterOperator::LessThan => Bson::Document(doc! { "$lt": value() }),
+        FilterOperator::GreaterOrEqual => Bson::Document(doc! { "$gte": value() }),
+        FilterOperator::LessOrEqual => Bson::Document(doc! { "$lte": value() }),
+        FilterOperator::Like => Bson::RegularExpression(like_to_regex(&filter.value)),
+        FilterOperator::NotLike => Bson::Document(doc! { "$not": Bson::RegularExpression(like_to_regex(&filter.value)) }),
+        FilterOperator::In => Bson::Document(doc! { "$in": list() }),
+        FilterOperator::NotIn => Bson::Document(doc! { "$nin": list() }),
+        FilterOperator::IsNull => Bson::Null,
+        FilterOperator::IsNotNull => Bson::Document(doc! { "$ne": Bson::Null }),
+    };
+
+    let mut document = Document::new();
+    document.insert(column, condition);
+    document
+}
+
+/// Filter of a table data request: the raw WHERE text is a JSON filter, structured filters are ANDed
+fn request_filter(request: &TableDataRequest) -> Result<Document> {
+    if let Some(raw) = request.where_clause.as_deref().filter(|raw| !raw.trim().is_empty()) {
+        return Ok(to_document(&parse_relaxed_json(raw)?)?);
+    }
+
+    let mut conditions: Vec<Document> = request.filters.iter().map(filter_condition).collect();
+    Ok(match conditions.len() {
+        0 => Document::new(),
+        1 => conditions.remove(0),
+        _ => doc! { "$and": conditions },
+    })
+}
+
+fn request_sort(request: &TableDataRequest) -> Result<Option<Document>> {
+    if let Some(raw) = request.order_by_clause.as_deref().filter(|raw| !raw.trim().is_empty()) {
+        return Ok(Some(to_document(&parse_relaxed_json(raw)?)?));
+    }
+    if request.sorts.is_empty() {
+        return Ok(None);
+    }
+
+    let mut sort = Document::new();
+    for condition in &request.sorts {
+        let direction = match condition.direction {
+            SortDirection::Asc => 1,
+            SortDirection::Desc => -1,
+        };
+        sort.insert(condition.column.clone(), direction);
+    }
+    Ok(Some(sort))
+}
+
+fn field_type(data_type: &str) -> FieldType {
+    match data_type {
+        "int" | "long" => FieldType::Integer,
+        "double" | "decimal" => FieldType::Decimal,
+        "bool" => FieldType::Boolean,
+        "date" => FieldType::DateTime,
+        "string" | "objectId" => FieldType::Text,
+        "object" | "array" => FieldType::Json,
+        "binData" => FieldType::Binary,
+        _ => FieldType::Unknown,
+    }
+}
+
+/// Shell command equivalent to a table data request, shown as the executed query
+fn find_shell_command(collection: &str, filter: &Document, sort: Option<&Document>, skip: u64, limit: Option<i64>) -> String {
+    let json = |document: &Document| Bson::Document(document.clone()).into_relaxed_extjson().to_string();
+    let mut command = format!(
+        "db.getCollection({}).find({})",
+        serde_json::Value::String(collection.to_string()),
+        json(filter)
+    );
+    if let Some(sort) = sort {
+        command.push_str(&format!(".sort({})", json(sort)));
+    }
+    if skip > 0 {
+        command.push_str(&format!(".skip({})", skip));
+    }
+    if let Some(limit) = limit {
+        command.push_str(&format!(".limit({})", limit));
+    }
+    command
+}
+
+#[async_trait]
+impl DatabasePlugin for MongoDbPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::MongoDB
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        serde_json::Value::String(identifier.to_string()).to_string()
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        Box::new(sqlparser::dialect::GenericDialect {})
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        SqlCompletionInfo {
+            keywords: vec![
+                ("db", "Current database"),
+                ("getCollection", "Collection by name"),
+                ("find", "Query documents"),
+                ("aggregate", "Run an aggregation pipeline"),
+                ("sort", "Sort the find result"),
+                ("skip", "Skip documents of the find result"),
+                ("limit", "Limit the find result"),
+            ],
+            functions: vec![
+                ("ObjectId(\"hex\")", "Object id"),
+                ("ISODate(\"2024-01-01T00:00:00Z\")", "Date"),
+                ("NumberLong(n)", "64-bit integer"),
+                ("NumberDecimal(\"n\")", "128-bit decimal"),
+                ("$match", "Filter documents (stage)"),
+                ("$project", "Reshape documents (stage)"),
+                ("$group", "Group documents (stage)"),
+                ("$sort", "Sort documents (stage)"),
+                ("$limit", "Limit documents (stage)"),
+                ("$skip", "Skip documents (stage)"),
+                ("$unwind", "Deconstruct an array field (stage)"),
+                ("$lookup", "Join another collection (stage)"),
+                ("$addFields", "Add fields (stage)"),
+                ("$count", "Count documents (stage)"),
+                ("$facet", "Run sub-pipelines (stage)"),
+                ("$sum", "Sum (accumulator)"),
+                ("$avg", "Average (accumulator)"),
+                ("$min", "Minimum (accumulator)"),
+                ("$max", "Maximum (accumulator)"),
+                ("$push", "Collect values into an array (accumulator)"),
+                ("$addToSet", "Collect unique values (accumulator)"),
+                ("$first", "First value (accumulator)"),
+                ("$last", "Last value (accumulator)"),
+            ],
+            operators: vec![
+                ("$eq", "Equal"),
+                ("$ne", "Not equal"),
+                ("$gt", "Greater than"),
+                ("$gte", "Greater than or equal"),
+                ("$lt", "Less than"),
+                ("$lte", "Less than or equal"),
+                ("$in", "In array"),
+                ("$nin", "Not in array"),
+                ("$and", "Logical AND"),
+                ("$or", "Logical OR"),
+                ("$not", "Logical NOT"),
+                ("$exists", "Field exists"),
+                ("$type", "Field has BSON type"),
+                ("$regex", "Regular expression match"),
+                ("$elemMatch", "Array element match"),
+                ("$size", "Array size"),
+            ],
+            data_types: vec![
+                ("string", "UTF-8 string"),
+                ("int", "32-bit integer"),
+                ("long", "64-bit integer"),
+                ("double", "Double-precision float"),
+                ("decimal", "128-bit decimal"),
+                ("bool", "Boolean"),
+                ("date", "UTC datetime"),
+                ("objectId", "Object id"),
+                ("object", "Embedded document"),
+                ("array", "Array"),
+            ],
+            snippets: vec![
+                ("find", "db.$1.find({ $2 })", "Find documents"),
+                ("agg", "db.$1.aggregate([\n  { $match: { $2 } },\n  { $group: { _id: \"$$3\", count: { $sum: 1 } } }\n])", "Aggregation pipeline"),
+            ],
+        }
+    }
+
+    fn split_statements(&self, script: &str) -> Vec<String> {
+        split_mongo_statements(script)
+    }
+
+    fn is_query_statement(&self, sql: &str) -> bool {
+        parse_mongo_command(sql).is_ok_and(|command| command.is_read_only())
+    }
+
+    fn classify_statement(&self, sql: &str) -> StatementType {
+        match parse_mongo_command(sql) {
+            Ok(command) if command.is_read_only() => StatementType::Query,
+            Ok(command) if command.is_ddl() => StatementType::Ddl,
+            Ok(_) => StatementType::Dml,
+            Err(_) => StatementType::Exec,
+        }
+    }
+
+    fn analyze_select_editability(&self, _sql: &str) -> Option<String> {
+        None
+    }
+
+    fn is_read_only_query(&self, sql: &str) -> bool {
+        self.split_statements(sql).len() == 1 && self.is_query_statement(sql)
+    }
+
+    /// The filter box takes a JSON query document such as `{ status: "paid" }`
+    fn validate_where_condition(&self, condition: &str) -> Result<()> {
+        to_document(&parse_relaxed_json(condition)?)?;
+        Ok(())
+    }
+
+    fn build_explain_sql(&self, _sql: &str) -> Option<String> {
+        None
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = MongoDbConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    // === Database/Schema Level Operations ===
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        let conn = self.mongo_connection(connection)?;
+        let databases = conn
+            .list_database_names()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list databases: {}", e))?;
+
+        Ok(databases
+            .into_iter()
+            .filter(|name| !SYSTEM_DATABASES.contains(&name.as_str()))
+            .collect())
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let databases = self.list_databases(connection).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = databases.iter().map(|db| vec![db.clone()]).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Database,
+            title: format!("{} database(s)", databases.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        let databases = self.list_databases(connection).await?;
+
+        Ok(databases.into_iter().map(|name| DatabaseInfo {
+            name,
+            charset: None,
+            collation: None,
+            size: None,
+            table_count: None,
+            comment: None,
+        }).collect())
+    }
+
+    // === Collection Operations ===
+
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        let conn = self.mongo_connection(connection)?;
+        let collections = conn
+            .list_collection_names(database)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list collections: {}", e))?;
+
+        Ok(collections.into_iter().map(|name| TableInfo {
+            name,
+            schema: None,
+            comment: None,
+            engine: None,
+            row_count: None,
+            create_time: None,
+            charset: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(240.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = tables.iter().map(|table| vec![table.name.clone()]).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} collection(s)", tables.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let conn = self.mongo_connection(connection)?;
+        conn.sample_fields(database, table)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list fields: {}", e))
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let fields = self.list_columns(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("type", "Type").width(px(180.0)),
+            Column::new("nullable", "Nullable").width(px(80.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = fields.iter().map(|field| {
+            vec![
+                field.name.clone(),
+                field.data_type.clone(),
+                if field.is_nullable { "YES" } else { "NO" }.to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: format!("{} field(s)", fields.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_indexes(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<IndexInfo>> {
+        let conn = self.mongo_connection(connection)?;
+        conn.list_indexes(database, table)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list indexes: {}", e))
+    }
+
+    async fn list_indexes_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let indexes = self.list_indexes(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("keys", "Keys").width(px(260.0)),
+            Column::new("unique", "Unique").width(px(80.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = indexes.iter().map(|index| {
+            vec![
+                index.name.clone(),
+                index.index_type.clone().unwrap_or_default(),
+                if index.is_unique { "YES" } else { "NO" }.to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: format!("{} index(es)", indexes.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_views(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<ViewInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_views_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::View,
+            title: "0 view(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Function,
+            title: "0 function(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Procedure,
+            title: "0 procedure(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(180.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_sequences(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SequenceInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sequences_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Sequence,
+            title: "0 sequence(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    // === Tree Building ===
+
+    /// Collections are the only objects of a database, so they are listed without the SQL object folders
+    async fn build_database_tree(&self, connection: &dyn DbConnection, node: &DbNode, global_storage_state: &GlobalStorageState) -> Result<Vec<DbNode>> {
+        let database = &node.name;
+        let id = &node.id;
+        let mut metadata: HashMap<String, String> = HashMap::new();
+        metadata.insert("database".to_string(), database.to_string());
+
+        let collections = self.list_tables(connection, database).await?;
+        let mut collections_folder = DbNode::new(
+            format!("{}:table_folder", id),
+            format!("Collections ({})", collections.len()),
+            DbNodeType::TablesFolder,
+            node.connection_id.clone(),
+            node.database_type
+        ).with_parent_context(id).with_metadata(metadata.clone());
+        if !collections.is_empty() {
+            let children: Vec<DbNode> = collections
+                .into_iter()
+                .map(|collection| {
+                    DbNode::new(
+                        format!("{}:table_folder:{}", id, collection.name),
+                        collection.name,
+                        DbNodeType::Table,
+                        node.connection_id.clone(),
+                        node.database_type
+                    )
+                    .with_parent_context(format!("{}:table_folder", id))
+                    .with_metadata(metadata.clone())
+                })
+                .collect();
+            collections_folder.set_children(children);
+        }
+
+        let queries_folder = self.load_queries(node, metadata, global_storage_state).await?;
+        Ok(vec![collections_folder, queries_folder])
+    }
+
+    async fn load_table_children(&self, connection: &dyn DbConnection, node: &DbNode, id: &str) -> Result<Vec<DbNode>> {
+        let metadata = node.metadata.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("集合节点缺少 metadata"))?;
+        let db = metadata.get("database")
+            .ok_or_else(|| anyhow::anyhow!("集合节点缺少 database 字段"))?;
+        let collection = &node.name;
+
+        let mut folder_metadata: HashMap<String, String> = metadata.clone();
+        folder_metadata.insert("table".to_string(), collection.clone());
+
+        let fields = self.list_columns(connection, db, None, collection).await?;
+        let indexes = self.list_indexes(connection, db, None, collection).await?;
+
+        Ok(vec![
+            self.build_table_subfolder(node, id, "columns_folder", "Fields", DbNodeType::ColumnsFolder, &folder_metadata,
+                fields.into_iter().map(|field| (field.name.clone(), DbNodeType::Column, {
+                    let mut m = folder_metadata.clone();
+                    m.insert("type".to_string(), field.data_type);
+                    m.insert("is_nullable".to_string(), field.is_nullable.to_string());
+                    m.insert("is_primary_key".to_string(), field.is_primary_key.to_string());
+                    m
+                })).collect()),
+            self.build_table_subfolder(node, id, "indexes_folder", "Indexes", DbNodeType::IndexesFolder, &folder_metadata,
+                indexes.into_iter().map(|index| (index.name.clone(), DbNodeType::Index, {
+                    let mut m = folder_metadata.clone();
+                    m.insert("unique".to_string(), index.is_unique.to_string());
+                    m.insert("columns".to_string(), index.columns.join(", "));
+                    m
+                })).collect()),
+        ])
+    }
+
+    // === Table Data Operations ===
+
+    /// Page through a collection with `find`; columns come from the fields of the returned documents
+    async fn query_table_data(&self, connection: &dyn DbConnection, request: &TableDataRequest) -> Result<TableDataResponse> {
+        let start_time = std::time::Instant::now();
+        let conn = self.mongo_connection(connection)?;
+
+        let filter = request_filter(request)?;
+        let sort = request_sort(request)?;
+        let skip = (request.page.saturating_sub(1) * request.page_size) as u64;
+        let limit = (request.page_size > 0).then_some(request.page_size as i64);
+
+        let total_count = conn
+            .count_documents(&request.database, &request.table, filter.clone())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to count documents: {}", e))? as usize;
+
+        let find = FindCommand {
+            collection: request.table.clone(),
+            filter: Bson::Document(filter.clone()).into_relaxed_extjson(),
+            projection: None,
+            sort: sort.as_ref().map(|sort| Bson::Document(sort.clone()).into_relaxed_extjson()),
+            skip: Some(skip),
+            limit,
+        };
+        let documents = conn
+            .find_documents(Some(&request.database), &find)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to query documents: {}", e))?;
+
+        let fields = if documents.is_empty() {
+            self.list_columns(connection, &request.database, None, &request.table).await?
+        } else {
+            infer_fields(&documents)
+        };
+        let columns: Vec<TableColumnMeta> = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| TableColumnMeta {
+                name: field.name.clone(),
+                db_type: field.data_type.clone(),
+                field_type: field_type(&field.data_type),
+                nullable: field.is_nullable,
+                is_primary_key: field.is_primary_key,
+                collation: None,
+                index,
+            })
+            .collect();
+
+        let executed_sql = find_shell_command(&request.table, &filter, sort.as_ref(), skip, limit);
+        let rows = documents_to_result(executed_sql.clone(), &documents, 0).rows;
+
+        Ok(TableDataResponse {
+            primary_key_indices: columns.iter().filter(|c| c.is_primary_key).map(|c| c.index).collect(),
+            columns,
+            rows,
+            total_count,
+            page: request.page,
+            page_size: request.page_size,
+            unique_key_indices: Vec::new(),
+            executed_sql,
+            duration: start_time.elapsed().as_millis(),
+        })
+    }
+
+    fn generate_table_changes_sql(&self, _request: &TableSaveRequest) -> String {
+        self.unsupported("editing documents in the grid")
+    }
+
+    // === SQL generation ===
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        if include_name {
+            format!("{} {}", column.name, column.data_type)
+        } else {
+            column.data_type.clone()
+        }
+    }
+
+    fn build_create_database_sql(&self, request: &DatabaseOperationRequest) -> String {
+        // A database is created implicitly together with its first collection
+        format!(
+            "db.getSiblingDB({}).createCollection(\"default\")",
+            self.quote_identifier(&request.database_name)
+        )
+    }
+
+    fn build_modify_database_sql(&self, _request: &DatabaseOperationRequest) -> String {
+        self.unsupported("modifying a database")
+    }
+
+    fn build_drop_database_sql(&self, database_name: &str) -> String {
+        format!("db.getSiblingDB({}).dropDatabase()", self.quote_identifier(database_name))
+    }
+
+    fn drop_database(&self, database: &str) -> String {
+        self.build_drop_database_sql(database)
+    }
+
+    fn drop_table(&self, database: &str, table: &str) -> String {
+        format!("{}.drop()", self.collection_reference(database, table))
+    }
+
+    fn truncate_table(&self, database: &str, table: &str) -> String {
+        format!("{}.deleteMany({{}})", self.collection_reference(database, table))
+    }
+
+    fn rename_table(&self, database: &str, old_name: &str, new_name: &str) -> String {
+        format!(
+            "{}.renameCollection({})",
+            self.collection_reference(database, old_name),
+            self.quote_identifier(new_name)
+        )
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        format!("{} {}", col.name, col.data_type)
+    }
+
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        format!("db.createCollection({})", self.quote_identifier(&design.table_name))
+    }
+
+    fn build_alter_table_sql(&self, _original: &TableDesign, _new: &TableDesign) -> String {
+        self.unsupported("altering a collection")
+    }
+
+    fn build_limit_clause(&self) -> String {
+        String::new()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        _request: &TableSaveRequest,
+        _original_data: &[String],
+    ) -> (String, String) {
+        (String::new(), String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mongodb::query::{parse_mongo_statement, MongoCommand};
+
+    fn create_plugin() -> MongoDbPlugin {
+        MongoDbPlugin::new()
+    }
+
+    fn request() -> TableDataRequest {
+        TableDataRequest {
+            database: "shop".to_string(),
+            schema: None,
+            table: "orders".to_string(),
+            page: 3,
+            page_size: 50,
+            filters: Vec::new(),
+            sorts: Vec::new(),
+            where_clause: None,
+            order_by_clause: None,
+        }
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        assert_eq!(create_plugin().name(), DatabaseType::MongoDB);
+    }
+
+    #[test]
+    fn test_statements_use_shell_syntax() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.split_statements("db.a.find()\ndb.b.find();").len(), 2);
+        assert!(plugin.is_query_statement("db.orders.find({ status: 'paid' })"));
+        assert!(!plugin.is_query_statement("SELECT * FROM orders"));
+        assert_eq!(plugin.classify_statement("db.orders.aggregate([{ $out: 'x' }])"), StatementType::Dml);
+        assert!(plugin.is_read_only_query("db.orders.aggregate([{ $match: {} }])"));
+        assert!(plugin.validate_where_condition("{ total: { $gt: 10 } }").is_ok());
+        assert!(plugin.validate_where_condition("total > 10").is_err());
+    }
+
+    #[test]
+    fn test_request_filter_from_grid_filters() {
+        let mut request = request();
+        request.filters = vec![
+            FilterCondition {
+                column: "_id".to_string(),
+                operator: FilterOperator::Equal,
+                value: "64b7f0c2a1b2c3d4e5f60718".to_string(),
+            },
+            FilterCondition {
+                column: "total".to_string(),
+                operator: FilterOperator::GreaterOrEqual,
+                value: "10".to_string(),
+            },
+            FilterCondition {
+                column: "name".to_string(),
+                operator: FilterOperator::Like,
+                value: "a_c%".to_string(),
+            },
+        ];
+
+        let filter = request_filter(&request).unwrap();
+        let conditions = filter.get_array("$and").unwrap();
+        assert_eq!(conditions.len(), 3);
+        assert!(matches!(
+            conditions[0].as_document().and_then(|d| d.get("_id")),
+            Some(Bson::ObjectId(_))
+        ));
+        assert_eq!(conditions[1], Bson::Document(doc! { "total": { "$gte": 10_i64 } }));
+        match conditions[2].as_document().and_then(|d| d.get("name")) {
+            Some(Bson::RegularExpression(regex)) => assert_eq!(regex.pattern, "^a.c.*$"),
+            other => panic!("unexpected condition {:?}", other),
+        }
+
+        request.where_clause = Some("{ status: 'paid' }".to_string());
+        assert_eq!(request_filter(&request).unwrap(), doc! { "status": "paid" });
+    }
+
+    #[test]
+    fn test_request_sort_and_shell_command() {
+        let mut request = request();
+        request.sorts = vec![SortCondition {
+            column: "created".to_string(),
+            direction: SortDirection::Desc,
+        }];
+        let sort = request_sort(&request).unwrap();
+        assert_eq!(sort, Some(doc! { "created": -1 }));
+
+        assert_eq!(
+            find_shell_command("orders", &doc! { "status": "paid" }, sort.as_ref(), 100, Some(50)),
+            "db.getCollection(\"orders\").find({\"status\":\"paid\"}).sort({\"created\":-1}).skip(100).limit(50)"
+        );
+    }
+
+    #[test]
+    fn test_collection_commands() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.drop_table("shop", "orders"),
+            "db.getSiblingDB(\"shop\").getCollection(\"orders\").drop()"
+        );
+        assert_eq!(
+            plugin.rename_table("shop", "orders", "orders_old"),
+            "db.getSiblingDB(\"shop\").getCollection(\"orders\").renameCollection(\"orders_old\")"
+        );
+        assert_eq!(plugin.build_drop_database_sql("shop"), "db.getSiblingDB(\"shop\").dropDatabase()");
+        assert_eq!(plugin.drop_database("shop"), "db.getSiblingDB(\"shop\").dropDatabase()");
+    }
+
+    #[test]
+    fn test_collection_commands_target_their_own_database() {
+        let plugin = create_plugin();
+        // The tree runs these on a fresh session whose current database is the connection default
+        for statement in [
+            plugin.drop_table("staging", "orders"),
+            plugin.truncate_table("staging", "orders"),
+            plugin.rename_table("staging", "orders", "orders_old"),
+            plugin.drop_database("staging"),
+        ] {
+            let parsed = parse_mongo_statement(&statement).unwrap();
+            assert_eq!(parsed.database.as_deref(), Some("staging"), "{}", statement);
+        }
+        assert_eq!(
+            parse_mongo_statement(&plugin.truncate_table("staging", "orders")).unwrap().command,
+            MongoCommand::DeleteMany { collection: "orders".to_string(), filter: serde_json::json!({}) }
+        );
+        assert_eq!(plugin.classify_statement(&plugin.drop_table("staging", "orders")), StatementType::Ddl);
+    }
+}
@@ -0,0 +1,622 @@
+//! Mongo shell commands accepted by the editor for MongoDB connections.
+//!
+//! Supported are `db.<collection>.find(filter, projection)` with `.sort()`, `.skip()` and `.limit()`,
+//! `db.<collection>.aggregate([...])`, the collection commands `drop()`, `deleteMany(filter)` and
+//! `renameCollection(name)`, and `db.dropDatabase()`. A leading `db.getSiblingDB("name")` runs the
+//! statement against that database instead of the current one. Arguments are written as in the shell: unquoted keys,
+//! single-quoted strings, trailing commas and the `ObjectId()`/`ISODate()` helpers are rewritten to
+//! extended JSON before parsing.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindCommand {
+    pub collection: String,
+    pub filter: Value,
+    pub projection: Option<Value>,
+    pub sort: Option<Value>,
+    pub skip: Option<u64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MongoCommand {
+    Find(FindCommand),
+    Aggregate { collection: String, pipeline: Vec<Value> },
+    Drop { collection: String },
+    DeleteMany { collection: String, filter: Value },
+    RenameCollection { collection: String, new_name: String },
+    DropDatabase,
+}
+
+/// A command and the database it targets, `None` meaning the connection's current database
+#[derive(Debug, Clone, PartialEq)]
+pub struct MongoStatement {
+    pub database: Option<String>,
+    pub command: MongoCommand,
+}
+
+impl MongoCommand {
+    /// Empty for `dropDatabase`, which works on the database rather than a collection
+    pub fn collection(&self) -> &str {
+        match self {
+            MongoCommand::Find(find) => &find.collection,
+            MongoCommand::Aggregate { collection, .. }
+            | MongoCommand::Drop { collection }
+            | MongoCommand::DeleteMany { collection, .. }
+            | MongoCommand::RenameCollection { collection, .. } => collection,
+            MongoCommand::DropDatabase => "",
+        }
+    }
+
+    pub fn is_ddl(&self) -> bool {
+        matches!(
+            self,
+            MongoCommand::Drop { .. } | MongoCommand::RenameCollection { .. } | MongoCommand::DropDatabase
+        )
+    }
+
+    /// `$out` and `$merge` stages write the pipeline output into a collection
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            MongoCommand::Find(_) => true,
+            MongoCommand::Aggregate { pipeline, .. } => !pipeline.iter().any(|stage| {
+                stage
+                    .as_object()
+                    .is_some_and(|stage| stage.contains_key("$out") || stage.contains_key("$merge"))
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// One `name` or `name(arguments)` element of a `db.a.b(...)` call chain
+struct ChainSegment {
+    name: String,
+    arguments: Option<String>,
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn skip_whitespace(chars: &[char], mut index: usize) -> usize {
+    while index < chars.len() && chars[index].is_whitespace() {
+        index += 1;
+    }
+    index
+}
+
+/// Index of the quote closing the string that starts at `start`, or `chars.len()` if it is unterminated
+fn string_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut index = start + 1;
+    while index < chars.len() {
+        match chars[index] {
+            '\\' => index += 2,
+            c if c == quote => return index,
+            _ => index += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Index of the `)` that closes the parenthesis at `open`
+fn matching_paren(chars: &[char], open: usize) -> Result<usize> {
+    let mut depth = 0;
+    let mut index = open;
+    while index < chars.len() {
+        match chars[index] {
+            '"' | '\'' => index = string_end(chars, index),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(index);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    Err(anyhow!("括号没有闭合"))
+}
+
+fn parse_call_chain(text: &str) -> Result<Vec<ChainSegment>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut segments = Vec::new();
+    let mut index = skip_whitespace(&chars, 0);
+
+    loop {
+        let start = index;
+        while index < chars.len() && (is_identifier_part(chars[index]) || chars[index] == '-') {
+            index += 1;
+        }
+        let name: String = chars[start..index].iter().collect();
+        if name.is_empty() {
+            bail!("无法解析 `{}`", chars[start..].iter().take(30).collect::<String>());
+        }
+
+        index = skip_whitespace(&chars, index);
+        let mut arguments = None;
+        if chars.get(index) == Some(&'(') {
+            let end = matching_paren(&chars, index)?;
+            arguments = Some(chars[index + 1..end].iter().collect());
+            index = skip_whitespace(&chars, end + 1);
+        }
+        segments.push(ChainSegment { name, arguments });
+
+        match chars.get(index) {
+            None => break,
+            Some('.') => index = skip_whitespace(&chars, index + 1),
+            Some(_) => bail!("无法解析 `{}`", chars[index..].iter().take(30).collect::<String>()),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Split call arguments on the commas outside of strings and brackets
+fn split_arguments(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        match c {
+            '"' | '\'' => {
+                let end = string_end(&chars, index).min(chars.len() - 1);
+                current.extend(&chars[index..=end]);
+                index = end + 1;
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(std::mem::take(&mut current));
+                index += 1;
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+        index += 1;
+    }
+    arguments.push(current);
+
+    arguments
+        .into_iter()
+        .map(|argument| argument.trim().to_string())
+        .filter(|argument| !argument.is_empty())
+        .collect()
+}
+
+fn call_arguments(segment: &ChainSegment) -> Result<Vec<Value>> {
+    let arguments = segment
+        .arguments
+        .as_deref()
+        .ok_or_else(|| anyhow!("{} 需要使用方法调用的形式", segment.name))?;
+    split_arguments(arguments).iter().map(|argument| parse_relaxed_json(argument)).collect()
+}
+
+fn decode_string(chars: &[char]) -> String {
+    let mut decoded = String::with_capacity(chars.len());
+    let mut index = 0;
+    while index < chars.len() {
+        if chars[index] != '\\' || index + 1 >= chars.len() {
+            decoded.push(chars[index]);
+            index += 1;
+            continue;
+        }
+        match chars[index + 1] {
+            'n' => decoded.push('\n'),
+            't' => decoded.push('\t'),
+            'r' => decoded.push('\r'),
+            'u' if index + 6 <= chars.len() => {
+                let hex: String = chars[index + 2..index + 6].iter().collect();
+                if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    decoded.push(c);
+                    index += 6;
+                    continue;
+                }
+                decoded.push('u');
+            }
+            other => decoded.push(other),
+        }
+        index += 2;
+    }
+    decoded
+}
+
+fn scalar_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+/// Extended JSON for the shell helpers `ObjectId`, `ISODate`/`Date` and the `Number*` wrappers
+fn shell_helper(name: &str, arguments: &str) -> Result<String> {
+    let argument = if arguments.trim().is_empty() {
+        None
+    } else {
+        Some(parse_relaxed_json(arguments)?)
+    };
+
+    let value = match (name, argument) {
+        ("ObjectId", Some(Value::String(id))) => json!({ "$oid": id }),
+        ("ISODate" | "Date", Some(Value::String(date))) => json!({ "$date": date }),
+        ("ISODate" | "Date", Some(Value::Number(millis))) => json!({ "$date": { "$numberLong": millis.to_string() } }),
+        ("NumberLong", Some(value)) if scalar_text(&value).is_some() => json!({ "$numberLong": scalar_text(&value) }),
+        ("NumberInt", Some(value)) if scalar_text(&value).is_some() => json!({ "$numberInt": scalar_text(&value) }),
+        ("NumberDecimal", Some(value)) if scalar_text(&value).is_some() => json!({ "$numberDecimal": scalar_text(&value) }),
+        _ => bail!("不支持 {}({})", name, arguments.trim()),
+    };
+    Ok(value.to_string())
+}
+
+fn to_strict_json(text: &str) -> Result<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        match c {
+            '"' | '\'' => {
+                let end = string_end(&chars, index);
+                if end >= chars.len() {
+                    bail!("字符串没有结束");
+                }
+                output.push_str(&serde_json::to_string(&decode_string(&chars[index + 1..end]))?);
+                index = end + 1;
+            }
+            ',' => {
+                // Trailing commas are valid in the shell but not in JSON
+                let next = skip_whitespace(&chars, index + 1);
+                if !matches!(chars.get(next), Some('}') | Some(']') | None) {
+                    output.push(',');
+                }
+                index += 1;
+            }
+            c if is_identifier_start(c) => {
+                let start = index;
+                while index < chars.len() && is_identifier_part(chars[index]) {
+                    index += 1;
+                }
+                let word: String = chars[start..index].iter().collect();
+                let next = skip_whitespace(&chars, index);
+                match chars.get(next) {
+                    Some(':') => output.push_str(&serde_json::to_string(&word)?),
+                    Some('(') => {
+                        let end = matching_paren(&chars, next)?;
+                        let arguments: String = chars[next + 1..end].iter().collect();
+                        output.push_str(&shell_helper(&word, &arguments)?);
+                        index = end + 1;
+                    }
+                    // `new Date(...)` is the same as `Date(...)`
+                    _ if word == "new" => {}
+                    _ => output.push_str(&word),
+                }
+            }
+            _ => {
+                output.push(c);
+                index += 1;
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Parse a shell-style value into JSON; BSON types are kept as extended JSON
+pub fn parse_relaxed_json(text: &str) -> Result<Value> {
+    let json = to_strict_json(text)?;
+    serde_json::from_str(&json).map_err(|e| anyhow!("无法解析 `{}`：{}", text.trim(), e))
+}
+
+fn parse_find(collection: String, arguments: Vec<Value>, modifiers: &[ChainSegment]) -> Result<MongoCommand> {
+    if arguments.len() > 2 {
+        bail!("find 最多接受查询条件和投影两个参数");
+    }
+    let mut arguments = arguments.into_iter();
+    let filter = arguments.next().unwrap_or_else(|| json!({}));
+    if !filter.is_object() {
+        bail!("find 的查询条件必须是对象");
+    }
+    let projection = arguments.next();
+    if projection.as_ref().is_some_and(|projection| !projection.is_object()) {
+        bail!("find 的投影必须是对象");
+    }
+
+    let mut find = FindCommand {
+        collection,
+        filter,
+        projection,
+        sort: None,
+        skip: None,
+        limit: None,
+    };
+    for modifier in modifiers {
+        let value = match call_arguments(modifier)?.as_slice() {
+            [value] => value.clone(),
+            _ => bail!("{} 需要一个参数", modifier.name),
+        };
+        match modifier.name.as_str() {
+            "sort" if value.is_object() => find.sort = Some(value),
+            "skip" => find.skip = Some(value.as_u64().ok_or_else(|| anyhow!("skip 需要非负整数"))?),
+            "limit" => find.limit = Some(value.as_i64().ok_or_else(|| anyhow!("limit 需要整数"))?),
+            "sort" => bail!("sort 的参数必须是对象"),
+            other => bail!("不支持 {}，find 之后只能使用 sort、skip 和 limit", other),
+        }
+    }
+
+    Ok(MongoCommand::Find(find))
+}
+
+fn string_argument(segment: &ChainSegment) -> Result<String> {
+    match call_arguments(segment)?.as_slice() {
+        [Value::String(text)] => Ok(text.clone()),
+        _ => bail!("{} 需要一个字符串参数", segment.name),
+    }
+}
+
+/// Parse one statement without its target database, see `parse_mongo_statement`
+pub fn parse_mongo_command(text: &str) -> Result<MongoCommand> {
+    parse_mongo_statement(text).map(|statement| statement.command)
+}
+
+/// Parse one `db.<collection>.<method>(...)` statement, optionally prefixed with `getSiblingDB(...)`.
+/// Collection names containing dots can be written directly or with `db.getCollection("name")`.
+pub fn parse_mongo_statement(text: &str) -> Result<MongoStatement> {
+    let text = text.trim().trim_end_matches(';').trim();
+    let Some(chain) = text.strip_prefix("db.") else {
+        bail!("MongoDB 查询需要以 db.<集合> 开头");
+    };
+    let segments = parse_call_chain(chain)?;
+
+    let (database, segments) = match segments.split_first() {
+        Some((first, rest)) if first.name == "getSiblingDB" => (Some(string_argument(first)?), rest),
+        _ => (None, segments.as_slice()),
+    };
+    if let [segment] = segments {
+        if segment.name == "dropDatabase" {
+            if !call_arguments(segment)?.is_empty() {
+                bail!("dropDatabase 不接受参数");
+            }
+            return Ok(MongoStatement { database, command: MongoCommand::DropDatabase });
+        }
+    }
+
+    let (collection, calls) = match segments.first() {
+        Some(segment) if segment.name == "getCollection" => (string_argument(segment)?, &segments[1..]),
+        _ => {
+            let method_index = segments
+                .iter()
+                .position(|segment| segment.arguments.is_some())
+                .ok_or_else(|| anyhow!("缺少 find(...) 或 aggregate(...) 调用"))?;
+            let collection = segments[..method_index]
+                .iter()
+                .map(|segment| segment.name.as_str())
+                .collect::<Vec<_>>()
+                .join(".");
+            (collection, &segments[method_index..])
+        }
+    };
+    if collection.is_empty() {
+        bail!("缺少集合名称");
+    }
+    let Some((method, modifiers)) = calls.split_first() else {
+        bail!("缺少 find(...) 或 aggregate(...) 调用");
+    };
+
+    let arguments = call_arguments(method)?;
+    if method.name != "find" {
+        if let Some(modifier) = modifiers.first() {
+            bail!("{} 之后不支持 {}", method.name, modifier.name);
+        }
+    }
+    let command = match method.name.as_str() {
+        "find" => parse_find(collection, arguments, modifiers)?,
+        "aggregate" => {
+            let pipeline = match arguments.as_slice() {
+                [Value::Array(stages)] if stages.iter().all(Value::is_object) => stages.clone(),
+                _ => bail!("aggregate 需要一个由阶段对象组成的数组"),
+            };
+            MongoCommand::Aggregate { collection, pipeline }
+        }
+        "drop" if arguments.is_empty() => MongoCommand::Drop { collection },
+        "deleteMany" => match arguments.as_slice() {
+            [filter] if filter.is_object() => MongoCommand::DeleteMany { collection, filter: filter.clone() },
+            _ => bail!("deleteMany 需要一个查询条件对象"),
+        },
+        "renameCollection" => MongoCommand::RenameCollection { collection, new_name: string_argument(method)? },
+        "drop" => bail!("drop 不接受参数"),
+        other => bail!("只支持 find、aggregate、drop、deleteMany 和 renameCollection，不支持 {}", other),
+    };
+
+    Ok(MongoStatement { database, command })
+}
+
+/// Split a script on `;` and on lines starting a new `db.` statement; `//` comments are dropped
+pub fn split_mongo_statements(script: &str) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut index = 0;
+
+    let mut flush = |current: &mut String| {
+        let statement = current.trim();
+        if !statement.is_empty() {
+            statements.push(statement.to_string());
+        }
+        current.clear();
+    };
+
+    while index < chars.len() {
+        let c = chars[index];
+        match c {
+            '"' | '\'' => {
+                let end = string_end(&chars, index).min(chars.len() - 1);
+                current.extend(&chars[index..=end]);
+                index = end + 1;
+                continue;
+            }
+            '/' if chars.get(index + 1) == Some(&'/') => {
+                while index < chars.len() && chars[index] != '\n' {
+                    index += 1;
+                }
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ';' if depth <= 0 => {
+                flush(&mut current);
+                index += 1;
+                continue;
+            }
+            '\n' if depth <= 0 => {
+                // A chained `.sort()` on the next line continues the statement, a new `db.` starts another
+                let next = skip_whitespace(&chars, index + 1);
+                if chars[next..].starts_with(&['d', 'b', '.']) {
+                    flush(&mut current);
+                }
+            }
+            _ => {}
+        }
+        current.push(c);
+        index += 1;
+    }
+    flush(&mut current);
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_relaxed_json() {
+        let value = parse_relaxed_json(
+            "{ status: 'paid', _id: ObjectId(\"64b7f0c2a1b2c3d4e5f60718\"), created: ISODate('2024-01-01T00:00:00Z'), 'it\\'s': [1, 2,], }",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "status": "paid",
+                "_id": { "$oid": "64b7f0c2a1b2c3d4e5f60718" },
+                "created": { "$date": "2024-01-01T00:00:00Z" },
+                "it's": [1, 2],
+            })
+        );
+        assert_eq!(parse_relaxed_json("{ total: { $gte: NumberLong(10) } }").unwrap(), json!({ "total": { "$gte": { "$numberLong": "10" } } }));
+        assert!(parse_relaxed_json("{ name: 'open").is_err());
+        assert!(parse_relaxed_json("{ n: Foo(1) }").is_err());
+    }
+
+    #[test]
+    fn test_parse_find_with_modifiers() {
+        let command = parse_mongo_command(
+            "db.orders.find({ status: \"paid\" }, { total: 1 })\n  .sort({ created: -1 })\n  .skip(20)\n  .limit(10);",
+        )
+        .unwrap();
+        assert_eq!(
+            command,
+            MongoCommand::Find(FindCommand {
+                collection: "orders".to_string(),
+                filter: json!({ "status": "paid" }),
+                projection: Some(json!({ "total": 1 })),
+                sort: Some(json!({ "created": -1 })),
+                skip: Some(20),
+                limit: Some(10),
+            })
+        );
+        assert!(command.is_read_only());
+
+        match parse_mongo_command("db.system.profile.find()").unwrap() {
+            MongoCommand::Find(find) => {
+                assert_eq!(find.collection, "system.profile");
+                assert_eq!(find.filter, json!({}));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+        assert_eq!(parse_mongo_command("db.getCollection('my.logs').find({})").unwrap().collection(), "my.logs");
+    }
+
+    #[test]
+    fn test_parse_aggregate() {
+        let command = parse_mongo_command("db.orders.aggregate([{ $match: { status: 'paid' } }, { $group: { _id: '$user', total: { $sum: '$total' } } }])").unwrap();
+        match &command {
+            MongoCommand::Aggregate { collection, pipeline } => {
+                assert_eq!(collection, "orders");
+                assert_eq!(pipeline.len(), 2);
+                assert_eq!(pipeline[1]["$group"]["total"], json!({ "$sum": "$total" }));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+        assert!(command.is_read_only());
+        assert!(!parse_mongo_command("db.orders.aggregate([{ $out: 'paid_orders' }])").unwrap().is_read_only());
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported_commands() {
+        assert!(parse_mongo_command("SELECT * FROM orders").is_err());
+        assert!(parse_mongo_command("db.orders.insertOne({ a: 1 })").is_err());
+        assert!(parse_mongo_command("db.orders.find().count()").is_err());
+        assert!(parse_mongo_command("db.orders.find([1])").is_err());
+        assert!(parse_mongo_command("db.orders.aggregate({ $match: {} })").is_err());
+        assert!(parse_mongo_command("db.orders").is_err());
+        assert!(parse_mongo_command("db.orders.drop({})").is_err());
+        assert!(parse_mongo_command("db.orders.deleteMany()").is_err());
+        assert!(parse_mongo_command("db.getSiblingDB(1).orders.find()").is_err());
+    }
+
+    #[test]
+    fn test_parse_collection_commands_with_sibling_database() {
+        let statement = parse_mongo_statement("db.getSiblingDB(\"staging\").getCollection(\"orders\").drop()").unwrap();
+        assert_eq!(statement.database.as_deref(), Some("staging"));
+        assert_eq!(statement.command, MongoCommand::Drop { collection: "orders".to_string() });
+        assert!(statement.command.is_ddl());
+
+        let statement = parse_mongo_statement("db.orders.deleteMany({})").unwrap();
+        assert_eq!(statement.database, None);
+        assert_eq!(
+            statement.command,
+            MongoCommand::DeleteMany { collection: "orders".to_string(), filter: json!({}) }
+        );
+        assert!(!statement.command.is_read_only());
+
+        assert_eq!(
+            parse_mongo_command("db.getSiblingDB('staging').orders.renameCollection('orders_old')").unwrap(),
+            MongoCommand::RenameCollection { collection: "orders".to_string(), new_name: "orders_old".to_string() }
+        );
+        let statement = parse_mongo_statement("db.getSiblingDB(\"staging\").dropDatabase()").unwrap();
+        assert_eq!(statement.database.as_deref(), Some("staging"));
+        assert_eq!(statement.command, MongoCommand::DropDatabase);
+    }
+
+    #[test]
+    fn test_split_mongo_statements() {
+        let script = "// recent orders\ndb.orders.find({ note: 'a;b' })\n  .limit(5)\ndb.users.find({\n  name: 'x'\n});db.users.aggregate([])";
+        assert_eq!(
+            split_mongo_statements(script),
+            vec![
+                "db.orders.find({ note: 'a;b' })\n  .limit(5)",
+                "db.users.find({\n  name: 'x'\n})",
+                "db.users.aggregate([])",
+            ]
+        );
+    }
+}
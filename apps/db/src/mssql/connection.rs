@@ -15,7 +15,7 @@ use crate::executor::{
     ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult,
     SqlStatementClassifier,
 };
-use crate::{DatabasePlugin, SqlValue};
+use crate::{proxy, tls, DatabasePlugin, SqlValue};
 
 pub struct MssqlDbConnection {
     config: DbConnectionConfig,
@@ -211,18 +211,33 @@ impl DbConnection for MssqlDbConnection {
         tiberius_config.port(config.port);
         tiberius_config.authentication(AuthMethod::sql_server(&config.username, &config.password));
 
-        // Trust certificate (default: true)
-        if config.get_param("trust_cert").map(|v| v != "false").unwrap_or(true) {
-            tiberius_config.trust_cert();
-        }
+        let ssl = config.ssl();
+        if ssl.is_enabled() {
+            // tiberius always checks the host name once the certificate is verified,
+            // so verify-ca behaves like verify-full
+            if tls::client_identity(&ssl)?.is_some() {
+                return Err(tls::unsupported("SQL Server", "client certificates"));
+            }
+            tiberius_config.encryption(tiberius::EncryptionLevel::Required);
+            if !ssl.mode.verifies_certificate() {
+                tiberius_config.trust_cert();
+            } else if let Some(ca) = &ssl.ca_cert {
+                tiberius_config.trust_cert_ca(ca);
+            }
+        } else {
+            // Trust certificate (default: true)
+            if config.get_param("trust_cert").map(|v| v != "false").unwrap_or(true) {
+                tiberius_config.trust_cert();
+            }
 
-        // Encryption level: off, on, required (default: off for compatibility)
-        let encrypt = config.get_param("encrypt").map(|s| s.as_str()).unwrap_or("off");
-        match encrypt {
-            "on" => tiberius_config.encryption(tiberius::EncryptionLevel::On),
-            "required" => tiberius_config.encryption(tiberius::EncryptionLevel::Required),
-            _ => tiberius_config.encryption(tiberius::EncryptionLevel::NotSupported),
-        };
+            // Encryption level: off, on, required (default: off for compatibility)
+            let encrypt = config.get_param("encrypt").map(|s| s.as_str()).unwrap_or("off");
+            match encrypt {
+                "on" => tiberius_config.encryption(tiberius::EncryptionLevel::On),
+                "required" => tiberius_config.encryption(tiberius::EncryptionLevel::Required),
+                _ => tiberius_config.encryption(tiberius::EncryptionLevel::NotSupported),
+            };
+        }
 
         // Application name
         if let Some(app_name) = config.get_param("application_name") {
@@ -239,16 +254,18 @@ impl DbConnection for MssqlDbConnection {
         debug!("[MSSQL] Connect timeout: {}s", connect_timeout);
 
         debug!("[MSSQL] Establishing TCP connection...");
-        let tcp = tokio::time::timeout(
-            std::time::Duration::from_secs(connect_timeout),
-            TcpStream::connect(tiberius_config.get_addr())
-        )
+        let dial = async {
+            match config.proxy() {
+                Some(proxy) => proxy::tunnel(&proxy, &config.host, config.port).await,
+                None => TcpStream::connect(tiberius_config.get_addr())
+                    .await
+                    .map_err(|e| DbError::ConnectionError(format!("Failed to connect to TCP: {}", e))),
+            }
+        };
+        let tcp = tokio::time::timeout(std::time::Duration::from_secs(connect_timeout), dial)
             .await
             .map_err(|_| DbError::ConnectionError("Connection timeout".to_string()))?
-            .map_err(|e| {
-                error!("[MSSQL] TCP connection failed: {}", e);
-                DbError::ConnectionError(format!("Failed to connect to TCP: {}", e))
-            })?;
+            .inspect_err(|e| error!("[MSSQL] TCP connection failed: {}", e))?;
         debug!("[MSSQL] TCP connection established");
 
         debug!("[MSSQL] Authenticating with SQL Server...");
@@ -256,7 +273,7 @@ impl DbConnection for MssqlDbConnection {
             .await
             .map_err(|e| {
                 error!("[MSSQL] Authentication failed: {}", e);
-                DbError::ConnectionError(format!("Failed to connect to MSSQL: {}", e))
+                tls::connection_error(&ssl, e)
             })?;
         info!("[MSSQL] Connected successfully");
 
@@ -9,6 +9,7 @@ use crate::connection::{DbConnection, DbError};
 use crate::executor::{ExecOptions, SqlResult};
 use crate::mssql::connection::MssqlDbConnection;
 use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::server_status::{query_server_status, ServerStatus};
 use crate::types::*;
 
 /// MSSQL database plugin implementation (stateless)
@@ -38,10 +39,107 @@ impl DatabasePlugin for MsSqlPlugin {
         true
     }
 
+    /// SQL Server rejects table value constructors with more than 1000 rows
+    fn max_rows_per_insert(&self) -> Option<usize> {
+        Some(1000)
+    }
+
     fn supports_sequences(&self) -> bool {
         true
     }
 
+    fn supports_synonyms(&self) -> bool {
+        true
+    }
+
+    fn supports_linked_servers(&self) -> bool {
+        true
+    }
+
+    fn build_preview_query(&self, object_reference: &str) -> String {
+        format!("SELECT TOP 100 * FROM {}", object_reference)
+    }
+
+    async fn list_synonyms(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<SynonymInfo>> {
+        let sql = format!(
+            r#"
+            SELECT
+                sn.name,
+                s.name AS schema_name,
+                sn.base_object_name
+            FROM [{database}].sys.synonyms sn
+            INNER JOIN [{database}].sys.schemas s ON sn.schema_id = s.schema_id
+            ORDER BY s.name, sn.name
+            "#,
+            database = database.replace("]", "]]")
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list synonyms: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                SynonymInfo {
+                    name: row.get(0).and_then(|v| v.clone()).unwrap_or_default(),
+                    schema: row.get(1).and_then(|v| v.clone()),
+                    base_object: row.get(2).and_then(|v| v.clone()).unwrap_or_default(),
+                }
+            }).collect())
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    async fn list_linked_servers(&self, connection: &dyn DbConnection) -> Result<Vec<LinkedServerInfo>> {
+        let sql = "SELECT name, product, data_source FROM sys.servers WHERE is_linked = 1 ORDER BY name";
+
+        let result = connection.query(sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list linked servers: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                LinkedServerInfo {
+                    name: row.get(0).and_then(|v| v.clone()).unwrap_or_default(),
+                    product: row.get(1).and_then(|v| v.clone()).filter(|v| !v.is_empty()),
+                    data_source: row.get(2).and_then(|v| v.clone()).filter(|v| !v.is_empty()),
+                }
+            }).collect())
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    /// Tables and views of the linked server's default catalog, read through the provider
+    async fn list_linked_server_tables(&self, connection: &dyn DbConnection, server: &str) -> Result<Vec<LinkedTableInfo>> {
+        let sql = format!("EXEC sp_tables_ex @table_server = N'{}'", server.replace("'", "''"));
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list tables of linked server {}: {}", server, e))?;
+
+        match result {
+            // TABLE_CAT, TABLE_SCHEM, TABLE_NAME, TABLE_TYPE, REMARKS
+            SqlResult::Query(query_result) => Ok(query_result.rows.iter()
+                .filter_map(|row| {
+                    let table_type = row.get(3).and_then(|v| v.clone()).unwrap_or_default();
+                    if table_type != "TABLE" && table_type != "VIEW" {
+                        return None;
+                    }
+                    Some(LinkedTableInfo {
+                        catalog: row.get(0).and_then(|v| v.clone()).filter(|v| !v.is_empty()),
+                        schema: row.get(1).and_then(|v| v.clone()).filter(|v| !v.is_empty()),
+                        name: row.get(2).and_then(|v| v.clone())?,
+                        table_type,
+                    })
+                })
+                .collect()),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("{}", err.message)),
+            _ => Ok(vec![]),
+        }
+    }
+
     fn format_pagination(&self, limit: usize, offset: usize, order_clause: &str) -> String {
         if order_clause.is_empty() {
             format!(" ORDER BY (SELECT NULL) OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit)
@@ -166,7 +264,6 @@ impl DatabasePlugin for MsSqlPlugin {
                 ("TOP", "Limit rows"),
                 ("OFFSET", "Skip rows"),
                 ("FETCH", "Fetch rows"),
-                ("PARTITION BY", "Partition window function"),
                 ("PIVOT", "Pivot rows to columns"),
                 ("UNPIVOT", "Unpivot columns to rows"),
                 ("CROSS APPLY", "Apply right expression for each left row"),
@@ -175,7 +272,6 @@ impl DatabasePlugin for MsSqlPlugin {
                 ("OUTPUT", "Output clause"),
                 ("INSERTED", "Inserted pseudo table"),
                 ("DELETED", "Deleted pseudo table"),
-                ("OVER", "Window function"),
                 ("ROW_NUMBER", "Row number window function"),
             ],
             functions: vec![
@@ -208,6 +304,11 @@ impl DatabasePlugin for MsSqlPlugin {
                 ("DAY(date)", "Extract day"),
                 ("EOMONTH(date)", "End of month"),
                 ("DATEFROMPARTS(y,m,d)", "Create date from parts"),
+                ("DATEDIFF_BIG(part, date1, date2)", "Difference as bigint"),
+                ("DATETRUNC(part, date)", "Truncate to date part (2022+)"),
+                ("DATENAME(part, date)", "Date part as string"),
+                ("SWITCHOFFSET(dto, offset)", "Change time zone offset"),
+                ("TODATETIMEOFFSET(dt, offset)", "Attach time zone offset"),
                 ("ROUND(num, decimals)", "Round number"),
                 ("CEILING(num)", "Ceiling function"),
                 ("FLOOR(num)", "Floor function"),
@@ -233,6 +334,13 @@ impl DatabasePlugin for MsSqlPlugin {
                 ("FOR JSON", "Format result as JSON"),
                 ("OPENXML(doc, xpath)", "Parse XML to table"),
                 ("ISJSON(expr)", "Check if valid JSON"),
+                ("JSON_MODIFY(json, path, val)", "Update JSON value"),
+                ("JSON_OBJECT(key: val, ...)", "Create JSON object (2022+)"),
+                ("JSON_ARRAY(val, ...)", "Create JSON array (2022+)"),
+                ("PERCENT_RANK()", "Relative rank"),
+                ("CUME_DIST()", "Cumulative distribution"),
+                ("PERCENTILE_CONT(p) WITHIN GROUP (ORDER BY col)", "Continuous percentile"),
+                ("PERCENTILE_DISC(p) WITHIN GROUP (ORDER BY col)", "Discrete percentile"),
             ],
             operators: vec![
                 ("+=", "Add and assign"),
@@ -243,6 +351,7 @@ impl DatabasePlugin for MsSqlPlugin {
                 ("!=", "Not equal"),
                 ("!<", "Not less than"),
                 ("!>", "Not greater than"),
+                ("AT TIME ZONE", "Convert to time zone"),
             ],
             data_types: vec![
                 ("BIT", "Boolean (0/1)"),
@@ -467,7 +576,8 @@ impl DatabasePlugin for MsSqlPlugin {
                 c.COLUMN_DEFAULT,
                 COLUMNPROPERTY(OBJECT_ID('[{database}].[{schema}].[{table}]'), c.COLUMN_NAME, 'IsIdentity') as is_identity,
                 CAST(ep.value AS NVARCHAR(MAX)) as column_comment,
-                CASE WHEN pk.COLUMN_NAME IS NOT NULL THEN 1 ELSE 0 END as is_primary_key
+                CASE WHEN pk.COLUMN_NAME IS NOT NULL THEN 1 ELSE 0 END as is_primary_key,
+                c.COLLATION_NAME
             FROM [{database}].INFORMATION_SCHEMA.COLUMNS c
             LEFT JOIN [{database}].sys.extended_properties ep
                 ON ep.major_id = OBJECT_ID('[{database}].[{schema}].[{table}]')
@@ -506,6 +616,7 @@ impl DatabasePlugin for MsSqlPlugin {
                     is_primary_key,
                     default_value: row.get(3).and_then(|v| v.clone()),
                     comment: row.get(5).and_then(|v| v.clone()),
+                    collation: row.get(7).and_then(|v| v.clone()),
                 }
             }).collect())
         } else {
@@ -778,6 +889,40 @@ impl DatabasePlugin for MsSqlPlugin {
         })
     }
 
+    async fn get_routine_source(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        if !matches!(object_type, DbNodeType::Function | DbNodeType::Procedure | DbNodeType::Trigger) {
+            return Err(anyhow::anyhow!("{} source is not supported: {}", object_type, name));
+        }
+        let schema_filter = match schema {
+            Some(schema) => format!(" AND SCHEMA_NAME(o.schema_id) = N'{}'", schema.replace("'", "''")),
+            None => String::new(),
+        };
+        let sql = format!(
+            r#"
+            SELECT m.definition
+            FROM [{database}].sys.sql_modules m
+            JOIN [{database}].sys.objects o ON o.object_id = m.object_id
+            WHERE o.name = N'{name}'{schema_filter}
+            "#,
+            database = database.replace("]", "]]"),
+            name = name.replace("'", "''"),
+            schema_filter = schema_filter
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load routine source: {}", e))?;
+
+        match result {
+            // 加密的对象 definition 为 NULL
+            SqlResult::Query(query_result) => query_result.rows.first()
+                .and_then(|row| row.first().cloned().flatten())
+                .ok_or_else(|| anyhow::anyhow!("Routine source not found or encrypted: {}", name)),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("{}", err.message)),
+            _ => Err(anyhow::anyhow!("Unexpected result type")),
+        }
+    }
+
     async fn list_sequences_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
         use gpui::px;
 
@@ -1199,6 +1344,50 @@ impl DatabasePlugin for MsSqlPlugin {
         sql
     }
 
+    fn build_explain_sql(&self, sql: &str) -> Option<String> {
+        // SHOWPLAN_TEXT must be the only statement in its batch
+        Some(format!(
+            "SET SHOWPLAN_TEXT ON\nGO\n{}\nGO\nSET SHOWPLAN_TEXT OFF",
+            sql.trim().trim_end_matches(';')
+        ))
+    }
+
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        // Despite its name the counter is cumulative; the rate is the difference between samples
+        Some("SELECT cntr_value FROM sys.dm_os_performance_counters WHERE counter_name = 'Batch Requests/sec'")
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        // The DMVs need VIEW SERVER STATE; without it only the version is known
+        let version = "SELECT CONCAT('SQL Server ', CAST(SERVERPROPERTY('ProductVersion') AS NVARCHAR(128)), ' ', CAST(SERVERPROPERTY('Edition') AS NVARCHAR(128)))";
+        match query_server_status(
+            connection,
+            &format!(
+                "{}, DATEDIFF(SECOND, sqlserver_start_time, SYSDATETIME()), \
+                 (SELECT COUNT(*) FROM sys.dm_exec_sessions WHERE is_user_process = 1) FROM sys.dm_os_sys_info",
+                version
+            ),
+        ).await {
+            Ok(status) => Ok(status),
+            Err(_) => query_server_status(connection, version).await,
+        }
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        Some("DBCC USEROPTIONS".to_string())
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        // SET options take bare keywords and numbers, e.g. SET DATEFORMAT dmy or SET ANSI_NULLS ON
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("isolation level") {
+            return Some(format!("SET TRANSACTION ISOLATION LEVEL {}", value.to_uppercase()));
+        }
+        // DBCC USEROPTIONS shows enabled ON/OFF options with the value SET
+        let value = if value.eq_ignore_ascii_case("set") { "ON" } else { value };
+        Some(format!("SET {} {}", name.to_uppercase(), value))
+    }
+
     fn build_limit_clause(&self) -> String {
         String::new()
     }
@@ -1689,4 +1878,36 @@ mod tests {
 
         assert!(info.keywords.iter().any(|(k, _)| *k == "TOP"));
     }
+
+    #[test]
+    fn test_format_linked_table_reference() {
+        let plugin = create_plugin();
+        let table = LinkedTableInfo {
+            catalog: Some("Sales".to_string()),
+            schema: Some("dbo".to_string()),
+            name: "Order]Lines".to_string(),
+            table_type: "TABLE".to_string(),
+        };
+        assert_eq!(
+            plugin.build_preview_query(&plugin.format_linked_table_reference("REMOTE-01", &table)),
+            "SELECT TOP 100 * FROM [REMOTE-01].[Sales].[dbo].[Order]]Lines]"
+        );
+
+        let without_catalog = LinkedTableInfo { catalog: None, ..table };
+        assert_eq!(
+            plugin.format_linked_table_reference("ORA", &without_catalog),
+            "[ORA]..[dbo].[Order]]Lines]"
+        );
+    }
+
+    #[test]
+    fn test_build_set_session_variable_sql() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.build_set_session_variable_sql("isolation level", "read committed").as_deref(),
+            Some("SET TRANSACTION ISOLATION LEVEL READ COMMITTED")
+        );
+        assert_eq!(plugin.build_set_session_variable_sql("ansi_nulls", "SET").as_deref(), Some("SET ANSI_NULLS ON"));
+        assert_eq!(plugin.build_set_session_variable_sql("dateformat", "dmy").as_deref(), Some("SET DATEFORMAT dmy"));
+    }
 }
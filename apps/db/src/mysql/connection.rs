@@ -1,19 +1,24 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
-use mysql_async::{prelude::*, Conn, Opts, OptsBuilder, Row, Value};
-use one_core::storage::DbConnectionConfig;
+use mysql_async::{prelude::*, ClientIdentity, Conn, Opts, OptsBuilder, Row, SslOpts, Value};
+use one_core::storage::{DbConnectionConfig, SslConfig};
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
 
+use crate::cloud_auth::{self, CloudAuth};
 use crate::connection::{DbConnection, DbError, StreamingProgress};
 use crate::executor::{ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult, SqlStatementClassifier};
-use crate::{DatabasePlugin, SqlValue};
+use crate::proxy::{self, ProxyForward};
+use crate::{tls, DatabasePlugin, SqlValue};
 
 pub struct MysqlDbConnection {
     config: DbConnectionConfig,
     conn: Arc<Mutex<Option<Conn>>>,
+    /// Keeps the local proxy port open while connected
+    proxy_forward: Option<ProxyForward>,
 }
 
 impl MysqlDbConnection {
@@ -21,9 +26,27 @@ impl MysqlDbConnection {
         Self {
             config,
             conn: Arc::new(Mutex::new(None)),
+            proxy_forward: None,
         }
     }
 
+    /// Certificate files are read by the driver when it connects
+    fn ssl_opts(ssl: &SslConfig) -> Result<SslOpts, DbError> {
+        let mut opts = SslOpts::default()
+            .with_danger_accept_invalid_certs(!ssl.mode.verifies_certificate())
+            .with_danger_skip_domain_validation(!ssl.mode.verifies_hostname());
+        if let Some(ca) = &ssl.ca_cert {
+            opts = opts.with_root_certs(vec![PathBuf::from(ca).into()]);
+        }
+        if let Some((cert, key)) = tls::client_identity(ssl)? {
+            opts = opts.with_client_identity(Some(ClientIdentity::new(
+                PathBuf::from(cert).into(),
+                PathBuf::from(key).into(),
+            )));
+        }
+        Ok(opts)
+    }
+
     /// Extract value from mysql_async::Value
     fn extract_value(value: &Value) -> Option<String> {
         match value {
@@ -180,13 +203,24 @@ impl DbConnection for MysqlDbConnection {
     }
 
     async fn connect(&mut self) -> anyhow::Result<(), DbError> {
-        let config = &self.config;
+        let config = &cloud_auth::resolve(&self.config).await?;
+        let proxy_forward = ProxyForward::start(config).await?;
+        let ssl = config.ssl();
+        if proxy_forward.is_some() && ssl.mode.verifies_hostname() {
+            return Err(proxy::hostname_check_unsupported());
+        }
+        let (host, port) = match &proxy_forward {
+            Some(forward) => ("127.0.0.1", forward.local_port()),
+            None => (config.host.as_str(), config.port),
+        };
 
         let mut opts_builder = OptsBuilder::default()
-            .ip_or_hostname(&config.host)
-            .tcp_port(config.port)
+            .ip_or_hostname(host)
+            .tcp_port(port)
             .user(Some(&config.username))
-            .pass(Some(&config.password));
+            .pass(Some(&config.password))
+            // IAM tokens are sent as they are, through the mysql_clear_password plugin
+            .enable_cleartext_plugin(CloudAuth::of(config).is_enabled());
 
         if let Some(ref db) = config.database {
             opts_builder = opts_builder.db_name(Some(db));
@@ -200,15 +234,20 @@ impl DbConnection for MysqlDbConnection {
             opts_builder = opts_builder.wait_timeout(Some(wait_timeout));
         }
 
+        if ssl.is_enabled() {
+            opts_builder = opts_builder.ssl_opts(Some(Self::ssl_opts(&ssl)?));
+        }
+
         let opts = Opts::from(opts_builder);
         let conn = Conn::new(opts)
             .await
-            .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))?;
+            .map_err(|e| ProxyForward::connect_error(proxy_forward.as_ref(), tls::connection_error(&ssl, e)))?;
 
         {
             let mut guard = self.conn.lock().await;
             *guard = Some(conn);
         }
+        self.proxy_forward = proxy_forward;
 
         Ok(())
     }
@@ -224,6 +263,7 @@ impl DbConnection for MysqlDbConnection {
                 .await
                 .map_err(|e| DbError::ConnectionError(format!("Failed to disconnect: {}", e)))?;
         }
+        self.proxy_forward = None;
 
         Ok(())
     }
@@ -1,4 +1,5 @@
 mod connection;
 mod plugin;
 
+pub use connection::MysqlDbConnection;
 pub use plugin::MySqlPlugin;
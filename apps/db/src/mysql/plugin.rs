@@ -5,9 +5,13 @@ use gpui_component::table::Column;
 use one_core::storage::{DatabaseType, DbConnectionConfig};
 
 use crate::connection::{DbConnection, DbError};
-use crate::executor::{ExecOptions, SqlResult};
+use crate::executor::{ExecOptions, QueryResult, SqlResult};
 use crate::mysql::connection::MysqlDbConnection;
 use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::import_export::{InsertConflictClause, SqlConflictMode};
+use crate::server_status::{query_server_status, ServerStatus};
+use crate::session_variable::session_variable_literal;
+use crate::trash::{TrashEntry, TRASH_SCHEMA};
 use crate::types::*;
 
 /// MySQL database plugin implementation (stateless)
@@ -17,6 +21,48 @@ impl MySqlPlugin {
     pub fn new() -> Self {
         Self
     }
+
+    fn show_create_target(&self, database: &str, table: &str) -> String {
+        if database.is_empty() {
+            self.quote_identifier(table)
+        } else {
+            self.format_table_reference(database, None, table)
+        }
+    }
+
+    /// 从 `SHOW CREATE PROCEDURE/FUNCTION/TRIGGER` 的结果中取出定义；没有权限查看定义时该列为 NULL
+    fn routine_source_from_show_create(result: &QueryResult) -> Option<String> {
+        let row = result.rows.first()?;
+        let index = result.columns.iter().position(|c| {
+            c.eq_ignore_ascii_case("Create Procedure")
+                || c.eq_ignore_ascii_case("Create Function")
+                || c.eq_ignore_ascii_case("Create Event")
+                || c.eq_ignore_ascii_case("SQL Original Statement")
+        })?;
+        row.get(index).cloned().flatten()
+    }
+
+    /// 事件的执行计划：一次性事件为 `AT 时间`，周期事件为 `EVERY 间隔 单位`
+    fn event_schedule(event_type: &str, execute_at: Option<&str>, interval_value: Option<&str>, interval_field: Option<&str>) -> String {
+        if event_type.eq_ignore_ascii_case("ONE TIME") {
+            return execute_at.map(|at| format!("AT {}", at)).unwrap_or_default();
+        }
+        match (interval_value, interval_field) {
+            (Some(value), Some(field)) => format!("EVERY {} {}", value, field),
+            _ => String::new(),
+        }
+    }
+
+    /// 从 `SHOW CREATE TABLE` 的结果中取出建表语句；对视图返回的是 `Create View` 列
+    fn create_sql_from_show_create(result: &QueryResult) -> Option<String> {
+        let row = result.rows.first()?;
+        let index = result
+            .columns
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case("Create Table") || c.eq_ignore_ascii_case("Create View"))
+            .unwrap_or(1);
+        row.get(index).cloned().flatten()
+    }
 }
 
 #[async_trait::async_trait]
@@ -29,10 +75,19 @@ impl DatabasePlugin for MySqlPlugin {
         format!("`{}`", identifier.replace("`", "``"))
     }
 
+    /// Without NO_BACKSLASH_ESCAPES a trailing `\` would escape the closing quote
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+
     fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
         Box::new(sqlparser::dialect::MySqlDialect {})
     }
 
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        Some(("START TRANSACTION READ ONLY", "ROLLBACK"))
+    }
+
     fn get_completion_info(&self) -> SqlCompletionInfo {
         SqlCompletionInfo {
             keywords: vec![
@@ -55,6 +110,10 @@ impl DatabasePlugin for MySqlPlugin {
                 ("FORCE INDEX", "Force index usage"),
                 ("USE INDEX", "Suggest index usage"),
                 ("IGNORE INDEX", "Ignore index"),
+                ("INTERVAL", "Interval for date arithmetic"),
+                ("WINDOW", "Named window definition"),
+                ("WITH RECURSIVE", "Recursive CTE"),
+                ("LATERAL", "Lateral derived table"),
             ],
             functions: vec![
                 // MySQL-specific functions only (standard SQL functions are added via with_standard_sql())
@@ -87,6 +146,22 @@ impl DatabasePlugin for MySqlPlugin {
                 ("WEEKDAY(date)", "Weekday (0=Monday)"),
                 ("DATE_ADD(date, INTERVAL)", "Add interval to date"),
                 ("DATE_SUB(date, INTERVAL)", "Subtract interval from date"),
+                ("ADDDATE(date, days)", "Add days to date"),
+                ("SUBDATE(date, days)", "Subtract days from date"),
+                ("ADDTIME(expr, time)", "Add time"),
+                ("SUBTIME(expr, time)", "Subtract time"),
+                ("TIMESTAMPADD(unit, n, dt)", "Add interval in specified unit"),
+                ("LAST_DAY(date)", "Last day of month"),
+                ("MAKEDATE(year, dayofyear)", "Create date"),
+                ("MAKETIME(h, m, s)", "Create time"),
+                ("PERIOD_ADD(period, n)", "Add months to YYYYMM period"),
+                ("PERIOD_DIFF(p1, p2)", "Months between YYYYMM periods"),
+                ("QUARTER(date)", "Quarter of year"),
+                ("EXTRACT(unit FROM date)", "Extract date part"),
+                ("CONVERT_TZ(dt, from_tz, to_tz)", "Convert time zone"),
+                ("UTC_TIMESTAMP()", "Current UTC date and time"),
+                ("SEC_TO_TIME(seconds)", "Seconds to time"),
+                ("TIME_TO_SEC(time)", "Time to seconds"),
                 ("DATEDIFF(date1, date2)", "Difference in days"),
                 ("TIMESTAMPDIFF(unit, dt1, dt2)", "Difference in specified unit"),
                 ("DATE_FORMAT(date, format)", "Format date"),
@@ -102,6 +177,29 @@ impl DatabasePlugin for MySqlPlugin {
                 ("JSON_ARRAY(val, ...)", "Create JSON array"),
                 ("JSON_CONTAINS(doc, val)", "Check if JSON contains value"),
                 ("JSON_LENGTH(doc)", "JSON document length"),
+                ("JSON_SET(doc, path, val)", "Insert or update JSON value"),
+                ("JSON_INSERT(doc, path, val)", "Insert JSON value if missing"),
+                ("JSON_REPLACE(doc, path, val)", "Replace existing JSON value"),
+                ("JSON_REMOVE(doc, path)", "Remove JSON value"),
+                ("JSON_KEYS(doc)", "Top-level object keys"),
+                ("JSON_SEARCH(doc, 'one', str)", "Path to matching string"),
+                ("JSON_VALID(val)", "Check if valid JSON"),
+                ("JSON_TYPE(json)", "JSON value type"),
+                ("JSON_MERGE_PATCH(doc1, doc2)", "Merge JSON documents (RFC 7396)"),
+                ("JSON_PRETTY(json)", "Pretty print JSON"),
+                ("JSON_OVERLAPS(doc1, doc2)", "Check if documents share elements"),
+                ("JSON_ARRAYAGG(col)", "Aggregate to JSON array"),
+                ("JSON_OBJECTAGG(key, val)", "Aggregate to JSON object"),
+                ("JSON_TABLE(doc, path COLUMNS (...))", "Parse JSON to table"),
+                ("NTH_VALUE(col, n)", "Nth value in frame"),
+                ("PERCENT_RANK()", "Relative rank"),
+                ("CUME_DIST()", "Cumulative distribution"),
+                ("GREATEST(a, b, ...)", "Maximum value"),
+                ("LEAST(a, b, ...)", "Minimum value"),
+                ("REGEXP_LIKE(str, pat)", "Regex match"),
+                ("REGEXP_REPLACE(str, pat, rep)", "Regex replace"),
+                ("REGEXP_SUBSTR(str, pat)", "Regex substring"),
+                ("ANY_VALUE(col)", "Any value from group"),
                 ("CONVERT(expr, type)", "Type conversion"),
                 ("UUID()", "Generate UUID"),
                 ("LAST_INSERT_ID()", "Last auto-increment ID"),
@@ -119,6 +217,9 @@ impl DatabasePlugin for MySqlPlugin {
                 ("DIV", "Integer division"),
                 ("XOR", "Logical XOR"),
                 (":=", "Assignment operator"),
+                ("->", "JSON extract (JSON_EXTRACT shorthand)"),
+                ("->>", "JSON extract and unquote"),
+                ("MEMBER OF", "Value is element of JSON array"),
             ],
             data_types: vec![
                 ("TINYINT", "1 byte integer"),
@@ -150,6 +251,11 @@ impl DatabasePlugin for MySqlPlugin {
                 ("ENUM('a','b')", "Enumeration"),
                 ("SET('a','b')", "Set of values"),
                 ("JSON", "JSON document"),
+                ("BOOLEAN", "Alias for TINYINT(1)"),
+                ("GEOMETRY", "Spatial value of any type"),
+                ("POINT", "Spatial point"),
+                ("LINESTRING", "Spatial line"),
+                ("POLYGON", "Spatial polygon"),
             ],
             snippets: vec![
                 ("crt", "CREATE TABLE $1 (\n  id INT AUTO_INCREMENT PRIMARY KEY,\n  $2\n) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4", "Create table"),
@@ -157,6 +263,7 @@ impl DatabasePlugin for MySqlPlugin {
                 ("alt", "ALTER TABLE $1 ADD COLUMN $2", "Add column"),
                 ("jn", "JOIN $1 ON $2.$3 = $4.$5", "Join clause"),
                 ("lj", "LEFT JOIN $1 ON $2.$3 = $4.$5", "Left join clause"),
+                ("wf", "SELECT $1,\n  ROW_NUMBER() OVER (PARTITION BY $2 ORDER BY $3) AS rn\nFROM $4", "Window function"),
             ],
         }.with_standard_sql()
     }
@@ -343,7 +450,7 @@ impl DatabasePlugin for MySqlPlugin {
 
     async fn list_columns(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
         let sql = format!(
-            "SELECT COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_KEY, COLUMN_DEFAULT, COLUMN_COMMENT \
+            "SELECT COLUMN_NAME, COLUMN_TYPE, IS_NULLABLE, COLUMN_KEY, COLUMN_DEFAULT, COLUMN_COMMENT, COLLATION_NAME \
              FROM INFORMATION_SCHEMA.COLUMNS \
              WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}' \
              ORDER BY ORDINAL_POSITION",
@@ -363,6 +470,7 @@ impl DatabasePlugin for MySqlPlugin {
                     is_primary_key: row.get(3).and_then(|v| v.clone()).map(|v| v == "PRI").unwrap_or(false),
                     default_value: row.get(4).and_then(|v| v.clone()),
                     comment: row.get(5).and_then(|v| v.clone()),
+                    collation: row.get(6).and_then(|v| v.clone()),
                 }
             }).collect())
         } else {
@@ -690,6 +798,107 @@ impl DatabasePlugin for MySqlPlugin {
         })
     }
 
+    // === Event Operations ===
+
+    fn supports_events(&self) -> bool {
+        true
+    }
+
+    async fn list_events(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<EventInfo>> {
+        let sql = format!(
+            "SELECT EVENT_NAME, STATUS, EVENT_TYPE, EXECUTE_AT, INTERVAL_VALUE, INTERVAL_FIELD, LAST_EXECUTED, EVENT_COMMENT \
+             FROM INFORMATION_SCHEMA.EVENTS \
+             WHERE EVENT_SCHEMA = '{}' \
+             ORDER BY EVENT_NAME",
+            database.replace('\'', "''")
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list events: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                let event_type = row.get(2).and_then(|v| v.clone()).unwrap_or_default();
+                let schedule = Self::event_schedule(
+                    &event_type,
+                    row.get(3).and_then(|v| v.as_deref()),
+                    row.get(4).and_then(|v| v.as_deref()),
+                    row.get(5).and_then(|v| v.as_deref()),
+                );
+                EventInfo {
+                    name: row.first().and_then(|v| v.clone()).unwrap_or_default(),
+                    status: row.get(1).and_then(|v| v.clone()).unwrap_or_default(),
+                    event_type,
+                    schedule,
+                    last_executed: row.get(6).and_then(|v| v.clone()),
+                    comment: row.get(7).and_then(|v| v.clone()),
+                }
+            }).collect())
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    fn build_alter_event_status_sql(&self, database: &str, event: &str, enabled: bool) -> Result<String> {
+        Ok(format!(
+            "ALTER EVENT {} {}",
+            self.show_create_target(database, event),
+            if enabled { "ENABLE" } else { "DISABLE" }
+        ))
+    }
+
+    fn build_drop_event_sql(&self, database: &str, event: &str) -> Result<String> {
+        Ok(format!("DROP EVENT IF EXISTS {}", self.show_create_target(database, event)))
+    }
+
+    /// 事件体可能是 BEGIN ... END 块，整段脚本用 DELIMITER 包裹以免在分号处被拆开
+    fn build_event_edit_script(&self, database: &str, event: &str, definition: &str) -> Result<String> {
+        Ok(format!(
+            "DELIMITER $$\n\nDROP EVENT IF EXISTS {}$$\n\n{}$$\n\nDELIMITER ;\n",
+            self.show_create_target(database, event),
+            definition.trim().trim_end_matches(';')
+        ))
+    }
+
+    fn build_create_event_template(&self, database: &str) -> Result<String> {
+        Ok(format!(
+            "DELIMITER $$\n\n\
+             CREATE EVENT {}\n\
+             ON SCHEDULE EVERY 1 DAY\n\
+             STARTS CURRENT_TIMESTAMP\n\
+             ON COMPLETION PRESERVE\n\
+             ENABLE\n\
+             COMMENT ''\n\
+             DO\n\
+             BEGIN\n    \
+             -- 事件执行的语句\n\
+             END$$\n\n\
+             DELIMITER ;\n",
+            self.show_create_target(database, "new_event")
+        ))
+    }
+
+    async fn get_routine_source(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        let keyword = match object_type {
+            DbNodeType::Function => "FUNCTION",
+            DbNodeType::Procedure => "PROCEDURE",
+            DbNodeType::Trigger => "TRIGGER",
+            DbNodeType::Event => "EVENT",
+            _ => return Err(anyhow::anyhow!("{} source is not supported: {}", object_type, name)),
+        };
+        let sql = format!("SHOW CREATE {} {}", keyword, self.show_create_target(database, name));
+        let result = connection.query(&sql, None, ExecOptions::default()).await
+            .map_err(|e| anyhow::anyhow!("Query failed: {}", e))?;
+
+        match result {
+            SqlResult::Query(query_result) => Self::routine_source_from_show_create(&query_result)
+                .ok_or_else(|| anyhow::anyhow!("No permission to view the definition of {}", name)),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("{}", err.message)),
+            _ => Err(anyhow::anyhow!("Unexpected result type")),
+        }
+    }
+
     async fn list_table_checks(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<CheckInfo>> {
         let sql = format!(
             "SELECT cc.CONSTRAINT_NAME, tc.TABLE_NAME, cc.CHECK_CLAUSE \
@@ -949,6 +1158,36 @@ impl DatabasePlugin for MySqlPlugin {
         format!("RENAME TABLE {} TO {}", self.quote_identifier(old_name), self.quote_identifier(new_name))
     }
 
+    fn supports_trash(&self) -> bool {
+        true
+    }
+
+    fn build_move_to_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        vec![
+            format!(
+                "RENAME TABLE {}.{} TO {}.{}",
+                self.quote_identifier(&entry.original_schema),
+                self.quote_identifier(&entry.original_name),
+                self.quote_identifier(TRASH_SCHEMA),
+                self.quote_identifier(&entry.trash_name)
+            ),
+            self.build_insert_trash_index_sql(entry),
+        ]
+    }
+
+    fn build_restore_from_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        vec![
+            format!(
+                "RENAME TABLE {}.{} TO {}.{}",
+                self.quote_identifier(TRASH_SCHEMA),
+                self.quote_identifier(&entry.trash_name),
+                self.quote_identifier(&entry.original_schema),
+                self.quote_identifier(&entry.original_name)
+            ),
+            self.build_delete_trash_index_sql(entry),
+        ]
+    }
+
     fn build_column_def(&self, col: &ColumnDefinition) -> String {
         let mut def = String::new();
         def.push_str(&self.quote_identifier(&col.name));
@@ -1048,6 +1287,34 @@ impl DatabasePlugin for MySqlPlugin {
         sql
     }
 
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        // Questions counts statements sent by clients, excluding those run inside stored programs
+        Some("SHOW GLOBAL STATUS LIKE 'Questions'")
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        let mut status = query_server_status(
+            connection,
+            "SELECT VERSION(), NULL, (SELECT COUNT(*) FROM information_schema.PROCESSLIST)",
+        ).await?;
+        // The status tables moved between versions and forks, while SHOW GLOBAL STATUS works everywhere
+        if let Ok(SqlResult::Query(result)) = connection.query("SHOW GLOBAL STATUS LIKE 'Uptime'", None, ExecOptions::default()).await {
+            status.uptime = result.rows.first()
+                .and_then(|row| row.last().cloned().flatten())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(std::time::Duration::from_secs);
+        }
+        Ok(status)
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        Some("SHOW SESSION VARIABLES".to_string())
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!("SET SESSION {} = {}", name, session_variable_literal(self, value)))
+    }
+
     fn build_limit_clause(&self) -> String {
         " LIMIT 1".to_string()
     }
@@ -1215,24 +1482,49 @@ impl DatabasePlugin for MySqlPlugin {
         }
     }
 
+    fn insert_conflict_clause(&self, mode: SqlConflictMode, columns: &[String], _key_columns: &[String]) -> Option<InsertConflictClause> {
+        // ON DUPLICATE KEY UPDATE matches on whichever unique key conflicts, so no key list is needed
+        let (keyword, suffix) = match mode {
+            SqlConflictMode::Error => ("INSERT", String::new()),
+            SqlConflictMode::Ignore => ("INSERT IGNORE", String::new()),
+            SqlConflictMode::Update => {
+                let assignments = columns
+                    .iter()
+                    .map(|column| {
+                        let quoted = self.quote_identifier(column);
+                        format!("{} = VALUES({})", quoted, quoted)
+                    })
+                    .collect::<Vec<_>>();
+                ("INSERT", format!(" ON DUPLICATE KEY UPDATE {}", assignments.join(", ")))
+            }
+        };
+        Some(InsertConflictClause {
+            keyword: keyword.to_string(),
+            suffix,
+        })
+    }
+
+    fn foreign_key_checks_sql(&self, enabled: bool) -> Option<String> {
+        Some(format!("SET FOREIGN_KEY_CHECKS = {}", if enabled { 1 } else { 0 }))
+    }
+
     async fn export_table_create_sql(
         &self,
         connection: &dyn DbConnection,
-        _database: &str,
+        database: &str,
         table: &str,
     ) -> Result<String> {
-        let show_create = format!("SHOW CREATE TABLE {}", self.quote_identifier(table));
+        // 使用服务器生成的语句，完整保留字符集、排序规则、生成列、分区和约束等选项；
+        // 会话的当前数据库可能与导出的数据库不同，需要限定数据库名
+        let show_create = format!("SHOW CREATE TABLE {}", self.show_create_target(database, table));
         let result = connection.query(&show_create, None, ExecOptions::default()).await
             .map_err(|e| anyhow::anyhow!("Query failed: {}", e))?;
 
-        if let SqlResult::Query(query_result) = result {
-            if let Some(row) = query_result.rows.first() {
-                if let Some(Some(create_sql)) = row.get(1) {
-                    return Ok(create_sql.clone());
-                }
-            }
+        match result {
+            SqlResult::Query(query_result) => Ok(Self::create_sql_from_show_create(&query_result).unwrap_or_default()),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("{}", err.message)),
+            _ => Ok(String::new()),
         }
-        Ok(String::new())
     }
 }
 
@@ -1706,5 +1998,141 @@ mod tests {
         assert!(info.keywords.iter().any(|(k, _)| *k == "AUTO_INCREMENT"));
         assert!(info.functions.iter().any(|(f, _)| f.starts_with("GROUP_CONCAT")));
         assert!(info.operators.iter().any(|(o, _)| *o == "REGEXP"));
+        assert!(info.operators.iter().any(|(o, _)| *o == "->>"));
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("ROW_NUMBER")));
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("JSON_TABLE")));
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("TIMESTAMPADD")));
+    }
+
+    // ==================== Trash Tests ====================
+
+    fn trash_entry() -> TrashEntry {
+        TrashEntry {
+            trash_name: "1700000000_users".to_string(),
+            original_schema: "app".to_string(),
+            original_name: "users".to_string(),
+            dropped_at: 1700000000,
+        }
+    }
+
+    #[test]
+    fn test_move_to_trash_and_restore() {
+        let plugin = create_plugin();
+        assert!(plugin.supports_trash());
+
+        let sql = plugin.build_move_to_trash_sql(&trash_entry());
+        assert_eq!(sql[0], "RENAME TABLE `app`.`users` TO `_onehub_trash`.`1700000000_users`");
+        assert!(sql[1].starts_with("INSERT INTO `_onehub_trash`.`_onehub_trash_index`"));
+        assert!(sql[1].ends_with("VALUES ('1700000000_users', 'app', 'users', 1700000000)"));
+
+        let sql = plugin.build_restore_from_trash_sql(&trash_entry());
+        assert_eq!(sql[0], "RENAME TABLE `_onehub_trash`.`1700000000_users` TO `app`.`users`");
+        assert_eq!(sql[1], "DELETE FROM `_onehub_trash`.`_onehub_trash_index` WHERE trash_name = '1700000000_users'");
+    }
+
+    #[test]
+    fn test_string_literal_escapes_backslash() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.string_literal("a\\"), "'a\\\\'");
+        assert_eq!(plugin.string_literal("it's"), "'it''s'");
+    }
+
+    // ==================== Show Create Tests ====================
+
+    #[test]
+    fn test_show_create_target_is_qualified() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.show_create_target("shop", "orders"), "`shop`.`orders`");
+        assert_eq!(plugin.show_create_target("", "orders"), "`orders`");
+    }
+
+    #[test]
+    fn test_create_sql_from_show_create() {
+        let view = QueryResult {
+            sql: String::new(),
+            columns: vec![
+                "View".to_string(),
+                "Create View".to_string(),
+                "character_set_client".to_string(),
+                "collation_connection".to_string(),
+            ],
+            rows: vec![vec![
+                Some("v_orders".to_string()),
+                Some("CREATE VIEW `v_orders` AS select 1".to_string()),
+                Some("utf8mb4".to_string()),
+                Some("utf8mb4_general_ci".to_string()),
+            ]],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        };
+        assert_eq!(
+            MySqlPlugin::create_sql_from_show_create(&view).as_deref(),
+            Some("CREATE VIEW `v_orders` AS select 1")
+        );
+
+        let empty = QueryResult { rows: vec![], ..view };
+        assert_eq!(MySqlPlugin::create_sql_from_show_create(&empty), None);
+    }
+
+    #[test]
+    fn test_routine_source_from_show_create() {
+        let trigger = QueryResult {
+            sql: String::new(),
+            columns: vec![
+                "Trigger".to_string(),
+                "sql_mode".to_string(),
+                "SQL Original Statement".to_string(),
+            ],
+            rows: vec![vec![
+                Some("trg_orders".to_string()),
+                Some(String::new()),
+                Some("CREATE TRIGGER `trg_orders` BEFORE INSERT ON `orders` FOR EACH ROW SET NEW.total = 0".to_string()),
+            ]],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        };
+        assert!(MySqlPlugin::routine_source_from_show_create(&trigger)
+            .is_some_and(|source| source.starts_with("CREATE TRIGGER")));
+
+        let hidden = QueryResult {
+            columns: vec!["Procedure".to_string(), "sql_mode".to_string(), "Create Procedure".to_string()],
+            rows: vec![vec![Some("p_sync".to_string()), Some(String::new()), None]],
+            ..trigger
+        };
+        assert_eq!(MySqlPlugin::routine_source_from_show_create(&hidden), None);
+    }
+
+    // ==================== Event Tests ====================
+
+    #[test]
+    fn test_event_schedule() {
+        assert_eq!(MySqlPlugin::event_schedule("RECURRING", None, Some("1"), Some("DAY")), "EVERY 1 DAY");
+        assert_eq!(
+            MySqlPlugin::event_schedule("ONE TIME", Some("2024-06-01 02:00:00"), None, None),
+            "AT 2024-06-01 02:00:00"
+        );
+    }
+
+    #[test]
+    fn test_event_sql() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.build_alter_event_status_sql("shop", "purge_logs", false).ok().as_deref(),
+            Some("ALTER EVENT `shop`.`purge_logs` DISABLE")
+        );
+        assert_eq!(
+            plugin.build_drop_event_sql("shop", "purge_logs").ok().as_deref(),
+            Some("DROP EVENT IF EXISTS `shop`.`purge_logs`")
+        );
+
+        let script = plugin
+            .build_event_edit_script("shop", "purge_logs", "CREATE EVENT `purge_logs` ON SCHEDULE EVERY 1 DAY DO BEGIN DELETE FROM logs; END")
+            .unwrap_or_default();
+        let statements = plugin.split_statements(&script);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].starts_with("DROP EVENT IF EXISTS `shop`.`purge_logs`"));
+        assert!(statements[1].ends_with("DELETE FROM logs; END"));
     }
 }
@@ -0,0 +1,5 @@
+pub mod connection;
+pub mod plugin;
+
+pub use connection::OdbcDbConnection;
+pub use plugin::OdbcPlugin;
@@ -0,0 +1,422 @@
+use std::any::Any;
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use odbc_api::buffers::TextRowSet;
+use odbc_api::{ConnectionOptions, Cursor, Environment, ResultSetMetadata};
+use one_core::storage::DbConnectionConfig;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+
+use crate::connection::{DbConnection, DbError, StreamingProgress};
+use crate::executor::{
+    ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult,
+    SqlStatementClassifier,
+};
+use crate::{ColumnInfo, DatabasePlugin, SqlValue};
+
+/// Rows fetched from the driver per round trip
+const FETCH_BATCH_SIZE: usize = 500;
+/// Upper bound for a single text cell, drivers report huge sizes for LOB columns
+const MAX_TEXT_LENGTH: usize = 8192;
+
+/// Table entry returned by the `SQLTables` catalog function
+#[derive(Debug, Clone)]
+pub struct OdbcCatalogTable {
+    pub schema: Option<String>,
+    pub name: String,
+    pub table_type: String,
+    pub remarks: Option<String>,
+}
+
+pub struct OdbcDbConnection {
+    config: DbConnectionConfig,
+    conn: Arc<Mutex<Option<odbc_api::Connection<'static>>>>,
+}
+
+impl OdbcDbConnection {
+    pub fn new(config: DbConnectionConfig) -> Self {
+        Self {
+            config,
+            conn: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The driver manager environment must outlive every connection, so it is created once per process
+    fn environment() -> Result<&'static Environment, DbError> {
+        static ENVIRONMENT: OnceLock<Result<Environment, String>> = OnceLock::new();
+        ENVIRONMENT
+            .get_or_init(|| Environment::new().map_err(|e| e.to_string()))
+            .as_ref()
+            .map_err(|e| DbError::ConnectionError(format!("Failed to load ODBC driver manager: {}", e)))
+    }
+
+    /// A full connection string in the advanced tab wins over the DSN in the host field
+    pub fn build_connection_string(config: &DbConnectionConfig) -> String {
+        if let Some(connection_string) = config.get_param("connection_string").filter(|s| !s.trim().is_empty()) {
+            return connection_string.trim().to_string();
+        }
+
+        let mut connection_string = format!("DSN={};", Self::escape_attribute(&config.host));
+        if !config.username.is_empty() {
+            connection_string.push_str(&format!("UID={};", Self::escape_attribute(&config.username)));
+        }
+        if !config.password.is_empty() {
+            connection_string.push_str(&format!("PWD={};", Self::escape_attribute(&config.password)));
+        }
+        connection_string
+    }
+
+    fn escape_attribute(value: &str) -> String {
+        if value.contains([';', '{', '}', '=']) || value.trim() != value {
+            format!("{{{}}}", value.replace('}', "}}"))
+        } else {
+            value.to_string()
+        }
+    }
+
+    fn fetch_rows(
+        mut cursor: impl Cursor,
+        max_rows: Option<usize>,
+    ) -> Result<(Vec<String>, Vec<Vec<Option<String>>>), odbc_api::Error> {
+        let columns = cursor.column_names()?.collect::<Result<Vec<String>, _>>()?;
+        let mut rows = Vec::new();
+        if columns.is_empty() {
+            return Ok((columns, rows));
+        }
+
+        let buffer = TextRowSet::for_cursor(FETCH_BATCH_SIZE, &mut cursor, Some(MAX_TEXT_LENGTH))?;
+        let mut row_set_cursor = cursor.bind_buffer(buffer)?;
+        'fetch: while let Some(batch) = row_set_cursor.fetch()? {
+            for row_index in 0..batch.num_rows() {
+                if max_rows.is_some_and(|max| rows.len() >= max) {
+                    break 'fetch;
+                }
+                let row = (0..batch.num_cols())
+                    .map(|column_index| {
+                        batch
+                            .at(column_index, row_index)
+                            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                    })
+                    .collect();
+                rows.push(row);
+            }
+        }
+
+        Ok((columns, rows))
+    }
+
+    /// ODBC has no portable LIMIT syntax, so `max_rows` is applied while fetching instead of rewriting the SQL
+    fn execute_sync(
+        conn: &odbc_api::Connection<'static>,
+        sql: &str,
+        max_rows: Option<usize>,
+    ) -> SqlResult {
+        let start = Instant::now();
+        let sql_string = sql.to_string();
+
+        let outcome = (|| -> Result<SqlResult, odbc_api::Error> {
+            let mut statement = conn.preallocate()?;
+            let fetched = match statement.execute(sql, ())? {
+                Some(cursor) => Some(Self::fetch_rows(cursor, max_rows)?),
+                None => None,
+            };
+
+            if let Some((columns, rows)) = fetched {
+                let table_name = SqlStatementClassifier::analyze_select_editability(sql);
+                let editable = table_name.is_some();
+                return Ok(SqlResult::Query(QueryResult {
+                    sql: sql_string.clone(),
+                    columns,
+                    rows,
+                    elapsed_ms: start.elapsed().as_millis(),
+                    table_name,
+                    editable,
+                }));
+            }
+
+            let rows_affected = statement.row_count()?.unwrap_or(0) as u64;
+            Ok(SqlResult::Exec(ExecResult {
+                sql: sql_string.clone(),
+                rows_affected,
+                elapsed_ms: start.elapsed().as_millis(),
+                message: Some(SqlStatementClassifier::format_message(sql, rows_affected)),
+            }))
+        })();
+
+        outcome.unwrap_or_else(|e| SqlResult::Error(SqlErrorInfo {
+            sql: sql_string,
+            message: e.to_string(),
+        }))
+    }
+
+    async fn with_connection<T, F>(&self, f: F) -> Result<T, DbError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&odbc_api::Connection<'static>) -> Result<T, DbError> + Send + 'static,
+    {
+        let conn_arc = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let guard = conn_arc.blocking_lock();
+            let conn = guard.as_ref()
+                .ok_or_else(|| DbError::ConnectionError("Not connected to database".to_string()))?;
+            f(conn)
+        })
+        .await
+        .map_err(|e| DbError::QueryError(format!("Task error: {}", e)))?
+    }
+
+    /// Catalogs reported by the driver, empty when the data source has no catalog concept
+    pub async fn list_catalogs(&self) -> Result<Vec<String>, DbError> {
+        self.with_connection(|conn| {
+            // SQL_ALL_CATALOGS with empty schema and table names enumerates catalogs
+            let cursor = conn.tables("%", "", "", "")
+                .map_err(|e| DbError::QueryError(format!("SQLTables failed: {}", e)))?;
+            let (_, rows) = Self::fetch_rows(cursor, None)
+                .map_err(|e| DbError::QueryError(format!("SQLTables failed: {}", e)))?;
+
+            let mut catalogs: Vec<String> = rows
+                .into_iter()
+                .filter_map(|row| row.into_iter().next().flatten())
+                .filter(|catalog| !catalog.is_empty())
+                .collect();
+            catalogs.sort();
+            catalogs.dedup();
+            Ok(catalogs)
+        })
+        .await
+    }
+
+    /// Tables of `table_type` ("TABLE", "VIEW") in `catalog`; an empty catalog means the current one
+    pub async fn list_catalog_tables(&self, catalog: &str, table_type: &str) -> Result<Vec<OdbcCatalogTable>, DbError> {
+        let catalog = catalog.to_string();
+        let table_type = table_type.to_string();
+        self.with_connection(move |conn| {
+            let cursor = conn.tables(&catalog, "", "%", &table_type)
+                .map_err(|e| DbError::QueryError(format!("SQLTables failed: {}", e)))?;
+            let (_, rows) = Self::fetch_rows(cursor, None)
+                .map_err(|e| DbError::QueryError(format!("SQLTables failed: {}", e)))?;
+
+            // TABLE_CAT, TABLE_SCHEM, TABLE_NAME, TABLE_TYPE, REMARKS
+            Ok(rows
+                .into_iter()
+                .filter_map(|row| {
+                    let name = row.get(2).cloned().flatten()?;
+                    Some(OdbcCatalogTable {
+                        schema: row.get(1).cloned().flatten().filter(|s| !s.is_empty()),
+                        name,
+                        table_type: row.get(3).cloned().flatten().unwrap_or_default(),
+                        remarks: row.get(4).cloned().flatten().filter(|s| !s.is_empty()),
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Columns of a table via `SQLColumns`, in ordinal order
+    pub async fn list_catalog_columns(&self, catalog: &str, schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>, DbError> {
+        let catalog = catalog.to_string();
+        let schema = schema.unwrap_or("").to_string();
+        let table = table.to_string();
+        self.with_connection(move |conn| {
+            let cursor = conn.columns(&catalog, &schema, &table, "%")
+                .map_err(|e| DbError::QueryError(format!("SQLColumns failed: {}", e)))?;
+            let (_, rows) = Self::fetch_rows(cursor, None)
+                .map_err(|e| DbError::QueryError(format!("SQLColumns failed: {}", e)))?;
+
+            // COLUMN_NAME(3), TYPE_NAME(5), COLUMN_SIZE(6), DECIMAL_DIGITS(8),
+            // NULLABLE(10), REMARKS(11), COLUMN_DEF(12)
+            Ok(rows
+                .into_iter()
+                .filter_map(|row| {
+                    let cell = |index: usize| row.get(index).cloned().flatten().filter(|s| !s.is_empty());
+                    let name = cell(3)?;
+                    let type_name = cell(5).unwrap_or_default();
+                    let data_type = match (cell(6), cell(8)) {
+                        (Some(size), Some(digits)) if digits != "0" => format!("{}({},{})", type_name, size, digits),
+                        (Some(size), _) if Self::is_sized_type(&type_name) => format!("{}({})", type_name, size),
+                        _ => type_name,
+                    };
+                    Some(ColumnInfo {
+                        name,
+                        data_type,
+                        is_nullable: cell(10).map(|v| v != "0").unwrap_or(true),
+                        is_primary_key: false,
+                        default_value: cell(12),
+                        comment: cell(11),
+                        collation: None,
+                    })
+                })
+                .collect())
+        })
+        .await
+    }
+
+    fn is_sized_type(type_name: &str) -> bool {
+        let upper = type_name.to_uppercase();
+        upper.contains("CHAR") || upper.contains("BINARY")
+    }
+}
+
+#[async_trait]
+impl DbConnection for OdbcDbConnection {
+    fn config(&self) -> &DbConnectionConfig {
+        &self.config
+    }
+
+    fn set_config_database(&mut self, database: Option<String>) {
+        self.config.database = database;
+    }
+
+    fn supports_database_switch(&self) -> bool {
+        false
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    async fn connect(&mut self) -> Result<(), DbError> {
+        let connection_string = Self::build_connection_string(&self.config);
+
+        let conn = tokio::task::spawn_blocking(move || {
+            Self::environment()?
+                .connect_with_connection_string(&connection_string, ConnectionOptions::default())
+                .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))
+        })
+        .await
+        .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))??;
+
+        {
+            let mut guard = self.conn.lock().await;
+            *guard = Some(conn);
+        }
+
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<(), DbError> {
+        let conn_opt = {
+            let mut guard = self.conn.lock().await;
+            guard.take()
+        };
+
+        if let Some(conn) = conn_opt {
+            // Dropping the handle disconnects, which may block on the driver
+            tokio::task::spawn_blocking(move || drop(conn))
+            .await
+            .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
+        let statements: Vec<String> = plugin.split_statements(script)
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut results = Vec::new();
+        let max_rows = options.max_rows;
+
+        for sql in statements {
+            let result = self.with_connection(move |conn| Ok(Self::execute_sync(conn, &sql, max_rows))).await?;
+
+            let is_error = result.is_error();
+            results.push(result);
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn query(
+        &self,
+        query: &str,
+        params: Option<Vec<SqlValue>>,
+        options: ExecOptions,
+    ) -> Result<SqlResult, DbError> {
+        if params.is_some() {
+            return Err(DbError::QueryError(
+                "Parameterized queries not yet supported for ODBC".to_string(),
+            ));
+        }
+
+        let sql = query.to_string();
+        let max_rows = options.max_rows;
+        self.with_connection(move |conn| Ok(Self::execute_sync(conn, &sql, max_rows))).await
+    }
+
+    /// `SELECT 1` is not valid on every ODBC data source, so ask the driver instead.
+    /// Drivers without SQL_ATTR_CONNECTION_DEAD are assumed alive
+    async fn ping(&self) -> Result<(), DbError> {
+        self.with_connection(|conn| match conn.is_dead() {
+            Ok(true) => Err(DbError::ConnectionError("Connection is dead".to_string())),
+            _ => Ok(()),
+        })
+        .await
+    }
+
+    async fn current_database(&self) -> Result<Option<String>, DbError> {
+        self.with_connection(|conn| Ok(conn.current_catalog().ok().filter(|catalog| !catalog.is_empty())))
+            .await
+    }
+
+    async fn switch_database(&self, _database: &str) -> Result<(), DbError> {
+        // The catalog is fixed by the DSN
+        Ok(())
+    }
+
+    async fn execute_streaming(
+        &self, plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        sender: mpsc::Sender<StreamingProgress>,
+    ) -> Result<(), DbError> {
+        let statements: Vec<String> = plugin.split_statements(script)
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let total = statements.len();
+        let max_rows = options.max_rows;
+
+        for (index, sql) in statements.into_iter().enumerate() {
+            let current = index + 1;
+            let sql_clone = sql.clone();
+
+            let result = match self.with_connection(move |conn| Ok(Self::execute_sync(conn, &sql_clone, max_rows))).await {
+                Ok(r) => r,
+                Err(e) => SqlResult::Error(SqlErrorInfo {
+                    sql: sql.clone(),
+                    message: e.to_string(),
+                }),
+            };
+
+            let is_error = result.is_error();
+            let progress = StreamingProgress {
+                current,
+                total,
+                result,
+            };
+
+            if sender.send(progress).await.is_err() {
+                break;
+            }
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
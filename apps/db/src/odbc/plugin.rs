@@ -0,0 +1,626 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig};
+
+use crate::connection::{DbConnection, DbError};
+use crate::odbc::OdbcDbConnection;
+use crate::odbc::connection::OdbcCatalogTable;
+use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::types::*;
+
+/// Placeholder database shown for data sources without catalogs (Access, most DB2 DSNs)
+pub const DEFAULT_CATALOG: &str = "default";
+
+/// Generic ODBC bridge plugin. Metadata comes from the ODBC catalog functions,
+/// SQL is passed to the driver unchanged.
+pub struct OdbcPlugin;
+
+impl OdbcPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn odbc_connection<'a>(&self, connection: &'a dyn DbConnection) -> Result<&'a OdbcDbConnection> {
+        connection
+            .as_any()
+            .and_then(|any| any.downcast_ref::<OdbcDbConnection>())
+            .ok_or_else(|| anyhow::anyhow!("Not an ODBC connection"))
+    }
+
+    /// Catalog argument for the ODBC catalog functions, empty for the current catalog
+    fn catalog_filter(database: &str) -> &str {
+        if database == DEFAULT_CATALOG {
+            ""
+        } else {
+            database
+        }
+    }
+
+    async fn list_catalog_tables(&self, connection: &dyn DbConnection, database: &str, table_type: &str) -> Result<Vec<OdbcCatalogTable>> {
+        let conn = self.odbc_connection(connection)?;
+        conn.list_catalog_tables(Self::catalog_filter(database), table_type)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list tables: {}", e))
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for OdbcPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::ODBC
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace("\"", "\"\""))
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        Box::new(sqlparser::dialect::GenericDialect {})
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        // The driver behind a DSN is unknown, so only portable SQL types are offered
+        SqlCompletionInfo {
+            keywords: vec![
+                ("FETCH NEXT", "Limit rows (SQL standard)"),
+                ("OFFSET", "Skip rows (SQL standard)"),
+                ("WITH RECURSIVE", "Recursive CTE"),
+            ],
+            data_types: vec![
+                ("SMALLINT", "2 byte integer"),
+                ("INTEGER", "4 byte integer"),
+                ("BIGINT", "8 byte integer"),
+                ("DECIMAL(p,s)", "Exact numeric"),
+                ("NUMERIC(p,s)", "Exact numeric"),
+                ("REAL", "Single-precision float"),
+                ("DOUBLE PRECISION", "Double-precision float"),
+                ("CHAR(n)", "Fixed-length string"),
+                ("VARCHAR(n)", "Variable-length string"),
+                ("DATE", "Date"),
+                ("TIME", "Time"),
+                ("TIMESTAMP", "Date and time"),
+                ("BOOLEAN", "True/False"),
+            ],
+            snippets: vec![
+                ("idx", "CREATE INDEX $1 ON $2 ($3)", "Create index"),
+                ("jn", "JOIN $1 ON $2.$3 = $4.$5", "Join clause"),
+            ],
+            ..Default::default()
+        }.with_standard_sql()
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = OdbcDbConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        let conn = self.odbc_connection(connection)?;
+        let catalogs = conn.list_catalogs()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list catalogs: {}", e))?;
+
+        if catalogs.is_empty() {
+            Ok(vec![DEFAULT_CATALOG.to_string()])
+        } else {
+            Ok(catalogs)
+        }
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let databases = self.list_databases(connection).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = databases.iter().map(|db| vec![db.clone()]).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Database,
+            title: format!("{} database(s)", databases.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        let databases = self.list_databases(connection).await?;
+
+        Ok(databases.into_iter().map(|name| DatabaseInfo {
+            name,
+            charset: None,
+            collation: None,
+            size: None,
+            table_count: None,
+            comment: None,
+        }).collect())
+    }
+
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        let tables = self.list_catalog_tables(connection, database, "TABLE").await?;
+
+        Ok(tables.into_iter().map(|table| TableInfo {
+            name: table.name,
+            schema: table.schema,
+            comment: table.remarks,
+            engine: None,
+            row_count: None,
+            create_time: None,
+            charset: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("schema", "Schema").width(px(120.0)),
+            Column::new("comment", "Comment").width(px(300.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = tables.iter().map(|table| {
+            vec![
+                table.name.clone(),
+                table.schema.as_deref().unwrap_or("").to_string(),
+                table.comment.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} table(s)", tables.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let conn = self.odbc_connection(connection)?;
+        conn.list_catalog_columns(Self::catalog_filter(database), schema, table)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list columns: {}", e))
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns_data = self.list_columns(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("type", "Type").width(px(150.0)),
+            Column::new("nullable", "Nullable").width(px(80.0)),
+            Column::new("default", "Default").width(px(120.0)),
+            Column::new("comment", "Comment").width(px(200.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = columns_data.iter().map(|col| {
+            vec![
+                col.name.clone(),
+                col.data_type.clone(),
+                if col.is_nullable { "YES" } else { "NO" }.to_string(),
+                col.default_value.as_deref().unwrap_or("").to_string(),
+                col.comment.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: format!("{} column(s)", columns_data.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_indexes(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<Vec<IndexInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_indexes_view(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: "0 index(es)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    async fn list_views(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<ViewInfo>> {
+        let views = self.list_catalog_tables(connection, database, "VIEW").await?;
+
+        Ok(views.into_iter().map(|view| ViewInfo {
+            name: view.name,
+            schema: view.schema,
+            definition: None,
+            comment: view.remarks,
+        }).collect())
+    }
+
+    async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let views = self.list_views(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("schema", "Schema").width(px(120.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = views.iter().map(|view| {
+            vec![
+                view.name.clone(),
+                view.schema.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::View,
+            title: format!("{} view(s)", views.len()),
+            columns,
+            rows,
+        })
+    }
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Function,
+            title: "0 function(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Procedure,
+            title: "0 procedure(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    async fn list_sequences(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SequenceInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sequences_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+        ];
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Sequence,
+            title: "0 sequence(s)".to_string(),
+            columns,
+            rows: vec![],
+        })
+    }
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        let mut def = String::new();
+
+        if include_name {
+            def.push_str(&self.quote_identifier(&column.name));
+            def.push(' ');
+        }
+
+        def.push_str(&column.data_type);
+
+        if !column.is_nullable {
+            def.push_str(" NOT NULL");
+        }
+
+        if let Some(default) = &column.default_value {
+            def.push_str(&format!(" DEFAULT {}", default));
+        }
+
+        def
+    }
+
+    fn build_create_database_sql(&self, _request: &crate::plugin::DatabaseOperationRequest) -> String {
+        "-- ODBC: create the database with the native tools of the data source".to_string()
+    }
+
+    fn build_modify_database_sql(&self, _request: &crate::plugin::DatabaseOperationRequest) -> String {
+        "-- ODBC: database modification not supported".to_string()
+    }
+
+    fn build_drop_database_sql(&self, _database_name: &str) -> String {
+        "-- ODBC: database drop not supported".to_string()
+    }
+
+    fn format_pagination(&self, limit: usize, offset: usize, _order_clause: &str) -> String {
+        format!(" OFFSET {} ROWS FETCH NEXT {} ROWS ONLY", offset, limit)
+    }
+
+    /// Catalogs are selected by the DSN, so only the schema qualifies the table
+    fn format_table_reference(&self, _database: &str, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(schema) if !schema.is_empty() => format!(
+                "{}.{}",
+                self.quote_identifier(schema),
+                self.quote_identifier(table)
+            ),
+            _ => self.quote_identifier(table),
+        }
+    }
+
+    fn drop_table(&self, _database: &str, table: &str) -> String {
+        format!("DROP TABLE {}", self.quote_identifier(table))
+    }
+
+    fn rename_table(&self, _database: &str, old_name: &str, new_name: &str) -> String {
+        format!(
+            "ALTER TABLE {} RENAME TO {}",
+            self.quote_identifier(old_name),
+            self.quote_identifier(new_name)
+        )
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        let mut def = String::new();
+        def.push_str(&self.quote_identifier(&col.name));
+        def.push(' ');
+        def.push_str(&self.build_type_string(col));
+
+        if !col.is_nullable {
+            def.push_str(" NOT NULL");
+        }
+
+        if let Some(default) = &col.default_value {
+            if !default.is_empty() {
+                def.push_str(&format!(" DEFAULT {}", default));
+            }
+        }
+
+        def
+    }
+
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        let mut definitions: Vec<String> = design.columns
+            .iter()
+            .map(|col| format!("  {}", self.build_column_def(col)))
+            .collect();
+
+        let pk_cols: Vec<String> = design.columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| self.quote_identifier(&c.name))
+            .collect();
+        if !pk_cols.is_empty() {
+            definitions.push(format!("  PRIMARY KEY ({})", pk_cols.join(", ")));
+        }
+
+        format!(
+            "CREATE TABLE {} (\n{}\n);",
+            self.quote_identifier(&design.table_name),
+            definitions.join(",\n")
+        )
+    }
+
+    /// Only ANSI ADD/DROP COLUMN is portable across drivers; type changes are left to the user
+    fn build_alter_table_sql(&self, original: &TableDesign, new: &TableDesign) -> String {
+        let mut statements: Vec<String> = Vec::new();
+        let table_name = self.quote_identifier(&new.table_name);
+
+        let original_cols: std::collections::HashMap<&str, &ColumnDefinition> = original.columns
+            .iter()
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+        let new_cols: std::collections::HashMap<&str, &ColumnDefinition> = new.columns
+            .iter()
+            .map(|c| (c.name.as_str(), c))
+            .collect();
+
+        for col in &original.columns {
+            if !new_cols.contains_key(col.name.as_str()) {
+                statements.push(format!(
+                    "ALTER TABLE {} DROP COLUMN {};",
+                    table_name,
+                    self.quote_identifier(&col.name)
+                ));
+            }
+        }
+
+        for col in &new.columns {
+            match original_cols.get(col.name.as_str()) {
+                Some(orig_col) if self.column_changed(orig_col, col) => {
+                    statements.push(format!(
+                        "-- Column {} changed, modify it with the syntax of the data source: {}",
+                        self.quote_identifier(&col.name),
+                        self.build_column_def(col)
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    statements.push(format!(
+                        "ALTER TABLE {} ADD COLUMN {};",
+                        table_name,
+                        self.build_column_def(col)
+                    ));
+                }
+            }
+        }
+
+        if statements.is_empty() {
+            "-- No changes detected".to_string()
+        } else {
+            statements.join("\n")
+        }
+    }
+
+    fn build_limit_clause(&self) -> String {
+        String::new()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        request: &crate::types::TableSaveRequest,
+        original_data: &[String],
+    ) -> (String, String) {
+        let where_clause = self.build_table_change_where_clause(request, original_data);
+        (where_clause, String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn create_plugin() -> OdbcPlugin {
+        OdbcPlugin::new()
+    }
+
+    fn create_config(host: &str, username: &str, password: &str) -> DbConnectionConfig {
+        DbConnectionConfig {
+            id: String::new(),
+            database_type: DatabaseType::ODBC,
+            name: "odbc".to_string(),
+            host: host.to_string(),
+            port: 0,
+            username: username.to_string(),
+            password: password.to_string(),
+            database: None,
+            workspace_id: None,
+            extra_params: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        assert_eq!(create_plugin().name(), DatabaseType::ODBC);
+    }
+
+    #[test]
+    fn test_quote_identifier() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.quote_identifier("table_name"), "\"table_name\"");
+        assert_eq!(plugin.quote_identifier("col\"umn"), "\"col\"\"umn\"");
+    }
+
+    #[test]
+    fn test_connection_string_from_dsn() {
+        let config = create_config("DB2PROD", "db2inst1", "p;w{d}");
+        assert_eq!(
+            OdbcDbConnection::build_connection_string(&config),
+            "DSN=DB2PROD;UID=db2inst1;PWD={p;w{d}}};"
+        );
+
+        let config = create_config("AccessFile", "", "");
+        assert_eq!(OdbcDbConnection::build_connection_string(&config), "DSN=AccessFile;");
+    }
+
+    #[test]
+    fn test_connection_string_override() {
+        let mut config = create_config("ignored", "user", "pass");
+        config.extra_params.insert(
+            "connection_string".to_string(),
+            " Driver={Microsoft Access Driver (*.mdb, *.accdb)};DBQ=C:\\data\\app.accdb; ".to_string(),
+        );
+        assert_eq!(
+            OdbcDbConnection::build_connection_string(&config),
+            "Driver={Microsoft Access Driver (*.mdb, *.accdb)};DBQ=C:\\data\\app.accdb;"
+        );
+
+        config.extra_params.insert("connection_string".to_string(), "  ".to_string());
+        assert_eq!(
+            OdbcDbConnection::build_connection_string(&config),
+            "DSN=ignored;UID=user;PWD=pass;"
+        );
+    }
+
+    #[test]
+    fn test_pagination_and_table_reference() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.format_pagination(100, 200, ""),
+            " OFFSET 200 ROWS FETCH NEXT 100 ROWS ONLY"
+        );
+        assert_eq!(
+            plugin.format_table_reference(DEFAULT_CATALOG, Some("DB2INST1"), "EMPLOYEE"),
+            "\"DB2INST1\".\"EMPLOYEE\""
+        );
+        assert_eq!(plugin.format_table_reference("main", None, "orders"), "\"orders\"");
+    }
+
+    #[test]
+    fn test_catalog_filter() {
+        assert_eq!(OdbcPlugin::catalog_filter(DEFAULT_CATALOG), "");
+        assert_eq!(OdbcPlugin::catalog_filter("SAMPLE"), "SAMPLE");
+    }
+
+    #[test]
+    fn test_get_completion_info() {
+        let plugin = create_plugin();
+        let info = plugin.get_completion_info();
+
+        assert!(!info.data_types.is_empty());
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("COALESCE")));
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("ROW_NUMBER")));
+    }
+}
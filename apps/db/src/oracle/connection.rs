@@ -11,11 +11,14 @@ use crate::executor::{
     ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult,
     SqlStatementClassifier,
 };
-use crate::{DatabasePlugin, SqlValue};
+use crate::proxy::{self, ProxyForward};
+use crate::{tls, DatabasePlugin, SqlValue};
 
 pub struct OracleDbConnection {
     config: DbConnectionConfig,
     conn: Arc<Mutex<Option<oracle::Connection>>>,
+    /// Keeps the local proxy port open while connected
+    proxy_forward: Option<ProxyForward>,
 }
 
 impl OracleDbConnection {
@@ -23,16 +26,37 @@ impl OracleDbConnection {
         Self {
             config,
             conn: Arc::new(Mutex::new(None)),
+            proxy_forward: None,
         }
     }
 
-    fn build_connect_string(config: &DbConnectionConfig) -> String {
-        // Oracle uses service_name, stored in database field
-        if let Some(ref service) = config.database {
-            format!("//{}:{}/{}", config.host, config.port, service)
+    /// Easy Connect string; with SSL enabled it becomes `tcps://` and the server
+    /// certificate is checked against the wallet named in the CA field
+    fn build_connect_string(config: &DbConnectionConfig) -> Result<String, DbError> {
+        let ssl = config.ssl();
+        let (scheme, params) = if ssl.is_enabled() {
+            // Client certificates live in the wallet too, so separate PEM files cannot be used
+            if tls::client_identity(&ssl)?.is_some() {
+                return Err(tls::unsupported("Oracle", "client certificate files, put them in the wallet instead"));
+            }
+            let mut params = vec![format!("ssl_server_dn_match={}", ssl.mode.verifies_hostname())];
+            if let Some(wallet) = &ssl.ca_cert {
+                params.push(format!("wallet_location={}", wallet));
+            }
+            ("tcps://", params)
         } else {
-            format!("//{}:{}", config.host, config.port)
+            ("//", Vec::new())
+        };
+
+        // Oracle uses service_name, stored in database field
+        let mut connect_string = match config.database {
+            Some(ref service) => format!("{}{}:{}/{}", scheme, config.host, config.port, service),
+            None => format!("{}{}:{}", scheme, config.host, config.port),
+        };
+        if !params.is_empty() {
+            connect_string = format!("{}?{}", connect_string, params.join("&"));
         }
+        Ok(connect_string)
     }
 
     fn extract_value(row: &oracle::Row, index: usize) -> Option<String> {
@@ -153,23 +177,35 @@ impl DbConnection for OracleDbConnection {
     }
 
     async fn connect(&mut self) -> Result<(), DbError> {
-        let config = self.config.clone();
+        let mut config = self.config.clone();
+        let ssl = config.ssl();
 
-        let connect_string = Self::build_connect_string(&config);
+        let proxy_forward = ProxyForward::start(&config).await?;
+        if let Some(forward) = &proxy_forward {
+            if ssl.mode.verifies_hostname() {
+                return Err(proxy::hostname_check_unsupported());
+            }
+            config.host = "127.0.0.1".to_string();
+            config.port = forward.local_port();
+        }
+
+        let connect_string = Self::build_connect_string(&config)?;
         let username = config.username.clone();
         let password = config.password.clone();
 
         let conn = tokio::task::spawn_blocking(move || {
             oracle::Connection::connect(&username, &password, &connect_string)
-                .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))
+                .map_err(|e| tls::connection_error(&ssl, e))
         })
         .await
-        .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))??;
+        .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))?
+        .map_err(|e| ProxyForward::connect_error(proxy_forward.as_ref(), e))?;
 
         {
             let mut guard = self.conn.lock().await;
             *guard = Some(conn);
         }
+        self.proxy_forward = proxy_forward;
 
         Ok(())
     }
@@ -187,6 +223,7 @@ impl DbConnection for OracleDbConnection {
             .await
             .map_err(|e| DbError::ConnectionError(format!("Task error: {}", e)))?;
         }
+        self.proxy_forward = None;
 
         Ok(())
     }
@@ -260,6 +297,14 @@ impl DbConnection for OracleDbConnection {
         .map_err(|e| DbError::QueryError(format!("Task error: {}", e)))?
     }
 
+    /// Oracle before 23c needs a FROM clause
+    async fn ping(&self) -> Result<(), DbError> {
+        match self.query("SELECT 1 FROM DUAL", None, ExecOptions::default()).await? {
+            SqlResult::Error(error) => Err(DbError::ConnectionError(error.message)),
+            _ => Ok(()),
+        }
+    }
+
     async fn current_database(&self) -> Result<Option<String>, DbError> {
         let conn_arc = self.conn.clone();
 
@@ -8,6 +8,8 @@ use crate::connection::{DbConnection, DbError};
 use crate::executor::{ExecOptions, SqlResult};
 use crate::oracle::connection::OracleDbConnection;
 use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::server_status::{query_server_status, ServerStatus};
+use crate::session_variable::session_variable_literal;
 use crate::types::*;
 
 pub struct OraclePlugin;
@@ -16,6 +18,29 @@ impl OraclePlugin {
     pub fn new() -> Self {
         Self
     }
+
+    /// Assemble package source from `(type, text)` rows of ALL_SOURCE ordered by type and line
+    fn package_source_from_rows(rows: &[Vec<Option<String>>]) -> PackageSource {
+        let mut source = PackageSource::default();
+        for row in rows {
+            let text = row.get(1).and_then(|v| v.clone()).unwrap_or_default();
+            match row.get(0).and_then(|v| v.as_deref()) {
+                Some("PACKAGE BODY") => source.body.get_or_insert_with(String::new).push_str(&text),
+                _ => source.spec.push_str(&text),
+            }
+        }
+        source
+    }
+
+    /// Tree node type of an ALL_OBJECTS object type; objects the tree does not show map to None
+    fn object_node_type(object_type: &str) -> Option<DbNodeType> {
+        match object_type {
+            "VIEW" => Some(DbNodeType::View),
+            "FUNCTION" => Some(DbNodeType::Function),
+            "PROCEDURE" => Some(DbNodeType::Procedure),
+            _ => None,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -32,6 +57,15 @@ impl DatabasePlugin for OraclePlugin {
         Box::new(sqlparser::dialect::OracleDialect {})
     }
 
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        Some(("SET TRANSACTION READ ONLY", "ROLLBACK"))
+    }
+
+    /// Multi-row VALUES lists only exist since Oracle 23ai
+    fn max_rows_per_insert(&self) -> Option<usize> {
+        Some(1)
+    }
+
     fn supports_sequences(&self) -> bool {
         true
     }
@@ -77,6 +111,10 @@ impl DatabasePlugin for OraclePlugin {
                 ("MONTHS_BETWEEN(d1, d2)", "Months between dates"),
                 ("LAST_DAY(date)", "Last day of month"),
                 ("NEXT_DAY(date, day)", "Next occurrence of day"),
+                ("NUMTODSINTERVAL(n, unit)", "Number to day-second interval"),
+                ("NUMTOYMINTERVAL(n, unit)", "Number to year-month interval"),
+                ("FROM_TZ(ts, tz)", "Attach time zone to timestamp"),
+                ("SYS_EXTRACT_UTC(ts)", "Convert timestamp to UTC"),
                 ("EXTRACT(part FROM date)", "Extract date component"),
                 ("SYSDATE", "Current date and time"),
                 ("SYSTIMESTAMP", "Current timestamp with timezone"),
@@ -99,13 +137,15 @@ impl DatabasePlugin for OraclePlugin {
                 ("JSON_VALUE(json, path)", "Extract JSON scalar"),
                 ("JSON_QUERY(json, path)", "Extract JSON object"),
                 ("JSON_TABLE(json, path)", "Parse JSON to table"),
-                ("ROW_NUMBER() OVER(...)", "Row number window function"),
-                ("RANK() OVER(...)", "Rank window function"),
-                ("DENSE_RANK() OVER(...)", "Dense rank"),
-                ("LEAD(col, offset) OVER(...)", "Next row value"),
-                ("LAG(col, offset) OVER(...)", "Previous row value"),
-                ("FIRST_VALUE(col) OVER(...)", "First value in window"),
-                ("LAST_VALUE(col) OVER(...)", "Last value in window"),
+                ("JSON_OBJECT(key VALUE val, ...)", "Create JSON object"),
+                ("JSON_ARRAY(val, ...)", "Create JSON array"),
+                ("JSON_ARRAYAGG(expr)", "Aggregate to JSON array"),
+                ("JSON_OBJECTAGG(key VALUE val)", "Aggregate to JSON object"),
+                ("JSON_EXISTS(json, path)", "Check JSON path exists"),
+                ("NTH_VALUE(col, n)", "Nth value in window"),
+                ("PERCENT_RANK()", "Relative rank"),
+                ("CUME_DIST()", "Cumulative distribution"),
+                ("RATIO_TO_REPORT(expr)", "Ratio to window sum"),
                 ("SYS_GUID()", "Generate GUID"),
                 ("RAWTOHEX(raw)", "Convert raw to hex"),
                 ("HEXTORAW(hex)", "Convert hex to raw"),
@@ -391,6 +431,7 @@ impl DatabasePlugin for OraclePlugin {
                     is_primary_key: is_pk,
                     default_value: row.get(3).and_then(|v| v.clone()),
                     comment: row.get(5).and_then(|v| v.clone()),
+                    collation: None,
                 }
             }).collect())
         } else {
@@ -744,6 +785,140 @@ impl DatabasePlugin for OraclePlugin {
         })
     }
 
+    fn supports_packages(&self) -> bool {
+        true
+    }
+
+    async fn list_packages(&self, connection: &dyn DbConnection, schema: &str) -> Result<Vec<PackageInfo>> {
+        let sql = format!(
+            r#"
+            SELECT
+                object_name,
+                MAX(CASE WHEN object_type = 'PACKAGE BODY' THEN 1 ELSE 0 END) AS has_body,
+                MIN(status) AS status
+            FROM all_objects
+            WHERE owner = '{}' AND object_type IN ('PACKAGE', 'PACKAGE BODY')
+            GROUP BY object_name
+            ORDER BY object_name
+            "#,
+            schema.replace("'", "''")
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list packages: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                PackageInfo {
+                    name: row.get(0).and_then(|v| v.clone()).unwrap_or_default(),
+                    has_body: row.get(1).and_then(|v| v.as_deref()) == Some("1"),
+                    status: row.get(2).and_then(|v| v.clone()),
+                }
+            }).collect())
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    async fn get_package_source(&self, connection: &dyn DbConnection, schema: &str, package: &str) -> Result<PackageSource> {
+        let sql = format!(
+            r#"
+            SELECT
+                type,
+                text
+            FROM all_source
+            WHERE owner = '{}' AND name = '{}' AND type IN ('PACKAGE', 'PACKAGE BODY')
+            ORDER BY type, line
+            "#,
+            schema.replace("'", "''"),
+            package.replace("'", "''")
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load package source: {}", e))?;
+
+        match result {
+            SqlResult::Query(query_result) if !query_result.rows.is_empty() => {
+                Ok(Self::package_source_from_rows(&query_result.rows))
+            }
+            _ => Err(anyhow::anyhow!("Package source not found: {}.{}", schema, package)),
+        }
+    }
+
+    async fn compile_package(&self, connection: &dyn DbConnection, schema: &str, package: &str) -> Result<Vec<String>> {
+        // COMPILE 同时重新编译包规范和包体；编译出错时 Oracle 返回警告，具体错误从 ALL_ERRORS 读取
+        let sql = format!(
+            "ALTER PACKAGE {}.{} COMPILE",
+            self.quote_identifier(schema),
+            self.quote_identifier(package)
+        );
+        let compile_error = match connection.query(&sql, None, ExecOptions::default()).await {
+            Ok(SqlResult::Error(err)) => Some(err.message),
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+
+        let errors_sql = format!(
+            r#"
+            SELECT
+                type,
+                line,
+                position,
+                text
+            FROM all_errors
+            WHERE owner = '{}' AND name = '{}' AND type IN ('PACKAGE', 'PACKAGE BODY')
+            ORDER BY type, sequence
+            "#,
+            schema.replace("'", "''"),
+            package.replace("'", "''")
+        );
+        let result = connection.query(&errors_sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read compilation errors: {}", e))?;
+
+        let errors: Vec<String> = if let SqlResult::Query(query_result) = result {
+            query_result.rows.iter().map(|row| {
+                let cell = |i: usize| row.get(i).and_then(|v| v.clone()).unwrap_or_default();
+                format!("{} {}:{} {}", cell(0), cell(1), cell(2), cell(3).trim_end())
+            }).collect()
+        } else {
+            vec![]
+        };
+
+        match compile_error {
+            Some(message) if errors.is_empty() => Err(anyhow::anyhow!("Failed to compile package: {}", message)),
+            _ => Ok(errors),
+        }
+    }
+
+    async fn list_invalid_objects(&self, connection: &dyn DbConnection, schema: &str) -> Result<Vec<(DbNodeType, String)>> {
+        let sql = format!(
+            r#"
+            SELECT
+                object_type,
+                object_name
+            FROM all_objects
+            WHERE owner = '{}' AND status = 'INVALID' AND object_type IN ('VIEW', 'FUNCTION', 'PROCEDURE')
+            "#,
+            schema.replace("'", "''")
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list invalid objects: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().filter_map(|row| {
+                let node_type = Self::object_node_type(row.get(0).and_then(|v| v.as_deref())?)?;
+                Some((node_type, row.get(1).and_then(|v| v.clone())?))
+            }).collect())
+        } else {
+            Ok(vec![])
+        }
+    }
+
     async fn list_triggers(&self, connection: &dyn DbConnection, schema: &str) -> Result<Vec<TriggerInfo>> {
         let sql = format!(
             r#"
@@ -830,6 +1005,38 @@ impl DatabasePlugin for OraclePlugin {
         })
     }
 
+    async fn get_routine_source(&self, connection: &dyn DbConnection, schema: &str, _node_schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        let source_type = match object_type {
+            DbNodeType::Function => "FUNCTION",
+            DbNodeType::Procedure => "PROCEDURE",
+            DbNodeType::Trigger => "TRIGGER",
+            _ => return Err(anyhow::anyhow!("{} source is not supported: {}", object_type, name)),
+        };
+        let sql = format!(
+            r#"
+            SELECT text
+            FROM all_source
+            WHERE owner = '{}' AND name = '{}' AND type = '{}'
+            ORDER BY line
+            "#,
+            schema.replace("'", "''"),
+            name.replace("'", "''"),
+            source_type
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load routine source: {}", e))?;
+
+        match result {
+            // ALL_SOURCE 每行已带换行符，按行号顺序拼接即可
+            SqlResult::Query(query_result) if !query_result.rows.is_empty() => Ok(query_result.rows.iter()
+                .filter_map(|row| row.first().cloned().flatten())
+                .collect::<String>()),
+            _ => Err(anyhow::anyhow!("Routine source not found: {}.{}", schema, name)),
+        }
+    }
+
     async fn list_sequences(&self, connection: &dyn DbConnection, schema: &str) -> Result<Vec<SequenceInfo>> {
         let sql = format!(
             r#"
@@ -1069,6 +1276,37 @@ impl DatabasePlugin for OraclePlugin {
         sql
     }
 
+    fn build_explain_sql(&self, sql: &str) -> Option<String> {
+        Some(format!(
+            "EXPLAIN PLAN FOR {};\nSELECT PLAN_TABLE_OUTPUT FROM TABLE(DBMS_XPLAN.DISPLAY())",
+            sql.trim().trim_end_matches(';')
+        ))
+    }
+
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        Some("SELECT value FROM v$sysstat WHERE name = 'execute count'")
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        // The v$ views need SELECT_CATALOG_ROLE; without it only the version is known
+        match query_server_status(
+            connection,
+            "SELECT (SELECT banner FROM v$version WHERE ROWNUM = 1), ROUND((SYSDATE - startup_time) * 86400), \
+             (SELECT COUNT(*) FROM v$session WHERE type = 'USER') FROM v$instance",
+        ).await {
+            Ok(status) => Ok(status),
+            Err(_) => query_server_status(connection, "SELECT 'Oracle ' || version FROM product_component_version WHERE ROWNUM = 1").await,
+        }
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        Some("SELECT parameter, value FROM nls_session_parameters ORDER BY parameter".to_string())
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!("ALTER SESSION SET {} = {}", name, session_variable_literal(self, value)))
+    }
+
     fn build_limit_clause(&self) -> String {
         String::new()
     }
@@ -1247,6 +1485,26 @@ mod tests {
         assert_eq!(plugin.quote_identifier("col\"umn"), "\"col\"\"umn\"");
     }
 
+    #[test]
+    fn test_package_source_from_rows() {
+        let row = |kind: &str, text: &str| vec![Some(kind.to_string()), Some(text.to_string())];
+        let rows = vec![
+            row("PACKAGE", "PACKAGE billing AS\n"),
+            row("PACKAGE", "  PROCEDURE charge(p_id NUMBER);\n"),
+            row("PACKAGE", "END billing;"),
+            row("PACKAGE BODY", "PACKAGE BODY billing AS\n"),
+            row("PACKAGE BODY", "END billing;"),
+        ];
+
+        let source = OraclePlugin::package_source_from_rows(&rows);
+        assert_eq!(source.spec, "PACKAGE billing AS\n  PROCEDURE charge(p_id NUMBER);\nEND billing;");
+        assert_eq!(source.body.as_deref(), Some("PACKAGE BODY billing AS\nEND billing;"));
+        assert_eq!(
+            source.to_script(),
+            "CREATE OR REPLACE PACKAGE billing AS\n  PROCEDURE charge(p_id NUMBER);\nEND billing;\n/\n\nCREATE OR REPLACE PACKAGE BODY billing AS\nEND billing;\n/\n"
+        );
+    }
+
     #[test]
     fn test_supports_sequences() {
         let plugin = create_plugin();
@@ -1615,4 +1873,11 @@ mod tests {
         assert!(info.functions.iter().any(|(f, _)| f.starts_with("LISTAGG")));
         assert!(info.functions.iter().any(|(f, _)| f.starts_with("SYS_GUID")));
     }
+
+    #[test]
+    fn test_object_node_type() {
+        assert_eq!(OraclePlugin::object_node_type("VIEW"), Some(DbNodeType::View));
+        assert_eq!(OraclePlugin::object_node_type("PROCEDURE"), Some(DbNodeType::Procedure));
+        assert_eq!(OraclePlugin::object_node_type("PACKAGE BODY"), None);
+    }
 }
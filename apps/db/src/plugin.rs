@@ -2,6 +2,9 @@ use crate::connection::{
     DbConnection, DbError
 };
 use crate::executor::{ExecOptions, SqlResult, StatementType};
+use crate::trash::{TrashEntry, TRASH_INDEX_TABLE, TRASH_SCHEMA};
+use crate::import_export::{InsertConflictClause, NativeExportFormat, SqlConflictMode};
+use crate::server_status::ServerStatus;
 use crate::types::*;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
@@ -11,6 +14,7 @@ use sqlparser::ast;
 use sqlparser::ast::{Expr, SetExpr, Statement, TableFactor};
 use sqlparser::dialect::{Dialect};
 use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
 use std::collections::HashMap;
 use tracing::log::error;
 
@@ -50,6 +54,15 @@ pub const STANDARD_SQL_FUNCTIONS: &[(&str, &str)] = &[
     ("AVG(col)", "Average value"),
     ("MIN(col)", "Minimum value"),
     ("MAX(col)", "Maximum value"),
+    // Window functions
+    ("ROW_NUMBER()", "Row number in partition"),
+    ("RANK()", "Rank with gaps"),
+    ("DENSE_RANK()", "Rank without gaps"),
+    ("NTILE(n)", "Divide into n buckets"),
+    ("LAG(col, n)", "Previous row value"),
+    ("LEAD(col, n)", "Next row value"),
+    ("FIRST_VALUE(col)", "First value in frame"),
+    ("LAST_VALUE(col)", "Last value in frame"),
     // Control flow
     ("COALESCE(val1, val2, ...)", "First non-NULL value"),
     ("NULLIF(val1, val2)", "Return NULL if equal"),
@@ -62,6 +75,9 @@ pub const STANDARD_SQL_FUNCTIONS: &[(&str, &str)] = &[
 pub const STANDARD_SQL_KEYWORDS: &[(&str, &str)] = &[
     ("IF EXISTS", "Conditional existence check"),
     ("IF NOT EXISTS", "Conditional non-existence check"),
+    ("OVER", "Window function clause"),
+    ("PARTITION BY", "Window partition"),
+    ("ROWS BETWEEN", "Window frame"),
 ];
 
 /// SQL completion information for a specific database type
@@ -129,6 +145,43 @@ impl<'a> FolderContext<'a> {
     }
 }
 
+/// Metadata of a package node: the folder metadata plus body and compile status
+fn package_metadata(metadata: &HashMap<String, String>, package: &PackageInfo) -> HashMap<String, String> {
+    let mut meta = metadata.clone();
+    meta.insert("has_body".to_string(), package.has_body.to_string());
+    if let Some(status) = &package.status {
+        meta.insert("status".to_string(), status.clone());
+    }
+    meta
+}
+
+/// Marks a view or routine node whose last compilation failed, so the tree can flag it
+fn object_metadata(metadata: &HashMap<String, String>, invalid_objects: &[(DbNodeType, String)], node_type: DbNodeType, name: &str) -> HashMap<String, String> {
+    let mut meta = metadata.clone();
+    if invalid_objects.iter().any(|(invalid_type, invalid_name)| *invalid_type == node_type && invalid_name == name) {
+        meta.insert("status".to_string(), "INVALID".to_string());
+    }
+    meta
+}
+
+/// Metadata of a materialized view node: the folder metadata plus whether it holds data
+fn materialized_view_metadata(metadata: &HashMap<String, String>, view: &MaterializedViewInfo) -> HashMap<String, String> {
+    let mut meta = metadata.clone();
+    meta.insert("populated".to_string(), view.is_populated.to_string());
+    meta
+}
+
+/// Metadata of an event node: the folder metadata plus its status and schedule
+fn event_metadata(metadata: &HashMap<String, String>, event: &EventInfo) -> HashMap<String, String> {
+    let mut meta = metadata.clone();
+    meta.insert("enabled".to_string(), event.is_enabled().to_string());
+    meta.insert("schedule".to_string(), event.schedule.clone());
+    if let Some(comment) = event.comment.as_ref().filter(|c| !c.is_empty()) {
+        meta.insert("comment".to_string(), comment.clone());
+    }
+    meta
+}
+
 /// Database plugin trait for supporting multiple database types
 #[async_trait]
 pub trait DatabasePlugin: Send + Sync {
@@ -155,6 +208,12 @@ pub trait DatabasePlugin: Send + Sync {
         false
     }
 
+    /// Node type of the entries returned by `list_databases`; engines whose top level
+    /// is a catalog (Trino) show catalogs instead of databases under the connection
+    fn database_node_type(&self) -> DbNodeType {
+        DbNodeType::Database
+    }
+
     /// Whether this database supports sequences (e.g., PostgreSQL, Oracle, MSSQL)
     fn supports_sequences(&self) -> bool {
         false
@@ -199,6 +258,66 @@ pub trait DatabasePlugin: Send + Sync {
         analyze_select_editability_fallback(sql)
     }
 
+    /// Check that `sql` is a single statement that only reads data: no DML, DDL, SELECT INTO or row locks.
+    /// Statements that fail to parse are not considered read-only
+    fn is_read_only_query(&self, sql: &str) -> bool {
+        match Parser::parse_sql(self.sql_dialect().as_ref(), sql) {
+            Ok(statements) => matches!(statements.as_slice(), [Statement::Query(query)] if query_is_read_only(query)),
+            Err(_) => false,
+        }
+    }
+
+    /// Statements that start and end a read-only transaction (or session mode) around a query
+    /// that passed `is_read_only_query`, so functions with side effects still cannot write.
+    /// None when the database has no such mode
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        None
+    }
+
+    /// Check that `condition` is a single expression usable after WHERE, such as a generated export filter
+    fn validate_where_condition(&self, condition: &str) -> Result<()> {
+        validate_where_condition(condition, self.sql_dialect().as_ref())
+    }
+
+    /// Build a script that returns the execution plan of `sql` without running it.
+    /// The plan is the last query result of the script; None if the dialect has no such statement.
+    fn build_explain_sql(&self, sql: &str) -> Option<String> {
+        Some(format!("EXPLAIN {}", sql.trim().trim_end_matches(';')))
+    }
+
+    /// Query whose first row ends with a cumulative, server-wide statement counter.
+    /// Polled periodically to chart queries per second; None if the dialect exposes no such counter.
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Server version, uptime and client session count for the connection health dashboard.
+    /// Items the dialect or the user's privileges do not expose are left unknown.
+    async fn server_status(&self, _connection: &dyn DbConnection) -> Result<ServerStatus> {
+        Ok(ServerStatus::default())
+    }
+
+    /// Query listing the session's settings as (name, value, description, editable) rows, see
+    /// `SessionVariable`; trailing columns may be left out. None if the dialect has no session settings.
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        None
+    }
+
+    /// Statement changing a setting for the current session only
+    fn build_set_session_variable_sql(&self, _name: &str, _value: &str) -> Option<String> {
+        None
+    }
+
+    /// Whether queries still running on a connection can be cancelled from another task
+    fn supports_query_cancel(&self) -> bool {
+        false
+    }
+
+    /// Cancel the queries currently running on a connection, returns how many were cancelled
+    async fn cancel_running_queries(&self, _connection_id: &str) -> Result<usize> {
+        Ok(0)
+    }
+
     /// List schemas in a database (for databases that support schemas)
     async fn list_schemas(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<String>> {
         Ok(Vec::new())
@@ -239,6 +358,69 @@ pub trait DatabasePlugin: Send + Sync {
     
     async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView>;
 
+    // === Materialized View Operations ===
+    /// Whether query results can be stored as materialized views (e.g., PostgreSQL)
+    fn supports_materialized_views(&self) -> bool {
+        false
+    }
+
+    async fn list_materialized_views(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<MaterializedViewInfo>> {
+        Ok(Vec::new())
+    }
+
+    /// Statement that re-runs the query of a materialized view. A concurrent refresh keeps
+    /// the view readable meanwhile but needs a unique index on it.
+    fn build_refresh_materialized_view_sql(&self, _schema: Option<&str>, view: &str, _concurrently: bool) -> Result<String> {
+        Err(anyhow::anyhow!("Materialized views are not supported: {}", view))
+    }
+
+    /// CREATE statements of all materialized views, appended to a structure export after
+    /// the tables they read from
+    async fn export_materialized_views_sql(&self, _connection: &dyn DbConnection, _database: &str) -> Result<String> {
+        Ok(String::new())
+    }
+
+    // === Synonym Operations ===
+    /// Whether schemas hold synonyms, alternative names for other objects (e.g., SQL Server)
+    fn supports_synonyms(&self) -> bool {
+        false
+    }
+
+    async fn list_synonyms(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SynonymInfo>> {
+        Ok(Vec::new())
+    }
+
+    // === Linked Server Operations ===
+    /// Whether other servers can be queried through four-part names (e.g., SQL Server linked servers)
+    fn supports_linked_servers(&self) -> bool {
+        false
+    }
+
+    async fn list_linked_servers(&self, _connection: &dyn DbConnection) -> Result<Vec<LinkedServerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_linked_server_tables(&self, _connection: &dyn DbConnection, server: &str) -> Result<Vec<LinkedTableInfo>> {
+        Err(anyhow::anyhow!("Linked servers are not supported: {}", server))
+    }
+
+    /// `server.catalog.schema.object`; parts a provider lacks stay empty, as in `server..schema.object`
+    fn format_linked_table_reference(&self, server: &str, table: &LinkedTableInfo) -> String {
+        let quote_part = |part: &Option<String>| part.as_deref().map(|p| self.quote_identifier(p)).unwrap_or_default();
+        format!(
+            "{}.{}.{}.{}",
+            self.quote_identifier(server),
+            quote_part(&table.catalog),
+            quote_part(&table.schema),
+            self.quote_identifier(&table.name)
+        )
+    }
+
+    /// Query opened in the editor for objects browsed by name only (synonyms, linked server tables)
+    fn build_preview_query(&self, object_reference: &str) -> String {
+        format!("SELECT * FROM {} LIMIT 100", object_reference)
+    }
+
     // === Function Operations ===
 
     fn supports_functions(&self) -> bool {
@@ -257,11 +439,104 @@ pub trait DatabasePlugin: Send + Sync {
     
     async fn list_procedures_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView>;
 
+    // === Package Operations ===
+    /// Whether routines are grouped into packages (e.g., Oracle)
+    fn supports_packages(&self) -> bool {
+        false
+    }
+
+    async fn list_packages(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<PackageInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_package_source(&self, _connection: &dyn DbConnection, _database: &str, package: &str) -> Result<PackageSource> {
+        Err(anyhow::anyhow!("Packages are not supported: {}", package))
+    }
+
+    /// Recompile a package spec and body, returning the compilation errors (empty on success)
+    async fn compile_package(&self, _connection: &dyn DbConnection, _database: &str, package: &str) -> Result<Vec<String>> {
+        Err(anyhow::anyhow!("Packages are not supported: {}", package))
+    }
+
+    /// Views, functions and procedures whose last compilation failed (e.g., Oracle `INVALID` objects)
+    async fn list_invalid_objects(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<(DbNodeType, String)>> {
+        Ok(Vec::new())
+    }
+
     // === Trigger Operations ===
     async fn list_triggers(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TriggerInfo>>;
 
     async fn list_triggers_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView>;
 
+    /// Source of a function, procedure or trigger as stored by the database
+    async fn get_routine_source(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        Err(anyhow::anyhow!("{} source is not supported: {}", object_type, name))
+    }
+
+    // === Event Operations ===
+    /// Whether databases hold scheduled events (e.g., MySQL event scheduler)
+    fn supports_events(&self) -> bool {
+        false
+    }
+
+    async fn list_events(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<EventInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_events_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+        use gpui_component::table::Column;
+
+        let events = self.list_events(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("status", "Status").width(px(100.0)),
+            Column::new("type", "Type").width(px(100.0)),
+            Column::new("schedule", "Schedule").width(px(200.0)),
+            Column::new("last_executed", "Last Executed").width(px(160.0)),
+            Column::new("comment", "Comment").width(px(200.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = events.iter().map(|event| {
+            vec![
+                event.name.clone(),
+                event.status.clone(),
+                event.event_type.clone(),
+                event.schedule.clone(),
+                event.last_executed.clone().unwrap_or_default(),
+                event.comment.clone().unwrap_or_default(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Event,
+            title: format!("{} event(s)", events.len()),
+            columns,
+            rows,
+        })
+    }
+
+    /// Enable or disable an event; a disabled event keeps its schedule but does not run
+    fn build_alter_event_status_sql(&self, _database: &str, event: &str, _enabled: bool) -> Result<String> {
+        Err(anyhow::anyhow!("Events are not supported: {}", event))
+    }
+
+    fn build_drop_event_sql(&self, _database: &str, event: &str) -> Result<String> {
+        Err(anyhow::anyhow!("Events are not supported: {}", event))
+    }
+
+    /// Script opened in the editor to change an event: events cannot be altered in full,
+    /// so the definition from `get_routine_source` is dropped and created again
+    fn build_event_edit_script(&self, _database: &str, event: &str, _definition: &str) -> Result<String> {
+        Err(anyhow::anyhow!("Events are not supported: {}", event))
+    }
+
+    /// Script opened in the editor when creating an event
+    fn build_create_event_template(&self, _database: &str) -> Result<String> {
+        Err(anyhow::anyhow!("Events are not supported"))
+    }
+
     // === Sequence Operations ===
     async fn list_sequences(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<SequenceInfo>>;
     
@@ -383,6 +658,8 @@ pub trait DatabasePlugin: Send + Sync {
         }
         nodes.push(table_folder);
 
+        let invalid_objects = self.list_invalid_objects(connection, database).await.unwrap_or_default();
+
         let views = self.list_views(connection, database).await?;
         let filtered_views: Vec<_> = if let Some(s) = schema {
             views.into_iter().filter(|v| v.schema.as_deref() == Some(s)).collect()
@@ -401,7 +678,7 @@ pub trait DatabasePlugin: Send + Sync {
             let children: Vec<DbNode> = filtered_views
                 .into_iter()
                 .map(|view| {
-                    let mut meta: HashMap<String, String> = metadata.clone();
+                    let mut meta = object_metadata(&metadata, &invalid_objects, DbNodeType::View, &view.name);
                     if let Some(comment) = view.comment {
                         meta.insert("comment".to_string(), comment);
                     }
@@ -425,6 +702,76 @@ pub trait DatabasePlugin: Send + Sync {
         }
         nodes.push(views_folder);
 
+        // Materialized views folder
+        if self.supports_materialized_views() {
+            let materialized_views = self.list_materialized_views(connection, database).await.unwrap_or_default();
+            let filtered_materialized_views: Vec<_> = match schema {
+                Some(s) => materialized_views.into_iter().filter(|v| v.schema.as_deref() == Some(s)).collect(),
+                None => materialized_views,
+            };
+            let materialized_view_count = filtered_materialized_views.len();
+            let mut materialized_views_folder = DbNode::new(
+                format!("{}:materialized_views_folder", id),
+                format!("Materialized Views ({})", materialized_view_count),
+                DbNodeType::MaterializedViewsFolder,
+                node.connection_id.clone(),
+                node.database_type
+            ).with_parent_context(id).with_metadata(metadata.clone());
+            if materialized_view_count > 0 {
+                let children: Vec<DbNode> = filtered_materialized_views
+                    .into_iter()
+                    .map(|view| {
+                        DbNode::new(
+                            format!("{}:materialized_views_folder:{}", id, view.name),
+                            view.name.clone(),
+                            DbNodeType::MaterializedView,
+                            node.connection_id.clone(),
+                            node.database_type
+                        )
+                            .with_parent_context(format!("{}:materialized_views_folder", id))
+                            .with_metadata(materialized_view_metadata(&metadata, &view))
+                    })
+                    .collect();
+                materialized_views_folder.set_children(children);
+            }
+            nodes.push(materialized_views_folder);
+        }
+
+        // Synonyms folder
+        if self.supports_synonyms() {
+            let synonyms = self.list_synonyms(connection, database).await.unwrap_or_default();
+            let filtered_synonyms: Vec<_> = match schema {
+                Some(s) => synonyms.into_iter().filter(|synonym| synonym.schema.as_deref() == Some(s)).collect(),
+                None => synonyms,
+            };
+            let synonym_count = filtered_synonyms.len();
+            let mut synonyms_folder = DbNode::new(
+                format!("{}:synonyms_folder", id),
+                format!("Synonyms ({})", synonym_count),
+                DbNodeType::SynonymsFolder,
+                node.connection_id.clone(),
+                node.database_type
+            ).with_parent_context(id).with_metadata(metadata.clone());
+            if synonym_count > 0 {
+                let children: Vec<DbNode> = filtered_synonyms
+                    .into_iter()
+                    .map(|synonym| {
+                        DbNode::new(
+                            format!("{}:synonyms_folder:{}", id, synonym.name),
+                            synonym.name.clone(),
+                            DbNodeType::Synonym,
+                            node.connection_id.clone(),
+                            node.database_type
+                        )
+                            .with_parent_context(format!("{}:synonyms_folder", id))
+                            .with_metadata(self.synonym_metadata(&metadata, database, &synonym))
+                    })
+                    .collect();
+                synonyms_folder.set_children(children);
+            }
+            nodes.push(synonyms_folder);
+        }
+
         // Functions folder
         if self.supports_functions() {
             let functions = self.list_functions(connection, database).await.unwrap_or_default();
@@ -448,7 +795,7 @@ pub trait DatabasePlugin: Send + Sync {
                             node.database_type
                         )
                             .with_parent_context(format!("{}:functions_folder", id))
-                            .with_metadata(metadata.clone())
+                            .with_metadata(object_metadata(&metadata, &invalid_objects, DbNodeType::Function, &func.name))
                     })
                     .collect();
                 functions_folder.set_children(children);
@@ -479,7 +826,7 @@ pub trait DatabasePlugin: Send + Sync {
                             node.database_type
                         )
                             .with_parent_context(format!("{}:procedures_folder", id))
-                            .with_metadata(metadata.clone())
+                            .with_metadata(object_metadata(&metadata, &invalid_objects, DbNodeType::Procedure, &proc.name))
                     })
                     .collect();
                 procedures_folder.set_children(children);
@@ -487,6 +834,68 @@ pub trait DatabasePlugin: Send + Sync {
             nodes.push(procedures_folder);
         }
 
+        // Packages folder (only for databases that group routines into packages)
+        if self.supports_packages() {
+            let packages = self.list_packages(connection, database).await.unwrap_or_default();
+            let package_count = packages.len();
+            let mut packages_folder = DbNode::new(
+                format!("{}:packages_folder", id),
+                format!("Packages ({})", package_count),
+                DbNodeType::PackagesFolder,
+                node.connection_id.clone(),
+                node.database_type
+            ).with_parent_context(id).with_metadata(metadata.clone());
+            if package_count > 0 {
+                let children: Vec<DbNode> = packages
+                    .into_iter()
+                    .map(|package| {
+                        DbNode::new(
+                            format!("{}:packages_folder:{}", id, package.name),
+                            package.name.clone(),
+                            DbNodeType::Package,
+                            node.connection_id.clone(),
+                            node.database_type
+                        )
+                            .with_parent_context(format!("{}:packages_folder", id))
+                            .with_metadata(package_metadata(&metadata, &package))
+                    })
+                    .collect();
+                packages_folder.set_children(children);
+            }
+            nodes.push(packages_folder);
+        }
+
+        // Events folder (only for databases with an event scheduler)
+        if self.supports_events() {
+            let events = self.list_events(connection, database).await.unwrap_or_default();
+            let event_count = events.len();
+            let mut events_folder = DbNode::new(
+                format!("{}:events_folder", id),
+                format!("Events ({})", event_count),
+                DbNodeType::EventsFolder,
+                node.connection_id.clone(),
+                node.database_type
+            ).with_parent_context(id).with_metadata(metadata.clone());
+            if event_count > 0 {
+                let children: Vec<DbNode> = events
+                    .into_iter()
+                    .map(|event| {
+                        DbNode::new(
+                            format!("{}:events_folder:{}", id, event.name),
+                            event.name.clone(),
+                            DbNodeType::Event,
+                            node.connection_id.clone(),
+                            node.database_type
+                        )
+                            .with_parent_context(format!("{}:events_folder", id))
+                            .with_metadata(event_metadata(&metadata, &event))
+                    })
+                    .collect();
+                events_folder.set_children(children);
+            }
+            nodes.push(events_folder);
+        }
+
         // Sequences folder (only for databases that support sequences)
         if self.supports_sequences() {
             let sequences = self.list_sequences(connection, database).await.unwrap_or_default();
@@ -622,15 +1031,26 @@ pub trait DatabasePlugin: Send + Sync {
         match node.node_type {
             DbNodeType::Connection => {
                 let databases = self.list_databases(connection).await?;
-                Ok(databases
+                let node_type = self.database_node_type();
+                let mut nodes: Vec<DbNode> = databases
                     .into_iter()
                     .map(|db| {
-                        DbNode::new(format!("{}:{}", &node.id, db), db.clone(), DbNodeType::Database, node.id.clone(), node.database_type)
-                            .with_parent_context(id)
+                        let database_node = DbNode::new(format!("{}:{}", &node.id, db), db.clone(), node_type.clone(), node.id.clone(), node.database_type)
+                            .with_parent_context(id);
+                        // 目录节点下的对象靠 metadata 中的 database 找到所属目录
+                        if node_type == DbNodeType::Catalog {
+                            database_node.with_metadata(HashMap::from([("database".to_string(), db)]))
+                        } else {
+                            database_node
+                        }
                     })
-                    .collect())
+                    .collect();
+                if self.supports_linked_servers() {
+                    nodes.push(self.build_linked_servers_folder(connection, node).await);
+                }
+                Ok(nodes)
             }
-            DbNodeType::Database => {
+            DbNodeType::Database | DbNodeType::Catalog => {
                 self.build_database_tree(connection, node, global_storage_state).await
             }
             DbNodeType::Schema => {
@@ -639,9 +1059,9 @@ pub trait DatabasePlugin: Send + Sync {
                     .map(|s| s.as_str());
                 self.build_schema_tree(connection, node, schema_name, global_storage_state).await
             }
-            DbNodeType::TablesFolder | DbNodeType::ViewsFolder |
-            DbNodeType::FunctionsFolder | DbNodeType::ProceduresFolder |
-            DbNodeType::SequencesFolder => {
+            DbNodeType::TablesFolder | DbNodeType::ViewsFolder | DbNodeType::MaterializedViewsFolder |
+            DbNodeType::SynonymsFolder | DbNodeType::FunctionsFolder | DbNodeType::ProceduresFolder |
+            DbNodeType::PackagesFolder | DbNodeType::EventsFolder | DbNodeType::SequencesFolder => {
                 if node.children_loaded {
                     return Ok(node.children.clone());
                 }
@@ -653,6 +1073,15 @@ pub trait DatabasePlugin: Send + Sync {
                 }
                 self.load_queries_children(node, id, global_storage_state).await
             }
+            DbNodeType::LinkedServersFolder => {
+                if node.children_loaded {
+                    return Ok(node.children.clone());
+                }
+                Ok(self.build_linked_servers_folder(connection, node).await.children)
+            }
+            DbNodeType::LinkedServer => {
+                self.load_linked_server_children(connection, node, id).await
+            }
             DbNodeType::Table => {
                 self.load_table_children(connection, node, id).await
             }
@@ -689,13 +1118,14 @@ pub trait DatabasePlugin: Send + Sync {
                 }).collect())
             }
             DbNodeType::ViewsFolder => {
+                let invalid_objects = self.list_invalid_objects(connection, ctx.database).await.unwrap_or_default();
                 let views = self.list_views(connection, ctx.database).await?;
                 let filtered: Vec<_> = match ctx.schema {
                     Some(s) => views.into_iter().filter(|v| v.schema.as_deref() == Some(s)).collect(),
                     None => views,
                 };
                 Ok(filtered.into_iter().map(|v| {
-                    let mut meta = ctx.metadata.clone();
+                    let mut meta = object_metadata(ctx.metadata, &invalid_objects, DbNodeType::View, &v.name);
                     if let Some(comment) = v.comment {
                         meta.insert("comment".to_string(), comment);
                     }
@@ -703,18 +1133,56 @@ pub trait DatabasePlugin: Send + Sync {
                         .with_parent_context(id).with_metadata(meta)
                 }).collect())
             }
+            DbNodeType::MaterializedViewsFolder => {
+                let views = self.list_materialized_views(connection, ctx.database).await?;
+                let filtered: Vec<_> = match ctx.schema {
+                    Some(s) => views.into_iter().filter(|v| v.schema.as_deref() == Some(s)).collect(),
+                    None => views,
+                };
+                Ok(filtered.into_iter().map(|v| {
+                    DbNode::new(format!("{}:{}", id, v.name), v.name.clone(), DbNodeType::MaterializedView, node.connection_id.clone(), node.database_type)
+                        .with_parent_context(id).with_metadata(materialized_view_metadata(ctx.metadata, &v))
+                }).collect())
+            }
+            DbNodeType::SynonymsFolder => {
+                let synonyms = self.list_synonyms(connection, ctx.database).await?;
+                let filtered: Vec<_> = match ctx.schema {
+                    Some(s) => synonyms.into_iter().filter(|synonym| synonym.schema.as_deref() == Some(s)).collect(),
+                    None => synonyms,
+                };
+                Ok(filtered.into_iter().map(|synonym| {
+                    DbNode::new(format!("{}:{}", id, synonym.name), synonym.name.clone(), DbNodeType::Synonym, node.connection_id.clone(), node.database_type)
+                        .with_parent_context(id).with_metadata(self.synonym_metadata(ctx.metadata, ctx.database, &synonym))
+                }).collect())
+            }
             DbNodeType::FunctionsFolder => {
+                let invalid_objects = self.list_invalid_objects(connection, ctx.database).await.unwrap_or_default();
                 let functions = self.list_functions(connection, ctx.database).await.unwrap_or_default();
                 Ok(functions.into_iter().map(|f| {
                     DbNode::new(format!("{}:{}", id, f.name), f.name.clone(), DbNodeType::Function, node.connection_id.clone(), node.database_type)
-                        .with_parent_context(id).with_metadata(ctx.metadata.clone())
+                        .with_parent_context(id).with_metadata(object_metadata(ctx.metadata, &invalid_objects, DbNodeType::Function, &f.name))
                 }).collect())
             }
             DbNodeType::ProceduresFolder => {
+                let invalid_objects = self.list_invalid_objects(connection, ctx.database).await.unwrap_or_default();
                 let procedures = self.list_procedures(connection, ctx.database).await.unwrap_or_default();
                 Ok(procedures.into_iter().map(|p| {
                     DbNode::new(format!("{}:{}", id, p.name), p.name.clone(), DbNodeType::Procedure, node.connection_id.clone(), node.database_type)
-                        .with_parent_context(id).with_metadata(ctx.metadata.clone())
+                        .with_parent_context(id).with_metadata(object_metadata(ctx.metadata, &invalid_objects, DbNodeType::Procedure, &p.name))
+                }).collect())
+            }
+            DbNodeType::PackagesFolder => {
+                let packages = self.list_packages(connection, ctx.database).await.unwrap_or_default();
+                Ok(packages.into_iter().map(|p| {
+                    DbNode::new(format!("{}:{}", id, p.name), p.name.clone(), DbNodeType::Package, node.connection_id.clone(), node.database_type)
+                        .with_parent_context(id).with_metadata(package_metadata(ctx.metadata, &p))
+                }).collect())
+            }
+            DbNodeType::EventsFolder => {
+                let events = self.list_events(connection, ctx.database).await?;
+                Ok(events.into_iter().map(|event| {
+                    DbNode::new(format!("{}:{}", id, event.name), event.name.clone(), DbNodeType::Event, node.connection_id.clone(), node.database_type)
+                        .with_parent_context(id).with_metadata(event_metadata(ctx.metadata, &event))
                 }).collect())
             }
             DbNodeType::SequencesFolder => {
@@ -737,6 +1205,67 @@ pub trait DatabasePlugin: Send + Sync {
         }
     }
 
+    /// Synonym nodes carry their target and a preview query for the editor
+    fn synonym_metadata(&self, metadata: &HashMap<String, String>, database: &str, synonym: &SynonymInfo) -> HashMap<String, String> {
+        let mut meta = metadata.clone();
+        let reference = self.format_table_reference(database, synonym.schema.as_deref(), &synonym.name);
+        meta.insert("base_object".to_string(), synonym.base_object.clone());
+        meta.insert("sql".to_string(), self.build_preview_query(&reference));
+        meta
+    }
+
+    /// Folder of linked servers beside the databases of a connection. Listing failures leave
+    /// the folder empty: reading sys.servers needs permissions the login may not have.
+    async fn build_linked_servers_folder(&self, connection: &dyn DbConnection, node: &DbNode) -> DbNode {
+        let folder_id = format!("{}:linked_servers_folder", node.id);
+        let servers = self.list_linked_servers(connection).await.unwrap_or_default();
+        let mut folder = DbNode::new(
+            folder_id.clone(),
+            format!("Linked Servers ({})", servers.len()),
+            DbNodeType::LinkedServersFolder,
+            node.connection_id.clone(),
+            node.database_type
+        ).with_parent_context(&node.id);
+        let children: Vec<DbNode> = servers
+            .into_iter()
+            .map(|server| {
+                let mut meta = HashMap::from([("linked_server".to_string(), server.name.clone())]);
+                if let Some(product) = server.product {
+                    meta.insert("product".to_string(), product);
+                }
+                if let Some(data_source) = server.data_source {
+                    meta.insert("data_source".to_string(), data_source);
+                }
+                DbNode::new(format!("{}:{}", folder_id, server.name), server.name.clone(), DbNodeType::LinkedServer, node.connection_id.clone(), node.database_type)
+                    .with_parent_context(&folder_id)
+                    .with_metadata(meta)
+            })
+            .collect();
+        folder.set_children(children);
+        folder
+    }
+
+    async fn load_linked_server_children(&self, connection: &dyn DbConnection, node: &DbNode, id: &str) -> Result<Vec<DbNode>> {
+        let tables = self.list_linked_server_tables(connection, &node.name).await?;
+        Ok(tables.into_iter().map(|table| {
+            let reference = self.format_linked_table_reference(&node.name, &table);
+            // 远程对象按 目录.模式.名称 显示，避免不同目录下的同名表无法区分
+            let label = [table.catalog.as_deref(), table.schema.as_deref(), Some(table.name.as_str())]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(".");
+            let meta = HashMap::from([
+                ("linked_server".to_string(), node.name.clone()),
+                ("table_type".to_string(), table.table_type.clone()),
+                ("sql".to_string(), self.build_preview_query(&reference)),
+            ]);
+            DbNode::new(format!("{}:{}", id, label), label, DbNodeType::LinkedTable, node.connection_id.clone(), node.database_type)
+                .with_parent_context(id)
+                .with_metadata(meta)
+        }).collect())
+    }
+
     async fn load_queries_children(&self, node: &DbNode, id: &str, global_storage_state: &GlobalStorageState) -> Result<Vec<DbNode>> {
         let metadata = node.metadata.as_ref().cloned().unwrap_or_default();
         let Some(conn_repo) = global_storage_state.storage.get::<QueryRepository>().await else {
@@ -936,6 +1465,7 @@ pub trait DatabasePlugin: Send + Sync {
                 field_type: FieldType::from_db_type(&c.data_type),
                 nullable: c.is_nullable,
                 is_primary_key: c.is_primary_key,
+                collation: c.collation.clone(),
                 index: i,
             })
             .collect();
@@ -1319,35 +1849,38 @@ pub trait DatabasePlugin: Send + Sync {
         let result = connection.query(&select_sql, None, ExecOptions::default()).await
             .map_err(|e| anyhow::anyhow!("Query failed: {}", e))?;
 
+        match result {
+            SqlResult::Query(query_result) => Ok(self.export_rows_sql(table, &query_result.rows)),
+            _ => Ok(String::new()),
+        }
+    }
+
+    /// Format rows as INSERT statements, one per line
+    fn export_rows_sql(&self, table: &str, rows: &[Vec<Option<String>>]) -> String {
         let mut output = String::new();
-        if let SqlResult::Query(query_result) = result {
-            if !query_result.rows.is_empty() {
-                let table_ident = self.quote_identifier(table);
-                for row in &query_result.rows {
-                    output.push_str("INSERT INTO ");
-                    output.push_str(&table_ident);
-                    output.push_str(" VALUES (");
-
-                    for (i, value) in row.iter().enumerate() {
-                        if i > 0 {
-                            output.push_str(", ");
-                        }
-                        match value {
-                            Some(v) => {
-                                output.push('\'');
-                                output.push_str(&v.replace('\'', "''"));
-                                output.push('\'');
-                            }
-                            None => output.push_str("NULL"),
-                        }
+        let table_ident = self.quote_identifier(table);
+        for row in rows {
+            output.push_str("INSERT INTO ");
+            output.push_str(&table_ident);
+            output.push_str(" VALUES (");
+
+            for (i, value) in row.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                match value {
+                    Some(v) => {
+                        output.push('\'');
+                        output.push_str(&v.replace('\'', "''"));
+                        output.push('\'');
                     }
-
-                    output.push_str(");\n");
+                    None => output.push_str("NULL"),
                 }
             }
-        }
 
-        Ok(output)
+            output.push_str(");\n");
+        }
+        output
     }
 
     // === Charset and Collation ===
@@ -1400,6 +1933,142 @@ pub trait DatabasePlugin: Send + Sync {
         format!("DROP VIEW IF EXISTS {}", self.quote_identifier(view))
     }
 
+    // === Trash ===
+    /// Whether dropped tables can be moved into the trash schema instead of being dropped
+    fn supports_trash(&self) -> bool {
+        false
+    }
+
+    /// String literal for generated SQL
+    fn string_literal(&self, value: &str) -> String {
+        string_literal(value)
+    }
+
+    /// Statements that create the trash schema and its index table
+    fn build_create_trash_sql(&self) -> Vec<String> {
+        vec![
+            format!("CREATE SCHEMA IF NOT EXISTS {}", self.quote_identifier(TRASH_SCHEMA)),
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (trash_name VARCHAR(128) PRIMARY KEY, original_schema VARCHAR(128) NOT NULL, original_name VARCHAR(128) NOT NULL, dropped_at BIGINT NOT NULL)",
+                self.quote_identifier(TRASH_SCHEMA),
+                self.quote_identifier(TRASH_INDEX_TABLE)
+            ),
+        ]
+    }
+
+    /// Statements that move the original table into the trash schema as `entry.trash_name`
+    fn build_move_to_trash_sql(&self, _entry: &TrashEntry) -> Vec<String> {
+        vec![]
+    }
+
+    /// Statements that move a trashed table back to its original location
+    fn build_restore_from_trash_sql(&self, _entry: &TrashEntry) -> Vec<String> {
+        vec![]
+    }
+
+    /// Query returning a row when the trash index table exists
+    fn build_trash_exists_sql(&self) -> String {
+        format!(
+            "SELECT 1 FROM information_schema.tables WHERE table_schema = {} AND table_name = {}",
+            self.string_literal(TRASH_SCHEMA),
+            self.string_literal(TRASH_INDEX_TABLE)
+        )
+    }
+
+    /// Query listing trashed tables, newest first, in `TrashEntry` column order
+    fn build_list_trash_sql(&self) -> String {
+        format!(
+            "SELECT trash_name, original_schema, original_name, dropped_at FROM {}.{} ORDER BY dropped_at DESC",
+            self.quote_identifier(TRASH_SCHEMA),
+            self.quote_identifier(TRASH_INDEX_TABLE)
+        )
+    }
+
+    /// Statements that permanently drop a trashed table
+    fn build_purge_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        vec![
+            format!(
+                "DROP TABLE IF EXISTS {}.{}",
+                self.quote_identifier(TRASH_SCHEMA),
+                self.quote_identifier(&entry.trash_name)
+            ),
+            self.build_delete_trash_index_sql(entry),
+        ]
+    }
+
+    fn build_insert_trash_index_sql(&self, entry: &TrashEntry) -> String {
+        format!(
+            "INSERT INTO {}.{} (trash_name, original_schema, original_name, dropped_at) VALUES ({}, {}, {}, {})",
+            self.quote_identifier(TRASH_SCHEMA),
+            self.quote_identifier(TRASH_INDEX_TABLE),
+            self.string_literal(&entry.trash_name),
+            self.string_literal(&entry.original_schema),
+            self.string_literal(&entry.original_name),
+            entry.dropped_at
+        )
+    }
+
+    fn build_delete_trash_index_sql(&self, entry: &TrashEntry) -> String {
+        format!(
+            "DELETE FROM {}.{} WHERE trash_name = {}",
+            self.quote_identifier(TRASH_SCHEMA),
+            self.quote_identifier(TRASH_INDEX_TABLE),
+            self.string_literal(&entry.trash_name)
+        )
+    }
+
+    // === Bulk Import / Export ===
+    /// Formats the server can render query results in directly, used by the export dialog
+    fn native_export_formats(&self) -> Vec<NativeExportFormat> {
+        vec![]
+    }
+
+    /// Whether bulk imports can use server-side asynchronous inserts
+    fn supports_async_insert(&self) -> bool {
+        false
+    }
+
+    /// Most rows a single `INSERT ... VALUES (...), (...)` may carry, `None` when unlimited
+    fn max_rows_per_insert(&self) -> Option<usize> {
+        None
+    }
+
+    /// How exported INSERTs skip or overwrite rows whose key already exists, `None` when the
+    /// dialect has no syntax for `mode`. Upserts match on `key_columns`, the table's primary key
+    fn insert_conflict_clause(&self, _mode: SqlConflictMode, _columns: &[String], _key_columns: &[String]) -> Option<InsertConflictClause> {
+        None
+    }
+
+    /// Statement turning foreign key checks off or back on for the session, wrapped around SQL
+    /// exports so tables can be loaded in any order. `None` when the database can't do this
+    fn foreign_key_checks_sql(&self, _enabled: bool) -> Option<String> {
+        None
+    }
+
+    // === Extensions ===
+    /// Whether the database supports installable extensions
+    fn supports_extensions(&self) -> bool {
+        false
+    }
+
+    /// Query listing available extensions in `ExtensionInfo` column order, installed ones first
+    fn build_list_extensions_sql(&self) -> Option<String> {
+        None
+    }
+
+    /// Query listing (type name, extension name) for data types provided by installed extensions
+    fn build_list_extension_types_sql(&self) -> Option<String> {
+        None
+    }
+
+    fn build_create_extension_sql(&self, _name: &str, _schema: Option<&str>) -> Option<String> {
+        None
+    }
+
+    fn build_drop_extension_sql(&self, _name: &str, _cascade: bool) -> Option<String> {
+        None
+    }
+
     /// Build column definition from ColumnDefinition (for table designer)
     fn build_column_def(&self, col: &ColumnDefinition) -> String;
 
@@ -1440,6 +2109,44 @@ pub trait DatabasePlugin: Send + Sync {
 
 }
 
+/// Standard SQL string literal that only doubles single quotes; MySQL-family plugins
+/// also escape backslashes in `DatabasePlugin::string_literal`
+pub(crate) fn string_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// `ON CONFLICT` clause shared by PostgreSQL-style dialects. An upsert needs a conflict target,
+/// so tables without a primary key can only skip conflicting rows
+pub fn on_conflict_clause(plugin: &dyn DatabasePlugin, mode: SqlConflictMode, columns: &[String], key_columns: &[String]) -> Option<InsertConflictClause> {
+    let suffix = match mode {
+        SqlConflictMode::Error => String::new(),
+        SqlConflictMode::Ignore => " ON CONFLICT DO NOTHING".to_string(),
+        SqlConflictMode::Update => {
+            if key_columns.is_empty() {
+                return None;
+            }
+            let target = key_columns.iter().map(|column| plugin.quote_identifier(column)).collect::<Vec<_>>().join(", ");
+            let assignments = columns
+                .iter()
+                .filter(|column| !key_columns.contains(column))
+                .map(|column| {
+                    let quoted = plugin.quote_identifier(column);
+                    format!("{} = EXCLUDED.{}", quoted, quoted)
+                })
+                .collect::<Vec<_>>();
+            if assignments.is_empty() {
+                format!(" ON CONFLICT ({}) DO NOTHING", target)
+            } else {
+                format!(" ON CONFLICT ({}) DO UPDATE SET {}", target, assignments.join(", "))
+            }
+        }
+    };
+    Some(InsertConflictClause {
+        keyword: "INSERT".to_string(),
+        suffix,
+    })
+}
+
 
 /// Split SQL statements using sqlparser's parser with the given dialect
 pub fn split_statements_with_dialect(script: &str, dialect: &dyn Dialect) -> Vec<String> {
@@ -1483,12 +2190,12 @@ pub fn can_use_sqlparser_with_db_type(script: &str, db_type: DatabaseType) -> bo
     }
 
     match db_type {
-        DatabaseType::MySQL => {
+        DatabaseType::MySQL | DatabaseType::TiDB => {
             if upper.contains("DELIMITER") {
                 return false;
             }
         }
-        DatabaseType::PostgreSQL => {
+        DatabaseType::PostgreSQL | DatabaseType::CockroachDB => {
             if script.contains('$') && script.matches('$').count() >= 2 {
                 return false;
             }
@@ -1537,6 +2244,8 @@ pub fn fallback_split_with_db_type(script: &str, db_type: DatabaseType) -> Vec<S
     let mut begin_depth = 0i32;
     let mut last_word_checked = String::new();
     let mut delimiter = ";".to_string();
+    // TiDB 使用 MySQL 的词法：反斜杠转义、# 注释、反引号标识符和 DELIMITER
+    let mysql_lexer = matches!(db_type, DatabaseType::MySQL | DatabaseType::TiDB);
 
     while let Some(ch) = chars.next() {
         // ---------- 行注释 ----------
@@ -1584,7 +2293,7 @@ pub fn fallback_split_with_db_type(script: &str, db_type: DatabaseType) -> Vec<S
                 } else {
                     in_string = false;
                 }
-            } else if ch == '\\' && db_type == DatabaseType::MySQL {
+            } else if ch == '\\' && mysql_lexer {
                 if let Some(_) = chars.peek() {
                     if let Some(next_ch) = chars.next() {
                         current.push(next_ch);
@@ -1604,7 +2313,7 @@ pub fn fallback_split_with_db_type(script: &str, db_type: DatabaseType) -> Vec<S
             continue;
         }
 
-        if ch == '#' && db_type == DatabaseType::MySQL {
+        if ch == '#' && mysql_lexer {
             current.push(ch);
             in_line_comment = true;
             continue;
@@ -1619,8 +2328,8 @@ pub fn fallback_split_with_db_type(script: &str, db_type: DatabaseType) -> Vec<S
             continue;
         }
 
-        // ---------- Dollar Quote 起始 (PostgreSQL) ----------
-        if ch == '$' && db_type == DatabaseType::PostgreSQL {
+        // ---------- Dollar Quote 起始 (PostgreSQL / CockroachDB / DuckDB) ----------
+        if ch == '$' && matches!(db_type, DatabaseType::PostgreSQL | DatabaseType::CockroachDB | DatabaseType::DuckDB) {
             if let Some(tag) = try_parse_dollar_quote(&mut chars) {
                 dollar_quote = Some(tag.clone());
                 current.push_str(&tag);
@@ -1636,7 +2345,7 @@ pub fn fallback_split_with_db_type(script: &str, db_type: DatabaseType) -> Vec<S
             continue;
         }
 
-        if ch == '`' && db_type == DatabaseType::MySQL {
+        if ch == '`' && mysql_lexer {
             in_string = true;
             string_char = ch;
             current.push(ch);
@@ -1664,7 +2373,7 @@ pub fn fallback_split_with_db_type(script: &str, db_type: DatabaseType) -> Vec<S
         }
 
         // ---------- DELIMITER 命令 (MySQL) ----------
-        if db_type == DatabaseType::MySQL && ch == '\n' {
+        if mysql_lexer && ch == '\n' {
             if let Some(new_delim) = try_parse_delimiter(&current) {
                 delimiter = new_delim;
                 let lines: Vec<&str> = current.lines().collect();
@@ -1804,6 +2513,42 @@ pub fn is_query_stmt(stmt: &Statement) -> bool {
     )
 }
 
+/// Whether a parsed query, including its CTEs and set operations, only reads data
+pub fn query_is_read_only(query: &ast::Query) -> bool {
+    query.locks.is_empty()
+        && query
+            .with
+            .as_ref()
+            .map_or(true, |with| with.cte_tables.iter().all(|cte| query_is_read_only(&cte.query)))
+        && set_expr_is_read_only(&query.body)
+}
+
+/// Parse `condition` as one expression; trailing tokens such as `; DROP TABLE ...` are rejected
+pub fn validate_where_condition(condition: &str, dialect: &dyn Dialect) -> Result<()> {
+    let condition = condition.trim();
+    if condition.is_empty() {
+        return Err(anyhow::anyhow!("WHERE condition is empty"));
+    }
+    let mut parser = Parser::new(dialect).try_with_sql(condition)?;
+    parser.parse_expr()?;
+    let next = parser.peek_token();
+    if next.token != Token::EOF {
+        return Err(anyhow::anyhow!("Unexpected `{}` after the WHERE condition", next.token));
+    }
+    Ok(())
+}
+
+fn set_expr_is_read_only(expr: &SetExpr) -> bool {
+    match expr {
+        SetExpr::Select(select) => select.into.is_none(),
+        SetExpr::Query(query) => query_is_read_only(query),
+        SetExpr::SetOperation { left, right, .. } => set_expr_is_read_only(left) && set_expr_is_read_only(right),
+        SetExpr::Values(_) | SetExpr::Table(_) => true,
+        // Data-modifying CTE bodies (INSERT/UPDATE/DELETE ... RETURNING)
+        _ => false,
+    }
+}
+
 pub fn is_query_statement_fallback(sql: &str) -> bool {
     let trimmed = sql.trim().to_uppercase();
     trimmed.starts_with("SELECT")
@@ -2026,7 +2771,7 @@ pub fn analyze_select_editability_fallback(sql: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sqlparser::dialect::{ClickHouseDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect, OracleDialect};
+    use sqlparser::dialect::{ClickHouseDialect, MsSqlDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect, OracleDialect, GenericDialect, DuckDbDialect};
     use sqlparser::parser::Parser;
 
     // ==================== split_statements_with_dialect tests ====================
@@ -2060,19 +2805,29 @@ mod tests {
         let sql = "SELECT 1; SELECT 2;";
         for db_type in [
             DatabaseType::MySQL,
+            DatabaseType::TiDB,
             DatabaseType::PostgreSQL,
+            DatabaseType::CockroachDB,
             DatabaseType::SQLite,
             DatabaseType::MSSQL,
             DatabaseType::Oracle,
             DatabaseType::ClickHouse,
+            DatabaseType::MongoDB,
+            DatabaseType::Redis,
+            DatabaseType::DuckDB,
+            DatabaseType::Trino,
+            DatabaseType::Elasticsearch,
+            DatabaseType::ODBC,
         ] {
            let dialect: Box<dyn Dialect> = match db_type {
-                DatabaseType::MySQL => Box::new(MySqlDialect {}),
-                DatabaseType::PostgreSQL => Box::new(PostgreSqlDialect {}),
+                DatabaseType::MySQL | DatabaseType::TiDB => Box::new(MySqlDialect {}),
+                DatabaseType::PostgreSQL | DatabaseType::CockroachDB => Box::new(PostgreSqlDialect {}),
                 DatabaseType::MSSQL => Box::new(MsSqlDialect {}),
                 DatabaseType::SQLite => Box::new(SQLiteDialect {}),
                 DatabaseType::ClickHouse => Box::new(ClickHouseDialect {}),
-                DatabaseType::Oracle => Box::new(OracleDialect {})
+                DatabaseType::Oracle => Box::new(OracleDialect {}),
+                DatabaseType::DuckDB => Box::new(DuckDbDialect {}),
+                DatabaseType::MongoDB | DatabaseType::Redis | DatabaseType::Trino | DatabaseType::Elasticsearch | DatabaseType::ODBC => Box::new(GenericDialect {}),
             };
             let stmts = split_statements_for_database(sql, db_type, dialect);
             assert_eq!(stmts.len(), 2, "Failed for {:?}", db_type);
@@ -2578,4 +3333,31 @@ SELECT 3;
         assert_eq!(analyze_select_editability_fallback("INSERT INTO users VALUES (1)"), None);
         assert_eq!(analyze_select_editability_fallback("UPDATE users SET name = 'test'"), None);
     }
+
+    #[test]
+    fn test_query_is_read_only() {
+        let read_only = |sql: &str| {
+            matches!(
+                Parser::parse_sql(&PostgreSqlDialect {}, sql).as_deref(),
+                Ok([Statement::Query(query)]) if query_is_read_only(query)
+            )
+        };
+        assert!(read_only("SELECT COUNT(*) FROM orders WHERE created_at >= '2026-09-01'"));
+        assert!(read_only("WITH recent AS (SELECT * FROM orders) SELECT id FROM recent UNION SELECT id FROM users"));
+        assert!(!read_only("SELECT * INTO backup FROM orders"));
+        assert!(!read_only("SELECT * FROM orders FOR UPDATE"));
+        assert!(!read_only("WITH gone AS (DELETE FROM orders RETURNING id) SELECT * FROM gone"));
+        assert!(!read_only("DELETE FROM orders"));
+    }
+
+    #[test]
+    fn test_validate_where_condition() {
+        let dialect = MySqlDialect {};
+        assert!(validate_where_condition("YEAR(created_at) = 2024 AND status = 'shipped'", &dialect).is_ok());
+        assert!(validate_where_condition("id IN (SELECT order_id FROM shipments)", &dialect).is_ok());
+        assert!(validate_where_condition("1 = 1; DROP TABLE orders", &dialect).is_err());
+        assert!(validate_where_condition("status = 'shipped' ORDER BY id", &dialect).is_err());
+        assert!(validate_where_condition("status = ", &dialect).is_err());
+        assert!(validate_where_condition("  ", &dialect).is_err());
+    }
 }
\ No newline at end of file
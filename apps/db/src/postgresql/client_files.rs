@@ -0,0 +1,235 @@
+//! libpq client files: connection parameters from pg_service.conf and passwords from ~/.pgpass,
+//! looked up the way psql does. Both are opt-in per connection.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use one_core::storage::DbConnectionConfig;
+use tracing::warn;
+
+use crate::connection::DbError;
+
+/// Connection parameter naming a service in pg_service.conf
+pub const SERVICE_PARAM: &str = "pg_service";
+/// Connection parameter enabling the password lookup in ~/.pgpass
+pub const USE_PGPASS_PARAM: &str = "use_pgpass";
+
+/// Apply the connection's service and .pgpass password. Parameters from the service replace
+/// the form's values; .pgpass is only consulted when no password is set, as in psql.
+pub fn resolve(config: &DbConnectionConfig) -> Result<DbConnectionConfig, DbError> {
+    let mut resolved = config.clone();
+
+    if let Some(service) = config.get_param(SERVICE_PARAM).map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let params = find_service(service)?;
+        apply_service(&mut resolved, &params)?;
+    }
+
+    if config.get_param_bool(USE_PGPASS_PARAM) && resolved.password.is_empty() {
+        if let Some(password) = read_pgpass().and_then(|contents| {
+            let database = resolved.database.as_deref().unwrap_or(&resolved.username);
+            lookup_pgpass(&contents, &resolved.host, resolved.port, database, &resolved.username)
+        }) {
+            resolved.password = password;
+        }
+    }
+
+    Ok(resolved)
+}
+
+fn home_file(unix_name: &str, windows_name: &str) -> Option<PathBuf> {
+    if cfg!(windows) {
+        dirs::config_dir().map(|dir| dir.join("postgresql").join(windows_name))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(unix_name))
+    }
+}
+
+/// The user's service file, then the system one; the first file defining the service wins
+fn service_files() -> Vec<PathBuf> {
+    let user_file = std::env::var_os("PGSERVICEFILE")
+        .map(PathBuf::from)
+        .or_else(|| home_file(".pg_service.conf", ".pg_service.conf"));
+    let system_file = std::env::var_os("PGSYSCONFDIR").map(|dir| PathBuf::from(dir).join("pg_service.conf"));
+    user_file.into_iter().chain(system_file).collect()
+}
+
+fn find_service(service: &str) -> Result<HashMap<String, String>, DbError> {
+    let files = service_files();
+    for file in &files {
+        let Ok(contents) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        if let Some(params) = parse_service(&contents, service) {
+            return Ok(params);
+        }
+    }
+    Err(DbError::ConnectionError(format!(
+        "Service \"{}\" not found in {}",
+        service,
+        files.iter().map(|file| file.display().to_string()).collect::<Vec<_>>().join(", ")
+    )))
+}
+
+/// Parameters of `[service]` in an INI style service file
+fn parse_service(contents: &str, service: &str) -> Option<HashMap<String, String>> {
+    let mut params = None;
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            if params.is_some() {
+                break;
+            }
+            if section.trim() == service {
+                params = Some(HashMap::new());
+            }
+            continue;
+        }
+        if let (Some(params), Some((key, value))) = (params.as_mut(), line.split_once('=')) {
+            params.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    params
+}
+
+fn apply_service(config: &mut DbConnectionConfig, params: &HashMap<String, String>) -> Result<(), DbError> {
+    for (key, value) in params {
+        match key.as_str() {
+            "host" => config.host = value.clone(),
+            "port" => {
+                config.port = value
+                    .parse()
+                    .map_err(|_| DbError::ConnectionError(format!("Invalid port in service file: {}", value)))?
+            }
+            "dbname" => config.database = Some(value.clone()),
+            "user" => config.username = value.clone(),
+            "password" => config.password = value.clone(),
+            "application_name" | "connect_timeout" => {
+                config.extra_params.insert(key.clone(), value.clone());
+            }
+            // prefer and allow fall back to plain text, which needs no change here
+            "sslmode" if matches!(value.as_str(), "disable" | "require" | "verify-ca" | "verify-full") => {
+                config.extra_params.insert("ssl_mode".to_string(), value.clone());
+            }
+            "sslrootcert" => {
+                config.extra_params.insert("ssl_ca".to_string(), value.clone());
+            }
+            "sslcert" => {
+                config.extra_params.insert("ssl_cert".to_string(), value.clone());
+            }
+            "sslkey" => {
+                config.extra_params.insert("ssl_key".to_string(), value.clone());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn read_pgpass() -> Option<String> {
+    let path = std::env::var_os("PGPASSFILE")
+        .map(PathBuf::from)
+        .or_else(|| home_file(".pgpass", "pgpass.conf"))?;
+
+    // psql ignores a password file readable by group or others
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).ok()?.permissions().mode();
+        if mode & 0o077 != 0 {
+            warn!("Password file {} has group or world access; permissions should be u=rw (0600)", path.display());
+            return None;
+        }
+    }
+
+    std::fs::read_to_string(&path).ok()
+}
+
+/// Password of the first `hostname:port:database:username:password` line matching the connection.
+/// `*` matches anything; `\:` and `\\` escape a colon and a backslash.
+fn lookup_pgpass(contents: &str, host: &str, port: u16, database: &str, username: &str) -> Option<String> {
+    // Unix socket directories are matched as localhost
+    let host = if host.is_empty() || host.starts_with('/') { "localhost" } else { host };
+    let port = port.to_string();
+    let wanted = [host, port.as_str(), database, username];
+
+    contents
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .map(split_pgpass_line)
+        .filter(|fields| fields.len() == 5)
+        .find(|fields| {
+            fields
+                .iter()
+                .zip(wanted)
+                .all(|(field, wanted)| field == "*" || field == wanted)
+        })
+        .map(|mut fields| fields.remove(4))
+}
+
+fn split_pgpass_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let (Some(next), Some(field)) = (chars.next(), fields.last_mut()) {
+                    field.push(next);
+                }
+            }
+            // The password may contain unescaped colons
+            ':' if fields.len() < 5 => fields.push(String::new()),
+            c => {
+                if let Some(field) = fields.last_mut() {
+                    field.push(c);
+                }
+            }
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_section_is_parsed() {
+        let contents = "# comment\n[other]\nhost=elsewhere\n\n[reporting]\nhost = db.internal\nport=6543\ndbname=reports\nsslmode=verify-full\n[last]\nhost=x\n";
+        let params = parse_service(contents, "reporting").unwrap();
+        assert_eq!(params.get("host").map(String::as_str), Some("db.internal"));
+        assert_eq!(params.len(), 4);
+        assert!(parse_service(contents, "missing").is_none());
+
+        let mut config = DbConnectionConfig {
+            id: String::new(),
+            database_type: one_core::storage::DatabaseType::PostgreSQL,
+            name: String::new(),
+            host: "localhost".to_string(),
+            port: 5432,
+            username: "postgres".to_string(),
+            password: String::new(),
+            database: None,
+            workspace_id: None,
+            extra_params: HashMap::new(),
+        };
+        apply_service(&mut config, &params).unwrap();
+        assert_eq!((config.host.as_str(), config.port), ("db.internal", 6543));
+        assert_eq!(config.database.as_deref(), Some("reports"));
+        assert_eq!(config.get_param("ssl_mode").map(String::as_str), Some("verify-full"));
+    }
+
+    #[test]
+    fn test_pgpass_first_match_wins() {
+        let contents = "\
+# hostname:port:database:username:password
+db.internal:5432:reports:alice:first
+*:*:*:alice:p\\:ass:with:colons
+localhost:5432:*:bob:local";
+        assert_eq!(lookup_pgpass(contents, "db.internal", 5432, "reports", "alice").as_deref(), Some("first"));
+        assert_eq!(lookup_pgpass(contents, "other", 5433, "app", "alice").as_deref(), Some("p:ass:with:colons"));
+        assert_eq!(lookup_pgpass(contents, "/var/run/postgresql", 5432, "app", "bob").as_deref(), Some("local"));
+        assert_eq!(lookup_pgpass(contents, "other", 5432, "app", "carol"), None);
+    }
+}
@@ -1,19 +1,27 @@
+use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
 use one_core::storage::DbConnectionConfig;
+use postgres_native_tls::MakeTlsConnector;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::{Mutex};
-use tokio_postgres::{Client, Config, NoTls, Row, types::Type};
+use tokio_postgres::config::SslMode;
+use tokio_postgres::{Client, Config, Connection, NoTls, Row, types::Type};
 
 use crate::connection::{DbConnection, DbError, StreamingProgress};
 use crate::executor::{ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult, SqlStatementClassifier};
 use tokio::sync::mpsc;
-use crate::{DatabasePlugin, SqlValue};
+use crate::proxy::ProxyForward;
+use super::client_files;
+use crate::{cloud_auth, tls, DatabasePlugin, SqlValue};
 
 pub struct PostgresDbConnection {
     config: DbConnectionConfig,
     client: Arc<Mutex<Option<Client>>>,
+    /// Keeps the local proxy port open while connected
+    proxy_forward: Option<ProxyForward>,
 }
 
 impl PostgresDbConnection {
@@ -21,9 +29,23 @@ impl PostgresDbConnection {
         Self {
             config,
             client: Arc::new(Mutex::new(None)),
+            proxy_forward: None,
         }
     }
 
+    /// The connection task handles communication with the server in the background
+    fn spawn_connection<S, T>(connection: Connection<S, T>)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+        T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!("PostgreSQL connection error: {}", e);
+            }
+        });
+    }
+
     /// Extract value from PostgreSQL row
     fn extract_value(row: &Row, index: usize) -> Option<String> {
         // Get column type
@@ -196,14 +218,21 @@ impl DbConnection for PostgresDbConnection {
     }
 
     async fn connect(&mut self) -> anyhow::Result<(), DbError> {
-        let config = &self.config;
+        let config = &cloud_auth::resolve(&client_files::resolve(&self.config)?).await?;
+        let proxy_forward = ProxyForward::start(config).await?;
 
         let mut pg_config = Config::new();
         pg_config
             .host(&config.host)
-            .port(config.port)
             .user(&config.username)
             .password(&config.password);
+        // `host` stays the database host so TLS still checks it; only the dialed address changes
+        match &proxy_forward {
+            Some(forward) => pg_config
+                .hostaddr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+                .port(forward.local_port()),
+            None => pg_config.port(config.port),
+        };
 
         if let Some(ref db) = config.database {
             pg_config.dbname(db);
@@ -218,22 +247,30 @@ impl DbConnection for PostgresDbConnection {
         }
 
         // Connect to PostgreSQL
-        let (client, connection) = pg_config
-            .connect(NoTls)
-            .await
-            .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))?;
-
-        // Spawn the connection task in background - it handles communication with the server
-        tokio::spawn(async move {
-            if let Err(e) = connection.await {
-                tracing::error!("PostgreSQL connection error: {}", e);
-            }
-        });
+        let ssl = config.ssl();
+        let client = if ssl.is_enabled() {
+            pg_config.ssl_mode(SslMode::Require);
+            let connector = MakeTlsConnector::new(tls::native_tls_connector(&ssl)?);
+            let (client, connection) = pg_config
+                .connect(connector)
+                .await
+                .map_err(|e| ProxyForward::connect_error(proxy_forward.as_ref(), tls::connection_error(&ssl, e)))?;
+            Self::spawn_connection(connection);
+            client
+        } else {
+            let (client, connection) = pg_config
+                .connect(NoTls)
+                .await
+                .map_err(|e| ProxyForward::connect_error(proxy_forward.as_ref(), tls::connection_error(&ssl, e)))?;
+            Self::spawn_connection(connection);
+            client
+        };
 
         {
             let mut guard = self.client.lock().await;
             *guard = Some(client);
         }
+        self.proxy_forward = proxy_forward;
 
         Ok(())
     }
@@ -241,6 +278,7 @@ impl DbConnection for PostgresDbConnection {
     async fn disconnect(&mut self) -> Result<(), DbError> {
         let mut guard = self.client.lock().await;
         *guard = None;
+        self.proxy_forward = None;
         Ok(())
     }
 
@@ -1,4 +1,7 @@
+mod client_files;
 mod connection;
 mod plugin;
 
+pub use client_files::{SERVICE_PARAM, USE_PGPASS_PARAM};
+pub use connection::PostgresDbConnection;
 pub use plugin::PostgresPlugin;
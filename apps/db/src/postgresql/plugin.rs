@@ -7,8 +7,12 @@ use one_core::storage::{DatabaseType, DbConnectionConfig};
 
 use crate::connection::{DbConnection, DbError};
 use crate::executor::{ExecOptions, SqlResult};
-use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::plugin::{on_conflict_clause, DatabasePlugin, SqlCompletionInfo};
+use crate::import_export::{InsertConflictClause, SqlConflictMode};
+use crate::server_status::{query_server_status, ServerStatus};
+use crate::session_variable::session_variable_literal;
 use crate::postgresql::connection::PostgresDbConnection;
+use crate::trash::{TrashEntry, TRASH_SCHEMA};
 use crate::types::*;
 
 /// PostgreSQL database plugin implementation (stateless)
@@ -18,6 +22,22 @@ impl PostgresPlugin {
     pub fn new() -> Self {
         Self
     }
+
+    /// CREATE statement of a materialized view followed by its indexes. The indexes are part
+    /// of the structure: a concurrent refresh is refused without a unique one.
+    fn materialized_view_script(&self, schema: &str, name: &str, definition: &str, indexes: &[String]) -> String {
+        let mut script = format!(
+            "CREATE MATERIALIZED VIEW {}.{} AS\n{};\n",
+            self.quote_identifier(schema),
+            self.quote_identifier(name),
+            definition.trim().trim_end_matches(';')
+        );
+        for index in indexes {
+            script.push_str(index.trim_end_matches(';'));
+            script.push_str(";\n");
+        }
+        script
+    }
 }
 
 #[async_trait]
@@ -34,6 +54,10 @@ impl DatabasePlugin for PostgresPlugin {
         Box::new(sqlparser::dialect::PostgreSqlDialect {})
     }
 
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        Some(("START TRANSACTION READ ONLY", "ROLLBACK"))
+    }
+
     fn supports_schema(&self) -> bool {
         true
     }
@@ -135,9 +159,6 @@ impl DatabasePlugin for PostgresPlugin {
                 ("NULLS LAST", "Sort NULLs last"),
                 ("ILIKE", "Case-insensitive LIKE"),
                 ("SIMILAR TO", "SQL regex pattern match"),
-                ("OVER", "Window function clause"),
-                ("PARTITION BY", "Window partition"),
-                ("ROWS BETWEEN", "Window frame"),
                 ("RANGE BETWEEN", "Window frame range"),
                 ("WITH RECURSIVE", "Recursive CTE"),
                 ("MATERIALIZED", "Materialized CTE"),
@@ -182,6 +203,10 @@ impl DatabasePlugin for PostgresPlugin {
                 ("MAKE_TIME(h, m, s)", "Create time"),
                 ("MAKE_TIMESTAMP(y,m,d,h,mi,s)", "Create timestamp"),
                 ("MAKE_INTERVAL(...)", "Create interval"),
+                ("DATE_BIN(stride, source, origin)", "Bin timestamp into intervals"),
+                ("JUSTIFY_DAYS(interval)", "Normalize 30-day periods to months"),
+                ("JUSTIFY_HOURS(interval)", "Normalize 24-hour periods to days"),
+                ("GENERATE_SERIES(start, stop, step)", "Generate series of values/timestamps"),
                 ("TO_CHAR(val, fmt)", "Format to string"),
                 ("TO_DATE(str, fmt)", "Parse date"),
                 ("TO_TIMESTAMP(str, fmt)", "Parse timestamp"),
@@ -197,14 +222,6 @@ impl DatabasePlugin for PostgresPlugin {
                 ("BOOL_OR(col)", "Logical OR"),
                 ("BIT_AND(col)", "Bitwise AND"),
                 ("BIT_OR(col)", "Bitwise OR"),
-                ("ROW_NUMBER()", "Row number in partition"),
-                ("RANK()", "Rank with gaps"),
-                ("DENSE_RANK()", "Rank without gaps"),
-                ("NTILE(n)", "Divide into n buckets"),
-                ("LAG(col, n)", "Previous row value"),
-                ("LEAD(col, n)", "Next row value"),
-                ("FIRST_VALUE(col)", "First value in frame"),
-                ("LAST_VALUE(col)", "Last value in frame"),
                 ("NTH_VALUE(col, n)", "Nth value in frame"),
                 ("PERCENT_RANK()", "Relative rank"),
                 ("CUME_DIST()", "Cumulative distribution"),
@@ -224,6 +241,9 @@ impl DatabasePlugin for PostgresPlugin {
                 ("JSONB_ARRAY_ELEMENTS(jsonb)", "Expand JSONB array"),
                 ("JSONB_STRIP_NULLS(jsonb)", "Remove null values"),
                 ("JSONB_PATH_QUERY(target, path)", "JSONPath query"),
+                ("JSONB_PATH_EXISTS(target, path)", "Check JSONPath match"),
+                ("JSONB_OBJECT_KEYS(jsonb)", "Top-level object keys"),
+                ("TO_JSONB(val)", "Convert value to JSONB"),
                 ("ARRAY_LENGTH(arr, dim)", "Array length"),
                 ("ARRAY_DIMS(arr)", "Array dimensions"),
                 ("ARRAY_UPPER(arr, dim)", "Upper bound"),
@@ -496,7 +516,8 @@ impl DatabasePlugin for PostgresPlugin {
               WHERE kcu.table_name = c.table_name AND kcu.column_name = c.column_name \
               AND kcu.table_schema = '{}' AND EXISTS \
               (SELECT 1 FROM information_schema.table_constraints tc \
-               WHERE tc.constraint_name = kcu.constraint_name AND tc.constraint_type = 'PRIMARY KEY')) > 0 AS is_primary \
+               WHERE tc.constraint_name = kcu.constraint_name AND tc.constraint_type = 'PRIMARY KEY')) > 0 AS is_primary, \
+             c.collation_name \
              FROM information_schema.columns c \
              WHERE table_schema = '{}' AND table_name = '{}' \
              ORDER BY ordinal_position",
@@ -516,6 +537,7 @@ impl DatabasePlugin for PostgresPlugin {
                     is_primary_key: row.get(4).and_then(|v| v.clone()).map(|v| v == "t" || v == "true" || v == "1").unwrap_or(false),
                     default_value: row.get(3).and_then(|v| v.clone()),
                     comment: None,
+                    collation: row.get(5).and_then(|v| v.clone()),
                 }
             }).collect())
         } else {
@@ -678,6 +700,79 @@ impl DatabasePlugin for PostgresPlugin {
         })
     }
 
+    // === Materialized View Operations ===
+
+    fn supports_materialized_views(&self) -> bool {
+        true
+    }
+
+    async fn list_materialized_views(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<MaterializedViewInfo>> {
+        let sql = "SELECT matviewname, schemaname, definition, ispopulated FROM pg_matviews \
+                   WHERE schemaname NOT IN ('pg_catalog', 'information_schema') \
+                   ORDER BY schemaname, matviewname";
+
+        let result = connection.query(sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list materialized views: {}", e))?;
+
+        if let SqlResult::Query(query_result) = result {
+            Ok(query_result.rows.iter().map(|row| {
+                MaterializedViewInfo {
+                    name: row.first().and_then(|v| v.clone()).unwrap_or_default(),
+                    schema: row.get(1).and_then(|v| v.clone()),
+                    definition: row.get(2).and_then(|v| v.clone()),
+                    is_populated: row.get(3).and_then(|v| v.clone()).map(|v| v == "t" || v == "true").unwrap_or(true),
+                }
+            }).collect())
+        } else {
+            Err(anyhow::anyhow!("Unexpected result type"))
+        }
+    }
+
+    fn build_refresh_materialized_view_sql(&self, schema: Option<&str>, view: &str, concurrently: bool) -> Result<String> {
+        Ok(format!(
+            "REFRESH MATERIALIZED VIEW {}{}.{}",
+            if concurrently { "CONCURRENTLY " } else { "" },
+            self.quote_identifier(schema.unwrap_or("public")),
+            self.quote_identifier(view)
+        ))
+    }
+
+    async fn export_materialized_views_sql(&self, connection: &dyn DbConnection, database: &str) -> Result<String> {
+        let views = self.list_materialized_views(connection, database).await?;
+        if views.is_empty() {
+            return Ok(String::new());
+        }
+
+        let index_sql = "SELECT i.schemaname, i.tablename, i.indexdef FROM pg_indexes i \
+                         JOIN pg_matviews m ON m.schemaname = i.schemaname AND m.matviewname = i.tablename \
+                         ORDER BY i.schemaname, i.tablename, i.indexname";
+        let mut indexes: HashMap<(String, String), Vec<String>> = HashMap::new();
+        if let SqlResult::Query(query_result) = connection.query(index_sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list materialized view indexes: {}", e))?
+        {
+            for row in &query_result.rows {
+                if let (Some(Some(schema)), Some(Some(view)), Some(Some(definition))) = (row.first(), row.get(1), row.get(2)) {
+                    indexes.entry((schema.clone(), view.clone())).or_default().push(definition.clone());
+                }
+            }
+        }
+
+        let mut output = String::new();
+        for view in views {
+            let schema = view.schema.unwrap_or_else(|| "public".to_string());
+            let Some(definition) = view.definition else {
+                continue;
+            };
+            let view_indexes = indexes.remove(&(schema.clone(), view.name.clone())).unwrap_or_default();
+            output.push_str(&format!("-- Materialized view structure for {}.{}\n", schema, view.name));
+            output.push_str(&self.materialized_view_script(&schema, &view.name, &definition, &view_indexes));
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
     // === Function Operations ===
 
     async fn list_functions(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
@@ -830,6 +925,47 @@ impl DatabasePlugin for PostgresPlugin {
         })
     }
 
+    async fn get_routine_source(&self, connection: &dyn DbConnection, _database: &str, schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        let schema = schema.unwrap_or("public").replace("'", "''");
+        let name = name.replace("'", "''");
+        // 触发器本身只是绑定，真正的逻辑在触发器函数里，两者一起返回
+        let sql = match object_type {
+            DbNodeType::Function | DbNodeType::Procedure => format!(
+                "SELECT pg_get_functiondef(p.oid) FROM pg_proc p \
+                 JOIN pg_namespace n ON n.oid = p.pronamespace \
+                 WHERE n.nspname = '{}' AND p.proname = '{}' ORDER BY p.oid",
+                schema, name
+            ),
+            DbNodeType::Trigger => format!(
+                "SELECT pg_get_triggerdef(t.oid, true) || ';' || chr(10) || chr(10) || pg_get_functiondef(t.tgfoid) FROM pg_trigger t \
+                 JOIN pg_class c ON c.oid = t.tgrelid \
+                 JOIN pg_namespace n ON n.oid = c.relnamespace \
+                 WHERE n.nspname = '{}' AND t.tgname = '{}' AND NOT t.tgisinternal",
+                schema, name
+            ),
+            DbNodeType::MaterializedView => format!(
+                "SELECT 'CREATE MATERIALIZED VIEW ' || quote_ident(schemaname) || '.' || quote_ident(matviewname) || ' AS' || chr(10) || definition \
+                 FROM pg_matviews WHERE schemaname = '{}' AND matviewname = '{}'",
+                schema, name
+            ),
+            _ => return Err(anyhow::anyhow!("{} source is not supported: {}", object_type, name)),
+        };
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load routine source: {}", e))?;
+
+        match result {
+            // 重载的同名函数各占一行
+            SqlResult::Query(query_result) if !query_result.rows.is_empty() => Ok(query_result.rows.iter()
+                .filter_map(|row| row.first().cloned().flatten())
+                .collect::<Vec<_>>()
+                .join("\n\n")),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("{}", err.message)),
+            _ => Err(anyhow::anyhow!("Routine source not found: {}.{}", schema, name)),
+        }
+    }
+
     async fn list_table_checks(&self, connection: &dyn DbConnection, _database: &str, schema: Option<&str>, table: &str) -> Result<Vec<CheckInfo>> {
         let schema_val = schema.unwrap_or("public");
         let sql = format!(
@@ -1012,6 +1148,47 @@ impl DatabasePlugin for PostgresPlugin {
         format!("ALTER TABLE {} RENAME TO {}", self.quote_identifier(old_name), self.quote_identifier(new_name))
     }
 
+    fn supports_trash(&self) -> bool {
+        true
+    }
+
+    fn build_move_to_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        // 先在原 schema 内改名，避免与回收站中的同名表冲突
+        vec![
+            format!(
+                "ALTER TABLE {}.{} RENAME TO {}",
+                self.quote_identifier(&entry.original_schema),
+                self.quote_identifier(&entry.original_name),
+                self.quote_identifier(&entry.trash_name)
+            ),
+            format!(
+                "ALTER TABLE {}.{} SET SCHEMA {}",
+                self.quote_identifier(&entry.original_schema),
+                self.quote_identifier(&entry.trash_name),
+                self.quote_identifier(TRASH_SCHEMA)
+            ),
+            self.build_insert_trash_index_sql(entry),
+        ]
+    }
+
+    fn build_restore_from_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        vec![
+            format!(
+                "ALTER TABLE {}.{} SET SCHEMA {}",
+                self.quote_identifier(TRASH_SCHEMA),
+                self.quote_identifier(&entry.trash_name),
+                self.quote_identifier(&entry.original_schema)
+            ),
+            format!(
+                "ALTER TABLE {}.{} RENAME TO {}",
+                self.quote_identifier(&entry.original_schema),
+                self.quote_identifier(&entry.trash_name),
+                self.quote_identifier(&entry.original_name)
+            ),
+            self.build_delete_trash_index_sql(entry),
+        ]
+    }
+
     fn build_column_def(&self, col: &ColumnDefinition) -> String {
         let mut def = String::new();
         def.push_str(&self.quote_identifier(&col.name));
@@ -1087,6 +1264,87 @@ impl DatabasePlugin for PostgresPlugin {
         sql
     }
 
+    fn activity_counter_sql(&self) -> Option<&'static str> {
+        // PostgreSQL does not count statements; committed and rolled back transactions are the closest measure
+        Some("SELECT sum(xact_commit + xact_rollback) FROM pg_stat_database")
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        query_server_status(
+            connection,
+            "SELECT 'PostgreSQL ' || current_setting('server_version'), \
+             EXTRACT(EPOCH FROM now() - pg_postmaster_start_time()), \
+             (SELECT count(*) FROM pg_stat_activity WHERE backend_type = 'client backend')",
+        ).await
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        // Only user and superuser settings can be changed with SET; the rest need a reload or restart
+        Some(
+            "SELECT name, setting, short_desc, context IN ('user', 'superuser') \
+             FROM pg_settings \
+             ORDER BY name"
+                .to_string(),
+        )
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        Some(format!("SET {} TO {}", name, session_variable_literal(self, value)))
+    }
+
+    fn insert_conflict_clause(&self, mode: SqlConflictMode, columns: &[String], key_columns: &[String]) -> Option<InsertConflictClause> {
+        on_conflict_clause(self, mode, columns, key_columns)
+    }
+
+    /// In replica mode foreign key triggers don't fire; changing it requires superuser
+    fn foreign_key_checks_sql(&self, enabled: bool) -> Option<String> {
+        Some(format!("SET session_replication_role = {}", if enabled { "DEFAULT" } else { "replica" }))
+    }
+
+    fn supports_extensions(&self) -> bool {
+        true
+    }
+
+    fn build_list_extensions_sql(&self) -> Option<String> {
+        Some(
+            "SELECT a.name, a.default_version, e.extversion, n.nspname, a.comment \
+             FROM pg_available_extensions a \
+             LEFT JOIN pg_extension e ON e.extname = a.name \
+             LEFT JOIN pg_namespace n ON n.oid = e.extnamespace \
+             ORDER BY (e.extname IS NULL), a.name"
+                .to_string(),
+        )
+    }
+
+    fn build_list_extension_types_sql(&self) -> Option<String> {
+        // 只取基础类型和枚举等可直接用作列类型的类型，排除数组和表的行类型
+        Some(
+            "SELECT DISTINCT t.typname, e.extname \
+             FROM pg_type t \
+             JOIN pg_depend d ON d.classid = 'pg_type'::regclass AND d.objid = t.oid AND d.deptype = 'e' \
+             JOIN pg_extension e ON d.refclassid = 'pg_extension'::regclass AND d.refobjid = e.oid \
+             WHERE t.typtype IN ('b', 'd', 'e', 'r') AND t.typelem = 0 \
+             ORDER BY t.typname"
+                .to_string(),
+        )
+    }
+
+    fn build_create_extension_sql(&self, name: &str, schema: Option<&str>) -> Option<String> {
+        let mut sql = format!("CREATE EXTENSION IF NOT EXISTS {}", self.quote_identifier(name));
+        if let Some(schema) = schema.filter(|s| !s.is_empty()) {
+            sql.push_str(&format!(" SCHEMA {}", self.quote_identifier(schema)));
+        }
+        Some(sql)
+    }
+
+    fn build_drop_extension_sql(&self, name: &str, cascade: bool) -> Option<String> {
+        let mut sql = format!("DROP EXTENSION IF EXISTS {}", self.quote_identifier(name));
+        if cascade {
+            sql.push_str(" CASCADE");
+        }
+        Some(sql)
+    }
+
     fn build_limit_clause(&self) -> String {
         " LIMIT 1".to_string()
     }
@@ -1670,5 +1928,74 @@ mod tests {
         assert!(info.keywords.iter().any(|(k, _)| *k == "RETURNING"));
         assert!(info.functions.iter().any(|(f, _)| f.starts_with("ARRAY_AGG")));
     }
-}
 
+    // ==================== Trash Tests ====================
+
+    #[test]
+    fn test_move_to_trash_and_restore() {
+        let plugin = create_plugin();
+        let entry = TrashEntry {
+            trash_name: "1700000000_users".to_string(),
+            original_schema: "public".to_string(),
+            original_name: "users".to_string(),
+            dropped_at: 1700000000,
+        };
+
+        let sql = plugin.build_move_to_trash_sql(&entry);
+        assert_eq!(sql[0], "ALTER TABLE \"public\".\"users\" RENAME TO \"1700000000_users\"");
+        assert_eq!(sql[1], "ALTER TABLE \"public\".\"1700000000_users\" SET SCHEMA \"_onehub_trash\"");
+
+        let sql = plugin.build_restore_from_trash_sql(&entry);
+        assert_eq!(sql[0], "ALTER TABLE \"_onehub_trash\".\"1700000000_users\" SET SCHEMA \"public\"");
+        assert_eq!(sql[1], "ALTER TABLE \"public\".\"1700000000_users\" RENAME TO \"users\"");
+    }
+
+    #[test]
+    fn test_extension_sql() {
+        let plugin = create_plugin();
+        assert!(plugin.supports_extensions());
+        assert_eq!(
+            plugin.build_create_extension_sql("pg_trgm", Some("ext")).unwrap(),
+            "CREATE EXTENSION IF NOT EXISTS \"pg_trgm\" SCHEMA \"ext\""
+        );
+        assert_eq!(
+            plugin.build_create_extension_sql("hstore", None).unwrap(),
+            "CREATE EXTENSION IF NOT EXISTS \"hstore\""
+        );
+        assert_eq!(
+            plugin.build_drop_extension_sql("postgis", true).unwrap(),
+            "DROP EXTENSION IF EXISTS \"postgis\" CASCADE"
+        );
+    }
+
+    // ==================== Materialized View Tests ====================
+
+    #[test]
+    fn test_refresh_materialized_view_sql() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.build_refresh_materialized_view_sql(Some("sales"), "daily_totals", false).unwrap(),
+            "REFRESH MATERIALIZED VIEW \"sales\".\"daily_totals\""
+        );
+        assert_eq!(
+            plugin.build_refresh_materialized_view_sql(None, "daily_totals", true).unwrap(),
+            "REFRESH MATERIALIZED VIEW CONCURRENTLY \"public\".\"daily_totals\""
+        );
+    }
+
+    #[test]
+    fn test_materialized_view_script() {
+        let plugin = create_plugin();
+        let script = plugin.materialized_view_script(
+            "sales",
+            "daily_totals",
+            " SELECT day, sum(amount) AS total\n   FROM orders\n  GROUP BY day;",
+            &["CREATE UNIQUE INDEX daily_totals_day ON sales.daily_totals USING btree (day)".to_string()],
+        );
+        assert_eq!(
+            script,
+            "CREATE MATERIALIZED VIEW \"sales\".\"daily_totals\" AS\nSELECT day, sum(amount) AS total\n   FROM orders\n  GROUP BY day;\n\
+             CREATE UNIQUE INDEX daily_totals_day ON sales.daily_totals USING btree (day);\n"
+        );
+    }
+}
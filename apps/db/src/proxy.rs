@@ -0,0 +1,324 @@
+//! Routing database connections through a SOCKS5 or HTTP CONNECT proxy.
+//!
+//! SQL Server takes a ready stream, so it dials through `tunnel` directly. The other drivers
+//! only accept a host and port; `ProxyForward` listens on a local port for them and tunnels
+//! every accepted connection through the proxy. HTTP based drivers use reqwest's own proxy support.
+
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use one_core::storage::{DbConnectionConfig, ProxyConfig, ProxyType};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::connection::DbError;
+
+/// Upper bound of the proxy's CONNECT response head
+const MAX_RESPONSE_HEAD: usize = 8 * 1024;
+
+/// Open a TCP connection to `host:port` through the proxy
+pub async fn tunnel(proxy: &ProxyConfig, host: &str, port: u16) -> Result<TcpStream, DbError> {
+    let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port))
+        .await
+        .map_err(|e| proxy_error(proxy, format!("cannot reach the proxy: {}", e)))?;
+    let handshake = match proxy.kind {
+        ProxyType::Socks5 => socks5_handshake(&mut stream, proxy, host, port).await,
+        ProxyType::Http => http_connect(&mut stream, proxy, host, port).await,
+        ProxyType::None => Ok(()),
+    };
+    handshake.map_err(|e| proxy_error(proxy, e))?;
+    Ok(stream)
+}
+
+fn proxy_error(proxy: &ProxyConfig, message: impl Display) -> DbError {
+    DbError::ConnectionError(format!("Proxy {}:{}: {}", proxy.host, proxy.port, message))
+}
+
+/// Drivers behind `ProxyForward` see 127.0.0.1 instead of the database host
+pub fn hostname_check_unsupported() -> DbError {
+    DbError::ConnectionError(
+        "Certificate host name checks cannot be used through a proxy for this database, choose a weaker SSL mode".to_string(),
+    )
+}
+
+/// reqwest dials the proxy itself; `socks5h` lets the proxy resolve the database host
+pub fn http_client_builder(config: &DbConnectionConfig, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, DbError> {
+    let Some(proxy) = config.proxy() else {
+        return Ok(builder);
+    };
+    let scheme = match proxy.kind {
+        ProxyType::Socks5 => "socks5h",
+        ProxyType::Http | ProxyType::None => "http",
+    };
+    let mut url = reqwest::Url::parse(&format!("{}://{}:{}", scheme, proxy.host, proxy.port))
+        .map_err(|e| proxy_error(&proxy, e))?;
+    if let Some(username) = &proxy.username {
+        let _ = url.set_username(username);
+        let _ = url.set_password(proxy.password.as_deref());
+    }
+    let http_proxy = reqwest::Proxy::all(url).map_err(|e| proxy_error(&proxy, e))?;
+    Ok(builder.proxy(http_proxy))
+}
+
+/// Local listener that tunnels each accepted connection through the proxy; it stops when dropped
+pub struct ProxyForward {
+    local_port: u16,
+    /// The driver only sees a closed socket when a tunnel fails, so the reason is kept here
+    last_error: Arc<Mutex<Option<String>>>,
+    task: JoinHandle<()>,
+}
+
+impl ProxyForward {
+    /// None when the connection has no proxy configured
+    pub async fn start(config: &DbConnectionConfig) -> Result<Option<Self>, DbError> {
+        let Some(proxy) = config.proxy() else {
+            return Ok(None);
+        };
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| DbError::ConnectionError(format!("Failed to open local proxy port: {}", e)))?;
+        let local_port = listener
+            .local_addr()
+            .map_err(|e| DbError::ConnectionError(format!("Failed to open local proxy port: {}", e)))?
+            .port();
+
+        let last_error = Arc::new(Mutex::new(None));
+        let task_error = last_error.clone();
+        let (host, port) = (config.host.clone(), config.port);
+        let task = tokio::spawn(async move {
+            while let Ok((mut inbound, _)) = listener.accept().await {
+                let (proxy, host, last_error) = (proxy.clone(), host.clone(), task_error.clone());
+                tokio::spawn(async move {
+                    match tunnel(&proxy, &host, port).await {
+                        Ok(mut outbound) => {
+                            let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                        }
+                        Err(e) => {
+                            tracing::warn!("{}", e);
+                            if let Ok(mut last_error) = last_error.lock() {
+                                *last_error = Some(e.to_string());
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(Some(Self { local_port, last_error, task }))
+    }
+
+    pub fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    /// Prefer the tunnel's failure over the driver's error, which only reports a dropped connection
+    pub fn connect_error(forward: Option<&ProxyForward>, driver_error: DbError) -> DbError {
+        forward
+            .and_then(|forward| forward.last_error.lock().ok()?.take())
+            .map(|message| DbError::ConnectionError(format!("Failed to connect: {}", message)))
+            .unwrap_or(driver_error)
+    }
+}
+
+impl Drop for ProxyForward {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn socks5_handshake(stream: &mut TcpStream, proxy: &ProxyConfig, host: &str, port: u16) -> Result<(), String> {
+    let credentials = proxy.username.as_deref().map(|username| (username, proxy.password.as_deref().unwrap_or("")));
+    write(stream, &socks5_greeting(credentials.is_some())).await?;
+
+    let mut choice = [0u8; 2];
+    read(stream, &mut choice).await?;
+    match choice {
+        [0x05, 0x00] => {}
+        [0x05, 0x02] => {
+            let (username, password) = credentials.ok_or("the proxy asks for a user name and password")?;
+            write(stream, &socks5_auth_request(username, password)?).await?;
+            let mut status = [0u8; 2];
+            read(stream, &mut status).await?;
+            if status[1] != 0x00 {
+                return Err("authentication rejected".to_string());
+            }
+        }
+        [0x05, _] => return Err("no acceptable authentication method".to_string()),
+        _ => return Err("not a SOCKS5 proxy".to_string()),
+    }
+
+    write(stream, &socks5_connect_request(host, port)?).await?;
+    let mut reply = [0u8; 4];
+    read(stream, &mut reply).await?;
+    if reply[1] != 0x00 {
+        return Err(socks5_reply_error(reply[1]).to_string());
+    }
+    // The bound address that follows is not needed, but must be consumed before the database talks
+    let address_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0u8; 1];
+            read(stream, &mut len).await?;
+            len[0] as usize
+        }
+        other => return Err(format!("unexpected address type {} in reply", other)),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    read(stream, &mut bound).await
+}
+
+async fn http_connect(stream: &mut TcpStream, proxy: &ProxyConfig, host: &str, port: u16) -> Result<(), String> {
+    let credentials = proxy.username.as_deref().map(|username| (username, proxy.password.as_deref().unwrap_or("")));
+    write(stream, http_connect_request(host, port, credentials).as_bytes()).await?;
+
+    // Read byte by byte: anything after the head already belongs to the database protocol
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() >= MAX_RESPONSE_HEAD {
+            return Err("response head too large".to_string());
+        }
+        let mut byte = [0u8; 1];
+        read(stream, &mut byte).await?;
+        head.push(byte[0]);
+    }
+    check_connect_response(&String::from_utf8_lossy(&head))
+}
+
+async fn write(stream: &mut TcpStream, bytes: &[u8]) -> Result<(), String> {
+    stream.write_all(bytes).await.map_err(|e| e.to_string())
+}
+
+async fn read(stream: &mut TcpStream, buffer: &mut [u8]) -> Result<(), String> {
+    stream
+        .read_exact(buffer)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("connection closed during handshake: {}", e))
+}
+
+fn socks5_greeting(with_credentials: bool) -> Vec<u8> {
+    if with_credentials {
+        vec![0x05, 0x02, 0x00, 0x02]
+    } else {
+        vec![0x05, 0x01, 0x00]
+    }
+}
+
+/// RFC 1929 user name and password sub-negotiation
+fn socks5_auth_request(username: &str, password: &str) -> Result<Vec<u8>, String> {
+    let (username_len, password_len) = match (u8::try_from(username.len()), u8::try_from(password.len())) {
+        (Ok(username_len), Ok(password_len)) => (username_len, password_len),
+        _ => return Err("user name and password must be at most 255 bytes".to_string()),
+    };
+    let mut request = vec![0x01, username_len];
+    request.extend_from_slice(username.as_bytes());
+    request.push(password_len);
+    request.extend_from_slice(password.as_bytes());
+    Ok(request)
+}
+
+/// Host names are sent as-is so the proxy resolves them, which is what makes internal names reachable
+fn socks5_connect_request(host: &str, port: u16) -> Result<Vec<u8>, String> {
+    let mut request = vec![0x05, 0x01, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(address)) => {
+            request.push(0x01);
+            request.extend_from_slice(&address.octets());
+        }
+        Ok(IpAddr::V6(address)) => {
+            request.push(0x04);
+            request.extend_from_slice(&address.octets());
+        }
+        Err(_) => {
+            let len = u8::try_from(host.len()).map_err(|_| "host name must be at most 255 bytes".to_string())?;
+            request.push(0x03);
+            request.push(len);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    Ok(request)
+}
+
+fn socks5_reply_error(code: u8) -> &'static str {
+    match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused by the database host",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown SOCKS error",
+    }
+}
+
+fn http_connect_request(host: &str, port: u16, credentials: Option<(&str, &str)>) -> String {
+    let authority = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V6(_)) => format!("[{}]:{}", host, port),
+        _ => format!("{}:{}", host, port),
+    };
+    let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", authority);
+    if let Some((username, password)) = credentials {
+        let token = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    request
+}
+
+/// Any 2xx status opens the tunnel
+fn check_connect_response(head: &str) -> Result<(), String> {
+    let status_line = head.lines().next().unwrap_or_default();
+    let mut parts = status_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some(version), Some(code)) if version.starts_with("HTTP/") && code.starts_with('2') => Ok(()),
+        (Some(version), Some("407")) if version.starts_with("HTTP/") => {
+            Err("proxy authentication required, check the proxy user name and password".to_string())
+        }
+        (Some(version), Some(_)) if version.starts_with("HTTP/") => Err(format!("CONNECT refused: {}", status_line)),
+        _ => Err("not an HTTP proxy".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socks5_requests() {
+        assert_eq!(socks5_greeting(false), vec![0x05, 0x01, 0x00]);
+        assert_eq!(socks5_auth_request("app", "pw").ok(), Some(vec![0x01, 3, b'a', b'p', b'p', 2, b'p', b'w']));
+        assert!(socks5_auth_request(&"u".repeat(256), "pw").is_err());
+
+        assert_eq!(
+            socks5_connect_request("db.internal", 3306).ok(),
+            Some([&[0x05, 0x01, 0x00, 0x03, 11][..], b"db.internal", &[0x0c, 0xea]].concat())
+        );
+        assert_eq!(
+            socks5_connect_request("10.0.0.5", 5432).ok(),
+            Some(vec![0x05, 0x01, 0x00, 0x01, 10, 0, 0, 5, 0x15, 0x38])
+        );
+        assert_eq!(socks5_connect_request("::1", 1433).ok().map(|request| request[3]), Some(0x04));
+    }
+
+    #[test]
+    fn test_http_connect() {
+        assert_eq!(
+            http_connect_request("db.internal", 5432, Some(("app", "secret"))),
+            "CONNECT db.internal:5432 HTTP/1.1\r\nHost: db.internal:5432\r\nProxy-Authorization: Basic YXBwOnNlY3JldA==\r\n\r\n"
+        );
+        assert!(http_connect_request("fe80::1", 3306, None).starts_with("CONNECT [fe80::1]:3306 "));
+
+        assert!(check_connect_response("HTTP/1.1 200 Connection established\r\n\r\n").is_ok());
+        assert!(check_connect_response("HTTP/1.0 407 Proxy Authentication Required\r\n\r\n")
+            .is_err_and(|e| e.contains("authentication")));
+        assert!(check_connect_response("HTTP/1.1 403 Forbidden\r\n\r\n").is_err_and(|e| e.contains("403 Forbidden")));
+        assert!(check_connect_response("SSH-2.0-OpenSSH_9.6\r\n\r\n").is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::Local;
+use tokio::sync::watch;
+
+use crate::server_status::LastError;
+
+/// Sessions idle longer than this are pinged before they are handed out again,
+/// so a connection dropped while idle is replaced instead of failing the next query
+pub const HEALTH_CHECK_IDLE: Duration = Duration::from_secs(15);
+
+/// A ping taking longer than this counts as a dropped connection
+pub const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connection attempts made when a previously working connection has dropped
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 4;
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(8);
+
+/// Health of a connection as shown on its tree node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    Connected,
+    /// The connection dropped; `attempt` failed attempts so far, another one is scheduled
+    Reconnecting { attempt: u32 },
+    Failed(String),
+}
+
+/// Wait before the next attempt after `attempt` (1-based) failed attempts: 0.5s, 1s, 2s, ... capped at 8s
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let doublings = attempt.saturating_sub(1).min(16);
+    BASE_DELAY.saturating_mul(1 << doublings).min(MAX_DELAY)
+}
+
+/// Status of every connection that has been used, keyed by connection id.
+/// Views subscribe to repaint their indicators when a status changes.
+#[derive(Clone)]
+pub struct ConnectionStatuses {
+    sender: Arc<watch::Sender<HashMap<String, ConnectionStatus>>>,
+    /// Kept after the connection recovers, for the health dashboard
+    last_errors: Arc<Mutex<HashMap<String, LastError>>>,
+}
+
+impl ConnectionStatuses {
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(HashMap::new())),
+            last_errors: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn get(&self, connection_id: &str) -> Option<ConnectionStatus> {
+        self.sender.borrow().get(connection_id).cloned()
+    }
+
+    pub fn set(&self, connection_id: &str, status: ConnectionStatus) {
+        if let ConnectionStatus::Failed(message) = &status {
+            self.record_error(connection_id, message.clone());
+        }
+        self.sender.send_if_modified(|statuses| {
+            if statuses.get(connection_id) == Some(&status) {
+                return false;
+            }
+            statuses.insert(connection_id.to_string(), status);
+            true
+        });
+    }
+
+    pub fn remove(&self, connection_id: &str) {
+        self.sender.send_if_modified(|statuses| statuses.remove(connection_id).is_some());
+        if let Ok(mut last_errors) = self.last_errors.lock() {
+            last_errors.remove(connection_id);
+        }
+    }
+
+    /// Snapshot of all statuses
+    pub fn all(&self) -> HashMap<String, ConnectionStatus> {
+        self.sender.borrow().clone()
+    }
+
+    /// Remember an error that did not necessarily fail the connection, such as a dropped session
+    pub fn record_error(&self, connection_id: &str, message: String) {
+        if let Ok(mut last_errors) = self.last_errors.lock() {
+            last_errors.insert(connection_id.to_string(), LastError { message, at: Local::now() });
+        }
+    }
+
+    pub fn last_error(&self, connection_id: &str) -> Option<LastError> {
+        self.last_errors.lock().ok()?.get(connection_id).cloned()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<HashMap<String, ConnectionStatus>> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for ConnectionStatuses {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let delays: Vec<u64> = (1..=6).map(|attempt| backoff_delay(attempt).as_millis() as u64).collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 4000, 8000, 8000]);
+        assert_eq!(backoff_delay(u32::MAX), MAX_DELAY);
+    }
+
+    #[test]
+    fn test_unchanged_status_does_not_notify() {
+        let statuses = ConnectionStatuses::new();
+        let mut receiver = statuses.subscribe();
+        statuses.set("1", ConnectionStatus::Connected);
+        assert!(receiver.has_changed().unwrap());
+        receiver.mark_unchanged();
+
+        statuses.set("1", ConnectionStatus::Connected);
+        assert!(!receiver.has_changed().unwrap());
+        statuses.remove("1");
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(statuses.get("1"), None);
+    }
+
+    #[test]
+    fn test_last_error_outlives_recovery() {
+        let statuses = ConnectionStatuses::new();
+        statuses.set("1", ConnectionStatus::Failed("connection refused".to_string()));
+        statuses.set("1", ConnectionStatus::Connected);
+        assert_eq!(statuses.last_error("1").map(|e| e.message).as_deref(), Some("connection refused"));
+
+        statuses.remove("1");
+        assert_eq!(statuses.last_error("1"), None);
+    }
+}
@@ -0,0 +1,6 @@
+pub mod command;
+pub mod connection;
+pub mod plugin;
+
+pub use connection::RedisConnection;
+pub use plugin::RedisPlugin;
@@ -0,0 +1,408 @@
+//! Parsing of redis-cli style command lines and the commands the key browser sends
+
+use anyhow::{anyhow, Result};
+
+/// Commands that never modify data; used to tell queries from writes in the console
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "GET", "MGET", "GETRANGE", "STRLEN", "EXISTS", "TYPE", "TTL", "PTTL", "EXPIRETIME", "KEYS", "SCAN",
+    "RANDOMKEY", "DBSIZE", "HGET", "HMGET", "HGETALL", "HKEYS", "HVALS", "HLEN", "HEXISTS", "HSTRLEN",
+    "HSCAN", "LRANGE", "LINDEX", "LLEN", "LPOS", "SMEMBERS", "SISMEMBER", "SMISMEMBER", "SCARD",
+    "SRANDMEMBER", "SSCAN", "SINTER", "SUNION", "SDIFF", "ZRANGE", "ZREVRANGE", "ZRANGEBYSCORE",
+    "ZREVRANGEBYSCORE", "ZRANGEBYLEX", "ZSCORE", "ZMSCORE", "ZRANK", "ZREVRANK", "ZCARD", "ZCOUNT",
+    "ZSCAN", "XRANGE", "XREVRANGE", "XLEN", "XINFO", "GETBIT", "BITCOUNT", "PFCOUNT", "GEOPOS", "GEODIST",
+    "OBJECT", "MEMORY", "INFO", "PING", "ECHO", "TIME", "LASTSAVE", "SLOWLOG",
+    "COMMAND", "DUMP",
+];
+
+/// Commands that never return and would block the console session
+const BLOCKING_COMMANDS: &[&str] = &["SUBSCRIBE", "PSUBSCRIBE", "SSUBSCRIBE", "MONITOR", "SYNC", "PSYNC"];
+
+/// Commands that remove whole databases or the server's data; classified like DDL so they need confirmation
+const DESTRUCTIVE_COMMANDS: &[&str] = &["FLUSHDB", "FLUSHALL", "SHUTDOWN", "DEBUG", "SWAPDB"];
+
+/// Split a console script into commands: one per line, `#` and `//` lines are comments
+pub fn split_redis_commands(script: &str) -> Vec<String> {
+    script
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+        .map(|line| line.trim_end_matches(';').trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Split a command line into arguments the way redis-cli does: double quotes support
+/// `\n`, `\t`, `\"`, `\\` and `\xHH` escapes, single quotes are literal except `\'`
+pub fn tokenize_command(line: &str) -> Result<Vec<String>> {
+    let mut arguments = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut argument = String::new();
+        match c {
+            '"' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => argument.push('\n'),
+                            Some('r') => argument.push('\r'),
+                            Some('t') => argument.push('\t'),
+                            Some('x') => {
+                                let hex: String = chars.by_ref().take(2).collect();
+                                let byte = u8::from_str_radix(&hex, 16)
+                                    .map_err(|_| anyhow!("无效的转义序列 \\x{}", hex))?;
+                                argument.push(byte as char);
+                            }
+                            Some(other) => argument.push(other),
+                            None => return Err(anyhow!("引号未闭合")),
+                        },
+                        Some(other) => argument.push(other),
+                        None => return Err(anyhow!("引号未闭合")),
+                    }
+                }
+            }
+            '\'' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            argument.push('\'');
+                        }
+                        Some(other) => argument.push(other),
+                        None => return Err(anyhow!("引号未闭合")),
+                    }
+                }
+            }
+            _ => {
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() {
+                        break;
+                    }
+                    argument.push(c);
+                    chars.next();
+                }
+            }
+        }
+
+        // A closing quote must be followed by a space, as in redis-cli
+        if chars.peek().is_some_and(|c| !c.is_whitespace()) && matches!(c, '"' | '\'') {
+            return Err(anyhow!("引号后缺少空格"));
+        }
+        arguments.push(argument);
+    }
+
+    if arguments.is_empty() {
+        return Err(anyhow!("命令为空"));
+    }
+    Ok(arguments)
+}
+
+/// Quote an argument so `tokenize_command` reads it back unchanged
+pub fn quote_argument(argument: &str) -> String {
+    let is_bare = !argument.is_empty()
+        && !argument.starts_with('\'')
+        && argument.chars().all(|c| !c.is_whitespace() && c != '"' && !c.is_control());
+    if is_bare {
+        return argument.to_string();
+    }
+
+    let mut quoted = String::with_capacity(argument.len() + 2);
+    quoted.push('"');
+    for c in argument.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Build a command line from its arguments
+pub fn format_command(arguments: &[&str]) -> String {
+    arguments.iter().map(|argument| quote_argument(argument)).collect::<Vec<_>>().join(" ")
+}
+
+fn command_name(line: &str) -> Option<String> {
+    tokenize_command(line).ok().and_then(|arguments| arguments.first().map(|name| name.to_uppercase()))
+}
+
+pub fn is_read_only_command(line: &str) -> bool {
+    command_name(line).is_some_and(|name| READ_ONLY_COMMANDS.contains(&name.as_str()))
+}
+
+pub fn is_blocking_command(name: &str) -> bool {
+    BLOCKING_COMMANDS.contains(&name.to_uppercase().as_str())
+}
+
+pub fn is_destructive_command(line: &str) -> bool {
+    command_name(line).is_some_and(|name| DESTRUCTIVE_COMMANDS.contains(&name.as_str()))
+}
+
+/// Tree and console names of a logical database are `db0`, `db1`, ...
+pub fn database_name(index: i64) -> String {
+    format!("db{}", index)
+}
+
+/// Index of a logical database, accepting both `db3` and `3`
+pub fn database_index(name: &str) -> Result<i64> {
+    let name = name.trim();
+    name.strip_prefix("db")
+        .unwrap_or(name)
+        .parse::<i64>()
+        .map_err(|_| anyhow!("无效的 Redis 数据库: {}", name))
+}
+
+/// Value types the key browser can show and edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedisKeyType {
+    String,
+    Hash,
+    List,
+    Set,
+    ZSet,
+    Stream,
+    /// The key does not exist (any more)
+    None,
+}
+
+impl RedisKeyType {
+    /// Parse the reply of `TYPE`
+    pub fn from_type_reply(reply: &str) -> Self {
+        match reply.trim().to_lowercase().as_str() {
+            "string" => Self::String,
+            "hash" => Self::Hash,
+            "list" => Self::List,
+            "set" => Self::Set,
+            "zset" => Self::ZSet,
+            "stream" => Self::Stream,
+            _ => Self::None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Hash => "hash",
+            Self::List => "list",
+            Self::Set => "set",
+            Self::ZSet => "zset",
+            Self::Stream => "stream",
+            Self::None => "none",
+        }
+    }
+
+    /// Command reading up to `limit` elements of the value
+    pub fn read_command(&self, key: &str, limit: usize) -> Option<String> {
+        let last = limit.saturating_sub(1).to_string();
+        let count = limit.to_string();
+        match self {
+            Self::String => Some(format_command(&["GET", key])),
+            Self::Hash => Some(format_command(&["HSCAN", key, "0", "COUNT", &count])),
+            Self::List => Some(format_command(&["LRANGE", key, "0", &last])),
+            Self::Set => Some(format_command(&["SSCAN", key, "0", "COUNT", &count])),
+            Self::ZSet => Some(format_command(&["ZRANGE", key, "0", &last, "WITHSCORES"])),
+            Self::Stream => Some(format_command(&["XRANGE", key, "-", "+", "COUNT", &count])),
+            Self::None => None,
+        }
+    }
+
+    /// Command storing one element: the hash field, list index, set member or zset member.
+    /// `value` is the string content, field value or zset score; sets ignore it.
+    pub fn set_entry_command(&self, key: &str, entry: &str, value: &str) -> Option<String> {
+        match self {
+            Self::String => Some(format_command(&["SET", key, value, "KEEPTTL"])),
+            Self::Hash => Some(format_command(&["HSET", key, entry, value])),
+            Self::List if entry.is_empty() => Some(format_command(&["RPUSH", key, value])),
+            Self::List => Some(format_command(&["LSET", key, entry, value])),
+            Self::Set => Some(format_command(&["SADD", key, entry])),
+            Self::ZSet => Some(format_command(&["ZADD", key, value, entry])),
+            Self::Stream | Self::None => None,
+        }
+    }
+
+    /// Command removing one element; lists remove the first element equal to `value`
+    pub fn remove_entry_command(&self, key: &str, entry: &str, value: &str) -> Option<String> {
+        match self {
+            Self::Hash => Some(format_command(&["HDEL", key, entry])),
+            Self::List => Some(format_command(&["LREM", key, "1", value])),
+            Self::Set => Some(format_command(&["SREM", key, entry])),
+            Self::ZSet => Some(format_command(&["ZREM", key, entry])),
+            Self::Stream => Some(format_command(&["XDEL", key, entry])),
+            Self::String | Self::None => None,
+        }
+    }
+}
+
+/// Keys grouped by namespace: `user:1:name` lives in `user` → `1`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyNamespace {
+    /// Namespace path including the trailing separator, e.g. `user:1:`
+    pub prefix: String,
+    pub name: String,
+    pub namespaces: Vec<KeyNamespace>,
+    pub keys: Vec<String>,
+}
+
+impl KeyNamespace {
+    /// Number of keys in this namespace and all nested ones
+    pub fn key_count(&self) -> usize {
+        self.keys.len() + self.namespaces.iter().map(KeyNamespace::key_count).sum::<usize>()
+    }
+
+    /// The nested namespace with the given prefix; the empty prefix is this namespace
+    pub fn find(&self, prefix: &str) -> Option<&KeyNamespace> {
+        if self.prefix == prefix {
+            return Some(self);
+        }
+        self.namespaces
+            .iter()
+            .filter(|namespace| prefix.starts_with(&namespace.prefix))
+            .find_map(|namespace| namespace.find(prefix))
+    }
+}
+
+/// Escape glob characters so a key prefix matches literally in `SCAN MATCH`
+pub fn escape_pattern(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Group keys into nested namespaces by `separator`; namespaces and keys come out sorted
+pub fn group_keys(keys: &[String], separator: &str) -> KeyNamespace {
+    fn insert(namespace: &mut KeyNamespace, key: &str, rest: &str, separator: &str) {
+        match rest.split_once(separator).filter(|(head, tail)| !head.is_empty() && !tail.is_empty()) {
+            Some((head, tail)) => {
+                let position = match namespace.namespaces.iter().position(|child| child.name == head) {
+                    Some(position) => position,
+                    None => {
+                        namespace.namespaces.push(KeyNamespace {
+                            prefix: format!("{}{}{}", namespace.prefix, head, separator),
+                            name: head.to_string(),
+                            ..Default::default()
+                        });
+                        namespace.namespaces.len() - 1
+                    }
+                };
+                insert(&mut namespace.namespaces[position], key, tail, separator);
+            }
+            None => namespace.keys.push(key.to_string()),
+        }
+    }
+
+    fn sort(namespace: &mut KeyNamespace) {
+        namespace.namespaces.sort_by(|a, b| a.name.cmp(&b.name));
+        namespace.keys.sort();
+        namespace.namespaces.iter_mut().for_each(sort);
+    }
+
+    let mut root = KeyNamespace::default();
+    for key in keys {
+        if separator.is_empty() {
+            root.keys.push(key.clone());
+        } else {
+            insert(&mut root, key, key, separator);
+        }
+    }
+    sort(&mut root);
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_command() {
+        assert_eq!(tokenize_command("set  name alice").unwrap(), vec!["set", "name", "alice"]);
+        assert_eq!(
+            tokenize_command(r#"SET "user:1" "a \"b\"\n\x41" 'it\'s'"#).unwrap(),
+            vec!["SET", "user:1", "a \"b\"\nA", "it's"]
+        );
+        assert_eq!(tokenize_command(r#"SET k """#).unwrap(), vec!["SET", "k", ""]);
+        assert!(tokenize_command("GET \"open").is_err());
+        assert!(tokenize_command("GET \"a\"b").is_err());
+        assert!(tokenize_command("   ").is_err());
+    }
+
+    #[test]
+    fn test_quote_argument_round_trip() {
+        for argument in ["plain", "with space", "quote\"d", "back\\slash", "line\nbreak", "", "'single"] {
+            let line = format!("SET {}", quote_argument(argument));
+            assert_eq!(tokenize_command(&line).unwrap()[1], argument);
+        }
+        assert_eq!(quote_argument("user:1"), "user:1");
+    }
+
+    #[test]
+    fn test_split_and_classify() {
+        let script = "# comment\nGET a\n\n// another\nset b 1;\n";
+        assert_eq!(split_redis_commands(script), vec!["GET a", "set b 1"]);
+        assert!(is_read_only_command("hgetall user:1"));
+        assert!(!is_read_only_command("HSET user:1 name bob"));
+        assert!(is_destructive_command("flushdb"));
+        assert!(is_blocking_command("subscribe"));
+    }
+
+    #[test]
+    fn test_database_index() {
+        assert_eq!(database_index("db3").unwrap(), 3);
+        assert_eq!(database_index("12").unwrap(), 12);
+        assert!(database_index("main").is_err());
+        assert_eq!(database_name(0), "db0");
+    }
+
+    #[test]
+    fn test_entry_commands() {
+        assert_eq!(
+            RedisKeyType::Hash.set_entry_command("user:1", "full name", "Bob").unwrap(),
+            "HSET user:1 \"full name\" Bob"
+        );
+        assert_eq!(RedisKeyType::List.set_entry_command("q", "", "job").unwrap(), "RPUSH q job");
+        assert_eq!(RedisKeyType::ZSet.set_entry_command("rank", "bob", "1.5").unwrap(), "ZADD rank 1.5 bob");
+        assert_eq!(RedisKeyType::List.remove_entry_command("q", "0", "job").unwrap(), "LREM q 1 job");
+        assert_eq!(RedisKeyType::String.remove_entry_command("k", "", ""), None);
+        assert_eq!(RedisKeyType::List.read_command("q", 100).unwrap(), "LRANGE q 0 99");
+        assert_eq!(RedisKeyType::from_type_reply("zset"), RedisKeyType::ZSet);
+    }
+
+    #[test]
+    fn test_group_keys() {
+        let keys: Vec<String> = ["user:2:name", "user:1:name", "user:1:mail", "config", "user:", ":x"]
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+
+        let root = group_keys(&keys, ":");
+        assert_eq!(root.keys, vec![":x", "config", "user:"]);
+        assert_eq!(root.namespaces.len(), 1);
+        let user = &root.namespaces[0];
+        assert_eq!((user.name.as_str(), user.prefix.as_str()), ("user", "user:"));
+        assert_eq!(user.key_count(), 3);
+        assert_eq!(user.namespaces[0].prefix, "user:1:");
+        assert_eq!(user.namespaces[0].keys, vec!["user:1:mail", "user:1:name"]);
+        assert_eq!(root.find("user:2:").map(|namespace| namespace.keys.clone()), Some(vec!["user:2:name".to_string()]));
+        assert!(root.find("order:").is_none());
+        assert_eq!(escape_pattern("a*b[1]"), "a\\*b\\[1\\]");
+    }
+}
@@ -0,0 +1,599 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use one_core::storage::DbConnectionConfig;
+use redis::aio::MultiplexedConnection;
+use redis::{Client, ClientTlsConfig, ConnectionAddr, ConnectionInfo, IntoConnectionInfo, RedisConnectionInfo, TlsCertificates, Value};
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+use crate::connection::{DbConnection, DbError, StreamingProgress};
+use crate::executor::{ExecOptions, ExecResult, QueryResult, SqlErrorInfo, SqlResult};
+use crate::redis::command::{database_index, database_name, is_blocking_command, is_read_only_command, tokenize_command};
+use crate::proxy::{self, ProxyForward};
+use crate::{tls, DatabasePlugin, SqlValue};
+
+/// Used when the server hides `CONFIG GET databases`, as managed services often do
+const DEFAULT_DATABASE_COUNT: i64 = 16;
+/// Keys fetched per `SCAN` round trip
+const SCAN_BATCH_SIZE: usize = 1000;
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct RedisConnection {
+    config: DbConnectionConfig,
+    connection_info: Option<ConnectionInfo>,
+    tls_certificates: Option<TlsCertificates>,
+    /// Keeps the local proxy port open while connected
+    proxy_forward: Option<ProxyForward>,
+    /// `SELECT` would switch every user of a multiplexed connection, so each logical database gets its own
+    connections: Mutex<HashMap<i64, MultiplexedConnection>>,
+    current_database: RwLock<i64>,
+}
+
+impl RedisConnection {
+    pub fn new(config: DbConnectionConfig) -> Self {
+        let current_database = config
+            .database
+            .as_deref()
+            .and_then(|name| database_index(name).ok())
+            .unwrap_or(0);
+        Self {
+            config,
+            connection_info: None,
+            tls_certificates: None,
+            proxy_forward: None,
+            connections: Mutex::new(HashMap::new()),
+            current_database: RwLock::new(current_database),
+        }
+    }
+
+    /// A `redis://` connection string in the advanced tab wins over the host and credential fields
+    fn build_connection_info(config: &DbConnectionConfig) -> Result<ConnectionInfo, DbError> {
+        if let Some(connection_string) = config.get_param("connection_string").filter(|s| !s.trim().is_empty()) {
+            return connection_string
+                .trim()
+                .into_connection_info()
+                .map_err(|e| DbError::ConnectionError(format!("Invalid connection string: {}", e)));
+        }
+
+        let ssl = config.ssl();
+        let addr = if ssl.is_enabled() {
+            ConnectionAddr::TcpTls {
+                host: config.host.clone(),
+                port: config.port,
+                insecure: !ssl.mode.verifies_certificate(),
+                tls_params: None,
+            }
+        } else {
+            ConnectionAddr::Tcp(config.host.clone(), config.port)
+        };
+
+        Ok(ConnectionInfo {
+            addr,
+            redis: RedisConnectionInfo {
+                username: Some(config.username.clone()).filter(|username| !username.is_empty()),
+                password: Some(config.password.clone()).filter(|password| !password.is_empty()),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Custom CA and client certificates from the SSL tab. rustls cannot skip only the
+    /// host name check, so verify-ca behaves like verify-full
+    fn tls_certificates(config: &DbConnectionConfig) -> Result<Option<TlsCertificates>, DbError> {
+        let ssl = config.ssl();
+        if !ssl.is_enabled() {
+            return Ok(None);
+        }
+        let client_tls = match tls::client_identity(&ssl)? {
+            Some((cert, key)) => Some(ClientTlsConfig {
+                client_cert: tls::read_pem(cert)?,
+                client_key: tls::read_pem(key)?,
+            }),
+            None => None,
+        };
+        let root_cert = ssl.ca_cert.as_deref().map(tls::read_pem).transpose()?;
+        if client_tls.is_none() && root_cert.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(TlsCertificates { client_tls, root_cert }))
+    }
+
+    fn connect_timeout(&self) -> Duration {
+        self.config
+            .get_param_as::<u64>("connect_timeout")
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT)
+    }
+
+    async fn connection(&self, database: i64) -> Result<MultiplexedConnection, DbError> {
+        let mut connections = self.connections.lock().await;
+        if let Some(connection) = connections.get(&database) {
+            return Ok(connection.clone());
+        }
+
+        let mut info = self
+            .connection_info
+            .clone()
+            .ok_or_else(|| DbError::ConnectionError("Not connected to database".to_string()))?;
+        info.redis.db = database;
+        let client = match &self.tls_certificates {
+            Some(certificates) => Client::build_with_tls(info, certificates.clone()),
+            None => Client::open(info),
+        }
+        .map_err(|e| DbError::ConnectionError(e.to_string()))?;
+        let connection = tokio::time::timeout(self.connect_timeout(), client.get_multiplexed_async_connection())
+            .await
+            .map_err(|_| DbError::ConnectionError("Connection timed out".to_string()))?
+            .map_err(|e| tls::connection_error(&self.config.ssl(), e))?;
+
+        connections.insert(database, connection.clone());
+        Ok(connection)
+    }
+
+    async fn run(&self, database: Option<i64>, arguments: &[String]) -> Result<Value, DbError> {
+        let database = match database {
+            Some(database) => database,
+            None => *self.current_database.read().await,
+        };
+        let mut connection = self.connection(database).await?;
+
+        let mut command = redis::cmd(&arguments[0]);
+        for argument in &arguments[1..] {
+            command.arg(argument);
+        }
+        command
+            .query_async::<Value>(&mut connection)
+            .await
+            .map_err(|e| DbError::QueryError(e.to_string()))
+    }
+
+    /// Number of logical databases configured on the server
+    pub async fn database_count(&self) -> i64 {
+        let arguments = ["CONFIG", "GET", "databases"].map(String::from);
+        match self.run(Some(0), &arguments).await {
+            Ok(reply) => value_pairs(&reply)
+                .and_then(|pairs| pairs.into_iter().next())
+                .and_then(|(_, count)| count.and_then(|count| count.parse().ok()))
+                .unwrap_or(DEFAULT_DATABASE_COUNT),
+            Err(_) => DEFAULT_DATABASE_COUNT,
+        }
+    }
+
+    /// Key count of every non-empty database, from `INFO keyspace`
+    pub async fn keyspace(&self) -> Result<HashMap<i64, u64>, DbError> {
+        let arguments = ["INFO", "keyspace"].map(String::from);
+        let reply = self.run(Some(0), &arguments).await?;
+        Ok(parse_keyspace(&value_to_text(&reply).unwrap_or_default()))
+    }
+
+    /// Keys matching `pattern`, stopping after `limit`; the flag tells whether more keys exist
+    pub async fn scan_keys(&self, database: i64, pattern: &str, limit: usize) -> Result<(Vec<String>, bool), DbError> {
+        let mut connection = self.connection(database).await?;
+        let mut keys = Vec::new();
+        let mut cursor: u64 = 0;
+
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH_SIZE)
+                .query_async(&mut connection)
+                .await
+                .map_err(|e| DbError::QueryError(e.to_string()))?;
+            keys.extend(batch);
+
+            if keys.len() >= limit {
+                keys.truncate(limit);
+                return Ok((keys, next != 0));
+            }
+            if next == 0 {
+                return Ok((keys, false));
+            }
+            cursor = next;
+        }
+    }
+
+    async fn execute_single(&self, statement: &str, max_rows: Option<usize>) -> SqlResult {
+        let start = Instant::now();
+        let sql = statement.to_string();
+
+        let arguments = match tokenize_command(statement) {
+            Ok(arguments) => arguments,
+            Err(e) => return SqlResult::Error(SqlErrorInfo { sql, message: e.to_string() }),
+        };
+        let name = arguments[0].to_uppercase();
+
+        if is_blocking_command(&name) {
+            return SqlResult::Error(SqlErrorInfo {
+                sql,
+                message: format!("{} 会一直阻塞会话，控制台不支持", name),
+            });
+        }
+
+        // Every logical database has its own connection, so SELECT only moves the session
+        if name == "SELECT" {
+            let selected = arguments.get(1).ok_or_else(|| "SELECT 缺少数据库编号".to_string())
+                .and_then(|index| database_index(index).map_err(|e| e.to_string()));
+            return match selected {
+                Ok(index) => {
+                    *self.current_database.write().await = index;
+                    SqlResult::Exec(ExecResult {
+                        sql,
+                        rows_affected: 0,
+                        elapsed_ms: start.elapsed().as_millis(),
+                        message: Some(format!("OK ({})", database_name(index))),
+                    })
+                }
+                Err(message) => SqlResult::Error(SqlErrorInfo { sql, message }),
+            };
+        }
+
+        match self.run(None, &arguments).await {
+            Ok(reply) => {
+                let is_scalar = matches!(reply, Value::Nil | Value::Okay | Value::Int(_) | Value::SimpleString(_));
+                if is_scalar && !is_read_only_command(statement) {
+                    SqlResult::Exec(ExecResult {
+                        sql,
+                        rows_affected: match reply {
+                            Value::Int(count) => count.max(0) as u64,
+                            _ => 0,
+                        },
+                        elapsed_ms: start.elapsed().as_millis(),
+                        message: Some(reply_message(&reply)),
+                    })
+                } else {
+                    let mut result = reply_to_result(sql, &name, &reply, start.elapsed().as_millis());
+                    if let Some(max_rows) = max_rows {
+                        result.rows.truncate(max_rows);
+                    }
+                    SqlResult::Query(result)
+                }
+            }
+            Err(e) => SqlResult::Error(SqlErrorInfo { sql, message: e.to_string() }),
+        }
+    }
+
+    async fn execute_statements(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+    ) -> Result<Vec<SqlResult>, DbError> {
+        let mut results = Vec::new();
+
+        for statement in plugin.split_statements(script) {
+            let result = self.execute_single(&statement, options.max_rows).await;
+            let is_error = result.is_error();
+            results.push(result);
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+/// Text of a reply element; arrays and maps are rendered inline
+pub fn value_to_text(value: &Value) -> Option<String> {
+    let join = |values: &[Value]| {
+        values
+            .iter()
+            .map(|value| value_to_text(value).unwrap_or_else(|| "(nil)".to_string()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match value {
+        Value::Nil => None,
+        Value::Okay => Some("OK".to_string()),
+        Value::Int(value) => Some(value.to_string()),
+        Value::Double(value) => Some(value.to_string()),
+        Value::Boolean(value) => Some(value.to_string()),
+        Value::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(text) => Some(text.clone()),
+        Value::VerbatimString { text, .. } => Some(text.clone()),
+        Value::Array(values) | Value::Set(values) => Some(format!("[{}]", join(values))),
+        Value::Map(pairs) => Some(format!(
+            "{{{}}}",
+            pairs
+                .iter()
+                .map(|(key, value)| format!(
+                    "{}: {}",
+                    value_to_text(key).unwrap_or_default(),
+                    value_to_text(value).unwrap_or_else(|| "(nil)".to_string())
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        other => Some(format!("{:?}", other)),
+    }
+}
+
+/// redis-cli style message for a write reply
+fn reply_message(reply: &Value) -> String {
+    match reply {
+        Value::Nil => "(nil)".to_string(),
+        Value::Int(value) => format!("(integer) {}", value),
+        other => value_to_text(other).unwrap_or_default(),
+    }
+}
+
+/// Field/value pairs of a flat `[k1, v1, k2, v2]` array or a RESP3 map
+fn value_pairs(value: &Value) -> Option<Vec<(String, Option<String>)>> {
+    match value {
+        Value::Map(pairs) => Some(
+            pairs
+                .iter()
+                .map(|(key, value)| (value_to_text(key).unwrap_or_default(), value_to_text(value)))
+                .collect(),
+        ),
+        Value::Array(values) if values.len() % 2 == 0 => Some(
+            values
+                .chunks(2)
+                .map(|pair| (value_to_text(&pair[0]).unwrap_or_default(), value_to_text(&pair[1])))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn single_column(values: &[Value]) -> Vec<Vec<Option<String>>> {
+    values.iter().map(|value| vec![value_to_text(value)]).collect()
+}
+
+/// Shape a reply as a result set: commands returning pairs get two columns,
+/// the `*SCAN` family shows the scanned page, everything else one `value` column
+pub fn reply_to_result(sql: String, command: &str, reply: &Value, elapsed_ms: u128) -> QueryResult {
+    let with_scores = sql.to_uppercase().split_whitespace().any(|argument| argument == "WITHSCORES");
+    let pair_columns = match command {
+        "HGETALL" | "HSCAN" | "CONFIG" => Some(("field", "value")),
+        "ZSCAN" => Some(("member", "score")),
+        "ZRANGE" | "ZREVRANGE" | "ZRANGEBYSCORE" | "ZREVRANGEBYSCORE" if with_scores => Some(("member", "score")),
+        _ => None,
+    };
+
+    // SCAN replies are [cursor, [elements]]
+    let reply = match (command, reply) {
+        ("SCAN" | "HSCAN" | "SSCAN" | "ZSCAN", Value::Array(parts)) if parts.len() == 2 => &parts[1],
+        _ => reply,
+    };
+
+    let (columns, rows): (Vec<&str>, Vec<Vec<Option<String>>>) = match (command, reply) {
+        ("XRANGE" | "XREVRANGE", Value::Array(entries)) => (
+            vec!["id", "fields"],
+            entries
+                .iter()
+                .map(|entry| match entry {
+                    Value::Array(parts) if parts.len() == 2 => {
+                        let fields = value_pairs(&parts[1])
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|(field, value)| format!("{}={}", field, value.unwrap_or_default()))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        vec![value_to_text(&parts[0]), Some(fields)]
+                    }
+                    other => vec![value_to_text(other), None],
+                })
+                .collect(),
+        ),
+        ("INFO", _) => (
+            vec!["field", "value"],
+            value_to_text(reply)
+                .unwrap_or_default()
+                .lines()
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .filter_map(|line| line.split_once(':'))
+                .map(|(field, value)| vec![Some(field.to_string()), Some(value.trim().to_string())])
+                .collect(),
+        ),
+        (_, Value::Map(_)) | (_, Value::Array(_)) if pair_columns.is_some() || matches!(reply, Value::Map(_)) => {
+            let (key_column, value_column) = pair_columns.unwrap_or(("field", "value"));
+            (
+                vec![key_column, value_column],
+                value_pairs(reply)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(field, value)| vec![Some(field), value])
+                    .collect(),
+            )
+        }
+        (_, Value::Array(values)) | (_, Value::Set(values)) => (vec!["value"], single_column(values)),
+        (_, other) => (vec!["value"], vec![vec![value_to_text(other)]]),
+    };
+
+    QueryResult {
+        sql,
+        columns: columns.into_iter().map(String::from).collect(),
+        rows,
+        elapsed_ms,
+        table_name: None,
+        editable: false,
+    }
+}
+
+/// Parse `INFO keyspace` lines such as `db0:keys=12,expires=0,avg_ttl=0`
+pub fn parse_keyspace(info: &str) -> HashMap<i64, u64> {
+    info.lines()
+        .filter_map(|line| {
+            let (database, stats) = line.trim().split_once(':')?;
+            let index = database.strip_prefix("db")?.parse().ok()?;
+            let keys = stats
+                .split(',')
+                .find_map(|stat| stat.strip_prefix("keys="))?
+                .parse()
+                .ok()?;
+            Some((index, keys))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl DbConnection for RedisConnection {
+    fn config(&self) -> &DbConnectionConfig {
+        &self.config
+    }
+
+    fn set_config_database(&mut self, database: Option<String>) {
+        *self.current_database.get_mut() = database
+            .as_deref()
+            .and_then(|name| database_index(name).ok())
+            .unwrap_or(0);
+        self.config.database = database;
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+
+    async fn connect(&mut self) -> Result<(), DbError> {
+        let mut connection_info = Self::build_connection_info(&self.config)?;
+        let proxy_forward = ProxyForward::start(&self.config).await?;
+        if let Some(forward) = &proxy_forward {
+            // The forward only reaches the host field, not whatever the connection string names
+            if self.config.get_param("connection_string").is_some_and(|s| !s.trim().is_empty()) {
+                return Err(DbError::ConnectionError(
+                    "A connection string cannot be used through a proxy, fill in the host instead".to_string(),
+                ));
+            }
+            connection_info.addr = match connection_info.addr {
+                ConnectionAddr::TcpTls { insecure: false, .. } => return Err(proxy::hostname_check_unsupported()),
+                ConnectionAddr::TcpTls { insecure, tls_params, .. } => ConnectionAddr::TcpTls {
+                    host: "127.0.0.1".to_string(),
+                    port: forward.local_port(),
+                    insecure,
+                    tls_params,
+                },
+                _ => ConnectionAddr::Tcp("127.0.0.1".to_string(), forward.local_port()),
+            };
+        }
+        self.connection_info = Some(connection_info);
+        self.tls_certificates = Self::tls_certificates(&self.config)?;
+        self.proxy_forward = proxy_forward;
+        self.ping()
+            .await
+            .map_err(|e| ProxyForward::connect_error(self.proxy_forward.as_ref(), e))
+    }
+
+    async fn disconnect(&mut self) -> Result<(), DbError> {
+        self.connections.get_mut().clear();
+        self.connection_info = None;
+        self.tls_certificates = None;
+        self.proxy_forward = None;
+        Ok(())
+    }
+
+    async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
+        self.execute_statements(plugin, script, options).await
+    }
+
+    async fn query(&self, query: &str, params: Option<Vec<SqlValue>>, options: ExecOptions) -> Result<SqlResult, DbError> {
+        if params.is_some() {
+            return Err(DbError::QueryError(
+                "Parameterized queries are not supported for Redis".to_string(),
+            ));
+        }
+        Ok(self.execute_single(query, options.max_rows).await)
+    }
+
+    async fn ping(&self) -> Result<(), DbError> {
+        let arguments = [String::from("PING")];
+        self.run(None, &arguments)
+            .await
+            .map(|_| ())
+            .map_err(|e| DbError::ConnectionError(e.to_string()))
+    }
+
+    async fn current_database(&self) -> Result<Option<String>, DbError> {
+        Ok(Some(database_name(*self.current_database.read().await)))
+    }
+
+    async fn switch_database(&self, database: &str) -> Result<(), DbError> {
+        let index = database_index(database).map_err(|e| DbError::QueryError(e.to_string()))?;
+        *self.current_database.write().await = index;
+        Ok(())
+    }
+
+    async fn execute_streaming(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        sender: mpsc::Sender<StreamingProgress>,
+    ) -> Result<(), DbError> {
+        let statements = plugin.split_statements(script);
+        let total = statements.len();
+
+        for (index, statement) in statements.into_iter().enumerate() {
+            let result = self.execute_single(&statement, options.max_rows).await;
+            let is_error = result.is_error();
+            let progress = StreamingProgress {
+                current: index + 1,
+                total,
+                result,
+            };
+
+            if sender.send(progress).await.is_err() {
+                break;
+            }
+
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(text: &str) -> Value {
+        Value::BulkString(text.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_reply_to_result_shapes() {
+        let hash = Value::Array(vec![Value::Int(0), Value::Array(vec![bulk("name"), bulk("alice"), bulk("age"), bulk("30")])]);
+        let result = reply_to_result("HSCAN user:1 0".to_string(), "HSCAN", &hash, 0);
+        assert_eq!(result.columns, vec!["field", "value"]);
+        assert_eq!(result.rows[1], vec![Some("age".to_string()), Some("30".to_string())]);
+
+        let zset = Value::Array(vec![bulk("bob"), bulk("1.5")]);
+        let result = reply_to_result("ZRANGE rank 0 -1 withscores".to_string(), "ZRANGE", &zset, 0);
+        assert_eq!(result.columns, vec!["member", "score"]);
+
+        let list = Value::Array(vec![bulk("a"), Value::Nil]);
+        let result = reply_to_result("LRANGE q 0 -1".to_string(), "LRANGE", &list, 0);
+        assert_eq!(result.columns, vec!["value"]);
+        assert_eq!(result.rows, vec![vec![Some("a".to_string())], vec![None]]);
+
+        let result = reply_to_result("GET missing".to_string(), "GET", &Value::Nil, 0);
+        assert_eq!(result.rows, vec![vec![None]]);
+    }
+
+    #[test]
+    fn test_parse_keyspace() {
+        let info = "# Keyspace\r\ndb0:keys=12,expires=1,avg_ttl=0\r\ndb3:keys=2,expires=0,avg_ttl=0\r\n";
+        let keyspace = parse_keyspace(info);
+        assert_eq!(keyspace.get(&0), Some(&12));
+        assert_eq!(keyspace.get(&3), Some(&2));
+        assert_eq!(keyspace.len(), 2);
+    }
+
+    #[test]
+    fn test_reply_message() {
+        assert_eq!(reply_message(&Value::Int(2)), "(integer) 2");
+        assert_eq!(reply_message(&Value::Okay), "OK");
+        assert_eq!(reply_message(&Value::Nil), "(nil)");
+    }
+}
@@ -0,0 +1,603 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig, GlobalStorageState};
+
+use crate::connection::{DbConnection, DbError};
+use crate::executor::StatementType;
+use crate::plugin::{DatabaseOperationRequest, DatabasePlugin, SqlCompletionInfo};
+use crate::redis::command::{
+    database_index, database_name, escape_pattern, format_command, group_keys, is_destructive_command,
+    is_read_only_command, quote_argument, split_redis_commands, tokenize_command, KeyNamespace,
+};
+use crate::redis::RedisConnection;
+use crate::types::*;
+
+/// Keys loaded into the tree per database; larger keyspaces are browsed through the console
+const MAX_TREE_KEYS: usize = 5000;
+const DEFAULT_KEY_SEPARATOR: &str = ":";
+
+/// Redis plugin. Logical databases `db0..dbN` hold keys grouped into namespace folders;
+/// keys are shown as tables and the editor is a command console instead of SQL.
+pub struct RedisPlugin;
+
+impl RedisPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn redis_connection<'a>(&self, connection: &'a dyn DbConnection) -> Result<&'a RedisConnection> {
+        connection
+            .as_any()
+            .and_then(|any| any.downcast_ref::<RedisConnection>())
+            .ok_or_else(|| anyhow::anyhow!("Not a Redis connection"))
+    }
+
+    fn key_separator(connection: &dyn DbConnection) -> String {
+        connection
+            .config()
+            .get_param("key_separator")
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_KEY_SEPARATOR.to_string())
+    }
+
+    fn unsupported(&self, operation: &str) -> String {
+        format!("# Redis: {} is not supported", operation)
+    }
+
+    /// Tree actions run on a fresh session that starts in the connection's default database
+    fn in_database(&self, database: &str, command: String) -> String {
+        let index = database_index(database).map(|index| index.to_string()).unwrap_or_else(|_| database.to_string());
+        format!("{}\n{}", format_command(&["SELECT", &index]), command)
+    }
+
+    async fn scan_keys(&self, connection: &dyn DbConnection, database: &str, prefix: &str) -> Result<Vec<String>> {
+        let conn = self.redis_connection(connection)?;
+        let pattern = format!("{}*", escape_pattern(prefix));
+        let (keys, truncated) = conn
+            .scan_keys(database_index(database)?, &pattern, MAX_TREE_KEYS)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to scan keys: {}", e))?;
+        if truncated {
+            tracing::warn!("Redis {} has more than {} keys matching {}, the tree shows the first ones", database, MAX_TREE_KEYS, pattern);
+        }
+        Ok(keys)
+    }
+
+    /// Child nodes of a namespace: nested namespaces as folders with their keys preloaded, then keys
+    fn namespace_children(&self, node: &DbNode, database_node_id: &str, namespace: &KeyNamespace, database: &str) -> Vec<DbNode> {
+        let mut metadata: HashMap<String, String> = HashMap::new();
+        metadata.insert("database".to_string(), database.to_string());
+
+        let mut children: Vec<DbNode> = namespace
+            .namespaces
+            .iter()
+            .map(|child| {
+                let mut folder_metadata = metadata.clone();
+                folder_metadata.insert("namespace".to_string(), child.prefix.clone());
+                let mut folder = DbNode::new(
+                    format!("{}:namespace:{}", database_node_id, child.prefix),
+                    format!("{} ({})", child.name, child.key_count()),
+                    DbNodeType::TablesFolder,
+                    node.connection_id.clone(),
+                    node.database_type
+                )
+                .with_parent_context(node.id.clone())
+                .with_metadata(folder_metadata);
+                folder.set_children(self.namespace_children(&folder, database_node_id, child, database));
+                folder
+            })
+            .collect();
+
+        children.extend(namespace.keys.iter().map(|key| {
+            DbNode::new(
+                format!("{}:key:{}", database_node_id, key),
+                key.clone(),
+                DbNodeType::Table,
+                node.connection_id.clone(),
+                node.database_type
+            )
+            .with_parent_context(node.id.clone())
+            .with_metadata(metadata.clone())
+            .with_children_loaded(true)
+        }));
+        children
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for RedisPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::Redis
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        quote_argument(identifier)
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        Box::new(sqlparser::dialect::GenericDialect {})
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        SqlCompletionInfo {
+            keywords: vec![
+                ("GET", "GET key - Get the string value"),
+                ("SET", "SET key value [EX seconds] [NX|XX] - Set the string value"),
+                ("DEL", "DEL key [key ...] - Delete keys"),
+                ("EXISTS", "EXISTS key [key ...] - Count existing keys"),
+                ("EXPIRE", "EXPIRE key seconds - Set a timeout"),
+                ("PERSIST", "PERSIST key - Remove the timeout"),
+                ("TTL", "TTL key - Remaining time to live in seconds"),
+                ("TYPE", "TYPE key - Value type"),
+                ("RENAME", "RENAME key newkey - Rename a key"),
+                ("SCAN", "SCAN cursor [MATCH pattern] [COUNT count] - Iterate keys"),
+                ("KEYS", "KEYS pattern - Find keys (blocks the server on large keyspaces)"),
+                ("HGET", "HGET key field - Get a hash field"),
+                ("HSET", "HSET key field value [field value ...] - Set hash fields"),
+                ("HDEL", "HDEL key field [field ...] - Delete hash fields"),
+                ("HGETALL", "HGETALL key - Get all hash fields"),
+                ("HSCAN", "HSCAN key cursor [MATCH pattern] [COUNT count] - Iterate hash fields"),
+                ("LRANGE", "LRANGE key start stop - Get list elements"),
+                ("LPUSH", "LPUSH key element [element ...] - Prepend to a list"),
+                ("RPUSH", "RPUSH key element [element ...] - Append to a list"),
+                ("LSET", "LSET key index element - Set a list element"),
+                ("LREM", "LREM key count element - Remove list elements"),
+                ("SMEMBERS", "SMEMBERS key - Get all set members"),
+                ("SADD", "SADD key member [member ...] - Add set members"),
+                ("SREM", "SREM key member [member ...] - Remove set members"),
+                ("ZRANGE", "ZRANGE key start stop [WITHSCORES] - Get sorted set members"),
+                ("ZADD", "ZADD key score member [score member ...] - Add sorted set members"),
+                ("ZREM", "ZREM key member [member ...] - Remove sorted set members"),
+                ("XRANGE", "XRANGE key start end [COUNT count] - Get stream entries"),
+                ("INFO", "INFO [section] - Server information"),
+                ("DBSIZE", "DBSIZE - Number of keys in the database"),
+                ("SELECT", "SELECT index - Switch the logical database"),
+                ("FLUSHDB", "FLUSHDB - Delete all keys of the database"),
+            ],
+            functions: vec![],
+            operators: vec![
+                ("MATCH", "SCAN option: key pattern"),
+                ("COUNT", "SCAN option: batch size hint"),
+                ("WITHSCORES", "Return scores with sorted set members"),
+                ("EX", "SET option: expire in seconds"),
+                ("PX", "SET option: expire in milliseconds"),
+                ("NX", "Only set when the key does not exist"),
+                ("XX", "Only set when the key exists"),
+                ("KEEPTTL", "SET option: keep the current timeout"),
+            ],
+            data_types: vec![
+                ("string", "Binary-safe string"),
+                ("hash", "Field/value map"),
+                ("list", "Ordered list"),
+                ("set", "Unordered unique members"),
+                ("zset", "Members ordered by score"),
+                ("stream", "Append-only log"),
+            ],
+            snippets: vec![
+                ("scan", "SCAN 0 MATCH $1* COUNT 100", "Iterate keys by prefix"),
+                ("setex", "SET $1 $2 EX $3", "Set a value with a timeout"),
+            ],
+        }
+    }
+
+    fn split_statements(&self, script: &str) -> Vec<String> {
+        split_redis_commands(script)
+    }
+
+    fn is_query_statement(&self, sql: &str) -> bool {
+        is_read_only_command(sql)
+    }
+
+    fn classify_statement(&self, sql: &str) -> StatementType {
+        if tokenize_command(sql).is_err() {
+            StatementType::Exec
+        } else if is_read_only_command(sql) {
+            StatementType::Query
+        } else if is_destructive_command(sql) {
+            StatementType::Ddl
+        } else {
+            StatementType::Dml
+        }
+    }
+
+    fn analyze_select_editability(&self, _sql: &str) -> Option<String> {
+        None
+    }
+
+    fn is_read_only_query(&self, sql: &str) -> bool {
+        self.split_statements(sql).len() == 1 && self.is_query_statement(sql)
+    }
+
+    /// The filter box takes a key pattern such as `user:*`
+    fn validate_where_condition(&self, condition: &str) -> Result<()> {
+        if condition.trim().is_empty() || condition.contains(char::is_whitespace) {
+            anyhow::bail!("请输入不含空格的键模式，如 user:*");
+        }
+        Ok(())
+    }
+
+    fn build_explain_sql(&self, _sql: &str) -> Option<String> {
+        None
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = RedisConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    // === Database Level Operations ===
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        let conn = self.redis_connection(connection)?;
+        let count = conn.database_count().await;
+        Ok((0..count).map(database_name).collect())
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let conn = self.redis_connection(connection)?;
+        let databases = self.list_databases(connection).await?;
+        let keyspace = conn.keyspace().await.unwrap_or_default();
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(120.0)),
+            Column::new("keys", "Keys").width(px(120.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = databases
+            .iter()
+            .enumerate()
+            .map(|(index, db)| vec![db.clone(), keyspace.get(&(index as i64)).copied().unwrap_or(0).to_string()])
+            .collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Database,
+            title: format!("{} database(s)", databases.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        let conn = self.redis_connection(connection)?;
+        let databases = self.list_databases(connection).await?;
+        let keyspace = conn.keyspace().await.unwrap_or_default();
+
+        Ok(databases.into_iter().enumerate().map(|(index, name)| DatabaseInfo {
+            name,
+            charset: None,
+            collation: None,
+            size: None,
+            table_count: keyspace.get(&(index as i64)).map(|keys| *keys as i64),
+            comment: None,
+        }).collect())
+    }
+
+    // === Key Operations ===
+
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        let mut keys = self.scan_keys(connection, database, "").await?;
+        keys.sort();
+
+        Ok(keys.into_iter().map(|name| TableInfo {
+            name,
+            schema: None,
+            comment: None,
+            engine: None,
+            row_count: None,
+            create_time: None,
+            charset: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Key").width(px(320.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = tables.iter().map(|table| vec![table.name.clone()]).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} key(s)", tables.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_columns(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<Vec<ColumnInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_columns_view(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: "0 column(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_indexes(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<Vec<IndexInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_indexes_view(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: "0 index(es)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_views(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<ViewInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_views_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::View,
+            title: "0 view(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Function,
+            title: "0 function(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Procedure,
+            title: "0 procedure(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(180.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_sequences(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SequenceInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sequences_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Sequence,
+            title: "0 sequence(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    // === Tree Building ===
+
+    /// Keys are grouped into namespace folders by the key separator; saved console scripts follow
+    async fn build_database_tree(&self, connection: &dyn DbConnection, node: &DbNode, global_storage_state: &GlobalStorageState) -> Result<Vec<DbNode>> {
+        let database = &node.name;
+        let mut metadata: HashMap<String, String> = HashMap::new();
+        metadata.insert("database".to_string(), database.to_string());
+
+        let keys = self.scan_keys(connection, database, "").await?;
+        let root = group_keys(&keys, &Self::key_separator(connection));
+
+        let mut nodes = self.namespace_children(node, &node.id, &root, database);
+        nodes.push(self.load_queries(node, metadata, global_storage_state).await?);
+        Ok(nodes)
+    }
+
+    /// Refreshing a namespace folder rescans only the keys under its prefix
+    async fn load_schema_folder_children(&self, connection: &dyn DbConnection, node: &DbNode, _id: &str) -> Result<Vec<DbNode>> {
+        let metadata = node.metadata.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("命名空间节点缺少 metadata"))?;
+        let database = metadata.get("database")
+            .ok_or_else(|| anyhow::anyhow!("命名空间节点缺少 database 字段"))?;
+        let prefix = metadata.get("namespace").map(String::as_str).unwrap_or("");
+        let database_node_id = node.id
+            .split(":namespace:")
+            .next()
+            .unwrap_or(&node.id)
+            .to_string();
+
+        let keys = self.scan_keys(connection, database, prefix).await?;
+        let root = group_keys(&keys, &Self::key_separator(connection));
+        Ok(root
+            .find(prefix)
+            .map(|namespace| self.namespace_children(node, &database_node_id, namespace, database))
+            .unwrap_or_default())
+    }
+
+    async fn load_table_children(&self, _connection: &dyn DbConnection, _node: &DbNode, _id: &str) -> Result<Vec<DbNode>> {
+        Ok(Vec::new())
+    }
+
+    // === Table Data Operations ===
+
+    async fn query_table_data(&self, _connection: &dyn DbConnection, _request: &TableDataRequest) -> Result<TableDataResponse> {
+        anyhow::bail!("Redis 键请在键值视图中查看")
+    }
+
+    fn generate_table_changes_sql(&self, _request: &TableSaveRequest) -> String {
+        self.unsupported("editing keys in the grid")
+    }
+
+    // === Command generation ===
+
+    fn build_column_definition(&self, column: &ColumnInfo, _include_name: bool) -> String {
+        column.name.clone()
+    }
+
+    fn build_create_database_sql(&self, _request: &DatabaseOperationRequest) -> String {
+        self.unsupported("creating a database")
+    }
+
+    fn build_modify_database_sql(&self, _request: &DatabaseOperationRequest) -> String {
+        self.unsupported("modifying a database")
+    }
+
+    /// Selects the database first, so only that one is emptied
+    fn build_drop_database_sql(&self, database_name: &str) -> String {
+        self.in_database(database_name, "FLUSHDB".to_string())
+    }
+
+    fn drop_database(&self, database: &str) -> String {
+        self.build_drop_database_sql(database)
+    }
+
+    fn drop_table(&self, database: &str, table: &str) -> String {
+        self.in_database(database, format_command(&["DEL", table]))
+    }
+
+    fn truncate_table(&self, database: &str, table: &str) -> String {
+        self.in_database(database, format_command(&["DEL", table]))
+    }
+
+    fn rename_table(&self, database: &str, old_name: &str, new_name: &str) -> String {
+        self.in_database(database, format_command(&["RENAME", old_name, new_name]))
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        col.name.clone()
+    }
+
+    fn build_create_table_sql(&self, _design: &TableDesign) -> String {
+        self.unsupported("designing keys")
+    }
+
+    fn build_alter_table_sql(&self, _original: &TableDesign, _new: &TableDesign) -> String {
+        self.unsupported("designing keys")
+    }
+
+    fn build_limit_clause(&self) -> String {
+        String::new()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        _request: &TableSaveRequest,
+        _original_data: &[String],
+    ) -> (String, String) {
+        (String::new(), String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_plugin() -> RedisPlugin {
+        RedisPlugin::new()
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        assert_eq!(create_plugin().name(), DatabaseType::Redis);
+    }
+
+    #[test]
+    fn test_classify_statement() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.classify_statement("HGETALL user:1"), StatementType::Query);
+        assert_eq!(plugin.classify_statement("hset user:1 name bob"), StatementType::Dml);
+        assert_eq!(plugin.classify_statement("FLUSHDB"), StatementType::Ddl);
+        assert_eq!(plugin.classify_statement("GET \"open"), StatementType::Exec);
+        assert!(plugin.is_read_only_query("GET a"));
+        assert!(!plugin.is_read_only_query("GET a\nGET b"));
+    }
+
+    #[test]
+    fn test_key_commands() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.drop_table("db0", "user:1"), "SELECT 0\nDEL user:1");
+        assert_eq!(plugin.rename_table("db0", "a b", "c"), "SELECT 0\nRENAME \"a b\" c");
+        assert_eq!(plugin.quote_identifier("x y"), "\"x y\"");
+    }
+
+    #[test]
+    fn test_key_commands_select_their_database() {
+        let plugin = create_plugin();
+        // The tree runs these on a fresh session whose current database is the connection default
+        assert_eq!(
+            split_redis_commands(&plugin.drop_table("db3", "user:1")),
+            vec!["SELECT 3", "DEL user:1"]
+        );
+        assert_eq!(plugin.truncate_table("db3", "user:1"), "SELECT 3\nDEL user:1");
+        assert_eq!(plugin.drop_database("db3"), "SELECT 3\nFLUSHDB");
+        assert_eq!(plugin.build_drop_database_sql("db3"), "SELECT 3\nFLUSHDB");
+    }
+
+    #[test]
+    fn test_namespace_children() {
+        let plugin = create_plugin();
+        let node = DbNode::new("conn:db0", "db0", DbNodeType::Database, "conn".to_string(), DatabaseType::Redis);
+        let keys: Vec<String> = ["user:1", "user:2", "config"].iter().map(|key| key.to_string()).collect();
+        let root = group_keys(&keys, ":");
+
+        let children = plugin.namespace_children(&node, &node.id, &root, "db0");
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name, "user (2)");
+        assert_eq!(children[0].node_type, DbNodeType::TablesFolder);
+        assert!(children[0].children_loaded);
+        assert_eq!(children[0].children[1].id, "conn:db0:key:user:2");
+        assert_eq!(children[1].name, "config");
+        assert!(children[1].children_loaded);
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use one_core::storage::DatabaseType;
+
+use crate::connection::DbConnection;
+use crate::executor::{ExecOptions, QueryResult, SqlResult};
+use crate::reconnect::ConnectionStatus;
+
+/// 数据库服务器的运行状态，取不到的项为 None
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerStatus {
+    pub version: Option<String>,
+    pub uptime: Option<Duration>,
+    /// 服务器上的客户端会话数（包括其它客户端）
+    pub active_sessions: Option<u64>,
+}
+
+impl ServerStatus {
+    /// 从一行（版本, 运行秒数, 会话数）的查询结果解析，NULL 列视为未知
+    pub fn from_query_result(result: &QueryResult) -> ServerStatus {
+        let Some(row) = result.rows.first() else {
+            return ServerStatus::default();
+        };
+        let value = |i: usize| row.get(i).cloned().flatten().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        // 不同数据库的计数可能以 NUMERIC 或浮点数文本返回
+        let number = |i: usize| value(i).and_then(|v| v.parse::<f64>().ok()).filter(|v| *v >= 0.0);
+
+        ServerStatus {
+            version: value(0),
+            uptime: number(1).map(|secs| Duration::from_secs(secs as u64)),
+            active_sessions: number(2).map(|count| count as u64),
+        }
+    }
+}
+
+/// 执行返回（版本, 运行秒数, 会话数）一行的查询，供各插件实现 `server_status`
+pub async fn query_server_status(connection: &dyn DbConnection, sql: &str) -> Result<ServerStatus> {
+    match connection.query(sql, None, ExecOptions::default()).await? {
+        SqlResult::Query(query) => Ok(ServerStatus::from_query_result(&query)),
+        SqlResult::Error(e) => Err(anyhow!("{}", e.message)),
+        SqlResult::Exec(_) => Err(anyhow!("Statement returned no result set")),
+    }
+}
+
+/// 连接池中一个连接的会话占用情况
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolUsage {
+    pub in_use: usize,
+    pub total: usize,
+}
+
+/// 连接上最近一次出现的错误，连接恢复后仍然保留
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastError {
+    pub message: String,
+    pub at: DateTime<Local>,
+}
+
+/// 连接健康面板中的一行
+#[derive(Debug, Clone)]
+pub struct ConnectionHealth {
+    pub connection_id: String,
+    pub name: String,
+    pub database_type: DatabaseType,
+    pub status: Option<ConnectionStatus>,
+    /// 探测前的会话占用，不包括探测本身借用的会话
+    pub pool: PoolUsage,
+    pub ping: Option<Duration>,
+    pub server: ServerStatus,
+    /// 本次探测失败的原因
+    pub probe_error: Option<String>,
+    pub last_error: Option<LastError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_result(row: Vec<Option<&str>>) -> QueryResult {
+        QueryResult {
+            sql: String::new(),
+            columns: vec![],
+            rows: vec![row.into_iter().map(|cell| cell.map(str::to_string)).collect()],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        }
+    }
+
+    #[test]
+    fn test_server_status_from_query_result() {
+        let status = ServerStatus::from_query_result(&query_result(vec![Some("PostgreSQL 16.2"), Some("86400.52"), Some("7")]));
+        assert_eq!(status.version.as_deref(), Some("PostgreSQL 16.2"));
+        assert_eq!(status.uptime, Some(Duration::from_secs(86400)));
+        assert_eq!(status.active_sessions, Some(7));
+
+        let status = ServerStatus::from_query_result(&query_result(vec![Some("3.45.1"), None, Some("")]));
+        assert_eq!(status.version.as_deref(), Some("3.45.1"));
+        assert_eq!((status.uptime, status.active_sessions), (None, None));
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use one_core::storage::DbConnectionConfig;
+
+/// Connection parameter (extra_params) with the seconds a pooled session may stay idle
+/// before the cleanup task closes it; the pool default applies when it is not set
+pub const IDLE_TIMEOUT_PARAM: &str = "idle_timeout";
+
+/// Connection parameter choosing whether the cleanup task closes the connection's sessions
+pub const IDLE_CLEANUP_PARAM: &str = "idle_cleanup";
+
+/// `IDLE_CLEANUP_PARAM` value that keeps sessions open, for servers slow to connect to
+/// or connections whose session state should survive a coffee break
+pub const IDLE_CLEANUP_NEVER: &str = "never";
+
+/// Number of cleanup events kept for the settings page, the oldest are dropped first
+pub const CLEANUP_LOG_CAPACITY: usize = 200;
+
+/// How the cleanup task treats the sessions of one connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleCleanupPolicy {
+    /// Close sessions idle for longer than this, or older than the pool's maximum lifetime
+    CloseAfter(Duration),
+    /// Leave sessions open until the connection is closed or a keepalive ping fails
+    Never,
+}
+
+impl IdleCleanupPolicy {
+    pub fn from_config(config: &DbConnectionConfig, default_idle_timeout: Duration) -> Self {
+        if config.get_param(IDLE_CLEANUP_PARAM).is_some_and(|value| value == IDLE_CLEANUP_NEVER) {
+            return IdleCleanupPolicy::Never;
+        }
+        let idle_timeout = config
+            .get_param_as::<u64>(IDLE_TIMEOUT_PARAM)
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(default_idle_timeout);
+        IdleCleanupPolicy::CloseAfter(idle_timeout)
+    }
+}
+
+/// Why a session was closed by the background task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanupReason {
+    /// Idle for the given time, longer than the connection's idle timeout
+    IdleTimeout(Duration),
+    /// Open for the given time, longer than the pool's maximum lifetime
+    LifetimeExceeded(Duration),
+    KeepaliveFailed,
+}
+
+#[derive(Debug, Clone)]
+pub struct CleanupEvent {
+    pub at: DateTime<Local>,
+    pub connection_id: String,
+    pub connection_name: String,
+    pub session_id: String,
+    pub reason: CleanupReason,
+}
+
+/// Recent sessions closed by the cleanup and keepalive task, so a dropped temp table or
+/// transaction can be traced back to the policy that closed its session
+#[derive(Clone, Default)]
+pub struct CleanupLog {
+    events: Arc<Mutex<VecDeque<CleanupEvent>>>,
+}
+
+impl CleanupLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, event: CleanupEvent) {
+        if let Ok(mut events) = self.events.lock() {
+            if events.len() == CLEANUP_LOG_CAPACITY {
+                events.pop_front();
+            }
+            events.push_back(event);
+        }
+    }
+
+    /// Newest first
+    pub fn events(&self) -> Vec<CleanupEvent> {
+        self.events
+            .lock()
+            .map(|events| events.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut events) = self.events.lock() {
+            events.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use one_core::storage::DatabaseType;
+
+    fn config_with(params: &[(&str, &str)]) -> DbConnectionConfig {
+        DbConnectionConfig {
+            id: "1".to_string(),
+            database_type: DatabaseType::MySQL,
+            name: "local".to_string(),
+            host: "localhost".to_string(),
+            port: 3306,
+            username: "root".to_string(),
+            password: String::new(),
+            database: None,
+            workspace_id: None,
+            extra_params: params.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let default = Duration::from_secs(300);
+        assert_eq!(IdleCleanupPolicy::from_config(&config_with(&[]), default), IdleCleanupPolicy::CloseAfter(default));
+        assert_eq!(
+            IdleCleanupPolicy::from_config(&config_with(&[(IDLE_TIMEOUT_PARAM, "60")]), default),
+            IdleCleanupPolicy::CloseAfter(Duration::from_secs(60))
+        );
+        assert_eq!(
+            IdleCleanupPolicy::from_config(&config_with(&[(IDLE_TIMEOUT_PARAM, "0")]), default),
+            IdleCleanupPolicy::CloseAfter(default)
+        );
+        assert_eq!(
+            IdleCleanupPolicy::from_config(&config_with(&[(IDLE_CLEANUP_PARAM, IDLE_CLEANUP_NEVER), (IDLE_TIMEOUT_PARAM, "60")]), default),
+            IdleCleanupPolicy::Never
+        );
+    }
+
+    #[test]
+    fn test_log_keeps_newest_events() {
+        let log = CleanupLog::new();
+        for i in 0..CLEANUP_LOG_CAPACITY + 5 {
+            log.record(CleanupEvent {
+                at: Local::now(),
+                connection_id: "1".to_string(),
+                connection_name: "local".to_string(),
+                session_id: format!("1:session:{}", i),
+                reason: CleanupReason::KeepaliveFailed,
+            });
+        }
+        let events = log.events();
+        assert_eq!(events.len(), CLEANUP_LOG_CAPACITY);
+        assert_eq!(events[0].session_id, format!("1:session:{}", CLEANUP_LOG_CAPACITY + 4));
+        assert_eq!(events[CLEANUP_LOG_CAPACITY - 1].session_id, "1:session:5");
+
+        log.clear();
+        assert!(log.events().is_empty());
+    }
+}
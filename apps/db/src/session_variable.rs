@@ -0,0 +1,90 @@
+use crate::executor::QueryResult;
+use crate::plugin::DatabasePlugin;
+
+/// 当前会话的一项设置（MySQL 的会话变量、PostgreSQL 的运行参数等）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionVariable {
+    pub name: String,
+    pub value: Option<String>,
+    pub description: Option<String>,
+    /// 能否在会话内修改，查询没有返回这一列时视为可以
+    pub editable: bool,
+}
+
+impl SessionVariable {
+    /// 从 `build_list_session_variables_sql` 的查询结果（名称、值、说明、可修改）解析
+    pub fn from_query_result(result: &QueryResult) -> Vec<SessionVariable> {
+        result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let value = |i: usize| row.get(i).cloned().flatten();
+                Some(SessionVariable {
+                    name: value(0)?,
+                    value: value(1),
+                    description: value(2).filter(|description| !description.trim().is_empty()),
+                    editable: value(3).is_none_or(|editable| {
+                        matches!(editable.trim().to_ascii_lowercase().as_str(), "1" | "t" | "true" | "yes" | "on")
+                    }),
+                })
+            })
+            .collect()
+    }
+}
+
+/// SET 语句中的值：数字、布尔值和 DEFAULT 原样写入，其它值作为字符串字面量，
+/// 因为不少数据库不接受给数值型设置传字符串；字符串按 `plugin` 的方言转义
+pub fn session_variable_literal(plugin: &dyn DatabasePlugin, value: &str) -> String {
+    let value = value.trim();
+    let is_keyword = ["DEFAULT", "TRUE", "FALSE", "ON", "OFF"]
+        .iter()
+        .any(|keyword| value.eq_ignore_ascii_case(keyword));
+    if is_keyword || value.parse::<f64>().is_ok_and(|number| number.is_finite()) {
+        value.to_string()
+    } else {
+        plugin.string_literal(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_query_result() {
+        let result = QueryResult {
+            sql: String::new(),
+            columns: vec![],
+            rows: vec![
+                vec![Some("work_mem".to_string()), Some("4MB".to_string()), Some("Sets the maximum memory".to_string()), Some("t".to_string())],
+                vec![Some("server_version".to_string()), Some("16.2".to_string()), Some(" ".to_string()), Some("false".to_string())],
+                vec![Some("sql_mode".to_string()), None],
+                vec![None, Some("ignored".to_string())],
+            ],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        };
+        let variables = SessionVariable::from_query_result(&result);
+        assert_eq!(variables.len(), 3);
+        assert!(variables[0].editable);
+        assert_eq!(variables[1].description, None);
+        assert!(!variables[1].editable);
+        assert_eq!((variables[2].value.as_deref(), variables[2].editable), (None, true));
+    }
+
+    #[test]
+    fn test_session_variable_literal() {
+        let postgres = crate::postgresql::PostgresPlugin::new();
+        assert_eq!(session_variable_literal(&postgres, " 262144 "), "262144");
+        assert_eq!(session_variable_literal(&postgres, "0.5"), "0.5");
+        assert_eq!(session_variable_literal(&postgres, "off"), "off");
+        assert_eq!(session_variable_literal(&postgres, "4MB"), "'4MB'");
+        assert_eq!(session_variable_literal(&postgres, "it's"), "'it''s'");
+        assert_eq!(session_variable_literal(&postgres, "inf"), "'inf'");
+        assert_eq!(session_variable_literal(&postgres, "C:\\temp"), "'C:\\temp'");
+        // MySQL 里反斜杠是转义符，不转义会吞掉后面的字符
+        let mysql = crate::mysql::MySqlPlugin::new();
+        assert_eq!(session_variable_literal(&mysql, "C:\\temp"), "'C:\\\\temp'");
+    }
+}
@@ -1,8 +1,9 @@
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
-use sqlx::sqlite::SqliteRow;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteRow};
 use sqlx::{Column, Connection, Row, SqliteConnection};
 use tokio::sync::Mutex;
 use one_core::storage::DbConnectionConfig;
@@ -16,6 +17,39 @@ use crate::executor::{
 
 use crate::types::{SqlValue};
 
+/// Connection parameter listing extensions to load, one path per line. `path@entry_point`
+/// names the entry point when it does not follow from the file name.
+pub const EXTENSIONS_PARAM: &str = "extensions";
+/// Connection parameter listing databases to attach, one `alias=path` per line
+pub const ATTACH_PARAM: &str = "attach";
+
+/// Lines of the extensions parameter as (path, entry point)
+pub fn parse_extensions(text: &str) -> Vec<(String, Option<String>)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.rsplit_once('@') {
+            Some((path, entry_point)) if !entry_point.trim().is_empty() => {
+                (path.trim().to_string(), Some(entry_point.trim().to_string()))
+            }
+            _ => (line.to_string(), None),
+        })
+        .collect()
+}
+
+/// Lines of the attach parameter as (alias, path); lines without an alias are skipped
+pub fn parse_attachments(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (alias, path) = line.split_once('=')?;
+            let (alias, path) = (alias.trim(), path.trim());
+            (!alias.is_empty() && !path.is_empty()).then(|| (alias.to_string(), path.to_string()))
+        })
+        .collect()
+}
+
 pub struct SqliteDbConnection {
     config: DbConnectionConfig,
     connection: Arc<Mutex<Option<SqliteConnection>>>,
@@ -135,10 +169,32 @@ impl DbConnection for SqliteDbConnection {
 
         // Handle create_if_missing for SQLite
         let url = format!("sqlite://{}?mode=rwc", database_path);
-        let conn = SqliteConnection::connect(&url)
+        let mut options = SqliteConnectOptions::from_str(&url)
+            .map_err(|e| DbError::ConnectionError(format!("Invalid database path: {}", e)))?;
+        // Extensions are loaded by the connection itself, before any statement runs
+        for (path, entry_point) in parse_extensions(config.get_param(EXTENSIONS_PARAM).map(String::as_str).unwrap_or("")) {
+            options = match entry_point {
+                Some(entry_point) => options.extension_with_entrypoint(path, entry_point),
+                None => options.extension(path),
+            };
+        }
+        let mut conn = SqliteConnection::connect_with(&options)
             .await
             .map_err(|e| DbError::ConnectionError(format!("Failed to connect: {}", e)))?;
 
+        // Attachments belong to the connection, so they are repeated on every reconnect
+        for (alias, path) in parse_attachments(config.get_param(ATTACH_PARAM).map(String::as_str).unwrap_or("")) {
+            let sql = format!(
+                "ATTACH DATABASE '{}' AS \"{}\"",
+                path.replace('\'', "''"),
+                alias.replace('"', "\"\"")
+            );
+            sqlx::query(&sql)
+                .execute(&mut conn)
+                .await
+                .map_err(|e| DbError::ConnectionError(format!("Failed to attach {} as {}: {}", path, alias, e)))?;
+        }
+
         {
             let mut guard = self.connection.lock().await;
             *guard = Some(conn);
@@ -637,3 +693,26 @@ impl DbConnection for SqliteDbConnection {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_extensions() {
+        let extensions = parse_extensions("/usr/lib/mod_spatialite.so\n\n# comment\n ./json1.so@sqlite3_json_init \n");
+        assert_eq!(extensions, vec![
+            ("/usr/lib/mod_spatialite.so".to_string(), None),
+            ("./json1.so".to_string(), Some("sqlite3_json_init".to_string())),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_attachments() {
+        let attachments = parse_attachments("archive = /data/archive.db\nbroken\n=/data/x.db\nlogs=C:\\data\\logs.db");
+        assert_eq!(attachments, vec![
+            ("archive".to_string(), "/data/archive.db".to_string()),
+            ("logs".to_string(), "C:\\data\\logs.db".to_string()),
+        ]);
+    }
+}
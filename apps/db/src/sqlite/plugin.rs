@@ -5,7 +5,9 @@ use one_core::storage::{DatabaseType, DbConnectionConfig};
 
 use crate::connection::{DbConnection, DbError};
 use crate::executor::{ExecOptions, SqlResult};
-use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::plugin::{on_conflict_clause, DatabasePlugin, SqlCompletionInfo};
+use crate::import_export::{InsertConflictClause, SqlConflictMode};
+use crate::server_status::{query_server_status, ServerStatus};
 use crate::sqlite::SqliteDbConnection;
 use crate::types::*;
 
@@ -17,6 +19,55 @@ impl SqlitePlugin {
         Self
     }
 
+    /// `"db"."name"` for attached databases; objects of `main` stay unqualified so
+    /// statements read the way they are usually written
+    fn qualified(database: &str, name: &str) -> String {
+        match database {
+            "" | "main" => format!("\"{}\"", name),
+            database => format!("\"{}\".\"{}\"", database.replace('"', "\"\""), name),
+        }
+    }
+
+    /// Schema table of a database; every attached file has its own
+    fn master_table(database: &str) -> String {
+        match database {
+            "" | "main" => "sqlite_master".to_string(),
+            database => format!("\"{}\".sqlite_master", database.replace('"', "\"\"")),
+        }
+    }
+
+    /// PRAGMA name prefixed with the database it should read
+    fn pragma(database: &str, pragma: &str) -> String {
+        match database {
+            "" | "main" => pragma.to_string(),
+            database => format!("\"{}\".{}", database.replace('"', "\"\""), pragma),
+        }
+    }
+
+    /// (name, file) of `main` and the attached databases. `temp` is left out: it only
+    /// holds objects created with CREATE TEMP and has no file of its own.
+    async fn database_list(&self, connection: &dyn DbConnection) -> Result<Vec<(String, String)>> {
+        let result = connection.query("PRAGMA database_list", None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list databases: {}", e))?;
+
+        let mut databases: Vec<(String, String)> = match result {
+            SqlResult::Query(query_result) => query_result.rows.iter()
+                .filter_map(|row| {
+                    let name = row.get(1).cloned().flatten()?;
+                    let file = row.get(2).cloned().flatten().unwrap_or_default();
+                    Some((name, file))
+                })
+                .filter(|(name, _)| name != "temp")
+                .collect(),
+            _ => Vec::new(),
+        };
+        if databases.is_empty() {
+            databases.push(("main".to_string(), String::new()));
+        }
+        Ok(databases)
+    }
+
     fn build_sqlite_simple_alter_sql(&self, original: &TableDesign, new: &TableDesign) -> String {
         let mut statements: Vec<String> = Vec::new();
         let table_name = self.quote_identifier(&new.table_name);
@@ -248,6 +299,11 @@ impl DatabasePlugin for SqlitePlugin {
         Box::new(sqlparser::dialect::SQLiteDialect {})
     }
 
+    /// query_only also rejects writes from functions and virtual tables, not only statements
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        Some(("PRAGMA query_only = ON", "PRAGMA query_only = OFF"))
+    }
+
     fn get_completion_info(&self) -> SqlCompletionInfo {
         SqlCompletionInfo {
             keywords: vec![
@@ -262,6 +318,13 @@ impl DatabasePlugin for SqlitePlugin {
                 ("NOT INDEXED", "Disable index usage"),
                 ("NULLS FIRST", "Sort NULLs first"),
                 ("NULLS LAST", "Sort NULLs last"),
+                ("WINDOW", "Named window definition"),
+                ("FILTER", "Aggregate filter clause"),
+                ("WITH RECURSIVE", "Recursive CTE"),
+                ("ON CONFLICT", "Upsert clause"),
+                ("RETURNING", "Return inserted/updated rows"),
+                ("WITHOUT ROWID", "Table without rowid"),
+                ("STRICT", "Strict typing table"),
             ],
             functions: vec![
                 ("IFNULL(x, y)", "Return y if x is NULL"),
@@ -285,11 +348,32 @@ impl DatabasePlugin for SqlitePlugin {
                 ("DATETIME(time, ...)", "Date and time"),
                 ("JULIANDAY(time)", "Julian day number"),
                 ("STRFTIME(fmt, time)", "Format date/time"),
+                ("DATE(time, '+N days')", "Date math via modifiers"),
+                ("DATETIME(time, 'start of month')", "Truncate via modifiers"),
+                ("UNIXEPOCH(time, ...)", "Unix timestamp"),
+                ("TIMEDIFF(time1, time2)", "Difference between times (3.43+)"),
                 ("JSON(json)", "Parse JSON"),
                 ("JSON_ARRAY(...)", "Create JSON array"),
                 ("JSON_OBJECT(...)", "Create JSON object"),
                 ("JSON_EXTRACT(json, path)", "Extract JSON value"),
                 ("JSON_TYPE(json, path)", "Get JSON type"),
+                ("JSON_SET(json, path, val)", "Insert or update JSON value"),
+                ("JSON_INSERT(json, path, val)", "Insert JSON value if missing"),
+                ("JSON_REPLACE(json, path, val)", "Replace existing JSON value"),
+                ("JSON_REMOVE(json, path)", "Remove JSON value"),
+                ("JSON_PATCH(target, patch)", "Merge JSON (RFC 7396)"),
+                ("JSON_VALID(json)", "Check if valid JSON"),
+                ("JSON_ARRAY_LENGTH(json)", "JSON array length"),
+                ("JSON_QUOTE(val)", "Quote value as JSON"),
+                ("JSON_GROUP_ARRAY(x)", "Aggregate to JSON array"),
+                ("JSON_GROUP_OBJECT(k, v)", "Aggregate to JSON object"),
+                ("JSON_EACH(json)", "Expand JSON to rows"),
+                ("JSON_TREE(json)", "Walk JSON recursively"),
+                ("NTH_VALUE(col, n)", "Nth value in frame"),
+                ("PERCENT_RANK()", "Relative rank"),
+                ("CUME_DIST()", "Cumulative distribution"),
+                ("TOTAL(x)", "Sum as float (0.0 if empty)"),
+                ("QUOTE(x)", "SQL literal of value"),
                 ("GROUP_CONCAT(x, sep)", "Concatenate group values"),
             ],
             operators: vec![
@@ -298,6 +382,9 @@ impl DatabasePlugin for SqlitePlugin {
                 ("->>", "JSON extract (text)"),
                 ("GLOB", "Unix pattern match"),
                 ("REGEXP", "Regular expression (if loaded)"),
+                ("IS", "NULL-safe equality"),
+                ("IS NOT", "NULL-safe inequality"),
+                ("MATCH", "Full-text search match"),
             ],
             data_types: vec![
                 ("INTEGER", "Signed integer"),
@@ -305,6 +392,10 @@ impl DatabasePlugin for SqlitePlugin {
                 ("TEXT", "UTF-8 text"),
                 ("BLOB", "Binary data"),
                 ("NUMERIC", "Numeric affinity"),
+                ("VARCHAR(N)", "Text affinity"),
+                ("BOOLEAN", "Numeric affinity (0/1)"),
+                ("DATETIME", "Numeric affinity, stored as text or number"),
+                ("ANY", "Any type (STRICT tables)"),
             ],
             snippets: vec![
                 ("crt", "CREATE TABLE $1 (\n  id INTEGER PRIMARY KEY AUTOINCREMENT,\n  $2\n)", "Create table"),
@@ -312,6 +403,7 @@ impl DatabasePlugin for SqlitePlugin {
                 ("uidx", "CREATE UNIQUE INDEX $1 ON $2 ($3)", "Create unique index"),
                 ("vac", "VACUUM", "Vacuum database"),
                 ("pragma", "PRAGMA $1", "Pragma statement"),
+                ("ups", "INSERT INTO $1 ($2) VALUES ($3)\nON CONFLICT ($4) DO UPDATE SET $5", "Upsert"),
             ],
         }.with_standard_sql()
     }
@@ -322,42 +414,50 @@ impl DatabasePlugin for SqlitePlugin {
         Ok(Box::new(conn))
     }
 
-    async fn list_databases(&self, _connection: &dyn DbConnection) -> Result<Vec<String>> {
-        Ok(vec!["main".to_string()])
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        Ok(self.database_list(connection).await?.into_iter().map(|(name, _)| name).collect())
     }
 
-    async fn list_databases_view(&self, _connection: &dyn DbConnection) -> Result<ObjectView> {
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
         use gpui::px;
 
+        let databases = self.database_list(connection).await?;
+
         let columns = vec![
             Column::new("name", "Name").width(px(180.0)),
+            Column::new("file", "File").width(px(400.0)),
         ];
 
-        let rows = vec![vec!["main".to_string()]];
+        let rows: Vec<Vec<String>> = databases.iter().map(|(name, file)| {
+            vec![name.clone(), file.clone()]
+        }).collect();
 
         Ok(ObjectView {
             db_node_type: DbNodeType::Database,
-            title: "1 database(s)".to_string(),
+            title: format!("{} database(s)", databases.len()),
             columns,
             rows,
         })
     }
 
-    async fn list_databases_detailed(&self, _connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
-        Ok(vec![DatabaseInfo {
-            name: "main".to_string(),
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        Ok(self.database_list(connection).await?.into_iter().map(|(name, file)| DatabaseInfo {
+            name,
             charset: None,
             collation: None,
             size: None,
             table_count: None,
-            comment: None,
-        }])
+            comment: (!file.is_empty()).then_some(file),
+        }).collect())
     }
 
-    async fn list_tables(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<TableInfo>> {
-        let sql = "SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name";
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        let sql = format!(
+            "SELECT name FROM {} WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+            Self::master_table(database)
+        );
 
-        let result = connection.query(sql, None, ExecOptions::default())
+        let result = connection.query(&sql, None, ExecOptions::default())
             .await
             .map_err(|e| anyhow::anyhow!("Failed to list tables: {}", e))?;
 
@@ -400,8 +500,8 @@ impl DatabasePlugin for SqlitePlugin {
         })
     }
 
-    async fn list_columns(&self, connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
-        let sql = format!("PRAGMA table_info(\"{}\")", table);
+    async fn list_columns(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let sql = format!("PRAGMA {}(\"{}\")", Self::pragma(database, "table_info"), table);
         tracing::info!("SQLite list_columns: executing SQL: {}", sql);
 
         let result = connection.query(&sql, None, ExecOptions::default())
@@ -423,6 +523,7 @@ impl DatabasePlugin for SqlitePlugin {
                     is_primary_key: row.get(5).and_then(|v| v.clone()).map(|v| v == "1").unwrap_or(false),
                     default_value: row.get(4).and_then(|v| v.clone()),
                     comment: None,
+                    collation: None,
                 }
             }).collect())
         } else {
@@ -461,8 +562,8 @@ impl DatabasePlugin for SqlitePlugin {
         })
     }
 
-    async fn list_indexes(&self, connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<IndexInfo>> {
-        let sql = format!("PRAGMA index_list(\"{}\")", table);
+    async fn list_indexes(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, table: &str) -> Result<Vec<IndexInfo>> {
+        let sql = format!("PRAGMA {}(\"{}\")", Self::pragma(database, "index_list"), table);
 
         let result = connection.query(&sql, None, ExecOptions::default())
             .await
@@ -475,7 +576,7 @@ impl DatabasePlugin for SqlitePlugin {
                 let index_name = row.get(1).and_then(|v| v.clone()).unwrap_or_default();
                 let is_unique = row.get(2).and_then(|v| v.clone()).map(|v| v == "1").unwrap_or(false);
 
-                let info_sql = format!("PRAGMA index_info(\"{}\")", index_name);
+                let info_sql = format!("PRAGMA {}(\"{}\")", Self::pragma(database, "index_info"), index_name);
                 let info_result = connection.query(&info_sql, None, ExecOptions::default()).await;
 
                 let columns = if let Ok(SqlResult::Query(info_query)) = info_result {
@@ -527,10 +628,10 @@ impl DatabasePlugin for SqlitePlugin {
         })
     }
 
-    async fn list_views(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<ViewInfo>> {
-        let sql = "SELECT name, sql FROM sqlite_master WHERE type='view' ORDER BY name";
+    async fn list_views(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<ViewInfo>> {
+        let sql = format!("SELECT name, sql FROM {} WHERE type='view' ORDER BY name", Self::master_table(database));
 
-        let result = connection.query(sql, None, ExecOptions::default())
+        let result = connection.query(&sql, None, ExecOptions::default())
             .await
             .map_err(|e| anyhow::anyhow!("Failed to list views: {}", e))?;
 
@@ -619,10 +720,13 @@ impl DatabasePlugin for SqlitePlugin {
         })
     }
 
-    async fn list_triggers(&self, connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
-        let sql = "SELECT name, tbl_name, sql FROM sqlite_master WHERE type='trigger' ORDER BY name";
+    async fn list_triggers(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TriggerInfo>> {
+        let sql = format!(
+            "SELECT name, tbl_name, sql FROM {} WHERE type='trigger' ORDER BY name",
+            Self::master_table(database)
+        );
 
-        let result = connection.query(sql, None, ExecOptions::default())
+        let result = connection.query(&sql, None, ExecOptions::default())
             .await
             .map_err(|e| anyhow::anyhow!("Failed to list triggers: {}", e))?;
 
@@ -666,6 +770,30 @@ impl DatabasePlugin for SqlitePlugin {
         })
     }
 
+    async fn get_routine_source(&self, connection: &dyn DbConnection, database: &str, _schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        // SQLite 没有存储过程和函数，只有触发器保存了定义
+        if object_type != DbNodeType::Trigger {
+            return Err(anyhow::anyhow!("{} source is not supported: {}", object_type, name));
+        }
+        let sql = format!(
+            "SELECT sql FROM {} WHERE type='trigger' AND name='{}'",
+            Self::master_table(database),
+            name.replace("'", "''")
+        );
+
+        let result = connection.query(&sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to load trigger source: {}", e))?;
+
+        match result {
+            SqlResult::Query(query_result) => query_result.rows.first()
+                .and_then(|row| row.first().cloned().flatten())
+                .ok_or_else(|| anyhow::anyhow!("Trigger not found: {}", name)),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("{}", err.message)),
+            _ => Err(anyhow::anyhow!("Unexpected result type")),
+        }
+    }
+
     async fn list_sequences(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SequenceInfo>> {
         Ok(Vec::new())
     }
@@ -735,20 +863,21 @@ impl DatabasePlugin for SqlitePlugin {
         "-- SQLite: delete the database file to drop the database".to_string()
     }
 
-    fn drop_table(&self, _database: &str, table: &str) -> String {
-        format!("DROP TABLE IF EXISTS \"{}\"", table)
+    fn drop_table(&self, database: &str, table: &str) -> String {
+        format!("DROP TABLE IF EXISTS {}", Self::qualified(database, table))
     }
 
-    fn truncate_table(&self, _database: &str, table: &str) -> String {
-        format!("DELETE FROM \"{}\"", table)
+    fn truncate_table(&self, database: &str, table: &str) -> String {
+        format!("DELETE FROM {}", Self::qualified(database, table))
     }
 
-    fn rename_table(&self, _database: &str, old_name: &str, new_name: &str) -> String {
-        format!("ALTER TABLE \"{}\" RENAME TO \"{}\"", old_name, new_name)
+    /// The new name stays unqualified: a table cannot be moved to another database
+    fn rename_table(&self, database: &str, old_name: &str, new_name: &str) -> String {
+        format!("ALTER TABLE {} RENAME TO \"{}\"", Self::qualified(database, old_name), new_name)
     }
 
-    fn drop_view(&self, _database: &str, view: &str) -> String {
-        format!("DROP VIEW IF EXISTS \"{}\"", view)
+    fn drop_view(&self, database: &str, view: &str) -> String {
+        format!("DROP VIEW IF EXISTS {}", Self::qualified(database, view))
     }
 
     fn build_column_def(&self, col: &ColumnDefinition) -> String {
@@ -826,6 +955,15 @@ impl DatabasePlugin for SqlitePlugin {
         sql
     }
 
+    fn build_explain_sql(&self, sql: &str) -> Option<String> {
+        Some(format!("EXPLAIN QUERY PLAN {}", sql.trim().trim_end_matches(';')))
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        // An embedded database has no server process or sessions of its own
+        query_server_status(connection, "SELECT 'SQLite ' || sqlite_version()").await
+    }
+
     fn build_limit_clause(&self) -> String {
         String::new()
     }
@@ -866,6 +1004,7 @@ impl DatabasePlugin for SqlitePlugin {
                 field_type: FieldType::from_db_type(&c.data_type),
                 nullable: c.is_nullable,
                 is_primary_key: c.is_primary_key,
+                collation: c.collation.clone(),
                 index: i,
             })
             .collect();
@@ -955,8 +1094,8 @@ impl DatabasePlugin for SqlitePlugin {
 
         // SQLite: use table name only (no database prefix needed)
         let count_sql = format!(
-            "SELECT COUNT(*) FROM \"{}\"{}",
-            request.table, where_clause
+            "SELECT COUNT(*) FROM {}{}",
+            Self::qualified(&request.database, &request.table), where_clause
         );
 
         // Get total count
@@ -974,15 +1113,15 @@ impl DatabasePlugin for SqlitePlugin {
         // Build data query with pagination
         let data_sql = if request.page_size == 0 {
             format!(
-                "SELECT * FROM \"{}\"{}{}",
-                request.table,
+                "SELECT * FROM {}{}{}",
+                Self::qualified(&request.database, &request.table),
                 where_clause,
                 order_clause
             )
         } else {
             format!(
-                "SELECT * FROM \"{}\"{}{} LIMIT {} OFFSET {}",
-                request.table,
+                "SELECT * FROM {}{}{} LIMIT {} OFFSET {}",
+                Self::qualified(&request.database, &request.table),
                 where_clause,
                 order_clause,
                 request.page_size,
@@ -1043,14 +1182,24 @@ impl DatabasePlugin for SqlitePlugin {
         }
     }
 
+    fn insert_conflict_clause(&self, mode: SqlConflictMode, columns: &[String], key_columns: &[String]) -> Option<InsertConflictClause> {
+        on_conflict_clause(self, mode, columns, key_columns)
+    }
+
+    /// Has no effect inside a transaction, so the pragma must run before the script opens one
+    fn foreign_key_checks_sql(&self, enabled: bool) -> Option<String> {
+        Some(format!("PRAGMA foreign_keys = {}", if enabled { "ON" } else { "OFF" }))
+    }
+
     async fn export_table_create_sql(
         &self,
         connection: &dyn DbConnection,
-        _database: &str,
+        database: &str,
         table: &str,
     ) -> Result<String> {
         let query = format!(
-            "SELECT sql FROM sqlite_master WHERE type='table' AND name='{}'",
+            "SELECT sql FROM {} WHERE type='table' AND name='{}'",
+            Self::master_table(database),
             table.replace('\'', "''")
         );
         let result = connection.query(&query, None, ExecOptions::default()).await
@@ -1099,6 +1248,15 @@ mod tests {
         assert_eq!(plugin.quote_identifier("col\"umn"), "\"col\"\"umn\"");
     }
 
+    #[test]
+    fn test_build_explain_sql() {
+        let plugin = create_plugin();
+        assert_eq!(
+            plugin.build_explain_sql("SELECT * FROM users WHERE id = 1;").as_deref(),
+            Some("EXPLAIN QUERY PLAN SELECT * FROM users WHERE id = 1")
+        );
+    }
+
     // ==================== DDL SQL Generation Tests ====================
 
     #[test]
@@ -1135,6 +1293,19 @@ mod tests {
         assert!(sql.contains("\"my_view\""));
     }
 
+    #[test]
+    fn test_attached_database_qualifies_objects() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.drop_table("archive", "users"), "DROP TABLE IF EXISTS \"archive\".\"users\"");
+        assert_eq!(
+            plugin.rename_table("archive", "old_name", "new_name"),
+            "ALTER TABLE \"archive\".\"old_name\" RENAME TO \"new_name\""
+        );
+        assert_eq!(SqlitePlugin::master_table("archive"), "\"archive\".sqlite_master");
+        assert_eq!(SqlitePlugin::pragma("archive", "table_info"), "\"archive\".table_info");
+        assert_eq!(SqlitePlugin::pragma("main", "table_info"), "table_info");
+    }
+
     // ==================== Database Operations Tests ====================
 
     #[test]
@@ -1355,6 +1526,11 @@ mod tests {
         assert!(!info.functions.is_empty());
         assert!(!info.data_types.is_empty());
         assert!(!info.snippets.is_empty());
+
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("LAG")));
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("JSON_GROUP_ARRAY")));
+        assert!(info.functions.iter().any(|(f, _)| f.starts_with("UNIXEPOCH")));
+        assert!(info.keywords.iter().any(|(k, _)| *k == "OVER"));
     }
 }
 
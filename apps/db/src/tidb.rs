@@ -0,0 +1,5 @@
+pub mod plan;
+pub mod plugin;
+
+pub use plan::{parse_tidb_plan, PlanOperator, PlanStore};
+pub use plugin::TiDbPlugin;
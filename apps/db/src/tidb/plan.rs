@@ -0,0 +1,232 @@
+//! Parsing of TiDB `EXPLAIN` / `EXPLAIN ANALYZE` output into an operator tree.
+//!
+//! TiDB returns the plan as rows whose `id` column carries the tree shape as a text prefix
+//! (`└─`, `├─`, `│ `, two characters per level), followed by the operator id and an optional
+//! `(Build)` / `(Probe)` role.
+
+use crate::executor::QueryResult;
+
+/// Where an operator runs, read from the `task` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanStore {
+    /// Executed by the TiDB server itself
+    Root,
+    /// Coprocessor task pushed down to TiKV
+    TiKv,
+    /// Coprocessor or MPP task on the TiFlash columnar replicas
+    TiFlash,
+}
+
+/// One operator of a TiDB execution plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanOperator {
+    pub depth: usize,
+    /// Operator id such as `IndexRangeScan_8`
+    pub id: String,
+    /// `Build` or `Probe` side of a join or index lookup
+    pub role: Option<String>,
+    pub est_rows: Option<f64>,
+    /// Only present for EXPLAIN ANALYZE
+    pub act_rows: Option<f64>,
+    pub task: String,
+    pub access_object: String,
+    pub execution_info: String,
+    pub operator_info: String,
+    pub memory: String,
+    pub disk: String,
+}
+
+impl PlanOperator {
+    /// Operator kind without the numeric suffix, e.g. `IndexRangeScan`
+    pub fn operator_name(&self) -> &str {
+        match self.id.rsplit_once('_') {
+            Some((name, suffix)) if !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) => name,
+            _ => &self.id,
+        }
+    }
+
+    pub fn store(&self) -> PlanStore {
+        let task = self.task.to_ascii_lowercase();
+        if task.contains("tiflash") {
+            PlanStore::TiFlash
+        } else if task.contains("tikv") {
+            PlanStore::TiKv
+        } else {
+            PlanStore::Root
+        }
+    }
+
+    /// Wall time of the operator from the leading `time:` entry of the execution info
+    pub fn execution_time(&self) -> Option<&str> {
+        let rest = self.execution_info.trim_start().strip_prefix("time:")?;
+        let end = rest.find([',', ' ']).unwrap_or(rest.len());
+        Some(&rest[..end]).filter(|time| !time.is_empty())
+    }
+
+    /// Full table scans on TiKV read every region of the table
+    pub fn is_full_scan(&self) -> bool {
+        self.operator_name() == "TableFullScan" && self.store() == PlanStore::TiKv
+    }
+
+    /// Actual rows differ from the estimate by more than 10x, a hint that statistics are stale
+    pub fn is_misestimated(&self) -> bool {
+        match (self.est_rows, self.act_rows) {
+            (Some(est), Some(act)) => {
+                let (low, high) = if est < act { (est, act) } else { (act, est) };
+                high >= 100.0 && high > low.max(1.0) * 10.0
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Split the `id` cell into tree depth, operator id and role
+fn parse_operator_id(cell: &str) -> (usize, String, Option<String>) {
+    let prefix_len = cell
+        .chars()
+        .take_while(|c| matches!(c, ' ' | '│' | '├' | '└' | '─'))
+        .count();
+    let name: String = cell.chars().skip(prefix_len).collect();
+    let (id, role) = match name.strip_suffix(')').and_then(|rest| rest.rsplit_once('(')) {
+        Some((id, role)) => (id.to_string(), Some(role.to_string())),
+        None => (name, None),
+    };
+    (prefix_len / 2, id, role)
+}
+
+fn parse_rows(value: Option<&str>) -> Option<f64> {
+    value.and_then(|v| v.trim().parse::<f64>().ok())
+}
+
+/// Parse the result of `EXPLAIN` or `EXPLAIN ANALYZE`; columns are looked up by name so
+/// both forms and older TiDB versions (`count` instead of `estRows`) are accepted
+pub fn parse_tidb_plan(result: &QueryResult) -> Vec<PlanOperator> {
+    let index_of = |names: &[&str]| {
+        result
+            .columns
+            .iter()
+            .position(|column| names.iter().any(|name| column.eq_ignore_ascii_case(name)))
+    };
+    let id_index = index_of(&["id"]).unwrap_or(0);
+    let est_index = index_of(&["estRows", "count"]);
+    let act_index = index_of(&["actRows"]);
+    let task_index = index_of(&["task"]);
+    let access_index = index_of(&["access object"]);
+    let execution_index = index_of(&["execution info"]);
+    let operator_index = index_of(&["operator info"]);
+    let memory_index = index_of(&["memory"]);
+    let disk_index = index_of(&["disk"]);
+
+    result
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let cell = |index: Option<usize>| index.and_then(|i| row.get(i)).and_then(|v| v.as_deref());
+            let text = |index: Option<usize>| cell(index).unwrap_or("").to_string();
+            let (depth, id, role) = parse_operator_id(cell(Some(id_index))?);
+            Some(PlanOperator {
+                depth,
+                id,
+                role,
+                est_rows: parse_rows(cell(est_index)),
+                act_rows: parse_rows(cell(act_index)),
+                task: text(task_index),
+                access_object: text(access_index),
+                execution_info: text(execution_index),
+                operator_info: text(operator_index),
+                memory: text(memory_index),
+                disk: text(disk_index),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze_result() -> QueryResult {
+        let row = |id: &str, est: &str, act: &str, task: &str, info: &str| {
+            vec![
+                Some(id.to_string()),
+                Some(est.to_string()),
+                Some(act.to_string()),
+                Some(task.to_string()),
+                Some(String::new()),
+                Some(info.to_string()),
+                Some(String::new()),
+                Some("N/A".to_string()),
+                Some("N/A".to_string()),
+            ]
+        };
+        QueryResult {
+            sql: String::new(),
+            columns: ["id", "estRows", "actRows", "task", "access object", "execution info", "operator info", "memory", "disk"]
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            rows: vec![
+                row("HashJoin_8", "12.50", "5000", "root", "time:12.3ms, loops:2"),
+                row("├─TableReader_15(Build)", "10.00", "10", "root", "time:1.1ms, loops:2"),
+                row("│ └─TableFullScan_14", "10.00", "10", "cop[tikv]", "tikv_task:{time:0s}"),
+                row("└─TableReader_12(Probe)", "10000.00", "5000", "root", "time:9ms"),
+                row("  └─TableFullScan_11", "10000.00", "5000", "mpp[tiflash]", "tiflash_task:{time:8ms}"),
+            ],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_tree_shape() {
+        let plan = parse_tidb_plan(&analyze_result());
+        let shape: Vec<(usize, &str, Option<&str>)> = plan
+            .iter()
+            .map(|op| (op.depth, op.id.as_str(), op.role.as_deref()))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                (0, "HashJoin_8", None),
+                (1, "TableReader_15", Some("Build")),
+                (2, "TableFullScan_14", None),
+                (1, "TableReader_12", Some("Probe")),
+                (2, "TableFullScan_11", None),
+            ]
+        );
+        assert_eq!(plan[0].operator_name(), "HashJoin");
+    }
+
+    #[test]
+    fn test_operator_annotations() {
+        let plan = parse_tidb_plan(&analyze_result());
+        assert_eq!(plan[0].execution_time(), Some("12.3ms"));
+        assert_eq!(plan[2].execution_time(), None);
+        assert!(plan[0].is_misestimated());
+        assert!(!plan[3].is_misestimated());
+        assert!(plan[2].is_full_scan());
+        assert_eq!(plan[4].store(), PlanStore::TiFlash);
+        assert!(!plan[4].is_full_scan());
+    }
+
+    #[test]
+    fn test_parse_plain_explain() {
+        let result = QueryResult {
+            columns: vec!["id".to_string(), "estRows".to_string(), "task".to_string(), "access object".to_string(), "operator info".to_string()],
+            rows: vec![vec![
+                Some("Point_Get_1".to_string()),
+                Some("1.00".to_string()),
+                Some("root".to_string()),
+                Some("table:t".to_string()),
+                Some("handle:1".to_string()),
+            ]],
+            ..analyze_result()
+        };
+        let plan = parse_tidb_plan(&result);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].operator_name(), "Point_Get");
+        assert_eq!(plan[0].act_rows, None);
+        assert_eq!(plan[0].access_object, "table:t");
+    }
+}
@@ -0,0 +1,391 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig};
+
+use crate::connection::{DbConnection, DbError};
+use crate::mysql::{MySqlPlugin, MysqlDbConnection};
+use crate::plugin::{DatabasePlugin, SqlCompletionInfo};
+use crate::import_export::{InsertConflictClause, SqlConflictMode};
+use crate::server_status::ServerStatus;
+use crate::trash::TrashEntry;
+use crate::types::*;
+
+/// TiDB plugin. TiDB speaks the MySQL protocol and dialect, so catalog queries and DDL are
+/// delegated to [`MySqlPlugin`]; TiDB has no stored programs, and adds TiFlash replicas,
+/// placement rules and its own plan format on top.
+pub struct TiDbPlugin {
+    mysql: MySqlPlugin,
+}
+
+impl TiDbPlugin {
+    pub fn new() -> Self {
+        Self {
+            mysql: MySqlPlugin::new(),
+        }
+    }
+
+    /// TiFlash replicas of the tables in `database` with their replication progress
+    pub fn build_list_tiflash_replicas_sql(&self, database: &str) -> String {
+        format!(
+            "SELECT TABLE_NAME, REPLICA_COUNT, LOCATION_LABELS, AVAILABLE, PROGRESS \
+             FROM information_schema.tiflash_replica \
+             WHERE TABLE_SCHEMA = {} \
+             ORDER BY TABLE_NAME",
+            self.string_literal(database)
+        )
+    }
+
+    /// Setting the count to 0 removes the TiFlash replicas of the table
+    pub fn build_set_tiflash_replica_sql(&self, database: &str, table: &str, count: u32) -> String {
+        format!(
+            "ALTER TABLE {} SET TIFLASH REPLICA {}",
+            self.format_table_reference(database, None, table),
+            count
+        )
+    }
+
+    /// Placement of every database, table and partition that has a placement policy or rule attached
+    pub fn build_show_placement_sql(&self) -> String {
+        "SHOW PLACEMENT".to_string()
+    }
+
+    pub fn build_list_placement_policies_sql(&self) -> String {
+        "SELECT POLICY_NAME, PRIMARY_REGION, REGIONS, CONSTRAINTS, LEADER_CONSTRAINTS, FOLLOWERS, FOLLOWER_CONSTRAINTS, LEARNERS \
+         FROM information_schema.placement_policies \
+         ORDER BY POLICY_NAME"
+            .to_string()
+    }
+
+    /// Whether a `SHOW PLACEMENT` target (`DATABASE db`, `TABLE db.t`, `TABLE db.t PARTITION p`) belongs to `database`
+    pub fn placement_target_in_database(target: &str, database: &str) -> bool {
+        let Some((kind, name)) = target.trim().split_once(' ') else {
+            return false;
+        };
+        let name = name.trim().trim_matches('`');
+        match kind.to_ascii_uppercase().as_str() {
+            "DATABASE" => name == database,
+            "TABLE" => name
+                .strip_prefix(database)
+                .is_some_and(|rest| rest.starts_with('.') || rest.starts_with("`.")),
+            _ => false,
+        }
+    }
+
+    /// EXPLAIN ANALYZE runs the statement and reports actual rows, time and memory per operator
+    pub fn build_explain_analyze_sql(&self, sql: &str) -> String {
+        format!("EXPLAIN ANALYZE {}", sql.trim().trim_end_matches(';'))
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for TiDbPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::TiDB
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        self.mysql.quote_identifier(identifier)
+    }
+
+    fn string_literal(&self, value: &str) -> String {
+        self.mysql.string_literal(value)
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        self.mysql.sql_dialect()
+    }
+
+    fn read_only_transaction_sql(&self) -> Option<(&'static str, &'static str)> {
+        self.mysql.read_only_transaction_sql()
+    }
+
+    async fn server_status(&self, connection: &dyn DbConnection) -> Result<ServerStatus> {
+        self.mysql.server_status(connection).await
+    }
+
+    fn build_list_session_variables_sql(&self) -> Option<String> {
+        self.mysql.build_list_session_variables_sql()
+    }
+
+    fn build_set_session_variable_sql(&self, name: &str, value: &str) -> Option<String> {
+        self.mysql.build_set_session_variable_sql(name, value)
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        let mut info = self.mysql.get_completion_info();
+        info.keywords.extend([
+            ("AUTO_RANDOM", "Random primary key to avoid write hotspots"),
+            ("SHARD_ROW_ID_BITS", "Scatter implicit row ids"),
+            ("PRE_SPLIT_REGIONS", "Pre-split regions on create"),
+            ("SET TIFLASH REPLICA", "Create TiFlash columnar replicas"),
+            ("PLACEMENT POLICY", "Placement policy for data"),
+            ("SHOW PLACEMENT", "Show placement of objects"),
+            ("SPLIT TABLE", "Split table regions"),
+            ("EXPLAIN ANALYZE", "Run and show actual plan statistics"),
+        ]);
+        info.functions.extend([
+            ("TIDB_VERSION()", "TiDB build information"),
+            ("TIDB_PARSE_TSO(tso)", "Convert a TSO to a timestamp"),
+            ("TIDB_BOUNDED_STALENESS(t1, t2)", "Stale read bounds"),
+            ("TIDB_DECODE_KEY(key)", "Decode a TiKV key"),
+        ]);
+        info
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = MysqlDbConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    // === Database/Schema Level Operations ===
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        self.mysql.list_databases(connection).await
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        self.mysql.list_databases_view(connection).await
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        self.mysql.list_databases_detailed(connection).await
+    }
+
+    // === Table Operations ===
+
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        self.mysql.list_tables(connection, database).await
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.mysql.list_tables_view(connection, database).await
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        self.mysql.list_columns(connection, database, schema, table).await
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        self.mysql.list_columns_view(connection, database, schema, table).await
+    }
+
+    async fn list_indexes(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<IndexInfo>> {
+        self.mysql.list_indexes(connection, database, schema, table).await
+    }
+
+    async fn list_indexes_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        self.mysql.list_indexes_view(connection, database, schema, table).await
+    }
+
+    async fn list_table_checks(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<CheckInfo>> {
+        self.mysql.list_table_checks(connection, database, schema, table).await
+    }
+
+    // === View Operations ===
+
+    async fn list_views(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<ViewInfo>> {
+        self.mysql.list_views(connection, database).await
+    }
+
+    async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.mysql.list_views_view(connection, database).await
+    }
+
+    // === Function/Procedure/Trigger Operations ===
+    // TiDB parses but does not implement stored functions, procedures or triggers
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        Ok(empty_object_view(DbNodeType::Function, "function(s)"))
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        Ok(empty_object_view(DbNodeType::Procedure, "procedure(s)"))
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        Ok(empty_object_view(DbNodeType::Trigger, "trigger(s)"))
+    }
+
+    async fn list_sequences(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<SequenceInfo>> {
+        self.mysql.list_sequences(connection, database).await
+    }
+
+    async fn list_sequences_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        self.mysql.list_sequences_view(connection, database).await
+    }
+
+    fn get_charsets(&self) -> Vec<CharsetInfo> {
+        self.mysql.get_charsets()
+    }
+
+    fn get_collations(&self, charset: &str) -> Vec<CollationInfo> {
+        self.mysql.get_collations(charset)
+    }
+
+    fn get_data_types(&self) -> Vec<DataTypeInfo> {
+        self.mysql.get_data_types()
+    }
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        self.mysql.build_column_definition(column, include_name)
+    }
+
+    fn build_create_database_sql(&self, request: &crate::plugin::DatabaseOperationRequest) -> String {
+        self.mysql.build_create_database_sql(request)
+    }
+
+    fn build_modify_database_sql(&self, request: &crate::plugin::DatabaseOperationRequest) -> String {
+        self.mysql.build_modify_database_sql(request)
+    }
+
+    fn build_drop_database_sql(&self, database_name: &str) -> String {
+        self.mysql.build_drop_database_sql(database_name)
+    }
+
+    fn rename_table(&self, database: &str, old_name: &str, new_name: &str) -> String {
+        self.mysql.rename_table(database, old_name, new_name)
+    }
+
+    fn supports_trash(&self) -> bool {
+        true
+    }
+
+    fn build_move_to_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        self.mysql.build_move_to_trash_sql(entry)
+    }
+
+    fn build_restore_from_trash_sql(&self, entry: &TrashEntry) -> Vec<String> {
+        self.mysql.build_restore_from_trash_sql(entry)
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        self.mysql.build_column_def(col)
+    }
+
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        self.mysql.build_create_table_sql(design)
+    }
+
+    fn build_alter_table_sql(&self, original: &TableDesign, new: &TableDesign) -> String {
+        self.mysql.build_alter_table_sql(original, new)
+    }
+
+    fn build_limit_clause(&self) -> String {
+        self.mysql.build_limit_clause()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        request: &crate::types::TableSaveRequest,
+        original_data: &[String],
+    ) -> (String, String) {
+        let where_clause = self.build_table_change_where_clause(request, original_data);
+        (where_clause, self.build_limit_clause())
+    }
+
+    async fn get_routine_source(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, object_type: DbNodeType, name: &str) -> Result<String> {
+        self.mysql.get_routine_source(connection, database, schema, object_type, name).await
+    }
+
+    fn insert_conflict_clause(&self, mode: SqlConflictMode, columns: &[String], key_columns: &[String]) -> Option<InsertConflictClause> {
+        self.mysql.insert_conflict_clause(mode, columns, key_columns)
+    }
+
+    fn foreign_key_checks_sql(&self, enabled: bool) -> Option<String> {
+        self.mysql.foreign_key_checks_sql(enabled)
+    }
+
+    async fn export_table_create_sql(
+        &self,
+        connection: &dyn DbConnection,
+        database: &str,
+        table: &str,
+    ) -> Result<String> {
+        // SHOW CREATE TABLE keeps TiDB-only options such as AUTO_RANDOM and placement policies
+        self.mysql.export_table_create_sql(connection, database, table).await
+    }
+}
+
+fn empty_object_view(db_node_type: DbNodeType, noun: &str) -> ObjectView {
+    use gpui::px;
+
+    ObjectView {
+        db_node_type,
+        title: format!("0 {}", noun),
+        columns: vec![Column::new("name", "Name").width(px(200.0))],
+        rows: Vec::new(),
+    }
+}
+
+impl Default for TiDbPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_plugin() -> TiDbPlugin {
+        TiDbPlugin::new()
+    }
+
+    #[test]
+    fn test_plugin_name() {
+        let plugin = create_plugin();
+        assert_eq!(plugin.name(), DatabaseType::TiDB);
+        assert!(!plugin.supports_functions());
+        assert!(!plugin.supports_procedures());
+    }
+
+    #[test]
+    fn test_tiflash_replica_sql() {
+        let plugin = create_plugin();
+        assert!(plugin.build_list_tiflash_replicas_sql("it's").contains("TABLE_SCHEMA = 'it''s'"));
+        assert_eq!(
+            plugin.build_set_tiflash_replica_sql("shop", "orders", 2),
+            "ALTER TABLE `shop`.`orders` SET TIFLASH REPLICA 2"
+        );
+    }
+
+    #[test]
+    fn test_placement_target_in_database() {
+        assert!(TiDbPlugin::placement_target_in_database("DATABASE shop", "shop"));
+        assert!(TiDbPlugin::placement_target_in_database("TABLE shop.orders", "shop"));
+        assert!(TiDbPlugin::placement_target_in_database("TABLE shop.orders PARTITION p0", "shop"));
+        assert!(!TiDbPlugin::placement_target_in_database("TABLE shop2.orders", "shop"));
+        assert!(!TiDbPlugin::placement_target_in_database("DATABASE shop2", "shop"));
+        assert!(!TiDbPlugin::placement_target_in_database("POLICY p1", "shop"));
+    }
+
+    #[test]
+    fn test_explain_analyze_sql() {
+        assert_eq!(
+            create_plugin().build_explain_analyze_sql(" SELECT * FROM t;\n"),
+            "EXPLAIN ANALYZE SELECT * FROM t"
+        );
+    }
+}
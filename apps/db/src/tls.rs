@@ -0,0 +1,136 @@
+//! TLS helpers shared by the drivers. The settings come from the SSL tab through
+//! `DbConnectionConfig::ssl`; each driver maps them onto its own TLS options.
+
+use one_core::storage::SslConfig;
+
+use crate::connection::DbError;
+
+pub fn read_pem(path: &str) -> Result<Vec<u8>, DbError> {
+    std::fs::read(path)
+        .map_err(|e| DbError::ConnectionError(format!("Failed to read certificate file {}: {}", path, e)))
+}
+
+/// Client certificate and key paths, rejecting a half-configured pair before connecting
+pub fn client_identity(ssl: &SslConfig) -> Result<Option<(&str, &str)>, DbError> {
+    ssl.client_identity().map_err(DbError::ConnectionError)
+}
+
+/// Error for drivers whose library cannot honour an SSL option, so the setting is never silently dropped
+pub fn unsupported(driver: &str, option: &str) -> DbError {
+    DbError::ConnectionError(format!("{} connections do not support {}", driver, option))
+}
+
+/// Connector for drivers built on native-tls
+pub fn native_tls_connector(ssl: &SslConfig) -> Result<native_tls::TlsConnector, DbError> {
+    let mut builder = native_tls::TlsConnector::builder();
+    builder
+        .danger_accept_invalid_certs(!ssl.mode.verifies_certificate())
+        .danger_accept_invalid_hostnames(!ssl.mode.verifies_hostname());
+
+    if let Some(ca) = &ssl.ca_cert {
+        let certificate = native_tls::Certificate::from_pem(&read_pem(ca)?)
+            .map_err(|e| DbError::ConnectionError(format!("Invalid CA certificate {}: {}", ca, e)))?;
+        builder.add_root_certificate(certificate);
+    }
+    if let Some((cert, key)) = client_identity(ssl)? {
+        let identity = native_tls::Identity::from_pkcs8(&read_pem(cert)?, &read_pem(key)?)
+            .map_err(|e| DbError::ConnectionError(format!("Invalid client certificate or key: {}", e)))?;
+        builder.identity(identity);
+    }
+
+    builder
+        .build()
+        .map_err(|e| DbError::ConnectionError(format!("Failed to set up TLS: {}", e)))
+}
+
+/// TLS settings of the HTTP based drivers. rustls cannot skip only the host name check,
+/// so verify-ca behaves like verify-full
+pub fn http_client_builder(ssl: &SslConfig, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, DbError> {
+    if !ssl.is_enabled() {
+        return Ok(builder);
+    }
+    let mut builder = builder.danger_accept_invalid_certs(!ssl.mode.verifies_certificate());
+    if let Some(ca) = &ssl.ca_cert {
+        let certificate = reqwest::Certificate::from_pem(&read_pem(ca)?)
+            .map_err(|e| DbError::ConnectionError(format!("Invalid CA certificate {}: {}", ca, e)))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    if let Some((cert, key)) = client_identity(ssl)? {
+        // rustls takes the certificate chain and the key as one PEM bundle
+        let mut pem = read_pem(cert)?;
+        pem.push(b'\n');
+        pem.extend(read_pem(key)?);
+        let identity = reqwest::Identity::from_pem(&pem)
+            .map_err(|e| DbError::ConnectionError(format!("Invalid client certificate or key: {}", e)))?;
+        builder = builder.identity(identity);
+    }
+    Ok(builder)
+}
+
+/// Connect failure, with a hint when the driver's message points at a TLS mismatch
+pub fn connection_error(ssl: &SslConfig, error: impl std::fmt::Display) -> DbError {
+    let message = error.to_string();
+    match tls_hint(ssl.is_enabled(), &message) {
+        Some(hint) => DbError::ConnectionError(format!("Failed to connect: {} ({})", message, hint)),
+        None => DbError::ConnectionError(format!("Failed to connect: {}", message)),
+    }
+}
+
+/// Driver messages differ per database, so the hint is picked from well-known fragments
+fn tls_hint(ssl_enabled: bool, message: &str) -> Option<&'static str> {
+    const SERVER_REQUIRES_TLS: &[&str] = &[
+        "insecure transport",
+        "require_secure_transport",
+        "no encryption",
+        "ssl required",
+        "requires ssl",
+        "encryption required",
+        "ssl off",
+    ];
+    const CERTIFICATE_REJECTED: &[&str] = &[
+        "certificate verify failed",
+        "self signed",
+        "self-signed",
+        "unknown ca",
+        "unknownissuer",
+        "invalid peer certificate",
+        "not valid for name",
+        "hostname mismatch",
+    ];
+    const SERVER_WITHOUT_TLS: &[&str] = &["does not support tls", "does not support ssl"];
+
+    let message = message.to_lowercase();
+    let mentions = |fragments: &[&str]| fragments.iter().any(|fragment| message.contains(fragment));
+
+    if !ssl_enabled && mentions(SERVER_REQUIRES_TLS) {
+        Some("the server requires an encrypted connection, enable SSL in the SSL tab")
+    } else if ssl_enabled && mentions(CERTIFICATE_REJECTED) {
+        Some("the server certificate was rejected, configure its CA certificate or use SSL mode require")
+    } else if ssl_enabled && mentions(SERVER_WITHOUT_TLS) {
+        Some("the server does not accept encrypted connections, set SSL mode to disable")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls_hint() {
+        let mysql = "Server error: `ERROR HY000 (3159): Connections using insecure transport are prohibited while --require_secure_transport=ON.'";
+        assert!(tls_hint(false, mysql).is_some_and(|hint| hint.contains("enable SSL")));
+        let postgres = "db error: FATAL: no pg_hba.conf entry for host \"10.0.0.5\", user \"app\", database \"app\", no encryption";
+        assert!(tls_hint(false, postgres).is_some_and(|hint| hint.contains("enable SSL")));
+
+        let rustls = "invalid peer certificate: UnknownIssuer";
+        assert!(tls_hint(true, rustls).is_some_and(|hint| hint.contains("CA certificate")));
+        assert!(tls_hint(true, "error performing TLS handshake: server does not support TLS")
+            .is_some_and(|hint| hint.contains("disable")));
+
+        assert_eq!(tls_hint(true, "Access denied for user 'app'@'10.0.0.5'"), None);
+        // The same message is not blamed on TLS when the setting cannot be the cause
+        assert_eq!(tls_hint(false, rustls), None);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::executor::QueryResult;
+
+/// 回收站所在的 schema（MySQL 中为数据库）
+pub const TRASH_SCHEMA: &str = "_onehub_trash";
+/// 记录回收站中表原始位置的索引表
+pub const TRASH_INDEX_TABLE: &str = "_onehub_trash_index";
+
+/// 表名长度上限（PostgreSQL 为 63 字节，MySQL 为 64 字符）
+const MAX_TRASH_NAME_BYTES: usize = 63;
+/// 回收站表名中随机后缀的长度
+const TRASH_NAME_SUFFIX_LEN: usize = 6;
+
+/// 回收站中的一张表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashEntry {
+    /// 在回收站中的表名
+    pub trash_name: String,
+    /// 原所在的 schema（MySQL 中为数据库）
+    pub original_schema: String,
+    pub original_name: String,
+    /// 移入回收站的时间（Unix 秒）
+    pub dropped_at: i64,
+}
+
+impl TrashEntry {
+    /// 从 `build_list_trash_sql` 的查询结果解析回收站条目
+    pub fn from_query_result(result: &QueryResult) -> Vec<TrashEntry> {
+        result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let value = |i: usize| row.get(i).cloned().flatten();
+                Some(TrashEntry {
+                    trash_name: value(0)?,
+                    original_schema: value(1)?,
+                    original_name: value(2)?,
+                    dropped_at: value(3)?.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 生成回收站中的表名：`<时间戳>_<随机后缀>_<原表名>`，超长时截断原表名。
+/// 同一秒内删除两次同名表时时间戳相同，靠随机后缀区分
+pub fn trash_table_name(original_name: &str, dropped_at: i64) -> String {
+    let unique = uuid::Uuid::new_v4().simple().to_string();
+    let mut name = format!("{}_{}_{}", dropped_at, &unique[..TRASH_NAME_SUFFIX_LEN], original_name);
+    if name.len() > MAX_TRASH_NAME_BYTES {
+        let mut end = MAX_TRASH_NAME_BYTES;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_table_name_is_truncated_on_char_boundary() {
+        let name = trash_table_name("users", 1700000000);
+        assert!(name.starts_with("1700000000_") && name.ends_with("_users"));
+        assert_eq!(name.len(), "1700000000__users".len() + TRASH_NAME_SUFFIX_LEN);
+
+        let long_name = "表".repeat(30);
+        let name = trash_table_name(&long_name, 1700000000);
+        assert!(name.len() <= MAX_TRASH_NAME_BYTES);
+        assert!(name.ends_with('表'));
+    }
+
+    #[test]
+    fn test_trash_table_names_within_one_second_differ() {
+        assert_ne!(trash_table_name("users", 1700000000), trash_table_name("users", 1700000000));
+    }
+
+    #[test]
+    fn test_entries_from_query_result() {
+        let result = QueryResult {
+            sql: String::new(),
+            columns: vec![],
+            rows: vec![
+                vec![
+                    Some("1700000000_users".to_string()),
+                    Some("app".to_string()),
+                    Some("users".to_string()),
+                    Some("1700000000".to_string()),
+                ],
+                vec![None, None, None, None],
+            ],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        };
+
+        let entries = TrashEntry::from_query_result(&result);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].original_name, "users");
+        assert_eq!(entries[0].dropped_at, 1700000000);
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use one_core::storage::DbConnectionConfig;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use tokio::sync::mpsc;
+
+use crate::connection::{DbConnection, DbError, StreamingProgress};
+use crate::executor::{ExecOptions, SqlErrorInfo, SqlResult};
+use crate::trino::protocol::{
+    QueryResults, StatementPages, HEADER_CATALOG, HEADER_SCHEMA, HEADER_SET_CATALOG, HEADER_SET_SCHEMA,
+    HEADER_SOURCE, HEADER_USER,
+};
+use crate::{proxy, tls, DatabasePlugin, SqlValue};
+
+/// Retries of a poll answered with 429/502/503/504 while the coordinator is busy
+const MAX_BUSY_RETRIES: u32 = 10;
+
+/// Queries in flight per connection id. The session running a script stays locked while it
+/// polls, so cancellation goes through this registry instead of the connection itself.
+static RUNNING_QUERIES: Lazy<Mutex<HashMap<String, HashMap<String, RunningQuery>>>> = Lazy::new(Default::default);
+
+#[derive(Clone)]
+struct RunningQuery {
+    http: TrinoHttp,
+    next_uri: String,
+}
+
+fn register_running_query(connection_id: &str, query_id: &str, http: &TrinoHttp, next_uri: &str) {
+    if let Ok(mut running) = RUNNING_QUERIES.lock() {
+        running.entry(connection_id.to_string()).or_default().insert(
+            query_id.to_string(),
+            RunningQuery { http: http.clone(), next_uri: next_uri.to_string() },
+        );
+    }
+}
+
+fn unregister_running_query(connection_id: &str, query_id: &str) {
+    if let Ok(mut running) = RUNNING_QUERIES.lock() {
+        if let Some(queries) = running.get_mut(connection_id) {
+            queries.remove(query_id);
+            if queries.is_empty() {
+                running.remove(connection_id);
+            }
+        }
+    }
+}
+
+/// Cancel every query still running on the connection; returns how many were cancelled
+pub async fn cancel_running_queries(connection_id: &str) -> usize {
+    let queries: Vec<RunningQuery> = match RUNNING_QUERIES.lock() {
+        Ok(mut running) => running.remove(connection_id).map(|queries| queries.into_values().collect()).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    let mut cancelled = 0;
+    for query in queries {
+        if query.http.cancel(&query.next_uri).await {
+            cancelled += 1;
+        }
+    }
+    cancelled
+}
+
+/// HTTP client with the credentials every request of the protocol carries
+#[derive(Clone)]
+struct TrinoHttp {
+    client: Client,
+    base_url: String,
+    user: String,
+    password: Option<String>,
+}
+
+impl TrinoHttp {
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let builder = self.client
+            .request(method, url)
+            .header(HEADER_USER, &self.user)
+            .header(HEADER_SOURCE, "OneHub");
+        match &self.password {
+            Some(password) => builder.basic_auth(&self.user, Some(password)),
+            None => builder,
+        }
+    }
+
+    async fn fetch(&self, method: Method, url: &str, body: Option<String>, headers: &[(&str, String)]) -> Result<(QueryResults, HeaderMap), DbError> {
+        let mut attempt = 0;
+        loop {
+            let mut request = self.request(method.clone(), url);
+            for (name, value) in headers {
+                request = request.header(*name, value);
+            }
+            if let Some(body) = &body {
+                request = request.body(body.clone());
+            }
+            let response = request.send().await.map_err(|e| DbError::QueryError(e.to_string()))?;
+            let status = response.status();
+            if matches!(status, StatusCode::TOO_MANY_REQUESTS | StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT)
+                && attempt < MAX_BUSY_RETRIES
+            {
+                attempt += 1;
+                tokio::time::sleep(Duration::from_millis(100 * attempt as u64)).await;
+                continue;
+            }
+            let response_headers = response.headers().clone();
+            let bytes = response.bytes().await.map_err(|e| DbError::QueryError(e.to_string()))?;
+            if !status.is_success() {
+                let text = String::from_utf8_lossy(&bytes);
+                return Err(DbError::QueryError(format!("HTTP {}: {}", status, text.trim())));
+            }
+            let results = serde_json::from_slice::<QueryResults>(&bytes)
+                .map_err(|e| DbError::QueryError(format!("Invalid response from Trino: {}", e)))?;
+            return Ok((results, response_headers));
+        }
+    }
+
+    /// `DELETE nextUri` stops the query on the coordinator
+    async fn cancel(&self, next_uri: &str) -> bool {
+        match self.request(Method::DELETE, next_uri).send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Catalog and schema sent with each statement; the protocol keeps no server-side session
+#[derive(Debug, Clone, Default)]
+struct TrinoSession {
+    catalog: Option<String>,
+    schema: Option<String>,
+}
+
+pub struct TrinoConnection {
+    config: DbConnectionConfig,
+    http: Option<TrinoHttp>,
+    session: Mutex<TrinoSession>,
+}
+
+impl TrinoConnection {
+    pub fn new(config: DbConnectionConfig) -> Self {
+        let session = TrinoSession {
+            catalog: config.database.clone().filter(|catalog| !catalog.is_empty()),
+            schema: config.get_param("schema").cloned().filter(|schema| !schema.is_empty()),
+        };
+        Self {
+            config,
+            http: None,
+            session: Mutex::new(session),
+        }
+    }
+
+    fn ensure_connected(&self) -> Result<&TrinoHttp, DbError> {
+        self.http
+            .as_ref()
+            .ok_or_else(|| DbError::ConnectionError("Not connected to Trino".to_string()))
+    }
+
+    fn session(&self) -> TrinoSession {
+        self.session.lock().map(|session| session.clone()).unwrap_or_default()
+    }
+
+    /// `USE catalog.schema` answers with headers the client must send from then on
+    fn apply_session_headers(&self, headers: &HeaderMap) {
+        let header_text = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let (catalog, schema) = (header_text(HEADER_SET_CATALOG), header_text(HEADER_SET_SCHEMA));
+        if catalog.is_none() && schema.is_none() {
+            return;
+        }
+        if let Ok(mut session) = self.session.lock() {
+            if catalog.is_some() {
+                session.catalog = catalog;
+            }
+            if schema.is_some() {
+                session.schema = schema;
+            }
+        }
+    }
+
+    /// Submit one statement and poll it to completion. Reading stops early once `max_rows`
+    /// rows arrived, and the remainder of the query is cancelled on the coordinator.
+    async fn run_statement(&self, sql: &str, max_rows: Option<usize>) -> Result<SqlResult, DbError> {
+        let http = self.ensure_connected()?;
+        let start = Instant::now();
+        let session = self.session();
+        let mut headers = Vec::new();
+        if let Some(catalog) = session.catalog {
+            headers.push((HEADER_CATALOG, catalog));
+        }
+        if let Some(schema) = session.schema {
+            headers.push((HEADER_SCHEMA, schema));
+        }
+
+        let url = format!("{}/v1/statement", http.base_url);
+        let (mut page, mut response_headers) = match http.fetch(Method::POST, &url, Some(sql.to_string()), &headers).await {
+            Ok(page) => page,
+            Err(e) => return Ok(SqlResult::Error(SqlErrorInfo { sql: sql.to_string(), message: e.to_string() })),
+        };
+        let query_id = page.id.clone();
+        let connection_id = self.config.id.clone();
+        let mut pages = StatementPages::default();
+
+        let outcome = loop {
+            self.apply_session_headers(&response_headers);
+            let next_uri = page.next_uri.clone();
+            let keep_reading = pages.absorb(page, max_rows);
+            let Some(next_uri) = next_uri else {
+                break Ok(());
+            };
+            if !keep_reading {
+                http.cancel(&next_uri).await;
+                break Ok(());
+            }
+            register_running_query(&connection_id, &query_id, http, &next_uri);
+            match http.fetch(Method::GET, &next_uri, None, &[]).await {
+                Ok((next_page, next_headers)) => {
+                    page = next_page;
+                    response_headers = next_headers;
+                }
+                Err(e) => break Err(e),
+            }
+        };
+        unregister_running_query(&connection_id, &query_id);
+
+        Ok(match outcome {
+            Ok(()) => pages.into_result(sql.to_string(), start.elapsed().as_millis()),
+            Err(e) => SqlResult::Error(SqlErrorInfo { sql: sql.to_string(), message: e.to_string() }),
+        })
+    }
+
+    fn statements(plugin: &Arc<dyn DatabasePlugin>, script: &str) -> Vec<String> {
+        plugin.split_statements(script)
+            .into_iter()
+            .map(|statement| statement.trim().to_string())
+            .filter(|statement| !statement.is_empty())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DbConnection for TrinoConnection {
+    fn config(&self) -> &DbConnectionConfig {
+        &self.config
+    }
+
+    fn set_config_database(&mut self, database: Option<String>) {
+        if let Ok(session) = self.session.get_mut() {
+            session.catalog = database.clone().filter(|catalog| !catalog.is_empty());
+        }
+        self.config.database = database;
+    }
+
+    async fn connect(&mut self) -> Result<(), DbError> {
+        let config = &self.config;
+        let ssl = config.ssl();
+        let scheme = if ssl.is_enabled() || config.get_param_bool("https") { "https" } else { "http" };
+        let connect_timeout = config.get_param_as::<u64>("connect_timeout").unwrap_or(30);
+        let builder = Client::builder().connect_timeout(Duration::from_secs(connect_timeout));
+        let builder = proxy::http_client_builder(config, builder)?;
+        let client = tls::http_client_builder(&ssl, builder)?
+            .build()
+            .map_err(|e| DbError::ConnectionError(e.to_string()))?;
+
+        let user = if config.username.is_empty() { "onehub".to_string() } else { config.username.clone() };
+        self.http = Some(TrinoHttp {
+            client,
+            base_url: format!("{}://{}:{}", scheme, config.host, config.port),
+            user,
+            password: Some(config.password.clone()).filter(|password| !password.is_empty()),
+        });
+
+        match self.run_statement("SELECT 1", None).await? {
+            SqlResult::Error(err) => {
+                self.http = None;
+                Err(tls::connection_error(&ssl, err.message))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn disconnect(&mut self) -> Result<(), DbError> {
+        self.http = None;
+        Ok(())
+    }
+
+    /// Statements run one by one in autocommit mode; the transactional option is not honoured
+    async fn execute(&self, plugin: Arc<dyn DatabasePlugin>, script: &str, options: ExecOptions) -> Result<Vec<SqlResult>, DbError> {
+        let mut results = Vec::new();
+        for sql in Self::statements(&plugin, script) {
+            let result = self.run_statement(&sql, options.max_rows).await?;
+            let is_error = result.is_error();
+            results.push(result);
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    async fn query(&self, query: &str, params: Option<Vec<SqlValue>>, options: ExecOptions) -> Result<SqlResult, DbError> {
+        if params.is_some() {
+            return Err(DbError::QueryError(
+                "Parameterized queries are not supported for Trino".to_string(),
+            ));
+        }
+        self.run_statement(query, options.max_rows).await
+    }
+
+    async fn current_database(&self) -> Result<Option<String>, DbError> {
+        Ok(self.session().catalog)
+    }
+
+    async fn switch_database(&self, database: &str) -> Result<(), DbError> {
+        if let Ok(mut session) = self.session.lock() {
+            session.catalog = Some(database.to_string());
+            session.schema = None;
+        }
+        Ok(())
+    }
+
+    async fn execute_streaming(
+        &self,
+        plugin: Arc<dyn DatabasePlugin>,
+        script: &str,
+        options: ExecOptions,
+        sender: mpsc::Sender<StreamingProgress>,
+    ) -> Result<(), DbError> {
+        let statements = Self::statements(&plugin, script);
+        let total = statements.len();
+
+        for (index, sql) in statements.into_iter().enumerate() {
+            let result = match self.run_statement(&sql, options.max_rows).await {
+                Ok(result) => result,
+                Err(e) => SqlResult::Error(SqlErrorInfo { sql: sql.clone(), message: e.to_string() }),
+            };
+
+            let is_error = result.is_error();
+            if sender.send(StreamingProgress { current: index + 1, total, result }).await.is_err() {
+                break;
+            }
+            if is_error && options.stop_on_error {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,650 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use gpui_component::table::Column;
+use one_core::storage::{DatabaseType, DbConnectionConfig};
+
+use crate::connection::{DbConnection, DbError};
+use crate::executor::{ExecOptions, SqlResult};
+use crate::plugin::{fallback_split_with_db_type, string_literal, DatabasePlugin, SqlCompletionInfo};
+use crate::trino::connection::cancel_running_queries;
+use crate::trino::TrinoConnection;
+use crate::types::*;
+
+fn cell(row: &[Option<String>], index: usize) -> Option<String> {
+    row.get(index).cloned().flatten()
+}
+
+/// Trino (and Presto) plugin. The tree is catalog → schema → table: catalogs take the place
+/// of databases, so every catalog query reads `<catalog>.information_schema`.
+pub struct TrinoPlugin;
+
+impl TrinoPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn query_rows(&self, connection: &dyn DbConnection, sql: &str, what: &str) -> Result<Vec<Vec<Option<String>>>> {
+        let result = connection.query(sql, None, ExecOptions::default())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list {}: {}", what, e))?;
+
+        match result {
+            SqlResult::Query(query_result) => Ok(query_result.rows),
+            SqlResult::Error(err) => Err(anyhow::anyhow!("Failed to list {}: {}", what, err.message)),
+            SqlResult::Exec(_) => Err(anyhow::anyhow!("Unexpected result type")),
+        }
+    }
+
+    /// `"catalog".information_schema.<view>`
+    fn information_schema(&self, catalog: &str, view: &str) -> String {
+        format!("{}.information_schema.{}", self.quote_identifier(catalog), view)
+    }
+
+    pub fn build_list_schemas_sql(&self, catalog: &str) -> String {
+        format!(
+            "SELECT schema_name FROM {} WHERE schema_name <> 'information_schema' ORDER BY schema_name",
+            self.information_schema(catalog, "schemata")
+        )
+    }
+
+    pub fn build_list_tables_sql(&self, catalog: &str, table_type: &str) -> String {
+        format!(
+            "SELECT table_name, table_schema FROM {} \
+             WHERE table_type = {} AND table_schema <> 'information_schema' \
+             ORDER BY table_schema, table_name",
+            self.information_schema(catalog, "tables"),
+            string_literal(table_type)
+        )
+    }
+
+    pub fn build_list_columns_sql(&self, catalog: &str, schema: &str, table: &str) -> String {
+        format!(
+            "SELECT column_name, data_type, is_nullable, column_default FROM {} \
+             WHERE table_schema = {} AND table_name = {} ORDER BY ordinal_position",
+            self.information_schema(catalog, "columns"),
+            string_literal(schema),
+            string_literal(table)
+        )
+    }
+}
+
+#[async_trait]
+impl DatabasePlugin for TrinoPlugin {
+    fn name(&self) -> DatabaseType {
+        DatabaseType::Trino
+    }
+
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace("\"", "\"\""))
+    }
+
+    fn sql_dialect(&self) -> Box<dyn sqlparser::dialect::Dialect> {
+        Box::new(sqlparser::dialect::GenericDialect {})
+    }
+
+    /// Split on the original text: re-printing through sqlparser would move OFFSET behind LIMIT
+    fn split_statements(&self, script: &str) -> Vec<String> {
+        fallback_split_with_db_type(script, DatabaseType::Trino)
+    }
+
+    fn supports_schema(&self) -> bool {
+        true
+    }
+
+    fn database_node_type(&self) -> DbNodeType {
+        DbNodeType::Catalog
+    }
+
+    fn supports_query_cancel(&self) -> bool {
+        true
+    }
+
+    async fn cancel_running_queries(&self, connection_id: &str) -> Result<usize> {
+        Ok(cancel_running_queries(connection_id).await)
+    }
+
+    /// Trino puts OFFSET before LIMIT
+    fn format_pagination(&self, limit: usize, offset: usize, _order_clause: &str) -> String {
+        format!(" OFFSET {} LIMIT {}", offset, limit)
+    }
+
+    fn format_table_reference(&self, database: &str, schema: Option<&str>, table: &str) -> String {
+        match schema {
+            Some(schema) => format!(
+                "{}.{}.{}",
+                self.quote_identifier(database),
+                self.quote_identifier(schema),
+                self.quote_identifier(table)
+            ),
+            None => format!("{}.{}", self.quote_identifier(database), self.quote_identifier(table)),
+        }
+    }
+
+    fn get_completion_info(&self) -> SqlCompletionInfo {
+        SqlCompletionInfo {
+            keywords: vec![
+                ("SHOW CATALOGS", "List catalogs"),
+                ("SHOW SCHEMAS FROM", "List schemas of a catalog"),
+                ("SHOW TABLES FROM", "List tables of a schema"),
+                ("SHOW COLUMNS FROM", "List columns of a table"),
+                ("SHOW CREATE TABLE", "Show table DDL"),
+                ("SHOW STATS FOR", "Table statistics"),
+                ("SHOW FUNCTIONS", "List functions"),
+                ("SHOW SESSION", "List session properties"),
+                ("SET SESSION", "Set a session property"),
+                ("USE", "Switch catalog.schema"),
+                ("DESCRIBE", "Describe table columns"),
+                ("EXPLAIN ANALYZE", "Run query and show distributed plan with statistics"),
+                ("EXPLAIN (TYPE DISTRIBUTED)", "Show distributed plan"),
+                ("ANALYZE", "Collect table statistics"),
+                ("CREATE TABLE AS", "Create table from query"),
+                ("UNNEST", "Expand array or map to rows"),
+                ("CROSS JOIN UNNEST", "Join with expanded array"),
+                ("TABLESAMPLE BERNOULLI", "Row-level sampling"),
+                ("FETCH FIRST", "Limit rows"),
+                ("MATCH_RECOGNIZE", "Row pattern recognition"),
+            ],
+            functions: vec![
+                ("approx_distinct(x)", "Approximate distinct count"),
+                ("approx_percentile(x, p)", "Approximate percentile"),
+                ("array_agg(x)", "Aggregate values into an array"),
+                ("map_agg(k, v)", "Aggregate into a map"),
+                ("cardinality(x)", "Size of array or map"),
+                ("element_at(container, key)", "Element of array or map"),
+                ("transform(array, lambda)", "Map array with lambda"),
+                ("filter(array, lambda)", "Filter array with lambda"),
+                ("reduce(array, init, input_fn, output_fn)", "Fold an array"),
+                ("json_extract_scalar(json, path)", "Extract scalar from JSON"),
+                ("json_parse(text)", "Parse text into JSON"),
+                ("date_trunc(unit, ts)", "Truncate timestamp"),
+                ("date_diff(unit, a, b)", "Difference between timestamps"),
+                ("date_add(unit, value, ts)", "Add interval"),
+                ("from_unixtime(seconds)", "Timestamp from epoch seconds"),
+                ("to_unixtime(ts)", "Epoch seconds of timestamp"),
+                ("format_datetime(ts, format)", "Format timestamp (Joda pattern)"),
+                ("regexp_extract(str, pattern)", "Extract first regex match"),
+                ("regexp_like(str, pattern)", "Regex match test"),
+                ("split(str, delimiter)", "Split string to array"),
+                ("try_cast(x AS type)", "Cast returning NULL on failure"),
+                ("try(expr)", "NULL instead of error"),
+                ("current_catalog", "Session catalog"),
+                ("current_schema", "Session schema"),
+            ],
+            operators: vec![
+                ("||", "String/array concatenation"),
+                ("->", "Lambda expression"),
+                ("IS DISTINCT FROM", "NULL-safe inequality"),
+            ],
+            data_types: vec![
+                ("BOOLEAN", "True/false"),
+                ("TINYINT", "1-byte integer"),
+                ("SMALLINT", "2-byte integer"),
+                ("INTEGER", "4-byte integer"),
+                ("BIGINT", "8-byte integer"),
+                ("REAL", "4-byte floating point"),
+                ("DOUBLE", "8-byte floating point"),
+                ("DECIMAL(p,s)", "Fixed-point decimal"),
+                ("VARCHAR", "Variable-length string"),
+                ("CHAR(n)", "Fixed-length string"),
+                ("VARBINARY", "Binary data"),
+                ("JSON", "JSON value"),
+                ("DATE", "Calendar date"),
+                ("TIMESTAMP", "Date and time"),
+                ("TIMESTAMP WITH TIME ZONE", "Timestamp with zone"),
+                ("ARRAY(T)", "Array"),
+                ("MAP(K, V)", "Map"),
+                ("ROW(...)", "Structure of named fields"),
+                ("UUID", "UUID"),
+            ],
+            snippets: vec![
+                ("cat", "SHOW CATALOGS", "List catalogs"),
+                ("sch", "SHOW SCHEMAS FROM $1", "List schemas of a catalog"),
+                ("ctas", "CREATE TABLE $1 AS SELECT * FROM $2", "Create table from query"),
+                ("unn", "CROSS JOIN UNNEST($1) AS t($2)", "Expand an array"),
+            ],
+        }.with_standard_sql()
+    }
+
+    async fn create_connection(&self, config: DbConnectionConfig) -> Result<Box<dyn DbConnection + Send + Sync>, DbError> {
+        let mut conn = TrinoConnection::new(config);
+        conn.connect().await?;
+        Ok(Box::new(conn))
+    }
+
+    async fn list_databases(&self, connection: &dyn DbConnection) -> Result<Vec<String>> {
+        let rows = self.query_rows(connection, "SHOW CATALOGS", "catalogs").await?;
+        Ok(rows.iter().filter_map(|row| cell(row, 0)).collect())
+    }
+
+    async fn list_databases_view(&self, connection: &dyn DbConnection) -> Result<ObjectView> {
+        use gpui::px;
+
+        let catalogs = self.list_databases(connection).await?;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Catalog,
+            title: format!("{} catalog(s)", catalogs.len()),
+            columns: vec![Column::new("name", "Name").width(px(240.0))],
+            rows: catalogs.into_iter().map(|catalog| vec![catalog]).collect(),
+        })
+    }
+
+    async fn list_databases_detailed(&self, connection: &dyn DbConnection) -> Result<Vec<DatabaseInfo>> {
+        let catalogs = self.list_databases(connection).await?;
+        Ok(catalogs.into_iter().map(|name| DatabaseInfo {
+            name,
+            charset: None,
+            collation: None,
+            size: None,
+            table_count: None,
+            comment: None,
+        }).collect())
+    }
+
+    async fn list_schemas(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<String>> {
+        let rows = self.query_rows(connection, &self.build_list_schemas_sql(database), "schemas").await?;
+        Ok(rows.iter().filter_map(|row| cell(row, 0)).collect())
+    }
+
+    async fn list_schemas_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let schemas = self.list_schemas(connection, database).await?;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Schema,
+            title: format!("{} schema(s)", schemas.len()),
+            columns: vec![Column::new("name", "Name").width(px(240.0))],
+            rows: schemas.into_iter().map(|schema| vec![schema]).collect(),
+        })
+    }
+
+    async fn list_tables(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<TableInfo>> {
+        let rows = self.query_rows(connection, &self.build_list_tables_sql(database, "BASE TABLE"), "tables").await?;
+
+        Ok(rows.iter().map(|row| TableInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            schema: cell(row, 1),
+            comment: None,
+            engine: None,
+            row_count: None,
+            create_time: None,
+            charset: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_tables_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let tables = self.list_tables(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(220.0)),
+            Column::new("schema", "Schema").width(px(160.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = tables.iter().map(|table| {
+            vec![table.name.clone(), table.schema.clone().unwrap_or_default()]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Table,
+            title: format!("{} table(s)", tables.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_columns(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<Vec<ColumnInfo>> {
+        let schema = schema.unwrap_or("default");
+        let rows = self.query_rows(connection, &self.build_list_columns_sql(database, schema, table), "columns").await?;
+
+        Ok(rows.iter().map(|row| ColumnInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            data_type: cell(row, 1).unwrap_or_default(),
+            is_nullable: cell(row, 2).map(|v| v == "YES").unwrap_or(true),
+            is_primary_key: false,
+            default_value: cell(row, 3),
+            comment: None,
+            collation: None,
+        }).collect())
+    }
+
+    async fn list_columns_view(&self, connection: &dyn DbConnection, database: &str, schema: Option<&str>, table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let columns_data = self.list_columns(connection, database, schema, table).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(180.0)),
+            Column::new("type", "Type").width(px(220.0)),
+            Column::new("nullable", "Nullable").width(px(80.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = columns_data.iter().map(|col| {
+            vec![
+                col.name.clone(),
+                col.data_type.clone(),
+                if col.is_nullable { "YES" } else { "NO" }.to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Column,
+            title: format!("{} column(s)", columns_data.len()),
+            columns,
+            rows,
+        })
+    }
+
+    async fn list_indexes(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<Vec<IndexInfo>> {
+        // Connectors expose no indexes through the SQL interface
+        Ok(Vec::new())
+    }
+
+    async fn list_indexes_view(&self, _connection: &dyn DbConnection, _database: &str, _schema: Option<&str>, _table: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Index,
+            title: "0 index(es)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(180.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_views(&self, connection: &dyn DbConnection, database: &str) -> Result<Vec<ViewInfo>> {
+        let sql = format!(
+            "SELECT table_name, table_schema, view_definition FROM {} \
+             WHERE table_schema <> 'information_schema' ORDER BY table_schema, table_name",
+            self.information_schema(database, "views")
+        );
+        let rows = self.query_rows(connection, &sql, "views").await?;
+
+        Ok(rows.iter().map(|row| ViewInfo {
+            name: cell(row, 0).unwrap_or_default(),
+            schema: cell(row, 1),
+            definition: cell(row, 2),
+            comment: None,
+        }).collect())
+    }
+
+    async fn list_views_view(&self, connection: &dyn DbConnection, database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        let views = self.list_views(connection, database).await?;
+
+        let columns = vec![
+            Column::new("name", "Name").width(px(200.0)),
+            Column::new("schema", "Schema").width(px(140.0)),
+            Column::new("definition", "Definition").width(px(400.0)),
+        ];
+
+        let rows: Vec<Vec<String>> = views.iter().map(|view| {
+            vec![
+                view.name.clone(),
+                view.schema.clone().unwrap_or_default(),
+                view.definition.as_deref().unwrap_or("").to_string(),
+            ]
+        }).collect();
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::View,
+            title: format!("{} view(s)", views.len()),
+            columns,
+            rows,
+        })
+    }
+
+    fn supports_functions(&self) -> bool {
+        false
+    }
+
+    async fn list_functions(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_functions_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Function,
+            title: "0 function(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn supports_procedures(&self) -> bool {
+        false
+    }
+
+    async fn list_procedures(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<FunctionInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_procedures_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Procedure,
+            title: "0 procedure(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    async fn list_triggers(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<TriggerInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_triggers_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Trigger,
+            title: "0 trigger(s)".to_string(),
+            columns: vec![
+                Column::new("name", "Name").width(px(180.0)),
+                Column::new("table", "Table").width(px(150.0)),
+            ],
+            rows: vec![],
+        })
+    }
+
+    async fn list_sequences(&self, _connection: &dyn DbConnection, _database: &str) -> Result<Vec<SequenceInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_sequences_view(&self, _connection: &dyn DbConnection, _database: &str) -> Result<ObjectView> {
+        use gpui::px;
+
+        Ok(ObjectView {
+            db_node_type: DbNodeType::Sequence,
+            title: "0 sequence(s)".to_string(),
+            columns: vec![Column::new("name", "Name").width(px(200.0))],
+            rows: vec![],
+        })
+    }
+
+    fn get_data_types(&self) -> Vec<DataTypeInfo> {
+        vec![
+            DataTypeInfo::new("BOOLEAN", "True/false").with_category(DataTypeCategory::Boolean),
+            DataTypeInfo::new("TINYINT", "1-byte integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("SMALLINT", "2-byte integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("INTEGER", "4-byte integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("BIGINT", "8-byte integer").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("REAL", "4-byte floating point").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("DOUBLE", "8-byte floating point").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("DECIMAL", "Fixed-point decimal").with_category(DataTypeCategory::Numeric),
+            DataTypeInfo::new("VARCHAR", "Variable-length string").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("CHAR", "Fixed-length string").with_category(DataTypeCategory::String),
+            DataTypeInfo::new("VARBINARY", "Binary data").with_category(DataTypeCategory::Binary),
+            DataTypeInfo::new("JSON", "JSON value").with_category(DataTypeCategory::Structured),
+            DataTypeInfo::new("DATE", "Calendar date").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("TIMESTAMP", "Date and time").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("TIMESTAMP WITH TIME ZONE", "Timestamp with zone").with_category(DataTypeCategory::DateTime),
+            DataTypeInfo::new("UUID", "UUID").with_category(DataTypeCategory::String),
+        ]
+    }
+
+    fn build_column_definition(&self, column: &ColumnInfo, include_name: bool) -> String {
+        let mut def = String::new();
+
+        if include_name {
+            def.push_str(&self.quote_identifier(&column.name));
+            def.push(' ');
+        }
+
+        def.push_str(&column.data_type);
+
+        if !column.is_nullable {
+            def.push_str(" NOT NULL");
+        }
+
+        def
+    }
+
+    /// Catalogs are configured on the coordinator; creating one needs dynamic catalog management
+    fn build_create_database_sql(&self, request: &crate::plugin::DatabaseOperationRequest) -> String {
+        format!("CREATE CATALOG {} USING <connector>", self.quote_identifier(&request.database_name))
+    }
+
+    fn build_modify_database_sql(&self, _request: &crate::plugin::DatabaseOperationRequest) -> String {
+        "-- Trino: catalog properties are set on the coordinator".to_string()
+    }
+
+    fn build_drop_database_sql(&self, database_name: &str) -> String {
+        format!("DROP CATALOG {}", self.quote_identifier(database_name))
+    }
+
+    fn truncate_table(&self, _database: &str, table: &str) -> String {
+        format!("TRUNCATE TABLE {}", self.quote_identifier(table))
+    }
+
+    fn rename_table(&self, _database: &str, old_name: &str, new_name: &str) -> String {
+        format!("ALTER TABLE {} RENAME TO {}", self.quote_identifier(old_name), self.quote_identifier(new_name))
+    }
+
+    fn build_column_def(&self, col: &ColumnDefinition) -> String {
+        let mut def = format!("{} {}", self.quote_identifier(&col.name), self.build_type_string(col));
+        if !col.is_nullable {
+            def.push_str(" NOT NULL");
+        }
+        if !col.comment.is_empty() {
+            def.push_str(&format!(" COMMENT {}", string_literal(&col.comment)));
+        }
+        def
+    }
+
+    /// Tables have no keys or indexes; connector-specific layout goes into `WITH (...)`
+    fn build_create_table_sql(&self, design: &TableDesign) -> String {
+        let definitions: Vec<String> = design.columns
+            .iter()
+            .map(|col| format!("  {}", self.build_column_def(col)))
+            .collect();
+        let mut sql = format!(
+            "CREATE TABLE {} (\n{}\n)",
+            self.quote_identifier(&design.table_name),
+            definitions.join(",\n")
+        );
+        if !design.options.comment.is_empty() {
+            sql.push_str(&format!("\nCOMMENT {}", string_literal(&design.options.comment)));
+        }
+        sql.push(';');
+        sql
+    }
+
+    fn build_alter_table_sql(&self, original: &TableDesign, new: &TableDesign) -> String {
+        let table_name = self.quote_identifier(&new.table_name);
+        let mut statements: Vec<String> = Vec::new();
+
+        for col in &original.columns {
+            if !new.columns.iter().any(|c| c.name == col.name) {
+                statements.push(format!("ALTER TABLE {} DROP COLUMN {};", table_name, self.quote_identifier(&col.name)));
+            }
+        }
+
+        for col in &new.columns {
+            let column_name = self.quote_identifier(&col.name);
+            let Some(orig) = original.columns.iter().find(|c| c.name == col.name) else {
+                statements.push(format!("ALTER TABLE {} ADD COLUMN {};", table_name, self.build_column_def(col)));
+                continue;
+            };
+            if !self.column_changed(orig, col) {
+                continue;
+            }
+
+            let new_type = self.build_type_string(col);
+            if self.build_type_string(orig).to_uppercase() != new_type.to_uppercase() {
+                statements.push(format!("ALTER TABLE {} ALTER COLUMN {} SET DATA TYPE {};", table_name, column_name, new_type));
+            }
+            if orig.is_nullable != col.is_nullable {
+                let action = if col.is_nullable { "DROP NOT NULL" } else { "SET NOT NULL" };
+                statements.push(format!("ALTER TABLE {} ALTER COLUMN {} {};", table_name, column_name, action));
+            }
+            if orig.comment != col.comment {
+                statements.push(format!("COMMENT ON COLUMN {}.{} IS {};", table_name, column_name, string_literal(&col.comment)));
+            }
+        }
+
+        if statements.is_empty() {
+            "-- No changes detected".to_string()
+        } else {
+            statements.join("\n")
+        }
+    }
+
+    fn build_limit_clause(&self) -> String {
+        String::new()
+    }
+
+    fn build_where_and_limit_clause(
+        &self,
+        request: &crate::types::TableSaveRequest,
+        original_data: &[String],
+    ) -> (String, String) {
+        let where_clause = self.build_table_change_where_clause(request, original_data);
+        (where_clause, self.build_limit_clause())
+    }
+}
+
+impl Default for TrinoPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_level_nodes() {
+        let plugin = TrinoPlugin::new();
+        assert_eq!(plugin.name(), DatabaseType::Trino);
+        assert_eq!(plugin.database_node_type(), DbNodeType::Catalog);
+        assert!(plugin.supports_schema());
+        assert!(plugin.supports_query_cancel());
+    }
+
+    #[test]
+    fn test_information_schema_queries() {
+        let plugin = TrinoPlugin::new();
+        assert_eq!(
+            plugin.build_list_schemas_sql("hive"),
+            "SELECT schema_name FROM \"hive\".information_schema.schemata WHERE schema_name <> 'information_schema' ORDER BY schema_name"
+        );
+        assert!(plugin
+            .build_list_columns_sql("iceberg", "sales", "o'rders")
+            .contains("WHERE table_schema = 'sales' AND table_name = 'o''rders'"));
+    }
+
+    #[test]
+    fn test_table_reference_and_pagination() {
+        let plugin = TrinoPlugin::new();
+        assert_eq!(plugin.format_table_reference("hive", Some("web"), "events"), "\"hive\".\"web\".\"events\"");
+        assert_eq!(plugin.format_pagination(100, 200, ""), " OFFSET 200 LIMIT 100");
+    }
+}
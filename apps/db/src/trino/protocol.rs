@@ -0,0 +1,225 @@
+//! Trino client REST protocol.
+//!
+//! A statement is submitted with `POST /v1/statement`; every response is a `QueryResults`
+//! page whose `nextUri` is polled until it disappears. Column metadata arrives with the first
+//! page that has it, rows are spread over any number of pages, and `DELETE nextUri` cancels
+//! the query.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::executor::{ExecResult, QueryResult, SqlErrorInfo, SqlResult};
+
+/// Request headers of the client protocol
+pub const HEADER_USER: &str = "X-Trino-User";
+pub const HEADER_SOURCE: &str = "X-Trino-Source";
+pub const HEADER_CATALOG: &str = "X-Trino-Catalog";
+pub const HEADER_SCHEMA: &str = "X-Trino-Schema";
+/// Response headers sent after `USE catalog.schema`
+pub const HEADER_SET_CATALOG: &str = "X-Trino-Set-Catalog";
+pub const HEADER_SET_SCHEMA: &str = "X-Trino-Set-Schema";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResults {
+    pub id: String,
+    #[serde(default)]
+    pub next_uri: Option<String>,
+    #[serde(default)]
+    pub columns: Option<Vec<QueryColumn>>,
+    #[serde(default)]
+    pub data: Option<Vec<Vec<Value>>>,
+    #[serde(default)]
+    pub stats: Option<QueryStats>,
+    #[serde(default)]
+    pub error: Option<QueryError>,
+    #[serde(default)]
+    pub update_type: Option<String>,
+    #[serde(default)]
+    pub update_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryColumn {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryStats {
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryError {
+    pub message: String,
+    #[serde(default)]
+    pub error_name: Option<String>,
+    #[serde(default)]
+    pub error_location: Option<ErrorLocation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorLocation {
+    pub line_number: u32,
+    pub column_number: u32,
+}
+
+impl QueryError {
+    pub fn describe(&self) -> String {
+        let mut text = match &self.error_name {
+            Some(name) => format!("{}: {}", name, self.message),
+            None => self.message.clone(),
+        };
+        if let Some(location) = &self.error_location {
+            text.push_str(&format!(" (line {}, column {})", location.line_number, location.column_number));
+        }
+        text
+    }
+}
+
+/// Render a cell; ARRAY, MAP and ROW values arrive as JSON and are shown as such
+pub fn cell_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Collects the pages of one statement into a single result
+#[derive(Debug, Default)]
+pub struct StatementPages {
+    columns: Option<Vec<String>>,
+    rows: Vec<Vec<Option<String>>>,
+    update_type: Option<String>,
+    update_count: Option<u64>,
+    error: Option<String>,
+    truncated: bool,
+}
+
+impl StatementPages {
+    /// Add a page; returns false once `max_rows` is reached and the rest of the query
+    /// should be cancelled instead of read
+    pub fn absorb(&mut self, page: QueryResults, max_rows: Option<usize>) -> bool {
+        if let Some(columns) = page.columns {
+            if self.columns.is_none() {
+                self.columns = Some(columns.into_iter().map(|column| column.name).collect());
+            }
+        }
+        if let Some(error) = page.error {
+            self.error = Some(error.describe());
+        }
+        if page.update_type.is_some() {
+            self.update_type = page.update_type;
+        }
+        if page.update_count.is_some() {
+            self.update_count = page.update_count;
+        }
+        for row in page.data.unwrap_or_default() {
+            if max_rows.is_some_and(|max| self.rows.len() >= max) {
+                self.truncated = true;
+                break;
+            }
+            self.rows.push(row.iter().map(cell_text).collect());
+        }
+        !self.truncated && !max_rows.is_some_and(|max| self.rows.len() >= max && self.columns.is_some())
+    }
+
+    pub fn into_result(self, sql: String, elapsed_ms: u128) -> SqlResult {
+        if let Some(message) = self.error {
+            return SqlResult::Error(SqlErrorInfo { sql, message });
+        }
+        // DDL and DML report an update type; anything with columns is a result set
+        match (self.columns, self.update_type) {
+            (Some(columns), None) => SqlResult::Query(QueryResult {
+                sql,
+                columns,
+                rows: self.rows,
+                elapsed_ms,
+                table_name: None,
+                editable: false,
+            }),
+            (_, update_type) => {
+                let rows_affected = self.update_count.unwrap_or(0);
+                let message = match update_type {
+                    Some(update_type) if self.update_count.is_some() => format!("{}: {} row(s)", update_type, rows_affected),
+                    Some(update_type) => update_type,
+                    None => "OK".to_string(),
+                };
+                SqlResult::Exec(ExecResult {
+                    sql,
+                    rows_affected,
+                    elapsed_ms,
+                    message: Some(message),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(json: &str) -> QueryResults {
+        serde_json::from_str(json).expect("valid page")
+    }
+
+    #[test]
+    fn test_collect_query_pages() {
+        let mut pages = StatementPages::default();
+        assert!(pages.absorb(page(r#"{"id":"q1","nextUri":"http://coordinator/v1/statement/queued/q1/1","stats":{"state":"QUEUED"}}"#), None));
+        assert!(pages.absorb(page(r#"{"id":"q1","nextUri":"http://coordinator/v1/statement/executing/q1/2",
+            "columns":[{"name":"id","type":"bigint"},{"name":"tags","type":"array(varchar)"}],
+            "data":[[1,["a","b"]],[2,null]]}"#), None));
+        assert!(pages.absorb(page(r#"{"id":"q1","columns":[{"name":"id","type":"bigint"},{"name":"tags","type":"array(varchar)"}],
+            "data":[[3,[]]],"stats":{"state":"FINISHED"}}"#), None));
+
+        match pages.into_result("SELECT id, tags FROM t".to_string(), 5) {
+            SqlResult::Query(result) => {
+                assert_eq!(result.columns, vec!["id", "tags"]);
+                assert_eq!(result.rows.len(), 3);
+                assert_eq!(result.rows[0], vec![Some("1".to_string()), Some("[\"a\",\"b\"]".to_string())]);
+                assert_eq!(result.rows[1][1], None);
+            }
+            other => panic!("expected query result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_max_rows_stops_reading() {
+        let mut pages = StatementPages::default();
+        let keep_reading = pages.absorb(page(r#"{"id":"q2","nextUri":"http://coordinator/next",
+            "columns":[{"name":"n","type":"integer"}],"data":[[1],[2],[3]]}"#), Some(2));
+        assert!(!keep_reading);
+        match pages.into_result("SELECT n FROM t".to_string(), 0) {
+            SqlResult::Query(result) => assert_eq!(result.rows.len(), 2),
+            other => panic!("expected query result, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_error_and_update_results() {
+        let mut pages = StatementPages::default();
+        pages.absorb(page(r#"{"id":"q3","error":{"message":"line 1:8: mismatched input","errorName":"SYNTAX_ERROR",
+            "errorLocation":{"lineNumber":1,"columnNumber":8}}}"#), None);
+        match pages.into_result("SELEC 1".to_string(), 0) {
+            SqlResult::Error(error) => assert_eq!(error.message, "SYNTAX_ERROR: line 1:8: mismatched input (line 1, column 8)"),
+            other => panic!("expected error, got {:?}", other),
+        }
+
+        let mut pages = StatementPages::default();
+        pages.absorb(page(r#"{"id":"q4","columns":[{"name":"rows","type":"bigint"}],"data":[[5]],"updateType":"INSERT","updateCount":5}"#), None);
+        match pages.into_result("INSERT INTO t SELECT * FROM s".to_string(), 0) {
+            SqlResult::Exec(exec) => {
+                assert_eq!(exec.rows_affected, 5);
+                assert_eq!(exec.message.as_deref(), Some("INSERT: 5 row(s)"));
+            }
+            other => panic!("expected exec result, got {:?}", other),
+        }
+    }
+}
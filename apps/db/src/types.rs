@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use std::fmt;
 use gpui_component::table::Column;
 use one_core::storage::DatabaseType;
+use serde::{Deserialize, Serialize};
 
 /// SQL value type for parameter binding
 #[derive(Debug, Clone)]
@@ -17,11 +18,13 @@ pub enum SqlValue {
 }
 
 /// Database tree node types for hierarchical display
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub enum DbNodeType {
     #[default]
     Connection,
     Database,
+    /// Top level of engines that federate several data sources (Trino), holds schemas
+    Catalog,
     Schema,
     TablesFolder,
     Table,
@@ -37,14 +40,27 @@ pub enum DbNodeType {
     Check,
     ViewsFolder,
     View,
+    MaterializedViewsFolder,
+    MaterializedView,
     FunctionsFolder,
     Function,
     ProceduresFolder,
     Procedure,
+    PackagesFolder,
+    Package,
     SequencesFolder,
     Sequence,
     QueriesFolder,
     NamedQuery,
+    SynonymsFolder,
+    Synonym,
+    /// Servers reachable through four-part names (SQL Server), listed beside the databases
+    LinkedServersFolder,
+    LinkedServer,
+    LinkedTable,
+    /// Scheduled events run by the server (MySQL event scheduler)
+    EventsFolder,
+    Event,
 }
 
 impl fmt::Display for DbNodeType {
@@ -52,6 +68,7 @@ impl fmt::Display for DbNodeType {
         match self {
             DbNodeType::Connection => write!(f, "Connection"),
             DbNodeType::Database => write!(f, "Database"),
+            DbNodeType::Catalog => write!(f, "Catalog"),
             DbNodeType::Schema => write!(f, "Schema"),
             DbNodeType::TablesFolder => write!(f, "Tables"),
             DbNodeType::Table => write!(f, "Table"),
@@ -67,20 +84,39 @@ impl fmt::Display for DbNodeType {
             DbNodeType::Check => write!(f, "Check"),
             DbNodeType::ViewsFolder => write!(f, "Views"),
             DbNodeType::View => write!(f, "View"),
+            DbNodeType::MaterializedViewsFolder => write!(f, "Materialized Views"),
+            DbNodeType::MaterializedView => write!(f, "Materialized View"),
             DbNodeType::FunctionsFolder => write!(f, "Functions"),
             DbNodeType::Function => write!(f, "Function"),
             DbNodeType::ProceduresFolder => write!(f, "Procedures"),
             DbNodeType::Procedure => write!(f, "Procedure"),
+            DbNodeType::PackagesFolder => write!(f, "Packages"),
+            DbNodeType::Package => write!(f, "Package"),
             DbNodeType::QueriesFolder => write!(f, "Queries"),
             DbNodeType::NamedQuery => write!(f, "Query"),
+            DbNodeType::SynonymsFolder => write!(f, "Synonyms"),
+            DbNodeType::Synonym => write!(f, "Synonym"),
+            DbNodeType::LinkedServersFolder => write!(f, "Linked Servers"),
+            DbNodeType::LinkedServer => write!(f, "Linked Server"),
+            DbNodeType::LinkedTable => write!(f, "Linked Table"),
+            DbNodeType::EventsFolder => write!(f, "Events"),
+            DbNodeType::Event => write!(f, "Event"),
             DbNodeType::SequencesFolder => write!(f, "Sequences"),
             DbNodeType::Sequence => write!(f, "Sequence"),
         }
     }
 }
 
+impl DbNodeType {
+    /// Nodes that scope queries the way a database does; a catalog is addressed by the
+    /// `database` field of the connection config like any database
+    pub fn is_database_level(&self) -> bool {
+        matches!(self, DbNodeType::Database | DbNodeType::Catalog)
+    }
+}
+
 /// Database tree node for lazy-loading hierarchical display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbNode {
     pub id: String,
     pub name: String,
@@ -188,6 +224,8 @@ pub struct ColumnInfo {
     pub is_primary_key: bool,
     pub default_value: Option<String>,
     pub comment: Option<String>,
+    /// Collation of character columns (e.g., "utf8mb4_general_ci")
+    pub collation: Option<String>,
 }
 
 /// Index information
@@ -221,6 +259,42 @@ pub struct ViewInfo {
     pub comment: Option<String>,
 }
 
+/// Materialized view information (PostgreSQL)
+#[derive(Debug, Clone)]
+pub struct MaterializedViewInfo {
+    pub name: String,
+    pub schema: Option<String>,
+    pub definition: Option<String>,
+    /// False after `WITH NO DATA` until the first refresh; such a view cannot be queried
+    pub is_populated: bool,
+}
+
+/// Synonym information (SQL Server)
+#[derive(Debug, Clone)]
+pub struct SynonymInfo {
+    pub name: String,
+    pub schema: Option<String>,
+    /// Name of the object the synonym stands for, as written in CREATE SYNONYM
+    pub base_object: String,
+}
+
+/// Linked server information (SQL Server)
+#[derive(Debug, Clone)]
+pub struct LinkedServerInfo {
+    pub name: String,
+    pub product: Option<String>,
+    pub data_source: Option<String>,
+}
+
+/// Table or view of a linked server; catalog and schema are absent for providers without them
+#[derive(Debug, Clone)]
+pub struct LinkedTableInfo {
+    pub catalog: Option<String>,
+    pub schema: Option<String>,
+    pub name: String,
+    pub table_type: String,
+}
+
 /// Function information
 #[derive(Debug, Clone)]
 pub struct FunctionInfo {
@@ -231,6 +305,33 @@ pub struct FunctionInfo {
     pub comment: Option<String>,
 }
 
+/// Package information (Oracle)
+#[derive(Debug, Clone)]
+pub struct PackageInfo {
+    pub name: String,
+    pub has_body: bool,
+    /// VALID, or INVALID when the spec or body failed to compile
+    pub status: Option<String>,
+}
+
+/// Package source split into its specification and body
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageSource {
+    pub spec: String,
+    pub body: Option<String>,
+}
+
+impl PackageSource {
+    /// Script that recreates the package, each unit terminated by `/`
+    pub fn to_script(&self) -> String {
+        let mut script = format!("CREATE OR REPLACE {}\n/\n", self.spec.trim_end());
+        if let Some(body) = &self.body {
+            script.push_str(&format!("\nCREATE OR REPLACE {}\n/\n", body.trim_end()));
+        }
+        script
+    }
+}
+
 /// Trigger information
 #[derive(Debug, Clone)]
 pub struct TriggerInfo {
@@ -241,6 +342,26 @@ pub struct TriggerInfo {
     pub definition: Option<String>,
 }
 
+/// Scheduled event information (MySQL)
+#[derive(Debug, Clone)]
+pub struct EventInfo {
+    pub name: String,
+    /// `ENABLED`, `DISABLED` or `SLAVESIDE_DISABLED` (disabled on a replica)
+    pub status: String,
+    /// `ONE TIME` or `RECURRING`
+    pub event_type: String,
+    /// Human readable schedule, e.g. `EVERY 1 DAY` or `AT 2024-01-01 00:00:00`
+    pub schedule: String,
+    pub last_executed: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl EventInfo {
+    pub fn is_enabled(&self) -> bool {
+        self.status.eq_ignore_ascii_case("ENABLED")
+    }
+}
+
 /// Sequence information
 #[derive(Debug, Clone)]
 pub struct SequenceInfo {
@@ -555,6 +676,8 @@ pub struct TableColumnMeta {
     pub nullable: bool,
     /// Whether the column is a primary key
     pub is_primary_key: bool,
+    /// Collation of character columns, used for client-side sorting
+    pub collation: Option<String>,
     /// Column index in the result set
     pub index: usize,
 }
@@ -887,6 +1010,15 @@ pub struct ForeignKeyDefinition {
     pub on_update: String,
 }
 
+/// A table with its columns, foreign keys and (when requested) indexes
+#[derive(Debug, Clone)]
+pub struct TableSchema {
+    pub table: TableInfo,
+    pub columns: Vec<ColumnInfo>,
+    pub foreign_keys: Vec<ForeignKeyDefinition>,
+    pub indexes: Vec<IndexInfo>,
+}
+
 /// Table options (engine, charset, etc.)
 #[derive(Debug, Clone, Default)]
 pub struct TableOptions {
@@ -0,0 +1,122 @@
+//! 连接活动监控 - 定期读取数据库的累计语句计数，换算为每秒查询数，在连接节点旁显示迷你趋势图
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use gpui::{div, px, App, Global, Hsla, IntoElement, ParentElement, Styled};
+use gpui_component::h_flex;
+
+/// 趋势图保留的采样点数量
+pub const MAX_SAMPLES: usize = 30;
+const SPARKLINE_HEIGHT: f32 = 14.;
+
+/// 活动监控设置，默认关闭以免对数据库产生额外查询
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivityMonitorSettings {
+    pub enabled: bool,
+    /// 轮询间隔（秒）
+    pub interval_secs: u64,
+}
+
+impl Default for ActivityMonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 5,
+        }
+    }
+}
+
+impl Global for ActivityMonitorSettings {}
+
+impl ActivityMonitorSettings {
+    pub fn global(cx: &App) -> ActivityMonitorSettings {
+        cx.try_global::<ActivityMonitorSettings>().copied().unwrap_or_default()
+    }
+
+    pub fn global_mut(cx: &mut App) -> &mut ActivityMonitorSettings {
+        cx.default_global::<ActivityMonitorSettings>()
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+}
+
+/// 一个连接的采样序列
+#[derive(Clone, Debug, Default)]
+pub struct ActivitySeries {
+    last: Option<(u64, Instant)>,
+    rates: VecDeque<f64>,
+}
+
+impl ActivitySeries {
+    /// 记录一次计数，与上次的差值换算为每秒速率；计数变小（服务器重启）时只作为新的基准
+    pub fn record(&mut self, counter: u64, at: Instant) {
+        if let Some((last, last_at)) = self.last {
+            let secs = at.duration_since(last_at).as_secs_f64();
+            if counter >= last && secs > 0.0 {
+                if self.rates.len() == MAX_SAMPLES {
+                    self.rates.pop_front();
+                }
+                self.rates.push_back((counter - last) as f64 / secs);
+            }
+        }
+        self.last = Some((counter, at));
+    }
+
+    pub fn rates(&self) -> &VecDeque<f64> {
+        &self.rates
+    }
+
+    pub fn latest(&self) -> Option<f64> {
+        self.rates.back().copied()
+    }
+}
+
+/// 柱状迷你趋势图，按序列中的最大值缩放
+pub fn render_sparkline(series: &ActivitySeries, color: Hsla) -> impl IntoElement {
+    let max = series.rates().iter().copied().fold(1.0, f64::max);
+
+    h_flex()
+        .items_end()
+        .h(px(SPARKLINE_HEIGHT))
+        .gap(px(1.))
+        .children(series.rates().iter().map(|rate| {
+            let height = (rate / max) as f32 * SPARKLINE_HEIGHT;
+            div().w(px(2.)).h(px(height.max(1.))).bg(color)
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_computes_rates() {
+        let start = Instant::now();
+        let mut series = ActivitySeries::default();
+        series.record(100, start);
+        assert!(series.rates().is_empty());
+
+        series.record(150, start + Duration::from_secs(5));
+        assert_eq!(series.latest(), Some(10.0));
+
+        // 服务器重启后计数从头开始
+        series.record(20, start + Duration::from_secs(10));
+        assert_eq!(series.rates().len(), 1);
+        series.record(40, start + Duration::from_secs(12));
+        assert_eq!(series.latest(), Some(10.0));
+    }
+
+    #[test]
+    fn test_record_keeps_recent_samples() {
+        let start = Instant::now();
+        let mut series = ActivitySeries::default();
+        for i in 0..=(MAX_SAMPLES as u64 + 5) {
+            series.record(i * i, start + Duration::from_secs(i));
+        }
+        assert_eq!(series.rates().len(), MAX_SAMPLES);
+        assert_eq!(series.latest(), Some((2 * MAX_SAMPLES as u64 + 9) as f64));
+    }
+}
@@ -0,0 +1,185 @@
+//! AI 数据脱敏助手 - 只把表的列名、类型和注释交给 LLM（不发送任何数据）判断可能含有个人信息的列，
+//! 给出每列的脱敏规则；用户确认规则后生成原地脱敏的 UPDATE 语句
+
+use anyhow::{anyhow, Result};
+use db::{ColumnInfo, GlobalDbState, MaskingRule};
+use gpui::AsyncApp;
+use one_core::llm::types::ChatMessage;
+use one_core::storage::DatabaseType;
+use serde_json::Value;
+
+use crate::ai_sql::{complete, strip_markdown_fence};
+
+/// 模型可以给出的个人信息类别
+pub const PII_CATEGORIES: [&str; 8] = ["email", "phone", "name", "address", "id_number", "credential", "financial", "none"];
+
+/// 一次脱敏扫描的请求
+#[derive(Debug, Clone)]
+pub struct PiiScanRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub table: String,
+}
+
+/// 一列的识别结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiiColumn {
+    pub column: String,
+    pub data_type: String,
+    /// `PII_CATEGORIES` 之一
+    pub category: String,
+    pub rule: MaskingRule,
+}
+
+impl PiiColumn {
+    pub fn category_label(&self) -> &'static str {
+        match self.category.as_str() {
+            "email" => "邮箱",
+            "phone" => "电话",
+            "name" => "姓名",
+            "address" => "地址",
+            "id_number" => "证件号",
+            "credential" => "密码/密钥",
+            "financial" => "金额",
+            _ => "无",
+        }
+    }
+}
+
+pub fn build_pii_messages(request: &PiiScanRequest, columns: &[ColumnInfo]) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是数据安全专家。根据 {} 表的列名、类型和注释判断每一列是否可能包含个人信息或敏感数据，并给出脱敏规则。\n\
+         类别只能是：{}。\n\
+         规则只能是：keep（保留）、hash（替换为稳定的哈希，适合需要保持唯一或可关联的列，如邮箱、电话、证件号）、\
+         shuffle（在行之间打乱，适合姓名、地址）、zero（数值置零，适合金额）、null（置空，适合密码、令牌）。\n\
+         主键、外键和不含个人信息的列使用 none 和 keep。\n\
+         只输出一个 JSON 数组，每个元素形如 {{\"column\": \"列名\", \"category\": \"类别\", \"rule\": \"规则\"}}，不要解释，不要使用 Markdown。",
+        request.database_type.as_str(),
+        PII_CATEGORIES.join("、"),
+    );
+
+    let mut user = format!("表 {} 的列：\n", request.table);
+    for column in columns {
+        user.push_str(&format!("- {} {}", column.name, column.data_type));
+        if column.is_primary_key {
+            user.push_str(" PK");
+        }
+        if let Some(comment) = column.comment.as_ref().filter(|c| !c.is_empty()) {
+            user.push_str(&format!(" -- {}", comment));
+        }
+        user.push('\n');
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 按表的列顺序整理模型的识别结果；模型遗漏的列或无效的规则按列名推测，主键始终保留
+pub fn parse_pii_columns(response: &str, columns: &[ColumnInfo]) -> Result<Vec<PiiColumn>> {
+    let body = strip_markdown_fence(response);
+    let json = match (body.find('['), body.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &body[start..=end],
+        _ => return Err(anyhow!("模型没有返回 JSON 数组")),
+    };
+    let items: Vec<Value> = serde_json::from_str(json).map_err(|e| anyhow!("识别结果无效：{}", e))?;
+
+    Ok(columns
+        .iter()
+        .map(|column| {
+            let item = items.iter().filter_map(Value::as_object).find(|object| {
+                object
+                    .get("column")
+                    .and_then(Value::as_str)
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&column.name))
+            });
+            let field = |key: &str| item.and_then(|object| object.get(key)).and_then(Value::as_str);
+            let category = field("category")
+                .map(str::to_lowercase)
+                .filter(|category| PII_CATEGORIES.contains(&category.as_str()))
+                .unwrap_or_else(|| "none".to_string());
+            let rule = if column.is_primary_key {
+                MaskingRule::Keep
+            } else {
+                field("rule")
+                    .and_then(MaskingRule::from_str)
+                    .unwrap_or_else(|| MaskingRule::suggest(&column.name))
+            };
+            PiiColumn {
+                column: column.name.clone(),
+                data_type: column.data_type.clone(),
+                category,
+                rule,
+            }
+        })
+        .collect())
+}
+
+/// 读取表的列并请求模型识别个人信息列
+pub async fn scan_pii_columns(request: PiiScanRequest, cx: &mut AsyncApp) -> Result<Vec<PiiColumn>> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let columns = global_state
+        .list_columns(
+            cx,
+            request.connection_id.clone(),
+            request.database.clone(),
+            request.schema.clone(),
+            request.table.clone(),
+        )
+        .await?;
+    if columns.is_empty() {
+        return Err(anyhow!("没有找到表 {} 的列", request.table));
+    }
+
+    let response = complete(build_pii_messages(&request, &columns), cx).await?;
+    parse_pii_columns(&response, &columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, is_primary_key: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: !is_primary_key,
+            is_primary_key,
+            default_value: None,
+            comment: None,
+            collation: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_pii_columns() {
+        let columns = vec![
+            column("id", "bigint", true),
+            column("Email", "varchar(100)", false),
+            column("mobile", "varchar(20)", false),
+            column("remark", "text", false),
+        ];
+        let response = "```json\n[\
+            {\"column\": \"id\", \"category\": \"none\", \"rule\": \"hash\"},\
+            {\"column\": \"email\", \"category\": \"Email\", \"rule\": \"hash\"},\
+            {\"column\": \"remark\", \"category\": \"secret\", \"rule\": \"redact\"}\
+        ]\n```";
+
+        let parsed = parse_pii_columns(response, &columns).unwrap();
+        let summary: Vec<(&str, &str, MaskingRule)> = parsed
+            .iter()
+            .map(|c| (c.column.as_str(), c.category.as_str(), c.rule))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("id", "none", MaskingRule::Keep),
+                ("Email", "email", MaskingRule::Hash),
+                ("mobile", "none", MaskingRule::Hash),
+                ("remark", "none", MaskingRule::Keep),
+            ]
+        );
+        assert!(parse_pii_columns("无法判断", &columns).is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! AI 图表建议 - 把结果集的列、推断的类型和部分样本行交给 LLM，由它选择合适的图表并返回图表描述
+
+use anyhow::{anyhow, Result};
+use db::QueryResult;
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AsyncApp, IntoElement, ParentElement, SharedString, Styled};
+use gpui_component::{
+    chart::{BarChart, LineChart, PieChart},
+    v_flex, ActiveTheme,
+};
+use one_core::llm::types::ChatMessage;
+use serde::Deserialize;
+
+use crate::ai_sql::{complete, strip_markdown_fence};
+
+/// 发给模型的样本行数
+const SAMPLE_ROWS: usize = 20;
+/// 柱状图和折线图最多展示的点数
+const MAX_POINTS: usize = 50;
+/// 饼图最多展示的扇区数
+const MAX_SLICES: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartKind {
+    Bar,
+    Line,
+    Pie,
+}
+
+/// 模型返回的图表描述：以 `x_column` 为分类或横轴，`y_column` 为数值
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ChartSpec {
+    pub kind: ChartKind,
+    #[serde(default)]
+    pub title: String,
+    pub x_column: String,
+    pub y_column: String,
+}
+
+/// 根据样本值推断列的类型，只用于提示模型
+fn infer_column_type(result: &QueryResult, col: usize) -> &'static str {
+    let values: Vec<&str> = result
+        .rows
+        .iter()
+        .take(SAMPLE_ROWS)
+        .filter_map(|row| row.get(col).and_then(|cell| cell.as_deref()))
+        .collect();
+    if values.is_empty() {
+        return "未知";
+    }
+    if values.iter().all(|v| v.trim().parse::<f64>().is_ok()) {
+        "数值"
+    } else if values.iter().all(|v| looks_like_date(v)) {
+        "日期时间"
+    } else {
+        "文本"
+    }
+}
+
+fn looks_like_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 7 && bytes[..4].iter().all(u8::is_ascii_digit) && matches!(bytes[4], b'-' | b'/')
+}
+
+pub fn build_chart_messages(result: &QueryResult) -> Vec<ChatMessage> {
+    let system = "你是数据可视化专家。根据查询结果的列和样本数据选择最合适的一种图表：\
+                  bar（分类对比）、line（随时间或有序横轴的趋势）、pie（少量分类的占比）。\n\
+                  只输出一个 JSON 对象，不要解释，不要使用 Markdown，格式为：\
+                  {\"kind\": \"bar|line|pie\", \"title\": \"图表标题\", \"x_column\": \"分类或横轴列\", \"y_column\": \"数值列\"}。\
+                  y_column 必须是数值列，两个列名都必须来自给出的列。";
+
+    let mut user = String::from("列：\n");
+    for (col, name) in result.columns.iter().enumerate() {
+        user.push_str(&format!("- {}（{}）\n", name, infer_column_type(result, col)));
+    }
+    user.push_str(&format!("\n共 {} 行，前 {} 行样本：\n", result.rows.len(), result.rows.len().min(SAMPLE_ROWS)));
+    user.push_str(&result.columns.join(" | "));
+    user.push('\n');
+    for row in result.rows.iter().take(SAMPLE_ROWS) {
+        let cells: Vec<&str> = row.iter().map(|cell| cell.as_deref().unwrap_or("NULL")).collect();
+        user.push_str(&cells.join(" | "));
+        user.push('\n');
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 解析模型回复并确认其中的列存在于结果集中
+pub fn parse_chart_spec(response: &str, columns: &[String]) -> Result<ChartSpec> {
+    let body = strip_markdown_fence(response);
+    let json = match (body.find('{'), body.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &body[start..=end],
+        _ => return Err(anyhow!("模型没有返回图表描述")),
+    };
+    let spec: ChartSpec = serde_json::from_str(json).map_err(|e| anyhow!("图表描述无效：{}", e))?;
+    for column in [&spec.x_column, &spec.y_column] {
+        if !columns.contains(column) {
+            return Err(anyhow!("结果中没有列 {}", column));
+        }
+    }
+    Ok(spec)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartPoint {
+    pub index: usize,
+    pub label: SharedString,
+    pub value: f64,
+}
+
+/// 按图表描述从结果集中取出数据点，数值无法解析的行会被跳过
+pub fn chart_points(result: &QueryResult, spec: &ChartSpec) -> Vec<ChartPoint> {
+    let position = |name: &str| result.columns.iter().position(|c| c == name);
+    let (Some(x), Some(y)) = (position(&spec.x_column), position(&spec.y_column)) else {
+        return Vec::new();
+    };
+    let limit = if spec.kind == ChartKind::Pie { MAX_SLICES } else { MAX_POINTS };
+
+    result
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let value = row.get(y)?.as_deref()?.trim().parse::<f64>().ok()?;
+            let label = row.get(x)?.clone().unwrap_or_else(|| "NULL".to_string());
+            Some((label, value))
+        })
+        .filter(|(_, value)| spec.kind != ChartKind::Pie || *value > 0.0)
+        .take(limit)
+        .enumerate()
+        .map(|(index, (label, value))| ChartPoint { index, label: label.into(), value })
+        .collect()
+}
+
+/// 请求第一个启用的模型为结果集选择图表
+pub async fn suggest_chart(result: QueryResult, cx: &mut AsyncApp) -> Result<ChartSpec> {
+    if result.rows.is_empty() {
+        return Err(anyhow!("结果为空，无法生成图表"));
+    }
+    let response = complete(build_chart_messages(&result), cx).await?;
+    parse_chart_spec(&response, &result.columns)
+}
+
+pub fn render_chart(spec: &ChartSpec, result: &QueryResult, cx: &App) -> AnyElement {
+    let points = chart_points(result, spec);
+    if points.is_empty() {
+        return div()
+            .text_sm()
+            .text_color(cx.theme().muted_foreground)
+            .child(format!("列 {} 中没有可绘制的数值", spec.y_column))
+            .into_any_element();
+    }
+
+    let chart = match spec.kind {
+        ChartKind::Bar => BarChart::new(points)
+            .x(|p| p.label.clone())
+            .y(|p| p.value)
+            .into_any_element(),
+        ChartKind::Line => LineChart::new(points)
+            .x(|p| p.label.clone())
+            .y(|p| p.value)
+            .dot()
+            .into_any_element(),
+        ChartKind::Pie => {
+            let colors = [
+                cx.theme().chart_1,
+                cx.theme().chart_2,
+                cx.theme().chart_3,
+                cx.theme().chart_4,
+                cx.theme().chart_5,
+            ];
+            PieChart::new(points)
+                .value(|p| p.value as f32)
+                .outer_radius(100.)
+                .color(move |p| colors[p.index % colors.len()])
+                .into_any_element()
+        }
+    };
+
+    v_flex()
+        .size_full()
+        .gap_1()
+        .when(!spec.title.is_empty(), |this| {
+            this.child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child(spec.title.clone()))
+        })
+        .child(div().flex_1().min_h(px(0.)).child(chart))
+        .into_any_element()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result() -> QueryResult {
+        QueryResult {
+            sql: "SELECT month, total FROM sales".to_string(),
+            columns: vec!["month".to_string(), "total".to_string()],
+            rows: vec![
+                vec![Some("2025-01".to_string()), Some("120.5".to_string())],
+                vec![Some("2025-02".to_string()), None],
+                vec![Some("2025-03".to_string()), Some("98".to_string())],
+            ],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        }
+    }
+
+    #[test]
+    fn test_chart_messages_describe_columns() {
+        let messages = build_chart_messages(&result());
+        assert!(messages[1].content.contains("- month（日期时间）"));
+        assert!(messages[1].content.contains("- total（数值）"));
+        assert!(messages[1].content.contains("2025-02 | NULL"));
+    }
+
+    #[test]
+    fn test_parse_chart_spec() {
+        let columns = result().columns;
+        let spec = parse_chart_spec(
+            "```json\n{\"kind\": \"line\", \"title\": \"月销售额\", \"x_column\": \"month\", \"y_column\": \"total\"}\n```",
+            &columns,
+        )
+        .unwrap();
+        assert_eq!(spec.kind, ChartKind::Line);
+        assert_eq!(spec.title, "月销售额");
+
+        assert!(parse_chart_spec("{\"kind\": \"bar\", \"x_column\": \"month\", \"y_column\": \"amount\"}", &columns).is_err());
+        assert!(parse_chart_spec("无法判断", &columns).is_err());
+    }
+
+    #[test]
+    fn test_chart_points_skip_non_numeric_values() {
+        let spec = ChartSpec {
+            kind: ChartKind::Bar,
+            title: String::new(),
+            x_column: "month".to_string(),
+            y_column: "total".to_string(),
+        };
+        let points = chart_points(&result(), &spec);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1], ChartPoint { index: 1, label: "2025-03".into(), value: 98.0 });
+    }
+}
@@ -1,25 +1,31 @@
 //! AI Chat Panel - 数据库 AI 助手对话面板
 
-use futures::StreamExt;
+use futures::{stream, StreamExt};
 use gpui::{div, prelude::FluentBuilder, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, RenderOnce, SharedString, StatefulInteractiveElement, Styled, Subscription, Task, Window};
 use gpui_component::{
     button::{Button, ButtonVariants},
     clipboard::Clipboard,
-    h_flex, list::{List, ListDelegate, ListState}, text::TextView, v_flex,
-    v_virtual_list, ActiveTheme,
+    h_flex, input::{Input, InputState}, list::{List, ListDelegate, ListState}, menu::{DropdownMenu, PopupMenuItem}, text::TextView, v_flex,
+    v_virtual_list, ActiveTheme, Disableable, WindowExt,
     Icon, IconName, IndexPath, Selectable, Sizable, Size, VirtualListScrollHandle,
 };
+use std::collections::HashMap;
 use std::rc::Rc;
 use uuid::Uuid;
 
-use crate::ai_input::{AIInput, AIInputEvent};
+use crate::ai_input::{AIInput, AIInputEvent, ProviderItem};
+use crate::prompt_variables::{referenced_prompt_variables, resolve_prompt_variables, SelectedTreeNode};
+use crate::{ai_sql, ai_tools};
+use db::GlobalDbState;
 use one_core::llm::{
-    chat_history::{ChatMessage, ChatSession, MessageRepository, SessionRepository},
+    chat_export::{export_conversation, ChatExportFormat},
+    chat_history::{sibling_ids, ChatMessage, ChatSearchResult, ChatSession, MessageRepository, SessionRepository},
     manager::GlobalProviderState,
     storage::ProviderRepository,
-    types::{ChatMessage as LlmChatMessage, ChatRequest, ChatStreamEvent},
+    types::{estimate_tokens, filter_models, ChatMessage as LlmChatMessage, ChatRequest, ChatStreamChunk, ChatStreamEvent, GenerationParams, ModelInfo},
 };
-use one_core::storage::{traits::Repository, GlobalStorageState};
+use one_core::storage::usage_metrics::record_feature;
+use one_core::storage::{traits::Repository, DatabaseType, DbConnectionConfig, GlobalStorageState};
 
 /// AI 聊天消息类型
 #[derive(Clone, Debug, PartialEq)]
@@ -40,6 +46,10 @@ pub struct ChatMessageUI {
     pub variant: MessageVariant,
     pub is_streaming: bool,
     pub is_expanded: bool,
+    /// 已保存到数据库的消息 ID
+    pub db_id: Option<i64>,
+    /// 同一位置的所有版本（编辑或重新生成产生的分支），按创建顺序
+    pub sibling_ids: Vec<i64>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -58,6 +68,8 @@ impl ChatMessageUI {
             variant: MessageVariant::Text,
             is_streaming: false,
             is_expanded: true,
+            db_id: None,
+            sibling_ids: Vec::new(),
         }
     }
 
@@ -69,6 +81,8 @@ impl ChatMessageUI {
             variant: MessageVariant::Text,
             is_streaming: false,
             is_expanded: true,
+            db_id: None,
+            sibling_ids: Vec::new(),
         }
     }
 
@@ -83,19 +97,19 @@ impl ChatMessageUI {
             },
             is_streaming: !is_done,
             is_expanded: !is_done,
+            db_id: None,
+            sibling_ids: Vec::new(),
         }
     }
 
     // Convert UI message to LLM message
     pub fn to_llm_message(&self) -> LlmChatMessage {
-        LlmChatMessage {
-            role: match self.role {
-                ChatRole::User => "user".to_string(),
-                ChatRole::Assistant => "assistant".to_string(),
-                ChatRole::System => "system".to_string(),
-            },
-            content: self.content.clone(),
-        }
+        let role = match self.role {
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+            ChatRole::System => "system",
+        };
+        LlmChatMessage::new(role, self.content.clone())
     }
 
     // Create from LLM message
@@ -114,15 +128,74 @@ impl ChatMessageUI {
             variant: MessageVariant::Text,
             is_streaming: false,
             is_expanded: true,
+            db_id: None,
+            sibling_ids: Vec::new(),
         }
     }
 }
 
+/// 对话的数据库上下文：AI 助手当前"看到"的连接、数据库和表
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChatDbContext {
+    pub connection_id: String,
+    pub connection_name: String,
+    pub database_type: DatabaseType,
+    pub database: Option<String>,
+    /// 为空时表结构上下文包含整个数据库
+    pub tables: Vec<String>,
+}
+
+impl ChatDbContext {
+    pub fn from_config(config: &DbConnectionConfig) -> Self {
+        Self {
+            connection_id: config.id.clone(),
+            connection_name: config.name.clone(),
+            database_type: config.database_type,
+            database: config.database.clone().filter(|db| !db.is_empty()),
+            tables: Vec::new(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let mut label = self.connection_name.clone();
+        if let Some(database) = &self.database {
+            label.push_str(&format!(" / {}", database));
+        }
+        if !self.tables.is_empty() {
+            label.push_str(&format!(" ({} 张表)", self.tables.len()));
+        }
+        label
+    }
+
+    /// 注入对话的系统提示词，`schema_prompt` 为裁剪后的表结构
+    pub fn system_prompt(&self, schema_prompt: &str) -> String {
+        let mut prompt = format!(
+            "当前对话关注的数据库：{} 连接 {}（连接 ID：{}）",
+            self.database_type.as_str(),
+            self.connection_name,
+            self.connection_id
+        );
+        if let Some(database) = &self.database {
+            prompt.push_str(&format!("，数据库 {}", database));
+        }
+        prompt.push_str("。回答问题和编写 SQL 时以此数据库为准，使用它的 SQL 方言。\n");
+        if !schema_prompt.is_empty() {
+            prompt.push_str("\n表结构：\n");
+            prompt.push_str(schema_prompt);
+        }
+        prompt
+    }
+}
+
 /// AI 聊天面板事件
 #[derive(Clone, Debug)]
 pub enum AiChatPanelEvent {
     Close,
     ExecuteSql { sql: String },
+    /// 插入到当前激活的 SQL 编辑器
+    InsertSql { sql: String },
+    /// 在对话选定的连接下打开新的查询标签页
+    OpenSqlInNewTab { connection_id: String, database: Option<String>, sql: String },
 }
 
 // ============================================================================
@@ -134,6 +207,7 @@ pub struct SessionListItem {
     session_id: i64,
     name: SharedString,
     updated_at: i64,
+    snippet: Option<SharedString>,
     selected: bool,
     panel: Entity<AiChatPanel>,
 }
@@ -149,10 +223,17 @@ impl SessionListItem {
             session_id,
             name,
             updated_at,
+            snippet: None,
             selected: false,
             panel,
         }
     }
+
+    /// 搜索命中的消息片段
+    pub fn snippet(mut self, snippet: Option<SharedString>) -> Self {
+        self.snippet = snippet;
+        self
+    }
 }
 
 impl Selectable for SessionListItem {
@@ -209,6 +290,15 @@ impl RenderOnce for SessionListItem {
                             .text_ellipsis()
                             .child(self.name.clone())
                     )
+                    .when_some(self.snippet.clone(), |this, snippet| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .overflow_hidden()
+                                .text_ellipsis()
+                                .child(snippet)
+                        )
+                    })
                     .child(
                         div()
                             .text_xs()
@@ -242,29 +332,95 @@ impl RenderOnce for SessionListItem {
 // SessionListDelegate - 历史会话列表代理
 // ============================================================================
 
+#[derive(Clone)]
+struct SessionEntry {
+    session_id: i64,
+    name: SharedString,
+    updated_at: i64,
+    snippet: Option<SharedString>,
+}
+
 pub struct SessionListDelegate {
     panel: Entity<AiChatPanel>,
-    sessions: Vec<(i64, SharedString, i64)>,
-    filtered_sessions: Vec<(i64, SharedString, i64)>,
+    sessions: Vec<SessionEntry>,
+    filtered_sessions: Vec<SessionEntry>,
     selected_index: Option<IndexPath>,
+    /// 最近一次搜索的关键字，用于丢弃已过期的异步全文搜索结果
+    current_query: String,
+}
+
+impl SessionListDelegate {
+    /// 将消息全文搜索结果合并到会话列表，每个会话只保留最相关的一条片段
+    fn merge_search_hits(&mut self, hits: Vec<ChatSearchResult>) {
+        for hit in hits {
+            let snippet = SharedString::from(hit.snippet.replace('\n', " "));
+            if let Some(entry) = self.filtered_sessions.iter_mut().find(|e| e.session_id == hit.session_id) {
+                if entry.snippet.is_none() {
+                    entry.snippet = Some(snippet);
+                }
+                continue;
+            }
+
+            let updated_at = self.sessions
+                .iter()
+                .find(|e| e.session_id == hit.session_id)
+                .map(|e| e.updated_at)
+                .unwrap_or(hit.created_at);
+            self.filtered_sessions.push(SessionEntry {
+                session_id: hit.session_id,
+                name: SharedString::from(hit.session_name),
+                updated_at,
+                snippet: Some(snippet),
+            });
+        }
+    }
 }
 
 impl ListDelegate for SessionListDelegate {
     type Item = SessionListItem;
 
     fn perform_search(&mut self, query: &str, _window: &mut Window, cx: &mut Context<ListState<Self>>) -> Task<()> {
+        let query = query.trim().to_string();
+        self.current_query = query.clone();
         if query.is_empty() {
             self.filtered_sessions = self.sessions.clone();
-        } else {
-            let query_lower = query.to_lowercase();
-            self.filtered_sessions = self.sessions
-                .iter()
-                .filter(|(_, name, _)| name.to_lowercase().contains(&query_lower))
-                .cloned()
-                .collect();
+            cx.notify();
+            return Task::ready(());
         }
+
+        let query_lower = query.to_lowercase();
+        self.filtered_sessions = self.sessions
+            .iter()
+            .filter(|e| e.name.to_lowercase().contains(&query_lower))
+            .cloned()
+            .collect();
         cx.notify();
-        Task::ready(())
+
+        // 同时全文搜索历史消息内容
+        let storage_manager = self.panel.read(cx).storage_manager.clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            use one_core::gpui_tokio::Tokio;
+
+            let search_query = query.clone();
+            let result = Tokio::spawn(cx, async move {
+                let message_repo = storage_manager.get::<MessageRepository>().await
+                    .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
+                message_repo.search(&search_query, 50).await
+            });
+
+            if let Ok(task) = result {
+                if let Ok(Ok(hits)) = task.await {
+                    let _ = this.update(cx, |state, cx| {
+                        // 快速输入时较早的搜索可能更晚返回，不能覆盖最新关键字的结果
+                        if state.delegate().current_query != query {
+                            return;
+                        }
+                        state.delegate_mut().merge_search_hits(hits);
+                        cx.notify();
+                    });
+                }
+            }
+        })
     }
 
     fn items_count(&self, _section: usize, _cx: &App) -> usize {
@@ -277,8 +433,11 @@ impl ListDelegate for SessionListDelegate {
         _window: &mut Window,
         _cx: &mut Context<ListState<Self>>,
     ) -> Option<Self::Item> {
-        let (session_id, name, updated_at) = self.filtered_sessions.get(ix.row)?.clone();
-        Some(SessionListItem::new(session_id, name, updated_at, self.panel.clone()))
+        let entry = self.filtered_sessions.get(ix.row)?.clone();
+        Some(
+            SessionListItem::new(entry.session_id, entry.name, entry.updated_at, self.panel.clone())
+                .snippet(entry.snippet),
+        )
     }
 
     fn set_selected_index(
@@ -292,8 +451,8 @@ impl ListDelegate for SessionListDelegate {
 
     fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<ListState<Self>>) {
         if let Some(ix) = self.selected_index {
-            if let Some((session_id, _, _)) = self.filtered_sessions.get(ix.row) {
-                let session_id = *session_id;
+            if let Some(entry) = self.filtered_sessions.get(ix.row) {
+                let session_id = entry.session_id;
                 self.panel.update(cx, |this, cx| {
                     this.history_popover_open = false;
                     this.load_session(session_id, cx);
@@ -311,14 +470,74 @@ impl ListDelegate for SessionListDelegate {
     }
 }
 
+/// 生成参数 Popover 中的输入框
+struct GenerationParamInputs {
+    temperature: Entity<InputState>,
+    top_p: Entity<InputState>,
+    max_tokens: Entity<InputState>,
+    stop: Entity<InputState>,
+    frequency_penalty: Entity<InputState>,
+}
+
+impl GenerationParamInputs {
+    fn new(window: &mut Window, cx: &mut App) -> Self {
+        Self {
+            temperature: cx.new(|cx| InputState::new(window, cx).placeholder("默认")),
+            top_p: cx.new(|cx| InputState::new(window, cx).placeholder("默认")),
+            max_tokens: cx.new(|cx| InputState::new(window, cx).placeholder("默认")),
+            stop: cx.new(|cx| InputState::new(window, cx).placeholder("以逗号分隔")),
+            frequency_penalty: cx.new(|cx| InputState::new(window, cx).placeholder("默认")),
+        }
+    }
+
+    /// 读取输入框中的参数，空值使用 provider 默认配置；有值但无法解析时返回出错的参数名，
+    /// 不能悄悄丢掉用户填的值
+    fn read(&self, cx: &App) -> Result<GenerationParams, &'static str> {
+        fn parse<T: std::str::FromStr>(input: &Entity<InputState>, label: &'static str, cx: &App) -> Result<Option<T>, &'static str> {
+            let value = input.read(cx).value();
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            value.parse().map(Some).map_err(|_| label)
+        }
+
+        Ok(GenerationParams {
+            max_tokens: parse(&self.max_tokens, "Max Tokens", cx)?,
+            temperature: parse(&self.temperature, "Temperature", cx)?,
+            top_p: parse(&self.top_p, "Top P", cx)?,
+            stop: self
+                .stop
+                .read(cx)
+                .value()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            frequency_penalty: parse(&self.frequency_penalty, "Frequency Penalty", cx)?,
+        })
+    }
+
+    /// 输入框有值但不是合法数字
+    fn is_invalid<T: std::str::FromStr>(input: &Entity<InputState>, cx: &App) -> bool {
+        let value = input.read(cx).value();
+        let value = value.trim();
+        !value.is_empty() && value.parse::<T>().is_err()
+    }
+}
+
 /// AI 聊天面板
 pub struct AiChatPanel {
     focus_handle: FocusHandle,
     messages: Vec<ChatMessageUI>,
     ai_input: Entity<AIInput>,
     _input_subscription: Subscription,
+    /// 生成参数输入变化时重绘，及时显示校验错误
+    _param_subscriptions: Vec<Subscription>,
     session_id: Option<i64>,
     provider_id: Option<String>,
+    /// 已启用的模型及其能力元数据，选择器按数据库工具开关从中过滤
+    providers: Vec<(ProviderItem, Option<ModelInfo>)>,
     connection_name: Option<String>,
     database: Option<String>,
     is_loading: bool,
@@ -328,6 +547,20 @@ pub struct AiChatPanel {
     auto_scroll_enabled: bool,
     history_popover_open: bool,
     session_list: Option<Entity<ListState<SessionListDelegate>>>,
+    params_popover_open: bool,
+    param_inputs: GenerationParamInputs,
+    /// 正在编辑的用户消息在 `messages` 中的位置
+    editing_index: Option<usize>,
+    /// 用户是否允许模型通过工具只读查询已打开的数据库
+    database_tools_enabled: bool,
+    /// 当前对话的数据库上下文，决定注入哪些表结构
+    db_context: Option<ChatDbContext>,
+    /// 每个已保存会话最后使用的数据库上下文
+    session_contexts: HashMap<i64, Option<ChatDbContext>>,
+    /// 上下文选择器的候选项
+    context_connections: Vec<DbConnectionConfig>,
+    context_databases: Vec<String>,
+    context_tables: Vec<String>,
 }
 
 
@@ -336,9 +569,15 @@ impl AiChatPanel {
         let focus_handle = cx.focus_handle();
         let ai_input = cx.new(|cx| AIInput::new(window, cx));
 
-        let input_subscription = cx.subscribe_in(&ai_input, window, |this, _input, event, _window, cx| {
+        let input_subscription = cx.subscribe_in(&ai_input, window, |this, _input, event, window, cx| {
             match event {
                 AIInputEvent::Submit { content } => {
+                    // 输入框在发出事件时已清空，参数有误时把问题放回去，改好参数后直接再发
+                    if let Err(label) = this.generation_params(cx) {
+                        this.show_invalid_param(label, cx);
+                        this.ai_input.update(cx, |input, cx| input.set_content(content.clone(), window, cx));
+                        return;
+                    }
                     this.send_message(content.clone(), cx);
                 },
                 AIInputEvent::ProviderChanged { provider_id } => {
@@ -352,13 +591,21 @@ impl AiChatPanel {
         let global_state = cx.global::<GlobalStorageState>();
         let storage_manager = global_state.storage.clone();
 
+        let param_inputs = GenerationParamInputs::new(window, cx);
+        let param_subscriptions = [&param_inputs.temperature, &param_inputs.top_p, &param_inputs.max_tokens, &param_inputs.frequency_penalty]
+            .into_iter()
+            .map(|input| cx.observe(input, |_, _, cx| cx.notify()))
+            .collect();
+
         let mut panel = Self {
             focus_handle,
             messages: Vec::new(),
             ai_input,
             _input_subscription: input_subscription,
+            _param_subscriptions: param_subscriptions,
             session_id: None,
             provider_id: None,
+            providers: Vec::new(),
             connection_name: None,
             database: None,
             is_loading: false,
@@ -368,30 +615,44 @@ impl AiChatPanel {
             auto_scroll_enabled: true,
             history_popover_open: false,
             session_list: None,
+            params_popover_open: false,
+            param_inputs,
+            editing_index: None,
+            database_tools_enabled: false,
+            db_context: None,
+            session_contexts: HashMap::new(),
+            context_connections: Vec::new(),
+            context_databases: Vec::new(),
+            context_tables: Vec::new(),
         };
 
         // 加载 providers
         panel.load_providers(cx);
+        panel.load_context_connections(cx);
         panel
     }
 
     fn load_providers(&mut self, cx: &mut Context<Self>) {
         let storage_manager = self.storage_manager.clone();
-        let ai_input = self.ai_input.clone();
+        let global_provider_state = cx.global::<GlobalProviderState>().clone();
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
             use one_core::gpui_tokio::Tokio;
-            use crate::ai_input::ProviderItem;
 
             // 在 tokio 运行时中执行持久层操作
             let result = Tokio::spawn(cx, async move {
                 let repo = storage_manager.get::<ProviderRepository>().await
                     .ok_or_else(|| anyhow::anyhow!("ProviderRepository not found"))?;
                 let all_providers = repo.list().await?;
-                let enabled_providers: Vec<_> = all_providers.into_iter()
-                    .filter(|p| p.enabled)
-                    .collect();
-                Ok::<_, anyhow::Error>(enabled_providers)
+                let mut providers = Vec::new();
+                for config in all_providers.into_iter().filter(|p| p.enabled) {
+                    let item = ProviderItem::from_config(&config);
+                    let model = global_provider_state.manager().get_provider(config).await
+                        .ok()
+                        .and_then(|provider| provider.model_info());
+                    providers.push((item, model));
+                }
+                Ok::<_, anyhow::Error>(providers)
             });
 
             if let Ok(task) = result {
@@ -400,43 +661,216 @@ impl AiChatPanel {
                         return;
                     }
 
-                    let items: Vec<ProviderItem> = providers
-                        .iter()
-                        .map(ProviderItem::from_config)
-                        .collect();
-                    let first_provider_id = providers[0].id.to_string();
-
                     let _ = cx.update(|cx| {
-                        if let Some(window_id) = cx.active_window() {
-                            cx.update_window(window_id, |_entity, window, cx| {
-                                ai_input.update(cx, |input, cx| {
-                                    input.update_providers(items.clone(), window, cx);
+                        let Some(window_id) = cx.active_window() else {
+                            return Ok(());
+                        };
+                        cx.update_window(window_id, |_entity, window, cx| {
+                            if let Some(entity) = this.upgrade() {
+                                entity.update(cx, |panel, cx| {
+                                    panel.providers = providers;
+                                    panel.refresh_provider_items(window, cx);
                                 });
-                            })
-                        } else {
-                            Ok(())
-                        }
+                            }
+                        })
                     });
-
-                    if let Some(entity) = this.upgrade() {
-                        let _ = cx.update(|cx| {
-                            entity.update(cx, |panel, cx| {
-                                panel.provider_id = Some(first_provider_id.clone());
-                                cx.notify();
-                            });
-                        });
-                    }
                 }
             }
         })
         .detach();
     }
 
+    /// 开启数据库工具时只列出支持工具调用的模型；元数据未知的模型（如自定义模型）无法判断，保留
+    fn visible_providers(&self) -> Vec<ProviderItem> {
+        let tool_models: Vec<String> = filter_models(
+            self.providers.iter().filter_map(|(_, model)| model.clone()).collect(),
+            true,
+            false,
+        )
+        .into_iter()
+        .map(|model| model.id)
+        .collect();
+
+        self.providers
+            .iter()
+            .filter(|(item, model)| !self.database_tools_enabled || model.is_none() || tool_models.contains(&item.model))
+            .map(|(item, _)| item.clone())
+            .collect()
+    }
+
+    /// 按当前的能力要求重建模型选择器，已选模型被过滤掉时切换到第一个可用模型
+    fn refresh_provider_items(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let items = self.visible_providers();
+        let still_visible = self.provider_id.as_ref().is_some_and(|id| items.iter().any(|item| &item.id == id));
+        if !still_visible {
+            self.provider_id = items.first().map(|item| item.id.clone());
+        }
+
+        let provider_id = self.provider_id.clone();
+        self.ai_input.update(cx, |input, cx| {
+            input.update_providers(items, window, cx);
+            input.set_selected_provider(provider_id, window, cx);
+        });
+        cx.notify();
+    }
+
     pub fn set_connection_info(&mut self, connection_name: Option<String>, database: Option<String>) {
         self.connection_name = connection_name;
         self.database = database;
     }
 
+    pub fn db_context(&self) -> Option<&ChatDbContext> {
+        self.db_context.as_ref()
+    }
+
+    /// 刷新上下文选择器中可选的连接
+    pub fn load_context_connections(&mut self, cx: &mut Context<Self>) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            match global_state.list_connections(cx).await {
+                Ok(mut connections) => {
+                    connections.sort_by(|a, b| a.name.cmp(&b.name));
+                    let _ = this.update(cx, |this, cx| {
+                        this.context_connections = connections;
+                        cx.notify();
+                    });
+                }
+                Err(e) => eprintln!("Failed to load connections: {}", e),
+            }
+        }).detach();
+    }
+
+    fn load_context_databases(&mut self, cx: &mut Context<Self>) {
+        self.context_databases.clear();
+        let Some(connection_id) = self.db_context.as_ref().map(|c| c.connection_id.clone()) else {
+            return;
+        };
+        let global_state = cx.global::<GlobalDbState>().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.list_databases(cx, connection_id.clone()).await;
+            let _ = this.update(cx, |this, cx| {
+                // 加载期间用户可能已切换到其他连接
+                if this.db_context.as_ref().map(|c| &c.connection_id) != Some(&connection_id) {
+                    return;
+                }
+                match result {
+                    Ok(databases) => this.context_databases = databases,
+                    Err(e) => Self::notify(cx, format!("加载数据库列表失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn load_context_tables(&mut self, cx: &mut Context<Self>) {
+        self.context_tables.clear();
+        let Some((connection_id, database)) = self
+            .db_context
+            .as_ref()
+            .and_then(|c| Some((c.connection_id.clone(), c.database.clone()?)))
+        else {
+            return;
+        };
+        let global_state = cx.global::<GlobalDbState>().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.list_tables(cx, connection_id.clone(), database.clone()).await;
+            let _ = this.update(cx, |this, cx| {
+                let current = this.db_context.as_ref().map(|c| (&c.connection_id, c.database.as_ref()));
+                if current != Some((&connection_id, Some(&database))) {
+                    return;
+                }
+                match result {
+                    Ok(tables) => this.context_tables = tables.into_iter().map(|t| t.name).collect(),
+                    Err(e) => Self::notify(cx, format!("加载表列表失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    /// 切换对话关注的连接，数据库默认使用连接配置中的数据库
+    pub fn select_context_connection(&mut self, config: &DbConnectionConfig, cx: &mut Context<Self>) {
+        if self.db_context.as_ref().is_some_and(|c| c.connection_id == config.id) {
+            return;
+        }
+        self.db_context = Some(ChatDbContext::from_config(config));
+        self.load_context_databases(cx);
+        self.load_context_tables(cx);
+        self.context_changed(cx);
+    }
+
+    pub fn select_context_database(&mut self, database: String, cx: &mut Context<Self>) {
+        let Some(context) = self.db_context.as_mut() else {
+            return;
+        };
+        if context.database.as_ref() == Some(&database) {
+            return;
+        }
+        context.database = Some(database);
+        context.tables.clear();
+        self.load_context_tables(cx);
+        self.context_changed(cx);
+    }
+
+    /// 选中或取消选中一张表；`None` 表示使用整个数据库
+    pub fn toggle_context_table(&mut self, table: Option<String>, cx: &mut Context<Self>) {
+        let Some(context) = self.db_context.as_mut() else {
+            return;
+        };
+        match table {
+            Some(table) => {
+                if let Some(ix) = context.tables.iter().position(|t| *t == table) {
+                    context.tables.remove(ix);
+                } else {
+                    context.tables.push(table);
+                }
+            }
+            None if context.tables.is_empty() => return,
+            None => context.tables.clear(),
+        }
+        self.context_changed(cx);
+    }
+
+    pub fn clear_context(&mut self, cx: &mut Context<Self>) {
+        if self.db_context.take().is_none() {
+            return;
+        }
+        self.context_databases.clear();
+        self.context_tables.clear();
+        self.context_changed(cx);
+    }
+
+    /// 记录到当前会话，并在对话中提示之后的回答基于新的上下文
+    fn context_changed(&mut self, cx: &mut Context<Self>) {
+        if let Some(session_id) = self.session_id {
+            self.session_contexts.insert(session_id, self.db_context.clone());
+        }
+        let notice = match &self.db_context {
+            Some(context) => format!("数据库上下文：{}", context.label()),
+            None => "已清除数据库上下文".to_string(),
+        };
+        // 连续切换时只保留最后一条提示
+        if self.messages.last().is_some_and(|m| m.role == ChatRole::System) {
+            self.messages.pop();
+        }
+        self.messages.push(ChatMessageUI::from_llm_message(&LlmChatMessage::system(notice)));
+        self.auto_scroll_to_bottom();
+        cx.notify();
+    }
+
+    /// 新标签页使用对话选定的连接和数据库
+    fn open_sql_in_new_tab(&mut self, sql: String, cx: &mut Context<Self>) {
+        let Some(context) = self.db_context.as_ref() else {
+            Self::notify(cx, "请先在上方选择对话的连接".to_string());
+            return;
+        };
+        cx.emit(AiChatPanelEvent::OpenSqlInNewTab {
+            connection_id: context.connection_id.clone(),
+            database: context.database.clone(),
+            sql,
+        });
+    }
+
     pub fn set_provider_id(&mut self, provider_id: String, cx: &mut Context<Self>) {
         self.provider_id = Some(provider_id);
         cx.notify();
@@ -445,6 +879,7 @@ impl AiChatPanel {
     // 创建新会话 - 同步返回，异步保存
     pub fn start_new_session(&mut self, cx: &mut Context<Self>) {
         self.session_id = None;
+        self.editing_index = None;
         self.messages.clear();
         cx.notify();
     }
@@ -459,10 +894,46 @@ impl AiChatPanel {
         cx.notify();
     }
 
+    pub fn toggle_params_popover(&mut self, cx: &mut Context<Self>) {
+        self.params_popover_open = !self.params_popover_open;
+        cx.notify();
+    }
+
+    pub fn database_tools_enabled(&self) -> bool {
+        self.database_tools_enabled
+    }
+
+    pub fn toggle_database_tools(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.database_tools_enabled = !self.database_tools_enabled;
+        let message = if self.database_tools_enabled {
+            "已允许 AI 助手查询数据库：只会执行只读的 SELECT 语句，模型列表只显示支持工具调用的模型"
+        } else {
+            "已关闭 AI 助手的数据库查询"
+        };
+        Self::notify(cx, message.to_string());
+        self.refresh_provider_items(window, cx);
+    }
+
+    pub fn generation_params(&self, cx: &App) -> Result<GenerationParams, &'static str> {
+        self.param_inputs.read(cx)
+    }
+
+    /// 打开参数面板让用户看到出错的输入框
+    fn show_invalid_param(&mut self, label: &str, cx: &mut Context<Self>) {
+        self.params_popover_open = true;
+        Self::notify(cx, format!("生成参数 {} 不是有效的数字，请修改或清空后再发送", label));
+        cx.notify();
+    }
+
     fn update_session_list(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let sessions_data: Vec<(i64, SharedString, i64)> = self.history_sessions
+        let sessions_data: Vec<SessionEntry> = self.history_sessions
             .iter()
-            .map(|s| (s.id, SharedString::from(s.name.clone()), s.updated_at))
+            .map(|s| SessionEntry {
+                session_id: s.id,
+                name: SharedString::from(s.name.clone()),
+                updated_at: s.updated_at,
+                snippet: None,
+            })
             .collect();
         let panel = cx.entity();
 
@@ -480,6 +951,7 @@ impl AiChatPanel {
                         sessions: sessions_data.clone(),
                         filtered_sessions: sessions_data,
                         selected_index: None,
+                        current_query: String::new(),
                     },
                     window,
                     cx,
@@ -600,31 +1072,21 @@ impl AiChatPanel {
             let result = Tokio::spawn(cx, async move {
                 let message_repo = storage_manager.get::<MessageRepository>().await
                     .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
-                message_repo.list_by_session(session_id).await
+                let branch = message_repo.list_by_session(session_id).await?;
+                let all = message_repo.list_all_by_session(session_id).await?;
+                Ok::<_, anyhow::Error>((branch, all))
             });
 
             if let Ok(task) = result {
-                if let Ok(Ok(messages)) = task.await {
+                if let Ok(Ok((branch, all))) = task.await {
                     if let Some(entity) = this.upgrade() {
                         let _ = cx.update(|cx| {
                             entity.update(cx, |this, cx| {
                                 this.session_id = Some(session_id);
-                                this.messages = messages.iter()
-                                    .map(|msg| ChatMessageUI {
-                                        id: msg.id.to_string(),
-                                        role: match msg.role.as_str() {
-                                            "user" => ChatRole::User,
-                                            "assistant" => ChatRole::Assistant,
-                                            "system" => ChatRole::System,
-                                            _ => ChatRole::User,
-                                        },
-                                        content: msg.content.clone(),
-                                        variant: MessageVariant::Text,
-                                        is_streaming: false,
-                                        is_expanded: true,
-                                    })
-                                    .collect();
+                                this.editing_index = None;
+                                this.messages = Self::messages_from_branch(&branch, &all);
                                 this.history_popover_open = false;
+                                this.restore_session_context(session_id, cx);
                                 cx.notify();
                             });
                         });
@@ -634,6 +1096,233 @@ impl AiChatPanel {
         }).detach();
     }
 
+    /// 切换到会话上次使用的数据库上下文；没有记录的会话沿用当前上下文
+    fn restore_session_context(&mut self, session_id: i64, cx: &mut Context<Self>) {
+        let Some(context) = self.session_contexts.get(&session_id).cloned() else {
+            self.session_contexts.insert(session_id, self.db_context.clone());
+            return;
+        };
+        if context == self.db_context {
+            return;
+        }
+        let connection_changed = context.as_ref().map(|c| &c.connection_id)
+            != self.db_context.as_ref().map(|c| &c.connection_id);
+        self.db_context = context;
+        if connection_changed {
+            self.load_context_databases(cx);
+        }
+        self.load_context_tables(cx);
+    }
+
+    fn messages_from_branch(branch: &[ChatMessage], all: &[ChatMessage]) -> Vec<ChatMessageUI> {
+        branch.iter()
+            .map(|msg| ChatMessageUI {
+                id: msg.id.to_string(),
+                role: match msg.role.as_str() {
+                    "user" => ChatRole::User,
+                    "assistant" => ChatRole::Assistant,
+                    "system" => ChatRole::System,
+                    _ => ChatRole::User,
+                },
+                content: msg.content.clone(),
+                variant: MessageVariant::Text,
+                is_streaming: false,
+                is_expanded: true,
+                db_id: Some(msg.id),
+                sibling_ids: sibling_ids(all, msg),
+            })
+            .collect()
+    }
+
+    /// 切换到同一位置的另一个版本
+    fn switch_version(&mut self, message_id: i64, cx: &mut Context<Self>) {
+        let Some(session_id) = self.session_id else {
+            return;
+        };
+        if self.is_loading {
+            return;
+        }
+        let storage_manager = self.storage_manager.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            use one_core::gpui_tokio::Tokio;
+
+            let result = Tokio::spawn_result(cx, async move {
+                let message_repo = storage_manager.get::<MessageRepository>().await
+                    .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
+                message_repo.switch_branch(session_id, message_id).await
+            });
+
+            match result {
+                Ok(task) => {
+                    if let Err(e) = task.await {
+                        let _ = cx.update(|cx| Self::notify(cx, format!("切换分支失败: {}", e)));
+                        return;
+                    }
+                    let _ = this.update(cx, |this, cx| this.load_session(session_id, cx));
+                }
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::notify(cx, format!("切换分支失败: {}", e)));
+                }
+            }
+        }).detach();
+    }
+
+    /// 编辑较早的用户消息：把内容放回输入框，发送后从该位置创建新分支
+    fn start_edit(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(msg) = self.messages.get(index) else {
+            return;
+        };
+        if self.is_loading || msg.role != ChatRole::User || msg.db_id.is_none() {
+            return;
+        }
+        self.editing_index = Some(index);
+        let content = msg.content.clone();
+        self.ai_input.update(cx, |input, cx| input.set_content(content, window, cx));
+        cx.notify();
+    }
+
+    fn cancel_edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing_index = None;
+        self.ai_input.update(cx, |input, cx| input.set_content(String::new(), window, cx));
+        cx.notify();
+    }
+
+    /// 重新生成助手回复，旧回复保留为同级分支
+    fn regenerate(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.is_loading || index == 0 {
+            return;
+        }
+        let Some(question) = self.messages.get(index - 1).filter(|m| m.role == ChatRole::User) else {
+            return;
+        };
+        let Some(question_id) = question.db_id else {
+            return;
+        };
+        let content = question.content.clone();
+        self.rewind_then(index, Some(question_id), cx, move |this, cx| {
+            this.request_reply(content, false, cx);
+        });
+    }
+
+    /// 用编辑后的内容替换 `index` 处的用户消息，原消息保留为同级分支
+    fn submit_edit(&mut self, index: usize, content: String, cx: &mut Context<Self>) {
+        let parent_id = match index {
+            0 => None,
+            _ => match self.messages.get(index - 1).and_then(|m| m.db_id) {
+                Some(id) => Some(id),
+                None => return,
+            },
+        };
+        self.rewind_then(index, parent_id, cx, move |this, cx| {
+            this.request_reply(content, true, cx);
+        });
+    }
+
+    /// 截断界面上 `index` 及之后的消息，并把活动分支回退到 `leaf_id`，完成后执行 `then`
+    fn rewind_then(
+        &mut self,
+        index: usize,
+        leaf_id: Option<i64>,
+        cx: &mut Context<Self>,
+        then: impl FnOnce(&mut Self, &mut Context<Self>) + 'static,
+    ) {
+        let Some(session_id) = self.session_id else {
+            return;
+        };
+        let storage_manager = self.storage_manager.clone();
+        self.messages.truncate(index);
+        self.editing_index = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            use one_core::gpui_tokio::Tokio;
+
+            let result = Tokio::spawn_result(cx, async move {
+                let message_repo = storage_manager.get::<MessageRepository>().await
+                    .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
+                message_repo.set_active_leaf(session_id, leaf_id).await
+            });
+
+            let error = match result {
+                Ok(task) => task.await.err(),
+                Err(e) => Some(e),
+            };
+            let _ = this.update(cx, |this, cx| match error {
+                Some(e) => {
+                    Self::notify(cx, format!("创建分支失败: {}", e));
+                    this.load_session(session_id, cx);
+                }
+                None => then(this, cx),
+            });
+        }).detach();
+    }
+
+    /// 将当前对话导出为 Markdown 或 JSON 文件
+    pub fn export_session(&mut self, format: ChatExportFormat, cx: &mut Context<Self>) {
+        let Some(session_id) = self.session_id else {
+            Self::notify(cx, "当前没有可导出的对话".to_string());
+            return;
+        };
+        let storage_manager = self.storage_manager.clone();
+        let directory = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default();
+
+        cx.spawn(async move |_this, cx: &mut AsyncApp| {
+            use one_core::gpui_tokio::Tokio;
+
+            let result = Tokio::spawn_result(cx, async move {
+                let session_repo = storage_manager.get::<SessionRepository>().await
+                    .ok_or_else(|| anyhow::anyhow!("SessionRepository not found"))?;
+                let message_repo = storage_manager.get::<MessageRepository>().await
+                    .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
+                let session = session_repo.get(session_id).await?
+                    .ok_or_else(|| anyhow::anyhow!("Session {} not found", session_id))?;
+                let messages = message_repo.list_by_session(session_id).await?;
+                let content = export_conversation(&session, &messages, format)?;
+                Ok((session.name, content))
+            });
+
+            let (name, content) = match result {
+                Ok(task) => match task.await {
+                    Ok(exported) => exported,
+                    Err(e) => {
+                        let _ = cx.update(|cx| Self::notify(cx, format!("导出对话失败: {}", e)));
+                        return;
+                    }
+                },
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::notify(cx, format!("导出对话失败: {}", e)));
+                    return;
+                }
+            };
+
+            let file_name = format!("{}.{}", sanitize_file_name(&name), format.extension());
+            let Ok(path_future) = cx.update(|cx| cx.prompt_for_new_path(&directory, Some(&file_name))) else {
+                return;
+            };
+            let path = match path_future.await {
+                Ok(Ok(Some(path))) => path,
+                _ => return,
+            };
+
+            let message = match std::fs::write(&path, content) {
+                Ok(()) => format!("对话已导出到 {}", path.display()),
+                Err(e) => format!("导出对话失败: {}", e),
+            };
+            let _ = cx.update(|cx| Self::notify(cx, message));
+        }).detach();
+    }
+
+    fn notify(cx: &mut App, message: String) {
+        if let Some(window) = cx.active_window() {
+            _ = window.update(cx, |_, window, cx| {
+                window.push_notification(message, cx);
+            });
+        }
+    }
+
     #[allow(dead_code)]
     fn save_message(&self, session_id: i64, role: &str, content: &str, cx: &mut Context<Self>) {
         let storage_manager = self.storage_manager.clone();
@@ -646,14 +1335,43 @@ impl AiChatPanel {
                 let message_repo = storage_manager.get::<MessageRepository>().await
                     .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
                 let mut message = ChatMessage::new(session_id, message_role, message_content);
-                message_repo.insert(&mut message).await
+                message_repo.append(&mut message).await
             }) {
                 let _ = task.await;
             }
         }).detach();
     }
 
+    /// 先按树中选中的节点替换 `{{table}}` 等上下文变量，界面和历史中保存替换后的内容
     fn send_message(&mut self, content: String, cx: &mut Context<Self>) {
+        if referenced_prompt_variables(&content).is_empty() {
+            self.dispatch_message(content, cx);
+            return;
+        }
+
+        let node = SelectedTreeNode::get(cx);
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            match resolve_prompt_variables(content, node, cx).await {
+                Ok(content) => {
+                    let _ = this.update(cx, |this, cx| this.dispatch_message(content, cx));
+                }
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::notify(cx, format!("替换提示词变量失败: {}", e)));
+                }
+            }
+        }).detach();
+    }
+
+    fn dispatch_message(&mut self, content: String, cx: &mut Context<Self>) {
+        record_feature("ai.chat", cx);
+        match self.editing_index.take() {
+            Some(index) => self.submit_edit(index, content, cx),
+            None => self.request_reply(content, true, cx),
+        }
+    }
+
+    /// 请求助手回复；`save_question` 为 false 时表示重新生成，问题已在活动分支上
+    fn request_reply(&mut self, content: String, save_question: bool, cx: &mut Context<Self>) {
         if content.trim().is_empty() || self.is_loading {
             return;
         }
@@ -677,9 +1395,20 @@ impl AiChatPanel {
         let storage_manager = self.storage_manager.clone();
         let connection_name = self.connection_name.clone();
         let session_id = self.session_id;
+        let generation_params = match self.generation_params(cx) {
+            Ok(params) => params,
+            Err(label) => {
+                self.show_invalid_param(label, cx);
+                return;
+            }
+        };
+        let database_tools_enabled = self.database_tools_enabled;
+        let db_context = self.db_context.clone();
 
         // 添加用户消息到 UI
-        self.messages.push(ChatMessageUI::user(content.clone()));
+        if save_question {
+            self.messages.push(ChatMessageUI::user(content.clone()));
+        }
 
         // 创建助手消息占位符
         let assistant_msg_id = Uuid::new_v4().to_string();
@@ -690,6 +1419,8 @@ impl AiChatPanel {
             variant: MessageVariant::Text,
             is_streaming: true,
             is_expanded: true,
+            db_id: None,
+            sibling_ids: Vec::new(),
         });
 
         self.auto_scroll_enabled = true;
@@ -720,6 +1451,7 @@ impl AiChatPanel {
                                     let _ = cx.update(|cx| {
                                         entity.update(cx, |this, cx| {
                                             this.session_id = Some(id);
+                                            this.session_contexts.insert(id, this.db_context.clone());
                                             cx.notify();
                                         });
                                     });
@@ -748,17 +1480,19 @@ impl AiChatPanel {
             };
 
             // 保存用户消息
-            let content_clone = content.clone();
-            let storage_manager_for_save = storage_manager.clone();
-            if let Ok(task) = Tokio::spawn_result(cx, async move {
-                let message_repo = storage_manager_for_save.get::<MessageRepository>().await
-                    .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
-                let mut message = ChatMessage::new(session_db_id, "user".to_string(), content_clone);
-                message_repo.insert(&mut message).await?;
-                Ok(())
-            }) {
-                if let Err(e) = task.await {
-                    eprintln!("Failed to save user message: {}", e);
+            if save_question {
+                let content_clone = content.clone();
+                let storage_manager_for_save = storage_manager.clone();
+                if let Ok(task) = Tokio::spawn_result(cx, async move {
+                    let message_repo = storage_manager_for_save.get::<MessageRepository>().await
+                        .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
+                    let mut message = ChatMessage::new(session_db_id, "user".to_string(), content_clone);
+                    message_repo.append(&mut message).await?;
+                    Ok(())
+                }) {
+                    if let Err(e) = task.await {
+                        eprintln!("Failed to save user message: {}", e);
+                    }
                 }
             }
 
@@ -769,10 +1503,7 @@ impl AiChatPanel {
                     .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
                 let messages = message_repo.list_by_session(session_db_id).await?;
                 Ok::<Vec<LlmChatMessage>, anyhow::Error>(
-                    messages.iter().map(|msg| LlmChatMessage {
-                        role: msg.role.clone(),
-                        content: msg.content.clone(),
-                    }).collect()
+                    messages.iter().map(|msg| LlmChatMessage::new(msg.role.clone(), msg.content.clone())).collect()
                 )
             });
 
@@ -792,21 +1523,92 @@ impl AiChatPanel {
                 history.push(LlmChatMessage::user(content.clone()));
             }
 
-            let request = ChatRequest {
-                messages: history,
-                max_tokens: Some(2000),
-                temperature: Some(0.7),
-                stream: true,
+            let mut system_prompts = Vec::new();
+            if database_tools_enabled {
+                system_prompts.push(ai_tools::system_prompt());
+            }
+            if let Some(context) = &db_context {
+                let schema_prompt = match &context.database {
+                    Some(database) => ai_sql::chat_schema_context(
+                        context.connection_id.clone(),
+                        database.clone(),
+                        context.tables.clone(),
+                        &content,
+                        cx,
+                    )
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to collect schema context: {}", e);
+                        String::new()
+                    }),
+                    None => String::new(),
+                };
+                system_prompts.push(context.system_prompt(&schema_prompt));
+            }
+            if !system_prompts.is_empty() && history.first().map_or(true, |m| m.role != "system") {
+                history.insert(0, LlmChatMessage::system(system_prompts.join("\n\n")));
+            }
+
+            let mut request = ChatRequest::new(history).with_params(&generation_params);
+
+            // 允许查询数据库时使用非流式的工具调用，最终回答作为一个整块返回
+            let tool_reply = if database_tools_enabled {
+                let mut progress = String::new();
+                let result = ai_tools::chat_with_tools(provider_id, request.clone(), |call, cx| {
+                    progress.push_str(&format!("> {}\n", ai_tools::describe_call(call)));
+                    if let Some(entity) = this.upgrade() {
+                        let content = progress.clone();
+                        let msg_id = assistant_msg_id.clone();
+                        let _ = cx.update(|cx| {
+                            entity.update(cx, |this, cx| {
+                                if let Some(msg) = this.messages.iter_mut().find(|m| m.id == msg_id) {
+                                    msg.content = content;
+                                }
+                                this.auto_scroll_to_bottom();
+                                cx.notify();
+                            });
+                        });
+                    }
+                }, cx).await;
+                let events = match result {
+                    Ok(answer) => {
+                        let delta = if progress.is_empty() { answer } else { format!("{}\n{}", progress, answer) };
+                        vec![
+                            ChatStreamEvent::Chunk(ChatStreamChunk { delta, finish_reason: None }),
+                            ChatStreamEvent::Done(None),
+                        ]
+                    }
+                    Err(e) => vec![ChatStreamEvent::Error(e.to_string())],
+                };
+                Some(events)
+            } else {
+                None
             };
+            request.stream = true;
 
             // 开始流式聊天
             let storage_manager_for_stream = storage_manager.clone();
             let stream_result = Tokio::spawn(cx, async move {
+                if let Some(events) = tool_reply {
+                    return Ok(stream::iter(events).boxed());
+                }
                 let repo = storage_manager_for_stream.get::<ProviderRepository>().await
                     .ok_or_else(|| anyhow::anyhow!("ProviderRepository not found"))?;
                 let config = repo.get(provider_id).await?
                     .ok_or_else(|| anyhow::anyhow!("Provider not found: {}", provider_id))?;
                 let provider = global_provider_state.manager().get_provider(config).await?;
+                if let Some(model) = provider.model_info() {
+                    let prompt_tokens = estimate_tokens(&request.messages);
+                    let reserved = request.max_tokens.or(provider.config().max_tokens).unwrap_or(0).max(0) as u32;
+                    if !model.fits_context(prompt_tokens, reserved) {
+                        anyhow::bail!(
+                            "对话内容约 {} tokens，超出模型 {} 的上下文窗口 ({} tokens)，请新建对话",
+                            prompt_tokens,
+                            model.name,
+                            model.context_window.unwrap_or_default()
+                        );
+                    }
+                }
                 provider.chat_stream(request).await
             });
 
@@ -874,6 +1676,8 @@ impl AiChatPanel {
                             });
                         }
                     }
+                    // 思考过程不展示在回答中
+                    ChatStreamEvent::Thinking(_) => {}
                     ChatStreamEvent::Done(_) => break,
                     ChatStreamEvent::Error(err) => {
                         if let Some(entity) = this.upgrade() {
@@ -910,18 +1714,22 @@ impl AiChatPanel {
 
                         // 保存助手消息到数据库
                         let final_content_inner = final_content.clone();
-                        cx.spawn(async move |_this, cx: &mut AsyncApp| {
+                        cx.spawn(async move |this, cx: &mut AsyncApp| {
                             use one_core::gpui_tokio::Tokio;
                             match Tokio::spawn_result(cx, async move {
                                 let message_repo = storage_manager_final.get::<MessageRepository>().await
                                     .ok_or_else(|| anyhow::anyhow!("MessageRepository not found"))?;
                                 let mut assistant_message = ChatMessage::new(session_db_id, "assistant".to_string(), final_content_inner);
-                                message_repo.insert(&mut assistant_message).await?;
+                                message_repo.append(&mut assistant_message).await?;
                                 Ok(())
                             }) {
                                 Ok(task) => {
-                                    if let Err(e) = task.await {
-                                        eprintln!("Error saving assistant message: {}", e);
+                                    match task.await {
+                                        // 重新加载以获取消息 ID 和分支信息
+                                        Ok(()) => {
+                                            let _ = this.update(cx, |this, cx| this.load_session(session_db_id, cx));
+                                        }
+                                        Err(e) => eprintln!("Error saving assistant message: {}", e),
                                     }
                                 }
                                 Err(e) => {
@@ -972,7 +1780,7 @@ impl AiChatPanel {
                             .into_iter()
                             .filter_map(|ix| {
                                 this.messages.get(ix).map(|msg| {
-                                    this.render_message(msg, window, cx)
+                                    this.render_message(ix, msg, window, cx)
                                 })
                             })
                             .collect()
@@ -989,12 +1797,14 @@ impl AiChatPanel {
         }
     }
 
-    fn render_message(&self, msg: &ChatMessageUI, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+    fn render_message(&self, index: usize, msg: &ChatMessageUI, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
         match msg.role {
             ChatRole::User => {
-                h_flex()
+                let is_editing = self.editing_index == Some(index);
+                v_flex()
                     .w_full()
-                    .justify_end()
+                    .items_end()
+                    .gap_1()
                     .child(
                         div()
                             .px_4()
@@ -1002,8 +1812,26 @@ impl AiChatPanel {
                             .bg(cx.theme().muted)
                             .rounded_lg()
                             .max_w_4_5()
+                            .when(is_editing, |this| this.border_1().border_color(cx.theme().primary))
                             .child(msg.content.clone())
                     )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(self.render_version_switcher(msg, cx))
+                            .when(msg.db_id.is_some() && !self.is_loading, |this| {
+                                this.child(
+                                    Button::new(SharedString::from(format!("edit-msg-{}", msg.id)))
+                                        .icon(IconName::Edit)
+                                        .ghost()
+                                        .xsmall()
+                                        .tooltip("编辑并重新发送")
+                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                            this.start_edit(index, window, cx);
+                                        }))
+                                )
+                            })
+                    )
                     .into_any_element()
             }
             ChatRole::Assistant => {
@@ -1012,7 +1840,32 @@ impl AiChatPanel {
                         self.render_status_message(msg.id.clone(), title, *is_done, msg.is_expanded, cx)
                     }
                     MessageVariant::Text => {
-                        self.render_assistant_message(msg, window, cx)
+                        let can_regenerate = msg.db_id.is_some()
+                            && !self.is_loading
+                            && index > 0
+                            && self.messages.get(index - 1).is_some_and(|m| m.role == ChatRole::User && m.db_id.is_some());
+                        v_flex()
+                            .w_full()
+                            .gap_1()
+                            .child(self.render_assistant_message(msg, window, cx))
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .child(self.render_version_switcher(msg, cx))
+                                    .when(can_regenerate, |this| {
+                                        this.child(
+                                            Button::new(SharedString::from(format!("regenerate-msg-{}", msg.id)))
+                                                .icon(IconName::Refresh)
+                                                .ghost()
+                                                .xsmall()
+                                                .tooltip("重新生成")
+                                                .on_click(cx.listener(move |this, _, _, cx| {
+                                                    this.regenerate(index, cx);
+                                                }))
+                                        )
+                                    })
+                            )
+                            .into_any_element()
                     }
                 }
             }
@@ -1031,6 +1884,50 @@ impl AiChatPanel {
         }
     }
 
+    /// 在同一位置的多个版本间切换：‹ 2/3 ›
+    fn render_version_switcher(&self, msg: &ChatMessageUI, cx: &mut Context<Self>) -> AnyElement {
+        let Some(position) = msg.db_id.and_then(|id| msg.sibling_ids.iter().position(|s| *s == id)) else {
+            return div().into_any_element();
+        };
+        if msg.sibling_ids.len() < 2 {
+            return div().into_any_element();
+        }
+        let previous = position.checked_sub(1).and_then(|i| msg.sibling_ids.get(i).copied());
+        let next = msg.sibling_ids.get(position + 1).copied();
+
+        h_flex()
+            .gap_0p5()
+            .items_center()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(
+                Button::new(SharedString::from(format!("prev-version-{}", msg.id)))
+                    .icon(IconName::ChevronLeft)
+                    .ghost()
+                    .xsmall()
+                    .disabled(previous.is_none() || self.is_loading)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if let Some(id) = previous {
+                            this.switch_version(id, cx);
+                        }
+                    }))
+            )
+            .child(format!("{}/{}", position + 1, msg.sibling_ids.len()))
+            .child(
+                Button::new(SharedString::from(format!("next-version-{}", msg.id)))
+                    .icon(IconName::ChevronRight)
+                    .ghost()
+                    .xsmall()
+                    .disabled(next.is_none() || self.is_loading)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if let Some(id) = next {
+                            this.switch_version(id, cx);
+                        }
+                    }))
+            )
+            .into_any_element()
+    }
+
     fn render_status_message(&self, id: String, title: &str, is_done: bool, _is_expanded: bool, cx: &mut Context<Self>) -> AnyElement {
         let icon = if is_done { IconName::Check } else { IconName::Loader };
 
@@ -1070,19 +1967,52 @@ impl AiChatPanel {
 
         // 使用消息的唯一 ID 作为 TextView 的 ID
         let view_id = SharedString::from(format!("ai-msg-{}", msg.id));
+        let panel = cx.entity();
 
         div()
             .w_full()
             .max_w_4_5()
             .child(
                 TextView::markdown(view_id, msg.content.clone())
-                    .code_block_actions(|code_block, _window, _cx| {
+                    .code_block_actions(move |code_block, _window, _cx| {
                         let code = code_block.code();
                         let lang = code_block.lang();
 
+                        let is_sql = is_sql_code_block(lang.as_deref());
+                        let panel_for_insert = panel.clone();
+                        let panel_for_open = panel.clone();
+                        let sql_for_insert = code.to_string();
+                        let sql_for_open = code.to_string();
+
                         h_flex()
                             .gap_1()
                             .child(Clipboard::new("copy").value(code.clone()))
+                            .when(is_sql, |this| {
+                                this.child(
+                                    Button::new("insert-sql")
+                                        .icon(IconName::ArrowDown)
+                                        .ghost()
+                                        .xsmall()
+                                        .tooltip("插入到当前编辑器")
+                                        .on_click(move |_, _, cx| {
+                                            panel_for_insert.update(cx, |_, cx| {
+                                                cx.emit(AiChatPanelEvent::InsertSql { sql: sql_for_insert.clone() });
+                                            });
+                                        }),
+                                )
+                                .child(
+                                    Button::new("open-sql")
+                                        .icon(IconName::ExternalLink)
+                                        .ghost()
+                                        .xsmall()
+                                        .tooltip("在新查询标签页中打开")
+                                        .on_click(move |_, _, cx| {
+                                            panel_for_open.update(cx, |this, cx| {
+                                                this.open_sql_in_new_tab(sql_for_open.clone(), cx);
+                                            });
+                                        }),
+                                )
+                            })
                             .when_some(lang, |this, lang| {
                                 if lang.as_ref() == "rust" || lang.as_ref() == "python" {
                                     this.child(
@@ -1105,6 +2035,149 @@ impl AiChatPanel {
             .into_any_element()
     }
 
+    fn render_params_popover(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let danger = cx.theme().danger;
+        let field = |label: &'static str, input: &Entity<InputState>, invalid: bool| {
+            v_flex()
+                .gap_1()
+                .child(div().text_xs().child(label))
+                .child(Input::new(input).small())
+                .when(invalid, |this| {
+                    this.child(div().text_xs().text_color(danger).child("请输入有效的数字"))
+                })
+        };
+        let inputs = &self.param_inputs;
+
+        v_flex()
+            .w(px(240.0))
+            .p_3()
+            .gap_2()
+            .bg(cx.theme().background)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(cx.theme().radius)
+            .shadow_lg()
+            .child(field("Temperature", &inputs.temperature, GenerationParamInputs::is_invalid::<f32>(&inputs.temperature, cx)))
+            .child(field("Top P", &inputs.top_p, GenerationParamInputs::is_invalid::<f32>(&inputs.top_p, cx)))
+            .child(field("Max Tokens", &inputs.max_tokens, GenerationParamInputs::is_invalid::<i32>(&inputs.max_tokens, cx)))
+            .child(field("Stop Sequences", &inputs.stop, false))
+            .child(field("Frequency Penalty", &inputs.frequency_penalty, GenerationParamInputs::is_invalid::<f32>(&inputs.frequency_penalty, cx)))
+    }
+
+    /// 连接 → 数据库 → 表 的上下文选择器
+    fn render_context_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity();
+        let context = self.db_context.clone();
+
+        let connection_button = {
+            let view = view.clone();
+            let connections = self.context_connections.clone();
+            let current = context.as_ref().map(|c| c.connection_id.clone());
+            Button::new("context-connection")
+                .icon(IconName::Database)
+                .label(context.as_ref().map_or("选择数据库上下文".to_string(), |c| c.connection_name.clone()))
+                .ghost()
+                .xsmall()
+                .tooltip("AI 助手查看的连接")
+                .dropdown_menu(move |mut menu, window, _cx| {
+                    menu = menu.scrollable(true).max_h(px(320.0)).item(
+                        PopupMenuItem::new("不使用数据库上下文")
+                            .checked(current.is_none())
+                            .on_click(window.listener_for(&view, |this, _, _, cx| this.clear_context(cx))),
+                    );
+                    if !connections.is_empty() {
+                        menu = menu.separator();
+                    }
+                    for config in &connections {
+                        let config = config.clone();
+                        menu = menu.item(
+                            PopupMenuItem::new(config.name.clone())
+                                .checked(current.as_ref() == Some(&config.id))
+                                .on_click(window.listener_for(&view, move |this, _, _, cx| {
+                                    this.select_context_connection(&config, cx);
+                                })),
+                        );
+                    }
+                    menu
+                })
+        };
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .items_center()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(connection_button)
+            .when_some(context, |this, context| {
+                let database_button = {
+                    let view = view.clone();
+                    let databases = self.context_databases.clone();
+                    let current = context.database.clone();
+                    Button::new("context-database")
+                        .label(current.clone().unwrap_or_else(|| "选择数据库".to_string()))
+                        .ghost()
+                        .xsmall()
+                        .dropdown_menu(move |mut menu, window, _cx| {
+                            menu = menu.scrollable(true).max_h(px(320.0));
+                            for database in &databases {
+                                let database = database.clone();
+                                menu = menu.item(
+                                    PopupMenuItem::new(database.clone())
+                                        .checked(current.as_ref() == Some(&database))
+                                        .on_click(window.listener_for(&view, move |this, _, _, cx| {
+                                            this.select_context_database(database.clone(), cx);
+                                        })),
+                                );
+                            }
+                            menu
+                        })
+                };
+
+                this.child("/").child(database_button).when(context.database.is_some(), |this| {
+                    let tables = self.context_tables.clone();
+                    let selected = context.tables.clone();
+                    let label = if selected.is_empty() {
+                        "全部表".to_string()
+                    } else {
+                        format!("{} 张表", selected.len())
+                    };
+                    this.child("/").child(
+                        Button::new("context-tables")
+                            .label(label)
+                            .ghost()
+                            .xsmall()
+                            .tooltip("只把选中的表结构提供给 AI 助手")
+                            .dropdown_menu(move |mut menu, window, _cx| {
+                                menu = menu.scrollable(true).max_h(px(320.0)).item(
+                                    PopupMenuItem::new("全部表")
+                                        .checked(selected.is_empty())
+                                        .on_click(window.listener_for(&view, |this, _, _, cx| {
+                                            this.toggle_context_table(None, cx);
+                                        })),
+                                );
+                                if !tables.is_empty() {
+                                    menu = menu.separator();
+                                }
+                                for table in &tables {
+                                    let table = table.clone();
+                                    menu = menu.item(
+                                        PopupMenuItem::new(table.clone())
+                                            .checked(selected.contains(&table))
+                                            .on_click(window.listener_for(&view, move |this, _, _, cx| {
+                                                this.toggle_context_table(Some(table.clone()), cx);
+                                            })),
+                                    );
+                                }
+                                menu
+                            }),
+                    )
+                })
+            })
+    }
+
     fn render_input(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .w_full()
@@ -1113,10 +2186,49 @@ impl AiChatPanel {
             .border_t_1()
             .border_color(cx.theme().border)
             .bg(cx.theme().muted)
+            .when(self.editing_index.is_some(), |this| {
+                this.child(
+                    h_flex()
+                        .pb_1()
+                        .justify_between()
+                        .items_center()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("正在编辑消息，发送后将从此处创建新分支")
+                        .child(
+                            Button::new("cancel-edit")
+                                .label("取消")
+                                .ghost()
+                                .xsmall()
+                                .on_click(cx.listener(|this, _, window, cx| this.cancel_edit(window, cx)))
+                        )
+                )
+            })
             .child(self.ai_input.clone())
     }
 }
 
+/// 没有标注语言的代码块在数据库对话中通常也是 SQL
+fn is_sql_code_block(lang: Option<&str>) -> bool {
+    match lang {
+        None => true,
+        Some(lang) => matches!(
+            lang.to_ascii_lowercase().as_str(),
+            "" | "sql" | "mysql" | "postgresql" | "postgres" | "pgsql" | "sqlite" | "tsql" | "plsql" | "clickhouse"
+        ),
+    }
+}
+
+/// 替换文件名中不允许出现的字符
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let sanitized = sanitized.trim();
+    if sanitized.is_empty() { "conversation".to_string() } else { sanitized.to_string() }
+}
+
 // 格式化时间戳为可读格式
 fn format_timestamp(timestamp: i64) -> String {
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -1160,6 +2272,7 @@ impl Render for AiChatPanel {
                 v_flex()
                     .size_full()
                     .bg(cx.theme().background)
+                    .child(self.render_context_bar(cx))
                     .child(self.render_messages(window, cx))
                     .child(self.render_input(window, cx))
             )
@@ -1187,5 +2300,54 @@ impl Render for AiChatPanel {
                         )
                 )
             })
+            // 生成参数 Popover
+            .when(self.params_popover_open, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top_2()
+                        .right_2()
+                        .child(self.render_params_popover(cx))
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(database: Option<&str>, tables: &[&str]) -> ChatDbContext {
+        ChatDbContext {
+            connection_id: "1".to_string(),
+            connection_name: "prod".to_string(),
+            database_type: DatabaseType::MySQL,
+            database: database.map(str::to_string),
+            tables: tables.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_db_context_label() {
+        assert_eq!(context(None, &[]).label(), "prod");
+        assert_eq!(context(Some("shop"), &[]).label(), "prod / shop");
+        assert_eq!(context(Some("shop"), &["users", "orders"]).label(), "prod / shop (2 张表)");
+    }
+
+    #[test]
+    fn test_db_context_system_prompt() {
+        let prompt = context(Some("shop"), &[]).system_prompt("users(id INT PK)\n");
+        assert!(prompt.contains("数据库 shop"));
+        assert!(prompt.ends_with("表结构：\nusers(id INT PK)\n"));
+
+        assert!(!context(None, &[]).system_prompt("").contains("表结构"));
+    }
+
+    #[test]
+    fn test_is_sql_code_block() {
+        assert!(is_sql_code_block(None));
+        assert!(is_sql_code_block(Some("SQL")));
+        assert!(is_sql_code_block(Some("postgresql")));
+        assert!(!is_sql_code_block(Some("rust")));
     }
 }
@@ -1,10 +1,11 @@
-//! AI Input - 支持模型选择和智能输入的组件
+//! AI Input - 支持模型选择和智能输入的组件，以及与 SQL 编辑器共用的快捷操作工具条
 
 use gpui::prelude::FluentBuilder;
 use gpui::{
-    div, App, AppContext, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement,
-    ParentElement, Render, SharedString, Styled, Subscription, Window,
+    div, App, AppContext, Context, ElementId, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, ParentElement, Render, RenderOnce, SharedString, Styled, Subscription, Window,
 };
+use std::rc::Rc;
 use gpui_component::{
     button::{Button, ButtonVariants},
     h_flex,
@@ -64,6 +65,96 @@ impl SelectItem for ProviderItem {
     }
 }
 
+/// 针对一段 SQL 的快捷 AI 操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AIQuickAction {
+    Explain,
+    Optimize,
+    WhereGuard,
+}
+
+impl AIQuickAction {
+    pub const ALL: [AIQuickAction; 3] = [
+        AIQuickAction::Explain,
+        AIQuickAction::Optimize,
+        AIQuickAction::WhereGuard,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AIQuickAction::Explain => "解释",
+            AIQuickAction::Optimize => "优化",
+            AIQuickAction::WhereGuard => "WHERE 检查",
+        }
+    }
+
+    pub fn icon(&self) -> IconName {
+        match self {
+            AIQuickAction::Explain => IconName::Info,
+            AIQuickAction::Optimize => IconName::Star,
+            AIQuickAction::WhereGuard => IconName::TriangleAlert,
+        }
+    }
+
+    /// 在对话中使用时填入输入框的提问前缀
+    pub fn prompt(&self) -> &'static str {
+        match self {
+            AIQuickAction::Explain => "解释下面这段 SQL 的作用和执行逻辑：\n",
+            AIQuickAction::Optimize => "在结果不变的前提下优化下面这段 SQL：\n",
+            AIQuickAction::WhereGuard => "检查下面这段 SQL 中的 UPDATE / DELETE 是否缺少 WHERE 条件：\n",
+        }
+    }
+}
+
+/// 快捷 AI 操作工具条：SQL 编辑器选中文本时浮动显示，AI 输入框中作为常用提问
+#[derive(IntoElement)]
+pub struct AIQuickActions {
+    id: ElementId,
+    on_action: Option<Rc<dyn Fn(AIQuickAction, &mut Window, &mut App)>>,
+}
+
+impl AIQuickActions {
+    pub fn new(id: impl Into<ElementId>) -> Self {
+        Self {
+            id: id.into(),
+            on_action: None,
+        }
+    }
+
+    pub fn on_action(mut self, handler: impl Fn(AIQuickAction, &mut Window, &mut App) + 'static) -> Self {
+        self.on_action = Some(Rc::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for AIQuickActions {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let id = self.id.clone();
+        h_flex()
+            .id(self.id)
+            .gap_1()
+            .p_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().background)
+            .shadow_md()
+            .children(AIQuickAction::ALL.into_iter().map(|action| {
+                let on_action = self.on_action.clone();
+                Button::new(SharedString::from(format!("{}-{:?}", id, action)))
+                    .ghost()
+                    .xsmall()
+                    .icon(action.icon())
+                    .label(action.label())
+                    .on_click(move |_, window, cx| {
+                        if let Some(on_action) = &on_action {
+                            on_action(action, window, cx);
+                        }
+                    })
+            }))
+    }
+}
+
 /// AI 输入框事件
 #[derive(Clone, Debug)]
 pub enum AIInputEvent {
@@ -181,6 +272,14 @@ impl AIInput {
         });
     }
 
+    /// 替换输入框内容并聚焦，用于编辑已发送的消息
+    pub fn set_content(&mut self, content: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(content, window, cx);
+            state.focus(window, cx);
+        });
+    }
+
     pub fn set_selected_provider(
         &mut self,
         provider_id: Option<String>,
@@ -238,6 +337,7 @@ impl Focusable for AIInput {
 
 impl Render for AIInput {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity();
         v_flex()
             .w_full()
             .bg(cx.theme().background)
@@ -300,12 +400,22 @@ impl Render for AIInput {
                             )
                     })
             )
+            // 常用提问
+            .child(
+                div().px_3().pt_2().child(
+                    AIQuickActions::new("ai-input-quick-actions").on_action(move |action, window, cx| {
+                        view.update(cx, |this, cx| {
+                            this.set_content(action.prompt().to_string(), window, cx);
+                        });
+                    }),
+                ),
+            )
             // 输入框
             .child(
                 div()
                     .w_full()
                     .px_3()
-                    .pt_3()
+                    .pt_2()
                     .pb_2()
                     .child(
                         Input::new(&self.input_state)
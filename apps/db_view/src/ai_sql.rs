@@ -0,0 +1,1784 @@
+//! AI 生成 SQL - 收集当前数据库的表结构作为上下文，让 LLM 生成符合方言的 SQL；
+//! 根据执行错误修正失败的语句；根据执行计划给出索引建议；为表结构生成 Markdown 文档；
+//! 按固定指令改写或解释选中的 SQL；优化 SQL 并对比执行耗时；把自然语言筛选描述翻译为当前表或导出表的 WHERE 条件；
+//! 执行表结构变更前审查 ALTER 脚本中的破坏性和长时间锁表操作，并把结构变更写成迁移提交信息；解释存储过程、函数和触发器的输入、副作用和涉及的表。
+//! 表结构上下文按模型上下文窗口裁剪，优先保留当前 SQL 或问题中提到的表；表较多时先按 embedding 检索与问题最相关的表；
+//! 也为 AI 聊天面板选定的数据库和表提供同样裁剪后的表结构上下文
+
+use anyhow::{anyhow, Result};
+use db::{
+    ColumnDefinition, ColumnInfo, DbNodeType, GlobalDbState, IndexDefinition, QueryResult, SqlResult, TableDesign, TableSchema,
+};
+use gpui::AsyncApp;
+use one_core::gpui_tokio::Tokio;
+use one_core::llm::{
+    manager::GlobalProviderState,
+    storage::ProviderRepository,
+    types::{ChatMessage, ChatRequest, ModelInfo},
+};
+use one_core::storage::{
+    cosine_similarity, traits::Repository, DatabaseType, GlobalStorageState, SchemaEmbedding, SchemaEmbeddingRepository,
+};
+
+/// 最多收集的表数量
+const MAX_SCHEMA_TABLES: usize = 200;
+/// 模型上下文窗口未知时表结构上下文的字符上限
+const MAX_SCHEMA_CHARS: usize = 24_000;
+/// 执行计划的字符上限
+const MAX_PLAN_CHARS: usize = 8_000;
+/// 提示词中表结构以外部分（指令、问题、执行计划）预留的 token 数
+const PROMPT_OVERHEAD_TOKENS: u32 = 4_000;
+/// 表数量超过该值时按 embedding 只检索最相关的表
+const MAX_RETRIEVED_TABLES: usize = 20;
+/// 每次 embeddings 请求包含的最大文本数
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+/// 一次自然语言生成 SQL 的请求
+#[derive(Debug, Clone)]
+pub struct TextToSqlRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub question: String,
+}
+
+fn dialect_hint(database_type: DatabaseType) -> &'static str {
+    match database_type {
+        DatabaseType::MySQL => "使用反引号引用标识符，分页使用 LIMIT",
+        DatabaseType::TiDB => "使用 MySQL 兼容语法和反引号引用标识符，不要使用存储过程、触发器和外键级联，分页使用 LIMIT",
+        DatabaseType::PostgreSQL => "使用双引号引用标识符，分页使用 LIMIT/OFFSET",
+        DatabaseType::CockroachDB => "使用 CockroachDB 兼容的 PostgreSQL 语法，避免触发器和存储过程，分页使用 LIMIT/OFFSET",
+        DatabaseType::SQLite => "只使用 SQLite 支持的函数，分页使用 LIMIT",
+        DatabaseType::MSSQL => "使用方括号引用标识符，分页使用 TOP 或 OFFSET ... FETCH",
+        DatabaseType::Oracle => "使用双引号引用标识符，分页使用 FETCH FIRST n ROWS ONLY",
+        DatabaseType::ClickHouse => "使用 ClickHouse 函数，分页使用 LIMIT",
+        DatabaseType::MongoDB => "输出 mongo shell 语法的 db.<集合>.find(...) 或 db.<集合>.aggregate([...])，不要输出 SQL",
+        DatabaseType::Redis => "输出 redis-cli 命令，每行一条，不要输出 SQL",
+        DatabaseType::DuckDB => "使用 DuckDB 语法，可用 read_csv/read_parquet 直接查询文件，分页使用 LIMIT",
+        DatabaseType::Trino => "使用双引号引用标识符，表名写成 catalog.schema.table，分页使用 OFFSET ... LIMIT",
+        DatabaseType::Elasticsearch => "只输出 Elasticsearch SQL 的 SELECT 查询，索引名用双引号，全文检索用 MATCH()/QUERY()，不支持 OFFSET 和写入语句",
+        DatabaseType::ODBC => "只使用标准 ANSI SQL，分页使用 OFFSET ... FETCH",
+    }
+}
+
+fn table_display_name(table: &TableSchema) -> String {
+    match &table.table.schema {
+        Some(schema) => format!("{}.{}", schema, table.table.name),
+        None => table.table.name.clone(),
+    }
+}
+
+/// 完整格式：`表名(列 类型 [PK] [NOT NULL], ...)`，外键和索引单独缩进列出
+fn format_table_entry(table: &TableSchema) -> String {
+    let columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|c| {
+            let mut column = format!("{} {}", c.name, c.data_type);
+            if c.is_primary_key {
+                column.push_str(" PK");
+            } else if !c.is_nullable {
+                column.push_str(" NOT NULL");
+            }
+            column
+        })
+        .collect();
+
+    let mut entry = format!("{}({})", table_display_name(table), columns.join(", "));
+    if let Some(comment) = table.table.comment.as_deref().filter(|c| !c.is_empty()) {
+        entry.push_str(&format!(" -- {}", comment));
+    }
+    entry.push('\n');
+    for fk in &table.foreign_keys {
+        entry.push_str(&format!(
+            "  FK ({}) -> {}({})\n",
+            fk.columns.join(", "),
+            fk.ref_table,
+            fk.ref_columns.join(", ")
+        ));
+    }
+    for index in &table.indexes {
+        entry.push_str(&format!(
+            "  {}INDEX {} ({})\n",
+            if index.is_unique { "UNIQUE " } else { "" },
+            index.name,
+            index.columns.join(", ")
+        ));
+    }
+    entry
+}
+
+/// 精简格式：只保留列名
+fn format_table_condensed(table: &TableSchema) -> String {
+    let columns: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+    format!("{}({})\n", table_display_name(table), columns.join(", "))
+}
+
+/// 每张表一行，超过 `max_chars` 时省略剩余的表
+pub fn build_schema_prompt(tables: &[TableSchema], max_chars: usize) -> String {
+    let mut prompt = String::new();
+    for (ix, table) in tables.iter().enumerate() {
+        let entry = format_table_entry(table);
+        if prompt.len() + entry.len() > max_chars {
+            prompt.push_str(&format!("... 另有 {} 张表未列出\n", tables.len() - ix));
+            break;
+        }
+        prompt.push_str(&entry);
+    }
+    prompt
+}
+
+/// 按预算组织表结构上下文：`context`（当前 SQL 或问题）中提到的表排在最前并尽量完整列出；
+/// 其余表预算足够时完整列出，不够时只列列名，再不够时只列表名，最后注明省略的数量
+pub fn summarize_schema(tables: &[TableSchema], context: &str, max_chars: usize) -> String {
+    let identifiers = referenced_identifiers(context);
+    let (referenced, others): (Vec<&TableSchema>, Vec<&TableSchema>) = tables
+        .iter()
+        .partition(|table| identifiers.iter().any(|id| id.eq_ignore_ascii_case(&table.table.name)));
+
+    let mut prompt = String::new();
+    let mut names_only: Vec<String> = Vec::new();
+    for table in referenced.into_iter().chain(others) {
+        let entry = format_table_entry(table);
+        if prompt.len() + entry.len() <= max_chars {
+            prompt.push_str(&entry);
+            continue;
+        }
+        let condensed = format_table_condensed(table);
+        if prompt.len() + condensed.len() <= max_chars {
+            prompt.push_str(&condensed);
+        } else {
+            names_only.push(table_display_name(table));
+        }
+    }
+
+    if names_only.is_empty() {
+        return prompt;
+    }
+    // 为末尾的省略说明留出空间
+    let tail_reserve = 32;
+    let mut listed = 0;
+    let mut line = String::from("其他表：");
+    for name in &names_only {
+        if prompt.len() + line.len() + name.len() + 2 + tail_reserve > max_chars {
+            break;
+        }
+        if listed > 0 {
+            line.push_str(", ");
+        }
+        line.push_str(name);
+        listed += 1;
+    }
+    if listed > 0 {
+        prompt.push_str(&line);
+        prompt.push('\n');
+    }
+    if listed < names_only.len() {
+        prompt.push_str(&format!("... 另有 {} 张表未列出\n", names_only.len() - listed));
+    }
+    prompt
+}
+
+/// 生成 embedding 的表文本：表名和注释，以及每列的名称、类型和注释
+pub fn table_document(table: &TableSchema) -> String {
+    let mut document = table_display_name(table);
+    if let Some(comment) = table.table.comment.as_ref().filter(|c| !c.is_empty()) {
+        document.push_str(&format!(" -- {}", comment));
+    }
+    for column in &table.columns {
+        document.push_str(&format!("\n{} {}", column.name, column.data_type));
+        if let Some(comment) = column.comment.as_ref().filter(|c| !c.is_empty()) {
+            document.push_str(&format!(" -- {}", comment));
+        }
+    }
+    document
+}
+
+/// 按与问题向量的相似度从高到低返回最多 `limit` 个表名
+pub fn rank_tables(question: &[f32], embeddings: &[SchemaEmbedding], limit: usize) -> Vec<String> {
+    let mut scored: Vec<(f32, &str)> = embeddings
+        .iter()
+        .map(|e| (cosine_similarity(question, &e.embedding), e.table_name.as_str()))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(limit).map(|(_, name)| name.to_string()).collect()
+}
+
+/// 根据模型上下文窗口估算表结构上下文可用的字符数：扣除预留的回复和提示词开销后取一半，
+/// 按 4 个字符 1 个 token 换算；上下文窗口未知时使用默认上限
+pub fn schema_char_budget(model: Option<&ModelInfo>, reserved_output_tokens: u32) -> usize {
+    let Some(window) = model.and_then(|m| m.context_window) else {
+        return MAX_SCHEMA_CHARS;
+    };
+    let available = window
+        .saturating_sub(reserved_output_tokens)
+        .saturating_sub(PROMPT_OVERHEAD_TOKENS)
+        / 2;
+    available as usize * 4
+}
+
+pub fn build_messages(request: &TextToSqlRequest, schema_prompt: &str) -> Vec<ChatMessage> {
+    let mut system = format!(
+        "你是 {} 数据库专家。根据给出的表结构，把用户的需求翻译成一条可以直接执行的 {} SQL。\n\
+         要求：{}；只使用表结构中存在的表和列；只输出 SQL，不要解释，不要使用 Markdown。\n\n\
+         当前数据库：{}\n",
+        request.database_type.as_str(),
+        request.database_type.as_str(),
+        dialect_hint(request.database_type),
+        request.database,
+    );
+    if let Some(schema) = &request.schema {
+        system.push_str(&format!("当前 schema：{}\n", schema));
+    }
+    system.push_str("\n表结构：\n");
+    system.push_str(schema_prompt);
+
+    vec![ChatMessage::system(system), ChatMessage::user(request.question.clone())]
+}
+
+/// 从模型回复中取出 SQL：优先取 Markdown 代码块中的内容，否则使用整段回复
+pub fn extract_sql(response: &str) -> String {
+    let response = response.trim();
+    if let Some(start) = response.find("```") {
+        let block = &response[start + 3..];
+        // 跳过代码块的语言标记（```sql）
+        let block = block.split_once('\n').map(|(_, rest)| rest).unwrap_or(block);
+        let block = block.find("```").map(|end| &block[..end]).unwrap_or(block);
+        return block.trim().to_string();
+    }
+    response.to_string()
+}
+
+/// 一次修正失败语句的请求
+#[derive(Debug, Clone)]
+pub struct SqlFixRequest {
+    pub database_type: DatabaseType,
+    pub sql: String,
+    pub error: String,
+}
+
+pub fn build_fix_messages(request: &SqlFixRequest) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 数据库专家。用户执行的 SQL 报错了，请根据错误信息修正这条语句，保持原本的意图不变。\n\
+         要求：{}；只输出修正后的一条 SQL，不要解释，不要使用 Markdown。",
+        request.database_type.as_str(),
+        dialect_hint(request.database_type),
+    );
+    let user = format!("SQL：\n{}\n\n错误信息：\n{}", request.sql, request.error);
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 对选中 SQL 的固定改写指令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlRewriteCommand {
+    /// 在不改变结果的前提下简化语句
+    Simplify,
+    /// 为每个子句添加注释
+    AddComments,
+    /// 在不改变结果的前提下提升执行效率
+    Optimize,
+    /// 为缺少条件的 UPDATE / DELETE 补充 WHERE 条件
+    AddWhereGuard,
+    /// 转换为目标数据库的语法
+    ConvertDialect(DatabaseType),
+}
+
+impl SqlRewriteCommand {
+    pub fn label(&self) -> String {
+        match self {
+            SqlRewriteCommand::Simplify => "简化".to_string(),
+            SqlRewriteCommand::AddComments => "逐句注释".to_string(),
+            SqlRewriteCommand::Optimize => "优化".to_string(),
+            SqlRewriteCommand::AddWhereGuard => "WHERE 安全检查".to_string(),
+            SqlRewriteCommand::ConvertDialect(target) => format!("转换为 {} 语法", target.as_str()),
+        }
+    }
+
+    fn instruction(&self, source: DatabaseType) -> String {
+        match self {
+            SqlRewriteCommand::Simplify => {
+                "在保证结果完全相同的前提下简化这段 SQL：去掉多余的子查询、嵌套和重复条件，能用 JOIN 或 CTE 表达得更清楚的就改写。"
+                    .to_string()
+            }
+            SqlRewriteCommand::AddComments => {
+                "为这段 SQL 的每个子句（SELECT、FROM、JOIN、WHERE、GROUP BY 等）在上一行添加 `--` 注释说明其作用，不要修改 SQL 本身。"
+                    .to_string()
+            }
+            SqlRewriteCommand::Optimize => {
+                "在保证结果完全相同的前提下优化这段 SQL 的执行效率：避免 SELECT *、对索引列使用函数、隐式类型转换和可以改写为 JOIN 的相关子查询。"
+                    .to_string()
+            }
+            SqlRewriteCommand::AddWhereGuard => {
+                "检查这段 SQL 中的 UPDATE 和 DELETE 语句：没有 WHERE 条件或条件恒为真的，补充基于主键或业务字段的 WHERE 条件；\
+                 无法确定条件时改为 `WHERE 1 = 0` 并在上一行添加 `-- TODO: 补充条件` 注释，防止误改全表。其他语句保持不变。"
+                    .to_string()
+            }
+            SqlRewriteCommand::ConvertDialect(target) => format!(
+                "把这段 {} SQL 转换为等价的 {} SQL，替换不兼容的函数、类型、标识符引用和分页写法。要求：{}。",
+                source.as_str(),
+                target.as_str(),
+                dialect_hint(*target),
+            ),
+        }
+    }
+}
+
+/// 一次改写请求
+#[derive(Debug, Clone)]
+pub struct SqlRewriteRequest {
+    pub database_type: DatabaseType,
+    pub sql: String,
+    pub command: SqlRewriteCommand,
+}
+
+pub fn build_rewrite_messages(request: &SqlRewriteRequest) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 数据库专家。{}\n只输出改写后的 SQL，保留原有的语句数量和顺序，不要解释，不要使用 Markdown。",
+        request.database_type.as_str(),
+        request.command.instruction(request.database_type),
+    );
+
+    vec![ChatMessage::system(system), ChatMessage::user(request.sql.clone())]
+}
+
+/// 请求模型优化 SQL，返回优化后的语句和改动理由
+pub async fn optimize_sql(database_type: DatabaseType, sql: &str, cx: &mut AsyncApp) -> Result<SqlOptimization> {
+    let optimization = parse_optimization(&complete(build_optimize_messages(database_type, sql), cx).await?);
+    if optimization.sql.is_empty() {
+        return Err(anyhow!("模型没有返回 SQL"));
+    }
+    Ok(optimization)
+}
+
+/// 请求模型解释 SQL 的作用和执行逻辑
+pub fn build_explain_messages(database_type: DatabaseType, sql: &str) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 数据库专家。用简洁的中文解释这段 SQL：先用一句话概括它做什么，再按子句说明查询了哪些表、\
+         如何关联和过滤、返回什么结果；如果存在全表更新、笛卡尔积等风险，在最后单独指出。不要改写 SQL，不要使用 Markdown 标题。",
+        database_type.as_str(),
+    );
+
+    vec![ChatMessage::system(system), ChatMessage::user(sql.to_string())]
+}
+
+/// 一次存储过程、函数或触发器解释的请求
+#[derive(Debug, Clone)]
+pub struct RoutineExplainRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub object_type: DbNodeType,
+    pub name: String,
+}
+
+fn routine_kind(object_type: &DbNodeType) -> &'static str {
+    match object_type {
+        DbNodeType::Procedure => "存储过程",
+        DbNodeType::Trigger => "触发器",
+        _ => "函数",
+    }
+}
+
+/// 请求模型总结数据库对象的输入、副作用和涉及的表
+pub fn build_routine_explain_messages(request: &RoutineExplainRequest, source: &str) -> Vec<ChatMessage> {
+    let kind = routine_kind(&request.object_type);
+    let system = format!(
+        "你是 {} 数据库专家。用简洁的中文解释给出的{}定义，依次说明：\n\
+         1. 作用：一句话概括它做什么；\n\
+         2. 输入：参数的名称、类型和含义，触发器说明触发时机、事件和可用的 NEW/OLD 行；\n\
+         3. 副作用：写入、更新、删除的数据，事务控制、动态 SQL、调用的其他过程或外部资源；\n\
+         4. 涉及的表：按读取和写入分别列出。\n\
+         只根据定义推断，不确定的内容注明“推测”；不要改写代码。",
+        request.database_type.as_str(),
+        kind,
+    );
+    let user = format!("{} {}：\n{}", kind, request.name, source.trim());
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 把自然语言筛选描述翻译为当前表 WHERE 条件的请求
+#[derive(Debug, Clone)]
+pub struct NaturalFilterRequest {
+    pub database_type: DatabaseType,
+    pub table: String,
+    /// 当前表的列：(列名, 类型)
+    pub columns: Vec<(String, String)>,
+    pub text: String,
+}
+
+pub fn build_filter_messages(request: &NaturalFilterRequest) -> Vec<ChatMessage> {
+    let mut system = format!(
+        "你是 {} 数据库专家。把用户对表 {} 的筛选描述翻译成 WHERE 子句中的条件表达式。\n\
+         要求：{}；只使用下面列出的列；涉及“今天”“今年”等相对时间时使用数据库的日期函数；\
+         只输出条件表达式本身，不要包含 WHERE 关键字，不要解释，不要使用 Markdown。\n\n列：\n",
+        request.database_type.as_str(),
+        request.table,
+        dialect_hint(request.database_type),
+    );
+    for (name, data_type) in &request.columns {
+        system.push_str(&format!("- {} {}\n", name, data_type));
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(request.text.clone())]
+}
+
+/// 从模型回复中取出条件表达式：去掉多余的 `WHERE` 前缀和结尾的分号
+pub fn extract_where_condition(response: &str) -> String {
+    let sql = extract_sql(response);
+    let condition = match sql.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("WHERE ") || prefix.eq_ignore_ascii_case("WHERE\n") => &sql[6..],
+        _ => sql.as_str(),
+    };
+    condition.trim().trim_end_matches(';').trim_end().to_string()
+}
+
+/// 导出时按描述生成 WHERE 条件的请求，条件会作用于每张导出的表
+#[derive(Debug, Clone)]
+pub struct ExportFilterRequest {
+    pub connection_id: String,
+    pub database: String,
+    pub tables: Vec<String>,
+    pub text: String,
+}
+
+/// 所有表共有的列（按列名忽略大小写匹配），类型取自第一张表
+pub fn common_columns(tables: &[Vec<ColumnInfo>]) -> Vec<(String, String)> {
+    let Some((first, rest)) = tables.split_first() else {
+        return Vec::new();
+    };
+    first
+        .iter()
+        .filter(|column| {
+            rest.iter().all(|columns| columns.iter().any(|c| c.name.eq_ignore_ascii_case(&column.name)))
+        })
+        .map(|column| (column.name.clone(), column.data_type.clone()))
+        .collect()
+}
+
+/// 逐行对比中的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// 基于最长公共子序列的逐行对比，用于预览改写前后的差异
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line.to_string())));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line.to_string())));
+    lines
+}
+
+/// 左右对照中的一行，`changed` 表示两侧内容不同
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SideBySideRow {
+    pub left: Option<String>,
+    pub right: Option<String>,
+    pub changed: bool,
+}
+
+/// 把逐行差异整理为左右两栏：连续的删除行和新增行按顺序配对，多出的一侧留空
+pub fn side_by_side(diff: &[DiffLine]) -> Vec<SideBySideRow> {
+    let mut rows = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut added: Vec<String> = Vec::new();
+
+    let flush = |rows: &mut Vec<SideBySideRow>, removed: &mut Vec<String>, added: &mut Vec<String>| {
+        let count = removed.len().max(added.len());
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        for _ in 0..count {
+            rows.push(SideBySideRow {
+                left: removed.next(),
+                right: added.next(),
+                changed: true,
+            });
+        }
+    };
+
+    for line in diff {
+        match line {
+            DiffLine::Removed(text) => removed.push(text.clone()),
+            DiffLine::Added(text) => added.push(text.clone()),
+            DiffLine::Same(text) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(SideBySideRow {
+                    left: Some(text.clone()),
+                    right: Some(text.clone()),
+                    changed: false,
+                });
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// AI 优化结果：优化后的 SQL 和模型给出的改动理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqlOptimization {
+    pub sql: String,
+    pub reasoning: String,
+}
+
+pub fn build_optimize_messages(database_type: DatabaseType, sql: &str) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 数据库性能专家。{}\n要求：{}；保留原有的语句数量和顺序。\
+         先用简短的要点逐条说明每处改动及理由，再把优化后的完整 SQL 放在一个 ```sql 代码块中；\
+         已经是最优写法时说明原因并原样给出 SQL。",
+        database_type.as_str(),
+        SqlRewriteCommand::Optimize.instruction(database_type),
+        dialect_hint(database_type),
+    );
+    vec![ChatMessage::system(system), ChatMessage::user(sql.to_string())]
+}
+
+/// 拆分模型回复：代码块中的内容为优化后的 SQL，代码块以外的文字为改动理由
+pub fn parse_optimization(response: &str) -> SqlOptimization {
+    let response = response.trim();
+    let Some(start) = response.find("```") else {
+        return SqlOptimization { sql: response.to_string(), reasoning: String::new() };
+    };
+
+    let after_open = &response[start + 3..];
+    let after_block = after_open
+        .find("```")
+        .map(|end| &after_open[end + 3..])
+        .unwrap_or("");
+    let reasoning = format!("{}\n{}", response[..start].trim(), after_block.trim());
+
+    SqlOptimization {
+        sql: extract_sql(response),
+        reasoning: reasoning.trim().to_string(),
+    }
+}
+
+/// 对比执行的耗时汇总：结果中前 `original_count` 条属于原始语句，其余属于优化后的语句
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingComparison {
+    pub original_ms: u128,
+    pub optimized_ms: u128,
+    pub original_rows: usize,
+    pub optimized_rows: usize,
+}
+
+impl TimingComparison {
+    /// 任一语句失败或结果不完整时返回 None
+    pub fn from_results(results: &[SqlResult], original_count: usize) -> Option<Self> {
+        if original_count == 0 || results.len() <= original_count {
+            return None;
+        }
+
+        let totals = |results: &[SqlResult]| -> Option<(u128, usize)> {
+            results.iter().try_fold((0u128, 0usize), |(ms, rows), result| match result {
+                SqlResult::Query(q) => Some((ms + q.elapsed_ms, rows + q.rows.len())),
+                SqlResult::Exec(e) => Some((ms + e.elapsed_ms, rows + e.rows_affected as usize)),
+                SqlResult::Error(_) => None,
+            })
+        };
+        let (original_ms, original_rows) = totals(&results[..original_count])?;
+        let (optimized_ms, optimized_rows) = totals(&results[original_count..])?;
+
+        Some(Self { original_ms, optimized_ms, original_rows, optimized_rows })
+    }
+
+    /// 优化后耗时相对原始语句的变化百分比，负数表示更快；原始耗时为 0 时无法比较
+    pub fn change_percent(&self) -> Option<f64> {
+        if self.original_ms == 0 {
+            return None;
+        }
+        Some((self.optimized_ms as f64 - self.original_ms as f64) / self.original_ms as f64 * 100.0)
+    }
+}
+
+/// 一次索引建议请求
+#[derive(Debug, Clone)]
+pub struct IndexAdviceRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub sql: String,
+}
+
+/// 索引建议：执行计划、模型的完整回复（含原因注释）以及其中的 CREATE INDEX 语句
+#[derive(Debug, Clone)]
+pub struct IndexAdvice {
+    pub plan: String,
+    pub suggestion: String,
+    pub statements: Vec<String>,
+}
+
+/// SQL 中出现的标识符（去掉引号），用于找出语句涉及的表
+pub fn referenced_identifiers(sql: &str) -> Vec<String> {
+    let mut identifiers: Vec<String> = Vec::new();
+    for token in sql.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$')) {
+        if token.is_empty() || token.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        if !identifiers.iter().any(|known| known.eq_ignore_ascii_case(token)) {
+            identifiers.push(token.to_string());
+        }
+    }
+    identifiers
+}
+
+/// 把执行计划结果集格式化为文本，每行一条记录，列之间用 ` | ` 分隔
+pub fn format_plan(plan: &QueryResult) -> String {
+    let mut text = plan.columns.join(" | ");
+    text.push('\n');
+    for row in &plan.rows {
+        let cells: Vec<&str> = row.iter().map(|cell| cell.as_deref().unwrap_or("NULL")).collect();
+        text.push_str(&cells.join(" | "));
+        text.push('\n');
+    }
+    if text.len() > MAX_PLAN_CHARS {
+        let end = (0..=MAX_PLAN_CHARS).rev().find(|&i| text.is_char_boundary(i)).unwrap_or(0);
+        text.truncate(end);
+        text.push_str("\n...（执行计划过长，已截断）\n");
+    }
+    text
+}
+
+pub fn build_index_messages(request: &IndexAdviceRequest, plan: &str, schema_prompt: &str) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 性能优化专家。根据查询语句、执行计划和表结构（含已有索引），给出能加速这条查询的索引。\n\
+         要求：{}；不要重复已有的索引；每条建议先用一行 `-- ` 注释说明原因，再给出一条以分号结尾的 CREATE INDEX 语句；\
+         如果不需要新增索引，只输出一行 `-- ` 注释说明原因；不要使用 Markdown。",
+        request.database_type.as_str(),
+        dialect_hint(request.database_type),
+    );
+    let user = format!(
+        "查询：\n{}\n\n执行计划：\n{}\n表结构：\n{}",
+        request.sql, plan, schema_prompt
+    );
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 从模型回复中取出 CREATE INDEX 语句（忽略注释行）
+pub fn extract_index_statements(suggestion: &str) -> Vec<String> {
+    suggestion
+        .split(';')
+        .filter_map(|part| {
+            let statement = part
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let statement = statement.trim();
+            let upper = statement.to_uppercase();
+            (upper.starts_with("CREATE") && upper.contains("INDEX")).then(|| statement.to_string())
+        })
+        .collect()
+}
+
+/// 一次生成表结构文档的请求，`table` 为空时为整个数据库（或模式）生成
+#[derive(Debug, Clone)]
+pub struct SchemaDocRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub table: Option<String>,
+}
+
+/// 列注释不在表结构上下文中，单独列出供模型参考
+fn column_comments(tables: &[TableSchema]) -> String {
+    let mut comments = String::new();
+    for table in tables {
+        for column in &table.columns {
+            if let Some(comment) = column.comment.as_deref().filter(|c| !c.is_empty()) {
+                comments.push_str(&format!("{}.{}: {}\n", table.table.name, column.name, comment));
+            }
+        }
+    }
+    comments
+}
+
+pub fn build_doc_messages(request: &SchemaDocRequest, tables: &[TableSchema]) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 数据库架构师。根据给出的表结构为开发人员编写 Markdown 格式的数据库文档。\n\
+         要求：先用一段话概述整体用途和主要业务实体；每张表一个二级标题，说明表的用途，\
+         再用表格列出列名、类型、是否可空、说明；最后用一节说明表之间的关联关系。\
+         只根据表结构推断，不确定的含义注明“推测”；直接输出 Markdown 正文，不要包裹在代码块中。",
+        request.database_type.as_str(),
+    );
+    let target = match &request.table {
+        Some(table) => format!("表 {}", table),
+        None => format!("数据库 {}", request.database),
+    };
+    let mut user = format!("为{}编写文档。\n\n表结构：\n{}", target, build_schema_prompt(tables, MAX_SCHEMA_CHARS));
+    let comments = column_comments(tables);
+    if !comments.is_empty() {
+        user.push_str(&format!("\n列注释：\n{}", comments));
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 一次 ALTER 脚本审查的请求
+#[derive(Debug, Clone)]
+pub struct AlterReviewRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub table: String,
+    pub sql: String,
+}
+
+/// 审查给出的整体风险等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlterRisk {
+    High,
+    Medium,
+    Low,
+}
+
+impl AlterRisk {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlterRisk::High => "高风险",
+            AlterRisk::Medium => "中风险",
+            AlterRisk::Low => "低风险",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AlterReview {
+    pub risk: Option<AlterRisk>,
+    pub text: String,
+}
+
+pub fn build_alter_review_messages(request: &AlterReviewRequest, row_count: Option<i64>, schema_prompt: &str) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} DBA。审查即将执行的表结构变更脚本，找出两类问题：\
+         一是破坏性操作，如删除列或索引、缩短长度、修改类型导致数据截断或精度丢失、把含空值的列改为 NOT NULL；\
+         二是会长时间锁表或重建表的操作，结合表的数据量判断，如在大表上修改列类型、调整列顺序、添加带默认值的列。\n\
+         要求：第一行只输出 `风险：高`、`风险：中` 或 `风险：低`；随后逐条说明每个问题涉及的语句、影响和更安全的替代做法\
+         （如分步迁移并回填数据、使用在线 DDL、安排在低峰期执行）；没有问题时说明可以直接执行；不要使用 Markdown 标题。",
+        request.database_type.as_str(),
+    );
+    let size = match row_count {
+        Some(rows) => format!("约 {} 行", rows),
+        None => "行数未知".to_string(),
+    };
+    let user = format!(
+        "表 {}（{}）\n\n当前结构：\n{}\n变更脚本：\n{}",
+        request.table, size, schema_prompt, request.sql
+    );
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 从审查结果第一行的 `风险：高/中/低` 取出风险等级
+pub fn parse_alter_risk(review: &str) -> Option<AlterRisk> {
+    let first_line = review.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let level = first_line
+        .trim_start_matches(['*', '#', ' '])
+        .strip_prefix("风险")?
+        .trim_start_matches(['：', ':', ' ']);
+    match level.chars().next()? {
+        '高' => Some(AlterRisk::High),
+        '中' => Some(AlterRisk::Medium),
+        '低' => Some(AlterRisk::Low),
+        _ => None,
+    }
+}
+
+/// 表设计器保存时生成变更摘要的请求
+#[derive(Debug, Clone)]
+pub struct ChangeSummaryRequest {
+    pub database_type: DatabaseType,
+    pub table: String,
+    /// `describe_design_changes` 得到的结构变更清单
+    pub changes: Vec<String>,
+}
+
+fn column_type_text(column: &ColumnDefinition) -> String {
+    let mut text = column.data_type.clone();
+    match (column.length, column.precision, column.scale) {
+        (_, Some(precision), Some(scale)) => text.push_str(&format!("({},{})", precision, scale)),
+        (_, Some(precision), None) => text.push_str(&format!("({})", precision)),
+        (Some(length), _, _) => text.push_str(&format!("({})", length)),
+        _ => {}
+    }
+    if column.is_unsigned {
+        text.push_str(" UNSIGNED");
+    }
+    text
+}
+
+fn column_summary(column: &ColumnDefinition) -> String {
+    let mut text = format!("{} {}", column.name, column_type_text(column));
+    if column.is_primary_key {
+        text.push_str(" PK");
+    }
+    if !column.is_nullable {
+        text.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default_value {
+        text.push_str(&format!(" DEFAULT {}", default));
+    }
+    text
+}
+
+fn column_changes(original: &ColumnDefinition, new: &ColumnDefinition) -> Vec<String> {
+    let mut changes = Vec::new();
+    let (old_type, new_type) = (column_type_text(original), column_type_text(new));
+    if old_type != new_type {
+        changes.push(format!("类型 {} → {}", old_type, new_type));
+    }
+    if original.is_nullable != new.is_nullable {
+        changes.push(if new.is_nullable { "改为可空" } else { "改为 NOT NULL" }.to_string());
+    }
+    if original.default_value != new.default_value {
+        changes.push(format!(
+            "默认值 {} → {}",
+            original.default_value.as_deref().unwrap_or("无"),
+            new.default_value.as_deref().unwrap_or("无")
+        ));
+    }
+    if original.is_auto_increment != new.is_auto_increment {
+        changes.push(if new.is_auto_increment { "设为自增" } else { "取消自增" }.to_string());
+    }
+    if original.is_primary_key != new.is_primary_key {
+        changes.push(if new.is_primary_key { "加入主键" } else { "移出主键" }.to_string());
+    }
+    if original.comment != new.comment {
+        changes.push(format!("注释改为 \"{}\"", new.comment));
+    }
+    if original.charset != new.charset || original.collation != new.collation {
+        changes.push("字符集或排序规则变化".to_string());
+    }
+    changes
+}
+
+fn index_summary(index: &IndexDefinition) -> String {
+    let kind = if index.is_primary {
+        "主键"
+    } else if index.is_unique {
+        "唯一索引"
+    } else {
+        "索引"
+    };
+    format!("{} {}({})", kind, index.name, index.columns.join(", "))
+}
+
+fn option_change(name: &str, original: Option<&str>, new: Option<&str>) -> Option<String> {
+    (original != new).then(|| format!("{} {} → {}", name, original.unwrap_or("默认"), new.unwrap_or("默认")))
+}
+
+/// 按名称对比原始和新的表设计，列出结构变更；与 `build_alter_table_sql` 相同，改名视为删除后新增
+pub fn describe_design_changes(original: &TableDesign, new: &TableDesign) -> Vec<String> {
+    let mut changes = Vec::new();
+    if original.table_name != new.table_name {
+        changes.push(format!("表重命名 {} → {}", original.table_name, new.table_name));
+    }
+
+    for column in &original.columns {
+        if !new.columns.iter().any(|c| c.name == column.name) {
+            changes.push(format!("删除列 {}", column.name));
+        }
+    }
+    for column in &new.columns {
+        match original.columns.iter().find(|c| c.name == column.name) {
+            Some(original_column) => {
+                let column_changes = column_changes(original_column, column);
+                if !column_changes.is_empty() {
+                    changes.push(format!("修改列 {}：{}", column.name, column_changes.join("，")));
+                }
+            }
+            None => changes.push(format!("新增列 {}", column_summary(column))),
+        }
+    }
+
+    for index in &original.indexes {
+        match new.indexes.iter().find(|i| i.name == index.name) {
+            Some(new_index)
+                if new_index.columns == index.columns
+                    && new_index.is_unique == index.is_unique
+                    && new_index.is_primary == index.is_primary => {}
+            Some(new_index) => changes.push(format!("修改{} → {}", index_summary(index), index_summary(new_index))),
+            None => changes.push(format!("删除{}", index_summary(index))),
+        }
+    }
+    for index in &new.indexes {
+        if !original.indexes.iter().any(|i| i.name == index.name) {
+            changes.push(format!("新增{}", index_summary(index)));
+        }
+    }
+
+    for foreign_key in &original.foreign_keys {
+        if !new.foreign_keys.iter().any(|f| f.name == foreign_key.name) {
+            changes.push(format!("删除外键 {}", foreign_key.name));
+        }
+    }
+    for foreign_key in &new.foreign_keys {
+        if !original.foreign_keys.iter().any(|f| f.name == foreign_key.name) {
+            changes.push(format!(
+                "新增外键 {}({}) → {}({})",
+                foreign_key.name,
+                foreign_key.columns.join(", "),
+                foreign_key.ref_table,
+                foreign_key.ref_columns.join(", ")
+            ));
+        }
+    }
+
+    let (original_options, new_options) = (&original.options, &new.options);
+    changes.extend(option_change("引擎", original_options.engine.as_deref(), new_options.engine.as_deref()));
+    changes.extend(option_change("字符集", original_options.charset.as_deref(), new_options.charset.as_deref()));
+    changes.extend(option_change("排序规则", original_options.collation.as_deref(), new_options.collation.as_deref()));
+    if original_options.comment != new_options.comment {
+        changes.push(format!("表注释改为 \"{}\"", new_options.comment));
+    }
+    changes
+}
+
+pub fn build_change_summary_messages(request: &ChangeSummaryRequest) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是 {} 数据库迁移的维护者。根据表结构变更清单写一段可直接用作迁移提交信息的摘要。\n\
+         要求：第一行是不超过 72 个字符的英文祈使句标题；空一行后用 `- ` 逐条用英文说明变更，合并同类变更，\
+         指出删除列、收紧约束等可能影响已有数据或应用代码的变更；不要编造清单以外的变更；不要使用 Markdown 标题或代码块。",
+        request.database_type.as_str(),
+    );
+    let changes = request
+        .changes
+        .iter()
+        .map(|change| format!("- {}", change))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let user = format!("表 {} 的结构变更：\n{}", request.table, changes);
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+/// 模型有时仍会把整篇文档包在 ```markdown 代码块中，去掉外层代码块
+pub fn strip_markdown_fence(response: &str) -> String {
+    let trimmed = response.trim();
+    let Some(body) = trimmed.strip_prefix("```") else {
+        return trimmed.to_string();
+    };
+    let Some(body) = body.strip_suffix("```") else {
+        return trimmed.to_string();
+    };
+    let body = match body.split_once('\n') {
+        Some((lang, rest)) if !lang.trim().contains(' ') => rest,
+        _ => body,
+    };
+    body.trim().to_string()
+}
+
+/// 使用第一个启用的模型完成对话，返回回复内容
+pub(crate) async fn complete(messages: Vec<ChatMessage>, cx: &mut AsyncApp) -> Result<String> {
+    let (storage, provider_state) = cx.update(|cx| {
+        (
+            cx.global::<GlobalStorageState>().storage.clone(),
+            cx.global::<GlobalProviderState>().clone(),
+        )
+    })?;
+
+    let response = Tokio::spawn_result(cx, async move {
+        let repo = storage.get::<ProviderRepository>().await
+            .ok_or_else(|| anyhow!("ProviderRepository not found"))?;
+        let config = repo.list().await?
+            .into_iter()
+            .find(|p| p.enabled)
+            .ok_or_else(|| anyhow!("没有可用的 AI 模型，请先在设置中配置"))?;
+        let provider = provider_state.manager().get_provider(config).await?;
+        provider.chat(ChatRequest::new(messages)).await
+    })?.await?;
+    Ok(response.content)
+}
+
+/// 第一个启用的模型可用于表结构上下文的字符数
+async fn schema_budget(cx: &mut AsyncApp) -> Result<usize> {
+    let (storage, provider_state) = cx.update(|cx| {
+        (
+            cx.global::<GlobalStorageState>().storage.clone(),
+            cx.global::<GlobalProviderState>().clone(),
+        )
+    })?;
+
+    Tokio::spawn_result(cx, async move {
+        let repo = storage.get::<ProviderRepository>().await
+            .ok_or_else(|| anyhow!("ProviderRepository not found"))?;
+        let config = repo.list().await?
+            .into_iter()
+            .find(|p| p.enabled)
+            .ok_or_else(|| anyhow!("没有可用的 AI 模型，请先在设置中配置"))?;
+        let reserved = config.max_tokens.unwrap_or(0).max(0) as u32;
+        let provider = provider_state.manager().get_provider(config).await?;
+        Ok(schema_char_budget(provider.model_info().as_ref(), reserved))
+    })?.await
+}
+
+/// 用第一个启用的模型更新表的向量索引（只为新增或结构变化的表重新生成），返回与问题最相关的表名
+async fn relevant_table_names(
+    connection_id: i64,
+    database: String,
+    documents: Vec<(String, String)>,
+    question: String,
+    cx: &mut AsyncApp,
+) -> Result<Vec<String>> {
+    let (storage, provider_state) = cx.update(|cx| {
+        (
+            cx.global::<GlobalStorageState>().storage.clone(),
+            cx.global::<GlobalProviderState>().clone(),
+        )
+    })?;
+
+    Tokio::spawn_result(cx, async move {
+        let repo = storage.get::<ProviderRepository>().await
+            .ok_or_else(|| anyhow!("ProviderRepository not found"))?;
+        let config = repo.list().await?
+            .into_iter()
+            .find(|p| p.enabled)
+            .ok_or_else(|| anyhow!("没有可用的 AI 模型，请先在设置中配置"))?;
+        let provider = provider_state.manager().get_provider(config).await?;
+        let embedding_repo = storage.get::<SchemaEmbeddingRepository>().await
+            .ok_or_else(|| anyhow!("SchemaEmbeddingRepository not found"))?;
+
+        let mut embeddings = embedding_repo.list(connection_id, &database).await?;
+        let stale: Vec<(String, String)> = documents
+            .iter()
+            .filter(|(name, document)| {
+                !embeddings.iter().any(|e| &e.table_name == name && &e.document == document)
+            })
+            .cloned()
+            .collect();
+        for batch in stale.chunks(EMBEDDING_BATCH_SIZE) {
+            let vectors = provider.embed(batch.iter().map(|(_, document)| document.clone()).collect()).await?;
+            if vectors.len() != batch.len() {
+                return Err(anyhow!("embedding 数量与表数量不一致"));
+            }
+            let fresh: Vec<SchemaEmbedding> = batch
+                .iter()
+                .zip(vectors)
+                .map(|((table_name, document), embedding)| SchemaEmbedding {
+                    table_name: table_name.clone(),
+                    document: document.clone(),
+                    embedding,
+                })
+                .collect();
+            embedding_repo.put(connection_id, &database, &fresh).await?;
+            embeddings.retain(|e| !fresh.iter().any(|f| f.table_name == e.table_name));
+            embeddings.extend(fresh);
+        }
+        let table_names: Vec<String> = documents.into_iter().map(|(name, _)| name).collect();
+        embedding_repo.retain(connection_id, &database, &table_names).await?;
+        embeddings.retain(|e| table_names.contains(&e.table_name));
+
+        let question_vector = provider
+            .embed(vec![question])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("模型没有返回问题的 embedding"))?;
+        Ok(rank_tables(&question_vector, &embeddings, MAX_RETRIEVED_TABLES))
+    })?.await
+}
+
+/// 表较多时只保留与问题最相关的表和问题中直接提到的表；模型不支持 embeddings 或检索失败时
+/// 保留全部表，仍由 `summarize_schema` 按预算裁剪
+async fn retrieve_relevant_tables(
+    connection_id: &str,
+    database: &str,
+    tables: Vec<TableSchema>,
+    question: &str,
+    cx: &mut AsyncApp,
+) -> Vec<TableSchema> {
+    if tables.len() <= MAX_RETRIEVED_TABLES || question.trim().is_empty() {
+        return tables;
+    }
+    let Ok(connection_id) = connection_id.parse::<i64>() else {
+        return tables;
+    };
+
+    let documents = tables
+        .iter()
+        .map(|table| (table_display_name(table), table_document(table)))
+        .collect();
+    let relevant = match relevant_table_names(connection_id, database.to_string(), documents, question.to_string(), cx).await {
+        Ok(names) => names,
+        Err(e) => {
+            tracing::debug!("Schema retrieval unavailable, using all tables: {}", e);
+            return tables;
+        }
+    };
+
+    let identifiers = referenced_identifiers(question);
+    tables
+        .into_iter()
+        .filter(|table| {
+            relevant.contains(&table_display_name(table))
+                || identifiers.iter().any(|id| id.eq_ignore_ascii_case(&table.table.name))
+        })
+        .collect()
+}
+
+/// 使用第一个启用的模型完成对话，返回其中的 SQL
+async fn complete_sql(messages: Vec<ChatMessage>, cx: &mut AsyncApp) -> Result<String> {
+    let sql = extract_sql(&complete(messages, cx).await?);
+    if sql.is_empty() {
+        return Err(anyhow!("模型没有返回 SQL"));
+    }
+    Ok(sql)
+}
+
+/// 收集表结构并请求第一个启用的模型生成 SQL
+pub async fn generate_sql(request: TextToSqlRequest, cx: &mut AsyncApp) -> Result<String> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let tables = global_state
+        .collect_table_schemas(
+            cx,
+            request.connection_id.clone(),
+            request.database.clone(),
+            request.schema.clone(),
+            MAX_SCHEMA_TABLES,
+        )
+        .await?;
+    let tables = retrieve_relevant_tables(&request.connection_id, &request.database, tables, &request.question, cx).await;
+    let budget = schema_budget(cx).await.unwrap_or(MAX_SCHEMA_CHARS);
+    let messages = build_messages(&request, &summarize_schema(&tables, &request.question, budget));
+    complete_sql(messages, cx).await
+}
+
+/// 聊天面板选定数据库的表结构上下文：`tables` 非空时只包含这些表，否则包含整个数据库中与问题相关的表，
+/// 按模型上下文窗口裁剪并优先保留 `question` 中提到的表
+pub async fn chat_schema_context(
+    connection_id: String,
+    database: String,
+    tables: Vec<String>,
+    question: &str,
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let schemas = if tables.is_empty() {
+        let schemas = global_state
+            .collect_table_schemas(cx, connection_id.clone(), database.clone(), None, MAX_SCHEMA_TABLES)
+            .await?;
+        retrieve_relevant_tables(&connection_id, &database, schemas, question, cx).await
+    } else {
+        global_state.describe_tables(cx, connection_id, database, None, tables).await?
+    };
+    let budget = schema_budget(cx).await.unwrap_or(MAX_SCHEMA_CHARS);
+    Ok(summarize_schema(&schemas, question, budget))
+}
+
+/// 执行 EXPLAIN，连同涉及的表结构和已有索引一起请求模型给出索引建议
+pub async fn suggest_indexes(request: IndexAdviceRequest, cx: &mut AsyncApp) -> Result<IndexAdvice> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let plan = global_state
+        .explain_query(
+            cx,
+            request.connection_id.clone(),
+            Some(request.database.clone()),
+            request.sql.clone(),
+        )
+        .await?;
+    let plan = format_plan(&plan);
+
+    let tables = global_state
+        .describe_tables(
+            cx,
+            request.connection_id.clone(),
+            request.database.clone(),
+            request.schema.clone(),
+            referenced_identifiers(&request.sql),
+        )
+        .await?;
+    let budget = schema_budget(cx).await.unwrap_or(MAX_SCHEMA_CHARS);
+    let messages = build_index_messages(&request, &plan, &summarize_schema(&tables, &request.sql, budget));
+
+    let suggestion = complete_sql(messages, cx).await?;
+    let statements = extract_index_statements(&suggestion);
+    Ok(IndexAdvice { plan, suggestion, statements })
+}
+
+/// 根据错误信息请求模型给出修正后的语句
+pub async fn fix_sql(request: SqlFixRequest, cx: &mut AsyncApp) -> Result<String> {
+    complete_sql(build_fix_messages(&request), cx).await
+}
+
+/// 按固定指令请求模型改写 SQL
+pub async fn rewrite_sql(request: SqlRewriteRequest, cx: &mut AsyncApp) -> Result<String> {
+    complete_sql(build_rewrite_messages(&request), cx).await
+}
+
+/// 请求模型解释 SQL
+pub async fn explain_sql(database_type: DatabaseType, sql: &str, cx: &mut AsyncApp) -> Result<String> {
+    let explanation = strip_markdown_fence(&complete(build_explain_messages(database_type, sql), cx).await?);
+    if explanation.is_empty() {
+        return Err(anyhow!("模型没有返回解释"));
+    }
+    Ok(explanation)
+}
+
+/// 读取对象定义并请求模型解释，返回 (定义, 解释)
+pub async fn explain_routine(request: RoutineExplainRequest, cx: &mut AsyncApp) -> Result<(String, String)> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+    let source = global_state
+        .get_routine_source(
+            cx,
+            request.connection_id.clone(),
+            request.database.clone(),
+            request.schema.clone(),
+            request.object_type.clone(),
+            request.name.clone(),
+        )
+        .await?;
+    if source.trim().is_empty() {
+        return Err(anyhow!("没有读取到 {} 的定义", request.name));
+    }
+
+    let explanation = strip_markdown_fence(&complete(build_routine_explain_messages(&request, &source), cx).await?);
+    if explanation.is_empty() {
+        return Err(anyhow!("模型没有返回解释"));
+    }
+    Ok((source, explanation))
+}
+
+/// 请求模型把筛选描述翻译为 WHERE 条件
+pub async fn translate_filter(request: NaturalFilterRequest, cx: &mut AsyncApp) -> Result<String> {
+    let condition = extract_where_condition(&complete(build_filter_messages(&request), cx).await?);
+    if condition.is_empty() {
+        return Err(anyhow!("模型没有返回筛选条件"));
+    }
+    Ok(condition)
+}
+
+/// 把导出筛选描述翻译为 WHERE 条件，并用连接对应插件的方言解析校验
+pub async fn generate_export_filter(request: ExportFilterRequest, cx: &mut AsyncApp) -> Result<String> {
+    if request.tables.is_empty() {
+        return Err(anyhow!("请先填写要导出的表"));
+    }
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+    let config = global_state
+        .get_config_async(&request.connection_id)
+        .await
+        .ok_or_else(|| anyhow!("连接不存在"))?;
+    let plugin = global_state.db_manager.get_plugin(&config.database_type)?;
+
+    let mut table_columns = Vec::with_capacity(request.tables.len());
+    for table in &request.tables {
+        let columns = global_state
+            .list_columns(cx, request.connection_id.clone(), request.database.clone(), None, table.clone())
+            .await?;
+        table_columns.push(columns);
+    }
+    let columns = common_columns(&table_columns);
+    if columns.is_empty() {
+        return Err(anyhow!("所选表没有共同的列，无法生成统一的筛选条件"));
+    }
+
+    let condition = translate_filter(
+        NaturalFilterRequest {
+            database_type: config.database_type,
+            table: request.tables.join(", "),
+            columns,
+            text: request.text,
+        },
+        cx,
+    )
+    .await?;
+    plugin
+        .validate_where_condition(&condition)
+        .map_err(|e| anyhow!("生成的条件无法解析：{}，条件为 {}", e, condition))?;
+    Ok(condition)
+}
+
+/// 收集表结构和数据量，请求模型审查 ALTER 脚本
+pub async fn review_alter_sql(request: AlterReviewRequest, cx: &mut AsyncApp) -> Result<AlterReview> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let tables = global_state
+        .describe_tables(
+            cx,
+            request.connection_id.clone(),
+            request.database.clone(),
+            request.schema.clone(),
+            vec![request.table.clone()],
+        )
+        .await?;
+    // 行数只用于判断锁表影响，取不到时不影响审查
+    let row_count = global_state
+        .list_tables(cx, request.connection_id.clone(), request.database.clone())
+        .await
+        .ok()
+        .and_then(|tables| {
+            tables
+                .into_iter()
+                .find(|t| t.name == request.table && (request.schema.is_none() || t.schema == request.schema))
+        })
+        .and_then(|t| t.row_count);
+
+    let messages = build_alter_review_messages(&request, row_count, &build_schema_prompt(&tables, MAX_SCHEMA_CHARS));
+    let text = strip_markdown_fence(&complete(messages, cx).await?);
+    if text.is_empty() {
+        return Err(anyhow!("模型没有返回审查结果"));
+    }
+    Ok(AlterReview { risk: parse_alter_risk(&text), text })
+}
+
+/// 请求模型把结构变更清单写成迁移提交信息
+pub async fn summarize_design_changes(request: ChangeSummaryRequest, cx: &mut AsyncApp) -> Result<String> {
+    if request.changes.is_empty() {
+        return Err(anyhow!("没有结构变更"));
+    }
+    let summary = strip_markdown_fence(&complete(build_change_summary_messages(&request), cx).await?);
+    if summary.is_empty() {
+        return Err(anyhow!("模型没有返回变更摘要"));
+    }
+    Ok(summary)
+}
+
+/// 收集表结构（单表时包含索引）并请求模型生成 Markdown 文档
+pub async fn generate_schema_doc(request: SchemaDocRequest, cx: &mut AsyncApp) -> Result<String> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let tables = match &request.table {
+        Some(table) => {
+            global_state
+                .describe_tables(
+                    cx,
+                    request.connection_id.clone(),
+                    request.database.clone(),
+                    request.schema.clone(),
+                    vec![table.clone()],
+                )
+                .await?
+        }
+        None => {
+            global_state
+                .collect_table_schemas(
+                    cx,
+                    request.connection_id.clone(),
+                    request.database.clone(),
+                    request.schema.clone(),
+                    MAX_SCHEMA_TABLES,
+                )
+                .await?
+        }
+    };
+    if tables.is_empty() {
+        return Err(anyhow!("没有找到可生成文档的表"));
+    }
+
+    let doc = strip_markdown_fence(&complete(build_doc_messages(&request, &tables), cx).await?);
+    if doc.is_empty() {
+        return Err(anyhow!("模型没有返回文档内容"));
+    }
+    Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::{ForeignKeyDefinition, IndexInfo, TableInfo};
+
+    fn table(name: &str, columns: &[(&str, &str, bool)], foreign_keys: Vec<ForeignKeyDefinition>) -> TableSchema {
+        TableSchema {
+            table: TableInfo {
+                name: name.to_string(),
+                schema: None,
+                comment: None,
+                engine: None,
+                row_count: None,
+                create_time: None,
+                charset: None,
+                collation: None,
+            },
+            columns: columns
+                .iter()
+                .map(|(name, data_type, pk)| ColumnInfo {
+                    name: name.to_string(),
+                    data_type: data_type.to_string(),
+                    is_nullable: !pk,
+                    is_primary_key: *pk,
+                    default_value: None,
+                    comment: None,
+                    collation: None,
+                })
+                .collect(),
+            foreign_keys,
+            indexes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_schema_prompt_lists_columns_and_foreign_keys() {
+        let tables = vec![
+            table("users", &[("id", "int", true), ("name", "varchar(50)", false)], vec![]),
+            table(
+                "orders",
+                &[("id", "int", true), ("user_id", "int", false)],
+                vec![ForeignKeyDefinition {
+                    columns: vec!["user_id".to_string()],
+                    ref_table: "users".to_string(),
+                    ref_columns: vec!["id".to_string()],
+                    ..Default::default()
+                }],
+            ),
+        ];
+
+        let prompt = build_schema_prompt(&tables, 10_000);
+        assert_eq!(
+            prompt,
+            "users(id int PK, name varchar(50))\norders(id int PK, user_id int)\n  FK (user_id) -> users(id)\n"
+        );
+
+        let truncated = build_schema_prompt(&tables, 40);
+        assert!(truncated.starts_with("users("));
+        assert!(truncated.ends_with("另有 1 张表未列出\n"));
+    }
+
+    #[test]
+    fn test_summarize_schema_prioritizes_referenced_tables() {
+        let tables = vec![
+            table("users", &[("id", "int", true), ("name", "varchar(50)", false)], vec![]),
+            table("orders", &[("id", "int", true), ("user_id", "int", false)], vec![]),
+        ];
+
+        let prompt = summarize_schema(&tables, "SELECT * FROM orders", 10_000);
+        assert_eq!(
+            prompt,
+            "orders(id int PK, user_id int)\nusers(id int PK, name varchar(50))\n"
+        );
+
+        // 预算不足时其余表只保留列名
+        let condensed = summarize_schema(&tables, "SELECT * FROM orders", 60);
+        assert_eq!(condensed, "orders(id int PK, user_id int)\nusers(id, name)\n");
+
+        let truncated = summarize_schema(&tables, "SELECT * FROM orders", 40);
+        assert!(truncated.starts_with("orders(id int PK"));
+        assert!(truncated.ends_with("另有 1 张表未列出\n"));
+    }
+
+    #[test]
+    fn test_schema_char_budget_from_context_window() {
+        assert_eq!(schema_char_budget(None, 0), MAX_SCHEMA_CHARS);
+
+        let model = ModelInfo::new("m", "M", "").context_window(128_000);
+        assert_eq!(schema_char_budget(Some(&model), 4_000), 240_000);
+
+        let tiny = ModelInfo::new("m", "M", "").context_window(2_000);
+        assert_eq!(schema_char_budget(Some(&tiny), 1_000), 0);
+    }
+
+    #[test]
+    fn test_parse_optimization_splits_reasoning_and_sql() {
+        let response = "- 去掉 SELECT *\n- 用 JOIN 替代子查询\n\n```sql\nSELECT u.id FROM users u JOIN orders o ON o.user_id = u.id\n```\n预计可以使用 user_id 索引";
+        let optimization = parse_optimization(response);
+        assert_eq!(optimization.sql, "SELECT u.id FROM users u JOIN orders o ON o.user_id = u.id");
+        assert_eq!(
+            optimization.reasoning,
+            "- 去掉 SELECT *\n- 用 JOIN 替代子查询\n预计可以使用 user_id 索引"
+        );
+
+        let plain = parse_optimization("SELECT 1");
+        assert_eq!(plain.sql, "SELECT 1");
+        assert!(plain.reasoning.is_empty());
+    }
+
+    #[test]
+    fn test_side_by_side_pairs_changed_lines() {
+        let rows = side_by_side(&diff_lines("SELECT *\nFROM t\nWHERE a = 1", "SELECT id\nFROM t\nWHERE a = 1\nLIMIT 10"));
+        assert_eq!(
+            rows,
+            vec![
+                SideBySideRow { left: Some("SELECT *".to_string()), right: Some("SELECT id".to_string()), changed: true },
+                SideBySideRow { left: Some("FROM t".to_string()), right: Some("FROM t".to_string()), changed: false },
+                SideBySideRow { left: Some("WHERE a = 1".to_string()), right: Some("WHERE a = 1".to_string()), changed: false },
+                SideBySideRow { left: None, right: Some("LIMIT 10".to_string()), changed: true },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_timing_comparison() {
+        let query = |ms: u128, rows: usize| SqlResult::Query(QueryResult {
+            sql: String::new(),
+            columns: vec!["id".to_string()],
+            rows: vec![vec![Some("1".to_string())]; rows],
+            elapsed_ms: ms,
+            table_name: None,
+            editable: false,
+        });
+
+        let comparison = TimingComparison::from_results(&[query(200, 3), query(50, 3)], 1).unwrap();
+        assert_eq!(comparison.original_ms, 200);
+        assert_eq!(comparison.optimized_ms, 50);
+        assert_eq!(comparison.change_percent(), Some(-75.0));
+
+        // 只执行了原始语句时无法比较
+        assert!(TimingComparison::from_results(&[query(200, 3)], 1).is_none());
+        let error = SqlResult::Error(db::SqlErrorInfo { sql: String::new(), message: "boom".to_string() });
+        assert!(TimingComparison::from_results(&[query(200, 3), error], 1).is_none());
+    }
+
+    #[test]
+    fn test_extract_sql_from_code_block() {
+        assert_eq!(extract_sql("```sql\nSELECT 1;\n```"), "SELECT 1;");
+        assert_eq!(extract_sql("结果如下：\n```\nSELECT * FROM t\n```\n说明"), "SELECT * FROM t");
+        assert_eq!(extract_sql("  SELECT 2  "), "SELECT 2");
+    }
+
+    #[test]
+    fn test_fix_messages_include_sql_and_error() {
+        let request = SqlFixRequest {
+            database_type: DatabaseType::PostgreSQL,
+            sql: "SELECT * FORM users".to_string(),
+            error: "syntax error at or near \"FORM\"".to_string(),
+        };
+
+        let messages = build_fix_messages(&request);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].content.contains("双引号"));
+        assert!(messages[1].content.contains("SELECT * FORM users"));
+        assert!(messages[1].content.contains("syntax error at or near"));
+    }
+
+    #[test]
+    fn test_schema_prompt_lists_indexes() {
+        let mut users = table("users", &[("id", "int", true), ("email", "varchar(100)", false)], vec![]);
+        users.indexes.push(IndexInfo {
+            name: "uk_email".to_string(),
+            columns: vec!["email".to_string()],
+            is_unique: true,
+            index_type: None,
+        });
+
+        let prompt = build_schema_prompt(&[users], 10_000);
+        assert!(prompt.ends_with("  UNIQUE INDEX uk_email (email)\n"));
+    }
+
+    #[test]
+    fn test_referenced_identifiers() {
+        let identifiers = referenced_identifiers("SELECT o.id FROM `orders` o JOIN Users u ON u.id = o.user_id WHERE o.id > 10");
+        assert!(identifiers.contains(&"orders".to_string()));
+        assert!(identifiers.contains(&"Users".to_string()));
+        assert!(!identifiers.contains(&"10".to_string()));
+        assert_eq!(identifiers.iter().filter(|i| i.eq_ignore_ascii_case("id")).count(), 1);
+    }
+
+    #[test]
+    fn test_extract_index_statements() {
+        let suggestion = "-- 按 user_id 过滤并按 created_at 排序\nCREATE INDEX idx_orders_user_created ON orders (user_id, created_at);\n\
+                          -- status 选择性低，不建议单独建索引\n";
+        assert_eq!(
+            extract_index_statements(suggestion),
+            vec!["CREATE INDEX idx_orders_user_created ON orders (user_id, created_at)".to_string()]
+        );
+        assert!(extract_index_statements("-- 已有合适的索引，无需新增").is_empty());
+    }
+
+    #[test]
+    fn test_strip_markdown_fence() {
+        assert_eq!(strip_markdown_fence("```markdown\n# 订单库\n\n正文\n```"), "# 订单库\n\n正文");
+        assert_eq!(strip_markdown_fence("  # 订单库\n```sql\nSELECT 1\n```\n"), "# 订单库\n```sql\nSELECT 1\n```");
+    }
+
+    #[test]
+    fn test_rewrite_messages_name_both_dialects() {
+        let request = SqlRewriteRequest {
+            database_type: DatabaseType::MySQL,
+            sql: "SELECT `id` FROM t LIMIT 1".to_string(),
+            command: SqlRewriteCommand::ConvertDialect(DatabaseType::PostgreSQL),
+        };
+        let messages = build_rewrite_messages(&request);
+        assert!(messages[0].content.contains("MySQL SQL 转换为等价的 PostgreSQL SQL"));
+        assert_eq!(messages[1].content, request.sql);
+    }
+
+    #[test]
+    fn test_where_guard_rewrite_keeps_other_statements() {
+        let request = SqlRewriteRequest {
+            database_type: DatabaseType::MySQL,
+            sql: "DELETE FROM orders;\nSELECT 1".to_string(),
+            command: SqlRewriteCommand::AddWhereGuard,
+        };
+        let messages = build_rewrite_messages(&request);
+        assert!(messages[0].content.contains("UPDATE 和 DELETE"));
+        assert!(messages[0].content.contains("WHERE 1 = 0"));
+        assert!(messages[0].content.contains("保留原有的语句数量和顺序"));
+
+        let messages = build_explain_messages(DatabaseType::PostgreSQL, "SELECT 1");
+        assert!(messages[0].content.starts_with("你是 PostgreSQL 数据库专家"));
+        assert_eq!(messages[1].content, "SELECT 1");
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("SELECT *\nFROM t\nWHERE a = 1", "SELECT id\nFROM t\nWHERE a = 1\nLIMIT 10");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Removed("SELECT *".to_string()),
+                DiffLine::Added("SELECT id".to_string()),
+                DiffLine::Same("FROM t".to_string()),
+                DiffLine::Same("WHERE a = 1".to_string()),
+                DiffLine::Added("LIMIT 10".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_where_condition() {
+        assert_eq!(
+            extract_where_condition("```sql\nWHERE status = 'active' AND YEAR(created_at) = YEAR(CURDATE());\n```"),
+            "status = 'active' AND YEAR(created_at) = YEAR(CURDATE())"
+        );
+        assert_eq!(extract_where_condition("where_flag = 1"), "where_flag = 1");
+        assert_eq!(extract_where_condition("  名称 = '张三' "), "名称 = '张三'");
+    }
+
+    #[test]
+    fn test_common_columns() {
+        let column = |name: &str, data_type: &str| ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: true,
+            is_primary_key: false,
+            default_value: None,
+            comment: None,
+            collation: None,
+        };
+        let orders = vec![column("id", "bigint"), column("status", "varchar(20)"), column("created_at", "datetime")];
+        let archived = vec![column("ID", "bigint"), column("created_at", "datetime")];
+
+        assert_eq!(
+            common_columns(&[orders.clone(), archived]),
+            vec![("id".to_string(), "bigint".to_string()), ("created_at".to_string(), "datetime".to_string())]
+        );
+        assert_eq!(common_columns(&[orders]).len(), 3);
+        assert!(common_columns(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_alter_risk() {
+        assert_eq!(parse_alter_risk("风险：高\n1. MODIFY COLUMN 会重建表"), Some(AlterRisk::High));
+        assert_eq!(parse_alter_risk("\n**风险: 中**\n"), Some(AlterRisk::Medium));
+        assert_eq!(parse_alter_risk("风险：低"), Some(AlterRisk::Low));
+        assert_eq!(parse_alter_risk("可以直接执行"), None);
+    }
+
+    #[test]
+    fn test_alter_review_messages_include_size() {
+        let request = AlterReviewRequest {
+            connection_id: "c".to_string(),
+            database_type: DatabaseType::MySQL,
+            database: "shop".to_string(),
+            schema: None,
+            table: "orders".to_string(),
+            sql: "ALTER TABLE `orders` MODIFY COLUMN `amount` DECIMAL(12,2);".to_string(),
+        };
+        let messages = build_alter_review_messages(&request, Some(5_000_000), "orders(id int PK)");
+        assert!(messages[0].content.contains("MySQL DBA"));
+        assert!(messages[1].content.contains("表 orders（约 5000000 行）"));
+        assert!(messages[1].content.contains("MODIFY COLUMN `amount`"));
+    }
+
+    #[test]
+    fn test_routine_explain_messages_name_kind_and_source() {
+        let request = RoutineExplainRequest {
+            connection_id: "c".to_string(),
+            database_type: DatabaseType::MySQL,
+            database: "shop".to_string(),
+            schema: None,
+            object_type: DbNodeType::Trigger,
+            name: "trg_orders".to_string(),
+        };
+        let messages = build_routine_explain_messages(&request, "  CREATE TRIGGER trg_orders BEFORE INSERT ON orders FOR EACH ROW SET NEW.total = 0\n");
+        assert!(messages[0].content.contains("触发器定义"));
+        assert!(messages[0].content.contains("涉及的表"));
+        assert_eq!(
+            messages[1].content,
+            "触发器 trg_orders：\nCREATE TRIGGER trg_orders BEFORE INSERT ON orders FOR EACH ROW SET NEW.total = 0"
+        );
+    }
+
+    #[test]
+    fn test_describe_design_changes() {
+        let mut original = TableDesign::new("shop", "orders");
+        original.add_column(ColumnDefinition::new("id").data_type("bigint").primary_key(true));
+        original.add_column(ColumnDefinition::new("note").data_type("varchar").length(100));
+        original.add_column(ColumnDefinition::new("legacy").data_type("int"));
+        original.add_index(IndexDefinition::new("idx_note").columns(vec!["note".to_string()]));
+
+        let mut new = original.clone();
+        new.columns.retain(|c| c.name != "legacy");
+        new.columns[1].length = Some(255);
+        new.columns[1].is_nullable = false;
+        new.add_column(ColumnDefinition::new("status").data_type("varchar").length(20).default_value("'new'"));
+        new.indexes[0].is_unique = true;
+        new.options.comment = "订单".to_string();
+
+        assert_eq!(
+            describe_design_changes(&original, &new),
+            vec![
+                "删除列 legacy".to_string(),
+                "修改列 note：类型 varchar(100) → varchar(255)，改为 NOT NULL".to_string(),
+                "新增列 status varchar(20) DEFAULT 'new'".to_string(),
+                "修改索引 idx_note(note) → 唯一索引 idx_note(note)".to_string(),
+                "表注释改为 \"订单\"".to_string(),
+            ]
+        );
+        assert!(describe_design_changes(&original, &original).is_empty());
+    }
+
+    #[test]
+    fn test_rank_tables_and_document() {
+        let embeddings = vec![
+            SchemaEmbedding { table_name: "users".to_string(), document: String::new(), embedding: vec![1.0, 0.0] },
+            SchemaEmbedding { table_name: "orders".to_string(), document: String::new(), embedding: vec![0.6, 0.8] },
+            SchemaEmbedding { table_name: "logs".to_string(), document: String::new(), embedding: vec![-1.0, 0.0] },
+        ];
+        assert_eq!(rank_tables(&[0.0, 1.0], &embeddings, 2), vec!["orders".to_string(), "users".to_string()]);
+
+        let mut orders = table("orders", &[("id", "bigint", true), ("amount", "decimal", false)], vec![]);
+        orders.table.comment = Some("订单".to_string());
+        orders.columns[1].comment = Some("实付金额".to_string());
+        assert_eq!(table_document(&orders), "orders -- 订单\nid bigint\namount decimal -- 实付金额");
+    }
+}
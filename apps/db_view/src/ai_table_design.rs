@@ -0,0 +1,178 @@
+//! AI 列建议 - 根据对实体的自然语言描述，让 LLM 给出列名、类型和注释，填入表设计器供用户修改
+
+use anyhow::{anyhow, Result};
+use db::ColumnInfo;
+use gpui::AsyncApp;
+use one_core::llm::types::ChatMessage;
+use one_core::storage::DatabaseType;
+use serde::Deserialize;
+
+use crate::ai_sql::{complete, strip_markdown_fence};
+
+/// 发给模型的可用类型上限，避免类型很多的数据库撑大提示词
+const MAX_TYPE_NAMES: usize = 80;
+
+/// 一次列建议请求
+#[derive(Debug, Clone)]
+pub struct ColumnSuggestionRequest {
+    pub database_type: DatabaseType,
+    /// 实体描述，如“博客文章，包含标题、作者、发布日期”
+    pub description: String,
+    /// 表设计器中可选的数据类型名称
+    pub type_names: Vec<String>,
+    /// 设计器中已有的列名，模型不应重复给出
+    pub existing_columns: Vec<String>,
+}
+
+/// 模型建议的一列
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SuggestedColumn {
+    pub name: String,
+    pub data_type: String,
+    #[serde(default = "default_nullable")]
+    pub nullable: bool,
+    #[serde(default)]
+    pub primary_key: bool,
+    #[serde(default)]
+    pub comment: String,
+}
+
+fn default_nullable() -> bool {
+    true
+}
+
+impl SuggestedColumn {
+    pub fn into_column_info(self) -> ColumnInfo {
+        ColumnInfo {
+            name: self.name,
+            data_type: self.data_type,
+            is_nullable: self.nullable && !self.primary_key,
+            is_primary_key: self.primary_key,
+            default_value: None,
+            comment: (!self.comment.is_empty()).then_some(self.comment),
+            collation: None,
+        }
+    }
+}
+
+pub fn build_column_messages(request: &ColumnSuggestionRequest) -> Vec<ChatMessage> {
+    let type_names: Vec<&str> = request.type_names.iter().take(MAX_TYPE_NAMES).map(String::as_str).collect();
+    let mut system = format!(
+        "你是 {} 数据库建模专家。根据用户对实体的描述设计表的列：列名使用小写蛇形命名，\
+         选择合适的类型，需要长度或精度时写在类型后的括号中（如 VARCHAR(255)、DECIMAL(10,2)），\
+         为每列写一句简短的中文注释；描述中没有主键时补充一个主键列。\n\
+         只输出一个 JSON 数组，不要解释，不要使用 Markdown，每个元素格式为：\
+         {{\"name\": \"列名\", \"data_type\": \"类型\", \"nullable\": true, \"primary_key\": false, \"comment\": \"注释\"}}。",
+        request.database_type.as_str(),
+    );
+    if !type_names.is_empty() {
+        system.push_str(&format!("\n类型只能从以下列表中选择：{}", type_names.join(", ")));
+    }
+    if !request.existing_columns.is_empty() {
+        system.push_str(&format!("\n表中已有这些列，不要重复给出：{}", request.existing_columns.join(", ")));
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(request.description.clone())]
+}
+
+/// 解析模型回复，跳过缺少列名或类型、以及与已有列重名的条目
+pub fn parse_column_suggestions(response: &str, existing_columns: &[String]) -> Result<Vec<SuggestedColumn>> {
+    let body = strip_markdown_fence(response);
+    let json = match (body.find('['), body.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &body[start..=end],
+        _ => return Err(anyhow!("模型没有返回列建议")),
+    };
+    let items: Vec<SuggestedColumn> = serde_json::from_str(json).map_err(|e| anyhow!("列建议无效：{}", e))?;
+
+    let mut seen: Vec<String> = existing_columns.iter().map(|name| name.to_lowercase()).collect();
+    let columns: Vec<SuggestedColumn> = items
+        .into_iter()
+        .filter_map(|mut column| {
+            column.name = column.name.trim().to_string();
+            column.data_type = column.data_type.trim().to_uppercase();
+            column.comment = column.comment.trim().to_string();
+            if column.name.is_empty() || column.data_type.is_empty() {
+                return None;
+            }
+            let key = column.name.to_lowercase();
+            if seen.contains(&key) {
+                return None;
+            }
+            seen.push(key);
+            Some(column)
+        })
+        .collect();
+
+    if columns.is_empty() {
+        return Err(anyhow!("模型没有给出新的列"));
+    }
+    Ok(columns)
+}
+
+/// 请求第一个启用的模型根据描述建议列
+pub async fn suggest_columns(request: ColumnSuggestionRequest, cx: &mut AsyncApp) -> Result<Vec<SuggestedColumn>> {
+    if request.description.trim().is_empty() {
+        return Err(anyhow!("请先描述要建的表"));
+    }
+    let response = complete(build_column_messages(&request), cx).await?;
+    parse_column_suggestions(&response, &request.existing_columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> ColumnSuggestionRequest {
+        ColumnSuggestionRequest {
+            database_type: DatabaseType::MySQL,
+            description: "博客文章，包含标题、作者、发布日期".to_string(),
+            type_names: vec!["BIGINT".to_string(), "VARCHAR".to_string(), "DATETIME".to_string()],
+            existing_columns: vec!["id".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_column_messages_list_types_and_existing_columns() {
+        let messages = build_column_messages(&request());
+        assert!(messages[0].content.contains("BIGINT, VARCHAR, DATETIME"));
+        assert!(messages[0].content.contains("不要重复给出：id"));
+        assert_eq!(messages[1].content, "博客文章，包含标题、作者、发布日期");
+    }
+
+    #[test]
+    fn test_parse_column_suggestions() {
+        let response = "```json\n[\
+            {\"name\": \"ID\", \"data_type\": \"bigint\", \"primary_key\": true},\
+            {\"name\": \" title \", \"data_type\": \"varchar(200)\", \"nullable\": false, \"comment\": \"标题\"},\
+            {\"name\": \"author\", \"data_type\": \"\"},\
+            {\"name\": \"title\", \"data_type\": \"text\"},\
+            {\"name\": \"published_at\", \"data_type\": \"datetime\"}\
+        ]\n```";
+        let columns = parse_column_suggestions(response, &["id".to_string()]).unwrap();
+
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].name, "title");
+        assert_eq!(columns[0].data_type, "VARCHAR(200)");
+        assert!(!columns[0].nullable);
+        assert!(columns[1].nullable);
+
+        let info = columns[0].clone().into_column_info();
+        assert_eq!(info.comment.as_deref(), Some("标题"));
+        assert_eq!(columns[1].clone().into_column_info().comment, None);
+
+        assert!(parse_column_suggestions("无法理解", &[]).is_err());
+        assert!(parse_column_suggestions("[{\"name\": \"id\", \"data_type\": \"int\"}]", &["ID".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_primary_key_is_not_nullable() {
+        let column = SuggestedColumn {
+            name: "id".to_string(),
+            data_type: "BIGINT".to_string(),
+            nullable: true,
+            primary_key: true,
+            comment: String::new(),
+        };
+        assert!(!column.into_column_info().is_nullable);
+    }
+}
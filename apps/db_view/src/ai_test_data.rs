@@ -0,0 +1,394 @@
+//! AI 生成测试数据 - 把表的列类型、NOT NULL、唯一约束和外键可选值交给 LLM 生成行数据，
+//! 程序按约束过滤后自行拼接 INSERT 语句（模型只提供值，不直接生成 SQL），预览后再执行
+
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use db::{ColumnInfo, GlobalDbState, TableRowChange, TableSaveRequest, TableSchema};
+use gpui::AsyncApp;
+use one_core::llm::types::ChatMessage;
+use one_core::storage::DatabaseType;
+use serde_json::Value;
+
+use crate::ai_sql::{complete, strip_markdown_fence};
+
+pub const DEFAULT_ROW_COUNT: usize = 20;
+pub const MAX_ROW_COUNT: usize = 200;
+
+/// 一次生成测试数据的请求
+#[derive(Debug, Clone)]
+pub struct TestDataRequest {
+    pub connection_id: String,
+    pub database_type: DatabaseType,
+    pub database: String,
+    pub schema: Option<String>,
+    pub table: String,
+    pub row_count: usize,
+}
+
+/// 需要生成值的列及其约束
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSpec {
+    pub name: String,
+    pub data_type: String,
+    pub not_null: bool,
+    pub comment: Option<String>,
+    /// 外键列的可选值，None 表示不限制
+    pub allowed_values: Option<Vec<String>>,
+}
+
+/// 生成计划：需要生成的列，以及以列下标表示的唯一约束
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestDataPlan {
+    pub table: String,
+    pub columns: Vec<ColumnSpec>,
+    pub unique_groups: Vec<Vec<usize>>,
+}
+
+/// 预览用的生成结果
+#[derive(Debug, Clone)]
+pub struct GeneratedTestData {
+    pub sql: String,
+    pub rows: usize,
+    /// 因违反约束被丢弃的行数
+    pub discarded: usize,
+}
+
+fn is_integer_type(data_type: &str) -> bool {
+    let data_type = data_type.to_lowercase();
+    data_type.contains("int") && !data_type.contains("interval") && !data_type.contains("point")
+}
+
+/// 由数据库生成值的列（自增、序列、标识列）不交给模型生成；
+/// MySQL 的列类型中不含自增信息，单列整数主键也视为自增
+fn is_generated_column(column: &ColumnInfo, single_primary_key: bool) -> bool {
+    let data_type = column.data_type.to_uppercase();
+    let default = column.default_value.as_deref().unwrap_or_default().to_lowercase();
+    data_type.contains("AUTO_INCREMENT")
+        || data_type.contains("SERIAL")
+        || data_type.contains("IDENTITY")
+        || default.starts_with("nextval(")
+        || default.contains("identity")
+        || (single_primary_key && column.is_primary_key && is_integer_type(&column.data_type))
+}
+
+pub fn plan_from_schema(schema: &TableSchema) -> TestDataPlan {
+    let primary_key: Vec<&str> = schema
+        .columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .map(|c| c.name.as_str())
+        .collect();
+
+    let columns: Vec<ColumnSpec> = schema
+        .columns
+        .iter()
+        .filter(|c| !is_generated_column(c, primary_key.len() == 1))
+        .map(|c| ColumnSpec {
+            name: c.name.clone(),
+            data_type: c.data_type.clone(),
+            not_null: !c.is_nullable && c.default_value.is_none(),
+            comment: c.comment.clone().filter(|comment| !comment.is_empty()),
+            allowed_values: None,
+        })
+        .collect();
+
+    // 只保留所有列都需要生成的唯一约束，包含自增列的约束由数据库保证
+    let position = |name: &str| columns.iter().position(|c| c.name.eq_ignore_ascii_case(name));
+    let mut unique_groups: Vec<Vec<usize>> = Vec::new();
+    let candidates = std::iter::once(primary_key.iter().map(|name| name.to_string()).collect::<Vec<_>>())
+        .chain(schema.indexes.iter().filter(|i| i.is_unique).map(|i| i.columns.clone()));
+    for group in candidates.filter(|group| !group.is_empty()) {
+        let indices: Option<Vec<usize>> = group.iter().map(|name| position(name)).collect();
+        if let Some(indices) = indices {
+            if !unique_groups.contains(&indices) {
+                unique_groups.push(indices);
+            }
+        }
+    }
+
+    TestDataPlan {
+        table: schema.table.name.clone(),
+        columns,
+        unique_groups,
+    }
+}
+
+pub fn build_test_data_messages(plan: &TestDataPlan, database_type: DatabaseType, row_count: usize) -> Vec<ChatMessage> {
+    let system = format!(
+        "你是测试数据生成专家。为 {} 表 {} 生成 {} 行真实可信的测试数据。\n\
+         要求：值符合列的类型和长度；NOT NULL 列不能为 null；唯一约束中的列（或列组合）在各行之间不能重复；\
+         外键列只能从给出的可选值中选择；日期使用 YYYY-MM-DD，时间使用 YYYY-MM-DD HH:MM:SS；\
+         根据列名和注释生成贴近业务的值，不要使用“测试1”“test”之类的占位内容。\n\
+         只输出一个 JSON 数组，每个元素是以列名为键的对象，不要解释，不要使用 Markdown。",
+        database_type.as_str(),
+        plan.table,
+        row_count,
+    );
+
+    let mut user = String::from("列：\n");
+    for column in &plan.columns {
+        user.push_str(&format!("- {} {}", column.name, column.data_type));
+        if column.not_null {
+            user.push_str(" NOT NULL");
+        }
+        if let Some(comment) = &column.comment {
+            user.push_str(&format!(" -- {}", comment));
+        }
+        user.push('\n');
+    }
+    if !plan.unique_groups.is_empty() {
+        user.push_str("\n唯一约束：\n");
+        for group in &plan.unique_groups {
+            let names: Vec<&str> = group.iter().map(|&i| plan.columns[i].name.as_str()).collect();
+            user.push_str(&format!("- ({})\n", names.join(", ")));
+        }
+    }
+    let foreign_keys: Vec<&ColumnSpec> = plan.columns.iter().filter(|c| c.allowed_values.is_some()).collect();
+    if !foreign_keys.is_empty() {
+        user.push_str("\n外键列的可选值：\n");
+        for column in foreign_keys {
+            let values = column.allowed_values.as_deref().unwrap_or_default();
+            user.push_str(&format!("- {}: {}\n", column.name, values.join(", ")));
+        }
+    }
+
+    vec![ChatMessage::system(system), ChatMessage::user(user)]
+}
+
+fn json_to_cell(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// 解析模型返回的 JSON 数组，按计划中的列顺序取值
+pub fn parse_generated_rows(response: &str, plan: &TestDataPlan) -> Result<Vec<Vec<Option<String>>>> {
+    let body = strip_markdown_fence(response);
+    let json = match (body.find('['), body.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &body[start..=end],
+        _ => return Err(anyhow!("模型没有返回 JSON 数组")),
+    };
+    let items: Vec<Value> = serde_json::from_str(json).map_err(|e| anyhow!("生成的数据无效：{}", e))?;
+
+    Ok(items
+        .iter()
+        .filter_map(Value::as_object)
+        .map(|object| {
+            plan.columns
+                .iter()
+                .map(|column| {
+                    let value = object
+                        .iter()
+                        .find(|(key, _)| key.eq_ignore_ascii_case(&column.name))
+                        .map(|(_, value)| value);
+                    json_to_cell(value)
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// 丢弃违反 NOT NULL、外键可选值或唯一约束的行，返回保留的行和丢弃的行数
+pub fn enforce_constraints(plan: &TestDataPlan, rows: Vec<Vec<Option<String>>>) -> (Vec<Vec<Option<String>>>, usize) {
+    let total = rows.len();
+    let mut seen: Vec<HashSet<Vec<Option<String>>>> = vec![HashSet::new(); plan.unique_groups.len()];
+    let mut kept = Vec::with_capacity(total);
+
+    for row in rows {
+        let valid = plan.columns.iter().zip(&row).all(|(column, cell)| match cell {
+            None => !column.not_null,
+            Some(value) => column
+                .allowed_values
+                .as_ref()
+                .is_none_or(|allowed| allowed.iter().any(|v| v == value)),
+        });
+        if !valid {
+            continue;
+        }
+
+        let keys: Vec<Vec<Option<String>>> = plan
+            .unique_groups
+            .iter()
+            .map(|group| group.iter().map(|&i| row[i].clone()).collect())
+            .collect();
+        // NULL 不参与唯一性比较
+        let duplicate = keys
+            .iter()
+            .zip(&seen)
+            .any(|(key, seen)| key.iter().all(Option::is_some) && seen.contains(key));
+        if duplicate {
+            continue;
+        }
+        for (key, seen) in keys.into_iter().zip(seen.iter_mut()) {
+            seen.insert(key);
+        }
+        kept.push(row);
+    }
+
+    let discarded = total - kept.len();
+    (kept, discarded)
+}
+
+/// 读取表结构和外键可选值，请求模型生成数据并拼接 INSERT 语句
+pub async fn generate_test_data(request: TestDataRequest, cx: &mut AsyncApp) -> Result<GeneratedTestData> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    let schema = global_state
+        .describe_tables(
+            cx,
+            request.connection_id.clone(),
+            request.database.clone(),
+            request.schema.clone(),
+            vec![request.table.clone()],
+        )
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("没有找到表 {}", request.table))?;
+
+    let mut plan = plan_from_schema(&schema);
+    if plan.columns.is_empty() {
+        return Err(anyhow!("表 {} 没有需要生成值的列", request.table));
+    }
+    for fk in schema.foreign_keys.iter().filter(|fk| fk.columns.len() == 1 && fk.ref_columns.len() == 1) {
+        let Some(column) = plan.columns.iter_mut().find(|c| c.name.eq_ignore_ascii_case(&fk.columns[0])) else {
+            continue;
+        };
+        let values = global_state
+            .sample_column_values(
+                cx,
+                request.connection_id.clone(),
+                request.database.clone(),
+                request.schema.clone(),
+                fk.ref_table.clone(),
+                fk.ref_columns[0].clone(),
+            )
+            .await?;
+        if values.is_empty() && column.not_null {
+            return Err(anyhow!("被引用的表 {} 中没有数据，无法为外键列 {} 生成值", fk.ref_table, column.name));
+        }
+        column.allowed_values = Some(values);
+    }
+
+    let row_count = request.row_count.clamp(1, MAX_ROW_COUNT);
+    let response = complete(build_test_data_messages(&plan, request.database_type, row_count), cx).await?;
+    let (rows, discarded) = enforce_constraints(&plan, parse_generated_rows(&response, &plan)?);
+    if rows.is_empty() {
+        return Err(anyhow!("生成的数据都不满足表的约束，请重试"));
+    }
+
+    let save_request = TableSaveRequest {
+        database: request.database.clone(),
+        schema: request.schema.clone(),
+        table: request.table.clone(),
+        column_names: plan.columns.iter().map(|c| c.name.clone()).collect(),
+        primary_key_indices: Vec::new(),
+        unique_key_indices: Vec::new(),
+        changes: rows
+            .iter()
+            .map(|row| TableRowChange::Added {
+                data: row.iter().map(|cell| cell.clone().unwrap_or_else(|| "NULL".to_string())).collect(),
+            })
+            .collect(),
+    };
+    let plugin = global_state.get_plugin(&request.database_type)?;
+
+    Ok(GeneratedTestData {
+        sql: plugin.generate_table_changes_sql(&save_request),
+        rows: rows.len(),
+        discarded,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db::{IndexInfo, TableInfo};
+
+    fn column(name: &str, data_type: &str, nullable: bool, primary_key: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: nullable,
+            is_primary_key: primary_key,
+            default_value: None,
+            comment: None,
+            collation: None,
+        }
+    }
+
+    fn schema() -> TableSchema {
+        TableSchema {
+            table: TableInfo {
+                name: "users".to_string(),
+                schema: None,
+                comment: None,
+                engine: None,
+                row_count: None,
+                create_time: None,
+                charset: None,
+                collation: None,
+            },
+            columns: vec![
+                column("id", "bigint", false, true),
+                column("email", "varchar(255)", false, false),
+                column("team_id", "int", true, false),
+            ],
+            foreign_keys: vec![],
+            indexes: vec![IndexInfo {
+                name: "uk_email".to_string(),
+                columns: vec!["email".to_string()],
+                is_unique: true,
+                index_type: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_plan_skips_generated_primary_key() {
+        let plan = plan_from_schema(&schema());
+        let names: Vec<&str> = plan.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["email", "team_id"]);
+        assert_eq!(plan.unique_groups, vec![vec![0]]);
+        assert!(plan.columns[0].not_null);
+    }
+
+    #[test]
+    fn test_parse_and_enforce_constraints() {
+        let mut plan = plan_from_schema(&schema());
+        plan.columns[1].allowed_values = Some(vec!["1".to_string(), "2".to_string()]);
+
+        let response = r#"```json
+[
+  {"email": "li.wei@example.com", "team_id": 1},
+  {"EMAIL": "li.wei@example.com", "team_id": 2},
+  {"email": null, "team_id": 1},
+  {"email": "zhang.min@example.com", "team_id": 9},
+  {"email": "wang.fang@example.com"}
+]
+```"#;
+        let rows = parse_generated_rows(response, &plan).unwrap();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[0], vec![Some("li.wei@example.com".to_string()), Some("1".to_string())]);
+
+        let (kept, discarded) = enforce_constraints(&plan, rows);
+        assert_eq!(discarded, 3);
+        assert_eq!(kept[1], vec![Some("wang.fang@example.com".to_string()), None]);
+    }
+
+    #[test]
+    fn test_messages_list_constraints() {
+        let mut plan = plan_from_schema(&schema());
+        plan.columns[1].allowed_values = Some(vec!["1".to_string(), "2".to_string()]);
+        let messages = build_test_data_messages(&plan, DatabaseType::MySQL, 10);
+        assert!(messages[0].content.contains("生成 10 行"));
+        assert!(messages[1].content.contains("- email varchar(255) NOT NULL"));
+        assert!(messages[1].content.contains("- (email)"));
+        assert!(messages[1].content.contains("- team_id: 1, 2"));
+    }
+}
@@ -0,0 +1,340 @@
+//! AI 对话的只读数据库工具 - 让模型查看连接、表结构并执行只读查询，根据真实数据回答问题
+
+use anyhow::{anyhow, Result};
+use db::{GlobalDbState, QueryResult};
+use gpui::AsyncApp;
+use one_core::gpui_tokio::Tokio;
+use one_core::llm::{
+    manager::GlobalProviderState,
+    storage::ProviderRepository,
+    types::{ChatMessage, ChatRequest, ToolCall, ToolDefinition},
+};
+use one_core::storage::{traits::Repository, GlobalStorageState};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+
+use crate::ai_sql::build_schema_prompt;
+
+/// 一次提问中最多进行的工具调用轮数，防止模型反复调用
+const MAX_TOOL_ROUNDS: usize = 8;
+const DEFAULT_SELECT_LIMIT: usize = 100;
+const MAX_SELECT_LIMIT: usize = 500;
+/// 单个工具结果的字符上限，避免查询结果撑爆上下文
+const MAX_RESULT_CHARS: usize = 16_000;
+
+pub fn tool_definitions() -> Vec<ToolDefinition> {
+    let connection_id = json!({ "type": "string", "description": "list_connections 返回的连接 ID" });
+    let database = json!({ "type": "string", "description": "数据库名" });
+    let schema = json!({ "type": "string", "description": "schema 名，数据库不支持 schema 时省略" });
+
+    vec![
+        ToolDefinition::new(
+            "list_connections",
+            "列出当前打开的数据库连接",
+            json!({ "type": "object", "properties": {} }),
+        ),
+        ToolDefinition::new(
+            "list_databases",
+            "列出连接中的数据库",
+            json!({
+                "type": "object",
+                "properties": { "connection_id": connection_id },
+                "required": ["connection_id"]
+            }),
+        ),
+        ToolDefinition::new(
+            "list_tables",
+            "列出数据库中的表及其注释",
+            json!({
+                "type": "object",
+                "properties": { "connection_id": connection_id, "database": database, "schema": schema },
+                "required": ["connection_id", "database"]
+            }),
+        ),
+        ToolDefinition::new(
+            "describe_table",
+            "查看表的列、主键、外键和索引",
+            json!({
+                "type": "object",
+                "properties": {
+                    "connection_id": connection_id,
+                    "database": database,
+                    "schema": schema,
+                    "table": { "type": "string", "description": "表名" }
+                },
+                "required": ["connection_id", "database", "table"]
+            }),
+        ),
+        ToolDefinition::new(
+            "run_select",
+            "执行一条只读 SELECT 语句并返回结果，只允许单条查询，不能修改数据",
+            json!({
+                "type": "object",
+                "properties": {
+                    "connection_id": connection_id,
+                    "database": database,
+                    "sql": { "type": "string", "description": "要执行的 SELECT 语句" },
+                    "limit": {
+                        "type": "integer",
+                        "description": format!("最多返回的行数，默认 {}，最大 {}", DEFAULT_SELECT_LIMIT, MAX_SELECT_LIMIT)
+                    }
+                },
+                "required": ["connection_id", "database", "sql"]
+            }),
+        ),
+    ]
+}
+
+pub fn system_prompt() -> String {
+    "你是数据库 AI 助手，可以使用工具只读地访问用户打开的数据库。\
+     需要数据才能回答时，先用 list_connections、list_tables、describe_table 了解表结构，再用 run_select 查询；\
+     统计类问题尽量用聚合查询，不要取回大量明细。只能执行 SELECT，不要尝试修改数据。\
+     回答时说明依据的查询结果。"
+        .to_string()
+}
+
+#[derive(Deserialize)]
+struct ConnectionArgs {
+    connection_id: String,
+}
+
+#[derive(Deserialize)]
+struct TablesArgs {
+    connection_id: String,
+    database: String,
+    #[serde(default)]
+    schema: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DescribeArgs {
+    connection_id: String,
+    database: String,
+    #[serde(default)]
+    schema: Option<String>,
+    table: String,
+}
+
+#[derive(Deserialize)]
+struct SelectArgs {
+    connection_id: String,
+    database: String,
+    sql: String,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+fn parse_args<T: DeserializeOwned>(call: &ToolCall) -> Result<T> {
+    serde_json::from_value(call.arguments.clone()).map_err(|e| anyhow!("参数无效：{}", e))
+}
+
+/// 展示在对话中的一行调用说明，让用户知道模型访问了什么
+pub fn describe_call(call: &ToolCall) -> String {
+    let arg = |key: &str| call.arguments.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    match call.name.as_str() {
+        "list_connections" => "查看连接列表".to_string(),
+        "list_databases" => "查看数据库列表".to_string(),
+        "list_tables" => format!("查看 {} 中的表", arg("database")),
+        "describe_table" => format!("查看表结构 {}", arg("table")),
+        "run_select" => format!("执行查询 `{}`", arg("sql").split_whitespace().collect::<Vec<_>>().join(" ")),
+        name => format!("调用 {}", name),
+    }
+}
+
+/// 截断到 `max_chars` 以内的字符边界
+fn truncate(mut text: String, max_chars: usize) -> String {
+    if text.len() > max_chars {
+        let end = (0..=max_chars).rev().find(|&i| text.is_char_boundary(i)).unwrap_or(0);
+        text.truncate(end);
+        text.push_str("\n...（结果过长，已截断）");
+    }
+    text
+}
+
+/// 查询结果格式化为文本，每行一条记录，列之间用 ` | ` 分隔
+pub fn format_rows(result: &QueryResult, limit: usize) -> String {
+    let mut text = result.columns.join(" | ");
+    text.push('\n');
+    for row in &result.rows {
+        let cells: Vec<&str> = row.iter().map(|cell| cell.as_deref().unwrap_or("NULL")).collect();
+        text.push_str(&cells.join(" | "));
+        text.push('\n');
+    }
+    if result.rows.len() >= limit {
+        text.push_str(&format!("（只返回了前 {} 行）\n", limit));
+    } else {
+        text.push_str(&format!("（共 {} 行）\n", result.rows.len()));
+    }
+    truncate(text, MAX_RESULT_CHARS)
+}
+
+async fn run_tool(call: &ToolCall, cx: &mut AsyncApp) -> Result<String> {
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+
+    match call.name.as_str() {
+        "list_connections" => {
+            let connections = global_state.list_connections(cx).await?;
+            if connections.is_empty() {
+                return Ok("当前没有打开的数据库连接".to_string());
+            }
+            Ok(connections
+                .iter()
+                .map(|c| {
+                    format!(
+                        "{} | {} | {} | {}",
+                        c.id,
+                        c.name,
+                        c.database_type.as_str(),
+                        c.database.as_deref().unwrap_or("")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        "list_databases" => {
+            let args: ConnectionArgs = parse_args(call)?;
+            let databases = global_state.list_databases(cx, args.connection_id).await?;
+            Ok(truncate(databases.join("\n"), MAX_RESULT_CHARS))
+        }
+        "list_tables" => {
+            let args: TablesArgs = parse_args(call)?;
+            let mut tables = global_state.list_tables(cx, args.connection_id, args.database).await?;
+            if let Some(schema) = &args.schema {
+                tables.retain(|t| t.schema.as_ref().map_or(true, |s| s == schema));
+            }
+            let lines: Vec<String> = tables
+                .iter()
+                .map(|t| {
+                    let mut line = match &t.schema {
+                        Some(schema) => format!("{}.{}", schema, t.name),
+                        None => t.name.clone(),
+                    };
+                    if let Some(comment) = t.comment.as_deref().filter(|c| !c.is_empty()) {
+                        line.push_str(&format!(" -- {}", comment));
+                    }
+                    line
+                })
+                .collect();
+            Ok(truncate(lines.join("\n"), MAX_RESULT_CHARS))
+        }
+        "describe_table" => {
+            let args: DescribeArgs = parse_args(call)?;
+            let tables = global_state
+                .describe_tables(cx, args.connection_id, args.database, args.schema, vec![args.table.clone()])
+                .await?;
+            if tables.is_empty() {
+                return Err(anyhow!("表 {} 不存在", args.table));
+            }
+            let mut text = build_schema_prompt(&tables, MAX_RESULT_CHARS);
+            for table in &tables {
+                for column in &table.columns {
+                    if let Some(comment) = column.comment.as_deref().filter(|c| !c.is_empty()) {
+                        text.push_str(&format!("{}: {}\n", column.name, comment));
+                    }
+                }
+            }
+            Ok(truncate(text, MAX_RESULT_CHARS))
+        }
+        "run_select" => {
+            let args: SelectArgs = parse_args(call)?;
+            let limit = args.limit.unwrap_or(DEFAULT_SELECT_LIMIT).clamp(1, MAX_SELECT_LIMIT);
+            let result = global_state
+                .run_read_only_query(cx, args.connection_id, Some(args.database), args.sql, limit)
+                .await?;
+            Ok(format_rows(&result, limit))
+        }
+        name => Err(anyhow!("未知的工具：{}", name)),
+    }
+}
+
+/// 执行工具调用；失败时把错误作为结果返回给模型，让它修正参数或换一种方式
+pub async fn execute_tool(call: &ToolCall, cx: &mut AsyncApp) -> String {
+    match run_tool(call, cx).await {
+        Ok(result) => result,
+        Err(e) => format!("错误：{}", e),
+    }
+}
+
+/// 带工具的对话：模型请求调用工具时执行并回传结果，直到模型给出最终回答。
+/// 每次调用工具前调用 `on_tool_call`，用于在界面上展示进度
+pub async fn chat_with_tools(
+    provider_id: i64,
+    request: ChatRequest,
+    mut on_tool_call: impl FnMut(&ToolCall, &mut AsyncApp),
+    cx: &mut AsyncApp,
+) -> Result<String> {
+    let (storage, provider_state) = cx.update(|cx| {
+        (
+            cx.global::<GlobalStorageState>().storage.clone(),
+            cx.global::<GlobalProviderState>().clone(),
+        )
+    })?;
+
+    let provider = Tokio::spawn_result(cx, async move {
+        let repo = storage.get::<ProviderRepository>().await
+            .ok_or_else(|| anyhow!("ProviderRepository not found"))?;
+        let config = repo.get(provider_id).await?
+            .ok_or_else(|| anyhow!("Provider not found: {}", provider_id))?;
+        provider_state.manager().get_provider(config).await
+    })?.await?;
+
+    let mut request = request.with_tools(tool_definitions());
+    request.stream = false;
+
+    for _ in 0..MAX_TOOL_ROUNDS {
+        let round = request.clone();
+        let response = Tokio::spawn_result(cx, {
+            let provider = provider.clone();
+            async move { provider.chat(round).await }
+        })?.await?;
+
+        if response.tool_calls.is_empty() {
+            return Ok(response.content);
+        }
+
+        request.messages.push(ChatMessage::assistant_tool_calls(response.content, response.tool_calls.clone()));
+        for call in &response.tool_calls {
+            on_tool_call(call, cx);
+            let result = execute_tool(call, cx).await;
+            request.messages.push(ChatMessage::tool_result(call.id.clone(), result));
+        }
+    }
+
+    Err(anyhow!("工具调用超过 {} 轮仍未得到回答", MAX_TOOL_ROUNDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_call() {
+        let call = ToolCall {
+            id: "call_1".to_string(),
+            name: "run_select".to_string(),
+            arguments: json!({ "sql": "SELECT COUNT(*)\n  FROM orders" }),
+        };
+        assert_eq!(describe_call(&call), "执行查询 `SELECT COUNT(*) FROM orders`");
+    }
+
+    #[test]
+    fn test_format_rows_marks_truncated_results() {
+        let result = QueryResult {
+            sql: "SELECT id, note FROM orders".to_string(),
+            columns: vec!["id".to_string(), "note".to_string()],
+            rows: vec![vec![Some("1".to_string()), None], vec![Some("2".to_string()), Some("加急".to_string())]],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        };
+        assert_eq!(format_rows(&result, 10), "id | note\n1 | NULL\n2 | 加急\n（共 2 行）\n");
+        assert!(format_rows(&result, 2).ends_with("（只返回了前 2 行）\n"));
+    }
+
+    #[test]
+    fn test_tool_definitions_have_object_schemas() {
+        for tool in tool_definitions() {
+            assert_eq!(tool.parameters["type"], "object", "{}", tool.name);
+        }
+    }
+}
@@ -87,6 +87,7 @@ impl DatabaseViewPlugin for ClickHouseDatabaseViewPlugin {
             supports_dump_database: true,
             supports_create_schema: false,
             supports_delete_schema: false,
+            supports_maintenance: false,
             supports_create_view: true,
             supports_edit_view: true,
             supports_sequences: false,
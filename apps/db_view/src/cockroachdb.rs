@@ -0,0 +1,2 @@
+pub mod cockroachdb_view_plugin;
+pub mod ranges_view;
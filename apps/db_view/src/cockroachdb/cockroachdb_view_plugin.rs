@@ -0,0 +1,100 @@
+use gpui::{App, AppContext, Entity, Window};
+use one_core::storage::DatabaseType;
+use crate::common::{DatabaseEditorView, DatabaseNameForm, SchemaEditorView};
+use crate::database_view_plugin::{DatabaseViewPlugin, TableDesignerCapabilities, NodeMenuCapabilities};
+use crate::common::db_connection_form::{DbConnectionForm, DbFormConfig};
+use crate::postgresql::schema_form::PostgreSqlSchemaForm;
+
+pub struct CockroachDbDatabaseViewPlugin;
+
+impl CockroachDbDatabaseViewPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DatabaseViewPlugin for CockroachDbDatabaseViewPlugin {
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::CockroachDB
+    }
+
+    fn create_connection_form(&self, window: &mut Window, cx: &mut App) -> Entity<DbConnectionForm> {
+        cx.new(|cx| DbConnectionForm::new(DbFormConfig::cockroachdb(), window, cx))
+    }
+
+    fn create_database_editor_view(
+        &self,
+        _connection_id: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| DatabaseNameForm::new(window, cx));
+            DatabaseEditorView::new(form, DatabaseType::CockroachDB, false, window, cx)
+        })
+    }
+
+    fn create_database_editor_view_for_edit(
+        &self,
+        _connection_id: String,
+        database_name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| DatabaseNameForm::new_for_edit(&database_name, window, cx));
+            DatabaseEditorView::new(form, DatabaseType::CockroachDB, true, window, cx)
+        })
+    }
+
+    fn create_schema_editor_view(
+        &self,
+        _connection_id: String,
+        _database_name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Entity<SchemaEditorView>> {
+        Some(cx.new(|cx| {
+            let form = cx.new(|cx| PostgreSqlSchemaForm::new(window, cx));
+            SchemaEditorView::new(form, DatabaseType::CockroachDB, window, cx)
+        }))
+    }
+
+    fn get_table_designer_capabilities(&self) -> TableDesignerCapabilities {
+        // 没有表空间和表级字符集，列级 COLLATE 仍可用
+        TableDesignerCapabilities {
+            supports_engine: false,
+            supports_charset: false,
+            supports_collation: true,
+            supports_auto_increment: false,
+            supports_tablespace: false,
+        }
+    }
+
+    fn get_engines(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn get_node_menu_capabilities(&self) -> NodeMenuCapabilities {
+        NodeMenuCapabilities {
+            supports_truncate_table: true,
+            supports_rename_table: true,
+            supports_table_import: true,
+            supports_table_export: true,
+            supports_create_database: true,
+            supports_edit_database: false,
+            supports_drop_database: true,
+            supports_dump_database: true,
+            supports_create_schema: true,
+            supports_delete_schema: true,
+            // 维护面板展示 range 分布和 zone 配置
+            supports_maintenance: true,
+            supports_create_view: true,
+            supports_edit_view: true,
+            supports_sequences: true,
+            supports_triggers: false,
+            supports_stored_procedures: false,
+            supports_functions: true,
+        }
+    }
+}
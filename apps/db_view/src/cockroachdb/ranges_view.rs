@@ -0,0 +1,284 @@
+//! CockroachDB 集群分布面板 - 查看数据库或表的 range 分布、leaseholder 和 zone 配置
+
+use std::any::Any;
+
+use db::cockroachdb::CockroachPlugin;
+use db::{GlobalDbState, QueryResult, SqlResult};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    v_flex, ActiveTheme, Icon, IconName, Sizable,
+};
+use one_core::tab_container::{TabContent, TabContentType};
+
+/// 面板可执行的查看操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClusterInspection {
+    Ranges,
+    ZoneConfiguration,
+}
+
+impl ClusterInspection {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClusterInspection::Ranges => "Range 分布",
+            ClusterInspection::ZoneConfiguration => "Zone 配置",
+        }
+    }
+
+    /// 表名为空时查看整个数据库，支持 schema.table 写法，未写 schema 时为 public
+    pub fn build_sql(&self, plugin: &CockroachPlugin, database: &str, table: &str) -> String {
+        let table = table.trim();
+        if table.is_empty() {
+            return match self {
+                ClusterInspection::Ranges => plugin.build_show_database_ranges_sql(database),
+                ClusterInspection::ZoneConfiguration => plugin.build_show_database_zone_configuration_sql(database),
+            };
+        }
+        let (schema, table) = match table.split_once('.') {
+            Some((schema, table)) => (Some(schema.trim()), table.trim()),
+            None => (None, table),
+        };
+        match self {
+            ClusterInspection::Ranges => plugin.build_show_ranges_sql(schema, table),
+            ClusterInspection::ZoneConfiguration => plugin.build_show_zone_configuration_sql(schema, table),
+        }
+    }
+}
+
+/// 最近一次查看的结果
+struct InspectionOutput {
+    sql: String,
+    result: Result<QueryResult, String>,
+}
+
+pub struct CockroachRangesView {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    database: String,
+    table_input: Entity<InputState>,
+    running: Option<ClusterInspection>,
+    output: Option<InspectionOutput>,
+}
+
+impl CockroachRangesView {
+    pub fn new(connection_id: String, database: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let table_input = cx.new(|cx| InputState::new(window, cx).placeholder("表名，如 public.orders（留空查看整个数据库）"));
+
+        let mut view = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            database,
+            table_input,
+            running: None,
+            output: None,
+        };
+        view.run(ClusterInspection::Ranges, cx);
+        view
+    }
+
+    fn run(&mut self, inspection: ClusterInspection, cx: &mut Context<Self>) {
+        if self.running.is_some() {
+            return;
+        }
+        let sql = inspection.build_sql(&CockroachPlugin::new(), &self.database, &self.table_input.read(cx).text().to_string());
+        self.running = Some(inspection);
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state
+                .execute_script(cx, connection_id, sql.clone(), Some(database), None)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|results| match results.into_iter().next() {
+                    Some(SqlResult::Query(query)) => Ok(query),
+                    Some(SqlResult::Error(err)) => Err(err.message),
+                    _ => Err("语句没有返回结果集".to_string()),
+                });
+            let _ = this.update(cx, |view, cx| {
+                view.running = None;
+                view.output = Some(InspectionOutput { sql, result });
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn render_output(&self, cx: &mut Context<Self>) -> AnyElement {
+        if let Some(inspection) = &self.running {
+            return div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child(format!("正在读取{}…", inspection.label()))
+                .into_any_element();
+        }
+        let Some(output) = &self.output else {
+            return div().into_any_element();
+        };
+
+        let query = match &output.result {
+            Ok(query) => query,
+            Err(e) => {
+                return v_flex()
+                    .gap_1()
+                    .child(div().text_xs().font_family("monospace").text_color(cx.theme().muted_foreground).child(output.sql.clone()))
+                    .child(div().text_sm().text_color(cx.theme().danger).child(e.clone()))
+                    .into_any_element();
+            }
+        };
+
+        let header = h_flex()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .children(query.columns.iter().map(|column| {
+                div().w(px(160.)).flex_shrink_0().px_2().py_1().text_xs().font_weight(gpui::FontWeight::SEMIBOLD).child(column.clone())
+            }));
+        let rows = query.rows.iter().map(|row| {
+            h_flex()
+                .items_start()
+                .border_b_1()
+                .border_color(cx.theme().border)
+                .children(row.iter().map(|value| {
+                    div()
+                        .w(px(160.))
+                        .flex_shrink_0()
+                        .px_2()
+                        .py_1()
+                        .text_xs()
+                        .font_family("monospace")
+                        .child(value.clone().unwrap_or_else(|| "NULL".to_string()))
+                }))
+        });
+
+        v_flex()
+            .gap_1()
+            .flex_1()
+            .min_h_0()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("{}（{} 行，耗时 {} ms）", output.sql, query.rows.len(), query.elapsed_ms)),
+            )
+            .child(
+                div()
+                    .id("cockroach-ranges-output")
+                    .flex_1()
+                    .overflow_scroll()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .child(v_flex().child(header).children(rows)),
+            )
+            .into_any_element()
+    }
+}
+
+impl Focusable for CockroachRangesView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for CockroachRangesView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.running;
+
+        let mut actions = h_flex().gap_2().items_center().child(Input::new(&self.table_input).w(px(320.)).small());
+        for inspection in [ClusterInspection::Ranges, ClusterInspection::ZoneConfiguration] {
+            actions = actions.child(
+                Button::new(SharedString::from(format!("cockroach-{}", inspection.label())))
+                    .small()
+                    .when(inspection == ClusterInspection::Ranges, |button| button.primary())
+                    .label(inspection.label())
+                    .loading(running == Some(inspection))
+                    .disabled(running.is_some())
+                    .on_click(cx.listener(move |view, _, _, cx| view.run(inspection, cx))),
+            );
+        }
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_4()
+            .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("集群分布"))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Range 分布包含副本位置和 leaseholder，需要 VIEWCLUSTERMETADATA 权限；Zone 配置未单独设置时显示继承的配置"),
+            )
+            .child(actions)
+            .child(self.render_output(cx))
+    }
+}
+
+pub struct CockroachRangesTabContent {
+    title: SharedString,
+    view: Entity<CockroachRangesView>,
+}
+
+impl CockroachRangesTabContent {
+    pub fn new(connection_id: String, database: String, window: &mut Window, cx: &mut App) -> Self {
+        let title = format!("集群分布: {}", database);
+        let view = cx.new(|cx| CockroachRangesView::new(connection_id, database, window, cx));
+        Self {
+            title: title.into(),
+            view,
+        }
+    }
+}
+
+impl TabContent for CockroachRangesTabContent {
+    fn title(&self) -> SharedString {
+        self.title.clone()
+    }
+
+    fn icon(&self) -> Option<Icon> {
+        Some(IconName::Database.color())
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn render_content(&self, _window: &mut Window, _cx: &mut App) -> AnyElement {
+        self.view.clone().into_any_element()
+    }
+
+    fn content_type(&self) -> TabContentType {
+        TabContentType::Custom("CockroachRanges".to_string())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_sql_scope() {
+        let plugin = CockroachPlugin::new();
+        assert_eq!(
+            ClusterInspection::Ranges.build_sql(&plugin, "shop", "  "),
+            "SHOW RANGES FROM DATABASE \"shop\" WITH TABLES"
+        );
+        assert_eq!(
+            ClusterInspection::Ranges.build_sql(&plugin, "shop", "orders"),
+            "SHOW RANGES FROM TABLE \"public\".\"orders\" WITH DETAILS"
+        );
+        assert_eq!(
+            ClusterInspection::ZoneConfiguration.build_sql(&plugin, "shop", "sales.orders"),
+            "SHOW ZONE CONFIGURATION FROM TABLE \"sales\".\"orders\""
+        );
+    }
+}
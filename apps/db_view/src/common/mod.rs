@@ -1,8 +1,10 @@
 mod database_editor_view;
+mod database_name_form;
 mod schema_editor_view;
 pub mod db_connection_form;
 
 pub use database_editor_view::DatabaseEditorView;
+pub use database_name_form::DatabaseNameForm;
 pub use schema_editor_view::SchemaEditorView;
 
 use db::plugin::DatabaseOperationRequest;
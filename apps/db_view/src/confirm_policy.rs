@@ -0,0 +1,356 @@
+//! 危险操作确认策略 - 根据连接环境决定删除、清空、DDL 等操作的确认方式
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use gpui::{App, AppContext, Global, ParentElement, SharedString, Styled, Window};
+use gpui_component::{
+    checkbox::Checkbox,
+    dialog::DialogButtonProps,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex, WindowExt,
+};
+use one_core::storage::ConnectionEnvironment;
+
+/// 需要确认的危险操作
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DestructiveAction {
+    DeleteConnection,
+    DropDatabase,
+    DropSchema,
+    DropTable,
+    TruncateTable,
+    DropView,
+    DropEvent,
+    DeleteQuery,
+    ExecuteDdl,
+    /// 在 SQL 编辑器中执行 INSERT/UPDATE/DELETE
+    ExecuteDml,
+}
+
+impl DestructiveAction {
+    /// 是否只影响本地保存的数据（连接配置、命名查询），不会修改数据库
+    pub fn is_local(&self) -> bool {
+        matches!(self, DestructiveAction::DeleteConnection | DestructiveAction::DeleteQuery)
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            DestructiveAction::TruncateTable => "确认清空",
+            DestructiveAction::ExecuteDdl | DestructiveAction::ExecuteDml => "确认执行",
+            _ => "确认删除",
+        }
+    }
+}
+
+/// 确认方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConfirmLevel {
+    /// 直接执行，不弹出确认
+    None,
+    /// 弹出确认对话框
+    #[default]
+    Confirm,
+    /// 需要输入对象名称才能确认
+    TypeName,
+}
+
+impl ConfirmLevel {
+    pub fn all() -> &'static [ConfirmLevel] {
+        &[ConfirmLevel::None, ConfirmLevel::Confirm, ConfirmLevel::TypeName]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfirmLevel::None => "none",
+            ConfirmLevel::Confirm => "confirm",
+            ConfirmLevel::TypeName => "type_name",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "none" => ConfirmLevel::None,
+            "type_name" => ConfirmLevel::TypeName,
+            _ => ConfirmLevel::Confirm,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConfirmLevel::None => "不确认",
+            ConfirmLevel::Confirm => "弹窗确认",
+            ConfirmLevel::TypeName => "输入对象名确认",
+        }
+    }
+}
+
+/// 各环境的确认规则，在设置页中配置
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfirmPolicy {
+    pub development: ConfirmLevel,
+    pub testing: ConfirmLevel,
+    pub production: ConfirmLevel,
+    /// 生产环境执行 DDL 语句前是否需要确认
+    pub confirm_ddl_on_production: bool,
+    /// 生产环境执行 DML 语句前是否需要确认
+    pub confirm_dml_on_production: bool,
+}
+
+impl Default for ConfirmPolicy {
+    fn default() -> Self {
+        Self {
+            development: ConfirmLevel::Confirm,
+            testing: ConfirmLevel::Confirm,
+            production: ConfirmLevel::TypeName,
+            confirm_ddl_on_production: true,
+            confirm_dml_on_production: false,
+        }
+    }
+}
+
+impl Global for ConfirmPolicy {}
+
+impl ConfirmPolicy {
+    pub fn global(cx: &App) -> ConfirmPolicy {
+        cx.try_global::<ConfirmPolicy>().copied().unwrap_or_default()
+    }
+
+    pub fn global_mut(cx: &mut App) -> &mut ConfirmPolicy {
+        cx.default_global::<ConfirmPolicy>()
+    }
+
+    pub fn environment_level(&self, environment: ConnectionEnvironment) -> ConfirmLevel {
+        match environment {
+            ConnectionEnvironment::Development => self.development,
+            ConnectionEnvironment::Testing => self.testing,
+            ConnectionEnvironment::Production => self.production,
+        }
+    }
+
+    pub fn set_environment_level(&mut self, environment: ConnectionEnvironment, level: ConfirmLevel) {
+        match environment {
+            ConnectionEnvironment::Development => self.development = level,
+            ConnectionEnvironment::Testing => self.testing = level,
+            ConnectionEnvironment::Production => self.production = level,
+        }
+    }
+
+    /// 计算某个操作在指定环境下的确认方式
+    pub fn level_for(&self, action: DestructiveAction, environment: ConnectionEnvironment) -> ConfirmLevel {
+        let statement_confirm = match action {
+            DestructiveAction::ExecuteDdl => Some(self.confirm_ddl_on_production),
+            DestructiveAction::ExecuteDml => Some(self.confirm_dml_on_production),
+            _ => None,
+        };
+        if let Some(enabled) = statement_confirm {
+            return if environment == ConnectionEnvironment::Production && enabled {
+                self.production
+            } else {
+                ConfirmLevel::None
+            };
+        }
+
+        let level = self.environment_level(environment);
+        // 本地数据可以重新创建，最多弹窗确认即可
+        if action.is_local() && level == ConfirmLevel::TypeName {
+            return ConfirmLevel::Confirm;
+        }
+        level
+    }
+}
+
+/// 一次危险操作的确认请求
+pub struct ConfirmRequest {
+    pub action: DestructiveAction,
+    pub environment: ConnectionEnvironment,
+    /// 需要输入确认的对象名称
+    pub object_name: String,
+    pub message: String,
+    pub detail: String,
+}
+
+impl ConfirmRequest {
+    pub fn new(
+        action: DestructiveAction,
+        environment: ConnectionEnvironment,
+        object_name: impl Into<String>,
+        message: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            action,
+            environment,
+            object_name: object_name.into(),
+            message: message.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// 按全局策略确认危险操作，确认通过后调用 `on_confirm`
+pub fn confirm_destructive(
+    request: ConfirmRequest,
+    window: &mut Window,
+    cx: &mut App,
+    on_confirm: impl Fn(&mut Window, &mut App) + 'static,
+) {
+    open_confirm(request, None, window, cx, Rc::new(move |_, window, cx| on_confirm(window, cx)));
+}
+
+/// 与 `confirm_destructive` 相同，但对话框中附带一个复选项，勾选状态传给 `on_confirm`。
+/// 策略无需确认时直接使用 `default_checked`。
+pub fn confirm_destructive_with_option(
+    request: ConfirmRequest,
+    option_label: impl Into<SharedString>,
+    default_checked: bool,
+    window: &mut Window,
+    cx: &mut App,
+    on_confirm: impl Fn(bool, &mut Window, &mut App) + 'static,
+) {
+    open_confirm(
+        request,
+        Some((option_label.into(), default_checked)),
+        window,
+        cx,
+        Rc::new(on_confirm),
+    );
+}
+
+fn open_confirm(
+    request: ConfirmRequest,
+    option: Option<(SharedString, bool)>,
+    window: &mut Window,
+    cx: &mut App,
+    on_confirm: Rc<dyn Fn(bool, &mut Window, &mut App)>,
+) {
+    let level = ConfirmPolicy::global(cx).level_for(request.action, request.environment);
+    let option_checked = Rc::new(Cell::new(option.as_ref().is_some_and(|(_, checked)| *checked)));
+    if level == ConfirmLevel::None {
+        on_confirm(option_checked.get(), window, cx);
+        return;
+    }
+
+    let name_input = (level == ConfirmLevel::TypeName).then(|| {
+        cx.new(|cx| InputState::new(window, cx).placeholder(request.object_name.clone()))
+    });
+    let title = if request.environment == ConnectionEnvironment::Production {
+        format!("{}（{}环境）", request.action.title(), request.environment.label())
+    } else {
+        request.action.title().to_string()
+    };
+
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        let on_confirm = on_confirm.clone();
+        let name_input = name_input.clone();
+        let object_name = request.object_name.clone();
+        let option_checked = option_checked.clone();
+
+        let mut content = v_flex()
+            .gap_2()
+            .child(request.message.clone())
+            .child(request.detail.clone());
+        if let Some((label, _)) = &option {
+            let checked = option_checked.clone();
+            content = content.child(
+                Checkbox::new("confirm-option")
+                    .checked(option_checked.get())
+                    .label(label.clone())
+                    .on_click(move |value: &bool, window, _cx| {
+                        checked.set(*value);
+                        window.refresh();
+                    }),
+            );
+        }
+        if let Some(input) = &name_input {
+            content = content
+                .child(format!("请输入 \"{}\" 以确认：", request.object_name))
+                .child(Input::new(input));
+        }
+
+        dialog
+            .title(title.clone())
+            .confirm()
+            .button_props(DialogButtonProps::default().ok_text("确认"))
+            .child(content)
+            .on_ok(move |_, window, cx| {
+                if let Some(input) = &name_input {
+                    if input.read(cx).text().to_string().trim() != object_name {
+                        window.push_notification(
+                            Notification::error("输入的名称不匹配").autohide(true),
+                            cx,
+                        );
+                        return false;
+                    }
+                }
+                on_confirm(option_checked.get(), window, cx);
+                true
+            })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_requires_name_on_production() {
+        let policy = ConfirmPolicy::default();
+        assert_eq!(
+            policy.level_for(DestructiveAction::DropTable, ConnectionEnvironment::Production),
+            ConfirmLevel::TypeName
+        );
+        assert_eq!(
+            policy.level_for(DestructiveAction::DropTable, ConnectionEnvironment::Development),
+            ConfirmLevel::Confirm
+        );
+    }
+
+    #[test]
+    fn test_local_actions_never_require_name() {
+        let policy = ConfirmPolicy::default();
+        assert_eq!(
+            policy.level_for(DestructiveAction::DeleteQuery, ConnectionEnvironment::Production),
+            ConfirmLevel::Confirm
+        );
+    }
+
+    #[test]
+    fn test_ddl_only_confirmed_on_production() {
+        let mut policy = ConfirmPolicy::default();
+        assert_eq!(
+            policy.level_for(DestructiveAction::ExecuteDdl, ConnectionEnvironment::Testing),
+            ConfirmLevel::None
+        );
+        assert_eq!(
+            policy.level_for(DestructiveAction::ExecuteDdl, ConnectionEnvironment::Production),
+            ConfirmLevel::TypeName
+        );
+
+        policy.confirm_ddl_on_production = false;
+        assert_eq!(
+            policy.level_for(DestructiveAction::ExecuteDdl, ConnectionEnvironment::Production),
+            ConfirmLevel::None
+        );
+    }
+
+    #[test]
+    fn test_dml_confirm_is_opt_in() {
+        let mut policy = ConfirmPolicy::default();
+        assert_eq!(
+            policy.level_for(DestructiveAction::ExecuteDml, ConnectionEnvironment::Production),
+            ConfirmLevel::None
+        );
+
+        policy.confirm_dml_on_production = true;
+        assert_eq!(
+            policy.level_for(DestructiveAction::ExecuteDml, ConnectionEnvironment::Production),
+            ConfirmLevel::TypeName
+        );
+        assert_eq!(
+            policy.level_for(DestructiveAction::ExecuteDml, ConnectionEnvironment::Testing),
+            ConfirmLevel::None
+        );
+    }
+}
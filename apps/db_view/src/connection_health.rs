@@ -0,0 +1,213 @@
+//! 连接健康面板 - 汇总所有打开的连接：服务器版本、运行时间、延迟、服务器会话数、连接池占用和最近的错误
+
+use std::time::Duration;
+
+use db::{ConnectionHealth, ConnectionStatus, GlobalDbState};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AsyncApp, Context, FocusHandle, Focusable, Hsla, InteractiveElement, IntoElement, ParentElement, Render, StatefulInteractiveElement, Styled, Task, Timer, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex, v_flex, ActiveTheme, IconName, Sizable,
+};
+
+/// 面板打开期间的自动刷新间隔，每次刷新会在每个连接上执行一次探测查询
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 各列宽度：连接、状态、版本、运行时间、延迟、服务器会话、连接池；最近错误占剩余宽度
+const COLUMN_WIDTHS: [f32; 7] = [160., 70., 180., 90., 70., 80., 70.];
+
+pub struct ConnectionHealthPanel {
+    focus_handle: FocusHandle,
+    connections: Vec<ConnectionHealth>,
+    loading: bool,
+    error: Option<String>,
+    _refresh_task: Task<()>,
+}
+
+impl ConnectionHealthPanel {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let refresh_task = cx.spawn(async move |this, cx: &mut AsyncApp| loop {
+            if this.update(cx, |panel, cx| panel.reload(cx)).is_err() {
+                return;
+            }
+            Timer::after(REFRESH_INTERVAL).await;
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            connections: Vec::new(),
+            loading: false,
+            error: None,
+            _refresh_task: refresh_task,
+        }
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.connection_health(cx).await;
+            let _ = this.update(cx, |panel, cx| {
+                panel.loading = false;
+                match result {
+                    Ok(connections) => {
+                        panel.connections = connections;
+                        panel.error = None;
+                    }
+                    Err(e) => panel.error = Some(format!("读取连接状态失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let titles = ["连接", "状态", "版本", "运行时间", "延迟", "服务器会话", "连接池"];
+        h_flex()
+            .gap_2()
+            .py_1()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .children(titles.iter().zip(COLUMN_WIDTHS).map(|(title, width)| div().w(px(width)).flex_shrink_0().child(*title)))
+            .child(div().flex_1().child("最近错误"))
+    }
+
+    fn render_row(&self, health: &ConnectionHealth, cx: &mut Context<Self>) -> impl IntoElement {
+        let (status_color, status_label) = status_badge(health.status.as_ref(), cx);
+        let unknown = || "-".to_string();
+        let cells: [AnyElement; 7] = [
+            v_flex()
+                .child(div().text_sm().truncate().child(health.name.clone()))
+                .child(div().text_xs().text_color(cx.theme().muted_foreground).child(health.database_type.as_str().to_string()))
+                .into_any_element(),
+            h_flex()
+                .gap_1()
+                .child(div().size(px(6.)).rounded_full().bg(status_color))
+                .child(status_label)
+                .into_any_element(),
+            div().truncate().child(health.server.version.clone().unwrap_or_else(unknown)).into_any_element(),
+            div().child(health.server.uptime.map(format_uptime).unwrap_or_else(unknown)).into_any_element(),
+            div()
+                .child(health.ping.map(|ping| format!("{} ms", ping.as_millis())).unwrap_or_else(unknown))
+                .into_any_element(),
+            div()
+                .child(health.server.active_sessions.map(|count| count.to_string()).unwrap_or_else(unknown))
+                .into_any_element(),
+            div().child(format!("{}/{}", health.pool.in_use, health.pool.total)).into_any_element(),
+        ];
+
+        let errors = v_flex()
+            .flex_1()
+            .min_w_0()
+            .when_some(health.probe_error.clone(), |this, error| {
+                this.child(div().text_color(cx.theme().danger).truncate().child(format!("探测失败：{}", error)))
+            })
+            .when_some(health.last_error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .text_color(cx.theme().muted_foreground)
+                        .truncate()
+                        .child(format!("{} {}", error.at.format("%m-%d %H:%M:%S"), error.message)),
+                )
+            });
+
+        h_flex()
+            .gap_2()
+            .py_1()
+            .items_center()
+            .text_xs()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .children(cells.into_iter().zip(COLUMN_WIDTHS).map(|(cell, width)| div().w(px(width)).flex_shrink_0().min_w_0().child(cell)))
+            .child(errors)
+    }
+}
+
+fn status_badge(status: Option<&ConnectionStatus>, cx: &App) -> (Hsla, String) {
+    match status {
+        Some(ConnectionStatus::Connected) | None => (cx.theme().success, "正常".to_string()),
+        Some(ConnectionStatus::Reconnecting { attempt }) => (cx.theme().warning, format!("重连中({})", attempt)),
+        Some(ConnectionStatus::Failed(_)) => (cx.theme().danger, "失败".to_string()),
+    }
+}
+
+/// 按最大的两个单位显示，如 3天4小时、2小时5分、45秒
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}天{}小时", days, hours)
+    } else if hours > 0 {
+        format!("{}小时{}分", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}分{}秒", minutes, secs % 60)
+    } else {
+        format!("{}秒", secs)
+    }
+}
+
+impl Focusable for ConnectionHealthPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ConnectionHealthPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if let Some(error) = &self.error {
+            div().text_color(cx.theme().danger).child(error.clone()).into_any_element()
+        } else if self.connections.is_empty() {
+            let message = if self.loading { "加载中..." } else { "没有打开的连接" };
+            div().text_color(cx.theme().muted_foreground).child(message).into_any_element()
+        } else {
+            let mut list = v_flex().child(self.render_header(cx));
+            let connections = self.connections.clone();
+            for health in &connections {
+                list = list.child(self.render_row(health, cx));
+            }
+            list.into_any_element()
+        };
+
+        v_flex()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("{} 个打开的连接，每 {} 秒刷新", self.connections.len(), REFRESH_INTERVAL.as_secs())),
+                    )
+                    .child(
+                        Button::new("connection-health-refresh")
+                            .icon(IconName::Refresh)
+                            .ghost()
+                            .small()
+                            .loading(self.loading)
+                            .on_click(cx.listener(|panel, _, _, cx| panel.reload(cx))),
+                    ),
+            )
+            .child(div().id("connection-health-list").max_h(px(480.0)).overflow_y_scroll().child(content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::from_secs(45)), "45秒");
+        assert_eq!(format_uptime(Duration::from_secs(125)), "2分5秒");
+        assert_eq!(format_uptime(Duration::from_secs(2 * 3600 + 5 * 60 + 9)), "2小时5分");
+        assert_eq!(format_uptime(Duration::from_secs(3 * 86400 + 4 * 3600 + 59)), "3天4小时");
+    }
+}
@@ -0,0 +1,35 @@
+//! 连接颜色标签 - 按连接 ID 记录颜色，供连接树、标签页标题和编辑器边框在渲染时读取
+
+use std::collections::HashMap;
+
+use gpui::{App, Global, Hsla};
+use one_core::storage::{ConnectionColor, ConnectionEnvironment, StoredConnection};
+
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionTags {
+    colors: HashMap<String, ConnectionColor>,
+}
+
+impl Global for ConnectionTags {}
+
+impl ConnectionTags {
+    /// 记录连接的颜色；未设置颜色时按环境取默认颜色（生产环境为红色）
+    pub fn register(connection: &StoredConnection, environment: ConnectionEnvironment, cx: &mut App) {
+        let id = connection.id.unwrap_or(0).to_string();
+        let tags = cx.default_global::<ConnectionTags>();
+        match connection.color.or(environment.default_color()) {
+            Some(color) => tags.colors.insert(id, color),
+            None => tags.colors.remove(&id),
+        };
+    }
+
+    pub fn color(connection_id: &str, cx: &App) -> Option<ConnectionColor> {
+        cx.try_global::<ConnectionTags>()
+            .and_then(|tags| tags.colors.get(connection_id))
+            .copied()
+    }
+
+    pub fn hsla(connection_id: &str, cx: &App) -> Option<Hsla> {
+        Self::color(connection_id, cx).map(|color| color.hsla(cx))
+    }
+}
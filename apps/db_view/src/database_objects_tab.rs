@@ -260,7 +260,7 @@ impl DatabaseObjects {
                             }
                         }
                     }
-                    DbNodeType::Database => {
+                    DbNodeType::Database | DbNodeType::Catalog => {
                         let database = &node_clone.name;
                         if plugin.supports_schema() {
                             plugin.list_schemas_view(&**conn, database).await.ok()
@@ -330,6 +330,14 @@ impl DatabaseObjects {
                         let database = metadata.get("database").unwrap_or(&node_clone.name);
                         plugin.list_triggers_view(&**conn, database).await.ok()
                     }
+                    DbNodeType::EventsFolder => {
+                        let metadata = match node_clone.metadata.as_ref() {
+                            Some(meta) => meta,
+                            None => return Ok(None),
+                        };
+                        let database = metadata.get("database").unwrap_or(&node_clone.name);
+                        plugin.list_events_view(&**conn, database).await.ok()
+                    }
                     DbNodeType::SequencesFolder => {
                         let metadata = match node_clone.metadata.as_ref() {
                             Some(meta) => meta,
@@ -601,10 +609,15 @@ impl DatabaseObjects {
             }
             DbNodeType::FunctionsFolder | DbNodeType::Function |
             DbNodeType::ProceduresFolder | DbNodeType::Procedure |
+            DbNodeType::PackagesFolder | DbNodeType::Package |
+            DbNodeType::EventsFolder | DbNodeType::Event |
             DbNodeType::TriggersFolder | DbNodeType::Trigger |
             DbNodeType::IndexesFolder | DbNodeType::Index |
             DbNodeType::ForeignKeysFolder | DbNodeType::ForeignKey |
             DbNodeType::SequencesFolder | DbNodeType::Sequence |
+            DbNodeType::MaterializedViewsFolder | DbNodeType::MaterializedView |
+            DbNodeType::SynonymsFolder | DbNodeType::Synonym |
+            DbNodeType::LinkedServersFolder | DbNodeType::LinkedServer | DbNodeType::LinkedTable |
             DbNodeType::ChecksFolder | DbNodeType::Check => {
             }
             DbNodeType::QueriesFolder => {
@@ -1,12 +1,15 @@
 use std::any::Any;
 
+use crate::connection_tag::ConnectionTags;
 use crate::database_objects_tab::DatabaseObjectsPanel;
 use crate::db_tree_event::DatabaseEventHandler;
 use crate::db_tree_view::DbTreeView;
+use crate::sql_editor_view::SqlEditorTabContent;
 use db::GlobalDbState;
 use gpui::{div, prelude::FluentBuilder, px, AnyElement, App, AppContext, AsyncApp, Entity, FontWeight, Hsla, IntoElement, ParentElement, SharedString, Styled, Window};
 use gpui_component::{h_flex, resizable::{h_resizable, resizable_panel}, v_flex, ActiveTheme, Icon, IconName, Sizable, Size};
 use one_core::storage::Workspace;
+use uuid::Uuid;
 use one_core::{storage::StoredConnection, tab_container::{TabContainer, TabContent, TabContentType, TabItem}};
 
 // Database connection tab content - using TabContainer architecture
@@ -94,6 +97,47 @@ impl DatabaseTabContent {
         Self::new_with_active_conn(workspace, vec![connection], active_conn_id, window, cx)
     }
 
+    /// 在该连接下打开一个新的查询标签页并填入 SQL（启动参数 / onehub:// 链接）
+    pub fn open_query(&self, connection_id: i64, sql: String, database: Option<String>, window: &mut Window, cx: &mut App) {
+        let Some(conn) = self.connections.iter().find(|c| c.id == Some(connection_id)) else {
+            return;
+        };
+        let Ok(config) = conn.to_db_connection() else {
+            return;
+        };
+
+        let database = database.or(config.database.clone());
+        let sql_editor = SqlEditorTabContent::new_with_config(
+            format!("{} - Query", database.as_deref().unwrap_or(&conn.name)),
+            connection_id.to_string(),
+            config.database_type,
+            None,
+            database,
+            window,
+            cx,
+        );
+        sql_editor.set_sql(sql, window, cx);
+
+        self.tab_container.update(cx, |container, cx| {
+            let tab = TabItem::new(format!("query-launch-{}", Uuid::new_v4()), sql_editor);
+            container.add_and_activate_tab(tab, cx);
+        });
+    }
+
+    pub fn has_connection(&self, connection_id: i64) -> bool {
+        self.connections.iter().any(|c| c.id == Some(connection_id))
+    }
+
+    /// 在当前激活的查询标签页光标处插入 SQL；激活的不是查询标签页时返回 false
+    pub fn insert_into_active_editor(&self, sql: String, window: &mut Window, cx: &mut App) -> bool {
+        let content = self.tab_container.read(cx).active_tab().map(|tab| tab.content().clone());
+        let Some(editor) = content.as_ref().and_then(|c| c.as_any().downcast_ref::<SqlEditorTabContent>()) else {
+            return false;
+        };
+        editor.insert_sql(sql, window, cx);
+        true
+    }
+
     fn render_connection_status(&self, cx: &mut App) -> AnyElement {
         let status_text = self.status_msg.read(cx).clone();
         let is_error = status_text.contains("Failed") || status_text.contains("failed");
@@ -255,6 +299,15 @@ impl TabContent for DatabaseTabContent {
         true
     }
 
+    /// A workspace mixes connections, so only a single-connection tab takes its color
+    fn accent_color(&self, cx: &App) -> Option<Hsla> {
+        if self.workspace.is_some() {
+            return None;
+        }
+        let id = self.connections.first()?.id?;
+        ConnectionTags::hsla(&id.to_string(), cx)
+    }
+
     fn render_content(&self, _window: &mut Window, cx: &mut App) -> AnyElement {
         let is_connected_flag = *self.is_connected.read(cx);
 
@@ -11,6 +11,14 @@ use crate::mssql::mssql_view_plugin::MsSqlDatabaseViewPlugin;
 use crate::oracle::oracle_view_plugin::OracleDatabaseViewPlugin;
 use crate::clickhouse::clickhouse_view_plugin::ClickHouseDatabaseViewPlugin;
 use crate::sqlite::sqlite_view_plugin::SqliteDatabaseViewPlugin;
+use crate::odbc::odbc_view_plugin::OdbcDatabaseViewPlugin;
+use crate::mongodb::mongodb_view_plugin::MongoDbDatabaseViewPlugin;
+use crate::redis::redis_view_plugin::RedisDatabaseViewPlugin;
+use crate::duckdb::duckdb_view_plugin::DuckDbDatabaseViewPlugin;
+use crate::cockroachdb::cockroachdb_view_plugin::CockroachDbDatabaseViewPlugin;
+use crate::tidb::tidb_view_plugin::TiDbDatabaseViewPlugin;
+use crate::trino::trino_view_plugin::TrinoDatabaseViewPlugin;
+use crate::elasticsearch::elasticsearch_view_plugin::ElasticsearchDatabaseViewPlugin;
 
 /// 表设计器 UI 配置能力
 #[derive(Clone, Debug)]
@@ -65,6 +73,8 @@ pub struct NodeMenuCapabilities {
     pub supports_create_schema: bool,
     /// 是否支持删除模式(Schema)
     pub supports_delete_schema: bool,
+    /// 是否支持维护面板（PRAGMA 设置、完整性检查等）
+    pub supports_maintenance: bool,
 
     // === View 节点菜单项 ===
     /// 是否支持新建视图
@@ -96,6 +106,7 @@ impl Default for NodeMenuCapabilities {
             supports_dump_database: false,
             supports_create_schema: false,
             supports_delete_schema: false,
+            supports_maintenance: false,
             supports_create_view: false,
             supports_edit_view: false,
             supports_sequences: false,
@@ -172,11 +183,19 @@ impl DatabaseViewPluginRegistry {
         };
 
         registry.register(MySqlDatabaseViewPlugin::new());
+        registry.register(TiDbDatabaseViewPlugin::new());
         registry.register(PostgreSqlDatabaseViewPlugin::new());
+        registry.register(CockroachDbDatabaseViewPlugin::new());
         registry.register(MsSqlDatabaseViewPlugin::new());
         registry.register(OracleDatabaseViewPlugin::new());
         registry.register(ClickHouseDatabaseViewPlugin::new());
         registry.register(SqliteDatabaseViewPlugin::new());
+        registry.register(MongoDbDatabaseViewPlugin::new());
+        registry.register(RedisDatabaseViewPlugin::new());
+        registry.register(DuckDbDatabaseViewPlugin::new());
+        registry.register(TrinoDatabaseViewPlugin::new());
+        registry.register(ElasticsearchDatabaseViewPlugin::new());
+        registry.register(OdbcDatabaseViewPlugin::new());
 
         registry
     }
@@ -3,7 +3,7 @@
 
 // 2. 外部 crate 导入（按字母顺序）
 use db::{DbNode, DbNodeType, GlobalDbState, SqlResult};
-use gpui::{div, px, App, AppContext, AsyncApp, Context, Entity, ParentElement, PathPromptOptions, Styled, Subscription, Window};
+use gpui::{div, px, App, AppContext, AsyncApp, ClipboardItem, Context, Entity, ParentElement, PathPromptOptions, Styled, Subscription, Window};
 use tracing::log::{error, warn};
 use gpui_component::{
     h_flex, v_flex, WindowExt,
@@ -18,11 +18,19 @@ use gpui_component::dialog::DialogButtonProps;
 use one_core::storage::query_model::Query;
 // 3. 当前 crate 导入（按模块分组）
 use crate::{
+    ai_test_data::{GeneratedTestData, TestDataRequest},
+    cockroachdb::ranges_view::CockroachRangesTabContent,
+    confirm_policy::{confirm_destructive, confirm_destructive_with_option, ConfirmRequest, DestructiveAction},
     database_objects_tab::DatabaseObjectsPanel,
     database_view_plugin::DatabaseViewPluginRegistry,
     db_tree_view::{DbTreeView, DbTreeViewEvent},
+    extension_browser::ExtensionBrowser,
+    session_variables::SessionVariablesTabContent,
     sql_editor_view::SqlEditorTabContent,
+    sqlite::maintenance_view::SqliteMaintenanceTabContent,
     table_designer::{TableDesignerConfig, TableDesignerTabContent},
+    tidb::cluster_view::TiDbClusterTabContent,
+    trash_browser::TrashBrowser,
 };
 
 // Event handler for database tree view events
@@ -67,7 +75,7 @@ impl DatabaseEventHandler {
 
     /// 从节点获取数据库名的辅助方法
     fn get_database_from_node(node: &DbNode) -> String {
-        if node.node_type == DbNodeType::Database {
+        if node.node_type.is_database_level() {
             node.name.clone()
         } else if let Some(metadata) = &node.metadata {
             metadata.get("database").cloned().unwrap_or_else(|| {
@@ -175,6 +183,16 @@ impl DatabaseEventHandler {
                         Self::handle_delete_connection(node, tree_view.clone(), window, cx);
                     }
                 }
+                DbTreeViewEvent::DuplicateConnection { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_duplicate_connection(node, cx);
+                    }
+                }
+                DbTreeViewEvent::OpenSessionVariables { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_open_session_variables(node, tab_container.clone(), window, cx);
+                    }
+                }
                 DbTreeViewEvent::CreateDatabase { node_id } => {
                     if let Some(node) = get_node(&node_id, cx) {
                         Self::handle_create_database(node, global_state, tree_view.clone(), window, cx);
@@ -250,6 +268,91 @@ impl DatabaseEventHandler {
                         Self::handle_dump_sql_file(node, *mode, global_state, window, cx);
                     }
                 }
+                DbTreeViewEvent::OpenTrash { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_open_trash(node, tree_view.clone(), window, cx);
+                    }
+                }
+                DbTreeViewEvent::OpenExtensions { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_open_extensions(node, tree_view.clone(), window, cx);
+                    }
+                }
+                DbTreeViewEvent::OpenMaintenance { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_open_maintenance(node, tab_container.clone(), window, cx);
+                    }
+                }
+                DbTreeViewEvent::ViewTableDdl { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_view_table_ddl(node, global_state, window, cx);
+                    }
+                }
+                DbTreeViewEvent::GenerateSchemaDoc { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_generate_schema_doc(node, window, cx);
+                    }
+                }
+                DbTreeViewEvent::GenerateTestData { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_generate_test_data(node, window, cx);
+                    }
+                }
+                DbTreeViewEvent::AnonymizeTable { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_anonymize_table(node, window, cx);
+                    }
+                }
+                DbTreeViewEvent::ViewPackageSource { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_view_package_source(node, global_state, window, cx);
+                    }
+                }
+                DbTreeViewEvent::CompilePackage { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_compile_package(node, global_state, tree_view.clone(), window, cx);
+                    }
+                }
+                DbTreeViewEvent::ExplainRoutine { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_explain_routine(node, window, cx);
+                    }
+                }
+                DbTreeViewEvent::ViewMaterializedViewDefinition { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_view_materialized_view_definition(node, global_state, window, cx);
+                    }
+                }
+                DbTreeViewEvent::RefreshMaterializedView { node_id, concurrently } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_refresh_materialized_view(node, *concurrently, global_state, tree_view.clone(), window, cx);
+                    }
+                }
+                DbTreeViewEvent::OpenObjectQuery { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_open_object_query(node, tab_container, window, cx);
+                    }
+                }
+                DbTreeViewEvent::EditEvent { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_edit_event(node, global_state, tab_container, window, cx);
+                    }
+                }
+                DbTreeViewEvent::CreateEvent { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_create_event(node, global_state, tab_container, window, cx);
+                    }
+                }
+                DbTreeViewEvent::SetEventEnabled { node_id, enabled } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_set_event_enabled(node, *enabled, global_state, tree_view.clone(), window, cx);
+                    }
+                }
+                DbTreeViewEvent::DropEvent { node_id } => {
+                    if let Some(node) = get_node(&node_id, cx) {
+                        Self::handle_drop_event(node, global_state, tree_view.clone(), window, cx);
+                    }
+                }
             }
         });
 
@@ -426,6 +529,7 @@ impl DatabaseEventHandler {
         window: &mut Window,
         cx: &mut App,
     ) {
+        use crate::redis::key_view::RedisKeyTabContent;
         use crate::table_data_tab::TableDataTabContent;
         use tracing::info;
 
@@ -478,6 +582,17 @@ impl DatabaseEventHandler {
                                 container.activate_or_add_tab_lazy(
                                     tab_id_for_lazy.clone(),
                                     move |window, cx| {
+                                        // Redis 的"表"是键，用键值视图代替表格
+                                        if database_type == one_core::storage::DatabaseType::Redis {
+                                            let key_view = RedisKeyTabContent::new(
+                                                config_id_clone.clone(),
+                                                database_clone.clone(),
+                                                table_clone.clone(),
+                                                window,
+                                                cx,
+                                            );
+                                            return TabItem::new(tab_id_clone.clone(), key_view);
+                                        }
                                         let table_data = TableDataTabContent::new(
                                             database_clone.clone(),
                                             schema_clone.clone(),
@@ -690,6 +805,10 @@ impl DatabaseEventHandler {
                     let config_id = config.id;
                     let db_for_view = db_string.clone();
                     let table_for_view = table_string.clone();
+                    let supports_async_insert = global_state
+                        .get_plugin(&config.database_type)
+                        .map(|plugin| plugin.supports_async_insert())
+                        .unwrap_or(false);
 
                     let _ = cx.update(|cx| {
                         if let Some(window_id) = cx.active_window() {
@@ -701,6 +820,9 @@ impl DatabaseEventHandler {
                                     window,
                                     cx,
                                 );
+                                import_view.update(cx, |view, cx| {
+                                    view.set_supports_async_insert(supports_async_insert, cx);
+                                });
 
                                 window.open_dialog(cx, move |dialog, _window, _cx| {
                                     dialog
@@ -823,6 +945,10 @@ impl DatabaseEventHandler {
                 let config_id = config.id;
                 let database_for_view = database_string.clone();
                 let table_name_for_view = table_name_option.clone();
+                let native_formats = global_state
+                    .get_plugin(&config.database_type)
+                    .map(|plugin| plugin.native_export_formats())
+                    .unwrap_or_default();
 
                 Self::with_window(cx, move |window, cx| {
                     let export_view = DataExportView::new(
@@ -832,6 +958,9 @@ impl DatabaseEventHandler {
                         cx,
                     );
 
+                    export_view.update(cx, |view, cx| {
+                        view.set_native_formats(native_formats, cx);
+                    });
                     if let Some(table) = table_name_for_view.clone() {
                         export_view.update(cx, |view, cx| {
                             view.tables.update(cx, |state, cx| {
@@ -928,63 +1057,91 @@ impl DatabaseEventHandler {
         let connection_id = node.connection_id.clone();
         let connection_name = node.name.clone();
         let storage_manager = cx.global::<GlobalStorageState>().storage.clone();
-
-        window.open_dialog(cx, move |dialog, _window, _cx| {
-            let conn_id = connection_id.clone();
-            let conn_name = connection_name.clone();
-            let storage = storage_manager.clone();
-            let tree = tree_view.clone();
-
-            dialog
-                .title("确认删除")
-                .confirm()
-                .child(
-                    v_flex()
-                        .gap_2()
-                        .child(format!("确定要删除连接 \"{}\" 吗？", conn_name))
-                        .child("此操作不可恢复。")
-                )
-                .on_ok(move |_, _, cx| {
-                    let conn_id = conn_id.clone();
-                    let storage = storage.clone();
-                    let tree = tree.clone();
-                    cx.spawn(async move |cx| {
-                        match conn_id.parse::<i64>() {
-                            Ok(id) => {
-                                if let Some(conn_repo_arc) = storage.get::<ConnectionRepository>().await {
-                                    let conn_repo = (*conn_repo_arc).clone();
-                                    match conn_repo.delete(id).await {
-                                        Ok(_) => {
-                                            // 刷新树
-                                            let _ = cx.update(|cx| {
-                                                tree.update(cx, |tree, cx| {
-                                                    tree.refresh_tree(conn_id.clone(), cx);
-                                                });
-                                                Self::show_success_async(cx, "连接已成功删除");
-                                            });
-                                        }
-                                        Err(e) => {
-                                            let _ = cx.update(|cx| {
-                                                Self::show_error_async(cx, format!("删除连接失败: {}", e));
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+
+        confirm_destructive(
+            ConfirmRequest::new(
+                DestructiveAction::DeleteConnection,
+                environment,
+                connection_name.clone(),
+                format!("确定要删除连接 \"{}\" 吗？", connection_name),
+                "此操作不可恢复。",
+            ),
+            window,
+            cx,
+            move |_window, cx| {
+                let conn_id = connection_id.clone();
+                let storage = storage_manager.clone();
+                let tree = tree_view.clone();
+                cx.spawn(async move |cx| {
+                    match conn_id.parse::<i64>() {
+                        Ok(id) => {
+                            if let Some(conn_repo_arc) = storage.get::<ConnectionRepository>().await {
+                                let conn_repo = (*conn_repo_arc).clone();
+                                match conn_repo.delete(id).await {
+                                    Ok(_) => {
+                                        // 刷新树
+                                        let _ = cx.update(|cx| {
+                                            tree.update(cx, |tree, cx| {
+                                                tree.refresh_tree(conn_id.clone(), cx);
                                             });
-                                        }
+                                            Self::show_success_async(cx, "连接已成功删除");
+                                        });
+                                    }
+                                    Err(e) => {
+                                        let _ = cx.update(|cx| {
+                                            Self::show_error_async(cx, format!("删除连接失败: {}", e));
+                                        });
                                     }
-                                } else {
-                                    let _ = cx.update(|cx| {
-                                        Self::show_error_async(cx, "删除连接失败：无法获取存储库");
-                                    });
                                 }
-                            }
-                            Err(e) => {
+                            } else {
                                 let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, format!("删除连接失败：无效的连接ID {}", e));
+                                    Self::show_error_async(cx, "删除连接失败：无法获取存储库");
                                 });
                             }
                         }
-                    }).detach();
-                    true
-                })
-        });
+                        Err(e) => {
+                            let _ = cx.update(|cx| {
+                                Self::show_error_async(cx, format!("删除连接失败：无效的连接ID {}", e));
+                            });
+                        }
+                    }
+                }).detach();
+            },
+        );
+    }
+
+    /// 处理复制连接事件：读取保存的连接，把副本交给主页打开编辑器，保存前不会写入存储
+    fn handle_duplicate_connection(node: DbNode, cx: &mut App) {
+        use one_core::storage::traits::Repository;
+        use one_core::storage::{ConnectionRepository, GlobalStorageState, PendingConnectionEdits};
+
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let connection_id = node.connection_id.clone();
+        cx.spawn(async move |cx| {
+            let id = match connection_id.parse::<i64>() {
+                Ok(id) => id,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("复制连接失败：无效的连接ID {}", e)));
+                    return;
+                }
+            };
+            let Some(conn_repo) = storage.get::<ConnectionRepository>().await else {
+                let _ = cx.update(|cx| Self::show_error_async(cx, "复制连接失败：无法获取存储库"));
+                return;
+            };
+            match conn_repo.get(id).await {
+                Ok(Some(connection)) => {
+                    let _ = cx.update(|cx| PendingConnectionEdits::push(connection.duplicate(), cx));
+                }
+                Ok(None) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, "复制连接失败：连接不存在"));
+                }
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("复制连接失败: {}", e)));
+                }
+            }
+        }).detach();
     }
 
     /// 处理新建数据库事件
@@ -1288,53 +1445,47 @@ impl DatabaseEventHandler {
     ) {
         let connection_id = node.connection_id.clone();
         let database_name = node.name.clone();
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
-            let conn_id = connection_id.clone();
-            let db_name = database_name.clone();
-            let state = global_state.clone();
-            let db_name_display = database_name.clone();
-            let tree = tree_view.clone();
-
-            dialog
-                .title("确认删除")
-                .confirm()
-                .child(
-                    v_flex()
-                        .gap_2()
-                        .child(format!("确定要删除数据库 \"{}\" 吗？", db_name_display))
-                        .child("此操作将删除数据库中的所有数据，不可恢复！")
-                )
-                .on_ok(move |_, _, cx| {
-                    let conn_id = conn_id.clone();
-                    let db_name = db_name.clone();
-                    let db_name_log = db_name.clone();
-                    let db_name_for_remove = db_name.clone();
-                    let tree = tree.clone();
-                    let state = state.clone();
-
-                    cx.spawn(async move |cx: &mut AsyncApp| {
-                        let result = state.drop_database(cx, conn_id.clone(), db_name.clone()).await;
-                        match result {
-                            Ok(_) => {
-                                // 直接移除数据库节点，不刷新整个连接
-                                let _ = cx.update(|cx| {
-                                    tree.update(cx, |tree, cx| {
-                                        tree.remove_database_node(&conn_id, &db_name_for_remove, cx);
-                                    });
-                                    Self::show_success_async(cx, format!("数据库 {} 已删除", db_name_log));
-                                });
-                            }
-                            Err(e) => {
-                                let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, format!("删除数据库失败: {}", e));
+        confirm_destructive(
+            ConfirmRequest::new(
+                DestructiveAction::DropDatabase,
+                environment,
+                database_name.clone(),
+                format!("确定要删除数据库 \"{}\" 吗？", database_name),
+                "此操作将删除数据库中的所有数据，不可恢复！",
+            ),
+            window,
+            cx,
+            move |_window, cx| {
+                let conn_id = connection_id.clone();
+                let db_name = database_name.clone();
+                let db_name_log = db_name.clone();
+                let db_name_for_remove = db_name.clone();
+                let tree = tree_view.clone();
+                let state = global_state.clone();
+
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let result = state.drop_database(cx, conn_id.clone(), db_name.clone()).await;
+                    match result {
+                        Ok(_) => {
+                            // 直接移除数据库节点，不刷新整个连接
+                            let _ = cx.update(|cx| {
+                                tree.update(cx, |tree, cx| {
+                                    tree.remove_database_node(&conn_id, &db_name_for_remove, cx);
                                 });
-                            }
+                                Self::show_success_async(cx, format!("数据库 {} 已删除", db_name_log));
+                            });
                         }
-                    }).detach();
-                    true
-                })
-        });
+                        Err(e) => {
+                            let _ = cx.update(|cx| {
+                                Self::show_error_async(cx, format!("删除数据库失败: {}", e));
+                            });
+                        }
+                    }
+                }).detach();
+            },
+        );
     }
 
     /// 处理新建模式事件
@@ -1461,77 +1612,69 @@ impl DatabaseEventHandler {
         let schema_name = node.name.clone();
         let metadata = node.metadata.clone();
         let database_type = node.database_type;
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+
+        confirm_destructive(
+            ConfirmRequest::new(
+                DestructiveAction::DropSchema,
+                environment,
+                schema_name.clone(),
+                format!("确定要删除模式 \"{}\" 吗？", schema_name),
+                "此操作将删除模式中的所有对象，不可恢复！",
+            ),
+            window,
+            cx,
+            move |_window, cx| {
+                let conn_id = connection_id.clone();
+                let schema = schema_name.clone();
+                let state = global_state.clone();
+                let schema_log = schema.clone();
+                let schema_for_remove = schema.clone();
+                let tree = tree_view.clone();
+                let database = metadata.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
+                let database_for_remove = database.clone();
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
-            let conn_id = connection_id.clone();
-            let schema = schema_name.clone();
-            let meta = metadata.clone();
-            let state = global_state.clone();
-            let schema_display = schema_name.clone();
-            let tree = tree_view.clone();
-
-            dialog
-                .title("确认删除")
-                .confirm()
-                .child(
-                    v_flex()
-                        .gap_2()
-                        .child(format!("确定要删除模式 \"{}\" 吗？", schema_display))
-                        .child("此操作将删除模式中的所有对象，不可恢复！")
-                )
-                .on_ok(move |_, _, cx| {
-                    let conn_id = conn_id.clone();
-                    let schema = schema.clone();
-                    let meta = meta.clone();
-                    let state = state.clone();
-                    let schema_log = schema.clone();
-                    let schema_for_remove = schema.clone();
-                    let tree = tree.clone();
-                    let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
-                    let database_for_remove = database.clone();
-
-                    let sql = state.get_plugin(&database_type)
-                        .map(|p| p.build_drop_schema_sql(&schema))
-                        .unwrap_or_else(|_| format!("DROP SCHEMA \"{}\"", schema));
-
-                    cx.spawn(async move |cx: &mut AsyncApp| {
-                        let result = state.execute_single(
-                            cx,
-                            conn_id.clone(),
-                            sql,
-                            Some(database.clone()),
-                            None,
-                        ).await;
+                let sql = state.get_plugin(&database_type)
+                    .map(|p| p.build_drop_schema_sql(&schema))
+                    .unwrap_or_else(|_| format!("DROP SCHEMA \"{}\"", schema));
 
-                        match result {
-                            Ok(sql_result) => {
-                                match sql_result {
-                                    SqlResult::Query(_) => {}
-                                    SqlResult::Exec(_) => {
-                                        let _ = cx.update(|cx| {
-                                            tree.update(cx, |tree, cx| {
-                                                tree.remove_schema_node(&conn_id, &database_for_remove, &schema_for_remove, cx);
-                                            });
-                                            Self::show_success_async(cx, format!("模式 {} 已删除", schema_log));
-                                        });
-                                    }
-                                    SqlResult::Error(err) => {
-                                        let _ = cx.update(|cx| {
-                                            Self::show_error_async(cx, format!("删除模式失败: {}", err.message));
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let result = state.execute_single(
+                        cx,
+                        conn_id.clone(),
+                        sql,
+                        Some(database.clone()),
+                        None,
+                    ).await;
+
+                    match result {
+                        Ok(sql_result) => {
+                            match sql_result {
+                                SqlResult::Query(_) => {}
+                                SqlResult::Exec(_) => {
+                                    let _ = cx.update(|cx| {
+                                        tree.update(cx, |tree, cx| {
+                                            tree.remove_schema_node(&conn_id, &database_for_remove, &schema_for_remove, cx);
                                         });
-                                    }
+                                        Self::show_success_async(cx, format!("模式 {} 已删除", schema_log));
+                                    });
+                                }
+                                SqlResult::Error(err) => {
+                                    let _ = cx.update(|cx| {
+                                        Self::show_error_async(cx, format!("删除模式失败: {}", err.message));
+                                    });
                                 }
-                            }
-                            Err(e) => {
-                                let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, format!("删除模式失败: {}", e));
-                                });
                             }
                         }
-                    }).detach();
-                    true
-                })
-        });
+                        Err(e) => {
+                            let _ = cx.update(|cx| {
+                                Self::show_error_async(cx, format!("删除模式失败: {}", e));
+                            });
+                        }
+                    }
+                }).detach();
+            },
+        );
     }
 
     /// 处理删除表事件
@@ -1546,58 +1689,71 @@ impl DatabaseEventHandler {
         let table_name = node.name.clone();
         let table_node_id = node.id.clone();
         let metadata = node.metadata.clone();
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+        let supports_trash = global_state
+            .get_plugin(&node.database_type)
+            .map(|plugin| plugin.supports_trash())
+            .unwrap_or(false);
+
+        let request = ConfirmRequest::new(
+            DestructiveAction::DropTable,
+            environment,
+            table_name.clone(),
+            format!("确定要删除表 \"{}\" 吗？", table_name),
+            if supports_trash {
+                "移到回收站后可在数据库的回收站中恢复，否则将删除表中的所有数据，不可恢复！"
+            } else {
+                "此操作将删除表中的所有数据，不可恢复！"
+            },
+        );
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
+        let on_confirm = move |move_to_trash: bool, _window: &mut Window, cx: &mut App| {
             let conn_id = connection_id.clone();
             let tbl_name = table_name.clone();
             let tbl_node_id = table_node_id.clone();
             let meta = metadata.clone();
             let state = global_state.clone();
-            let tbl_name_display = table_name.clone();
+            let tbl_name_log = tbl_name.clone();
             let tree = tree_view.clone();
 
-            dialog
-                .title("确认删除")
-                .confirm()
-                .child(
-                    v_flex()
-                        .gap_2()
-                        .child(format!("确定要删除表 \"{}\" 吗？", tbl_name_display))
-                        .child("此操作将删除表中的所有数据，不可恢复！")
-                )
-                .on_ok(move |_, _, cx| {
-                    let conn_id = conn_id.clone();
-                    let tbl_name = tbl_name.clone();
-                    let tbl_node_id = tbl_node_id.clone();
-                    let meta = meta.clone();
-                    let state = state.clone();
-                    let tbl_name_log = tbl_name.clone();
-                    let tree = tree.clone();
-
-                    cx.spawn(async move |cx: &mut AsyncApp| {
-                        let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
-                        let task = state.drop_table(cx, conn_id.clone(), database, tbl_name.clone()).await;
+            cx.spawn(async move |cx: &mut AsyncApp| {
+                let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
+                let task = if move_to_trash {
+                    let schema = meta.as_ref().and_then(|m| m.get("schema")).map(|s| s.to_string());
+                    state.move_table_to_trash(cx, conn_id.clone(), database, schema, tbl_name.clone()).await.map(|_| ())
+                } else {
+                    state.drop_table(cx, conn_id.clone(), database, tbl_name.clone()).await.map(|_| ())
+                };
 
-                        match task {
-                            Ok(_) => {
-                                // 直接移除表节点
-                                let _ = cx.update(|cx| {
-                                    tree.update(cx, |tree, cx| {
-                                        tree.remove_table_node(&tbl_node_id, cx);
-                                    });
-                                    Self::show_success_async(cx, format!("表 {} 已删除", tbl_name_log));
-                                });
-                            }
-                            Err(e) => {
-                                let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, format!("删除表失败: {}", e));
-                                });
-                            }
-                        }
-                    }).detach();
-                    true
-                })
-        });
+                match task {
+                    Ok(_) => {
+                        // 直接移除表节点
+                        let _ = cx.update(|cx| {
+                            tree.update(cx, |tree, cx| {
+                                tree.remove_table_node(&tbl_node_id, cx);
+                            });
+                            let message = if move_to_trash {
+                                format!("表 {} 已移到回收站", tbl_name_log)
+                            } else {
+                                format!("表 {} 已删除", tbl_name_log)
+                            };
+                            Self::show_success_async(cx, message);
+                        });
+                    }
+                    Err(e) => {
+                        let _ = cx.update(|cx| {
+                            Self::show_error_async(cx, format!("删除表失败: {}", e));
+                        });
+                    }
+                }
+            }).detach();
+        };
+
+        if supports_trash {
+            confirm_destructive_with_option(request, "移到回收站（可恢复）", true, window, cx, on_confirm);
+        } else {
+            confirm_destructive(request, window, cx, move |window, cx| on_confirm(false, window, cx));
+        }
     }
 
     /// 处理重命名表事件
@@ -1713,57 +1869,51 @@ impl DatabaseEventHandler {
     fn handle_truncate_table(
         node: DbNode,
         global_state: GlobalDbState,
-        _tree_view: Entity<DbTreeView>,
+        tree_view: Entity<DbTreeView>,
         window: &mut Window,
         cx: &mut App,
     ) {
         let connection_id = node.connection_id.clone();
         let table_name = node.name.clone();
         let metadata = node.metadata.clone();
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+
+        confirm_destructive(
+            ConfirmRequest::new(
+                DestructiveAction::TruncateTable,
+                environment,
+                table_name.clone(),
+                format!("确定要清空表 \"{}\" 吗？", table_name),
+                "此操作将删除表中的所有数据，但保留表结构，不可恢复！",
+            ),
+            window,
+            cx,
+            move |_window, cx| {
+                let conn_id = connection_id.clone();
+                let tbl_name = table_name.clone();
+                let meta = metadata.clone();
+                let state = global_state.clone();
+                let tbl_name_log = tbl_name.clone();
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
-            let conn_id = connection_id.clone();
-            let tbl_name = table_name.clone();
-            let meta = metadata.clone();
-            let state = global_state.clone();
-            let tbl_name_display = table_name.clone();
-
-            dialog
-                .title("确认清空")
-                .confirm()
-                .child(
-                    v_flex()
-                        .gap_2()
-                        .child(format!("确定要清空表 \"{}\" 吗？", tbl_name_display))
-                        .child("此操作将删除表中的所有数据，但保留表结构，不可恢复！")
-                )
-                .on_ok(move |_, _, cx| {
-                    let conn_id = conn_id.clone();
-                    let tbl_name = tbl_name.clone();
-                    let meta = meta.clone();
-                    let state = state.clone();
-                    let tbl_name_log = tbl_name.clone();
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
+                    let task = state.truncate_table(cx, conn_id.clone(), database, tbl_name.clone()).await;
 
-                    cx.spawn(async move |cx: &mut AsyncApp| {
-                        let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
-                        let task = state.truncate_table(cx, conn_id.clone(), database, tbl_name.clone()).await;
-                        
-                        match task {
-                            Ok(_) => {
-                                let _ = cx.update(|cx| {
-                                    Self::show_success_async(cx, format!("表 {} 已清空", tbl_name_log));
-                                });
-                            }
-                            Err(e) => {
-                                let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, format!("清空表失败: {}", e));
-                                });
-                            }
+                    match task {
+                        Ok(_) => {
+                            let _ = cx.update(|cx| {
+                                Self::show_success_async(cx, format!("表 {} 已清空", tbl_name_log));
+                            });
                         }
-                    }).detach();
-                    true
-                })
-        });
+                        Err(e) => {
+                            let _ = cx.update(|cx| {
+                                Self::show_error_async(cx, format!("清空表失败: {}", e));
+                            });
+                        }
+                    }
+                }).detach();
+            },
+        );
     }
 
     /// 处理删除视图事件
@@ -1778,58 +1928,50 @@ impl DatabaseEventHandler {
         let view_name = node.name.clone();
         let view_node_id = node.id.clone();
         let metadata = node.metadata.clone();
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+
+        confirm_destructive(
+            ConfirmRequest::new(
+                DestructiveAction::DropView,
+                environment,
+                view_name.clone(),
+                format!("确定要删除视图 \"{}\" 吗？", view_name),
+                "此操作不可恢复。",
+            ),
+            window,
+            cx,
+            move |_window, cx| {
+                let conn_id = connection_id.clone();
+                let v_name = view_name.clone();
+                let v_node_id = view_node_id.clone();
+                let meta = metadata.clone();
+                let state = global_state.clone();
+                let v_name_log = v_name.clone();
+                let tree = tree_view.clone();
 
-        window.open_dialog(cx, move |dialog, _window, _cx| {
-            let conn_id = connection_id.clone();
-            let v_name = view_name.clone();
-            let v_node_id = view_node_id.clone();
-            let meta = metadata.clone();
-            let state = global_state.clone();
-            let v_name_display = view_name.clone();
-            let tree = tree_view.clone();
-
-            dialog
-                .title("确认删除")
-                .confirm()
-                .child(
-                    v_flex()
-                        .gap_2()
-                        .child(format!("确定要删除视图 \"{}\" 吗？", v_name_display))
-                        .child("此操作不可恢复。")
-                )
-                .on_ok(move |_, _, cx| {
-                    let conn_id = conn_id.clone();
-                    let v_name = v_name.clone();
-                    let v_node_id = v_node_id.clone();
-                    let meta = meta.clone();
-                    let state = state.clone();
-                    let v_name_log = v_name.clone();
-                    let tree = tree.clone();
-
-                    cx.spawn(async move |cx: &mut AsyncApp| {
-                        let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
-                        let result = state.drop_view(cx, conn_id.clone(), database, v_name.clone()).await;
-
-                        match result {
-                            Ok(_) => {
-                                // 直接移除视图节点
-                                let _ = cx.update(|cx| {
-                                    tree.update(cx, |tree, cx| {
-                                        tree.remove_view_node(&v_node_id, cx);
-                                    });
-                                    Self::show_success_async(cx, format!("视图 {} 已删除", v_name_log));
-                                });
-                            }
-                            Err(e) => {
-                                let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, format!("删除视图失败: {}", e));
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let database = meta.as_ref().and_then(|m| m.get("database")).map(|s| s.to_string()).unwrap_or_default();
+                    let result = state.drop_view(cx, conn_id.clone(), database, v_name.clone()).await;
+
+                    match result {
+                        Ok(_) => {
+                            // 直接移除视图节点
+                            let _ = cx.update(|cx| {
+                                tree.update(cx, |tree, cx| {
+                                    tree.remove_view_node(&v_node_id, cx);
                                 });
-                            }
+                                Self::show_success_async(cx, format!("视图 {} 已删除", v_name_log));
+                            });
                         }
-                    }).detach();
-                    true
-                })
-        });
+                        Err(e) => {
+                            let _ = cx.update(|cx| {
+                                Self::show_error_async(cx, format!("删除视图失败: {}", e));
+                            });
+                        }
+                    }
+                }).detach();
+            },
+        );
     }
 
     /// 处理打开命名查询事件
@@ -1870,6 +2012,179 @@ impl DatabaseEventHandler {
         }
     }
 
+    /// 处理打开对象查询事件：同义词和链接服务器表没有可编辑的数据页，
+    /// 直接打开带有完整限定名称的查询，由用户在编辑器中修改
+    fn handle_open_object_query(
+        node: DbNode,
+        tab_container: Entity<TabContainer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(metadata) = node.metadata.as_ref() else {
+            Self::show_error(window, "无效的节点数据", cx);
+            return;
+        };
+        let Some(sql) = metadata.get("sql").cloned() else {
+            Self::show_error(window, "无法生成查询语句", cx);
+            return;
+        };
+        // 链接服务器表使用四段式名称，不依赖当前数据库
+        let database = metadata.get("database").cloned();
+
+        Self::open_sql_editor(format!("{} - Query", node.name), &node, database, sql, tab_container, window, cx);
+    }
+
+    /// 打开一个预填 SQL 的查询编辑器标签页
+    fn open_sql_editor(
+        title: String,
+        node: &DbNode,
+        database: Option<String>,
+        sql: String,
+        tab_container: Entity<TabContainer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use crate::sql_editor_view::SqlEditorTabContent;
+
+        let sql_editor = SqlEditorTabContent::new_with_config(
+            title,
+            node.connection_id.clone(),
+            node.database_type,
+            None,
+            database,
+            window,
+            cx,
+        );
+        sql_editor.set_sql(sql, window, cx);
+
+        tab_container.update(cx, |container, cx| {
+            let tab = TabItem::new(format!("query-object-{}", Uuid::new_v4()), sql_editor);
+            container.add_and_activate_tab(tab, cx);
+        });
+    }
+
+    /// 处理编辑事件：读取当前定义，生成删除后重建的脚本在编辑器中打开
+    fn handle_edit_event(
+        node: DbNode,
+        global_state: GlobalDbState,
+        tab_container: Entity<TabContainer>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let event = node.name.clone();
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let script = match global_state.get_event_edit_script(cx, connection_id, database.clone(), event.clone()).await {
+                Ok(script) => script,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("获取事件定义失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_sql_editor(format!("{} - Event", event), &node, Some(database), script, tab_container, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    /// 处理新建事件：在编辑器中打开 CREATE EVENT 模板
+    fn handle_create_event(
+        node: DbNode,
+        global_state: GlobalDbState,
+        tab_container: Entity<TabContainer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let database = Self::get_database_from_node(&node);
+        let template = global_state
+            .get_plugin(&node.database_type)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+            .and_then(|plugin| plugin.build_create_event_template(&database));
+
+        match template {
+            Ok(template) => {
+                Self::open_sql_editor(format!("{} - New Event", database), &node, Some(database), template, tab_container, window, cx);
+            }
+            Err(e) => Self::show_error(window, format!("无法新建事件: {}", e), cx),
+        }
+    }
+
+    /// 处理启用/禁用事件：完成后刷新事件列表以更新状态
+    fn handle_set_event_enabled(
+        node: DbNode,
+        enabled: bool,
+        global_state: GlobalDbState,
+        tree_view: Entity<DbTreeView>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let event = node.name.clone();
+        let folder_id = node.parent_context.clone().unwrap_or_else(|| node.id.clone());
+        let action = if enabled { "启用" } else { "禁用" };
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let result = global_state.set_event_enabled(cx, connection_id, database, event.clone(), enabled).await;
+            let _ = cx.update(|cx| {
+                match result {
+                    Ok(_) => Self::show_success_async(cx, format!("事件 {} 已{}", event, action)),
+                    Err(e) => Self::show_error_async(cx, format!("{}事件失败: {}", action, e)),
+                }
+                tree_view.update(cx, |tree, cx| tree.refresh_tree(folder_id, cx));
+            });
+        }).detach();
+    }
+
+    /// 处理删除事件
+    fn handle_drop_event(
+        node: DbNode,
+        global_state: GlobalDbState,
+        tree_view: Entity<DbTreeView>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let event = node.name.clone();
+        let folder_id = node.parent_context.clone().unwrap_or_else(|| node.id.clone());
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+
+        confirm_destructive(
+            ConfirmRequest::new(
+                DestructiveAction::DropEvent,
+                environment,
+                event.clone(),
+                format!("确定要删除事件 \"{}\" 吗？", event),
+                "此操作不可恢复。",
+            ),
+            window,
+            cx,
+            move |_window, cx| {
+                let connection_id = connection_id.clone();
+                let database = database.clone();
+                let event = event.clone();
+                let folder_id = folder_id.clone();
+                let state = global_state.clone();
+                let tree = tree_view.clone();
+
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let result = state.drop_event(cx, connection_id, database, event.clone()).await;
+                    let _ = cx.update(|cx| {
+                        match result {
+                            Ok(_) => Self::show_success_async(cx, format!("事件 {} 已删除", event)),
+                            Err(e) => Self::show_error_async(cx, format!("删除事件失败: {}", e)),
+                        }
+                        tree.update(cx, |tree, cx| tree.refresh_tree(folder_id, cx));
+                    });
+                }).detach();
+            },
+        );
+    }
+
     /// 处理重命名查询事件
     fn handle_rename_query(
         node: DbNode,
@@ -2009,55 +2324,50 @@ impl DatabaseEventHandler {
             let query_name = node.name.clone();
             let connection_id = node.connection_id.clone();
             let storage_manager = cx.global::<GlobalStorageState>().storage.clone();
+            let environment = tree_view.read(cx).connection_environment(&connection_id);
+
+            confirm_destructive(
+                ConfirmRequest::new(
+                    DestructiveAction::DeleteQuery,
+                    environment,
+                    query_name.clone(),
+                    format!("确定要删除查询 \"{}\" 吗？", query_name),
+                    "此操作不可恢复。",
+                ),
+                window,
+                cx,
+                move |_window, cx| {
+                    let storage = storage_manager.clone();
+                    let tree = tree_view.clone();
+                    let conn_id = connection_id.clone();
 
-            window.open_dialog(cx, move |dialog, _window, _cx| {
-                let q_name = query_name.clone();
-                let storage = storage_manager.clone();
-                let tree = tree_view.clone();
-                let conn_id = connection_id.clone();
-
-                dialog
-                    .title("确认删除")
-                    .confirm()
-                    .child(
-                        v_flex()
-                            .gap_2()
-                            .child(format!("确定要删除查询 \"{}\" 吗？", q_name))
-                            .child("此操作不可恢复。")
-                    )
-                    .on_ok(move |_, _, cx| {
-                        let storage = storage.clone();
-                        let tree = tree.clone();
-                        let conn_id = conn_id.clone();
-
-                        cx.spawn(async move |cx| {
-                            if let Some(query_repo_arc) = storage.get::<QueryRepository>().await {
-                                let query_repo = (*query_repo_arc).clone();
-                                match query_repo.delete(qid).await {
-                                    Ok(_) => {
-                                        // 刷新树
-                                        let _ = cx.update(|cx| {
-                                            tree.update(cx, |tree, cx| {
-                                                tree.refresh_tree(conn_id.clone(), cx);
-                                            });
-                                            Self::show_success_async(cx, "查询已删除");
-                                        });
-                                    }
-                                    Err(e) => {
-                                        let _ = cx.update(|cx| {
-                                            Self::show_error_async(cx, format!("删除查询失败: {}", e));
+                    cx.spawn(async move |cx| {
+                        if let Some(query_repo_arc) = storage.get::<QueryRepository>().await {
+                            let query_repo = (*query_repo_arc).clone();
+                            match query_repo.delete(qid).await {
+                                Ok(_) => {
+                                    // 刷新树
+                                    let _ = cx.update(|cx| {
+                                        tree.update(cx, |tree, cx| {
+                                            tree.refresh_tree(conn_id.clone(), cx);
                                         });
-                                    }
+                                        Self::show_success_async(cx, "查询已删除");
+                                    });
+                                }
+                                Err(e) => {
+                                    let _ = cx.update(|cx| {
+                                        Self::show_error_async(cx, format!("删除查询失败: {}", e));
+                                    });
                                 }
-                            } else {
-                                let _ = cx.update(|cx| {
-                                    Self::show_error_async(cx, "删除查询失败：无法获取存储库");
-                                });
                             }
-                        }).detach();
-                        true
-                    })
-            });
+                        } else {
+                            let _ = cx.update(|cx| {
+                                Self::show_error_async(cx, "删除查询失败：无法获取存储库");
+                            });
+                        }
+                    }).detach();
+                },
+            );
         }
     }
 
@@ -2071,7 +2381,7 @@ impl DatabaseEventHandler {
         use crate::import_export::sql_run_view::SqlRunView;
 
         let connection_id = node.connection_id.clone();
-        let database = if node.node_type == DbNodeType::Database {
+        let database = if node.node_type.is_database_level() {
             Some(node.name.clone())
         } else {
             None
@@ -2087,25 +2397,90 @@ impl DatabaseEventHandler {
         });
     }
 
-    /// 处理转储SQL文件事件
-    fn handle_dump_sql_file(
+    /// 处理打开回收站事件
+    fn handle_open_trash(
         node: DbNode,
-        mode: crate::db_tree_view::SqlDumpMode,
-        global_state: GlobalDbState,
-        _window: &mut Window,
+        tree_view: Entity<DbTreeView>,
+        window: &mut Window,
         cx: &mut App,
     ) {
-        use crate::import_export::sql_dump_view::SqlDumpView;
-        use std::path::PathBuf;
-
         let connection_id = node.connection_id.clone();
         let database = node.name.clone();
+        let database_node_id = node.id.clone();
 
-        let future = cx.prompt_for_paths(PathPromptOptions {
-            files: false,
-            multiple: false,
-            directories: true,
-            prompt: Some("选择导出目录".into()),
+        let browser = cx.new(|cx| TrashBrowser::new(connection_id, database.clone(), database_node_id, tree_view, cx));
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(format!("回收站 - {}", database))
+                .child(browser.clone())
+                .width(px(600.0))
+                .on_cancel(|_, _window, _cx| true)
+        });
+    }
+
+    /// 处理打开会话变量事件，同一连接只打开一个标签页
+    fn handle_open_session_variables(
+        node: DbNode,
+        tab_container: Entity<TabContainer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let connection_name = node.name.clone();
+        let tab_id = format!("session-variables-{}", connection_id);
+        tab_container.update(cx, |container, cx| {
+            container.activate_or_add_tab_lazy(
+                tab_id.clone(),
+                move |window, cx| {
+                    let content = SessionVariablesTabContent::new(connection_id, connection_name, window, cx);
+                    TabItem::new(tab_id, content)
+                },
+                window,
+                cx,
+            );
+        });
+    }
+
+    /// 处理打开扩展管理事件
+    fn handle_open_extensions(
+        node: DbNode,
+        tree_view: Entity<DbTreeView>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = node.name.clone();
+        let database_node_id = node.id.clone();
+
+        let browser = cx.new(|cx| ExtensionBrowser::new(connection_id, database.clone(), database_node_id, tree_view, window, cx));
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            dialog
+                .title(format!("扩展 - {}", database))
+                .child(browser.clone())
+                .width(px(680.0))
+                .on_cancel(|_, _window, _cx| true)
+        });
+    }
+
+    /// 处理转储SQL文件事件
+    fn handle_dump_sql_file(
+        node: DbNode,
+        mode: crate::db_tree_view::SqlDumpMode,
+        global_state: GlobalDbState,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        use crate::import_export::sql_dump_view::SqlDumpView;
+        use std::path::PathBuf;
+
+        let connection_id = node.connection_id.clone();
+        let database = node.name.clone();
+
+        let future = cx.prompt_for_paths(PathPromptOptions {
+            files: false,
+            multiple: false,
+            directories: true,
+            prompt: Some("选择导出目录".into()),
         });
 
         let connection_id_for_error = connection_id.clone();
@@ -2182,4 +2557,769 @@ impl DatabaseEventHandler {
             }
         }).detach();
     }
-}
\ No newline at end of file
+
+    /// 处理查看 DDL 事件：获取建表语句后在对话框中展示，可复制
+    /// 处理打开维护面板事件，同一数据库只打开一个标签页
+    fn handle_open_maintenance(
+        node: DbNode,
+        tab_container: Entity<TabContainer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+
+        // CockroachDB 没有可调的存储参数，维护面板换成 range 分布和 zone 配置
+        if node.database_type == one_core::storage::DatabaseType::CockroachDB {
+            let tab_id = format!("cockroach-ranges-{}-{}", connection_id, database);
+            tab_container.update(cx, |container, cx| {
+                container.activate_or_add_tab_lazy(
+                    tab_id.clone(),
+                    move |window, cx| {
+                        let content = CockroachRangesTabContent::new(connection_id, database, window, cx);
+                        TabItem::new(tab_id, content)
+                    },
+                    window,
+                    cx,
+                );
+            });
+            return;
+        }
+
+        // TiDB 的维护面板用来管理 TiFlash 副本和查看放置规则
+        if node.database_type == one_core::storage::DatabaseType::TiDB {
+            let tab_id = format!("tidb-cluster-{}-{}", connection_id, database);
+            tab_container.update(cx, |container, cx| {
+                container.activate_or_add_tab_lazy(
+                    tab_id.clone(),
+                    move |window, cx| {
+                        let content = TiDbClusterTabContent::new(connection_id, database, window, cx);
+                        TabItem::new(tab_id, content)
+                    },
+                    window,
+                    cx,
+                );
+            });
+            return;
+        }
+
+        let tab_id = format!("sqlite-maintenance-{}-{}", connection_id, database);
+        tab_container.update(cx, |container, cx| {
+            container.activate_or_add_tab_lazy(
+                tab_id.clone(),
+                move |window, cx| {
+                    let content = SqliteMaintenanceTabContent::new(connection_id, database, window, cx);
+                    TabItem::new(tab_id, content)
+                },
+                window,
+                cx,
+            );
+        });
+    }
+
+    fn handle_view_table_ddl(
+        node: DbNode,
+        global_state: GlobalDbState,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let table = node.name.clone();
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let ddl = match global_state.get_table_ddl(cx, connection_id, database, table.clone()).await {
+                Ok(ddl) if !ddl.is_empty() => ddl,
+                Ok(_) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("未获取到表 {} 的建表语句", table)));
+                    return;
+                }
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("获取建表语句失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_table_ddl_dialog(table, ddl, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    fn open_table_ddl_dialog(table: String, ddl: String, window: &mut Window, cx: &mut App) {
+        use gpui_component::highlighter::Language;
+        use gpui_component::input::{Input, InputState};
+
+        let ddl_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).code_editor(Language::from_str("sql"));
+            state.set_value(format!("{};", ddl.trim_end().trim_end_matches(';')), window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let ddl_input = ddl_input.clone();
+
+            dialog
+                .title(format!("{} DDL", table))
+                .width(px(800.))
+                .child(Input::new(&ddl_input).h(px(480.)))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("复制"))
+                .on_ok(move |_, window, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(ddl_input.read(cx).text().to_string()));
+                    window.push_notification("已复制 DDL", cx);
+                    true
+                })
+        });
+    }
+
+    /// 处理查看物化视图定义事件：以 CREATE MATERIALIZED VIEW 语句展示
+    fn handle_view_materialized_view_definition(
+        node: DbNode,
+        global_state: GlobalDbState,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let schema = node.metadata.as_ref().and_then(|m| m.get("schema")).cloned();
+        let view = node.name.clone();
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let definition = match global_state
+                .get_routine_source(cx, connection_id, database, schema, DbNodeType::MaterializedView, view.clone())
+                .await
+            {
+                Ok(definition) => definition,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("获取物化视图定义失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_table_ddl_dialog(view, definition, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    /// 处理刷新物化视图事件：刷新后更新节点，WITH NO DATA 创建的视图在首次刷新后才可查询
+    fn handle_refresh_materialized_view(
+        node: DbNode,
+        concurrently: bool,
+        global_state: GlobalDbState,
+        tree_view: Entity<DbTreeView>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let schema = node.metadata.as_ref().and_then(|m| m.get("schema")).cloned();
+        let view = node.name.clone();
+        let folder_id = node.parent_context.clone().unwrap_or_else(|| node.id.clone());
+
+        window.push_notification(format!("正在刷新物化视图 {}…", view), cx);
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let result = global_state
+                .refresh_materialized_view(cx, connection_id, database, schema, view.clone(), concurrently)
+                .await;
+            let _ = cx.update(|cx| {
+                match result {
+                    Ok(_) => Self::show_success_async(cx, format!("物化视图 {} 已刷新", view)),
+                    Err(e) => Self::show_error_async(cx, format!("刷新物化视图失败: {}", e)),
+                }
+                tree_view.update(cx, |tree, cx| tree.refresh_tree(folder_id, cx));
+            });
+        }).detach();
+    }
+
+    /// 处理查看包源码事件：包规范和包体合并为一个可直接执行的脚本
+    fn handle_view_package_source(
+        node: DbNode,
+        global_state: GlobalDbState,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let package = node.name.clone();
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let source = match global_state.get_package_source(cx, connection_id, database, package.clone()).await {
+                Ok(source) => source,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("获取包源码失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_package_source_dialog(package, source.to_script(), window, cx);
+            }).await;
+        }).detach();
+    }
+
+    fn open_package_source_dialog(package: String, script: String, window: &mut Window, cx: &mut App) {
+        use gpui_component::highlighter::Language;
+        use gpui_component::input::{Input, InputState};
+
+        let source_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).code_editor(Language::from_str("sql"));
+            state.set_value(script, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let source_input = source_input.clone();
+
+            dialog
+                .title(format!("包 {}", package))
+                .width(px(800.))
+                .child(Input::new(&source_input).h(px(480.)))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("复制"))
+                .on_ok(move |_, window, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(source_input.read(cx).text().to_string()));
+                    window.push_notification("已复制包源码", cx);
+                    true
+                })
+        });
+    }
+
+    /// 处理编译包事件：编译后刷新节点以更新状态，有错误时逐条列出
+    fn handle_compile_package(
+        node: DbNode,
+        global_state: GlobalDbState,
+        tree_view: Entity<DbTreeView>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) {
+        let connection_id = node.connection_id.clone();
+        let database = Self::get_database_from_node(&node);
+        let package = node.name.clone();
+        let folder_id = node.parent_context.clone().unwrap_or_else(|| node.id.clone());
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let result = global_state.compile_package(cx, connection_id, database, package.clone()).await;
+            let _ = cx.update(|cx| {
+                match result {
+                    Ok(errors) if errors.is_empty() => Self::show_success_async(cx, format!("包 {} 编译成功", package)),
+                    Ok(errors) => Self::show_error_async(cx, format!("包 {} 编译出错:\n{}", package, errors.join("\n"))),
+                    Err(e) => Self::show_error_async(cx, format!("编译包失败: {}", e)),
+                }
+                tree_view.update(cx, |tree, cx| tree.refresh_tree(folder_id, cx));
+            });
+        }).detach();
+    }
+
+    /// 处理 AI 生成文档事件：生成后预览，可编辑后导出为 Markdown 文件
+    fn handle_generate_schema_doc(
+        node: DbNode,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use crate::ai_sql::{generate_schema_doc, SchemaDocRequest};
+
+        let (database, schema, table) = match node.node_type {
+            DbNodeType::Table => {
+                let metadata = node.metadata.as_ref();
+                let Some(database) = metadata.and_then(|m| m.get("database")).cloned() else {
+                    Self::show_error(window, "无法获取数据库名称", cx);
+                    return;
+                };
+                (database, metadata.and_then(|m| m.get("schema")).cloned(), Some(node.name.clone()))
+            }
+            _ => (node.name.clone(), None, None),
+        };
+        let title = table.clone().unwrap_or_else(|| database.clone());
+        let request = SchemaDocRequest {
+            connection_id: node.connection_id.clone(),
+            database_type: node.database_type,
+            database,
+            schema,
+            table,
+        };
+
+        window.push_notification(format!("正在生成 {} 的文档…", title), cx);
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let doc = match generate_schema_doc(request, cx).await {
+                Ok(doc) => doc,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("生成文档失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_schema_doc_dialog(title, doc, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    /// 处理 AI 解释事件：读取对象定义后请求模型总结输入、副作用和涉及的表
+    fn handle_explain_routine(
+        node: DbNode,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use crate::ai_sql::{explain_routine, RoutineExplainRequest};
+
+        let title = node.name.clone();
+        let request = RoutineExplainRequest {
+            connection_id: node.connection_id.clone(),
+            database_type: node.database_type,
+            database: Self::get_database_from_node(&node),
+            schema: node.metadata.as_ref().and_then(|m| m.get("schema")).cloned(),
+            object_type: node.node_type.clone(),
+            name: node.name.clone(),
+        };
+
+        window.push_notification(format!("正在解释 {}…", title), cx);
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let (source, explanation) = match explain_routine(request, cx).await {
+                Ok(result) => result,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("AI 解释失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_routine_explain_dialog(title, source, explanation, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    fn open_routine_explain_dialog(title: String, source: String, explanation: String, window: &mut Window, cx: &mut App) {
+        use gpui_component::highlighter::Language;
+        use gpui_component::input::{Input, InputState};
+
+        let source_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).code_editor(Language::from_str("sql"));
+            state.set_value(source, window, cx);
+            state
+        });
+        let explanation_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true);
+            state.set_value(explanation, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let explanation_input = explanation_input.clone();
+
+            dialog
+                .title(format!("AI 解释 {}", title))
+                .width(px(800.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(Input::new(&source_input).h(px(200.)))
+                        .child(Input::new(&explanation_input).h(px(320.))),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("复制解释"))
+                .on_ok(move |_, window, cx| {
+                    cx.write_to_clipboard(ClipboardItem::new_string(explanation_input.read(cx).text().to_string()));
+                    window.push_notification("已复制解释", cx);
+                    true
+                })
+        });
+    }
+
+    fn open_schema_doc_dialog(title: String, doc: String, window: &mut Window, cx: &mut App) {
+        use gpui_component::input::{Input, InputState};
+
+        let doc_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true);
+            state.set_value(doc, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let doc_input = doc_input.clone();
+            let title = title.clone();
+
+            dialog
+                .title(format!("{} 文档", title))
+                .width(px(800.))
+                .child(Input::new(&doc_input).h(px(480.)))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("导出 Markdown"))
+                .on_ok(move |_, _window, cx| {
+                    let content = doc_input.read(cx).text().to_string();
+                    let file_name = format!("{}.md", sanitize_file_name(&title));
+                    let directory = dirs::download_dir()
+                        .or_else(dirs::home_dir)
+                        .unwrap_or_default();
+                    let path_future = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+                    cx.spawn(async move |cx: &mut AsyncApp| {
+                        let path = match path_future.await {
+                            Ok(Ok(Some(path))) => path,
+                            _ => return,
+                        };
+                        let _ = cx.update(|cx| match std::fs::write(&path, content) {
+                            Ok(()) => Self::show_success_async(cx, format!("文档已导出到 {}", path.display())),
+                            Err(e) => Self::show_error_async(cx, format!("导出文档失败: {}", e)),
+                        });
+                    }).detach();
+                    true
+                })
+        });
+    }
+
+    /// 处理 AI 生成测试数据事件：先选择行数，生成后预览 INSERT 语句，确认后在事务中执行
+    fn handle_generate_test_data(
+        node: DbNode,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use crate::ai_test_data::{DEFAULT_ROW_COUNT, MAX_ROW_COUNT};
+        use gpui_component::input::{Input, InputState};
+
+        let metadata = node.metadata.as_ref();
+        let Some(database) = metadata.and_then(|m| m.get("database")).cloned() else {
+            Self::show_error(window, "无法获取数据库名称", cx);
+            return;
+        };
+        let request = TestDataRequest {
+            connection_id: node.connection_id.clone(),
+            database_type: node.database_type,
+            database,
+            schema: metadata.and_then(|m| m.get("schema")).cloned(),
+            table: node.name.clone(),
+            row_count: DEFAULT_ROW_COUNT,
+        };
+
+        let count_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder(format!("1 - {}", MAX_ROW_COUNT));
+            state.set_value(DEFAULT_ROW_COUNT.to_string(), window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let count_input = count_input.clone();
+            let request = request.clone();
+
+            dialog
+                .title(format!("AI 生成 {} 的测试数据", request.table))
+                .width(px(400.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child("生成行数")
+                        .child(Input::new(&count_input)),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("生成"))
+                .on_ok(move |_, window, cx| {
+                    let text = count_input.read(cx).text().to_string();
+                    let row_count = match text.trim().parse::<usize>() {
+                        Ok(count) if (1..=MAX_ROW_COUNT).contains(&count) => count,
+                        _ => {
+                            Self::show_error(window, format!("行数必须在 1 到 {} 之间", MAX_ROW_COUNT), cx);
+                            return false;
+                        }
+                    };
+                    Self::start_generate_test_data(TestDataRequest { row_count, ..request.clone() }, window, cx);
+                    true
+                })
+        });
+    }
+
+    fn start_generate_test_data(request: TestDataRequest, window: &mut Window, cx: &mut App) {
+        use crate::ai_test_data::generate_test_data;
+
+        window.push_notification(format!("正在为 {} 生成 {} 行测试数据…", request.table, request.row_count), cx);
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let generated = match generate_test_data(request.clone(), cx).await {
+                Ok(generated) => generated,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("生成测试数据失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_test_data_dialog(request, generated, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    fn open_test_data_dialog(
+        request: TestDataRequest,
+        generated: GeneratedTestData,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use db::{ExecOptions, StatementTimeout};
+        use gpui_component::highlighter::Language;
+        use gpui_component::input::{Input, InputState};
+
+        let sql_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).code_editor(Language::from_str("sql"));
+            state.set_value(generated.sql.clone(), window, cx);
+            state
+        });
+        let summary = if generated.discarded > 0 {
+            format!("共 {} 行，已丢弃 {} 行不满足约束的数据", generated.rows, generated.discarded)
+        } else {
+            format!("共 {} 行", generated.rows)
+        };
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let sql_input = sql_input.clone();
+            let request = request.clone();
+
+            dialog
+                .title(format!("{} 测试数据", request.table))
+                .width(px(800.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(summary.clone())
+                        .child(Input::new(&sql_input).h(px(480.))),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("执行"))
+                .on_ok(move |_, _window, cx| {
+                    let sql = sql_input.read(cx).text().to_string();
+                    let global_state = cx.global::<GlobalDbState>().clone();
+                    let request = request.clone();
+                    let options = ExecOptions {
+                        stop_on_error: true,
+                        transactional: true,
+                        max_rows: None,
+                        source: None,
+                        timeout: StatementTimeout::ConnectionDefault,
+                        session_id: None,
+                    };
+
+                    cx.spawn(async move |cx: &mut AsyncApp| {
+                        let result = global_state
+                            .execute_script(cx, request.connection_id, sql, Some(request.database), Some(options))
+                            .await;
+                        let _ = cx.update(|cx| match result {
+                            Ok(results) => match results.iter().find_map(|r| match r {
+                                SqlResult::Error(err) => Some(err.message.clone()),
+                                _ => None,
+                            }) {
+                                Some(message) => Self::show_error_async(cx, format!("插入测试数据失败: {}", message)),
+                                None => Self::show_success_async(cx, format!("已向 {} 插入测试数据", request.table)),
+                            },
+                            Err(e) => Self::show_error_async(cx, format!("插入测试数据失败: {}", e)),
+                        });
+                    }).detach();
+                    true
+                })
+        });
+    }
+
+    fn handle_anonymize_table(node: DbNode, window: &mut Window, cx: &mut App) {
+        use crate::ai_anonymize::{scan_pii_columns, PiiScanRequest};
+
+        let metadata = node.metadata.as_ref();
+        let Some(database) = metadata.and_then(|m| m.get("database")).cloned() else {
+            Self::show_error(window, "无法获取数据库名称", cx);
+            return;
+        };
+        let request = PiiScanRequest {
+            connection_id: node.connection_id.clone(),
+            database_type: node.database_type,
+            database,
+            schema: metadata.and_then(|m| m.get("schema")).cloned(),
+            table: node.name.clone(),
+        };
+
+        window.push_notification(format!("正在识别 {} 中的个人信息列…", request.table), cx);
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let columns = match scan_pii_columns(request.clone(), cx).await {
+                Ok(columns) => columns,
+                Err(e) => {
+                    let _ = cx.update(|cx| Self::show_error_async(cx, format!("识别个人信息列失败: {}", e)));
+                    return;
+                }
+            };
+
+            Self::with_window(cx, move |window, cx| {
+                Self::open_anonymize_dialog(request, columns, window, cx);
+            }).await;
+        }).detach();
+    }
+
+    /// 按当前选择的规则生成脱敏语句
+    fn masking_sql(request: &crate::ai_anonymize::PiiScanRequest, columns: &[db::ColumnMasking], cx: &App) -> String {
+        match cx.global::<GlobalDbState>().db_manager.get_plugin(&request.database_type) {
+            Ok(plugin) => db::build_masking_updates(
+                plugin.as_ref(),
+                &request.database,
+                request.schema.as_deref(),
+                &request.table,
+                columns,
+            ),
+            Err(e) => format!("-- {}", e),
+        }
+    }
+
+    /// 展示每列的识别结果和可修改的规则，确认后在事务中执行脱敏语句
+    fn open_anonymize_dialog(
+        request: crate::ai_anonymize::PiiScanRequest,
+        columns: Vec<crate::ai_anonymize::PiiColumn>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use crate::table_data::anonymize_dialog::MaskingRuleItem;
+        use db::{ColumnMasking, ExecOptions, MaskingRule};
+        use gpui::{InteractiveElement, StatefulInteractiveElement};
+        use gpui_component::button::{Button, ButtonVariants};
+        use gpui_component::highlighter::Language;
+        use gpui_component::input::{Input, InputState};
+        use gpui_component::select::{Select, SelectState};
+        use gpui_component::{ActiveTheme, IndexPath};
+
+        let rule_items: Vec<MaskingRuleItem> = MaskingRule::all().into_iter().map(MaskingRuleItem).collect();
+        let rules: Vec<(crate::ai_anonymize::PiiColumn, Entity<SelectState<Vec<MaskingRuleItem>>>)> = columns
+            .into_iter()
+            .map(|column| {
+                let selected = MaskingRule::all().iter().position(|rule| *rule == column.rule).unwrap_or(0);
+                let select = cx.new(|cx| SelectState::new(rule_items.clone(), Some(IndexPath::new(selected)), window, cx));
+                (column, select)
+            })
+            .collect();
+        let current_masking = move |rules: &[(crate::ai_anonymize::PiiColumn, Entity<SelectState<Vec<MaskingRuleItem>>>)], cx: &App| -> Vec<ColumnMasking> {
+            rules
+                .iter()
+                .map(|(column, select)| ColumnMasking {
+                    column: column.column.clone(),
+                    rule: select.read(cx).selected_value().copied().unwrap_or(MaskingRule::Keep),
+                    is_email: column.category == "email",
+                })
+                .collect()
+        };
+        let initial_sql = Self::masking_sql(&request, &current_masking(&rules, cx), cx);
+        let sql_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).code_editor(Language::from_str("sql"));
+            state.set_value(initial_sql, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let sql_input = sql_input.clone();
+            let request = request.clone();
+            let rules = rules.clone();
+
+            let mut rule_rows = v_flex().gap_1();
+            for (column, select) in &rules {
+                rule_rows = rule_rows.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().flex_1().text_sm().overflow_hidden().child(format!("{} {}", column.column, column.data_type)))
+                        .child(div().w_20().text_sm().text_color(cx.theme().muted_foreground).child(column.category_label()))
+                        .child(Select::new(select).w_32()),
+                );
+            }
+
+            dialog
+                .title(format!("{} 数据脱敏", request.table))
+                .width(px(720.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("识别只使用列名、类型和注释；脱敏会直接修改表中的数据，请在副本或测试库中执行"),
+                        )
+                        .child(div().id("anonymize-table-rules").max_h(px(240.)).overflow_y_scroll().child(rule_rows))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Button::new("regenerate-masking-sql")
+                                        .small()
+                                        .label("按规则重新生成")
+                                        .on_click({
+                                            let sql_input = sql_input.clone();
+                                            let request = request.clone();
+                                            let rules = rules.clone();
+                                            move |_, window, cx| {
+                                                let sql = Self::masking_sql(&request, &current_masking(&rules, cx), cx);
+                                                sql_input.update(cx, |state, cx| state.set_value(sql, window, cx));
+                                            }
+                                        }),
+                                )
+                                .child(
+                                    Button::new("copy-masking-sql")
+                                        .small()
+                                        .ghost()
+                                        .label("复制 SQL")
+                                        .on_click({
+                                            let sql_input = sql_input.clone();
+                                            move |_, _, cx| {
+                                                cx.write_to_clipboard(ClipboardItem::new_string(sql_input.read(cx).text().to_string()));
+                                            }
+                                        }),
+                                ),
+                        )
+                        .child(Input::new(&sql_input).h(px(200.))),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("执行"))
+                .on_ok(move |_, window, cx| {
+                    let sql = sql_input.read(cx).text().to_string();
+                    let has_statements = sql
+                        .lines()
+                        .map(str::trim)
+                        .any(|line| !line.is_empty() && !line.starts_with("--"));
+                    if !has_statements {
+                        Self::show_error(window, "没有需要脱敏的列", cx);
+                        return false;
+                    }
+                    let global_state = cx.global::<GlobalDbState>().clone();
+                    let request = request.clone();
+                    let options = ExecOptions {
+                        stop_on_error: true,
+                        transactional: true,
+                        max_rows: None,
+                        source: None,
+                        timeout: StatementTimeout::ConnectionDefault,
+                        session_id: None,
+                    };
+
+                    cx.spawn(async move |cx: &mut AsyncApp| {
+                        let result = global_state
+                            .execute_script(cx, request.connection_id, sql, Some(request.database), Some(options))
+                            .await;
+                        let _ = cx.update(|cx| match result {
+                            Ok(results) => match results.iter().find_map(|r| match r {
+                                SqlResult::Error(err) => Some(err.message.clone()),
+                                _ => None,
+                            }) {
+                                Some(message) => Self::show_error_async(cx, format!("脱敏失败: {}", message)),
+                                None => Self::show_success_async(cx, format!("已完成 {} 的数据脱敏", request.table)),
+                            },
+                            Err(e) => Self::show_error_async(cx, format!("脱敏失败: {}", e)),
+                        });
+                    }).detach();
+                    true
+                })
+        });
+    }
+}
+
+/// 替换文件名中不允许出现的字符
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let sanitized = sanitized.trim();
+    if sanitized.is_empty() { "schema".to_string() } else { sanitized.to_string() }
+}
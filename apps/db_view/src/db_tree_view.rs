@@ -1,10 +1,10 @@
 // 1. 标准库导入
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // 2. 外部 crate 导入（按字母顺序）
-use gpui::{App, AppContext, Context, Entity, IntoElement, InteractiveElement, ParentElement, Render, RenderOnce, Styled, Window, div, StatefulInteractiveElement, EventEmitter, SharedString, Focusable, FocusHandle, AsyncApp, px, prelude::FluentBuilder, Subscription, Task};
+use gpui::{App, AppContext, Context, Entity, IntoElement, InteractiveElement, ParentElement, Render, RenderOnce, Styled, Window, div, StatefulInteractiveElement, EventEmitter, SharedString, Focusable, FocusHandle, AsyncApp, px, prelude::FluentBuilder, Subscription, Task, Timer};
 use gpui_component::{
     ActiveTheme, IconName, h_flex,
     menu::{ContextMenuExt, PopupMenuItem},
@@ -19,17 +19,23 @@ use gpui_component::{
     list::{List, ListDelegate, ListState},
     IndexPath, Selectable,
     clipboard::Clipboard,
+    WindowExt,
 };
-use tracing::log::{error, info, trace};
+use tracing::log::{error, info, trace, warn};
 
 // 3. 当前 crate 导入（按模块分组）
-use db::{GlobalDbState, DbNode, DbNodeType};
+use db::{ConnectionStatus, GlobalDbState, DbNode, DbNodeType};
 use gpui_component::label::Label;
+use crate::activity_monitor::{render_sparkline, ActivityMonitorSettings, ActivitySeries};
+use crate::connection_health::ConnectionHealthPanel;
+use crate::connection_tag::ConnectionTags;
 use crate::database_view_plugin::DatabaseViewPluginRegistry;
+use crate::schema_cache;
+use crate::prompt_variables::SelectedTreeNode;
 use one_core::{
     storage::{ActiveConnections, GlobalStorageState, StoredConnection},
 };
-use one_core::storage::DatabaseType;
+use one_core::storage::{ConnectionEnvironment, DatabaseType};
 use one_core::utils::debouncer::Debouncer;
 
 // ============================================================================
@@ -248,6 +254,10 @@ pub enum DbTreeViewEvent {
     CloseConnection { node_id: String },
     /// 删除连接
     DeleteConnection { node_id: String },
+    /// 复制连接并打开编辑器
+    DuplicateConnection { node_id: String },
+    /// 打开会话变量
+    OpenSessionVariables { node_id: String },
     /// 新建数据库
     CreateDatabase { node_id: String },
     /// 编辑数据库
@@ -272,6 +282,40 @@ pub enum DbTreeViewEvent {
     RunSqlFile { node_id: String },
     /// 转储SQL文件（导出结构和/或数据）
     DumpSqlFile { node_id: String, mode: SqlDumpMode },
+    /// 打开回收站
+    OpenTrash { node_id: String },
+    /// 打开扩展管理
+    OpenExtensions { node_id: String },
+    /// 打开维护面板
+    OpenMaintenance { node_id: String },
+    /// 查看表的建表语句
+    ViewTableDdl { node_id: String },
+    /// AI 生成表结构文档
+    GenerateSchemaDoc { node_id: String },
+    /// AI 生成测试数据
+    GenerateTestData { node_id: String },
+    /// AI 识别个人信息列并生成脱敏语句
+    AnonymizeTable { node_id: String },
+    /// 查看包规范和包体源码
+    ViewPackageSource { node_id: String },
+    /// 重新编译包
+    CompilePackage { node_id: String },
+    /// AI 解释存储过程、函数或触发器
+    ExplainRoutine { node_id: String },
+    /// 查看物化视图定义
+    ViewMaterializedViewDefinition { node_id: String },
+    /// 刷新物化视图数据，concurrently 时不阻塞读取
+    RefreshMaterializedView { node_id: String, concurrently: bool },
+    /// 在编辑器中打开同义词或链接服务器表的查询（四段式名称）
+    OpenObjectQuery { node_id: String },
+    /// 在编辑器中打开事件定义（删除后重建）
+    EditEvent { node_id: String },
+    /// 在编辑器中打开新建事件模板
+    CreateEvent { node_id: String },
+    /// 启用或禁用事件
+    SetEventEnabled { node_id: String, enabled: bool },
+    /// 删除事件
+    DropEvent { node_id: String },
 }
 
 /// 根据节点类型获取图标（公共函数，可被其他模块复用）
@@ -279,15 +323,19 @@ pub fn get_icon_for_node_type(node_type: &DbNodeType, _theme: &gpui_component::T
     match node_type {
         DbNodeType::Connection => IconName::MySQLLineColor.color().with_size(Size::Large),
         DbNodeType::Schema => IconName::Schema.color(),
-        DbNodeType::Database => Icon::from(IconName::Database).color().with_size(Size::Size(px(20.))),
+        DbNodeType::Database | DbNodeType::Catalog => Icon::from(IconName::Database).color().with_size(Size::Size(px(20.))),
         DbNodeType::Table => Icon::from(IconName::Table).color(),
-        DbNodeType::View => Icon::from(IconName::View).color(),
+        DbNodeType::View | DbNodeType::MaterializedView | DbNodeType::Synonym => Icon::from(IconName::View).color(),
+        DbNodeType::LinkedServer => Icon::from(IconName::Database).color(),
+        DbNodeType::LinkedTable => Icon::from(IconName::Table).color(),
         DbNodeType::Function => Icon::from(IconName::Function).color(),
         DbNodeType::Procedure => Icon::from(IconName::Procedure).color(),
+        DbNodeType::Package => Icon::from(IconName::Procedure).color(),
         DbNodeType::Column => Icon::from(IconName::Column).color(),
         DbNodeType::Index => Icon::from(IconName::Index).color(),
         DbNodeType::Trigger => Icon::from(IconName::Trigger).color(),
         DbNodeType::Sequence => Icon::from(IconName::Sequence).color(),
+        DbNodeType::Event => Icon::from(IconName::Calendar).color(),
         DbNodeType::NamedQuery => Icon::from(IconName::Query).color(),
         _ => IconName::File.color()
     }
@@ -328,6 +376,12 @@ pub struct DbTreeView {
     db_filter_search: HashMap<String, String>,
     // 数据库筛选列表状态：连接ID -> ListState
     db_filter_list_states: HashMap<String, Entity<ListState<DatabaseListDelegate>>>,
+    // 连接所属环境：连接ID -> 环境
+    connection_environments: HashMap<String, ConnectionEnvironment>,
+    // 连接活动采样：连接ID -> 每秒查询数序列
+    activity: HashMap<String, ActivitySeries>,
+    _activity_task: Task<()>,
+    _status_task: Task<()>,
 
     _sub: Subscription
 }
@@ -356,6 +410,7 @@ impl DbTreeView {
         let mut init_nodes = vec![];
         let mut workspace_id = None;
         let mut unselected_databases_map = HashMap::new();
+        let mut connection_environments = HashMap::new();
 
         if connections.is_empty() {
             let node =  DbNode::new("root", "No Database Connected", DbNodeType::Connection, "".to_string(), DatabaseType::MySQL);
@@ -383,6 +438,8 @@ impl DbTreeView {
                     unselected_databases_map.insert(id.clone(), Some(selected));
                 }
 
+                connection_environments.insert(id.clone(), conn_config.environment());
+                ConnectionTags::register(conn, conn_config.environment(), cx);
                 let node = DbNode::new(id.clone(), conn_config.name.to_string(), DbNodeType::Connection, id.clone(), conn_config.database_type);
                 db_nodes.insert(id, node.clone());
                 init_nodes.push(node);
@@ -420,6 +477,9 @@ impl DbTreeView {
             }
         });
 
+        let _activity_task = Self::spawn_activity_poller(cx);
+        let _status_task = Self::spawn_status_watcher(cx);
+
         Self {
             focus_handle,
             tree_state,
@@ -438,10 +498,99 @@ impl DbTreeView {
             selected_databases: unselected_databases_map,
             db_filter_search: HashMap::new(),
             db_filter_list_states: HashMap::new(),
+            connection_environments,
+            activity: HashMap::new(),
+            _activity_task,
+            _status_task,
             _sub
         }
     }
 
+    /// 按设置的间隔轮询已连接节点的活动计数；关闭监控时清空已有的采样
+    fn spawn_activity_poller(cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            loop {
+                let Ok(settings) = cx.update(|cx| ActivityMonitorSettings::global(cx)) else {
+                    return;
+                };
+                Timer::after(settings.interval()).await;
+
+                if !settings.enabled {
+                    let cleared = this.update(cx, |this, cx| {
+                        if !this.activity.is_empty() {
+                            this.activity.clear();
+                            cx.notify();
+                        }
+                    });
+                    if cleared.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+
+                let Ok(connection_ids) = this.update(cx, |this, cx| this.active_connection_ids(cx)) else {
+                    return;
+                };
+                let Ok(global_state) = cx.update(|cx| cx.global::<GlobalDbState>().clone()) else {
+                    return;
+                };
+                for connection_id in connection_ids {
+                    let counter = global_state.activity_counter(cx, connection_id.clone()).await;
+                    let now = Instant::now();
+                    let updated = this.update(cx, |this, cx| {
+                        match counter {
+                            Ok(Some(counter)) => this.activity.entry(connection_id).or_default().record(counter, now),
+                            Ok(None) => {
+                                this.activity.remove(&connection_id);
+                            }
+                            Err(e) => {
+                                trace!("activity counter failed for {}: {}", connection_id, e);
+                                this.activity.remove(&connection_id);
+                            }
+                        }
+                        cx.notify();
+                    });
+                    if updated.is_err() {
+                        return;
+                    }
+                }
+            }
+        })
+    }
+
+    /// 连接断开、重连或重连失败时重绘连接节点的状态指示
+    fn spawn_status_watcher(cx: &mut Context<Self>) -> Task<()> {
+        let mut statuses = cx.global::<GlobalDbState>().connection_manager.subscribe_statuses();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            while statuses.changed().await.is_ok() {
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    return;
+                }
+            }
+        })
+    }
+
+    /// 已连接节点的连接状态，未连接的节点不显示
+    fn connection_status(&self, node_id: &str, cx: &App) -> Option<ConnectionStatus> {
+        let node = self.db_nodes.get(node_id).filter(|node| node.node_type == DbNodeType::Connection)?;
+        let id = node.id.parse::<i64>().ok()?;
+        if !cx.global::<ActiveConnections>().is_active(id) {
+            return None;
+        }
+        cx.global::<GlobalDbState>().connection_manager.connection_status(node_id)
+    }
+
+    /// 当前树中处于连接状态的连接节点
+    fn active_connection_ids(&self, cx: &App) -> Vec<String> {
+        let active = cx.global::<ActiveConnections>();
+        self.db_nodes
+            .values()
+            .filter(|node| node.node_type == DbNodeType::Connection)
+            .filter(|node| node.id.parse::<i64>().is_ok_and(|id| active.is_active(id)))
+            .map(|node| node.id.clone())
+            .collect()
+    }
+
     /// 折叠所有节点
     pub fn collapse_all(&mut self, cx: &mut Context<Self>) {
         self.expanded_nodes.clear();
@@ -472,7 +621,7 @@ impl DbTreeView {
         let mut databases = Vec::new();
         if let Some(conn_node) = self.db_nodes.get(connection_id) {
             for child in &conn_node.children {
-                if child.node_type == DbNodeType::Database {
+                if child.node_type.is_database_level() {
                     databases.push((child.id.clone(), child.name.clone()));
                 }
             }
@@ -674,11 +823,12 @@ impl DbTreeView {
             node.children.clear();
         }
         
-        // 如果节点已展开，重新加载子节点
+        // 如果节点已展开，跳过缓存重新加载子节点
         if self.expanded_nodes.contains(&node_id) {
-            self.lazy_load_children(node_id, cx);
+            self.load_children(node_id, false, cx);
         } else {
-            // 如果节点未展开，只需重建树以更新占位符
+            // 如果节点未展开，清除缓存使下次展开时重新加载，并重建树以更新占位符
+            self.invalidate_schema_cache(&node_id, cx);
             self.rebuild_tree(cx);
         }
     }
@@ -708,8 +858,17 @@ impl DbTreeView {
         }
     }
 
-    /// 懒加载节点的子节点
+    /// 懒加载节点的子节点，优先显示持久化缓存
     fn lazy_load_children(&mut self, node_id: String, cx: &mut Context<Self>) {
+        self.load_children(node_id, true, cx);
+    }
+
+    /// 加载节点的子节点
+    ///
+    /// `use_cache` 为 true 时先显示上次保存的子节点：缓存未过期则不再访问数据库，
+    /// 否则在后台重新加载，结果有变化时再替换。为 false 时（手动刷新）清除该节点及
+    /// 其后代的缓存后直接从数据库加载。
+    fn load_children(&mut self, node_id: String, use_cache: bool, cx: &mut Context<Self>) {
         // 如果已经加载过或正在加载，跳过
         if self.loaded_children.contains(&node_id) || self.loading_nodes.contains(&node_id) {
             return;
@@ -733,24 +892,57 @@ impl DbTreeView {
 
         let global_state = cx.global::<GlobalDbState>().clone();
         let global_storage_state = cx.global::<GlobalStorageState>().clone();
+        let storage = global_storage_state.storage.clone();
         let clone_node_id = node_id.clone();
         let connection_id = node.connection_id.clone();
         let node_type = node.node_type.clone();
+        let cache_connection_id = connection_id
+            .parse::<i64>()
+            .ok()
+            .filter(|_| schema_cache::is_cacheable(&node_type));
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
+            // 已显示的缓存内容，后台刷新结果相同时不再重建子节点
+            let mut shown_cache: Option<String> = None;
+
+            if let Some(cache_conn_id) = cache_connection_id {
+                if use_cache {
+                    match schema_cache::load(storage.clone(), cache_conn_id, clone_node_id.clone(), cx).await {
+                        Ok(Some(cached)) => {
+                            trace!("DbTreeView lazy_load_children: using cached children for: {}", clone_node_id);
+                            // 连接节点需要真正建立连接才能标记为活跃
+                            let refresh = cached.stale || node_type == DbNodeType::Connection;
+                            let applied = this.update(cx, |this: &mut Self, cx| {
+                                this.loading_nodes.remove(&clone_node_id);
+                                this.apply_loaded_children(&clone_node_id, cached.children, cx);
+                            }).is_ok();
+                            if !applied || !refresh {
+                                return;
+                            }
+                            shown_cache = Some(cached.json);
+                        }
+                        Ok(None) => {}
+                        Err(e) => warn!("DbTreeView: failed to read schema cache for {}: {}", clone_node_id, e),
+                    }
+                } else if let Err(e) = schema_cache::invalidate(storage.clone(), cache_conn_id, clone_node_id.clone(), cx).await {
+                    warn!("DbTreeView: failed to invalidate schema cache for {}: {}", clone_node_id, e);
+                }
+            }
+
             // 使用 DatabasePlugin 的方法加载子节点，添加超时机制
             let children_result = global_state.load_node_children(cx, connection_id.clone(), node.clone(), global_storage_state.clone()).await;
+            let children_json = children_result
+                .as_ref()
+                .ok()
+                .and_then(|children| serde_json::to_string(children).ok());
 
-            this.update(cx, |this: &mut Self, cx| {
+            let updated = this.update(cx, |this: &mut Self, cx| {
                 // 移除加载状态
                 this.loading_nodes.remove(&clone_node_id);
 
                 match children_result {
                     Ok(children) => {
                         info!("DbTreeView lazy_load_children: loaded {} children for node: {}", children.len(), clone_node_id);
-                        // 标记为已加载，清除错误状态
-                        this.loaded_children.insert(clone_node_id.clone());
-                        this.error_nodes.remove(&clone_node_id);
 
                         // 如果是 Connection 节点，设置为活跃状态
                         if node_type == DbNodeType::Connection {
@@ -759,43 +951,27 @@ impl DbTreeView {
                             }
                         }
 
-                        // 更新节点的子节点
-                        if let Some(parent_node) = this.db_nodes.get_mut(&clone_node_id) {
-                            parent_node.children = children.clone();
-                            parent_node.children_loaded = true;
-                        }
-
-                        // 递归地将所有子节点及其后代添加到 db_nodes
-                        fn insert_nodes_recursive(
-                            db_nodes: &mut HashMap<String, DbNode>,
-                            node: &DbNode,
-                        ) {
-                            db_nodes.insert(node.id.clone(), node.clone());
-                            for child in &node.children {
-                                insert_nodes_recursive(db_nodes, child);
+                        match &shown_cache {
+                            Some(json) if children_json.as_ref() == Some(json) => {
+                                this.error_nodes.remove(&clone_node_id);
+                                cx.notify();
                             }
+                            Some(_) => {
+                                // 缓存已过时，替换为最新的子节点
+                                this.clear_node_descendants(&clone_node_id);
+                                this.apply_loaded_children(&clone_node_id, children, cx);
+                            }
+                            None => this.apply_loaded_children(&clone_node_id, children, cx),
                         }
-
-                        for child in &children {
-                            trace!("DbTreeView lazy_load_children: adding child: {} (type: {:?})", child.id, child.node_type);
-                            insert_nodes_recursive(&mut this.db_nodes, child);
-                        }
-
-                        // 检查子节点是否在 expanded_nodes 中，如果是，递归加载它们的子节点
-                        // 这确保刷新后已展开的节点能恢复其子节点
-                        let children_to_expand: Vec<String> = children
-                            .iter()
-                            .filter(|child| this.expanded_nodes.contains(&child.id))
-                            .map(|child| child.id.clone())
-                            .collect();
-
-                        // 重建树结构
-                        this.rebuild_tree(cx);
-
-                        // 触发已展开子节点的懒加载
-                        for child_id in children_to_expand {
-                            this.lazy_load_children(child_id, cx);
-                        }
+                        true
+                    }
+                    Err(e) if shown_cache.is_some() => {
+                        // 保留缓存内容，只标记错误，双击可重试
+                        warn!("DbTreeView lazy_load_children: background refresh failed for {}: {}", clone_node_id, e);
+                        this.loaded_children.remove(&clone_node_id);
+                        this.error_nodes.insert(clone_node_id.clone(), e.to_string());
+                        cx.notify();
+                        false
                     }
                     Err(e) => {
                         error!("DbTreeView lazy_load_children: failed to execute load_node_children for {}: {}", clone_node_id, e);
@@ -803,9 +979,105 @@ impl DbTreeView {
                         // 记录错误状态
                         this.error_nodes.insert(clone_node_id.clone(), e.to_string());
                         this.rebuild_tree(cx);
+                        false
                     }
                 }
-            }).ok();
+            }).unwrap_or(false);
+
+            if let (true, Some(cache_conn_id), Some(json)) = (updated, cache_connection_id, children_json) {
+                if let Err(e) = schema_cache::store(storage, cache_conn_id, clone_node_id.clone(), json, cx).await {
+                    warn!("DbTreeView: failed to write schema cache for {}: {}", clone_node_id, e);
+                }
+            }
+        }).detach();
+    }
+
+    /// 显示加载到的子节点，并继续加载其中已展开的节点
+    fn apply_loaded_children(&mut self, node_id: &str, children: Vec<DbNode>, cx: &mut Context<Self>) {
+        // 标记为已加载，清除错误状态
+        self.loaded_children.insert(node_id.to_string());
+        self.error_nodes.remove(node_id);
+
+        // 更新节点的子节点
+        if let Some(parent_node) = self.db_nodes.get_mut(node_id) {
+            parent_node.children = children.clone();
+            parent_node.children_loaded = true;
+        }
+
+        // 递归地将所有子节点及其后代添加到 db_nodes
+        fn insert_nodes_recursive(
+            db_nodes: &mut HashMap<String, DbNode>,
+            node: &DbNode,
+        ) {
+            db_nodes.insert(node.id.clone(), node.clone());
+            for child in &node.children {
+                insert_nodes_recursive(db_nodes, child);
+            }
+        }
+
+        for child in &children {
+            trace!("DbTreeView lazy_load_children: adding child: {} (type: {:?})", child.id, child.node_type);
+            insert_nodes_recursive(&mut self.db_nodes, child);
+        }
+
+        // 检查子节点是否在 expanded_nodes 中，如果是，递归加载它们的子节点
+        // 这确保刷新后已展开的节点能恢复其子节点
+        let children_to_expand: Vec<String> = children
+            .iter()
+            .filter(|child| self.expanded_nodes.contains(&child.id))
+            .map(|child| child.id.clone())
+            .collect();
+
+        // 重建树结构
+        self.rebuild_tree(cx);
+
+        // 触发已展开子节点的懒加载
+        for child_id in children_to_expand {
+            self.lazy_load_children(child_id, cx);
+        }
+    }
+
+    /// 删除节点及其所有后代的缓存
+    fn invalidate_schema_cache(&self, node_id: &str, cx: &mut Context<Self>) {
+        let Some(connection_id) = self.db_nodes.get(node_id)
+            .and_then(|node| node.connection_id.parse::<i64>().ok()) else {
+            return;
+        };
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let node_id = node_id.to_string();
+
+        cx.spawn(async move |_this, cx: &mut AsyncApp| {
+            if let Err(e) = schema_cache::invalidate(storage, connection_id, node_id.clone(), cx).await {
+                warn!("DbTreeView: failed to invalidate schema cache for {}: {}", node_id, e);
+            }
+        }).detach();
+    }
+
+    /// 把节点当前的子节点写入缓存（本地增删节点后保持缓存与树一致）
+    fn persist_children_cache(&self, node_id: &str, cx: &mut Context<Self>) {
+        let Some(node) = self.db_nodes.get(node_id) else {
+            return;
+        };
+        let Ok(connection_id) = node.connection_id.parse::<i64>() else {
+            return;
+        };
+        if !node.children_loaded || !schema_cache::is_cacheable(&node.node_type) {
+            return;
+        }
+        let json = match serde_json::to_string(&node.children) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("DbTreeView: failed to serialize children of {}: {}", node_id, e);
+                return;
+            }
+        };
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let node_id = node_id.to_string();
+
+        cx.spawn(async move |_this, cx: &mut AsyncApp| {
+            if let Err(e) = schema_cache::store(storage, connection_id, node_id.clone(), json, cx).await {
+                warn!("DbTreeView: failed to write schema cache for {}: {}", node_id, e);
+            }
         }).detach();
     }
 
@@ -870,7 +1142,7 @@ impl DbTreeView {
         };
 
         // 如果是数据库节点，检查是否被选中
-        if node.node_type == DbNodeType::Database {
+        if node.node_type.is_database_level() {
             if let Some(conn_id) = conn_id {
                 let is_selected = match selected_databases.get(conn_id) {
                     None => true,
@@ -933,10 +1205,16 @@ impl DbTreeView {
                 DbNodeType::Table
                 | DbNodeType::TablesFolder
                 | DbNodeType::ViewsFolder
+                | DbNodeType::MaterializedViewsFolder
+                | DbNodeType::SynonymsFolder
+                | DbNodeType::LinkedServersFolder
+                | DbNodeType::LinkedServer
                 | DbNodeType::ColumnsFolder
                 | DbNodeType::IndexesFolder
                 | DbNodeType::FunctionsFolder
                 | DbNodeType::ProceduresFolder
+                | DbNodeType::PackagesFolder
+                | DbNodeType::EventsFolder
                 | DbNodeType::TriggersFolder
                 | DbNodeType::SequencesFolder
                 | DbNodeType::QueriesFolder
@@ -1056,13 +1334,15 @@ impl DbTreeView {
                     IconName::Database.color().with_size(Size::Large)
                 }
             }
-            Some(DbNodeType::Database) => Icon::from(IconName::Database).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::Database) | Some(DbNodeType::Catalog) => Icon::from(IconName::Database).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Schema) => Icon::from(IconName::Schema).color().with_size(Size::Size(px(20.))),
 
             Some(DbNodeType::TablesFolder) => Icon::from(IconName::FolderTables).color().with_size(Size::Size(px(20.))),
-            Some(DbNodeType::ViewsFolder) => Icon::from(IconName::FolderViews).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::ViewsFolder) | Some(DbNodeType::MaterializedViewsFolder) => Icon::from(IconName::FolderViews).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::FunctionsFolder) => Icon::from(IconName::FolderFunctions).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::ProceduresFolder) => Icon::from(IconName::FolderProcedures).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::PackagesFolder) | Some(DbNodeType::EventsFolder) |
+            Some(DbNodeType::SynonymsFolder) | Some(DbNodeType::LinkedServersFolder) => Icon::from(IconName::Folder).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::TriggersFolder) => Icon::from(IconName::FolderTriggers).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::ForeignKeysFolder) => Icon::from(IconName::FolderForeignKeys).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::ChecksFolder) => Icon::from(IconName::FolderCheckConstraints).color().with_size(Size::Size(px(20.))),
@@ -1072,9 +1352,12 @@ impl DbTreeView {
             Some(DbNodeType::SequencesFolder) => Icon::from(IconName::FolderSequences).color().with_size(Size::Size(px(20.))),
 
             Some(DbNodeType::Table) => Icon::from(IconName::Table).color().with_size(Size::Size(px(20.))),
-            Some(DbNodeType::View) => Icon::from(IconName::View).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::View) | Some(DbNodeType::MaterializedView) | Some(DbNodeType::Synonym) => Icon::from(IconName::View).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::LinkedServer) => Icon::from(IconName::Database).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::LinkedTable) => Icon::from(IconName::Table).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Function) => Icon::from(IconName::Function).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Procedure) => Icon::from(IconName::Procedure).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::Package) => Icon::from(IconName::Procedure).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Column) => {
                 let is_primary_key = node
                     .and_then(|n| n.metadata.as_ref())
@@ -1091,6 +1374,7 @@ impl DbTreeView {
             Some(DbNodeType::ForeignKey) => Icon::from(IconName::GoldKey).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Trigger) => Icon::from(IconName::Trigger).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Sequence) => Icon::from(IconName::Sequence).color().with_size(Size::Size(px(20.))),
+            Some(DbNodeType::Event) => Icon::from(IconName::Calendar).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::Check) => Icon::from(IconName::CheckConstraint).color().with_size(Size::Size(px(20.))),
             Some(DbNodeType::NamedQuery) => Icon::from(IconName::Query).color().with_size(Size::Size(px(20.))),
             _ => Icon::from(IconName::Loader).with_size(Size::Size(px(14.))),
@@ -1125,7 +1409,7 @@ impl DbTreeView {
                         });
                     }
                 }
-                DbNodeType::View => {
+                DbNodeType::View | DbNodeType::MaterializedView => {
                     // 查找所属数据库
                     if let Some(database) = self.find_parent_database(&node.id) {
                         info!("DbTreeView: opening view data tab: {}.{}", database, node.name);
@@ -1134,6 +1418,21 @@ impl DbTreeView {
                         });
                     }
                 }
+                DbNodeType::Package => {
+                    cx.emit(DbTreeViewEvent::ViewPackageSource {
+                        node_id: node.id.clone()
+                    });
+                }
+                DbNodeType::Synonym | DbNodeType::LinkedTable => {
+                    cx.emit(DbTreeViewEvent::OpenObjectQuery {
+                        node_id: node.id.clone()
+                    });
+                }
+                DbNodeType::Event => {
+                    cx.emit(DbTreeViewEvent::EditEvent {
+                        node_id: node.id.clone()
+                    });
+                }
                 DbNodeType::NamedQuery => {
                     // 打开命名查询
                     info!("DbTreeView: opening named query: {}", node.name);
@@ -1141,11 +1440,13 @@ impl DbTreeView {
                         node_id: node.id.clone()
                     });
                 }
-                DbNodeType::Connection | DbNodeType::Database | DbNodeType::Schema |
+                DbNodeType::Connection | DbNodeType::Database | DbNodeType::Catalog | DbNodeType::Schema |
                 DbNodeType::ColumnsFolder | DbNodeType::IndexesFolder |
                 DbNodeType::FunctionsFolder | DbNodeType::ProceduresFolder |
+                DbNodeType::PackagesFolder | DbNodeType::EventsFolder |
                 DbNodeType::TriggersFolder | DbNodeType::QueriesFolder |
-                DbNodeType::TablesFolder | DbNodeType::ViewsFolder  => {
+                DbNodeType::TablesFolder | DbNodeType::ViewsFolder | DbNodeType::MaterializedViewsFolder |
+                DbNodeType::SynonymsFolder | DbNodeType::LinkedServersFolder | DbNodeType::LinkedServer => {
                     let node_id = item.id.to_string();
                     let is_expanded = self.expanded_nodes.contains(&node_id);
                     
@@ -1177,7 +1478,8 @@ impl DbTreeView {
     
     fn handle_item_click(&mut self, item: TreeItem, cx: &mut Context<Self>) {
         self.selected_item = Some(item.clone());
-        if self.db_nodes.contains_key(item.id.as_ref()) {
+        if let Some(node) = self.db_nodes.get(item.id.as_ref()) {
+            SelectedTreeNode::set(node.clone(), cx);
             // 发出节点选择事件
             cx.emit(DbTreeViewEvent::NodeSelected {
                 node_id: item.id.to_string()
@@ -1187,6 +1489,14 @@ impl DbTreeView {
     }
 
     /// 获取节点信息（公开方法）
+    /// 获取连接所属环境
+    pub fn connection_environment(&self, connection_id: &str) -> ConnectionEnvironment {
+        self.connection_environments
+            .get(connection_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn get_node(&self, node_id: &str) -> Option<&DbNode> {
         self.db_nodes.get(node_id)
     }
@@ -1216,6 +1526,7 @@ impl DbTreeView {
         self.loaded_children.remove(connection_id);
         self.loading_nodes.remove(connection_id);
         self.error_nodes.remove(connection_id);
+        self.activity.remove(connection_id);
         
         // 重建树以反映变化
         self.rebuild_tree(cx);
@@ -1277,6 +1588,7 @@ impl DbTreeView {
             conn_node.children.push(db_node);
             conn_node.children.sort();
         }
+        self.persist_children_cache(connection_id, cx);
 
         // 重建树以反映变化
         self.rebuild_tree(cx);
@@ -1289,6 +1601,7 @@ impl DbTreeView {
         let db_node_id = format!("{}:{}", connection_id, database_name);
 
         // 清理数据库节点的所有后代
+        self.invalidate_schema_cache(&db_node_id, cx);
         self.clear_node_descendants(&db_node_id);
 
         // 从 db_nodes 中移除
@@ -1304,6 +1617,7 @@ impl DbTreeView {
         if let Some(conn_node) = self.db_nodes.get_mut(connection_id) {
             conn_node.children.retain(|child| child.id != db_node_id);
         }
+        self.persist_children_cache(connection_id, cx);
 
         // 重建树以反映变化
         self.rebuild_tree(cx);
@@ -1342,6 +1656,7 @@ impl DbTreeView {
             db_node.children.push(schema_node);
             db_node.children.sort();
         }
+        self.persist_children_cache(&db_node_id, cx);
 
         // 重建树以反映变化
         self.rebuild_tree(cx);
@@ -1355,6 +1670,7 @@ impl DbTreeView {
         let schema_node_id = format!("{}:{}:{}", connection_id, database_name, schema_name);
 
         // 清理 Schema 节点的所有后代
+        self.invalidate_schema_cache(&schema_node_id, cx);
         self.clear_node_descendants(&schema_node_id);
 
         // 从 db_nodes 中移除
@@ -1370,6 +1686,7 @@ impl DbTreeView {
         if let Some(db_node) = self.db_nodes.get_mut(&db_node_id) {
             db_node.children.retain(|child| child.id != schema_node_id);
         }
+        self.persist_children_cache(&db_node_id, cx);
 
         // 重建树以反映变化
         self.rebuild_tree(cx);
@@ -1380,6 +1697,7 @@ impl DbTreeView {
         info!("Removing table node: {}", node_id);
 
         // 清理表节点的所有后代
+        self.invalidate_schema_cache(node_id, cx);
         self.clear_node_descendants(node_id);
 
         // 获取父节点ID并从父节点的子节点列表中移除
@@ -1388,6 +1706,7 @@ impl DbTreeView {
                 if let Some(parent_node) = self.db_nodes.get_mut(parent_context) {
                     parent_node.children.retain(|child| child.id != node_id);
                 }
+                self.persist_children_cache(parent_context, cx);
             }
         }
 
@@ -1409,11 +1728,13 @@ impl DbTreeView {
         info!("Removing view node: {}", node_id);
 
         // 获取父节点ID并从父节点的子节点列表中移除
+        self.invalidate_schema_cache(node_id, cx);
         if let Some(view_node) = self.db_nodes.get(node_id).cloned() {
             if let Some(parent_context) = &view_node.parent_context {
                 if let Some(parent_node) = self.db_nodes.get_mut(parent_context) {
                     parent_node.children.retain(|child| child.id != node_id);
                 }
+                self.persist_children_cache(parent_context, cx);
             }
         }
 
@@ -1436,7 +1757,7 @@ impl DbTreeView {
             // 从选中的节点ID中提取数据库名
             if let Some(node) = self.db_nodes.get(item.id.as_ref()) {
                 return match node.node_type {
-                    DbNodeType::Database => {
+                    DbNodeType::Database | DbNodeType::Catalog => {
                         Some(node.name.clone())
                     }
                     _ => {
@@ -1455,7 +1776,7 @@ impl DbTreeView {
         let mut current_id = node_id.to_string();
 
         while let Some(node) = self.db_nodes.get(&current_id) {
-            if node.node_type == DbNodeType::Database {
+            if node.node_type.is_database_level() {
                 return Some(node.name.clone());
             }
 
@@ -1504,6 +1825,23 @@ impl Render for DbTreeView {
                                 .small()
                                 .w_full())
                     )
+                    .child(
+                        Button::new("connection-health")
+                            .icon(IconName::Heart)
+                            .ghost()
+                            .small()
+                            .tooltip("连接健康")
+                            .on_click(|_, window, cx| {
+                                let panel = cx.new(ConnectionHealthPanel::new);
+                                window.open_dialog(cx, move |dialog, _window, _cx| {
+                                    dialog
+                                        .title("连接健康")
+                                        .child(panel.clone())
+                                        .width(px(1000.0))
+                                        .on_cancel(|_, _window, _cx| true)
+                                });
+                            })
+                    )
                     .child(
                         Button::new("collapse-all")
                             .icon(IconName::ChevronsUpDown)
@@ -1567,16 +1905,19 @@ impl Render for DbTreeView {
                                             // 判断是否是分组类型（Folder 类型）
                                             let is_folder_type = matches!(
                                                 node_type,
-                                                Some(DbNodeType::TablesFolder) | Some(DbNodeType::ViewsFolder) |
+                                                Some(DbNodeType::TablesFolder) | Some(DbNodeType::ViewsFolder) | Some(DbNodeType::MaterializedViewsFolder) |
                                                 Some(DbNodeType::FunctionsFolder) | Some(DbNodeType::ProceduresFolder) |
+                                                Some(DbNodeType::PackagesFolder) | Some(DbNodeType::EventsFolder) |
                                                 Some(DbNodeType::TriggersFolder) | Some(DbNodeType::QueriesFolder) |
-                                                Some(DbNodeType::ColumnsFolder) | Some(DbNodeType::IndexesFolder)
+                                                Some(DbNodeType::ColumnsFolder) | Some(DbNodeType::IndexesFolder) |
+                                                Some(DbNodeType::SynonymsFolder) | Some(DbNodeType::LinkedServersFolder) |
+                                                Some(DbNodeType::LinkedServer)
                                             );
 
                                             // Connection、Database、Schema 只能通过双击展开，不响应箭头点击
                                             let requires_double_click = matches!(
                                                 node_type,
-                                                Some(DbNodeType::Connection) | Some(DbNodeType::Database) | Some(DbNodeType::Schema)
+                                                Some(DbNodeType::Connection) | Some(DbNodeType::Database) | Some(DbNodeType::Catalog) | Some(DbNodeType::Schema)
                                             );
 
                                             // 同步节点展开状态
@@ -1625,11 +1966,22 @@ impl Render for DbTreeView {
                                         let node_id_clone = node_id.clone();
                                         trace!("node_id: {}, item: {}", &node_id, &item.label);
 
-                                        let (is_loading, error_msg, db_filter_list) = view.update(cx, |this, _cx| {
+                                        let (is_loading, error_msg, db_filter_list, activity, is_invalid, connection_tag, connection_status) = view.update(cx, |this, cx| {
                                             let is_loading = this.loading_nodes.contains(&node_id);
                                             let error_msg = this.error_nodes.get(&node_id);
                                             let list_state = this.db_filter_list_states.get(&node_id).cloned();
-                                            (is_loading, error_msg.cloned(), list_state)
+                                            let activity = this.activity.get(&node_id).filter(|series| !series.rates().is_empty()).cloned();
+                                            // Oracle 等数据库中编译失败的视图、例程和包
+                                            let is_invalid = this.db_nodes.get(&node_id)
+                                                .and_then(|node| node.metadata.as_ref())
+                                                .and_then(|metadata| metadata.get("status"))
+                                                .is_some_and(|status| status == "INVALID");
+                                            // 连接节点显示颜色标签和非开发环境的徽标
+                                            let connection_tag = this.db_nodes.get(&node_id)
+                                                .filter(|node| node.node_type == DbNodeType::Connection)
+                                                .map(|_| (ConnectionTags::hsla(&node_id, cx), this.connection_environment(&node_id)));
+                                            let connection_status = this.connection_status(&node_id, cx);
+                                            (is_loading, error_msg.cloned(), list_state, activity, is_invalid, connection_tag, connection_status)
                                         });
 
                                         let view_for_filter = view.clone();
@@ -1698,6 +2050,79 @@ impl Render for DbTreeView {
                                                                 Tooltip::new(label_for_tooltip.clone()).build(window, cx)
                                                             })
                                                     )
+                                                    .when_some(connection_tag, |this, (color, environment)| {
+                                                        let badge_color = color.unwrap_or(cx.theme().muted_foreground);
+                                                        this.when_some(color, |this, color| {
+                                                            this.child(
+                                                                div()
+                                                                    .flex_shrink_0()
+                                                                    .size(px(8.))
+                                                                    .rounded_full()
+                                                                    .bg(color)
+                                                            )
+                                                        })
+                                                        .when(environment != ConnectionEnvironment::Development, |this| {
+                                                            this.child(
+                                                                div()
+                                                                    .flex_shrink_0()
+                                                                    .px_1()
+                                                                    .rounded(px(3.))
+                                                                    .text_xs()
+                                                                    .border_1()
+                                                                    .border_color(badge_color)
+                                                                    .text_color(badge_color)
+                                                                    .child(environment.label())
+                                                            )
+                                                        })
+                                                    })
+                                                    .when_some(connection_status, |this, status| {
+                                                        let (color, message) = match status {
+                                                            ConnectionStatus::Connected => (cx.theme().success, "已连接".to_string()),
+                                                            ConnectionStatus::Reconnecting { attempt } => (
+                                                                cx.theme().warning,
+                                                                format!("连接已断开，正在重连（已尝试 {} 次）", attempt),
+                                                            ),
+                                                            ConnectionStatus::Failed(error) => (cx.theme().danger, format!("重连失败：{}", error)),
+                                                        };
+                                                        this.child(
+                                                            div()
+                                                                .id(SharedString::from(format!("status-{}", ix)))
+                                                                .flex_shrink_0()
+                                                                .size(px(6.))
+                                                                .rounded_full()
+                                                                .bg(color)
+                                                                .tooltip(move |window, cx| {
+                                                                    Tooltip::new(message.clone()).build(window, cx)
+                                                                })
+                                                        )
+                                                    })
+                                                    .when(is_invalid, |this| {
+                                                        this.child(
+                                                            div()
+                                                                .id(SharedString::from(format!("invalid-{}", ix)))
+                                                                .flex_shrink_0()
+                                                                .child(
+                                                                    Icon::new(IconName::TriangleAlert)
+                                                                        .with_size(Size::Small)
+                                                                        .text_color(cx.theme().danger)
+                                                                )
+                                                                .tooltip(|window, cx| {
+                                                                    Tooltip::new("编译无效 (INVALID)，需要重新编译").build(window, cx)
+                                                                })
+                                                        )
+                                                    })
+                                                    .when_some(activity, |this, series| {
+                                                        let rate = series.latest().unwrap_or_default();
+                                                        this.child(
+                                                            div()
+                                                                .id(SharedString::from(format!("activity-{}", ix)))
+                                                                .flex_shrink_0()
+                                                                .child(render_sparkline(&series, cx.theme().success))
+                                                                .tooltip(move |window, cx| {
+                                                                    Tooltip::new(format!("{:.1} 次查询/秒", rate)).build(window, cx)
+                                                                })
+                                                        )
+                                                    })
                                                     .when_some(db_count, |this, (selected, total)| {
                                                         if total > 0 {
                                                             let view_open = view_for_filter.clone();
@@ -1824,15 +2249,23 @@ impl Render for DbTreeView {
                                                             match node.node_type {
                                                                 DbNodeType::Connection => {
                                                                     menu = menu
-                                                                        .item(Self::create_menu_item(&node_id_clone, "运行SQL文件".to_string(), &view_clone, window, |n| DbTreeViewEvent::RunSqlFile { node_id: n }))
+                                                                        .item(Self::create_menu_item(&node_id_clone, "运行SQL文件".to_string(), &view_clone, window, |n| DbTreeViewEvent::RunSqlFile { node_id: n }));
+                                                                    let supports_session_variables = cx.global::<GlobalDbState>()
+                                                                        .get_plugin(&node.database_type)
+                                                                        .is_ok_and(|p| p.build_list_session_variables_sql().is_some());
+                                                                    if supports_session_variables {
+                                                                        menu = menu.item(Self::create_menu_item(&node_id_clone, "会话变量".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenSessionVariables { node_id: n }));
+                                                                    }
+                                                                    menu = menu
                                                                         .separator()
                                                                         .item(Self::create_menu_item(&node_id_clone, "关闭连接".to_string(), &view_clone, window, |n| DbTreeViewEvent::CloseConnection { node_id: n }))
                                                                         .separator()
+                                                                        .item(Self::create_menu_item(&node_id_clone, "复制连接".to_string(), &view_clone, window, |n| DbTreeViewEvent::DuplicateConnection { node_id: n }))
                                                                         .item(Self::create_menu_item(&node_id_clone, "删除连接".to_string(), &view_clone, window, |n| DbTreeViewEvent::DeleteConnection { node_id: n }))
                                                                         .separator()
                                                                         .item(Self::create_menu_item(&node_id_clone, "新建数据库".to_string(), &view_clone, window, |n| DbTreeViewEvent::CreateDatabase { node_id: n }))
                                                                 }
-                                                                DbNodeType::Database => {
+                                                                DbNodeType::Database | DbNodeType::Catalog => {
                                                                     let node_id_for_menu = node_id_clone.clone();
 
                                                                     let capabilities = {
@@ -1905,10 +2338,28 @@ impl Render for DbTreeView {
                                                                     if capabilities.supports_drop_database {
                                                                         menu = menu.item(Self::create_menu_item(&node_id_for_menu, "删除数据库".to_string(), &view_clone, window, |n| DbTreeViewEvent::DeleteDatabase { node_id: n.clone() }));
                                                                     }
+                                                                    let supports_trash = cx.global::<GlobalDbState>()
+                                                                        .get_plugin(&node.database_type)
+                                                                        .map(|p| p.supports_trash())
+                                                                        .unwrap_or(false);
+                                                                    if supports_trash {
+                                                                        menu = menu.item(Self::create_menu_item(&node_id_for_menu, "回收站".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenTrash { node_id: n.clone() }));
+                                                                    }
+                                                                    let supports_extensions = cx.global::<GlobalDbState>()
+                                                                        .get_plugin(&node.database_type)
+                                                                        .map(|p| p.supports_extensions())
+                                                                        .unwrap_or(false);
+                                                                    if supports_extensions {
+                                                                        menu = menu.item(Self::create_menu_item(&node_id_for_menu, "扩展".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenExtensions { node_id: n.clone() }));
+                                                                    }
+                                                                    if capabilities.supports_maintenance {
+                                                                        menu = menu.item(Self::create_menu_item(&node_id_for_menu, "维护".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenMaintenance { node_id: n.clone() }));
+                                                                    }
 
                                                                     menu = menu.separator()
                                                                         .item(Self::create_menu_item(&node_id_for_menu, "导入数据".to_string(), &view_clone, window, |n| DbTreeViewEvent::ImportData { node_id: n.clone() }))
                                                                         .item(Self::create_menu_item(&node_id_for_menu, "导出数据库".to_string(), &view_clone, window, |n| DbTreeViewEvent::ExportData { node_id: n }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "AI 生成文档".to_string(), &view_clone, window, |n| DbTreeViewEvent::GenerateSchemaDoc { node_id: n }))
                                                                         .separator();
                                                                 }
                                                                 DbNodeType::Table => {
@@ -1924,6 +2375,7 @@ impl Render for DbTreeView {
                                                                     menu = menu
                                                                         .item(Self::create_menu_item(&node_id_for_menu, "查看表数据".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenTableData { node_id: n.clone() }))
                                                                         .item(Self::create_menu_item(&node_id_for_menu, "设计表".to_string(), &view_clone, window, |n| DbTreeViewEvent::DesignTable { node_id: n.clone() }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "查看 DDL".to_string(), &view_clone, window, |n| DbTreeViewEvent::ViewTableDdl { node_id: n.clone() }))
                                                                         .separator();
 
                                                                     if capabilities.supports_rename_table {
@@ -1941,6 +2393,9 @@ impl Render for DbTreeView {
                                                                     if capabilities.supports_table_export {
                                                                         menu = menu.item(Self::create_menu_item(&node_id_for_menu, "导出表".to_string(), &view_clone, window, |n| DbTreeViewEvent::ExportData { node_id: n }));
                                                                     }
+                                                                    menu = menu.item(Self::create_menu_item(&node_id_for_menu, "AI 生成文档".to_string(), &view_clone, window, |n| DbTreeViewEvent::GenerateSchemaDoc { node_id: n }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "AI 生成测试数据".to_string(), &view_clone, window, |n| DbTreeViewEvent::GenerateTestData { node_id: n }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "AI 数据脱敏".to_string(), &view_clone, window, |n| DbTreeViewEvent::AnonymizeTable { node_id: n }));
                                                                     menu = menu.separator();
                                                                 }
                                                                 DbNodeType::View => {
@@ -1952,6 +2407,24 @@ impl Render for DbTreeView {
                                                                         .item(Self::create_menu_item(&node_id_for_menu, "删除视图".to_string(), &view_clone, window, |n| DbTreeViewEvent::DeleteView { node_id: n }))
                                                                         .separator();
                                                                 }
+                                                                DbNodeType::MaterializedView => {
+                                                                    let node_id_for_menu = node_id_clone.clone();
+
+                                                                    menu = menu
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "查看视图数据".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenViewData { node_id: n.clone() }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "查看定义".to_string(), &view_clone, window, |n| DbTreeViewEvent::ViewMaterializedViewDefinition { node_id: n }))
+                                                                        .separator()
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "刷新数据".to_string(), &view_clone, window, |n| DbTreeViewEvent::RefreshMaterializedView { node_id: n, concurrently: false }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "并发刷新数据 (CONCURRENTLY)".to_string(), &view_clone, window, |n| DbTreeViewEvent::RefreshMaterializedView { node_id: n, concurrently: true }))
+                                                                        .separator();
+                                                                }
+                                                                DbNodeType::Synonym | DbNodeType::LinkedTable => {
+                                                                    let node_id_for_menu = node_id_clone.clone();
+
+                                                                    menu = menu
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "查询数据".to_string(), &view_clone, window, |n| DbTreeViewEvent::OpenObjectQuery { node_id: n }))
+                                                                        .separator();
+                                                                }
                                                                 DbNodeType::Schema => {
                                                                     let node_id_for_menu = node_id_clone.clone();
 
@@ -1994,6 +2467,45 @@ impl Render for DbTreeView {
                                                                         .item(Self::create_menu_item(&node_id_for_menu, "删除查询".to_string(), &view_clone, window, |n| DbTreeViewEvent::DeleteQuery { node_id: n }))
                                                                         .separator();
                                                                 }
+                                                                DbNodeType::Package => {
+                                                                    let node_id_for_menu = node_id_clone.clone();
+
+                                                                    menu = menu
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "查看源码".to_string(), &view_clone, window, |n| DbTreeViewEvent::ViewPackageSource { node_id: n.clone() }))
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "编译".to_string(), &view_clone, window, |n| DbTreeViewEvent::CompilePackage { node_id: n }))
+                                                                        .separator();
+                                                                }
+                                                                DbNodeType::EventsFolder => {
+                                                                    let node_id_for_menu = node_id_clone.clone();
+
+                                                                    menu = menu
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "新建事件".to_string(), &view_clone, window, |n| DbTreeViewEvent::CreateEvent { node_id: n }))
+                                                                        .separator();
+                                                                }
+                                                                DbNodeType::Event => {
+                                                                    let node_id_for_menu = node_id_clone.clone();
+                                                                    let enabled = node.metadata.as_ref()
+                                                                        .and_then(|m| m.get("enabled"))
+                                                                        .is_some_and(|v| v == "true");
+
+                                                                    menu = menu
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "编辑事件".to_string(), &view_clone, window, |n| DbTreeViewEvent::EditEvent { node_id: n }))
+                                                                        .item(if enabled {
+                                                                            Self::create_menu_item(&node_id_for_menu, "禁用事件".to_string(), &view_clone, window, |n| DbTreeViewEvent::SetEventEnabled { node_id: n, enabled: false })
+                                                                        } else {
+                                                                            Self::create_menu_item(&node_id_for_menu, "启用事件".to_string(), &view_clone, window, |n| DbTreeViewEvent::SetEventEnabled { node_id: n, enabled: true })
+                                                                        })
+                                                                        .separator()
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "删除事件".to_string(), &view_clone, window, |n| DbTreeViewEvent::DropEvent { node_id: n }))
+                                                                        .separator();
+                                                                }
+                                                                DbNodeType::Function | DbNodeType::Procedure | DbNodeType::Trigger => {
+                                                                    let node_id_for_menu = node_id_clone.clone();
+
+                                                                    menu = menu
+                                                                        .item(Self::create_menu_item(&node_id_for_menu, "AI 解释".to_string(), &view_clone, window, |n| DbTreeViewEvent::ExplainRoutine { node_id: n }))
+                                                                        .separator();
+                                                                }
                                                                 DbNodeType::TablesFolder => {
                                                                     let node_id_for_menu = node_id_clone.clone();
 
@@ -0,0 +1 @@
+pub mod duckdb_view_plugin;
@@ -0,0 +1 @@
+pub mod elasticsearch_view_plugin;
@@ -0,0 +1,85 @@
+use gpui::{App, AppContext, Entity, Window};
+use one_core::storage::DatabaseType;
+use crate::common::{DatabaseEditorView, DatabaseNameForm};
+use crate::database_view_plugin::{DatabaseViewPlugin, TableDesignerCapabilities, NodeMenuCapabilities};
+use crate::common::db_connection_form::{DbConnectionForm, DbFormConfig};
+
+/// Elasticsearch 数据库视图插件；SQL 接口只读，树上不提供建库、建表和改名等操作
+pub struct ElasticsearchDatabaseViewPlugin;
+
+impl ElasticsearchDatabaseViewPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DatabaseViewPlugin for ElasticsearchDatabaseViewPlugin {
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::Elasticsearch
+    }
+
+    fn create_connection_form(&self, window: &mut Window, cx: &mut App) -> Entity<DbConnectionForm> {
+        cx.new(|cx| DbConnectionForm::new(DbFormConfig::elasticsearch(), window, cx))
+    }
+
+    fn create_database_editor_view(
+        &self,
+        _connection_id: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| DatabaseNameForm::new(window, cx));
+            DatabaseEditorView::new(form, DatabaseType::Elasticsearch, false, window, cx)
+        })
+    }
+
+    fn create_database_editor_view_for_edit(
+        &self,
+        _connection_id: String,
+        database_name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| DatabaseNameForm::new_for_edit(&database_name, window, cx));
+            DatabaseEditorView::new(form, DatabaseType::Elasticsearch, true, window, cx)
+        })
+    }
+
+    fn get_table_designer_capabilities(&self) -> TableDesignerCapabilities {
+        TableDesignerCapabilities {
+            supports_engine: false,
+            supports_charset: false,
+            supports_collation: false,
+            supports_auto_increment: false,
+            supports_tablespace: false,
+        }
+    }
+
+    fn get_engines(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn get_node_menu_capabilities(&self) -> NodeMenuCapabilities {
+        NodeMenuCapabilities {
+            supports_truncate_table: false,
+            supports_rename_table: false,
+            supports_table_import: false,
+            supports_table_export: true,
+            supports_create_database: false,
+            supports_edit_database: false,
+            supports_drop_database: false,
+            supports_dump_database: false,
+            supports_create_schema: false,
+            supports_delete_schema: false,
+            supports_maintenance: false,
+            supports_create_view: false,
+            supports_edit_view: false,
+            supports_sequences: false,
+            supports_triggers: false,
+            supports_stored_procedures: false,
+            supports_functions: false,
+        }
+    }
+}
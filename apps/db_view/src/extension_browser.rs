@@ -0,0 +1,259 @@
+//! 扩展管理 - 查看数据库中可用和已安装的扩展及版本，安装或删除扩展
+
+use db::{ExtensionInfo, GlobalDbState};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Subscription, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Sizable, WindowExt,
+};
+use one_core::storage::ConnectionEnvironment;
+
+use crate::confirm_policy::{confirm_destructive_with_option, ConfirmRequest, DestructiveAction};
+use crate::db_tree_view::DbTreeView;
+
+pub struct ExtensionBrowser {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    database: String,
+    /// 安装或删除后需要刷新的数据库节点
+    database_node_id: String,
+    environment: ConnectionEnvironment,
+    tree_view: Entity<DbTreeView>,
+    filter_input: Entity<InputState>,
+    /// 安装到的 schema，留空时使用 search_path 中的第一个
+    schema_input: Entity<InputState>,
+    extensions: Vec<ExtensionInfo>,
+    loading: bool,
+    error: Option<String>,
+    _filter_sub: Subscription,
+}
+
+impl ExtensionBrowser {
+    pub fn new(
+        connection_id: String,
+        database: String,
+        database_node_id: String,
+        tree_view: Entity<DbTreeView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder("筛选扩展"));
+        let schema_input = cx.new(|cx| InputState::new(window, cx).placeholder("安装到模式（可选）"));
+        let filter_sub = cx.subscribe_in(&filter_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
+                cx.notify();
+            }
+        });
+
+        let mut browser = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            database,
+            database_node_id,
+            environment,
+            tree_view,
+            filter_input,
+            schema_input,
+            extensions: Vec::new(),
+            loading: false,
+            error: None,
+            _filter_sub: filter_sub,
+        };
+        browser.reload(cx);
+        browser
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.loading = true;
+        self.error = None;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.list_extensions(cx, connection_id, database).await;
+            let _ = this.update(cx, |browser, cx| {
+                browser.loading = false;
+                match result {
+                    Ok(extensions) => browser.extensions = extensions,
+                    Err(e) => browser.error = Some(format!("加载扩展失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    /// 扩展变更后刷新列表和树中的数据库节点
+    fn after_change(&mut self, message: String, cx: &mut Context<Self>) {
+        let database_node_id = self.database_node_id.clone();
+        self.tree_view.update(cx, |tree, cx| tree.refresh_tree(database_node_id, cx));
+        self.notify(Notification::success(message), cx);
+        self.reload(cx);
+    }
+
+    fn install(&mut self, extension: ExtensionInfo, cx: &mut Context<Self>) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let schema = Some(self.schema_input.read(cx).text().to_string().trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let name = extension.name.clone();
+            let result = global_state.create_extension(cx, connection_id, database, extension.name, schema).await;
+            let _ = this.update(cx, |browser, cx| match result {
+                Ok(_) => browser.after_change(format!("扩展 {} 已安装", name), cx),
+                Err(e) => browser.notify(Notification::error(format!("安装失败: {}", e)), cx),
+            });
+        }).detach();
+    }
+
+    fn drop_extension(&mut self, extension: ExtensionInfo, window: &mut Window, cx: &mut Context<Self>) {
+        let view = cx.entity();
+        let request = ConfirmRequest::new(
+            DestructiveAction::ExecuteDdl,
+            self.environment,
+            extension.name.clone(),
+            format!("确定要删除扩展 \"{}\" 吗？", extension.name),
+            "扩展提供的类型、函数等对象会一并删除。",
+        );
+
+        confirm_destructive_with_option(request, "级联删除依赖这些对象的列和表（CASCADE）", false, window, cx, move |cascade, _window, cx| {
+            let extension = extension.clone();
+            view.update(cx, |browser, cx| {
+                let global_state = cx.global::<GlobalDbState>().clone();
+                let connection_id = browser.connection_id.clone();
+                let database = browser.database.clone();
+
+                cx.spawn(async move |this, cx: &mut AsyncApp| {
+                    let name = extension.name.clone();
+                    let result = global_state.drop_extension(cx, connection_id, database, extension.name, cascade).await;
+                    let _ = this.update(cx, |browser, cx| match result {
+                        Ok(_) => browser.after_change(format!("扩展 {} 已删除", name), cx),
+                        Err(e) => browser.notify(Notification::error(format!("删除失败: {}", e)), cx),
+                    });
+                }).detach();
+            });
+        });
+    }
+
+    fn notify(&self, notification: Notification, cx: &mut App) {
+        if let Some(window) = cx.active_window() {
+            let _ = window.update(cx, |_, window, cx| {
+                window.push_notification(notification.autohide(true), cx);
+            });
+        }
+    }
+
+    fn render_extension(&self, index: usize, extension: &ExtensionInfo, cx: &mut Context<Self>) -> impl IntoElement {
+        let version = match (&extension.installed_version, &extension.default_version) {
+            (Some(installed), _) if extension.has_update() => {
+                format!("已安装 {}（可用 {}）", installed, extension.default_version.clone().unwrap_or_default())
+            }
+            (Some(installed), _) => format!("已安装 {}", installed),
+            (None, Some(default)) => format!("可用 {}", default),
+            (None, None) => String::new(),
+        };
+        let location = extension.schema.as_ref().map(|schema| format!(" · 模式 {}", schema)).unwrap_or_default();
+        let action_extension = extension.clone();
+
+        h_flex()
+            .gap_2()
+            .py_1()
+            .items_center()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(div().text_sm().child(extension.name.clone()))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(if extension.is_installed() { cx.theme().success } else { cx.theme().muted_foreground })
+                                    .child(format!("{}{}", version, location)),
+                            ),
+                    )
+                    .when_some(extension.comment.clone(), |this, comment| {
+                        this.child(div().text_xs().text_color(cx.theme().muted_foreground).truncate().child(comment))
+                    }),
+            )
+            .child(if extension.is_installed() {
+                Button::new(SharedString::from(format!("extension-drop-{}", index)))
+                    .small()
+                    .danger()
+                    .label("删除")
+                    .on_click(cx.listener(move |browser, _, window, cx| {
+                        browser.drop_extension(action_extension.clone(), window, cx);
+                    }))
+            } else {
+                Button::new(SharedString::from(format!("extension-install-{}", index)))
+                    .small()
+                    .label("安装")
+                    .on_click(cx.listener(move |browser, _, _, cx| {
+                        browser.install(action_extension.clone(), cx);
+                    }))
+            })
+    }
+}
+
+impl Focusable for ExtensionBrowser {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ExtensionBrowser {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let filter = self.filter_input.read(cx).text().to_string().trim().to_lowercase();
+        let installed = self.extensions.iter().filter(|e| e.is_installed()).count();
+
+        let content = if self.loading {
+            div().child("加载中...").into_any_element()
+        } else if let Some(error) = &self.error {
+            div().text_color(cx.theme().danger).child(error.clone()).into_any_element()
+        } else if self.extensions.is_empty() {
+            div().text_color(cx.theme().muted_foreground).child("没有可用的扩展").into_any_element()
+        } else {
+            let extensions: Vec<ExtensionInfo> = self
+                .extensions
+                .iter()
+                .filter(|e| filter.is_empty() || e.name.to_lowercase().contains(&filter))
+                .cloned()
+                .collect();
+            let mut list = v_flex();
+            for (index, extension) in extensions.iter().enumerate() {
+                list = list.child(self.render_extension(index, extension, cx));
+            }
+            list.into_any_element()
+        };
+
+        v_flex()
+            .gap_2()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("数据库 {} 已安装 {} 个扩展，共 {} 个可用", self.database, installed, self.extensions.len())),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(Input::new(&self.filter_input).small()))
+                    .child(div().w(px(200.0)).child(Input::new(&self.schema_input).small())),
+            )
+            .child(div().id("extension-list").max_h(px(400.0)).overflow_y_scroll().child(content))
+    }
+}
@@ -1,23 +1,83 @@
-use gpui::{div, App, AppContext, ClickEvent, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, PathPromptOptions, Render, Styled, Window};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, App, AppContext, AsyncApp, ClickEvent, ClipboardItem, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, PathPromptOptions, Render, Styled, Window};
 use gpui_component::{
     button::{Button, ButtonVariants as _},
     h_flex,
     input::{Input, InputState},
+    select::{Select, SelectItem, SelectState},
     switch::Switch,
-    v_flex, ActiveTheme, Sizable,
+    v_flex, ActiveTheme, IconName, IndexPath, Sizable,
 };
 
-use db::{DataExporter, DataFormat, ExportConfig, GlobalDbState};
+use db::{CsvExportConfig, CsvQuotePolicy, DataExporter, DataFormat, ExportConfig, GlobalDbState, NativeExportFormat, SqlConflictMode, SqlExportConfig, TextEncoding, DEFAULT_SQL_EXPORT_ROWS_PER_INSERT};
+
+use crate::ai_sql::{generate_export_filter, ExportFilterRequest};
+use crate::import_export::table_import_view::{EncodingItem, FieldSeparator, RecordSeparator};
+
+/// CSV 导出的引号策略选项
+#[derive(Clone, Debug, PartialEq)]
+struct QuotePolicyItem(CsvQuotePolicy);
+
+impl SelectItem for QuotePolicyItem {
+    type Value = CsvQuotePolicy;
+
+    fn title(&self) -> gpui::SharedString {
+        self.0.label().into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        &self.0
+    }
+}
+
+/// SQL 导出遇到已存在的行时的处理方式选项
+#[derive(Clone, Debug, PartialEq)]
+struct ConflictModeItem(SqlConflictMode);
+
+impl SelectItem for ConflictModeItem {
+    type Value = SqlConflictMode;
+
+    fn title(&self) -> gpui::SharedString {
+        self.0.label().into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        &self.0
+    }
+}
+
+/// 导出到文件还是复制到剪贴板
+#[derive(Clone, Copy, PartialEq)]
+enum ExportTarget {
+    File,
+    Clipboard,
+}
 
 pub struct DataExportView {
     connection_id: String,
     database: Entity<InputState>,
     pub tables: Entity<InputState>,
     format: Entity<DataFormat>,
+    /// 服务端格式导出，选中时优先于 `format`
+    native_format: Entity<Option<NativeExportFormat>>,
+    native_formats: Vec<NativeExportFormat>,
     include_schema: Entity<bool>,
     include_data: Entity<bool>,
     where_clause: Entity<InputState>,
+    /// 导出范围的自然语言描述，由 AI 翻译为 `where_clause`
+    filter_description: Entity<InputState>,
+    generating_filter: bool,
     limit: Entity<InputState>,
+    // CSV 导出选项，分隔符选制表符即导出 TSV
+    field_separator: Entity<SelectState<Vec<FieldSeparator>>>,
+    record_separator: Entity<SelectState<Vec<RecordSeparator>>>,
+    quote_policy: Entity<SelectState<Vec<QuotePolicyItem>>>,
+    include_bom: Entity<bool>,
+    encoding: Entity<SelectState<Vec<EncodingItem>>>,
+    // SQL 导出选项
+    rows_per_insert: Entity<InputState>,
+    conflict_mode: Entity<SelectState<Vec<ConflictModeItem>>>,
+    disable_foreign_keys: Entity<bool>,
     output_path: Entity<InputState>,
     pending_output_path: Entity<Option<String>>,
     status: Entity<String>,
@@ -39,17 +99,55 @@ impl DataExportView {
             });
             let tables_input = cx.new(|cx| InputState::new(window, cx));
             let where_input = cx.new(|cx| InputState::new(window, cx));
+            let filter_description_input = cx.new(|cx| {
+                InputState::new(window, cx).placeholder("描述要导出的数据，如：2024 年已发货的订单")
+            });
             let limit_input = cx.new(|cx| InputState::new(window, cx));
+            let field_separator = cx.new(|cx| {
+                SelectState::new(FieldSeparator::all(), Some(IndexPath::default()), window, cx)
+            });
+            let record_separator = cx.new(|cx| {
+                SelectState::new(RecordSeparator::all(), Some(IndexPath::default()), window, cx)
+            });
+            let quote_policy_items = CsvQuotePolicy::all().into_iter().map(QuotePolicyItem).collect();
+            let quote_policy = cx.new(|cx| {
+                SelectState::new(quote_policy_items, Some(IndexPath::default()), window, cx)
+            });
+            let encoding_items: Vec<EncodingItem> = TextEncoding::all().into_iter().map(EncodingItem).collect();
+            let encoding = cx.new(|cx| {
+                SelectState::new(encoding_items, Some(IndexPath::default()), window, cx)
+            });
+            let rows_per_insert = cx.new(|cx| {
+                let mut state = InputState::new(window, cx);
+                state.set_value(DEFAULT_SQL_EXPORT_ROWS_PER_INSERT.to_string(), window, cx);
+                state
+            });
+            let conflict_mode_items = SqlConflictMode::all().into_iter().map(ConflictModeItem).collect();
+            let conflict_mode = cx.new(|cx| {
+                SelectState::new(conflict_mode_items, Some(IndexPath::default()), window, cx)
+            });
 
             Self {
                 connection_id: connection_id.into(),
                 database: database_input,
                 tables: tables_input,
                 format: cx.new(|_| DataFormat::Sql),
+                native_format: cx.new(|_| None),
+                native_formats: Vec::new(),
                 include_schema: cx.new(|_| true),
                 include_data: cx.new(|_| true),
                 where_clause: where_input,
+                filter_description: filter_description_input,
+                generating_filter: false,
                 limit: limit_input,
+                field_separator,
+                record_separator,
+                quote_policy,
+                include_bom: cx.new(|_| false),
+                encoding,
+                rows_per_insert,
+                conflict_mode,
+                disable_foreign_keys: cx.new(|_| false),
                 output_path: cx.new(|cx| InputState::new(window, cx)),
                 pending_output_path: cx.new(|_| None),
                 status: cx.new(|_| String::new()),
@@ -58,6 +156,12 @@ impl DataExportView {
         })
     }
 
+    /// 设置数据库支持的服务端导出格式，为空时只提供逐行导出
+    pub fn set_native_formats(&mut self, formats: Vec<NativeExportFormat>, cx: &mut Context<Self>) {
+        self.native_formats = formats;
+        cx.notify();
+    }
+
     fn select_output(&mut self, _window: &mut Window, cx: &mut App) {
         let pending = self.pending_output_path.clone();
         let status = self.status.clone();
@@ -88,19 +192,95 @@ impl DataExportView {
         .detach();
     }
 
-    fn start_export(&mut self, _window: &mut Window, cx: &mut App) {
+    /// 把导出范围描述翻译为 WHERE 条件，校验通过后填入 WHERE 输入框供确认
+    fn generate_where_clause(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.filter_description.read(cx).text().to_string().trim().to_string();
+        if text.is_empty() || self.generating_filter {
+            return;
+        }
+        let request = ExportFilterRequest {
+            connection_id: self.connection_id.clone(),
+            database: self.database.read(cx).text().to_string(),
+            tables: self.tables
+                .read(cx)
+                .text()
+                .to_string()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            text,
+        };
+        self.generating_filter = true;
+        self.status.update(cx, |s, cx| {
+            *s = "Generating WHERE clause...".to_string();
+            cx.notify();
+        });
+        cx.notify();
+
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = generate_export_filter(request, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                let _ = this.update(cx, |view, cx| {
+                    view.generating_filter = false;
+                    let message = match result {
+                        Ok(condition) => {
+                            view.where_clause.update(cx, |state, cx| state.set_value(condition, window, cx));
+                            "WHERE clause generated, please review it before exporting".to_string()
+                        }
+                        Err(e) => format!("Generate WHERE clause error: {}", e),
+                    };
+                    view.status.update(cx, |s, cx| {
+                        *s = message;
+                        cx.notify();
+                    });
+                    cx.notify();
+                });
+            });
+        }).detach();
+    }
+
+    fn start_export(&mut self, target: ExportTarget, cx: &mut App) {
         let global_state = cx.global::<GlobalDbState>().clone();
         let connection_id = self.connection_id.clone();
 
         let database = self.database.read(cx).text().to_string();
         let tables_str = self.tables.read(cx).text().to_string();
         let format = *self.format.read(cx);
+        let native_format = *self.native_format.read(cx);
         let include_schema = *self.include_schema.read(cx);
         let include_data = *self.include_data.read(cx);
         let where_clause_str = self.where_clause.read(cx).text().to_string();
         let limit_str = self.limit.read(cx).text().to_string();
         let output_path_str = self.output_path.read(cx).text().to_string();
 
+        let csv_config = (format == DataFormat::Csv).then(|| CsvExportConfig {
+            field_delimiter: self.field_separator.read(cx)
+                .selected_value()
+                .map(|v| v.to_separator_char())
+                .unwrap_or(','),
+            quote_policy: self.quote_policy.read(cx).selected_value().copied().unwrap_or_default(),
+            record_terminator: self.record_separator.read(cx)
+                .selected_value()
+                .map(|v| v.to_separator_string())
+                .unwrap_or_else(|| "\n".to_string()),
+            include_bom: *self.include_bom.read(cx),
+            ..Default::default()
+        });
+        let encoding = self.encoding.read(cx).selected_value().copied().unwrap_or_default();
+        let sql_config = (format == DataFormat::Sql).then(|| SqlExportConfig {
+            rows_per_insert: self.rows_per_insert.read(cx)
+                .text()
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|rows| *rows > 0)
+                .unwrap_or(DEFAULT_SQL_EXPORT_ROWS_PER_INSERT),
+            conflict_mode: self.conflict_mode.read(cx).selected_value().copied().unwrap_or_default(),
+            disable_foreign_keys: *self.disable_foreign_keys.read(cx),
+        });
+
         let status = self.status.clone();
 
         if tables_str.is_empty() {
@@ -111,7 +291,15 @@ impl DataExportView {
             return;
         }
 
-        if output_path_str.is_empty() {
+        if target == ExportTarget::Clipboard && (native_format.is_some() || format == DataFormat::Avro) {
+            status.update(cx, |s, cx| {
+                *s = "Only text formats can be copied to the clipboard".to_string();
+                cx.notify();
+            });
+            return;
+        }
+
+        if target == ExportTarget::File && output_path_str.is_empty() {
             status.update(cx, |s, cx| {
                 *s = "Please enter output file path".to_string();
                 cx.notify();
@@ -190,21 +378,99 @@ impl DataExportView {
                 include_data,
                 where_clause,
                 limit,
+                csv_config,
+                sql_config,
+                encoding,
+                ..Default::default()
             };
 
-            match DataExporter::export(plugin, connection.as_ref(),  export_config).await {
-                Ok(result) => {
-                    // 写入文件
-                    if let Err(e) = std::fs::write(&output_path_str, result.output) {
-                        cx.update(|cx| {
-                            status.update(cx, |s, cx| {
-                                *s = format!("File write error: {}", e);
-                                cx.notify();
-                            });
-                        }).ok();
-                        return;
+            // 导出结果整个放进剪贴板，适合行数不多的表
+            if target == ExportTarget::Clipboard {
+                let message = match DataExporter::export(plugin, connection.as_ref(), export_config).await {
+                    Ok(result) => {
+                        let rows_exported = result.rows_exported;
+                        cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(result.output))).ok();
+                        format!("Success: {} rows copied to clipboard in {}ms", rows_exported, result.elapsed_ms)
+                    }
+                    Err(e) => format!("Export error: {}", e),
+                };
+                cx.update(|cx| {
+                    status.update(cx, |s, cx| {
+                        *s = message;
+                        cx.notify();
+                    });
+                }).ok();
+                return;
+            }
+
+            if let Some(native_format) = native_format {
+                let start = std::time::Instant::now();
+                let message = match DataExporter::export_native(plugin, connection.as_ref(), &export_config, native_format).await {
+                    Ok(files) => {
+                        let mut total_bytes = 0;
+                        let mut write_error = None;
+                        for file in &files {
+                            let path = std::path::Path::new(&output_path_str)
+                                .join(format!("{}.{}", file.table, native_format.extension()));
+                            if let Err(e) = std::fs::write(&path, &file.data) {
+                                write_error = Some(format!("File write error: {}", e));
+                                break;
+                            }
+                            total_bytes += file.data.len();
+                        }
+                        write_error.unwrap_or_else(|| format!(
+                            "Success: {} tables ({} bytes) exported as {} to {} in {}ms",
+                            files.len(),
+                            total_bytes,
+                            native_format.format_name(),
+                            output_path_str,
+                            start.elapsed().as_millis()
+                        ))
                     }
+                    Err(e) => format!("Export error: {}", e),
+                };
+                cx.update(|cx| {
+                    status.update(cx, |s, cx| {
+                        *s = message;
+                        cx.notify();
+                    });
+                }).ok();
+                return;
+            }
 
+            // 多个表的非 SQL 导出每个表一个文件，一起打包成 zip；输出路径是目录时在其中按数据库名生成
+            if format != DataFormat::Sql && export_config.tables.len() > 1 {
+                let output_path = std::path::Path::new(&output_path_str);
+                let zip_path = if output_path.is_dir() {
+                    let stem = if export_config.database.is_empty() { "export" } else { export_config.database.as_str() };
+                    output_path.join(format!("{}.zip", stem))
+                } else {
+                    output_path.with_extension("zip")
+                };
+                let table_count = export_config.tables.len();
+                let message = match DataExporter::export_to_zip(plugin, connection.as_ref(), export_config, &zip_path, None).await {
+                    Ok(result) => format!(
+                        "Success: {} tables ({} rows) exported to {} in {}ms",
+                        table_count,
+                        result.rows_exported,
+                        zip_path.display(),
+                        result.elapsed_ms
+                    ),
+                    Err(e) => format!("Export error: {}", e),
+                };
+                cx.update(|cx| {
+                    status.update(cx, |s, cx| {
+                        *s = message;
+                        cx.notify();
+                    });
+                }).ok();
+                return;
+            }
+
+            // 边查询边写文件，大表也不会把整个导出内容放进内存
+            let output_path = std::path::Path::new(&output_path_str);
+            match DataExporter::export_to_file(plugin, connection.as_ref(), export_config, output_path, None).await {
+                Ok(result) => {
                     cx.update(|cx| {
                         status.update(cx, |s, cx| {
                             *s = format!(
@@ -241,10 +507,22 @@ impl Clone for DataExportView {
             database: self.database.clone(),
             tables: self.tables.clone(),
             format: self.format.clone(),
+            native_format: self.native_format.clone(),
+            native_formats: self.native_formats.clone(),
             include_schema: self.include_schema.clone(),
             include_data: self.include_data.clone(),
             where_clause: self.where_clause.clone(),
+            filter_description: self.filter_description.clone(),
+            generating_filter: self.generating_filter,
             limit: self.limit.clone(),
+            field_separator: self.field_separator.clone(),
+            record_separator: self.record_separator.clone(),
+            quote_policy: self.quote_policy.clone(),
+            include_bom: self.include_bom.clone(),
+            encoding: self.encoding.clone(),
+            rows_per_insert: self.rows_per_insert.clone(),
+            conflict_mode: self.conflict_mode.clone(),
+            disable_foreign_keys: self.disable_foreign_keys.clone(),
             output_path: self.output_path.clone(),
             pending_output_path: self.pending_output_path.clone(),
             status: self.status.clone(),
@@ -265,6 +543,7 @@ impl Render for DataExportView {
 
         let status_text = self.status.read(cx).clone();
         let current_format = *self.format.read(cx);
+        let current_native_format = *self.native_format.read(cx);
 
         v_flex()
             .gap_3()
@@ -294,10 +573,11 @@ impl Render for DataExportView {
                             .gap_1()
                             .child({
                                 let mut btn = Button::new("format_sql").child("SQL");
-                                if current_format == DataFormat::Sql {
+                                if current_native_format.is_none() && current_format == DataFormat::Sql {
                                     btn = btn.primary();
                                 }
                                 btn.on_click(window.listener_for(&cx.entity(), |view, _, _, cx| {
+                                    view.native_format.update(cx, |f, _| *f = None);
                                     view.format.update(cx, |f, cx| {
                                         *f = DataFormat::Sql;
                                         cx.notify();
@@ -306,10 +586,11 @@ impl Render for DataExportView {
                             })
                             .child({
                                 let mut btn = Button::new("format_json").child("JSON");
-                                if current_format == DataFormat::Json {
+                                if current_native_format.is_none() && current_format == DataFormat::Json {
                                     btn = btn.primary();
                                 }
                                 btn.on_click(window.listener_for(&cx.entity(), |view, _, _, cx| {
+                                    view.native_format.update(cx, |f, _| *f = None);
                                     view.format.update(cx, |f, cx| {
                                         *f = DataFormat::Json;
                                         cx.notify();
@@ -318,16 +599,143 @@ impl Render for DataExportView {
                             })
                             .child({
                                 let mut btn = Button::new("format_csv").child("CSV");
-                                if current_format == DataFormat::Csv {
+                                if current_native_format.is_none() && current_format == DataFormat::Csv {
                                     btn = btn.primary();
                                 }
                                 btn.on_click(window.listener_for(&cx.entity(), |view, _, _, cx| {
+                                    view.native_format.update(cx, |f, _| *f = None);
                                     view.format.update(cx, |f, cx| {
                                         *f = DataFormat::Csv;
                                         cx.notify();
                                     });
                                 }))
                             })
+                            .child({
+                                let mut btn = Button::new("format_yaml").child("YAML");
+                                if current_native_format.is_none() && current_format == DataFormat::Yaml {
+                                    btn = btn.primary();
+                                }
+                                btn.on_click(window.listener_for(&cx.entity(), |view, _, _, cx| {
+                                    view.native_format.update(cx, |f, _| *f = None);
+                                    view.format.update(cx, |f, cx| {
+                                        *f = DataFormat::Yaml;
+                                        cx.notify();
+                                    });
+                                }))
+                            })
+                            .child({
+                                let mut btn = Button::new("format_avro").child("Avro");
+                                if current_native_format.is_none() && current_format == DataFormat::Avro {
+                                    btn = btn.primary();
+                                }
+                                btn.on_click(window.listener_for(&cx.entity(), |view, _, _, cx| {
+                                    view.native_format.update(cx, |f, _| *f = None);
+                                    view.format.update(cx, |f, cx| {
+                                        *f = DataFormat::Avro;
+                                        cx.notify();
+                                    });
+                                }))
+                            })
+                    ),
+            )
+            .when(current_native_format.is_none() && current_format == DataFormat::Csv, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().w_24().child("Delimiter:"))
+                        .child(Select::new(&self.field_separator).w_40())
+                        .child(div().child("Quoting:"))
+                        .child(Select::new(&self.quote_policy).w_32())
+                        .child(div().child("Line ending:"))
+                        .child(Select::new(&self.record_separator).w_40())
+                        .child(
+                            Switch::new("include_bom")
+                                .checked(*self.include_bom.read(cx))
+                                .on_click(cx.listener(|view, checked, _, cx| {
+                                    view.include_bom.update(cx, |state, cx| {
+                                        *state = *checked;
+                                        cx.notify();
+                                    });
+                                }))
+                        )
+                        .child("BOM"),
+                )
+            })
+            .when(current_native_format.is_none() && current_format == DataFormat::Sql, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().w_24().child("Rows/INSERT:"))
+                        .child(Input::new(&self.rows_per_insert).w_24())
+                        .child(div().child("On conflict:"))
+                        .child(Select::new(&self.conflict_mode).w_32())
+                        .child(
+                            Switch::new("disable_foreign_keys")
+                                .checked(*self.disable_foreign_keys.read(cx))
+                                .on_click(cx.listener(|view, checked, _, cx| {
+                                    view.disable_foreign_keys.update(cx, |state, cx| {
+                                        *state = *checked;
+                                        cx.notify();
+                                    });
+                                }))
+                        )
+                        .child("Disable foreign keys"),
+                )
+            })
+            // Avro 和服务端格式不经过转码
+            .when(current_native_format.is_none() && current_format != DataFormat::Avro, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().w_24().child("Encoding:"))
+                        .child(Select::new(&self.encoding).w_40())
+                )
+            })
+            .when(!self.native_formats.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().w_24().child("Server:"))
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .children(self.native_formats.iter().map(|native_format| {
+                                    let native_format = *native_format;
+                                    let mut btn = Button::new(native_format.format_name())
+                                        .child(native_format.format_name());
+                                    if current_native_format == Some(native_format) {
+                                        btn = btn.primary();
+                                    }
+                                    btn.on_click(window.listener_for(&cx.entity(), move |view, _, _, cx| {
+                                        view.native_format.update(cx, |f, cx| {
+                                            *f = Some(native_format);
+                                            cx.notify();
+                                        });
+                                    }))
+                                }))
+                        )
+                        .child(div().text_xs().text_color(cx.theme().muted_foreground).child("(Formatted by the server, one file per table)")),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().w_24().child("Filter:"))
+                    .child(Input::new(&self.filter_description).w_96())
+                    .child(
+                        Button::new("generate_where")
+                            .small()
+                            .icon(IconName::Bot)
+                            .child("AI")
+                            .loading(self.generating_filter)
+                            .on_click(cx.listener(|view, _: &ClickEvent, window, cx| {
+                                view.generate_where_clause(window, cx);
+                            })),
                     ),
             )
             .child(
@@ -404,8 +812,15 @@ impl Render for DataExportView {
                         Button::new("export")
                             .primary()
                             .child("Export")
-                            .on_click(window.listener_for(&cx.entity(), |view, _: &ClickEvent, window, cx| {
-                                view.start_export(window, cx);
+                            .on_click(window.listener_for(&cx.entity(), |view, _: &ClickEvent, _window, cx| {
+                                view.start_export(ExportTarget::File, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("copy_export")
+                            .child("Copy to Clipboard")
+                            .on_click(window.listener_for(&cx.entity(), |view, _: &ClickEvent, _window, cx| {
+                                view.start_export(ExportTarget::Clipboard, cx);
                             })),
                     ),
             )
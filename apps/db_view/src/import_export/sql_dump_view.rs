@@ -124,14 +124,22 @@ impl SqlDumpView {
                 include_data,
                 where_clause: None,
                 limit: None,
+                ..Default::default()
             };
 
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            let full_path = output_path.join(format!("{}_{}.sql", database, timestamp));
+
             let global_state_clone = global_state.clone();
             let connection_id_clone = connection_id.clone();
+            let full_path_clone = full_path.clone();
 
             let export_handle = cx.background_spawn(async move {
                 global_state_clone
-                    .export_data_with_progress_sync(connection_id_clone, export_config, Some(progress_tx))
+                    .export_data_to_file_with_progress_sync(connection_id_clone, export_config, full_path_clone, Some(progress_tx))
                     .await
             });
 
@@ -197,12 +205,14 @@ impl SqlDumpView {
                                 cx.notify();
                             });
                         }
-                        ExportProgressEvent::DataExported { table, rows } => {
-                            transferred_records_clone.update(cx, |r, cx| {
+                        ExportProgressEvent::DataProgress { rows, .. } => {
+                            processed_records_clone.update(cx, |r, cx| {
                                 *r += rows;
                                 cx.notify();
                             });
-                            processed_records_clone.update(cx, |r, cx| {
+                        }
+                        ExportProgressEvent::DataExported { table, rows } => {
+                            transferred_records_clone.update(cx, |r, cx| {
                                 *r += rows;
                                 cx.notify();
                             });
@@ -271,35 +281,14 @@ impl SqlDumpView {
                 });
 
                 match result {
-                    Ok(export_result) => {
-                        let timestamp = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs();
-                        let filename = format!("{}_{}.sql", database, timestamp);
-                        let full_path = output_path.join(&filename);
-
-                        if let Err(e) = std::fs::write(&full_path, export_result.output) {
-                            logs.update(cx, |l, cx| {
-                                l.push(LogEntry {
-                                    table: "".to_string(),
-                                    message: format!("File write error: {}", e),
-                                });
-                                cx.notify();
-                            });
-                            error_count.update(cx, |e, cx| {
-                                *e += 1;
-                                cx.notify();
-                            });
-                        } else {
-                            logs.update(cx, |l, cx| {
-                                l.push(LogEntry {
-                                    table: "".to_string(),
-                                    message: format!("File saved: {}", full_path.display()),
-                                });
-                                cx.notify();
+                    Ok(_) => {
+                        logs.update(cx, |l, cx| {
+                            l.push(LogEntry {
+                                table: "".to_string(),
+                                message: format!("File saved: {}", full_path.display()),
                             });
-                        }
+                            cx.notify();
+                        });
                     }
                     Err(e) => {
                         logs.update(cx, |l, cx| {
@@ -131,46 +131,6 @@ impl SqlImportView {
                     scroll_handle_clone.scroll_to_bottom();
                 });
 
-                let logs_clone = logs.clone();
-                let scroll_handle_clone = scroll_handle.clone();
-                let file_name_clone = file_name.clone();
-                let _ = cx.update(|cx| {
-                    logs_clone.update(cx, |l, cx| {
-                        l.push(LogEntry {
-                            file: file_name_clone,
-                            message: "Reading file...".to_string(),
-                        });
-                        cx.notify();
-                    });
-                    scroll_handle_clone.scroll_to_bottom();
-                });
-
-                let data = match std::fs::read_to_string(&file_path) {
-                    Ok(d) => d,
-                    Err(e) => {
-                        let logs_clone = logs.clone();
-                        let scroll_handle_clone = scroll_handle.clone();
-                        let error_count_clone = error_count.clone();
-                        let file_name_clone = file_name.clone();
-                        let _ = cx.update(|cx| {
-                            logs_clone.update(cx, |l, cx| {
-                                l.push(LogEntry {
-                                    file: file_name_clone,
-                                    message: format!("Error: Failed to read file: {}", e),
-                                });
-                                cx.notify();
-                            });
-                            error_count_clone.update(cx, |e, cx| {
-                                *e += 1;
-                                cx.notify();
-                            });
-                            scroll_handle_clone.scroll_to_bottom();
-                        });
-                        total_errors += 1;
-                        continue;
-                    }
-                };
-
                 let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<ImportProgressEvent>();
 
                 let import_config = ImportConfig {
@@ -181,19 +141,21 @@ impl SqlImportView {
                     use_transaction: false,
                     truncate_before_import: false,
                     csv_config: None,
+                    async_insert: false,
+                    ..Default::default()
                 };
 
                 let global_state_clone = global_state.clone();
                 let connection_id_clone = connection_id.clone();
-                let file_name_for_import = file_name.clone();
+                let file_path_for_import = file_path.clone();
 
+                // 边读边执行，大脚本不会整个读进内存
                 let import_handle = cx.background_spawn(async move {
                     global_state_clone
-                        .import_data_with_progress_sync(
+                        .import_file_with_progress_sync(
                             connection_id_clone,
                             import_config,
-                            data,
-                            &file_name_for_import,
+                            file_path_for_import,
                             Some(progress_tx),
                         )
                         .await
@@ -229,7 +191,7 @@ impl SqlImportView {
                                 });
                             }
                             ImportProgressEvent::ExecutingStatement { file, statement_index, total_statements } => {
-                                if statement_index % 100 == 0 || statement_index == total_statements - 1 {
+                                if statement_index % 100 == 0 || statement_index + 1 == total_statements {
                                     logs_clone.update(cx, |l, cx| {
                                         l.push(LogEntry {
                                             file: file.clone(),
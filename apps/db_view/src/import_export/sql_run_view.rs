@@ -15,7 +15,7 @@ use gpui_component::{
 };
 
 // 3. 当前 crate 导入（按模块分组）
-use db::{ExecOptions, GlobalDbState, SqlResult, StreamingProgress};
+use db::{ExecOptions, GlobalDbState, SqlResult, StatementTimeout, StreamingProgress};
 
 pub struct SqlRunView {
     connection_id: String,
@@ -144,6 +144,9 @@ impl SqlRunView {
                     stop_on_error,
                     transactional,
                     max_rows: None,
+                    source: None,
+                    timeout: StatementTimeout::ConnectionDefault,
+                    session_id: None,
                 };
 
                 let rx_result = global_state.execute_script_streaming(
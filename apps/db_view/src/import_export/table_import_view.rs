@@ -1,4 +1,5 @@
-use gpui::{div, App, AppContext, ClickEvent, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, PathPromptOptions, Render, Styled, Window};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, App, AppContext, ClickEvent, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, PathPromptOptions, Render, Styled, Window};
 use gpui_component::{
     button::{Button, ButtonVariants as _},
     h_flex,
@@ -8,7 +9,7 @@ use gpui_component::{
     v_flex, ActiveTheme, IndexPath, Sizable,
 };
 
-use db::{CsvImportConfig, DataFormat, DataImporter, GlobalDbState, ImportConfig};
+use db::{parse_column_mappings, preview_csv, read_csv_sample, sniff_csv, sniff_encoding, CsvImportConfig, DataFormat, DataImporter, GlobalDbState, ImportConfig, TextEncoding, DEFAULT_IMPORT_BATCH_ROWS};
 
 // 记录分隔符选项
 #[derive(Clone, Debug, PartialEq)]
@@ -18,14 +19,14 @@ pub enum RecordSeparator {
 }
 
 impl RecordSeparator {
-    fn to_separator_string(&self) -> String {
+    pub(crate) fn to_separator_string(&self) -> String {
         match self {
             RecordSeparator::Lf => "\n".to_string(),
             RecordSeparator::CrLf => "\r\n".to_string(),
         }
     }
 
-    fn all() -> Vec<Self> {
+    pub(crate) fn all() -> Vec<Self> {
         vec![RecordSeparator::Lf, RecordSeparator::CrLf]
     }
 }
@@ -55,7 +56,7 @@ pub enum FieldSeparator {
 }
 
 impl FieldSeparator {
-    fn to_separator_char(&self) -> char {
+    pub(crate) fn to_separator_char(&self) -> char {
         match self {
             FieldSeparator::Comma => ',',
             FieldSeparator::Tab => '\t',
@@ -64,7 +65,7 @@ impl FieldSeparator {
         }
     }
 
-    fn all() -> Vec<Self> {
+    pub(crate) fn all() -> Vec<Self> {
         vec![
             FieldSeparator::Comma,
             FieldSeparator::Tab,
@@ -119,6 +120,25 @@ impl SelectItem for TextQualifierItem {
     }
 }
 
+// 文件编码选项
+#[derive(Clone, Debug)]
+pub struct EncodingItem(pub TextEncoding);
+
+impl SelectItem for EncodingItem {
+    type Value = TextEncoding;
+
+    fn title(&self) -> gpui::SharedString {
+        self.0.label().into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        &self.0
+    }
+}
+
+/// 预览显示的行数
+const PREVIEW_ROWS: usize = 5;
+
 pub struct TableImportView {
     connection_id: String,
     database: Entity<InputState>,
@@ -134,12 +154,26 @@ pub struct TableImportView {
     record_separator: Entity<SelectState<Vec<RecordSeparator>>>,
     field_separator: Entity<SelectState<Vec<FieldSeparator>>>,
     text_qualifier: Entity<SelectState<Vec<TextQualifierItem>>>,
+    // 文件开头的一段，用于识别格式和预览
+    csv_sample: Entity<Option<Vec<u8>>>,
+    // 识别出的编码和格式，下次渲染时填到界面上
+    pending_sniff: Entity<Option<(TextEncoding, CsvImportConfig)>>,
 
     // 导入选项
+    encoding: Entity<SelectState<Vec<EncodingItem>>>,
     has_header: Entity<bool>,
     stop_on_error: Entity<bool>,
     use_transaction: Entity<bool>,
     truncate_before: Entity<bool>,
+    async_insert: Entity<bool>,
+    supports_async_insert: bool,
+    // 每条 INSERT 包含的行数
+    batch_size: Entity<InputState>,
+
+    // 列映射和类型转换提示
+    column_mapping: Entity<InputState>,
+    date_format: Entity<InputState>,
+    decimal_separator: Entity<InputState>,
 
     status: Entity<String>,
     focus_handle: FocusHandle,
@@ -188,6 +222,11 @@ impl TableImportView {
                 SelectState::new(FieldSeparator::all(), Some(IndexPath::default()), window, cx)
             });
 
+            let encoding_items: Vec<EncodingItem> = TextEncoding::all().into_iter().map(EncodingItem).collect();
+            let encoding = cx.new(|cx| {
+                SelectState::new(encoding_items, Some(IndexPath::default()), window, cx)
+            });
+
             Self {
                 connection_id: connection_id.into(),
                 database: database_input,
@@ -201,11 +240,29 @@ impl TableImportView {
                 record_separator,
                 field_separator,
                 text_qualifier,
+                encoding,
+                csv_sample: cx.new(|_| None),
+                pending_sniff: cx.new(|_| None),
 
                 has_header: cx.new(|_| true),
                 stop_on_error: cx.new(|_| true),
                 use_transaction: cx.new(|_| true),
                 truncate_before: cx.new(|_| false),
+                async_insert: cx.new(|_| false),
+                supports_async_insert: false,
+                batch_size: cx.new(|cx| {
+                    let mut state = InputState::new(window, cx);
+                    state.set_value(DEFAULT_IMPORT_BATCH_ROWS.to_string(), window, cx);
+                    state
+                }),
+
+                column_mapping: cx.new(|cx| {
+                    InputState::new(window, cx)
+                        .multi_line(true)
+                        .placeholder("每行一条，留空按同名导入：\n源列 -> 目标列\n源列 ->（跳过该列）\n目标列 = '常量'\n目标列 = CURRENT_TIMESTAMP")
+                }),
+                date_format: cx.new(|cx| InputState::new(window, cx).placeholder("如 %d/%m/%Y")),
+                decimal_separator: cx.new(|cx| InputState::new(window, cx).placeholder(".")),
 
                 status: cx.new(|_| String::new()),
                 focus_handle: cx.focus_handle(),
@@ -213,8 +270,16 @@ impl TableImportView {
         })
     }
 
+    /// 数据库支持服务端异步插入时显示 async_insert 开关
+    pub fn set_supports_async_insert(&mut self, supported: bool, cx: &mut Context<Self>) {
+        self.supports_async_insert = supported;
+        cx.notify();
+    }
+
     fn select_file(&mut self, _window: &mut Window, cx: &mut App) {
         let pending = self.pending_file_path.clone();
+        let csv_sample = self.csv_sample.clone();
+        let pending_sniff = self.pending_sniff.clone();
         let status = self.status.clone();
         let future = cx.prompt_for_paths(PathPromptOptions {
             files: true,
@@ -227,7 +292,20 @@ impl TableImportView {
             if let Ok(Ok(Some(paths))) = future.await {
                 if let Some(path_buf) = paths.first() {
                     let path = path_buf.to_string_lossy().to_string();
+                    // 读不到样本时不影响导入，只是没有自动识别和预览
+                    let sample = read_csv_sample(path_buf).ok();
                     let _ = cx.update(|cx| {
+                        pending_sniff.update(cx, |p, cx| {
+                            *p = sample.as_deref().map(|sample| {
+                                let encoding = sniff_encoding(sample);
+                                (encoding, sniff_csv(sample, encoding))
+                            });
+                            cx.notify();
+                        });
+                        csv_sample.update(cx, |s, cx| {
+                            *s = sample;
+                            cx.notify();
+                        });
                         pending.update(cx, |p, cx| {
                             *p = Some(path.clone());
                             cx.notify();
@@ -243,6 +321,52 @@ impl TableImportView {
         .detach();
     }
 
+    /// 当前界面上的 CSV 配置
+    fn csv_config(&self, cx: &App) -> CsvImportConfig {
+        let field_delimiter = self.field_separator.read(cx)
+            .selected_value()
+            .map(|v| v.to_separator_char())
+            .unwrap_or(',');
+
+        let record_terminator = self.record_separator.read(cx)
+            .selected_value()
+            .map(|v| v.to_separator_string())
+            .unwrap_or_else(|| "\n".to_string());
+
+        let text_qualifier = self.text_qualifier.read(cx)
+            .selected_value()
+            .and_then(|s| s.chars().next());
+
+        CsvImportConfig {
+            field_delimiter,
+            text_qualifier,
+            has_header: *self.has_header.read(cx),
+            record_terminator,
+        }
+    }
+
+    fn selected_encoding(&self, cx: &App) -> TextEncoding {
+        self.encoding.read(cx).selected_value().copied().unwrap_or_default()
+    }
+
+    /// 把识别出的编码和格式填到界面上，用户仍可手动修改
+    fn apply_sniffed(&mut self, encoding: TextEncoding, config: &CsvImportConfig, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(field) = FieldSeparator::all().into_iter().find(|v| v.to_separator_char() == config.field_delimiter) {
+            self.field_separator.update(cx, |state, cx| state.set_selected_value(&field, window, cx));
+        }
+        if let Some(record) = RecordSeparator::all().into_iter().find(|v| v.to_separator_string() == config.record_terminator) {
+            self.record_separator.update(cx, |state, cx| state.set_selected_value(&record, window, cx));
+        }
+        let qualifier = config.text_qualifier.map(String::from).unwrap_or_default();
+        self.text_qualifier.update(cx, |state, cx| state.set_selected_value(&qualifier, window, cx));
+        self.encoding.update(cx, |state, cx| state.set_selected_value(&encoding, window, cx));
+        let has_header = config.has_header;
+        self.has_header.update(cx, |state, cx| {
+            *state = has_header;
+            cx.notify();
+        });
+    }
+
     fn start_import(&mut self, _window: &mut Window, cx: &mut App) {
         let global_state = cx.global::<GlobalDbState>().clone();
         let connection_id = self.connection_id.clone();
@@ -276,32 +400,35 @@ impl TableImportView {
         let stop_on_error = *self.stop_on_error.read(cx);
         let use_transaction = *self.use_transaction.read(cx);
         let truncate_before = *self.truncate_before.read(cx);
-        let has_header = *self.has_header.read(cx);
-
-        let field_delimiter = self.field_separator.read(cx)
-            .selected_value()
-            .map(|v| v.to_separator_char())
-            .unwrap_or(',');
-
-        let record_terminator = self.record_separator.read(cx)
-            .selected_value()
-            .map(|v| v.to_separator_string())
-            .unwrap_or_else(|| "\n".to_string());
-
-        let text_qualifier = self.text_qualifier.read(cx)
-            .selected_value()
-            .and_then(|s| s.chars().next());
+        let async_insert = self.supports_async_insert && *self.async_insert.read(cx);
+        let batch_size = self.batch_size.read(cx)
+            .text()
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|size| *size > 0)
+            .unwrap_or(DEFAULT_IMPORT_BATCH_ROWS);
+
+        let column_mappings = match parse_column_mappings(&self.column_mapping.read(cx).text().to_string()) {
+            Ok(mappings) => mappings,
+            Err(e) => {
+                status.update(cx, |s, cx| {
+                    *s = format!("列映射错误: {}", e);
+                    cx.notify();
+                });
+                return;
+            }
+        };
+        let date_format = Some(self.date_format.read(cx).text().trim().to_string())
+            .filter(|format| !format.is_empty());
+        let decimal_separator = self.decimal_separator.read(cx).text().trim().chars().next();
 
         let csv_config = if format == DataFormat::Csv {
-            Some(CsvImportConfig {
-                field_delimiter,
-                text_qualifier,
-                has_header,
-                record_terminator,
-            })
+            Some(self.csv_config(cx))
         } else {
             None
         };
+        let encoding = self.selected_encoding(cx);
 
         cx.spawn(async move |cx| {
             let config = match global_state.get_config_async(&connection_id).await {
@@ -343,19 +470,6 @@ impl TableImportView {
                 }
             };
 
-            let data = match std::fs::read_to_string(&file_path_str) {
-                Ok(d) => d,
-                Err(e) => {
-                    cx.update(|cx| {
-                        status.update(cx, |s, cx| {
-                            *s = format!("文件读取错误: {}", e);
-                            cx.notify();
-                        });
-                    }).ok();
-                    return;
-                }
-            };
-
             let import_config = ImportConfig {
                 format,
                 database,
@@ -364,9 +478,25 @@ impl TableImportView {
                 use_transaction,
                 truncate_before_import: truncate_before,
                 csv_config,
+                async_insert,
+                batch_size,
+                column_mappings,
+                date_format,
+                decimal_separator,
+                encoding,
+                ..Default::default()
             };
 
-            match DataImporter::import(plugin.clone(), connection.as_ref(), import_config, data).await {
+            // 边读文件边分批插入，大文件不会整个读进内存
+            let import_result = DataImporter::import_file(
+                plugin.clone(),
+                connection.as_ref(),
+                import_config,
+                std::path::Path::new(&file_path_str),
+                None,
+            ).await;
+
+            match import_result {
                 Ok(result) => {
                     cx.update(|cx| {
                         status.update(cx, |s, cx| {
@@ -420,11 +550,21 @@ impl Clone for TableImportView {
             record_separator: self.record_separator.clone(),
             field_separator: self.field_separator.clone(),
             text_qualifier: self.text_qualifier.clone(),
+            encoding: self.encoding.clone(),
+            csv_sample: self.csv_sample.clone(),
+            pending_sniff: self.pending_sniff.clone(),
 
             has_header: self.has_header.clone(),
             stop_on_error: self.stop_on_error.clone(),
             use_transaction: self.use_transaction.clone(),
             truncate_before: self.truncate_before.clone(),
+            async_insert: self.async_insert.clone(),
+            supports_async_insert: self.supports_async_insert,
+            batch_size: self.batch_size.clone(),
+
+            column_mapping: self.column_mapping.clone(),
+            date_format: self.date_format.clone(),
+            decimal_separator: self.decimal_separator.clone(),
 
             status: self.status.clone(),
             focus_handle: self.focus_handle.clone(),
@@ -441,10 +581,21 @@ impl Render for TableImportView {
             });
             self.pending_file_path.update(cx, |p, _| *p = None);
         }
+        if let Some((encoding, sniffed)) = self.pending_sniff.read(cx).clone() {
+            self.pending_sniff.update(cx, |p, _| *p = None);
+            self.apply_sniffed(encoding, &sniffed, window, cx);
+        }
 
         let status_text = self.status.read(cx).clone();
         let _current_format = *self.format.read(cx);
         let current_format_display = self.format_display.read(cx).clone();
+        // 随分隔符、编码等设置实时更新
+        let preview = if *self.format.read(cx) == DataFormat::Csv {
+            self.csv_sample.read(cx).as_deref().map(|sample| preview_csv(sample, self.selected_encoding(cx), &self.csv_config(cx), PREVIEW_ROWS))
+        } else {
+            None
+        };
+        let preview_has_header = *self.has_header.read(cx);
 
         v_flex()
             .gap_4()
@@ -519,6 +670,22 @@ impl Render for TableImportView {
                                     });
                                 }))
                             })
+                            .child({
+                                let mut btn = Button::new("format_avro").child("Avro");
+                                if current_format_display == "Avro" {
+                                    btn = btn.primary();
+                                }
+                                btn.on_click(window.listener_for(&cx.entity(), |view, _, _, cx| {
+                                    view.format.update(cx, |f, cx| {
+                                        *f = DataFormat::Avro;
+                                        cx.notify();
+                                    });
+                                    view.format_display.update(cx, |d, cx| {
+                                        *d = "Avro".to_string();
+                                        cx.notify();
+                                    });
+                                }))
+                            })
                     ),
             )
             .child(
@@ -536,6 +703,16 @@ impl Render for TableImportView {
                             })),
                     ),
             )
+            // Avro 中的字符串总是 UTF-8
+            .when(current_format_display != "Avro", |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().w_20().child("编码:"))
+                        .child(Select::new(&self.encoding).w_40())
+                )
+            })
             // 分隔符配置（仅对 CSV/TXT 显示）
             .child(
                 if current_format_display != "JSON" {
@@ -643,7 +820,82 @@ impl Render for TableImportView {
                                     }))
                             )
                             .child("导入前清空表"),
-                    ),
+                    )
+                    .when(self.supports_async_insert, |this| {
+                        this.child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    Switch::new("async_insert")
+                                        .checked(*self.async_insert.read(cx))
+                                        .on_click(cx.listener(|view, checked, _, cx| {
+                                            view.async_insert.update(cx, |state, cx| {
+                                                *state = *checked;
+                                                cx.notify();
+                                            });
+                                        }))
+                                )
+                                .child("异步插入 (async_insert)"),
+                        )
+                    }),
+            )
+            .when_some(preview.filter(|rows| !rows.is_empty()), |this, rows| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .p_3()
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .child(
+                            div()
+                                .text_sm()
+                                .font_weight(gpui::FontWeight::SEMIBOLD)
+                                .child("预览")
+                        )
+                        .children(rows.into_iter().enumerate().map(|(row_index, row)| {
+                            h_flex()
+                                .gap_2()
+                                .text_xs()
+                                .when(row_index == 0 && preview_has_header, |this| {
+                                    this.font_weight(gpui::FontWeight::SEMIBOLD)
+                                })
+                                .children(row.into_iter().map(|cell| div().w_32().truncate().child(cell)))
+                        }))
+                )
+            })
+            .child(
+                v_flex()
+                    .gap_3()
+                    .p_3()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded_md()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(gpui::FontWeight::SEMIBOLD)
+                            .child("列映射")
+                    )
+                    .child(Input::new(&self.column_mapping).h(px(96.)))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(div().w_24().child("日期格式:"))
+                            .child(Input::new(&self.date_format).w_40())
+                            .child(div().w_24().child("小数点:"))
+                            .child(Input::new(&self.decimal_separator).w_16())
+                    )
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().w_20().child("批大小:"))
+                    .child(Input::new(&self.batch_size).w_24())
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child("每条 INSERT 的行数")),
             )
             .child(
                 h_flex()
@@ -1,16 +1,33 @@
+pub mod activity_monitor;
+pub mod ai_anonymize;
+pub mod ai_chart;
 pub mod ai_chat_panel;
 pub mod ai_input;
+pub mod ai_sql;
+pub mod ai_table_design;
+pub mod ai_test_data;
+pub mod ai_tools;
 pub mod common;
+pub mod confirm_policy;
+pub mod connection_tag;
 pub mod database_objects_tab;
 pub mod database_tab;
 pub mod db_tree_view;
+pub mod prompt_variables;
+pub mod query_run_trend;
 pub mod sql_editor;
 #[cfg(test)]
 mod sql_editor_completion_tests;
 pub mod sql_editor_view;
 pub mod sql_result_tab;
+pub mod sql_snippet_builder;
 pub mod table_data_tab;
 pub mod table_designer;
+pub mod variable_prompt;
+mod trash_browser;
+mod extension_browser;
+mod connection_health;
+mod session_variables;
 mod db_tree_event;
 pub mod database_view_plugin;
 pub mod mysql;
@@ -19,7 +36,16 @@ pub mod mssql;
 pub mod oracle;
 pub mod clickhouse;
 pub mod sqlite;
+pub mod odbc;
+pub mod mongodb;
+pub mod redis;
+pub mod duckdb;
+pub mod cockroachdb;
+pub mod tidb;
+pub mod trino;
+pub mod elasticsearch;
 mod import_export;
 mod table_data;
+mod schema_cache;
 
 pub use common::DatabaseFormEvent;
@@ -0,0 +1 @@
+pub mod mongodb_view_plugin;
@@ -87,6 +87,7 @@ impl DatabaseViewPlugin for MsSqlDatabaseViewPlugin {
             supports_dump_database: true,
             supports_create_schema: true,
             supports_delete_schema: true,
+            supports_maintenance: false,
             supports_create_view: true,
             supports_edit_view: true,
             supports_sequences: true,
@@ -0,0 +1 @@
+pub mod odbc_view_plugin;
@@ -0,0 +1,180 @@
+//! 提示词中的数据库上下文变量：`{{connection}}`、`{{database}}`、`{{table}}`、`{{columns}}`，
+//! 发送给模型前按树中当前选中的节点替换
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use db::{ColumnInfo, DbNode, DbNodeType, GlobalDbState};
+use gpui::{App, AsyncApp, Global};
+
+pub const PROMPT_VARIABLES: [&str; 4] = ["connection", "database", "table", "columns"];
+
+/// 数据库树中最近选中的节点
+#[derive(Clone, Default)]
+pub struct SelectedTreeNode(Option<DbNode>);
+
+impl Global for SelectedTreeNode {}
+
+impl SelectedTreeNode {
+    pub fn get(cx: &App) -> Option<DbNode> {
+        cx.try_global::<SelectedTreeNode>().and_then(|selected| selected.0.clone())
+    }
+
+    pub fn set(node: DbNode, cx: &mut App) {
+        cx.set_global(SelectedTreeNode(Some(node)));
+    }
+}
+
+fn placeholder(name: &str) -> String {
+    format!("{{{{{}}}}}", name)
+}
+
+/// 文本中引用的上下文变量，按 `PROMPT_VARIABLES` 的顺序
+pub fn referenced_prompt_variables(text: &str) -> Vec<&'static str> {
+    PROMPT_VARIABLES
+        .into_iter()
+        .filter(|name| text.contains(&placeholder(name)))
+        .collect()
+}
+
+/// 把 `{{name}}` 替换为 `values` 中的值，没有值的变量保持原样
+pub fn substitute_prompt_variables(text: &str, values: &HashMap<&str, String>) -> String {
+    let mut result = text.to_string();
+    for name in PROMPT_VARIABLES {
+        if let Some(value) = values.get(name) {
+            result = result.replace(&placeholder(name), value);
+        }
+    }
+    result
+}
+
+/// 节点所在的表：表和视图节点本身，或列、索引等表下级节点记录的表名
+fn node_table(node: &DbNode) -> Option<String> {
+    match node.node_type {
+        DbNodeType::Table | DbNodeType::View | DbNodeType::MaterializedView => Some(node.name.clone()),
+        _ => node.metadata.as_ref().and_then(|m| m.get("table")).cloned(),
+    }
+}
+
+fn node_database(node: &DbNode) -> Option<String> {
+    match node.node_type {
+        DbNodeType::Database | DbNodeType::Catalog => Some(node.name.clone()),
+        _ => node.metadata.as_ref().and_then(|m| m.get("database")).cloned(),
+    }
+}
+
+pub fn format_columns(columns: &[ColumnInfo]) -> String {
+    columns
+        .iter()
+        .map(|column| {
+            let mut line = format!("{} {}", column.name, column.data_type);
+            if column.is_primary_key {
+                line.push_str(" PK");
+            }
+            if !column.is_nullable {
+                line.push_str(" NOT NULL");
+            }
+            if let Some(comment) = column.comment.as_ref().filter(|c| !c.is_empty()) {
+                line.push_str(&format!(" -- {}", comment));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 替换文本中引用的上下文变量；选中的节点无法提供某个变量时返回错误并列出缺少的变量
+pub async fn resolve_prompt_variables(text: String, node: Option<DbNode>, cx: &mut AsyncApp) -> Result<String> {
+    let referenced = referenced_prompt_variables(&text);
+    if referenced.is_empty() {
+        return Ok(text);
+    }
+    let Some(node) = node else {
+        return Err(anyhow!("请先在数据库树中选中节点"));
+    };
+
+    let global_state = cx.update(|cx| cx.global::<GlobalDbState>().clone())?;
+    let database = node_database(&node);
+    let table = node_table(&node);
+    let mut values: HashMap<&str, String> = HashMap::new();
+
+    if referenced.contains(&"connection") {
+        if let Some(config) = global_state.get_config_async(&node.connection_id).await {
+            values.insert("connection", config.name);
+        }
+    }
+    if let Some(database) = &database {
+        values.insert("database", database.clone());
+    }
+    if let Some(table) = &table {
+        values.insert("table", table.clone());
+    }
+    if referenced.contains(&"columns")
+        && let (Some(database), Some(table)) = (database, table)
+    {
+        let schema = node.metadata.as_ref().and_then(|m| m.get("schema")).cloned();
+        let columns = global_state
+            .list_columns(cx, node.connection_id.clone(), database, schema, table)
+            .await?;
+        values.insert("columns", format_columns(&columns));
+    }
+
+    let missing: Vec<String> = referenced
+        .iter()
+        .filter(|name| !values.contains_key(*name))
+        .map(|name| placeholder(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!("选中的节点 {} 无法提供 {}", node.name, missing.join("、")));
+    }
+
+    Ok(substitute_prompt_variables(&text, &values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_referenced_prompt_variables() {
+        assert_eq!(
+            referenced_prompt_variables("{{table}} 中 {{columns}} 哪些可以建索引？{{table}}"),
+            vec!["table", "columns"]
+        );
+        assert!(referenced_prompt_variables("{{ table }} ${table} {table}").is_empty());
+    }
+
+    #[test]
+    fn test_substitute_keeps_unknown_placeholders() {
+        let values = HashMap::from([("database", "shop".to_string()), ("table", "orders".to_string())]);
+        assert_eq!(
+            substitute_prompt_variables("统计 {{database}}.{{table}} 的行数，参考 {{columns}} 和 {{schema}}", &values),
+            "统计 shop.orders 的行数，参考 {{columns}} 和 {{schema}}"
+        );
+    }
+
+    #[test]
+    fn test_format_columns() {
+        let columns = vec![
+            ColumnInfo {
+                name: "id".to_string(),
+                data_type: "bigint".to_string(),
+                is_nullable: false,
+                is_primary_key: true,
+                default_value: None,
+                comment: None,
+                collation: None,
+            },
+            ColumnInfo {
+                name: "status".to_string(),
+                data_type: "varchar(20)".to_string(),
+                is_nullable: true,
+                is_primary_key: false,
+                default_value: None,
+                comment: Some("订单状态".to_string()),
+                collation: None,
+            },
+        ];
+        assert_eq!(format_columns(&columns), "id bigint PK NOT NULL\nstatus varchar(20) -- 订单状态");
+    }
+}
@@ -0,0 +1,229 @@
+//! 保存的查询的执行趋势 - 记录每次执行的耗时和行数，在编辑器工具栏下方用折线图展示最近的执行，
+//! 最近一次明显慢于历史中位数时高亮提示
+
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, App, AsyncApp, Context, Entity, IntoElement, ParentElement, Render, Styled, Window};
+use gpui_component::{chart::LineChart, h_flex, ActiveTheme};
+use one_core::gpui_tokio::Tokio;
+use one_core::storage::query_model::QueryRun;
+use one_core::storage::query_repository::QueryRepository;
+use one_core::storage::GlobalStorageState;
+use tracing::log::error;
+
+use crate::sql_result_tab::ScriptRunStats;
+
+/// 图表展示的最近执行次数
+const TREND_RUNS: i64 = 30;
+/// 判断变慢时至少需要的历史执行次数
+const MIN_BASELINE_RUNS: usize = 5;
+/// 最近一次耗时超过历史中位数的倍数时视为变慢
+const REGRESSION_FACTOR: f64 = 2.0;
+
+/// 最近一次执行相对历史的变化
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunTrendSummary {
+    pub latest_ms: i64,
+    pub median_ms: i64,
+    pub latest_rows: i64,
+    pub regressed: bool,
+}
+
+fn median(values: &mut [i64]) -> i64 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2
+    } else {
+        values[middle]
+    }
+}
+
+/// 以最近一次之前的执行为基线；基线太短时不判断变慢
+pub fn summarize_runs(runs: &[QueryRun]) -> Option<RunTrendSummary> {
+    let (latest, history) = runs.split_last()?;
+    if history.is_empty() {
+        return Some(RunTrendSummary {
+            latest_ms: latest.elapsed_ms,
+            median_ms: latest.elapsed_ms,
+            latest_rows: latest.row_count,
+            regressed: false,
+        });
+    }
+
+    let mut durations: Vec<i64> = history.iter().map(|run| run.elapsed_ms).collect();
+    let median_ms = median(&mut durations);
+    let regressed = history.len() >= MIN_BASELINE_RUNS
+        && latest.elapsed_ms as f64 > median_ms.max(1) as f64 * REGRESSION_FACTOR;
+
+    Some(RunTrendSummary {
+        latest_ms: latest.elapsed_ms,
+        median_ms,
+        latest_rows: latest.row_count,
+        regressed,
+    })
+}
+
+#[derive(Clone)]
+struct TrendPoint {
+    label: String,
+    elapsed_ms: f64,
+}
+
+pub struct QueryRunTrend {
+    query_id: i64,
+    runs: Vec<QueryRun>,
+}
+
+impl QueryRunTrend {
+    pub fn new(query_id: i64, cx: &mut Context<Self>) -> Self {
+        let trend = Self {
+            query_id,
+            runs: Vec::new(),
+        };
+        Self::reload(cx.entity(), query_id, cx);
+        trend
+    }
+
+    /// 写入一次执行记录并刷新图表
+    pub fn record(&mut self, stats: ScriptRunStats, cx: &mut Context<Self>) {
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let query_id = self.query_id;
+        let entity = cx.entity();
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let task = Tokio::spawn_result(cx, async move {
+                let query_repo = storage
+                    .get::<QueryRepository>()
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Query repository not found"))?;
+                query_repo.record_run(query_id, stats.elapsed_ms, stats.row_count).await
+            });
+            let result = match task {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => {
+                    if let Err(e) = cx.update(|cx| Self::reload(entity, query_id, cx)) {
+                        error!("Failed to reload query runs: {}", e);
+                    }
+                }
+                Err(e) => error!("Failed to record run of query {}: {}", query_id, e),
+            }
+        })
+        .detach();
+    }
+
+    fn reload(entity: Entity<Self>, query_id: i64, cx: &mut App) {
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let task = Tokio::spawn_result(cx, async move {
+                let query_repo = storage
+                    .get::<QueryRepository>()
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Query repository not found"))?;
+                query_repo.list_runs(query_id, TREND_RUNS).await
+            });
+            let result = match task {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(runs) => {
+                    if let Err(e) = cx.update(|cx| {
+                        entity.update(cx, |trend, cx| {
+                            trend.runs = runs;
+                            cx.notify();
+                        })
+                    }) {
+                        error!("Failed to update query runs: {}", e);
+                    }
+                }
+                Err(e) => error!("Failed to load runs of query {}: {}", query_id, e),
+            }
+        })
+        .detach();
+    }
+}
+
+impl Render for QueryRunTrend {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(summary) = summarize_runs(&self.runs) else {
+            return div().into_any_element();
+        };
+
+        let points: Vec<TrendPoint> = self
+            .runs
+            .iter()
+            .enumerate()
+            .map(|(index, run)| TrendPoint {
+                label: (index + 1).to_string(),
+                elapsed_ms: run.elapsed_ms as f64,
+            })
+            .collect();
+
+        h_flex()
+            .gap_3()
+            .px_2()
+            .items_center()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(format!("最近 {} 次执行", self.runs.len()))
+            .child(
+                div().w(px(240.)).h(px(36.)).child(
+                    LineChart::new(points)
+                        .x(|point| point.label.clone())
+                        .y(|point| point.elapsed_ms),
+                ),
+            )
+            .child(format!(
+                "本次 {} ms · 中位数 {} ms · {} 行",
+                summary.latest_ms, summary.median_ms, summary.latest_rows
+            ))
+            .when(summary.regressed, |this| {
+                this.child(
+                    div()
+                        .text_color(cx.theme().danger)
+                        .child("明显慢于以往，检查数据量或执行计划是否变化"),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(durations: &[i64]) -> Vec<QueryRun> {
+        durations
+            .iter()
+            .enumerate()
+            .map(|(index, elapsed_ms)| QueryRun {
+                query_id: 1,
+                elapsed_ms: *elapsed_ms,
+                row_count: 100,
+                executed_at: index as i64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_summarize_runs_flags_regression_against_median() {
+        let summary = summarize_runs(&runs(&[100, 120, 90, 110, 105, 400])).unwrap();
+        assert_eq!(summary.median_ms, 105);
+        assert_eq!(summary.latest_ms, 400);
+        assert!(summary.regressed);
+
+        let steady = summarize_runs(&runs(&[100, 120, 90, 110, 105, 130])).unwrap();
+        assert!(!steady.regressed);
+    }
+
+    #[test]
+    fn test_summarize_runs_needs_baseline() {
+        assert_eq!(summarize_runs(&[]), None);
+        assert!(!summarize_runs(&runs(&[100, 900])).unwrap().regressed);
+        assert_eq!(summarize_runs(&runs(&[80])).unwrap().median_ms, 80);
+    }
+}
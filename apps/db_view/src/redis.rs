@@ -0,0 +1,2 @@
+pub mod key_view;
+pub mod redis_view_plugin;
@@ -0,0 +1,416 @@
+//! Redis 键值视图 - 按类型查看和编辑单个键的内容、修改 TTL、删除键
+
+use std::any::Any;
+
+use db::redis::command::{format_command, RedisKeyType};
+use db::{GlobalDbState, SqlResult};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Icon, IconName, Sizable, WindowExt,
+};
+use one_core::tab_container::{TabContent, TabContentType};
+
+/// 一次最多读取的元素数量，超大集合只展示前面部分
+const MAX_ELEMENTS: usize = 1000;
+
+/// 取查询结果第一行第一列
+fn first_value(result: &SqlResult) -> Option<String> {
+    match result {
+        SqlResult::Query(query) => query.rows.first().and_then(|row| row.first().cloned().flatten()),
+        _ => None,
+    }
+}
+
+/// 把读取命令的结果转换为 (元素, 值) 行：列表的元素是下标，字符串只有值
+fn value_rows(key_type: RedisKeyType, result: SqlResult) -> Result<Vec<(String, String)>, String> {
+    let query = match result {
+        SqlResult::Query(query) => query,
+        SqlResult::Exec(_) => return Ok(Vec::new()),
+        SqlResult::Error(err) => return Err(err.message),
+    };
+    let rows = query.rows.into_iter().enumerate().map(|(index, row)| {
+        let mut cells = row.into_iter().map(Option::unwrap_or_default);
+        let first = cells.next().unwrap_or_default();
+        let second = cells.next().unwrap_or_default();
+        match key_type {
+            RedisKeyType::List => (index.to_string(), first),
+            RedisKeyType::Set | RedisKeyType::String | RedisKeyType::None => (first.clone(), first),
+            RedisKeyType::Hash | RedisKeyType::ZSet | RedisKeyType::Stream => (first, second),
+        }
+    });
+    Ok(rows.collect())
+}
+
+pub struct RedisKeyView {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    database: String,
+    key: String,
+    key_type: RedisKeyType,
+    ttl: Option<i64>,
+    rows: Vec<(String, String)>,
+    selected: Option<usize>,
+    loading: bool,
+    ttl_input: Entity<InputState>,
+    entry_input: Entity<InputState>,
+    value_input: Entity<InputState>,
+}
+
+impl RedisKeyView {
+    pub fn new(connection_id: String, database: String, key: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let ttl_input = cx.new(|cx| InputState::new(window, cx).placeholder("秒，留空表示永不过期"));
+        let entry_input = cx.new(|cx| InputState::new(window, cx).placeholder("字段 / 下标 / 成员"));
+        let value_input = cx.new(|cx| InputState::new(window, cx).multi_line(true).placeholder("值"));
+
+        let mut view = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            database,
+            key,
+            key_type: RedisKeyType::None,
+            ttl: None,
+            rows: Vec::new(),
+            selected: None,
+            loading: false,
+            ttl_input,
+            entry_input,
+            value_input,
+        };
+        view.load(window, cx);
+        view
+    }
+
+    fn load(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.loading = true;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let script = format!("{}\n{}", format_command(&["TYPE", &self.key]), format_command(&["TTL", &self.key]));
+        let key = self.key.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            // 先取类型才能决定读取命令，所以分两次执行
+            let header = global_state
+                .execute_script(cx, connection_id.clone(), script, Some(database.clone()), None)
+                .await;
+            let loaded = match header {
+                Ok(results) => {
+                    let key_type = RedisKeyType::from_type_reply(&results.first().and_then(first_value).unwrap_or_default());
+                    let ttl = results.get(1).and_then(first_value).and_then(|ttl| ttl.parse::<i64>().ok());
+                    let rows = match key_type.read_command(&key, MAX_ELEMENTS) {
+                        Some(command) => global_state
+                            .execute_single(cx, connection_id, command, Some(database), None)
+                            .await
+                            .map_err(|e| e.to_string())
+                            .and_then(|result| value_rows(key_type, result)),
+                        None => Ok(Vec::new()),
+                    };
+                    rows.map(|rows| (key_type, ttl, rows))
+                }
+                Err(e) => Err(e.to_string()),
+            };
+
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                let _ = this.update(cx, |view, cx| {
+                    view.loading = false;
+                    view.selected = None;
+                    match loaded {
+                        Ok((key_type, ttl, rows)) => {
+                            view.key_type = key_type;
+                            view.ttl = ttl;
+                            view.rows = rows;
+                            // TTL 为 -1 表示永不过期，-2 表示键不存在
+                            let ttl_text = ttl.filter(|ttl| *ttl >= 0).map(|ttl| ttl.to_string()).unwrap_or_default();
+                            view.ttl_input.update(cx, |input, cx| input.set_value(ttl_text, window, cx));
+                            let string_value = match key_type {
+                                RedisKeyType::String => view.rows.first().map(|(_, value)| value.clone()).unwrap_or_default(),
+                                _ => String::new(),
+                            };
+                            view.entry_input.update(cx, |input, cx| input.set_value(String::new(), window, cx));
+                            view.value_input.update(cx, |input, cx| input.set_value(string_value, window, cx));
+                        }
+                        Err(e) => window.push_notification(Notification::error(format!("读取键失败: {}", e)), cx),
+                    }
+                    cx.notify();
+                });
+            });
+        }).detach();
+    }
+
+    /// 执行一条修改命令，成功后重新加载
+    fn run_command(&mut self, command: String, success: String, window: &mut Window, cx: &mut Context<Self>) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state
+                .execute_single(cx, connection_id, command, Some(database), None)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| match result {
+                    SqlResult::Error(err) => Err(err.message),
+                    _ => Ok(()),
+                });
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                match result {
+                    Ok(()) => window.push_notification(Notification::success(success).autohide(true), cx),
+                    Err(e) => window.push_notification(Notification::error(format!("执行失败: {}", e)), cx),
+                }
+                let _ = this.update(cx, |view, cx| view.load(window, cx));
+            });
+        }).detach();
+    }
+
+    fn select_row(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((entry, value)) = self.rows.get(index).cloned() else {
+            return;
+        };
+        self.selected = Some(index);
+        self.entry_input.update(cx, |input, cx| input.set_value(entry, window, cx));
+        self.value_input.update(cx, |input, cx| input.set_value(value, window, cx));
+        cx.notify();
+    }
+
+    fn save_entry(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entry = self.entry_input.read(cx).text().to_string();
+        let value = self.value_input.read(cx).text().to_string();
+        let Some(command) = self.key_type.set_entry_command(&self.key, entry.trim(), &value) else {
+            window.push_notification(Notification::error(format!("{} 类型不支持在此编辑", self.key_type.as_str())), cx);
+            return;
+        };
+        self.run_command(command, "已保存".to_string(), window, cx);
+    }
+
+    fn remove_entry(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let entry = self.entry_input.read(cx).text().to_string();
+        let value = self.value_input.read(cx).text().to_string();
+        let Some(command) = self.key_type.remove_entry_command(&self.key, entry.trim(), &value) else {
+            return;
+        };
+        self.run_command(command, "已删除元素".to_string(), window, cx);
+    }
+
+    fn apply_ttl(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.ttl_input.read(cx).text().trim().to_string();
+        let command = if text.is_empty() {
+            format_command(&["PERSIST", &self.key])
+        } else {
+            match text.parse::<u64>() {
+                Ok(seconds) => format_command(&["EXPIRE", &self.key, &seconds.to_string()]),
+                Err(_) => {
+                    window.push_notification(Notification::error("TTL 必须是非负整数"), cx);
+                    return;
+                }
+            }
+        };
+        self.run_command(command, "TTL 已更新".to_string(), window, cx);
+    }
+
+    fn delete_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let command = format_command(&["DEL", &self.key]);
+        self.run_command(command, format!("已删除键 {}", self.key), window, cx);
+    }
+
+    fn ttl_label(&self) -> String {
+        match self.ttl {
+            Some(-2) => "键不存在".to_string(),
+            Some(ttl) if ttl >= 0 => format!("TTL {} 秒", ttl),
+            _ => "永不过期".to_string(),
+        }
+    }
+
+    fn render_rows(&self, cx: &mut Context<Self>) -> AnyElement {
+        let (entry_label, value_label) = match self.key_type {
+            RedisKeyType::Hash => ("字段", "值"),
+            RedisKeyType::List => ("下标", "值"),
+            RedisKeyType::ZSet => ("成员", "分数"),
+            RedisKeyType::Stream => ("ID", "字段"),
+            _ => ("成员", ""),
+        };
+        let show_value = !value_label.is_empty();
+
+        v_flex()
+            .id("redis-key-rows")
+            .flex_1()
+            .min_h(px(160.))
+            .overflow_y_scroll()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .text_sm()
+            .font_family("monospace")
+            .child(
+                h_flex()
+                    .px_3()
+                    .py_1()
+                    .gap_3()
+                    .bg(cx.theme().muted)
+                    .text_color(cx.theme().muted_foreground)
+                    .child(div().w(px(240.)).child(entry_label))
+                    .when(show_value, |this| this.child(div().flex_1().child(value_label))),
+            )
+            .children(self.rows.iter().enumerate().map(|(index, (entry, value))| {
+                h_flex()
+                    .id(SharedString::from(format!("redis-row-{}", index)))
+                    .px_3()
+                    .py_1()
+                    .gap_3()
+                    .cursor_pointer()
+                    .when(self.selected == Some(index), |this| this.bg(cx.theme().accent))
+                    .hover(|this| this.bg(cx.theme().muted))
+                    .child(div().w(px(240.)).truncate().child(entry.clone()))
+                    .when(show_value, |this| this.child(div().flex_1().truncate().child(value.clone())))
+                    .on_click(cx.listener(move |view, _, window, cx| view.select_row(index, window, cx)))
+            }))
+            .into_any_element()
+    }
+}
+
+impl Focusable for RedisKeyView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RedisKeyView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let key_type = self.key_type;
+        let is_string = key_type == RedisKeyType::String;
+        let editable = key_type.set_entry_command(&self.key, "", "").is_some();
+        let removable = key_type.remove_entry_command(&self.key, "", "").is_some();
+
+        let header = h_flex()
+            .gap_3()
+            .items_center()
+            .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).font_family("monospace").child(self.key.clone()))
+            .child(div().text_xs().px_2().rounded_md().bg(cx.theme().muted).child(key_type.as_str()))
+            .child(div().text_xs().text_color(cx.theme().muted_foreground).child(self.ttl_label()))
+            .when(self.loading, |this| {
+                this.child(div().text_xs().text_color(cx.theme().muted_foreground).child("加载中..."))
+            })
+            .child(div().flex_1())
+            .child(
+                Button::new("redis-key-refresh")
+                    .small()
+                    .label("刷新")
+                    .disabled(self.loading)
+                    .on_click(cx.listener(|view, _, window, cx| view.load(window, cx))),
+            )
+            .child(
+                Button::new("redis-key-delete")
+                    .small()
+                    .danger()
+                    .label("删除键")
+                    .disabled(self.loading || key_type == RedisKeyType::None)
+                    .on_click(cx.listener(|view, _, window, cx| view.delete_key(window, cx))),
+            );
+
+        let ttl_row = h_flex()
+            .gap_2()
+            .items_center()
+            .child(div().text_sm().child("TTL"))
+            .child(Input::new(&self.ttl_input).w(px(200.)).small())
+            .child(
+                Button::new("redis-key-ttl")
+                    .small()
+                    .label("应用")
+                    .disabled(self.loading || key_type == RedisKeyType::None)
+                    .on_click(cx.listener(|view, _, window, cx| view.apply_ttl(window, cx))),
+            );
+
+        let editor = v_flex()
+            .gap_2()
+            .when(!is_string, |this| this.child(Input::new(&self.entry_input).small()))
+            .child(Input::new(&self.value_input).h(px(if is_string { 320. } else { 120. })))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("redis-entry-save")
+                            .small()
+                            .primary()
+                            .label("保存")
+                            .disabled(self.loading || !editable)
+                            .on_click(cx.listener(|view, _, window, cx| view.save_entry(window, cx))),
+                    )
+                    .when(removable, |this| {
+                        this.child(
+                            Button::new("redis-entry-remove")
+                                .small()
+                                .label("删除")
+                                .disabled(self.loading || self.selected.is_none())
+                                .on_click(cx.listener(|view, _, window, cx| view.remove_entry(window, cx))),
+                        )
+                    })
+                    .when(self.rows.len() >= MAX_ELEMENTS && !is_string, |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("仅显示前 {} 个元素", MAX_ELEMENTS)),
+                        )
+                    }),
+            );
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_3()
+            .child(header)
+            .child(ttl_row)
+            .when(!is_string, |this| this.child(self.render_rows(cx)))
+            .child(editor)
+    }
+}
+
+pub struct RedisKeyTabContent {
+    title: SharedString,
+    view: Entity<RedisKeyView>,
+}
+
+impl RedisKeyTabContent {
+    pub fn new(connection_id: String, database: String, key: String, window: &mut Window, cx: &mut App) -> Self {
+        let title = format!("{}: {}", database, key);
+        let view = cx.new(|cx| RedisKeyView::new(connection_id, database, key, window, cx));
+        Self {
+            title: title.into(),
+            view,
+        }
+    }
+}
+
+impl TabContent for RedisKeyTabContent {
+    fn title(&self) -> SharedString {
+        self.title.clone()
+    }
+
+    fn icon(&self) -> Option<Icon> {
+        Some(IconName::RedisColor.color())
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn render_content(&self, _window: &mut Window, _cx: &mut App) -> AnyElement {
+        self.view.clone().into_any_element()
+    }
+
+    fn content_type(&self) -> TabContentType {
+        TabContentType::Custom("RedisKey".to_string())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -0,0 +1,78 @@
+//! 数据库树的持久化缓存 - 启动后首次展开节点时先显示上次加载的子节点，再在后台刷新
+
+use anyhow::{anyhow, Result};
+use db::{DbNode, DbNodeType};
+use gpui::AsyncApp;
+use one_core::gpui_tokio::Tokio;
+use one_core::storage::{SchemaCacheRepository, StorageManager};
+
+/// 缓存写入超过该时间（秒）后，展开节点时在后台重新加载
+pub const SCHEMA_CACHE_TTL_SECS: i64 = 10 * 60;
+
+/// 已保存的查询来自本地存储，加载本身就很快，不需要缓存
+pub fn is_cacheable(node_type: &DbNodeType) -> bool {
+    !matches!(node_type, DbNodeType::QueriesFolder | DbNodeType::NamedQuery)
+}
+
+/// 缓存命中的子节点
+pub struct CachedChildren {
+    pub children: Vec<DbNode>,
+    /// 序列化后的内容，用于判断后台刷新的结果是否有变化
+    pub json: String,
+    pub stale: bool,
+}
+
+async fn repository(storage: &StorageManager) -> Result<std::sync::Arc<SchemaCacheRepository>> {
+    storage
+        .get::<SchemaCacheRepository>()
+        .await
+        .ok_or_else(|| anyhow!("SchemaCacheRepository not found"))
+}
+
+pub async fn load(
+    storage: StorageManager,
+    connection_id: i64,
+    node_id: String,
+    cx: &mut AsyncApp,
+) -> Result<Option<CachedChildren>> {
+    let entry = Tokio::spawn_result(cx, async move {
+        repository(&storage).await?.get(connection_id, &node_id).await
+    })?
+    .await?;
+
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+    let children: Vec<DbNode> = serde_json::from_str(&entry.children)?;
+    Ok(Some(CachedChildren {
+        children,
+        stale: entry.is_stale(SCHEMA_CACHE_TTL_SECS),
+        json: entry.children,
+    }))
+}
+
+pub async fn store(
+    storage: StorageManager,
+    connection_id: i64,
+    node_id: String,
+    json: String,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    Tokio::spawn_result(cx, async move {
+        repository(&storage).await?.put(connection_id, &node_id, &json).await
+    })?
+    .await
+}
+
+/// 删除节点及其所有后代的缓存
+pub async fn invalidate(
+    storage: StorageManager,
+    connection_id: i64,
+    node_id: String,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    Tokio::spawn_result(cx, async move {
+        repository(&storage).await?.invalidate(connection_id, &node_id).await
+    })?
+    .await
+}
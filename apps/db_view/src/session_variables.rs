@@ -0,0 +1,356 @@
+//! 会话变量 - 查看并修改连接会话的设置（SHOW VARIABLES、pg_settings 等）
+//!
+//! 查询和修改都在标签页自己持有的会话上执行，这样 SET 的结果能在列表中看到；
+//! 其它标签页的查询使用各自的会话，不受这里的修改影响。
+
+use std::any::Any;
+
+use db::{GlobalDbState, SessionVariable};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Subscription, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Icon, IconName, Sizable, WindowExt,
+};
+use one_core::tab_container::{TabContent, TabContentType};
+
+/// 正在行内编辑的变量
+struct EditingVariable {
+    name: String,
+    input: Entity<InputState>,
+    _input_sub: Subscription,
+}
+
+pub struct SessionVariablesView {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    /// 标签页持有的会话，出错后丢弃，下次刷新时重新打开
+    session_id: Option<String>,
+    filter_input: Entity<InputState>,
+    variables: Vec<SessionVariable>,
+    editing: Option<EditingVariable>,
+    loading: bool,
+    error: Option<String>,
+    _filter_sub: Subscription,
+}
+
+impl SessionVariablesView {
+    pub fn new(connection_id: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let filter_input = cx.new(|cx| InputState::new(window, cx).placeholder("搜索变量名、值或说明"));
+        let filter_sub = cx.subscribe_in(&filter_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
+                cx.notify();
+            }
+        });
+
+        cx.on_release(|view: &mut Self, cx: &mut App| {
+            if let Some(session_id) = view.session_id.take() {
+                let global_state = cx.global::<GlobalDbState>().clone();
+                cx.spawn(async move |cx: &mut AsyncApp| {
+                    let _ = global_state.close_session(cx, session_id).await;
+                }).detach();
+            }
+        }).detach();
+
+        let mut view = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            session_id: None,
+            filter_input,
+            variables: Vec::new(),
+            editing: None,
+            loading: false,
+            error: None,
+            _filter_sub: filter_sub,
+        };
+        view.reload(cx);
+        view
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        if self.loading {
+            return;
+        }
+        self.loading = true;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let session_id = self.session_id.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let session_id = match session_id {
+                Some(session_id) => Ok(session_id),
+                None => global_state.create_session(cx, connection_id, None).await,
+            };
+            let (session_id, result) = match session_id {
+                Ok(session_id) => {
+                    let result = global_state.list_session_variables(cx, session_id.clone()).await;
+                    (Some(session_id), result)
+                }
+                Err(e) => (None, Err(e)),
+            };
+            let _ = this.update(cx, |view, cx| {
+                view.loading = false;
+                view.session_id = session_id;
+                match result {
+                    Ok(variables) => {
+                        view.variables = variables;
+                        view.error = None;
+                    }
+                    Err(e) => {
+                        view.error = Some(format!("读取会话变量失败: {}", e));
+                        view.discard_session(cx);
+                    }
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    /// 会话可能已被关闭或连接已断开，关闭后下次刷新重新打开，之前的修改随之失效
+    fn discard_session(&mut self, cx: &mut Context<Self>) {
+        let Some(session_id) = self.session_id.take() else {
+            return;
+        };
+        let global_state = cx.global::<GlobalDbState>().clone();
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let _ = global_state.close_session(cx, session_id).await;
+        }).detach();
+    }
+
+    fn start_editing(&mut self, variable: &SessionVariable, window: &mut Window, cx: &mut Context<Self>) {
+        let value = variable.value.clone().unwrap_or_default();
+        let input = cx.new(|cx| InputState::new(window, cx).default_value(value));
+        let input_sub = cx.subscribe_in(&input, window, |view, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                view.apply_editing(cx);
+            }
+        });
+        input.update(cx, |input, cx| input.focus(window, cx));
+        self.editing = Some(EditingVariable {
+            name: variable.name.clone(),
+            input,
+            _input_sub: input_sub,
+        });
+        cx.notify();
+    }
+
+    fn apply_editing(&mut self, cx: &mut Context<Self>) {
+        let (Some(editing), Some(session_id)) = (self.editing.take(), self.session_id.clone()) else {
+            return;
+        };
+        let value = editing.input.read(cx).text().to_string();
+        let name = editing.name;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.set_session_variable(cx, session_id, name.clone(), value).await;
+            let _ = this.update(cx, |view, cx| match result {
+                Ok(sql) => {
+                    view.notify(Notification::success(format!("已执行 {}", sql)), cx);
+                    view.reload(cx);
+                }
+                Err(e) => view.notify(Notification::error(format!("修改 {} 失败: {}", name, e)), cx),
+            });
+        }).detach();
+    }
+
+    fn notify(&self, notification: Notification, cx: &mut App) {
+        if let Some(window) = cx.active_window() {
+            let _ = window.update(cx, |_, window, cx| {
+                window.push_notification(notification.autohide(true), cx);
+            });
+        }
+    }
+
+    fn render_variable(&self, index: usize, variable: &SessionVariable, cx: &mut Context<Self>) -> impl IntoElement {
+        let editing = self.editing.as_ref().filter(|editing| editing.name == variable.name);
+        let value = match editing {
+            Some(editing) => h_flex()
+                .gap_1()
+                .child(div().flex_1().child(Input::new(&editing.input).small()))
+                .child(
+                    Button::new(SharedString::from(format!("session-variable-apply-{}", index)))
+                        .small()
+                        .primary()
+                        .label("应用")
+                        .on_click(cx.listener(|view, _, _, cx| view.apply_editing(cx))),
+                )
+                .child(
+                    Button::new(SharedString::from(format!("session-variable-cancel-{}", index)))
+                        .small()
+                        .ghost()
+                        .label("取消")
+                        .on_click(cx.listener(|view, _, _, cx| {
+                            view.editing = None;
+                            cx.notify();
+                        })),
+                )
+                .into_any_element(),
+            None => {
+                let edit_variable = variable.clone();
+                h_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .truncate()
+                            .font_family("monospace")
+                            .child(variable.value.clone().unwrap_or_else(|| "NULL".to_string())),
+                    )
+                    .when(variable.editable && self.session_id.is_some(), |this| {
+                        this.child(
+                            Button::new(SharedString::from(format!("session-variable-edit-{}", index)))
+                                .icon(IconName::Edit)
+                                .small()
+                                .ghost()
+                                .on_click(cx.listener(move |view, _, window, cx| {
+                                    view.start_editing(&edit_variable, window, cx);
+                                })),
+                        )
+                    })
+                    .into_any_element()
+            }
+        };
+
+        h_flex()
+            .gap_2()
+            .py_1()
+            .items_center()
+            .text_xs()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .w(px(260.))
+                    .flex_shrink_0()
+                    .truncate()
+                    .when(!variable.editable, |this| this.text_color(cx.theme().muted_foreground))
+                    .child(variable.name.clone()),
+            )
+            .child(div().w(px(320.)).flex_shrink_0().child(value))
+            .child(
+                div()
+                    .flex_1()
+                    .min_w_0()
+                    .truncate()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(variable.description.clone().unwrap_or_default()),
+            )
+    }
+}
+
+impl Focusable for SessionVariablesView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SessionVariablesView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let filter = self.filter_input.read(cx).text().to_string().trim().to_lowercase();
+        let variables: Vec<SessionVariable> = self
+            .variables
+            .iter()
+            .filter(|variable| {
+                filter.is_empty()
+                    || variable.name.to_lowercase().contains(&filter)
+                    || variable.value.as_ref().is_some_and(|value| value.to_lowercase().contains(&filter))
+                    || variable.description.as_ref().is_some_and(|description| description.to_lowercase().contains(&filter))
+            })
+            .cloned()
+            .collect();
+
+        let content = if let Some(error) = &self.error {
+            div().text_sm().text_color(cx.theme().danger).child(error.clone()).into_any_element()
+        } else if self.variables.is_empty() {
+            let message = if self.loading { "加载中..." } else { "该数据库没有可查看的会话变量" };
+            div().text_sm().text_color(cx.theme().muted_foreground).child(message).into_any_element()
+        } else {
+            let mut list = v_flex();
+            for (index, variable) in variables.iter().enumerate() {
+                list = list.child(self.render_variable(index, variable, cx));
+            }
+            list.into_any_element()
+        };
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_2()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().w(px(320.)).child(Input::new(&self.filter_input).small()))
+                    .child(
+                        Button::new("session-variables-refresh")
+                            .icon(IconName::Refresh)
+                            .ghost()
+                            .small()
+                            .loading(self.loading)
+                            .on_click(cx.listener(|view, _, _, cx| view.reload(cx))),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("共 {} 项，显示 {} 项", self.variables.len(), variables.len())),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("修改只作用于本标签页的会话，关闭标签页后失效；需要对所有查询生效时请写入连接的初始化 SQL"),
+            )
+            .child(div().id("session-variables-list").flex_1().overflow_y_scroll().child(content))
+    }
+}
+
+pub struct SessionVariablesTabContent {
+    title: SharedString,
+    view: Entity<SessionVariablesView>,
+}
+
+impl SessionVariablesTabContent {
+    pub fn new(connection_id: String, connection_name: String, window: &mut Window, cx: &mut App) -> Self {
+        let view = cx.new(|cx| SessionVariablesView::new(connection_id, window, cx));
+        Self {
+            title: format!("会话变量: {}", connection_name).into(),
+            view,
+        }
+    }
+}
+
+impl TabContent for SessionVariablesTabContent {
+    fn title(&self) -> SharedString {
+        self.title.clone()
+    }
+
+    fn icon(&self) -> Option<Icon> {
+        Some(IconName::Settings.color())
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn render_content(&self, _window: &mut Window, _cx: &mut App) -> AnyElement {
+        self.view.clone().into_any_element()
+    }
+
+    fn content_type(&self) -> TabContentType {
+        TabContentType::Custom("SessionVariables".to_string())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
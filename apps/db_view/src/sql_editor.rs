@@ -1321,6 +1321,21 @@ impl SqlEditor {
         });
     }
 
+    /// Edit non-SQL commands (mongo shell, redis-cli); the SQL format/minify actions would mangle them.
+    pub fn set_command_mode(
+        &mut self,
+        language: &'static str,
+        placeholder: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |state, cx| {
+            state.set_highlighter(language, cx);
+            state.set_placeholder(placeholder, window, cx);
+            state.lsp.code_action_providers.clear();
+        });
+    }
+
     /// Access underlying editor state.
     pub fn input(&self) -> Entity<InputState> {
         self.editor.clone()
@@ -1448,6 +1463,25 @@ impl SqlEditor {
         self.editor.update(cx, |s, cx| s.set_value(text, window, cx));
     }
 
+    /// Insert text at the cursor, replacing the current selection.
+    pub fn insert_text(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor.update(cx, |s, cx| s.replace(text, window, cx));
+    }
+
+    /// Replace the first occurrence of `statement` with `replacement`.
+    /// Falls back to inserting at the cursor when the statement is no longer in the editor.
+    pub fn replace_statement(&mut self, statement: &str, replacement: String, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.editor.read(cx).text().to_string();
+        match text.find(statement.trim()) {
+            Some(start) if !statement.trim().is_empty() => {
+                let end = start + statement.trim().len();
+                let value = format!("{}{}{}", &text[..start], replacement, &text[end..]);
+                self.set_value(value, window, cx);
+            }
+            _ => self.insert_text(replacement, window, cx),
+        }
+    }
+
     /// Get the current text content of the editor.
     /// This is a convenience method that accesses the underlying InputState.
     pub fn get_text<T>(&self, cx: &Context<T>) -> String {
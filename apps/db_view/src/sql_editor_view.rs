@@ -1,21 +1,59 @@
 use one_core::storage::traits::Repository;
 use one_core::gpui_tokio::Tokio;
 use one_core::storage::DatabaseType;
+use crate::ai_input::{AIQuickAction, AIQuickActions};
+use crate::ai_sql::{diff_lines, explain_sql, extract_index_statements, generate_sql, optimize_sql, rewrite_sql, side_by_side, suggest_indexes, DiffLine, IndexAdvice, IndexAdviceRequest, SqlOptimization, SqlRewriteCommand, SqlRewriteRequest, TextToSqlRequest};
+use crate::query_run_trend::QueryRunTrend;
 use crate::sql_editor::SqlEditor;
-use crate::sql_result_tab::SqlResultTabContainer;
+use crate::sql_result_tab::{ScriptRunStats, SqlResultTabContainer};
+use crate::sql_snippet_builder::SqlSnippetBuilder;
+use crate::variable_prompt::resolve_variables;
 use one_core::tab_container::{TabContent, TabContentType};
-use db::{format_sql, compress_sql, GlobalDbState};
-use gpui::{px, AnyElement, App, AppContext, AsyncApp, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable, IntoElement, ParentElement, Render, SharedString, Styled, WeakEntity, Window};
+use db::{format_sql, compress_sql, GlobalDbState, SessionInfo, SqlResult, StatementTimeout, StatementType};
+use db::tidb::{parse_tidb_plan, TiDbPlugin};
+use crate::connection_tag::ConnectionTags;
+use crate::confirm_policy::{confirm_destructive, ConfirmLevel, ConfirmPolicy, ConfirmRequest, DestructiveAction};
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla, IntoElement, ParentElement, Render, SharedString, Styled, WeakEntity, Window};
 use gpui::prelude::*;
 use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::dialog::DialogButtonProps;
+use gpui_component::input::{Input, InputState};
+use gpui_component::menu::{DropdownMenu, PopupMenuItem};
+use gpui_component::notification::Notification;
 use gpui_component::resizable::{resizable_panel, v_resizable};
 use gpui_component::select::{SearchableVec, Select, SelectEvent, SelectState};
 use gpui_component::{h_flex, v_flex, ActiveTheme, Icon, IconName, IndexPath, Sizable, Size, WindowExt};
 use std::any::Any;
+use std::rc::Rc;
+use std::time::Duration;
 use tracing::log::error;
 use one_core::storage::GlobalStorageState;
 use one_core::storage::query_repository::QueryRepository;
 
+/// 工具栏中可以为单次运行选择的语句超时
+const STATEMENT_TIMEOUT_CHOICES: [StatementTimeout; 6] = [
+    StatementTimeout::ConnectionDefault,
+    StatementTimeout::Disabled,
+    StatementTimeout::After(Duration::from_secs(10)),
+    StatementTimeout::After(Duration::from_secs(30)),
+    StatementTimeout::After(Duration::from_secs(60)),
+    StatementTimeout::After(Duration::from_secs(300)),
+];
+
+/// 会话 ID 形如 `连接ID:session:序号`，界面上只显示序号
+fn session_short_id(session_id: &str) -> String {
+    let number = session_id.rsplit(':').next().unwrap_or(session_id);
+    format!("#{}", number)
+}
+
+fn statement_timeout_label(timeout: StatementTimeout) -> String {
+    match timeout {
+        StatementTimeout::ConnectionDefault => "连接默认".to_string(),
+        StatementTimeout::Disabled => "不限制".to_string(),
+        StatementTimeout::After(duration) => format!("{} 秒", duration.as_secs()),
+    }
+}
+
 // Events emitted by SqlEditorTabContent
 #[derive(Debug, Clone)]
 pub enum SqlEditorEvent {
@@ -33,6 +71,10 @@ pub struct SqlEditorTab {
     database_select: Entity<SelectState<SearchableVec<String>>>,
     schema_select: Entity<SelectState<SearchableVec<String>>>,
     supports_schema: bool,
+    /// 运行中的查询可以从另一条通道取消（如 Trino）
+    supports_query_cancel: bool,
+    /// 保存的查询的执行趋势，新建的查询没有
+    run_trend: Option<Entity<QueryRunTrend>>,
     // Add focus handle
     focus_handle: FocusHandle,
 }
@@ -47,7 +89,15 @@ impl SqlEditorTab {
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
-        let editor = cx.new(|cx| SqlEditor::new(window, cx));
+        let editor = cx.new(|cx| {
+            let mut editor = SqlEditor::new(window, cx);
+            match database_type {
+                DatabaseType::MongoDB => editor.set_command_mode("javascript", "db.collection.find({})", window, cx),
+                DatabaseType::Redis => editor.set_command_mode("bash", "SCAN 0 MATCH user:* COUNT 100", window, cx),
+                _ => {}
+            }
+            editor
+        });
         let focus_handle = cx.focus_handle();
         // Create database select with empty items initially
         let database_select = cx.new(|cx| {
@@ -60,16 +110,44 @@ impl SqlEditorTab {
 
         let global_state = cx.global::<GlobalDbState>().clone();
         let supports_schema = global_state.supports_schema(&database_type);
+        let supports_query_cancel = global_state.get_plugin(&database_type)
+            .map(|plugin| plugin.supports_query_cancel())
+            .unwrap_or(false);
+
+        let title: SharedString = title.into();
+        let sql_result_tab_container = cx.new(|cx| SqlResultTabContainer::new(window, cx));
+        let editor_for_fix = editor.clone();
+        sql_result_tab_container.update(cx, |container, cx| {
+            container.set_source(title.clone());
+            container.set_ai_fix(
+                database_type,
+                Rc::new(move |sql: String, fixed: String, window: &mut Window, cx: &mut App| {
+                    editor_for_fix.update(cx, |e, cx| e.replace_statement(&sql, fixed, window, cx));
+                }),
+                cx,
+            );
+        });
+
+        let run_trend = query_id.map(|query_id| cx.new(|cx| QueryRunTrend::new(query_id, cx)));
+        if let Some(run_trend) = run_trend.clone() {
+            sql_result_tab_container.update(cx, |container, _cx| {
+                container.set_on_run_finished(Rc::new(move |stats: ScriptRunStats, cx: &mut App| {
+                    run_trend.update(cx, |trend, cx| trend.record(stats, cx));
+                }));
+            });
+        }
 
         let instance = Self {
-            title: title.into(),
+            title,
             editor: editor.clone(),
             connection_id: connection_id.into(),
             database_type,
-            sql_result_tab_container: cx.new(|cx| SqlResultTabContainer::new(window, cx)),
+            sql_result_tab_container,
             database_select: database_select.clone(),
             schema_select: schema_select.clone(),
             supports_schema,
+            supports_query_cancel,
+            run_trend,
             focus_handle,
         };
 
@@ -156,6 +234,11 @@ impl SqlEditorTab {
         self.editor.update(cx, |e, cx| e.set_value(sql, window, cx));
     }
 
+    /// 在光标处插入 SQL
+    pub fn insert_sql(&self, sql: String, window: &mut Window, cx: &mut App) {
+        self.editor.update(cx, |e, cx| e.insert_text(sql, window, cx));
+    }
+
 
 
     /// Load databases into the select dropdown
@@ -343,7 +426,6 @@ impl SqlEditorTab {
         };
 
         let connection_id = self.connection_id.clone();
-        let sql_result_tab_container = self.sql_result_tab_container.clone();
 
         let current_database_value = match self.database_select.read(cx).selected_value() {
             Some(database) => Some(database.clone()),
@@ -358,9 +440,91 @@ impl SqlEditorTab {
             return;
         }
 
-        sql_result_tab_container.update(cx, |container, cx| {
-            container.handle_run_query(sql, connection_id, current_database_value, window, cx);
-        })
+        let this = cx.entity().downgrade();
+        resolve_variables(sql, connection_id, window, cx, move |sql, window, cx| {
+            let current_database_value = current_database_value.clone();
+            _ = this.update(cx, |this, cx| this.run_query(sql, current_database_value, window, cx));
+        });
+    }
+
+    /// 执行已替换变量的 SQL；包含 DDL 或 DML 时先按连接环境的确认策略确认
+    fn run_query(&mut self, sql: String, current_database_value: Option<String>, window: &mut Window, cx: &mut Context<Self>) {
+        let connection_id = self.connection_id.clone();
+        let sql_result_tab_container = self.sql_result_tab_container.clone();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let (ddl_count, dml_count) = global_state
+            .get_plugin(&self.database_type)
+            .map(|plugin| {
+                plugin
+                    .split_statements(&sql)
+                    .iter()
+                    .fold((0, 0), |(ddl, dml), stmt| match plugin.classify_statement(stmt) {
+                        StatementType::Ddl => (ddl + 1, dml),
+                        StatementType::Dml => (ddl, dml + 1),
+                        _ => (ddl, dml),
+                    })
+            })
+            .unwrap_or((0, 0));
+
+        if ddl_count == 0 && dml_count == 0 {
+            sql_result_tab_container.update(cx, |container, cx| {
+                container.handle_run_query(sql, connection_id, current_database_value, window, cx);
+            });
+            return;
+        }
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let environment = global_state
+                .get_config_async(&connection_id)
+                .await
+                .map(|config| config.environment())
+                .unwrap_or_default();
+
+            let _ = cx.update(|cx| {
+                if let Some(window_id) = cx.active_window() {
+                    let _ = cx.update_window(window_id, |_, window, cx| {
+                        let database = current_database_value.clone().unwrap_or_default();
+                        // DDL 的确认优先；DDL 无需确认时再看 DML 是否需要确认
+                        let confirm_ddl = ddl_count > 0
+                            && ConfirmPolicy::global(cx).level_for(DestructiveAction::ExecuteDdl, environment) != ConfirmLevel::None;
+                        let request = if confirm_ddl || dml_count == 0 {
+                            ConfirmRequest::new(
+                                DestructiveAction::ExecuteDdl,
+                                environment,
+                                database.clone(),
+                                format!("即将在数据库 \"{}\" 上执行 {} 条 DDL 语句", database, ddl_count),
+                                "DDL 语句会修改数据库结构，请确认后再执行。",
+                            )
+                        } else {
+                            ConfirmRequest::new(
+                                DestructiveAction::ExecuteDml,
+                                environment,
+                                database.clone(),
+                                format!("即将在数据库 \"{}\" 上执行 {} 条 INSERT/UPDATE/DELETE 语句", database, dml_count),
+                                "这些语句会修改数据，请确认后再执行。",
+                            )
+                        };
+                        confirm_destructive(
+                            request,
+                            window,
+                            cx,
+                            move |window, cx| {
+                                sql_result_tab_container.update(cx, |container, cx| {
+                                    container.handle_run_query(
+                                        sql.clone(),
+                                        connection_id.clone(),
+                                        current_database_value.clone(),
+                                        window,
+                                        cx,
+                                    );
+                                });
+                            },
+                        );
+                    });
+                }
+            });
+        }).detach();
     }
 
     fn handle_format_query(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
@@ -374,12 +538,894 @@ impl SqlEditorTab {
         self.editor.update(cx, |s, cx| s.set_value(formatted, window, cx));
     }
 
+    /// 打开窗口函数和递归 CTE 生成器，生成的 SQL 插入到光标处
+    fn handle_open_snippet_builder(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let database_type = self.database_type;
+        let builder = cx.new(|cx| SqlSnippetBuilder::new(database_type, window, cx));
+        let editor = self.editor.clone();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let builder = builder.clone();
+            let editor = editor.clone();
+
+            dialog
+                .title("窗口函数 / 递归 CTE")
+                .width(px(640.))
+                .child(builder.clone())
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("插入"))
+                .on_ok(move |_, window, cx| match builder.read(cx).current_sql(cx) {
+                    Ok(sql) => {
+                        editor.update(cx, |e, cx| e.insert_text(sql, window, cx));
+                        true
+                    }
+                    Err(e) => {
+                        window.push_notification(e.to_string(), cx);
+                        false
+                    }
+                })
+        });
+    }
+
     fn handle_compress_query(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         let text = self.get_sql_text(cx);
         let compressed = compress_sql(&text);
         self.editor.update(cx, |e, cx| e.set_value(compressed, window, cx));
     }
 
+    /// 用自然语言描述需求，由 AI 结合当前数据库的表结构生成 SQL 并插入编辑器
+    fn handle_generate_sql(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(database) = self.database_select.read(cx).selected_value().cloned() else {
+            window.push_notification("请先选择数据库", cx);
+            return;
+        };
+        let schema = if self.supports_schema {
+            self.schema_select
+                .read(cx)
+                .selected_value()
+                .filter(|schema| schema.as_str() != "No schemas available")
+                .cloned()
+        } else {
+            None
+        };
+
+        let question_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("描述要查询的数据，例如：最近 7 天每个用户的订单数")
+        });
+        let connection_id = self.connection_id.clone();
+        let database_type = self.database_type;
+        let editor = self.editor.clone();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let question_input = question_input.clone();
+            let connection_id = connection_id.clone();
+            let database = database.clone();
+            let schema = schema.clone();
+            let editor = editor.clone();
+
+            dialog
+                .title("AI 生成 SQL")
+                .child(Input::new(&question_input).h(px(120.)))
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let question = question_input.read(cx).text().to_string();
+                    if question.trim().is_empty() {
+                        return false;
+                    }
+
+                    let connection_id = connection_id.clone();
+                    let database = database.clone();
+                    let schema = schema.clone();
+                    let editor = editor.clone();
+                    resolve_variables(question, connection_id.clone(), window, cx, move |question, window, cx| {
+                        let request = TextToSqlRequest {
+                            connection_id: connection_id.clone(),
+                            database_type,
+                            database: database.clone(),
+                            schema: schema.clone(),
+                            question,
+                        };
+                        let editor = editor.clone();
+                        let window_handle = window.window_handle();
+                        window.push_notification("正在生成 SQL...", cx);
+
+                        cx.spawn(async move |cx: &mut AsyncApp| {
+                            let result = generate_sql(request, cx).await;
+                            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                                Ok(sql) => editor.update(cx, |e, cx| e.insert_text(sql, window, cx)),
+                                Err(e) => window.push_notification(
+                                    Notification::error(format!("生成 SQL 失败: {}", e)),
+                                    cx,
+                                ),
+                            });
+                        }).detach();
+                    });
+                    true
+                })
+        });
+    }
+
+    /// 对当前语句（有选中时为选中的语句）执行 EXPLAIN，请求 AI 给出索引建议
+    fn handle_suggest_indexes(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(database) = self.database_select.read(cx).selected_value().cloned() else {
+            window.push_notification("请先选择数据库", cx);
+            return;
+        };
+        let schema = if self.supports_schema {
+            self.schema_select
+                .read(cx)
+                .selected_value()
+                .filter(|schema| schema.as_str() != "No schemas available")
+                .cloned()
+        } else {
+            None
+        };
+
+        let selected_text = self.editor.read(cx).get_selected_text_from_app(cx);
+        let text = if selected_text.trim().is_empty() {
+            self.get_sql_text(cx)
+        } else {
+            selected_text
+        };
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let sql = match global_state.get_plugin(&self.database_type) {
+            Ok(plugin) => plugin
+                .split_statements(&text)
+                .into_iter()
+                .find(|stmt| plugin.is_query_statement(stmt) || plugin.classify_statement(stmt) == StatementType::Dml),
+            Err(_) => None,
+        };
+        let Some(sql) = sql else {
+            window.push_notification("请输入要分析的查询语句", cx);
+            return;
+        };
+
+        let request = IndexAdviceRequest {
+            connection_id: self.connection_id.clone(),
+            database_type: self.database_type,
+            database: database.clone(),
+            schema,
+            sql,
+        };
+        let container = self.sql_result_tab_container.clone();
+        let connection_id = self.connection_id.clone();
+        let window_handle = window.window_handle();
+        window.push_notification("正在分析执行计划...", cx);
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = suggest_indexes(request, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(advice) => Self::open_index_advice_dialog(advice, container, connection_id, database, window, cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("索引建议失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    /// TiDB: 对当前查询执行 EXPLAIN ANALYZE 并按算子树展示实际执行情况
+    fn handle_explain_analyze(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let database = self.database_select.read(cx).selected_value().cloned();
+        let selected_text = self.editor.read(cx).get_selected_text_from_app(cx);
+        let text = if selected_text.trim().is_empty() {
+            self.get_sql_text(cx)
+        } else {
+            selected_text
+        };
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let statement = match global_state.get_plugin(&self.database_type) {
+            Ok(plugin) => plugin.split_statements(&text).into_iter().next().map(|stmt| {
+                let read_only = plugin.is_read_only_query(&stmt);
+                (stmt, read_only)
+            }),
+            Err(_) => None,
+        };
+        let sql = match statement {
+            Some((sql, true)) => sql,
+            // EXPLAIN ANALYZE 会真正执行语句，只允许只读查询
+            Some((_, false)) => {
+                window.push_notification("EXPLAIN ANALYZE 会实际执行语句，仅支持只读查询", cx);
+                return;
+            }
+            None => {
+                window.push_notification("请输入要分析的查询语句", cx);
+                return;
+            }
+        };
+
+        let explain_sql = TiDbPlugin::new().build_explain_analyze_sql(&sql);
+        let connection_id = self.connection_id.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = global_state.execute_single(cx, connection_id, explain_sql, database, None).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(SqlResult::Query(query)) => {
+                    crate::tidb::plan_view::open_plan_dialog(sql, parse_tidb_plan(&query), window, cx)
+                }
+                Ok(SqlResult::Error(err)) => window.push_notification(
+                    Notification::error(format!("EXPLAIN ANALYZE 失败: {}", err.message)),
+                    cx,
+                ),
+                Ok(_) => window.push_notification("EXPLAIN ANALYZE 没有返回执行计划", cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("EXPLAIN ANALYZE 失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    /// 只影响这个编辑器之后的运行，连接设置中的默认超时不变
+    fn set_statement_timeout(&mut self, timeout: StatementTimeout, cx: &mut Context<Self>) {
+        self.sql_result_tab_container.update(cx, |container, _cx| container.set_statement_timeout(timeout));
+        cx.notify();
+    }
+
+    /// 同一个命名会话可以被多个编辑器选中，它们共享事务状态和临时表
+    fn set_session(&mut self, session: Option<(String, String)>, cx: &mut Context<Self>) {
+        self.sql_result_tab_container.update(cx, |container, _cx| container.set_session(session));
+        cx.notify();
+    }
+
+    /// 读取连接上的会话后打开会话列表
+    fn handle_open_sessions(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database_select.read(cx).selected_value().cloned();
+        let current = self.sql_result_tab_container.read(cx).session.as_ref().map(|(session_id, _)| session_id.clone());
+        let view = cx.entity().downgrade();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = global_state.list_sessions(cx, connection_id.clone()).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(sessions) => Self::open_sessions_dialog(sessions, current, view, connection_id, database, window, cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("读取会话失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    /// 列出连接上的会话：选择本编辑器执行所用的会话，新建或关闭命名会话。
+    /// 命名会话不回到连接池，事务和临时表一直保留到它被关闭
+    fn open_sessions_dialog(
+        sessions: Vec<SessionInfo>,
+        current: Option<String>,
+        view: WeakEntity<Self>,
+        connection_id: String,
+        database: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("新会话名称"));
+        let named_count = sessions.iter().filter(|session| session.name.is_some()).count();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let mut rows = v_flex().gap_1().child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .text_xs()
+                    .child(div().flex_1().child("自动（连接池）"))
+                    .child(
+                        div()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("每次运行从连接池取一个会话"),
+                    )
+                    .child(
+                        Button::new("session-use-pool")
+                            .small()
+                            .ghost()
+                            .label(if current.is_none() { "使用中" } else { "使用" })
+                            .disabled(current.is_none())
+                            .on_click({
+                                let view = view.clone();
+                                move |_, window, cx| {
+                                    let _ = view.update(cx, |this, cx| this.set_session(None, cx));
+                                    window.close_dialog(cx);
+                                }
+                            }),
+                    ),
+            );
+
+            for (index, session) in sessions.iter().enumerate() {
+                let selected = current.as_deref() == Some(session.session_id.as_str());
+                let status = if session.in_use {
+                    "执行中".to_string()
+                } else {
+                    format!("空闲 {} 秒", session.idle_time.as_secs())
+                };
+                let mut row = h_flex()
+                    .gap_2()
+                    .items_center()
+                    .text_xs()
+                    .child(div().w(px(48.)).font_family("monospace").child(session_short_id(&session.session_id)))
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .truncate()
+                            .when(session.name.is_none(), |this| this.text_color(cx.theme().muted_foreground))
+                            .child(session.name.clone().unwrap_or_else(|| "连接池".to_string())),
+                    )
+                    .child(div().w(px(140.)).truncate().child(session.database.clone().unwrap_or_default()))
+                    .child(div().w(px(80.)).text_color(cx.theme().muted_foreground).child(status));
+
+                if let Some(name) = session.name.clone() {
+                    let session_id = session.session_id.clone();
+                    row = row
+                        .child(
+                            Button::new(SharedString::from(format!("session-use-{}", index)))
+                                .small()
+                                .ghost()
+                                .label(if selected { "使用中" } else { "使用" })
+                                .disabled(selected)
+                                .on_click({
+                                    let view = view.clone();
+                                    let session_id = session_id.clone();
+                                    move |_, window, cx| {
+                                        let session = Some((session_id.clone(), name.clone()));
+                                        let _ = view.update(cx, |this, cx| this.set_session(session, cx));
+                                        window.close_dialog(cx);
+                                    }
+                                }),
+                        )
+                        .child(
+                            Button::new(SharedString::from(format!("session-close-{}", index)))
+                                .small()
+                                .ghost()
+                                .label("关闭")
+                                .on_click({
+                                    let view = view.clone();
+                                    move |_, window, cx| {
+                                        window.close_dialog(cx);
+                                        let global_state = cx.global::<GlobalDbState>().clone();
+                                        let session_id = session_id.clone();
+                                        let view = view.clone();
+                                        let window_handle = window.window_handle();
+                                        cx.spawn(async move |cx: &mut AsyncApp| {
+                                            let result = global_state.close_session(cx, session_id.clone()).await;
+                                            let _ = cx.update_window(window_handle, |_, window, cx| {
+                                                // 正在使用的会话被关闭后回到连接池，避免下次运行报会话已关闭
+                                                let _ = view.update(cx, |this, cx| {
+                                                    let is_current = this.sql_result_tab_container.read(cx).session
+                                                        .as_ref()
+                                                        .is_some_and(|(current, _)| *current == session_id);
+                                                    if is_current {
+                                                        this.set_session(None, cx);
+                                                    }
+                                                });
+                                                match result {
+                                                    Ok(()) => window.push_notification(
+                                                        Notification::success("会话已关闭，未提交的事务已丢弃").autohide(true),
+                                                        cx,
+                                                    ),
+                                                    Err(e) => window.push_notification(
+                                                        Notification::error(format!("关闭会话失败: {}", e)),
+                                                        cx,
+                                                    ),
+                                                }
+                                            });
+                                        }).detach();
+                                    }
+                                }),
+                        );
+                }
+                rows = rows.child(row);
+            }
+
+            let create_view = view.clone();
+            let create_input = name_input.clone();
+            let create_connection_id = connection_id.clone();
+            let create_database = database.clone();
+
+            dialog
+                .title("会话")
+                .width(px(640.))
+                .child(
+                    v_flex()
+                        .gap_3()
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("命名会话有独立的事务和临时表，在关闭前一直保留；多个编辑器可以选择同一个会话"),
+                        )
+                        .child(div().id("session-list").max_h(px(320.)).overflow_y_scroll().child(rows))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(div().flex_1().child(Input::new(&name_input).small()))
+                                .child(
+                                    Button::new("session-create")
+                                        .small()
+                                        .primary()
+                                        .label("新建并使用")
+                                        .on_click(move |_, window, cx| {
+                                            let name = create_input.read(cx).text().to_string().trim().to_string();
+                                            let name = if name.is_empty() {
+                                                format!("会话 {}", named_count + 1)
+                                            } else {
+                                                name
+                                            };
+                                            window.close_dialog(cx);
+
+                                            let global_state = cx.global::<GlobalDbState>().clone();
+                                            let connection_id = create_connection_id.clone();
+                                            let database = create_database.clone();
+                                            let view = create_view.clone();
+                                            let window_handle = window.window_handle();
+                                            cx.spawn(async move |cx: &mut AsyncApp| {
+                                                let result = global_state.open_named_session(cx, connection_id, name.clone(), database).await;
+                                                let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                                                    Ok(session_id) => {
+                                                        let label = format!("已打开会话 {} ({})", name, session_short_id(&session_id));
+                                                        let _ = view.update(cx, |this, cx| this.set_session(Some((session_id, name)), cx));
+                                                        window.push_notification(Notification::success(label).autohide(true), cx);
+                                                    }
+                                                    Err(e) => window.push_notification(
+                                                        Notification::error(format!("打开会话失败: {}", e)),
+                                                        cx,
+                                                    ),
+                                                });
+                                            }).detach();
+                                        }),
+                                ),
+                        ),
+                )
+        });
+    }
+
+    /// 取消当前连接上仍在运行的查询；执行中的会话被锁住，取消请求走插件的独立通道
+    fn handle_cancel_queries(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = global_state.cancel_running_queries(cx, connection_id).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(0) => window.push_notification("没有正在运行的查询", cx),
+                Ok(cancelled) => window.push_notification(
+                    Notification::success(format!("已取消 {} 个查询", cancelled)).autohide(true),
+                    cx,
+                ),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("取消查询失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    /// 预览执行计划和建议，确认后在结果面板中执行 CREATE INDEX 语句
+    fn open_index_advice_dialog(
+        advice: IndexAdvice,
+        container: Entity<SqlResultTabContainer>,
+        connection_id: String,
+        database: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let suggestion_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true);
+            state.set_value(advice.suggestion.clone(), window, cx);
+            state
+        });
+        let has_statements = !advice.statements.is_empty();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let suggestion_input = suggestion_input.clone();
+            let container = container.clone();
+            let connection_id = connection_id.clone();
+            let database = database.clone();
+
+            dialog
+                .title("AI 索引建议")
+                .width(px(720.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("执行计划"))
+                        .child(
+                            div()
+                                .id("index-advice-plan")
+                                .max_h(px(160.))
+                                .overflow_y_scroll()
+                                .p_2()
+                                .rounded_md()
+                                .bg(cx.theme().muted)
+                                .font_family("monospace")
+                                .text_xs()
+                                .child(advice.plan.clone()),
+                        )
+                        .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("建议"))
+                        .child(Input::new(&suggestion_input).h(px(180.)))
+                        .when(!has_statements, |this| {
+                            this.child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child("没有需要新增的索引"),
+                            )
+                        }),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("应用"))
+                .on_ok(move |_, window, cx| {
+                    let statements = extract_index_statements(&suggestion_input.read(cx).text().to_string());
+                    if statements.is_empty() {
+                        window.push_notification("没有可执行的 CREATE INDEX 语句", cx);
+                        return false;
+                    }
+                    let script = statements
+                        .iter()
+                        .map(|statement| format!("{};", statement))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    container.update(cx, |container, cx| {
+                        container.handle_run_query(script, connection_id.clone(), Some(database.clone()), window, cx);
+                    });
+                    true
+                })
+        });
+    }
+
+    /// 把选中的 SQL（没有选中时为全部内容）交给 AI 按固定指令改写，结果以差异预览的方式确认后再应用
+    fn handle_rewrite_sql(&mut self, command: SqlRewriteCommand, window: &mut Window, cx: &mut Context<Self>) {
+        let selected_text = self.editor.read(cx).get_selected_text_from_app(cx);
+        let sql = if selected_text.trim().is_empty() {
+            self.get_sql_text(cx)
+        } else {
+            selected_text
+        };
+        if sql.trim().is_empty() {
+            window.push_notification("请输入要改写的 SQL", cx);
+            return;
+        }
+
+        let request = SqlRewriteRequest {
+            database_type: self.database_type,
+            sql: sql.clone(),
+            command,
+        };
+        let editor = self.editor.clone();
+        let window_handle = window.window_handle();
+        window.push_notification(format!("正在{}...", command.label()), cx);
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = rewrite_sql(request, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(rewritten) => Self::open_rewrite_dialog(command, sql, rewritten, editor, window, cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("AI 改写失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    /// 选中文本浮动工具条的快捷操作：解释直接展示说明，优化走左右对照预览，WHERE 检查走改写的差异预览
+    fn handle_quick_action(&mut self, action: AIQuickAction, window: &mut Window, cx: &mut Context<Self>) {
+        match action {
+            AIQuickAction::Explain => self.handle_explain_sql(window, cx),
+            AIQuickAction::Optimize => self.handle_optimize_sql(window, cx),
+            AIQuickAction::WhereGuard => self.handle_rewrite_sql(SqlRewriteCommand::AddWhereGuard, window, cx),
+        }
+    }
+
+    /// 请求 AI 优化当前 SQL（有选中时为选中的部分），左右对照展示改动和理由
+    fn handle_optimize_sql(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let selected_text = self.editor.read(cx).get_selected_text_from_app(cx);
+        let sql = if selected_text.trim().is_empty() {
+            self.get_sql_text(cx)
+        } else {
+            selected_text
+        };
+        if sql.trim().is_empty() {
+            window.push_notification("请输入要优化的 SQL", cx);
+            return;
+        }
+
+        let database_type = self.database_type;
+        let view = cx.entity().downgrade();
+        let window_handle = window.window_handle();
+        window.push_notification("正在优化 SQL...", cx);
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = optimize_sql(database_type, &sql, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(optimization) => Self::open_optimize_dialog(view, sql, optimization, window, cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("AI 优化失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    /// 在结果面板中依次执行原始语句和优化后的语句并对比耗时，只允许只读查询，避免重复修改数据
+    fn run_comparison(&mut self, original: String, optimized: String, window: &mut Window, cx: &mut Context<Self>) {
+        let current_database_value = match self.database_select.read(cx).selected_value() {
+            Some(database) => Some(database.clone()),
+            None => {
+                window.push_notification("Please select a database", cx);
+                return;
+            }
+        };
+
+        let Ok(plugin) = cx.global::<GlobalDbState>().get_plugin(&self.database_type) else {
+            return;
+        };
+        let original_statements = plugin.split_statements(&original);
+        let optimized_statements = plugin.split_statements(&optimized);
+        if original_statements.is_empty() || optimized_statements.is_empty() {
+            return;
+        }
+        let read_only = original_statements
+            .iter()
+            .chain(optimized_statements.iter())
+            .all(|stmt| plugin.is_read_only_query(stmt));
+        if !read_only {
+            window.push_notification("对比执行只支持查询语句", cx);
+            return;
+        }
+
+        let connection_id = self.connection_id.clone();
+        let original_count = original_statements.len();
+        self.sql_result_tab_container.update(cx, |container, cx| {
+            container.handle_compare_queries(original, optimized, original_count, connection_id, current_database_value, window, cx);
+        });
+    }
+
+    /// 左右对照展示优化前后的差异和改动理由；优化结果可以继续编辑，可以对比执行或直接应用
+    fn open_optimize_dialog(
+        view: WeakEntity<Self>,
+        original: String,
+        optimization: SqlOptimization,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let optimized_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true);
+            state.set_value(optimization.sql, window, cx);
+            state
+        });
+        let reasoning = optimization.reasoning;
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let optimized_input = optimized_input.clone();
+            let original = original.clone();
+            let view = view.clone();
+
+            let rows = side_by_side(&diff_lines(original.trim(), optimized_input.read(cx).text().to_string().trim()));
+            let mut left_column = v_flex().flex_1().min_w(px(0.));
+            let mut right_column = v_flex().flex_1().min_w(px(0.));
+            for row in rows {
+                let (left_color, right_color) = if row.changed {
+                    (cx.theme().danger, cx.theme().success)
+                } else {
+                    (cx.theme().muted_foreground, cx.theme().muted_foreground)
+                };
+                // 空行用空格占位，保持两栏逐行对齐
+                left_column = left_column.child(div().text_color(left_color).child(row.left.unwrap_or_else(|| " ".to_string())));
+                right_column = right_column.child(div().text_color(right_color).child(row.right.unwrap_or_else(|| " ".to_string())));
+            }
+
+            dialog
+                .title("AI 优化")
+                .width(px(960.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .when(!reasoning.is_empty(), |this| {
+                            this.child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("改动说明"))
+                                .child(
+                                    div()
+                                        .id("optimize-reasoning")
+                                        .max_h(px(120.))
+                                        .overflow_y_scroll()
+                                        .text_sm()
+                                        .child(reasoning.clone()),
+                                )
+                        })
+                        .child(
+                            h_flex()
+                                .gap_4()
+                                .child(div().flex_1().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("原始"))
+                                .child(div().flex_1().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("优化后")),
+                        )
+                        .child(
+                            div()
+                                .id("optimize-diff")
+                                .max_h(px(240.))
+                                .overflow_y_scroll()
+                                .p_2()
+                                .rounded_md()
+                                .bg(cx.theme().muted)
+                                .font_family("monospace")
+                                .text_xs()
+                                .child(h_flex().items_start().gap_4().child(left_column).child(right_column)),
+                        )
+                        .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("优化结果（可编辑）"))
+                        .child(Input::new(&optimized_input).h(px(160.))),
+                )
+                .button_props(DialogButtonProps::default().ok_text("应用"))
+                .footer({
+                    let optimized_input = optimized_input.clone();
+                    let original = original.clone();
+                    let view = view.clone();
+                    move |ok, cancel, window, cx| {
+                        let optimized_input = optimized_input.clone();
+                        let original = original.clone();
+                        let view = view.clone();
+                        vec![
+                            cancel(window, cx),
+                            Button::new("compare-optimized")
+                                .label("对比执行")
+                                .icon(IconName::ArrowRight)
+                                .on_click(move |_, window, cx| {
+                                    let optimized = optimized_input.read(cx).text().to_string();
+                                    if optimized.trim().is_empty() {
+                                        window.push_notification("优化结果为空", cx);
+                                        return;
+                                    }
+                                    let original = original.clone();
+                                    let _ = view.update(cx, |this, cx| this.run_comparison(original, optimized, window, cx));
+                                    window.close_dialog(cx);
+                                })
+                                .into_any_element(),
+                            ok(window, cx),
+                        ]
+                    }
+                })
+                .on_ok(move |_, window, cx| {
+                    let replacement = optimized_input.read(cx).text().to_string();
+                    if replacement.trim().is_empty() {
+                        window.push_notification("优化结果为空", cx);
+                        return false;
+                    }
+                    let original = original.clone();
+                    let _ = view.update(cx, |this, cx| {
+                        this.editor.update(cx, |editor, cx| {
+                            editor.replace_statement(&original, replacement, window, cx);
+                        });
+                    });
+                    true
+                })
+        });
+    }
+
+    fn handle_explain_sql(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let sql = self.editor.read(cx).get_selected_text_from_app(cx);
+        if sql.trim().is_empty() {
+            window.push_notification("请先选中要解释的 SQL", cx);
+            return;
+        }
+
+        let database_type = self.database_type;
+        let window_handle = window.window_handle();
+        window.push_notification("正在解释 SQL...", cx);
+
+        cx.spawn(async move |_, cx: &mut AsyncApp| {
+            let result = explain_sql(database_type, &sql, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(explanation) => Self::open_explain_dialog(sql, explanation, window, cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("AI 解释失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+
+    fn open_explain_dialog(sql: String, explanation: String, window: &mut Window, cx: &mut App) {
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            dialog
+                .title("AI 解释")
+                .width(px(640.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .id("explain-sql-source")
+                                .max_h(px(160.))
+                                .overflow_y_scroll()
+                                .p_2()
+                                .rounded_md()
+                                .bg(cx.theme().muted)
+                                .font_family("monospace")
+                                .text_xs()
+                                .child(sql.trim().to_string()),
+                        )
+                        .child(
+                            div()
+                                .id("explain-sql-text")
+                                .max_h(px(320.))
+                                .overflow_y_scroll()
+                                .text_sm()
+                                .child(explanation.clone()),
+                        ),
+                )
+        });
+    }
+
+    /// 展示改写前后的逐行差异，改写结果可以继续编辑，差异随编辑实时更新
+    fn open_rewrite_dialog(
+        command: SqlRewriteCommand,
+        original: String,
+        rewritten: String,
+        editor: Entity<SqlEditor>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let rewritten_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true);
+            state.set_value(rewritten, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let rewritten_input = rewritten_input.clone();
+            let original = original.clone();
+            let editor = editor.clone();
+
+            let diff = diff_lines(original.trim(), rewritten_input.read(cx).text().to_string().trim());
+            let mut diff_view = v_flex();
+            for line in diff {
+                let (prefix, text, color) = match line {
+                    DiffLine::Same(text) => ("  ", text, cx.theme().muted_foreground),
+                    DiffLine::Removed(text) => ("- ", text, cx.theme().danger),
+                    DiffLine::Added(text) => ("+ ", text, cx.theme().success),
+                };
+                diff_view = diff_view.child(div().text_color(color).child(format!("{}{}", prefix, text)));
+            }
+
+            dialog
+                .title(format!("AI 改写 - {}", command.label()))
+                .width(px(760.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("差异"))
+                        .child(
+                            div()
+                                .id("rewrite-diff")
+                                .max_h(px(240.))
+                                .overflow_y_scroll()
+                                .p_2()
+                                .rounded_md()
+                                .bg(cx.theme().muted)
+                                .font_family("monospace")
+                                .text_xs()
+                                .child(diff_view),
+                        )
+                        .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("改写结果（可编辑）"))
+                        .child(Input::new(&rewritten_input).h(px(200.))),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("应用"))
+                .on_ok(move |_, window, cx| {
+                    let replacement = rewritten_input.read(cx).text().to_string();
+                    if replacement.trim().is_empty() {
+                        window.push_notification("改写结果为空", cx);
+                        return false;
+                    }
+                    editor.update(cx, |editor, cx| {
+                        editor.replace_statement(&original, replacement, window, cx);
+                    });
+                    true
+                })
+        });
+    }
+
     fn handle_save_query(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         use one_core::storage::query_model::Query;
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -586,13 +1632,22 @@ impl Render for SqlEditorTab {
         let database_select = self.database_select.clone();
         let schema_select = self.schema_select.clone();
         let supports_schema = self.supports_schema;
+        let database_type = self.database_type;
+        let view = cx.entity();
 
         // Check if there are any results and if the panel is visible
         let has_results = self.sql_result_tab_container.read(cx).has_results(cx);
+        let statement_timeout = self.sql_result_tab_container.read(cx).statement_timeout;
+        let session_label = match &self.sql_result_tab_container.read(cx).session {
+            Some((session_id, name)) => format!("会话: {} ({})", name, session_short_id(session_id)),
+            None => "会话: 自动".to_string(),
+        };
         let results_visible = self.sql_result_tab_container.read(cx).is_visible(cx);
 
         // Check if there is selected text in the editor
         let has_selection = !self.editor.read(cx).get_selected_text_from_app(cx).trim().is_empty();
+        // Tagged connections frame the editor in their color so a production editor stands out
+        let connection_color = ConnectionTags::hsla(&self.connection_id, cx);
 
         // Build the main layout with conditional resizable panels
         v_flex()
@@ -642,6 +1697,127 @@ impl Render for SqlEditorTab {
                                             .icon(IconName::ArrowRight)
                                             .on_click(cx.listener(Self::handle_run_query)),
                                     )
+                                    .child(
+                                        Button::new("statement-timeout")
+                                            .with_size(Size::Small)
+                                            .ghost()
+                                            .label(format!("超时: {}", statement_timeout_label(statement_timeout)))
+                                            .tooltip("本次运行中每条语句的超时，默认使用连接设置")
+                                            .dropdown_menu({
+                                                let view = view.clone();
+                                                move |mut menu, window, _cx| {
+                                                    for choice in STATEMENT_TIMEOUT_CHOICES {
+                                                        menu = menu.item(
+                                                            PopupMenuItem::new(statement_timeout_label(choice))
+                                                                .checked(choice == statement_timeout)
+                                                                .on_click(window.listener_for(&view, move |this, _, _window, cx| {
+                                                                    this.set_statement_timeout(choice, cx);
+                                                                }))
+                                                        );
+                                                    }
+                                                    menu
+                                                }
+                                            }),
+                                    )
+                                    .child(
+                                        Button::new("sessions")
+                                            .with_size(Size::Small)
+                                            .ghost()
+                                            .label(session_label)
+                                            .tooltip("选择运行所用的会话，或新建、关闭命名会话")
+                                            .on_click(cx.listener(Self::handle_open_sessions)),
+                                    )
+                                    .when(self.supports_query_cancel, |this| {
+                                        this.child(
+                                            Button::new("cancel-queries")
+                                                .with_size(Size::Small)
+                                                .ghost()
+                                                .label("取消查询")
+                                                .icon(IconName::Delete)
+                                                .tooltip("取消当前连接上正在运行的查询")
+                                                .on_click(cx.listener(Self::handle_cancel_queries)),
+                                        )
+                                    })
+                                    .child(
+                                        Button::new("generate-sql")
+                                            .with_size(Size::Small)
+                                            .ghost()
+                                            .label("AI 生成")
+                                            .icon(IconName::Bot)
+                                            .on_click(cx.listener(Self::handle_generate_sql)),
+                                    )
+                                    .child(
+                                        Button::new("rewrite-sql")
+                                            .with_size(Size::Small)
+                                            .ghost()
+                                            .label("AI 改写")
+                                            .icon(IconName::Bot)
+                                            .tooltip("改写选中的 SQL，没有选中时改写全部内容")
+                                            .dropdown_menu(move |menu, window, _cx| {
+                                                let mut menu = menu
+                                                    .item(
+                                                        PopupMenuItem::new(SqlRewriteCommand::Simplify.label())
+                                                            .on_click(window.listener_for(&view, |this, _, window, cx| {
+                                                                this.handle_rewrite_sql(SqlRewriteCommand::Simplify, window, cx);
+                                                            }))
+                                                    )
+                                                    .item(
+                                                        PopupMenuItem::new(SqlRewriteCommand::AddComments.label())
+                                                            .on_click(window.listener_for(&view, |this, _, window, cx| {
+                                                                this.handle_rewrite_sql(SqlRewriteCommand::AddComments, window, cx);
+                                                            }))
+                                                    )
+                                                    .item(
+                                                        PopupMenuItem::new(SqlRewriteCommand::Optimize.label())
+                                                            .on_click(window.listener_for(&view, |this, _, window, cx| {
+                                                                this.handle_optimize_sql(window, cx);
+                                                            }))
+                                                    )
+                                                    .separator();
+                                                for target in DatabaseType::all() {
+                                                    // Mongo shell and Redis commands are not SQL dialects
+                                                    if *target == database_type || matches!(target, DatabaseType::MongoDB | DatabaseType::Redis) {
+                                                        continue;
+                                                    }
+                                                    let command = SqlRewriteCommand::ConvertDialect(*target);
+                                                    menu = menu.item(
+                                                        PopupMenuItem::new(command.label())
+                                                            .on_click(window.listener_for(&view, move |this, _, window, cx| {
+                                                                this.handle_rewrite_sql(command, window, cx);
+                                                            }))
+                                                    );
+                                                }
+                                                menu
+                                            }),
+                                    )
+                                    .child(
+                                        Button::new("suggest-indexes")
+                                            .with_size(Size::Small)
+                                            .ghost()
+                                            .label("索引建议")
+                                            .icon(IconName::ChartPie)
+                                            .tooltip("执行 EXPLAIN 并由 AI 给出索引建议")
+                                            .on_click(cx.listener(Self::handle_suggest_indexes)),
+                                    )
+                                    .when(self.database_type == DatabaseType::TiDB, |this| {
+                                        this.child(
+                                            Button::new("tidb-explain-analyze")
+                                                .with_size(Size::Small)
+                                                .ghost()
+                                                .label("EXPLAIN ANALYZE")
+                                                .icon(IconName::ChartPie)
+                                                .tooltip("执行查询并查看 TiDB 算子树的实际行数和耗时")
+                                                .on_click(cx.listener(Self::handle_explain_analyze)),
+                                        )
+                                    })
+                                    .child(
+                                        Button::new("snippet-builder")
+                                            .with_size(Size::Small)
+                                            .ghost()
+                                            .label("窗口函数/CTE")
+                                            .tooltip("生成窗口函数表达式或递归 CTE 并插入到光标处")
+                                            .on_click(cx.listener(Self::handle_open_snippet_builder)),
+                                    )
                                     .child(
                                         Button::new("format-query")
                                             .with_size(Size::Small)
@@ -699,11 +1875,33 @@ impl Render for SqlEditorTab {
                                         )
                                     })
                             )
+                            .when_some(self.run_trend.clone(), |this, run_trend| this.child(run_trend))
                             .child(
-                                // Editor
+                                // Editor, with AI quick actions floating over the selection
                                 v_flex()
                                     .flex_1()
+                                    .relative()
+                                    .when_some(connection_color, |this, color| {
+                                        this.border_2().border_color(color).rounded_md()
+                                    })
                                     .child(editor.clone())
+                                    .when(has_selection, |this| {
+                                        let view = cx.entity();
+                                        this.child(
+                                            div()
+                                                .absolute()
+                                                .top_2()
+                                                .right_4()
+                                                .child(
+                                                    AIQuickActions::new("sql-editor-quick-actions")
+                                                        .on_action(move |action, window, cx| {
+                                                            view.update(cx, |this, cx| {
+                                                                this.handle_quick_action(action, window, cx);
+                                                            });
+                                                        }),
+                                                ),
+                                        )
+                                    })
                             )
                             )
                     )
@@ -733,6 +1931,8 @@ impl Clone for SqlEditorTab {
             database_select: self.database_select.clone(),
             schema_select: self.schema_select.clone(),
             supports_schema: self.supports_schema,
+            supports_query_cancel: self.supports_query_cancel,
+            run_trend: self.run_trend.clone(),
             focus_handle: self.focus_handle.clone(),
         }
     }
@@ -791,6 +1991,14 @@ impl SqlEditorTabContent {
     ) -> Self {
         Self::new_with_config(title, connection_id, database_type, Some(query_id), None, window, cx)
     }
+
+    pub fn set_sql(&self, sql: String, window: &mut Window, cx: &mut App) {
+        self.sql_editor_tab.update(cx, |tab, cx| tab.set_sql(sql, window, cx));
+    }
+
+    pub fn insert_sql(&self, sql: String, window: &mut Window, cx: &mut App) {
+        self.sql_editor_tab.update(cx, |tab, cx| tab.insert_sql(sql, window, cx));
+    }
 }
 
 impl TabContent for SqlEditorTabContent {
@@ -810,6 +2018,10 @@ impl TabContent for SqlEditorTabContent {
         self.sql_editor_tab.clone().into_any_element()
     }
 
+    fn accent_color(&self, cx: &App) -> Option<Hsla> {
+        ConnectionTags::hsla(&self.sql_editor_tab.read(cx).connection_id, cx)
+    }
+
     fn content_type(&self) -> TabContentType {
         TabContentType::SqlEditor
     }
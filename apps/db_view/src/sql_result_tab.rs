@@ -1,13 +1,18 @@
+use std::rc::Rc;
 use std::sync::Arc;
 // 2. 外部 crate 导入（按字母顺序）
 use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Task, Window};
 use gpui::prelude::FluentBuilder;
 use tracing::log::error;
-use gpui_component::{button::ButtonVariants, h_flex, list::{List, ListDelegate, ListItem, ListState}, progress::Progress, tab::{Tab, TabBar}, table::Column, v_flex, ActiveTheme, IconName, IndexPath, Sizable, Size, StyledExt};
+use gpui_component::{button::{Button, ButtonVariants}, dialog::DialogButtonProps, h_flex, input::{Input, InputState}, list::{List, ListDelegate, ListItem, ListState}, notification::Notification, progress::Progress, tab::{Tab, TabBar}, table::Column, v_flex, ActiveTheme, IconName, IndexPath, Sizable, Size, StyledExt, WindowExt};
+use one_core::storage::usage_metrics::{record_error, record_feature};
+use one_core::storage::DatabaseType;
 
+use crate::ai_chart::{render_chart, suggest_chart, ChartSpec};
+use crate::ai_sql::{fix_sql, SqlFixRequest, TimingComparison};
 use crate::table_data::data_grid::{DataGrid, DataGridConfig, DataGridUsage};
 // 3. 当前 crate 导入（按模块分组）
-use db::{GlobalDbState, SqlResult};
+use db::{GlobalDbState, SqlResult, StatementTimeout};
 
 // Structure to hold a single SQL result with its metadata
 #[derive(Clone)]
@@ -17,6 +22,8 @@ pub struct SqlResultTab {
     pub execution_time: String,
     pub rows_count: String,
     pub data_grid: Option<Entity<DataGrid>>,
+    /// AI 建议的图表，显示在结果表格上方
+    pub chart: Option<ChartSpec>,
 }
 
 /// 执行状态
@@ -37,12 +44,105 @@ pub struct StatementListItem {
     pub status_text: String,
 }
 
+/// 应用 AI 修正后的语句：参数为原语句和修正后的语句
+pub type ApplyFixHandler = Rc<dyn Fn(String, String, &mut Window, &mut App) + 'static>;
+
+/// 一次脚本执行的汇总：总耗时和返回或影响的行数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptRunStats {
+    pub elapsed_ms: i64,
+    pub row_count: i64,
+}
+
+/// 脚本全部语句执行成功后调用，用于记录保存的查询的执行历史
+pub type RunFinishedHandler = Rc<dyn Fn(ScriptRunStats, &mut App) + 'static>;
+
+/// 失败语句的“AI 修复”操作
+#[derive(Clone)]
+pub struct AiFixAction {
+    database_type: DatabaseType,
+    on_apply: ApplyFixHandler,
+}
+
+impl AiFixAction {
+    /// 请求模型修正语句，完成后弹出修正结果供用户确认应用
+    fn run(&self, sql: String, error: String, window: &mut Window, cx: &mut App) {
+        let request = SqlFixRequest {
+            database_type: self.database_type,
+            sql: sql.clone(),
+            error: error.clone(),
+        };
+        let on_apply = self.on_apply.clone();
+        let window_handle = window.window_handle();
+        window.push_notification("正在分析错误...", cx);
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let result = fix_sql(request, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(fixed) => open_fix_dialog(sql, error, fixed, on_apply, window, cx),
+                Err(e) => window.push_notification(
+                    Notification::error(format!("AI 修复失败: {}", e)),
+                    cx,
+                ),
+            });
+        }).detach();
+    }
+}
+
+/// 展示错误信息和修正后的语句，修正结果可编辑，确认后替换编辑器中的原语句
+fn open_fix_dialog(
+    sql: String,
+    error: String,
+    fixed: String,
+    on_apply: ApplyFixHandler,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let fixed_input = cx.new(|cx| {
+        let mut state = InputState::new(window, cx).multi_line(true);
+        state.set_value(fixed, window, cx);
+        state
+    });
+
+    window.open_dialog(cx, move |dialog, _window, cx| {
+        let fixed_input = fixed_input.clone();
+        let sql = sql.clone();
+        let on_apply = on_apply.clone();
+
+        dialog
+            .title("AI 修复建议")
+            .width(px(640.))
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().danger)
+                            .child(error.clone())
+                    )
+                    .child(Input::new(&fixed_input).h(px(160.)))
+            )
+            .confirm()
+            .button_props(DialogButtonProps::default().ok_text("应用"))
+            .on_ok(move |_, window, cx| {
+                let fixed = fixed_input.read(cx).text().to_string();
+                if fixed.trim().is_empty() {
+                    return false;
+                }
+                on_apply(sql.clone(), fixed, window, cx);
+                true
+            })
+    });
+}
+
 /// 语句列表委托 - 实现虚拟滚动
 pub struct StatementListDelegate {
     all_items: Vec<StatementListItem>,
     filtered_items: Vec<StatementListItem>,
     selected_index: Option<IndexPath>,
     show_errors_only: bool,
+    ai_fix: Option<AiFixAction>,
 }
 
 impl StatementListDelegate {
@@ -52,6 +152,7 @@ impl StatementListDelegate {
             filtered_items: Vec::new(),
             selected_index: None,
             show_errors_only: false,
+            ai_fix: None,
         }
     }
 
@@ -141,6 +242,9 @@ impl ListDelegate for StatementListDelegate {
         let sql_display = item.sql.replace('\n', " ").replace('\r', "");
         let status_text = item.status_text.clone();
         let status_text_for_tooltip = item.status_text.clone();
+        let ai_fix = self.ai_fix.clone().filter(|_| item.is_error);
+        let fix_sql = item.sql.clone();
+        let fix_error = item.status_text.clone();
 
         Some(
             ListItem::new(ix)
@@ -180,6 +284,19 @@ impl ListDelegate for StatementListDelegate {
                                 .tooltip(move |window, cx| gpui_component::tooltip::Tooltip::new(status_text_for_tooltip.clone()).build(window, cx))
                                 .child(status_text)
                         )
+                        .when_some(ai_fix, |this, ai_fix| {
+                            this.child(
+                                Button::new(SharedString::from(format!("ai-fix-{}", ix.row)))
+                                    .with_size(Size::XSmall)
+                                    .ghost()
+                                    .icon(IconName::Bot)
+                                    .label("AI 修复")
+                                    .on_click(move |_, window, cx| {
+                                        cx.stop_propagation();
+                                        ai_fix.run(fix_sql.clone(), fix_error.clone(), window, cx);
+                                    })
+                            )
+                        })
                         .child(
                             div()
                                 .w(px(80.))
@@ -203,6 +320,15 @@ pub struct SqlResultTabContainer {
     pub statement_list: Entity<ListState<StatementListDelegate>>,
     pub show_errors_only: Entity<bool>,
     pub total_elapsed_ms: Entity<f64>,
+    /// 对比执行时原始语句的条数，结果中排在前面的属于原始语句
+    pub comparison: Entity<Option<usize>>,
+    /// 发起执行的编辑器标签页标题，供语句钩子标注来源
+    pub source: Option<SharedString>,
+    /// 编辑器工具栏为本次运行选择的语句超时
+    pub statement_timeout: StatementTimeout,
+    /// 编辑器选择的命名会话（ID、名称），为空时每次运行从连接池取会话
+    pub session: Option<(String, String)>,
+    pub on_run_finished: Option<RunFinishedHandler>,
 }
 
 impl SqlResultTabContainer {
@@ -217,6 +343,7 @@ impl SqlResultTabContainer {
         });
         let show_errors_only = cx.new(|_| false);
         let total_elapsed_ms = cx.new(|_| 0.0);
+        let comparison = cx.new(|_| None);
         SqlResultTabContainer {
             result_tabs,
             active_result_tab,
@@ -226,6 +353,11 @@ impl SqlResultTabContainer {
             statement_list,
             show_errors_only,
             total_elapsed_ms,
+            comparison,
+            source: None,
+            statement_timeout: StatementTimeout::ConnectionDefault,
+            session: None,
+            on_run_finished: None,
         }
     }
 }
@@ -264,6 +396,9 @@ impl SqlResultTabContainer {
             // 设置stop_on_error为false，确保即使某条语句失败也继续执行后续语句
             let exec_opts = db::ExecOptions {
                 stop_on_error: false,
+                source: clone_self.source.as_ref().map(|source| source.to_string()),
+                timeout: clone_self.statement_timeout,
+                session_id: clone_self.session.as_ref().map(|(session_id, _)| session_id.clone()),
                 ..Default::default()
             };
             let mut rx = match global_state
@@ -285,6 +420,8 @@ impl SqlResultTabContainer {
             let mut has_query_result = false;
             let mut first_query_index: Option<usize> = None;
             let mut query_count = 0usize;
+            let mut has_error = false;
+            let mut row_count = 0i64;
 
             let mut pending_results: Vec<SqlResult> = Vec::new();
             let mut last_ui_update = std::time::Instant::now();
@@ -300,6 +437,12 @@ impl SqlResultTabContainer {
                 let (current, total) = (progress.current, progress.total);
                 let result = progress.result;
 
+                match &result {
+                    SqlResult::Query(query_result) => row_count += query_result.rows.len() as i64,
+                    SqlResult::Exec(exec_result) => row_count += exec_result.rows_affected as i64,
+                    SqlResult::Error(_) => has_error = true,
+                }
+
                 let is_query = matches!(&result, SqlResult::Query(_));
                 if is_query {
                     if first_query_index.is_none() {
@@ -370,7 +513,27 @@ impl SqlResultTabContainer {
                     cx.notify();
                 });
 
-                if has_query_result {
+                record_feature("sql.execute", cx);
+                if has_error {
+                    record_error("sql.execute", cx);
+                }
+
+                // 对比执行停留在摘要页
+                let is_comparison = clone_self.comparison.read(cx).is_some();
+                // 失败和对比执行的耗时不代表查询本身，不计入执行历史
+                if let Some(on_run_finished) = clone_self.on_run_finished.as_ref()
+                    && !has_error
+                    && !is_comparison
+                {
+                    on_run_finished(
+                        ScriptRunStats {
+                            elapsed_ms: (total_elapsed * 1000.0) as i64,
+                            row_count,
+                        },
+                        cx,
+                    );
+                }
+                if has_query_result && !is_comparison {
                     if let Some(idx) = first_query_index {
                         clone_self.active_result_tab.update(cx, |active, cx| {
                             *active = Arc::new(idx + 1);
@@ -382,6 +545,30 @@ impl SqlResultTabContainer {
         }).detach();
     }
 
+    /// 依次执行原始语句和优化后的语句，摘要中对比两者的耗时
+    pub fn handle_compare_queries(
+        &mut self,
+        original: String,
+        optimized: String,
+        original_count: usize,
+        connection_id: String,
+        current_database_value: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let script = format!("{};\n{}", original.trim().trim_end_matches(';'), optimized.trim());
+        self.handle_run_query(script, connection_id, current_database_value, window, cx);
+        self.comparison.update(cx, |comparison, cx| {
+            *comparison = Some(original_count);
+            cx.notify();
+        });
+        // 对比结果在摘要页展示
+        self.active_result_tab.update(cx, |active, cx| {
+            *active = Arc::new(0);
+            cx.notify();
+        });
+    }
+
     fn clear_results(&mut self, cx: &mut App) {
         self.result_tabs.update(cx, |tabs, cx| {
             tabs.clear();
@@ -403,6 +590,10 @@ impl SqlResultTabContainer {
             *s = false;
             cx.notify();
         });
+        self.comparison.update(cx, |comparison, cx| {
+            *comparison = None;
+            cx.notify();
+        });
     }
 
     /// 批量添加streaming结果并滚动到最新位置
@@ -473,6 +664,7 @@ impl SqlResultTabContainer {
                     execution_time: format!("{}ms", query_result.elapsed_ms),
                     rows_count: format!("{} rows", query_result.rows.len()),
                     data_grid: Some(data_grid),
+                    chart: None,
                 };
 
                 new_tabs.push(tab);
@@ -498,6 +690,62 @@ impl SqlResultTabContainer {
         });
     }
 
+    pub fn set_source(&mut self, source: impl Into<SharedString>) {
+        self.source = Some(source.into());
+    }
+
+    pub fn set_statement_timeout(&mut self, timeout: StatementTimeout) {
+        self.statement_timeout = timeout;
+    }
+
+    pub fn set_session(&mut self, session: Option<(String, String)>) {
+        self.session = session;
+    }
+
+    pub fn set_on_run_finished(&mut self, handler: RunFinishedHandler) {
+        self.on_run_finished = Some(handler);
+    }
+
+    /// 为失败的语句启用“AI 修复”，`on_apply` 负责把修正后的语句写回编辑器
+    pub fn set_ai_fix(&mut self, database_type: DatabaseType, on_apply: ApplyFixHandler, cx: &mut App) {
+        self.statement_list.update(cx, |list, cx| {
+            list.delegate_mut().ai_fix = Some(AiFixAction { database_type, on_apply });
+            cx.notify();
+        });
+    }
+
+    /// 请求 AI 为第 `tab_idx` 个结果选择图表，完成后显示在该结果上方
+    fn suggest_chart(&self, tab_idx: usize, window: &mut Window, cx: &mut App) {
+        let Some(SqlResult::Query(query_result)) = self.result_tabs.read(cx).get(tab_idx).map(|tab| tab.result.clone()) else {
+            return;
+        };
+        let result_tabs = self.result_tabs.clone();
+        let window_handle = window.window_handle();
+        window.push_notification("正在生成图表建议...", cx);
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let result = suggest_chart(query_result, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(spec) => result_tabs.update(cx, |tabs, cx| {
+                    if let Some(tab) = tabs.get_mut(tab_idx) {
+                        tab.chart = Some(spec);
+                        cx.notify();
+                    }
+                }),
+                Err(e) => window.push_notification(Notification::error(format!("图表建议失败: {}", e)), cx),
+            });
+        }).detach();
+    }
+
+    fn close_chart(&self, tab_idx: usize, cx: &mut App) {
+        self.result_tabs.update(cx, |tabs, cx| {
+            if let Some(tab) = tabs.get_mut(tab_idx) {
+                tab.chart = None;
+                cx.notify();
+            }
+        });
+    }
+
     /// 切换结果面板的显示/隐藏状态
     pub fn toggle_visibility(&mut self, cx: &mut App) {
         self.is_visible.update(cx, |visible, cx| {
@@ -545,6 +793,7 @@ impl Render for SqlResultTabContainer {
         let statement_list = self.statement_list.clone();
         let show_errors_only = *self.show_errors_only.read(cx);
         let total_elapsed_ms = *self.total_elapsed_ms.read(cx);
+        let comparison = *self.comparison.read(cx);
 
         if !is_visible {
             return div().size_full();
@@ -612,8 +861,23 @@ impl Render for SqlResultTabContainer {
                         .child(
                             div().flex_1()
                         )
+                        .when(active_idx > 0 && query_tabs.get(active_idx - 1).is_some_and(|tab| matches!(&tab.result, SqlResult::Query(q) if !q.rows.is_empty())), |this| {
+                            this.child(
+                                Button::new("suggest-chart")
+                                    .with_size(Size::Small)
+                                    .ghost()
+                                    .icon(IconName::ChartPie)
+                                    .tooltip("AI 图表建议")
+                                    .on_click({
+                                        let chart_self = clone_self.clone();
+                                        move |_, window, cx| {
+                                            chart_self.suggest_chart(active_idx - 1, window, cx);
+                                        }
+                                    })
+                            )
+                        })
                         .child(
-                            gpui_component::button::Button::new("close-results")
+                            Button::new("close-results")
                                 .with_size(Size::Small)
                                 .ghost()
                                 .icon(IconName::Close)
@@ -641,15 +905,42 @@ impl Render for SqlResultTabContainer {
                                 &statement_list,
                                 show_errors_only,
                                 total_elapsed_ms,
+                                comparison,
                                 clone_self.clone(),
                                 cx,
                             ))
                             .into_any_element()
                     } else {
                         query_tabs.get(active_idx - 1)
-                            .and_then(|tab| tab.data_grid.as_ref())
-                            .map(|data_grid| {
-                                data_grid.clone().into_any_element()
+                            .and_then(|tab| tab.data_grid.as_ref().map(|data_grid| (tab, data_grid)))
+                            .map(|(tab, data_grid)| match (&tab.chart, &tab.result) {
+                                (Some(spec), SqlResult::Query(query_result)) => v_flex()
+                                    .flex_1()
+                                    .size_full()
+                                    .child(
+                                        h_flex()
+                                            .h(px(280.))
+                                            .flex_shrink_0()
+                                            .p_2()
+                                            .gap_2()
+                                            .border_b_1()
+                                            .border_color(cx.theme().border)
+                                            .child(div().flex_1().h_full().child(render_chart(spec, query_result, cx)))
+                                            .child(
+                                                Button::new("close-chart")
+                                                    .with_size(Size::Small)
+                                                    .ghost()
+                                                    .icon(IconName::Close)
+                                                    .tooltip("关闭图表")
+                                                    .on_click({
+                                                        let chart_self = clone_self.clone();
+                                                        move |_, _, cx| chart_self.close_chart(active_idx - 1, cx)
+                                                    })
+                                            )
+                                    )
+                                    .child(div().flex_1().min_h(px(0.)).child(data_grid.clone()))
+                                    .into_any_element(),
+                                _ => data_grid.clone().into_any_element(),
                             })
                             .unwrap_or_else(|| {
                                 div()
@@ -673,6 +964,7 @@ fn render_summary_view(
     statement_list: &Entity<ListState<StatementListDelegate>>,
     show_errors_only: bool,
     total_elapsed_ms: f64,
+    comparison: Option<usize>,
     container: SqlResultTabContainer,
     cx: &App,
 ) -> AnyElement {
@@ -805,6 +1097,9 @@ fn render_summary_view(
                         )
                 )
         )
+        .when_some(comparison.filter(|_| !is_executing), |this, original_count| {
+            this.child(render_comparison(tabs, original_count, cx))
+        })
         .when(is_executing && total > 0, |this| {
             let progress_percent = if total > 0 { (current as f32 / total as f32) * 100.0 } else { 0.0 };
             this.child(
@@ -862,3 +1157,40 @@ fn render_summary_view(
         )
         .into_any_element()
 }
+
+/// 对比执行的耗时汇总：原始语句、优化后语句的耗时和行数，以及耗时变化
+fn render_comparison(results: &[SqlResult], original_count: usize, cx: &App) -> AnyElement {
+    let Some(comparison) = TimingComparison::from_results(results, original_count) else {
+        return div()
+            .px_4()
+            .pb_2()
+            .text_sm()
+            .text_color(cx.theme().danger)
+            .child("对比执行未完成，请查看下方失败的语句")
+            .into_any_element();
+    };
+
+    let (change_text, change_color) = match comparison.change_percent() {
+        Some(percent) if percent < 0.0 => (format!("快 {:.1}%", -percent), cx.theme().success),
+        Some(percent) if percent > 0.0 => (format!("慢 {:.1}%", percent), cx.theme().danger),
+        Some(_) => ("耗时相同".to_string(), cx.theme().muted_foreground),
+        None => ("耗时过短，无法比较".to_string(), cx.theme().muted_foreground),
+    };
+
+    h_flex()
+        .px_4()
+        .pb_2()
+        .gap_6()
+        .text_sm()
+        .child(format!("原始: {}ms ({} rows)", comparison.original_ms, comparison.original_rows))
+        .child(format!("优化后: {}ms ({} rows)", comparison.optimized_ms, comparison.optimized_rows))
+        .child(div().font_semibold().text_color(change_color).child(change_text))
+        .when(comparison.original_rows != comparison.optimized_rows, |this| {
+            this.child(
+                div()
+                    .text_color(cx.theme().warning)
+                    .child("结果行数不一致，请检查改写是否等价")
+            )
+        })
+        .into_any_element()
+}
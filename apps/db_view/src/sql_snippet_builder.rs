@@ -0,0 +1,697 @@
+//! 窗口函数和递归 CTE 生成器 - 通过选择分区、排序和窗口框架拼出窗口函数表达式，
+//! 或按父子关系生成递归 CTE 骨架，按当前数据库方言插入到编辑器光标处
+
+use anyhow::{anyhow, Result};
+use gpui::prelude::FluentBuilder;
+use gpui::{
+    div, px, AnyElement, App, AppContext, Context, Entity, IntoElement, ParentElement, Render,
+    SharedString, Styled, Subscription, Window,
+};
+use gpui_component::{
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    select::{Select, SelectItem, SelectState},
+    tab::{Tab, TabBar},
+    v_flex, ActiveTheme, IndexPath, Sizable, Size,
+};
+use one_core::storage::DatabaseType;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowFunction {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Ntile,
+    Lag,
+    Lead,
+    FirstValue,
+    LastValue,
+    Sum,
+    Avg,
+    Count,
+    Min,
+    Max,
+}
+
+impl WindowFunction {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::RowNumber,
+            Self::Rank,
+            Self::DenseRank,
+            Self::Ntile,
+            Self::Lag,
+            Self::Lead,
+            Self::FirstValue,
+            Self::LastValue,
+            Self::Sum,
+            Self::Avg,
+            Self::Count,
+            Self::Min,
+            Self::Max,
+        ]
+    }
+
+    /// 函数名；ClickHouse 没有 LAG/LEAD，使用按框架取值的 lagInFrame/leadInFrame
+    fn name(&self, database_type: DatabaseType) -> &'static str {
+        match (self, database_type) {
+            (Self::Lag, DatabaseType::ClickHouse) => "lagInFrame",
+            (Self::Lead, DatabaseType::ClickHouse) => "leadInFrame",
+            (Self::RowNumber, _) => "ROW_NUMBER",
+            (Self::Rank, _) => "RANK",
+            (Self::DenseRank, _) => "DENSE_RANK",
+            (Self::Ntile, _) => "NTILE",
+            (Self::Lag, _) => "LAG",
+            (Self::Lead, _) => "LEAD",
+            (Self::FirstValue, _) => "FIRST_VALUE",
+            (Self::LastValue, _) => "LAST_VALUE",
+            (Self::Sum, _) => "SUM",
+            (Self::Avg, _) => "AVG",
+            (Self::Count, _) => "COUNT",
+            (Self::Min, _) => "MIN",
+            (Self::Max, _) => "MAX",
+        }
+    }
+
+    /// 参数输入框的提示，None 表示函数没有参数
+    pub fn argument_hint(&self) -> Option<&'static str> {
+        match self {
+            Self::RowNumber | Self::Rank | Self::DenseRank => None,
+            Self::Ntile => Some("分桶数，如 4"),
+            Self::Lag | Self::Lead => Some("列名[, 偏移量[, 默认值]]"),
+            Self::Count => Some("列名或 *"),
+            _ => Some("列名或表达式"),
+        }
+    }
+
+    /// 排名和偏移函数的结果依赖行的顺序
+    fn requires_order(&self) -> bool {
+        matches!(self, Self::RowNumber | Self::Rank | Self::DenseRank | Self::Ntile | Self::Lag | Self::Lead)
+    }
+
+    /// 排名函数不接受窗口框架
+    fn supports_frame(&self) -> bool {
+        !matches!(self, Self::RowNumber | Self::Rank | Self::DenseRank | Self::Ntile)
+    }
+}
+
+impl SelectItem for WindowFunction {
+    type Value = WindowFunction;
+
+    fn title(&self) -> SharedString {
+        self.name(DatabaseType::MySQL).into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameUnit {
+    /// 不指定框架，使用数据库默认行为
+    Default,
+    Rows,
+    Range,
+}
+
+impl FrameUnit {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Default, Self::Rows, Self::Range]
+    }
+}
+
+impl SelectItem for FrameUnit {
+    type Value = FrameUnit;
+
+    fn title(&self) -> SharedString {
+        match self {
+            Self::Default => "默认框架".into(),
+            Self::Rows => "ROWS".into(),
+            Self::Range => "RANGE".into(),
+        }
+    }
+
+    fn value(&self) -> &Self::Value {
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameBound {
+    UnboundedPreceding,
+    Preceding,
+    CurrentRow,
+    Following,
+    UnboundedFollowing,
+}
+
+impl FrameBound {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::UnboundedPreceding,
+            Self::Preceding,
+            Self::CurrentRow,
+            Self::Following,
+            Self::UnboundedFollowing,
+        ]
+    }
+
+    fn has_offset(&self) -> bool {
+        matches!(self, Self::Preceding | Self::Following)
+    }
+
+    /// 框架边界的先后顺序，起点不能晚于终点
+    fn position(&self) -> u8 {
+        match self {
+            Self::UnboundedPreceding => 0,
+            Self::Preceding => 1,
+            Self::CurrentRow => 2,
+            Self::Following => 3,
+            Self::UnboundedFollowing => 4,
+        }
+    }
+
+    fn to_sql(self, offset: u32) -> String {
+        match self {
+            Self::UnboundedPreceding => "UNBOUNDED PRECEDING".to_string(),
+            Self::Preceding => format!("{} PRECEDING", offset),
+            Self::CurrentRow => "CURRENT ROW".to_string(),
+            Self::Following => format!("{} FOLLOWING", offset),
+            Self::UnboundedFollowing => "UNBOUNDED FOLLOWING".to_string(),
+        }
+    }
+}
+
+impl SelectItem for FrameBound {
+    type Value = FrameBound;
+
+    fn title(&self) -> SharedString {
+        match self {
+            Self::UnboundedPreceding => "UNBOUNDED PRECEDING".into(),
+            Self::Preceding => "N PRECEDING".into(),
+            Self::CurrentRow => "CURRENT ROW".into(),
+            Self::Following => "N FOLLOWING".into(),
+            Self::UnboundedFollowing => "UNBOUNDED FOLLOWING".into(),
+        }
+    }
+
+    fn value(&self) -> &Self::Value {
+        self
+    }
+}
+
+/// 窗口框架，`offset` 用于 N PRECEDING / N FOLLOWING
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowFrame {
+    pub unit: FrameUnit,
+    pub start: FrameBound,
+    pub end: FrameBound,
+    pub offset: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct WindowSpec {
+    pub function: WindowFunction,
+    pub argument: String,
+    /// 逗号分隔的分区列
+    pub partition_by: String,
+    /// 逗号分隔的排序列，可带 ASC/DESC
+    pub order_by: String,
+    pub frame: WindowFrame,
+    pub alias: String,
+}
+
+fn build_frame(frame: &WindowFrame, database_type: DatabaseType) -> Result<Option<String>> {
+    let unit = match frame.unit {
+        FrameUnit::Default => return Ok(None),
+        FrameUnit::Rows => "ROWS",
+        FrameUnit::Range => "RANGE",
+    };
+    if frame.start == FrameBound::UnboundedFollowing || frame.end == FrameBound::UnboundedPreceding {
+        return Err(anyhow!("框架起点不能是 UNBOUNDED FOLLOWING，终点不能是 UNBOUNDED PRECEDING"));
+    }
+    if frame.start.position() > frame.end.position() {
+        return Err(anyhow!("框架起点不能晚于终点"));
+    }
+    if frame.unit == FrameUnit::Range
+        && database_type == DatabaseType::MSSQL
+        && (frame.start.has_offset() || frame.end.has_offset())
+    {
+        return Err(anyhow!("SQL Server 的 RANGE 框架只支持 UNBOUNDED 和 CURRENT ROW"));
+    }
+
+    Ok(Some(format!(
+        "{} BETWEEN {} AND {}",
+        unit,
+        frame.start.to_sql(frame.offset),
+        frame.end.to_sql(frame.offset),
+    )))
+}
+
+/// 拼出 `FUNC(arg) OVER (PARTITION BY ... ORDER BY ... frame) AS alias`
+pub fn build_window_expression(spec: &WindowSpec, database_type: DatabaseType) -> Result<String> {
+    let function = spec.function;
+    let argument = spec.argument.trim();
+    if function.argument_hint().is_some() && argument.is_empty() {
+        return Err(anyhow!("{} 需要参数", function.name(database_type)));
+    }
+    let order_by = spec.order_by.trim();
+    if function.requires_order() && order_by.is_empty() {
+        return Err(anyhow!("{} 需要 ORDER BY", function.name(database_type)));
+    }
+
+    let mut clauses = Vec::new();
+    let partition_by = spec.partition_by.trim();
+    if !partition_by.is_empty() {
+        clauses.push(format!("PARTITION BY {}", partition_by));
+    }
+    if !order_by.is_empty() {
+        clauses.push(format!("ORDER BY {}", order_by));
+    }
+
+    let frame = if function.supports_frame() {
+        build_frame(&spec.frame, database_type)?
+    } else {
+        None
+    };
+    match frame {
+        Some(frame) => clauses.push(frame),
+        // lagInFrame/leadInFrame 只在框架内取值，默认框架截止到当前行时取不到后续行
+        None if database_type == DatabaseType::ClickHouse && matches!(function, WindowFunction::Lag | WindowFunction::Lead) => {
+            clauses.push("ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING".to_string());
+        }
+        None => {}
+    }
+
+    let mut expression = format!(
+        "{}({}) OVER ({})",
+        function.name(database_type),
+        argument,
+        clauses.join(" "),
+    );
+    let alias = spec.alias.trim();
+    if !alias.is_empty() {
+        expression.push_str(" AS ");
+        expression.push_str(alias);
+    }
+    Ok(expression)
+}
+
+/// 按父子关系遍历树的递归 CTE
+#[derive(Clone, Debug)]
+pub struct RecursiveCteSpec {
+    pub name: String,
+    pub table: String,
+    pub id_column: String,
+    pub parent_column: String,
+    /// 锚点成员的条件，为空时从父列为 NULL 的行开始
+    pub root_condition: String,
+    /// 最大递归深度，防止数据中存在环时无限递归
+    pub max_depth: Option<u32>,
+}
+
+pub fn build_recursive_cte(spec: &RecursiveCteSpec, database_type: DatabaseType) -> Result<String> {
+    let name = spec.name.trim();
+    let table = spec.table.trim();
+    let id = spec.id_column.trim();
+    let parent = spec.parent_column.trim();
+    if name.is_empty() || table.is_empty() || id.is_empty() || parent.is_empty() {
+        return Err(anyhow!("请填写 CTE 名称、表名、主键列和父级列"));
+    }
+
+    // SQL Server 和 Oracle 的递归 CTE 不使用 RECURSIVE 关键字
+    let with = match database_type {
+        DatabaseType::MSSQL | DatabaseType::Oracle => "WITH",
+        _ => "WITH RECURSIVE",
+    };
+    let root_condition = match spec.root_condition.trim() {
+        "" => format!("{} IS NULL", parent),
+        condition => condition.to_string(),
+    };
+    let depth_limit = spec
+        .max_depth
+        .map(|depth| format!("\n    WHERE p.depth < {}", depth))
+        .unwrap_or_default();
+
+    Ok(format!(
+        "{with} {name} ({id}, {parent}, depth) AS (\n    \
+         SELECT {id}, {parent}, 1\n    \
+         FROM {table}\n    \
+         WHERE {root_condition}\n    \
+         UNION ALL\n    \
+         SELECT c.{id}, c.{parent}, p.depth + 1\n    \
+         FROM {table} c\n    \
+         JOIN {name} p ON c.{parent} = p.{id}{depth_limit}\n\
+         )\n\
+         SELECT * FROM {name}",
+    ))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BuilderMode {
+    Window,
+    RecursiveCte,
+}
+
+/// 生成器对话框的内容，`current_sql` 返回要插入的 SQL
+pub struct SqlSnippetBuilder {
+    database_type: DatabaseType,
+    mode: BuilderMode,
+    function_select: Entity<SelectState<Vec<WindowFunction>>>,
+    argument_input: Entity<InputState>,
+    partition_input: Entity<InputState>,
+    order_input: Entity<InputState>,
+    frame_unit_select: Entity<SelectState<Vec<FrameUnit>>>,
+    frame_start_select: Entity<SelectState<Vec<FrameBound>>>,
+    frame_end_select: Entity<SelectState<Vec<FrameBound>>>,
+    frame_offset_input: Entity<InputState>,
+    alias_input: Entity<InputState>,
+    cte_name_input: Entity<InputState>,
+    table_input: Entity<InputState>,
+    id_input: Entity<InputState>,
+    parent_input: Entity<InputState>,
+    root_input: Entity<InputState>,
+    depth_input: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SqlSnippetBuilder {
+    pub fn new(database_type: DatabaseType, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = |placeholder: &'static str, value: &'static str, window: &mut Window, cx: &mut Context<Self>| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx).placeholder(placeholder);
+                if !value.is_empty() {
+                    state.set_value(value, window, cx);
+                }
+                state
+            })
+        };
+
+        let function_select = cx.new(|cx| SelectState::new(WindowFunction::all(), Some(IndexPath::new(0)), window, cx));
+        let frame_unit_select = cx.new(|cx| SelectState::new(FrameUnit::all(), Some(IndexPath::new(0)), window, cx));
+        let frame_start_select = cx.new(|cx| SelectState::new(FrameBound::all(), Some(IndexPath::new(0)), window, cx));
+        let frame_end_select = cx.new(|cx| SelectState::new(FrameBound::all(), Some(IndexPath::new(2)), window, cx));
+
+        let argument_input = input("参数", "", window, cx);
+        let partition_input = input("分区列，逗号分隔", "", window, cx);
+        let order_input = input("排序列，如 created_at DESC", "", window, cx);
+        let frame_offset_input = input("N", "1", window, cx);
+        let alias_input = input("别名", "", window, cx);
+        let cte_name_input = input("CTE 名称", "tree", window, cx);
+        let table_input = input("表名", "", window, cx);
+        let id_input = input("主键列", "id", window, cx);
+        let parent_input = input("父级列", "parent_id", window, cx);
+        let root_input = input("根节点条件，默认父级列为 NULL", "", window, cx);
+        let depth_input = input("最大深度，留空不限制", "", window, cx);
+
+        let mut subscriptions = Vec::new();
+        for state in [
+            &argument_input, &partition_input, &order_input, &frame_offset_input, &alias_input,
+            &cte_name_input, &table_input, &id_input, &parent_input, &root_input, &depth_input,
+        ] {
+            subscriptions.push(cx.subscribe_in(state, window, |_, _, event: &InputEvent, _, cx| {
+                if let InputEvent::Change = event {
+                    cx.notify();
+                }
+            }));
+        }
+        subscriptions.push(cx.observe(&function_select, |_, _, cx| cx.notify()));
+        subscriptions.push(cx.observe(&frame_unit_select, |_, _, cx| cx.notify()));
+        subscriptions.push(cx.observe(&frame_start_select, |_, _, cx| cx.notify()));
+        subscriptions.push(cx.observe(&frame_end_select, |_, _, cx| cx.notify()));
+
+        Self {
+            database_type,
+            mode: BuilderMode::Window,
+            function_select,
+            argument_input,
+            partition_input,
+            order_input,
+            frame_unit_select,
+            frame_start_select,
+            frame_end_select,
+            frame_offset_input,
+            alias_input,
+            cte_name_input,
+            table_input,
+            id_input,
+            parent_input,
+            root_input,
+            depth_input,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn text(input: &Entity<InputState>, cx: &App) -> String {
+        input.read(cx).text().to_string()
+    }
+
+    fn selected_function(&self, cx: &App) -> WindowFunction {
+        self.function_select.read(cx).selected_value().copied().unwrap_or(WindowFunction::RowNumber)
+    }
+
+    fn window_spec(&self, cx: &App) -> Result<WindowSpec> {
+        let offset_text = Self::text(&self.frame_offset_input, cx);
+        let offset = offset_text
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| anyhow!("框架偏移量必须是非负整数"))?;
+
+        Ok(WindowSpec {
+            function: self.selected_function(cx),
+            argument: Self::text(&self.argument_input, cx),
+            partition_by: Self::text(&self.partition_input, cx),
+            order_by: Self::text(&self.order_input, cx),
+            frame: WindowFrame {
+                unit: self.frame_unit_select.read(cx).selected_value().copied().unwrap_or(FrameUnit::Default),
+                start: self.frame_start_select.read(cx).selected_value().copied().unwrap_or(FrameBound::UnboundedPreceding),
+                end: self.frame_end_select.read(cx).selected_value().copied().unwrap_or(FrameBound::CurrentRow),
+                offset,
+            },
+            alias: Self::text(&self.alias_input, cx),
+        })
+    }
+
+    fn cte_spec(&self, cx: &App) -> Result<RecursiveCteSpec> {
+        let depth_text = Self::text(&self.depth_input, cx);
+        let max_depth = match depth_text.trim() {
+            "" => None,
+            depth => Some(depth.parse::<u32>().map_err(|_| anyhow!("最大深度必须是正整数"))?),
+        };
+
+        Ok(RecursiveCteSpec {
+            name: Self::text(&self.cte_name_input, cx),
+            table: Self::text(&self.table_input, cx),
+            id_column: Self::text(&self.id_input, cx),
+            parent_column: Self::text(&self.parent_input, cx),
+            root_condition: Self::text(&self.root_input, cx),
+            max_depth,
+        })
+    }
+
+    /// 按当前选项生成要插入的 SQL
+    pub fn current_sql(&self, cx: &App) -> Result<String> {
+        match self.mode {
+            BuilderMode::Window => build_window_expression(&self.window_spec(cx)?, self.database_type),
+            BuilderMode::RecursiveCte => build_recursive_cte(&self.cte_spec(cx)?, self.database_type),
+        }
+    }
+
+    fn labeled(label: &'static str, child: impl IntoElement, cx: &App) -> AnyElement {
+        h_flex()
+            .gap_3()
+            .items_center()
+            .child(div().w(px(80.)).text_sm().text_color(cx.theme().muted_foreground).child(label))
+            .child(div().flex_1().child(child))
+            .into_any_element()
+    }
+
+    fn render_window_form(&self, cx: &App) -> AnyElement {
+        let function = self.selected_function(cx);
+        let argument_hint = function.argument_hint();
+        let frame_unit = self.frame_unit_select.read(cx).selected_value().copied().unwrap_or(FrameUnit::Default);
+
+        v_flex()
+            .gap_2()
+            .child(Self::labeled("函数", Select::new(&self.function_select).small(), cx))
+            .when_some(argument_hint, |this, hint| {
+                this.child(Self::labeled("参数", v_flex().gap_1().child(Input::new(&self.argument_input).small()).child(
+                    div().text_xs().text_color(cx.theme().muted_foreground).child(hint),
+                ), cx))
+            })
+            .child(Self::labeled("PARTITION BY", Input::new(&self.partition_input).small(), cx))
+            .child(Self::labeled("ORDER BY", Input::new(&self.order_input).small(), cx))
+            .when(function.supports_frame(), |this| {
+                this.child(Self::labeled(
+                    "窗口框架",
+                    h_flex()
+                        .gap_2()
+                        .child(Select::new(&self.frame_unit_select).small().w(px(110.)))
+                        .when(frame_unit != FrameUnit::Default, |this| {
+                            this.child(Select::new(&self.frame_start_select).small().w(px(180.)))
+                                .child(div().text_sm().child("AND"))
+                                .child(Select::new(&self.frame_end_select).small().w(px(180.)))
+                                .child(Input::new(&self.frame_offset_input).small().w(px(60.)))
+                        }),
+                    cx,
+                ))
+            })
+            .child(Self::labeled("别名", Input::new(&self.alias_input).small(), cx))
+            .into_any_element()
+    }
+
+    fn render_cte_form(&self, cx: &App) -> AnyElement {
+        v_flex()
+            .gap_2()
+            .child(Self::labeled("CTE 名称", Input::new(&self.cte_name_input).small(), cx))
+            .child(Self::labeled("表名", Input::new(&self.table_input).small(), cx))
+            .child(Self::labeled("主键列", Input::new(&self.id_input).small(), cx))
+            .child(Self::labeled("父级列", Input::new(&self.parent_input).small(), cx))
+            .child(Self::labeled("根节点条件", Input::new(&self.root_input).small(), cx))
+            .child(Self::labeled("最大深度", Input::new(&self.depth_input).small(), cx))
+            .into_any_element()
+    }
+}
+
+impl Render for SqlSnippetBuilder {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let preview = self.current_sql(cx);
+        let selected_index = match self.mode {
+            BuilderMode::Window => 0,
+            BuilderMode::RecursiveCte => 1,
+        };
+
+        v_flex()
+            .gap_3()
+            .child(
+                TabBar::new("snippet-builder-tabs")
+                    .underline()
+                    .with_size(Size::Small)
+                    .selected_index(selected_index)
+                    .on_click(cx.listener(|this, ix: &usize, _window, cx| {
+                        this.mode = if *ix == 1 { BuilderMode::RecursiveCte } else { BuilderMode::Window };
+                        cx.notify();
+                    }))
+                    .child(Tab::new().label("窗口函数"))
+                    .child(Tab::new().label("递归 CTE")),
+            )
+            .child(match self.mode {
+                BuilderMode::Window => self.render_window_form(cx),
+                BuilderMode::RecursiveCte => self.render_cte_form(cx),
+            })
+            .child(
+                div()
+                    .p_2()
+                    .rounded_md()
+                    .bg(cx.theme().muted)
+                    .font_family("monospace")
+                    .text_xs()
+                    .whitespace_normal()
+                    .map(|this| match &preview {
+                        Ok(sql) => this.child(sql.clone()),
+                        Err(e) => this.text_color(cx.theme().muted_foreground).child(e.to_string()),
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(function: WindowFunction) -> WindowSpec {
+        WindowSpec {
+            function,
+            argument: String::new(),
+            partition_by: "dept_id".to_string(),
+            order_by: "salary DESC".to_string(),
+            frame: WindowFrame {
+                unit: FrameUnit::Default,
+                start: FrameBound::UnboundedPreceding,
+                end: FrameBound::CurrentRow,
+                offset: 1,
+            },
+            alias: "rn".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ranking_function_ignores_frame() {
+        let mut spec = spec(WindowFunction::RowNumber);
+        spec.frame.unit = FrameUnit::Rows;
+        assert_eq!(
+            build_window_expression(&spec, DatabaseType::MySQL).unwrap(),
+            "ROW_NUMBER() OVER (PARTITION BY dept_id ORDER BY salary DESC) AS rn"
+        );
+
+        spec.order_by.clear();
+        assert!(build_window_expression(&spec, DatabaseType::MySQL).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_with_moving_frame() {
+        let mut spec = spec(WindowFunction::Avg);
+        spec.argument = "amount".to_string();
+        spec.partition_by.clear();
+        spec.order_by = "created_at".to_string();
+        spec.alias = "moving_avg".to_string();
+        spec.frame = WindowFrame { unit: FrameUnit::Rows, start: FrameBound::Preceding, end: FrameBound::CurrentRow, offset: 6 };
+        assert_eq!(
+            build_window_expression(&spec, DatabaseType::PostgreSQL).unwrap(),
+            "AVG(amount) OVER (ORDER BY created_at ROWS BETWEEN 6 PRECEDING AND CURRENT ROW) AS moving_avg"
+        );
+
+        spec.argument.clear();
+        assert!(build_window_expression(&spec, DatabaseType::PostgreSQL).is_err());
+    }
+
+    #[test]
+    fn test_invalid_frames() {
+        let mut spec = spec(WindowFunction::Sum);
+        spec.argument = "amount".to_string();
+        spec.frame = WindowFrame { unit: FrameUnit::Rows, start: FrameBound::CurrentRow, end: FrameBound::Preceding, offset: 1 };
+        assert!(build_window_expression(&spec, DatabaseType::MySQL).is_err());
+
+        spec.frame = WindowFrame { unit: FrameUnit::Range, start: FrameBound::Preceding, end: FrameBound::CurrentRow, offset: 3 };
+        assert!(build_window_expression(&spec, DatabaseType::MSSQL).is_err());
+        assert!(build_window_expression(&spec, DatabaseType::PostgreSQL).is_ok());
+    }
+
+    #[test]
+    fn test_clickhouse_lag_uses_full_frame() {
+        let mut spec = spec(WindowFunction::Lag);
+        spec.argument = "salary, 1".to_string();
+        spec.alias = "prev_salary".to_string();
+        assert_eq!(
+            build_window_expression(&spec, DatabaseType::ClickHouse).unwrap(),
+            "lagInFrame(salary, 1) OVER (PARTITION BY dept_id ORDER BY salary DESC \
+             ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING) AS prev_salary"
+        );
+        assert!(build_window_expression(&spec, DatabaseType::MySQL).unwrap().starts_with("LAG(salary, 1) OVER"));
+    }
+
+    #[test]
+    fn test_recursive_cte_per_dialect() {
+        let spec = RecursiveCteSpec {
+            name: "tree".to_string(),
+            table: "categories".to_string(),
+            id_column: "id".to_string(),
+            parent_column: "parent_id".to_string(),
+            root_condition: String::new(),
+            max_depth: Some(10),
+        };
+
+        let sql = build_recursive_cte(&spec, DatabaseType::PostgreSQL).unwrap();
+        assert!(sql.starts_with("WITH RECURSIVE tree (id, parent_id, depth) AS ("));
+        assert!(sql.contains("WHERE parent_id IS NULL"));
+        assert!(sql.contains("JOIN tree p ON c.parent_id = p.id\n    WHERE p.depth < 10\n)"));
+        assert!(sql.ends_with("SELECT * FROM tree"));
+
+        assert!(build_recursive_cte(&spec, DatabaseType::MSSQL).unwrap().starts_with("WITH tree ("));
+        assert!(build_recursive_cte(&spec, DatabaseType::Oracle).unwrap().starts_with("WITH tree ("));
+
+        let missing_table = RecursiveCteSpec { table: " ".to_string(), ..spec };
+        assert!(build_recursive_cte(&missing_table, DatabaseType::MySQL).is_err());
+    }
+}
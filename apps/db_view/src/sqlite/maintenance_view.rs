@@ -0,0 +1,462 @@
+//! SQLite 维护面板 - 查看和修改常用 PRAGMA，执行完整性检查、VACUUM 和 ANALYZE
+
+use std::any::Any;
+use std::time::Instant;
+
+use db::{GlobalDbState, SqlResult};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Icon, IconName, Sizable, WindowExt,
+};
+use one_core::tab_container::{TabContent, TabContentType};
+
+/// 面板中可修改的 PRAGMA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlitePragma {
+    JournalMode,
+    Synchronous,
+    PageSize,
+    CacheSize,
+}
+
+impl SqlitePragma {
+    pub const ALL: [SqlitePragma; 4] = [
+        SqlitePragma::JournalMode,
+        SqlitePragma::Synchronous,
+        SqlitePragma::PageSize,
+        SqlitePragma::CacheSize,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SqlitePragma::JournalMode => "journal_mode",
+            SqlitePragma::Synchronous => "synchronous",
+            SqlitePragma::PageSize => "page_size",
+            SqlitePragma::CacheSize => "cache_size",
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            SqlitePragma::JournalMode => "DELETE / TRUNCATE / PERSIST / MEMORY / WAL / OFF，写入数据库文件",
+            SqlitePragma::Synchronous => "OFF / NORMAL / FULL / EXTRA，仅对当前连接有效",
+            SqlitePragma::PageSize => "512 到 65536 之间的 2 的幂，执行 VACUUM 后生效（WAL 模式下不可修改）",
+            SqlitePragma::CacheSize => "正数为页数，负数为 KiB，仅对当前连接有效",
+        }
+    }
+
+    /// 校验并规范化要设置的值
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self {
+            SqlitePragma::JournalMode => {
+                let upper = value.to_uppercase();
+                if ["DELETE", "TRUNCATE", "PERSIST", "MEMORY", "WAL", "OFF"].contains(&upper.as_str()) {
+                    Ok(upper)
+                } else {
+                    Err(format!("无效的 journal_mode: {}", value))
+                }
+            }
+            SqlitePragma::Synchronous => {
+                let upper = value.to_uppercase();
+                if ["OFF", "NORMAL", "FULL", "EXTRA", "0", "1", "2", "3"].contains(&upper.as_str()) {
+                    Ok(upper)
+                } else {
+                    Err(format!("无效的 synchronous: {}", value))
+                }
+            }
+            SqlitePragma::PageSize => match value.parse::<u32>() {
+                Ok(size) if (512..=65536).contains(&size) && size.is_power_of_two() => Ok(size.to_string()),
+                _ => Err(format!("无效的 page_size: {}", value)),
+            },
+            SqlitePragma::CacheSize => value
+                .parse::<i64>()
+                .map(|size| size.to_string())
+                .map_err(|_| format!("无效的 cache_size: {}", value)),
+        }
+    }
+
+    /// synchronous 查询返回的是数字，显示为名称
+    pub fn display_value(&self, value: &str) -> String {
+        match (self, value) {
+            (SqlitePragma::Synchronous, "0") => "OFF".to_string(),
+            (SqlitePragma::Synchronous, "1") => "NORMAL".to_string(),
+            (SqlitePragma::Synchronous, "2") => "FULL".to_string(),
+            (SqlitePragma::Synchronous, "3") => "EXTRA".to_string(),
+            (SqlitePragma::JournalMode, _) => value.to_uppercase(),
+            _ => value.to_string(),
+        }
+    }
+
+    pub fn build_set_sql(&self, value: &str) -> Result<String, String> {
+        Ok(format!("PRAGMA {} = {}", self.name(), self.normalize(value)?))
+    }
+}
+
+/// 维护操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceTask {
+    IntegrityCheck,
+    Vacuum,
+    Analyze,
+}
+
+impl MaintenanceTask {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MaintenanceTask::IntegrityCheck => "完整性检查",
+            MaintenanceTask::Vacuum => "VACUUM",
+            MaintenanceTask::Analyze => "ANALYZE",
+        }
+    }
+
+    pub fn sql(&self) -> &'static str {
+        match self {
+            MaintenanceTask::IntegrityCheck => "PRAGMA integrity_check",
+            MaintenanceTask::Vacuum => "VACUUM",
+            MaintenanceTask::Analyze => "ANALYZE",
+        }
+    }
+}
+
+/// 把执行结果整理为输出行：查询结果取第一列，其余显示影响行数或错误
+fn result_lines(results: Vec<SqlResult>) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for result in results {
+        match result {
+            SqlResult::Query(query) => {
+                lines.extend(query.rows.into_iter().filter_map(|row| row.into_iter().next().flatten()));
+            }
+            SqlResult::Exec(_) => lines.push("完成".to_string()),
+            SqlResult::Error(err) => return Err(err.message),
+        }
+    }
+    Ok(lines)
+}
+
+struct PragmaRow {
+    pragma: SqlitePragma,
+    input: Entity<InputState>,
+    current: Option<String>,
+}
+
+/// 最近一次维护操作的结果
+struct TaskOutput {
+    task: MaintenanceTask,
+    elapsed_ms: u128,
+    result: Result<Vec<String>, String>,
+}
+
+pub struct SqliteMaintenanceView {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    database: String,
+    pragmas: Vec<PragmaRow>,
+    loading: bool,
+    running: Option<(MaintenanceTask, Instant)>,
+    output: Option<TaskOutput>,
+}
+
+impl SqliteMaintenanceView {
+    pub fn new(connection_id: String, database: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let pragmas = SqlitePragma::ALL
+            .iter()
+            .map(|pragma| PragmaRow {
+                pragma: *pragma,
+                input: cx.new(|cx| InputState::new(window, cx).placeholder(pragma.name())),
+                current: None,
+            })
+            .collect();
+
+        let mut view = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            database,
+            pragmas,
+            loading: false,
+            running: None,
+            output: None,
+        };
+        view.load_pragmas(window, cx);
+        view
+    }
+
+    fn load_pragmas(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.loading = true;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let script = SqlitePragma::ALL
+            .iter()
+            .map(|pragma| format!("PRAGMA {};", pragma.name()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.execute_script(cx, connection_id, script, Some(database), None).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                let _ = this.update(cx, |view, cx| {
+                    view.loading = false;
+                    match result {
+                        Ok(results) => {
+                            for (row, result) in view.pragmas.iter_mut().zip(results) {
+                                if let SqlResult::Query(query) = result {
+                                    let value = query.rows.into_iter().next().and_then(|r| r.into_iter().next().flatten());
+                                    row.current = value.map(|v| row.pragma.display_value(&v));
+                                }
+                                let current = row.current.clone().unwrap_or_default();
+                                row.input.update(cx, |input, cx| input.set_value(current, window, cx));
+                            }
+                        }
+                        Err(e) => window.push_notification(Notification::error(format!("读取 PRAGMA 失败: {}", e)), cx),
+                    }
+                    cx.notify();
+                });
+            });
+        }).detach();
+    }
+
+    fn apply_pragma(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.pragmas.get(index) else {
+            return;
+        };
+        let sql = match row.pragma.build_set_sql(&row.input.read(cx).text().to_string()) {
+            Ok(sql) => sql,
+            Err(e) => {
+                window.push_notification(Notification::error(e), cx);
+                return;
+            }
+        };
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state
+                .execute_script(cx, connection_id, sql.clone(), Some(database), None)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(result_lines);
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                match result {
+                    Ok(_) => window.push_notification(Notification::success(format!("已执行 {}", sql)).autohide(true), cx),
+                    Err(e) => window.push_notification(Notification::error(format!("设置失败: {}", e)), cx),
+                }
+                let _ = this.update(cx, |view, cx| view.load_pragmas(window, cx));
+            });
+        }).detach();
+    }
+
+    fn run_task(&mut self, task: MaintenanceTask, cx: &mut Context<Self>) {
+        if self.running.is_some() {
+            return;
+        }
+        let started = Instant::now();
+        self.running = Some((task, started));
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state
+                .execute_script(cx, connection_id, task.sql().to_string(), Some(database), None)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(result_lines);
+            let _ = this.update(cx, |view, cx| {
+                view.running = None;
+                view.output = Some(TaskOutput {
+                    task,
+                    elapsed_ms: started.elapsed().as_millis(),
+                    result,
+                });
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn render_pragma_row(&self, index: usize, row: &PragmaRow, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .gap_3()
+            .items_center()
+            .child(div().w(px(120.)).text_sm().font_family("monospace").child(row.pragma.name()))
+            .child(Input::new(&row.input).w(px(160.)).small())
+            .child(
+                Button::new(SharedString::from(format!("apply-pragma-{}", index)))
+                    .small()
+                    .label("应用")
+                    .disabled(self.loading)
+                    .on_click(cx.listener(move |view, _, window, cx| view.apply_pragma(index, window, cx))),
+            )
+            .child(div().text_xs().text_color(cx.theme().muted_foreground).child(row.pragma.hint()))
+    }
+
+    fn render_output(&self, cx: &mut Context<Self>) -> AnyElement {
+        if let Some((task, _)) = &self.running {
+            return div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child(format!("正在执行{}…", task.label()))
+                .into_any_element();
+        }
+        let Some(output) = &self.output else {
+            return div().into_any_element();
+        };
+
+        let (summary, color, lines) = match &output.result {
+            Ok(lines) if output.task == MaintenanceTask::IntegrityCheck && lines.iter().any(|l| l != "ok") => {
+                (format!("{}发现问题（耗时 {} ms）", output.task.label(), output.elapsed_ms), cx.theme().danger, lines.clone())
+            }
+            Ok(lines) => (format!("{}完成（耗时 {} ms）", output.task.label(), output.elapsed_ms), cx.theme().success, lines.clone()),
+            Err(e) => (format!("{}失败", output.task.label()), cx.theme().danger, vec![e.clone()]),
+        };
+
+        v_flex()
+            .gap_1()
+            .child(div().text_sm().text_color(color).child(summary))
+            .child(
+                div()
+                    .id("maintenance-output")
+                    .max_h(px(320.))
+                    .overflow_y_scroll()
+                    .p_3()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().muted)
+                    .text_sm()
+                    .font_family("monospace")
+                    .children(lines.into_iter().map(|line| div().child(line))),
+            )
+            .into_any_element()
+    }
+}
+
+impl Focusable for SqliteMaintenanceView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SqliteMaintenanceView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut settings = v_flex().gap_2();
+        for (index, row) in self.pragmas.iter().enumerate() {
+            settings = settings.child(self.render_pragma_row(index, row, cx));
+        }
+        let running = self.running.map(|(task, _)| task);
+
+        let mut tasks = h_flex().gap_2();
+        for task in [MaintenanceTask::IntegrityCheck, MaintenanceTask::Vacuum, MaintenanceTask::Analyze] {
+            tasks = tasks.child(
+                Button::new(SharedString::from(format!("maintenance-{}", task.label())))
+                    .small()
+                    .when(task == MaintenanceTask::IntegrityCheck, |button| button.primary())
+                    .label(task.label())
+                    .loading(running == Some(task))
+                    .disabled(running.is_some())
+                    .on_click(cx.listener(move |view, _, _, cx| view.run_task(task, cx))),
+            );
+        }
+
+        v_flex()
+            .size_full()
+            .p_4()
+            .gap_4()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("PRAGMA 设置"))
+                    .when(self.loading, |this| {
+                        this.child(div().text_xs().text_color(cx.theme().muted_foreground).child("加载中..."))
+                    }),
+            )
+            .child(settings)
+            .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("维护"))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("VACUUM 会重写整个数据库文件，期间数据库被锁定，需要额外的磁盘空间"),
+            )
+            .child(tasks)
+            .child(self.render_output(cx))
+    }
+}
+
+pub struct SqliteMaintenanceTabContent {
+    title: SharedString,
+    view: Entity<SqliteMaintenanceView>,
+}
+
+impl SqliteMaintenanceTabContent {
+    pub fn new(connection_id: String, database: String, window: &mut Window, cx: &mut App) -> Self {
+        let title = format!("维护: {}", database);
+        let view = cx.new(|cx| SqliteMaintenanceView::new(connection_id, database, window, cx));
+        Self {
+            title: title.into(),
+            view,
+        }
+    }
+}
+
+impl TabContent for SqliteMaintenanceTabContent {
+    fn title(&self) -> SharedString {
+        self.title.clone()
+    }
+
+    fn icon(&self) -> Option<Icon> {
+        Some(IconName::Database.color())
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn render_content(&self, _window: &mut Window, _cx: &mut App) -> AnyElement {
+        self.view.clone().into_any_element()
+    }
+
+    fn content_type(&self) -> TabContentType {
+        TabContentType::Custom("SqliteMaintenance".to_string())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_set_sql() {
+        assert_eq!(SqlitePragma::JournalMode.build_set_sql(" wal ").unwrap(), "PRAGMA journal_mode = WAL");
+        assert_eq!(SqlitePragma::Synchronous.build_set_sql("normal").unwrap(), "PRAGMA synchronous = NORMAL");
+        assert_eq!(SqlitePragma::PageSize.build_set_sql("8192").unwrap(), "PRAGMA page_size = 8192");
+        assert_eq!(SqlitePragma::CacheSize.build_set_sql("-20000").unwrap(), "PRAGMA cache_size = -20000");
+        assert!(SqlitePragma::JournalMode.build_set_sql("wal; DROP TABLE t").is_err());
+        assert!(SqlitePragma::PageSize.build_set_sql("1000").is_err());
+    }
+
+    #[test]
+    fn test_display_value() {
+        assert_eq!(SqlitePragma::Synchronous.display_value("2"), "FULL");
+        assert_eq!(SqlitePragma::JournalMode.display_value("wal"), "WAL");
+        assert_eq!(SqlitePragma::CacheSize.display_value("-2000"), "-2000");
+    }
+}
@@ -1,2 +1,3 @@
 pub mod database_form;
+pub mod maintenance_view;
 pub mod sqlite_view_plugin;
@@ -73,6 +73,7 @@ impl DatabaseViewPlugin for SqliteDatabaseViewPlugin {
             supports_dump_database: true,
             supports_create_schema: false,
             supports_delete_schema: false,
+            supports_maintenance: true,
             supports_create_view: true,
             supports_edit_view: true,
             supports_sequences: false,
@@ -0,0 +1,187 @@
+//! 导出脱敏样本 - 随机抽取部分行并按列脱敏，便于分享给同事或附到 AI 提示词中
+
+use gpui::{div, px, App, AppContext, AsyncApp, ClipboardItem, Entity, InteractiveElement, IntoElement, ParentElement, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    h_flex,
+    input::{Input, InputState},
+    select::{Select, SelectItem, SelectState},
+    v_flex, ActiveTheme, IndexPath, WindowExt,
+};
+
+use db::{AnonymizeConfig, DataFormat, GridExportData, MaskingRule};
+
+use crate::table_data::data_grid::notification;
+
+const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+#[derive(Clone, Debug)]
+pub(crate) struct MaskingRuleItem(pub(crate) MaskingRule);
+
+impl SelectItem for MaskingRuleItem {
+    type Value = MaskingRule;
+
+    fn title(&self) -> SharedString {
+        self.0.label().into()
+    }
+
+    fn value(&self) -> &Self::Value {
+        &self.0
+    }
+}
+
+/// 样本的输出方式
+#[derive(Clone, Debug, PartialEq)]
+enum SampleOutput {
+    CsvFile,
+    JsonFile,
+    Clipboard,
+}
+
+impl SampleOutput {
+    fn all() -> Vec<Self> {
+        vec![Self::CsvFile, Self::JsonFile, Self::Clipboard]
+    }
+}
+
+impl SelectItem for SampleOutput {
+    type Value = SampleOutput;
+
+    fn title(&self) -> SharedString {
+        match self {
+            SampleOutput::CsvFile => "保存为 CSV 文件".into(),
+            SampleOutput::JsonFile => "保存为 JSON 文件".into(),
+            SampleOutput::Clipboard => "复制 CSV 到剪贴板".into(),
+        }
+    }
+
+    fn value(&self) -> &Self::Value {
+        self
+    }
+}
+
+pub fn open_anonymize_dialog(data: GridExportData, file_stem: String, window: &mut Window, cx: &mut App) {
+    if data.rows.is_empty() {
+        window.push_notification("没有可导出的数据", cx);
+        return;
+    }
+
+    let sample_size = cx.new(|cx| {
+        let mut state = InputState::new(window, cx).placeholder("抽样行数");
+        state.set_value(DEFAULT_SAMPLE_SIZE.to_string(), window, cx);
+        state
+    });
+    let output = cx.new(|cx| SelectState::new(SampleOutput::all(), Some(IndexPath::default()), window, cx));
+    let rule_items: Vec<MaskingRuleItem> = MaskingRule::all().into_iter().map(MaskingRuleItem).collect();
+    let rules: Vec<(String, Entity<SelectState<Vec<MaskingRuleItem>>>)> = data
+        .columns
+        .iter()
+        .map(|column| {
+            let suggested = MaskingRule::all()
+                .iter()
+                .position(|rule| *rule == MaskingRule::suggest(column))
+                .unwrap_or(0);
+            let select = cx.new(|cx| SelectState::new(rule_items.clone(), Some(IndexPath::new(suggested)), window, cx));
+            (column.clone(), select)
+        })
+        .collect();
+
+    window.open_dialog(cx, move |dialog, _window, cx| {
+        let data = data.clone();
+        let file_stem = file_stem.clone();
+        let sample_size = sample_size.clone();
+        let output = output.clone();
+        let rules = rules.clone();
+
+        let mut rule_rows = v_flex().gap_1();
+        for (column, select) in &rules {
+            rule_rows = rule_rows.child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(div().flex_1().text_sm().overflow_hidden().child(column.clone()))
+                    .child(Select::new(select).w_32()),
+            );
+        }
+
+        dialog
+            .title("导出脱敏样本")
+            .w(px(460.0))
+            .child(
+                v_flex()
+                    .gap_3()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("从当前 {} 行中随机抽样，并按列规则脱敏", data.rows.len())),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(v_flex().flex_1().gap_1().child(div().text_sm().child("抽样行数")).child(Input::new(&sample_size)))
+                            .child(v_flex().flex_1().gap_1().child(div().text_sm().child("输出")).child(Select::new(&output))),
+                    )
+                    .child(
+                        div()
+                            .id("anonymize-rules")
+                            .max_h(px(320.0))
+                            .overflow_y_scroll()
+                            .child(rule_rows),
+                    ),
+            )
+            .confirm()
+            .on_ok(move |_, window, cx| {
+                let sample_size = match sample_size.read(cx).text().to_string().trim().parse::<usize>() {
+                    Ok(size) if size > 0 => size,
+                    _ => {
+                        window.push_notification("抽样行数必须是正整数", cx);
+                        return false;
+                    }
+                };
+                let config = AnonymizeConfig {
+                    sample_size,
+                    rules: rules
+                        .iter()
+                        .map(|(_, select)| select.read(cx).selected_value().copied().unwrap_or(MaskingRule::Keep))
+                        .collect(),
+                    seed: rand::random(),
+                };
+                let sample = data.anonymized_sample(&config);
+
+                match output.read(cx).selected_value().cloned().unwrap_or(SampleOutput::CsvFile) {
+                    SampleOutput::Clipboard => {
+                        cx.write_to_clipboard(ClipboardItem::new_string(sample.to_csv()));
+                        window.push_notification(format!("已复制 {} 行脱敏样本", sample.rows.len()), cx);
+                    }
+                    SampleOutput::CsvFile => save_sample(sample, DataFormat::Csv, &file_stem, cx),
+                    SampleOutput::JsonFile => save_sample(sample, DataFormat::Json, &file_stem, cx),
+                }
+                true
+            })
+    });
+}
+
+fn save_sample(sample: GridExportData, format: DataFormat, file_stem: &str, cx: &mut App) {
+    let file_name = format!("{}_sample.{}", file_stem, format.extension());
+    let directory = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_default();
+    let path_future = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let path = match path_future.await {
+            Ok(Ok(Some(path))) => path,
+            _ => return,
+        };
+
+        let message = match sample.render(format).and_then(|content| {
+            std::fs::write(&path, content)?;
+            Ok(())
+        }) {
+            Ok(()) => format!("已导出 {} 行脱敏样本到 {}", sample.rows.len(), path.display()),
+            Err(err) => format!("导出失败: {}", err),
+        };
+        cx.update(|cx| notification(cx, message)).ok();
+    })
+    .detach();
+}
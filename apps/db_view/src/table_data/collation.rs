@@ -0,0 +1,266 @@
+//! 表格客户端排序的排序规则 - 根据列的字符集/排序规则元数据选择 ICU 比较方式
+
+use std::cmp::Ordering;
+
+use db::FieldType;
+use icu_collator::{Collator, CollatorOptions, Strength};
+use icu_locid::Locale;
+
+/// 比较强度
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollationStrength {
+    /// 忽略大小写和重音（如 utf8mb4_0900_ai_ci）
+    Primary,
+    /// 忽略大小写（如 utf8mb4_general_ci）
+    Secondary,
+    /// 区分大小写
+    Tertiary,
+}
+
+impl CollationStrength {
+    fn to_icu(self) -> Strength {
+        match self {
+            CollationStrength::Primary => Strength::Primary,
+            CollationStrength::Secondary => Strength::Secondary,
+            CollationStrength::Tertiary => Strength::Tertiary,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            CollationStrength::Primary => "不区分大小写和重音",
+            CollationStrength::Secondary => "不区分大小写",
+            CollationStrength::Tertiary => "区分大小写",
+        }
+    }
+}
+
+/// 客户端排序使用的排序规则
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortCollation {
+    /// 列元数据中的排序规则名称
+    pub name: Option<String>,
+    /// ICU 语言标识，None 表示按字节比较
+    pub locale: Option<String>,
+    pub strength: CollationStrength,
+}
+
+/// 数据库排序规则名称中的语言名
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("chinese", "zh"),
+    ("japanese", "ja"),
+    ("korean", "ko"),
+    ("vietnamese", "vi"),
+    ("swedish", "sv"),
+    ("danish", "da"),
+    ("german", "de"),
+    ("french", "fr"),
+    ("spanish", "es"),
+    ("polish", "pl"),
+    ("czech", "cs"),
+    ("slovak", "sk"),
+    ("slovenian", "sl"),
+    ("croatian", "hr"),
+    ("hungarian", "hu"),
+    ("romanian", "ro"),
+    ("turkish", "tr"),
+    ("estonian", "et"),
+    ("latvian", "lv"),
+    ("lithuanian", "lt"),
+    ("persian", "fa"),
+];
+
+/// 暗含语言的字符集
+const CHARSET_LOCALES: &[(&str, &str)] = &[
+    ("gbk", "zh"),
+    ("gb2312", "zh"),
+    ("gb18030", "zh"),
+    ("big5", "zh-Hant"),
+    ("sjis", "ja"),
+    ("ujis", "ja"),
+    ("cp932", "ja"),
+    ("eucjpms", "ja"),
+    ("euckr", "ko"),
+];
+
+/// 名称中出现即按 Unicode 通用规则比较
+const UNICODE_MARKERS: &[&str] = &["general", "unicode", "0900", "0520", "icu", "und"];
+
+impl SortCollation {
+    /// 按字节（码点）比较
+    pub fn binary(name: Option<String>) -> Self {
+        Self { name, locale: None, strength: CollationStrength::Tertiary }
+    }
+
+    /// 解析 MySQL / PostgreSQL / SQL Server 的排序规则名称，
+    /// 如 `gbk_chinese_ci`、`utf8mb4_zh_0900_as_cs`、`zh_CN.UTF-8`、`Chinese_PRC_CI_AS`
+    pub fn from_name(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        let tokens: Vec<&str> = lower.split(['_', '-', '.', '@']).filter(|t| !t.is_empty()).collect();
+
+        let is_binary = matches!(lower.as_str(), "binary" | "c" | "posix" | "default" | "ucs_basic")
+            || tokens.iter().any(|t| t.starts_with("bin"));
+        if is_binary {
+            return Self::binary(Some(name.to_string()));
+        }
+
+        let strength = if tokens.contains(&"ai") {
+            CollationStrength::Primary
+        } else if tokens.contains(&"ci") {
+            CollationStrength::Secondary
+        } else {
+            CollationStrength::Tertiary
+        };
+
+        let language_name = tokens.iter().find_map(|token| {
+            LANGUAGE_NAMES
+                .iter()
+                .find(|(language, _)| token.starts_with(language))
+                .map(|(_, locale)| locale.to_string())
+        });
+        // ISO 语言代码只出现在开头（zh_CN.UTF-8）或字符集之后（utf8mb4_zh_0900_as_cs），
+        // 其他位置的 cs/as 等是大小写、重音标记
+        let iso_code = || {
+            let first = tokens.first().filter(|t| t.len() == 2);
+            let after_charset = tokens
+                .get(1)
+                .filter(|t| t.len() == 2 && tokens.get(2).is_some_and(|v| v.chars().all(|c| c.is_ascii_digit())));
+            first.or(after_charset).map(|code| code.to_string())
+        };
+        let charset_locale = || {
+            let charset = tokens.first()?;
+            CHARSET_LOCALES
+                .iter()
+                .find(|(name, _)| charset == name)
+                .map(|(_, locale)| locale.to_string())
+        };
+        let unicode = || {
+            tokens
+                .iter()
+                .any(|t| UNICODE_MARKERS.contains(t) || *t == "ci" || *t == "cs")
+                .then(|| "und".to_string())
+        };
+
+        match language_name.or_else(iso_code).or_else(charset_locale).or_else(unicode) {
+            Some(locale) => Self { name: Some(name.to_string()), locale: Some(locale), strength },
+            None => Self::binary(Some(name.to_string())),
+        }
+    }
+
+    pub fn is_binary(&self) -> bool {
+        self.locale.is_none()
+    }
+
+    /// 列头菜单中显示的说明，如 `gbk_chinese_ci（拼音，不区分大小写）`
+    pub fn label(&self) -> String {
+        let rule = match self.locale.as_deref() {
+            None => "二进制".to_string(),
+            Some(locale) if locale.starts_with("zh-Hant") => format!("笔画，{}", self.strength.label()),
+            Some(locale) if locale.starts_with("zh") => format!("拼音，{}", self.strength.label()),
+            Some("und") => format!("Unicode，{}", self.strength.label()),
+            Some(locale) => format!("{}，{}", locale, self.strength.label()),
+        };
+        match &self.name {
+            Some(name) => format!("{}（{}）", name, rule),
+            None => rule,
+        }
+    }
+
+    pub fn comparator(&self) -> CollationComparator {
+        let collator = self.locale.as_deref().and_then(|locale| {
+            let locale: Locale = locale.parse().ok()?;
+            let mut options = CollatorOptions::new();
+            options.strength = Some(self.strength.to_icu());
+            Collator::try_new(&(&locale).into(), options).ok()
+        });
+        CollationComparator { collator }
+    }
+}
+
+/// 按排序规则比较单元格的值
+pub struct CollationComparator {
+    collator: Option<Collator>,
+}
+
+impl CollationComparator {
+    pub fn compare_text(&self, a: &str, b: &str) -> Ordering {
+        match &self.collator {
+            Some(collator) => collator.compare(a, b).then_with(|| a.cmp(b)),
+            None => a.cmp(b),
+        }
+    }
+
+    /// NULL 排在最前；数值列按数值比较，其余按排序规则比较
+    pub fn compare_cells(&self, a: Option<&str>, b: Option<&str>, field_type: FieldType) -> Ordering {
+        let (a, b) = match (a, b) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => (a, b),
+        };
+
+        let numeric = matches!(field_type, FieldType::Integer | FieldType::Decimal | FieldType::Unknown);
+        if numeric {
+            if let (Ok(x), Ok(y)) = (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+                return x.partial_cmp(&y).unwrap_or(Ordering::Equal);
+            }
+        }
+        self.compare_text(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_collation_names() {
+        let gbk = SortCollation::from_name("gbk_chinese_ci");
+        assert_eq!(gbk.locale.as_deref(), Some("zh"));
+        assert_eq!(gbk.strength, CollationStrength::Secondary);
+
+        let zh = SortCollation::from_name("utf8mb4_zh_0900_as_cs");
+        assert_eq!(zh.locale.as_deref(), Some("zh"));
+        assert_eq!(zh.strength, CollationStrength::Tertiary);
+
+        let czech = SortCollation::from_name("utf8mb4_cs_0900_ai_ci");
+        assert_eq!(czech.locale.as_deref(), Some("cs"));
+        assert_eq!(czech.strength, CollationStrength::Primary);
+
+        assert_eq!(SortCollation::from_name("zh_CN.UTF-8").locale.as_deref(), Some("zh"));
+        assert_eq!(SortCollation::from_name("Chinese_PRC_CI_AS").locale.as_deref(), Some("zh"));
+        assert_eq!(SortCollation::from_name("utf8mb4_0900_ai_ci").locale.as_deref(), Some("und"));
+
+        assert!(SortCollation::from_name("utf8mb4_bin").is_binary());
+        assert!(SortCollation::from_name("C").is_binary());
+        assert!(SortCollation::from_name("Latin1_General_BIN2").is_binary());
+    }
+
+    #[test]
+    fn test_pinyin_ordering() {
+        let comparator = SortCollation::from_name("gbk_chinese_ci").comparator();
+        let mut names = vec!["张三", "李四", "阿明", "王五"];
+        names.sort_by(|a, b| comparator.compare_text(a, b));
+        assert_eq!(names, vec!["阿明", "李四", "王五", "张三"]);
+    }
+
+    #[test]
+    fn test_case_insensitive_ordering() {
+        let comparator = SortCollation::from_name("utf8mb4_general_ci").comparator();
+        let mut values = vec!["banana", "apple", "Cherry"];
+        values.sort_by(|a, b| comparator.compare_text(a, b));
+        assert_eq!(values, vec!["apple", "banana", "Cherry"]);
+
+        let binary = SortCollation::binary(None).comparator();
+        values.sort_by(|a, b| binary.compare_text(a, b));
+        assert_eq!(values, vec!["Cherry", "apple", "banana"]);
+    }
+
+    #[test]
+    fn test_cells_nulls_first_and_numeric() {
+        let comparator = SortCollation::binary(None).comparator();
+        assert_eq!(comparator.compare_cells(None, Some("1"), FieldType::Integer), Ordering::Less);
+        assert_eq!(comparator.compare_cells(Some("10"), Some("9"), FieldType::Integer), Ordering::Greater);
+        assert_eq!(comparator.compare_cells(Some("10"), Some("9"), FieldType::Text), Ordering::Less);
+    }
+}
@@ -1,5 +1,5 @@
 use gpui::prelude::*;
-use gpui::{actions, div, px, AnyElement, App, AsyncApp, ClickEvent, Context, Corner, Entity, FocusHandle, Focusable, IntoElement, ParentElement, SharedString, Styled, Subscription, Window};
+use gpui::{actions, div, px, AnyElement, App, AsyncApp, ClickEvent, ClipboardItem, Context, Corner, Entity, FocusHandle, Focusable, IntoElement, ParentElement, SharedString, Styled, Subscription, Window};
 use tracing::log::trace;
 use gpui_component::{
     button::Button,
@@ -9,15 +9,16 @@ use gpui_component::{
     ActiveTheme as _, IconName, Sizable as _, Size, WindowExt,
 };
 
+use crate::table_data::anonymize_dialog::open_anonymize_dialog;
 use crate::table_data::multi_text_editor::create_multi_text_editor_with_content;
 use crate::table_data::results_delegate::{EditorTableDelegate, RowChange};
 use crate::sql_editor::SqlEditor;
 use crate::table_data::filter_editor::{ColumnSchema, FilterEditorEvent, TableFilterEditor, TableSchema};
-use db::{ExecOptions, GlobalDbState, SqlResult, TableCellChange, TableRowChange, TableSaveRequest, TableDataRequest};
+use db::{ClipboardFormat, DataFormat, ExecOptions, GlobalDbState, GridExportData, GridExportScope, GridExportTarget, SqlResult, StatementTimeout, TableCellChange, TableRowChange, TableSaveRequest, TableDataRequest};
 use gpui_component::dialog::DialogButtonProps;
-use gpui_component::menu::DropdownMenu;
+use gpui_component::menu::{DropdownMenu, PopupMenuItem};
 
-actions!(data_grid, [Page500, Page1000, Page2000, Page10000, Page100000]);
+actions!(data_grid, [Page500, Page1000, Page2000, Page10000, Page100000, ExportVisibleCsv, ExportVisibleJson, ExportRawCsv, ExportRawJson, CopyVisibleCsv, CopyVisibleJson, CopyVisibleMarkdown, CopyVisibleInsert, ExportAnonymizedSample]);
 
 /// 数据表格使用场景
 #[derive(Clone, Debug, PartialEq)]
@@ -191,6 +192,33 @@ impl DataGrid {
         &self.table
     }
 
+    pub fn config(&self) -> &DataGridConfig {
+        &self.config
+    }
+
+    /// 当前表的列名和类型，数据尚未加载时为空
+    pub fn column_types(&self, cx: &App) -> Vec<(String, String)> {
+        self.filter_editor
+            .read(cx)
+            .schema()
+            .map(|schema| {
+                schema
+                    .columns
+                    .iter()
+                    .map(|col| (col.name.clone(), col.data_type.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 替换 WHERE 条件并从第一页重新加载
+    pub fn apply_where_clause(&self, clause: String, window: &mut Window, cx: &mut App) {
+        self.filter_editor.update(cx, |editor, cx| {
+            editor.set_where_clause(clause, window, cx);
+        });
+        self.load_data_with_clauses(1, cx);
+    }
+
     pub fn update_data(
         &self,
         columns: Vec<Column>,
@@ -401,6 +429,43 @@ impl DataGrid {
         self.handle_page_size_change(100000, cx)
     }
 
+    fn handle_export_visible_csv(&mut self, _: &ExportVisibleCsv, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::Visible, GridExportTarget::File(DataFormat::Csv), cx);
+    }
+
+    fn handle_export_visible_json(&mut self, _: &ExportVisibleJson, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::Visible, GridExportTarget::File(DataFormat::Json), cx);
+    }
+
+    fn handle_export_raw_csv(&mut self, _: &ExportRawCsv, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::RawQuery, GridExportTarget::File(DataFormat::Csv), cx);
+    }
+
+    fn handle_export_raw_json(&mut self, _: &ExportRawJson, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::RawQuery, GridExportTarget::File(DataFormat::Json), cx);
+    }
+
+    fn handle_copy_visible_csv(&mut self, _: &CopyVisibleCsv, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::Visible, GridExportTarget::Clipboard(ClipboardFormat::Csv), cx);
+    }
+
+    fn handle_copy_visible_json(&mut self, _: &CopyVisibleJson, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::Visible, GridExportTarget::Clipboard(ClipboardFormat::Json), cx);
+    }
+
+    fn handle_copy_visible_markdown(&mut self, _: &CopyVisibleMarkdown, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::Visible, GridExportTarget::Clipboard(ClipboardFormat::Markdown), cx);
+    }
+
+    fn handle_copy_visible_insert(&mut self, _: &CopyVisibleInsert, _: &mut Window, cx: &mut Context<Self>) {
+        self.export_grid(GridExportScope::Visible, GridExportTarget::Clipboard(ClipboardFormat::Insert), cx);
+    }
+
+    fn handle_export_anonymized_sample(&mut self, _: &ExportAnonymizedSample, window: &mut Window, cx: &mut Context<Self>) {
+        let data = self.visible_export_data(cx);
+        open_anonymize_dialog(data, self.config.table_name.clone(), window, cx);
+    }
+
     fn handle_add_row(&mut self, _: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
         self.table.update(cx, |state, cx| state.add_row(window, cx));
     }
@@ -454,17 +519,18 @@ impl DataGrid {
         let Some(actual_row_ix) = delegate.resolve_display_row(row_ix) else {
             return;
         };
+        // 第 0 列是行号，隐藏列之后的列号也要换算回原始列
+        let actual_col_ix = delegate.column_index(col_ix.saturating_sub(1));
         let current_content = delegate
             .rows
             .get(actual_row_ix)
-            .and_then(|r| r.get(col_ix - 1))
+            .and_then(|r| r.get(actual_col_ix))
             .cloned()
             .unwrap_or(None);
 
-        let column_name = self.table.read(cx)
-            .delegate()
+        let column_name = delegate
             .columns
-            .get(col_ix.saturating_sub(1))
+            .get(actual_col_ix)
             .map(|col| col.name.to_string())
             .unwrap_or_else(|| format!("列 {}", col_ix));
         let title = format!("编辑单元格 - {} (行 {})", column_name, row_ix + 1);
@@ -515,7 +581,7 @@ impl DataGrid {
                                     return false;
                                 };
 
-                                let col_index = col_ix.saturating_sub(1);
+                                let col_index = delegate.column_index(col_ix.saturating_sub(1));
                                 let changed = delegate.record_cell_change(actual_row_ix, col_index, val);
 
                                 if changed {
@@ -701,6 +767,9 @@ impl DataGrid {
                 stop_on_error: true,
                 transactional: true,
                 max_rows: None,
+                source: None,
+                timeout: StatementTimeout::ConnectionDefault,
+                session_id: None,
             };
 
             let result = global_state
@@ -861,6 +930,9 @@ impl DataGrid {
             stop_on_error: true,
             transactional: true,
             max_rows: None,
+            source: None,
+            timeout: StatementTimeout::ConnectionDefault,
+            session_id: None,
         };
 
         let result = global_state
@@ -949,6 +1021,111 @@ impl DataGrid {
         }
     }
 
+    // ========== 导出 ==========
+
+    /// 当前表格所见即所得的数据（应用筛选后的行、界面显示的值）
+    pub fn visible_export_data(&self, cx: &App) -> GridExportData {
+        let delegate = self.table.read(cx).delegate();
+        GridExportData::new(delegate.column_names(), delegate.visible_rows())
+            .select_columns(&delegate.visible_column_indices())
+    }
+
+    /// 表格背后的原始查询语句
+    fn underlying_sql(&self, cx: &App) -> Option<String> {
+        match self.config.usage {
+            DataGridUsage::TableData => {
+                let sql = self.table_data_info.read(cx).current_sql.clone();
+                if sql.trim().is_empty() { None } else { Some(sql) }
+            }
+            DataGridUsage::SqlResult => self.config.sql.clone(),
+        }
+    }
+
+    fn export_grid(&self, scope: GridExportScope, target: GridExportTarget, cx: &mut App) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.config.connection_id.clone();
+        let database_name = self.config.database_name.clone();
+        let table_name = self.config.table_name.clone();
+        let plugin = global_state.db_manager.get_plugin(&self.config.database_type).ok();
+        let visible_data = self.visible_export_data(cx);
+        let raw_sql = self.underlying_sql(cx);
+        // 复制到剪贴板时不弹保存对话框
+        let path_future = match target {
+            GridExportTarget::File(format) => {
+                let file_name = format!("{}.{}", table_name, format.extension());
+                let directory = dirs::download_dir()
+                    .or_else(dirs::home_dir)
+                    .unwrap_or_default();
+                Some(cx.prompt_for_new_path(&directory, Some(&file_name)))
+            }
+            GridExportTarget::Clipboard(_) => None,
+        };
+
+        cx.spawn(async move |cx: &mut AsyncApp| {
+            let path = match path_future {
+                Some(path_future) => match path_future.await {
+                    Ok(Ok(Some(path))) => Some(path),
+                    _ => return,
+                },
+                None => None,
+            };
+
+            let data = match scope {
+                GridExportScope::Visible => visible_data,
+                GridExportScope::RawQuery => {
+                    let Some(sql) = raw_sql else {
+                        cx.update(|cx| notification(cx, "没有可导出的查询".to_string())).ok();
+                        return;
+                    };
+                    let result = global_state
+                        .execute_script(cx, connection_id, sql, Some(database_name), None)
+                        .await;
+                    match result {
+                        Ok(results) => results
+                            .into_iter()
+                            .find_map(|result| match result {
+                                SqlResult::Query(query_result) => Some(GridExportData::new(
+                                    query_result.columns,
+                                    query_result.rows,
+                                )),
+                                _ => None,
+                            })
+                            .unwrap_or_default(),
+                        Err(err) => {
+                            cx.update(|cx| notification(cx, format!("Failed to execute SQL: {}", err))).ok();
+                            return;
+                        }
+                    }
+                }
+            };
+
+            let rendered = match target {
+                GridExportTarget::File(format) => data.render(format).map_err(|err| err.to_string()),
+                GridExportTarget::Clipboard(format) => match &plugin {
+                    Some(plugin) => data
+                        .render_clipboard(format, plugin.as_ref(), &table_name)
+                        .map_err(|err| err.to_string()),
+                    None => Err("无法获取数据库插件".to_string()),
+                },
+            };
+            let rows = data.rows.len();
+            cx.update(|cx| {
+                let message = match (rendered, path) {
+                    (Ok(content), Some(path)) => match std::fs::write(&path, content) {
+                        Ok(()) => format!("已导出 {} 行到 {}", rows, path.display()),
+                        Err(err) => format!("导出失败: {}", err),
+                    },
+                    (Ok(content), None) => {
+                        cx.write_to_clipboard(ClipboardItem::new_string(content));
+                        format!("已复制 {} 行到剪贴板", rows)
+                    }
+                    (Err(err), _) => format!("导出失败: {}", err),
+                };
+                notification(cx, message);
+            }).ok();
+        }).detach();
+    }
+
     // ========== 渲染辅助方法 ==========
 
     pub fn render_toolbar(&self, _window: &mut Window, cx: &Context<Self>) -> AnyElement {
@@ -1015,6 +1192,54 @@ impl DataGrid {
                 )
             })
             .child(div().flex_1())
+            .child({
+                let table = self.table.clone();
+                let delegate = self.table.read(cx).delegate();
+                let columns: Vec<(usize, SharedString, bool)> = delegate
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(|(col_ix, col)| (col_ix, col.name.clone(), !delegate.is_column_hidden(col_ix)))
+                    .collect();
+
+                Button::new("toggle-columns")
+                    .with_size(Size::Medium)
+                    .icon(IconName::FolderColumns)
+                    .tooltip("显示/隐藏列")
+                    .dropdown_menu_with_anchor(Corner::TopRight, move |mut menu, _, _| {
+                        for (col_ix, name, visible) in columns.iter().cloned() {
+                            let table = table.clone();
+                            menu = menu.item(PopupMenuItem::new(name).checked(visible).on_click(move |_, _, cx| {
+                                table.update(cx, |state, cx| {
+                                    state.delegate_mut().set_column_hidden(col_ix, visible);
+                                    state.refresh(cx);
+                                    cx.notify();
+                                });
+                            }));
+                        }
+                        menu.scrollable(true)
+                    })
+            })
+            .child(
+                Button::new("export-grid")
+                    .with_size(Size::Medium)
+                    .icon(IconName::ArrowDown)
+                    .tooltip("导出")
+                    .dropdown_menu_with_anchor(Corner::TopRight, move |menu, _, _| {
+                        menu.menu("导出可见数据为 CSV", Box::new(ExportVisibleCsv))
+                            .menu("导出可见数据为 JSON", Box::new(ExportVisibleJson))
+                            .separator()
+                            .menu("导出原始查询为 CSV", Box::new(ExportRawCsv))
+                            .menu("导出原始查询为 JSON", Box::new(ExportRawJson))
+                            .separator()
+                            .menu("复制可见数据为 CSV", Box::new(CopyVisibleCsv))
+                            .menu("复制可见数据为 JSON", Box::new(CopyVisibleJson))
+                            .menu("复制可见数据为 Markdown", Box::new(CopyVisibleMarkdown))
+                            .menu("复制可见数据为 INSERT", Box::new(CopyVisibleInsert))
+                            .separator()
+                            .menu("导出脱敏样本...", Box::new(ExportAnonymizedSample))
+                    }),
+            )
             .child(
                 Button::new("toggle-editor")
                     .with_size(Size::Medium)
@@ -1161,6 +1386,15 @@ impl Render for DataGrid {
                     .on_action(cx.listener(Self::handle_page_change_10000))
                     .on_action(cx.listener(Self::handle_page_change_100000))
             })
+            .on_action(cx.listener(Self::handle_export_visible_csv))
+            .on_action(cx.listener(Self::handle_export_visible_json))
+            .on_action(cx.listener(Self::handle_export_raw_csv))
+            .on_action(cx.listener(Self::handle_export_raw_json))
+            .on_action(cx.listener(Self::handle_copy_visible_csv))
+            .on_action(cx.listener(Self::handle_copy_visible_json))
+            .on_action(cx.listener(Self::handle_copy_visible_markdown))
+            .on_action(cx.listener(Self::handle_copy_visible_insert))
+            .on_action(cx.listener(Self::handle_export_anonymized_sample))
             .size_full()
             .gap_0()
             .child(self.render_toolbar(window, cx))
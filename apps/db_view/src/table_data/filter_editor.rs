@@ -592,6 +592,7 @@ pub fn create_simple_editor(
 pub struct TableFilterEditor {
     where_editor: Entity<SimpleCodeEditor>,
     order_by_editor: Entity<SimpleCodeEditor>,
+    schema: Option<TableSchema>,
     _subs: Vec<Subscription>,
 }
 
@@ -621,6 +622,7 @@ impl TableFilterEditor {
         Self {
             where_editor,
             order_by_editor,
+            schema: None,
             _subs: vec![where_sub, order_by_sub],
         }
     }
@@ -633,9 +635,22 @@ impl TableFilterEditor {
         self.order_by_editor.read(cx).get_text_from_app(cx)
     }
 
+    pub fn set_where_clause(&self, clause: String, window: &mut Window, cx: &mut App) {
+        let editor = self.where_editor.read(cx).editor.clone();
+        editor.update(cx, |state, cx| {
+            state.set_value(clause, window, cx);
+        });
+    }
+
+    /// 最近一次加载数据时的列信息
+    pub fn schema(&self) -> Option<&TableSchema> {
+        self.schema.as_ref()
+    }
+
 
     pub fn set_schema(&mut self, schema: TableSchema, cx: &mut Context<Self>) {
         let schema_clone = schema.clone();
+        self.schema = Some(schema.clone());
         
         self.where_editor.update(cx, |editor, cx| {
             editor.editor.update(cx, |input_state, _cx| {
@@ -1,3 +1,5 @@
+pub mod anonymize_dialog;
+pub mod collation;
 pub mod data_grid;
 pub mod filter_editor;
 pub mod multi_text_editor;
@@ -1,12 +1,14 @@
 use std::collections::{HashMap, HashSet};
 
 use db::{FieldType, TableColumnMeta};
-use gpui::{div, px, App, AppContext, Context, Entity, IntoElement, ParentElement as _, Styled, Subscription, Window};
+use gpui::{div, px, App, AppContext, Context, Entity, IntoElement, ParentElement as _, SharedString, Styled, Subscription, Window};
 use gpui_component::input::{InputEvent, InputState};
-use gpui_component::table::Column;
+use gpui_component::table::{Column, ColumnSort};
 use gpui_component::{h_flex, table::{ TableDelegate, TableState}, ActiveTheme};
 use gpui_component::table::filter_panel::FilterValue;
 
+use crate::table_data::collation::SortCollation;
+
 /// Represents a single cell change with old and new values
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CellChange {
@@ -85,8 +87,14 @@ pub struct EditorTableDelegate {
     filtered_row_indices: Option<Vec<usize>>,
     /// Column filter conditions: col_ix -> selected values
     column_filters: HashMap<usize, HashSet<String>>,
+    /// Client-side sort column and direction
+    sort: Option<(usize, ColumnSort)>,
+    /// Whether text columns sort by their collation instead of code point order
+    locale_sort: bool,
     /// Whether cells are editable
     editable: bool,
+    /// Columns hidden by the user (indices into `columns`); the table only sees the remaining ones
+    hidden_columns: HashSet<usize>,
 }
 
 impl Clone for EditorTableDelegate {
@@ -108,7 +116,10 @@ impl Clone for EditorTableDelegate {
             active_filter_columns: self.active_filter_columns.clone(),
             filtered_row_indices: self.filtered_row_indices.clone(),
             column_filters: self.column_filters.clone(),
+            sort: self.sort,
+            locale_sort: self.locale_sort,
             editable: self.editable,
+            hidden_columns: self.hidden_columns.clone(),
         }
     }
 }
@@ -134,7 +145,10 @@ impl EditorTableDelegate {
             active_filter_columns: HashSet::new(),
             filtered_row_indices: None,
             column_filters: HashMap::new(),
+            sort: None,
+            locale_sort: true,
             editable,
+            hidden_columns: HashSet::new(),
         }
     }
 
@@ -227,6 +241,13 @@ impl EditorTableDelegate {
     }
 
     pub fn update_data(&mut self, columns: Vec<Column>, rows: Vec<Vec<Option<String>>>, _cx: &mut App) {
+        // Paging reloads the same columns and keeps them hidden; a different result set starts over
+        let same_columns = self.columns.len() == columns.len()
+            && self.columns.iter().zip(&columns).all(|(old, new)| old.name == new.name);
+        if !same_columns {
+            self.hidden_columns.clear();
+        }
+
         // Calculate column widths based on content
         let mut col_widths: Vec<usize> = columns.iter().map(|c| c.name.len()).collect();
 
@@ -258,6 +279,7 @@ impl EditorTableDelegate {
         self.original_rows = rows.clone();
         self.rows = rows.clone();
         self.row_index_map = (0..row_count).map(|i| (i, i)).collect();
+        self.sort = None;
 
         // Clear all change tracking
         self.clear_changes();
@@ -432,6 +454,45 @@ impl EditorTableDelegate {
 
 
 
+    /// Indices (into `columns`) of the columns shown in the table, in display order
+    pub fn visible_column_indices(&self) -> Vec<usize> {
+        (0..self.columns.len()).filter(|col_ix| !self.hidden_columns.contains(col_ix)).collect()
+    }
+
+    pub fn is_column_hidden(&self, col_ix: usize) -> bool {
+        self.hidden_columns.contains(&col_ix)
+    }
+
+    /// Hide or show a column; the last visible column cannot be hidden.
+    /// The caller must refresh the `TableState` so it rebuilds its column groups.
+    pub fn set_column_hidden(&mut self, col_ix: usize, hidden: bool) {
+        if !hidden {
+            self.hidden_columns.remove(&col_ix);
+        } else if col_ix < self.columns.len() && self.hidden_columns.len() + 1 < self.columns.len() {
+            self.hidden_columns.insert(col_ix);
+        }
+    }
+
+    /// Map a column index as seen by the table to the index in `columns`
+    pub fn column_index(&self, display_col_ix: usize) -> usize {
+        if self.hidden_columns.is_empty() {
+            return display_col_ix;
+        }
+        (0..self.columns.len())
+            .filter(|col_ix| !self.hidden_columns.contains(col_ix))
+            .nth(display_col_ix)
+            .unwrap_or(display_col_ix)
+    }
+
+    /// 按当前显示顺序返回可见行（应用列筛选后），NULL 保留为 None，由导出格式决定写法
+    pub fn visible_rows(&self) -> Vec<Vec<Option<String>>> {
+        (0..self.filtered_row_count())
+            .filter_map(|display_ix| self.resolve_display_row(display_ix))
+            .filter_map(|row_ix| self.rows.get(row_ix))
+            .cloned()
+            .collect()
+    }
+
     // ============================================================================
     // Column Filter Methods (to be called from external code)
     // ============================================================================
@@ -455,21 +516,50 @@ impl EditorTableDelegate {
         self.recalculate_filtered_indices();
     }
 
-    /// 清除所有筛选
+    /// 清除所有筛选（保留排序）
     pub fn clear_all_filters(&mut self) {
         self.column_filters.clear();
         self.active_filter_columns.clear();
-        self.filtered_row_indices = None;
+        self.recalculate_filtered_indices();
     }
 
-    /// 重新计算筛选后的行索引（多列 AND 组合）
+    /// 列的客户端排序规则，关闭按排序规则排序时按码点比较
+    pub fn sort_collation(&self, col_ix: usize) -> SortCollation {
+        let name = self.column_meta.get(col_ix).and_then(|m| m.collation.clone());
+        match name {
+            Some(name) if self.locale_sort => SortCollation::from_name(&name),
+            name => SortCollation::binary(name),
+        }
+    }
+
+    fn sort_indices(&self, indices: &mut [usize], col_ix: usize, direction: ColumnSort) {
+        let comparator = self.sort_collation(col_ix).comparator();
+        let field_type = self.get_field_type(col_ix);
+        let value = |row_ix: usize| {
+            self.rows
+                .get(row_ix)
+                .and_then(|row| row.get(col_ix))
+                .and_then(|v| v.as_deref())
+        };
+        indices.sort_by(|&a, &b| {
+            let ordering = comparator.compare_cells(value(a), value(b), field_type);
+            if direction == ColumnSort::Descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    /// 重新计算筛选后的行索引（多列 AND 组合），有排序时按排序后的顺序显示
     fn recalculate_filtered_indices(&mut self) {
-        if self.column_filters.is_empty() {
+        let sort = self.sort.filter(|(_, direction)| *direction != ColumnSort::Default);
+        if self.column_filters.is_empty() && sort.is_none() {
             self.filtered_row_indices = None;
             return;
         }
 
-        let filtered_indices: Vec<usize> = self.rows
+        let mut filtered_indices: Vec<usize> = self.rows
             .iter()
             .enumerate()
             .filter(|(_, row)| {
@@ -485,6 +575,12 @@ impl EditorTableDelegate {
             .map(|(ix, _)| ix)
             .collect();
 
+        if let Some((col_ix, direction)) = sort {
+            self.sort_indices(&mut filtered_indices, col_ix, direction);
+            self.filtered_row_indices = Some(filtered_indices);
+            return;
+        }
+
         // 如果筛选后的行数等于总行数，说明没有实际筛选效果
         if filtered_indices.len() == self.rows.len() {
             self.filtered_row_indices = None;
@@ -500,7 +596,7 @@ impl TableDelegate for EditorTableDelegate {
         true
     }
     fn columns_count(&self, _cx: &App) -> usize {
-        self.columns.len()
+        self.columns.len() - self.hidden_columns.len()
     }
 
     fn rows_count(&self, _cx: &App) -> usize {
@@ -509,10 +605,23 @@ impl TableDelegate for EditorTableDelegate {
     }
 
     fn column(&self, col_ix: usize, _cx: &App) -> Column {
-        self.columns[col_ix].clone()
+        self.columns[self.column_index(col_ix)].clone()
+    }
+
+    fn perform_sort(
+        &mut self,
+        col_ix: usize,
+        sort: ColumnSort,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) {
+        let col_ix = self.column_index(col_ix);
+        self.sort = (sort != ColumnSort::Default).then_some((col_ix, sort));
+        self.recalculate_filtered_indices();
     }
 
     fn render_th(&mut self, col_ix: usize, _window: &mut Window, _: &mut Context<TableState<Self>>) -> impl IntoElement {
+        let col_ix = self.column_index(col_ix);
         let col_name = self
             .columns
             .get(col_ix)
@@ -542,6 +651,7 @@ impl TableDelegate for EditorTableDelegate {
     ) -> impl IntoElement {
         // Map display row index to actual row index
         let actual_row = self.map_display_to_actual_row(row);
+        let col = self.column_index(col);
 
         let value = self.rows
             .get(actual_row)
@@ -568,6 +678,7 @@ impl TableDelegate for EditorTableDelegate {
 
         // Map display row index to actual row index
         let actual_row = self.map_display_to_actual_row(row_ix);
+        let col_ix = self.column_index(col_ix);
 
         if self.is_deleted_row(actual_row) {
             return None;
@@ -605,15 +716,16 @@ impl TableDelegate for EditorTableDelegate {
         col_ix: usize,
         new_value: String,
         _window: &mut Window,
-        cx: &mut Context<TableState<Self>>,
+        _cx: &mut Context<TableState<Self>>,
     ) -> bool {
         // Map display row index to actual row index
         let actual_row = self.map_display_to_actual_row(row_ix);
+        let col_ix = self.column_index(col_ix);
         // 空字符串转换为 None (NULL)
         let new_opt_value: Option<String> = if new_value.is_empty() { None } else { Some(new_value) };
 
         // Check if cell is already modified
-        if self.is_cell_modified(row_ix, col_ix, cx) {
+        if self.modified_cells.contains(&(actual_row, col_ix)) {
             // Check if user reverted to original value
             if let Some(row) = self.original_rows.get(actual_row) {
                 if let Some(original_cell) = row.get(col_ix) {
@@ -713,7 +825,7 @@ impl TableDelegate for EditorTableDelegate {
     fn is_cell_modified(&self, row_ix: usize, col_ix: usize, _cx: &App) -> bool {
         // Map display row index to actual row index
         let actual_row = self.map_display_to_actual_row(row_ix);
-        self.modified_cells.contains(&(actual_row, col_ix))
+        self.modified_cells.contains(&(actual_row, self.column_index(col_ix)))
     }
 
     fn is_row_deleted(&self, row_ix: usize, _cx: &App) -> bool {
@@ -733,6 +845,10 @@ impl TableDelegate for EditorTableDelegate {
         self.next_new_row_id += 1;
         self.new_rows.insert(new_row_id, new_row);
         self.row_status.insert(row_ix, RowStatus::New);
+        // 筛选或排序时新行追加在末尾显示
+        if let Some(indices) = self.filtered_row_indices.as_mut() {
+            indices.push(row_ix);
+        }
 
         // Map the new row index to the new_row_id (using high number as marker)
         self.row_index_map.insert(row_ix, new_row_id);
@@ -789,6 +905,7 @@ impl TableDelegate for EditorTableDelegate {
     fn get_column_filter_values(&self, col_ix: usize, _cx: &App) -> Vec<FilterValue> {
         use std::collections::HashMap;
 
+        let col_ix = self.column_index(col_ix);
         let mut value_counts: HashMap<String, usize> = HashMap::new();
 
         // 获取其他列的筛选条件（排除当前列）
@@ -826,7 +943,7 @@ impl TableDelegate for EditorTableDelegate {
     }
 
     fn is_column_filtered(&self, col_ix: usize, _cx: &App) -> bool {
-        self.active_filter_columns.contains(&col_ix)
+        self.active_filter_columns.contains(&self.column_index(col_ix))
     }
 
     fn on_column_filter_changed(
@@ -836,7 +953,7 @@ impl TableDelegate for EditorTableDelegate {
         _window: &mut Window,
         _cx: &mut Context<TableState<Self>>,
     ) {
-        self.apply_filter(col_ix, selected_values);
+        self.apply_filter(self.column_index(col_ix), selected_values);
     }
 
     fn on_column_filter_cleared(
@@ -845,7 +962,29 @@ impl TableDelegate for EditorTableDelegate {
         _window: &mut Window,
         _cx: &mut Context<TableState<Self>>,
     ) {
-        self.clear_column_filter(col_ix);
+        self.clear_column_filter(self.column_index(col_ix));
+    }
+
+    fn column_sort_collation(&self, col_ix: usize, _cx: &App) -> Option<SharedString> {
+        let col_ix = self.column_index(col_ix);
+        if matches!(self.get_field_type(col_ix), FieldType::Integer | FieldType::Decimal) {
+            return None;
+        }
+        Some(self.sort_collation(col_ix).label().into())
+    }
+
+    fn locale_sort_enabled(&self, _cx: &App) -> Option<bool> {
+        Some(self.locale_sort)
+    }
+
+    fn set_locale_sort_enabled(
+        &mut self,
+        enabled: bool,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) {
+        self.locale_sort = enabled;
+        self.recalculate_filtered_indices();
     }
 }
 
@@ -1,14 +1,148 @@
 use std::any::Any;
-use gpui::{AnyElement, App, AppContext, Entity, IntoElement, SharedString, Window};
-use gpui_component::{Icon, IconName};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, Hsla, IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Window};
+use gpui_component::{
+    dialog::DialogButtonProps,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Icon, IconName, Sizable, WindowExt,
+};
 
+use crate::connection_tag::ConnectionTags;
+use crate::ai_sql::{translate_filter, NaturalFilterRequest};
 use crate::table_data::data_grid::{DataGrid, DataGridConfig};
 use one_core::tab_container::{TabContent, TabContentType};
 
 pub struct TableDataTabContent {
     pub data_grid: Entity<DataGrid>,
+    natural_filter: Entity<NaturalFilterBar>,
     database_name: String,
     table_name: String,
+    connection_id: String,
+}
+
+/// 自然语言筛选：输入描述后由模型翻译为当前表的 WHERE 条件，确认后应用到表格
+pub struct NaturalFilterBar {
+    data_grid: Entity<DataGrid>,
+    input: Entity<InputState>,
+    translating: bool,
+    _input_sub: Subscription,
+}
+
+impl NaturalFilterBar {
+    fn new(data_grid: Entity<DataGrid>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder("用自然语言描述筛选条件，例如“今年创建的活跃用户”，回车生成 WHERE 条件")
+        });
+        let input_sub = cx.subscribe_in(&input, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.translate(window, cx);
+            }
+        });
+
+        Self {
+            data_grid,
+            input,
+            translating: false,
+            _input_sub: input_sub,
+        }
+    }
+
+    fn translate(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.input.read(cx).text().to_string().trim().to_string();
+        if text.is_empty() || self.translating {
+            return;
+        }
+
+        let grid = self.data_grid.read(cx);
+        let columns = grid.column_types(cx);
+        if columns.is_empty() {
+            window.push_notification("表数据尚未加载，无法获取列信息", cx);
+            return;
+        }
+        let request = NaturalFilterRequest {
+            database_type: grid.config().database_type,
+            table: grid.config().table_name.clone(),
+            columns,
+            text,
+        };
+
+        self.translating = true;
+        cx.notify();
+
+        let data_grid = self.data_grid.clone();
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = translate_filter(request, cx).await;
+            let _ = this.update(cx, |bar, cx| {
+                bar.translating = false;
+                cx.notify();
+            });
+            let _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(condition) => Self::confirm_condition(condition, data_grid, window, cx),
+                Err(e) => window.push_notification(Notification::error(format!("生成筛选条件失败: {}", e)), cx),
+            });
+        }).detach();
+    }
+
+    /// 展示生成的条件，可修改后应用
+    fn confirm_condition(condition: String, data_grid: Entity<DataGrid>, window: &mut Window, cx: &mut App) {
+        let condition_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).multi_line(true);
+            state.set_value(condition, window, cx);
+            state
+        });
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let condition_input = condition_input.clone();
+            let data_grid = data_grid.clone();
+
+            dialog
+                .title("应用筛选条件")
+                .w(px(560.))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("将替换当前的 WHERE 条件，确认无误后应用"),
+                        )
+                        .child(Input::new(&condition_input).h(px(120.))),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("应用"))
+                .on_ok(move |_, window, cx| {
+                    let condition = condition_input.read(cx).text().to_string().trim().to_string();
+                    data_grid.update(cx, |grid, cx| {
+                        grid.apply_where_clause(condition, window, cx);
+                    });
+                    true
+                })
+        });
+    }
+}
+
+impl Render for NaturalFilterBar {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .px_2()
+            .pt_1()
+            .gap_2()
+            .items_center()
+            .child(
+                Icon::new(IconName::Bot)
+                    .small()
+                    .text_color(cx.theme().primary),
+            )
+            .child(div().flex_1().child(Input::new(&self.input).small().cleanable(true)))
+            .when(self.translating, |this| {
+                this.child(div().text_xs().text_color(cx.theme().muted_foreground).child("生成中..."))
+            })
+    }
 }
 
 impl TableDataTabContent {
@@ -21,13 +155,15 @@ impl TableDataTabContent {
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
+        let connection_id = connection_id.into();
         let mut config = DataGridConfig::new(
             database_name.clone(),
             table_name.clone(),
-            connection_id,
+            connection_id.clone(),
             database_type,
         )
-        .editable(true)
+        // Saving grid edits generates SQL, which a MongoDB connection cannot run
+        .editable(database_type != one_core::storage::DatabaseType::MongoDB)
         .show_toolbar(true);
 
         if let Some(schema) = schema_name {
@@ -35,11 +171,14 @@ impl TableDataTabContent {
         }
 
         let data_grid = cx.new(|cx| DataGrid::new(config, window, cx));
+        let natural_filter = cx.new(|cx| NaturalFilterBar::new(data_grid.clone(), window, cx));
 
         Self {
             data_grid,
+            natural_filter,
             database_name,
             table_name,
+            connection_id,
         }
     }
 }
@@ -58,7 +197,15 @@ impl TabContent for TableDataTabContent {
     }
 
     fn render_content(&self, _: &mut Window, _: &mut App) -> AnyElement {
-        self.data_grid.clone().into_any_element()
+        v_flex()
+            .size_full()
+            .child(self.natural_filter.clone())
+            .child(div().flex_1().min_h(px(0.)).child(self.data_grid.clone()))
+            .into_any_element()
+    }
+
+    fn accent_color(&self, cx: &App) -> Option<Hsla> {
+        ConnectionTags::hsla(&self.connection_id, cx)
     }
 
     fn content_type(&self) -> TabContentType {
@@ -74,8 +221,10 @@ impl Clone for TableDataTabContent {
     fn clone(&self) -> Self {
         Self {
             data_grid: self.data_grid.clone(),
+            natural_filter: self.natural_filter.clone(),
             database_name: self.database_name.clone(),
             table_name: self.table_name.clone(),
+            connection_id: self.connection_id.clone(),
         }
     }
 }
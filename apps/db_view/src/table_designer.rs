@@ -3,7 +3,7 @@ use std::ops::Range;
 
 use gpui::prelude::*;
 use gpui::{
-    div, px, uniform_list, AnyElement, App, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
+    div, px, uniform_list, AnyElement, App, AsyncApp, ClipboardItem, Context, Entity, EventEmitter, FocusHandle,
     Focusable, IntoElement, InteractiveElement, ListSizingBehavior, MouseButton, ParentElement,
     Render, SharedString, StatefulInteractiveElement, Styled, Subscription,
     UniformListScrollHandle, Window,
@@ -24,8 +24,15 @@ use db::types::{
     CharsetInfo, CollationInfo, ColumnDefinition, ColumnInfo, DataTypeInfo, IndexDefinition,
     IndexInfo, TableDesign, TableOptions,
 };
-use db::GlobalDbState;
+use db::{GlobalDbState, SqlResult};
+use gpui_component::dialog::DialogButtonProps;
+use crate::ai_sql::{
+    describe_design_changes, review_alter_sql, summarize_design_changes, AlterReview, AlterReviewRequest, AlterRisk,
+    ChangeSummaryRequest,
+};
+use crate::ai_table_design::{suggest_columns, ColumnSuggestionRequest, SuggestedColumn};
 use crate::database_view_plugin::DatabaseViewPluginRegistry;
+use one_core::storage::usage_metrics::{record_error, record_feature};
 use one_core::storage::DatabaseType;
 use one_core::tab_container::{TabContent, TabContentType};
 
@@ -221,6 +228,8 @@ impl TableDesigner {
 
         if designer.config.table_name.is_some() {
             designer.load_table_structure(window, cx);
+        } else {
+            designer.load_extension_types(cx);
         }
 
         designer
@@ -320,6 +329,37 @@ impl TableDesigner {
         cx.notify();
     }
 
+    /// 新建表时加载数据库中已安装扩展提供的数据类型
+    fn load_extension_types(&mut self, cx: &mut Context<Self>) {
+        let supports_extensions = cx.global::<GlobalDbState>()
+            .get_plugin(&self.config.database_type)
+            .map(|p| p.supports_extensions())
+            .unwrap_or(false);
+        if !supports_extensions {
+            return;
+        }
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.config.connection_id.clone();
+        let database_name = self.config.database_name.clone();
+        let columns_editor = self.columns_editor.clone();
+
+        cx.spawn(async move |_this, cx: &mut AsyncApp| {
+            let Ok(extension_types) = global_state.list_extension_types(cx, connection_id, database_name).await else {
+                return;
+            };
+            let _ = cx.update(|cx| {
+                if let Some(window_id) = cx.active_window() {
+                    let _ = cx.update_window(window_id, |_entity, window, cx| {
+                        columns_editor.update(cx, |editor, cx| {
+                            editor.add_data_types(extension_types, window, cx);
+                        });
+                    });
+                }
+            });
+        }).detach();
+    }
+
     pub fn load_table_structure(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         let Some(table_name) = self.config.table_name.clone() else {
             return;
@@ -333,6 +373,13 @@ impl TableDesigner {
         let indexes_editor = self.indexes_editor.clone();
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
+            // 先取扩展类型，使已有列中的扩展类型能在下拉列表中选中
+            let extension_types = global_state.list_extension_types(
+                cx,
+                connection_id.clone(),
+                database_name.clone(),
+            ).await.unwrap_or_default();
+
             let columns_result = global_state.list_columns(
                 cx,
                 connection_id.clone(),
@@ -355,6 +402,10 @@ impl TableDesigner {
                         let columns = columns_result.ok();
                         let indexes = indexes_result.ok();
 
+                        columns_editor.update(cx, |editor, cx| {
+                            editor.add_data_types(extension_types, window, cx);
+                        });
+
                         if let Some(ref cols) = columns {
                             columns_editor.update(cx, |editor, cx| {
                                 editor.load_columns(cols.clone(), window, cx);
@@ -467,36 +518,106 @@ impl TableDesigner {
             return;
         }
 
+        if self.original_design.is_none() {
+            let Ok(plugin) = cx.global::<GlobalDbState>().db_manager.get_plugin(&self.config.database_type) else {
+                return;
+            };
+            self.run_script(plugin.build_create_table_sql(&design), "表创建成功", "创建表失败", cx);
+            return;
+        }
+
+        self.update_sql_preview(cx);
+        let sql = self.sql_preview_text.clone();
+        let has_statements = sql
+            .lines()
+            .map(str::trim)
+            .any(|line| !line.is_empty() && !line.starts_with("--"));
+        if !has_statements {
+            window.push_notification("没有需要保存的变更", cx);
+            return;
+        }
+        let changes = self
+            .original_design
+            .as_ref()
+            .map(|original| describe_design_changes(original, &design))
+            .unwrap_or_default();
+        self.confirm_alter(sql, changes, window, cx);
+    }
+
+    /// 修改已有表前展示 ALTER 脚本，可先让 AI 审查其中的破坏性和锁表操作，或把变更写成迁移提交信息
+    fn confirm_alter(&mut self, sql: String, changes: Vec<String>, window: &mut Window, cx: &mut Context<Self>) {
+        let summary_request = ChangeSummaryRequest {
+            database_type: self.config.database_type,
+            table: self.config.table_name.clone().unwrap_or_default(),
+            changes,
+        };
+        let request = AlterReviewRequest {
+            connection_id: self.config.connection_id.clone(),
+            database_type: self.config.database_type,
+            database: self.config.database_name.clone(),
+            schema: self.config.schema_name.clone(),
+            table: self.config.table_name.clone().unwrap_or_default(),
+            sql: sql.clone(),
+        };
+        let review = cx.new(|_| AlterScriptReview::new(request, summary_request));
+        let designer = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let designer = designer.clone();
+            let sql = sql.clone();
+
+            dialog
+                .title("保存表结构变更")
+                .width(px(720.))
+                .child(review.clone())
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("执行"))
+                .on_ok(move |_, _window, cx| {
+                    let sql = sql.clone();
+                    designer.update(cx, |designer, cx| {
+                        designer.run_script(sql, "表结构修改成功", "修改表结构失败", cx);
+                    });
+                    true
+                })
+        });
+    }
+
+    /// 执行建表或修改脚本；修改成功后重新加载表结构，使后续变更基于最新结构生成
+    fn run_script(&mut self, sql: String, success: &'static str, failure: &'static str, cx: &mut Context<Self>) {
         let global_state = cx.global::<GlobalDbState>().clone();
         let connection_id = self.config.connection_id.clone();
         let database_name = self.config.database_name.clone();
-        let database_type = self.config.database_type;
-
-        cx.spawn(async move |_this, cx: &mut AsyncApp| {
-            let sql = {
-                let plugin_result = cx.update(|cx: &mut App| {
-                    let global_state = cx.global::<GlobalDbState>().clone();
-                    global_state.db_manager.get_plugin(&database_type)
-                });
-                match plugin_result {
-                    Ok(Ok(plugin)) => plugin.build_create_table_sql(&design),
-                    _ => return,
-                }
-            };
+        let reload = self.original_design.is_some();
 
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
             let result = global_state
                 .execute_script(cx, connection_id, sql, Some(database_name), None)
-                .await;
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|results| {
+                    match results.into_iter().find_map(|r| match r {
+                        SqlResult::Error(err) => Some(err.message),
+                        _ => None,
+                    }) {
+                        Some(message) => Err(message),
+                        None => Ok(()),
+                    }
+                });
 
             let _ = cx.update(|cx: &mut App| {
                 if let Some(window_id) = cx.active_window() {
                     let _ = cx.update_window(window_id, |_, window, cx| {
+                        record_feature("table_designer.save", cx);
                         match &result {
                             Ok(_) => {
-                                window.push_notification("表创建成功", cx);
+                                window.push_notification(success, cx);
+                                if reload {
+                                    let _ = this.update(cx, |designer, cx| designer.load_table_structure(window, cx));
+                                }
                             }
                             Err(e) => {
-                                window.push_notification(format!("创建表失败: {}", e), cx);
+                                record_error("table_designer.save", cx);
+                                window.push_notification(format!("{}: {}", failure, e), cx);
                             }
                         }
                     });
@@ -679,6 +800,194 @@ impl Render for TableDesigner {
     }
 }
 
+// === Alter Script Review ===
+
+/// 保存确认框的内容：ALTER 脚本和可选的 AI 审查结果
+struct AlterScriptReview {
+    request: AlterReviewRequest,
+    reviewing: bool,
+    review: Option<AlterReview>,
+    summary_request: ChangeSummaryRequest,
+    summarizing: bool,
+    summary: Option<String>,
+    error: Option<String>,
+}
+
+impl AlterScriptReview {
+    fn new(request: AlterReviewRequest, summary_request: ChangeSummaryRequest) -> Self {
+        Self {
+            request,
+            reviewing: false,
+            review: None,
+            summary_request,
+            summarizing: false,
+            summary: None,
+            error: None,
+        }
+    }
+
+    fn summarize(&mut self, cx: &mut Context<Self>) {
+        if self.summarizing {
+            return;
+        }
+        self.summarizing = true;
+        self.error = None;
+        cx.notify();
+
+        let request = self.summary_request.clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = summarize_design_changes(request, cx).await;
+            let _ = this.update(cx, |view, cx| {
+                view.summarizing = false;
+                match result {
+                    Ok(summary) => view.summary = Some(summary),
+                    Err(e) => view.error = Some(format!("生成变更摘要失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn review(&mut self, cx: &mut Context<Self>) {
+        if self.reviewing {
+            return;
+        }
+        self.reviewing = true;
+        self.error = None;
+        cx.notify();
+
+        let request = self.request.clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = review_alter_sql(request, cx).await;
+            let _ = this.update(cx, |view, cx| {
+                view.reviewing = false;
+                match result {
+                    Ok(review) => view.review = Some(review),
+                    Err(e) => view.error = Some(format!("审查失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+}
+
+impl Render for AlterScriptReview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let risk_color = match self.review.as_ref().and_then(|r| r.risk) {
+            Some(AlterRisk::High) => cx.theme().danger,
+            Some(AlterRisk::Medium) => cx.theme().warning,
+            Some(AlterRisk::Low) => cx.theme().success,
+            None => cx.theme().foreground,
+        };
+
+        v_flex()
+            .gap_2()
+            .child(
+                div()
+                    .id("alter-script")
+                    .max_h(px(240.))
+                    .overflow_y_scroll()
+                    .p_3()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().muted)
+                    .text_sm()
+                    .font_family("monospace")
+                    .child(self.request.sql.clone()),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        Button::new("review-alter")
+                            .small()
+                            .icon(IconName::Bot)
+                            .label("AI 审查")
+                            .loading(self.reviewing)
+                            .on_click(cx.listener(|view, _, _, cx| view.review(cx))),
+                    )
+                    .when(!self.summary_request.changes.is_empty(), |this| {
+                        this.child(
+                            Button::new("summarize-alter")
+                                .small()
+                                .icon(IconName::Bot)
+                                .label("变更摘要")
+                                .loading(self.summarizing)
+                                .on_click(cx.listener(|view, _, _, cx| view.summarize(cx))),
+                        )
+                    })
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("检查删除、截断数据和长时间锁表等操作"),
+                    ),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_sm().text_color(cx.theme().danger).child(error))
+            })
+            .when_some(self.review.clone(), |this, review| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .when_some(review.risk, |this, risk| {
+                            this.child(div().text_sm().text_color(risk_color).child(risk.label()))
+                        })
+                        .child(
+                            div()
+                                .id("alter-review")
+                                .max_h(px(260.))
+                                .overflow_y_scroll()
+                                .p_3()
+                                .rounded_md()
+                                .border_1()
+                                .border_color(risk_color)
+                                .text_sm()
+                                .child(review.text),
+                        ),
+                )
+            })
+            .when_some(self.summary.clone(), |this, summary| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .justify_between()
+                                .items_center()
+                                .child(div().text_sm().text_color(cx.theme().muted_foreground).child("变更摘要"))
+                                .child(
+                                    Button::new("copy-alter-summary")
+                                        .xsmall()
+                                        .ghost()
+                                        .icon(IconName::Copy)
+                                        .label("复制")
+                                        .on_click({
+                                            let summary = summary.clone();
+                                            move |_, _, cx| cx.write_to_clipboard(ClipboardItem::new_string(summary.clone()))
+                                        }),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .id("alter-summary")
+                                .max_h(px(200.))
+                                .overflow_y_scroll()
+                                .p_3()
+                                .rounded_md()
+                                .border_1()
+                                .border_color(cx.theme().border)
+                                .text_sm()
+                                .font_family("monospace")
+                                .child(summary),
+                        ),
+                )
+            })
+    }
+}
+
 // === Data Type Select Item ===
 
 #[derive(Clone, Debug)]
@@ -744,7 +1053,8 @@ pub struct ColumnsEditor {
     selected_index: Option<usize>,
     data_types: Vec<DataTypeInfo>,
     charsets: Vec<CharsetInfo>,
-    _database_type: DatabaseType,
+    database_type: DatabaseType,
+    suggesting: bool,
     scroll_handle: UniformListScrollHandle,
     search_input: Entity<InputState>,
     search_query: String,
@@ -786,7 +1096,8 @@ impl ColumnsEditor {
             selected_index: None,
             data_types,
             charsets,
-            _database_type: database_type,
+            database_type,
+            suggesting: false,
             scroll_handle: UniformListScrollHandle::default(),
             search_input,
             search_query: String::new(),
@@ -822,6 +1133,35 @@ impl ColumnsEditor {
         cx.notify();
     }
 
+    /// 追加运行时才能确定的数据类型（如扩展提供的类型），已有列保留当前选择
+    pub fn add_data_types(&mut self, types: Vec<DataTypeInfo>, window: &mut Window, cx: &mut Context<Self>) {
+        let before = self.data_types.len();
+        for info in types {
+            if !self.data_types.iter().any(|t| t.name.eq_ignore_ascii_case(&info.name)) {
+                self.data_types.push(info);
+            }
+        }
+        if self.data_types.len() == before {
+            return;
+        }
+
+        let type_items: Vec<DataTypeSelectItem> = self.data_types
+            .iter()
+            .cloned()
+            .map(DataTypeSelectItem::new)
+            .collect();
+        for row in &self.columns {
+            row.type_select.update(cx, |state, cx| {
+                let selected = state.selected_value().cloned();
+                state.set_items(type_items.clone(), window, cx);
+                if let Some(selected) = selected {
+                    state.set_selected_value(&selected, window, cx);
+                }
+            });
+        }
+        cx.notify();
+    }
+
     fn get_data_types(database_type: &DatabaseType, cx: &App) -> Vec<DataTypeInfo> {
         let global_state = cx.global::<GlobalDbState>();
         if let Ok(plugin) = global_state.db_manager.get_plugin(database_type) {
@@ -947,6 +1287,77 @@ impl ColumnsEditor {
         cx.notify();
     }
 
+    /// 输入实体描述，由 AI 建议列名、类型和注释
+    fn open_suggest_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.suggesting {
+            return;
+        }
+        let description_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("描述要建的表，如：博客文章，包含标题、作者、发布日期")
+        });
+        let editor = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let description_input = description_input.clone();
+            let editor = editor.clone();
+
+            dialog
+                .title("AI 建议列")
+                .width(px(560.))
+                .child(Input::new(&description_input).h(px(120.)))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("生成"))
+                .on_ok(move |_, window, cx| {
+                    let description = description_input.read(cx).text().to_string();
+                    if description.trim().is_empty() {
+                        window.push_notification("请先描述要建的表", cx);
+                        return false;
+                    }
+                    editor.update(cx, |editor, cx| editor.request_column_suggestions(description, window, cx));
+                    true
+                })
+        });
+    }
+
+    fn request_column_suggestions(&mut self, description: String, window: &mut Window, cx: &mut Context<Self>) {
+        let request = ColumnSuggestionRequest {
+            database_type: self.database_type,
+            description,
+            type_names: self.data_types.iter().map(|t| t.name.clone()).collect(),
+            existing_columns: self.columns
+                .iter()
+                .map(|row| row.name_input.read(cx).text().to_string())
+                .filter(|name| !name.trim().is_empty())
+                .collect(),
+        };
+        self.suggesting = true;
+        cx.notify();
+
+        let window_handle = window.window_handle();
+        window.push_notification("正在生成列建议...", cx);
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = suggest_columns(request, cx).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                let _ = this.update(cx, |editor, cx| {
+                    editor.suggesting = false;
+                    match result {
+                        Ok(columns) => {
+                            let count = columns.len();
+                            let columns = columns.into_iter().map(SuggestedColumn::into_column_info).collect();
+                            editor.append_columns(columns, window, cx);
+                            window.push_notification(format!("已添加 {} 个建议列，请检查后再保存", count), cx);
+                        }
+                        Err(e) => window.push_notification(format!("列建议失败: {}", e), cx),
+                    }
+                    cx.notify();
+                });
+            });
+        }).detach();
+    }
+
     fn remove_column(&mut self, cx: &mut Context<Self>) {
         if let Some(idx) = self.selected_index {
             if idx < self.columns.len() {
@@ -1069,150 +1480,173 @@ impl ColumnsEditor {
         self._subscriptions.clear();
 
         for col in columns {
-            let name_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx).placeholder("列名");
-                input.set_value(col.name.clone(), window, cx);
-                input
-            });
-
-            let type_items: Vec<DataTypeSelectItem> = self.data_types
-                .iter()
-                .cloned()
-                .map(DataTypeSelectItem::new)
-                .collect();
-            let type_idx = type_items.iter()
-                .position(|t| t.info.name.to_uppercase() == col.data_type.to_uppercase()
-                    || col.data_type.to_uppercase().starts_with(&t.info.name.to_uppercase()))
-                .unwrap_or(0);
-            let type_select = cx.new(|cx| {
-                SelectState::new(type_items, Some(IndexPath::new(type_idx)), window, cx)
-            });
+            self.push_column(col, window, cx);
+        }
 
-            let length_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx).placeholder("长度");
-                if let Some(len) = Self::extract_length_from_type(&col.data_type) {
-                    input.set_value(len.to_string(), window, cx);
-                }
-                input
-            });
+        self.update_filtered_indices(cx);
+        cx.emit(ColumnsEditorEvent::Changed);
+        cx.notify();
+    }
 
-            let scale_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx).placeholder("小数位");
-                if let Some(scale) = Self::extract_scale_from_type(&col.data_type) {
-                    input.set_value(scale.to_string(), window, cx);
-                }
-                input
-            });
+    /// 在末尾追加列（如 AI 建议的列），已有列保持不变
+    pub fn append_columns(&mut self, columns: Vec<ColumnInfo>, window: &mut Window, cx: &mut Context<Self>) {
+        if columns.is_empty() {
+            return;
+        }
+        let first_new = self.columns.len();
+        for col in columns {
+            self.push_column(col, window, cx);
+        }
 
-            let default_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx).placeholder("默认值");
-                if let Some(ref default) = col.default_value {
-                    input.set_value(default.clone(), window, cx);
-                }
-                input
-            });
+        self.selected_index = Some(first_new);
+        self.update_filtered_indices(cx);
+        if let Some(pos) = self.filtered_indices.iter().position(|&i| i == first_new) {
+            self.scroll_handle.scroll_to_item(pos, gpui::ScrollStrategy::Top);
+        }
+        cx.emit(ColumnsEditorEvent::Changed);
+        cx.notify();
+    }
 
-            let comment_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx).placeholder("注释");
-                if let Some(ref comment) = col.comment {
-                    input.set_value(comment.clone(), window, cx);
-                }
-                input
-            });
+    fn push_column(&mut self, col: ColumnInfo, window: &mut Window, cx: &mut Context<Self>) {
+        let name_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("列名");
+            input.set_value(col.name.clone(), window, cx);
+            input
+        });
 
-            let charset_items: Vec<CharsetSelectItem> = std::iter::once(CharsetSelectItem {
-                info: CharsetInfo {
-                    name: "".to_string(),
-                    description: "默认".to_string(),
-                    default_collation: "".to_string(),
-                }
-            })
-            .chain(self.charsets.iter().cloned().map(|info| CharsetSelectItem { info }))
+        let type_items: Vec<DataTypeSelectItem> = self.data_types
+            .iter()
+            .cloned()
+            .map(DataTypeSelectItem::new)
             .collect();
-            let charset_select = cx.new(|cx| {
-                SelectState::new(charset_items, Some(IndexPath::new(0)), window, cx)
-            });
+        let type_idx = type_items.iter()
+            .position(|t| t.info.name.to_uppercase() == col.data_type.to_uppercase()
+                || col.data_type.to_uppercase().starts_with(&t.info.name.to_uppercase()))
+            .unwrap_or(0);
+        let type_select = cx.new(|cx| {
+            SelectState::new(type_items, Some(IndexPath::new(type_idx)), window, cx)
+        });
 
-            let collation_select = cx.new(|cx| {
-                let items = vec![CollationSelectItem {
-                    info: CollationInfo {
-                        name: "".to_string(),
-                        charset: "".to_string(),
-                        is_default: true,
-                    }
-                }];
-                SelectState::new(items, Some(IndexPath::new(0)), window, cx)
-            });
+        let length_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("长度");
+            if let Some(len) = Self::extract_length_from_type(&col.data_type) {
+                input.set_value(len.to_string(), window, cx);
+            }
+            input
+        });
 
-            let enum_values_input = cx.new(|cx| {
-                let mut input = InputState::new(window, cx).placeholder("值列表，如: 'a','b','c'");
-                if let Some(values) = Self::extract_enum_values(&col.data_type) {
-                    input.set_value(values, window, cx);
-                }
-                input
-            });
+        let scale_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("小数位");
+            if let Some(scale) = Self::extract_scale_from_type(&col.data_type) {
+                input.set_value(scale.to_string(), window, cx);
+            }
+            input
+        });
 
-            let name_sub = cx.subscribe_in(&name_input, window, |_this, _, event: &InputEvent, _window, cx| {
-                if let InputEvent::Change = event {
-                    cx.emit(ColumnsEditorEvent::Changed);
-                }
-            });
-            let length_sub = cx.subscribe_in(&length_input, window, |_this, _, event: &InputEvent, _window, cx| {
-                if let InputEvent::Change = event {
-                    cx.emit(ColumnsEditorEvent::Changed);
-                }
-            });
-            let scale_sub = cx.subscribe_in(&scale_input, window, |_this, _, event: &InputEvent, _window, cx| {
-                if let InputEvent::Change = event {
-                    cx.emit(ColumnsEditorEvent::Changed);
-                }
-            });
-            let default_sub = cx.subscribe_in(&default_input, window, |_this, _, event: &InputEvent, _window, cx| {
-                if let InputEvent::Change = event {
-                    cx.emit(ColumnsEditorEvent::Changed);
-                }
-            });
-            let comment_sub = cx.subscribe_in(&comment_input, window, |_this, _, event: &InputEvent, _window, cx| {
-                if let InputEvent::Change = event {
-                    cx.emit(ColumnsEditorEvent::Changed);
+        let default_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("默认值");
+            if let Some(ref default) = col.default_value {
+                input.set_value(default.clone(), window, cx);
+            }
+            input
+        });
+
+        let comment_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("注释");
+            if let Some(ref comment) = col.comment {
+                input.set_value(comment.clone(), window, cx);
+            }
+            input
+        });
+
+        let charset_items: Vec<CharsetSelectItem> = std::iter::once(CharsetSelectItem {
+            info: CharsetInfo {
+                name: "".to_string(),
+                description: "默认".to_string(),
+                default_collation: "".to_string(),
+            }
+        })
+        .chain(self.charsets.iter().cloned().map(|info| CharsetSelectItem { info }))
+        .collect();
+        let charset_select = cx.new(|cx| {
+            SelectState::new(charset_items, Some(IndexPath::new(0)), window, cx)
+        });
+
+        let collation_select = cx.new(|cx| {
+            let items = vec![CollationSelectItem {
+                info: CollationInfo {
+                    name: "".to_string(),
+                    charset: "".to_string(),
+                    is_default: true,
                 }
-            });
-            let type_sub = cx.observe(&type_select, |_this, _, cx| {
+            }];
+            SelectState::new(items, Some(IndexPath::new(0)), window, cx)
+        });
+
+        let enum_values_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("值列表，如: 'a','b','c'");
+            if let Some(values) = Self::extract_enum_values(&col.data_type) {
+                input.set_value(values, window, cx);
+            }
+            input
+        });
+
+        let name_sub = cx.subscribe_in(&name_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
                 cx.emit(ColumnsEditorEvent::Changed);
-            });
-            let charset_sub = cx.observe(&charset_select, |_this, _, cx| {
+            }
+        });
+        let length_sub = cx.subscribe_in(&length_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
                 cx.emit(ColumnsEditorEvent::Changed);
-            });
-            let collation_sub = cx.observe(&collation_select, |_this, _, cx| {
+            }
+        });
+        let scale_sub = cx.subscribe_in(&scale_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
                 cx.emit(ColumnsEditorEvent::Changed);
-            });
-            let enum_values_sub = cx.subscribe_in(&enum_values_input, window, |_this, _, event: &InputEvent, _window, cx| {
-                if let InputEvent::Change = event {
-                    cx.emit(ColumnsEditorEvent::Changed);
-                }
-            });
-
-            self._subscriptions.extend([name_sub, length_sub, scale_sub, default_sub, comment_sub, type_sub, charset_sub, collation_sub, enum_values_sub]);
+            }
+        });
+        let default_sub = cx.subscribe_in(&default_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
+                cx.emit(ColumnsEditorEvent::Changed);
+            }
+        });
+        let comment_sub = cx.subscribe_in(&comment_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
+                cx.emit(ColumnsEditorEvent::Changed);
+            }
+        });
+        let type_sub = cx.observe(&type_select, |_this, _, cx| {
+            cx.emit(ColumnsEditorEvent::Changed);
+        });
+        let charset_sub = cx.observe(&charset_select, |_this, _, cx| {
+            cx.emit(ColumnsEditorEvent::Changed);
+        });
+        let collation_sub = cx.observe(&collation_select, |_this, _, cx| {
+            cx.emit(ColumnsEditorEvent::Changed);
+        });
+        let enum_values_sub = cx.subscribe_in(&enum_values_input, window, |_this, _, event: &InputEvent, _window, cx| {
+            if let InputEvent::Change = event {
+                cx.emit(ColumnsEditorEvent::Changed);
+            }
+        });
 
-            self.columns.push(ColumnEditorRow {
-                name_input,
-                type_select,
-                length_input,
-                scale_input,
-                nullable: col.is_nullable,
-                is_pk: col.is_primary_key,
-                auto_increment: col.data_type.to_uppercase().contains("AUTO_INCREMENT"),
-                default_input,
-                comment_input,
-                charset_select,
-                collation_select,
-                enum_values_input,
-            });
-        }
+        self._subscriptions.extend([name_sub, length_sub, scale_sub, default_sub, comment_sub, type_sub, charset_sub, collation_sub, enum_values_sub]);
 
-        self.update_filtered_indices(cx);
-        cx.emit(ColumnsEditorEvent::Changed);
-        cx.notify();
+        self.columns.push(ColumnEditorRow {
+            name_input,
+            type_select,
+            length_input,
+            scale_input,
+            nullable: col.is_nullable,
+            is_pk: col.is_primary_key,
+            auto_increment: col.data_type.to_uppercase().contains("AUTO_INCREMENT"),
+            default_input,
+            comment_input,
+            charset_select,
+            collation_select,
+            enum_values_input,
+        });
     }
 
     fn extract_length_from_type(data_type: &str) -> Option<u32> {
@@ -1283,6 +1717,15 @@ impl ColumnsEditor {
                     .tooltip("删除列")
                     .on_click(cx.listener(|this, _, _window, cx| this.remove_column(cx)))
             )
+            .child(
+                Button::new("suggest-cols")
+                    .small()
+                    .icon(IconName::Bot)
+                    .ghost()
+                    .loading(self.suggesting)
+                    .tooltip("AI 建议列")
+                    .on_click(cx.listener(|this, _, window, cx| this.open_suggest_dialog(window, cx)))
+            )
             .child(div().flex_1())
             .child(
                 Input::new(&self.search_input)
@@ -0,0 +1,3 @@
+pub mod cluster_view;
+pub mod plan_view;
+pub mod tidb_view_plugin;
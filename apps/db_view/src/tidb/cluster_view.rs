@@ -0,0 +1,403 @@
+//! TiDB 集群面板 - 管理数据库内表的 TiFlash 副本，查看放置规则和放置策略
+
+use std::any::Any;
+
+use db::tidb::TiDbPlugin;
+use db::{ExecOptions, GlobalDbState, QueryResult, SqlResult};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    notification::Notification,
+    v_flex, ActiveTheme, Icon, IconName, Sizable, WindowExt,
+};
+use one_core::tab_container::{TabContent, TabContentType};
+
+/// information_schema.tiflash_replica 的一行
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiFlashReplica {
+    pub table: String,
+    pub replica_count: u32,
+    pub location_labels: String,
+    pub available: bool,
+    /// 0 到 1 之间的同步进度
+    pub progress: f64,
+}
+
+pub fn parse_tiflash_replicas(result: &QueryResult) -> Vec<TiFlashReplica> {
+    result
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let cell = |index: usize| row.get(index).cloned().flatten().unwrap_or_default();
+            let table = row.first().cloned().flatten()?;
+            Some(TiFlashReplica {
+                table,
+                replica_count: cell(1).parse().unwrap_or(0),
+                location_labels: cell(2),
+                available: matches!(cell(3).as_str(), "1" | "true"),
+                progress: cell(4).parse().unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+/// 面板中的一个结果区块，读取失败时保留错误信息，不影响其他区块
+type Section = Option<Result<QueryResult, String>>;
+
+fn section_from(result: Option<SqlResult>) -> Section {
+    match result {
+        Some(SqlResult::Query(query)) => Some(Ok(query)),
+        Some(SqlResult::Error(err)) => Some(Err(err.message)),
+        _ => Some(Err("语句没有返回结果集".to_string())),
+    }
+}
+
+pub struct TiDbClusterView {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    database: String,
+    table_input: Entity<InputState>,
+    count_input: Entity<InputState>,
+    loading: bool,
+    replicas: Section,
+    placement: Section,
+    policies: Section,
+}
+
+impl TiDbClusterView {
+    pub fn new(connection_id: String, database: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let table_input = cx.new(|cx| InputState::new(window, cx).placeholder("表名"));
+        let count_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder("副本数");
+            state.set_value("1", window, cx);
+            state
+        });
+
+        let mut view = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            database,
+            table_input,
+            count_input,
+            loading: false,
+            replicas: None,
+            placement: None,
+            policies: None,
+        };
+        view.load(cx);
+        view
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        self.loading = true;
+        cx.notify();
+
+        let plugin = TiDbPlugin::new();
+        let script = [
+            plugin.build_list_tiflash_replicas_sql(&self.database),
+            plugin.build_show_placement_sql(),
+            plugin.build_list_placement_policies_sql(),
+        ]
+        .join(";\n");
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        // 低版本没有放置规则，单个语句失败时继续读取其余部分
+        let options = ExecOptions { stop_on_error: false, ..Default::default() };
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.execute_script(cx, connection_id, script, Some(database), Some(options)).await;
+            let _ = this.update(cx, |view, cx| {
+                view.loading = false;
+                match result {
+                    Ok(results) => {
+                        let mut results = results.into_iter();
+                        view.replicas = section_from(results.next());
+                        view.placement = section_from(results.next()).map(|section| {
+                            section.map(|mut query| {
+                                let database = view.database.clone();
+                                query.rows.retain(|row| {
+                                    row.first()
+                                        .cloned()
+                                        .flatten()
+                                        .is_some_and(|target| TiDbPlugin::placement_target_in_database(&target, &database))
+                                });
+                                query
+                            })
+                        });
+                        view.policies = section_from(results.next());
+                    }
+                    Err(e) => {
+                        view.replicas = Some(Err(e.to_string()));
+                        view.placement = None;
+                        view.policies = None;
+                    }
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn set_replica(&mut self, table: String, count: u32, window: &mut Window, cx: &mut Context<Self>) {
+        if table.trim().is_empty() {
+            window.push_notification("请输入表名", cx);
+            return;
+        }
+        let sql = TiDbPlugin::new().build_set_tiflash_replica_sql(&self.database, table.trim(), count);
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let window_handle = window.window_handle();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.execute_single(cx, connection_id, sql.clone(), Some(database), None).await;
+            let _ = cx.update_window(window_handle, |_, window, cx| {
+                match result {
+                    Ok(SqlResult::Error(err)) => window.push_notification(Notification::error(format!("设置失败: {}", err.message)), cx),
+                    Ok(_) => window.push_notification(Notification::success(format!("已执行 {}", sql)).autohide(true), cx),
+                    Err(e) => window.push_notification(Notification::error(format!("设置失败: {}", e)), cx),
+                }
+                let _ = this.update(cx, |view, cx| view.load(cx));
+            });
+        }).detach();
+    }
+
+    fn apply_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let table = self.table_input.read(cx).text().to_string();
+        let count = match self.count_input.read(cx).text().to_string().trim().parse::<u32>() {
+            Ok(count) => count,
+            Err(_) => {
+                window.push_notification(Notification::error("副本数必须是非负整数"), cx);
+                return;
+            }
+        };
+        self.set_replica(table, count, window, cx);
+    }
+
+    fn render_error(message: &str, cx: &App) -> AnyElement {
+        div().text_sm().text_color(cx.theme().danger).child(message.to_string()).into_any_element()
+    }
+
+    fn render_replicas(&self, cx: &mut Context<Self>) -> AnyElement {
+        let query = match &self.replicas {
+            None => return div().into_any_element(),
+            Some(Err(e)) => return Self::render_error(e, cx),
+            Some(Ok(query)) => query,
+        };
+        let replicas = parse_tiflash_replicas(query);
+        if replicas.is_empty() {
+            return div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child("该数据库中没有表设置 TiFlash 副本")
+                .into_any_element();
+        }
+
+        v_flex()
+            .children(replicas.into_iter().enumerate().map(|(index, replica)| {
+                let status = if replica.available {
+                    ("可用".to_string(), cx.theme().success)
+                } else {
+                    (format!("同步中 {:.0}%", replica.progress * 100.0), cx.theme().warning)
+                };
+                let table = replica.table.clone();
+                h_flex()
+                    .gap_3()
+                    .py_1()
+                    .items_center()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .text_sm()
+                    .child(div().w(px(200.)).font_family("monospace").child(replica.table.clone()))
+                    .child(div().w(px(80.)).child(format!("{} 个副本", replica.replica_count)))
+                    .child(div().w(px(120.)).text_color(status.1).child(status.0))
+                    .child(div().flex_1().text_xs().text_color(cx.theme().muted_foreground).child(replica.location_labels.clone()))
+                    .child(
+                        Button::new(SharedString::from(format!("remove-tiflash-{}", index)))
+                            .small()
+                            .ghost()
+                            .label("移除")
+                            .on_click(cx.listener(move |view, _, window, cx| view.set_replica(table.clone(), 0, window, cx))),
+                    )
+            }))
+            .into_any_element()
+    }
+
+    fn render_query(section: &Section, empty_text: &'static str, cx: &App) -> AnyElement {
+        let query = match section {
+            None => return div().into_any_element(),
+            Some(Err(e)) => return Self::render_error(e, cx),
+            Some(Ok(query)) => query,
+        };
+        if query.rows.is_empty() {
+            return div().text_sm().text_color(cx.theme().muted_foreground).child(empty_text).into_any_element();
+        }
+
+        v_flex()
+            .child(
+                h_flex()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .children(query.columns.iter().map(|column| {
+                        div().w(px(180.)).flex_shrink_0().px_2().py_1().text_xs().font_weight(gpui::FontWeight::SEMIBOLD).child(column.clone())
+                    })),
+            )
+            .children(query.rows.iter().map(|row| {
+                h_flex()
+                    .items_start()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .children(row.iter().map(|value| {
+                        div()
+                            .w(px(180.))
+                            .flex_shrink_0()
+                            .px_2()
+                            .py_1()
+                            .text_xs()
+                            .font_family("monospace")
+                            .child(value.clone().unwrap_or_default())
+                    }))
+            }))
+            .into_any_element()
+    }
+}
+
+impl Focusable for TiDbClusterView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TiDbClusterView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let section_title = |title: &'static str| div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child(title);
+
+        div()
+            .id("tidb-cluster-view")
+            .size_full()
+            .overflow_y_scroll()
+            .child(
+                v_flex()
+                    .p_4()
+                    .gap_4()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(section_title("TiFlash 副本"))
+                            .child(
+                                Button::new("refresh-tidb-cluster")
+                                    .small()
+                                    .ghost()
+                                    .label("刷新")
+                                    .loading(self.loading)
+                                    .on_click(cx.listener(|view, _, _, cx| view.load(cx))),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("副本数不能超过 TiFlash 节点数，设置为 0 会删除该表的 TiFlash 副本"),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(Input::new(&self.table_input).w(px(220.)).small())
+                            .child(Input::new(&self.count_input).w(px(80.)).small())
+                            .child(
+                                Button::new("apply-tiflash-replica")
+                                    .small()
+                                    .primary()
+                                    .label("设置副本")
+                                    .on_click(cx.listener(|view, _, window, cx| view.apply_input(window, cx))),
+                            ),
+                    )
+                    .child(self.render_replicas(cx))
+                    .child(section_title("放置规则"))
+                    .child(Self::render_query(&self.placement, "该数据库没有对象绑定放置策略", cx))
+                    .child(section_title("放置策略"))
+                    .child(Self::render_query(&self.policies, "集群中没有定义放置策略", cx))
+                    .when(self.loading, |this| {
+                        this.child(div().text_xs().text_color(cx.theme().muted_foreground).child("加载中..."))
+                    }),
+            )
+    }
+}
+
+pub struct TiDbClusterTabContent {
+    title: SharedString,
+    view: Entity<TiDbClusterView>,
+}
+
+impl TiDbClusterTabContent {
+    pub fn new(connection_id: String, database: String, window: &mut Window, cx: &mut App) -> Self {
+        let title = format!("集群: {}", database);
+        let view = cx.new(|cx| TiDbClusterView::new(connection_id, database, window, cx));
+        Self {
+            title: title.into(),
+            view,
+        }
+    }
+}
+
+impl TabContent for TiDbClusterTabContent {
+    fn title(&self) -> SharedString {
+        self.title.clone()
+    }
+
+    fn icon(&self) -> Option<Icon> {
+        Some(IconName::Database.color())
+    }
+
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    fn render_content(&self, _window: &mut Window, _cx: &mut App) -> AnyElement {
+        self.view.clone().into_any_element()
+    }
+
+    fn content_type(&self) -> TabContentType {
+        TabContentType::Custom("TiDbCluster".to_string())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tiflash_replicas() {
+        let result = QueryResult {
+            sql: String::new(),
+            columns: vec!["TABLE_NAME", "REPLICA_COUNT", "LOCATION_LABELS", "AVAILABLE", "PROGRESS"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            rows: vec![
+                vec![Some("orders".into()), Some("2".into()), Some("".into()), Some("1".into()), Some("1".into())],
+                vec![Some("events".into()), Some("1".into()), Some("zone".into()), Some("0".into()), Some("0.35".into())],
+                vec![None, Some("1".into()), None, None, None],
+            ],
+            elapsed_ms: 0,
+            table_name: None,
+            editable: false,
+        };
+        let replicas = parse_tiflash_replicas(&result);
+        assert_eq!(replicas.len(), 2);
+        assert_eq!(replicas[0].replica_count, 2);
+        assert!(replicas[0].available);
+        assert!(!replicas[1].available);
+        assert_eq!(replicas[1].progress, 0.35);
+        assert_eq!(replicas[1].location_labels, "zone");
+    }
+}
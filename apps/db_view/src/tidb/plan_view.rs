@@ -0,0 +1,150 @@
+//! TiDB 执行计划展示 - 按算子树缩进显示 EXPLAIN ANALYZE 的结果，标出执行位置、估算偏差和全表扫描
+
+use db::tidb::{PlanOperator, PlanStore};
+use gpui::prelude::FluentBuilder;
+use gpui::{div, px, AnyElement, App, Hsla, InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{h_flex, v_flex, ActiveTheme, WindowExt};
+
+fn store_label(store: PlanStore) -> &'static str {
+    match store {
+        PlanStore::Root => "TiDB",
+        PlanStore::TiKv => "TiKV",
+        PlanStore::TiFlash => "TiFlash",
+    }
+}
+
+fn store_color(store: PlanStore, cx: &App) -> Hsla {
+    match store {
+        PlanStore::Root => cx.theme().muted_foreground,
+        PlanStore::TiKv => cx.theme().primary,
+        PlanStore::TiFlash => cx.theme().success,
+    }
+}
+
+fn format_rows(rows: Option<f64>) -> String {
+    match rows {
+        Some(rows) if rows.fract() == 0.0 => format!("{}", rows as i64),
+        Some(rows) => format!("{:.2}", rows),
+        None => "-".to_string(),
+    }
+}
+
+/// 计划的概要：根算子耗时、估算偏差和 TiKV 全表扫描的数量，以及是否用到了 TiFlash
+pub fn plan_summary(plan: &[PlanOperator]) -> String {
+    let mut parts = Vec::new();
+    if let Some(time) = plan.first().and_then(|op| op.execution_time()) {
+        parts.push(format!("总耗时 {}", time));
+    }
+    let misestimated = plan.iter().filter(|op| op.is_misestimated()).count();
+    if misestimated > 0 {
+        parts.push(format!("{} 个算子行数估算偏差超过 10 倍，可能需要 ANALYZE TABLE", misestimated));
+    }
+    let full_scans = plan.iter().filter(|op| op.is_full_scan()).count();
+    if full_scans > 0 {
+        parts.push(format!("{} 个 TiKV 全表扫描", full_scans));
+    }
+    if plan.iter().any(|op| op.store() == PlanStore::TiFlash) {
+        parts.push("使用了 TiFlash 副本".to_string());
+    }
+    parts.join("；")
+}
+
+fn render_operator(operator: &PlanOperator, cx: &App) -> impl IntoElement {
+    let store = operator.store();
+    let act_color = if operator.is_misestimated() { cx.theme().danger } else { cx.theme().foreground };
+    let details = [operator.access_object.as_str(), operator.operator_info.as_str()]
+        .iter()
+        .filter(|text| !text.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    v_flex()
+        .pl(px(operator.depth as f32 * 18.))
+        .py_1()
+        .border_b_1()
+        .border_color(cx.theme().border)
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .text_xs()
+                .child(div().font_weight(gpui::FontWeight::SEMIBOLD).font_family("monospace").child(operator.id.clone()))
+                .when_some(operator.role.clone(), |this, role| {
+                    this.child(div().text_color(cx.theme().muted_foreground).child(role))
+                })
+                .child(
+                    div()
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(store_color(store, cx))
+                        .text_color(store_color(store, cx))
+                        .child(store_label(store)),
+                )
+                .when(operator.is_full_scan(), |this| {
+                    this.child(div().text_color(cx.theme().warning).child("全表扫描"))
+                })
+                .child(div().text_color(cx.theme().muted_foreground).child(format!("估算 {}", format_rows(operator.est_rows))))
+                .when(operator.act_rows.is_some(), |this| {
+                    this.child(div().text_color(act_color).child(format!("实际 {}", format_rows(operator.act_rows))))
+                })
+                .when_some(operator.execution_time(), |this, time| {
+                    this.child(div().child(time.to_string()))
+                })
+                .when(!operator.memory.is_empty() && operator.memory != "N/A", |this| {
+                    this.child(div().text_color(cx.theme().muted_foreground).child(format!("内存 {}", operator.memory)))
+                }),
+        )
+        .when(!details.is_empty(), |this| {
+            this.child(
+                div()
+                    .text_xs()
+                    .font_family("monospace")
+                    .text_color(cx.theme().muted_foreground)
+                    .child(details),
+            )
+        })
+}
+
+pub fn render_plan(plan: &[PlanOperator], cx: &App) -> AnyElement {
+    v_flex()
+        .children(plan.iter().map(|operator| render_operator(operator, cx)))
+        .into_any_element()
+}
+
+/// 在对话框中展示执行计划；EXPLAIN ANALYZE 已实际执行过语句
+pub fn open_plan_dialog(sql: String, plan: Vec<PlanOperator>, window: &mut Window, cx: &mut App) {
+    let summary = plan_summary(&plan);
+    window.open_dialog(cx, move |dialog, _window, cx| {
+        dialog
+            .title("执行计划 (EXPLAIN ANALYZE)")
+            .width(px(900.))
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .id("tidb-plan-sql")
+                            .max_h(px(100.))
+                            .overflow_y_scroll()
+                            .p_2()
+                            .rounded_md()
+                            .bg(cx.theme().muted)
+                            .font_family("monospace")
+                            .text_xs()
+                            .child(sql.trim().to_string()),
+                    )
+                    .when(!summary.is_empty(), |this| {
+                        this.child(div().text_sm().child(summary.clone()))
+                    })
+                    .child(
+                        div()
+                            .id("tidb-plan-tree")
+                            .max_h(px(480.))
+                            .overflow_y_scroll()
+                            .child(render_plan(&plan, cx)),
+                    ),
+            )
+    });
+}
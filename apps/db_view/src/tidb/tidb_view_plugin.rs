@@ -0,0 +1,89 @@
+use gpui::{App, AppContext, Entity, Window};
+use one_core::storage::DatabaseType;
+use crate::common::DatabaseEditorView;
+use crate::database_view_plugin::{DatabaseViewPlugin, TableDesignerCapabilities, NodeMenuCapabilities};
+use crate::common::db_connection_form::{DbConnectionForm, DbFormConfig};
+use crate::mysql::database_form::MySqlDatabaseForm;
+
+/// TiDB 数据库视图插件，数据库表单沿用 MySQL
+pub struct TiDbDatabaseViewPlugin;
+
+impl TiDbDatabaseViewPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DatabaseViewPlugin for TiDbDatabaseViewPlugin {
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::TiDB
+    }
+
+    fn create_connection_form(&self, window: &mut Window, cx: &mut App) -> Entity<DbConnectionForm> {
+        cx.new(|cx| DbConnectionForm::new(DbFormConfig::tidb(), window, cx))
+    }
+
+    fn create_database_editor_view(
+        &self,
+        _connection_id: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| MySqlDatabaseForm::new(window, cx));
+            DatabaseEditorView::new(form, DatabaseType::TiDB, false, window, cx)
+        })
+    }
+
+    fn create_database_editor_view_for_edit(
+        &self,
+        _connection_id: String,
+        database_name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| MySqlDatabaseForm::new_for_edit(&database_name, window, cx));
+            DatabaseEditorView::new(form, DatabaseType::TiDB, true, window, cx)
+        })
+    }
+
+    fn get_table_designer_capabilities(&self) -> TableDesignerCapabilities {
+        // 存储引擎固定为 TiKV，ENGINE 子句会被忽略
+        TableDesignerCapabilities {
+            supports_engine: false,
+            supports_charset: true,
+            supports_collation: true,
+            supports_auto_increment: true,
+            supports_tablespace: false,
+        }
+    }
+
+    fn get_engines(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn get_node_menu_capabilities(&self) -> NodeMenuCapabilities {
+        NodeMenuCapabilities {
+            supports_truncate_table: true,
+            supports_rename_table: true,
+            supports_table_import: true,
+            supports_table_export: true,
+            supports_create_database: true,
+            supports_edit_database: true,
+            supports_drop_database: true,
+            supports_dump_database: true,
+            supports_create_schema: false,
+            supports_delete_schema: false,
+            // 维护面板管理 TiFlash 副本和放置规则
+            supports_maintenance: true,
+            supports_create_view: true,
+            supports_edit_view: true,
+            supports_sequences: false,
+            // TiDB 不支持触发器、存储过程和存储函数
+            supports_triggers: false,
+            supports_stored_procedures: false,
+            supports_functions: false,
+        }
+    }
+}
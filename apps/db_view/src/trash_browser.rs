@@ -0,0 +1,233 @@
+//! 回收站浏览器 - 查看、恢复、彻底删除被移到回收站的表
+
+use db::{GlobalDbState, TrashEntry};
+use gpui::{div, px, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    button::{Button, ButtonVariants},
+    h_flex,
+    notification::Notification,
+    v_flex, ActiveTheme, Sizable, WindowExt,
+};
+use one_core::storage::ConnectionEnvironment;
+
+use crate::confirm_policy::{confirm_destructive, ConfirmRequest, DestructiveAction};
+use crate::db_tree_view::DbTreeView;
+
+fn format_timestamp(ts: i64) -> String {
+    use chrono::{DateTime, Local};
+    if let Some(dt) = DateTime::from_timestamp(ts, 0) {
+        let local: DateTime<Local> = dt.into();
+        local.format("%Y-%m-%d %H:%M:%S").to_string()
+    } else {
+        "".to_string()
+    }
+}
+
+pub struct TrashBrowser {
+    focus_handle: FocusHandle,
+    connection_id: String,
+    database: String,
+    /// 恢复后需要刷新的数据库节点
+    database_node_id: String,
+    environment: ConnectionEnvironment,
+    tree_view: Entity<DbTreeView>,
+    entries: Vec<TrashEntry>,
+    loading: bool,
+    error: Option<String>,
+}
+
+impl TrashBrowser {
+    pub fn new(
+        connection_id: String,
+        database: String,
+        database_node_id: String,
+        tree_view: Entity<DbTreeView>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let environment = tree_view.read(cx).connection_environment(&connection_id);
+        let mut browser = Self {
+            focus_handle: cx.focus_handle(),
+            connection_id,
+            database,
+            database_node_id,
+            environment,
+            tree_view,
+            entries: Vec::new(),
+            loading: false,
+            error: None,
+        };
+        browser.reload(cx);
+        browser
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.loading = true;
+        self.error = None;
+        cx.notify();
+
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = global_state.list_trash(cx, connection_id, database).await;
+            let _ = this.update(cx, |browser, cx| {
+                browser.loading = false;
+                match result {
+                    Ok(entries) => browser.entries = entries,
+                    Err(e) => browser.error = Some(format!("加载回收站失败: {}", e)),
+                }
+                cx.notify();
+            });
+        }).detach();
+    }
+
+    fn restore(&mut self, entry: TrashEntry, cx: &mut Context<Self>) {
+        let global_state = cx.global::<GlobalDbState>().clone();
+        let connection_id = self.connection_id.clone();
+        let database = self.database.clone();
+        let database_node_id = self.database_node_id.clone();
+        let tree_view = self.tree_view.clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let name = entry.original_name.clone();
+            let result = global_state.restore_from_trash(cx, connection_id, database, entry).await;
+            let _ = this.update(cx, |browser, cx| {
+                match result {
+                    Ok(_) => {
+                        tree_view.update(cx, |tree, cx| tree.refresh_tree(database_node_id, cx));
+                        browser.notify(Notification::success(format!("表 {} 已恢复", name)), cx);
+                        browser.reload(cx);
+                    }
+                    Err(e) => browser.notify(Notification::error(format!("恢复失败: {}", e)), cx),
+                }
+            });
+        }).detach();
+    }
+
+    fn purge(&mut self, entry: TrashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let view = cx.entity();
+        let request = ConfirmRequest::new(
+            DestructiveAction::DropTable,
+            self.environment,
+            entry.original_name.clone(),
+            format!("确定要彻底删除表 \"{}\" 吗？", entry.original_name),
+            "彻底删除后无法恢复！",
+        );
+
+        confirm_destructive(request, window, cx, move |_window, cx| {
+            let entry = entry.clone();
+            view.update(cx, |browser, cx| {
+                let global_state = cx.global::<GlobalDbState>().clone();
+                let connection_id = browser.connection_id.clone();
+                let database = browser.database.clone();
+
+                cx.spawn(async move |this, cx: &mut AsyncApp| {
+                    let name = entry.original_name.clone();
+                    let result = global_state.purge_trash(cx, connection_id, database, entry).await;
+                    let _ = this.update(cx, |browser, cx| {
+                        match result {
+                            Ok(_) => {
+                                browser.notify(Notification::success(format!("表 {} 已彻底删除", name)), cx);
+                                browser.reload(cx);
+                            }
+                            Err(e) => browser.notify(Notification::error(format!("删除失败: {}", e)), cx),
+                        }
+                    });
+                }).detach();
+            });
+        });
+    }
+
+    fn notify(&self, notification: Notification, cx: &mut App) {
+        if let Some(window) = cx.active_window() {
+            let _ = window.update(cx, |_, window, cx| {
+                window.push_notification(notification.autohide(true), cx);
+            });
+        }
+    }
+
+    fn render_entry(&self, index: usize, entry: &TrashEntry, cx: &mut Context<Self>) -> impl IntoElement {
+        let restore_entry = entry.clone();
+        let purge_entry = entry.clone();
+        let location = if entry.original_schema.is_empty() {
+            entry.original_name.clone()
+        } else {
+            format!("{}.{}", entry.original_schema, entry.original_name)
+        };
+
+        h_flex()
+            .gap_2()
+            .py_1()
+            .items_center()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                v_flex()
+                    .child(div().text_sm().child(location))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("删除于 {}", format_timestamp(entry.dropped_at))),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(SharedString::from(format!("trash-restore-{}", index)))
+                            .small()
+                            .label("恢复")
+                            .on_click(cx.listener(move |browser, _, _, cx| {
+                                browser.restore(restore_entry.clone(), cx);
+                            })),
+                    )
+                    .child(
+                        Button::new(SharedString::from(format!("trash-purge-{}", index)))
+                            .small()
+                            .danger()
+                            .label("彻底删除")
+                            .on_click(cx.listener(move |browser, _, window, cx| {
+                                browser.purge(purge_entry.clone(), window, cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Focusable for TrashBrowser {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TrashBrowser {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.loading {
+            div().child("加载中...").into_any_element()
+        } else if let Some(error) = &self.error {
+            div().text_color(cx.theme().danger).child(error.clone()).into_any_element()
+        } else if self.entries.is_empty() {
+            div().text_color(cx.theme().muted_foreground).child("回收站为空").into_any_element()
+        } else {
+            let entries = self.entries.clone();
+            let mut list = v_flex();
+            for (index, entry) in entries.iter().enumerate() {
+                list = list.child(self.render_entry(index, entry, cx));
+            }
+            list.into_any_element()
+        };
+
+        v_flex()
+            .gap_2()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("数据库 {} 中被移到回收站的表", self.database)),
+            )
+            .child(div().id("trash-entries").max_h(px(360.0)).overflow_y_scroll().child(content))
+    }
+}
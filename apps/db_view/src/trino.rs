@@ -0,0 +1 @@
+pub mod trino_view_plugin;
@@ -0,0 +1,99 @@
+use gpui::{App, AppContext, Entity, Window};
+use one_core::storage::DatabaseType;
+use crate::common::{DatabaseEditorView, DatabaseNameForm, SchemaEditorView};
+use crate::database_view_plugin::{DatabaseViewPlugin, TableDesignerCapabilities, NodeMenuCapabilities};
+use crate::common::db_connection_form::{DbConnectionForm, DbFormConfig};
+use crate::postgresql::schema_form::PostgreSqlSchemaForm;
+
+/// Trino 数据库视图插件；目录由服务端配置，树上只能新建和删除 schema
+pub struct TrinoDatabaseViewPlugin;
+
+impl TrinoDatabaseViewPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DatabaseViewPlugin for TrinoDatabaseViewPlugin {
+    fn database_type(&self) -> DatabaseType {
+        DatabaseType::Trino
+    }
+
+    fn create_connection_form(&self, window: &mut Window, cx: &mut App) -> Entity<DbConnectionForm> {
+        cx.new(|cx| DbConnectionForm::new(DbFormConfig::trino(), window, cx))
+    }
+
+    fn create_database_editor_view(
+        &self,
+        _connection_id: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| DatabaseNameForm::new(window, cx));
+            DatabaseEditorView::new(form, DatabaseType::Trino, false, window, cx)
+        })
+    }
+
+    fn create_database_editor_view_for_edit(
+        &self,
+        _connection_id: String,
+        database_name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<DatabaseEditorView> {
+        cx.new(|cx| {
+            let form = cx.new(|cx| DatabaseNameForm::new_for_edit(&database_name, window, cx));
+            DatabaseEditorView::new(form, DatabaseType::Trino, true, window, cx)
+        })
+    }
+
+    fn create_schema_editor_view(
+        &self,
+        _connection_id: String,
+        _database_name: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Entity<SchemaEditorView>> {
+        Some(cx.new(|cx| {
+            let form = cx.new(|cx| PostgreSqlSchemaForm::new(window, cx));
+            SchemaEditorView::new(form, DatabaseType::Trino, window, cx)
+        }))
+    }
+
+    fn get_table_designer_capabilities(&self) -> TableDesignerCapabilities {
+        TableDesignerCapabilities {
+            supports_engine: false,
+            supports_charset: false,
+            supports_collation: false,
+            supports_auto_increment: false,
+            supports_tablespace: false,
+        }
+    }
+
+    fn get_engines(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn get_node_menu_capabilities(&self) -> NodeMenuCapabilities {
+        NodeMenuCapabilities {
+            supports_truncate_table: true,
+            supports_rename_table: true,
+            supports_table_import: true,
+            supports_table_export: true,
+            supports_create_database: false,
+            supports_edit_database: false,
+            supports_drop_database: false,
+            supports_dump_database: false,
+            supports_create_schema: true,
+            supports_delete_schema: true,
+            supports_maintenance: false,
+            supports_create_view: true,
+            supports_edit_view: true,
+            supports_sequences: false,
+            supports_triggers: false,
+            supports_stored_procedures: false,
+            supports_functions: false,
+        }
+    }
+}
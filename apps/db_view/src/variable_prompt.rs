@@ -0,0 +1,131 @@
+//! 执行前替换工作区变量 - 变量取自连接所在的工作区，未定义的变量弹窗让用户填写本次使用的值
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use db::GlobalDbState;
+use gpui::{div, px, App, AppContext, AsyncApp, Entity, ParentElement, Styled, Window};
+use gpui_component::input::{Input, InputState};
+use gpui_component::notification::Notification;
+use gpui_component::{v_flex, ActiveTheme, WindowExt};
+use one_core::gpui_tokio::Tokio;
+use one_core::storage::{referenced_variables, substitute_variables, GlobalStorageState, WorkspaceVariableRepository};
+
+/// 替换 `text` 中的 `${NAME}` 后调用 `on_resolved`。
+/// 总是延迟到当前更新结束后回调，调用方可以在回调中更新正在处理事件的实体
+pub fn resolve_variables(
+    text: String,
+    connection_id: String,
+    window: &mut Window,
+    cx: &mut App,
+    on_resolved: impl Fn(String, &mut Window, &mut App) + 'static,
+) {
+    if referenced_variables(&text).is_empty() {
+        window.defer(cx, move |window, cx| on_resolved(text, window, cx));
+        return;
+    }
+
+    let global_state = cx.global::<GlobalDbState>().clone();
+    let storage = cx.global::<GlobalStorageState>().storage.clone();
+    let window_handle = window.window_handle();
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        let workspace_id = global_state
+            .get_config_async(&connection_id)
+            .await
+            .and_then(|config| config.workspace_id);
+
+        let variables = match workspace_id {
+            Some(workspace_id) => {
+                let task = Tokio::spawn_result(cx, async move {
+                    let repo = storage.get::<WorkspaceVariableRepository>().await
+                        .ok_or_else(|| anyhow::anyhow!("WorkspaceVariableRepository not found"))?;
+                    repo.list(workspace_id).await
+                });
+                match task {
+                    Ok(task) => task.await,
+                    Err(e) => Err(e),
+                }
+            }
+            None => Ok(Vec::new()),
+        };
+
+        let _ = cx.update_window(window_handle, |_, window, cx| {
+            let values: HashMap<String, String> = match variables {
+                Ok(variables) => variables.into_iter().map(|v| (v.name, v.value)).collect(),
+                Err(e) => {
+                    window.push_notification(Notification::error(format!("加载工作区变量失败: {}", e)), cx);
+                    return;
+                }
+            };
+            let missing: Vec<String> = referenced_variables(&text)
+                .into_iter()
+                .filter(|name| !values.contains_key(name))
+                .collect();
+            if missing.is_empty() {
+                on_resolved(substitute_variables(&text, &values), window, cx);
+            } else {
+                open_prompt(text, values, missing, Rc::new(on_resolved), window, cx);
+            }
+        });
+    })
+    .detach();
+}
+
+fn open_prompt(
+    text: String,
+    values: HashMap<String, String>,
+    missing: Vec<String>,
+    on_resolved: Rc<dyn Fn(String, &mut Window, &mut App)>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let inputs: Vec<(String, Entity<InputState>)> = missing
+        .into_iter()
+        .map(|name| {
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder("按原样替换，字符串需自行加引号"));
+            (name, input)
+        })
+        .collect();
+
+    window.open_dialog(cx, move |dialog, _window, cx| {
+        let text = text.clone();
+        let values = values.clone();
+        let inputs = inputs.clone();
+        let on_resolved = on_resolved.clone();
+
+        let mut fields = v_flex().gap_2().child(
+            div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child("以下变量未在工作区中定义，请填写本次执行使用的值"),
+        );
+        for (name, input) in &inputs {
+            fields = fields.child(
+                v_flex()
+                    .gap_1()
+                    .child(div().text_sm().child(format!("${{{}}}", name)))
+                    .child(Input::new(input)),
+            );
+        }
+
+        dialog
+            .title("填写变量")
+            .width(px(420.))
+            .child(fields)
+            .confirm()
+            .on_ok(move |_, window, cx| {
+                let mut values = values.clone();
+                for (name, input) in &inputs {
+                    let value = input.read(cx).text().to_string();
+                    if value.is_empty() {
+                        window.push_notification(format!("请填写变量 {}", name), cx);
+                        return false;
+                    }
+                    values.insert(name.clone(), value);
+                }
+                on_resolved(substitute_variables(&text, &values), window, cx);
+                true
+            })
+    });
+}
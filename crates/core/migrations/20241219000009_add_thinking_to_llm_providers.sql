@@ -0,0 +1,3 @@
+-- Add extended thinking options to llm_providers table
+ALTER TABLE llm_providers ADD COLUMN extended_thinking INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE llm_providers ADD COLUMN thinking_budget_tokens INTEGER;
@@ -0,0 +1,22 @@
+-- Store chat messages as a tree so edited/regenerated messages become branches
+ALTER TABLE chat_messages ADD COLUMN parent_id INTEGER REFERENCES chat_messages(id) ON DELETE CASCADE;
+ALTER TABLE chat_sessions ADD COLUMN active_leaf_id INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_chat_messages_parent_id ON chat_messages (parent_id);
+
+-- Existing conversations are linear: chain each message to the one before it
+UPDATE chat_messages SET parent_id = (
+    SELECT p.id FROM chat_messages p
+    WHERE p.session_id = chat_messages.session_id
+      AND (p.created_at < chat_messages.created_at
+           OR (p.created_at = chat_messages.created_at AND p.id < chat_messages.id))
+    ORDER BY p.created_at DESC, p.id DESC
+    LIMIT 1
+);
+
+UPDATE chat_sessions SET active_leaf_id = (
+    SELECT m.id FROM chat_messages m
+    WHERE m.session_id = chat_sessions.id
+    ORDER BY m.created_at DESC, m.id DESC
+    LIMIT 1
+);
@@ -0,0 +1,2 @@
+-- Open the connection automatically when the app starts
+ALTER TABLE connections ADD COLUMN auto_open INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Cached children of database tree nodes, shown immediately on the next launch
+CREATE TABLE IF NOT EXISTS schema_cache (
+    connection_id INTEGER NOT NULL,
+    node_id TEXT NOT NULL,
+    children TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (connection_id, node_id),
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
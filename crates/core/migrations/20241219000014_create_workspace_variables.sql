@@ -0,0 +1,9 @@
+-- User-defined variables referenced as ${NAME} in SQL and AI prompts, scoped to a workspace
+CREATE TABLE IF NOT EXISTS workspace_variables (
+    workspace_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (workspace_id, name),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Duration and row count of each saved query execution, shown as a trend in the editor
+CREATE TABLE IF NOT EXISTS query_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    query_id INTEGER NOT NULL,
+    elapsed_ms INTEGER NOT NULL,
+    row_count INTEGER NOT NULL,
+    executed_at INTEGER NOT NULL,
+    FOREIGN KEY (query_id) REFERENCES queries(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_query_runs_query ON query_runs(query_id, executed_at);
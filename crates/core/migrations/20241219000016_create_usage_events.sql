@@ -0,0 +1,9 @@
+-- Local feature usage and error counts, only recorded after the user opts in to usage metrics
+CREATE TABLE IF NOT EXISTS usage_events (
+    kind TEXT NOT NULL,
+    name TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    first_at INTEGER NOT NULL,
+    last_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, name)
+);
@@ -0,0 +1,11 @@
+-- Embedding of each table's name, columns and comments, used to pick the tables sent to the AI
+CREATE TABLE IF NOT EXISTS schema_embeddings (
+    connection_id INTEGER NOT NULL,
+    database_name TEXT NOT NULL,
+    table_name TEXT NOT NULL,
+    document TEXT NOT NULL,
+    embedding BLOB NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (connection_id, database_name, table_name),
+    FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Color tag shown on the tree node, tab titles and editor border
+ALTER TABLE connections ADD COLUMN color TEXT;
@@ -0,0 +1,148 @@
+//! Launch requests from command-line arguments and `onehub://` deep links
+
+use anyhow::{anyhow, Result};
+
+pub const URL_SCHEME: &str = "onehub://";
+
+/// What to open when the app is launched or receives a deep link
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchRequest {
+    /// Name of the saved connection to open
+    pub connection: Option<String>,
+    pub database: Option<String>,
+    /// SQL to put into a new query tab
+    pub sql: Option<String>,
+}
+
+impl LaunchRequest {
+    pub fn is_empty(&self) -> bool {
+        self.connection.is_none()
+    }
+
+    /// Parse `--connection <name> [--database <db>] [--sql <sql>]`, also accepting
+    /// `--flag=value` and a single `onehub://` URL (as passed by the OS for deep links)
+    pub fn from_args<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut request = LaunchRequest::default();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            if arg.starts_with(URL_SCHEME) {
+                request = Self::from_url(&arg)?;
+                continue;
+            }
+
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let slot = match flag.as_str() {
+                "--connection" | "-c" => &mut request.connection,
+                "--database" | "-d" => &mut request.database,
+                "--sql" => &mut request.sql,
+                // Unknown arguments (e.g. platform-specific ones) are ignored
+                _ => continue,
+            };
+            let value = match inline_value {
+                Some(value) => value,
+                None => args.next().ok_or_else(|| anyhow!("Missing value for {}", flag))?,
+            };
+            *slot = Some(value);
+        }
+
+        Ok(request)
+    }
+
+    /// Parse `onehub://connect?name=<connection>&database=<db>&sql=<sql>`
+    pub fn from_url(url: &str) -> Result<Self> {
+        let rest = url
+            .strip_prefix(URL_SCHEME)
+            .ok_or_else(|| anyhow!("Not a onehub URL: {}", url))?;
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if action.trim_end_matches('/') != "connect" {
+            return Err(anyhow!("Unsupported onehub URL action: {}", action));
+        }
+
+        let mut request = LaunchRequest::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            match key {
+                "name" | "connection" => request.connection = Some(value),
+                "database" => request.database = Some(value),
+                "sql" => request.sql = Some(value),
+                _ => {}
+            }
+        }
+
+        if request.connection.is_none() {
+            return Err(anyhow!("onehub URL is missing the connection name"));
+        }
+        Ok(request)
+    }
+}
+
+/// Decode `%XX` escapes and `+` (space) in a URL query value
+fn percent_decode(value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| anyhow!("Invalid percent escape in {}", value))?;
+                decoded.push(hex);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Ok(String::from_utf8(decoded)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_line_flags() {
+        let request = LaunchRequest::from_args(["--connection", "prod", "--sql=select 1", "-d", "app"]).unwrap();
+        assert_eq!(request.connection.as_deref(), Some("prod"));
+        assert_eq!(request.database.as_deref(), Some("app"));
+        assert_eq!(request.sql.as_deref(), Some("select 1"));
+
+        assert!(LaunchRequest::from_args(Vec::<String>::new()).unwrap().is_empty());
+        assert!(LaunchRequest::from_args(["--sql"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_deep_link() {
+        let request = LaunchRequest::from_url("onehub://connect?name=%E7%94%9F%E4%BA%A7&sql=select+*+from+t%3B").unwrap();
+        assert_eq!(request.connection.as_deref(), Some("生产"));
+        assert_eq!(request.sql.as_deref(), Some("select * from t;"));
+
+        let from_args = LaunchRequest::from_args(["onehub://connect/?name=prod"]).unwrap();
+        assert_eq!(from_args.connection.as_deref(), Some("prod"));
+    }
+
+    #[test]
+    fn test_reject_invalid_deep_links() {
+        assert!(LaunchRequest::from_url("onehub://open?name=prod").is_err());
+        assert!(LaunchRequest::from_url("onehub://connect?sql=select").is_err());
+        assert!(LaunchRequest::from_url("onehub://connect?name=%ZZ").is_err());
+    }
+}
@@ -1,15 +1,22 @@
 use gpui::App;
+use storage::encryption::DatabaseKey;
 
 pub mod tab_container;
 pub mod themes;
 pub mod storage;
 pub mod gpui_tokio;
+pub mod launch;
 pub mod llm;
 pub mod utils;
+pub mod window_state;
 
 pub fn init(cx: &mut App){
     gpui_tokio::init(cx);
     themes::init(cx);
-    storage::init(cx);
+}
+
+/// 启用主密码时存储需要在解锁之后才能打开，因此与 `init` 分开
+pub fn init_storage(cx: &mut App, key: Option<DatabaseKey>){
+    storage::init(cx, key);
     llm::init(cx);
 }
\ No newline at end of file
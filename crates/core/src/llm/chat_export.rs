@@ -0,0 +1,222 @@
+//! Export chat conversations to Markdown and JSON files
+
+use anyhow::Result;
+use chrono::DateTime;
+use serde::Serialize;
+
+use super::chat_history::{ChatMessage, ChatSession};
+
+/// Supported conversation export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ChatExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChatExportFormat::Markdown => "md",
+            ChatExportFormat::Json => "json",
+        }
+    }
+}
+
+/// A fenced code block extracted from a message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeBlock {
+    pub language: Option<String>,
+    pub code: String,
+}
+
+#[derive(Serialize)]
+struct ExportedMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+    created_at: i64,
+    timestamp: String,
+    code_blocks: Vec<CodeBlock>,
+}
+
+#[derive(Serialize)]
+struct ExportedConversation<'a> {
+    id: i64,
+    name: &'a str,
+    provider_id: &'a str,
+    created_at: i64,
+    updated_at: i64,
+    messages: Vec<ExportedMessage<'a>>,
+}
+
+/// Render a conversation in the requested format
+pub fn export_conversation(
+    session: &ChatSession,
+    messages: &[ChatMessage],
+    format: ChatExportFormat,
+) -> Result<String> {
+    match format {
+        ChatExportFormat::Markdown => Ok(to_markdown(session, messages)),
+        ChatExportFormat::Json => to_json(session, messages),
+    }
+}
+
+pub fn to_markdown(session: &ChatSession, messages: &[ChatMessage]) -> String {
+    let mut output = format!("# {}\n\n", session.name);
+    output.push_str(&format!("_Created {}_\n", format_timestamp(session.created_at)));
+
+    for message in messages {
+        output.push_str(&format!(
+            "\n## {} · {}\n\n",
+            role_title(&message.role),
+            format_timestamp(message.created_at)
+        ));
+        output.push_str(message.content.trim_end());
+        output.push('\n');
+        // An unterminated fence would swallow every following message
+        if count_fences(&message.content) % 2 == 1 {
+            output.push_str("```\n");
+        }
+    }
+
+    output
+}
+
+pub fn to_json(session: &ChatSession, messages: &[ChatMessage]) -> Result<String> {
+    let conversation = ExportedConversation {
+        id: session.id,
+        name: &session.name,
+        provider_id: &session.provider_id,
+        created_at: session.created_at,
+        updated_at: session.updated_at,
+        messages: messages
+            .iter()
+            .map(|message| ExportedMessage {
+                role: &message.role,
+                content: &message.content,
+                created_at: message.created_at,
+                timestamp: format_timestamp(message.created_at),
+                code_blocks: extract_code_blocks(&message.content),
+            })
+            .collect(),
+    };
+
+    Ok(serde_json::to_string_pretty(&conversation)?)
+}
+
+/// Extract fenced (```) code blocks; an unterminated block runs to the end of the text
+pub fn extract_code_blocks(content: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, Vec<&str>)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        match current.take() {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    let language = info.trim();
+                    let language = (!language.is_empty()).then(|| language.to_string());
+                    current = Some((language, Vec::new()));
+                }
+            }
+            Some((language, mut lines)) => {
+                if trimmed.starts_with("```") {
+                    blocks.push(CodeBlock { language, code: lines.join("\n") });
+                } else {
+                    lines.push(line);
+                    current = Some((language, lines));
+                }
+            }
+        }
+    }
+
+    if let Some((language, lines)) = current {
+        blocks.push(CodeBlock { language, code: lines.join("\n") });
+    }
+    blocks
+}
+
+fn count_fences(content: &str) -> usize {
+    content
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count()
+}
+
+fn role_title(role: &str) -> &str {
+    match role {
+        "user" => "User",
+        "assistant" => "Assistant",
+        "system" => "System",
+        other => other,
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> (ChatSession, Vec<ChatMessage>) {
+        let session = ChatSession {
+            id: 7,
+            name: "Add age column".to_string(),
+            provider_id: "1".to_string(),
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_060,
+            active_leaf_id: None,
+        };
+        let mut question = ChatMessage::user(7, "How do I add a column?".to_string());
+        question.created_at = 1_700_000_000;
+        let mut answer = ChatMessage::assistant(
+            7,
+            "Use:\n```sql\nALTER TABLE users ADD COLUMN age INT;\n```".to_string(),
+        );
+        answer.created_at = 1_700_000_060;
+        (session, vec![question, answer])
+    }
+
+    #[test]
+    fn test_markdown_contains_headings_timestamps_and_code() {
+        let (session, messages) = conversation();
+        let markdown = to_markdown(&session, &messages);
+
+        assert!(markdown.starts_with("# Add age column\n"));
+        assert!(markdown.contains("## User · 2023-11-14 22:13:20 UTC"));
+        assert!(markdown.contains("## Assistant · 2023-11-14 22:14:20 UTC"));
+        assert!(markdown.contains("```sql\nALTER TABLE users ADD COLUMN age INT;\n```"));
+    }
+
+    #[test]
+    fn test_markdown_closes_unterminated_fence() {
+        let (session, mut messages) = conversation();
+        messages[0].content = "```sql\nSELECT 1".to_string();
+
+        let markdown = to_markdown(&session, &messages);
+        assert_eq!(count_fences(&markdown) % 2, 0);
+    }
+
+    #[test]
+    fn test_json_includes_code_blocks() {
+        let (session, messages) = conversation();
+        let value: serde_json::Value = serde_json::from_str(&to_json(&session, &messages).unwrap()).unwrap();
+
+        assert_eq!(value["name"], "Add age column");
+        assert_eq!(value["messages"][1]["timestamp"], "2023-11-14 22:14:20 UTC");
+        assert_eq!(
+            value["messages"][1]["code_blocks"],
+            serde_json::json!([{ "language": "sql", "code": "ALTER TABLE users ADD COLUMN age INT;" }])
+        );
+        assert_eq!(value["messages"][0]["code_blocks"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_extract_unterminated_block() {
+        let blocks = extract_code_blocks("text\n```\nSELECT 1\nFROM t");
+        assert_eq!(blocks, vec![CodeBlock { language: None, code: "SELECT 1\nFROM t".to_string() }]);
+    }
+}
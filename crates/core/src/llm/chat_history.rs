@@ -16,6 +16,8 @@ pub struct ChatSession {
     pub provider_id: String,
     pub created_at: i64,
     pub updated_at: i64,
+    /// Last message of the branch currently shown for this session
+    pub active_leaf_id: Option<i64>,
 }
 
 impl crate::storage::traits::Entity for ChatSession {
@@ -41,6 +43,7 @@ impl ChatSession {
             provider_id,
             created_at: now,
             updated_at: now,
+            active_leaf_id: None,
         }
     }
 }
@@ -49,6 +52,8 @@ impl ChatSession {
 pub struct ChatMessage {
     pub id: i64,
     pub session_id: i64,
+    /// Previous message in the conversation tree; `None` for the first message
+    pub parent_id: Option<i64>,
     pub role: String,
     pub content: String,
     pub created_at: i64,
@@ -73,6 +78,7 @@ impl ChatMessage {
         Self {
             id: 0,
             session_id,
+            parent_id: None,
             role,
             content,
             created_at: now(),
@@ -92,6 +98,20 @@ impl ChatMessage {
     }
 }
 
+/// Full-text search hit in chat history
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ChatSearchResult {
+    pub message_id: i64,
+    pub session_id: i64,
+    pub session_name: String,
+    pub role: String,
+    pub snippet: String,
+    pub created_at: i64,
+}
+
+/// The trigram tokenizer cannot match queries shorter than three characters
+const FTS_MIN_QUERY_CHARS: usize = 3;
+
 #[derive(Clone)]
 pub struct SessionRepository {
     pool: SqlitePool,
@@ -228,11 +248,12 @@ impl Repository for MessageRepository {
     async fn insert(&self, item: &mut Self::Entity) -> Result<i64> {
         let result = sqlx::query(
             r#"
-            INSERT INTO chat_messages (session_id, role, content, created_at)
-            VALUES (?, ?, ?, ?)
+            INSERT INTO chat_messages (session_id, parent_id, role, content, created_at)
+            VALUES (?, ?, ?, ?, ?)
             "#,
         )
         .bind(item.session_id)
+        .bind(item.parent_id)
         .bind(&item.role)
         .bind(&item.content)
         .bind(item.created_at)
@@ -241,6 +262,8 @@ impl Repository for MessageRepository {
 
         let id = result.last_insert_rowid();
         item.id = id;
+        // A new message always becomes the tip of the active branch
+        self.set_active_leaf(item.session_id, Some(id)).await?;
         Ok(id)
     }
 
@@ -309,8 +332,30 @@ impl Repository for MessageRepository {
 }
 
 impl MessageRepository {
+    /// Messages on the session's active branch, oldest first
     pub async fn list_by_session(&self, session_id: i64) -> Result<Vec<ChatMessage>> {
-        let rows: Vec<ChatMessage> = sqlx::query_as("SELECT * FROM chat_messages WHERE session_id = ? ORDER BY created_at ASC")
+        let rows: Vec<ChatMessage> = sqlx::query_as(
+            r#"
+            WITH RECURSIVE branch(id) AS (
+                SELECT active_leaf_id FROM chat_sessions WHERE id = ?
+                UNION ALL
+                SELECT m.parent_id FROM chat_messages m JOIN branch ON m.id = branch.id
+                WHERE m.parent_id IS NOT NULL
+            )
+            SELECT m.* FROM chat_messages m JOIN branch ON m.id = branch.id
+            ORDER BY m.id ASC
+            "#,
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Every message of the session across all branches
+    pub async fn list_all_by_session(&self, session_id: i64) -> Result<Vec<ChatMessage>> {
+        let rows: Vec<ChatMessage> = sqlx::query_as("SELECT * FROM chat_messages WHERE session_id = ? ORDER BY id ASC")
             .bind(session_id)
             .fetch_all(&self.pool)
             .await?;
@@ -318,6 +363,42 @@ impl MessageRepository {
         Ok(rows)
     }
 
+    /// Insert a message as the child of the session's active leaf
+    pub async fn append(&self, item: &mut ChatMessage) -> Result<i64> {
+        let (leaf,): (Option<i64>,) = sqlx::query_as("SELECT active_leaf_id FROM chat_sessions WHERE id = ?")
+            .bind(item.session_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .unwrap_or((None,));
+        item.parent_id = leaf;
+        self.insert(item).await
+    }
+
+    /// Move the active branch tip, e.g. back to a user message before regenerating its reply.
+    /// `None` makes the next appended message a new root.
+    pub async fn set_active_leaf(&self, session_id: i64, message_id: Option<i64>) -> Result<()> {
+        sqlx::query("UPDATE chat_sessions SET active_leaf_id = ? WHERE id = ?")
+            .bind(message_id)
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Make the branch through `message_id` active, following its most recent
+    /// descendants down to a leaf, and return the new active branch
+    pub async fn switch_branch(&self, session_id: i64, message_id: i64) -> Result<Vec<ChatMessage>> {
+        let all = self.list_all_by_session(session_id).await?;
+        if !all.iter().any(|m| m.id == message_id) {
+            anyhow::bail!("Message {} not found in session {}", message_id, session_id);
+        }
+
+        let leaf = latest_leaf(&all, message_id);
+        self.set_active_leaf(session_id, Some(leaf)).await?;
+        self.list_by_session(session_id).await
+    }
+
     pub async fn list_recent(&self, limit: i32) -> Result<Vec<ChatMessage>> {
         let rows: Vec<ChatMessage> = sqlx::query_as("SELECT * FROM chat_messages ORDER BY created_at DESC LIMIT ?")
             .bind(limit)
@@ -336,10 +417,173 @@ impl MessageRepository {
         Ok(())
     }
 
-    pub fn to_llm_message(chat_message: &ChatMessage) -> LlmChatMessage {
-        LlmChatMessage {
-            role: chat_message.role.clone(),
-            content: chat_message.content.clone(),
+    /// Full-text search over all chat messages, best matches first
+    pub async fn search(&self, query: &str, limit: i32) -> Result<Vec<ChatSearchResult>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if query.chars().count() < FTS_MIN_QUERY_CHARS {
+            let pattern = format!("%{}%", query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+            let rows: Vec<ChatSearchResult> = sqlx::query_as(
+                r#"
+                SELECT m.id AS message_id, m.session_id, s.name AS session_name, m.role,
+                       substr(m.content, 1, 120) AS snippet, m.created_at
+                FROM chat_messages m
+                JOIN chat_sessions s ON s.id = m.session_id
+                WHERE m.content LIKE ? ESCAPE '\'
+                ORDER BY m.created_at DESC
+                LIMIT ?
+                "#,
+            )
+            .bind(pattern)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+            return Ok(rows);
         }
+
+        // Quote as a single phrase so user input is never parsed as FTS syntax
+        let phrase = format!("\"{}\"", query.replace('"', "\"\""));
+        let rows: Vec<ChatSearchResult> = sqlx::query_as(
+            r#"
+            SELECT m.id AS message_id, m.session_id, s.name AS session_name, m.role,
+                   snippet(chat_messages_fts, 0, '', '', '…', 32) AS snippet, m.created_at
+            FROM chat_messages_fts
+            JOIN chat_messages m ON m.id = chat_messages_fts.rowid
+            JOIN chat_sessions s ON s.id = m.session_id
+            WHERE chat_messages_fts MATCH ?
+            ORDER BY rank
+            LIMIT ?
+            "#,
+        )
+        .bind(phrase)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    pub fn to_llm_message(chat_message: &ChatMessage) -> LlmChatMessage {
+        LlmChatMessage::new(chat_message.role.clone(), chat_message.content.clone())
+    }
+}
+
+/// Ids of the alternative versions of `message` (messages sharing its parent), oldest first
+pub fn sibling_ids(all: &[ChatMessage], message: &ChatMessage) -> Vec<i64> {
+    all.iter()
+        .filter(|m| m.session_id == message.session_id && m.parent_id == message.parent_id)
+        .map(|m| m.id)
+        .collect()
+}
+
+fn latest_leaf(all: &[ChatMessage], message_id: i64) -> i64 {
+    let mut current = message_id;
+    while let Some(child) = all.iter().filter(|m| m.parent_id == Some(current)).map(|m| m.id).max() {
+        current = child;
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::run_migrations;
+
+    async fn setup() -> (SessionRepository, MessageRepository) {
+        // Each in-memory connection is a separate database, so keep a single one
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        (SessionRepository::new(pool.clone()), MessageRepository::new(pool))
+    }
+
+    #[tokio::test]
+    async fn test_search_finds_messages_across_sessions() {
+        let (sessions, messages) = setup().await;
+        let mut session = ChatSession::new("schema changes".to_string(), "1".to_string());
+        sessions.insert(&mut session).await.unwrap();
+
+        let mut answer = ChatMessage::assistant(session.id, "Try ALTER TABLE users ADD COLUMN age INT".to_string());
+        messages.insert(&mut answer).await.unwrap();
+        let mut other = ChatMessage::user(session.id, "show me the slow queries".to_string());
+        messages.insert(&mut other).await.unwrap();
+
+        let results = messages.search("alter table", 10).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message_id, answer.id);
+        assert_eq!(results[0].session_name, "schema changes");
+    }
+
+    #[tokio::test]
+    async fn test_search_index_follows_updates_and_deletes() {
+        let (sessions, messages) = setup().await;
+        let mut session = ChatSession::new("s".to_string(), "1".to_string());
+        sessions.insert(&mut session).await.unwrap();
+
+        let mut message = ChatMessage::assistant(session.id, "CREATE INDEX idx_a".to_string());
+        messages.insert(&mut message).await.unwrap();
+        message.content = "DROP INDEX idx_a".to_string();
+        messages.update(&message).await.unwrap();
+
+        assert!(messages.search("CREATE INDEX", 10).await.unwrap().is_empty());
+        assert_eq!(messages.search("DROP INDEX", 10).await.unwrap().len(), 1);
+
+        messages.delete_by_session(session.id).await.unwrap();
+        assert!(messages.search("DROP INDEX", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_short_query_and_fts_syntax_are_literal() {
+        let (sessions, messages) = setup().await;
+        let mut session = ChatSession::new("s".to_string(), "1".to_string());
+        sessions.insert(&mut session).await.unwrap();
+        let mut message = ChatMessage::user(session.id, "用 \"OR\" 连接条件".to_string());
+        messages.insert(&mut message).await.unwrap();
+
+        assert_eq!(messages.search("条件", 10).await.unwrap().len(), 1);
+        assert_eq!(messages.search("\"OR\" 连接", 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_regenerate_and_edit_create_branches() {
+        let (sessions, messages) = setup().await;
+        let mut session = ChatSession::new("s".to_string(), "1".to_string());
+        sessions.insert(&mut session).await.unwrap();
+
+        let mut question = ChatMessage::user(session.id, "q1".to_string());
+        messages.append(&mut question).await.unwrap();
+        let mut answer = ChatMessage::assistant(session.id, "a1".to_string());
+        messages.append(&mut answer).await.unwrap();
+
+        // Regenerate: rewind to the question and append another answer
+        messages.set_active_leaf(session.id, Some(question.id)).await.unwrap();
+        let mut regenerated = ChatMessage::assistant(session.id, "a2".to_string());
+        messages.append(&mut regenerated).await.unwrap();
+
+        let branch = messages.list_by_session(session.id).await.unwrap();
+        let contents: Vec<_> = branch.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["q1", "a2"]);
+
+        let all = messages.list_all_by_session(session.id).await.unwrap();
+        assert_eq!(sibling_ids(&all, &regenerated), vec![answer.id, regenerated.id]);
+
+        // Edit the first message: a new root next to the original question
+        messages.set_active_leaf(session.id, None).await.unwrap();
+        let mut edited = ChatMessage::user(session.id, "q1 edited".to_string());
+        messages.append(&mut edited).await.unwrap();
+        assert_eq!(edited.parent_id, None);
+        assert_eq!(messages.list_by_session(session.id).await.unwrap().len(), 1);
+
+        // Switching back to the original question restores its latest answer
+        let branch = messages.switch_branch(session.id, question.id).await.unwrap();
+        let contents: Vec<_> = branch.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["q1", "a2"]);
     }
 }
@@ -12,7 +12,10 @@ use gpui::http_client::{AsyncBody, HttpClient, Request};
 use http_body_util::BodyExt;
 use serde::{Deserialize, Serialize};
 
-use super::types::{ChatRequest, ChatResponse, ChatStreamChunk, ChatStreamEvent, ProviderConfig, Usage};
+use super::types::{
+    ApiKeyError, ChatMessage, ChatRequest, ChatResponse, ChatStreamChunk, ChatStreamEvent, ProviderConfig, ToolCall,
+    ToolDefinition, Usage,
+};
 
 /// Claude API client
 pub struct ClaudeClient {
@@ -35,27 +38,22 @@ impl ClaudeClient {
     ) -> Result<ChatResponse> {
         let url = format!("{}/messages", api_base);
         
-        // Separate system message from other messages
-        let mut system_message: Option<String> = None;
-        let mut claude_messages = Vec::new();
-
-        for msg in request.messages {
-            if msg.role == "system" {
-                system_message = Some(msg.content);
-            } else {
-                claude_messages.push(ClaudeMessage {
-                    role: msg.role,
-                    content: msg.content,
-                });
-            }
-        }
+        let (system_message, claude_messages) = to_claude_messages(request.messages);
 
+        // 开启扩展思考时，工具调用的后续请求必须回传 thinking 块，这里不保留思考内容，所以带工具时关闭思考
+        let thinking = ClaudeThinking::from_config(config).filter(|_| request.tools.is_empty());
+        let tools: Vec<ClaudeTool> = request.tools.iter().map(ClaudeTool::from).collect();
         let claude_request = ClaudeChatRequest {
             model: config.model.clone(),
             messages: claude_messages,
-            max_tokens: request.max_tokens.or(config.max_tokens).or(Some(4096)),
-            temperature: request.temperature.or(config.temperature),
+            max_tokens: thinking_max_tokens(request.max_tokens.or(config.max_tokens).or(Some(4096)), thinking.as_ref()),
+            // Claude rejects custom temperature/top_p when extended thinking is enabled
+            temperature: request.temperature.or(config.temperature).filter(|_| thinking.is_none()),
+            top_p: request.top_p.filter(|_| thinking.is_none()),
+            stop_sequences: request.stop.filter(|stop| !stop.is_empty()),
             system: system_message,
+            thinking,
+            tools,
         };
 
         let body_json = serde_json::to_vec(&claude_request)?;
@@ -83,11 +81,28 @@ impl ClaudeClient {
         
         let claude_response: ClaudeChatResponse = serde_json::from_slice(&bytes)?;
 
+        let tool_calls: Vec<ToolCall> = claude_response
+            .content
+            .iter()
+            .filter(|c| c.block_type == "tool_use")
+            .map(|c| ToolCall {
+                id: c.id.clone().unwrap_or_default(),
+                name: c.name.clone().unwrap_or_default(),
+                arguments: c.input.clone().unwrap_or_else(|| serde_json::Value::Object(Default::default())),
+            })
+            .collect();
+
+        // With extended thinking the first blocks are `thinking` blocks, so pick the text block
         let content = claude_response
             .content
-            .first()
-            .map(|c| c.text.clone())
-            .ok_or_else(|| anyhow::anyhow!("No response from Claude"))?;
+            .iter()
+            .find(|c| c.block_type == "text")
+            .and_then(|c| c.text.clone());
+        let content = match content {
+            Some(content) => content,
+            None if !tool_calls.is_empty() => String::new(),
+            None => anyhow::bail!("No response from Claude"),
+        };
 
         let usage = Usage {
             prompt_tokens: claude_response.usage.input_tokens,
@@ -99,6 +114,7 @@ impl ClaudeClient {
             content,
             model: claude_response.model,
             usage: Some(usage),
+            tool_calls,
         })
     }
 
@@ -112,27 +128,18 @@ impl ClaudeClient {
     ) -> Result<Pin<Box<dyn Stream<Item = ChatStreamEvent> + Send>>> {
         let url = format!("{}/messages", api_base);
         
-        // Separate system message from other messages
-        let mut system_message: Option<String> = None;
-        let mut claude_messages = Vec::new();
-
-        for msg in request.messages {
-            if msg.role == "system" {
-                system_message = Some(msg.content);
-            } else {
-                claude_messages.push(ClaudeMessage {
-                    role: msg.role,
-                    content: msg.content,
-                });
-            }
-        }
+        let (system_message, claude_messages) = to_claude_messages(request.messages);
 
+        let thinking = ClaudeThinking::from_config(config);
         let claude_request = ClaudeStreamRequest {
             model: config.model.clone(),
             messages: claude_messages,
-            max_tokens: request.max_tokens.or(config.max_tokens).or(Some(4096)),
-            temperature: request.temperature.or(config.temperature),
+            max_tokens: thinking_max_tokens(request.max_tokens.or(config.max_tokens).or(Some(4096)), thinking.as_ref()),
+            temperature: request.temperature.or(config.temperature).filter(|_| thinking.is_none()),
+            top_p: request.top_p.filter(|_| thinking.is_none()),
+            stop_sequences: request.stop.filter(|stop| !stop.is_empty()),
             system: system_message,
+            thinking,
             stream: true,
         };
 
@@ -181,11 +188,15 @@ impl ClaudeClient {
             model: model.to_string(),
             messages: vec![ClaudeMessage {
                 role: "user".to_string(),
-                content: "Hi".to_string(),
+                content: ClaudeContent::Text("Hi".to_string()),
             }],
             max_tokens: Some(10),
             temperature: None,
+            top_p: None,
+            stop_sequences: None,
             system: None,
+            thinking: None,
+            tools: Vec::new(),
         };
 
         let body_json = serde_json::to_vec(&test_request)?;
@@ -201,6 +212,32 @@ impl ClaudeClient {
         let response = self.client.send(req).await?;
         Ok(response.status().is_success())
     }
+
+    /// Verify the API key by listing models
+    pub async fn verify_api_key(&self, api_base: &str, api_key: &str) -> std::result::Result<(), ApiKeyError> {
+        let url = format!("{}/models", api_base);
+
+        let req = Request::builder()
+            .method("GET")
+            .uri(&url)
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .body(AsyncBody::default())
+            .map_err(|e| ApiKeyError::Other(e.to_string()))?;
+
+        let response = self
+            .client
+            .send(req)
+            .await
+            .map_err(|e| ApiKeyError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(ApiKeyError::from_status(status.as_u16(), status.to_string()))
+        }
+    }
 }
 
 // Claude-specific types
@@ -214,13 +251,112 @@ struct ClaudeChatRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ClaudeThinking>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<ClaudeTool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize)]
 struct ClaudeMessage {
     role: String,
-    content: String,
+    content: ClaudeContent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ClaudeContent {
+    Text(String),
+    Blocks(Vec<ClaudeContentBlock>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClaudeContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct ClaudeTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+impl From<&ToolDefinition> for ClaudeTool {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            input_schema: tool.parameters.clone(),
+        }
+    }
+}
+
+/// Split out the system prompt and convert the rest to Claude messages.
+/// Tool calls become `tool_use` blocks; tool results are sent as `tool_result` blocks in a user turn,
+/// with consecutive results merged because Claude expects them in a single message
+fn to_claude_messages(messages: Vec<ChatMessage>) -> (Option<String>, Vec<ClaudeMessage>) {
+    let mut system_message: Option<String> = None;
+    let mut claude_messages: Vec<ClaudeMessage> = Vec::new();
+
+    for msg in messages {
+        if msg.role == "system" {
+            system_message = Some(msg.content);
+        } else if let Some(tool_use_id) = msg.tool_call_id {
+            let block = ClaudeContentBlock::ToolResult {
+                tool_use_id,
+                content: msg.content,
+            };
+            match claude_messages.last_mut() {
+                Some(ClaudeMessage {
+                    role,
+                    content: ClaudeContent::Blocks(blocks),
+                }) if role == "user" => blocks.push(block),
+                _ => claude_messages.push(ClaudeMessage {
+                    role: "user".to_string(),
+                    content: ClaudeContent::Blocks(vec![block]),
+                }),
+            }
+        } else if !msg.tool_calls.is_empty() {
+            let mut blocks = Vec::new();
+            if !msg.content.is_empty() {
+                blocks.push(ClaudeContentBlock::Text { text: msg.content });
+            }
+            blocks.extend(msg.tool_calls.into_iter().map(|call| ClaudeContentBlock::ToolUse {
+                id: call.id,
+                name: call.name,
+                input: call.arguments,
+            }));
+            claude_messages.push(ClaudeMessage {
+                role: msg.role,
+                content: ClaudeContent::Blocks(blocks),
+            });
+        } else {
+            claude_messages.push(ClaudeMessage {
+                role: msg.role,
+                content: ClaudeContent::Text(msg.content),
+            });
+        }
+    }
+
+    (system_message, claude_messages)
 }
 
 #[derive(Debug, Deserialize)]
@@ -232,7 +368,67 @@ struct ClaudeChatResponse {
 
 #[derive(Debug, Deserialize)]
 struct ContentBlock {
-    text: String,
+    #[serde(rename = "type", default = "default_block_type")]
+    block_type: String,
+    #[serde(default)]
+    text: Option<String>,
+    /// `tool_use` blocks carry the call id, tool name and JSON input
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    input: Option<serde_json::Value>,
+}
+
+fn default_block_type() -> String {
+    "text".to_string()
+}
+
+/// Default thinking budget when extended thinking is enabled without an explicit budget
+const DEFAULT_THINKING_BUDGET: i32 = 4096;
+/// Minimum budget accepted by the Claude API
+const MIN_THINKING_BUDGET: i32 = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+struct ClaudeThinking {
+    #[serde(rename = "type")]
+    thinking_type: &'static str,
+    budget_tokens: i32,
+}
+
+impl ClaudeThinking {
+    fn from_config(config: &ProviderConfig) -> Option<Self> {
+        if !config.extended_thinking || !supports_extended_thinking(&config.model) {
+            return None;
+        }
+        let budget_tokens = config
+            .thinking_budget_tokens
+            .unwrap_or(DEFAULT_THINKING_BUDGET)
+            .max(MIN_THINKING_BUDGET);
+        Some(Self {
+            thinking_type: "enabled",
+            budget_tokens,
+        })
+    }
+}
+
+/// Extended thinking is only available on Claude 4.x (and 3.7 Sonnet) models
+fn supports_extended_thinking(model: &str) -> bool {
+    model.starts_with("claude-3-7")
+        || model.starts_with("claude-opus-4")
+        || model.starts_with("claude-sonnet-4")
+        || model.starts_with("claude-haiku-4")
+}
+
+/// `max_tokens` must be strictly greater than the thinking budget
+fn thinking_max_tokens(max_tokens: Option<i32>, thinking: Option<&ClaudeThinking>) -> Option<i32> {
+    match (max_tokens, thinking) {
+        (Some(max_tokens), Some(thinking)) if max_tokens <= thinking.budget_tokens => {
+            Some(thinking.budget_tokens + max_tokens)
+        }
+        (max_tokens, _) => max_tokens,
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -250,7 +446,13 @@ struct ClaudeStreamRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ClaudeThinking>,
     stream: bool,
 }
 
@@ -268,6 +470,8 @@ struct StreamEvent {
 struct StreamDelta {
     #[serde(default)]
     text: Option<String>,
+    #[serde(default)]
+    thinking: Option<String>,
 }
 
 fn parse_claude_sse_events(text: &str) -> Vec<ChatStreamEvent> {
@@ -279,14 +483,19 @@ fn parse_claude_sse_events(text: &str) -> Vec<ChatStreamEvent> {
                 Ok(event) => {
                     match event.event_type.as_str() {
                         "content_block_delta" => {
-                            if let Some(delta) = event.delta
-                                && let Some(text) = delta.text
+                            if let Some(delta) = event.delta {
+                                if let Some(thinking) = delta.thinking
+                                    && !thinking.is_empty() {
+                                        events.push(ChatStreamEvent::Thinking(thinking));
+                                    }
+                                if let Some(text) = delta.text
                                     && !text.is_empty() {
                                         events.push(ChatStreamEvent::Chunk(ChatStreamChunk {
                                             delta: text,
                                             finish_reason: None,
                                         }));
                                     }
+                            }
                         }
                         "message_delta" => {
                             let usage = event.usage.map(|u| Usage {
@@ -314,3 +523,34 @@ fn parse_claude_sse_events(text: &str) -> Vec<ChatStreamEvent> {
 
     events
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_messages_use_content_blocks() {
+        let call = ToolCall {
+            id: "toolu_1".to_string(),
+            name: "run_select".to_string(),
+            arguments: serde_json::json!({ "sql": "SELECT 1" }),
+        };
+        let messages = vec![
+            ChatMessage::system("prompt"),
+            ChatMessage::user("how many orders?"),
+            ChatMessage::assistant_tool_calls("", vec![call]),
+            ChatMessage::tool_result("toolu_1", "1"),
+            ChatMessage::tool_result("toolu_2", "2"),
+        ];
+
+        let (system, messages) = to_claude_messages(messages);
+        assert_eq!(system.as_deref(), Some("prompt"));
+        let json = serde_json::to_value(&messages).unwrap();
+        assert_eq!(json[0]["content"], "how many orders?");
+        assert_eq!(json[1]["content"][0]["type"], "tool_use");
+        assert_eq!(json[1]["content"][0]["input"]["sql"], "SELECT 1");
+        assert_eq!(json[2]["role"], "user");
+        assert_eq!(json[2]["content"].as_array().map(Vec::len), Some(2));
+        assert_eq!(json[2]["content"][1]["tool_use_id"], "toolu_2");
+    }
+}
@@ -6,6 +6,7 @@ pub mod openai_compat;
 pub mod openai_client;
 pub mod claude_client;
 pub mod chat_history;
+pub mod chat_export;
 
 pub use manager::register_provider;
 
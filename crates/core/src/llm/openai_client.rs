@@ -12,8 +12,11 @@ use gpui::http_client::{AsyncBody, HttpClient, Request};
 use http_body_util::BodyExt;
 use serde::Serialize;
 
-use super::openai_compat::{OpenAICompatRequest, OpenAICompatResponse, parse_sse_events};
-use super::types::{ChatRequest, ChatResponse, ChatStreamEvent, ProviderConfig};
+use super::openai_compat::{
+    OpenAICompatEmbeddingRequest, OpenAICompatEmbeddingResponse, OpenAICompatRequest, OpenAICompatResponse,
+    parse_sse_events,
+};
+use super::types::{ApiKeyError, ChatRequest, ChatResponse, ChatStreamEvent, ProviderConfig};
 
 /// OpenAI-compatible API client
 pub struct OpenAIClient {
@@ -92,6 +95,25 @@ impl OpenAIClient {
         Ok(Box::pin(event_stream))
     }
 
+    /// Embed `inputs` with the given embedding model
+    pub async fn embeddings(
+        &self,
+        api_base: &str,
+        api_key: &str,
+        model: &str,
+        inputs: Vec<String>,
+    ) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/embeddings", api_base);
+        let request = OpenAICompatEmbeddingRequest {
+            model: model.to_string(),
+            input: inputs,
+        };
+
+        let response = self.post_json(&url, api_key, &request).await?;
+        let embedding_response: OpenAICompatEmbeddingResponse = serde_json::from_slice(&response)?;
+        Ok(embedding_response.into_vectors())
+    }
+
     /// Test connection by listing models
     pub async fn test_connection(&self, api_base: &str, api_key: &str) -> Result<bool> {
         let url = format!("{}/models", api_base);
@@ -106,6 +128,31 @@ impl OpenAIClient {
         Ok(response.status().is_success())
     }
 
+    /// Verify the API key by listing models
+    pub async fn verify_api_key(&self, api_base: &str, api_key: &str) -> std::result::Result<(), ApiKeyError> {
+        let url = format!("{}/models", api_base);
+
+        let req = Request::builder()
+            .method("GET")
+            .uri(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .body(AsyncBody::default())
+            .map_err(|e| ApiKeyError::Other(e.to_string()))?;
+
+        let response = self
+            .client
+            .send(req)
+            .await
+            .map_err(|e| ApiKeyError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(ApiKeyError::from_status(status.as_u16(), status.to_string()))
+        }
+    }
+
     /// Helper method to send POST request with JSON body
     async fn post_json<T: Serialize>(&self, url: &str, api_key: &str, body: &T) -> Result<Vec<u8>> {
         let body_json = serde_json::to_vec(body)?;
@@ -11,34 +11,184 @@ use serde::{Deserialize, Serialize};
 
 use super::types::{
     ChatMessage, ChatRequest, ChatResponse, ChatStreamChunk, ChatStreamEvent,
-    ProviderConfig, Usage,
+    ProviderConfig, ToolCall, ToolDefinition, Usage,
 };
 
 /// OpenAI-compatible chat request
 #[derive(Debug, Serialize)]
 pub struct OpenAICompatRequest {
     pub model: String,
-    pub messages: Vec<ChatMessage>,
+    pub messages: Vec<OpenAICompatMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<OpenAICompatTool>,
 }
 
 impl OpenAICompatRequest {
     pub fn from_chat_request(request: ChatRequest, config: &ProviderConfig, stream: bool) -> Self {
+        // 流式响应不解析工具调用，只在非流式请求中提供工具
+        let tools = if stream {
+            Vec::new()
+        } else {
+            request.tools.iter().map(OpenAICompatTool::from).collect()
+        };
         Self {
             model: config.model.clone(),
-            messages: request.messages,
+            messages: request.messages.into_iter().map(Into::into).collect(),
             max_tokens: request.max_tokens.or(config.max_tokens),
             temperature: request.temperature.or(config.temperature),
+            top_p: request.top_p,
+            stop: request.stop.filter(|stop| !stop.is_empty()),
+            frequency_penalty: request.frequency_penalty,
             stream: Some(stream),
+            tools,
+        }
+    }
+}
+
+/// OpenAI-compatible message. `content` is null for assistant turns that only call tools
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAICompatMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<OpenAICompatToolCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl From<ChatMessage> for OpenAICompatMessage {
+    fn from(message: ChatMessage) -> Self {
+        let content = if message.content.is_empty() && !message.tool_calls.is_empty() {
+            None
+        } else {
+            Some(message.content)
+        };
+        Self {
+            role: message.role,
+            content,
+            tool_calls: message.tool_calls.into_iter().map(Into::into).collect(),
+            tool_call_id: message.tool_call_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAICompatToolCall {
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub call_type: String,
+    pub function: OpenAICompatFunctionCall,
+}
+
+/// `arguments` is a JSON-encoded string in the OpenAI wire format
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OpenAICompatFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+impl From<ToolCall> for OpenAICompatToolCall {
+    fn from(call: ToolCall) -> Self {
+        Self {
+            id: call.id,
+            call_type: function_type(),
+            function: OpenAICompatFunctionCall {
+                name: call.name,
+                arguments: call.arguments.to_string(),
+            },
+        }
+    }
+}
+
+impl From<OpenAICompatToolCall> for ToolCall {
+    fn from(call: OpenAICompatToolCall) -> Self {
+        // 参数不是合法 JSON 时原样保留为字符串，由调用方把错误反馈给模型
+        let arguments = if call.function.arguments.trim().is_empty() {
+            serde_json::Value::Object(Default::default())
+        } else {
+            serde_json::from_str(&call.function.arguments)
+                .unwrap_or(serde_json::Value::String(call.function.arguments))
+        };
+        Self {
+            id: call.id,
+            name: call.function.name,
+            arguments,
         }
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct OpenAICompatTool {
+    #[serde(rename = "type")]
+    pub tool_type: &'static str,
+    pub function: OpenAICompatFunction,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OpenAICompatFunction {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+impl From<&ToolDefinition> for OpenAICompatTool {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            tool_type: "function",
+            function: OpenAICompatFunction {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.parameters.clone(),
+            },
+        }
+    }
+}
+
+/// OpenAI-compatible embeddings request
+#[derive(Debug, Serialize)]
+pub struct OpenAICompatEmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+/// OpenAI-compatible embeddings response
+#[derive(Debug, Deserialize)]
+pub struct OpenAICompatEmbeddingResponse {
+    pub data: Vec<OpenAICompatEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAICompatEmbedding {
+    pub index: usize,
+    pub embedding: Vec<f32>,
+}
+
+impl OpenAICompatEmbeddingResponse {
+    /// Vectors in input order; the API does not guarantee `data` is sorted
+    pub fn into_vectors(mut self) -> Vec<Vec<f32>> {
+        self.data.sort_by_key(|item| item.index);
+        self.data.into_iter().map(|item| item.embedding).collect()
+    }
+}
+
 /// OpenAI-compatible chat response
 #[derive(Debug, Deserialize)]
 pub struct OpenAICompatResponse {
@@ -53,7 +203,7 @@ pub struct OpenAICompatResponse {
 #[derive(Debug, Deserialize)]
 pub struct Choice {
     pub index: i32,
-    pub message: ChatMessage,
+    pub message: OpenAICompatMessage,
     pub finish_reason: Option<String>,
 }
 
@@ -76,16 +226,18 @@ impl From<OpenAICompatUsage> for Usage {
 
 impl OpenAICompatResponse {
     pub fn into_chat_response(self) -> Result<ChatResponse> {
-        let content = self
+        let message = self
             .choices
-            .first()
-            .map(|c| c.message.content.clone())
+            .into_iter()
+            .next()
+            .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response content"))?;
 
         Ok(ChatResponse {
-            content,
+            content: message.content.unwrap_or_default(),
             model: self.model,
             usage: self.usage.map(Into::into),
+            tool_calls: message.tool_calls.into_iter().map(Into::into).collect(),
         })
     }
 }
@@ -163,3 +315,53 @@ pub fn create_sse_stream(
 
     Box::pin(event_stream)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_call_round_trip() {
+        let call = ToolCall {
+            id: "call_1".to_string(),
+            name: "list_tables".to_string(),
+            arguments: serde_json::json!({ "database": "shop" }),
+        };
+        let message: OpenAICompatMessage = ChatMessage::assistant_tool_calls("", vec![call.clone()]).into();
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["content"], serde_json::Value::Null);
+        assert_eq!(json["tool_calls"][0]["type"], "function");
+        assert_eq!(json["tool_calls"][0]["function"]["arguments"], "{\"database\":\"shop\"}");
+
+        let response: OpenAICompatResponse = serde_json::from_value(serde_json::json!({
+            "id": "r1",
+            "object": "chat.completion",
+            "created": 0,
+            "model": "gpt-4o",
+            "choices": [{ "index": 0, "finish_reason": "tool_calls", "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{ "id": "call_1", "type": "function",
+                    "function": { "name": "list_tables", "arguments": "{\"database\":\"shop\"}" } }]
+            }}]
+        }))
+        .unwrap();
+        let response = response.into_chat_response().unwrap();
+        assert!(response.content.is_empty());
+        assert_eq!(response.tool_calls, vec![call]);
+    }
+
+    #[test]
+    fn test_embedding_response_is_sorted_by_index() {
+        let response: OpenAICompatEmbeddingResponse = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [
+                { "object": "embedding", "index": 1, "embedding": [0.5, 0.5] },
+                { "object": "embedding", "index": 0, "embedding": [1.0, 0.0] }
+            ],
+            "model": "text-embedding-3-small"
+        }))
+        .unwrap();
+        assert_eq!(response.into_vectors(), vec![vec![1.0, 0.0], vec![0.5, 0.5]]);
+    }
+}
@@ -4,7 +4,9 @@ use anyhow::Result;
 use async_trait::async_trait;
 use futures::Stream;
 
-use super::types::{ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig};
+use super::types::{
+    ApiKeyError, ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig,
+};
 
 /// LLM Provider trait - all providers must implement this interface
 #[async_trait]
@@ -27,6 +29,11 @@ pub trait LlmProvider: Send + Sync {
         request: ChatRequest,
     ) -> Result<Pin<Box<dyn Stream<Item = ChatStreamEvent> + Send>>>;
 
+    /// Embed each input into a vector with the provider's embedding model, in input order
+    async fn embed(&self, _inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        anyhow::bail!("{} does not support embeddings", self.config().provider_type.display_name())
+    }
+
     /// Validate API key format (optional, provider-specific)
     fn validate_api_key(&self, api_key: &str) -> Result<()> {
         if api_key.is_empty() {
@@ -35,6 +42,15 @@ pub trait LlmProvider: Send + Sync {
         Ok(())
     }
 
+    /// Verify the API key against the provider with a minimal authenticated call
+    async fn verify_api_key(&self) -> std::result::Result<(), ApiKeyError> {
+        match self.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(ApiKeyError::InvalidKey("Provider rejected the request".to_string())),
+            Err(e) => Err(ApiKeyError::Network(e.to_string())),
+        }
+    }
+
     /// Get default API base URL for this provider
     fn default_api_base(&self) -> Option<String> {
         None
@@ -44,4 +60,10 @@ pub trait LlmProvider: Send + Sync {
     fn default_models(&self) -> Vec<ModelInfo> {
         vec![]
     }
+
+    /// Metadata for the configured model, if known
+    fn model_info(&self) -> Option<ModelInfo> {
+        let model = &self.config().model;
+        self.default_models().into_iter().find(|info| &info.id == model)
+    }
 }
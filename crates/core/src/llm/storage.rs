@@ -6,6 +6,7 @@ use crate::gpui_tokio::Tokio;
 use crate::llm::chat_history::{MessageRepository, SessionRepository};
 use super::types::{ProviderConfig, ProviderType};
 use crate::storage::{now, GlobalStorageState};
+use crate::storage::secrets::{self, llm_provider_key, SharedSecretStore};
 use crate::storage::traits::{Entity, Repository};
 
 #[derive(FromRow)]
@@ -18,6 +19,8 @@ struct ProviderConfigRow {
     model: String,
     max_tokens: Option<i32>,
     temperature: Option<f32>,
+    extended_thinking: i32,
+    thinking_budget_tokens: Option<i32>,
     enabled: i32,
     created_at: i64,
     updated_at: i64,
@@ -39,6 +42,8 @@ impl TryFrom<ProviderConfigRow> for ProviderConfig {
             model: row.model,
             max_tokens: row.max_tokens,
             temperature: row.temperature,
+            extended_thinking: row.extended_thinking != 0,
+            thinking_budget_tokens: row.thinking_budget_tokens,
             enabled: row.enabled != 0,
             created_at: row.created_at,
             updated_at: row.updated_at,
@@ -49,11 +54,57 @@ impl TryFrom<ProviderConfigRow> for ProviderConfig {
 #[derive(Clone)]
 pub struct ProviderRepository {
     pool: SqlitePool,
+    secrets: SharedSecretStore,
 }
 
 impl ProviderRepository {
-    pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+    pub fn new(pool: SqlitePool, secrets: SharedSecretStore) -> Self {
+        Self { pool, secrets }
+    }
+
+    /// 数据库中保存的 API Key：写入钥匙串后保存 NULL，钥匙串不可用时仍保存在数据库中
+    async fn api_key_for_storage(&self, id: i64, api_key: &Option<String>) -> Option<String> {
+        let key = llm_provider_key(id);
+        let result = match api_key.as_deref().filter(|api_key| !api_key.is_empty()) {
+            Some(api_key) => secrets::save(&self.secrets, key, api_key.to_string()).await,
+            None => secrets::remove(&self.secrets, key).await,
+        };
+        match result {
+            Ok(()) => None,
+            Err(e) => {
+                tracing::warn!("Keychain unavailable, keeping the API key of provider {} in the database: {}", id, e);
+                api_key.clone()
+            }
+        }
+    }
+
+    async fn restore_api_key(&self, row: ProviderConfigRow) -> Result<ProviderConfig> {
+        let mut config: ProviderConfig = row.try_into()?;
+        if config.api_key.is_none() {
+            config.api_key = secrets::load(&self.secrets, llm_provider_key(config.id))
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read the API key of provider {} from keychain: {}", config.id, e);
+                    None
+                });
+        }
+        Ok(config)
+    }
+
+    /// 旧版本把 API Key 保存在数据库中，启动时移到钥匙串
+    pub async fn move_api_keys_to_keychain(&self) -> Result<()> {
+        let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, api_key FROM llm_providers WHERE api_key IS NOT NULL AND api_key != ''")
+            .fetch_all(&self.pool)
+            .await?;
+        for (id, api_key) in rows {
+            if self.api_key_for_storage(id, &Some(api_key)).await.is_none() {
+                sqlx::query("UPDATE llm_providers SET api_key = NULL WHERE id = ?")
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -80,22 +131,26 @@ impl Repository for ProviderRepository {
     }
 
     async fn insert(&self, item: &mut Self::Entity) -> Result<i64> {
+        let api_key = self.api_key_for_storage(item.id, &item.api_key).await;
         sqlx::query(
             r#"
             INSERT INTO llm_providers (
                 id, name, provider_type, api_key, api_base, model,
-                max_tokens, temperature, enabled, created_at, updated_at
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                max_tokens, temperature, extended_thinking, thinking_budget_tokens,
+                enabled, created_at, updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(item.id)
         .bind(&item.name)
         .bind(item.provider_type.as_str())
-        .bind(&item.api_key)
+        .bind(&api_key)
         .bind(&item.api_base)
         .bind(&item.model)
         .bind(item.max_tokens)
         .bind(item.temperature)
+        .bind(item.extended_thinking)
+        .bind(item.thinking_budget_tokens)
         .bind(item.enabled)
         .bind(item.created_at)
         .bind(item.updated_at)
@@ -107,6 +162,7 @@ impl Repository for ProviderRepository {
 
     async fn update(&self, item: &Self::Entity) -> Result<()> {
         let updated_at = now();
+        let api_key = self.api_key_for_storage(item.id, &item.api_key).await;
         sqlx::query(
             r#"
             UPDATE llm_providers SET
@@ -117,6 +173,8 @@ impl Repository for ProviderRepository {
                 model = ?,
                 max_tokens = ?,
                 temperature = ?,
+                extended_thinking = ?,
+                thinking_budget_tokens = ?,
                 enabled = ?,
                 updated_at = ?
             WHERE id = ?
@@ -124,11 +182,13 @@ impl Repository for ProviderRepository {
         )
         .bind(&item.name)
         .bind(item.provider_type.as_str())
-        .bind(&item.api_key)
+        .bind(&api_key)
         .bind(&item.api_base)
         .bind(&item.model)
         .bind(item.max_tokens)
         .bind(item.temperature)
+        .bind(item.extended_thinking)
+        .bind(item.thinking_budget_tokens)
         .bind(item.enabled)
         .bind(updated_at)
         .bind(item.id)
@@ -143,6 +203,9 @@ impl Repository for ProviderRepository {
             .bind(id)
             .execute(&self.pool)
             .await?;
+        if let Err(e) = secrets::remove(&self.secrets, llm_provider_key(id)).await {
+            tracing::warn!("Failed to delete the API key of provider {} from keychain: {}", id, e);
+        }
         Ok(())
     }
 
@@ -152,7 +215,10 @@ impl Repository for ProviderRepository {
             .fetch_optional(&self.pool)
             .await?;
 
-        row.map(TryInto::try_into).transpose()
+        match row {
+            Some(row) => Ok(Some(self.restore_api_key(row).await?)),
+            None => Ok(None),
+        }
     }
 
     async fn list(&self) -> Result<Vec<Self::Entity>> {
@@ -160,9 +226,11 @@ impl Repository for ProviderRepository {
             .fetch_all(&self.pool)
             .await?;
 
-        rows.into_iter()
-            .map(TryInto::try_into)
-            .collect::<Result<Vec<_>>>()
+        let mut configs = Vec::with_capacity(rows.len());
+        for row in rows {
+            configs.push(self.restore_api_key(row).await?);
+        }
+        Ok(configs)
     }
 
     async fn count(&self) -> Result<i64> {
@@ -188,7 +256,10 @@ pub fn init(cx: &mut App) {
     let result: Result<()> = Tokio::block_on(cx, async move {
         let pool = storage.get_pool().await?;
 
-        let provider_repo = ProviderRepository::new(pool.clone());
+        let provider_repo = ProviderRepository::new(pool.clone(), secrets::keychain());
+        if let Err(e) = provider_repo.move_api_keys_to_keychain().await {
+            tracing::warn!("Failed to move LLM API keys to the keychain: {}", e);
+        }
         let session_repo = SessionRepository::new(pool.clone());
         let message_repo = MessageRepository::new(pool);
 
@@ -70,6 +70,12 @@ pub struct ProviderConfig {
     pub model: String,
     pub max_tokens: Option<i32>,
     pub temperature: Option<f32>,
+    /// Enable Claude extended thinking (Claude 4.x models only)
+    #[serde(default)]
+    pub extended_thinking: bool,
+    /// Token budget for extended thinking, must be lower than `max_tokens`
+    #[serde(default)]
+    pub thinking_budget_tokens: Option<i32>,
     pub enabled: bool,
     pub created_at: i64,
     pub updated_at: i64
@@ -93,6 +99,8 @@ impl ProviderConfig {
             model,
             max_tokens: None,
             temperature: None,
+            extended_thinking: false,
+            thinking_budget_tokens: None,
             enabled: true,
             created_at: now,
             updated_at: now,
@@ -105,38 +113,97 @@ impl ProviderConfig {
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Tools the assistant asked to call in this message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// For `tool` messages, the id of the call this message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 impl ChatMessage {
-    pub fn user(content: impl Into<String>) -> Self {
+    pub fn new(role: impl Into<String>, content: impl Into<String>) -> Self {
         Self {
-            role: "user".to_string(),
+            role: role.into(),
             content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
         }
     }
 
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new("user", content)
+    }
+
     pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new("assistant", content)
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content)
+    }
+
+    /// Assistant turn that requested tool calls, to be followed by one `tool` message per call
+    pub fn assistant_tool_calls(content: impl Into<String>, tool_calls: Vec<ToolCall>) -> Self {
         Self {
-            role: "assistant".to_string(),
-            content: content.into(),
+            tool_calls,
+            ..Self::assistant(content)
         }
     }
 
-    pub fn system(content: impl Into<String>) -> Self {
+    /// Result of a tool call
+    pub fn tool_result(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
         Self {
-            role: "system".to_string(),
-            content: content.into(),
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new("tool", content)
+        }
+    }
+}
+
+/// Tool the model may call. `parameters` is a JSON Schema object describing the arguments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: serde_json::Value,
+}
+
+impl ToolDefinition {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, parameters: serde_json::Value) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            parameters,
         }
     }
 }
 
+/// Tool call requested by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    /// Arguments as a JSON object
+    pub arguments: serde_json::Value,
+}
+
 /// Chat completion request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatRequest {
     pub messages: Vec<ChatMessage>,
     pub max_tokens: Option<i32>,
     pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+    /// Ignored by providers that do not support it (e.g. Claude)
+    #[serde(default)]
+    pub frequency_penalty: Option<f32>,
     pub stream: bool,
+    /// Tools offered to the model; only honored by non-streaming `chat`
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
 }
 
 impl ChatRequest {
@@ -145,9 +212,49 @@ impl ChatRequest {
             messages,
             max_tokens: None,
             temperature: None,
+            top_p: None,
+            stop: None,
+            frequency_penalty: None,
             stream: false,
+            tools: Vec::new(),
         }
     }
+
+    pub fn with_tools(mut self, tools: Vec<ToolDefinition>) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Apply per-request generation parameter overrides
+    pub fn with_params(mut self, params: &GenerationParams) -> Self {
+        if params.max_tokens.is_some() {
+            self.max_tokens = params.max_tokens;
+        }
+        if params.temperature.is_some() {
+            self.temperature = params.temperature;
+        }
+        if params.top_p.is_some() {
+            self.top_p = params.top_p;
+        }
+        if !params.stop.is_empty() {
+            self.stop = Some(params.stop.clone());
+        }
+        if params.frequency_penalty.is_some() {
+            self.frequency_penalty = params.frequency_penalty;
+        }
+        self
+    }
+}
+
+/// Generation parameters chosen by the user for a single chat request.
+/// Unset fields fall back to the `ProviderConfig` defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    pub max_tokens: Option<i32>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub stop: Vec<String>,
+    pub frequency_penalty: Option<f32>,
 }
 
 /// Chat completion response
@@ -156,6 +263,9 @@ pub struct ChatResponse {
     pub content: String,
     pub model: String,
     pub usage: Option<Usage>,
+    /// Tool calls requested by the model; the caller runs them and continues the conversation
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
 }
 
 /// Token usage information
@@ -172,8 +282,133 @@ pub struct ModelInfo {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    /// Maximum number of tokens (prompt + completion) the model accepts
+    #[serde(default)]
+    pub context_window: Option<u32>,
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
+    #[serde(default)]
+    pub supports_vision: bool,
+    #[serde(default)]
+    pub supports_tools: bool,
+    #[serde(default)]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// Model pricing in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
 }
 
+impl ModelInfo {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            description: Some(description.into()),
+            context_window: None,
+            max_output_tokens: None,
+            supports_vision: false,
+            supports_tools: false,
+            pricing: None,
+        }
+    }
+
+    pub fn context_window(mut self, tokens: u32) -> Self {
+        self.context_window = Some(tokens);
+        self
+    }
+
+    pub fn max_output_tokens(mut self, tokens: u32) -> Self {
+        self.max_output_tokens = Some(tokens);
+        self
+    }
+
+    pub fn with_vision(mut self) -> Self {
+        self.supports_vision = true;
+        self
+    }
+
+    pub fn with_tools(mut self) -> Self {
+        self.supports_tools = true;
+        self
+    }
+
+    pub fn pricing(mut self, input_per_million: f64, output_per_million: f64) -> Self {
+        self.pricing = Some(ModelPricing {
+            input_per_million,
+            output_per_million,
+        });
+        self
+    }
+
+    /// Whether a prompt of `prompt_tokens` plus the reserved output still fits the context window.
+    /// Models without a known context window are assumed to fit.
+    pub fn fits_context(&self, prompt_tokens: u32, reserved_output_tokens: u32) -> bool {
+        match self.context_window {
+            Some(window) => prompt_tokens.saturating_add(reserved_output_tokens) <= window,
+            None => true,
+        }
+    }
+}
+
+/// Keep only the models usable with the requested capabilities
+pub fn filter_models(models: Vec<ModelInfo>, require_tools: bool, require_vision: bool) -> Vec<ModelInfo> {
+    models
+        .into_iter()
+        .filter(|model| !require_tools || model.supports_tools)
+        .filter(|model| !require_vision || model.supports_vision)
+        .collect()
+}
+
+/// Rough token estimate for chat messages (~4 characters per token plus per-message overhead)
+pub fn estimate_tokens(messages: &[ChatMessage]) -> u32 {
+    messages
+        .iter()
+        .map(|message| (message.content.chars().count() as u32).div_ceil(4) + 4)
+        .sum()
+}
+
+/// Failure reasons for live API key verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeyError {
+    /// The provider rejected the key (401/403)
+    InvalidKey(String),
+    /// The request never reached the provider or the connection failed
+    Network(String),
+    /// The key is valid but rate limited or out of credit (429/402)
+    Quota(String),
+    /// Any other unexpected response
+    Other(String),
+}
+
+impl ApiKeyError {
+    /// Classify a non-success HTTP status returned by a provider
+    pub fn from_status(status: u16, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match status {
+            401 | 403 => ApiKeyError::InvalidKey(message),
+            402 | 429 => ApiKeyError::Quota(message),
+            _ => ApiKeyError::Other(message),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyError::InvalidKey(msg) => write!(f, "Invalid API key: {}", msg),
+            ApiKeyError::Network(msg) => write!(f, "Network error: {}", msg),
+            ApiKeyError::Quota(msg) => write!(f, "Quota exceeded: {}", msg),
+            ApiKeyError::Other(msg) => write!(f, "Verification failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiKeyError {}
+
 /// Stream chunk for chat completion
 #[derive(Debug, Clone)]
 pub struct ChatStreamChunk {
@@ -186,8 +421,46 @@ pub struct ChatStreamChunk {
 pub enum ChatStreamEvent {
     /// Content chunk received
     Chunk(ChatStreamChunk),
+    /// Reasoning chunk received (e.g. Claude extended thinking)
+    Thinking(String),
     /// Stream completed with usage info
     Done(Option<Usage>),
     /// Error occurred
     Error(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fits_context() {
+        let model = ModelInfo::new("m", "M", "").context_window(1000);
+        assert!(model.fits_context(800, 200));
+        assert!(!model.fits_context(800, 201));
+        assert!(!model.fits_context(u32::MAX, 1));
+        assert!(ModelInfo::new("m", "M", "").fits_context(u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(&[]), 0);
+        // ~4 characters per token rounded up, plus 4 tokens of overhead per message
+        let messages = [ChatMessage::user("12345"), ChatMessage::new("assistant", "")];
+        assert_eq!(estimate_tokens(&messages), 2 + 4 + 4);
+    }
+
+    #[test]
+    fn test_filter_models() {
+        let models = vec![
+            ModelInfo::new("plain", "Plain", ""),
+            ModelInfo::new("tools", "Tools", "").with_tools(),
+            ModelInfo::new("both", "Both", "").with_tools().with_vision(),
+        ];
+        let ids = |models: Vec<ModelInfo>| models.into_iter().map(|model| model.id).collect::<Vec<_>>();
+
+        assert_eq!(ids(filter_models(models.clone(), false, false)), ["plain", "tools", "both"]);
+        assert_eq!(ids(filter_models(models.clone(), true, false)), ["tools", "both"]);
+        assert_eq!(ids(filter_models(models, true, true)), ["both"]);
+    }
+}
@@ -0,0 +1,505 @@
+//! 从其他工具导入连接 - 解析 DBeaver、Navicat (NCX) 和 DataGrip 的连接配置，生成待保存的连接
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockDecryptMut, KeyIvInit};
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::storage::models::{ConnectionEnvironment, DatabaseType, DbConnectionConfig, StoredConnection};
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+
+/// DBeaver 加密 credentials-config.json 使用的固定密钥
+const DBEAVER_CREDENTIALS_KEY: [u8; 16] = [
+    0xba, 0xbb, 0x4a, 0x9f, 0x77, 0x4a, 0xb8, 0x53, 0xc9, 0x6c, 0x2d, 0x65, 0x3d, 0xfe, 0x54, 0x4a,
+];
+/// Navicat 12 及以上版本加密 NCX 密码使用的固定密钥和 IV
+const NAVICAT_KEY: &[u8; 16] = b"libcckeylibcckey";
+const NAVICAT_IV: &[u8; 16] = b"libcciv libcciv ";
+
+/// 连接配置来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    DBeaver,
+    Navicat,
+    DataGrip,
+}
+
+impl ImportSource {
+    pub fn all() -> &'static [ImportSource] {
+        &[ImportSource::DBeaver, ImportSource::Navicat, ImportSource::DataGrip]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportSource::DBeaver => "DBeaver",
+            ImportSource::Navicat => "Navicat",
+            ImportSource::DataGrip => "DataGrip",
+        }
+    }
+
+    /// 选择文件时的提示，说明该工具的连接配置保存在哪个文件
+    pub fn file_hint(&self) -> &'static str {
+        match self {
+            ImportSource::DBeaver => "选择 DBeaver 的 data-sources.json",
+            ImportSource::Navicat => "选择 Navicat 导出的 .ncx 文件",
+            ImportSource::DataGrip => "选择 DataGrip 的 dataSources.xml",
+        }
+    }
+}
+
+/// 导入结果，无法识别的连接不会导入，只记录名称
+#[derive(Debug, Default)]
+pub struct ImportedConnections {
+    pub connections: Vec<StoredConnection>,
+    pub skipped: Vec<String>,
+}
+
+/// 读取工具的连接配置文件。DBeaver 的密码和 DataGrip 的用户名保存在同目录的另一个文件中，一并读取
+pub fn import_from_file(source: ImportSource, path: &Path) -> Result<ImportedConnections> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let sibling = |name: &str| path.parent().map(|dir| dir.join(name)).filter(|file| file.exists());
+
+    match source {
+        ImportSource::DBeaver => {
+            let credentials = sibling("credentials-config.json").and_then(|file| std::fs::read(file).ok());
+            import_dbeaver(&content, credentials.as_deref())
+        }
+        ImportSource::Navicat => import_navicat(&content),
+        ImportSource::DataGrip => {
+            let local = sibling("dataSources.local.xml").and_then(|file| std::fs::read_to_string(file).ok());
+            import_datagrip(&content, local.as_deref())
+        }
+    }
+}
+
+/// 解析 DBeaver 的 data-sources.json；credentials 为同目录加密的 credentials-config.json
+pub fn import_dbeaver(data_sources: &str, credentials: Option<&[u8]>) -> Result<ImportedConnections> {
+    let root: Value = serde_json::from_str(data_sources)
+        .map_err(|e| anyhow!("Invalid DBeaver data-sources.json: {}", e))?;
+    let connections = root
+        .get("connections")
+        .and_then(Value::as_object)
+        .ok_or_else(|| anyhow!("No connections found in DBeaver data-sources.json"))?;
+    let credentials = match credentials {
+        Some(data) => decrypt_dbeaver_credentials(data)?,
+        None => Value::Null,
+    };
+
+    let mut imported = ImportedConnections::default();
+    for (id, connection) in connections {
+        let text = |value: Option<&Value>| value.and_then(Value::as_str).unwrap_or_default().to_string();
+        let name = non_empty_or(text(connection.get("name")), id);
+        let configuration = connection.get("configuration").cloned().unwrap_or(Value::Null);
+        let url = text(configuration.get("url"));
+        let jdbc = parse_jdbc_url(&url);
+
+        let database_type = database_type_from_name(&text(connection.get("provider")))
+            .or_else(|| database_type_from_name(&text(connection.get("driver"))))
+            .or(jdbc.as_ref().map(|target| target.database_type));
+        let Some(database_type) = database_type else {
+            imported.skipped.push(name);
+            continue;
+        };
+
+        let secret = credentials.get(id).and_then(|entry| entry.get("#connection"));
+        let mut target = jdbc.unwrap_or_else(|| JdbcTarget::new(database_type));
+        target.database_type = database_type;
+        if is_file_database(database_type) {
+            // 文件型数据库的文件路径保存在 database 中
+            target.host = non_empty_or(text(configuration.get("database")), &target.host);
+        } else {
+            target.host = non_empty_or(text(configuration.get("host")), &target.host);
+            target.port = text(configuration.get("port")).parse().unwrap_or(target.port);
+            target.database = non_empty_or(text(configuration.get("database")), &target.database);
+        }
+
+        let mut config = target.into_config(name);
+        config.username = text(secret.and_then(|secret| secret.get("user")).or(configuration.get("user")));
+        config.password = text(secret.and_then(|secret| secret.get("password")).or(configuration.get("password")));
+        let environment = match text(configuration.get("type")).as_str() {
+            "prod" => ConnectionEnvironment::Production,
+            "test" => ConnectionEnvironment::Testing,
+            _ => ConnectionEnvironment::Development,
+        };
+        if environment != ConnectionEnvironment::Development {
+            config.extra_params.insert("environment".to_string(), environment.as_str().to_string());
+        }
+        imported.connections.push(StoredConnection::from_db_connection(config));
+    }
+    Ok(imported)
+}
+
+/// 解析 Navicat 导出的 NCX 文件。Navicat 12 以前的密码使用另一种加密方式，无法解密时留空
+pub fn import_navicat(ncx: &str) -> Result<ImportedConnections> {
+    let document = roxmltree::Document::parse(ncx).map_err(|e| anyhow!("Invalid Navicat NCX file: {}", e))?;
+
+    let mut imported = ImportedConnections::default();
+    for connection in document.descendants().filter(|node| node.has_tag_name("Connection")) {
+        let attribute = |name: &str| connection.attribute(name).unwrap_or_default().trim().to_string();
+        let name = attribute("ConnectionName");
+        let Some(database_type) = database_type_from_name(&attribute("ConnType")) else {
+            imported.skipped.push(name);
+            continue;
+        };
+
+        let mut target = JdbcTarget::new(database_type);
+        if is_file_database(database_type) {
+            target.host = attribute("DatabaseFileName");
+        } else {
+            target.host = non_empty_or(attribute("Host"), &target.host);
+            target.port = attribute("Port").parse().unwrap_or(target.port);
+            target.database = non_empty_or(attribute("InitialDatabase"), &attribute("Database"));
+        }
+
+        let mut config = target.into_config(name);
+        config.username = attribute("UserName");
+        if attribute("SavePassword") != "false" {
+            config.password = decrypt_navicat_password(&attribute("Password")).unwrap_or_default();
+        }
+        imported.connections.push(StoredConnection::from_db_connection(config));
+    }
+    Ok(imported)
+}
+
+/// 解析 DataGrip 的 dataSources.xml。密码保存在系统钥匙串中无法导入，用户名来自 dataSources.local.xml
+pub fn import_datagrip(data_sources: &str, local: Option<&str>) -> Result<ImportedConnections> {
+    let document = roxmltree::Document::parse(data_sources)
+        .map_err(|e| anyhow!("Invalid DataGrip dataSources.xml: {}", e))?;
+    let local_document = local
+        .map(|local| roxmltree::Document::parse(local).map_err(|e| anyhow!("Invalid DataGrip dataSources.local.xml: {}", e)))
+        .transpose()?;
+    let user_names: HashMap<String, String> = local_document
+        .iter()
+        .flat_map(|document| datagrip_sources(document))
+        .filter_map(|source| Some((source.attribute("uuid")?.to_string(), child_text(source, "user-name")?)))
+        .collect();
+
+    let mut imported = ImportedConnections::default();
+    for source in datagrip_sources(&document) {
+        let name = source.attribute("name").unwrap_or_default().to_string();
+        let jdbc = child_text(source, "jdbc-url").and_then(|url| parse_jdbc_url(&url));
+        let database_type = child_text(source, "driver-ref")
+            .and_then(|driver| database_type_from_name(&driver))
+            .or(jdbc.as_ref().map(|target| target.database_type));
+        let (Some(database_type), Some(mut target)) = (database_type, jdbc) else {
+            imported.skipped.push(name);
+            continue;
+        };
+        target.database_type = database_type;
+
+        let mut config = target.into_config(name);
+        config.username = child_text(source, "user-name")
+            .or_else(|| source.attribute("uuid").and_then(|uuid| user_names.get(uuid).cloned()))
+            .unwrap_or_default();
+        imported.connections.push(StoredConnection::from_db_connection(config));
+    }
+    Ok(imported)
+}
+
+fn datagrip_sources<'a, 'input>(document: &'a roxmltree::Document<'input>) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    document.descendants().filter(|node| node.has_tag_name("data-source"))
+}
+
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// credentials-config.json 为 16 字节 IV 加 AES-128-CBC 密文，明文是按连接 ID 索引的 JSON
+fn decrypt_dbeaver_credentials(data: &[u8]) -> Result<Value> {
+    if data.len() <= 16 {
+        return Err(anyhow!("Invalid DBeaver credentials-config.json"));
+    }
+    let (iv, ciphertext) = data.split_at(16);
+    let mut buffer = ciphertext.to_vec();
+    let plaintext = Aes128CbcDec::new_from_slices(&DBEAVER_CREDENTIALS_KEY, iv)
+        .map_err(|_| anyhow!("Invalid DBeaver credentials key"))?
+        .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+        .map_err(|_| anyhow!("Failed to decrypt DBeaver credentials-config.json"))?;
+    serde_json::from_slice(plaintext).map_err(|e| anyhow!("Invalid DBeaver credentials: {}", e))
+}
+
+/// NCX 中的密码为十六进制编码的 AES-128-CBC 密文
+fn decrypt_navicat_password(hex: &str) -> Option<String> {
+    if hex.is_empty() {
+        return None;
+    }
+    let mut buffer = (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let plaintext = Aes128CbcDec::new_from_slices(NAVICAT_KEY, NAVICAT_IV)
+        .ok()?
+        .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+        .ok()?;
+    String::from_utf8(plaintext.to_vec()).ok()
+}
+
+/// 各工具的驱动名称不同，按名称中的关键字识别数据库类型。TiDB 和 CockroachDB 分别兼容 MySQL 和 PostgreSQL，需先判断
+fn database_type_from_name(name: &str) -> Option<DatabaseType> {
+    const KEYWORDS: &[(&str, DatabaseType)] = &[
+        ("tidb", DatabaseType::TiDB),
+        ("cockroach", DatabaseType::CockroachDB),
+        ("mysql", DatabaseType::MySQL),
+        ("mariadb", DatabaseType::MySQL),
+        ("postgres", DatabaseType::PostgreSQL),
+        ("pgsql", DatabaseType::PostgreSQL),
+        ("sqlserver", DatabaseType::MSSQL),
+        ("mssql", DatabaseType::MSSQL),
+        ("jtds", DatabaseType::MSSQL),
+        ("oracle", DatabaseType::Oracle),
+        ("clickhouse", DatabaseType::ClickHouse),
+        ("duckdb", DatabaseType::DuckDB),
+        ("sqlite", DatabaseType::SQLite),
+        ("mongo", DatabaseType::MongoDB),
+        ("redis", DatabaseType::Redis),
+        ("trino", DatabaseType::Trino),
+        ("presto", DatabaseType::Trino),
+        ("elasticsearch", DatabaseType::Elasticsearch),
+    ];
+    let name = name.to_lowercase();
+    KEYWORDS
+        .iter()
+        .find(|(keyword, _)| name.contains(keyword))
+        .map(|(_, database_type)| *database_type)
+}
+
+/// 文件型数据库的文件路径放在 host 中
+fn is_file_database(database_type: DatabaseType) -> bool {
+    matches!(database_type, DatabaseType::SQLite | DatabaseType::DuckDB)
+}
+
+fn default_port(database_type: DatabaseType) -> u16 {
+    match database_type {
+        DatabaseType::MySQL => 3306,
+        DatabaseType::TiDB => 4000,
+        DatabaseType::PostgreSQL => 5432,
+        DatabaseType::CockroachDB => 26257,
+        DatabaseType::MSSQL => 1433,
+        DatabaseType::Oracle => 1521,
+        DatabaseType::ClickHouse => 8123,
+        DatabaseType::MongoDB => 27017,
+        DatabaseType::Redis => 6379,
+        DatabaseType::Trino => 8080,
+        DatabaseType::Elasticsearch => 9200,
+        DatabaseType::SQLite | DatabaseType::DuckDB | DatabaseType::ODBC => 0,
+    }
+}
+
+/// 从 JDBC URL 或工具配置中取出的连接目标
+#[derive(Debug, Clone, PartialEq)]
+struct JdbcTarget {
+    database_type: DatabaseType,
+    host: String,
+    port: u16,
+    database: String,
+}
+
+impl JdbcTarget {
+    fn new(database_type: DatabaseType) -> Self {
+        let host = if is_file_database(database_type) { "" } else { "localhost" };
+        Self {
+            database_type,
+            host: host.to_string(),
+            port: default_port(database_type),
+            database: String::new(),
+        }
+    }
+
+    fn into_config(self, name: String) -> DbConnectionConfig {
+        DbConnectionConfig {
+            id: String::new(),
+            database_type: self.database_type,
+            name,
+            host: self.host,
+            port: self.port,
+            username: String::new(),
+            password: String::new(),
+            database: Some(self.database).filter(|database| !database.is_empty()),
+            workspace_id: None,
+            extra_params: HashMap::new(),
+        }
+    }
+
+    fn set_host_port(&mut self, host_port: &str) {
+        // 多主机地址只取第一个
+        let host_port = host_port.split(',').next().unwrap_or_default();
+        let (host, port) = match host_port.rsplit_once(':') {
+            Some((host, port)) if !host_port.ends_with(']') => (host, port.parse().ok()),
+            _ => (host_port, None),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if !host.is_empty() {
+            self.host = host.to_string();
+        }
+        if let Some(port) = port {
+            self.port = port;
+        }
+    }
+}
+
+/// 解析常见数据库的 JDBC URL，如 `jdbc:mysql://host:3306/db`、`jdbc:sqlserver://host:1433;databaseName=db`、
+/// `jdbc:oracle:thin:@//host:1521/service` 和 `jdbc:sqlite:/path/to/file.db`
+fn parse_jdbc_url(url: &str) -> Option<JdbcTarget> {
+    let rest = url.trim().strip_prefix("jdbc:")?;
+    let (driver, _) = rest.split_once(':')?;
+    let database_type = database_type_from_name(driver).or_else(|| (driver == "ch").then_some(DatabaseType::ClickHouse))?;
+    let mut target = JdbcTarget::new(database_type);
+
+    if is_file_database(database_type) {
+        target.host = rest[driver.len() + 1..].to_string();
+        return Some(target);
+    }
+    if database_type == DatabaseType::Oracle {
+        // thin 驱动的地址形如 @host:port:SID、@host:port/service 或 @//host:port/service，TNS 描述符不解析
+        let address = rest.split_once('@')?.1.trim_start_matches("//");
+        if address.starts_with('(') {
+            return None;
+        }
+        let (host_port, database) = match address.split_once('/') {
+            Some((host_port, service)) => (host_port, service),
+            None => match address.rsplit_once(':') {
+                Some((host_port, sid)) if host_port.contains(':') => (host_port, sid),
+                _ => (address, ""),
+            },
+        };
+        target.set_host_port(host_port);
+        target.database = database.to_string();
+        return Some(target);
+    }
+
+    let address = rest.split_once("//")?.1;
+    if database_type == DatabaseType::MSSQL && !driver.eq_ignore_ascii_case("jtds") {
+        // sqlserver 驱动的属性以分号分隔，数据库名在 databaseName 属性中
+        let mut parts = address.split(';');
+        target.set_host_port(parts.next().unwrap_or_default());
+        target.database = parts
+            .filter_map(|part| part.split_once('='))
+            .find(|(key, _)| key.eq_ignore_ascii_case("databaseName") || key.eq_ignore_ascii_case("database"))
+            .map(|(_, value)| value.to_string())
+            .unwrap_or_default();
+        return Some(target);
+    }
+
+    let address = address.split(['?', ';']).next().unwrap_or_default();
+    let (host_port, path) = address.split_once('/').unwrap_or((address, ""));
+    // 带认证信息的地址形如 user:password@host:port
+    let host_port = host_port.rsplit_once('@').map(|(_, host_port)| host_port).unwrap_or(host_port);
+    target.set_host_port(host_port);
+    target.database = path.split('/').next().unwrap_or_default().to_string();
+    Some(target)
+}
+
+fn non_empty_or(value: String, fallback: &str) -> String {
+    if value.is_empty() { fallback.to_string() } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+
+    type Aes128CbcEnc = cbc::Encryptor<aes::Aes128>;
+
+    fn encrypt(key: &[u8], iv: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        Aes128CbcEnc::new_from_slices(key, iv).unwrap().encrypt_padded_vec_mut::<Pkcs7>(plaintext)
+    }
+
+    #[test]
+    fn test_parse_jdbc_url() {
+        let mysql = parse_jdbc_url("jdbc:mysql://db.example.com:3307/app?useSSL=false").unwrap();
+        assert_eq!((mysql.database_type, mysql.host.as_str(), mysql.port, mysql.database.as_str()),
+            (DatabaseType::MySQL, "db.example.com", 3307, "app"));
+
+        let postgres = parse_jdbc_url("jdbc:postgresql://localhost/postgres").unwrap();
+        assert_eq!((postgres.port, postgres.database.as_str()), (5432, "postgres"));
+
+        let mssql = parse_jdbc_url("jdbc:sqlserver://sql01:1433;databaseName=sales;encrypt=true").unwrap();
+        assert_eq!((mssql.database_type, mssql.host.as_str(), mssql.database.as_str()), (DatabaseType::MSSQL, "sql01", "sales"));
+
+        let service = parse_jdbc_url("jdbc:oracle:thin:@//ora01:1522/ORCLPDB1").unwrap();
+        assert_eq!((service.host.as_str(), service.port, service.database.as_str()), ("ora01", 1522, "ORCLPDB1"));
+        let sid = parse_jdbc_url("jdbc:oracle:thin:@ora01:1521:ORCL").unwrap();
+        assert_eq!((sid.host.as_str(), sid.port, sid.database.as_str()), ("ora01", 1521, "ORCL"));
+
+        let sqlite = parse_jdbc_url("jdbc:sqlite:/home/me/app.db").unwrap();
+        assert_eq!((sqlite.database_type, sqlite.host.as_str()), (DatabaseType::SQLite, "/home/me/app.db"));
+
+        assert!(parse_jdbc_url("jdbc:h2:mem:test").is_none());
+    }
+
+    #[test]
+    fn test_import_dbeaver_with_credentials() {
+        let data_sources = r#"{
+            "folders": {},
+            "connections": {
+                "mysql8-1": {
+                    "provider": "mysql", "driver": "mysql8", "name": "prod",
+                    "configuration": { "host": "db.example.com", "port": "3306", "database": "app", "type": "prod",
+                        "url": "jdbc:mysql://db.example.com:3306/app" }
+                },
+                "h2-1": { "provider": "h2", "name": "embedded", "configuration": { "url": "jdbc:h2:mem:test" } }
+            }
+        }"#;
+        let iv = [7u8; 16];
+        let mut credentials = iv.to_vec();
+        credentials.extend(encrypt(&DBEAVER_CREDENTIALS_KEY, &iv,
+            br##"{"mysql8-1":{"#connection":{"user":"root","password":"secret"}}}"##));
+
+        let imported = import_dbeaver(data_sources, Some(&credentials)).unwrap();
+        assert_eq!(imported.skipped, vec!["embedded".to_string()]);
+        let config = imported.connections[0].to_db_connection().unwrap();
+        assert_eq!((config.host.as_str(), config.port, config.database.as_deref()), ("db.example.com", 3306, Some("app")));
+        assert_eq!((config.username.as_str(), config.password.as_str()), ("root", "secret"));
+        assert_eq!(config.environment(), ConnectionEnvironment::Production);
+    }
+
+    #[test]
+    fn test_import_navicat() {
+        let password: String = encrypt(NAVICAT_KEY, NAVICAT_IV, b"secret").iter().map(|byte| format!("{:02X}", byte)).collect();
+        let ncx = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Connections Ver="1.5">
+                <Connection ConnectionName="orders" ConnType="PGSQL" Host="pg01" Port="5433" Database="orders"
+                    UserName="app" Password="{}" SavePassword="true"/>
+                <Connection ConnectionName="local" ConnType="SQLITE" DatabaseFileName="/data/local.db" Password=""/>
+            </Connections>"#, password);
+
+        let imported = import_navicat(&ncx).unwrap();
+        let postgres = imported.connections[0].to_db_connection().unwrap();
+        assert_eq!((postgres.database_type, postgres.host.as_str(), postgres.port), (DatabaseType::PostgreSQL, "pg01", 5433));
+        assert_eq!((postgres.username.as_str(), postgres.password.as_str()), ("app", "secret"));
+        let sqlite = imported.connections[1].to_db_connection().unwrap();
+        assert_eq!((sqlite.database_type, sqlite.host.as_str()), (DatabaseType::SQLite, "/data/local.db"));
+    }
+
+    #[test]
+    fn test_import_datagrip_with_local_user_names() {
+        let data_sources = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <project version="4">
+              <component name="DataSourceManagerImpl" format="xml" multifile-model="true">
+                <data-source source="LOCAL" name="analytics" uuid="a1">
+                  <driver-ref>clickhouse</driver-ref>
+                  <jdbc-url>jdbc:clickhouse://ch01:8123/events</jdbc-url>
+                </data-source>
+              </component>
+            </project>"#;
+        let local = r#"<project version="4">
+              <component name="dataSourceStorageLocal">
+                <data-source name="analytics" uuid="a1"><user-name>reader</user-name></data-source>
+              </component>
+            </project>"#;
+
+        let imported = import_datagrip(data_sources, Some(local)).unwrap();
+        let config = imported.connections[0].to_db_connection().unwrap();
+        assert_eq!((config.database_type, config.host.as_str(), config.database.as_deref()), (DatabaseType::ClickHouse, "ch01", Some("events")));
+        assert_eq!((config.username.as_str(), config.password.as_str()), ("reader", ""));
+    }
+}
@@ -0,0 +1,336 @@
+//! 连接分享文件 - 将选中的连接导出为口令加密的文件，便于团队成员导入
+
+use std::num::NonZeroU32;
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::storage::models::{ConnectionColor, ConnectionType, StoredConnection};
+
+const SHARE_FORMAT: &str = "onehub-connections";
+const SHARE_VERSION: u32 = 1;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+/// 导入时接受的迭代次数上限，文件中的次数过大会让派生卡住界面
+const MAX_PBKDF2_ITERATIONS: u32 = 10 * PBKDF2_ITERATIONS;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// 数据库连接 extra_params 中属于 SSH 密钥的参数
+const SSH_KEY_PARAMS: &[&str] = &["ssh_private_key", "ssh_key_path", "ssh_passphrase"];
+/// 数据库连接 extra_params 中属于密码的参数，Elasticsearch 的 API Key 同样按密码处理
+const PASSWORD_PARAMS: &[&str] = &["ssh_password", "proxy_password", "api_key"];
+
+/// 导出时包含哪些敏感字段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionShareOptions {
+    pub include_passwords: bool,
+    pub include_ssh_keys: bool,
+}
+
+/// 分享文件中的单个连接
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SharedConnection {
+    name: String,
+    connection_type: ConnectionType,
+    params: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    selected_databases: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    remark: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<ConnectionColor>,
+}
+
+/// 分享文件外层结构，连接列表加密后存放在 ciphertext 中
+#[derive(Debug, Serialize, Deserialize)]
+struct ShareEnvelope {
+    format: String,
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// 将连接导出为口令加密的分享文件内容
+pub fn export_connections(
+    connections: &[StoredConnection],
+    options: ConnectionShareOptions,
+    passphrase: &str,
+) -> Result<String> {
+    if passphrase.is_empty() {
+        return Err(anyhow!("Passphrase cannot be empty"));
+    }
+
+    let shared = connections
+        .iter()
+        .map(|conn| {
+            Ok(SharedConnection {
+                name: conn.name.clone(),
+                connection_type: conn.connection_type,
+                params: scrub_params(conn.connection_type, &conn.params, options)?,
+                selected_databases: conn.selected_databases.clone(),
+                remark: conn.remark.clone(),
+                color: conn.color,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let plaintext = serde_json::to_vec(&shared)?;
+
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill(&mut salt).map_err(|_| anyhow!("Failed to generate salt"))?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce).map_err(|_| anyhow!("Failed to generate nonce"))?;
+
+    let key = derive_key(passphrase, &salt, PBKDF2_ITERATIONS)?;
+    let mut in_out = plaintext;
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(SHARE_FORMAT.as_bytes()), &mut in_out)
+        .map_err(|_| anyhow!("Failed to encrypt connections"))?;
+
+    let envelope = ShareEnvelope {
+        format: SHARE_FORMAT.to_string(),
+        version: SHARE_VERSION,
+        iterations: PBKDF2_ITERATIONS,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(in_out),
+    };
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+/// 解密分享文件，返回待保存的连接（未分配 ID 和工作区）
+pub fn import_connections(data: &str, passphrase: &str) -> Result<Vec<StoredConnection>> {
+    let envelope: ShareEnvelope = serde_json::from_str(data)
+        .map_err(|e| anyhow!("Invalid connection share file: {}", e))?;
+    if envelope.format != SHARE_FORMAT {
+        return Err(anyhow!("Invalid connection share file format: {}", envelope.format));
+    }
+    if envelope.version > SHARE_VERSION {
+        return Err(anyhow!("Unsupported connection share file version: {}", envelope.version));
+    }
+
+    if !(PBKDF2_ITERATIONS..=MAX_PBKDF2_ITERATIONS).contains(&envelope.iterations) {
+        return Err(anyhow!("Invalid iteration count in connection share file: {}", envelope.iterations));
+    }
+
+    let salt = STANDARD.decode(&envelope.salt)?;
+    let nonce: [u8; NONCE_LEN] = STANDARD
+        .decode(&envelope.nonce)?
+        .try_into()
+        .map_err(|_| anyhow!("Invalid nonce length"))?;
+    let mut in_out = STANDARD.decode(&envelope.ciphertext)?;
+
+    let key = derive_key(passphrase, &salt, envelope.iterations)?;
+    let plaintext = key
+        .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(SHARE_FORMAT.as_bytes()), &mut in_out)
+        .map_err(|_| anyhow!("Wrong passphrase or corrupted file"))?;
+
+    let shared: Vec<SharedConnection> = serde_json::from_slice(plaintext)?;
+    Ok(shared
+        .into_iter()
+        .map(|conn| StoredConnection {
+            id: None,
+            name: conn.name,
+            connection_type: conn.connection_type,
+            params: conn.params,
+            workspace_id: None,
+            selected_databases: conn.selected_databases,
+            remark: conn.remark,
+            auto_open: false,
+            color: conn.color,
+            created_at: None,
+            updated_at: None,
+        })
+        .collect())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).ok_or_else(|| anyhow!("Invalid iteration count"))?;
+    let mut key_bytes = [0u8; KEY_LEN];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key_bytes);
+    let key = UnboundKey::new(&AES_256_GCM, &key_bytes).map_err(|_| anyhow!("Failed to create key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// 按导出选项移除连接参数中的密码和 SSH 密钥
+fn scrub_params(connection_type: ConnectionType, params: &str, options: ConnectionShareOptions) -> Result<String> {
+    let mut value: Value = serde_json::from_str(params)?;
+
+    match connection_type {
+        ConnectionType::Database | ConnectionType::All => {
+            if !options.include_passwords {
+                if let Some(password) = value.get_mut("password") {
+                    *password = Value::String(String::new());
+                }
+            }
+            if let Some(Value::Object(extra)) = value.get_mut("extra_params") {
+                if !options.include_passwords {
+                    for key in PASSWORD_PARAMS {
+                        extra.remove(*key);
+                    }
+                }
+                if !options.include_ssh_keys {
+                    for key in SSH_KEY_PARAMS {
+                        extra.remove(*key);
+                    }
+                }
+            }
+        }
+        ConnectionType::SshSftp => {
+            if let Some(auth) = value.get_mut("auth_method") {
+                if let Some(Value::Object(password_auth)) = auth.get_mut("Password") {
+                    if !options.include_passwords {
+                        password_auth.insert("password".to_string(), Value::String(String::new()));
+                    }
+                }
+                if let Some(Value::Object(key_auth)) = auth.get_mut("PrivateKey") {
+                    if !options.include_ssh_keys {
+                        key_auth.insert("key_path".to_string(), Value::String(String::new()));
+                        key_auth.insert("passphrase".to_string(), Value::Null);
+                    }
+                }
+            }
+        }
+        ConnectionType::Redis => {
+            if !options.include_passwords {
+                if let Some(password) = value.get_mut("password") {
+                    *password = Value::Null;
+                }
+            }
+        }
+        ConnectionType::MongoDB => {
+            if !options.include_passwords {
+                if let Some(Value::String(connection_string)) = value.get_mut("connection_string") {
+                    *connection_string = strip_url_password(connection_string);
+                }
+            }
+        }
+    }
+
+    // 数据库插件（如 MongoDB）把连接串放在 extra_params 中，与连接类型无关
+    if !options.include_passwords {
+        if let Some(Value::String(connection_string)) = value.pointer_mut("/extra_params/connection_string") {
+            *connection_string = strip_url_password(connection_string);
+        }
+    }
+
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// 去掉连接串中 `user:password@` 的密码部分
+fn strip_url_password(url: &str) -> String {
+    let Some(scheme_end) = url.find("://") else {
+        return url.to_string();
+    };
+    let authority_start = scheme_end + 3;
+    let rest = &url[authority_start..];
+    let authority_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let Some(at) = rest[..authority_end].rfind('@') else {
+        return url.to_string();
+    };
+    let user_info = &rest[..at];
+    match user_info.find(':') {
+        Some(colon) => format!("{}{}{}", &url[..authority_start], &user_info[..colon], &rest[at..]),
+        None => url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::models::{DatabaseType, DbConnectionConfig};
+    use std::collections::HashMap;
+
+    fn mysql_connection() -> StoredConnection {
+        let mut extra_params = HashMap::new();
+        extra_params.insert("ssh_key_path".to_string(), "/home/me/.ssh/id_rsa".to_string());
+        extra_params.insert("connect_timeout".to_string(), "30".to_string());
+        extra_params.insert("api_key".to_string(), "es-key".to_string());
+        StoredConnection::new_database(
+            "prod".to_string(),
+            DbConnectionConfig {
+                id: String::new(),
+                database_type: DatabaseType::MySQL,
+                name: "prod".to_string(),
+                host: "db.example.com".to_string(),
+                port: 3306,
+                username: "root".to_string(),
+                password: "secret".to_string(),
+                database: Some("app".to_string()),
+                workspace_id: None,
+                extra_params,
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn test_round_trip_without_secrets() {
+        let data = export_connections(&[mysql_connection()], ConnectionShareOptions::default(), "pass").unwrap();
+        assert!(!data.contains("secret"));
+        assert!(!data.contains("es-key"));
+
+        let imported = import_connections(&data, "pass").unwrap();
+        assert_eq!(imported.len(), 1);
+        let config = imported[0].to_db_connection().unwrap();
+        assert_eq!(config.host, "db.example.com");
+        assert_eq!(config.password, "");
+        assert_eq!(config.get_param("connect_timeout").map(String::as_str), Some("30"));
+        assert!(config.get_param("ssh_key_path").is_none());
+        assert!(config.get_param("api_key").is_none());
+    }
+
+    #[test]
+    fn test_round_trip_with_secrets() {
+        let options = ConnectionShareOptions {
+            include_passwords: true,
+            include_ssh_keys: true,
+        };
+        let data = export_connections(&[mysql_connection()], options, "pass").unwrap();
+
+        let config = import_connections(&data, "pass").unwrap()[0].to_db_connection().unwrap();
+        assert_eq!(config.password, "secret");
+        assert_eq!(config.get_param("api_key").map(String::as_str), Some("es-key"));
+        assert_eq!(config.get_param("ssh_key_path").map(String::as_str), Some("/home/me/.ssh/id_rsa"));
+    }
+
+    #[test]
+    fn test_wrong_passphrase_is_rejected() {
+        let data = export_connections(&[mysql_connection()], ConnectionShareOptions::default(), "pass").unwrap();
+        assert!(import_connections(&data, "other").is_err());
+    }
+
+    #[test]
+    fn test_connection_string_password_is_stripped() {
+        let mut connection = mysql_connection();
+        let mut config = connection.to_db_connection().unwrap();
+        config.extra_params.insert("connection_string".to_string(), "mongodb://user:pw@host/db".to_string());
+        connection.params = serde_json::to_string(&config).unwrap();
+
+        let data = export_connections(&[connection], ConnectionShareOptions::default(), "pass").unwrap();
+        let config = import_connections(&data, "pass").unwrap()[0].to_db_connection().unwrap();
+        assert_eq!(config.get_param("connection_string").map(String::as_str), Some("mongodb://user@host/db"));
+    }
+
+    #[test]
+    fn test_unreasonable_iterations_are_rejected() {
+        let data = export_connections(&[mysql_connection()], ConnectionShareOptions::default(), "pass").unwrap();
+        for iterations in [1, u32::MAX] {
+            let mut envelope: Value = serde_json::from_str(&data).unwrap();
+            envelope["iterations"] = Value::from(iterations);
+            assert!(import_connections(&envelope.to_string(), "pass").is_err());
+        }
+    }
+
+    #[test]
+    fn test_strip_url_password() {
+        assert_eq!(strip_url_password("mongodb://user:pw@host:27017/db"), "mongodb://user@host:27017/db");
+        assert_eq!(strip_url_password("mongodb://host:27017/db"), "mongodb://host:27017/db");
+    }
+}
@@ -0,0 +1,247 @@
+//! 存储加密 - 主密码经 PBKDF2 派生为 SQLCipher 密钥，加密整个存储数据库（连接、查询、对话历史）。
+//! 配置目录中的 master-password.json 只保存盐和迭代次数，文件存在即表示已启用加密
+
+use std::borrow::Cow;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
+
+const MASTER_PASSWORD_FILE_NAME: &str = "master-password.json";
+/// 更换主密码期间记录新的派生参数，数据库替换完成后才变成 master-password.json
+const PENDING_MASTER_PASSWORD_FILE_NAME: &str = "master-password.pending.json";
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+/// 每次启动只派生一次，取较高的迭代次数增加暴力破解的成本
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// SQLCipher 的原始密钥，跳过 SQLCipher 自身的口令派生
+#[derive(Clone, PartialEq, Eq)]
+pub struct DatabaseKey([u8; KEY_LEN]);
+
+impl DatabaseKey {
+    fn hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// `PRAGMA key` 的取值
+    fn pragma_value(&self) -> String {
+        format!("\"x'{}'\"", self.hex())
+    }
+
+    /// `ATTACH ... KEY ?` 绑定的取值
+    fn attach_value(&self) -> String {
+        format!("x'{}'", self.hex())
+    }
+}
+
+/// 避免密钥出现在日志中
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DatabaseKey(..)")
+    }
+}
+
+/// 主密码的派生参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MasterPassword {
+    salt: String,
+    iterations: u32,
+}
+
+impl MasterPassword {
+    /// 为新的主密码生成随机盐
+    pub fn generate() -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| anyhow!("Failed to generate salt"))?;
+        Ok(Self { salt: BASE64.encode(salt), iterations: PBKDF2_ITERATIONS })
+    }
+
+    /// 未启用加密时返回 None
+    pub fn load(config_dir: &Path) -> Result<Option<Self>> {
+        let path = master_password_path(config_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&data)?))
+    }
+
+    pub fn save(&self, config_dir: &Path) -> Result<()> {
+        write_atomically(&master_password_path(config_dir), &serde_json::to_string_pretty(self)?)
+    }
+
+    /// 替换数据库之前记下新的派生参数，`None` 表示取消加密。
+    /// 替换中途崩溃时，启动时靠它判断数据库用的是新密钥还是旧密钥
+    pub fn save_pending(master: Option<&Self>, config_dir: &Path) -> Result<()> {
+        write_atomically(&pending_path(config_dir), &serde_json::to_string_pretty(&master)?)
+    }
+
+    /// 没有进行中的更换时返回 None
+    pub fn load_pending(config_dir: &Path) -> Result<Option<Option<Self>>> {
+        let path = pending_path(config_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&data)?))
+    }
+
+    /// 数据库已经替换，让新的派生参数生效；中途崩溃后再次调用结果相同
+    pub fn commit_pending(config_dir: &Path) -> Result<()> {
+        match Self::load_pending(config_dir)? {
+            None => {}
+            Some(Some(_)) => std::fs::rename(pending_path(config_dir), master_password_path(config_dir))?,
+            Some(None) => {
+                Self::remove(config_dir)?;
+                std::fs::remove_file(pending_path(config_dir))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 数据库没有被替换，丢弃新的派生参数
+    pub fn discard_pending(config_dir: &Path) -> Result<()> {
+        let path = pending_path(config_dir);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn remove(config_dir: &Path) -> Result<()> {
+        let path = master_password_path(config_dir);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// 派生耗时较长，不要在 UI 线程上调用
+    pub fn derive_key(&self, password: &str) -> Result<DatabaseKey> {
+        let salt = BASE64.decode(&self.salt)?;
+        let iterations = NonZeroU32::new(self.iterations).ok_or_else(|| anyhow!("Invalid iteration count"))?;
+        let mut key = [0u8; KEY_LEN];
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, &salt, password.as_bytes(), &mut key);
+        Ok(DatabaseKey(key))
+    }
+}
+
+fn master_password_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(MASTER_PASSWORD_FILE_NAME)
+}
+
+fn pending_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(PENDING_MASTER_PASSWORD_FILE_NAME)
+}
+
+/// 先写临时文件再改名，崩溃时文件要么是旧内容要么是完整的新内容
+fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    let mut name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".tmp");
+    let temp = path.with_file_name(name);
+    let mut file = File::create(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// 为连接设置密钥；`key` 必须是连接上执行的第一条语句，sqlx 会把它排在其它 PRAGMA 之前
+pub fn with_key(options: SqliteConnectOptions, key: Option<&DatabaseKey>) -> SqliteConnectOptions {
+    match key {
+        Some(key) => options.pragma("key", Cow::<'static, str>::Owned(key.pragma_value())),
+        None => options,
+    }
+}
+
+/// 用密钥读取数据库；密码错误时 SQLCipher 报告 SQLITE_NOTADB，返回 false
+pub async fn verify_key(db_path: &Path, key: &DatabaseKey) -> Result<bool> {
+    let options = with_key(SqliteConnectOptions::new().filename(db_path).read_only(true), Some(key));
+    let result = async {
+        let mut conn = SqliteConnection::connect_with(&options).await?;
+        let count: i64 = sqlx::query_scalar("SELECT count(*) FROM sqlite_master")
+            .fetch_one(&mut conn)
+            .await?;
+        let _ = conn.close().await;
+        Ok::<_, sqlx::Error>(count)
+    }
+    .await;
+
+    match result {
+        Ok(_) => Ok(true),
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("26") => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 把数据库完整导出到 `target`，`key` 为 None 时导出为明文。
+/// sqlcipher_export 在一个读事务中完成，其它连接同时写入也能得到一致的快照
+pub async fn export_database(pool: &SqlitePool, target: &Path, key: Option<&DatabaseKey>) -> Result<()> {
+    if target.exists() {
+        std::fs::remove_file(target)?;
+    }
+
+    // ATTACH 的数据库只对当前连接可见，三条语句必须在同一个连接上执行
+    let mut conn = pool.acquire().await?;
+    sqlx::query("ATTACH DATABASE ? AS export KEY ?")
+        .bind(target.to_string_lossy().to_string())
+        .bind(key.map(DatabaseKey::attach_value).unwrap_or_default())
+        .execute(&mut *conn)
+        .await?;
+    let exported = sqlx::query("SELECT sqlcipher_export('export')")
+        .execute(&mut *conn)
+        .await;
+    sqlx::query("DETACH DATABASE export").execute(&mut *conn).await?;
+    exported?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_depends_on_password_and_salt() {
+        let master = MasterPassword::generate().unwrap();
+        let key = master.derive_key("secret").unwrap();
+        assert_eq!(key, master.derive_key("secret").unwrap());
+        assert_ne!(key, master.derive_key("Secret").unwrap());
+        assert_ne!(key, MasterPassword::generate().unwrap().derive_key("secret").unwrap());
+        assert!(!format!("{:?}", key).contains(&key.hex()));
+    }
+
+    #[test]
+    fn test_pending_master_password() {
+        let config_dir = std::env::temp_dir().join(format!("one-hub-master-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&config_dir).unwrap();
+        let master = MasterPassword::generate().unwrap();
+
+        MasterPassword::save_pending(Some(&master), &config_dir).unwrap();
+        assert!(MasterPassword::load(&config_dir).unwrap().is_none());
+        MasterPassword::commit_pending(&config_dir).unwrap();
+        assert!(MasterPassword::load_pending(&config_dir).unwrap().is_none());
+        assert_eq!(MasterPassword::load(&config_dir).unwrap().map(|loaded| loaded.salt), Some(master.salt.clone()));
+
+        MasterPassword::save_pending(None, &config_dir).unwrap();
+        MasterPassword::discard_pending(&config_dir).unwrap();
+        assert!(MasterPassword::load(&config_dir).unwrap().is_some());
+
+        MasterPassword::save_pending(None, &config_dir).unwrap();
+        MasterPassword::commit_pending(&config_dir).unwrap();
+        assert!(MasterPassword::load(&config_dir).unwrap().is_none());
+        assert!(MasterPassword::load_pending(&config_dir).unwrap().is_none());
+    }
+}
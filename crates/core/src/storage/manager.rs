@@ -1,21 +1,53 @@
 use anyhow::Result;
 use gpui::{App, Global};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
-use sqlx::SqlitePool;
+use sqlx::{Connection, SqliteConnection, SqlitePool};
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::str::FromStr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::log;
 use crate::gpui_tokio::Tokio;
+use crate::storage::encryption::{self, DatabaseKey, MasterPassword};
+
+const DB_FILE_NAME: &str = "one-hub.db";
+const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_PREFIX: &str = "one-hub-";
+/// 更换主密码时先导出到这个文件，再替换数据库
+const EXPORTED_SUFFIX: &str = ".export";
+/// 保留的备份数量，超出时删除最旧的
+pub const MAX_BACKUPS: usize = 5;
+/// 距离上一次备份超过该时间（秒）时，启动时自动备份
+const BACKUP_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+/// 启动时存储健康检查的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageRecovery {
+    Healthy,
+    /// 数据库已损坏，已从备份恢复；损坏的文件被重命名保留
+    RestoredFromBackup { backup: PathBuf, corrupt: PathBuf },
+    /// 数据库已损坏且没有可用的备份，已创建新的数据库
+    Recreated { corrupt: PathBuf },
+}
+
+/// 一个数据库备份文件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageBackup {
+    pub path: PathBuf,
+    pub created_at: i64,
+    pub size: u64,
+}
 
 /// Storage manager - unified entry point for all repositories
 pub struct StorageManager {
     pool: Arc<RwLock<SqlitePool>>,
     repositories: Arc<RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>>,
+    db_path: PathBuf,
+    recovery: StorageRecovery,
+    /// 启用主密码时数据库的 SQLCipher 密钥
+    key: Option<DatabaseKey>,
 }
 
 pub struct  GlobalStorageState {
@@ -34,17 +66,69 @@ impl Clone for GlobalStorageState {
 
 impl StorageManager {
     /// Create a new storage manager
-    pub async fn new() -> Result<Self> {
-        let database_url = get_db_path()?;
-        let options = SqliteConnectOptions::from_str(&database_url)?
+    pub async fn new(key: Option<DatabaseKey>) -> Result<Self> {
+        let config_dir = get_config_dir()?;
+        std::fs::create_dir_all(&config_dir)?;
+        Self::open(config_dir.join(DB_FILE_NAME), key).await
+    }
+
+    /// Derivation parameters of the master password, None when the storage is not encrypted
+    pub fn master_password() -> Result<Option<MasterPassword>> {
+        let config_dir = get_config_dir()?;
+        finish_master_password_change(&config_dir.join(DB_FILE_NAME))?;
+        MasterPassword::load(&config_dir)
+    }
+
+    /// Derive the key of the default database from the master password; None when the password is wrong
+    pub async fn unlock(password: &str) -> Result<Option<DatabaseKey>> {
+        let master = Self::master_password()?.ok_or_else(|| anyhow::anyhow!("Master password is not enabled"))?;
+        let key = derive_key(master, password.to_string()).await?;
+        let db_path = get_config_dir()?.join(DB_FILE_NAME);
+        Ok(encryption::verify_key(&db_path, &key).await?.then_some(key))
+    }
+
+    /// Open the database at `db_path`, recovering from a backup when it is corrupt
+    pub async fn open(db_path: PathBuf, key: Option<DatabaseKey>) -> Result<Self> {
+        finish_master_password_change(&db_path)?;
+        let backup_dir = backup_dir(&db_path);
+        let recovery = recover_if_corrupt(&db_path, &backup_dir, key.as_ref()).await?;
+        match &recovery {
+            StorageRecovery::Healthy => {}
+            StorageRecovery::RestoredFromBackup { backup, corrupt } => log::warn!(
+                "Storage database was corrupt (moved to {}), restored from {}",
+                corrupt.display(),
+                backup.display()
+            ),
+            StorageRecovery::Recreated { corrupt } => log::warn!(
+                "Storage database was corrupt (moved to {}) and no usable backup was found",
+                corrupt.display()
+            ),
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(&db_path)
             .create_if_missing(true);
+        let options = encryption::with_key(options, key.as_ref());
         let pool = SqlitePoolOptions::new()
             .max_connections(5)
             .connect_with(options)
             .await?;
+
+        let due = list_backups(&backup_dir)?
+            .first()
+            .is_none_or(|latest| now() - latest.created_at > BACKUP_INTERVAL_SECS);
+        if due {
+            if let Err(e) = create_backup(&pool, &backup_dir, key.as_ref()).await {
+                log::warn!("Failed to back up storage database: {}", e);
+            }
+        }
+
         let manager = Self {
             pool: Arc::new(RwLock::new(pool)),
             repositories: Arc::new(RwLock::new(HashMap::new())),
+            db_path,
+            recovery,
+            key,
         };
         Ok(manager)
     }
@@ -77,6 +161,264 @@ impl StorageManager {
         let pool = self.pool.read().await;
         Ok(pool.clone())
     }
+
+    /// Result of the health check performed when the storage was opened
+    pub fn recovery(&self) -> &StorageRecovery {
+        &self.recovery
+    }
+
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Run a full integrity check; returns the reported problems, empty when healthy
+    pub async fn integrity_check(&self) -> Result<Vec<String>> {
+        let pool = self.get_pool().await?;
+        let rows: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&pool)
+            .await?;
+        Ok(rows.into_iter().filter(|row| row != "ok").collect())
+    }
+
+    /// Back up the database now, keeping only the newest backups
+    pub async fn backup(&self) -> Result<StorageBackup> {
+        let pool = self.get_pool().await?;
+        create_backup(&pool, &backup_dir(&self.db_path), self.key.as_ref()).await
+    }
+
+    pub fn backups(&self) -> Result<Vec<StorageBackup>> {
+        list_backups(&backup_dir(&self.db_path))
+    }
+
+    /// Rebuild the database file to reclaim free pages; returns the size before and after
+    pub async fn compact(&self) -> Result<(u64, u64)> {
+        let before = std::fs::metadata(&self.db_path)?.len();
+        let pool = self.get_pool().await?;
+        sqlx::query("VACUUM").execute(&pool).await?;
+        let after = std::fs::metadata(&self.db_path)?.len();
+        Ok((before, after))
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+
+    fn config_dir(&self) -> &Path {
+        self.db_path.parent().unwrap_or_else(|| Path::new("."))
+    }
+
+    /// Check the master password the database was unlocked with
+    pub async fn check_master_password(&self, password: &str) -> Result<bool> {
+        let Some(master) = MasterPassword::load(self.config_dir())? else {
+            return Ok(false);
+        };
+        let key = derive_key(master, password.to_string()).await?;
+        Ok(self.key.as_ref() == Some(&key))
+    }
+
+    /// Encrypt the database with a new master password, or decrypt it when `password` is None.
+    /// The database is rewritten and the pool is closed, so the application has to restart afterwards.
+    pub async fn set_master_password(&self, password: Option<String>) -> Result<()> {
+        let config_dir = self.config_dir().to_path_buf();
+        let (master, key) = match password {
+            Some(password) => {
+                let master = MasterPassword::generate()?;
+                let key = derive_key(master.clone(), password).await?;
+                (Some(master), Some(key))
+            }
+            None => (None, None),
+        };
+
+        let exported = append_to_file_name(&self.db_path, EXPORTED_SUFFIX);
+        let pool = self.get_pool().await?;
+        encryption::export_database(&pool, &exported, key.as_ref()).await?;
+        // 先落盘新的派生参数，替换数据库的任何一步崩溃后都还能知道该用哪个密钥
+        MasterPassword::save_pending(master.as_ref(), &config_dir)?;
+
+        // 替换文件前关闭所有连接，Windows 上打开的文件无法被覆盖
+        pool.close().await;
+        for suffix in ["-wal", "-shm"] {
+            let journal = append_to_file_name(&self.db_path, suffix);
+            if journal.exists() {
+                std::fs::remove_file(&journal)?;
+            }
+        }
+        std::fs::rename(&exported, &self.db_path)?;
+        MasterPassword::commit_pending(&config_dir)?;
+
+        // 旧备份是明文或使用旧密钥，保留会泄露数据或无法用于恢复；下次启动时重新备份
+        for backup in self.backups()? {
+            if let Err(e) = std::fs::remove_file(&backup.path) {
+                log::warn!("Failed to remove backup {}: {}", backup.path.display(), e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 上次更换主密码时中途退出：导出的新数据库还在说明数据库没有被替换，放弃新的派生参数；
+/// 否则替换已经完成，让新的派生参数生效
+fn finish_master_password_change(db_path: &Path) -> Result<()> {
+    let config_dir = db_path.parent().unwrap_or_else(|| Path::new("."));
+    if MasterPassword::load_pending(config_dir)?.is_none() {
+        return Ok(());
+    }
+    let exported = append_to_file_name(db_path, EXPORTED_SUFFIX);
+    if exported.exists() {
+        log::warn!("Master password change was interrupted before the database was replaced, keeping the old password");
+        std::fs::remove_file(&exported)?;
+        MasterPassword::discard_pending(config_dir)
+    } else {
+        log::warn!("Master password change was interrupted after the database was replaced, applying the new password");
+        MasterPassword::commit_pending(config_dir)
+    }
+}
+
+/// PBKDF2 派生需要几百毫秒，放到阻塞线程中执行
+async fn derive_key(master: MasterPassword, password: String) -> Result<DatabaseKey> {
+    tokio::task::spawn_blocking(move || master.derive_key(&password)).await?
+}
+
+fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .map(|dir| dir.join(BACKUP_DIR_NAME))
+        .unwrap_or_else(|| PathBuf::from(BACKUP_DIR_NAME))
+}
+
+/// 备份文件按时间从新到旧排列
+fn list_backups(backup_dir: &Path) -> Result<Vec<StorageBackup>> {
+    if !backup_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(backup_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(created_at) = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix(BACKUP_PREFIX))
+            .and_then(|name| name.strip_suffix(".db"))
+            .and_then(|ts| ts.parse::<i64>().ok())
+        else {
+            continue;
+        };
+        backups.push(StorageBackup {
+            path: entry.path(),
+            created_at,
+            size: entry.metadata()?.len(),
+        });
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+async fn create_backup(pool: &SqlitePool, backup_dir: &Path, key: Option<&DatabaseKey>) -> Result<StorageBackup> {
+    std::fs::create_dir_all(backup_dir)?;
+    let created_at = now();
+    let path = backup_dir.join(format!("{}{}.db", BACKUP_PREFIX, created_at));
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+
+    match key {
+        // 备份使用与数据库相同的密钥
+        Some(key) => encryption::export_database(pool, &path, Some(key)).await?,
+        None => {
+            // VACUUM INTO 生成一致的快照，不受其他连接正在写入的影响
+            sqlx::query("VACUUM INTO ?")
+                .bind(path.to_string_lossy().to_string())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    for old in list_backups(backup_dir)?.into_iter().skip(MAX_BACKUPS) {
+        if let Err(e) = std::fs::remove_file(&old.path) {
+            log::warn!("Failed to remove old backup {}: {}", old.path.display(), e);
+        }
+    }
+
+    let size = std::fs::metadata(&path)?.len();
+    Ok(StorageBackup { path, created_at, size })
+}
+
+/// 检查数据库文件，返回损坏原因；数据库被锁定等其他错误直接返回错误，避免误判为损坏。
+/// `encrypted` 时密钥不对也会报告 SQLITE_NOTADB，不能当成损坏把整个存储挪走
+async fn find_corruption(path: &Path, key: Option<&DatabaseKey>, encrypted: bool) -> Result<Option<String>> {
+    let options = encryption::with_key(SqliteConnectOptions::new().filename(path).read_only(true), key);
+    let result = async {
+        let mut conn = SqliteConnection::connect_with(&options).await?;
+        let rows: Vec<String> = sqlx::query_scalar("PRAGMA quick_check")
+            .fetch_all(&mut conn)
+            .await?;
+        let _ = conn.close().await;
+        Ok::<_, sqlx::Error>(rows)
+    }
+    .await;
+
+    match result {
+        Ok(rows) if rows.iter().all(|row| row == "ok") => Ok(None),
+        Ok(rows) => Ok(Some(rows.join("; "))),
+        Err(sqlx::Error::Database(e)) if is_corruption_code(e.code().as_deref(), encrypted) => {
+            Ok(Some(e.message().to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// SQLITE_CORRUPT(11) 及其扩展码；未加密时还有 SQLITE_NOTADB(26)
+fn is_corruption_code(code: Option<&str>, encrypted: bool) -> bool {
+    code.and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| match code & 0xff {
+            11 => true,
+            26 => !encrypted,
+            _ => false,
+        })
+}
+
+async fn recover_if_corrupt(db_path: &Path, backup_dir: &Path, key: Option<&DatabaseKey>) -> Result<StorageRecovery> {
+    if !db_path.exists() {
+        return Ok(StorageRecovery::Healthy);
+    }
+    // 已启用或正在更换主密码时，NOTADB 更可能是密钥不对而不是文件损坏
+    let config_dir = db_path.parent().unwrap_or_else(|| Path::new("."));
+    let encrypted = key.is_some()
+        || MasterPassword::load(config_dir)?.is_some()
+        || MasterPassword::load_pending(config_dir)?.is_some();
+    let Some(reason) = find_corruption(db_path, key, encrypted).await? else {
+        return Ok(StorageRecovery::Healthy);
+    };
+    log::error!("Storage database {} is corrupt: {}", db_path.display(), reason);
+
+    // 保留损坏的文件（连同 WAL 日志）以便手动排查
+    let corrupt = append_to_file_name(db_path, &format!(".corrupt-{}", now()));
+    std::fs::rename(db_path, &corrupt)?;
+    for suffix in ["-wal", "-shm"] {
+        let journal = append_to_file_name(db_path, suffix);
+        if journal.exists() {
+            std::fs::rename(&journal, append_to_file_name(&corrupt, suffix))?;
+        }
+    }
+
+    for backup in list_backups(backup_dir)? {
+        match find_corruption(&backup.path, key, encrypted).await {
+            Ok(None) => {
+                std::fs::copy(&backup.path, db_path)?;
+                return Ok(StorageRecovery::RestoredFromBackup { backup: backup.path, corrupt });
+            }
+            Ok(Some(reason)) => log::warn!("Skipping corrupt backup {}: {}", backup.path.display(), reason),
+            Err(e) => log::warn!("Skipping unreadable backup {}: {}", backup.path.display(), e),
+        }
+    }
+    Ok(StorageRecovery::Recreated { corrupt })
+}
+
+fn append_to_file_name(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
 }
 
 impl Clone for StorageManager {
@@ -84,13 +426,16 @@ impl Clone for StorageManager {
         Self {
             pool: Arc::clone(&self.pool),
             repositories: Arc::clone(&self.repositories),
+            db_path: self.db_path.clone(),
+            recovery: self.recovery.clone(),
+            key: self.key.clone(),
         }
     }
 }
 
 pub fn get_db_path() -> Result<String> {
     let config_dir = get_config_dir()?;
-    let db_path = config_dir.join("one-hub.db");
+    let db_path = config_dir.join(DB_FILE_NAME);
     Ok(format!("sqlite://{}",db_path.display()))
 }
 
@@ -122,10 +467,10 @@ pub fn now() -> i64 {
         .as_secs() as i64
 }
 
-/// Initialize storage manager and set as global
-pub fn init(cx: &mut App) {
+/// Initialize storage manager and set as global; `key` is the one unlocked with the master password
+pub fn init(cx: &mut App, key: Option<DatabaseKey>) {
     let global_storage_state = Tokio::block_on(cx, async move {
-        match StorageManager::new().await {
+        match StorageManager::new(key).await {
             Ok(manager) => {
                 GlobalStorageState { storage: manager }
             }
@@ -140,3 +485,131 @@ pub fn init(cx: &mut App) {
 
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("one-hub-storage-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(DB_FILE_NAME)
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_database_is_restored_from_backup() {
+        let db_path = temp_db_path();
+        let storage = StorageManager::open(db_path.clone(), None).await.unwrap();
+        assert_eq!(storage.recovery(), &StorageRecovery::Healthy);
+        assert!(storage.integrity_check().await.unwrap().is_empty());
+
+        let pool = storage.get_pool().await.unwrap();
+        sqlx::query("CREATE TABLE notes (body TEXT)").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO notes VALUES ('kept')").execute(&pool).await.unwrap();
+        let backup = storage.backup().await.unwrap();
+        pool.close().await;
+
+        std::fs::write(&db_path, vec![0xA5u8; 8192]).unwrap();
+
+        let storage = StorageManager::open(db_path.clone(), None).await.unwrap();
+        match storage.recovery() {
+            StorageRecovery::RestoredFromBackup { backup: restored, corrupt } => {
+                assert_eq!(restored, &backup.path);
+                assert!(corrupt.exists());
+            }
+            other => panic!("unexpected recovery: {:?}", other),
+        }
+        let pool = storage.get_pool().await.unwrap();
+        let body: String = sqlx::query_scalar("SELECT body FROM notes").fetch_one(&pool).await.unwrap();
+        assert_eq!(body, "kept");
+    }
+
+    #[tokio::test]
+    async fn test_old_backups_are_pruned() {
+        let db_path = temp_db_path();
+        let backup_dir = backup_dir(&db_path);
+        std::fs::create_dir_all(&backup_dir).unwrap();
+        for ts in 1..=7 {
+            std::fs::write(backup_dir.join(format!("{}{}.db", BACKUP_PREFIX, ts)), b"").unwrap();
+        }
+
+        // 最新的备份很旧，打开时会自动备份并清理
+        let storage = StorageManager::open(db_path, None).await.unwrap();
+        let backups = storage.backups().unwrap();
+        assert_eq!(backups.len(), MAX_BACKUPS);
+        assert!(backups[0].created_at > 7);
+        assert_eq!(backups.last().map(|b| b.created_at), Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_master_password_encrypts_database() {
+        let db_path = temp_db_path();
+        let config_dir = db_path.parent().unwrap().to_path_buf();
+        let storage = StorageManager::open(db_path.clone(), None).await.unwrap();
+        let pool = storage.get_pool().await.unwrap();
+        sqlx::query("CREATE TABLE notes (body TEXT)").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO notes VALUES ('secret note')").execute(&pool).await.unwrap();
+
+        storage.set_master_password(Some("correct horse".to_string())).await.unwrap();
+        assert!(storage.backups().unwrap().is_empty());
+        let contents = std::fs::read(&db_path).unwrap();
+        assert!(!contents.windows(b"secret note".len()).any(|window| window == b"secret note"));
+
+        let master = MasterPassword::load(&config_dir).unwrap().unwrap();
+        assert!(!encryption::verify_key(&db_path, &master.derive_key("wrong").unwrap()).await.unwrap());
+        let key = master.derive_key("correct horse").unwrap();
+        let storage = StorageManager::open(db_path.clone(), Some(key)).await.unwrap();
+        assert!(storage.check_master_password("correct horse").await.unwrap());
+        let pool = storage.get_pool().await.unwrap();
+        let body: String = sqlx::query_scalar("SELECT body FROM notes").fetch_one(&pool).await.unwrap();
+        assert_eq!(body, "secret note");
+
+        storage.set_master_password(None).await.unwrap();
+        assert!(MasterPassword::load(&config_dir).unwrap().is_none());
+        let storage = StorageManager::open(db_path, None).await.unwrap();
+        let pool = storage.get_pool().await.unwrap();
+        let body: String = sqlx::query_scalar("SELECT body FROM notes").fetch_one(&pool).await.unwrap();
+        assert_eq!(body, "secret note");
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_master_password_change() {
+        let db_path = temp_db_path();
+        let config_dir = db_path.parent().unwrap().to_path_buf();
+        let master = MasterPassword::generate().unwrap();
+
+        // 导出完成但数据库还没替换
+        MasterPassword::save_pending(Some(&master), &config_dir).unwrap();
+        std::fs::write(append_to_file_name(&db_path, EXPORTED_SUFFIX), b"").unwrap();
+        finish_master_password_change(&db_path).unwrap();
+        assert!(MasterPassword::load(&config_dir).unwrap().is_none());
+        assert!(!append_to_file_name(&db_path, EXPORTED_SUFFIX).exists());
+
+        // 数据库已替换但派生参数还没生效
+        MasterPassword::save_pending(Some(&master), &config_dir).unwrap();
+        finish_master_password_change(&db_path).unwrap();
+        assert!(MasterPassword::load(&config_dir).unwrap().is_some());
+        assert!(MasterPassword::load_pending(&config_dir).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_key_is_not_treated_as_corruption() {
+        let db_path = temp_db_path();
+        let storage = StorageManager::open(db_path.clone(), None).await.unwrap();
+        storage.set_master_password(Some("correct horse".to_string())).await.unwrap();
+
+        let master = MasterPassword::load(db_path.parent().unwrap()).unwrap().unwrap();
+        assert!(StorageManager::open(db_path.clone(), Some(master.derive_key("wrong").unwrap())).await.is_err());
+        assert!(db_path.exists());
+    }
+
+    #[test]
+    fn test_corruption_codes() {
+        assert!(is_corruption_code(Some("11"), false));
+        assert!(is_corruption_code(Some("779"), true));
+        assert!(is_corruption_code(Some("26"), false));
+        assert!(!is_corruption_code(Some("26"), true));
+        assert!(!is_corruption_code(Some("5"), false));
+        assert!(!is_corruption_code(None, false));
+    }
+}
@@ -0,0 +1,324 @@
+//! 内部存储的版本化迁移
+//!
+//! 迁移按迁移集（通常一个仓库一个）分组，每个迁移集内按版本号顺序执行，
+//! 执行记录保存在 `storage_migrations` 表中。内置的 `crates/core/migrations`
+//! 属于 `core` 迁移集；早期版本由 sqlx 记录在 `_sqlx_migrations` 中的历史会被导入，
+//! 原表保留，降级回早期版本时 sqlx 仍能认出已执行的迁移。
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use ring::digest::{digest, SHA384};
+use sqlx::{FromRow, SqlitePool};
+use tracing::log;
+
+use crate::storage::manager::now;
+
+/// 内置迁移所属的迁移集
+pub const CORE_MIGRATION_SET: &str = "core";
+
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub version: i64,
+    pub description: Cow<'static, str>,
+    pub sql: Cow<'static, str>,
+}
+
+impl Migration {
+    pub fn new(
+        version: i64,
+        description: impl Into<Cow<'static, str>>,
+        sql: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            version,
+            description: description.into(),
+            sql: sql.into(),
+        }
+    }
+
+    /// 与 sqlx 相同的校验和（SQL 的 SHA-384），导入的历史记录可以直接比对
+    fn checksum(&self) -> Vec<u8> {
+        digest(&SHA384, self.sql.as_bytes()).as_ref().to_vec()
+    }
+}
+
+/// 一组按版本号顺序执行的迁移
+#[derive(Debug, Clone)]
+pub struct MigrationSet {
+    pub name: Cow<'static, str>,
+    migrations: Vec<Migration>,
+}
+
+impl MigrationSet {
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            migrations: Vec::new(),
+        }
+    }
+
+    pub fn migration(
+        mut self,
+        version: i64,
+        description: impl Into<Cow<'static, str>>,
+        sql: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.migrations.push(Migration::new(version, description, sql));
+        self
+    }
+
+    /// `crates/core/migrations` 下的内置迁移
+    pub fn core() -> Self {
+        let migrations = sqlx::migrate!("./migrations")
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| Migration::new(m.version, m.description.clone(), m.sql.clone()))
+            .collect();
+        Self {
+            name: CORE_MIGRATION_SET.into(),
+            migrations,
+        }
+    }
+}
+
+/// 尚未执行（或刚刚执行）的迁移
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingMigration {
+    pub set: String,
+    pub version: i64,
+    pub description: String,
+}
+
+#[derive(FromRow)]
+struct AppliedRow {
+    set_name: String,
+    version: i64,
+    checksum: Vec<u8>,
+}
+
+pub struct Migrator {
+    sets: Vec<MigrationSet>,
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Migrator {
+    /// 包含内置迁移的迁移器
+    pub fn new() -> Self {
+        Self {
+            sets: vec![MigrationSet::core()],
+        }
+    }
+
+    pub fn with_set(mut self, set: MigrationSet) -> Self {
+        self.sets.push(set);
+        self
+    }
+
+    /// 预演：校验已执行的迁移并列出待执行的迁移，不执行任何迁移
+    pub async fn pending(&self, pool: &SqlitePool) -> Result<Vec<PendingMigration>> {
+        Ok(self
+            .plan(pool)
+            .await?
+            .into_iter()
+            .map(|(set, migration)| PendingMigration {
+                set: set.to_string(),
+                version: migration.version,
+                description: migration.description.to_string(),
+            })
+            .collect())
+    }
+
+    /// 依次执行待执行的迁移，每个迁移与其执行记录在同一个事务中提交
+    pub async fn run(&self, pool: &SqlitePool) -> Result<Vec<PendingMigration>> {
+        let mut applied = Vec::new();
+        for (set, migration) in self.plan(pool).await? {
+            let mut tx = pool.begin().await?;
+            sqlx::raw_sql(&migration.sql)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow!("迁移 {}/{} ({}) 执行失败: {}", set, migration.version, migration.description, e))?;
+            sqlx::query(
+                "INSERT INTO storage_migrations (set_name, version, description, checksum, applied_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(set)
+            .bind(migration.version)
+            .bind(migration.description.as_ref())
+            .bind(migration.checksum())
+            .bind(now())
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            log::info!("Applied storage migration {}/{} {}", set, migration.version, migration.description);
+            applied.push(PendingMigration {
+                set: set.to_string(),
+                version: migration.version,
+                description: migration.description.to_string(),
+            });
+        }
+        Ok(applied)
+    }
+
+    async fn plan(&self, pool: &SqlitePool) -> Result<Vec<(&str, &Migration)>> {
+        ensure_migrations_table(pool).await?;
+
+        let rows: Vec<AppliedRow> = sqlx::query_as("SELECT set_name, version, checksum FROM storage_migrations")
+            .fetch_all(pool)
+            .await?;
+        let applied: HashMap<(String, i64), Vec<u8>> = rows
+            .into_iter()
+            .map(|row| ((row.set_name, row.version), row.checksum))
+            .collect();
+
+        let mut pending = Vec::new();
+        for set in &self.sets {
+            let mut migrations: Vec<&Migration> = set.migrations.iter().collect();
+            migrations.sort_by_key(|m| m.version);
+            if let Some(pair) = migrations.windows(2).find(|pair| pair[0].version == pair[1].version) {
+                return Err(anyhow!("迁移集 {} 中存在重复的版本号 {}", set.name, pair[0].version));
+            }
+
+            for migration in migrations {
+                match applied.get(&(set.name.to_string(), migration.version)) {
+                    Some(checksum) if *checksum != migration.checksum() => {
+                        return Err(anyhow!(
+                            "迁移 {}/{} ({}) 在执行后被修改",
+                            set.name,
+                            migration.version,
+                            migration.description
+                        ));
+                    }
+                    Some(_) => {}
+                    None => pending.push((set.name.as_ref(), migration)),
+                }
+            }
+        }
+        Ok(pending)
+    }
+}
+
+async fn ensure_migrations_table(pool: &SqlitePool) -> Result<()> {
+    // 建表和导入要么都完成要么都不做，避免留下只导入了一半的历史
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS storage_migrations (
+            set_name TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            description TEXT NOT NULL,
+            checksum BLOB NOT NULL,
+            applied_at INTEGER NOT NULL,
+            PRIMARY KEY (set_name, version)
+        )
+        "#,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // 导入 sqlx 记录的内置迁移历史，已导入的记录被忽略
+    let has_sqlx_history: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'")
+            .fetch_optional(&mut *tx)
+            .await?;
+    if has_sqlx_history.is_some() {
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO storage_migrations (set_name, version, description, checksum, applied_at)
+            SELECT ?, version, description, checksum, CAST(strftime('%s', installed_on) AS INTEGER)
+            FROM _sqlx_migrations
+            WHERE success = 1
+            "#,
+        )
+        .bind(CORE_MIGRATION_SET)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn memory_pool() -> SqlitePool {
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    fn notes_set() -> MigrationSet {
+        MigrationSet::new("notes")
+            .migration(2, "add notes title", "ALTER TABLE notes ADD COLUMN title TEXT")
+            .migration(1, "create notes", "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)")
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_then_run_in_version_order() {
+        let pool = memory_pool().await;
+        let migrator = Migrator::new().with_set(notes_set());
+
+        let pending = migrator.pending(&pool).await.unwrap();
+        let notes: Vec<i64> = pending.iter().filter(|m| m.set == "notes").map(|m| m.version).collect();
+        assert_eq!(notes, vec![1, 2]);
+        assert!(pending.iter().any(|m| m.set == CORE_MIGRATION_SET));
+
+        // 预演不会创建任何业务表
+        let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE name = 'notes'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tables, 0);
+
+        let applied = migrator.run(&pool).await.unwrap();
+        assert_eq!(applied.len(), pending.len());
+        sqlx::query("INSERT INTO notes (body, title) VALUES ('b', 't')").execute(&pool).await.unwrap();
+
+        assert!(migrator.pending(&pool).await.unwrap().is_empty());
+        assert!(migrator.run(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_modified_migration_is_rejected() {
+        let pool = memory_pool().await;
+        Migrator::new().with_set(notes_set()).run(&pool).await.unwrap();
+
+        let changed = MigrationSet::new("notes")
+            .migration(1, "create notes", "CREATE TABLE notes (id INTEGER PRIMARY KEY)")
+            .migration(2, "add notes title", "ALTER TABLE notes ADD COLUMN title TEXT");
+        let error = Migrator::new().with_set(changed).pending(&pool).await.unwrap_err();
+        assert!(error.to_string().contains("notes/1"));
+    }
+
+    #[tokio::test]
+    async fn test_sqlx_history_is_imported() {
+        let pool = memory_pool().await;
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let migrator = Migrator::new();
+        assert!(migrator.pending(&pool).await.unwrap().is_empty());
+        let imported: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM storage_migrations WHERE set_name = ?")
+            .bind(CORE_MIGRATION_SET)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(imported as usize, MigrationSet::core().migrations.len());
+
+        // 保留 sqlx 的历史，降级后旧版本不会重新执行迁移
+        assert!(migrator.pending(&pool).await.unwrap().is_empty());
+        let legacy: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(legacy as usize, MigrationSet::core().migrations.len());
+    }
+}
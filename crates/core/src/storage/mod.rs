@@ -4,15 +4,30 @@ pub mod repository;
 pub mod traits;
 pub mod query_model;
 pub mod query_repository;
+pub mod connection_share;
+pub mod connection_import;
+pub mod secrets;
+pub mod encryption;
+pub mod schema_cache;
+pub mod migration;
+pub mod workspace_variable;
+pub mod usage_metrics;
+pub mod schema_embedding;
 
 use gpui::App;
+use encryption::DatabaseKey;
 pub use manager::*;
 pub use models::*;
 pub use repository::*;
+pub use schema_cache::*;
+pub use migration::*;
+pub use workspace_variable::*;
+pub use usage_metrics::*;
+pub use schema_embedding::*;
 
 
-pub fn init(cx: &mut App){
+pub fn init(cx: &mut App, key: Option<DatabaseKey>){
     cx.set_global(ActiveConnections::new());
-    manager::init(cx);
+    manager::init(cx, key);
     repository::init(cx);
 }
\ No newline at end of file
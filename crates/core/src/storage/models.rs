@@ -1,7 +1,7 @@
 use crate::storage::traits::Entity;
-use gpui::Global;
+use gpui::{App, Global, Hsla};
 use gpui_component::Size::Large;
-use gpui_component::{Icon, IconName, Sizable};
+use gpui_component::{ActiveTheme, Icon, IconName, Sizable};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fmt;
@@ -38,6 +38,24 @@ impl ActiveConnections {
     }
 }
 
+/// 连接树等处请求打开的连接编辑器，由主页打开表单并负责保存
+#[derive(Default)]
+pub struct PendingConnectionEdits {
+    connections: Vec<StoredConnection>,
+}
+
+impl Global for PendingConnectionEdits {}
+
+impl PendingConnectionEdits {
+    pub fn push(connection: StoredConnection, cx: &mut App) {
+        cx.default_global::<PendingConnectionEdits>().connections.push(connection);
+    }
+
+    pub fn take(cx: &mut App) -> Vec<StoredConnection> {
+        std::mem::take(&mut cx.default_global::<PendingConnectionEdits>().connections)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ConnectionType {
     All,
@@ -76,44 +94,76 @@ impl ConnectionType {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DatabaseType {
     MySQL,
+    TiDB,
     PostgreSQL,
+    CockroachDB,
     SQLite,
     MSSQL,
     Oracle,
     ClickHouse,
+    MongoDB,
+    Redis,
+    DuckDB,
+    Trino,
+    Elasticsearch,
+    ODBC,
 }
 
 impl DatabaseType {
     pub fn all() -> &'static [DatabaseType] {
         &[
             DatabaseType::MySQL,
+            DatabaseType::TiDB,
             DatabaseType::PostgreSQL,
+            DatabaseType::CockroachDB,
             DatabaseType::SQLite,
             DatabaseType::MSSQL,
             DatabaseType::Oracle,
             DatabaseType::ClickHouse,
+            DatabaseType::MongoDB,
+            DatabaseType::Redis,
+            DatabaseType::DuckDB,
+            DatabaseType::Trino,
+            DatabaseType::Elasticsearch,
+            DatabaseType::ODBC,
         ]
     }
 
     pub fn as_str(&self) -> &str {
         match self {
             DatabaseType::MySQL => "MySQL",
+            DatabaseType::TiDB => "TiDB",
             DatabaseType::PostgreSQL => "PostgreSQL",
+            DatabaseType::CockroachDB => "CockroachDB",
             DatabaseType::SQLite => "SQLite",
             DatabaseType::MSSQL => "MSSQL",
             DatabaseType::Oracle => "Oracle",
             DatabaseType::ClickHouse => "ClickHouse",
+            DatabaseType::MongoDB => "MongoDB",
+            DatabaseType::Redis => "Redis",
+            DatabaseType::DuckDB => "DuckDB",
+            DatabaseType::Trino => "Trino",
+            DatabaseType::Elasticsearch => "Elasticsearch",
+            DatabaseType::ODBC => "ODBC",
         }
     }
 
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "MySQL" => Some(DatabaseType::MySQL),
+            "TiDB" => Some(DatabaseType::TiDB),
             "PostgreSQL" => Some(DatabaseType::PostgreSQL),
+            "CockroachDB" => Some(DatabaseType::CockroachDB),
             "SQLite" => Some(DatabaseType::SQLite),
             "MSSQL" => Some(DatabaseType::MSSQL),
             "Oracle" => Some(DatabaseType::Oracle),
             "ClickHouse" => Some(DatabaseType::ClickHouse),
+            "MongoDB" => Some(DatabaseType::MongoDB),
+            "Redis" => Some(DatabaseType::Redis),
+            "DuckDB" => Some(DatabaseType::DuckDB),
+            "Trino" => Some(DatabaseType::Trino),
+            "Elasticsearch" => Some(DatabaseType::Elasticsearch),
+            "ODBC" => Some(DatabaseType::ODBC),
             _ => None,
         }
     }
@@ -121,21 +171,37 @@ impl DatabaseType {
     pub fn as_icon(&self) -> Icon {
         match self {
             DatabaseType::MySQL => IconName::MySQLColor.color().with_size(Large),
+            DatabaseType::TiDB => Icon::new(IconName::Database).with_size(Large),
             DatabaseType::PostgreSQL => IconName::PostgreSQLColor.color().with_size(Large),
+            DatabaseType::CockroachDB => Icon::new(IconName::Database).with_size(Large),
             DatabaseType::SQLite => IconName::SQLiteColor.color().with_size(Large),
             DatabaseType::MSSQL => IconName::MSSQLColor.color().with_size(Large),
             DatabaseType::Oracle => IconName::OracleColor.color().with_size(Large),
             DatabaseType::ClickHouse => IconName::ClickHouseColor.color().with_size(Large),
+            DatabaseType::MongoDB => IconName::MongoDB.color().with_size(Large),
+            DatabaseType::Redis => IconName::RedisColor.color().with_size(Large),
+            DatabaseType::DuckDB => Icon::new(IconName::Database).with_size(Large),
+            DatabaseType::Trino => Icon::new(IconName::Database).with_size(Large),
+            DatabaseType::Elasticsearch => Icon::new(IconName::Database).with_size(Large),
+            DatabaseType::ODBC => Icon::new(IconName::Database).with_size(Large),
         }
     }
     pub fn as_node_icon(&self) -> Icon {
         match self {
             DatabaseType::MySQL => IconName::MySQLLineColor.color().with_size(Large),
+            DatabaseType::TiDB => Icon::new(IconName::Database).with_size(Large),
             DatabaseType::PostgreSQL => IconName::PostgreSQLLineColor.color().with_size(Large),
+            DatabaseType::CockroachDB => Icon::new(IconName::Database).with_size(Large),
             DatabaseType::SQLite => IconName::SQLiteLineColor.color().with_size(Large),
             DatabaseType::MSSQL => IconName::MSSQLLineColor.color().with_size(Large),
             DatabaseType::Oracle => IconName::OracleLineColor.color().with_size(Large),
             DatabaseType::ClickHouse => IconName::ClickHouseLineColor.color().with_size(Large),
+            DatabaseType::MongoDB => IconName::MongoDB.color().with_size(Large),
+            DatabaseType::Redis => IconName::RedisColor.color().with_size(Large),
+            DatabaseType::DuckDB => Icon::new(IconName::Database).with_size(Large),
+            DatabaseType::Trino => Icon::new(IconName::Database).with_size(Large),
+            DatabaseType::Elasticsearch => Icon::new(IconName::Database).with_size(Large),
+            DatabaseType::ODBC => Icon::new(IconName::Database).with_size(Large),
         }
     }
 }
@@ -201,6 +267,296 @@ impl DbConnectionConfig {
             .map(|v| v == "true" || v == "1")
             .unwrap_or(false)
     }
+
+    /// 连接所属环境，保存在 extra_params 的 `environment` 中
+    pub fn environment(&self) -> ConnectionEnvironment {
+        self.get_param("environment")
+            .map(|v| ConnectionEnvironment::from_str(v))
+            .unwrap_or_default()
+    }
+
+    /// SSL/TLS 设置，保存在 extra_params 的 `ssl_mode`、`ssl_ca`、`ssl_cert`、`ssl_key` 中
+    pub fn ssl(&self) -> SslConfig {
+        let path = |key: &str| {
+            self.get_param(key)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        SslConfig {
+            mode: self.get_param("ssl_mode").map(|v| SslMode::from_str(v)).unwrap_or_default(),
+            ca_cert: path("ssl_ca"),
+            client_cert: path("ssl_cert"),
+            client_key: path("ssl_key"),
+        }
+    }
+
+    /// 代理设置，保存在 extra_params 的 `proxy_type`、`proxy_host`、`proxy_port`、
+    /// `proxy_username`、`proxy_password` 中；未选择代理类型或主机为空时返回 None
+    pub fn proxy(&self) -> Option<ProxyConfig> {
+        let kind = self.get_param("proxy_type").map(|v| ProxyType::from_str(v)).unwrap_or_default();
+        let host = self.get_param("proxy_host").map(|v| v.trim().to_string()).filter(|v| !v.is_empty())?;
+        let port = match kind {
+            ProxyType::None => return None,
+            ProxyType::Socks5 => self.get_param_as::<u16>("proxy_port").unwrap_or(1080),
+            ProxyType::Http => self.get_param_as::<u16>("proxy_port").unwrap_or(8080),
+        };
+        Some(ProxyConfig {
+            kind,
+            host,
+            port,
+            username: self.get_param("proxy_username").filter(|v| !v.is_empty()).cloned(),
+            password: self.get_param("proxy_password").filter(|v| !v.is_empty()).cloned(),
+        })
+    }
+}
+
+/// 数据库连接使用的代理类型
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProxyType {
+    #[default]
+    None,
+    Socks5,
+    /// 通过 HTTP CONNECT 建立隧道
+    Http,
+}
+
+impl ProxyType {
+    pub fn all() -> &'static [ProxyType] {
+        &[ProxyType::None, ProxyType::Socks5, ProxyType::Http]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProxyType::None => "none",
+            ProxyType::Socks5 => "socks5",
+            ProxyType::Http => "http",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "socks5" => ProxyType::Socks5,
+            "http" => ProxyType::Http,
+            _ => ProxyType::None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProxyType::None => "不使用",
+            ProxyType::Socks5 => "SOCKS5",
+            ProxyType::Http => "HTTP (CONNECT)",
+        }
+    }
+}
+
+/// 连接的代理设置
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub kind: ProxyType,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// 连接的 TLS 模式，含义与 libpq 的 sslmode 一致
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SslMode {
+    #[default]
+    Disable,
+    /// 加密传输，但不校验服务器证书
+    Require,
+    /// 校验服务器证书由受信任的 CA 签发，不校验主机名
+    VerifyCa,
+    /// 校验服务器证书和主机名
+    VerifyFull,
+}
+
+impl SslMode {
+    pub fn all() -> &'static [SslMode] {
+        &[SslMode::Disable, SslMode::Require, SslMode::VerifyCa, SslMode::VerifyFull]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "disable",
+            SslMode::Require => "require",
+            SslMode::VerifyCa => "verify-ca",
+            SslMode::VerifyFull => "verify-full",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "require" => SslMode::Require,
+            "verify-ca" => SslMode::VerifyCa,
+            "verify-full" => SslMode::VerifyFull,
+            _ => SslMode::Disable,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SslMode::Disable => "不使用",
+            SslMode::Require => "加密 (不校验证书)",
+            SslMode::VerifyCa => "校验 CA",
+            SslMode::VerifyFull => "校验 CA 和主机名",
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        *self != SslMode::Disable
+    }
+
+    pub fn verifies_certificate(&self) -> bool {
+        matches!(self, SslMode::VerifyCa | SslMode::VerifyFull)
+    }
+
+    pub fn verifies_hostname(&self) -> bool {
+        *self == SslMode::VerifyFull
+    }
+}
+
+/// 连接的 SSL/TLS 设置；证书和私钥均为 PEM 文件路径
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SslConfig {
+    pub mode: SslMode,
+    pub ca_cert: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+}
+
+impl SslConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.mode.is_enabled()
+    }
+
+    /// 客户端证书和私钥需要成对配置
+    pub fn client_identity(&self) -> Result<Option<(&str, &str)>, String> {
+        match (self.client_cert.as_deref(), self.client_key.as_deref()) {
+            (Some(cert), Some(key)) => Ok(Some((cert, key))),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err("已配置客户端证书，但缺少客户端私钥".to_string()),
+            (None, Some(_)) => Err("已配置客户端私钥，但缺少客户端证书".to_string()),
+        }
+    }
+}
+
+/// 连接所属环境，用于决定危险操作的确认方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConnectionEnvironment {
+    #[default]
+    Development,
+    Testing,
+    Production,
+}
+
+impl ConnectionEnvironment {
+    pub fn all() -> &'static [ConnectionEnvironment] {
+        &[
+            ConnectionEnvironment::Development,
+            ConnectionEnvironment::Testing,
+            ConnectionEnvironment::Production,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionEnvironment::Development => "development",
+            ConnectionEnvironment::Testing => "testing",
+            ConnectionEnvironment::Production => "production",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "testing" => ConnectionEnvironment::Testing,
+            "production" => ConnectionEnvironment::Production,
+            _ => ConnectionEnvironment::Development,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionEnvironment::Development => "开发",
+            ConnectionEnvironment::Testing => "测试",
+            ConnectionEnvironment::Production => "生产",
+        }
+    }
+
+    /// 未设置颜色标签时生产环境默认标红，避免误操作
+    pub fn default_color(&self) -> Option<ConnectionColor> {
+        match self {
+            ConnectionEnvironment::Production => Some(ConnectionColor::Red),
+            _ => None,
+        }
+    }
+}
+
+/// 连接的颜色标签
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionColor {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Cyan,
+    Magenta,
+}
+
+impl ConnectionColor {
+    pub fn all() -> &'static [ConnectionColor] {
+        &[
+            ConnectionColor::Red,
+            ConnectionColor::Yellow,
+            ConnectionColor::Green,
+            ConnectionColor::Blue,
+            ConnectionColor::Cyan,
+            ConnectionColor::Magenta,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectionColor::Red => "red",
+            ConnectionColor::Yellow => "yellow",
+            ConnectionColor::Green => "green",
+            ConnectionColor::Blue => "blue",
+            ConnectionColor::Cyan => "cyan",
+            ConnectionColor::Magenta => "magenta",
+        }
+    }
+
+    /// 未知值视为未设置颜色
+    pub fn from_str(s: &str) -> Option<Self> {
+        ConnectionColor::all().iter().copied().find(|color| color.as_str() == s)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionColor::Red => "红色",
+            ConnectionColor::Yellow => "黄色",
+            ConnectionColor::Green => "绿色",
+            ConnectionColor::Blue => "蓝色",
+            ConnectionColor::Cyan => "青色",
+            ConnectionColor::Magenta => "紫色",
+        }
+    }
+
+    /// 跟随主题的颜色值
+    pub fn hsla(&self, cx: &App) -> Hsla {
+        let theme = cx.theme();
+        match self {
+            ConnectionColor::Red => theme.red,
+            ConnectionColor::Yellow => theme.yellow,
+            ConnectionColor::Green => theme.green,
+            ConnectionColor::Blue => theme.blue,
+            ConnectionColor::Cyan => theme.cyan,
+            ConnectionColor::Magenta => theme.magenta,
+        }
+    }
 }
 
 impl ConnectionType {
@@ -283,6 +639,12 @@ pub struct StoredConnection {
     pub selected_databases: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub remark: Option<String>,
+    /// 启动时自动打开
+    #[serde(default)]
+    pub auto_open: bool,
+    /// 颜色标签，显示在连接树、标签页和编辑器边框上
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ConnectionColor>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -313,6 +675,8 @@ impl StoredConnection {
             workspace_id,
             selected_databases: if let Some(database) = &params.database { Some(format!("[\"{}\"]", database)) } else {None},
             remark: None,
+            auto_open: false,
+            color: None,
             created_at: None,
             updated_at: None,
         }
@@ -327,6 +691,8 @@ impl StoredConnection {
             workspace_id,
             selected_databases: None,
             remark: None,
+            auto_open: false,
+            color: None,
             created_at: None,
             updated_at: None,
         }
@@ -341,6 +707,8 @@ impl StoredConnection {
             workspace_id,
             selected_databases: None,
             remark: None,
+            auto_open: false,
+            color: None,
             created_at: None,
             updated_at: None,
         }
@@ -355,6 +723,8 @@ impl StoredConnection {
             workspace_id,
             selected_databases: None,
             remark: None,
+            auto_open: false,
+            color: None,
             created_at: None,
             updated_at: None,
         }
@@ -380,6 +750,18 @@ impl StoredConnection {
         Ok(params)
     }
 
+    /// 复制出的新连接，保存前没有 id；名称加上 "(copy)" 后缀，不继承启动时自动打开
+    pub fn duplicate(&self) -> Self {
+        Self {
+            id: None,
+            name: format!("{} (copy)", self.name),
+            auto_open: false,
+            created_at: None,
+            updated_at: None,
+            ..self.clone()
+        }
+    }
+
     pub fn from_db_connection(connection: DbConnectionConfig) -> Self {
         let name = connection.name.clone();
         let workspace_id = connection.workspace_id.clone();
@@ -44,4 +44,12 @@ impl Query {
             updated_at: None,
         }
     }
-}
\ No newline at end of file
+}
+/// 保存的查询的一次执行记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct QueryRun {
+    pub query_id: i64,
+    pub elapsed_ms: i64,
+    pub row_count: i64,
+    pub executed_at: i64,
+}
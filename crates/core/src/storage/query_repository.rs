@@ -1,10 +1,14 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use gpui::SharedString;
-use sqlx::SqlitePool;
+use sqlx::{SqliteConnection, SqliteExecutor, SqlitePool};
 
 use crate::storage::traits::Repository;
-use crate::storage::query_model::Query;
+use crate::storage::query_model::{Query, QueryRun};
+use crate::storage::manager::now;
+
+/// 每个查询保留的执行记录条数
+pub const MAX_QUERY_RUNS: i64 = 200;
 
 #[derive(Clone)]
 pub struct QueryRepository {
@@ -15,24 +19,14 @@ impl QueryRepository {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
     }
-}
-
-#[async_trait]
-impl Repository for QueryRepository {
-    type Entity = Query;
-
-    fn entity_type(&self) -> SharedString {
-        SharedString::from("Query")
-    }
 
-    async fn insert(&self, item: &mut Self::Entity) -> Result<i64> {
+    async fn insert_with(conn: &mut SqliteConnection, item: &mut Query, now: i64) -> Result<i64> {
         Self::validate_query_name(&item.name)?;
 
-        if self.find_by_name(&item.connection_id, &item.name).await?.is_some() {
+        if Self::find_by_name_with(&mut *conn, &item.connection_id, &item.name).await?.is_some() {
             return Err(anyhow::anyhow!("A query with this name already exists in the connection"));
         }
 
-        let now = crate::storage::manager::now();
         let result = sqlx::query(
             r#"
             INSERT INTO queries (name, content, connection_id, database_name, created_at, updated_at)
@@ -45,7 +39,7 @@ impl Repository for QueryRepository {
         .bind(&item.database_name)
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
 
         let id = result.last_insert_rowid();
@@ -56,17 +50,16 @@ impl Repository for QueryRepository {
         Ok(id)
     }
 
-    async fn update(&self, item: &Self::Entity) -> Result<()> {
+    async fn update_with(conn: &mut SqliteConnection, item: &Query, now: i64) -> Result<()> {
         let id = item.id.ok_or_else(|| anyhow::anyhow!("Cannot update without ID"))?;
 
         Self::validate_query_name(&item.name)?;
 
-        if let Some(existing) = self.find_by_name(&item.connection_id, &item.name).await?
+        if let Some(existing) = Self::find_by_name_with(&mut *conn, &item.connection_id, &item.name).await?
             && existing.id != item.id {
                 return Err(anyhow::anyhow!("A query with this name already exists in the connection"));
             }
 
-        let now = crate::storage::manager::now();
         sqlx::query(
             r#"
             UPDATE queries
@@ -80,11 +73,30 @@ impl Repository for QueryRepository {
         .bind(&item.database_name)
         .bind(now)
         .bind(id)
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl Repository for QueryRepository {
+    type Entity = Query;
+
+    fn entity_type(&self) -> SharedString {
+        SharedString::from("Query")
+    }
+
+    async fn insert(&self, item: &mut Self::Entity) -> Result<i64> {
+        let mut conn = self.pool.acquire().await?;
+        Self::insert_with(&mut conn, item, now()).await
+    }
+
+    async fn update(&self, item: &Self::Entity) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_with(&mut conn, item, now()).await
+    }
 
     async fn delete(&self, id: i64) -> Result<()> {
         sqlx::query("DELETE FROM queries WHERE id = ?")
@@ -140,6 +152,43 @@ impl Repository for QueryRepository {
 
         Ok(row.is_some())
     }
+
+    /// 名称校验或重名检查失败时整批回滚
+    async fn insert_batch(&self, items: &mut [Self::Entity]) -> Result<Vec<i64>> {
+        let now = now();
+        let mut tx = self.pool.begin().await?;
+        let mut ids = Vec::with_capacity(items.len());
+        for item in items.iter_mut() {
+            ids.push(Self::insert_with(&mut tx, item, now).await?);
+        }
+        tx.commit().await?;
+
+        Ok(ids)
+    }
+
+    async fn update_batch(&self, items: &[Self::Entity]) -> Result<()> {
+        let now = now();
+        let mut tx = self.pool.begin().await?;
+        for item in items {
+            Self::update_with(&mut tx, item, now).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_batch(&self, ids: &[i64]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for id in ids {
+            sqlx::query("DELETE FROM queries WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
 
 impl QueryRepository {
@@ -194,7 +243,59 @@ impl QueryRepository {
         Ok(rows)
     }
 
+    /// 记录一次执行，每个查询只保留最近 `MAX_QUERY_RUNS` 条
+    pub async fn record_run(&self, query_id: i64, elapsed_ms: i64, row_count: i64) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO query_runs (query_id, elapsed_ms, row_count, executed_at) VALUES (?, ?, ?, ?)")
+            .bind(query_id)
+            .bind(elapsed_ms)
+            .bind(row_count)
+            .bind(now())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            DELETE FROM query_runs
+            WHERE query_id = ? AND id NOT IN (
+                SELECT id FROM query_runs WHERE query_id = ? ORDER BY id DESC LIMIT ?
+            )
+            "#,
+        )
+        .bind(query_id)
+        .bind(query_id)
+        .bind(MAX_QUERY_RUNS)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 最近 `limit` 次执行，按执行顺序从早到晚排列
+    pub async fn list_runs(&self, query_id: i64, limit: i64) -> Result<Vec<QueryRun>> {
+        let mut runs: Vec<QueryRun> = sqlx::query_as(
+            r#"
+            SELECT query_id, elapsed_ms, row_count, executed_at
+            FROM query_runs
+            WHERE query_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(query_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        runs.reverse();
+
+        Ok(runs)
+    }
+
     pub async fn find_by_name(&self, connection_id: &str, name: &str) -> Result<Option<Query>> {
+        Self::find_by_name_with(&self.pool, connection_id, name).await
+    }
+
+    async fn find_by_name_with(executor: impl SqliteExecutor<'_>, connection_id: &str, name: &str) -> Result<Option<Query>> {
         let row: Option<Query> = sqlx::query_as(
             r#"
             SELECT id, name, content, connection_id, database_name, created_at, updated_at
@@ -204,9 +305,64 @@ impl QueryRepository {
         )
         .bind(connection_id)
         .bind(name)
-        .fetch_optional(&self.pool)
+        .fetch_optional(executor)
         .await?;
 
         Ok(row)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::run_migrations;
+
+    async fn setup() -> QueryRepository {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        QueryRepository::new(pool)
+    }
+
+    fn query(name: &str) -> Query {
+        Query::new(name.to_string(), "SELECT 1".to_string(), "1".to_string(), None)
+    }
+
+    #[tokio::test]
+    async fn test_batch_insert_rejects_duplicate_names_atomically() {
+        let repo = setup().await;
+
+        let mut items = vec![query("daily report"), query("weekly report"), query("daily report")];
+        assert!(repo.insert_batch(&mut items).await.is_err());
+        assert_eq!(repo.count().await.unwrap(), 0);
+
+        let mut items = vec![query("daily report"), query("weekly report")];
+        let ids = repo.insert_batch(&mut items).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 2);
+
+        repo.delete_batch(&ids).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_runs_are_listed_oldest_first_and_deleted_with_query() {
+        let repo = setup().await;
+
+        let mut item = query("daily report");
+        let id = repo.insert(&mut item).await.unwrap();
+        for (elapsed_ms, row_count) in [(120, 10), (130, 11), (900, 12)] {
+            repo.record_run(id, elapsed_ms, row_count).await.unwrap();
+        }
+
+        let runs = repo.list_runs(id, 2).await.unwrap();
+        let elapsed: Vec<i64> = runs.iter().map(|run| run.elapsed_ms).collect();
+        assert_eq!(elapsed, vec![130, 900]);
+        assert_eq!(runs[1].row_count, 12);
+
+        repo.delete(id).await.unwrap();
+        assert!(repo.list_runs(id, 10).await.unwrap().is_empty());
+    }
+}
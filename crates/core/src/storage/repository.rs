@@ -1,10 +1,16 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use gpui::{App, SharedString};
-use sqlx::{FromRow, SqlitePool};
+use sqlx::{FromRow, SqliteExecutor, SqlitePool};
 use crate::gpui_tokio::Tokio;
-use crate::storage::{traits::Repository, StoredConnection, ConnectionType};
+use crate::storage::{traits::Repository, StoredConnection, ConnectionColor, ConnectionType};
 use crate::storage::query_repository::QueryRepository;
+use crate::storage::schema_cache::SchemaCacheRepository;
+use crate::storage::workspace_variable::WorkspaceVariableRepository;
+use crate::storage::usage_metrics::UsageMetricsRepository;
+use crate::storage::schema_embedding::SchemaEmbeddingRepository;
+use crate::storage::migration::Migrator;
+use crate::storage::secrets::{self, connection_key, merge_connection_secrets, split_connection_secrets, SharedSecretStore};
 use crate::storage::manager::{now, GlobalStorageState};
 use crate::storage::Workspace;
 
@@ -17,6 +23,8 @@ struct ConnectionRow {
     workspace_id: Option<i64>,
     selected_databases: Option<String>,
     remark: Option<String>,
+    auto_open: bool,
+    color: Option<String>,
     created_at: i64,
     updated_at: i64,
 }
@@ -31,6 +39,8 @@ impl From<ConnectionRow> for StoredConnection {
             workspace_id: row.workspace_id,
             selected_databases: row.selected_databases,
             remark: row.remark,
+            auto_open: row.auto_open,
+            color: row.color.as_deref().and_then(ConnectionColor::from_str),
             created_at: Some(row.created_at),
             updated_at: Some(row.updated_at),
         }
@@ -63,40 +73,129 @@ impl From<WorkspaceRow> for Workspace {
 #[derive(Clone)]
 pub struct ConnectionRepository {
     pool: SqlitePool,
+    secrets: SharedSecretStore,
 }
 
 impl ConnectionRepository {
-    pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+    pub fn new(pool: SqlitePool, secrets: SharedSecretStore) -> Self {
+        Self { pool, secrets }
     }
-}
 
-#[async_trait]
-impl Repository for ConnectionRepository {
-    type Entity = StoredConnection;
+    /// 数据库中保存的参数：密码写入钥匙串后只保存去掉密码的参数；钥匙串不可用时仍保存原参数，避免丢失密码
+    async fn params_for_storage(&self, id: i64, params: &str) -> String {
+        let Ok((scrubbed, connection_secrets)) = split_connection_secrets(params) else {
+            return params.to_string();
+        };
+        let result = match connection_secrets {
+            Some(connection_secrets) => secrets::save(&self.secrets, connection_key(id), connection_secrets).await,
+            // 密码被清空时一并删除钥匙串中的旧密码，否则读取时会被放回
+            None => secrets::remove(&self.secrets, connection_key(id)).await,
+        };
+        match result {
+            Ok(()) => scrubbed,
+            Err(e) => {
+                tracing::warn!("Keychain unavailable, keeping connection {} secrets in the database: {}", id, e);
+                params.to_string()
+            }
+        }
+    }
 
-    fn entity_type(&self) -> SharedString {
-       SharedString::from("Connection")
+    /// 新连接插入后才有 ID，先保存去掉密码的参数，写入钥匙串失败时再改回原参数
+    async fn store_new_secrets(&self, item: &StoredConnection, inserted_params: &str) -> Result<()> {
+        let Some(id) = item.id else {
+            return Ok(());
+        };
+        let params = self.params_for_storage(id, &item.params).await;
+        if params != inserted_params {
+            sqlx::query("UPDATE connections SET params = ? WHERE id = ?")
+                .bind(&params)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
     }
 
-    async fn insert(&self, item: &mut Self::Entity) -> Result<i64> {
-        let now = now();
+    async fn restore_secrets(&self, mut connection: StoredConnection) -> StoredConnection {
+        let Some(id) = connection.id else {
+            return connection;
+        };
+        match secrets::load(&self.secrets, connection_key(id)).await {
+            Ok(Some(connection_secrets)) => match merge_connection_secrets(&connection.params, &connection_secrets) {
+                Ok(params) => connection.params = params,
+                Err(e) => tracing::warn!("Invalid secrets of connection {}: {}", id, e),
+            },
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read connection {} secrets from keychain: {}", id, e),
+        }
+        connection
+    }
+
+    async fn restore_all_secrets(&self, rows: Vec<ConnectionRow>) -> Vec<StoredConnection> {
+        let mut connections = Vec::with_capacity(rows.len());
+        for row in rows {
+            connections.push(self.restore_secrets(row.into()).await);
+        }
+        connections
+    }
+
+    async fn remove_secrets(&self, id: i64) {
+        if let Err(e) = secrets::remove(&self.secrets, connection_key(id)).await {
+            tracing::warn!("Failed to delete connection {} secrets from keychain: {}", id, e);
+        }
+    }
+
+    /// 旧版本把密码保存在数据库中，启动时移到钥匙串
+    pub async fn move_secrets_to_keychain(&self) -> Result<()> {
+        let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, params FROM connections")
+            .fetch_all(&self.pool)
+            .await?;
+        for (id, params) in rows {
+            if !matches!(split_connection_secrets(&params), Ok((_, Some(_)))) {
+                continue;
+            }
+            // 新增的密钥字段（如连接字符串）可能出现在已经移过密码的连接中，
+            // 先合并钥匙串中已有的密钥，否则写入钥匙串时会把它们覆盖掉
+            let merged = match secrets::load(&self.secrets, connection_key(id)).await {
+                Ok(Some(existing)) => merge_connection_secrets(&params, &existing).unwrap_or_else(|_| params.clone()),
+                Ok(None) => params.clone(),
+                Err(e) => {
+                    tracing::warn!("Failed to read connection {} secrets from keychain: {}", id, e);
+                    continue;
+                }
+            };
+            let stored = self.params_for_storage(id, &merged).await;
+            // 写入钥匙串失败时返回的是合并后的参数，不能把已经移走的密码写回数据库
+            if stored != merged && stored != params {
+                sqlx::query("UPDATE connections SET params = ? WHERE id = ?")
+                    .bind(&stored)
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn insert_with(executor: impl SqliteExecutor<'_>, item: &mut StoredConnection, params: &str, now: i64) -> Result<i64> {
         let connection_type = item.connection_type.to_string();
         let result = sqlx::query(
             r#"
-            INSERT INTO connections (name, connection_type, params, workspace_id, selected_databases, remark, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO connections (name, connection_type, params, workspace_id, selected_databases, remark, auto_open, color, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&item.name)
         .bind(&connection_type)
-        .bind(&item.params)
+        .bind(params)
         .bind(item.workspace_id)
         .bind(&item.selected_databases)
         .bind(&item.remark)
+        .bind(item.auto_open)
+        .bind(item.color.map(|color| color.as_str()))
         .bind(now)
         .bind(now)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         let id = result.last_insert_rowid();
@@ -107,36 +206,60 @@ impl Repository for ConnectionRepository {
         Ok(id)
     }
 
-    async fn update(&self, item: &Self::Entity) -> Result<()> {
+    async fn update_with(executor: impl SqliteExecutor<'_>, item: &StoredConnection, params: &str, now: i64) -> Result<()> {
         let id = item.id.ok_or_else(|| anyhow::anyhow!("Cannot update without ID"))?;
-        let now = now();
         let connection_type = item.connection_type.to_string();
         sqlx::query(
             r#"
             UPDATE connections
-            SET name = ?, connection_type = ?, params = ?, workspace_id = ?, selected_databases = ?, remark = ?, updated_at = ?
+            SET name = ?, connection_type = ?, params = ?, workspace_id = ?, selected_databases = ?, remark = ?, auto_open = ?, color = ?, updated_at = ?
             WHERE id = ?
             "#,
         )
         .bind(&item.name)
         .bind(&connection_type)
-        .bind(&item.params)
+        .bind(params)
         .bind(item.workspace_id)
         .bind(&item.selected_databases)
         .bind(&item.remark)
+        .bind(item.auto_open)
+        .bind(item.color.map(|color| color.as_str()))
         .bind(now)
         .bind(id)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(())
     }
+}
+
+#[async_trait]
+impl Repository for ConnectionRepository {
+    type Entity = StoredConnection;
+
+    fn entity_type(&self) -> SharedString {
+       SharedString::from("Connection")
+    }
+
+    async fn insert(&self, item: &mut Self::Entity) -> Result<i64> {
+        let params = scrubbed_params(&item.params);
+        let id = Self::insert_with(&self.pool, item, &params, now()).await?;
+        self.store_new_secrets(item, &params).await?;
+        Ok(id)
+    }
+
+    async fn update(&self, item: &Self::Entity) -> Result<()> {
+        let id = item.id.ok_or_else(|| anyhow::anyhow!("Cannot update without ID"))?;
+        let params = self.params_for_storage(id, &item.params).await;
+        Self::update_with(&self.pool, item, &params, now()).await
+    }
 
     async fn delete(&self, id: i64) -> Result<()> {
         sqlx::query("DELETE FROM connections WHERE id = ?")
             .bind(id)
             .execute(&self.pool)
             .await?;
+        self.remove_secrets(id).await;
 
         Ok(())
     }
@@ -144,7 +267,7 @@ impl Repository for ConnectionRepository {
     async fn get(&self, id: i64) -> Result<Option<Self::Entity>> {
         let row: Option<ConnectionRow> = sqlx::query_as(
             r#"
-            SELECT id, name, connection_type, params, workspace_id, selected_databases, remark, created_at, updated_at
+            SELECT id, name, connection_type, params, workspace_id, selected_databases, remark, auto_open, color, created_at, updated_at
             FROM connections
             WHERE id = ?
             "#,
@@ -153,13 +276,16 @@ impl Repository for ConnectionRepository {
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(Into::into))
+        match row {
+            Some(row) => Ok(Some(self.restore_secrets(row.into()).await)),
+            None => Ok(None),
+        }
     }
 
     async fn list(&self) -> Result<Vec<Self::Entity>> {
         let rows: Vec<ConnectionRow> = sqlx::query_as(
             r#"
-            SELECT id, name, connection_type, params, workspace_id, selected_databases, remark, created_at, updated_at
+            SELECT id, name, connection_type, params, workspace_id, selected_databases, remark, auto_open, color, created_at, updated_at
             FROM connections
             ORDER BY updated_at DESC
             "#,
@@ -167,7 +293,7 @@ impl Repository for ConnectionRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(Into::into).collect())
+        Ok(self.restore_all_secrets(rows).await)
     }
 
     async fn count(&self) -> Result<i64> {
@@ -186,13 +312,70 @@ impl Repository for ConnectionRepository {
 
         Ok(row.is_some())
     }
+
+    async fn insert_batch(&self, items: &mut [Self::Entity]) -> Result<Vec<i64>> {
+        let now = now();
+        let params: Vec<String> = items.iter().map(|item| scrubbed_params(&item.params)).collect();
+        let mut tx = self.pool.begin().await?;
+        let mut ids = Vec::with_capacity(items.len());
+        for (item, params) in items.iter_mut().zip(&params) {
+            ids.push(Self::insert_with(&mut *tx, item, params, now).await?);
+        }
+        tx.commit().await?;
+
+        for (item, params) in items.iter().zip(&params) {
+            self.store_new_secrets(item, params).await?;
+        }
+        Ok(ids)
+    }
+
+    async fn update_batch(&self, items: &[Self::Entity]) -> Result<()> {
+        let now = now();
+        let mut params = Vec::with_capacity(items.len());
+        for item in items {
+            params.push(match item.id {
+                Some(id) => self.params_for_storage(id, &item.params).await,
+                None => item.params.clone(),
+            });
+        }
+        let mut tx = self.pool.begin().await?;
+        for (item, params) in items.iter().zip(&params) {
+            Self::update_with(&mut *tx, item, params, now).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_batch(&self, ids: &[i64]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for id in ids {
+            sqlx::query("DELETE FROM connections WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        for id in ids {
+            self.remove_secrets(*id).await;
+        }
+        Ok(())
+    }
+}
+
+/// 插入时数据库中不出现密码，解析失败的参数原样保存
+fn scrubbed_params(params: &str) -> String {
+    split_connection_secrets(params)
+        .map(|(scrubbed, _)| scrubbed)
+        .unwrap_or_else(|_| params.to_string())
 }
 
 impl ConnectionRepository {
     pub async fn list_by_workspace(&self, workspace_id: Option<i64>) -> Result<Vec<StoredConnection>> {
         let rows: Vec<ConnectionRow> = sqlx::query_as(
             r#"
-            SELECT id, name, connection_type, params, workspace_id, selected_databases, remark, created_at, updated_at
+            SELECT id, name, connection_type, params, workspace_id, selected_databases, remark, auto_open, color, created_at, updated_at
             FROM connections
             WHERE workspace_id IS ? OR (? IS NULL AND workspace_id IS NULL)
             ORDER BY updated_at DESC
@@ -203,8 +386,94 @@ impl ConnectionRepository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(Into::into).collect())
+        Ok(self.restore_all_secrets(rows).await)
+    }
+
+    /// 单独修改启动时自动打开标记，不影响连接的排序（updated_at）
+    pub async fn set_auto_open(&self, id: i64, auto_open: bool) -> Result<()> {
+        sqlx::query("UPDATE connections SET auto_open = ? WHERE id = ?")
+            .bind(auto_open)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 除 `exclude_id` 外已使用的连接名称；连接名称在所有工作区之间唯一
+    pub async fn connection_names(&self, exclude_id: Option<i64>) -> Result<Vec<String>> {
+        let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM connections WHERE id IS NOT ?")
+            .bind(exclude_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(names.into_iter().map(|(name,)| name).collect())
+    }
+
+    /// 把连接移动或复制到另一个工作区（None 为未分配）。复制出的连接与已有名称冲突时按
+    /// `unique_connection_name` 改名；保存的查询按 connection_id 关联，移动时随连接一起走，
+    /// 复制时为新连接复制一份，执行历史不复制
+    pub async fn transfer_to_workspace(&self, id: i64, workspace_id: Option<i64>, transfer: WorkspaceTransfer) -> Result<StoredConnection> {
+        let mut connection = self
+            .get(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Connection {} not found", id))?;
+        let exclude_id = match transfer {
+            WorkspaceTransfer::Move => Some(id),
+            WorkspaceTransfer::Copy => None,
+        };
+        let taken = self.connection_names(exclude_id).await?;
+        connection.name = unique_connection_name(&connection.name, &taken);
+        connection.workspace_id = workspace_id;
+
+        match transfer {
+            WorkspaceTransfer::Move => self.update(&connection).await?,
+            WorkspaceTransfer::Copy => {
+                let now = now();
+                connection.auto_open = false;
+                let params = scrubbed_params(&connection.params);
+                let mut tx = self.pool.begin().await?;
+                let new_id = Self::insert_with(&mut *tx, &mut connection, &params, now).await?;
+                sqlx::query(
+                    r#"
+                    INSERT INTO queries (name, content, connection_id, database_name, created_at, updated_at)
+                    SELECT name, content, ?, database_name, ?, ?
+                    FROM queries
+                    WHERE connection_id = ?
+                    "#,
+                )
+                .bind(new_id.to_string())
+                .bind(now)
+                .bind(now)
+                .bind(id.to_string())
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await?;
+
+                self.store_new_secrets(&connection, &params).await?;
+            }
+        }
+
+        Ok(connection)
+    }
+}
+
+/// 连接在工作区之间的转移方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceTransfer {
+    Move,
+    Copy,
+}
+
+/// 名称已被占用时依次尝试 "name (2)"、"name (3)"……
+pub fn unique_connection_name(name: &str, taken: &[String]) -> String {
+    if !taken.iter().any(|existing| existing == name) {
+        return name.to_string();
     }
+    (2..)
+        .map(|suffix| format!("{} ({})", name, suffix))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
 }
 
 #[derive(Clone)]
@@ -333,9 +602,7 @@ impl Repository for WorkspaceRepository {
 }
 
 pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
-    sqlx::migrate!("./migrations")
-        .run(pool)
-        .await?;
+    Migrator::new().run(pool).await?;
     Ok(())
 }
 
@@ -347,16 +614,143 @@ pub fn init(cx: &mut App) {
         let pool = storage.get_pool().await?;
         run_migrations(&pool).await?;
 
-        let conn_repo = ConnectionRepository::new(pool.clone());
+        let conn_repo = ConnectionRepository::new(pool.clone(), secrets::keychain());
+        if let Err(e) = conn_repo.move_secrets_to_keychain().await {
+            tracing::warn!("Failed to move connection secrets to the keychain: {}", e);
+        }
         let workspace_repo = WorkspaceRepository::new(pool.clone());
-        let query_repo = QueryRepository::new(pool);
+        let query_repo = QueryRepository::new(pool.clone());
+        let schema_cache_repo = SchemaCacheRepository::new(pool.clone());
+        let variable_repo = WorkspaceVariableRepository::new(pool.clone());
+        let usage_repo = UsageMetricsRepository::new(pool.clone());
+        let embedding_repo = SchemaEmbeddingRepository::new(pool);
 
         storage.register(workspace_repo).await?;
         storage.register(conn_repo).await?;
         storage.register(query_repo).await?;
+        storage.register(schema_cache_repo).await?;
+        storage.register(variable_repo).await?;
+        storage.register(usage_repo).await?;
+        storage.register(embedding_repo).await?;
         Ok(())
     });
     if let Err(e) = result {
         panic!("Failed to initialize repositories: {}", e);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::secrets::MemorySecretStore;
+    use crate::storage::query_model::Query;
+    use std::sync::Arc;
+
+    async fn setup() -> ConnectionRepository {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        ConnectionRepository::new(pool, Arc::new(MemorySecretStore::default()))
+    }
+
+    fn connection(name: &str) -> StoredConnection {
+        StoredConnection {
+            id: None,
+            name: name.to_string(),
+            connection_type: ConnectionType::Database,
+            params: "{}".to_string(),
+            workspace_id: None,
+            selected_databases: None,
+            remark: None,
+            auto_open: false,
+            color: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_insert_update_delete() {
+        let repo = setup().await;
+        let mut items: Vec<StoredConnection> = (0..3).map(|i| connection(&format!("conn-{}", i))).collect();
+
+        let ids = repo.insert_batch(&mut items).await.unwrap();
+        assert_eq!(ids.len(), 3);
+        assert!(items.iter().zip(&ids).all(|(item, id)| item.id == Some(*id)));
+
+        for item in &mut items {
+            item.remark = Some("imported".to_string());
+        }
+        repo.update_batch(&items).await.unwrap();
+        assert!(repo.list().await.unwrap().iter().all(|c| c.remark.as_deref() == Some("imported")));
+
+        repo.delete_batch(&ids[..2]).await.unwrap();
+        assert_eq!(repo.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_rolls_back_on_error() {
+        let repo = setup().await;
+        let mut items = vec![connection("a")];
+        repo.insert_batch(&mut items).await.unwrap();
+
+        items[0].name = "renamed".to_string();
+        items.push(connection("missing id"));
+        assert!(repo.update_batch(&items).await.is_err());
+
+        let stored = repo.list().await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].name, "a");
+    }
+
+    #[tokio::test]
+    async fn test_passwords_are_kept_out_of_the_database() {
+        let repo = setup().await;
+        let mut item = connection("prod");
+        item.params = r#"{"host":"db","password":"secret","extra_params":{}}"#.to_string();
+        let id = repo.insert(&mut item).await.unwrap();
+
+        let (params,): (String,) = sqlx::query_as("SELECT params FROM connections WHERE id = ?")
+            .bind(id)
+            .fetch_one(&repo.pool)
+            .await
+            .unwrap();
+        assert!(!params.contains("secret"));
+        assert!(repo.get(id).await.unwrap().unwrap().params.contains("secret"));
+
+        repo.delete(id).await.unwrap();
+        assert_eq!(repo.secrets.get(&connection_key(id)).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_to_workspace() {
+        let repo = setup().await;
+        let queries = QueryRepository::new(repo.pool.clone());
+        let workspace_id = WorkspaceRepository::new(repo.pool.clone())
+            .insert(&mut Workspace::new("team".to_string()))
+            .await
+            .unwrap();
+        let mut item = connection("prod");
+        let id = repo.insert(&mut item).await.unwrap();
+        let mut query = Query::new("daily".to_string(), "SELECT 1".to_string(), id.to_string(), None);
+        queries.insert(&mut query).await.unwrap();
+
+        let copied = repo.transfer_to_workspace(id, Some(workspace_id), WorkspaceTransfer::Copy).await.unwrap();
+        assert_eq!(copied.name, "prod (2)");
+        assert_eq!(copied.workspace_id, Some(workspace_id));
+        let copied_id = copied.id.unwrap();
+        assert_ne!(copied_id, id);
+        assert_eq!(queries.list_by_connection(&copied_id.to_string()).await.unwrap()[0].content, "SELECT 1");
+
+        let moved = repo.transfer_to_workspace(id, Some(workspace_id), WorkspaceTransfer::Move).await.unwrap();
+        assert_eq!(moved.name, "prod");
+        assert_eq!(repo.list_by_workspace(Some(workspace_id)).await.unwrap().len(), 2);
+        assert_eq!(queries.list_by_connection(&id.to_string()).await.unwrap().len(), 1);
+
+        assert_eq!(unique_connection_name("prod", &["prod".to_string(), "prod (2)".to_string()]), "prod (3)");
+    }
+
+}
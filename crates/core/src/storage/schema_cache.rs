@@ -0,0 +1,137 @@
+use anyhow::Result;
+use sqlx::{FromRow, SqlitePool};
+
+use crate::storage::manager::now;
+
+/// 缓存的树节点子节点（JSON），由数据库树视图负责序列化
+#[derive(Debug, Clone, FromRow)]
+pub struct SchemaCacheEntry {
+    pub children: String,
+    pub updated_at: i64,
+}
+
+impl SchemaCacheEntry {
+    /// 缓存写入后是否已超过 `ttl_secs`
+    pub fn is_stale(&self, ttl_secs: i64) -> bool {
+        now() - self.updated_at > ttl_secs
+    }
+}
+
+#[derive(Clone)]
+pub struct SchemaCacheRepository {
+    pool: SqlitePool,
+}
+
+impl SchemaCacheRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get(&self, connection_id: i64, node_id: &str) -> Result<Option<SchemaCacheEntry>> {
+        let entry = sqlx::query_as(
+            "SELECT children, updated_at FROM schema_cache WHERE connection_id = ? AND node_id = ?",
+        )
+        .bind(connection_id)
+        .bind(node_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    pub async fn put(&self, connection_id: i64, node_id: &str, children: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO schema_cache (connection_id, node_id, children, updated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (connection_id, node_id) DO UPDATE SET
+                children = excluded.children,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(connection_id)
+        .bind(node_id)
+        .bind(children)
+        .bind(now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 删除节点自身及所有后代节点的缓存（节点 ID 以父节点 ID 加 `:` 为前缀）
+    pub async fn invalidate(&self, connection_id: i64, node_id: &str) -> Result<()> {
+        sqlx::query(
+            "DELETE FROM schema_cache WHERE connection_id = ? AND (node_id = ? OR substr(node_id, 1, length(?) + 1) = ? || ':')",
+        )
+        .bind(connection_id)
+        .bind(node_id)
+        .bind(node_id)
+        .bind(node_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn clear_connection(&self, connection_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM schema_cache WHERE connection_id = ?")
+            .bind(connection_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::run_migrations;
+
+    async fn setup() -> SchemaCacheRepository {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO connections (id, name, connection_type, params, created_at, updated_at) VALUES (1, 'local', 'Database', '{}', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        SchemaCacheRepository::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_put_replaces_entry() {
+        let repo = setup().await;
+        assert!(repo.get(1, "1").await.unwrap().is_none());
+
+        repo.put(1, "1", "[\"a\"]").await.unwrap();
+        repo.put(1, "1", "[\"a\",\"b\"]").await.unwrap();
+
+        let entry = repo.get(1, "1").await.unwrap().unwrap();
+        assert_eq!(entry.children, "[\"a\",\"b\"]");
+        assert!(!entry.is_stale(60));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_removes_descendants_only() {
+        let repo = setup().await;
+        for node_id in ["1", "1:db", "1:db:table_folder", "1:db2"] {
+            repo.put(1, node_id, "[]").await.unwrap();
+        }
+
+        repo.invalidate(1, "1:db").await.unwrap();
+        assert!(repo.get(1, "1").await.unwrap().is_some());
+        assert!(repo.get(1, "1:db").await.unwrap().is_none());
+        assert!(repo.get(1, "1:db:table_folder").await.unwrap().is_none());
+        assert!(repo.get(1, "1:db2").await.unwrap().is_some());
+
+        repo.clear_connection(1).await.unwrap();
+        assert!(repo.get(1, "1").await.unwrap().is_none());
+    }
+}
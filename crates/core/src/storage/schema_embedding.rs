@@ -0,0 +1,161 @@
+//! 表结构向量索引：按表保存表名、列名和注释组成的文本及其 embedding，
+//! AI 生成 SQL 时只把与问题最相关的表放进上下文
+
+use anyhow::Result;
+use sqlx::SqlitePool;
+
+use crate::storage::manager::now;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaEmbedding {
+    pub table_name: String,
+    /// 生成 embedding 的文本，变化时需要重新生成
+    pub document: String,
+    pub embedding: Vec<f32>,
+}
+
+/// 向量以小端 f32 序列保存在 BLOB 中
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// 余弦相似度；维度不同（换了 embedding 模型）或存在零向量时为 0
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[derive(Clone)]
+pub struct SchemaEmbeddingRepository {
+    pool: SqlitePool,
+}
+
+impl SchemaEmbeddingRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, connection_id: i64, database: &str) -> Result<Vec<SchemaEmbedding>> {
+        let rows: Vec<(String, String, Vec<u8>)> = sqlx::query_as(
+            "SELECT table_name, document, embedding FROM schema_embeddings WHERE connection_id = ? AND database_name = ?",
+        )
+        .bind(connection_id)
+        .bind(database)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(table_name, document, embedding)| SchemaEmbedding {
+                table_name,
+                document,
+                embedding: decode_embedding(&embedding),
+            })
+            .collect())
+    }
+
+    pub async fn put(&self, connection_id: i64, database: &str, embeddings: &[SchemaEmbedding]) -> Result<()> {
+        let now = now();
+        let mut tx = self.pool.begin().await?;
+        for embedding in embeddings {
+            sqlx::query(
+                r#"
+                INSERT INTO schema_embeddings (connection_id, database_name, table_name, document, embedding, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT (connection_id, database_name, table_name) DO UPDATE SET
+                    document = excluded.document,
+                    embedding = excluded.embedding,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(connection_id)
+            .bind(database)
+            .bind(&embedding.table_name)
+            .bind(&embedding.document)
+            .bind(encode_embedding(&embedding.embedding))
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 删除已不存在的表的向量
+    pub async fn retain(&self, connection_id: i64, database: &str, table_names: &[String]) -> Result<()> {
+        let existing = self.list(connection_id, database).await?;
+        let mut tx = self.pool.begin().await?;
+        for embedding in existing.iter().filter(|e| !table_names.contains(&e.table_name)) {
+            sqlx::query("DELETE FROM schema_embeddings WHERE connection_id = ? AND database_name = ? AND table_name = ?")
+                .bind(connection_id)
+                .bind(database)
+                .bind(&embedding.table_name)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::run_migrations;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_put_list_retain() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::query("INSERT INTO connections (id, name, connection_type, params, created_at, updated_at) VALUES (1, 'local', 'mysql', '{}', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let repo = SchemaEmbeddingRepository::new(pool);
+
+        let orders = SchemaEmbedding {
+            table_name: "orders".to_string(),
+            document: "orders: id, amount".to_string(),
+            embedding: vec![0.25, -1.5],
+        };
+        let users = SchemaEmbedding {
+            table_name: "users".to_string(),
+            document: "users: id, name".to_string(),
+            embedding: vec![1.0, 2.0],
+        };
+        repo.put(1, "shop", &[orders.clone(), users]).await.unwrap();
+        repo.retain(1, "shop", &["orders".to_string()]).await.unwrap();
+
+        assert_eq!(repo.list(1, "shop").await.unwrap(), vec![orders]);
+        assert!(repo.list(1, "other").await.unwrap().is_empty());
+    }
+}
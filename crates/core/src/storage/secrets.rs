@@ -0,0 +1,211 @@
+//! 密钥存储 - 连接密码和 LLM API Key 保存在系统钥匙串中（macOS Keychain、Windows 凭据管理器、Secret Service），
+//! 存储数据库中只保留去掉密钥后的配置
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// 钥匙串中的服务名
+const KEYCHAIN_SERVICE: &str = "onehub";
+
+/// 连接参数中属于密钥的字段（JSON Pointer），覆盖数据库、SSH、Redis 连接的各种参数结构。
+/// 连接字符串中常带密码（MongoDB URI、ODBC 的 `PWD=`），整串放进钥匙串
+const CONNECTION_SECRET_FIELDS: &[&str] = &[
+    "/password",
+    "/extra_params/ssh_password",
+    "/extra_params/ssh_passphrase",
+    "/extra_params/proxy_password",
+    "/extra_params/connection_string",
+    "/extra_params/api_key",
+    "/auth_method/Password/password",
+    "/auth_method/PrivateKey/passphrase",
+];
+
+pub trait SecretStore: Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<String>>;
+    fn set(&self, key: &str, secret: &str) -> Result<()>;
+    fn delete(&self, key: &str) -> Result<()>;
+}
+
+pub type SharedSecretStore = Arc<dyn SecretStore>;
+
+/// 系统钥匙串
+pub struct KeychainStore {
+    service: String,
+}
+
+impl KeychainStore {
+    pub fn new() -> Self {
+        Self { service: KEYCHAIN_SERVICE.to_string() }
+    }
+
+    fn entry(&self, key: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(&self.service, key).map_err(|e| anyhow!("Failed to open keychain entry {}: {}", key, e))
+    }
+}
+
+impl Default for KeychainStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SecretStore for KeychainStore {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match self.entry(key)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(anyhow!("Failed to read {} from keychain: {}", key, e)),
+        }
+    }
+
+    fn set(&self, key: &str, secret: &str) -> Result<()> {
+        self.entry(key)?
+            .set_password(secret)
+            .map_err(|e| anyhow!("Failed to write {} to keychain: {}", key, e))
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        match self.entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(anyhow!("Failed to delete {} from keychain: {}", key, e)),
+        }
+    }
+}
+
+pub fn keychain() -> SharedSecretStore {
+    Arc::new(KeychainStore::new())
+}
+
+/// 进程内的密钥存储，测试中代替系统钥匙串
+#[derive(Default)]
+pub struct MemorySecretStore {
+    secrets: Mutex<HashMap<String, String>>,
+}
+
+impl SecretStore for MemorySecretStore {
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        let secrets = self.secrets.lock().map_err(|_| anyhow!("Secret store lock poisoned"))?;
+        Ok(secrets.get(key).cloned())
+    }
+
+    fn set(&self, key: &str, secret: &str) -> Result<()> {
+        let mut secrets = self.secrets.lock().map_err(|_| anyhow!("Secret store lock poisoned"))?;
+        secrets.insert(key.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<()> {
+        let mut secrets = self.secrets.lock().map_err(|_| anyhow!("Secret store lock poisoned"))?;
+        secrets.remove(key);
+        Ok(())
+    }
+}
+
+/// 钥匙串访问可能等待系统解锁，放到阻塞线程中执行
+pub async fn load(store: &SharedSecretStore, key: String) -> Result<Option<String>> {
+    let store = store.clone();
+    tokio::task::spawn_blocking(move || store.get(&key)).await?
+}
+
+pub async fn save(store: &SharedSecretStore, key: String, secret: String) -> Result<()> {
+    let store = store.clone();
+    tokio::task::spawn_blocking(move || store.set(&key, &secret)).await?
+}
+
+pub async fn remove(store: &SharedSecretStore, key: String) -> Result<()> {
+    let store = store.clone();
+    tokio::task::spawn_blocking(move || store.delete(&key)).await?
+}
+
+/// 一个连接的所有密钥存为钥匙串中的一项，读取连接列表时每个连接只访问一次钥匙串
+pub fn connection_key(id: i64) -> String {
+    format!("connection/{}", id)
+}
+
+pub fn llm_provider_key(id: i64) -> String {
+    format!("llm-provider/{}/api_key", id)
+}
+
+/// 从连接参数中取出密钥，返回去掉密钥的参数和按字段保存的密钥（JSON 编码）；没有密钥时返回 None
+pub fn split_connection_secrets(params: &str) -> Result<(String, Option<String>)> {
+    let mut value: Value = serde_json::from_str(params)?;
+    let mut secrets = serde_json::Map::new();
+
+    for field in CONNECTION_SECRET_FIELDS {
+        let Some((parent, name)) = field.rsplit_once('/') else {
+            continue;
+        };
+        let Some(Value::Object(object)) = value.pointer_mut(parent) else {
+            continue;
+        };
+        let Some(Value::String(secret)) = object.get(name) else {
+            continue;
+        };
+        if secret.is_empty() {
+            continue;
+        }
+        secrets.insert(field.to_string(), Value::String(secret.clone()));
+        // extra_params 中缺少的参数等同于未设置，其它字段保留空字符串以便参数仍能反序列化
+        if parent == "/extra_params" {
+            object.remove(name);
+        } else {
+            object.insert(name.to_string(), Value::String(String::new()));
+        }
+    }
+
+    if secrets.is_empty() {
+        return Ok((params.to_string(), None));
+    }
+    Ok((serde_json::to_string(&value)?, Some(serde_json::to_string(&secrets)?)))
+}
+
+/// 把钥匙串中的密钥放回连接参数
+pub fn merge_connection_secrets(params: &str, secrets: &str) -> Result<String> {
+    let mut value: Value = serde_json::from_str(params)?;
+    let secrets: HashMap<String, String> = serde_json::from_str(secrets)?;
+
+    for (field, secret) in secrets {
+        let Some((parent, name)) = field.rsplit_once('/') else {
+            continue;
+        };
+        if let Some(Value::Object(object)) = value.pointer_mut(parent) {
+            object.insert(name.to_string(), Value::String(secret));
+        }
+    }
+    Ok(serde_json::to_string(&value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_merge_connection_secrets() {
+        let params = r#"{"host":"db","password":"secret","extra_params":{"proxy_password":"proxy","connect_timeout":"30"}}"#;
+        let (scrubbed, secrets) = split_connection_secrets(params).unwrap();
+        assert!(!scrubbed.contains("secret") && !scrubbed.contains("proxy_password"));
+        assert!(scrubbed.contains("connect_timeout"));
+
+        let merged: Value = serde_json::from_str(&merge_connection_secrets(&scrubbed, &secrets.unwrap()).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::from_str::<Value>(params).unwrap());
+    }
+
+    #[test]
+    fn test_connection_string_and_api_key_are_secrets() {
+        let params = r#"{"host":"es","extra_params":{"api_key":"key123","connection_string":"DSN=x;PWD=pw"}}"#;
+        let (scrubbed, secrets) = split_connection_secrets(params).unwrap();
+        assert!(!scrubbed.contains("key123") && !scrubbed.contains("PWD=pw"));
+
+        let merged: Value = serde_json::from_str(&merge_connection_secrets(&scrubbed, &secrets.unwrap()).unwrap()).unwrap();
+        assert_eq!(merged, serde_json::from_str::<Value>(params).unwrap());
+    }
+
+    #[test]
+    fn test_params_without_secrets_are_unchanged() {
+        let params = r#"{"host":"ssh","auth_method":{"PrivateKey":{"key_path":"/home/me/.ssh/id_rsa","passphrase":null}}}"#;
+        assert_eq!(split_connection_secrets(params).unwrap(), (params.to_string(), None));
+    }
+}
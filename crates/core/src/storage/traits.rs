@@ -31,4 +31,27 @@ pub trait Repository: Send + Sync {
     async fn count(&self) -> Result<i64>;
 
     async fn exists(&self, id: i64) -> Result<bool>;
+
+    /// 批量插入，返回新记录的 ID。默认逐条插入，支持事务的仓库应重写为全部成功或全部回滚
+    async fn insert_batch(&self, items: &mut [Self::Entity]) -> Result<Vec<i64>> {
+        let mut ids = Vec::with_capacity(items.len());
+        for item in items.iter_mut() {
+            ids.push(self.insert(item).await?);
+        }
+        Ok(ids)
+    }
+
+    async fn update_batch(&self, items: &[Self::Entity]) -> Result<()> {
+        for item in items {
+            self.update(item).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_batch(&self, ids: &[i64]) -> Result<()> {
+        for id in ids {
+            self.delete(*id).await?;
+        }
+        Ok(())
+    }
 }
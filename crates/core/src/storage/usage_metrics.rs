@@ -0,0 +1,168 @@
+//! 匿名使用统计：用户开启后在本地累计功能使用次数和错误次数，只记录事件名称，不记录 SQL、
+//! 连接信息或错误内容；发送前完整展示报告内容，由用户决定是否附带到反馈中
+
+use anyhow::Result;
+use gpui::{App, Global};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::gpui_tokio::Tokio;
+use crate::storage::manager::now;
+use crate::storage::GlobalStorageState;
+
+/// 报告中最多列出的事件数
+pub const MAX_REPORT_EVENTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageEventKind {
+    Feature,
+    Error,
+}
+
+impl UsageEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageEventKind::Feature => "feature",
+            UsageEventKind::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct UsageEvent {
+    pub kind: String,
+    pub name: String,
+    pub count: i64,
+    pub first_at: i64,
+    pub last_at: i64,
+}
+
+/// 是否记录使用统计，默认关闭
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UsageMetricsSettings {
+    pub enabled: bool,
+}
+
+impl Global for UsageMetricsSettings {}
+
+impl UsageMetricsSettings {
+    pub fn global(cx: &App) -> UsageMetricsSettings {
+        cx.try_global::<UsageMetricsSettings>().copied().unwrap_or_default()
+    }
+
+    pub fn global_mut(cx: &mut App) -> &mut UsageMetricsSettings {
+        cx.default_global::<UsageMetricsSettings>()
+    }
+}
+
+#[derive(Clone)]
+pub struct UsageMetricsRepository {
+    pool: SqlitePool,
+}
+
+impl UsageMetricsRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(&self, kind: UsageEventKind, name: &str) -> Result<()> {
+        let now = now();
+        sqlx::query(
+            "INSERT INTO usage_events (kind, name, count, first_at, last_at) VALUES (?, ?, 1, ?, ?) \
+             ON CONFLICT(kind, name) DO UPDATE SET count = count + 1, last_at = excluded.last_at",
+        )
+        .bind(kind.as_str())
+        .bind(name)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 按次数从多到少
+    pub async fn list(&self) -> Result<Vec<UsageEvent>> {
+        let events = sqlx::query_as(
+            "SELECT kind, name, count, first_at, last_at FROM usage_events ORDER BY count DESC, kind, name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    pub async fn clear(&self) -> Result<()> {
+        sqlx::query("DELETE FROM usage_events").execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+/// 记录一次功能使用；未开启使用统计时不做任何事
+pub fn record_feature(name: &'static str, cx: &App) {
+    record_usage(UsageEventKind::Feature, name, cx);
+}
+
+/// 记录一次错误，只记录发生错误的功能名称
+pub fn record_error(name: &'static str, cx: &App) {
+    record_usage(UsageEventKind::Error, name, cx);
+}
+
+fn record_usage(kind: UsageEventKind, name: &'static str, cx: &App) {
+    if !UsageMetricsSettings::global(cx).enabled {
+        return;
+    }
+    let Some(storage) = cx.try_global::<GlobalStorageState>().map(|state| state.storage.clone()) else {
+        return;
+    };
+
+    Tokio::handle(cx).spawn(async move {
+        let Some(repo) = storage.get::<UsageMetricsRepository>().await else {
+            return;
+        };
+        if let Err(e) = repo.record(kind, name).await {
+            tracing::warn!("Failed to record usage event {}: {}", name, e);
+        }
+    });
+}
+
+/// 发送前展示给用户的报告正文，也是附带到反馈中的全部统计内容
+pub fn format_usage_report(events: &[UsageEvent]) -> String {
+    let mut lines: Vec<String> = events
+        .iter()
+        .take(MAX_REPORT_EVENTS)
+        .map(|event| format!("{} {} {}", event.kind, event.name, event.count))
+        .collect();
+    if events.len() > MAX_REPORT_EVENTS {
+        lines.push(format!("... {} more", events.len() - MAX_REPORT_EVENTS));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::run_migrations;
+
+    #[tokio::test]
+    async fn test_record_and_report() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        let repo = UsageMetricsRepository::new(pool);
+
+        repo.record(UsageEventKind::Feature, "sql.execute").await.unwrap();
+        repo.record(UsageEventKind::Feature, "sql.execute").await.unwrap();
+        repo.record(UsageEventKind::Error, "sql.execute").await.unwrap();
+
+        let events = repo.list().await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].kind.as_str(), events[0].count), ("feature", 2));
+        assert_eq!(format_usage_report(&events), "feature sql.execute 2\nerror sql.execute 1");
+
+        repo.clear().await.unwrap();
+        assert!(repo.list().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! 工作区变量：在 SQL 和 AI 提示词中以 `${NAME}` 引用，执行时替换为所在工作区中定义的值
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::storage::manager::now;
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct WorkspaceVariable {
+    pub name: String,
+    pub value: String,
+}
+
+impl WorkspaceVariable {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WorkspaceVariableRepository {
+    pool: SqlitePool,
+}
+
+impl WorkspaceVariableRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self, workspace_id: i64) -> Result<Vec<WorkspaceVariable>> {
+        let variables = sqlx::query_as(
+            "SELECT name, value FROM workspace_variables WHERE workspace_id = ? ORDER BY name",
+        )
+        .bind(workspace_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(variables)
+    }
+
+    /// 用 `variables` 替换工作区的全部变量
+    pub async fn replace_all(&self, workspace_id: i64, variables: &[WorkspaceVariable]) -> Result<()> {
+        let now = now();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM workspace_variables WHERE workspace_id = ?")
+            .bind(workspace_id)
+            .execute(&mut *tx)
+            .await?;
+        for variable in variables {
+            sqlx::query("INSERT INTO workspace_variables (workspace_id, name, value, updated_at) VALUES (?, ?, ?, ?)")
+                .bind(workspace_id)
+                .bind(&variable.name)
+                .bind(&variable.value)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// 变量名由字母、数字和下划线组成，且不以数字开头（`${1:name}` 之类的编辑器片段占位符不是变量）
+pub fn is_valid_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    /// 变量名以及包括 `${}` 在内的原始文本
+    Variable(&'a str, &'a str),
+}
+
+fn scan_variables(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) if is_valid_variable_name(&after[..end]) => {
+                segments.push(Segment::Text(&rest[..start]));
+                segments.push(Segment::Variable(&after[..end], &rest[start..start + end + 3]));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                segments.push(Segment::Text(&rest[..start + 2]));
+                rest = after;
+            }
+        }
+    }
+    segments.push(Segment::Text(rest));
+    segments
+}
+
+/// 文本中引用的变量名，去重并保持首次出现的顺序
+pub fn referenced_variables(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for segment in scan_variables(text) {
+        if let Segment::Variable(name, _) = segment {
+            if !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// 把 `${NAME}` 替换为 `values` 中的值，未定义的变量保持原样
+pub fn substitute_variables(text: &str, values: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    for segment in scan_variables(text) {
+        match segment {
+            Segment::Text(text) => result.push_str(text),
+            Segment::Variable(name, raw) => result.push_str(values.get(name).map(String::as_str).unwrap_or(raw)),
+        }
+    }
+    result
+}
+
+/// 解析 `NAME=value` 形式的多行文本，空行和 `#` 开头的行会被忽略
+pub fn parse_variable_lines(text: &str) -> Result<Vec<WorkspaceVariable>> {
+    let mut variables: Vec<WorkspaceVariable> = Vec::new();
+    for (ix, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("第 {} 行缺少 `=`", ix + 1))?;
+        let name = name.trim();
+        if !is_valid_variable_name(name) {
+            return Err(anyhow!("第 {} 行的变量名 `{}` 无效", ix + 1, name));
+        }
+        let variable = WorkspaceVariable::new(name, value.trim());
+        match variables.iter_mut().find(|v| v.name == name) {
+            Some(existing) => *existing = variable,
+            None => variables.push(variable),
+        }
+    }
+    Ok(variables)
+}
+
+pub fn format_variable_lines(variables: &[WorkspaceVariable]) -> String {
+    variables
+        .iter()
+        .map(|v| format!("{}={}", v.name, v.value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::run_migrations;
+
+    #[test]
+    fn test_substitute_variables() {
+        let sql = "SELECT * FROM orders WHERE tenant_id = ${TENANT_ID} AND region = '${region}' AND note = '${1:x}' ${";
+        assert_eq!(referenced_variables(sql), vec!["TENANT_ID".to_string(), "region".to_string()]);
+
+        let values = HashMap::from([("TENANT_ID".to_string(), "42".to_string())]);
+        assert_eq!(
+            substitute_variables(sql, &values),
+            "SELECT * FROM orders WHERE tenant_id = 42 AND region = '${region}' AND note = '${1:x}' ${"
+        );
+    }
+
+    #[test]
+    fn test_parse_variable_lines() {
+        let variables = parse_variable_lines("# 租户\nTENANT_ID = 42\n\nSCHEMA=sales=eu\nTENANT_ID=7").unwrap();
+        assert_eq!(
+            variables,
+            vec![WorkspaceVariable::new("TENANT_ID", "7"), WorkspaceVariable::new("SCHEMA", "sales=eu")]
+        );
+        assert_eq!(format_variable_lines(&variables), "TENANT_ID=7\nSCHEMA=sales=eu");
+        assert!(parse_variable_lines("1ST=a").is_err());
+        assert!(parse_variable_lines("MISSING_VALUE").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replace_all() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::query("INSERT INTO workspaces (id, name, created_at, updated_at) VALUES (1, 'prod', 0, 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let repo = WorkspaceVariableRepository::new(pool);
+
+        repo.replace_all(1, &[WorkspaceVariable::new("B", "2"), WorkspaceVariable::new("A", "1")])
+            .await
+            .unwrap();
+        assert_eq!(repo.list(1).await.unwrap()[0], WorkspaceVariable::new("A", "1"));
+
+        repo.replace_all(1, &[WorkspaceVariable::new("C", "3")]).await.unwrap();
+        assert_eq!(repo.list(1).await.unwrap(), vec![WorkspaceVariable::new("C", "3")]);
+        assert!(repo.list(2).await.unwrap().is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use gpui::prelude::FluentBuilder;
-use gpui::{div, px, AnyElement, App, AppContext as _, Context, Corner, Entity, Focusable, InteractiveElement, IntoElement, MouseButton, ParentElement, Render, RenderOnce, SharedString, Styled, Task, Window};
+use gpui::{div, px, AnyElement, App, AppContext as _, Context, Corner, Entity, Focusable, Hsla, InteractiveElement, IntoElement, MouseButton, ParentElement, Render, RenderOnce, SharedString, Styled, Task, Window};
 use gpui::{ScrollHandle, StatefulInteractiveElement as _};
 use gpui_component::button::{Button, ButtonVariants as _};
 use gpui_component::list::{List, ListDelegate, ListState};
@@ -22,6 +22,11 @@ pub trait TabContent: Send + Sync {
         None
     }
 
+    /// Color of the stripe under the tab title, e.g. the connection's color tag
+    fn accent_color(&self, _cx: &App) -> Option<Hsla> {
+        None
+    }
+
     /// Check if tab can be closed
     fn closeable(&self) -> bool {
         true
@@ -767,6 +772,7 @@ impl TabContainer {
                         let title = tab.content().title();
                         let icon = tab.content().icon();
                         let closeable = tab.content().closeable();
+                        let accent_color = tab.content().accent_color(cx);
                         let is_active = idx == active_index;
                         let view_clone = view.clone();
                         let title_clone = title.clone();
@@ -783,6 +789,7 @@ impl TabContainer {
                             .w(tab_width)
                             .px_3()
                             .rounded(px(6.0))
+                            .when_some(accent_color, |el, color| el.border_b_2().border_color(color))
                             .cursor_grab()
                             .when(is_active, |el| el.bg(active_tab_color))
                             .when(!is_active, |el| el.hover(move |style| style.bg(hover_tab_color)).bg(inactive_tab_color))
@@ -0,0 +1,169 @@
+//! Persist main window size, position and maximized state per display configuration
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use gpui::{point, px, size, Bounds, Pixels, WindowBounds};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::get_config_dir;
+
+const WINDOW_STATE_FILE: &str = "window_state.json";
+
+/// Saved geometry of a window, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+}
+
+impl WindowState {
+    pub fn from_window_bounds(bounds: WindowBounds) -> Self {
+        let (rect, maximized) = match bounds {
+            WindowBounds::Windowed(rect) => (rect, false),
+            // Keep the restore size so un-maximizing returns to it
+            WindowBounds::Maximized(rect) | WindowBounds::Fullscreen(rect) => (rect, true),
+        };
+        Self {
+            x: rect.origin.x.as_f32(),
+            y: rect.origin.y.as_f32(),
+            width: rect.size.width.as_f32(),
+            height: rect.size.height.as_f32(),
+            maximized,
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds<Pixels> {
+        Bounds::new(point(px(self.x), px(self.y)), size(px(self.width), px(self.height)))
+    }
+
+    pub fn to_window_bounds(&self) -> WindowBounds {
+        if self.maximized {
+            WindowBounds::Maximized(self.bounds())
+        } else {
+            WindowBounds::Windowed(self.bounds())
+        }
+    }
+
+    /// Whether the window's top-left area is on one of the displays, so it can be grabbed
+    pub fn is_visible_on(&self, displays: &[Bounds<Pixels>]) -> bool {
+        let grab_point = point(px(self.x + 40.0), px(self.y + 10.0));
+        self.width > 0.0 && self.height > 0.0 && displays.iter().any(|display| display.contains(&grab_point))
+    }
+
+    /// Shift a window opened next to this one so the two do not overlap exactly
+    pub fn cascaded(&self) -> Self {
+        Self { x: self.x + 30.0, y: self.y + 30.0, maximized: false, ..*self }
+    }
+}
+
+/// Identify a display arrangement by the bounds of every attached display
+pub fn display_key(displays: &[Bounds<Pixels>]) -> String {
+    let mut parts: Vec<String> = displays
+        .iter()
+        .map(|d| {
+            format!(
+                "{}x{}@{},{}",
+                d.size.width.as_f32().round(),
+                d.size.height.as_f32().round(),
+                d.origin.x.as_f32().round(),
+                d.origin.y.as_f32().round()
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join(";")
+}
+
+/// Window states for every display arrangement seen so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowStateStore {
+    states: HashMap<String, WindowState>,
+}
+
+impl WindowStateStore {
+    fn path() -> Result<PathBuf> {
+        Ok(get_config_dir()?.join(WINDOW_STATE_FILE))
+    }
+
+    /// Load the saved states; a missing or corrupt file yields an empty store
+    pub fn load() -> Self {
+        Self::path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Saved state for this display arrangement, if it is still on screen
+    pub fn get(&self, displays: &[Bounds<Pixels>]) -> Option<WindowState> {
+        self.states
+            .get(&display_key(displays))
+            .copied()
+            .filter(|state| state.is_visible_on(displays))
+    }
+
+    pub fn set(&mut self, displays: &[Bounds<Pixels>], state: WindowState) {
+        self.states.insert(display_key(displays), state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(x: f32, width: f32) -> Bounds<Pixels> {
+        Bounds::new(point(px(x), px(0.0)), size(px(width), px(1080.0)))
+    }
+
+    #[test]
+    fn test_display_key_ignores_display_order() {
+        let laptop = display(0.0, 1440.0);
+        let monitor = display(1440.0, 2560.0);
+        assert_eq!(display_key(&[laptop, monitor]), display_key(&[monitor, laptop]));
+        assert_ne!(display_key(&[laptop]), display_key(&[laptop, monitor]));
+    }
+
+    #[test]
+    fn test_state_is_per_display_arrangement() {
+        let laptop = display(0.0, 1440.0);
+        let monitor = display(1440.0, 2560.0);
+        let state = WindowState { x: 1600.0, y: 100.0, width: 1200.0, height: 800.0, maximized: true };
+
+        let mut store = WindowStateStore::default();
+        store.set(&[laptop, monitor], state);
+
+        assert_eq!(store.get(&[monitor, laptop]), Some(state));
+        assert_eq!(store.get(&[laptop]), None);
+    }
+
+    #[test]
+    fn test_offscreen_state_is_ignored() {
+        let laptop = display(0.0, 1440.0);
+        let state = WindowState { x: 1600.0, y: 100.0, width: 1200.0, height: 800.0, maximized: false };
+        assert!(!state.is_visible_on(&[laptop]));
+
+        let mut store = WindowStateStore::default();
+        store.set(&[laptop], state);
+        assert_eq!(store.get(&[laptop]), None);
+    }
+
+    #[test]
+    fn test_maximized_round_trip_keeps_restore_bounds() {
+        let state = WindowState { x: 10.0, y: 20.0, width: 800.0, height: 600.0, maximized: true };
+        assert_eq!(WindowState::from_window_bounds(state.to_window_bounds()), state);
+    }
+}
@@ -9,7 +9,7 @@ use gpui::http_client::HttpClient;
 use one_core::llm::claude_client::ClaudeClient;
 use one_core::llm::provider::LlmProvider;
 use one_core::llm::types::{
-    ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
+    ApiKeyError, ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
 };
 
 /// Initialize and register the Claude provider factory
@@ -64,6 +64,13 @@ impl LlmProvider for ClaudeProvider {
         Ok(self.default_models())
     }
 
+    async fn verify_api_key(&self) -> std::result::Result<(), ApiKeyError> {
+        let api_key = self
+            .api_key()
+            .map_err(|e| ApiKeyError::InvalidKey(e.to_string()))?;
+        self.client.verify_api_key(&self.api_base(), &api_key).await
+    }
+
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         let api_key = self.api_key()?;
         self.client
@@ -97,26 +104,29 @@ impl LlmProvider for ClaudeProvider {
 
     fn default_models(&self) -> Vec<ModelInfo> {
         vec![
-            ModelInfo {
-                id: "claude-opus-4-5-20251101".to_string(),
-                name: "Claude Opus 4.5".to_string(),
-                description: Some("Most capable Claude model".to_string()),
-            },
-            ModelInfo {
-                id: "claude-sonnet-4-5-20250929".to_string(),
-                name: "Claude Sonnet 4.5".to_string(),
-                description: Some("Balanced performance and speed".to_string()),
-            },
-            ModelInfo {
-                id: "claude-3-5-sonnet-20241022".to_string(),
-                name: "Claude 3.5 Sonnet".to_string(),
-                description: Some("Previous generation model".to_string()),
-            },
-            ModelInfo {
-                id: "claude-3-5-haiku-20241022".to_string(),
-                name: "Claude 3.5 Haiku".to_string(),
-                description: Some("Fast and cost-effective".to_string()),
-            },
+            ModelInfo::new("claude-opus-4-5-20251101", "Claude Opus 4.5", "Most capable Claude model")
+                .context_window(200_000)
+                .max_output_tokens(64_000)
+                .with_vision()
+                .with_tools()
+                .pricing(5.0, 25.0),
+            ModelInfo::new("claude-sonnet-4-5-20250929", "Claude Sonnet 4.5", "Balanced performance and speed")
+                .context_window(200_000)
+                .max_output_tokens(64_000)
+                .with_vision()
+                .with_tools()
+                .pricing(3.0, 15.0),
+            ModelInfo::new("claude-3-5-sonnet-20241022", "Claude 3.5 Sonnet", "Previous generation model")
+                .context_window(200_000)
+                .max_output_tokens(8_192)
+                .with_vision()
+                .with_tools()
+                .pricing(3.0, 15.0),
+            ModelInfo::new("claude-3-5-haiku-20241022", "Claude 3.5 Haiku", "Fast and cost-effective")
+                .context_window(200_000)
+                .max_output_tokens(8_192)
+                .with_tools()
+                .pricing(0.8, 4.0),
         ]
     }
 }
@@ -9,7 +9,7 @@ use gpui::http_client::HttpClient;
 use one_core::llm::openai_client::OpenAIClient;
 use one_core::llm::provider::LlmProvider;
 use one_core::llm::types::{
-    ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
+    ApiKeyError, ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
 };
 
 /// Initialize and register the DeepSeek provider factory
@@ -62,6 +62,13 @@ impl LlmProvider for DeepSeekProvider {
         Ok(self.default_models())
     }
 
+    async fn verify_api_key(&self) -> std::result::Result<(), ApiKeyError> {
+        let api_key = self
+            .api_key()
+            .map_err(|e| ApiKeyError::InvalidKey(e.to_string()))?;
+        self.client.verify_api_key(&self.api_base(), &api_key).await
+    }
+
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         let api_key = self.api_key()?;
         self.client
@@ -85,16 +92,15 @@ impl LlmProvider for DeepSeekProvider {
 
     fn default_models(&self) -> Vec<ModelInfo> {
         vec![
-            ModelInfo {
-                id: "deepseek-chat".to_string(),
-                name: "DeepSeek Chat".to_string(),
-                description: Some("General purpose chat model".to_string()),
-            },
-            ModelInfo {
-                id: "deepseek-coder".to_string(),
-                name: "DeepSeek Coder".to_string(),
-                description: Some("Code generation and completion".to_string()),
-            },
+            ModelInfo::new("deepseek-chat", "DeepSeek Chat", "General purpose chat model")
+                .context_window(64_000)
+                .max_output_tokens(8_192)
+                .with_tools()
+                .pricing(0.27, 1.1),
+            ModelInfo::new("deepseek-coder", "DeepSeek Coder", "Code generation and completion")
+                .context_window(64_000)
+                .max_output_tokens(8_192)
+                .pricing(0.27, 1.1),
         ]
     }
 }
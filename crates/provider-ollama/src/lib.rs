@@ -19,6 +19,9 @@ pub fn init() {
     });
 }
 
+/// Model used for `embed`; the configured chat model cannot produce embeddings
+const EMBEDDING_MODEL: &str = "nomic-embed-text";
+
 pub struct OllamaProvider {
     config: ProviderConfig,
     client: OpenAIClient,
@@ -57,6 +60,13 @@ impl LlmProvider for OllamaProvider {
         Ok(self.default_models())
     }
 
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        // Ollama doesn't require API key
+        self.client
+            .embeddings(&self.api_base(), "", EMBEDDING_MODEL, inputs)
+            .await
+    }
+
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         // Ollama doesn't require API key
         self.client
@@ -85,21 +95,14 @@ impl LlmProvider for OllamaProvider {
 
     fn default_models(&self) -> Vec<ModelInfo> {
         vec![
-            ModelInfo {
-                id: "llama3.1:latest".to_string(),
-                name: "Llama 3.1".to_string(),
-                description: Some("Meta's latest Llama model".to_string()),
-            },
-            ModelInfo {
-                id: "mistral:latest".to_string(),
-                name: "Mistral".to_string(),
-                description: Some("Mistral AI model".to_string()),
-            },
-            ModelInfo {
-                id: "codellama:latest".to_string(),
-                name: "Code Llama".to_string(),
-                description: Some("Code generation model".to_string()),
-            },
+            ModelInfo::new("llama3.1:latest", "Llama 3.1", "Meta's latest Llama model")
+                .context_window(128_000)
+                .with_tools(),
+            ModelInfo::new("mistral:latest", "Mistral", "Mistral AI model")
+                .context_window(32_768)
+                .with_tools(),
+            ModelInfo::new("codellama:latest", "Code Llama", "Code generation model")
+                .context_window(16_384),
         ]
     }
 }
@@ -9,7 +9,7 @@ use gpui::http_client::HttpClient;
 use one_core::llm::openai_client::OpenAIClient;
 use one_core::llm::provider::LlmProvider;
 use one_core::llm::types::{
-    ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
+    ApiKeyError, ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
 };
 
 /// Initialize and register the OpenAI provider factory
@@ -19,6 +19,9 @@ pub fn init() {
     });
 }
 
+/// Model used for `embed`; the configured chat model cannot produce embeddings
+const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+
 pub struct OpenAIProvider {
     config: ProviderConfig,
     client: OpenAIClient,
@@ -62,6 +65,20 @@ impl LlmProvider for OpenAIProvider {
         Ok(self.default_models())
     }
 
+    async fn verify_api_key(&self) -> std::result::Result<(), ApiKeyError> {
+        let api_key = self
+            .api_key()
+            .map_err(|e| ApiKeyError::InvalidKey(e.to_string()))?;
+        self.client.verify_api_key(&self.api_base(), &api_key).await
+    }
+
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let api_key = self.api_key()?;
+        self.client
+            .embeddings(&self.api_base(), &api_key, EMBEDDING_MODEL, inputs)
+            .await
+    }
+
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         let api_key = self.api_key()?;
         self.client
@@ -95,26 +112,29 @@ impl LlmProvider for OpenAIProvider {
 
     fn default_models(&self) -> Vec<ModelInfo> {
         vec![
-            ModelInfo {
-                id: "gpt-4o".to_string(),
-                name: "GPT-4o".to_string(),
-                description: Some("Most advanced GPT-4 model".to_string()),
-            },
-            ModelInfo {
-                id: "gpt-4o-mini".to_string(),
-                name: "GPT-4o Mini".to_string(),
-                description: Some("Faster and more affordable".to_string()),
-            },
-            ModelInfo {
-                id: "gpt-4-turbo".to_string(),
-                name: "GPT-4 Turbo".to_string(),
-                description: Some("Previous generation model".to_string()),
-            },
-            ModelInfo {
-                id: "gpt-3.5-turbo".to_string(),
-                name: "GPT-3.5 Turbo".to_string(),
-                description: Some("Fast and cost-effective".to_string()),
-            },
+            ModelInfo::new("gpt-4o", "GPT-4o", "Most advanced GPT-4 model")
+                .context_window(128_000)
+                .max_output_tokens(16_384)
+                .with_vision()
+                .with_tools()
+                .pricing(2.5, 10.0),
+            ModelInfo::new("gpt-4o-mini", "GPT-4o Mini", "Faster and more affordable")
+                .context_window(128_000)
+                .max_output_tokens(16_384)
+                .with_vision()
+                .with_tools()
+                .pricing(0.15, 0.6),
+            ModelInfo::new("gpt-4-turbo", "GPT-4 Turbo", "Previous generation model")
+                .context_window(128_000)
+                .max_output_tokens(4_096)
+                .with_vision()
+                .with_tools()
+                .pricing(10.0, 30.0),
+            ModelInfo::new("gpt-3.5-turbo", "GPT-3.5 Turbo", "Fast and cost-effective")
+                .context_window(16_385)
+                .max_output_tokens(4_096)
+                .with_tools()
+                .pricing(0.5, 1.5),
         ]
     }
 }
@@ -9,7 +9,7 @@ use gpui::http_client::HttpClient;
 use one_core::llm::openai_client::OpenAIClient;
 use one_core::llm::provider::LlmProvider;
 use one_core::llm::types::{
-    ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
+    ApiKeyError, ChatRequest, ChatResponse, ChatStreamEvent, ModelInfo, ProviderConfig, ProviderType,
 };
 
 /// Initialize and register the Qwen provider factory
@@ -19,6 +19,9 @@ pub fn init() {
     });
 }
 
+/// Model used for `embed`; the configured chat model cannot produce embeddings
+const EMBEDDING_MODEL: &str = "text-embedding-v3";
+
 pub struct QwenProvider {
     config: ProviderConfig,
     client: OpenAIClient,
@@ -62,6 +65,20 @@ impl LlmProvider for QwenProvider {
         Ok(self.default_models())
     }
 
+    async fn verify_api_key(&self) -> std::result::Result<(), ApiKeyError> {
+        let api_key = self
+            .api_key()
+            .map_err(|e| ApiKeyError::InvalidKey(e.to_string()))?;
+        self.client.verify_api_key(&self.api_base(), &api_key).await
+    }
+
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let api_key = self.api_key()?;
+        self.client
+            .embeddings(&self.api_base(), &api_key, EMBEDDING_MODEL, inputs)
+            .await
+    }
+
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
         let api_key = self.api_key()?;
         self.client
@@ -85,26 +102,21 @@ impl LlmProvider for QwenProvider {
 
     fn default_models(&self) -> Vec<ModelInfo> {
         vec![
-            ModelInfo {
-                id: "qwen-max".to_string(),
-                name: "Qwen Max".to_string(),
-                description: Some("Most capable Qwen model".to_string()),
-            },
-            ModelInfo {
-                id: "qwen-plus".to_string(),
-                name: "Qwen Plus".to_string(),
-                description: Some("Balanced performance".to_string()),
-            },
-            ModelInfo {
-                id: "qwen-turbo".to_string(),
-                name: "Qwen Turbo".to_string(),
-                description: Some("Fast and cost-effective".to_string()),
-            },
-            ModelInfo {
-                id: "qwen-long".to_string(),
-                name: "Qwen Long".to_string(),
-                description: Some("Long context support".to_string()),
-            },
+            ModelInfo::new("qwen-max", "Qwen Max", "Most capable Qwen model")
+                .context_window(32_768)
+                .max_output_tokens(8_192)
+                .with_tools(),
+            ModelInfo::new("qwen-plus", "Qwen Plus", "Balanced performance")
+                .context_window(131_072)
+                .max_output_tokens(8_192)
+                .with_tools(),
+            ModelInfo::new("qwen-turbo", "Qwen Turbo", "Fast and cost-effective")
+                .context_window(1_000_000)
+                .max_output_tokens(8_192)
+                .with_tools(),
+            ModelInfo::new("qwen-long", "Qwen Long", "Long context support")
+                .context_window(10_000_000)
+                .max_output_tokens(8_192),
         ]
     }
 }
@@ -1,6 +1,6 @@
 use std::{collections::HashSet, ops::Range};
 
-use gpui::{div, App, Context, Div, Entity, InteractiveElement as _, IntoElement, ParentElement as _, Stateful, Styled as _, Subscription, Window};
+use gpui::{div, App, Context, Div, Entity, InteractiveElement as _, IntoElement, ParentElement as _, SharedString, Stateful, Styled as _, Subscription, Window};
 
 use crate::{
     h_flex,
@@ -285,4 +285,29 @@ pub trait TableDelegate: Sized + 'static {
         cx: &mut Context<TableState<Self>>,
     ) {
     }
+
+    // ============================================================================
+    // Sort Collation Methods
+    // ============================================================================
+
+    /// Return the description of the collation used when sorting the column,
+    /// shown in the column header menu. Return None to hide it.
+    fn column_sort_collation(&self, col_ix: usize, cx: &App) -> Option<SharedString> {
+        None
+    }
+
+    /// Return whether sorting follows the column collation,
+    /// or None if the delegate does not support switching it.
+    fn locale_sort_enabled(&self, cx: &App) -> Option<bool> {
+        None
+    }
+
+    /// Called when collation-aware sorting is toggled from the column header menu.
+    fn set_locale_sort_enabled(
+        &mut self,
+        enabled: bool,
+        window: &mut Window,
+        cx: &mut Context<TableState<Self>>,
+    ) {
+    }
 }
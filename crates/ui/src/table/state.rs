@@ -1363,11 +1363,13 @@ where
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        use crate::{button::Button, button::ButtonVariants, Sizable, Size};
+        use crate::{button::Button, button::ButtonVariants, checkbox::Checkbox, Sizable, Size};
 
         let table_entity = cx.entity().clone();
         let selected_count = self.filter_panel_selected_count(cx);
         let total_count = self.filter_panel_total_count(cx);
+        let collation = self.delegate.column_sort_collation(col_ix, cx);
+        let locale_sort = self.delegate.locale_sort_enabled(cx);
 
         let filter_list = match &self.filter_list {
             Some(list) => list.clone(),
@@ -1424,6 +1426,34 @@ where
                             ),
                     ),
             )
+            // 排序规则
+            .when_some(collation, |this, collation| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("排序规则：{}", collation)),
+                        )
+                        .when_some(locale_sort, |this, enabled| {
+                            let entity = table_entity.clone();
+                            this.child(
+                                Checkbox::new("filter-locale-sort")
+                                    .checked(enabled)
+                                    .label("按排序规则排序")
+                                    .with_size(Size::XSmall)
+                                    .on_click(move |checked: &bool, window, cx| {
+                                        entity.update(cx, |table, cx| {
+                                            table.delegate_mut().set_locale_sort_enabled(*checked, window, cx);
+                                            cx.notify();
+                                        });
+                                    }),
+                            )
+                        }),
+                )
+            })
             // 分隔线
             .child(div().h(px(1.)).w_full().bg(cx.theme().border))
             // 列表
@@ -0,0 +1,186 @@
+//! Connection Export Form - 选择连接并导出为口令加密的分享文件
+
+use std::collections::HashSet;
+
+use gpui::{div, px, App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render, SharedString, Styled, Window};
+use gpui::prelude::FluentBuilder;
+use gpui_component::{
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputState},
+    switch::Switch,
+    v_flex, ActiveTheme,
+};
+use one_core::storage::connection_share::ConnectionShareOptions;
+use one_core::storage::StoredConnection;
+
+pub struct ConnectionExportForm {
+    focus_handle: FocusHandle,
+    connections: Vec<StoredConnection>,
+    selected_ids: HashSet<i64>,
+    include_passwords: bool,
+    include_ssh_keys: bool,
+    passphrase_input: Entity<InputState>,
+    confirm_input: Entity<InputState>,
+}
+
+impl ConnectionExportForm {
+    pub fn new(
+        connections: Vec<StoredConnection>,
+        selected_id: Option<i64>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let selected_ids = match selected_id {
+            Some(id) => HashSet::from([id]),
+            None => connections.iter().filter_map(|conn| conn.id).collect(),
+        };
+        let passphrase_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Passphrase")
+                .masked(true)
+        });
+        let confirm_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Confirm passphrase")
+                .masked(true)
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            connections,
+            selected_ids,
+            include_passwords: false,
+            include_ssh_keys: false,
+            passphrase_input,
+            confirm_input,
+        }
+    }
+
+    fn toggle_connection(&mut self, id: i64, cx: &mut Context<Self>) {
+        if !self.selected_ids.remove(&id) {
+            self.selected_ids.insert(id);
+        }
+        cx.notify();
+    }
+
+    pub fn selected_connections(&self) -> Vec<StoredConnection> {
+        self.connections
+            .iter()
+            .filter(|conn| conn.id.is_some_and(|id| self.selected_ids.contains(&id)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn options(&self) -> ConnectionShareOptions {
+        ConnectionShareOptions {
+            include_passwords: self.include_passwords,
+            include_ssh_keys: self.include_ssh_keys,
+        }
+    }
+
+    /// 校验表单并返回口令
+    pub fn passphrase(&self, cx: &App) -> Result<String, &'static str> {
+        let passphrase = self.passphrase_input.read(cx).value().to_string();
+        let confirm = self.confirm_input.read(cx).value().to_string();
+
+        if self.selected_ids.is_empty() {
+            return Err("请至少选择一个连接");
+        }
+        if passphrase.is_empty() {
+            return Err("请输入口令");
+        }
+        if passphrase != confirm {
+            return Err("两次输入的口令不一致");
+        }
+        Ok(passphrase)
+    }
+}
+
+impl Focusable for ConnectionExportForm {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for ConnectionExportForm {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let view = cx.entity();
+
+        let mut list = v_flex().gap_1();
+        for conn in &self.connections {
+            let Some(id) = conn.id else {
+                continue;
+            };
+            let view = view.clone();
+            list = list.child(
+                Checkbox::new(SharedString::from(format!("export-conn-{}", id)))
+                    .checked(self.selected_ids.contains(&id))
+                    .label(conn.name.clone())
+                    .on_click(move |_, _, cx| {
+                        view.update(cx, |form, cx| form.toggle_connection(id, cx));
+                    }),
+            );
+        }
+
+        v_flex()
+            .gap_3()
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(gpui::FontWeight::MEDIUM)
+                    .child("选择连接"),
+            )
+            .child(
+                div()
+                    .max_h(px(240.0))
+                    .p_2()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded(cx.theme().radius)
+                    .child(list),
+            )
+            .child(
+                h_flex()
+                    .gap_4()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                Switch::new("include-passwords")
+                                    .checked(self.include_passwords)
+                                    .on_click(cx.listener(|form, checked, _, cx| {
+                                        form.include_passwords = *checked;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child("包含密码"),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                Switch::new("include-ssh-keys")
+                                    .checked(self.include_ssh_keys)
+                                    .on_click(cx.listener(|form, checked, _, cx| {
+                                        form.include_ssh_keys = *checked;
+                                        cx.notify();
+                                    })),
+                            )
+                            .child("包含 SSH 密钥"),
+                    ),
+            )
+            .when(self.include_passwords || self.include_ssh_keys, |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().warning)
+                        .child("导出文件将包含敏感信息，请妥善保管口令"),
+                )
+            })
+            .child(Input::new(&self.passphrase_input))
+            .child(Input::new(&self.confirm_input))
+    }
+}
@@ -1,11 +1,16 @@
 use std::any::Any;
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 use anyhow::Error;
-use gpui::{div, px, AnyElement, App, AppContext, AsyncApp, Context, ElementId, Entity, FontWeight, InteractiveElement, IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window};
+use gpui::{div, px, AnyElement, AnyWindowHandle, App, AppContext, AsyncApp, Context, ElementId, Entity, FontWeight, InteractiveElement, IntoElement, ParentElement, PathPromptOptions, Render, SharedString, StatefulInteractiveElement, Styled, Window};
 use gpui::prelude::FluentBuilder;
-use gpui_component::{button::{Button, ButtonVariants as _}, h_flex, input::{Input, InputEvent, InputState}, menu::PopupMenuItem, v_flex, ActiveTheme, Disableable, Icon, IconName, InteractiveElementExt, Sizable, Size, ThemeMode, WindowExt, tooltip::Tooltip};
+use gpui_component::{button::{Button, ButtonVariants as _}, h_flex, input::{Input, InputEvent, InputState}, menu::PopupMenuItem, notification::Notification, v_flex, ActiveTheme, Disableable, Icon, IconName, InteractiveElementExt, Sizable, Size, ThemeMode, WindowExt, tooltip::Tooltip};
 
-use one_core::storage::{ActiveConnections, ConnectionRepository, ConnectionType, DatabaseType, GlobalStorageState, StoredConnection, Workspace, WorkspaceRepository};
+use one_core::launch::LaunchRequest;
+use one_core::storage::connection_import::{import_from_file, ImportSource, ImportedConnections};
+use one_core::storage::connection_share::{export_connections, import_connections};
+use one_core::storage::{format_variable_lines, parse_variable_lines, ActiveConnections, ConnectionRepository, ConnectionType, DatabaseType, GlobalStorageState, PendingConnectionEdits, StoredConnection, Workspace, WorkspaceRepository, WorkspaceTransfer, WorkspaceVariable, WorkspaceVariableRepository};
 use one_core::storage::traits::Repository;
 use one_core::tab_container::{TabContainer, TabContent, TabContentType, TabItem};
 use one_core::themes::SwitchThemeMode;
@@ -16,6 +21,9 @@ use gpui_component::dialog::DialogButtonProps;
 use gpui_component::menu::DropdownMenu;
 use one_core::gpui_tokio::Tokio;
 
+use crate::connection_export_form::ConnectionExportForm;
+use crate::launch::PendingLaunches;
+use crate::onehup_app::NewWindow;
 use crate::setting_tab::SettingsTabContent;
 
 
@@ -30,8 +38,13 @@ pub struct HomePage {
     search_input: Entity<InputState>,
     search_query: Entity<String>,
     editing_connection_id: Option<i64>,
+    /// 复制出的连接副本，在表单中修改后作为新连接保存
+    duplicating_connection: Option<StoredConnection>,
     selected_connection_id: Option<i64>,
     editing_workspace_id: Option<i64>,
+    window_handle: AnyWindowHandle,
+    /// 首次加载连接列表后才能处理自动打开和启动请求
+    connections_loaded: bool,
 }
 
 impl HomePage {
@@ -54,6 +67,12 @@ impl HomePage {
         })
         .detach();
 
+        // 应用运行期间收到的 onehub:// 链接
+        cx.observe_global_in::<PendingLaunches>(window, |this, window, cx| {
+            this.process_launch_requests(window, cx);
+        })
+        .detach();
+
         let mut page = Self {
             selected_filter: ConnectionType::All,
             workspaces: Vec::new(),
@@ -63,10 +82,21 @@ impl HomePage {
             search_input,
             search_query,
             editing_connection_id: None,
+            duplicating_connection: None,
             selected_connection_id: None,
             editing_workspace_id: None,
+            window_handle: window.window_handle(),
+            connections_loaded: false,
         };
 
+        // 连接树中复制的连接在这里打开编辑器
+        cx.observe_global_in::<PendingConnectionEdits>(window, |this, window, cx| {
+            for connection in PendingConnectionEdits::take(cx) {
+                this.show_duplicate_connection_form(connection, window, cx);
+            }
+        })
+        .detach();
+
         // 异步加载工作区和连接列表
         page.load_workspaces(cx);
         page.load_connections(cx);
@@ -113,10 +143,19 @@ impl HomePage {
             }.await;
             match task_result {
                 Ok(connections) => {
-                    _ = this.update(cx, |this, cx| {
+                    let first_load = this.update(cx, |this, cx| {
                         this.connections = connections;
                         cx.notify();
+                        !std::mem::replace(&mut this.connections_loaded, true)
                     });
+                    if let Ok(true) = first_load {
+                        let window_handle = this.update(cx, |this, _| this.window_handle);
+                        if let Ok(window_handle) = window_handle {
+                            let _ = cx.update_window(window_handle, |_, window, cx| {
+                                _ = this.update(cx, |this, cx| this.open_startup_connections(window, cx));
+                            });
+                        }
+                    }
                 }
                 Err(e) => {
                     tracing::error!("Task join error: {}", e);
@@ -152,6 +191,322 @@ impl HomePage {
         }).detach();
     }
 
+    fn set_auto_open(&mut self, conn_id: i64, auto_open: bool, cx: &mut Context<Self>) {
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = async {
+                Tokio::spawn_result(cx, async move {
+                    let repo = storage.get::<ConnectionRepository>().await
+                        .ok_or_else(|| anyhow::anyhow!("ConnectionRepository not found"))?;
+                    repo.set_auto_open(conn_id, auto_open).await
+                })?.await
+            }.await;
+            match result {
+                Ok(_) => {
+                    _ = this.update(cx, |this, cx| {
+                        if let Some(conn) = this.connections.iter_mut().find(|c| c.id == Some(conn_id)) {
+                            conn.auto_open = auto_open;
+                        }
+                        cx.notify();
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("Failed to update auto open: {}", e);
+                }
+            }
+        }).detach();
+    }
+
+    /// 移动或复制连接（连同保存的查询）到另一个工作区，复制出的连接重名时自动改名并提示
+    fn transfer_connection(&mut self, conn_id: i64, workspace_id: Option<i64>, transfer: WorkspaceTransfer, cx: &mut Context<Self>) {
+        let Some(original_name) = self.connections.iter().find(|c| c.id == Some(conn_id)).map(|c| c.name.clone()) else {
+            return;
+        };
+        let workspace_name = workspace_id
+            .and_then(|id| self.workspaces.iter().find(|w| w.id == Some(id)))
+            .map(|w| w.name.clone())
+            .unwrap_or_else(|| "未分配工作区".to_string());
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = async {
+                Tokio::spawn_result(cx, async move {
+                    let repo = storage.get::<ConnectionRepository>().await
+                        .ok_or_else(|| anyhow::anyhow!("ConnectionRepository not found"))?;
+                    repo.transfer_to_workspace(conn_id, workspace_id, transfer).await
+                })?.await
+            }.await;
+            let action = match transfer {
+                WorkspaceTransfer::Move => "移动",
+                WorkspaceTransfer::Copy => "复制",
+            };
+            let message = match result {
+                Ok(connection) => {
+                    _ = this.update(cx, |this, cx| this.load_connections(cx));
+                    if connection.name == original_name {
+                        Notification::success(format!("已将连接 \"{}\" {}到 {}", original_name, action, workspace_name))
+                    } else {
+                        Notification::info(format!(
+                            "已将连接 \"{}\" {}到 {}，因名称重复改名为 \"{}\"",
+                            original_name, action, workspace_name, connection.name
+                        ))
+                    }
+                }
+                Err(e) => Notification::error(format!("{}连接失败: {}", action, e)),
+            };
+            if let Ok(window_handle) = this.update(cx, |this, _| this.window_handle) {
+                _ = cx.update_window(window_handle, |_, window, cx| {
+                    window.push_notification(message.autohide(true), cx);
+                });
+            }
+        }).detach();
+    }
+
+    /// 打开标记为启动时自动打开的连接（每次启动只执行一次），再处理启动参数
+    fn open_startup_connections(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if PendingLaunches::claim_auto_open(cx) {
+            let auto_open: Vec<StoredConnection> = self.connections.iter().filter(|c| c.auto_open).cloned().collect();
+            for conn in &auto_open {
+                self.add_item_to_tab(conn, None, window, cx);
+            }
+        }
+        self.process_launch_requests(window, cx);
+    }
+
+    fn process_launch_requests(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.connections_loaded {
+            return;
+        }
+        for request in PendingLaunches::take_requests(cx) {
+            self.open_launch_request(request, window, cx);
+        }
+    }
+
+    fn open_launch_request(&mut self, request: LaunchRequest, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(name) = request.connection.as_deref() else {
+            return;
+        };
+        let conn = self.connections.iter().find(|c| c.name == name)
+            .or_else(|| self.connections.iter().find(|c| c.name.eq_ignore_ascii_case(name)))
+            .cloned();
+        let Some(conn) = conn else {
+            window.push_notification(Notification::error(format!("未找到连接 \"{}\"", name)).autohide(true), cx);
+            return;
+        };
+
+        self.add_item_to_tab(&conn, None, window, cx);
+
+        let Some(conn_id) = conn.id else {
+            return;
+        };
+        if request.sql.is_none() && request.database.is_none() {
+            return;
+        }
+        let tab_id = format!("database-tab-{}", conn_id);
+        let content = self.tab_container.read(cx).tabs().iter()
+            .find(|tab| tab.id() == tab_id)
+            .map(|tab| tab.content().clone());
+        if let Some(db_content) = content.as_ref().and_then(|c| c.as_any().downcast_ref::<DatabaseTabContent>()) {
+            db_content.open_query(conn_id, request.sql.unwrap_or_default(), request.database, window, cx);
+        }
+    }
+
+    fn show_export_connections(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let connections = self.connections.clone();
+        let selected_id = self.selected_connection_id;
+        let form = cx.new(|cx| ConnectionExportForm::new(connections, selected_id, window, cx));
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let form_clone = form.clone();
+            dialog
+                .title("导出连接")
+                .w(px(480.0))
+                .child(form.clone())
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("导出"))
+                .on_ok(move |_, window, cx| {
+                    let form = form_clone.read(cx);
+                    let passphrase = match form.passphrase(cx) {
+                        Ok(passphrase) => passphrase,
+                        Err(message) => {
+                            window.push_notification(message, cx);
+                            return false;
+                        }
+                    };
+                    let connections = form.selected_connections();
+                    let options = form.options();
+
+                    let directory = std::env::current_dir().unwrap_or_default();
+                    let path_future = cx.prompt_for_new_path(&directory, Some("connections.onehub"));
+                    cx.spawn(async move |cx: &mut AsyncApp| {
+                        let path = match path_future.await {
+                            Ok(Ok(Some(path))) => path,
+                            _ => return,
+                        };
+                        let result = export_connections(&connections, options, &passphrase)
+                            .and_then(|content| {
+                                std::fs::write(&path, content)?;
+                                Ok(())
+                            });
+                        let message = match result {
+                            Ok(()) => format!("已导出 {} 个连接到 {}", connections.len(), path.display()),
+                            Err(e) => format!("导出连接失败: {}", e),
+                        };
+                        _ = cx.update(|cx| {
+                            if let Some(window) = cx.active_window() {
+                                _ = window.update(cx, |_, window, cx| window.push_notification(message, cx));
+                            }
+                        });
+                    }).detach();
+                    true
+                })
+        });
+    }
+
+    /// 从其他工具的连接配置导入连接
+    fn import_from_tool(&mut self, source: ImportSource, cx: &mut Context<Self>) {
+        let path_future = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            multiple: false,
+            directories: false,
+            prompt: Some(source.file_hint().into()),
+        });
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let Ok(Ok(Some(paths))) = path_future.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            match import_from_file(source, &path) {
+                Ok(imported) => {
+                    _ = this.update(cx, |this, cx| this.save_imported_connections(source.label(), imported, cx));
+                }
+                Err(e) => {
+                    _ = cx.update(|cx| {
+                        if let Some(window) = cx.active_window() {
+                            _ = window.update(cx, |_, window, cx| {
+                                window.push_notification(format!("导入连接失败: {}", e), cx)
+                            });
+                        }
+                    });
+                }
+            }
+        }).detach();
+    }
+
+    /// 选择分享文件后输入口令解密导入
+    fn import_share_file(&mut self, cx: &mut Context<Self>) {
+        let path_future = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            multiple: false,
+            directories: false,
+            prompt: Some("选择连接分享文件".into()),
+        });
+        let window_handle = self.window_handle;
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let Ok(Ok(Some(paths))) = path_future.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            _ = cx.update_window(window_handle, |_, window, cx| {
+                _ = this.update(cx, |this, cx| this.show_share_passphrase_dialog(path, window, cx));
+            });
+        }).detach();
+    }
+
+    fn show_share_passphrase_dialog(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) => {
+                window.push_notification(format!("读取分享文件失败: {}", e), cx);
+                return;
+            }
+        };
+        let view = cx.entity().clone();
+        let passphrase_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("Passphrase")
+                .masked(true)
+        });
+
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let input = passphrase_input.clone();
+            let data = data.clone();
+            let view = view.clone();
+            dialog
+                .title("导入连接")
+                .w(px(400.0))
+                .child(Input::new(&passphrase_input))
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text("导入"))
+                .on_ok(move |_, window, cx| {
+                    let passphrase = input.read(cx).value().to_string();
+                    // 口令错误时保留对话框，便于重新输入
+                    match import_connections(&data, &passphrase) {
+                        Ok(connections) => {
+                            let imported = ImportedConnections { connections, skipped: Vec::new() };
+                            view.update(cx, |this, cx| this.save_imported_connections("分享文件", imported, cx));
+                            true
+                        }
+                        Err(e) => {
+                            window.push_notification(format!("导入连接失败: {}", e), cx);
+                            false
+                        }
+                    }
+                })
+        });
+    }
+
+    /// 保存导入的连接，名称已存在的连接不会重复导入
+    fn save_imported_connections(&mut self, source: &'static str, imported: ImportedConnections, cx: &mut Context<Self>) {
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let mut existing_names: HashSet<String> = self.connections.iter().map(|c| c.name.clone()).collect();
+        let mut skipped = imported.skipped;
+        let mut connections = Vec::new();
+        for connection in imported.connections {
+            if existing_names.insert(connection.name.clone()) {
+                connections.push(connection);
+            } else {
+                skipped.push(connection.name);
+            }
+        }
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let count = connections.len();
+            let result = async {
+                Tokio::spawn_result(cx, async move {
+                    let repo = storage.get::<ConnectionRepository>().await
+                        .ok_or_else(|| anyhow::anyhow!("ConnectionRepository not found"))?;
+                    for mut connection in connections {
+                        repo.insert(&mut connection).await?;
+                    }
+                    Ok(())
+                })?.await
+            }.await;
+
+            let message = match result {
+                Ok(()) if skipped.is_empty() => format!("已导入 {} 个连接 ({})", count, source),
+                Ok(()) => format!(
+                    "已导入 {} 个连接 ({})，跳过已存在或不支持的连接: {}",
+                    count,
+                    source,
+                    skipped.join(", ")
+                ),
+                Err(e) => format!("导入连接失败: {}", e),
+            };
+            _ = this.update(cx, |this, cx| this.load_connections(cx));
+            _ = cx.update(|cx| {
+                if let Some(window) = cx.active_window() {
+                    _ = window.update(cx, |_, window, cx| window.push_notification(message, cx));
+                }
+            });
+        }).detach();
+    }
+
     fn show_workspace_form(&mut self, workspace_id: Option<i64>, window: &mut Window, cx: &mut Context<Self>) {
         let workspace_data = workspace_id.and_then(|id| {
             self.workspaces.iter().find(|w| w.id == Some(id)).cloned()
@@ -259,6 +614,91 @@ impl HomePage {
         }).detach();
     }
 
+    fn show_workspace_variables(&mut self, workspace_id: i64, cx: &mut Context<Self>) {
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let window_handle = self.window_handle;
+        let task = Tokio::spawn_result(cx, async move {
+            let repo = storage.get::<WorkspaceVariableRepository>().await
+                .ok_or_else(|| anyhow::anyhow!("WorkspaceVariableRepository not found"))?;
+            repo.list(workspace_id).await
+        });
+
+        cx.spawn(async move |_this, cx| {
+            let variables = task.await;
+            _ = cx.update_window(window_handle, |_, window, cx| match variables {
+                Ok(variables) => Self::open_workspace_variables_dialog(workspace_id, variables, window, cx),
+                Err(e) => window.push_notification(Notification::error(format!("加载工作区变量失败: {}", e)), cx),
+            });
+        }).detach();
+    }
+
+    /// 以 `NAME=value` 每行一个的文本编辑工作区变量，保存时整体替换
+    fn open_workspace_variables_dialog(workspace_id: i64, variables: Vec<WorkspaceVariable>, window: &mut Window, cx: &mut App) {
+        let input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("每行一个变量，例如 TENANT_ID=42");
+            state.set_value(format_variable_lines(&variables), window, cx);
+            state
+        });
+        let storage = cx.global::<GlobalStorageState>().storage.clone();
+        let window_handle = window.window_handle();
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let input = input.clone();
+            let storage = storage.clone();
+            dialog
+                .title("工作区变量")
+                .w(px(480.0))
+                .child(
+                    v_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("在 SQL 和 AI 提示词中以 ${NAME} 引用，执行时替换为这里定义的值")
+                        )
+                        .child(Input::new(&input).h(px(200.0)))
+                )
+                .confirm()
+                .on_ok(move |_, window, cx| {
+                    let variables = match parse_variable_lines(&input.read(cx).text().to_string()) {
+                        Ok(variables) => variables,
+                        Err(e) => {
+                            window.push_notification(e.to_string(), cx);
+                            return false;
+                        }
+                    };
+                    let storage = storage.clone();
+                    let task = Tokio::spawn_result(cx, async move {
+                        let repo = storage.get::<WorkspaceVariableRepository>().await
+                            .ok_or_else(|| anyhow::anyhow!("WorkspaceVariableRepository not found"))?;
+                        repo.replace_all(workspace_id, &variables).await
+                    });
+                    cx.spawn(async move |cx: &mut AsyncApp| {
+                        if let Err(e) = task.await {
+                            _ = cx.update_window(window_handle, |_, window, cx| {
+                                window.push_notification(Notification::error(format!("保存工作区变量失败: {}", e)), cx);
+                            });
+                        }
+                    }).detach();
+                    true
+                })
+        });
+    }
+
+    /// 以连接的副本打开新建表单，用于快速创建其它环境的同类连接
+    fn show_duplicate_connection_form(&mut self, connection: StoredConnection, window: &mut Window, cx: &mut Context<Self>) {
+        let Ok(params) = connection.to_db_connection() else {
+            window.push_notification(Notification::error("复制连接失败：连接参数无法解析").autohide(true), cx);
+            return;
+        };
+        self.editing_connection_id = None;
+        self.duplicating_connection = Some(connection);
+        self.show_connection_form(params.database_type, window, cx);
+    }
+
     fn show_connection_form(&mut self, db_type: DatabaseType, window: &mut Window, cx: &mut Context<Self>) {
         let plugin_registry = cx.global::<DatabaseViewPluginRegistry>();
         let plugin = match plugin_registry.get(&db_type) {
@@ -282,10 +722,18 @@ impl HomePage {
                 });
             }
         }
+        let duplicating = self.duplicating_connection.take();
+        if let Some(conn) = &duplicating {
+            form.update(cx, |f, cx| {
+                f.load_connection(conn, window, cx);
+            });
+        }
 
         let is_editing = self.editing_connection_id.is_some();
         let title = if is_editing {
             format!("编辑 {} 连接", db_type.as_str())
+        } else if duplicating.is_some() {
+            format!("复制 {} 连接", db_type.as_str())
         } else {
             format!("新建 {} 连接", db_type.as_str())
         };
@@ -443,6 +891,7 @@ impl HomePage {
 
     fn render_toolbar(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let view = cx.entity();
+        let view_for_import = view.clone();
 
         h_flex()
             .p_4()
@@ -489,6 +938,42 @@ impl HomePage {
                                 menu
                             })
                     )
+                    .child(
+                        Button::new("export-connections-button")
+                            .icon(IconName::ArrowUp)
+                            .label("导出连接")
+                            .with_size(Size::Large)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.show_export_connections(window, cx);
+                            }))
+                    )
+                    .child(
+                        Button::new("import-connections-button")
+                            .icon(IconName::ArrowDown)
+                            .label("导入连接")
+                            .with_size(Size::Large)
+                            .dropdown_menu(move |menu, window, _cx| {
+                                let mut menu = menu
+                                    .large()
+                                    .item(
+                                        PopupMenuItem::new("从分享文件导入")
+                                            .on_click(window.listener_for(&view_for_import, |this, _, _window, cx| {
+                                                this.import_share_file(cx);
+                                            }))
+                                    )
+                                    .separator();
+                                for source in ImportSource::all() {
+                                    let source = *source;
+                                    menu = menu.item(
+                                        PopupMenuItem::new(format!("从 {} 导入", source.label()))
+                                            .on_click(window.listener_for(&view_for_import, move |this, _, _window, cx| {
+                                                this.import_from_tool(source, cx);
+                                            }))
+                                    );
+                                }
+                                menu
+                            })
+                    )
             )
             .child(
                 h_flex()
@@ -593,6 +1078,16 @@ impl HomePage {
                                 window.dispatch_action(Box::new(SwitchThemeMode(new_mode)), cx);
                             }))
                     )
+                    .child(
+                        Button::new("new_window")
+                            .icon(IconName::WindowRestore)
+                            .label("新建窗口")
+                            .w_full()
+                            .justify_start()
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(NewWindow), cx);
+                            })
+                    )
                     .child(
                         Button::new("open_settings")
                             .icon(IconName::Settings)
@@ -745,6 +1240,19 @@ impl HomePage {
                                 this.show_workspace_form(workspace_id, window, cx);
                             }))
                     )
+                    .when_some(workspace_id, |this, workspace_id| {
+                        this.child(
+                            Button::new(SharedString::from(format!("workspace-variables-{}", workspace_id)))
+                                .icon(IconName::Settings2)
+                                .with_size(Size::Small)
+                                .ghost()
+                                .tooltip("工作区变量")
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    cx.stop_propagation();
+                                    this.show_workspace_variables(workspace_id, cx);
+                                }))
+                        )
+                    })
             )
             .when(!connections.is_empty(), |this| {
                 // 使用 flex 布局实现响应式卡片网格
@@ -825,8 +1333,15 @@ impl HomePage {
         let conn_id = conn.id;
         let clone_conn = conn.clone();
         let edit_conn = conn.clone();
+        let duplicate_conn = conn.clone();
+        let view = cx.entity().clone();
+        let transfer_targets: Vec<(Option<i64>, SharedString)> = std::iter::once((None, SharedString::from("未分配工作区")))
+            .chain(self.workspaces.iter().map(|w| (w.id, SharedString::from(w.name.clone()))))
+            .filter(|(target_id, _)| *target_id != conn.workspace_id)
+            .collect();
         let delete_conn_id = conn.id;
         let delete_conn_name = conn.name.clone();
+        let auto_open = conn.auto_open;
         let is_selected = selected_id == conn.id;
         let workspace = workspace_id.and_then(|id| {
             self.workspaces.iter().find(|w| w.id == Some(id)).cloned()
@@ -894,6 +1409,19 @@ impl HomePage {
                     .gap_1()
                     .group_hover("", |style| style.opacity(1.0))
                     .opacity(0.0)
+                    .child(
+                        Button::new(SharedString::from(format!("auto-open-conn-{}", conn.id.unwrap_or(0))))
+                            .icon(if auto_open { IconName::Star } else { IconName::StarOff })
+                            .with_size(Size::Small)
+                            .ghost()
+                            .tooltip(if auto_open { "取消启动时自动打开" } else { "启动时自动打开" })
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                cx.stop_propagation();
+                                if let Some(conn_id) = conn_id {
+                                    this.set_auto_open(conn_id, !auto_open, cx);
+                                }
+                            }))
+                    )
                     .child(
                         Button::new(SharedString::from(format!("edit-conn-{}", conn.id.unwrap_or(0))))
                             .icon(IconName::Edit)
@@ -910,6 +1438,46 @@ impl HomePage {
                                 }
                             }))
                     )
+                    .child(
+                        Button::new(SharedString::from(format!("duplicate-conn-{}", conn.id.unwrap_or(0))))
+                            .icon(IconName::Copy)
+                            .with_size(Size::Small)
+                            .ghost()
+                            .tooltip("复制连接")
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                cx.stop_propagation();
+                                this.show_duplicate_connection_form(duplicate_conn.duplicate(), window, cx);
+                            }))
+                    )
+                    .when_some(conn_id.filter(|_| !transfer_targets.is_empty()), |this, conn_id| {
+                        this.child(
+                            Button::new(SharedString::from(format!("transfer-conn-{}", conn_id)))
+                                .icon(IconName::FolderOpen)
+                                .with_size(Size::Small)
+                                .ghost()
+                                .tooltip("移动或复制到工作区")
+                                .dropdown_menu(move |menu, window, cx| {
+                                    let mut menu = menu;
+                                    for (label, transfer) in [("移动到", WorkspaceTransfer::Move), ("复制到", WorkspaceTransfer::Copy)] {
+                                        let view = view.clone();
+                                        let targets = transfer_targets.clone();
+                                        menu = menu.submenu(label, window, cx, move |mut submenu, window, _cx| {
+                                            for (workspace_id, name) in &targets {
+                                                let workspace_id = *workspace_id;
+                                                submenu = submenu.item(
+                                                    PopupMenuItem::new(name.clone())
+                                                        .on_click(window.listener_for(&view, move |this, _, _window, cx| {
+                                                            this.transfer_connection(conn_id, workspace_id, transfer, cx);
+                                                        }))
+                                                );
+                                            }
+                                            submenu
+                                        });
+                                    }
+                                    menu
+                                })
+                        )
+                    })
                     .child(
                         Button::new(SharedString::from(format!("delete-conn-{}", conn.id.unwrap_or(0))))
                             .icon(IconName::Remove)
@@ -992,6 +1560,18 @@ impl HomePage {
                             .when_some(conn.to_db_connection().ok(), |this, params| {
                                 let conn_info = if params.database_type == DatabaseType::SQLite {
                                     params.host.clone()
+                                } else if params.database_type == DatabaseType::DuckDB {
+                                    if params.host.is_empty() {
+                                        ":memory:".to_string()
+                                    } else {
+                                        params.host.clone()
+                                    }
+                                } else if params.database_type == DatabaseType::ODBC {
+                                    if params.host.is_empty() {
+                                        "ODBC".to_string()
+                                    } else {
+                                        format!("DSN={}", params.host)
+                                    }
                                 } else {
                                     let database = match params.database {
                                         Some(database) => format!("/{}", database),
@@ -0,0 +1,60 @@
+//! 启动参数与 onehub:// 链接 - 收集待打开的连接，由主页在连接列表加载后处理
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::StreamExt;
+use gpui::{App, AsyncApp, Global};
+use one_core::launch::LaunchRequest;
+
+#[derive(Default)]
+pub struct PendingLaunches {
+    requests: Vec<LaunchRequest>,
+    /// 自动打开的连接只在第一个窗口中打开一次
+    auto_open_done: bool,
+}
+
+impl Global for PendingLaunches {}
+
+impl PendingLaunches {
+    pub fn take_requests(cx: &mut App) -> Vec<LaunchRequest> {
+        std::mem::take(&mut cx.default_global::<PendingLaunches>().requests)
+    }
+
+    /// 返回 true 表示本次需要执行自动打开
+    pub fn claim_auto_open(cx: &mut App) -> bool {
+        let pending = cx.default_global::<PendingLaunches>();
+        !std::mem::replace(&mut pending.auto_open_done, true)
+    }
+}
+
+/// 命令行参数中的启动请求，解析失败时记录日志并忽略
+pub fn parse_args() -> Option<LaunchRequest> {
+    match LaunchRequest::from_args(std::env::args().skip(1)) {
+        Ok(request) if !request.is_empty() => Some(request),
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!("Invalid launch arguments: {}", e);
+            None
+        }
+    }
+}
+
+/// 记录启动请求，并持续接收应用运行期间系统转发的 onehub:// 链接
+pub fn init(initial: Option<LaunchRequest>, mut urls: UnboundedReceiver<String>, cx: &mut App) {
+    cx.set_global(PendingLaunches {
+        requests: initial.into_iter().collect(),
+        auto_open_done: false,
+    });
+
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        while let Some(url) = urls.next().await {
+            match LaunchRequest::from_url(&url) {
+                Ok(request) => {
+                    let _ = cx.update(|cx| {
+                        cx.global_mut::<PendingLaunches>().requests.push(request);
+                    });
+                }
+                Err(e) => tracing::warn!("Ignoring deep link {}: {}", url, e),
+            }
+        }
+    }).detach();
+}
@@ -1,65 +1,43 @@
 mod onehup_app;
 mod home;
+mod connection_export_form;
 mod setting_tab;
 mod settings;
+mod launch;
+mod unlock;
 
 use gpui::*;
-use gpui_component::Root;
 use db::GlobalDbState;
 use db_view::database_view_plugin::DatabaseViewPluginRegistry;
 use gpui_component_assets::Assets;
-use crate::onehup_app::OneHupApp;
 
 fn main() {
     let app = Application::new().with_assets(Assets);
 
-
+    let launch_request = launch::parse_args();
+    // 应用运行期间打开的 onehub:// 链接
+    let (url_tx, url_rx) = futures::channel::mpsc::unbounded();
+    app.on_open_urls(move |urls| {
+        for url in urls {
+            let _ = url_tx.unbounded_send(url);
+        }
+    });
 
     app.run(move |cx| {
         onehup_app::init(cx);
-        // Initialize global database state
-        let db_state = GlobalDbState::new();
-        // Start cleanup task
-        db_state.start_cleanup_task(cx);
-        cx.set_global(db_state);
-
-        // Initialize database view plugin registry
-        let view_registry = DatabaseViewPluginRegistry::new();
-        cx.set_global(view_registry);
-        let mut window_size = size(px(1600.0), px(1200.0));
-        if let Some(display) = cx.primary_display() {
-            let display_size = display.bounds().size;
-            window_size.width = window_size.width.min(display_size.width * 0.85);
-            window_size.height = window_size.height.min(display_size.height * 0.85);
-        }
-
-        let window_bounds = Bounds::centered(None, window_size, cx);
-        let options = WindowOptions {
-            window_bounds: Some(WindowBounds::Windowed(window_bounds)),
-            #[cfg(not(target_os = "linux"))]
-            titlebar: Some(gpui_component::TitleBar::title_bar_options()),
-            window_min_size: Some(Size {
-                width: px(640.),
-                height: px(480.),
-            }),
-            #[cfg(target_os = "linux")]
-            window_background: gpui::WindowBackgroundAppearance::Transparent,
-            #[cfg(target_os = "linux")]
-            window_decorations: Some(gpui::WindowDecorations::Client),
-            kind: WindowKind::Normal,
-            ..Default::default()
-        };
-
-        cx.spawn(async move |cx| {
-            cx.open_window(options, |window, cx| {
-                let view = cx.new(|cx| {
-                    OneHupApp::new(window, cx)
-                });
-                cx.new(|cx| Root::new(view, window, cx))
-            })?;
-
-            Ok::<_, anyhow::Error>(())
-        })
-        .detach();
+        unlock::unlock_storage(cx, move |key, cx| {
+            one_core::init_storage(cx, key);
+            launch::init(launch_request, url_rx, cx);
+            // Initialize global database state
+            let db_state = GlobalDbState::new();
+            // Start cleanup task
+            db_state.start_cleanup_task(cx);
+            cx.set_global(db_state);
+
+            // Initialize database view plugin registry
+            let view_registry = DatabaseViewPluginRegistry::new();
+            cx.set_global(view_registry);
+            onehup_app::open_main_window(cx);
+        });
     });
 }
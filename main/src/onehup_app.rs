@@ -1,13 +1,24 @@
-use gpui::{div, px, App, AppContext, Context, Entity, IntoElement, InteractiveElement, KeyBinding, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Point, Render, Styled, Window, Focusable};
+use std::sync::Arc;
+use std::time::Duration;
+
+use gpui::{actions, div, px, size, App, AppContext, Bounds, Context, Entity, IntoElement, InteractiveElement, KeyBinding, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point, Render, Size, Styled, Subscription, Window, WindowBounds, WindowKind, WindowOptions, Focusable};
 use gpui_component::dock::{ClosePanel, ToggleZoom};
-use gpui_component::{ActiveTheme, Root, button::Button, IconName, Sizable, WindowExt, h_flex};
+use gpui_component::{ActiveTheme, Root, button::Button, IconName, Selectable, Sizable, WindowExt, h_flex};
 use gpui_component::button::ButtonVariants;
+use gpui_component::menu::{DropdownMenu, PopupMenuItem};
 use one_core::tab_container::{TabContainer, TabItem};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use reqwest_client::ReqwestClient;
 use crate::home::HomeTabContent;
-use db_view::ai_chat_panel::AiChatPanel;
+use db_view::ai_chat_panel::{AiChatPanel, AiChatPanelEvent};
+use db_view::database_tab::DatabaseTabContent;
+use one_core::llm::chat_export::ChatExportFormat;
+use one_core::utils::debouncer::Debouncer;
+use one_core::storage::GlobalStorageState;
+use one_core::window_state::{WindowState, WindowStateStore};
+
+actions!(onehub, [NewWindow]);
 
 /// Initialize all LLM provider factories
 fn init_providers() {
@@ -36,11 +47,81 @@ pub fn init(cx: &mut App) {
     cx.bind_keys(vec![
         KeyBinding::new("shift-escape", ToggleZoom, None),
         KeyBinding::new("ctrl-w", ClosePanel, None),
+        KeyBinding::new("secondary-shift-n", NewWindow, None),
     ]);
+    cx.on_action(|_: &NewWindow, cx| {
+        // 解锁窗口中存储尚未打开
+        if cx.has_global::<GlobalStorageState>() {
+            open_main_window(cx);
+        }
+    });
     init_providers();
     cx.activate(true);
 }
 
+fn display_bounds(cx: &App) -> Vec<Bounds<Pixels>> {
+    cx.displays().iter().map(|display| display.bounds()).collect()
+}
+
+/// 没有保存过窗口状态时，在主显示器居中打开
+fn default_window_bounds(cx: &App) -> WindowBounds {
+    let mut window_size = size(px(1600.0), px(1200.0));
+    if let Some(display) = cx.primary_display() {
+        let display_size = display.bounds().size;
+        window_size.width = window_size.width.min(display_size.width * 0.85);
+        window_size.height = window_size.height.min(display_size.height * 0.85);
+    }
+    WindowBounds::Windowed(Bounds::centered(None, window_size, cx))
+}
+
+fn save_window_state(state: WindowState, cx: &App) {
+    let displays = display_bounds(cx);
+    let mut store = WindowStateStore::load();
+    store.set(&displays, state);
+    if let Err(e) = store.save() {
+        tracing::warn!("Failed to save window state: {}", e);
+    }
+}
+
+/// 打开一个主窗口。恢复当前显示器布局下上次的窗口位置和大小；
+/// 已有窗口时新窗口错开显示，所有窗口共享同一套全局状态
+pub fn open_main_window(cx: &mut App) {
+    let displays = display_bounds(cx);
+    let window_bounds = match WindowStateStore::load().get(&displays) {
+        Some(state) if !cx.windows().is_empty() => state.cascaded().to_window_bounds(),
+        Some(state) => state.to_window_bounds(),
+        None => default_window_bounds(cx),
+    };
+
+    let options = WindowOptions {
+        window_bounds: Some(window_bounds),
+        #[cfg(not(target_os = "linux"))]
+        titlebar: Some(gpui_component::TitleBar::title_bar_options()),
+        window_min_size: Some(Size {
+            width: px(640.),
+            height: px(480.),
+        }),
+        #[cfg(target_os = "linux")]
+        window_background: gpui::WindowBackgroundAppearance::Transparent,
+        #[cfg(target_os = "linux")]
+        window_decorations: Some(gpui::WindowDecorations::Client),
+        kind: WindowKind::Normal,
+        ..Default::default()
+    };
+
+    cx.spawn(async move |cx| {
+        cx.open_window(options, |window, cx| {
+            let view = cx.new(|cx| {
+                OneHupApp::new(window, cx)
+            });
+            cx.new(|cx| Root::new(view, window, cx))
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
 pub struct OneHupApp {
     tab_container: Entity<TabContainer>,
     ai_button_y: gpui::Pixels,
@@ -48,6 +129,8 @@ pub struct OneHupApp {
     drag_start_pos: Option<Point<gpui::Pixels>>,
     drag_start_button_y: Option<gpui::Pixels>,
     ai_panel: Entity<AiChatPanel>,
+    _bounds_subscription: Subscription,
+    _ai_panel_subscription: Subscription,
 }
 
 impl OneHupApp {
@@ -89,6 +172,22 @@ impl OneHupApp {
         });
 
         let ai_panel = cx.new(|cx| AiChatPanel::new(window, cx));
+        let ai_panel_subscription = cx.subscribe_in(&ai_panel, window, |this, _panel, event, window, cx| {
+            this.handle_ai_panel_event(event, window, cx);
+        });
+
+        // 窗口移动或缩放停止后再写入，避免拖动过程中频繁写文件
+        let bounds_debouncer = Arc::new(Debouncer::new(Duration::from_millis(500)));
+        let bounds_subscription = cx.observe_window_bounds(window, move |_this, window, cx| {
+            let state = WindowState::from_window_bounds(window.window_bounds());
+            let debouncer = bounds_debouncer.clone();
+            cx.spawn(async move |_this, cx| {
+                if debouncer.debounce().await {
+                    let _ = cx.update(|cx| save_window_state(state, cx));
+                }
+            })
+            .detach();
+        });
 
         Self {
             tab_container,
@@ -97,16 +196,60 @@ impl OneHupApp {
             drag_start_pos: None,
             drag_start_button_y: None,
             ai_panel,
+            _bounds_subscription: bounds_subscription,
+            _ai_panel_subscription: ai_panel_subscription,
+        }
+    }
+
+    fn handle_ai_panel_event(&mut self, event: &AiChatPanelEvent, window: &mut Window, cx: &mut Context<Self>) {
+        match event {
+            AiChatPanelEvent::InsertSql { sql } => {
+                let content = self.tab_container.read(cx).active_tab().map(|tab| tab.content().clone());
+                let inserted = content
+                    .as_ref()
+                    .and_then(|c| c.as_any().downcast_ref::<DatabaseTabContent>())
+                    .is_some_and(|db_content| db_content.insert_into_active_editor(sql.clone(), window, cx));
+                if !inserted {
+                    window.push_notification("请先打开一个查询编辑器", cx);
+                }
+            }
+            AiChatPanelEvent::OpenSqlInNewTab { connection_id, database, sql } => {
+                let Ok(connection_id) = connection_id.parse::<i64>() else {
+                    return;
+                };
+                // 连接可能在单独的连接标签页中，也可能属于某个工作区标签页
+                let tab = self.tab_container.read(cx).tabs().iter()
+                    .find(|tab| {
+                        tab.content().as_any().downcast_ref::<DatabaseTabContent>()
+                            .is_some_and(|db_content| db_content.has_connection(connection_id))
+                    })
+                    .map(|tab| (tab.id().to_string(), tab.content().clone()));
+                let Some((tab_id, content)) = tab else {
+                    window.push_notification("该连接尚未打开，请先在主页打开连接", cx);
+                    return;
+                };
+                self.tab_container.update(cx, |container, cx| container.set_active_by_id(&tab_id, window, cx));
+                if let Some(db_content) = content.as_any().downcast_ref::<DatabaseTabContent>() {
+                    db_content.open_query(connection_id, sql.clone(), database.clone(), window, cx);
+                }
+            }
+            AiChatPanelEvent::Close | AiChatPanelEvent::ExecuteSql { .. } => {}
         }
     }
 
     fn toggle_ai_panel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // 打开期间新建立的连接需要出现在上下文选择器中
+        self.ai_panel.update(cx, |panel, cx| panel.load_context_connections(cx));
         let ai_panel = self.ai_panel.clone();
         window.open_sheet(cx, move |sheet, _window, cx| {
             let ai_panel_for_new = ai_panel.clone();
             let ai_panel_for_history = ai_panel.clone();
+            let ai_panel_for_params = ai_panel.clone();
+            let ai_panel_for_tools = ai_panel.clone();
+            let ai_panel_for_export = ai_panel.clone();
             let ai_panel_for_content = ai_panel.clone();
             ai_panel.focus_handle(cx);
+            let database_tools_enabled = ai_panel.read(cx).database_tools_enabled();
 
             sheet
                 .overlay(false)
@@ -148,6 +291,56 @@ impl OneHupApp {
                                             });
                                         })
                                 )
+                                .child(
+                                    Button::new("database-tools")
+                                        .icon(IconName::Database)
+                                        .small()
+                                        .ghost()
+                                        .selected(database_tools_enabled)
+                                        .tooltip("允许 AI 只读查询数据库")
+                                        .on_click(move |_, window, cx| {
+                                            ai_panel_for_tools.update(cx, |panel, cx| {
+                                                panel.toggle_database_tools(window, cx);
+                                            });
+                                        })
+                                )
+                                .child(
+                                    Button::new("generation-params")
+                                        .icon(IconName::Settings)
+                                        .small()
+                                        .ghost()
+                                        .tooltip("生成参数")
+                                        .on_click(move |_, _, cx| {
+                                            ai_panel_for_params.update(cx, |panel, cx| {
+                                                panel.toggle_params_popover(cx);
+                                            });
+                                        })
+                                )
+                                .child(
+                                    Button::new("export-chat")
+                                        .icon(IconName::Download)
+                                        .small()
+                                        .ghost()
+                                        .tooltip("导出对话")
+                                        .dropdown_menu(move |menu, _window, _cx| {
+                                            let panel_for_markdown = ai_panel_for_export.clone();
+                                            let panel_for_json = ai_panel_for_export.clone();
+                                            menu.item(
+                                                PopupMenuItem::new("Markdown").on_click(move |_, _, cx| {
+                                                    panel_for_markdown.update(cx, |panel, cx| {
+                                                        panel.export_session(ChatExportFormat::Markdown, cx);
+                                                    });
+                                                }),
+                                            )
+                                            .item(
+                                                PopupMenuItem::new("JSON").on_click(move |_, _, cx| {
+                                                    panel_for_json.update(cx, |panel, cx| {
+                                                        panel.export_session(ChatExportFormat::Json, cx);
+                                                    });
+                                                }),
+                                            )
+                                        })
+                                )
                         )
                 )
                 .child(ai_panel_for_content)
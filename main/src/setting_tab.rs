@@ -4,9 +4,15 @@ use gpui::{AnyElement, App, AppContext, Entity, FocusHandle, Focusable, Global,
 use gpui_component::{ActiveTheme, IconName, Sizable, Size, Theme, ThemeMode, group_box::GroupBoxVariant, setting::{
     NumberFieldOptions, SettingField, SettingGroup, SettingItem, SettingPage, Settings,
 }, Icon};
+use db_view::activity_monitor::ActivityMonitorSettings;
+use db_view::confirm_policy::{ConfirmLevel, ConfirmPolicy};
+use one_core::storage::{ConnectionEnvironment, UsageMetricsSettings};
 use one_core::tab_container::{TabContent, TabContentType};
 
+use crate::settings::cleanup_log_view::CleanupLogView;
+use crate::settings::feedback_view::FeedbackView;
 use crate::settings::llm_providers_view::LlmProvidersView;
+use crate::settings::storage_view::StorageView;
 
 struct AppSettings {
     auto_switch_theme: bool,
@@ -44,23 +50,58 @@ impl AppSettings {
 pub struct SettingsTabContent {
     focus_handle: FocusHandle,
     llm_providers_view: Entity<LlmProvidersView>,
+    storage_view: Entity<StorageView>,
+    feedback_view: Entity<FeedbackView>,
+    cleanup_log_view: Entity<CleanupLogView>,
     size: Size,
     group_variant: GroupBoxVariant,
 }
 
 impl SettingsTabContent {
-    pub fn new(_window: &mut Window, cx: &mut App) -> Self {
+    pub fn new(window: &mut Window, cx: &mut App) -> Self {
         let llm_providers_view = cx.new(|cx| LlmProvidersView::new(cx));
+        let storage_view = cx.new(|cx| StorageView::new(cx));
+        let feedback_view = cx.new(|cx| FeedbackView::new(window, cx));
+        let cleanup_log_view = cx.new(|cx| CleanupLogView::new(cx));
         Self {
             focus_handle: cx.focus_handle(),
             llm_providers_view,
+            storage_view,
+            feedback_view,
+            cleanup_log_view,
             size: Size::default(),
             group_variant: GroupBoxVariant::Outline,
         }
     }
 
+    /// 某个环境下删除、清空等危险操作的确认方式
+    fn confirm_level_item(environment: ConnectionEnvironment) -> SettingItem {
+        let options = ConfirmLevel::all()
+            .iter()
+            .map(|level| (SharedString::from(level.as_str()), SharedString::from(level.label())))
+            .collect();
+
+        SettingItem::new(
+            format!("{}环境", environment.label()),
+            SettingField::dropdown(
+                options,
+                move |cx: &App| ConfirmPolicy::global(cx).environment_level(environment).as_str().into(),
+                move |val: SharedString, cx: &mut App| {
+                    ConfirmPolicy::global_mut(cx).set_environment_level(environment, ConfirmLevel::from_str(&val));
+                },
+            )
+            .default_value(SharedString::from(
+                ConfirmPolicy::default().environment_level(environment).as_str(),
+            )),
+        )
+        .description("删除连接、删除或清空表等操作的确认方式")
+    }
+
     fn setting_pages(&self, _window: &mut Window, _cx: &App) -> Vec<SettingPage> {
         let llm_view = self.llm_providers_view.clone();
+        let storage_view = self.storage_view.clone();
+        let feedback_view = self.feedback_view.clone();
+        let cleanup_log_view = self.cleanup_log_view.clone();
         let default_settings = AppSettings::default();
         let resettable = AppSettings::global(_cx).resettable;
         
@@ -138,7 +179,74 @@ impl SettingsTabContent {
                             )
                             .description("调整字体大小以获得更好的可读性（8-72）"),
                         ),
+                    SettingGroup::new().title("连接活动").items(vec![
+                        SettingItem::new(
+                            "显示活动趋势",
+                            SettingField::switch(
+                                |cx: &App| ActivityMonitorSettings::global(cx).enabled,
+                                |val: bool, cx: &mut App| {
+                                    ActivityMonitorSettings::global_mut(cx).enabled = val;
+                                },
+                            )
+                            .default_value(ActivityMonitorSettings::default().enabled),
+                        )
+                        .description("定期读取服务器状态，在已连接的节点旁显示每秒查询数趋势"),
+                        SettingItem::new(
+                            "采样间隔（秒）",
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: 1.0,
+                                    max: 300.0,
+                                    ..Default::default()
+                                },
+                                |cx: &App| ActivityMonitorSettings::global(cx).interval_secs as f64,
+                                |val: f64, cx: &mut App| {
+                                    ActivityMonitorSettings::global_mut(cx).interval_secs = val as u64;
+                                },
+                            )
+                            .default_value(ActivityMonitorSettings::default().interval_secs as f64),
+                        )
+                        .description("每次采样都会向数据库发送一条状态查询（1-300）"),
+                    ]),
                 ]),
+            SettingPage::new("安全")
+                .groups(vec![
+                    SettingGroup::new().title("危险操作确认").items(vec![
+                        Self::confirm_level_item(ConnectionEnvironment::Development),
+                        Self::confirm_level_item(ConnectionEnvironment::Testing),
+                        Self::confirm_level_item(ConnectionEnvironment::Production),
+                        SettingItem::new(
+                            "生产环境 DDL 确认",
+                            SettingField::switch(
+                                |cx: &App| ConfirmPolicy::global(cx).confirm_ddl_on_production,
+                                |val: bool, cx: &mut App| {
+                                    ConfirmPolicy::global_mut(cx).confirm_ddl_on_production = val;
+                                },
+                            )
+                            .default_value(ConfirmPolicy::default().confirm_ddl_on_production),
+                        )
+                        .description("在生产环境连接的 SQL 编辑器中执行 CREATE/ALTER/DROP 等语句前需要确认"),
+                        SettingItem::new(
+                            "生产环境 DML 确认",
+                            SettingField::switch(
+                                |cx: &App| ConfirmPolicy::global(cx).confirm_dml_on_production,
+                                |val: bool, cx: &mut App| {
+                                    ConfirmPolicy::global_mut(cx).confirm_dml_on_production = val;
+                                },
+                            )
+                            .default_value(ConfirmPolicy::default().confirm_dml_on_production),
+                        )
+                        .description("在生产环境连接的 SQL 编辑器中执行 INSERT/UPDATE/DELETE 语句前需要确认"),
+                    ]),
+                ]),
+            SettingPage::new("连接")
+                .group(
+                    SettingGroup::new()
+                        .title("空闲会话清理")
+                        .item(SettingItem::render(move |_options, _window, _cx| {
+                            cleanup_log_view.clone().into_any_element()
+                        }))
+                ),
             SettingPage::new("LLM 提供商")
                 .group(
                     SettingGroup::new()
@@ -146,6 +254,33 @@ impl SettingsTabContent {
                             llm_view.clone().into_any_element()
                         }))
                 ),
+            SettingPage::new("存储")
+                .group(
+                    SettingGroup::new()
+                        .title("应用数据")
+                        .item(SettingItem::render(move |_options, _window, _cx| {
+                            storage_view.clone().into_any_element()
+                        }))
+                ),
+            SettingPage::new("反馈")
+                .groups(vec![
+                    SettingGroup::new().title("使用统计").items(vec![
+                        SettingItem::new(
+                            "记录匿名使用统计",
+                            SettingField::switch(
+                                |cx: &App| UsageMetricsSettings::global(cx).enabled,
+                                |val: bool, cx: &mut App| {
+                                    UsageMetricsSettings::global_mut(cx).enabled = val;
+                                },
+                            )
+                            .default_value(UsageMetricsSettings::default().enabled),
+                        )
+                        .description("在本地累计各功能的使用和出错次数，帮助维护者决定优先改进的功能"),
+                        SettingItem::render(move |_options, _window, _cx| {
+                            feedback_view.clone().into_any_element()
+                        }),
+                    ]),
+                ]),
         ]
     }
 }
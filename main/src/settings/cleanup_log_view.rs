@@ -0,0 +1,113 @@
+use db::{CleanupEvent, CleanupReason, GlobalDbState};
+use gpui::{div, px, App, Context, FocusHandle, Focusable, InteractiveElement, IntoElement, ParentElement, Render, StatefulInteractiveElement, Styled, Window};
+use gpui_component::{
+    ActiveTheme, Disableable, h_flex, v_flex,
+    button::{Button, ButtonVariant, ButtonVariants},
+};
+
+/// 后台任务最近关闭的会话，用来确认临时表或未提交的事务是被哪条清理规则关掉的
+pub struct CleanupLogView {
+    focus_handle: FocusHandle,
+    events: Vec<CleanupEvent>,
+}
+
+impl CleanupLogView {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let events = cx.global::<GlobalDbState>().connection_manager.cleanup_events();
+        Self {
+            focus_handle: cx.focus_handle(),
+            events,
+        }
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.events = cx.global::<GlobalDbState>().connection_manager.cleanup_events();
+        cx.notify();
+    }
+
+    fn clear(&mut self, cx: &mut Context<Self>) {
+        cx.global::<GlobalDbState>().connection_manager.clear_cleanup_events();
+        self.events.clear();
+        cx.notify();
+    }
+}
+
+fn reason_label(reason: &CleanupReason) -> String {
+    match reason {
+        CleanupReason::IdleTimeout(idle) => format!("空闲 {} 秒，超过空闲关闭时间", idle.as_secs()),
+        CleanupReason::LifetimeExceeded(lifetime) => format!("已打开 {} 秒，超过最长存活时间", lifetime.as_secs()),
+        CleanupReason::KeepaliveFailed => "保活查询失败，连接已断开".to_string(),
+    }
+}
+
+impl Render for CleanupLogView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_events = !self.events.is_empty();
+        let mut rows = v_flex().gap_1();
+        for event in &self.events {
+            rows = rows.child(
+                h_flex()
+                    .gap_3()
+                    .text_sm()
+                    .child(
+                        div()
+                            .w(px(140.))
+                            .flex_shrink_0()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(event.at.format("%m-%d %H:%M:%S").to_string()),
+                    )
+                    .child(div().w(px(160.)).flex_shrink_0().truncate().child(event.connection_name.clone()))
+                    .child(div().flex_1().min_w_0().truncate().child(reason_label(&event.reason))),
+            );
+        }
+
+        v_flex()
+            .gap_3()
+            .w_full()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("每个连接的空闲关闭时间和“从不关闭”在连接设置的高级页中配置；SQL 编辑器里的命名会话不会被清理"),
+            )
+            .child(
+                div()
+                    .id("cleanup-log")
+                    .max_h(px(240.))
+                    .overflow_y_scroll()
+                    .p_3()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().muted)
+                    .child(if has_events {
+                        rows.into_any_element()
+                    } else {
+                        div().text_sm().child("本次启动后还没有关闭过会话").into_any_element()
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("reload-cleanup-log")
+                            .with_variant(ButtonVariant::Secondary)
+                            .label("刷新")
+                            .on_click(cx.listener(|view, _, _, cx| view.reload(cx))),
+                    )
+                    .child(
+                        Button::new("clear-cleanup-log")
+                            .with_variant(ButtonVariant::Secondary)
+                            .label("清空记录")
+                            .disabled(!has_events)
+                            .on_click(cx.listener(|view, _, _, cx| view.clear(cx))),
+                    ),
+            )
+    }
+}
+
+impl Focusable for CleanupLogView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
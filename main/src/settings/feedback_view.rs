@@ -0,0 +1,238 @@
+use gpui::{div, App, AppContext, AsyncApp, ClipboardItem, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render, Styled, Window};
+use gpui_component::{
+    ActiveTheme, Disableable, WindowExt, h_flex, v_flex,
+    button::{Button, ButtonVariant, ButtonVariants},
+    checkbox::Checkbox,
+    input::{Input, InputState},
+    notification::Notification,
+};
+use one_core::gpui_tokio::Tokio;
+use one_core::storage::{format_usage_report, GlobalStorageState, StorageManager, UsageEvent, UsageMetricsRepository};
+
+/// 反馈提交到项目的 issue 页面，由用户在浏览器中确认后再提交
+const FEEDBACK_URL: &str = "https://github.com/feigeCode/onehub/issues/new";
+
+/// 本地使用统计的查看、清空，以及附带版本和平台信息的反馈表单
+pub struct FeedbackView {
+    focus_handle: FocusHandle,
+    storage_manager: StorageManager,
+    events: Vec<UsageEvent>,
+    description_input: Entity<InputState>,
+    attach_usage: bool,
+}
+
+impl FeedbackView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let storage_manager = cx.global::<GlobalStorageState>().storage.clone();
+        let description_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .multi_line(true)
+                .placeholder("描述遇到的问题或希望增加的功能")
+        });
+
+        let view = Self {
+            focus_handle: cx.focus_handle(),
+            storage_manager,
+            events: vec![],
+            description_input,
+            attach_usage: false,
+        };
+        view.reload(cx);
+        view
+    }
+
+    fn reload(&self, cx: &mut Context<Self>) {
+        let storage = self.storage_manager.clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = match Tokio::spawn_result(cx, async move {
+                let repo = storage
+                    .get::<UsageMetricsRepository>()
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Usage metrics repository not found"))?;
+                repo.list().await
+            }) {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(events) => {
+                    _ = this.update(cx, |view, cx| {
+                        view.events = events;
+                        cx.notify();
+                    });
+                }
+                Err(e) => tracing::warn!("Failed to load usage events: {}", e),
+            }
+        })
+        .detach();
+    }
+
+    fn clear(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let storage = self.storage_manager.clone();
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = match Tokio::spawn_result(cx, async move {
+                let repo = storage
+                    .get::<UsageMetricsRepository>()
+                    .await
+                    .ok_or_else(|| anyhow::anyhow!("Usage metrics repository not found"))?;
+                repo.clear().await
+            }) {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+
+            _ = cx.update_window(window_handle, |_, window, cx| match &result {
+                Ok(()) => window.push_notification(Notification::success("已清空使用统计"), cx),
+                Err(e) => window.push_notification(Notification::error(format!("清空使用统计失败: {}", e)), cx),
+            });
+            if result.is_ok() {
+                _ = this.update(cx, |view, cx| {
+                    view.events.clear();
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    fn feedback_body(&self, cx: &App) -> String {
+        let usage_report = (self.attach_usage && !self.events.is_empty()).then(|| format_usage_report(&self.events));
+        build_feedback_body(
+            self.description_input.read(cx).text().to_string().trim(),
+            &environment_info(),
+            usage_report.as_deref(),
+        )
+    }
+
+    fn submit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.description_input.read(cx).text().to_string().trim().is_empty() {
+            window.push_notification("请先填写反馈内容", cx);
+            return;
+        }
+        let body = self.feedback_body(cx);
+        cx.open_url(&format!("{}?body={}", FEEDBACK_URL, encode_query_component(&body)));
+    }
+}
+
+/// 应用版本和运行平台，附带在每条反馈中
+fn environment_info() -> String {
+    format!(
+        "OneHub {} ({} {})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+fn build_feedback_body(description: &str, environment: &str, usage_report: Option<&str>) -> String {
+    let mut body = format!("{}\n\n---\n环境：{}", description, environment);
+    if let Some(report) = usage_report {
+        body.push_str(&format!("\n\n使用统计：\n```\n{}\n```", report));
+    }
+    body
+}
+
+fn encode_query_component(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+impl Render for FeedbackView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let report = format_usage_report(&self.events);
+        let has_events = !self.events.is_empty();
+
+        v_flex()
+            .gap_3()
+            .w_full()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("统计只保存在本地，内容只有功能名称和次数，不包含 SQL、连接信息或错误详情；只有在提交反馈时勾选附带才会发送"),
+            )
+            .child(
+                div()
+                    .p_3()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().muted)
+                    .text_sm()
+                    .font_family("monospace")
+                    .child(if has_events { report } else { "暂无统计数据".to_string() }),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("reload-usage")
+                            .with_variant(ButtonVariant::Secondary)
+                            .label("刷新")
+                            .on_click(cx.listener(|view, _, _, cx| view.reload(cx))),
+                    )
+                    .child(
+                        Button::new("clear-usage")
+                            .with_variant(ButtonVariant::Secondary)
+                            .label("清空统计")
+                            .disabled(!has_events)
+                            .on_click(cx.listener(|view, _, window, cx| view.clear(window, cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                    .child("反馈"),
+            )
+            .child(Input::new(&self.description_input).h(gpui::px(120.)))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("将附带：{}", environment_info())),
+            )
+            .child(
+                Checkbox::new("attach-usage")
+                    .checked(self.attach_usage && has_events)
+                    .disabled(!has_events)
+                    .label("附带上方的使用统计")
+                    .on_click(cx.listener(|view, checked: &bool, _, cx| {
+                        view.attach_usage = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("submit-feedback")
+                            .primary()
+                            .label("在浏览器中提交")
+                            .on_click(cx.listener(|view, _, window, cx| view.submit(window, cx))),
+                    )
+                    .child(
+                        Button::new("copy-feedback")
+                            .with_variant(ButtonVariant::Secondary)
+                            .label("复制反馈内容")
+                            .on_click(cx.listener(|view, _, _, cx| {
+                                cx.write_to_clipboard(ClipboardItem::new_string(view.feedback_body(cx)));
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Focusable for FeedbackView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
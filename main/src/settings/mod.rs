@@ -1,2 +1,5 @@
+pub mod cleanup_log_view;
+pub mod feedback_view;
 pub mod llm_providers_view;
 pub mod provider_form_dialog;
+pub mod storage_view;
@@ -1,13 +1,18 @@
 //! Provider Form Dialog - 添加/编辑 LLM Provider 的表单对话框
 
-use gpui::{div, App, AppContext, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render, SharedString, Styled, Window};
+use gpui::{div, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render, SharedString, Styled, Window};
+use gpui::prelude::FluentBuilder;
 use gpui_component::{
-    v_flex,
+    ActiveTheme, h_flex, v_flex,
+    button::{Button, ButtonVariants},
     input::{Input, InputState},
+    switch::Switch,
     select::{Select, SelectItem, SelectState},
     IndexPath,
 };
-use one_core::llm::types::{ProviderConfig, ProviderType};
+use one_core::gpui_tokio::Tokio;
+use one_core::llm::manager::ProviderFactory;
+use one_core::llm::types::{ApiKeyError, ProviderConfig, ProviderType};
 
 /// Provider 类型选择项
 #[derive(Clone, Debug)]
@@ -43,6 +48,10 @@ pub struct ProviderForm {
     api_key_input: Entity<InputState>,
     api_base_input: Entity<InputState>,
     model_input: Entity<InputState>,
+    extended_thinking: bool,
+    thinking_budget_input: Entity<InputState>,
+    verifying: bool,
+    verify_result: Option<Result<(), ApiKeyError>>,
 }
 
 impl ProviderForm {
@@ -114,6 +123,15 @@ impl ProviderForm {
             state
         });
 
+        let thinking_budget_input = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).placeholder("Budget tokens (default 4096)");
+            if let Some(budget) = config.as_ref().and_then(|cfg| cfg.thinking_budget_tokens) {
+                state = state.default_value(budget.to_string());
+            }
+            state
+        });
+        let extended_thinking = config.as_ref().is_some_and(|cfg| cfg.extended_thinking);
+
         Self {
             focus_handle,
             provider_id: config.map(|c| c.id),
@@ -122,9 +140,48 @@ impl ProviderForm {
             api_key_input,
             api_base_input,
             model_input,
+            extended_thinking,
+            thinking_budget_input,
+            verifying: false,
+            verify_result: None,
         }
     }
 
+    /// 使用当前表单内容发起一次真实的鉴权请求，校验 API Key
+    pub fn verify_api_key(&mut self, cx: &mut Context<Self>) {
+        let Some(config) = self.get_config(cx) else {
+            self.verify_result = Some(Err(ApiKeyError::Other("Please fill in all required fields".to_string())));
+            cx.notify();
+            return;
+        };
+
+        self.verifying = true;
+        self.verify_result = None;
+        cx.notify();
+
+        let client = cx.http_client();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = match Tokio::spawn(cx, async move {
+                let provider = ProviderFactory::create_provider(config, client)
+                    .map_err(|e| ApiKeyError::Other(e.to_string()))?;
+                provider.verify_api_key().await
+            }) {
+                Ok(task) => match task.await {
+                    Ok(result) => result,
+                    Err(e) => Err(ApiKeyError::Other(e.to_string())),
+                },
+                Err(e) => Err(ApiKeyError::Other(e.to_string())),
+            };
+
+            _ = this.update(cx, |form, cx| {
+                form.verifying = false;
+                form.verify_result = Some(result);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
     pub fn get_config(&mut self, cx: &mut Context<Self>) -> Option<ProviderConfig>{
         let name = self.name_input.read(cx).value().to_string();
         let provider_type = self
@@ -136,6 +193,7 @@ impl ProviderForm {
         let api_key = self.api_key_input.read(cx).value().to_string();
         let api_base = self.api_base_input.read(cx).value().to_string();
         let model = self.model_input.read(cx).value().to_string();
+        let thinking_budget_tokens = self.thinking_budget_input.read(cx).value().trim().parse().ok();
 
         if name.trim().is_empty() {
             tracing::warn!("Provider name is required");
@@ -161,6 +219,8 @@ impl ProviderForm {
             model,
             max_tokens: Some(4096),
             temperature: Some(0.7),
+            extended_thinking: self.extended_thinking && provider_type == ProviderType::Claude,
+            thinking_budget_tokens,
             enabled: true,
             created_at: now,
             updated_at: now,
@@ -174,7 +234,13 @@ impl Focusable for ProviderForm {
 }
 
 impl Render for ProviderForm {
-    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_claude = self.provider_type_select.read(cx).selected_value() == Some(&ProviderType::Claude);
+        let verify_message = self.verify_result.as_ref().map(|result| match result {
+            Ok(()) => ("API key verified".to_string(), cx.theme().success),
+            Err(e) => (e.to_string(), cx.theme().danger),
+        });
+
         v_flex()
             .gap_3()
             .child(
@@ -232,5 +298,50 @@ impl Render for ProviderForm {
                     )
                     .child(Input::new(&self.model_input)),
             )
+            .when(is_claude, |this| {
+                this.child(
+                    v_flex()
+                        .gap_1()
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .items_center()
+                                .child(
+                                    Switch::new("extended-thinking")
+                                        .checked(self.extended_thinking)
+                                        .on_click(cx.listener(|form, checked, _, cx| {
+                                            form.extended_thinking = *checked;
+                                            cx.notify();
+                                        })),
+                                )
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .font_weight(gpui::FontWeight::MEDIUM)
+                                        .child("Extended Thinking (Claude 4.x)"),
+                                ),
+                        )
+                        .when(self.extended_thinking, |this| {
+                            this.child(Input::new(&self.thinking_budget_input))
+                        }),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        Button::new("verify-api-key")
+                            .outline()
+                            .label(if self.verifying { "Testing..." } else { "Test" })
+                            .disabled(self.verifying)
+                            .on_click(cx.listener(|form, _, _, cx| {
+                                form.verify_api_key(cx);
+                            })),
+                    )
+                    .when_some(verify_message, |this, (message, color)| {
+                        this.child(div().text_sm().text_color(color).child(message))
+                    }),
+            )
     }
 }
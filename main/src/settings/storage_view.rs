@@ -0,0 +1,383 @@
+use gpui::{div, px, App, AppContext, AsyncApp, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement, Render, SharedString, Styled, Window};
+use gpui::prelude::FluentBuilder;
+use gpui_component::{
+    ActiveTheme, Disableable, WindowExt, h_flex, v_flex,
+    button::{Button, ButtonVariant, ButtonVariants},
+    dialog::DialogButtonProps,
+    input::{Input, InputState},
+    notification::Notification,
+};
+use one_core::gpui_tokio::Tokio;
+use one_core::storage::{GlobalStorageState, StorageBackup, StorageManager, StorageRecovery, MAX_BACKUPS};
+
+/// 主密码的设置操作
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MasterPasswordAction {
+    Enable,
+    Change,
+    Disable,
+}
+
+impl MasterPasswordAction {
+    fn title(&self) -> &'static str {
+        match self {
+            MasterPasswordAction::Enable => "设置主密码",
+            MasterPasswordAction::Change => "修改主密码",
+            MasterPasswordAction::Disable => "关闭加密",
+        }
+    }
+
+    fn needs_current(&self) -> bool {
+        *self != MasterPasswordAction::Enable
+    }
+
+    fn needs_new(&self) -> bool {
+        *self != MasterPasswordAction::Disable
+    }
+}
+
+/// 应用自身存储（one-hub.db）的健康检查、备份、压缩与加密
+pub struct StorageView {
+    focus_handle: FocusHandle,
+    storage_manager: StorageManager,
+    backups: Vec<StorageBackup>,
+    /// 正在执行的操作名称，执行期间禁用按钮
+    running: Option<&'static str>,
+}
+
+impl StorageView {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let storage_manager = cx.global::<GlobalStorageState>().storage.clone();
+        let backups = storage_manager.backups().unwrap_or_else(|e| {
+            tracing::warn!("Failed to list storage backups: {}", e);
+            vec![]
+        });
+
+        Self {
+            focus_handle: cx.focus_handle(),
+            storage_manager,
+            backups,
+            running: None,
+        }
+    }
+
+    fn refresh_backups(&mut self) {
+        match self.storage_manager.backups() {
+            Ok(backups) => self.backups = backups,
+            Err(e) => tracing::warn!("Failed to list storage backups: {}", e),
+        }
+    }
+
+    fn check_integrity(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let storage = self.storage_manager.clone();
+        self.run("check", window, cx, async move {
+            let problems = storage.integrity_check().await?;
+            if problems.is_empty() {
+                Ok("完整性检查通过".to_string())
+            } else {
+                Err(anyhow::anyhow!("发现 {} 个问题：{}", problems.len(), problems.join("; ")))
+            }
+        });
+    }
+
+    fn backup_now(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let storage = self.storage_manager.clone();
+        self.run("backup", window, cx, async move {
+            let backup = storage.backup().await?;
+            Ok(format!("已备份到 {}", backup.path.display()))
+        });
+    }
+
+    fn compact(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let storage = self.storage_manager.clone();
+        self.run("compact", window, cx, async move {
+            let (before, after) = storage.compact().await?;
+            Ok(format!("压缩完成：{} → {}", format_size(before), format_size(after)))
+        });
+    }
+
+    fn open_master_password_dialog(&mut self, action: MasterPasswordAction, window: &mut Window, cx: &mut Context<Self>) {
+        let view = cx.entity().clone();
+        let masked_input = |placeholder: &'static str, window: &mut Window, cx: &mut Context<Self>| {
+            cx.new(|cx| InputState::new(window, cx).placeholder(placeholder).masked(true))
+        };
+        let current_input = action.needs_current().then(|| masked_input("当前主密码", window, cx));
+        let new_input = action.needs_new().then(|| masked_input("新主密码", window, cx));
+        let confirm_input = action.needs_new().then(|| masked_input("再次输入新主密码", window, cx));
+
+        window.open_dialog(cx, move |dialog, _window, cx| {
+            let view = view.clone();
+            let (current_input, new_input, confirm_input) =
+                (current_input.clone(), new_input.clone(), confirm_input.clone());
+            dialog
+                .title(action.title())
+                .w(px(420.0))
+                .child(
+                    v_flex()
+                        .gap_3()
+                        .children(current_input.as_ref().map(Input::new))
+                        .children(new_input.as_ref().map(Input::new))
+                        .children(confirm_input.as_ref().map(Input::new))
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("完成后 OneHub 将重新启动，现有备份会被删除。主密码丢失后无法恢复本地数据"),
+                        ),
+                )
+                .confirm()
+                .button_props(DialogButtonProps::default().ok_text(action.title()))
+                .on_ok(move |_, window, cx| {
+                    let value = |input: &Option<Entity<InputState>>, cx: &App| {
+                        input.as_ref().map(|input| input.read(cx).value().to_string())
+                    };
+                    let current = value(&current_input, cx);
+                    let new_password = value(&new_input, cx);
+                    if current.as_ref().is_some_and(|current| current.is_empty()) {
+                        window.push_notification(Notification::error("请输入当前主密码"), cx);
+                        return false;
+                    }
+                    if new_password.as_ref().is_some_and(|password| password.is_empty()) {
+                        window.push_notification(Notification::error("新主密码不能为空"), cx);
+                        return false;
+                    }
+                    if new_password != value(&confirm_input, cx) {
+                        window.push_notification(Notification::error("两次输入的新主密码不一致"), cx);
+                        return false;
+                    }
+                    view.update(cx, |view, cx| view.change_master_password(current, new_password, window, cx));
+                    true
+                })
+        });
+    }
+
+    /// 重新写入数据库后存储连接已关闭，成功后重启应用
+    fn change_master_password(
+        &mut self,
+        current: Option<String>,
+        new_password: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.running.is_some() {
+            return;
+        }
+        self.running = Some("master_password");
+        cx.notify();
+
+        let storage = self.storage_manager.clone();
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let task = async move {
+                if let Some(current) = current {
+                    if !storage.check_master_password(&current).await? {
+                        anyhow::bail!("当前主密码错误");
+                    }
+                }
+                storage.set_master_password(new_password).await
+            };
+            let result = match Tokio::spawn_result(cx, task) {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    _ = cx.update(|cx| cx.restart());
+                }
+                Err(e) => {
+                    _ = this.update(cx, |view, cx| {
+                        view.running = None;
+                        cx.notify();
+                    });
+                    _ = cx.update_window(window_handle, |_, window, cx| {
+                        window.push_notification(Notification::error(e.to_string()), cx)
+                    });
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// 在 tokio 线程池中执行存储操作，完成后通知结果
+    fn run(
+        &mut self,
+        action: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        task: impl Future<Output = anyhow::Result<String>> + Send + 'static,
+    ) {
+        if self.running.is_some() {
+            return;
+        }
+        self.running = Some(action);
+        cx.notify();
+
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = match Tokio::spawn_result(cx, task) {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+
+            _ = this.update(cx, |view, cx| {
+                view.running = None;
+                view.refresh_backups();
+                cx.notify();
+            });
+            _ = cx.update_window(window_handle, |_, window, cx| match result {
+                Ok(message) => window.push_notification(Notification::success(message), cx),
+                Err(e) => window.push_notification(Notification::error(e.to_string()), cx),
+            });
+        })
+        .detach();
+    }
+
+    fn render_recovery(&self, cx: &App) -> Option<impl IntoElement> {
+        let message = match self.storage_manager.recovery() {
+            StorageRecovery::Healthy => return None,
+            StorageRecovery::RestoredFromBackup { backup, corrupt } => format!(
+                "启动时发现数据库已损坏，已从备份 {} 恢复。损坏的文件保留在 {}",
+                backup.display(),
+                corrupt.display()
+            ),
+            StorageRecovery::Recreated { corrupt } => format!(
+                "启动时发现数据库已损坏且没有可用的备份，已创建新的数据库。损坏的文件保留在 {}",
+                corrupt.display()
+            ),
+        };
+
+        Some(
+            div()
+                .p_3()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().danger)
+                .text_sm()
+                .text_color(cx.theme().danger)
+                .child(message),
+        )
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+impl Render for StorageView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.running;
+        let encrypted = self.storage_manager.is_encrypted();
+        let action_button = |id: &'static str, label: &'static str| {
+            Button::new(id)
+                .with_variant(ButtonVariant::Secondary)
+                .label(label)
+                .loading(running == Some(id))
+                .disabled(running.is_some())
+        };
+
+        v_flex()
+            .gap_3()
+            .w_full()
+            .children(self.render_recovery(cx))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!("数据库文件：{}", self.storage_manager.db_path().display())),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        action_button("check", "检查完整性")
+                            .on_click(cx.listener(|view, _, window, cx| view.check_integrity(window, cx))),
+                    )
+                    .child(
+                        action_button("backup", "立即备份")
+                            .on_click(cx.listener(|view, _, window, cx| view.backup_now(window, cx))),
+                    )
+                    .child(
+                        action_button("compact", "压缩数据库")
+                            .on_click(cx.listener(|view, _, window, cx| view.compact(window, cx))),
+                    ),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                    .child("主密码"),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(if encrypted {
+                        "已启用：本地数据（连接、查询、对话历史）已加密，启动时需要输入主密码"
+                    } else {
+                        "未启用：设置主密码后加密本地数据（连接、查询、对话历史），启动时需要输入主密码"
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_2()
+                    .when(!encrypted, |this| {
+                        this.child(action_button("master_password", MasterPasswordAction::Enable.title()).on_click(
+                            cx.listener(|view, _, window, cx| {
+                                view.open_master_password_dialog(MasterPasswordAction::Enable, window, cx)
+                            }),
+                        ))
+                    })
+                    .when(encrypted, |this| {
+                        this.child(action_button("master_password", MasterPasswordAction::Change.title()).on_click(
+                            cx.listener(|view, _, window, cx| {
+                                view.open_master_password_dialog(MasterPasswordAction::Change, window, cx)
+                            }),
+                        ))
+                        .child(action_button("disable_encryption", MasterPasswordAction::Disable.title()).on_click(
+                            cx.listener(|view, _, window, cx| {
+                                view.open_master_password_dialog(MasterPasswordAction::Disable, window, cx)
+                            }),
+                        ))
+                    }),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .font_weight(gpui::FontWeight::SEMIBOLD)
+                    .child(format!("备份（每天启动时自动备份，保留最近 {} 份）", MAX_BACKUPS)),
+            )
+            .when(self.backups.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("暂无备份"),
+                )
+            })
+            .children(self.backups.iter().map(|backup| {
+                let created_at = chrono::DateTime::from_timestamp(backup.created_at, 0)
+                    .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                h_flex()
+                    .gap_4()
+                    .text_sm()
+                    .child(div().w(gpui::px(160.)).child(created_at))
+                    .child(
+                        div()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(SharedString::from(format_size(backup.size))),
+                    )
+            }))
+    }
+}
+
+impl Focusable for StorageView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
@@ -0,0 +1,145 @@
+use gpui::{div, px, size, App, AppContext, AsyncApp, Bounds, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Window, WindowBounds, WindowKind, WindowOptions};
+use gpui::prelude::FluentBuilder;
+use gpui_component::{
+    ActiveTheme, Disableable, Root, v_flex,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+};
+use one_core::gpui_tokio::Tokio;
+use one_core::storage::encryption::DatabaseKey;
+use one_core::storage::StorageManager;
+
+type OnUnlocked = Box<dyn FnOnce(Option<DatabaseKey>, &mut App)>;
+
+/// 启用主密码时先显示解锁窗口，解锁后才打开存储；未启用时直接继续
+pub fn unlock_storage(cx: &mut App, on_unlocked: impl FnOnce(Option<DatabaseKey>, &mut App) + 'static) {
+    match StorageManager::master_password() {
+        Ok(None) => on_unlocked(None, cx),
+        // 读取主密码配置失败时也要求解锁，解锁时会显示具体错误，避免误把加密的数据库当作明文打开
+        Ok(Some(_)) | Err(_) => open_unlock_window(Box::new(on_unlocked), cx),
+    }
+}
+
+fn open_unlock_window(on_unlocked: OnUnlocked, cx: &mut App) {
+    let options = WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(Bounds::centered(None, size(px(420.), px(260.)), cx))),
+        #[cfg(not(target_os = "linux"))]
+        titlebar: Some(gpui_component::TitleBar::title_bar_options()),
+        is_resizable: false,
+        kind: WindowKind::Normal,
+        ..Default::default()
+    };
+
+    cx.spawn(async move |cx| {
+        cx.open_window(options, |window, cx| {
+            let view = cx.new(|cx| UnlockView::new(on_unlocked, window, cx));
+            cx.new(|cx| Root::new(view, window, cx))
+        })?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+pub struct UnlockView {
+    password_input: Entity<InputState>,
+    error: Option<SharedString>,
+    unlocking: bool,
+    on_unlocked: Option<OnUnlocked>,
+    _input_subscription: Subscription,
+}
+
+impl UnlockView {
+    fn new(on_unlocked: OnUnlocked, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let password_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("主密码")
+                .masked(true)
+        });
+        password_input.update(cx, |state, cx| state.focus(window, cx));
+        let input_subscription = cx.subscribe_in(&password_input, window, |this, _, event: &InputEvent, window, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.unlock(window, cx);
+            }
+        });
+
+        Self {
+            password_input,
+            error: None,
+            unlocking: false,
+            on_unlocked: Some(on_unlocked),
+            _input_subscription: input_subscription,
+        }
+    }
+
+    fn unlock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let password = self.password_input.read(cx).value().to_string();
+        if self.unlocking || password.is_empty() {
+            return;
+        }
+        self.unlocking = true;
+        self.error = None;
+        cx.notify();
+
+        let window_handle = window.window_handle();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let result = match Tokio::spawn_result(cx, async move { StorageManager::unlock(&password).await }) {
+                Ok(task) => task.await,
+                Err(e) => Err(e),
+            };
+
+            let key = match result {
+                Ok(Some(key)) => key,
+                failed => {
+                    let message = match failed {
+                        Err(e) => format!("解锁失败：{}", e),
+                        _ => "主密码错误".to_string(),
+                    };
+                    _ = this.update(cx, |view, cx| {
+                        view.unlocking = false;
+                        view.error = Some(message.into());
+                        cx.notify();
+                    });
+                    return;
+                }
+            };
+
+            let Ok(Some(on_unlocked)) = this.update(cx, |view, _| view.on_unlocked.take()) else {
+                return;
+            };
+            _ = cx.update_window(window_handle, |_, window, _| window.remove_window());
+            _ = cx.update(|cx| on_unlocked(Some(key), cx));
+        })
+        .detach();
+    }
+}
+
+impl Render for UnlockView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .justify_center()
+            .gap_3()
+            .p_6()
+            .bg(cx.theme().background)
+            .child(div().text_lg().font_weight(gpui::FontWeight::SEMIBOLD).child("解锁 OneHub"))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("本地数据已加密，请输入主密码"),
+            )
+            .child(Input::new(&self.password_input))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(div().text_sm().text_color(cx.theme().danger).child(error))
+            })
+            .child(
+                Button::new("unlock")
+                    .primary()
+                    .label("解锁")
+                    .loading(self.unlocking)
+                    .disabled(self.unlocking)
+                    .on_click(cx.listener(|view, _, window, cx| view.unlock(window, cx))),
+            )
+    }
+}